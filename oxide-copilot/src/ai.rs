@@ -2,43 +2,148 @@ use crate::errors::CopilotError;
 use crate::functions::FunctionRegistry;
 use crate::gemini_api::{
     Content, FunctionCall, FunctionDeclaration, FunctionResponse, GenerateContentRequest,
-    GenerateContentResponse, Part, Tool,
+    GenerateContentResponse, InlineData, Part, Tool,
 };
+use crate::post_process::{PostProcessContext, PostProcessPipeline, ProcessedResponse};
 use async_trait::async_trait;
 use log::{error, info, warn};
+use oxide_core::capabilities::{self, Feature};
 use oxide_core::config::{
     AIProvidersConfig, AnthropicConfig, AzureOpenAIConfig, GoogleConfig, OllamaConfig, OpenAIConfig,
 };
+use oxide_core::gemini_auth::GeminiAuth;
 use oxide_core::google_auth::{authenticate_google, get_access_token};
-use oxide_core::types::{AgentAction, Interaction};
-use reqwest::Client;
+use oxide_core::types::{AgentAction, ImageAttachment, Interaction};
+use oxide_core::{api_governor, llm_audit, network, openai_auth, openai_key, prometheus};
+use reqwest::{Client, Response, StatusCode};
+use serde::Deserialize;
 use serde_json::json;
 // use std::sync::Arc; // Reserved for future use
+use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 
+const GEMINI_API_BASE: &str = "https://generativelanguage.googleapis.com";
+const OPENAI_API_BASE: &str = "https://api.openai.com/v1";
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(8);
+const RETRY_AFTER_LIMIT: Duration = Duration::from_secs(60);
+
 #[async_trait]
 pub trait AIProvider {
     fn name(&self) -> &str;
+    /// Model requests go to, for the LLM audit trail.
+    fn model(&self) -> Option<&str> {
+        None
+    }
+    /// Only called with non-empty `images` when [`Self::supports_images`].
     async fn generate_response(
         &self,
         prompt: &str,
+        images: &[ImageAttachment],
         history: &[Interaction],
         function_registry: Option<&FunctionRegistry>,
     ) -> Result<String, CopilotError>;
+    fn supports_images(&self) -> bool {
+        false
+    }
     async fn call_function(&self, action: &AgentAction) -> Result<serde_json::Value, CopilotError>;
 }
 
 pub struct GoogleAIProvider {
-    #[allow(dead_code)]
     config: GoogleConfig,
     http_client: Client,
+    // generateContent-capable model IDs; empty when the lookup failed
+    available_models: Mutex<Option<Vec<String>>>,
 }
 
 impl GoogleAIProvider {
     pub fn new(config: GoogleConfig) -> Self {
         Self {
             config,
-            http_client: Client::new(),
+            http_client: network::http_client(),
+            available_models: Mutex::new(None),
+        }
+    }
+
+    fn endpoint(&self, model: &str) -> String {
+        format!(
+            "{GEMINI_API_BASE}/{}/models/{model}:generateContent",
+            self.config.api_version()
+        )
+    }
+
+    /// Reject models the account cannot use. The list is fetched once; if
+    /// that fails the check is skipped rather than blocking every request.
+    async fn check_model(&self, model: &str) -> Result<(), CopilotError> {
+        let mut cached = self.available_models.lock().await;
+        if cached.is_none() {
+            let models = GeminiAuth::new()
+                .get_generate_content_models()
+                .await
+                .unwrap_or_else(|e| {
+                    warn!("Could not list Gemini models, skipping model check: {e}");
+                    Vec::new()
+                });
+            *cached = Some(models);
+        }
+        match cached.as_deref() {
+            Some(models) if !models.is_empty() && !models.iter().any(|m| m == model) => {
+                Err(CopilotError::Configuration(format!(
+                    "Gemini model '{model}' is not available; choose one of: {}",
+                    models.join(", ")
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// POST with exponential backoff on 429, 5xx, timeouts and connection
+    /// errors. Each attempt counts against the API budget.
+    async fn post_with_retry(
+        &self,
+        url: &str,
+        access_token: &str,
+        body: &GenerateContentRequest,
+    ) -> Result<Response, CopilotError> {
+        let max_retries = self.config.max_retries();
+        let mut attempt = 0;
+        loop {
+            network::ensure_online(url)?;
+            let permit = api_governor::acquire(url).await?;
+            let result = self
+                .http_client
+                .post(url)
+                .bearer_auth(access_token)
+                .json(body)
+                .send()
+                .await;
+            drop(permit);
+
+            let server_delay = match result {
+                Ok(response) if attempt >= max_retries || !is_retryable(response.status()) => {
+                    return Ok(response)
+                }
+                Ok(response) => {
+                    warn!(
+                        "Gemini API returned {} (attempt {}/{}), retrying",
+                        response.status(),
+                        attempt + 1,
+                        max_retries + 1
+                    );
+                    retry_after(&response)
+                }
+                Err(e) if attempt < max_retries && (e.is_timeout() || e.is_connect()) => {
+                    warn!(
+                        "Gemini request failed (attempt {}/{}), retrying: {e}",
+                        attempt + 1,
+                        max_retries + 1
+                    );
+                    None
+                }
+                Err(e) => return Err(CopilotError::APIRequest(e.to_string())),
+            };
+            tokio::time::sleep(retry_delay(attempt, server_delay)).await;
+            attempt += 1;
         }
     }
 
@@ -64,13 +169,23 @@ impl AIProvider for GoogleAIProvider {
         "Google AI"
     }
 
+    fn model(&self) -> Option<&str> {
+        Some(self.config.model())
+    }
+
+    fn supports_images(&self) -> bool {
+        true
+    }
+
     async fn generate_response(
         &self,
         prompt: &str,
+        images: &[ImageAttachment],
         history: &[Interaction],
         function_registry: Option<&FunctionRegistry>,
     ) -> Result<String, CopilotError> {
         info!("Google AI: Generating response for prompt: {}", prompt);
+        network::ensure_online(GEMINI_API_BASE)?;
         let access_token = self.get_valid_access_token().await?;
 
         let mut contents: Vec<Content> = Vec::new();
@@ -147,15 +262,28 @@ impl AIProvider for GoogleAIProvider {
             }
         }
 
-        // Add the current prompt
-        contents.push(Content {
-            role: "user".to_string(),
-            parts: vec![Part {
-                text: Some(prompt.to_string()),
+        // Add the current prompt, followed by any attached images
+        let mut prompt_parts = vec![Part {
+            text: Some(prompt.to_string()),
+            function_call: None,
+            function_response: None,
+            inline_data: None,
+        }];
+        for image in images {
+            image.validate().map_err(CopilotError::Configuration)?;
+            prompt_parts.push(Part {
+                text: None,
                 function_call: None,
                 function_response: None,
-                inline_data: None,
-            }],
+                inline_data: Some(InlineData {
+                    mime_type: image.mime_type.clone(),
+                    data: image.data.clone(),
+                }),
+            });
+        }
+        contents.push(Content {
+            role: "user".to_string(),
+            parts: prompt_parts,
         });
 
         let mut request_body = GenerateContentRequest {
@@ -181,26 +309,22 @@ impl AIProvider for GoogleAIProvider {
             }
         }
 
-        // Use gemini-pro-vision if there are images in the request
+        // Requests with images go to the configured vision model
         let has_images = request_body
             .contents
             .iter()
             .any(|content| content.parts.iter().any(|part| part.inline_data.is_some()));
 
-        let model_endpoint = if has_images {
-            "https://generativelanguage.googleapis.com/v1beta/models/gemini-pro-vision:generateContent"
+        let model = if has_images {
+            self.config.vision_model()
         } else {
-            "https://generativelanguage.googleapis.com/v1beta/models/gemini-pro:generateContent"
+            self.config.model()
         };
+        self.check_model(model).await?;
 
         let response = self
-            .http_client
-            .post(model_endpoint)
-            .bearer_auth(&access_token)
-            .json(&request_body)
-            .send()
-            .await
-            .map_err(|e| CopilotError::APIRequest(e.to_string()))?;
+            .post_with_retry(&self.endpoint(model), &access_token, &request_body)
+            .await?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -259,14 +383,71 @@ impl AIProvider for GoogleAIProvider {
     }
 }
 
+fn is_retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Seconds from a `Retry-After` header; HTTP-date values are ignored.
+fn retry_after(response: &Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+/// Doubling backoff capped at `RETRY_MAX_DELAY`, stretched to the server's
+/// `Retry-After` when that is longer.
+fn retry_delay(attempt: u32, server_delay: Option<Duration>) -> Duration {
+    let backoff = RETRY_BASE_DELAY
+        .saturating_mul(1 << attempt.min(16))
+        .min(RETRY_MAX_DELAY);
+    server_delay.map_or(backoff, |d| d.min(RETRY_AFTER_LIMIT).max(backoff))
+}
+
 pub struct OpenAIProvider {
-    #[allow(dead_code)]
     config: OpenAIConfig,
+    http_client: Client,
 }
 
 impl OpenAIProvider {
     pub fn new(config: OpenAIConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            http_client: network::http_client(),
+        }
+    }
+
+    fn endpoint() -> String {
+        // Overridable for enterprise tenants, as in `openai_client`
+        let base = std::env::var("OPENAI_API_BASE").unwrap_or_else(|_| OPENAI_API_BASE.to_string());
+        format!("{}/chat/completions", base.trim_end_matches('/'))
+    }
+
+    /// The key from the config, else the stored API key (env or keyring),
+    /// else the OAuth access token.
+    async fn credential(&self) -> Result<String, CopilotError> {
+        if let Some(key) = self.config.inline_api_key() {
+            return Ok(key.to_string());
+        }
+        if let Some(key) = openai_key::get_api_key()
+            .await
+            .map_err(|e| CopilotError::Authentication(e.to_string()))?
+        {
+            return Ok(key);
+        }
+        openai_auth::get_access_token()
+            .await
+            .map_err(|e| CopilotError::Authentication(e.to_string()))?
+            .ok_or_else(|| {
+                CopilotError::Authentication(
+                    "No OpenAI API key or login found; configure one in Settings".to_string(),
+                )
+            })
     }
 }
 
@@ -276,24 +457,195 @@ impl AIProvider for OpenAIProvider {
         "OpenAI"
     }
 
+    fn model(&self) -> Option<&str> {
+        Some(self.config.model())
+    }
+
     async fn generate_response(
         &self,
         prompt: &str,
-        _history: &[Interaction],
-        _function_registry: Option<&FunctionRegistry>,
+        _images: &[ImageAttachment],
+        history: &[Interaction],
+        function_registry: Option<&FunctionRegistry>,
     ) -> Result<String, CopilotError> {
         info!("OpenAI: Generating response for prompt: {}", prompt);
-        // Placeholder for actual OpenAI API call
-        Ok(format!("OpenAI response to: {prompt}"))
+        let url = Self::endpoint();
+        network::ensure_online(&url)?;
+        let credential = self.credential().await?;
+
+        let mut body = json!({
+            "model": self.config.model(),
+            "messages": openai_messages(prompt, history)?,
+        });
+        if let Some(temperature) = self.config.temperature {
+            body["temperature"] = json!(temperature);
+        }
+        if let Some(tools) = function_registry.and_then(openai_tools) {
+            body["tools"] = json!(tools);
+        }
+
+        let permit = api_governor::acquire(&url).await?;
+        let response = self
+            .http_client
+            .post(&url)
+            .bearer_auth(&credential)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| CopilotError::APIRequest(e.to_string()))?;
+        drop(permit);
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            error!("OpenAI API error: Status: {status}, Body: {error_text}");
+            return Err(CopilotError::APIRequest(format!(
+                "OpenAI API returned non-success status: {status} - {error_text}"
+            )));
+        }
+
+        let api_response = response
+            .json::<OpenAIChatResponse>()
+            .await
+            .map_err(|e| CopilotError::APIResponseParse(e.to_string()))?;
+        openai_reply(api_response)
     }
 
     async fn call_function(&self, action: &AgentAction) -> Result<serde_json::Value, CopilotError> {
         info!("OpenAI: Calling function: {}", action.action_type);
-        // Placeholder for actual OpenAI function call
+        // Functions run locally through the registry; nothing to send here
         Ok(serde_json::json!({ "status": "success", "action": action.action_type }))
     }
 }
 
+#[derive(Deserialize, Debug)]
+struct OpenAIChatResponse {
+    choices: Vec<OpenAIChoice>,
+}
+
+#[derive(Deserialize, Debug)]
+struct OpenAIChoice {
+    message: OpenAIResponseMessage,
+}
+
+#[derive(Deserialize, Debug)]
+struct OpenAIResponseMessage {
+    content: Option<String>,
+    #[serde(default)]
+    tool_calls: Vec<OpenAIToolCall>,
+}
+
+#[derive(Deserialize, Debug)]
+struct OpenAIToolCall {
+    function: OpenAIFunctionCall,
+}
+
+#[derive(Deserialize, Debug)]
+struct OpenAIFunctionCall {
+    name: String,
+    // JSON-encoded arguments
+    arguments: String,
+}
+
+/// Chat messages for `history` followed by `prompt`. Stored function calls
+/// become assistant `tool_calls` and their results `tool` messages, paired
+/// through synthetic call IDs since the history does not keep OpenAI's.
+fn openai_messages(
+    prompt: &str,
+    history: &[Interaction],
+) -> Result<Vec<serde_json::Value>, CopilotError> {
+    let mut messages = Vec::new();
+    let mut call_count = 0usize;
+    for interaction in history {
+        if !interaction.user_input.is_empty() {
+            messages.push(json!({ "role": "user", "content": interaction.user_input }));
+        }
+        let response = interaction.agent_response.as_str();
+        if response.is_empty() {
+            continue;
+        }
+        if let Some(call) = response.strip_prefix("FUNCTION_CALL:") {
+            let call: FunctionCall = serde_json::from_str(call.trim())?;
+            call_count += 1;
+            messages.push(json!({
+                "role": "assistant",
+                "content": null,
+                "tool_calls": [{
+                    "id": format!("call_{call_count}"),
+                    "type": "function",
+                    "function": {
+                        "name": call.name,
+                        "arguments": call.args.to_string(),
+                    }
+                }]
+            }));
+        } else if let Some(result) = response.strip_prefix("FUNCTION_RESPONSE:") {
+            let result: FunctionResponse = serde_json::from_str(result.trim())?;
+            messages.push(json!({
+                "role": "tool",
+                "tool_call_id": format!("call_{call_count}"),
+                "content": result.response.to_string(),
+            }));
+        } else {
+            messages.push(json!({ "role": "assistant", "content": response }));
+        }
+    }
+    messages.push(json!({ "role": "user", "content": prompt }));
+    Ok(messages)
+}
+
+/// Registry functions as OpenAI tool definitions; `None` when there are none.
+fn openai_tools(registry: &FunctionRegistry) -> Option<Vec<serde_json::Value>> {
+    let tools: Vec<_> = registry
+        .get_all_function_schemas()
+        .into_iter()
+        .map(|schema| {
+            // OpenAI requires an object schema even for argument-less functions
+            let parameters = match &schema["parameters"] {
+                serde_json::Value::Object(map) if !map.is_empty() => schema["parameters"].clone(),
+                _ => json!({ "type": "object", "properties": {} }),
+            };
+            json!({
+                "type": "function",
+                "function": {
+                    "name": schema["name"],
+                    "description": schema["description"],
+                    "parameters": parameters,
+                }
+            })
+        })
+        .collect();
+    (!tools.is_empty()).then_some(tools)
+}
+
+/// The reply text, or the first requested tool call in the
+/// `FUNCTION_CALL:` form the copilot loop executes.
+fn openai_reply(response: OpenAIChatResponse) -> Result<String, CopilotError> {
+    let message = response
+        .choices
+        .into_iter()
+        .next()
+        .ok_or(CopilotError::NoAICandidates)?
+        .message;
+    if let Some(call) = message.tool_calls.into_iter().next() {
+        let args = if call.function.arguments.trim().is_empty() {
+            json!({})
+        } else {
+            serde_json::from_str(&call.function.arguments)
+                .map_err(|e| CopilotError::InvalidFunctionCallJson(e.to_string()))?
+        };
+        let call = FunctionCall {
+            name: call.function.name,
+            args,
+        };
+        return Ok(format!("FUNCTION_CALL: {}", serde_json::to_string(&call)?));
+    }
+    message
+        .content
+        .filter(|text| !text.is_empty())
+        .ok_or(CopilotError::NoAIResponseContent)
+}
+
 pub struct AnthropicProvider {
     #[allow(dead_code)]
     config: AnthropicConfig,
@@ -314,6 +666,7 @@ impl AIProvider for AnthropicProvider {
     async fn generate_response(
         &self,
         prompt: &str,
+        _images: &[ImageAttachment],
         _history: &[Interaction],
         _function_registry: Option<&FunctionRegistry>,
     ) -> Result<String, CopilotError> {
@@ -349,6 +702,7 @@ impl AIProvider for AzureOpenAIProvider {
     async fn generate_response(
         &self,
         prompt: &str,
+        _images: &[ImageAttachment],
         _history: &[Interaction],
         _function_registry: Option<&FunctionRegistry>,
     ) -> Result<String, CopilotError> {
@@ -384,6 +738,7 @@ impl AIProvider for OllamaProvider {
     async fn generate_response(
         &self,
         prompt: &str,
+        _images: &[ImageAttachment],
         _history: &[Interaction],
         _function_registry: Option<&FunctionRegistry>,
     ) -> Result<String, CopilotError> {
@@ -402,11 +757,22 @@ impl AIProvider for OllamaProvider {
 pub struct AIOrchestrator {
     providers: Vec<Box<dyn AIProvider + Send + Sync>>,
     current_provider_index: Mutex<usize>,
+    // Applied to every response before it is returned
+    post_processing: PostProcessPipeline,
 }
 
 impl AIOrchestrator {
     pub fn new(config: AIProvidersConfig) -> Self {
         let mut providers: Vec<Box<dyn AIProvider + Send + Sync>> = Vec::new();
+        if config.google.is_some()
+            || config.openai.is_some()
+            || config.anthropic.is_some()
+            || config.azure_openai.is_some()
+        {
+            capabilities::available(Feature::CloudLlm);
+        } else {
+            capabilities::unavailable(Feature::CloudLlm, "No cloud AI provider is configured");
+        }
 
         if let Some(google_config) = config.google {
             providers.push(Box::new(GoogleAIProvider::new(google_config)));
@@ -427,15 +793,83 @@ impl AIOrchestrator {
         Self {
             providers,
             current_provider_index: Mutex::new(0),
+            post_processing: PostProcessPipeline::default(),
         }
     }
 
+    /// Replace the default post-processing stages.
+    pub fn with_post_processing(mut self, pipeline: PostProcessPipeline) -> Self {
+        self.post_processing = pipeline;
+        self
+    }
+
     pub async fn generate_response(
         &self,
         prompt: &str,
         history: &[Interaction],
         function_registry: Option<&FunctionRegistry>,
     ) -> Result<String, CopilotError> {
+        self.generate_response_with_images(prompt, &[], history, function_registry)
+            .await
+    }
+
+    /// Like [`Self::generate_response`]; with images attached only
+    /// vision-capable providers are tried and the preferred provider is
+    /// left unchanged.
+    /// Name of the provider the next request is sent to first.
+    pub async fn current_provider_name(&self) -> Option<String> {
+        let index = *self.current_provider_index.lock().await;
+        self.providers.get(index).map(|p| p.name().to_string())
+    }
+
+    pub async fn generate_response_with_images(
+        &self,
+        prompt: &str,
+        images: &[ImageAttachment],
+        history: &[Interaction],
+        function_registry: Option<&FunctionRegistry>,
+    ) -> Result<String, CopilotError> {
+        let response = self
+            .generate_raw(prompt, images, history, function_registry)
+            .await?;
+        Ok(self
+            .post_processing
+            .run(&response, &PostProcessContext::default())
+            .text)
+    }
+
+    /// Answer from `sources`, returning the extracted commands, warnings and
+    /// citation checks along with the text. Citations outside `sources` are
+    /// flagged.
+    pub async fn generate_grounded_response(
+        &self,
+        prompt: &str,
+        sources: Vec<String>,
+        history: &[Interaction],
+        function_registry: Option<&FunctionRegistry>,
+    ) -> Result<ProcessedResponse, CopilotError> {
+        let response = self
+            .generate_raw(prompt, &[], history, function_registry)
+            .await?;
+        Ok(self
+            .post_processing
+            .run(&response, &PostProcessContext::grounded(sources)))
+    }
+
+    async fn generate_raw(
+        &self,
+        prompt: &str,
+        images: &[ImageAttachment],
+        history: &[Interaction],
+        function_registry: Option<&FunctionRegistry>,
+    ) -> Result<String, CopilotError> {
+        // All providers are cloud-hosted; don't cycle through them just to fail
+        network::ensure_online("AI providers")?;
+        if !images.is_empty() {
+            return self
+                .generate_vision_response(prompt, images, history, function_registry)
+                .await;
+        }
         let initial_index = {
             let current_index = self.current_provider_index.lock().await;
             *current_index
@@ -448,10 +882,7 @@ impl AIOrchestrator {
                 "Attempting to generate response with {} provider.",
                 provider.name()
             );
-            match provider
-                .generate_response(prompt, history, function_registry)
-                .await
-            {
+            match timed_generate(provider.as_ref(), prompt, &[], history, function_registry).await {
                 Ok(response) => return Ok(response),
                 Err(e) => {
                     error!("Provider {} failed: {}", provider.name(), e);
@@ -510,4 +941,192 @@ impl AIOrchestrator {
             }
         }
     }
+
+    async fn generate_vision_response(
+        &self,
+        prompt: &str,
+        images: &[ImageAttachment],
+        history: &[Interaction],
+        function_registry: Option<&FunctionRegistry>,
+    ) -> Result<String, CopilotError> {
+        let mut last_error = None;
+        for provider in self.providers.iter().filter(|p| p.supports_images()) {
+            info!(
+                "Attempting to answer with {} image(s) using {} provider.",
+                images.len(),
+                provider.name()
+            );
+            match timed_generate(
+                provider.as_ref(),
+                prompt,
+                images,
+                history,
+                function_registry,
+            )
+            .await
+            {
+                Ok(response) => return Ok(response),
+                Err(e) => {
+                    error!("Provider {} failed: {}", provider.name(), e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(match last_error {
+            Some(e) => CopilotError::AIProvider(format!(
+                "All vision-capable AI providers failed to generate a response: {e}"
+            )),
+            None => CopilotError::Configuration(
+                "No configured AI provider supports image input".to_string(),
+            ),
+        })
+    }
+}
+
+/// One provider attempt, recorded in the LLM latency histogram.
+async fn timed_generate(
+    provider: &(dyn AIProvider + Send + Sync),
+    prompt: &str,
+    images: &[ImageAttachment],
+    history: &[Interaction],
+    function_registry: Option<&FunctionRegistry>,
+) -> Result<String, CopilotError> {
+    let started = Instant::now();
+    let request = provider.generate_response(prompt, images, history, function_registry);
+    let sent = audit_text(prompt, images, history);
+    let result = llm_audit::audited(provider.name(), provider.model(), &sent, request).await;
+    let outcome = if result.is_ok() { "success" } else { "error" };
+    prometheus::LLM_REQUEST_SECONDS.observe(
+        &[("provider", provider.name()), ("outcome", outcome)],
+        started.elapsed().as_secs_f64(),
+    );
+    result
+}
+
+/// The conversation a provider is sent, as audit text.
+fn audit_text(prompt: &str, images: &[ImageAttachment], history: &[Interaction]) -> String {
+    let mut blocks: Vec<String> = history
+        .iter()
+        .flat_map(|turn| {
+            [
+                format!("user: {}", turn.user_input),
+                format!("assistant: {}", turn.agent_response),
+            ]
+        })
+        .collect();
+    blocks.push(format!("user: {prompt}"));
+    if !images.is_empty() {
+        blocks.push(format!("[{} image(s) attached]", images.len()));
+    }
+    blocks.join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn google_config() -> GoogleConfig {
+        GoogleConfig {
+            api_key: "test".to_string(),
+            model: Some("gemini-1.5-pro".to_string()),
+            vision_model: None,
+            api_version: None,
+            max_retries: None,
+        }
+    }
+
+    #[test]
+    fn gemini_endpoint_uses_configured_model_and_version() {
+        let provider = GoogleAIProvider::new(google_config());
+        assert_eq!(
+            provider.endpoint(provider.config.vision_model()),
+            "https://generativelanguage.googleapis.com/v1/models/gemini-1.5-pro:generateContent"
+        );
+    }
+
+    fn interaction(user_input: &str, agent_response: &str) -> Interaction {
+        Interaction {
+            id: uuid::Uuid::new_v4(),
+            timestamp: chrono::Utc::now(),
+            user_input: user_input.to_string(),
+            agent_response: agent_response.to_string(),
+            context: oxide_core::types::Context {
+                active_window: None,
+                clipboard_text: None,
+                system_status: None,
+                recent_events: Vec::new(),
+                memory_context: None,
+            },
+        }
+    }
+
+    #[test]
+    fn openai_history_maps_function_calls_to_tool_messages() {
+        let history = [
+            interaction("What time is it?", ""),
+            interaction(
+                "",
+                r#"FUNCTION_CALL: {"name":"get_current_time","args":{}}"#,
+            ),
+            interaction(
+                "",
+                r#"FUNCTION_RESPONSE: {"name":"get_current_time","response":{"current_time":"noon"}}"#,
+            ),
+        ];
+        let messages = openai_messages("And the date?", &history).unwrap();
+        assert_eq!(messages.len(), 4);
+        assert_eq!(messages[0]["role"], "user");
+        let call = &messages[1]["tool_calls"][0];
+        assert_eq!(call["function"]["name"], "get_current_time");
+        assert_eq!(messages[2]["role"], "tool");
+        assert_eq!(messages[2]["tool_call_id"], call["id"]);
+        assert_eq!(messages[3]["content"], "And the date?");
+
+        let tools = openai_tools(&FunctionRegistry::new()).unwrap();
+        let time = tools
+            .iter()
+            .find(|t| t["function"]["name"] == "get_current_time")
+            .unwrap();
+        assert_eq!(time["function"]["parameters"]["type"], "object");
+    }
+
+    #[test]
+    fn openai_tool_call_reply_becomes_function_call() {
+        let reply: OpenAIChatResponse = serde_json::from_str(
+            r#"{"choices":[{"message":{"role":"assistant","content":null,"tool_calls":[
+                {"id":"call_abc","type":"function","function":{"name":"read_file","arguments":"{\"path\":\"a.txt\"}"}}
+            ]}}]}"#,
+        )
+        .unwrap();
+        let text = openai_reply(reply).unwrap();
+        let call: FunctionCall =
+            serde_json::from_str(text.strip_prefix("FUNCTION_CALL:").unwrap().trim()).unwrap();
+        assert_eq!(call.name, "read_file");
+        assert_eq!(call.args["path"], "a.txt");
+
+        let empty: OpenAIChatResponse = serde_json::from_str(r#"{"choices":[]}"#).unwrap();
+        assert!(matches!(
+            openai_reply(empty),
+            Err(CopilotError::NoAICandidates)
+        ));
+    }
+
+    #[test]
+    fn retry_backoff_doubles_and_honours_retry_after() {
+        assert!(is_retryable(StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_retryable(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(!is_retryable(StatusCode::BAD_REQUEST));
+
+        assert_eq!(retry_delay(0, None), Duration::from_millis(500));
+        assert_eq!(retry_delay(2, None), Duration::from_secs(2));
+        assert_eq!(retry_delay(10, None), RETRY_MAX_DELAY);
+        assert_eq!(
+            retry_delay(0, Some(Duration::from_secs(5))),
+            Duration::from_secs(5)
+        );
+        assert_eq!(
+            retry_delay(0, Some(Duration::from_secs(600))),
+            RETRY_AFTER_LIMIT
+        );
+    }
 }
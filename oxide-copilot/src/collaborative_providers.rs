@@ -1,11 +1,23 @@
 use crate::errors::CopilotError;
-use crate::llm_orchestrator::{CollaborativeContext, CollaborativeLLM, LLMRole};
+use crate::llm_orchestrator::{CollaborativeContext, CollaborativeLLM, LLMConfig, LLMRole};
+use crate::response_cache;
 use async_trait::async_trait;
 use log::info;
+use oxide_core::config::OrchestratorConfig;
 use oxide_core::gemini_auth::GeminiAuth;
 use oxide_core::openai_client::{self, ChatMessage};
+use oxide_core::prompts;
 use oxide_core::qwen_auth::QwenAuth;
 
+/// System prompt for `role`, preferring `provider`'s variant of the template.
+fn role_system_prompt(role: &LLMRole, provider: &str) -> String {
+    prompts::render(
+        &prompts::role_template(&role.to_string()),
+        Some(provider),
+        &[],
+    )
+}
+
 /// Gemini implementation for collaborative tasks
 pub struct CollaborativeGemini {
     auth: GeminiAuth,
@@ -78,14 +90,7 @@ impl CollaborativeLLM for CollaborativeGemini {
     ) -> Result<String, CopilotError> {
         self.ensure_authenticated().await?;
 
-        // Create role-specific system prompt
-        let system_prompt = match self.role() {
-            LLMRole::Coordinator => "You are the primary coordinator for system tasks. You analyze requests, create execution plans, and coordinate with other AI agents.",
-            LLMRole::Analyst => "You are a technical analyst specializing in deep system analysis, performance optimization, and security assessment.",
-            LLMRole::Executor => "You are a system executor responsible for carrying out system operations, commands, and automated tasks.",
-            LLMRole::Innovator => "You are an innovator who provides creative solutions, alternative approaches, and optimization strategies.",
-            LLMRole::Validator => "You are a validator who reviews and validates AI responses, ensuring quality, consistency, and safety.",
-        };
+        let system_prompt = role_system_prompt(&self.role(), self.name());
 
         let full_prompt = format!(
             "{}\n\n{}\n\nTask: {}\nUser Input: {}",
@@ -189,14 +194,7 @@ impl CollaborativeLLM for CollaborativeQwen {
     ) -> Result<String, CopilotError> {
         self.ensure_authenticated().await?;
 
-        // Create role-specific system prompt
-        let system_prompt = match self.role() {
-            LLMRole::Coordinator => "You are the primary coordinator for system tasks. You analyze requests, create execution plans, and coordinate with other AI agents.",
-            LLMRole::Analyst => "You are a technical analyst specializing in deep system analysis, performance optimization, and security assessment.",
-            LLMRole::Executor => "You are a system executor responsible for carrying out system operations, commands, and automated tasks.",
-            LLMRole::Innovator => "You are an innovator who provides creative solutions, alternative approaches, and optimization strategies.",
-            LLMRole::Validator => "You are a validator who reviews and validates AI responses, ensuring quality, consistency, and safety.",
-        };
+        let system_prompt = role_system_prompt(&self.role(), self.name());
 
         let full_prompt = format!(
             "{}\n\n{}\n\nTask: {}\nUser Input: {}",
@@ -216,6 +214,8 @@ impl CollaborativeLLM for CollaborativeQwen {
 pub struct CollaborativeOpenAI {
     role: LLMRole,
     model: String,
+    temperature: f32,
+    max_tokens: u32,
 }
 
 impl CollaborativeOpenAI {
@@ -223,7 +223,46 @@ impl CollaborativeOpenAI {
         Self {
             role,
             model: model.unwrap_or_else(|| "gpt-4o".to_string()),
+            temperature: 0.7,
+            max_tokens: 2000,
+        }
+    }
+
+    /// Override the sampling temperature and response length.
+    pub fn with_generation(mut self, temperature: f32, max_tokens: Option<u32>) -> Self {
+        self.temperature = temperature;
+        if let Some(max_tokens) = max_tokens {
+            self.max_tokens = max_tokens;
         }
+        self
+    }
+
+    /// Chat completion, served from the response cache at low temperatures.
+    async fn chat(
+        &self,
+        messages: Vec<ChatMessage>,
+    ) -> Result<String, openai_client::OpenAIClientError> {
+        let prompt = response_cache::messages_key(
+            messages
+                .iter()
+                .map(|m| (m.role.as_str(), m.content.as_str())),
+        );
+        response_cache::global()
+            .get_or_fetch(
+                "openai",
+                &self.model,
+                Some(self.temperature),
+                &prompt,
+                || {
+                    openai_client::chat_completion(
+                        &self.model,
+                        messages,
+                        Some(self.temperature),
+                        Some(self.max_tokens),
+                    )
+                },
+            )
+            .await
     }
 
     async fn ensure_authenticated(&self) -> Result<(), CopilotError> {
@@ -277,7 +316,7 @@ impl CollaborativeLLM for CollaborativeOpenAI {
         let messages = vec![
             ChatMessage {
                 role: "system".to_string(),
-                content: "You are an intelligent system assistant helping with system analysis and automation.".to_string(),
+                content: prompts::render(prompts::ASSISTANT_SYSTEM, Some(self.name()), &[]),
             },
             ChatMessage {
                 role: "user".to_string(),
@@ -285,7 +324,7 @@ impl CollaborativeLLM for CollaborativeOpenAI {
             },
         ];
 
-        openai_client::chat_completion(&self.model, messages, Some(0.7), Some(2000))
+        self.chat(messages)
             .await
             .map_err(|e| CopilotError::AIProvider(format!("OpenAI error: {e}")))
     }
@@ -298,14 +337,7 @@ impl CollaborativeLLM for CollaborativeOpenAI {
     ) -> Result<String, CopilotError> {
         self.ensure_authenticated().await?;
 
-        // Create role-specific system prompt
-        let system_prompt = match self.role() {
-            LLMRole::Coordinator => "You are the primary coordinator for system tasks. You analyze requests, create execution plans, and coordinate with other AI agents.",
-            LLMRole::Analyst => "You are a technical analyst specializing in deep system analysis, performance optimization, and security assessment.",
-            LLMRole::Executor => "You are a system executor responsible for carrying out system operations, commands, and automated tasks.",
-            LLMRole::Innovator => "You are an innovator who provides creative solutions, alternative approaches, and optimization strategies.",
-            LLMRole::Validator => "You are a validator who reviews and validates AI responses, ensuring quality, consistency, and safety.",
-        };
+        let system_prompt = role_system_prompt(&self.role(), self.name());
 
         let full_prompt = format!(
             "{}\n\nTask: {}\nUser Input: {}",
@@ -320,7 +352,7 @@ impl CollaborativeLLM for CollaborativeOpenAI {
         let messages = vec![
             ChatMessage {
                 role: "system".to_string(),
-                content: system_prompt.to_string(),
+                content: system_prompt,
             },
             ChatMessage {
                 role: "user".to_string(),
@@ -328,13 +360,16 @@ impl CollaborativeLLM for CollaborativeOpenAI {
             },
         ];
 
-        openai_client::chat_completion(&self.model, messages, Some(0.7), Some(2000))
+        self.chat(messages)
             .await
             .map_err(|e| CopilotError::AIProvider(format!("OpenAI analysis error: {e}")))
     }
 }
 
 /// Factory for creating collaborative LLM providers
+/// Provider name, instance and the configuration it was built from.
+pub type ConfiguredProvider = (String, Box<dyn CollaborativeLLM>, LLMConfig);
+
 pub struct CollaborativeProviderFactory;
 
 impl CollaborativeProviderFactory {
@@ -378,4 +413,81 @@ impl CollaborativeProviderFactory {
             ),
         ]
     }
+
+    /// Create providers for the role assignments in `config`, in role
+    /// order. Temperature and max tokens are applied by providers that
+    /// support them (currently OpenAI) and recorded in each [`LLMConfig`].
+    pub fn create_from_config(
+        config: &OrchestratorConfig,
+    ) -> Result<Vec<ConfiguredProvider>, CopilotError> {
+        let mut providers = Vec::new();
+        for role_name in OrchestratorConfig::ROLES {
+            let Some(role_cfg) = config.roles.get(role_name) else {
+                continue;
+            };
+            let role: LLMRole = role_name.parse()?;
+            let model = role_cfg.model.clone();
+            let provider: Box<dyn CollaborativeLLM> = match role_cfg.provider.as_str() {
+                "gemini" => Self::create_gemini(role.clone(), model.clone()),
+                "qwen" => Self::create_qwen(role.clone(), model.clone()),
+                "openai" => Box::new(
+                    CollaborativeOpenAI::new(role.clone(), model.clone())
+                        .with_generation(role_cfg.temperature, role_cfg.max_tokens),
+                ),
+                other => {
+                    return Err(CopilotError::Configuration(format!(
+                        "Unknown provider '{other}' for role {role_name}"
+                    )))
+                }
+            };
+            let name = format!("{}_{role_name}", role_cfg.provider);
+            let llm_config = LLMConfig {
+                provider: name.clone(),
+                model: model.or_else(|| Some("default".to_string())),
+                role: role.clone(),
+                temperature: role_cfg.temperature,
+                max_tokens: role_cfg.max_tokens,
+                system_prompt: role_system_prompt(&role, &role_cfg.provider),
+            };
+            providers.push((name, provider, llm_config));
+        }
+        Ok(providers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxide_core::config::OrchestratorRoleConfig;
+
+    #[test]
+    fn providers_follow_role_assignments() {
+        let mut config = OrchestratorConfig::default();
+        config.roles.insert(
+            "analyst".to_string(),
+            OrchestratorRoleConfig {
+                provider: "openai".to_string(),
+                model: Some("gpt-4o-mini".to_string()),
+                temperature: 0.4,
+                max_tokens: Some(1024),
+            },
+        );
+
+        let providers = CollaborativeProviderFactory::create_from_config(&config).unwrap();
+        let names: Vec<&str> = providers.iter().map(|(n, _, _)| n.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "gemini_coordinator",
+                "openai_analyst",
+                "gemini_executor",
+                "qwen_validator"
+            ]
+        );
+        let (_, provider, analyst) = &providers[1];
+        assert_eq!(provider.name(), "OpenAI");
+        assert_eq!(analyst.role, LLMRole::Analyst);
+        assert_eq!(analyst.temperature, 0.4);
+        assert_eq!(analyst.max_tokens, Some(1024));
+    }
 }
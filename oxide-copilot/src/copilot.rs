@@ -1,13 +1,16 @@
 use crate::ai::AIOrchestrator;
 use crate::functions::FunctionRegistry;
 use oxide_core::config::CopilotConfig;
-use oxide_core::types::{Context, Interaction};
+use oxide_core::context_provider;
+use oxide_core::types::{Context, ImageAttachment, Interaction};
 // use serde_json::Value; // Reserved for future use
 
 use crate::errors::CopilotError;
-use crate::gemini_api::{FunctionCall, FunctionResponse, Part};
+use crate::functions::{capture_screen_attachment, recognize_screen_text};
+use crate::gemini_api::{FunctionCall, FunctionResponse};
 // use image::{ImageBuffer, Rgba}; // Reserved for future use
 use log::{error, info};
+use oxide_rpa::ocr::OcrEngine;
 use oxide_rpa::rpa::ScreenCapture;
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -17,6 +20,7 @@ pub struct CopilotAgent {
     ai_orchestrator: Arc<AIOrchestrator>,
     conversation_history: Mutex<Vec<Interaction>>,
     screen_capture: ScreenCapture,
+    ocr: OcrEngine,
     function_registry: Arc<FunctionRegistry>,
 }
 
@@ -31,20 +35,72 @@ impl CopilotAgent {
             ai_orchestrator,
             conversation_history: Mutex::new(Vec::new()),
             screen_capture: ScreenCapture::new(),
+            ocr: OcrEngine::default(),
             function_registry,
         }
     }
 
+    /// Provider that will answer the next request, for sizing its context.
+    pub async fn current_provider_name(&self) -> Option<String> {
+        self.ai_orchestrator.current_provider_name().await
+    }
+
     pub async fn analyze_screen(&self) -> Result<String, CopilotError> {
         info!("CopilotAgent: Performing screen analysis.");
-        let _screenshot = self
-            .screen_capture
-            .capture_screen()
+        let screenshot = self.capture_screen().await?;
+        let mut prompt = "Describe what is on this screen and point out any errors, warnings or \
+                          security prompts."
+            .to_string();
+        // OCR text helps with small or dense text the image alone may blur
+        match self.read_screen_text().await {
+            Ok(text) if !text.trim().is_empty() => {
+                prompt.push_str("\n\nText recognized on screen (OCR, may contain errors):\n");
+                prompt.push_str(&text);
+            }
+            Ok(_) => {}
+            Err(e) => info!("Screen OCR unavailable: {e}"),
+        }
+        self.ai_orchestrator
+            .generate_response_with_images(&prompt, &[screenshot], &[], None)
+            .await
+    }
+
+    /// Screenshot of the primary screen, ready to attach to a prompt.
+    pub async fn capture_screen(&self) -> Result<ImageAttachment, CopilotError> {
+        capture_screen_attachment(&self.screen_capture)
+            .await
+            .map_err(CopilotError::ScreenCapture)
+    }
+
+    /// Text on the primary screen, for providers without vision or as
+    /// extra context.
+    pub async fn read_screen_text(&self) -> Result<String, CopilotError> {
+        recognize_screen_text(&self.screen_capture, &self.ocr, None)
+            .await
+            .map(|result| result.text)
+            .map_err(CopilotError::ScreenCapture)
+    }
+
+    /// One-shot answer to `prompt` without conversation history or tools.
+    pub async fn summarize(&self, prompt: &str) -> Result<String, CopilotError> {
+        self.ai_orchestrator
+            .generate_response(prompt, &[], None)
+            .await
+    }
+
+    /// Answer `user_input` after the caller's own `history`, leaving the
+    /// stored conversation untouched. Tools are not offered, since the
+    /// caller is not the local user.
+    pub async fn complete(
+        &self,
+        user_input: &str,
+        history: &[Interaction],
+        context: &Context,
+    ) -> Result<String, CopilotError> {
+        let prompt = context_provider::prompt_with_context(user_input, context);
+        self.ai_orchestrator
+            .generate_response(&prompt, history, None)
             .await
-            .map_err(CopilotError::ScreenCapture)?;
-        // In a real scenario, this image would be sent to a vision-capable LLM
-        // For now, we return a simple analysis result
-        Ok("Screen analysis completed successfully".to_string())
     }
 
     pub async fn update_config(&self, new_config: CopilotConfig) {
@@ -58,7 +114,22 @@ impl CopilotAgent {
         user_input: String,
         context: Context,
     ) -> Result<String, CopilotError> {
-        info!("Handling user input: {user_input}");
+        self.handle_user_input_with_images(user_input, Vec::new(), context)
+            .await
+    }
+
+    /// Answer `user_input` about the attached images (screenshots, photos of
+    /// error dialogs). Requires a vision-capable provider.
+    pub async fn handle_user_input_with_images(
+        &self,
+        user_input: String,
+        mut images: Vec<ImageAttachment>,
+        context: Context,
+    ) -> Result<String, CopilotError> {
+        info!(
+            "Handling user input with {} image(s): {user_input}",
+            images.len()
+        );
 
         // Get current history without holding the lock
         let mut current_history: Vec<Interaction> = {
@@ -76,6 +147,9 @@ impl CopilotAgent {
         };
         current_history.push(initial_interaction.clone());
 
+        // The window title and shared clipboard reach the model as a preamble
+        let prompt = context_provider::prompt_with_context(&user_input, &context);
+
         #[allow(unused_assignments)]
         let mut final_agent_response = String::new();
         let mut turn_count = 0;
@@ -89,8 +163,9 @@ impl CopilotAgent {
 
             let agent_response_str = self
                 .ai_orchestrator
-                .generate_response(
-                    &user_input, // The original prompt, or a follow-up if needed
+                .generate_response_with_images(
+                    &prompt, // The original prompt, or a follow-up if needed
+                    &images,
                     &current_history,
                     Some(&self.function_registry),
                 )
@@ -106,44 +181,17 @@ impl CopilotAgent {
                             "Executing function: {} with args: {}",
                             function_call.name, function_call.args
                         );
-                        let function_result = self
+                        let mut function_result = self
                             .function_registry
                             .execute_function(&function_call.name, function_call.args.clone())
                             .await
                             .map_err(CopilotError::FunctionExecution)?;
 
-                        // Check if this is an analyze_screen function that returned image data
-                        let mut parts = vec![];
-                        if function_call.name == "analyze_screen"
-                            && function_result.get("image_data").is_some()
-                        {
-                            // Add the image as inline data for vision analysis
-                            if let (Some(image_data), Some(mime_type)) = (
-                                function_result.get("image_data").and_then(|v| v.as_str()),
-                                function_result.get("mime_type").and_then(|v| v.as_str()),
-                            ) {
-                                parts.push(Part {
-                                    text: None,
-                                    function_call: None,
-                                    function_response: None,
-                                    inline_data: Some(crate::gemini_api::InlineData {
-                                        mime_type: mime_type.to_string(),
-                                        data: image_data.to_string(),
-                                    }),
-                                });
-                            }
+                        // Screenshots reach the model as image input on the
+                        // next turn rather than as base64 in the function output
+                        if let Some(image) = take_image(&mut function_result) {
+                            images.push(image);
                         }
-
-                        // Add the function response as text
-                        parts.push(Part {
-                            text: None,
-                            function_call: None,
-                            function_response: Some(FunctionResponse {
-                                name: function_call.name.clone(),
-                                response: function_result.clone(),
-                            }),
-                            inline_data: None,
-                        });
                         info!(
                             "Function {} executed with result: {}",
                             function_call.name, function_result
@@ -204,4 +252,20 @@ impl CopilotAgent {
     pub async fn get_conversation_history(&self) -> Vec<Interaction> {
         self.conversation_history.lock().await.clone()
     }
+
+    /// Forget the conversation, e.g. when another profile becomes active.
+    pub async fn clear_conversation_history(&self) {
+        self.conversation_history.lock().await.clear();
+    }
+}
+
+/// Remove `image_data`/`mime_type` from a function result and return them as
+/// an attachment.
+fn take_image(result: &mut serde_json::Value) -> Option<ImageAttachment> {
+    let object = result.as_object_mut()?;
+    let data = object.get("image_data")?.as_str()?.to_string();
+    let mime_type = object.get("mime_type")?.as_str()?.to_string();
+    object.remove("image_data");
+    object.remove("mime_type");
+    Some(ImageAttachment { mime_type, data })
 }
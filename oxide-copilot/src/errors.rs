@@ -26,6 +26,12 @@ pub enum CopilotError {
     APIRequest(String),
     #[error("Failed to parse API response: {0}")]
     APIResponseParse(String),
+    #[error("Configuration error: {0}")]
+    Configuration(String),
+    #[error(transparent)]
+    Offline(#[from] oxide_core::network::OfflineError),
+    #[error(transparent)]
+    Quota(#[from] oxide_core::api_governor::QuotaError),
 }
 
 #[cfg(test)]
@@ -1,7 +1,10 @@
 use async_trait::async_trait;
 // use base64::{Engine as _, engine::general_purpose}; // Reserved for future use
 use log::info;
+use oxide_core::types::ImageAttachment;
+use oxide_rpa::ocr::{OcrEngine, OcrResult, ScreenRegion};
 use oxide_rpa::rpa::{KeyboardController, MouseController, ScreenCapture};
+use oxide_rpa::window_manager::{self, WindowAction, WindowQuery};
 
 use serde_json::{json, Value};
 use std::collections::HashMap;
@@ -15,6 +18,12 @@ pub trait ExecutableFunction: Send + Sync {
     fn description(&self) -> &str;
     fn parameters(&self) -> Value;
     async fn execute(&self, args: Value) -> Result<Value, String>;
+
+    /// Whether a call with `args` changes system state in a way that needs
+    /// user confirmation before an execution plan may run it.
+    fn is_destructive(&self, _args: &Value) -> bool {
+        false
+    }
 }
 
 // Example function: get_current_time
@@ -190,6 +199,10 @@ impl ExecutableFunction for ClickMouseFunction {
         })
     }
 
+    fn is_destructive(&self, _args: &Value) -> bool {
+        true
+    }
+
     async fn execute(&self, args: Value) -> Result<Value, String> {
         let x = args["x"]
             .as_f64()
@@ -264,6 +277,10 @@ impl ExecutableFunction for TypeTextFunction {
         })
     }
 
+    fn is_destructive(&self, _args: &Value) -> bool {
+        true
+    }
+
     async fn execute(&self, args: Value) -> Result<Value, String> {
         let text = args["text"].as_str().ok_or("Missing 'text' argument")?;
 
@@ -278,6 +295,134 @@ impl ExecutableFunction for TypeTextFunction {
     }
 }
 
+/// Longest screenshot edge sent to vision models; larger captures are scaled
+/// down to stay well inside inline request limits.
+const MAX_SCREENSHOT_EDGE: u32 = 1920;
+
+/// Capture the primary screen as a PNG attachment for a vision model.
+pub async fn capture_screen_attachment(
+    screen_capture: &ScreenCapture,
+) -> Result<ImageAttachment, String> {
+    let capture = screen_capture
+        .capture_screen()
+        .await
+        .map_err(|e| format!("Failed to capture screen: {e}"))?;
+    // oxide-rpa uses an older `image` release; hand over the raw RGBA buffer
+    let (width, height) = capture.dimensions();
+    let mut image = image::RgbaImage::from_raw(width, height, capture.into_raw())
+        .ok_or("Screenshot buffer has unexpected size")?;
+    let longest = width.max(height);
+    if longest > MAX_SCREENSHOT_EDGE {
+        let scale = MAX_SCREENSHOT_EDGE as f64 / longest as f64;
+        image = image::imageops::resize(
+            &image,
+            ((width as f64 * scale) as u32).max(1),
+            ((height as f64 * scale) as u32).max(1),
+            image::imageops::FilterType::Triangle,
+        );
+    }
+    let mut png = Vec::new();
+    image
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode screenshot: {e}"))?;
+    ImageAttachment::from_bytes(&png, "image/png")
+}
+
+/// Recognize the text in `region` of the primary screen, or all of it.
+pub async fn recognize_screen_text(
+    screen_capture: &ScreenCapture,
+    ocr: &OcrEngine,
+    region: Option<ScreenRegion>,
+) -> Result<OcrResult, String> {
+    let capture = match region {
+        Some(r) => {
+            screen_capture
+                .capture_area(r.x, r.y, r.width, r.height)
+                .await
+        }
+        None => screen_capture.capture_screen().await,
+    }
+    .map_err(|e| format!("Failed to capture screen: {e}"))?;
+    let ocr = ocr.clone();
+    tokio::task::spawn_blocking(move || ocr.recognize(&capture))
+        .await
+        .map_err(|e| format!("OCR task failed: {e}"))?
+}
+
+// Function: read_screen_text
+pub struct ReadScreenTextFunction {
+    screen_capture: ScreenCapture,
+    ocr: OcrEngine,
+}
+
+impl Default for ReadScreenTextFunction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ReadScreenTextFunction {
+    pub fn new() -> Self {
+        Self {
+            screen_capture: ScreenCapture::new(),
+            ocr: OcrEngine::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl ExecutableFunction for ReadScreenTextFunction {
+    fn name(&self) -> &str {
+        "read_screen_text"
+    }
+
+    fn description(&self) -> &str {
+        "Reads the text on screen with OCR, optionally within a region. With 'expected', also reports whether that text is visible, e.g. to check that a dialog says 'Saved'."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "x": { "type": "integer", "description": "Region left edge in pixels (optional)" },
+                "y": { "type": "integer", "description": "Region top edge in pixels (optional)" },
+                "width": { "type": "integer", "description": "Region width in pixels (optional)" },
+                "height": { "type": "integer", "description": "Region height in pixels (optional)" },
+                "expected": {
+                    "type": "string",
+                    "description": "Text that should be visible (optional)"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: Value) -> Result<Value, String> {
+        let coord = |key: &str| args[key].as_u64().and_then(|v| u32::try_from(v).ok());
+        let region = match (coord("x"), coord("y"), coord("width"), coord("height")) {
+            (Some(x), Some(y), Some(width), Some(height)) => Some(ScreenRegion {
+                x,
+                y,
+                width,
+                height,
+            }),
+            (None, None, None, None) => None,
+            _ => return Err("Region needs x, y, width and height together".to_string()),
+        };
+        info!("Executing read_screen_text for region {region:?}");
+
+        let result = recognize_screen_text(&self.screen_capture, &self.ocr, region).await?;
+        let mut response = json!({
+            "text": result.text,
+            "confidence": result.confidence,
+        });
+        if let Some(expected) = args["expected"].as_str() {
+            response["expected"] = json!(expected);
+            response["found"] = json!(result.contains(expected));
+        }
+        Ok(response)
+    }
+}
+
 // Function: analyze_screen
 pub struct AnalyzeScreenFunction {
     screen_capture: ScreenCapture,
@@ -326,19 +471,15 @@ impl ExecutableFunction for AnalyzeScreenFunction {
 
         info!("Analyzing screen with question: {}", question);
 
-        match self.screen_capture.capture_screen().await {
-            Ok(_image) => {
-                // For now, return a simplified response without image encoding
-                // to avoid version conflicts between image crates
-                Ok(json!({
-                    "success": true,
-                    "message": "Screenshot captured for analysis",
-                    "question": question,
-                    "note": "Image encoding temporarily disabled due to version conflicts"
-                }))
-            }
-            Err(e) => Err(format!("Failed to capture screen: {e}")),
-        }
+        // The copilot moves image_data into the next model turn as image input
+        let image = capture_screen_attachment(&self.screen_capture).await?;
+        Ok(json!({
+            "success": true,
+            "message": "Screenshot captured for analysis",
+            "question": question,
+            "image_data": image.data,
+            "mime_type": image.mime_type,
+        }))
     }
 }
 
@@ -373,6 +514,10 @@ impl ExecutableFunction for ExecuteCommandFunction {
         })
     }
 
+    fn is_destructive(&self, _args: &Value) -> bool {
+        true
+    }
+
     async fn execute(&self, args: Value) -> Result<Value, String> {
         let command = args["command"]
             .as_str()
@@ -405,6 +550,95 @@ impl ExecutableFunction for ExecuteCommandFunction {
     }
 }
 
+// Function: list_windows
+pub struct ListWindowsFunction;
+
+#[async_trait]
+impl ExecutableFunction for ListWindowsFunction {
+    fn name(&self) -> &str {
+        "list_windows"
+    }
+
+    fn description(&self) -> &str {
+        "Lists open application windows with their title, process name, position and size."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({})
+    }
+
+    async fn execute(&self, _args: Value) -> Result<Value, String> {
+        info!("Executing list_windows function.");
+        let windows = tokio::task::spawn_blocking(window_manager::list_windows)
+            .await
+            .map_err(|e| format!("Window listing task failed: {e}"))??;
+        Ok(json!({ "windows": windows }))
+    }
+}
+
+// Function: manage_window
+pub struct ManageWindowFunction;
+
+#[async_trait]
+impl ExecutableFunction for ManageWindowFunction {
+    fn name(&self) -> &str {
+        "manage_window"
+    }
+
+    fn description(&self) -> &str {
+        "Focuses (brings to the front), minimizes, maximizes, restores or closes an application window, selected by title and/or process name."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["focus", "minimize", "maximize", "restore", "close"],
+                    "description": "What to do with the window"
+                },
+                "title": {
+                    "type": "string",
+                    "description": "Part of the window title, case-insensitive"
+                },
+                "process": {
+                    "type": "string",
+                    "description": "Process name, e.g. 'Code' for VS Code or 'chrome'"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    fn is_destructive(&self, args: &Value) -> bool {
+        args["action"]
+            .as_str()
+            .and_then(|a| a.parse::<WindowAction>().ok())
+            == Some(WindowAction::Close)
+    }
+
+    async fn execute(&self, args: Value) -> Result<Value, String> {
+        let action: WindowAction = args["action"]
+            .as_str()
+            .ok_or("Missing 'action' argument for manage_window function.")?
+            .parse()?;
+        let query = WindowQuery {
+            title: args["title"].as_str().map(str::to_string),
+            process: args["process"].as_str().map(str::to_string),
+        };
+        info!("Executing manage_window: {action:?} {query}");
+
+        let window = tokio::task::spawn_blocking(move || {
+            let window = window_manager::find_window(&query)?;
+            window_manager::perform(&window.id, action).map(|_| window)
+        })
+        .await
+        .map_err(|e| format!("Window task failed: {e}"))??;
+        Ok(json!({ "success": true, "action": action, "window": window }))
+    }
+}
+
 // Function Registry
 pub struct FunctionRegistry {
     functions: HashMap<String, Box<dyn ExecutableFunction>>,
@@ -427,7 +661,10 @@ impl FunctionRegistry {
         registry.register_function(Box::new(ClickMouseFunction::new()));
         registry.register_function(Box::new(TypeTextFunction::new()));
         registry.register_function(Box::new(AnalyzeScreenFunction::new()));
+        registry.register_function(Box::new(ReadScreenTextFunction::new()));
         registry.register_function(Box::new(ExecuteCommandFunction));
+        registry.register_function(Box::new(ListWindowsFunction));
+        registry.register_function(Box::new(ManageWindowFunction));
         registry
     }
 
@@ -440,6 +677,10 @@ impl FunctionRegistry {
         self.functions.get(name).map(|b| &**b)
     }
 
+    pub fn function_names(&self) -> Vec<String> {
+        self.functions.keys().cloned().collect()
+    }
+
     pub async fn execute_function(&self, name: &str, args: Value) -> Result<Value, String> {
         if let Some(function) = self.get_function(name) {
             function.execute(args).await
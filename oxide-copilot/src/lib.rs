@@ -7,3 +7,6 @@ pub mod functions;
 pub mod gemini_api;
 pub mod llm_orchestrator;
 pub mod oauth;
+pub mod plan_executor;
+pub mod post_process;
+pub mod response_cache;
@@ -1,6 +1,7 @@
 use crate::errors::CopilotError;
 use async_trait::async_trait;
 use log::{info, warn};
+use oxide_core::prompts;
 use serde_json::{json, Value};
 use std::collections::HashMap;
 
@@ -31,6 +32,24 @@ impl std::fmt::Display for LLMRole {
     }
 }
 
+impl std::str::FromStr for LLMRole {
+    type Err = CopilotError;
+
+    /// Parse the lowercase role names used in `orchestrator.roles`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "coordinator" => Ok(LLMRole::Coordinator),
+            "analyst" => Ok(LLMRole::Analyst),
+            "executor" => Ok(LLMRole::Executor),
+            "innovator" => Ok(LLMRole::Innovator),
+            "validator" => Ok(LLMRole::Validator),
+            other => Err(CopilotError::Configuration(format!(
+                "Unknown orchestrator role: {other}"
+            ))),
+        }
+    }
+}
+
 /// Configuration for LLM collaboration
 #[derive(Debug, Clone)]
 pub struct LLMConfig {
@@ -163,21 +182,15 @@ impl LLMOrchestrator {
     ) -> Result<String, CopilotError> {
         let coordinator = self.find_provider_by_role(LLMRole::Coordinator)?;
 
-        let prompt = format!(
-            "You are the primary coordinator for this system task. Analyze the following request and create a detailed execution plan:\n\n\
-            Task: {}\n\
-            System State: {}\n\
-            User Input: {}\n\n\
-            Provide:\n\
-            1. Task breakdown into subtasks\n\
-            2. Required system operations\n\
-            3. Risk assessment\n\
-            4. Recommended approach\n\
-            5. Success criteria\n\n\
-            Format your response as a structured plan.",
-            task,
-            context.system_state,
-            context.user_input
+        let system_state = context.system_state.to_string();
+        let prompt = prompts::render(
+            prompts::ORCHESTRATOR_PLAN,
+            Some(coordinator.name()),
+            &[
+                ("task", task),
+                ("system_state", &system_state),
+                ("user_input", &context.user_input),
+            ],
         );
 
         coordinator.generate_response(&prompt, context).await
@@ -212,6 +225,7 @@ impl LLMOrchestrator {
                     &task_clone,
                     &context_clone,
                     provider.role(),
+                    provider.name(),
                     &coordinator_response_clone,
                 );
 
@@ -255,21 +269,15 @@ impl LLMOrchestrator {
     ) -> Result<String, CopilotError> {
         let validator = self.find_provider_by_role(LLMRole::Validator)?;
 
-        let prompt = format!(
-            "You are the validator for this collaborative task. Review all responses and provide validation:\n\n\
-            Original Task: {}\n\
-            Coordinator Plan: {}\n\
-            Specialized Responses: {}\n\n\
-            Provide:\n\
-            1. Quality assessment of each response\n\
-            2. Consistency check across responses\n\
-            3. Risk identification\n\
-            4. Recommendations for improvement\n\
-            5. Final validation score (0-100)\n\n\
-            Format as a structured validation report.",
-            task,
-            coordinator_response,
-            serde_json::to_string_pretty(specialized_responses).unwrap_or_default()
+        let responses = serde_json::to_string_pretty(specialized_responses).unwrap_or_default();
+        let prompt = prompts::render(
+            prompts::ORCHESTRATOR_VALIDATE,
+            Some(validator.name()),
+            &[
+                ("task", task),
+                ("coordinator_plan", coordinator_response),
+                ("responses", &responses),
+            ],
         );
 
         validator.generate_response(&prompt, context).await
@@ -317,61 +325,30 @@ impl LLMOrchestrator {
         task: &str,
         context: &CollaborativeContext,
         role: LLMRole,
+        provider: &str,
         coordinator_response: &str,
     ) -> String {
-        match role {
-            LLMRole::Analyst => format!(
-                "As a technical analyst, provide deep analysis of this task:\n\n\
-                Task: {}\n\
-                Coordinator Plan: {}\n\
-                System State: {}\n\n\
-                Focus on:\n\
-                - Technical feasibility\n\
-                - Performance implications\n\
-                - Security considerations\n\
-                - Detailed implementation steps",
-                task, coordinator_response, context.system_state
-            ),
-
-            LLMRole::Executor => format!(
-                "As a system executor, provide execution details for this task:\n\n\
-                Task: {}\n\
-                Coordinator Plan: {}\n\
-                Available Functions: {}\n\n\
-                Focus on:\n\
-                - Specific commands to execute\n\
-                - System operations required\n\
-                - Error handling strategies\n\
-                - Monitoring and logging",
-                task,
-                coordinator_response,
-                context.available_functions.join(", ")
-            ),
-
-            LLMRole::Innovator => format!(
-                "As an innovator, provide creative solutions for this task:\n\n\
-                Task: {}\n\
-                Coordinator Plan: {}\n\
-                Constraints: {}\n\n\
-                Focus on:\n\
-                - Alternative approaches\n\
-                - Creative solutions\n\
-                - Optimization opportunities\n\
-                - Future improvements",
-                task,
-                coordinator_response,
-                serde_json::to_string_pretty(&context.constraints).unwrap_or_default()
-            ),
-
-            _ => format!(
-                "Provide your specialized analysis for this task:\n\n\
-                Task: {}\n\
-                Coordinator Plan: {}\n\
-                Context: {}\n\n\
-                Provide detailed analysis from your perspective.",
-                task, coordinator_response, context.user_input
-            ),
-        }
+        let template = match role {
+            LLMRole::Analyst => prompts::ORCHESTRATOR_ANALYST,
+            LLMRole::Executor => prompts::ORCHESTRATOR_EXECUTOR,
+            LLMRole::Innovator => prompts::ORCHESTRATOR_INNOVATOR,
+            _ => prompts::ORCHESTRATOR_SPECIALIST,
+        };
+        let system_state = context.system_state.to_string();
+        let available_functions = context.available_functions.join(", ");
+        let constraints = serde_json::to_string_pretty(&context.constraints).unwrap_or_default();
+        prompts::render(
+            template,
+            Some(provider),
+            &[
+                ("task", task),
+                ("coordinator_plan", coordinator_response),
+                ("system_state", &system_state),
+                ("user_input", &context.user_input),
+                ("available_functions", &available_functions),
+                ("constraints", &constraints),
+            ],
+        )
     }
 
     /// Calculate consensus score based on response consistency
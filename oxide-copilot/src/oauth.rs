@@ -192,6 +192,7 @@ impl GoogleOAuthManager {
         println!("✅ Authentication code received, exchanging for token...");
 
         // Exchange authorization code for token
+        oxide_core::network::ensure_online("OAuth token endpoint")?;
         let token_result = self
             .client
             .exchange_code(AuthorizationCode::new(auth_code))
@@ -214,6 +215,7 @@ impl GoogleOAuthManager {
         &self,
         refresh_token: &str,
     ) -> Result<OAuthToken, Box<dyn std::error::Error>> {
+        oxide_core::network::ensure_online("OAuth token endpoint")?;
         let token_result = self
             .client
             .exchange_refresh_token(&oauth2::RefreshToken::new(refresh_token.to_string()))
@@ -0,0 +1,468 @@
+//! Runs the `execution_plan` produced by collaborative analysis.
+//!
+//! Plan steps are mapped onto functions in a [`FunctionRegistry`] and run in
+//! order under the analysis `constraints`: `max_execution_time` (seconds) is
+//! a budget for the whole plan and `security_level` decides what happens to
+//! destructive steps. At `low`/`medium` a destructive step runs only once
+//! its index is confirmed; at `high` it is blocked outright. The run stops at
+//! the first step that awaits confirmation so the caller can ask the user and
+//! resume from that step. Raising [`PlanRunOptions::cancel`] skips every step
+//! not yet started.
+
+use crate::functions::FunctionRegistry;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Budget used when the constraints do not set `max_execution_time`
+pub const DEFAULT_MAX_EXECUTION_SECS: u64 = 300;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecurityLevel {
+    Low,
+    Medium,
+    High,
+}
+
+impl SecurityLevel {
+    /// Unknown levels are treated as `High`.
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "low" => SecurityLevel::Low,
+            "medium" | "normal" => SecurityLevel::Medium,
+            _ => SecurityLevel::High,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanConstraints {
+    pub max_execution_time: Duration,
+    pub security_level: SecurityLevel,
+}
+
+impl Default for PlanConstraints {
+    fn default() -> Self {
+        Self {
+            max_execution_time: Duration::from_secs(DEFAULT_MAX_EXECUTION_SECS),
+            security_level: SecurityLevel::High,
+        }
+    }
+}
+
+impl PlanConstraints {
+    /// Read the `constraints` map of a `CollaborativeContext`; other keys are
+    /// advisory and ignored here.
+    pub fn from_map(constraints: &HashMap<String, Value>) -> Self {
+        let defaults = Self::default();
+        Self {
+            max_execution_time: constraints
+                .get("max_execution_time")
+                .and_then(Value::as_u64)
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.max_execution_time),
+            security_level: constraints
+                .get("security_level")
+                .and_then(Value::as_str)
+                .map(SecurityLevel::parse)
+                .unwrap_or(defaults.security_level),
+        }
+    }
+}
+
+/// One plan step resolved against the registry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanStep {
+    pub index: usize,
+    pub description: String,
+    /// Registered function to call; `None` for informational steps
+    pub function: Option<String>,
+    pub args: Value,
+    pub destructive: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanStepStatus {
+    Running,
+    Completed,
+    Failed,
+    /// Informational step, or not reached because the run stopped early
+    Skipped,
+    AwaitingConfirmation,
+    /// Refused by the security level
+    Blocked,
+    TimedOut,
+}
+
+/// Progress update emitted for every step state change.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanProgress {
+    pub step: usize,
+    pub total_steps: usize,
+    pub description: String,
+    pub function: Option<String>,
+    pub status: PlanStepStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanExecutionReport {
+    pub steps: Vec<PlanProgress>,
+    pub completed: usize,
+    pub failed: usize,
+    /// Step to confirm and resume from, when the run paused
+    pub awaiting_confirmation: Option<usize>,
+    pub timed_out: bool,
+    #[serde(default)]
+    pub cancelled: bool,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PlanRunOptions {
+    /// Indices of destructive steps the user has approved
+    pub confirmed_steps: HashSet<usize>,
+    /// First step to run; earlier steps are not reported
+    pub start_step: usize,
+    /// Checked before each step; once raised the rest are skipped
+    pub cancel: Option<Arc<AtomicBool>>,
+}
+
+/// Turn an `execution_plan` into steps. `steps` may hold objects
+/// (`description`/`action`, `function`/`tool`, `args`/`arguments`,
+/// optional `destructive`) or free text, in which case a step calls the
+/// first registered function it names.
+pub fn parse_plan(plan: &Value, registry: &FunctionRegistry) -> Vec<PlanStep> {
+    let raw_steps: Vec<&Value> = match plan.get("steps").or(Some(plan)) {
+        Some(Value::Array(items)) => items.iter().collect(),
+        _ => Vec::new(),
+    };
+
+    let mut names = registry.function_names();
+    // Longest first so a name wins over registered prefixes of itself
+    names.sort_by_key(|n| std::cmp::Reverse(n.len()));
+
+    let mut steps = Vec::new();
+    for raw in raw_steps {
+        let (description, function, args, flagged) = match raw {
+            Value::String(text) => {
+                let text = clean_step_text(text);
+                if text.is_empty() {
+                    continue;
+                }
+                let function = names.iter().find(|n| text.contains(n.as_str())).cloned();
+                (text, function, json!({}), false)
+            }
+            Value::Object(obj) => {
+                let function = obj
+                    .get("function")
+                    .or_else(|| obj.get("tool"))
+                    .and_then(Value::as_str)
+                    .map(str::to_string);
+                let description = obj
+                    .get("description")
+                    .or_else(|| obj.get("action"))
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+                    .or_else(|| function.clone())
+                    .unwrap_or_default();
+                let args = obj
+                    .get("args")
+                    .or_else(|| obj.get("arguments"))
+                    .cloned()
+                    .unwrap_or_else(|| json!({}));
+                let flagged = obj
+                    .get("destructive")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false);
+                (description, function, args, flagged)
+            }
+            _ => continue,
+        };
+
+        let destructive = flagged
+            || function
+                .as_deref()
+                .and_then(|name| registry.get_function(name))
+                .is_some_and(|f| f.is_destructive(&args));
+        steps.push(PlanStep {
+            index: steps.len(),
+            description,
+            function,
+            args,
+            destructive,
+        });
+    }
+    steps
+}
+
+/// Strip list markers such as `1.`, `-` or `*` from a text step.
+fn clean_step_text(text: &str) -> String {
+    let trimmed = text.trim();
+    let without_number = trimmed
+        .trim_start_matches(|c: char| c.is_ascii_digit())
+        .trim_start_matches(['.', ')']);
+    let stripped = if without_number.len() < trimmed.len() {
+        without_number
+    } else {
+        trimmed.trim_start_matches(['-', '*', '•'])
+    };
+    stripped.trim().to_string()
+}
+
+pub struct PlanExecutor<'a> {
+    registry: &'a FunctionRegistry,
+    constraints: PlanConstraints,
+}
+
+impl<'a> PlanExecutor<'a> {
+    pub fn new(registry: &'a FunctionRegistry, constraints: PlanConstraints) -> Self {
+        Self {
+            registry,
+            constraints,
+        }
+    }
+
+    /// Run `steps` from `options.start_step`, calling `on_progress` for every
+    /// status change.
+    pub async fn run<F>(
+        &self,
+        steps: &[PlanStep],
+        options: &PlanRunOptions,
+        mut on_progress: F,
+    ) -> PlanExecutionReport
+    where
+        F: FnMut(&PlanProgress),
+    {
+        let started = Instant::now();
+        let deadline = started + self.constraints.max_execution_time;
+        let total_steps = steps.len();
+        let mut report = PlanExecutionReport {
+            steps: Vec::new(),
+            completed: 0,
+            failed: 0,
+            awaiting_confirmation: None,
+            timed_out: false,
+            cancelled: false,
+            duration_ms: 0,
+        };
+        let mut stopped = false;
+
+        for step in steps.iter().skip(options.start_step) {
+            let cancelled = options
+                .cancel
+                .as_ref()
+                .is_some_and(|flag| flag.load(Ordering::SeqCst));
+            if cancelled && !stopped {
+                info!("Plan cancelled before step {}", step.index);
+                stopped = true;
+                report.cancelled = true;
+            }
+            let progress = |status, output, error| PlanProgress {
+                step: step.index,
+                total_steps,
+                description: step.description.clone(),
+                function: step.function.clone(),
+                status,
+                output,
+                error,
+            };
+
+            let outcome = if stopped {
+                progress(PlanStepStatus::Skipped, None, None)
+            } else if let Some(function) = step.function.as_deref() {
+                if step.destructive && self.constraints.security_level == SecurityLevel::High {
+                    progress(
+                        PlanStepStatus::Blocked,
+                        None,
+                        Some("Destructive steps are not allowed at security level high".into()),
+                    )
+                } else if step.destructive && !options.confirmed_steps.contains(&step.index) {
+                    stopped = true;
+                    report.awaiting_confirmation = Some(step.index);
+                    progress(PlanStepStatus::AwaitingConfirmation, None, None)
+                } else {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    on_progress(&progress(PlanStepStatus::Running, None, None));
+                    info!("Plan step {}: {function}", step.index);
+                    match tokio::time::timeout(
+                        remaining,
+                        self.registry.execute_function(function, step.args.clone()),
+                    )
+                    .await
+                    {
+                        Ok(Ok(output)) => {
+                            report.completed += 1;
+                            progress(PlanStepStatus::Completed, Some(output), None)
+                        }
+                        Ok(Err(e)) => {
+                            warn!("Plan step {} failed: {e}", step.index);
+                            report.failed += 1;
+                            progress(PlanStepStatus::Failed, None, Some(e))
+                        }
+                        Err(_) => {
+                            stopped = true;
+                            report.timed_out = true;
+                            progress(
+                                PlanStepStatus::TimedOut,
+                                None,
+                                Some(format!(
+                                    "Plan exceeded max_execution_time of {}s",
+                                    self.constraints.max_execution_time.as_secs()
+                                )),
+                            )
+                        }
+                    }
+                }
+            } else {
+                progress(PlanStepStatus::Skipped, None, None)
+            };
+
+            on_progress(&outcome);
+            report.steps.push(outcome);
+        }
+
+        report.duration_ms = started.elapsed().as_millis() as u64;
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::functions::ExecutableFunction;
+    use async_trait::async_trait;
+
+    struct Wipe;
+
+    #[async_trait]
+    impl ExecutableFunction for Wipe {
+        fn name(&self) -> &str {
+            "wipe_temp"
+        }
+        fn description(&self) -> &str {
+            "Test-only destructive function"
+        }
+        fn parameters(&self) -> Value {
+            json!({})
+        }
+        async fn execute(&self, _args: Value) -> Result<Value, String> {
+            Ok(json!({ "wiped": true }))
+        }
+        fn is_destructive(&self, _args: &Value) -> bool {
+            true
+        }
+    }
+
+    fn registry() -> FunctionRegistry {
+        let mut registry = FunctionRegistry::new();
+        registry.register_function(Box::new(Wipe));
+        registry
+    }
+
+    #[tokio::test]
+    async fn destructive_steps_wait_for_confirmation() {
+        let registry = registry();
+        let plan = json!({
+            "steps": [
+                "1. Check the clock with get_current_time",
+                "",
+                "- Review the results",
+                { "function": "wipe_temp", "description": "Clear temp files" },
+                "3. get_current_time again"
+            ]
+        });
+        let steps = parse_plan(&plan, &registry);
+        assert_eq!(steps.len(), 4);
+        assert_eq!(
+            steps[0].description,
+            "Check the clock with get_current_time"
+        );
+        assert_eq!(steps[0].function.as_deref(), Some("get_current_time"));
+        assert!(steps[1].function.is_none());
+        assert!(steps[2].destructive);
+
+        let constraints = PlanConstraints::from_map(&HashMap::from([(
+            "security_level".to_string(),
+            json!("medium"),
+        )]));
+        let executor = PlanExecutor::new(&registry, constraints);
+
+        let mut events = Vec::new();
+        let report = executor
+            .run(&steps, &PlanRunOptions::default(), |p| {
+                events.push(p.status)
+            })
+            .await;
+        assert_eq!(report.completed, 1);
+        assert_eq!(report.awaiting_confirmation, Some(2));
+        assert_eq!(report.steps[3].status, PlanStepStatus::Skipped);
+        assert_eq!(events[0], PlanStepStatus::Running);
+
+        let resumed = executor
+            .run(
+                &steps,
+                &PlanRunOptions {
+                    confirmed_steps: HashSet::from([2]),
+                    start_step: 2,
+                    ..Default::default()
+                },
+                |_| {},
+            )
+            .await;
+        assert_eq!(resumed.steps.len(), 2);
+        assert_eq!(resumed.completed, 2);
+        assert_eq!(resumed.awaiting_confirmation, None);
+    }
+
+    #[tokio::test]
+    async fn high_security_blocks_destructive_steps() {
+        let registry = registry();
+        let steps = parse_plan(&json!(["wipe_temp now"]), &registry);
+        let executor = PlanExecutor::new(&registry, PlanConstraints::default());
+        let report = executor
+            .run(
+                &steps,
+                &PlanRunOptions {
+                    confirmed_steps: HashSet::from([0]),
+                    start_step: 0,
+                    ..Default::default()
+                },
+                |_| {},
+            )
+            .await;
+        assert_eq!(report.steps[0].status, PlanStepStatus::Blocked);
+        assert_eq!(report.completed, 0);
+    }
+
+    #[tokio::test]
+    async fn cancelled_runs_skip_remaining_steps() {
+        let registry = registry();
+        let steps = parse_plan(&json!(["wipe_temp now", "wipe_temp again"]), &registry);
+        let executor = PlanExecutor::new(&registry, PlanConstraints::default());
+        let report = executor
+            .run(
+                &steps,
+                &PlanRunOptions {
+                    cancel: Some(Arc::new(AtomicBool::new(true))),
+                    ..Default::default()
+                },
+                |_| {},
+            )
+            .await;
+        assert!(report.cancelled);
+        assert!(report
+            .steps
+            .iter()
+            .all(|step| step.status == PlanStepStatus::Skipped));
+    }
+}
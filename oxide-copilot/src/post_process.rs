@@ -0,0 +1,567 @@
+//! Post-processing of LLM responses before they reach callers.
+//!
+//! [`AIOrchestrator`](crate::ai::AIOrchestrator) runs every response through
+//! a [`PostProcessPipeline`]. The default stages normalize markdown, extract
+//! shell commands from fenced blocks, warn about destructive commands and
+//! check cited URLs. Stages run in order on a shared [`ProcessedResponse`],
+//! so later stages see what earlier ones extracted. Warnings are appended to
+//! the text as well, so callers that only use the text still show them.
+
+use regex::Regex;
+use serde::Serialize;
+use std::net::IpAddr;
+use std::sync::OnceLock;
+use url::{Host, Url};
+
+/// Fence languages treated as commands; a fence without a language counts too
+const SHELL_LANGUAGES: [&str; 14] = [
+    "bash",
+    "sh",
+    "shell",
+    "zsh",
+    "fish",
+    "console",
+    "terminal",
+    "powershell",
+    "pwsh",
+    "ps",
+    "ps1",
+    "cmd",
+    "bat",
+    "batch",
+];
+
+/// Domains reserved for documentation, which models cite when they make up
+/// a link
+const PLACEHOLDER_DOMAINS: [&str; 3] = ["example.com", "example.org", "example.net"];
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CommandBlock {
+    /// Fence language, e.g. `bash`; `None` for a bare fence
+    pub language: Option<String>,
+    pub code: String,
+    pub dangerous: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CommandWarning {
+    /// The offending command line
+    pub command: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Citation {
+    pub url: String,
+    pub valid: bool,
+    /// Why the citation failed validation
+    pub problem: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ProcessedResponse {
+    pub text: String,
+    pub commands: Vec<CommandBlock>,
+    pub warnings: Vec<CommandWarning>,
+    pub citations: Vec<Citation>,
+}
+
+/// What the response was generated from
+#[derive(Debug, Clone, Default)]
+pub struct PostProcessContext {
+    /// URLs the model was given as sources. When not empty the response is
+    /// grounded and should only cite these.
+    pub sources: Vec<String>,
+}
+
+impl PostProcessContext {
+    pub fn grounded(sources: Vec<String>) -> Self {
+        Self { sources }
+    }
+}
+
+/// One step of the pipeline
+pub trait PostProcessor: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn process(&self, response: &mut ProcessedResponse, context: &PostProcessContext);
+}
+
+/// Ordered post-processing stages
+pub struct PostProcessPipeline {
+    stages: Vec<Box<dyn PostProcessor>>,
+}
+
+impl Default for PostProcessPipeline {
+    fn default() -> Self {
+        Self::empty()
+            .with_stage(MarkdownNormalizer)
+            .with_stage(CommandExtractor)
+            .with_stage(DangerousCommandDetector)
+            .with_stage(CitationValidator)
+    }
+}
+
+impl PostProcessPipeline {
+    /// A pipeline that returns responses unchanged
+    pub fn empty() -> Self {
+        Self { stages: Vec::new() }
+    }
+
+    pub fn with_stage(mut self, stage: impl PostProcessor + 'static) -> Self {
+        self.stages.push(Box::new(stage));
+        self
+    }
+
+    pub fn stage_names(&self) -> Vec<&'static str> {
+        self.stages.iter().map(|stage| stage.name()).collect()
+    }
+
+    pub fn run(&self, text: &str, context: &PostProcessContext) -> ProcessedResponse {
+        let mut response = ProcessedResponse {
+            text: text.to_string(),
+            ..ProcessedResponse::default()
+        };
+        for stage in &self.stages {
+            stage.process(&mut response, context);
+        }
+        response
+    }
+}
+
+/// Unifies line endings and bullets, trims trailing whitespace, collapses
+/// runs of blank lines and closes an unterminated code fence. Code inside
+/// fences is left as is.
+pub struct MarkdownNormalizer;
+
+impl PostProcessor for MarkdownNormalizer {
+    fn name(&self) -> &'static str {
+        "markdown"
+    }
+
+    fn process(&self, response: &mut ProcessedResponse, _context: &PostProcessContext) {
+        response.text = normalize_markdown(&response.text);
+    }
+}
+
+fn normalize_markdown(text: &str) -> String {
+    let text = text.replace("\r\n", "\n").replace('\r', "\n");
+    let mut lines: Vec<String> = Vec::new();
+    let mut in_fence = false;
+    let mut previous_blank = false;
+    for line in text.lines() {
+        let is_fence = line.trim_start().starts_with("```");
+        if in_fence && !is_fence {
+            lines.push(line.to_string());
+            continue;
+        }
+        if is_fence {
+            in_fence = !in_fence;
+            previous_blank = false;
+            lines.push(line.trim_end().to_string());
+            continue;
+        }
+
+        let line = line.trim_end();
+        if line.is_empty() {
+            if !previous_blank {
+                lines.push(String::new());
+            }
+            previous_blank = true;
+            continue;
+        }
+        previous_blank = false;
+        let indent = line.len() - line.trim_start().len();
+        match line.trim_start().strip_prefix("• ") {
+            Some(item) => lines.push(format!("{}- {item}", &line[..indent])),
+            None => lines.push(line.to_string()),
+        }
+    }
+    if in_fence {
+        lines.push("```".to_string());
+    }
+    lines.join("\n").trim_matches('\n').to_string()
+}
+
+/// Collects shell code blocks into [`ProcessedResponse::commands`]
+pub struct CommandExtractor;
+
+impl PostProcessor for CommandExtractor {
+    fn name(&self) -> &'static str {
+        "commands"
+    }
+
+    fn process(&self, response: &mut ProcessedResponse, _context: &PostProcessContext) {
+        response.commands = fenced_blocks(&response.text)
+            .into_iter()
+            .filter(|(language, _)| {
+                language
+                    .as_deref()
+                    .is_none_or(|lang| SHELL_LANGUAGES.contains(&lang))
+            })
+            .map(|(language, code)| CommandBlock {
+                language,
+                code,
+                dangerous: false,
+            })
+            .collect();
+    }
+}
+
+/// `(language, code)` of each fenced block
+fn fenced_blocks(text: &str) -> Vec<(Option<String>, String)> {
+    let mut blocks = Vec::new();
+    let mut current: Option<(Option<String>, Vec<&str>)> = None;
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        match (current.take(), trimmed.strip_prefix("```")) {
+            (None, Some(info)) => {
+                let language = info
+                    .split_whitespace()
+                    .next()
+                    .map(|lang| lang.to_ascii_lowercase());
+                current = Some((language, Vec::new()));
+            }
+            (Some((language, code)), Some(_)) => blocks.push((language, code.join("\n"))),
+            (Some((language, mut code)), None) => {
+                code.push(line);
+                current = Some((language, code));
+            }
+            (None, None) => {}
+        }
+    }
+    if let Some((language, code)) = current {
+        blocks.push((language, code.join("\n")));
+    }
+    blocks
+}
+
+/// Flags destructive commands in code blocks and inline code, and appends a
+/// warning listing them
+pub struct DangerousCommandDetector;
+
+impl PostProcessor for DangerousCommandDetector {
+    fn name(&self) -> &'static str {
+        "dangerous_commands"
+    }
+
+    fn process(&self, response: &mut ProcessedResponse, _context: &PostProcessContext) {
+        let mut warnings: Vec<CommandWarning> = Vec::new();
+        let mut check = |line: &str| -> bool {
+            let command = strip_prompt(line.trim());
+            match dangerous_reason(command) {
+                Some(reason) => {
+                    if !warnings.iter().any(|w| w.command == command) {
+                        warnings.push(CommandWarning {
+                            command: command.to_string(),
+                            reason: reason.to_string(),
+                        });
+                    }
+                    true
+                }
+                None => false,
+            }
+        };
+
+        for block in &mut response.commands {
+            // Every line is checked so each one gets its own warning
+            let flagged = block.code.lines().filter(|line| check(line)).count();
+            block.dangerous = flagged > 0;
+        }
+        for span in inline_code(&response.text) {
+            check(span);
+        }
+
+        if !warnings.is_empty() {
+            response.text.push_str(
+                "\n\n> **Warning:** this response contains commands that can cause \
+                 irreversible damage. Review them before running:",
+            );
+            for warning in &warnings {
+                response
+                    .text
+                    .push_str(&format!("\n> - `{}`: {}", warning.command, warning.reason));
+            }
+        }
+        response.warnings.extend(warnings);
+    }
+}
+
+/// Drop a leading shell prompt such as `$ ` or `PS C:\> `
+fn strip_prompt(line: &str) -> &str {
+    static PROMPT: OnceLock<Regex> = OnceLock::new();
+    let prompt = PROMPT.get_or_init(|| Regex::new(r"^(?:PS [^>]*> ?|[$#>] )").unwrap());
+    match prompt.find(line) {
+        Some(m) => &line[m.end()..],
+        None => line,
+    }
+}
+
+/// Inline code spans outside fenced blocks
+fn inline_code(text: &str) -> Vec<&str> {
+    static SPAN: OnceLock<Regex> = OnceLock::new();
+    let span = SPAN.get_or_init(|| Regex::new(r"`([^`\n]+)`").unwrap());
+    let mut in_fence = false;
+    let mut spans = Vec::new();
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if !in_fence {
+            spans.extend(
+                span.captures_iter(line)
+                    .filter_map(|c| c.get(1))
+                    .map(|m| m.as_str()),
+            );
+        }
+    }
+    spans
+}
+
+fn dangerous_reason(command: &str) -> Option<&'static str> {
+    static PATTERNS: OnceLock<Vec<(Regex, &'static str)>> = OnceLock::new();
+    let patterns = PATTERNS.get_or_init(|| {
+        [
+            (
+                r"\brm\s+(?:\S+\s+)*?(?:-[a-z]*r[a-z]*f[a-z]*|-[a-z]*f[a-z]*r[a-z]*|(?:-r|--recursive)\s+(?:-f|--force)|(?:-f|--force)\s+(?:-r|--recursive))\b",
+                "recursively force-deletes files",
+            ),
+            (r"\bformat(?:\.com)?\s+[a-z]:", "formats a drive"),
+            (r"\b(?:format-volume|clear-disk)\b", "erases a disk or volume"),
+            (r"\bmkfs(?:\.\w+)?\b", "creates a file system, erasing the device"),
+            (r"\bdd\b.*\bof=/dev/", "overwrites a raw device"),
+            (r">\s*/dev/(?:sd|hd|nvme|disk)", "overwrites a raw device"),
+            (r"\breg(?:\.exe)?\s+delete\b", "deletes registry keys"),
+            (r"\bremove-itemproperty\b|\bremove-item\s+(?:-path\s+)?['\x22]?hk(?:lm|cu|cr|u)", "deletes registry keys"),
+            (r"\bremove-item\b.*-recurse", "recursively deletes files"),
+            (r"\b(?:del|erase)\s+.*/s\b", "recursively deletes files"),
+            (r"\b(?:rd|rmdir)\s+.*/s\b", "deletes directory trees"),
+            (r"\bdiskpart\b", "edits disk partitions"),
+            (r"\bvssadmin\s+delete\s+shadows\b", "deletes volume shadow copies"),
+            (r"\bbcdedit\b", "changes the boot configuration"),
+            (r"\bcipher\s+/w\b", "wipes free disk space"),
+            (r"\bshred\b", "irrecoverably destroys files"),
+            (r"\bchmod\s+-r\s+\S+\s+/(?:\s|$)", "changes permissions on the whole file system"),
+            (r":\(\)\s*\{\s*:\s*\|\s*:\s*&\s*\}\s*;\s*:", "fork bomb that hangs the system"),
+        ]
+        .into_iter()
+        .map(|(pattern, reason)| (Regex::new(&format!("(?i){pattern}")).unwrap(), reason))
+        .collect()
+    });
+    patterns
+        .iter()
+        .find(|(pattern, _)| pattern.is_match(command))
+        .map(|(_, reason)| *reason)
+}
+
+/// Checks URLs cited in the prose (not in code) and appends a note listing
+/// the ones that failed
+pub struct CitationValidator;
+
+impl PostProcessor for CitationValidator {
+    fn name(&self) -> &'static str {
+        "citations"
+    }
+
+    fn process(&self, response: &mut ProcessedResponse, context: &PostProcessContext) {
+        let sources: Vec<Url> = context
+            .sources
+            .iter()
+            .filter_map(|source| Url::parse(source).ok())
+            .collect();
+        let mut citations: Vec<Citation> = Vec::new();
+        for url in find_urls(&prose(&response.text)) {
+            if citations.iter().any(|c| c.url == url) {
+                continue;
+            }
+            let problem = citation_problem(&url, &sources);
+            citations.push(Citation {
+                url,
+                valid: problem.is_none(),
+                problem,
+            });
+        }
+
+        let invalid: Vec<&Citation> = citations.iter().filter(|c| !c.valid).collect();
+        if !invalid.is_empty() {
+            response
+                .text
+                .push_str("\n\n> **Note:** these links could not be verified:");
+            for citation in invalid {
+                response.text.push_str(&format!(
+                    "\n> - {} ({})",
+                    citation.url,
+                    citation.problem.as_deref().unwrap_or("invalid")
+                ));
+            }
+        }
+        response.citations = citations;
+    }
+}
+
+/// `text` without fenced blocks and inline code
+fn prose(text: &str) -> String {
+    static SPAN: OnceLock<Regex> = OnceLock::new();
+    let span = SPAN.get_or_init(|| Regex::new(r"`[^`\n]+`").unwrap());
+    let mut in_fence = false;
+    let mut lines = Vec::new();
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        } else if !in_fence {
+            lines.push(span.replace_all(line, " ").into_owned());
+        }
+    }
+    lines.join("\n")
+}
+
+/// http(s) URLs in `text`, without trailing punctuation
+fn find_urls(text: &str) -> Vec<String> {
+    static URL: OnceLock<Regex> = OnceLock::new();
+    let url = URL.get_or_init(|| Regex::new(r#"https?://[^\s<>()\[\]"'`]+"#).unwrap());
+    url.find_iter(text)
+        .map(|m| {
+            m.as_str()
+                .trim_end_matches(['.', ',', ';', ':', '!', '?', '*', '_'])
+                .to_string()
+        })
+        .collect()
+}
+
+fn citation_problem(url: &str, sources: &[Url]) -> Option<String> {
+    let parsed = match Url::parse(url) {
+        Ok(parsed) => parsed,
+        Err(e) => return Some(format!("malformed URL: {e}")),
+    };
+    let local = match parsed.host() {
+        None => return Some("no host".to_string()),
+        Some(Host::Domain(domain)) => {
+            let domain = domain.to_ascii_lowercase();
+            if PLACEHOLDER_DOMAINS
+                .iter()
+                .any(|d| domain == *d || domain.ends_with(&format!(".{d}")))
+            {
+                return Some("placeholder domain".to_string());
+            }
+            domain == "localhost" || domain.ends_with(".local") || !domain.contains('.')
+        }
+        Some(Host::Ipv4(ip)) => is_local_ip(IpAddr::V4(ip)),
+        Some(Host::Ipv6(ip)) => is_local_ip(IpAddr::V6(ip)),
+    };
+    if local {
+        return Some("points to a local or private address".to_string());
+    }
+    if !sources.is_empty() && !sources.iter().any(|source| cites_source(&parsed, source)) {
+        return Some("not one of the sources the answer was based on".to_string());
+    }
+    None
+}
+
+fn is_local_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => ip.is_loopback() || ip.is_private() || ip.is_link_local(),
+        IpAddr::V6(ip) => ip.is_loopback() || (ip.segments()[0] & 0xfe00) == 0xfc00,
+    }
+}
+
+/// Same host, and the URL is the source page or below it
+fn cites_source(url: &Url, source: &Url) -> bool {
+    let host = |u: &Url| {
+        u.host_str()
+            .map(|h| h.trim_start_matches("www.").to_ascii_lowercase())
+    };
+    if host(url) != host(source) {
+        return false;
+    }
+    let source_path = source.path().trim_end_matches('/');
+    let path = url.path().trim_end_matches('/');
+    path == source_path || path.starts_with(&format!("{source_path}/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_dangerous_commands_and_extracts_blocks() {
+        let raw = "To clean up:\r\n\r\n\r\n• first item   \n```bash\n$ ls -la\nsudo rm -rf /var/tmp/cache\n```\n\
+                   Or on Windows run `reg delete HKCU\\Software\\Foo /f` or\n```powershell\nGet-Process\n";
+        let response = PostProcessPipeline::default().run(raw, &PostProcessContext::default());
+
+        assert!(response
+            .text
+            .starts_with("To clean up:\n\n- first item\n```bash"));
+        assert_eq!(response.commands.len(), 2);
+        assert!(response.commands[0].dangerous);
+        assert_eq!(response.commands[1].language.as_deref(), Some("powershell"));
+        assert!(!response.commands[1].dangerous);
+
+        let reasons: Vec<&str> = response
+            .warnings
+            .iter()
+            .map(|w| w.reason.as_str())
+            .collect();
+        assert_eq!(
+            reasons,
+            ["recursively force-deletes files", "deletes registry keys"]
+        );
+        assert!(response.text.contains("> **Warning:**"));
+        assert!(response.text.contains("`sudo rm -rf /var/tmp/cache`"));
+
+        for safe in [
+            "rm file.txt",
+            "rm -r build",
+            "format the output",
+            "Remove-Item a.txt",
+        ] {
+            assert!(dangerous_reason(safe).is_none(), "{safe}");
+        }
+        for risky in [
+            "rm -fr ~",
+            "rm --recursive --force .",
+            "format C: /q",
+            "dd if=x of=/dev/sda",
+        ] {
+            assert!(dangerous_reason(risky).is_some(), "{risky}");
+        }
+    }
+
+    #[test]
+    fn validates_citations_against_sources() {
+        let text = "See [the advisory](https://www.cve.org/CVERecord?id=CVE-2024-1234), \
+                    https://example.com/fake and http://192.168.1.10/admin. \
+                    Also https://nvd.nist.gov/vuln/detail/CVE-2024-1234.";
+        let context = PostProcessContext::grounded(vec!["https://cve.org/CVERecord".to_string()]);
+        let response = PostProcessPipeline::default().run(text, &context);
+
+        let problems: Vec<(&str, Option<&str>)> = response
+            .citations
+            .iter()
+            .map(|c| (c.url.as_str(), c.problem.as_deref()))
+            .collect();
+        assert_eq!(
+            problems,
+            [
+                ("https://www.cve.org/CVERecord?id=CVE-2024-1234", None),
+                ("https://example.com/fake", Some("placeholder domain")),
+                (
+                    "http://192.168.1.10/admin",
+                    Some("points to a local or private address")
+                ),
+                (
+                    "https://nvd.nist.gov/vuln/detail/CVE-2024-1234",
+                    Some("not one of the sources the answer was based on")
+                ),
+            ]
+        );
+        assert!(response.text.contains("> **Note:**"));
+
+        let ungrounded = PostProcessPipeline::default().run(
+            "Docs: https://docs.rs/tokio\n```sh\ncurl http://localhost:8080\n```",
+            &PostProcessContext::default(),
+        );
+        assert_eq!(ungrounded.citations.len(), 1);
+        assert!(ungrounded.citations[0].valid);
+        assert!(!ungrounded.text.contains("Note"));
+    }
+}
@@ -0,0 +1,352 @@
+//! In-memory cache of LLM responses for deterministic prompts.
+//!
+//! Only calls at or below the configured temperature are cached: sampling
+//! that close to zero returns practically the same answer for the same
+//! prompt, so replaying it saves a paid round trip. Entries are keyed by
+//! provider, model and a hash of the full prompt, expire after the TTL, and
+//! the oldest are evicted once the entry or byte budget is exceeded.
+
+use log::debug;
+use oxide_core::config::LlmCacheConfig;
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::{Hash, Hasher};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, Instant};
+
+const DEFAULT_TTL_SECS: u64 = 3600;
+const DEFAULT_MAX_ENTRIES: usize = 256;
+const DEFAULT_MAX_BYTES: usize = 8 * 1024 * 1024;
+const DEFAULT_MAX_TEMPERATURE: f32 = 0.2;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    provider: String,
+    model: String,
+    prompt_hash: u64,
+    prompt_len: usize,
+}
+
+impl CacheKey {
+    fn new(provider: &str, model: &str, prompt: &str) -> Self {
+        let mut hasher = DefaultHasher::new();
+        prompt.hash(&mut hasher);
+        Self {
+            provider: provider.to_string(),
+            model: model.to_string(),
+            prompt_hash: hasher.finish(),
+            prompt_len: prompt.len(),
+        }
+    }
+}
+
+struct Entry {
+    response: String,
+    stored_at: Instant,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CacheStats {
+    pub enabled: bool,
+    pub entries: usize,
+    pub bytes: usize,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+struct Inner {
+    config: LlmCacheConfig,
+    entries: HashMap<CacheKey, Entry>,
+    bytes: usize,
+    hits: u64,
+    misses: u64,
+}
+
+impl Inner {
+    fn ttl(&self) -> Duration {
+        Duration::from_secs(self.config.ttl_secs.unwrap_or(DEFAULT_TTL_SECS))
+    }
+
+    fn remove(&mut self, key: &CacheKey) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.bytes -= entry.response.len();
+        }
+    }
+
+    /// Drop expired entries, then the oldest until within both limits.
+    fn trim(&mut self, now: Instant) {
+        let ttl = self.ttl();
+        let expired: Vec<CacheKey> = self
+            .entries
+            .iter()
+            .filter(|(_, e)| now.duration_since(e.stored_at) >= ttl)
+            .map(|(k, _)| k.clone())
+            .collect();
+        for key in &expired {
+            self.remove(key);
+        }
+        let max_entries = self.config.max_entries.unwrap_or(DEFAULT_MAX_ENTRIES);
+        let max_bytes = self.config.max_bytes.unwrap_or(DEFAULT_MAX_BYTES);
+        while self.entries.len() > max_entries || self.bytes > max_bytes {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, e)| e.stored_at)
+                .map(|(k, _)| k.clone())
+            else {
+                break;
+            };
+            self.remove(&oldest);
+        }
+    }
+}
+
+pub struct ResponseCache {
+    inner: Mutex<Inner>,
+}
+
+impl ResponseCache {
+    pub fn new(config: LlmCacheConfig) -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                config,
+                entries: HashMap::new(),
+                bytes: 0,
+                hits: 0,
+                misses: 0,
+            }),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Replace the settings; entries beyond the new limits are dropped.
+    pub fn configure(&self, config: LlmCacheConfig) {
+        let mut inner = self.lock();
+        inner.config = config;
+        if !inner.config.enabled {
+            inner.entries.clear();
+            inner.bytes = 0;
+        }
+        inner.trim(Instant::now());
+    }
+
+    /// Whether a call with this sampling temperature may be cached. Calls
+    /// that leave the temperature to the provider default are not.
+    pub fn is_cacheable(&self, temperature: Option<f32>) -> bool {
+        let inner = self.lock();
+        let max = inner
+            .config
+            .max_temperature
+            .unwrap_or(DEFAULT_MAX_TEMPERATURE);
+        inner.config.enabled && temperature.is_some_and(|t| t <= max)
+    }
+
+    pub fn get(&self, provider: &str, model: &str, prompt: &str) -> Option<String> {
+        self.get_at(Instant::now(), provider, model, prompt)
+    }
+
+    fn get_at(&self, now: Instant, provider: &str, model: &str, prompt: &str) -> Option<String> {
+        let mut inner = self.lock();
+        if !inner.config.enabled {
+            return None;
+        }
+        let key = CacheKey::new(provider, model, prompt);
+        let ttl = inner.ttl();
+        let fresh = inner
+            .entries
+            .get(&key)
+            .map(|e| now.duration_since(e.stored_at) < ttl);
+        match fresh {
+            Some(true) => {
+                inner.hits += 1;
+                inner.entries.get(&key).map(|e| e.response.clone())
+            }
+            Some(false) => {
+                inner.remove(&key);
+                inner.misses += 1;
+                None
+            }
+            None => {
+                inner.misses += 1;
+                None
+            }
+        }
+    }
+
+    pub fn insert(&self, provider: &str, model: &str, prompt: &str, response: String) {
+        self.insert_at(Instant::now(), provider, model, prompt, response);
+    }
+
+    fn insert_at(&self, now: Instant, provider: &str, model: &str, prompt: &str, response: String) {
+        let mut inner = self.lock();
+        if !inner.config.enabled {
+            return;
+        }
+        let key = CacheKey::new(provider, model, prompt);
+        inner.remove(&key);
+        inner.bytes += response.len();
+        inner.entries.insert(
+            key,
+            Entry {
+                response,
+                stored_at: now,
+            },
+        );
+        inner.trim(now);
+    }
+
+    /// Return the cached response for this prompt, or run `fetch` and cache
+    /// its result. Calls above the temperature limit always run `fetch`.
+    pub async fn get_or_fetch<F, Fut, E>(
+        &self,
+        provider: &str,
+        model: &str,
+        temperature: Option<f32>,
+        prompt: &str,
+        fetch: F,
+    ) -> Result<String, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<String, E>>,
+    {
+        if !self.is_cacheable(temperature) {
+            return fetch().await;
+        }
+        if let Some(response) = self.get(provider, model, prompt) {
+            debug!("LLM cache hit for {provider}/{model}");
+            return Ok(response);
+        }
+        let response = fetch().await?;
+        self.insert(provider, model, prompt, response.clone());
+        Ok(response)
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let inner = self.lock();
+        CacheStats {
+            enabled: inner.config.enabled,
+            entries: inner.entries.len(),
+            bytes: inner.bytes,
+            hits: inner.hits,
+            misses: inner.misses,
+        }
+    }
+
+    pub fn clear(&self) {
+        let mut inner = self.lock();
+        inner.entries.clear();
+        inner.bytes = 0;
+    }
+}
+
+/// Process-wide cache shared by all providers; see [`ResponseCache::configure`].
+pub fn global() -> &'static ResponseCache {
+    static CACHE: OnceLock<ResponseCache> = OnceLock::new();
+    CACHE.get_or_init(|| ResponseCache::new(LlmCacheConfig::default()))
+}
+
+/// Flatten chat messages into one cache prompt.
+pub fn messages_key<'a>(messages: impl IntoIterator<Item = (&'a str, &'a str)>) -> String {
+    messages
+        .into_iter()
+        .map(|(role, content)| format!("{role}\u{1f}{content}"))
+        .collect::<Vec<_>>()
+        .join("\u{1e}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expires_and_evicts_oldest() {
+        let cache = ResponseCache::new(LlmCacheConfig {
+            ttl_secs: Some(60),
+            max_entries: Some(2),
+            ..Default::default()
+        });
+        let t0 = Instant::now();
+        let at = |secs| t0 + Duration::from_secs(secs);
+
+        cache.insert_at(at(0), "openai", "gpt-4o", "a", "A".to_string());
+        cache.insert_at(at(1), "openai", "gpt-4o", "b", "B".to_string());
+        assert_eq!(
+            cache.get_at(at(2), "openai", "gpt-4o", "a").as_deref(),
+            Some("A")
+        );
+        // Same prompt, different model or provider
+        assert_eq!(cache.get_at(at(2), "openai", "gpt-4o-mini", "a"), None);
+        assert_eq!(cache.get_at(at(2), "qwen", "gpt-4o", "a"), None);
+
+        cache.insert_at(at(3), "openai", "gpt-4o", "c", "C".to_string());
+        assert_eq!(cache.stats().entries, 2);
+        assert_eq!(cache.get_at(at(4), "openai", "gpt-4o", "a"), None);
+        assert_eq!(cache.get_at(at(62), "openai", "gpt-4o", "b"), None);
+        assert_eq!(
+            cache.get_at(at(62), "openai", "gpt-4o", "c").as_deref(),
+            Some("C")
+        );
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (2, 4));
+        assert_eq!(stats.bytes, 1);
+    }
+
+    #[tokio::test]
+    async fn caches_only_low_temperature_calls() {
+        let cache = ResponseCache::new(LlmCacheConfig {
+            max_bytes: Some(8),
+            ..Default::default()
+        });
+        let calls = std::sync::atomic::AtomicUsize::new(0);
+        let fetch = || async {
+            calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok::<_, String>("answer".to_string())
+        };
+
+        for _ in 0..3 {
+            let r = cache
+                .get_or_fetch("qwen", "qwen-plus", Some(0.1), "p", fetch)
+                .await;
+            assert_eq!(r.unwrap(), "answer");
+        }
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        cache
+            .get_or_fetch("qwen", "qwen-plus", Some(0.7), "q", fetch)
+            .await
+            .unwrap();
+        cache
+            .get_or_fetch("qwen", "qwen-plus", None, "q", fetch)
+            .await
+            .unwrap();
+        cache
+            .get_or_fetch("qwen", "qwen-plus", Some(0.7), "q", fetch)
+            .await
+            .unwrap();
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 4);
+
+        // A response larger than the byte budget is not kept
+        cache
+            .get_or_fetch("qwen", "qwen-plus", Some(0.0), "big", || async {
+                Ok::<_, String>("x".repeat(64))
+            })
+            .await
+            .unwrap();
+        assert_eq!(cache.get("qwen", "qwen-plus", "big"), None);
+
+        cache.configure(LlmCacheConfig {
+            enabled: false,
+            ..Default::default()
+        });
+        assert!(!cache.is_cacheable(Some(0.0)));
+        assert_eq!(cache.stats().entries, 0);
+    }
+}
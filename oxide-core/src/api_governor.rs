@@ -0,0 +1,393 @@
+//! Shared budget for outbound cloud API requests.
+//!
+//! Every client that talks to a remote API (LLMs, embeddings, VirusTotal)
+//! calls [`acquire`] right after [`crate::network::ensure_online`] and keeps
+//! the returned [`RequestPermit`] alive until the response has been read.
+//! Limits are tracked per host: a one-minute sliding window for
+//! `requests_per_minute`, a semaphore for `max_concurrent` and a counter for
+//! `daily_quota` that resets at midnight UTC. Loopback targets are exempt.
+
+use crate::config::{ApiLimitConfig, ApiLimitsConfig};
+use crate::network::is_local_target;
+use chrono::{DateTime, NaiveDate, Utc};
+use log::{debug, info, warn};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore, TryAcquireError};
+
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+const BLOCKING_POLL: Duration = Duration::from_millis(25);
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Daily API quota of {quota} requests for {host} is exhausted")]
+pub struct QuotaError {
+    pub host: String,
+    pub quota: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiQuotaStatus {
+    pub host: String,
+    pub requests_per_minute: Option<u32>,
+    pub max_concurrent: Option<u32>,
+    pub daily_quota: Option<u64>,
+    pub used_today: u64,
+    pub remaining_today: Option<u64>,
+    pub requests_last_minute: usize,
+    pub in_flight: u32,
+    // Requests that had to wait for a rate-limit slot
+    pub throttled: u64,
+    // Requests refused because the daily quota was exhausted
+    pub rejected: u64,
+    pub resets_at: DateTime<Utc>,
+}
+
+/// Held for the duration of an outbound request.
+#[derive(Debug)]
+pub struct RequestPermit {
+    host: Option<String>,
+    _slot: Option<OwnedSemaphorePermit>,
+}
+
+impl Drop for RequestPermit {
+    fn drop(&mut self) {
+        if let Some(host) = self.host.take() {
+            if let Some(state) = governor().hosts.get_mut(&host) {
+                state.in_flight = state.in_flight.saturating_sub(1);
+            }
+        }
+    }
+}
+
+struct HostState {
+    limits: ApiLimitConfig,
+    semaphore: Option<Arc<Semaphore>>,
+    window: VecDeque<Instant>,
+    day: NaiveDate,
+    used_today: u64,
+    in_flight: u32,
+    throttled: u64,
+    rejected: u64,
+}
+
+impl HostState {
+    fn new(limits: ApiLimitConfig) -> Self {
+        Self {
+            semaphore: semaphore_for(&limits),
+            limits,
+            window: VecDeque::new(),
+            day: Utc::now().date_naive(),
+            used_today: 0,
+            in_flight: 0,
+            throttled: 0,
+            rejected: 0,
+        }
+    }
+
+    fn apply(&mut self, limits: &ApiLimitConfig) {
+        if self.limits.max_concurrent != limits.max_concurrent {
+            // Requests holding a permit on the old semaphore finish normally.
+            self.semaphore = semaphore_for(limits);
+        }
+        self.limits = limits.clone();
+    }
+
+    fn roll(&mut self, now: Instant) {
+        let today = Utc::now().date_naive();
+        if today != self.day {
+            self.day = today;
+            self.used_today = 0;
+        }
+        while self
+            .window
+            .front()
+            .is_some_and(|t| now.duration_since(*t) >= RATE_WINDOW)
+        {
+            self.window.pop_front();
+        }
+    }
+
+    fn status(&self, host: &str) -> ApiQuotaStatus {
+        let recent = self
+            .window
+            .iter()
+            .filter(|t| t.elapsed() < RATE_WINDOW)
+            .count();
+        let used_today = if self.day == Utc::now().date_naive() {
+            self.used_today
+        } else {
+            0
+        };
+        ApiQuotaStatus {
+            host: host.to_string(),
+            requests_per_minute: self.limits.requests_per_minute,
+            max_concurrent: self.limits.max_concurrent,
+            daily_quota: self.limits.daily_quota,
+            used_today,
+            remaining_today: self
+                .limits
+                .daily_quota
+                .map(|q| q.saturating_sub(used_today)),
+            requests_last_minute: recent,
+            in_flight: self.in_flight,
+            throttled: self.throttled,
+            rejected: self.rejected,
+            resets_at: next_reset(),
+        }
+    }
+}
+
+fn semaphore_for(limits: &ApiLimitConfig) -> Option<Arc<Semaphore>> {
+    limits
+        .max_concurrent
+        .map(|n| Arc::new(Semaphore::new(n as usize)))
+}
+
+fn next_reset() -> DateTime<Utc> {
+    let tomorrow = Utc::now().date_naive() + chrono::Duration::days(1);
+    tomorrow
+        .and_hms_opt(0, 0, 0)
+        .map(|t| t.and_utc())
+        .unwrap_or_else(Utc::now)
+}
+
+struct Governor {
+    config: ApiLimitsConfig,
+    hosts: HashMap<String, HostState>,
+}
+
+impl Governor {
+    fn host(&mut self, host: &str) -> &mut HostState {
+        let limits = self.config.limits_for(host).clone();
+        self.hosts
+            .entry(host.to_string())
+            .or_insert_with(|| HostState::new(limits))
+    }
+}
+
+fn governor() -> MutexGuard<'static, Governor> {
+    static GOVERNOR: OnceLock<Mutex<Governor>> = OnceLock::new();
+    GOVERNOR
+        .get_or_init(|| {
+            Mutex::new(Governor {
+                config: ApiLimitsConfig::with_defaults(),
+                hosts: HashMap::new(),
+            })
+        })
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Replace the active limits. Usage counters survive reconfiguration.
+pub fn configure(config: &ApiLimitsConfig) {
+    let mut gov = governor();
+    gov.config = config.clone();
+    let Governor { config, hosts } = &mut *gov;
+    for (host, state) in hosts.iter_mut() {
+        state.apply(config.limits_for(host));
+    }
+    info!(
+        "API limits configured for {} host(s) plus default",
+        config.hosts.len()
+    );
+}
+
+/// Host name used to key limits; service labels are used verbatim.
+fn host_key(target: &str) -> String {
+    Url::parse(target)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_ascii_lowercase))
+        .unwrap_or_else(|| target.to_ascii_lowercase())
+}
+
+/// Count a request against `host` if the rate window has room, otherwise
+/// return how long to wait before trying again.
+fn reserve(host: &str, waited: &mut bool) -> Result<Option<Duration>, QuotaError> {
+    let mut gov = governor();
+    let state = gov.host(host);
+    let now = Instant::now();
+    state.roll(now);
+    if let Some(quota) = state.limits.daily_quota {
+        if state.used_today >= quota {
+            state.rejected += 1;
+            warn!("Daily API quota for {host} exhausted ({quota} requests)");
+            return Err(QuotaError {
+                host: host.to_string(),
+                quota,
+            });
+        }
+    }
+    let limited = state
+        .limits
+        .requests_per_minute
+        .is_some_and(|rpm| state.window.len() >= rpm as usize);
+    match state.window.front() {
+        Some(oldest) if limited => {
+            let wait = RATE_WINDOW.saturating_sub(now.duration_since(*oldest));
+            if !*waited {
+                *waited = true;
+                state.throttled += 1;
+                debug!("Rate limit reached for {host}; waiting {wait:?}");
+            }
+            Ok(Some(wait))
+        }
+        _ => {
+            state.window.push_back(now);
+            state.used_today += 1;
+            state.in_flight += 1;
+            Ok(None)
+        }
+    }
+}
+
+fn unlimited() -> RequestPermit {
+    RequestPermit {
+        host: None,
+        _slot: None,
+    }
+}
+
+/// Wait for a concurrency slot and a rate-limit slot for `target` (a URL or
+/// a service label), then count the request against today's quota.
+pub async fn acquire(target: &str) -> Result<RequestPermit, QuotaError> {
+    if is_local_target(target) {
+        return Ok(unlimited());
+    }
+    let host = host_key(target);
+
+    let semaphore = governor().host(&host).semaphore.clone();
+    let slot = match semaphore {
+        Some(semaphore) => semaphore.acquire_owned().await.ok(),
+        None => None,
+    };
+
+    let mut waited = false;
+    while let Some(wait) = reserve(&host, &mut waited)? {
+        tokio::time::sleep(wait).await;
+    }
+    Ok(RequestPermit {
+        host: Some(host),
+        _slot: slot,
+    })
+}
+
+/// [`acquire`] for synchronous clients such as `reqwest::blocking`. Must not
+/// be called from an async task.
+pub fn acquire_blocking(target: &str) -> Result<RequestPermit, QuotaError> {
+    if is_local_target(target) {
+        return Ok(unlimited());
+    }
+    let host = host_key(target);
+
+    let semaphore = governor().host(&host).semaphore.clone();
+    let slot = match semaphore {
+        Some(semaphore) => loop {
+            match semaphore.clone().try_acquire_owned() {
+                Ok(permit) => break Some(permit),
+                Err(TryAcquireError::NoPermits) => std::thread::sleep(BLOCKING_POLL),
+                Err(TryAcquireError::Closed) => break None,
+            }
+        },
+        None => None,
+    };
+
+    let mut waited = false;
+    while let Some(wait) = reserve(&host, &mut waited)? {
+        std::thread::sleep(wait);
+    }
+    Ok(RequestPermit {
+        host: Some(host),
+        _slot: slot,
+    })
+}
+
+/// Usage for every host that has been contacted or has explicit limits.
+pub fn status() -> Vec<ApiQuotaStatus> {
+    let mut gov = governor();
+    let configured: Vec<String> = gov.config.hosts.keys().cloned().collect();
+    for host in configured {
+        gov.host(&host);
+    }
+    let mut statuses: Vec<ApiQuotaStatus> = gov
+        .hosts
+        .iter()
+        .map(|(host, state)| state.status(host))
+        .collect();
+    statuses.sort_by(|a, b| a.host.cmp(&b.host));
+    statuses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find(host: &str) -> ApiQuotaStatus {
+        status().into_iter().find(|s| s.host == host).unwrap()
+    }
+
+    // Tests share the process-wide governor, so they all install the same limits.
+    fn configure_for_tests() {
+        let mut config = ApiLimitsConfig::with_defaults();
+        config.hosts.insert(
+            "quota.governor.test".to_string(),
+            ApiLimitConfig {
+                daily_quota: Some(2),
+                ..ApiLimitConfig::default()
+            },
+        );
+        config.hosts.insert(
+            "slots.governor.test".to_string(),
+            ApiLimitConfig {
+                max_concurrent: Some(1),
+                ..ApiLimitConfig::default()
+            },
+        );
+        configure(&config);
+    }
+
+    #[tokio::test]
+    async fn daily_quota_is_enforced_per_host() {
+        configure_for_tests();
+
+        for _ in 0..2 {
+            acquire("https://quota.governor.test/v1?key=secret")
+                .await
+                .unwrap();
+        }
+        let err = acquire("https://quota.governor.test/v1").await.unwrap_err();
+        assert_eq!(err.host, "quota.governor.test");
+        assert!(acquire("https://other.governor.test/v1").await.is_ok());
+        assert!(acquire("http://127.0.0.1:1234/v1").await.is_ok());
+        assert!(acquire_blocking("https://quota.governor.test/v1").is_err());
+
+        let status = find("quota.governor.test");
+        assert_eq!(status.used_today, 2);
+        assert_eq!(status.remaining_today, Some(0));
+        assert_eq!(status.rejected, 2);
+        assert!(!status.host.contains("127.0.0.1"));
+    }
+
+    #[tokio::test]
+    async fn concurrency_is_capped_until_permit_drops() {
+        configure_for_tests();
+
+        let first = acquire("https://slots.governor.test/a").await.unwrap();
+        assert_eq!(find("slots.governor.test").in_flight, 1);
+        let second = tokio::time::timeout(
+            Duration::from_millis(50),
+            acquire("https://slots.governor.test/b"),
+        )
+        .await;
+        assert!(second.is_err());
+
+        drop(first);
+        let second = acquire("https://slots.governor.test/b").await.unwrap();
+        assert_eq!(find("slots.governor.test").in_flight, 1);
+        drop(second);
+        assert_eq!(find("slots.governor.test").in_flight, 0);
+    }
+}
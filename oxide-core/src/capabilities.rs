@@ -0,0 +1,178 @@
+//! Which optional features work in this build on this machine.
+//!
+//! Features can be compiled out (`yara-detection`, `surrealdb-metrics`) or
+//! fail at runtime (no microphone, no cloud provider configured). Each
+//! subsystem records its [`Capability`] when it initializes, and the
+//! frontend reads the whole map through [`snapshot`] to hide what cannot
+//! work instead of letting commands fail. Commands that need a feature call
+//! [`require`] for an error that says why it is missing.
+
+use crate::network;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Feature {
+    Yara,
+    VectorSearch,
+    CloudLlm,
+    Voice,
+    Rpa,
+}
+
+impl Feature {
+    pub const ALL: [Feature; 5] = [
+        Feature::Yara,
+        Feature::VectorSearch,
+        Feature::CloudLlm,
+        Feature::Voice,
+        Feature::Rpa,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Feature::Yara => "YARA scanning",
+            Feature::VectorSearch => "Vector search",
+            Feature::CloudLlm => "Cloud AI",
+            Feature::Voice => "Voice",
+            Feature::Rpa => "Automation (RPA)",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CapabilityState {
+    Available,
+    /// Works with reduced function, e.g. keyword instead of vector search
+    Degraded,
+    Unavailable,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Capability {
+    pub feature: Feature,
+    pub state: CapabilityState,
+    /// Compiled into this build
+    pub compiled: bool,
+    /// Why the feature is degraded or unavailable
+    pub reason: Option<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+static REGISTRY: RwLock<BTreeMap<Feature, Capability>> = RwLock::new(BTreeMap::new());
+
+fn record(feature: Feature, state: CapabilityState, compiled: bool, reason: Option<String>) {
+    let capability = Capability {
+        feature,
+        state,
+        compiled,
+        reason,
+        updated_at: Some(Utc::now()),
+    };
+    REGISTRY
+        .write()
+        .unwrap_or_else(|p| p.into_inner())
+        .insert(feature, capability);
+}
+
+pub fn available(feature: Feature) {
+    record(feature, CapabilityState::Available, true, None);
+}
+
+pub fn degraded(feature: Feature, reason: impl Into<String>) {
+    record(
+        feature,
+        CapabilityState::Degraded,
+        true,
+        Some(reason.into()),
+    );
+}
+
+pub fn unavailable(feature: Feature, reason: impl Into<String>) {
+    record(
+        feature,
+        CapabilityState::Unavailable,
+        true,
+        Some(reason.into()),
+    );
+}
+
+/// The build left out `cargo_feature`, which `feature` needs.
+pub fn not_compiled(feature: Feature, cargo_feature: &str) {
+    record(
+        feature,
+        CapabilityState::Unavailable,
+        false,
+        Some(format!("Built without the {cargo_feature} feature")),
+    );
+}
+
+/// Current state of every feature. Features no subsystem has registered
+/// yet are unavailable; cloud AI is unavailable while offline mode is on.
+pub fn snapshot() -> BTreeMap<Feature, Capability> {
+    let registry = REGISTRY.read().unwrap_or_else(|p| p.into_inner());
+    Feature::ALL
+        .into_iter()
+        .map(|feature| {
+            let mut capability = registry.get(&feature).cloned().unwrap_or(Capability {
+                feature,
+                state: CapabilityState::Unavailable,
+                compiled: true,
+                reason: Some("Not initialized yet".to_string()),
+                updated_at: None,
+            });
+            if feature == Feature::CloudLlm
+                && network::is_offline()
+                && capability.state != CapabilityState::Unavailable
+            {
+                capability.state = CapabilityState::Unavailable;
+                capability.reason = Some("Offline mode is on".to_string());
+            }
+            (feature, capability)
+        })
+        .collect()
+}
+
+/// `Ok` unless `feature` is unavailable; the error names the reason.
+pub fn require(feature: Feature) -> Result<(), String> {
+    let capability = &snapshot()[&feature];
+    match capability.state {
+        CapabilityState::Unavailable => Err(format!(
+            "{} is unavailable: {}",
+            feature.label(),
+            capability.reason.as_deref().unwrap_or("unknown reason")
+        )),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registered_states_and_defaults() {
+        not_compiled(Feature::Yara, "yara-detection");
+        degraded(Feature::VectorSearch, "Keyword matching only");
+        available(Feature::Rpa);
+        let map = snapshot();
+
+        assert_eq!(map.len(), Feature::ALL.len());
+        assert!(!map[&Feature::Yara].compiled);
+        assert_eq!(
+            require(Feature::Yara).unwrap_err(),
+            "YARA scanning is unavailable: Built without the yara-detection feature"
+        );
+        assert_eq!(map[&Feature::VectorSearch].state, CapabilityState::Degraded);
+        assert!(require(Feature::VectorSearch).is_ok());
+        assert!(require(Feature::Rpa).is_ok());
+        assert_eq!(
+            serde_json::to_value(&map).unwrap()["vector_search"]["state"],
+            "degraded"
+        );
+    }
+}
@@ -0,0 +1,528 @@
+//! Cleanup of temp files and caches that are safe to delete, with undo.
+//!
+//! [`preview`] lists the known temp, browser and package manager cache
+//! locations on this machine with the size that would be cleaned. [`execute`]
+//! moves the chosen items' files into a run directory under the staging area
+//! instead of deleting them, so [`undo`] can put them back until the run
+//! expires. Space is freed for good when a run is purged, either on request
+//! or by [`purge_expired`] once its retention ends.
+//!
+//! Browsers keep their cache files open; close them first for a full cleanup.
+//! Files that cannot be moved are counted as failed and left in place.
+
+use crate::config::CleanupConfig;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+pub const STAGING_DIR: &str = "./data/cleanup";
+const MANIFEST: &str = "manifest.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CleanupCategory {
+    Temp,
+    Browser,
+    PackageManager,
+}
+
+/// A location that can be cleaned and what cleaning it would remove.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleanupItem {
+    /// Stable id, e.g. `npm_cache` or `chrome_cache:Default`
+    pub id: String,
+    pub category: CleanupCategory,
+    pub name: String,
+    pub path: PathBuf,
+    /// Only files untouched for this many days are cleaned
+    pub min_age_days: u64,
+    pub files: u64,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleanupPreview {
+    pub generated_at: DateTime<Utc>,
+    /// Largest first
+    pub items: Vec<CleanupItem>,
+    pub total_bytes: u64,
+}
+
+/// What one run did for one item.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleanupRunItem {
+    pub id: String,
+    pub category: CleanupCategory,
+    pub name: String,
+    pub files: u64,
+    pub bytes: u64,
+    /// Files that could not be moved, usually because they are in use
+    pub failed: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleanupRunSummary {
+    pub id: String,
+    pub executed_at: DateTime<Utc>,
+    /// Staged files are deleted for good after this
+    pub expires_at: DateTime<Utc>,
+    pub items: Vec<CleanupRunItem>,
+    /// Bytes removed from the cleaned locations
+    pub reclaimed_bytes: u64,
+    pub failed: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StagedFile {
+    pub original: PathBuf,
+    pub staged: PathBuf,
+    pub bytes: u64,
+}
+
+/// A run's manifest, kept in its staging directory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleanupRun {
+    #[serde(flatten)]
+    pub summary: CleanupRunSummary,
+    pub files: Vec<StagedFile>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UndoReport {
+    pub run_id: String,
+    pub restored_files: u64,
+    pub restored_bytes: u64,
+    /// Files whose original path exists again; they are dropped with the run
+    pub skipped: u64,
+    pub failed: u64,
+}
+
+/// The cleanable locations on this machine, largest first. Items listed in
+/// `cfg.exclude` and locations with nothing to clean are left out.
+pub fn preview(cfg: &CleanupConfig) -> CleanupPreview {
+    let now = SystemTime::now();
+    let mut items: Vec<CleanupItem> = locations(cfg.temp_min_age_days())
+        .into_iter()
+        .filter(|item| !cfg.exclude.contains(&item.id) && item.path.is_dir())
+        .filter_map(|mut item| {
+            let files = collect_files(&item.path, cutoff(item.min_age_days, now));
+            if files.is_empty() {
+                return None;
+            }
+            item.files = files.len() as u64;
+            item.bytes = files.iter().map(|(_, len)| len).sum();
+            Some(item)
+        })
+        .collect();
+    items.sort_by_key(|item| std::cmp::Reverse(item.bytes));
+    CleanupPreview {
+        generated_at: Utc::now(),
+        total_bytes: items.iter().map(|i| i.bytes).sum(),
+        items,
+    }
+}
+
+/// Move the files of `items` into a new run under `staging`. The files are
+/// listed again, so the run matches the disk rather than the preview.
+pub fn execute(
+    items: &[CleanupItem],
+    staging: &Path,
+    retention_days: u64,
+) -> Result<CleanupRun, String> {
+    let executed_at = Utc::now();
+    let id = format!(
+        "{}-{}",
+        executed_at.format("%Y%m%d%H%M%S"),
+        &uuid::Uuid::new_v4().simple().to_string()[..8]
+    );
+    let run_dir = staging.join(&id);
+    fs::create_dir_all(&run_dir).map_err(|e| format!("Failed to create {run_dir:?}: {e}"))?;
+
+    let now = SystemTime::now();
+    let mut run = CleanupRun {
+        summary: CleanupRunSummary {
+            id,
+            executed_at,
+            expires_at: executed_at + Duration::days(retention_days as i64),
+            items: Vec::new(),
+            reclaimed_bytes: 0,
+            failed: 0,
+        },
+        files: Vec::new(),
+    };
+    for (index, item) in items.iter().enumerate() {
+        let mut result = CleanupRunItem {
+            id: item.id.clone(),
+            category: item.category,
+            name: item.name.clone(),
+            files: 0,
+            bytes: 0,
+            failed: 0,
+        };
+        // Items are staged by index; ids may contain characters paths cannot
+        let item_dir = run_dir.join(index.to_string());
+        for (path, bytes) in collect_files(&item.path, cutoff(item.min_age_days, now)) {
+            let Ok(relative) = path.strip_prefix(&item.path) else {
+                continue;
+            };
+            let staged = item_dir.join(relative);
+            match move_file(&path, &staged) {
+                Ok(()) => {
+                    result.files += 1;
+                    result.bytes += bytes;
+                    run.files.push(StagedFile {
+                        original: path,
+                        staged,
+                        bytes,
+                    });
+                }
+                Err(_) => result.failed += 1,
+            }
+        }
+        run.summary.reclaimed_bytes += result.bytes;
+        run.summary.failed += result.failed;
+        run.summary.items.push(result);
+    }
+
+    if run.files.is_empty() {
+        let _ = fs::remove_dir_all(&run_dir);
+    } else {
+        let json = serde_json::to_string_pretty(&run).map_err(|e| e.to_string())?;
+        fs::write(run_dir.join(MANIFEST), json)
+            .map_err(|e| format!("Failed to write cleanup manifest: {e}"))?;
+    }
+    Ok(run)
+}
+
+/// Runs that can still be undone, newest first.
+pub fn list_runs(staging: &Path) -> Vec<CleanupRunSummary> {
+    let Ok(entries) = fs::read_dir(staging) else {
+        return Vec::new();
+    };
+    let mut runs: Vec<CleanupRunSummary> = entries
+        .flatten()
+        .filter_map(|e| fs::read_to_string(e.path().join(MANIFEST)).ok())
+        .filter_map(|json| serde_json::from_str::<CleanupRun>(&json).ok())
+        .map(|run| run.summary)
+        .collect();
+    runs.sort_by_key(|run| std::cmp::Reverse(run.executed_at));
+    runs
+}
+
+pub fn load_run(staging: &Path, id: &str) -> Result<CleanupRun, String> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(format!("Invalid cleanup run id: {id}"));
+    }
+    let path = staging.join(id).join(MANIFEST);
+    let json = fs::read_to_string(&path).map_err(|_| format!("Cleanup run {id} not found"))?;
+    serde_json::from_str(&json).map_err(|e| format!("Failed to read cleanup run {id}: {e}"))
+}
+
+/// Put a run's files back where they were and drop the run.
+pub fn undo(staging: &Path, id: &str) -> Result<UndoReport, String> {
+    let run = load_run(staging, id)?;
+    let mut report = UndoReport {
+        run_id: id.to_string(),
+        ..Default::default()
+    };
+    for file in &run.files {
+        if file.original.exists() {
+            report.skipped += 1;
+        } else if move_file(&file.staged, &file.original).is_ok() {
+            report.restored_files += 1;
+            report.restored_bytes += file.bytes;
+        } else {
+            report.failed += 1;
+        }
+    }
+    if report.failed > 0 {
+        // Keep the run so the remaining files can be restored later
+        return Ok(report);
+    }
+    fs::remove_dir_all(staging.join(id))
+        .map_err(|e| format!("Restored files but failed to remove run {id}: {e}"))?;
+    Ok(report)
+}
+
+/// Delete a run's staged files for good; returns the bytes freed.
+pub fn purge(staging: &Path, id: &str) -> Result<u64, String> {
+    let run = load_run(staging, id)?;
+    fs::remove_dir_all(staging.join(id))
+        .map_err(|e| format!("Failed to purge cleanup run {id}: {e}"))?;
+    Ok(run.files.iter().map(|f| f.bytes).sum())
+}
+
+/// Purge runs whose retention has ended; returns their ids.
+pub fn purge_expired(staging: &Path, now: DateTime<Utc>) -> Vec<String> {
+    list_runs(staging)
+        .into_iter()
+        .filter(|run| run.expires_at <= now)
+        .filter_map(|run| purge(staging, &run.id).ok().map(|_| run.id))
+        .collect()
+}
+
+fn cutoff(min_age_days: u64, now: SystemTime) -> Option<SystemTime> {
+    (min_age_days > 0)
+        .then(|| now.checked_sub(std::time::Duration::from_secs(min_age_days * 24 * 60 * 60)))
+        .flatten()
+}
+
+/// Regular files below `dir` last modified before `cutoff` (all files
+/// without one). Symlinks are never followed.
+fn collect_files(dir: &Path, cutoff: Option<SystemTime>) -> Vec<(PathBuf, u64)> {
+    fn walk(dir: &Path, cutoff: Option<SystemTime>, out: &mut Vec<(PathBuf, u64)>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                walk(&entry.path(), cutoff, out);
+            } else if file_type.is_file() {
+                let Ok(meta) = entry.metadata() else {
+                    continue;
+                };
+                let old_enough = match cutoff {
+                    Some(cutoff) => meta.modified().is_ok_and(|m| m < cutoff),
+                    None => true,
+                };
+                if old_enough {
+                    out.push((entry.path(), meta.len()));
+                }
+            }
+        }
+    }
+
+    let mut out = Vec::new();
+    walk(dir, cutoff, &mut out);
+    out
+}
+
+/// Rename `from` to `to`, copying across volumes.
+fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    fs::copy(from, to)?;
+    if let Err(e) = fs::remove_file(from) {
+        let _ = fs::remove_file(to);
+        return Err(e);
+    }
+    Ok(())
+}
+
+fn item(
+    id: impl Into<String>,
+    category: CleanupCategory,
+    name: impl Into<String>,
+    path: PathBuf,
+) -> CleanupItem {
+    CleanupItem {
+        id: id.into(),
+        category,
+        name: name.into(),
+        path,
+        min_age_days: 0,
+        files: 0,
+        bytes: 0,
+    }
+}
+
+/// `Cache` of every Chromium profile (`Default`, `Profile 1`, ...) under `root`.
+fn chromium_caches(key: &str, browser: &str, root: &Path, out: &mut Vec<CleanupItem>) {
+    let Ok(entries) = fs::read_dir(root) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let profile = entry.file_name().to_string_lossy().into_owned();
+        if profile == "Default" || profile.starts_with("Profile ") {
+            out.push(item(
+                format!("{key}_cache:{profile}"),
+                CleanupCategory::Browser,
+                format!("{browser} cache ({profile})"),
+                entry.path().join("Cache"),
+            ));
+        }
+    }
+}
+
+/// `cache2` of every Firefox profile under `root`.
+fn firefox_caches(root: &Path, out: &mut Vec<CleanupItem>) {
+    let Ok(entries) = fs::read_dir(root) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let profile = entry.file_name().to_string_lossy().into_owned();
+        out.push(item(
+            format!("firefox_cache:{profile}"),
+            CleanupCategory::Browser,
+            format!("Firefox cache ({profile})"),
+            entry.path().join("cache2"),
+        ));
+    }
+}
+
+fn env_dir(name: &str) -> Option<PathBuf> {
+    std::env::var_os(name)
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Known cleanable locations for this platform, whether or not they exist.
+fn locations(temp_min_age_days: u64) -> Vec<CleanupItem> {
+    use CleanupCategory::{PackageManager, Temp};
+
+    let mut temp = item("temp", Temp, "Temporary files", std::env::temp_dir());
+    temp.min_age_days = temp_min_age_days;
+    let mut out = vec![temp];
+    let home = env_dir(if cfg!(windows) { "USERPROFILE" } else { "HOME" });
+
+    if cfg!(windows) {
+        if let Some(local) = env_dir("LOCALAPPDATA") {
+            chromium_caches(
+                "chrome",
+                "Google Chrome",
+                &local.join("Google/Chrome/User Data"),
+                &mut out,
+            );
+            chromium_caches(
+                "edge",
+                "Microsoft Edge",
+                &local.join("Microsoft/Edge/User Data"),
+                &mut out,
+            );
+            firefox_caches(&local.join("Mozilla/Firefox/Profiles"), &mut out);
+            out.push(item(
+                "npm_cache",
+                PackageManager,
+                "npm cache",
+                local.join("npm-cache"),
+            ));
+            out.push(item(
+                "pip_cache",
+                PackageManager,
+                "pip cache",
+                local.join("pip/Cache"),
+            ));
+            out.push(item(
+                "yarn_cache",
+                PackageManager,
+                "Yarn cache",
+                local.join("Yarn/Cache"),
+            ));
+        }
+    } else if let Some(home) = &home {
+        let caches = if cfg!(target_os = "macos") {
+            home.join("Library/Caches")
+        } else {
+            env_dir("XDG_CACHE_HOME").unwrap_or_else(|| home.join(".cache"))
+        };
+        if cfg!(target_os = "macos") {
+            chromium_caches(
+                "chrome",
+                "Google Chrome",
+                &caches.join("Google/Chrome"),
+                &mut out,
+            );
+            firefox_caches(&caches.join("Firefox/Profiles"), &mut out);
+            out.push(item(
+                "yarn_cache",
+                PackageManager,
+                "Yarn cache",
+                caches.join("Yarn"),
+            ));
+        } else {
+            chromium_caches(
+                "chrome",
+                "Google Chrome",
+                &caches.join("google-chrome"),
+                &mut out,
+            );
+            chromium_caches("chromium", "Chromium", &caches.join("chromium"), &mut out);
+            firefox_caches(&caches.join("mozilla/firefox"), &mut out);
+            out.push(item(
+                "yarn_cache",
+                PackageManager,
+                "Yarn cache",
+                caches.join("yarn"),
+            ));
+        }
+        out.push(item(
+            "pip_cache",
+            PackageManager,
+            "pip cache",
+            caches.join("pip"),
+        ));
+        out.push(item(
+            "npm_cache",
+            PackageManager,
+            "npm cache",
+            home.join(".npm/_cacache"),
+        ));
+    }
+    if let Some(home) = &home {
+        out.push(item(
+            "cargo_cache",
+            PackageManager,
+            "Cargo download cache",
+            home.join(".cargo/registry/cache"),
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn execute_stages_files_and_undo_restores_them() {
+        let root = std::env::temp_dir().join(format!("oxide-cleanup-{}", uuid::Uuid::new_v4()));
+        let cache = root.join("cache");
+        let staging = root.join("staging");
+        fs::create_dir_all(cache.join("nested")).unwrap();
+        fs::write(cache.join("a.bin"), vec![0u8; 300]).unwrap();
+        fs::write(cache.join("nested/b.bin"), vec![0u8; 200]).unwrap();
+        let target = item(
+            "test_cache",
+            CleanupCategory::PackageManager,
+            "Test",
+            cache.clone(),
+        );
+
+        let run = execute(&[target], &staging, 7).unwrap();
+        assert_eq!(run.summary.reclaimed_bytes, 500);
+        assert_eq!(run.summary.items[0].files, 2);
+        assert!(!cache.join("nested/b.bin").exists());
+        assert_eq!(list_runs(&staging)[0].id, run.summary.id);
+
+        // A file recreated since the run is not overwritten
+        fs::write(cache.join("a.bin"), b"new").unwrap();
+        let undo = undo(&staging, &run.summary.id).unwrap();
+        assert_eq!(undo.restored_files, 1);
+        assert_eq!(undo.skipped, 1);
+        assert_eq!(fs::read(cache.join("nested/b.bin")).unwrap().len(), 200);
+        assert_eq!(fs::read(cache.join("a.bin")).unwrap(), b"new");
+        assert!(list_runs(&staging).is_empty());
+
+        // Expired runs are purged
+        fs::write(cache.join("c.bin"), vec![0u8; 100]).unwrap();
+        let run = execute(
+            &[item("c", CleanupCategory::Temp, "C", cache.clone())],
+            &staging,
+            0,
+        )
+        .unwrap();
+        assert_eq!(purge_expired(&staging, Utc::now()), vec![run.summary.id]);
+        assert!(load_run(&staging, "../escape").is_err());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct OxidePilotConfig {
@@ -11,23 +12,184 @@ pub struct OxidePilotConfig {
     pub surreal: Option<SurrealDbConfig>,
     // Optional embedded MCP server configuration
     pub mcp: Option<McpConfig>,
+    // Optional OpenAI-compatible chat API for other local tools
+    #[serde(default)]
+    pub local_api: Option<LocalApiConfig>,
+    // Outbound webhooks for threats, scan results and consensus reports
+    #[serde(default)]
+    pub webhooks: Option<WebhooksConfig>,
+    // Redaction applied to data sent to cloud LLMs; defaults to enabled when absent
+    pub privacy: Option<PrivacyConfig>,
+    // Hard-disable outbound network calls (cloud LLMs, VirusTotal, OAuth refresh)
+    pub offline_mode: Option<bool>,
+    // Proxy and extra trusted CA certificates for outbound HTTP
+    #[serde(default)]
+    pub network: Option<NetworkConfig>,
+    // Threat consensus provider weights, models and timeouts
+    pub consensus: Option<ConsensusConfig>,
+    // Collaborative analysis role -> provider assignments
+    #[serde(default)]
+    pub orchestrator: Option<OrchestratorConfig>,
+    // Outbound request budgets shared by all cloud API clients
+    #[serde(default)]
+    pub api_limits: Option<ApiLimitsConfig>,
+    // Default profile for per-user data isolation; switch_profile overrides it
+    #[serde(default)]
+    pub profile: Option<ProfileConfig>,
+    // Proactive suggestions from habits, threats and process memory trends
+    #[serde(default)]
+    pub suggestions: Option<SuggestionsConfig>,
+    // Response cache for low-temperature LLM prompts; defaults to enabled when absent
+    #[serde(default)]
+    pub llm_cache: Option<LlmCacheConfig>,
+    // Token budget for memories injected into copilot prompts
+    #[serde(default)]
+    pub context_budget: Option<ContextBudgetConfig>,
+    // Global hotkeys for push-to-talk, screenshot questions and panic-stop
+    #[serde(default)]
+    pub hotkeys: Option<HotkeyConfig>,
+    // Language for messages and assistant replies, e.g. "es" or "es-MX";
+    // absent or "auto" follows the OS language
+    #[serde(default)]
+    pub locale: Option<String>,
+    // Speech recognition and synthesis languages
+    #[serde(default)]
+    pub voice: Option<VoiceConfig>,
+    // Overrides for the built-in LLM prompt templates
+    #[serde(default)]
+    pub prompts: Option<PromptConfig>,
+    // Audit trail of prompts and responses exchanged with external LLMs;
+    // defaults to enabled when absent
+    #[serde(default)]
+    pub llm_audit: Option<LlmAuditConfig>,
+    // Release channel and signed manifest endpoint for update checks
+    #[serde(default)]
+    pub updates: Option<UpdatesConfig>,
+    // Where crash reports may be uploaded on request; they always stay on disk
+    #[serde(default)]
+    pub crash_reports: Option<CrashReportConfig>,
+    // Temp and cache cleanup: undo window and locations to leave alone
+    #[serde(default)]
+    pub cleanup: Option<CleanupConfig>,
+    // Battery saver on laptops: when it starts and what it turns down
+    #[serde(default)]
+    pub power: Option<PowerConfig>,
+    // Role checks on guarded commands invoked without a session
+    #[serde(default)]
+    pub security: Option<SecurityConfig>,
 }
 
 impl OxidePilotConfig {
+    /// The first error from [`validate_fields`](Self::validate_fields).
     pub fn validate(&self) -> Result<(), String> {
-        self.guardian.validate()?;
-        self.copilot.validate()?;
-        self.ai_providers.validate(self.copilot.enabled)?;
+        match self
+            .validate_fields()
+            .into_iter()
+            .find(ConfigIssue::is_error)
+        {
+            Some(issue) => Err(issue.message),
+            None => Ok(()),
+        }
+    }
+
+    /// Every problem in the configuration, tagged with the field it belongs
+    /// to. Warnings describe settings that are accepted but will not work
+    /// as expected.
+    pub fn validate_fields(&self) -> Vec<ConfigIssue> {
+        let mut issues = self.guardian.issues();
+        let mut check = |field: &str, result: Result<(), String>| {
+            if let Err(message) = result {
+                issues.push(ConfigIssue::error(field, message));
+            }
+        };
+        check("copilot", self.copilot.validate());
+        check(
+            "ai_providers",
+            self.ai_providers.validate(self.copilot.enabled),
+        );
         if let Some(cognee) = &self.cognee {
-            cognee.validate()?;
+            check("cognee", cognee.validate());
         }
         if let Some(surreal) = &self.surreal {
-            surreal.validate()?;
+            check("surreal", surreal.validate());
         }
         if let Some(mcp) = &self.mcp {
-            mcp.validate()?;
+            check("mcp", mcp.validate());
         }
-        Ok(())
+        if let Some(local_api) = &self.local_api {
+            check("local_api", local_api.validate());
+        }
+        if let Some(webhooks) = &self.webhooks {
+            check("webhooks", webhooks.validate());
+        }
+        if let Some(privacy) = &self.privacy {
+            check("privacy", privacy.validate());
+        }
+        if let Some(network) = &self.network {
+            check("network", network.validate());
+        }
+        if let Some(consensus) = &self.consensus {
+            check("consensus", consensus.validate());
+        }
+        if let Some(orchestrator) = &self.orchestrator {
+            check("orchestrator", orchestrator.validate());
+        }
+        if let Some(api_limits) = &self.api_limits {
+            check("api_limits", api_limits.validate());
+        }
+        if let Some(profile) = &self.profile {
+            check("profile", profile.validate());
+        }
+        if let Some(suggestions) = &self.suggestions {
+            check("suggestions", suggestions.validate());
+        }
+        if let Some(llm_cache) = &self.llm_cache {
+            check("llm_cache", llm_cache.validate());
+        }
+        if let Some(context_budget) = &self.context_budget {
+            check("context_budget", context_budget.validate());
+        }
+        if let Some(hotkeys) = &self.hotkeys {
+            check("hotkeys", hotkeys.validate());
+        }
+        if let Some(voice) = &self.voice {
+            check("voice", voice.validate());
+        }
+        if let Some(prompts) = &self.prompts {
+            check("prompts", prompts.validate());
+        }
+        if let Some(llm_audit) = &self.llm_audit {
+            check("llm_audit", llm_audit.validate());
+        }
+        if let Some(updates) = &self.updates {
+            check("updates", updates.validate());
+        }
+        if let Some(crash_reports) = &self.crash_reports {
+            check("crash_reports", crash_reports.validate());
+        }
+        if let Some(cleanup) = &self.cleanup {
+            check("cleanup", cleanup.validate());
+        }
+        if let Some(power) = &self.power {
+            check("power", power.validate());
+        }
+        if let Some(locale) = self
+            .locale
+            .as_deref()
+            .filter(|tag| !tag.trim().is_empty() && !tag.eq_ignore_ascii_case("auto"))
+        {
+            if let Err(e) = locale.parse::<crate::i18n::Locale>() {
+                issues.push(ConfigIssue::warning(
+                    "locale",
+                    format!("{e}; falling back to the OS language"),
+                ));
+            }
+        }
+        issues
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.offline_mode.unwrap_or(false)
     }
 }
 
@@ -51,39 +213,482 @@ pub struct GuardianConfig {
     // Folder scan tuning
     pub folder_scan_max_workers: Option<usize>,
     pub folder_scan_max_depth: Option<usize>,
+    // Hours a clean folder-scan verdict stays valid for unchanged files; 0 disables the cache (default 24)
+    #[serde(default)]
+    pub scan_cache_ttl_hours: Option<u64>,
+    // Per-directory and per-file timeout for scans of network shares (default 60)
+    #[serde(default)]
+    pub network_scan_timeout_secs: Option<u64>,
     // Optional YARA feature toggles/paths (feature-gated in guardian)
     pub yara_enabled: Option<bool>,
     pub yara_rules_paths: Option<Vec<String>>,
+    // JSON or TOML heuristic ruleset; the built-in rules apply when unset
+    #[serde(default)]
+    pub heuristic_rules_path: Option<String>,
+    // Seconds between baseline drift checks; 0 disables them (default 3600)
+    #[serde(default)]
+    pub baseline_check_interval_secs: Option<u64>,
+    // Seconds between self-integrity checks of Oxide Pilot's own files; 0 disables them (default 300)
+    #[serde(default)]
+    pub integrity_check_interval_secs: Option<u64>,
+    // Directory of YAML/JSON incident response playbooks (default ./data/playbooks)
+    #[serde(default)]
+    pub playbooks_dir: Option<String>,
+    // Syslog, Windows Event Log and email destinations for threat alerts
+    #[serde(default)]
+    pub alert_sinks: Option<Vec<AlertSinkConfig>>,
+    // Default include/exclude rules for folder scans; a scan request may pass its own
+    #[serde(default)]
+    pub scan_filter: Option<ScanFilterConfig>,
+    // Flag processes whose memory keeps growing; needs per-process history
+    // from the SurrealDB metrics collector
+    #[serde(default)]
+    pub leak_detection: Option<LeakDetectionConfig>,
+    // Leave on-access work to another antivirus with real-time protection on:
+    // no process memory scans while monitoring, fewer folder scan workers (default true)
+    #[serde(default)]
+    pub av_coexistence_tuning: Option<bool>,
 }
 
 impl GuardianConfig {
-    fn validate(&self) -> Result<(), String> {
+    /// Most folder scan workers; more only adds contention
+    pub const MAX_FOLDER_SCAN_WORKERS: usize = 256;
+
+    /// Field-level problems, all of them rather than the first.
+    pub fn issues(&self) -> Vec<ConfigIssue> {
+        let mut issues = Vec::new();
+        let mut error = |field: &str, message: &str| {
+            issues.push(ConfigIssue::error(
+                &format!("guardian.{field}"),
+                message.to_string(),
+            ))
+        };
+
         if self.enabled && self.monitor_interval_secs == 0 {
-            return Err("monitor_interval_secs must be greater than 0".to_string());
+            error(
+                "monitor_interval_secs",
+                "monitor_interval_secs must be greater than 0",
+            );
+        }
+        for (field, value) in [
+            ("max_file_size_mb", self.max_file_size_mb),
+            ("vt_cache_ttl_secs", self.vt_cache_ttl_secs),
+            ("network_scan_timeout_secs", self.network_scan_timeout_secs),
+        ] {
+            if value == Some(0) {
+                error(field, &format!("{field} must be greater than 0"));
+            }
+        }
+        for (field, value) in [
+            ("vt_cache_max_entries", self.vt_cache_max_entries),
+            ("folder_scan_max_workers", self.folder_scan_max_workers),
+            ("folder_scan_max_depth", self.folder_scan_max_depth),
+        ] {
+            if value == Some(0) {
+                error(field, &format!("{field} must be greater than 0"));
+            }
         }
-        if let Some(mb) = self.max_file_size_mb {
-            if mb == 0 {
-                return Err("max_file_size_mb must be greater than 0".to_string());
+        if self
+            .folder_scan_max_workers
+            .is_some_and(|w| w > Self::MAX_FOLDER_SCAN_WORKERS)
+        {
+            error(
+                "folder_scan_max_workers",
+                &format!(
+                    "folder_scan_max_workers must be at most {}",
+                    Self::MAX_FOLDER_SCAN_WORKERS
+                ),
+            );
+        }
+        if let Some(secs) = self.baseline_check_interval_secs {
+            if secs > 0 && secs < 60 {
+                error(
+                    "baseline_check_interval_secs",
+                    "baseline_check_interval_secs must be 0 or at least 60",
+                );
+            }
+        }
+        if let Some(secs) = self.integrity_check_interval_secs {
+            if secs > 0 && secs < 60 {
+                error(
+                    "integrity_check_interval_secs",
+                    "integrity_check_interval_secs must be 0 or at least 60",
+                );
+            }
+        }
+        for (field, value) in [
+            ("quarantine_dir", &self.quarantine_dir),
+            ("signatures_path", &self.signatures_path),
+            ("heuristic_rules_path", &self.heuristic_rules_path),
+            ("playbooks_dir", &self.playbooks_dir),
+        ] {
+            if value.as_ref().is_some_and(|v| v.trim().is_empty()) {
+                error(
+                    field,
+                    &format!("{field} must not be empty; remove it to use the default"),
+                );
             }
         }
-        if let Some(ttl) = self.vt_cache_ttl_secs {
-            if ttl == 0 {
-                return Err("vt_cache_ttl_secs must be greater than 0".to_string());
+        if self
+            .yara_rules_paths
+            .iter()
+            .flatten()
+            .any(|p| p.trim().is_empty())
+        {
+            error(
+                "yara_rules_paths",
+                "yara_rules_paths must not contain empty paths",
+            );
+        }
+        for (i, sink) in self.alert_sinks.iter().flatten().enumerate() {
+            if let Err(message) = sink.validate() {
+                error(&format!("alert_sinks[{i}]"), &message);
             }
         }
-        if let Some(max) = self.vt_cache_max_entries {
-            if max == 0 {
-                return Err("vt_cache_max_entries must be greater than 0".to_string());
+        if let Some(Err(message)) = self.scan_filter.as_ref().map(ScanFilterConfig::validate) {
+            error("scan_filter", &message);
+        }
+        if let Some(Err(message)) = self
+            .leak_detection
+            .as_ref()
+            .map(LeakDetectionConfig::validate)
+        {
+            error("leak_detection", &message);
+        }
+
+        // Accepted, but the feature silently does nothing at runtime
+        let antivirus = self.antivirus_enabled.unwrap_or(true);
+        if antivirus && self.quarantine_dir.is_none() {
+            issues.push(ConfigIssue::warning(
+                "guardian.quarantine_dir",
+                "Malicious files cannot be quarantined until quarantine_dir is set".to_string(),
+            ));
+        }
+        if antivirus
+            && self.virustotal_api_key.is_none()
+            && self.hybrid_analysis_api_key.is_none()
+            && std::env::var("VIRUSTOTAL_API_KEY").map_or(true, |k| k.is_empty())
+        {
+            issues.push(ConfigIssue::warning(
+                "guardian.virustotal_api_key",
+                "Cloud scans are unavailable without a VirusTotal or Hybrid Analysis API key"
+                    .to_string(),
+            ));
+        }
+        if self.yara_enabled == Some(true)
+            && self.yara_rules_paths.as_ref().is_none_or(Vec::is_empty)
+        {
+            issues.push(ConfigIssue::warning(
+                "guardian.yara_rules_paths",
+                "YARA is enabled but no rule paths are set".to_string(),
+            ));
+        }
+        issues
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigIssueSeverity {
+    // The configuration is rejected
+    Error,
+    // Accepted, but the setting will not have the intended effect
+    Warning,
+}
+
+/// One problem found by [`OxidePilotConfig::validate_fields`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ConfigIssue {
+    /// Dotted path of the field, e.g. `guardian.quarantine_dir`; a section
+    /// name when the section reports a single error
+    pub field: String,
+    pub message: String,
+    pub severity: ConfigIssueSeverity,
+}
+
+impl ConfigIssue {
+    pub fn error(field: &str, message: String) -> Self {
+        Self {
+            field: field.to_string(),
+            message,
+            severity: ConfigIssueSeverity::Error,
+        }
+    }
+
+    pub fn warning(field: &str, message: String) -> Self {
+        Self {
+            field: field.to_string(),
+            message,
+            severity: ConfigIssueSeverity::Warning,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == ConfigIssueSeverity::Error
+    }
+}
+
+/// Thresholds for flagging a process as leaking: its summed memory must
+/// fit a rising line over at least `min_duration_hours` of the window.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LeakDetectionConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    // Minutes between checks (default 30)
+    #[serde(default)]
+    pub check_interval_mins: Option<u64>,
+    // Hours of history fitted per process (default 6)
+    #[serde(default)]
+    pub window_hours: Option<u64>,
+    // Shortest history worth judging, in hours (default 2)
+    #[serde(default)]
+    pub min_duration_hours: Option<f64>,
+    // Growth below this many MB per hour is ignored (default 50)
+    #[serde(default)]
+    pub min_growth_mb_per_hour: Option<f64>,
+    // R² of the fitted line; closer to 1 means steadier growth (default 0.8)
+    #[serde(default)]
+    pub min_fit: Option<f64>,
+}
+
+impl Default for LeakDetectionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            check_interval_mins: None,
+            window_hours: None,
+            min_duration_hours: None,
+            min_growth_mb_per_hour: None,
+            min_fit: None,
+        }
+    }
+}
+
+impl LeakDetectionConfig {
+    pub const DEFAULT_CHECK_INTERVAL_MINS: u64 = 30;
+    pub const DEFAULT_WINDOW_HOURS: u64 = 6;
+    pub const DEFAULT_MIN_DURATION_HOURS: f64 = 2.0;
+    pub const DEFAULT_MIN_GROWTH_MB_PER_HOUR: f64 = 50.0;
+    pub const DEFAULT_MIN_FIT: f64 = 0.8;
+
+    pub fn check_interval_mins(&self) -> u64 {
+        self.check_interval_mins
+            .unwrap_or(Self::DEFAULT_CHECK_INTERVAL_MINS)
+    }
+
+    pub fn window_hours(&self) -> u64 {
+        self.window_hours.unwrap_or(Self::DEFAULT_WINDOW_HOURS)
+    }
+
+    pub fn min_duration_hours(&self) -> f64 {
+        self.min_duration_hours
+            .unwrap_or(Self::DEFAULT_MIN_DURATION_HOURS)
+    }
+
+    pub fn min_growth_mb_per_hour(&self) -> f64 {
+        self.min_growth_mb_per_hour
+            .unwrap_or(Self::DEFAULT_MIN_GROWTH_MB_PER_HOUR)
+    }
+
+    pub fn min_fit(&self) -> f64 {
+        self.min_fit.unwrap_or(Self::DEFAULT_MIN_FIT)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.check_interval_mins == Some(0) || self.window_hours == Some(0) {
+            return Err("check_interval_mins and window_hours must be greater than 0".to_string());
+        }
+        if self.min_duration_hours() <= 0.0
+            || self.min_duration_hours() > self.window_hours() as f64
+        {
+            return Err("min_duration_hours must be positive and within window_hours".to_string());
+        }
+        if self.min_growth_mb_per_hour() <= 0.0 {
+            return Err("min_growth_mb_per_hour must be greater than 0".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.min_fit()) {
+            return Err("min_fit must be in [0, 1]".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Which files a folder scan looks at. Excludes win over includes; when any
+/// include list is set, a file must match at least one include rule.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScanFilterConfig {
+    // Extensions without the dot, case-insensitive, e.g. "exe", "ps1"
+    #[serde(default)]
+    pub include_extensions: Vec<String>,
+    #[serde(default)]
+    pub exclude_extensions: Vec<String>,
+    // Sniffed from file headers: a category (executable, script, archive,
+    // document, image, audio, video) or a MIME type, optionally "image/*"
+    #[serde(default)]
+    pub include_content: Vec<String>,
+    #[serde(default)]
+    pub exclude_content: Vec<String>,
+    // Glob patterns matched against the full path, e.g. "**/node_modules/**"
+    #[serde(default)]
+    pub include_globs: Vec<String>,
+    #[serde(default)]
+    pub exclude_globs: Vec<String>,
+}
+
+impl ScanFilterConfig {
+    pub const CONTENT_CATEGORIES: [&'static str; 7] = [
+        "executable",
+        "script",
+        "archive",
+        "document",
+        "image",
+        "audio",
+        "video",
+    ];
+
+    pub fn is_empty(&self) -> bool {
+        self.include_extensions.is_empty()
+            && self.exclude_extensions.is_empty()
+            && self.include_content.is_empty()
+            && self.exclude_content.is_empty()
+            && self.include_globs.is_empty()
+            && self.exclude_globs.is_empty()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        for content in self.include_content.iter().chain(&self.exclude_content) {
+            let content = content.trim().to_ascii_lowercase();
+            if !content.contains('/') && !Self::CONTENT_CATEGORIES.contains(&content.as_str()) {
+                return Err(format!(
+                    "Unknown scan content type '{content}'; use a MIME type or one of: {}",
+                    Self::CONTENT_CATEGORIES.join(", ")
+                ));
             }
         }
-        if let Some(w) = self.folder_scan_max_workers {
-            if w == 0 {
-                return Err("folder_scan_max_workers must be greater than 0".to_string());
+        if self
+            .include_globs
+            .iter()
+            .chain(&self.exclude_globs)
+            .any(|glob| glob.trim().is_empty())
+        {
+            return Err("Scan filter glob patterns must not be empty".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Where Guardian forwards threat alerts.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AlertSinkConfig {
+    // RFC 5424 messages to a syslog collector
+    Syslog {
+        // host:port of the collector
+        address: String,
+        #[serde(default)]
+        protocol: SyslogProtocol,
+        // Syslog facility code 0-23 (default 4, security/authorization)
+        #[serde(default)]
+        facility: Option<u8>,
+        // Lowest severity forwarded: low, medium, high or critical (default high)
+        #[serde(default)]
+        min_severity: Option<String>,
+    },
+    // Application log of the Windows Event Log; ignored on other platforms
+    EventLog {
+        // Event source name (default "Oxide Pilot")
+        #[serde(default)]
+        source: Option<String>,
+        #[serde(default)]
+        min_severity: Option<String>,
+    },
+    // Email through an SMTP relay; the password lives in the OS keyring
+    Email {
+        host: String,
+        // Default 587 for STARTTLS, 465 for implicit TLS
+        #[serde(default)]
+        port: Option<u16>,
+        #[serde(default)]
+        tls: SmtpTls,
+        #[serde(default)]
+        username: Option<String>,
+        from: String,
+        to: Vec<String>,
+        // Collect alerts for this many minutes and send one digest; 0 sends
+        // each alert immediately (default 10)
+        #[serde(default)]
+        digest_minutes: Option<u64>,
+        // Default critical
+        #[serde(default)]
+        min_severity: Option<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpTls {
+    #[default]
+    StartTls,
+    // TLS from the first byte, usually port 465
+    Implicit,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SyslogProtocol {
+    #[default]
+    Udp,
+    Tcp,
+}
+
+impl AlertSinkConfig {
+    pub fn min_severity(&self) -> Option<&str> {
+        match self {
+            Self::Syslog { min_severity, .. }
+            | Self::EventLog { min_severity, .. }
+            | Self::Email { min_severity, .. } => min_severity.as_deref(),
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if let Some(severity) = self.min_severity() {
+            if !matches!(
+                severity.to_ascii_lowercase().as_str(),
+                "low" | "medium" | "high" | "critical"
+            ) {
+                return Err(format!(
+                    "Alert sink min_severity must be low, medium, high or critical, not '{severity}'"
+                ));
             }
         }
-        if let Some(d) = self.folder_scan_max_depth {
-            if d == 0 {
-                return Err("folder_scan_max_depth must be greater than 0".to_string());
+        match self {
+            Self::Syslog {
+                address, facility, ..
+            } => {
+                if address.rsplit_once(':').is_none_or(|(host, port)| {
+                    host.is_empty() || port.parse::<u16>().map_or(true, |p| p == 0)
+                }) {
+                    return Err(format!("Syslog address must be host:port, not '{address}'"));
+                }
+                if facility.is_some_and(|f| f > 23) {
+                    return Err("Syslog facility must be between 0 and 23".to_string());
+                }
+            }
+            Self::EventLog { source, .. } => {
+                if source.as_deref().is_some_and(|s| s.trim().is_empty()) {
+                    return Err("Event Log source must not be empty".to_string());
+                }
+            }
+            Self::Email { host, from, to, .. } => {
+                if host.trim().is_empty() {
+                    return Err("Email alert sink requires an SMTP host".to_string());
+                }
+                if to.is_empty() {
+                    return Err("Email alert sink requires at least one recipient".to_string());
+                }
+                if let Some(address) = std::iter::once(from).chain(to).find(|a| !a.contains('@')) {
+                    return Err(format!("'{address}' is not an email address"));
+                }
             }
         }
         Ok(())
@@ -94,6 +699,12 @@ impl GuardianConfig {
 pub struct CopilotConfig {
     pub enabled: bool,
     pub wake_word: String,
+    // Include the foreground window title and process in prompts
+    #[serde(default = "default_true")]
+    pub share_active_window: bool,
+    // Include clipboard text in prompts; off until the user opts in
+    #[serde(default)]
+    pub share_clipboard: bool,
 }
 
 impl CopilotConfig {
@@ -138,74 +749,605 @@ pub struct SurrealDbConfig {
     pub distributed: bool,
     #[serde(default)]
     pub tikv_endpoints: Option<Vec<String>>,
+    /// Remote SurrealDB server (`ws://`, `wss://`, `http://`, `https://`)
+    /// shared by a fleet; the password is read from `OXIDE_SURREAL_PASS`
+    #[serde(default)]
+    pub remote_url: Option<String>,
+    #[serde(default)]
+    pub username: Option<String>,
+    /// Mirror metrics, threats and scan summaries to a central fleet server
+    /// while keeping this backend
+    #[serde(default)]
+    pub uplink: Option<FleetUplinkConfig>,
     #[serde(default)]
     pub enable_js_functions: bool,
     #[serde(default)]
     pub enable_computed_views: bool,
+    /// Periodic LLM summaries that replace clusters of old agent memories
+    #[serde(default)]
+    pub consolidation: Option<MemoryConsolidationConfig>,
+    /// How long texts are split before embedding into agent memory
+    #[serde(default)]
+    pub chunking: Option<MemoryChunkingConfig>,
+    /// Periodic collection of labelled threats and threat model retraining
+    #[serde(default)]
+    pub threat_training: Option<ThreatTrainingConfig>,
 }
 
-impl SurrealDbConfig {
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FleetUplinkConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    // Central SurrealDB server (`ws://`, `wss://`, `http://`, `https://`)
+    pub url: String,
+    // Sign-in user; the password is read from `OXIDE_FLEET_PASS`
+    #[serde(default)]
+    pub username: Option<String>,
+    // Sign in as a user of the fleet database rather than a root user
+    #[serde(default)]
+    pub database_user: bool,
+    // Namespace and database on the central server (default oxide_fleet/fleet)
+    #[serde(default)]
+    pub namespace: Option<String>,
+    #[serde(default)]
+    pub database: Option<String>,
+    // Tag records with this id instead of the one generated per machine
+    #[serde(default)]
+    pub machine_id: Option<String>,
+    // Most records sent in one batch (default 500)
+    #[serde(default)]
+    pub batch_size: Option<usize>,
+    // Seconds between flushes (default 30)
+    #[serde(default)]
+    pub flush_interval_secs: Option<u64>,
+    // Records kept while the server is unreachable before the oldest are
+    // dropped (default 20000)
+    #[serde(default)]
+    pub max_queued: Option<usize>,
+}
+
+impl FleetUplinkConfig {
+    pub const DEFAULT_NAMESPACE: &'static str = "oxide_fleet";
+    pub const DEFAULT_DATABASE: &'static str = "fleet";
+    pub const DEFAULT_BATCH_SIZE: usize = 500;
+    pub const DEFAULT_FLUSH_INTERVAL_SECS: u64 = 30;
+    pub const DEFAULT_MAX_QUEUED: usize = 20_000;
+
+    pub fn namespace(&self) -> &str {
+        self.namespace.as_deref().unwrap_or(Self::DEFAULT_NAMESPACE)
+    }
+
+    pub fn database(&self) -> &str {
+        self.database.as_deref().unwrap_or(Self::DEFAULT_DATABASE)
+    }
+
+    pub fn batch_size(&self) -> usize {
+        self.batch_size.unwrap_or(Self::DEFAULT_BATCH_SIZE)
+    }
+
+    pub fn flush_interval_secs(&self) -> u64 {
+        self.flush_interval_secs
+            .unwrap_or(Self::DEFAULT_FLUSH_INTERVAL_SECS)
+    }
+
+    pub fn max_queued(&self) -> usize {
+        self.max_queued.unwrap_or(Self::DEFAULT_MAX_QUEUED)
+    }
+
     fn validate(&self) -> Result<(), String> {
-        if self.enabled {
-            if let Some(path) = &self.db_path {
-                if path.trim().is_empty() {
-                    return Err("SurrealDB path must not be empty when enabled".to_string());
-                }
-            }
-            if let Some(interval) = self.metrics_interval_secs {
-                if interval == 0 {
-                    return Err(
-                        "SurrealDB metrics interval must be greater than 0 seconds".to_string()
-                    );
-                }
-            }
-            if self.distributed {
-                let endpoint_count = self
-                    .tikv_endpoints
-                    .as_ref()
-                    .map(|v| v.iter().filter(|item| !item.trim().is_empty()).count())
-                    .unwrap_or(0);
-                if endpoint_count == 0 {
-                    return Err(
-                        "SurrealDB distributed mode requires at least one TiKV endpoint"
-                            .to_string(),
-                    );
-                }
-            }
+        if !self.enabled {
+            return Ok(());
+        }
+        let url = self.url.trim().to_ascii_lowercase();
+        if !["ws://", "wss://", "http://", "https://"]
+            .iter()
+            .any(|scheme| url.starts_with(scheme))
+        {
+            return Err(
+                "Fleet uplink URL must start with ws://, wss://, http:// or https://".to_string(),
+            );
+        }
+        if self.batch_size == Some(0) {
+            return Err("Fleet uplink batch_size must be greater than 0".to_string());
+        }
+        if self.flush_interval_secs == Some(0) {
+            return Err("Fleet uplink flush_interval_secs must be greater than 0".to_string());
+        }
+        if self.max_queued.is_some_and(|max| max < self.batch_size()) {
+            return Err("Fleet uplink max_queued must be at least batch_size".to_string());
+        }
+        if [&self.namespace, &self.database, &self.machine_id]
+            .into_iter()
+            .flatten()
+            .any(|value| value.trim().is_empty())
+        {
+            return Err(
+                "Fleet uplink namespace, database and machine_id must not be empty".to_string(),
+            );
         }
         Ok(())
     }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct McpConfig {
-    // Whether the embedded MCP server should run
+pub struct MemoryConsolidationConfig {
+    #[serde(default = "default_true")]
     pub enabled: bool,
-    // Localhost port for the MCP HTTP server (streamable)
-    pub port: u16,
-    // Optional encrypted password for simple bearer auth
-    pub password: Option<crate::encryption::EncryptedData>,
+    // Hours between runs (default 24)
+    #[serde(default)]
+    pub interval_hours: Option<u64>,
+    // Only memories older than this are consolidated (default 30 days)
+    #[serde(default)]
+    pub min_age_days: Option<u32>,
+    // Cosine similarity for two memories to share a summary (default 0.8)
+    #[serde(default)]
+    pub similarity_threshold: Option<f64>,
+    // Most memories folded into one summary (default 20)
+    #[serde(default)]
+    pub max_cluster_size: Option<usize>,
+    // What happens to the raw memories once summarized
+    #[serde(default)]
+    pub originals: ConsolidationRetention,
 }
 
-impl McpConfig {
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsolidationRetention {
+    // Move to `agent_memory_archive`, out of semantic search
+    #[default]
+    Archive,
+    Delete,
+}
+
+impl Default for MemoryConsolidationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_hours: None,
+            min_age_days: None,
+            similarity_threshold: None,
+            max_cluster_size: None,
+            originals: ConsolidationRetention::default(),
+        }
+    }
+}
+
+impl MemoryConsolidationConfig {
     fn validate(&self) -> Result<(), String> {
-        if self.enabled {
-            if self.port == 0 {
-                return Err("MCP port must be greater than 0".to_string());
-            }
-            // Disallow privileged or invalid ranges conservatively
-            if self.port < 1024 {
-                return Err("MCP port must be between 1024 and 65535".to_string());
-            }
+        if self.interval_hours == Some(0) {
+            return Err("Memory consolidation interval_hours must be greater than 0".to_string());
+        }
+        if self
+            .similarity_threshold
+            .is_some_and(|t| !(t > 0.0 && t <= 1.0))
+        {
+            return Err("Memory consolidation similarity_threshold must be in (0, 1]".to_string());
+        }
+        if self.max_cluster_size.is_some_and(|n| n < 2) {
+            return Err("Memory consolidation max_cluster_size must be at least 2".to_string());
         }
         Ok(())
     }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct AIProvidersConfig {
-    pub google: Option<GoogleConfig>,
-    pub openai: Option<OpenAIConfig>,
+pub struct ThreatTrainingConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    // Hours between collection and retraining runs (default 24)
+    #[serde(default)]
+    pub interval_hours: Option<u64>,
+    // Consensus runs below this confidence are not used as labels (default 0.7)
+    #[serde(default)]
+    pub consensus_min_confidence: Option<f64>,
+    // Samples needed before the model is retrained (default 20)
+    #[serde(default)]
+    pub min_samples: Option<usize>,
+}
+
+impl Default for ThreatTrainingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_hours: None,
+            consensus_min_confidence: None,
+            min_samples: None,
+        }
+    }
+}
+
+impl ThreatTrainingConfig {
+    fn validate(&self) -> Result<(), String> {
+        if self.interval_hours == Some(0) {
+            return Err("Threat training interval_hours must be greater than 0".to_string());
+        }
+        if self
+            .consensus_min_confidence
+            .is_some_and(|c| !(0.0..=1.0).contains(&c))
+        {
+            return Err("Threat training consensus_min_confidence must be in [0, 1]".to_string());
+        }
+        if self.min_samples == Some(0) {
+            return Err("Threat training min_samples must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MemoryChunkingConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    // Largest chunk in characters, roughly 4 per token (default 2000)
+    #[serde(default)]
+    pub chunk_size: Option<usize>,
+    // Characters repeated at the start of the next chunk (default 200)
+    #[serde(default)]
+    pub chunk_overlap: Option<usize>,
+    // Break at sentence and line ends rather than mid-sentence
+    #[serde(default = "default_true")]
+    pub sentence_aware: bool,
+}
+
+impl Default for MemoryChunkingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            chunk_size: None,
+            chunk_overlap: None,
+            sentence_aware: true,
+        }
+    }
+}
+
+impl MemoryChunkingConfig {
+    pub const DEFAULT_CHUNK_SIZE: usize = 2000;
+    pub const DEFAULT_CHUNK_OVERLAP: usize = 200;
+
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size.unwrap_or(Self::DEFAULT_CHUNK_SIZE)
+    }
+
+    /// Overlap, clamped to half the chunk size so chunking always advances.
+    pub fn chunk_overlap(&self) -> usize {
+        self.chunk_overlap
+            .unwrap_or(Self::DEFAULT_CHUNK_OVERLAP)
+            .min(self.chunk_size() / 2)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.chunk_size == Some(0) {
+            return Err("Memory chunk_size must be greater than 0".to_string());
+        }
+        if self
+            .chunk_overlap
+            .is_some_and(|overlap| overlap > self.chunk_size() / 2)
+        {
+            return Err("Memory chunk_overlap must be at most half of chunk_size".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl SurrealDbConfig {
+    fn validate(&self) -> Result<(), String> {
+        if self.enabled {
+            if let Some(path) = &self.db_path {
+                if path.trim().is_empty() {
+                    return Err("SurrealDB path must not be empty when enabled".to_string());
+                }
+            }
+            if let Some(interval) = self.metrics_interval_secs {
+                if interval == 0 {
+                    return Err(
+                        "SurrealDB metrics interval must be greater than 0 seconds".to_string()
+                    );
+                }
+            }
+            if self.distributed {
+                let endpoint_count = self
+                    .tikv_endpoints
+                    .as_ref()
+                    .map(|v| v.iter().filter(|item| !item.trim().is_empty()).count())
+                    .unwrap_or(0);
+                if endpoint_count == 0 {
+                    return Err(
+                        "SurrealDB distributed mode requires at least one TiKV endpoint"
+                            .to_string(),
+                    );
+                }
+            }
+            if let Some(url) = &self.remote_url {
+                let url = url.trim().to_ascii_lowercase();
+                if !["ws://", "wss://", "http://", "https://"]
+                    .iter()
+                    .any(|scheme| url.starts_with(scheme))
+                {
+                    return Err(
+                        "SurrealDB remote URL must start with ws://, wss://, http:// or https://"
+                            .to_string(),
+                    );
+                }
+                if self.distributed {
+                    return Err(
+                        "SurrealDB remote URL and distributed TiKV mode are mutually exclusive"
+                            .to_string(),
+                    );
+                }
+            }
+            if let Some(consolidation) = &self.consolidation {
+                consolidation.validate()?;
+            }
+            if let Some(chunking) = &self.chunking {
+                chunking.validate()?;
+            }
+            if let Some(threat_training) = &self.threat_training {
+                threat_training.validate()?;
+            }
+        }
+        if let Some(uplink) = &self.uplink {
+            uplink.validate()?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct McpConfig {
+    // Whether the embedded MCP server should run
+    pub enabled: bool,
+    // Localhost port for the MCP HTTP server (streamable)
+    pub port: u16,
+    // Optional encrypted password for simple bearer auth
+    pub password: Option<crate::encryption::EncryptedData>,
+    // Serve HTTPS instead of plaintext HTTP
+    #[serde(default)]
+    pub tls: Option<McpTlsConfig>,
+    // Listen on all interfaces so other instances on the LAN can manage this
+    // one; needs TLS and at least one token
+    #[serde(default)]
+    pub allow_remote: bool,
+}
+
+impl McpConfig {
+    fn validate(&self) -> Result<(), String> {
+        if self.enabled {
+            if self.port == 0 {
+                return Err("MCP port must be greater than 0".to_string());
+            }
+            // Disallow privileged or invalid ranges conservatively
+            if self.port < 1024 {
+                return Err("MCP port must be between 1024 and 65535".to_string());
+            }
+        }
+        if let Some(tls) = &self.tls {
+            tls.validate()?;
+        }
+        if self.allow_remote && !self.tls.as_ref().is_some_and(|t| t.enabled) {
+            return Err("Remote MCP access requires TLS".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct McpTlsConfig {
+    pub enabled: bool,
+    // PEM certificate chain and private key; both or neither. Without them a
+    // self-signed certificate is generated on first use and reused afterwards
+    pub cert_path: Option<String>,
+    pub key_path: Option<String>,
+}
+
+impl McpTlsConfig {
+    fn validate(&self) -> Result<(), String> {
+        match (&self.cert_path, &self.key_path) {
+            (Some(_), None) | (None, Some(_)) => {
+                Err("MCP TLS needs both cert_path and key_path, or neither".to_string())
+            }
+            (Some(cert), Some(key)) if cert.trim().is_empty() || key.trim().is_empty() => {
+                Err("MCP TLS cert_path and key_path must not be empty".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LocalApiConfig {
+    // Whether the OpenAI-compatible API server should run
+    pub enabled: bool,
+    // Localhost port for `/v1/chat/completions`
+    pub port: u16,
+    // Encrypted bearer token clients must send; the server refuses to start without one
+    pub token: Option<crate::encryption::EncryptedData>,
+}
+
+impl LocalApiConfig {
+    fn validate(&self) -> Result<(), String> {
+        if self.enabled {
+            if self.port < 1024 {
+                return Err("Local API port must be between 1024 and 65535".to_string());
+            }
+            if self.token.is_none() {
+                return Err("Local API requires an access token".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct WebhooksConfig {
+    #[serde(default)]
+    pub endpoints: Vec<WebhookEndpoint>,
+    // Delivery attempts per event, including the first; defaults to 4
+    #[serde(default)]
+    pub max_attempts: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WebhookEndpoint {
+    pub url: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    // Sign with the HMAC-SHA256 key kept in the OS keyring for this URL;
+    // deliveries fail while the key is missing
+    #[serde(default)]
+    pub signed: bool,
+    // Plaintext key from older configs; only read so validation can reject it
+    #[serde(default, skip_serializing)]
+    pub secret: Option<String>,
+    // Events to send; every event when empty
+    #[serde(default)]
+    pub events: Vec<WebhookEvent>,
+    #[serde(default)]
+    pub format: WebhookFormat,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    ThreatDetected,
+    ScanCompleted,
+    ConsensusReport,
+    // Sent only by the "test webhook" action
+    Test,
+}
+
+/// Body shape expected by the receiving service.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookFormat {
+    // The full event envelope, for SIEMs and custom receivers
+    #[default]
+    Json,
+    Slack,
+    Discord,
+    Teams,
+}
+
+impl WebhooksConfig {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if self.max_attempts == Some(0) {
+            return Err("Webhook max_attempts must be at least 1".to_string());
+        }
+        // Endpoints are numbered rather than named: chat webhook URLs embed tokens
+        for (n, endpoint) in self.endpoints.iter().enumerate().map(|(i, e)| (i + 1, e)) {
+            let url = url::Url::parse(&endpoint.url)
+                .map_err(|e| format!("Webhook endpoint {n} has an invalid URL: {e}"))?;
+            if !matches!(url.scheme(), "http" | "https") {
+                return Err(format!("Webhook endpoint {n} must use http or https"));
+            }
+            if endpoint.secret.is_some() {
+                return Err(format!(
+                    "Webhook endpoint {n} has a plaintext secret; store it in the keyring \
+                     and set `signed` instead"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct NetworkConfig {
+    // http://, https://, socks5:// or socks5h:// proxy for non-loopback requests
+    #[serde(default)]
+    pub proxy_url: Option<String>,
+    // Proxy credentials; the password is read from `OXIDE_PROXY_PASSWORD`
+    #[serde(default)]
+    pub proxy_username: Option<String>,
+    // Hosts (or ".domain" suffixes) that bypass the proxy
+    #[serde(default)]
+    pub no_proxy: Vec<String>,
+    // PEM or DER files trusted in addition to the system roots
+    #[serde(default)]
+    pub ca_cert_paths: Vec<String>,
+}
+
+impl NetworkConfig {
+    pub const PROXY_SCHEMES: [&'static str; 4] = ["http", "https", "socks5", "socks5h"];
+
+    fn validate(&self) -> Result<(), String> {
+        if let Some(url) = &self.proxy_url {
+            let scheme = url.split("://").next().unwrap_or_default();
+            if !url.contains("://") || !Self::PROXY_SCHEMES.contains(&scheme) {
+                return Err(format!(
+                    "Proxy URL must start with one of: {}",
+                    Self::PROXY_SCHEMES
+                        .iter()
+                        .map(|s| format!("{s}://"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+            }
+        }
+        if self
+            .proxy_username
+            .as_deref()
+            .is_some_and(|u| u.trim().is_empty())
+        {
+            return Err("Proxy username must not be empty".to_string());
+        }
+        if self.proxy_username.is_some() && self.proxy_url.is_none() {
+            return Err("Proxy username requires proxy_url".to_string());
+        }
+        if self.ca_cert_paths.iter().any(|p| p.trim().is_empty()) {
+            return Err("CA certificate paths must not be empty".to_string());
+        }
+        Ok(())
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PrivacyConfig {
+    // Master switch for pseudonymization before cloud LLM calls
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_true")]
+    pub redact_paths: bool,
+    #[serde(default = "default_true")]
+    pub redact_hostnames: bool,
+    #[serde(default = "default_true")]
+    pub redact_ips: bool,
+    #[serde(default = "default_true")]
+    pub redact_usernames: bool,
+    // Additional literal terms to pseudonymize (e.g. company or project names)
+    #[serde(default)]
+    pub extra_terms: Vec<String>,
+}
+
+impl Default for PrivacyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            redact_paths: true,
+            redact_hostnames: true,
+            redact_ips: true,
+            redact_usernames: true,
+            extra_terms: Vec::new(),
+        }
+    }
+}
+
+impl PrivacyConfig {
+    fn validate(&self) -> Result<(), String> {
+        if self.extra_terms.iter().any(|t| t.trim().is_empty()) {
+            return Err("privacy extra_terms must not contain empty entries".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AIProvidersConfig {
+    pub google: Option<GoogleConfig>,
+    pub openai: Option<OpenAIConfig>,
     pub anthropic: Option<AnthropicConfig>,
     pub azure_openai: Option<AzureOpenAIConfig>,
     pub ollama: Option<OllamaConfig>,
@@ -249,27 +1391,106 @@ impl AIProvidersConfig {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GoogleConfig {
     pub api_key: String,
+    // Model used for text requests; defaults to DEFAULT_MODEL
+    #[serde(default)]
+    pub model: Option<String>,
+    // Model used when a request carries images; defaults to `model`
+    #[serde(default)]
+    pub vision_model: Option<String>,
+    // "v1" or "v1beta"; the beta surface is needed for preview models
+    #[serde(default)]
+    pub api_version: Option<String>,
+    // Retries on 429 and 5xx responses
+    #[serde(default)]
+    pub max_retries: Option<u32>,
 }
 
 impl GoogleConfig {
+    pub const DEFAULT_MODEL: &'static str = "gemini-1.5-flash";
+    pub const API_VERSIONS: [&'static str; 2] = ["v1", "v1beta"];
+    pub const DEFAULT_MAX_RETRIES: u32 = 3;
+
+    pub fn model(&self) -> &str {
+        self.model.as_deref().unwrap_or(Self::DEFAULT_MODEL)
+    }
+
+    pub fn vision_model(&self) -> &str {
+        self.vision_model.as_deref().unwrap_or(self.model())
+    }
+
+    pub fn api_version(&self) -> &str {
+        self.api_version.as_deref().unwrap_or(Self::API_VERSIONS[0])
+    }
+
+    pub fn max_retries(&self) -> u32 {
+        self.max_retries.unwrap_or(Self::DEFAULT_MAX_RETRIES)
+    }
+
     pub fn validate(&self) -> Result<(), String> {
         if self.api_key.is_empty() {
             return Err("Google API key cannot be empty".to_string());
         }
+        for model in [&self.model, &self.vision_model].into_iter().flatten() {
+            if model.trim().is_empty() || model.contains('/') {
+                return Err(format!(
+                    "Google model '{model}' must be a bare model ID such as '{}'",
+                    Self::DEFAULT_MODEL
+                ));
+            }
+        }
+        if !Self::API_VERSIONS.contains(&self.api_version()) {
+            return Err(format!(
+                "Google api_version must be one of: {}",
+                Self::API_VERSIONS.join(", ")
+            ));
+        }
+        if self.max_retries() > 10 {
+            return Err("Google max_retries must be at most 10".to_string());
+        }
         Ok(())
     }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct OpenAIConfig {
+    // A literal key, or KEYRING_PLACEHOLDER to use the stored key or OAuth login
     pub api_key: String,
+    // Chat model; defaults to DEFAULT_MODEL
+    #[serde(default)]
+    pub model: Option<String>,
+    // Sampling temperature, 0.0 to 2.0; the API default when unset
+    #[serde(default)]
+    pub temperature: Option<f32>,
 }
 
 impl OpenAIConfig {
+    pub const DEFAULT_MODEL: &'static str = "gpt-4o";
+    pub const KEYRING_PLACEHOLDER: &'static str = "keyring";
+
+    pub fn model(&self) -> &str {
+        self.model.as_deref().unwrap_or(Self::DEFAULT_MODEL)
+    }
+
+    /// The key written in the config, unless it defers to the keyring.
+    pub fn inline_api_key(&self) -> Option<&str> {
+        let key = self.api_key.trim();
+        (!key.is_empty() && key != Self::KEYRING_PLACEHOLDER).then_some(key)
+    }
+
     fn validate(&self) -> Result<(), String> {
         if self.api_key.is_empty() {
             return Err("OpenAI API key must not be empty".to_string());
         }
+        if let Some(model) = &self.model {
+            if model.trim().is_empty() {
+                return Err("OpenAI model must not be empty".to_string());
+            }
+        }
+        if let Some(temperature) = self.temperature {
+            if !(0.0..=2.0).contains(&temperature) {
+                return Err("OpenAI temperature must be between 0.0 and 2.0".to_string());
+            }
+        }
         Ok(())
     }
 }
@@ -319,3 +1540,935 @@ impl OllamaConfig {
         Ok(())
     }
 }
+
+fn default_consensus_timeout_secs() -> u64 {
+    90
+}
+
+fn default_trust_weight() -> f32 {
+    1.0
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConsensusConfig {
+    // Per-provider timeout used when a provider has no override
+    #[serde(default = "default_consensus_timeout_secs")]
+    pub timeout_secs: u64,
+    // Store each run (inputs, provider reports, final score) in SurrealDB
+    #[serde(default = "default_true")]
+    pub persist_runs: bool,
+    // Keyed by provider id: "gemini", "qwen" or "openai"
+    #[serde(default)]
+    pub providers: HashMap<String, ConsensusProviderConfig>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConsensusProviderConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    // Trust multiplier applied on top of the model-reported confidence
+    #[serde(default = "default_trust_weight")]
+    pub weight: f32,
+    // Overrides the provider's default/env model
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+impl Default for ConsensusConfig {
+    fn default() -> Self {
+        Self {
+            timeout_secs: default_consensus_timeout_secs(),
+            persist_runs: true,
+            providers: HashMap::new(),
+        }
+    }
+}
+
+impl Default for ConsensusProviderConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            weight: default_trust_weight(),
+            model: None,
+            timeout_secs: None,
+        }
+    }
+}
+
+impl ConsensusConfig {
+    pub const PROVIDERS: [&'static str; 3] = ["gemini", "qwen", "openai"];
+
+    /// Settings for `provider`, falling back to defaults when not configured.
+    pub fn provider(&self, provider: &str) -> ConsensusProviderConfig {
+        self.providers.get(provider).cloned().unwrap_or_default()
+    }
+
+    pub fn timeout_secs_for(&self, provider: &str) -> u64 {
+        self.providers
+            .get(provider)
+            .and_then(|p| p.timeout_secs)
+            .unwrap_or(self.timeout_secs)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.timeout_secs == 0 {
+            return Err("consensus timeout_secs must be greater than 0".to_string());
+        }
+        for (name, provider) in &self.providers {
+            if !Self::PROVIDERS.contains(&name.as_str()) {
+                return Err(format!(
+                    "Unknown consensus provider '{name}' (expected one of: {})",
+                    Self::PROVIDERS.join(", ")
+                ));
+            }
+            if !provider.weight.is_finite() || provider.weight < 0.0 {
+                return Err(format!(
+                    "Consensus weight for '{name}' must be a non-negative number"
+                ));
+            }
+            if provider.timeout_secs == Some(0) {
+                return Err(format!(
+                    "Consensus timeout_secs for '{name}' must be greater than 0"
+                ));
+            }
+            if provider
+                .model
+                .as_deref()
+                .is_some_and(|m| m.trim().is_empty())
+            {
+                return Err(format!("Consensus model for '{name}' must not be empty"));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OrchestratorConfig {
+    // Keyed by role: "coordinator", "analyst", "executor", "innovator" or "validator"
+    #[serde(default)]
+    pub roles: HashMap<String, OrchestratorRoleConfig>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OrchestratorRoleConfig {
+    // "gemini", "qwen" or "openai"
+    pub provider: String,
+    // Overrides the provider's default model
+    #[serde(default)]
+    pub model: Option<String>,
+    pub temperature: f32,
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+}
+
+impl OrchestratorRoleConfig {
+    fn new(provider: &str, model: Option<&str>, temperature: f32) -> Self {
+        Self {
+            provider: provider.to_string(),
+            model: model.map(str::to_string),
+            temperature,
+            max_tokens: Some(2048),
+        }
+    }
+}
+
+impl Default for OrchestratorConfig {
+    /// Gemini coordinates and executes, Qwen analyses and validates.
+    fn default() -> Self {
+        let roles = [
+            (
+                "coordinator",
+                OrchestratorRoleConfig::new("gemini", Some("gemini-1.5-pro"), 0.3),
+            ),
+            ("analyst", OrchestratorRoleConfig::new("qwen", None, 0.1)),
+            (
+                "executor",
+                OrchestratorRoleConfig::new("gemini", Some("gemini-1.5-flash"), 0.2),
+            ),
+            ("validator", OrchestratorRoleConfig::new("qwen", None, 0.1)),
+        ];
+        Self {
+            roles: roles
+                .into_iter()
+                .map(|(role, cfg)| (role.to_string(), cfg))
+                .collect(),
+        }
+    }
+}
+
+impl OrchestratorConfig {
+    pub const ROLES: [&'static str; 5] = [
+        "coordinator",
+        "analyst",
+        "executor",
+        "innovator",
+        "validator",
+    ];
+    pub const PROVIDERS: [&'static str; 3] = ["gemini", "qwen", "openai"];
+    pub const MAX_TOKENS_LIMIT: u32 = 32_768;
+
+    fn validate(&self) -> Result<(), String> {
+        if !self.roles.contains_key("coordinator") {
+            return Err("orchestrator roles must include a coordinator".to_string());
+        }
+        for (role, cfg) in &self.roles {
+            if !Self::ROLES.contains(&role.as_str()) {
+                return Err(format!(
+                    "Unknown orchestrator role '{role}' (expected one of: {})",
+                    Self::ROLES.join(", ")
+                ));
+            }
+            if !Self::PROVIDERS.contains(&cfg.provider.as_str()) {
+                return Err(format!(
+                    "Unknown provider '{}' for orchestrator role '{role}' (expected one of: {})",
+                    cfg.provider,
+                    Self::PROVIDERS.join(", ")
+                ));
+            }
+            if !cfg.temperature.is_finite() || !(0.0..=2.0).contains(&cfg.temperature) {
+                return Err(format!(
+                    "Orchestrator temperature for '{role}' must be between 0.0 and 2.0"
+                ));
+            }
+            if let Some(max_tokens) = cfg.max_tokens {
+                if max_tokens == 0 || max_tokens > Self::MAX_TOKENS_LIMIT {
+                    return Err(format!(
+                        "Orchestrator max_tokens for '{role}' must be between 1 and {}",
+                        Self::MAX_TOKENS_LIMIT
+                    ));
+                }
+            }
+            if cfg.model.as_deref().is_some_and(|m| m.trim().is_empty()) {
+                return Err(format!("Orchestrator model for '{role}' must not be empty"));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ApiLimitsConfig {
+    // Applied to every host without an entry in `hosts`
+    #[serde(default)]
+    pub default: ApiLimitConfig,
+    // Keyed by host name, e.g. "www.virustotal.com"
+    #[serde(default)]
+    pub hosts: HashMap<String, ApiLimitConfig>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ApiLimitConfig {
+    #[serde(default)]
+    pub requests_per_minute: Option<u32>,
+    #[serde(default)]
+    pub max_concurrent: Option<u32>,
+    // Resets at midnight UTC
+    #[serde(default)]
+    pub daily_quota: Option<u64>,
+}
+
+impl ApiLimitsConfig {
+    /// Free-tier VirusTotal limits; everything else is unthrottled.
+    pub fn with_defaults() -> Self {
+        let mut hosts = HashMap::new();
+        hosts.insert(
+            "www.virustotal.com".to_string(),
+            ApiLimitConfig {
+                requests_per_minute: Some(4),
+                max_concurrent: Some(1),
+                daily_quota: Some(500),
+            },
+        );
+        Self {
+            default: ApiLimitConfig::default(),
+            hosts,
+        }
+    }
+
+    /// Limits for `host`, falling back to `default`.
+    pub fn limits_for(&self, host: &str) -> &ApiLimitConfig {
+        self.hosts.get(host).unwrap_or(&self.default)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        self.default.validate("default")?;
+        for (host, limits) in &self.hosts {
+            if host.trim().is_empty() || host.contains("://") || host.contains('/') {
+                return Err(format!(
+                    "API limit host '{host}' must be a bare host name without scheme or path"
+                ));
+            }
+            limits.validate(host)?;
+        }
+        Ok(())
+    }
+}
+
+impl ApiLimitConfig {
+    fn validate(&self, host: &str) -> Result<(), String> {
+        if self.requests_per_minute == Some(0) {
+            return Err(format!(
+                "API requests_per_minute for '{host}' must be greater than 0"
+            ));
+        }
+        if self.max_concurrent == Some(0) {
+            return Err(format!(
+                "API max_concurrent for '{host}' must be greater than 0"
+            ));
+        }
+        if self.daily_quota == Some(0) {
+            return Err(format!(
+                "API daily_quota for '{host}' must be greater than 0"
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ProfileConfig {
+    // Profile used when none was chosen with switch_profile
+    #[serde(default)]
+    pub name: Option<String>,
+    // Without a name, derive the profile from the OS user account
+    #[serde(default)]
+    pub per_os_user: bool,
+}
+
+impl ProfileConfig {
+    fn validate(&self) -> Result<(), String> {
+        if let Some(name) = &self.name {
+            crate::profile::normalize_name(name).map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SuggestionsConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    // Minutes between evaluations (default 15)
+    #[serde(default)]
+    pub interval_minutes: Option<u64>,
+    // Days in a row a process must grow before a restart is suggested (default 3)
+    #[serde(default)]
+    pub memory_growth_days: Option<u32>,
+}
+
+impl Default for SuggestionsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_minutes: None,
+            memory_growth_days: None,
+        }
+    }
+}
+
+impl SuggestionsConfig {
+    fn validate(&self) -> Result<(), String> {
+        if self.interval_minutes == Some(0) {
+            return Err("Suggestions interval_minutes must be greater than 0".to_string());
+        }
+        if self
+            .memory_growth_days
+            .is_some_and(|d| !(2..=7).contains(&d))
+        {
+            return Err("Suggestions memory_growth_days must be between 2 and 7".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LlmCacheConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    // Seconds a cached response is reused (default 3600)
+    #[serde(default)]
+    pub ttl_secs: Option<u64>,
+    // Oldest responses are evicted past either limit (defaults 256 entries, 8 MiB)
+    #[serde(default)]
+    pub max_entries: Option<usize>,
+    #[serde(default)]
+    pub max_bytes: Option<usize>,
+    // Only calls at or below this temperature are cached (default 0.2)
+    #[serde(default)]
+    pub max_temperature: Option<f32>,
+}
+
+impl Default for LlmCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ttl_secs: None,
+            max_entries: None,
+            max_bytes: None,
+            max_temperature: None,
+        }
+    }
+}
+
+impl LlmCacheConfig {
+    fn validate(&self) -> Result<(), String> {
+        if self.ttl_secs == Some(0) {
+            return Err("LLM cache ttl_secs must be greater than 0".to_string());
+        }
+        if self.max_entries == Some(0) || self.max_bytes == Some(0) {
+            return Err("LLM cache size limits must be greater than 0".to_string());
+        }
+        if self
+            .max_temperature
+            .is_some_and(|t| !t.is_finite() || !(0.0..=2.0).contains(&t))
+        {
+            return Err("LLM cache max_temperature must be between 0.0 and 2.0".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ContextBudgetConfig {
+    // Tokens of memory context per prompt (default 1500)
+    #[serde(default)]
+    pub default_tokens: Option<usize>,
+    // Overrides by provider name, lowercase (e.g. "ollama": 600, "google ai": 4000)
+    #[serde(default)]
+    pub provider_tokens: HashMap<String, usize>,
+    // Memories below this relevance are never injected (default 0.3)
+    #[serde(default)]
+    pub min_relevance: Option<f32>,
+}
+
+impl ContextBudgetConfig {
+    pub const DEFAULT_TOKENS: usize = 1500;
+    pub const DEFAULT_MIN_RELEVANCE: f32 = 0.3;
+
+    /// Token budget for `provider`, matched case-insensitively.
+    pub fn tokens_for(&self, provider: &str) -> usize {
+        self.provider_tokens
+            .get(&provider.to_ascii_lowercase())
+            .copied()
+            .or(self.default_tokens)
+            .unwrap_or(Self::DEFAULT_TOKENS)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self
+            .min_relevance
+            .is_some_and(|r| !r.is_finite() || !(0.0..=1.0).contains(&r))
+        {
+            return Err("Context budget min_relevance must be between 0.0 and 1.0".to_string());
+        }
+        if let Some(name) = self
+            .provider_tokens
+            .keys()
+            .find(|name| name.trim().is_empty() || **name != name.to_ascii_lowercase())
+        {
+            return Err(format!(
+                "Context budget provider '{name}' must be a lowercase provider name"
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HotkeyConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    // Accelerators such as "CmdOrCtrl+Shift+Space"; absent uses the default
+    // binding and an empty string disables the hotkey
+    #[serde(default)]
+    pub push_to_talk: Option<String>,
+    #[serde(default)]
+    pub screenshot_question: Option<String>,
+    #[serde(default)]
+    pub panic_stop: Option<String>,
+}
+
+impl Default for HotkeyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            push_to_talk: None,
+            screenshot_question: None,
+            panic_stop: None,
+        }
+    }
+}
+
+impl HotkeyConfig {
+    /// Accelerator bound to each enabled action.
+    pub fn bindings(&self) -> Vec<(crate::hotkeys::HotkeyAction, String)> {
+        use crate::hotkeys::HotkeyAction;
+        if !self.enabled {
+            return Vec::new();
+        }
+        HotkeyAction::ALL
+            .iter()
+            .filter_map(|action| {
+                let configured = match action {
+                    HotkeyAction::PushToTalk => &self.push_to_talk,
+                    HotkeyAction::ScreenshotQuestion => &self.screenshot_question,
+                    HotkeyAction::PanicStop => &self.panic_stop,
+                };
+                let accelerator = configured
+                    .as_deref()
+                    .unwrap_or(action.default_accelerator())
+                    .trim();
+                (!accelerator.is_empty()).then(|| (*action, accelerator.to_string()))
+            })
+            .collect()
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        match crate::hotkeys::find_conflicts(&self.bindings()).first() {
+            Some(conflict) => Err(format!(
+                "Hotkey for {} ('{}'): {}",
+                conflict.action, conflict.accelerator, conflict.reason
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Most languages Google STT accepts as alternatives to the primary one
+pub const MAX_DETECT_LANGUAGES: usize = 3;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VoiceConfig {
+    // BCP-47 tag such as "es-ES"; absent follows the message locale
+    #[serde(default)]
+    pub language: Option<String>,
+    // Let speech recognition pick among detect_languages as well
+    #[serde(default = "default_true")]
+    pub auto_detect: bool,
+    // Other languages the user may speak; empty uses the built-in ones
+    #[serde(default)]
+    pub detect_languages: Vec<String>,
+    // TTS voice per language tag, e.g. "es-ES" -> "es-ES-Neural2-A"
+    #[serde(default)]
+    pub voices: HashMap<String, String>,
+}
+
+impl Default for VoiceConfig {
+    fn default() -> Self {
+        Self {
+            language: None,
+            auto_detect: true,
+            detect_languages: Vec::new(),
+            voices: HashMap::new(),
+        }
+    }
+}
+
+impl VoiceConfig {
+    fn validate(&self) -> Result<(), String> {
+        let tags = self
+            .language
+            .iter()
+            .chain(&self.detect_languages)
+            .chain(self.voices.keys());
+        if let Some(tag) = tags.into_iter().find(|tag| !is_language_tag(tag)) {
+            return Err(format!(
+                "Voice language '{tag}' must be a tag such as 'en-US' or 'es'"
+            ));
+        }
+        if self.detect_languages.len() > MAX_DETECT_LANGUAGES {
+            return Err(format!(
+                "At most {MAX_DETECT_LANGUAGES} voice detect_languages are supported"
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A language tag of the form "es" or "es-MX" (also "cmn-Hans-CN").
+pub fn is_language_tag(tag: &str) -> bool {
+    let mut parts = tag.split('-');
+    let language = parts.next().unwrap_or_default();
+    (2..=3).contains(&language.len())
+        && language.chars().all(|c| c.is_ascii_alphabetic())
+        && parts.all(|part| {
+            (2..=8).contains(&part.len()) && part.chars().all(|c| c.is_ascii_alphanumeric())
+        })
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct PromptConfig {
+    // Directory of `<name>.txt` templates, reloaded when they change;
+    // absent uses `data/prompts`
+    #[serde(default)]
+    pub directory: Option<String>,
+    // Inline templates by name; files in `directory` take precedence
+    #[serde(default)]
+    pub templates: HashMap<String, PromptOverride>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PromptOverride {
+    pub text: String,
+    // Absent counts as one past the built-in template's version
+    #[serde(default)]
+    pub version: Option<u32>,
+}
+
+impl PromptConfig {
+    fn validate(&self) -> Result<(), String> {
+        for (name, template) in &self.templates {
+            crate::prompts::validate_name(name)?;
+            if template.text.trim().is_empty() {
+                return Err(format!("Prompt template '{name}' is empty"));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LlmAuditConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    // Days an audit entry is kept (default 90)
+    #[serde(default)]
+    pub retention_days: Option<u32>,
+}
+
+impl Default for LlmAuditConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            retention_days: None,
+        }
+    }
+}
+
+impl LlmAuditConfig {
+    fn validate(&self) -> Result<(), String> {
+        if self.retention_days == Some(0) {
+            return Err("LLM audit retention_days must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    // Stable releases plus pre-releases
+    Beta,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct UpdatesConfig {
+    // Periodic checks; `check_for_updates` works either way
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub channel: UpdateChannel,
+    // URL of the signed release manifest; checks fail while unset
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    // Hours between periodic checks (default 24)
+    #[serde(default)]
+    pub check_interval_hours: Option<u32>,
+}
+
+impl Default for UpdatesConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            channel: UpdateChannel::Stable,
+            endpoint: None,
+            check_interval_hours: None,
+        }
+    }
+}
+
+impl UpdatesConfig {
+    fn validate(&self) -> Result<(), String> {
+        if let Some(endpoint) = &self.endpoint {
+            let url = url::Url::parse(endpoint)
+                .map_err(|e| format!("Update endpoint is not a valid URL: {e}"))?;
+            // Plain http only for a local release server
+            if url.scheme() != "https" && !crate::network::is_local_target(endpoint) {
+                return Err("Update endpoint must use https".to_string());
+            }
+        }
+        if self.check_interval_hours == Some(0) {
+            return Err("Update check_interval_hours must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct CrashReportConfig {
+    // Endpoint receiving redacted reports as JSON; upload is unavailable while unset
+    #[serde(default)]
+    pub upload_url: Option<String>,
+}
+
+impl CrashReportConfig {
+    fn validate(&self) -> Result<(), String> {
+        if let Some(upload_url) = &self.upload_url {
+            let url = url::Url::parse(upload_url)
+                .map_err(|e| format!("Crash report upload_url is not a valid URL: {e}"))?;
+            if url.scheme() != "https" && !crate::network::is_local_target(upload_url) {
+                return Err("Crash report upload_url must use https".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct CleanupConfig {
+    // Days cleaned files stay in the staging area and can be restored (default 7)
+    #[serde(default)]
+    pub undo_retention_days: Option<u64>,
+    // Temp files modified more recently than this many days are kept (default 7)
+    #[serde(default)]
+    pub temp_min_age_days: Option<u64>,
+    // Cleanup item ids never offered, e.g. "cargo_cache" or "chrome_cache:Default"
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+impl CleanupConfig {
+    pub const DEFAULT_UNDO_RETENTION_DAYS: u64 = 7;
+    pub const DEFAULT_TEMP_MIN_AGE_DAYS: u64 = 7;
+
+    pub fn undo_retention_days(&self) -> u64 {
+        self.undo_retention_days
+            .unwrap_or(Self::DEFAULT_UNDO_RETENTION_DAYS)
+    }
+
+    pub fn temp_min_age_days(&self) -> u64 {
+        self.temp_min_age_days
+            .unwrap_or(Self::DEFAULT_TEMP_MIN_AGE_DAYS)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.undo_retention_days() > 365 {
+            return Err("Cleanup undo_retention_days must be at most 365".to_string());
+        }
+        if self.temp_min_age_days == Some(0) {
+            return Err(
+                "Cleanup temp_min_age_days must be at least 1 so files in use are kept".to_string(),
+            );
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct PowerConfig {
+    // Switch to battery saver automatically while on battery power (default true)
+    #[serde(default)]
+    pub battery_aware: Option<bool>,
+    // Battery saver starts at or below this charge while on battery; 100 starts
+    // it as soon as the charger is unplugged (default 100)
+    #[serde(default)]
+    pub activate_below_percent: Option<u8>,
+    // Metrics are collected this many times less often in battery saver (default 4)
+    #[serde(default)]
+    pub metrics_interval_multiplier: Option<u32>,
+    // Postpone Guardian's scheduled baseline and integrity checks (default true)
+    #[serde(default)]
+    pub defer_scheduled_scans: Option<bool>,
+    // Refuse cloud threat consensus runs (default true)
+    #[serde(default)]
+    pub pause_cloud_consensus: Option<bool>,
+}
+
+impl PowerConfig {
+    pub const DEFAULT_ACTIVATE_BELOW_PERCENT: u8 = 100;
+    pub const DEFAULT_METRICS_INTERVAL_MULTIPLIER: u32 = 4;
+
+    pub fn battery_aware(&self) -> bool {
+        self.battery_aware.unwrap_or(true)
+    }
+
+    pub fn activate_below_percent(&self) -> u8 {
+        self.activate_below_percent
+            .unwrap_or(Self::DEFAULT_ACTIVATE_BELOW_PERCENT)
+    }
+
+    pub fn metrics_interval_multiplier(&self) -> u32 {
+        self.metrics_interval_multiplier
+            .unwrap_or(Self::DEFAULT_METRICS_INTERVAL_MULTIPLIER)
+    }
+
+    pub fn defer_scheduled_scans(&self) -> bool {
+        self.defer_scheduled_scans.unwrap_or(true)
+    }
+
+    pub fn pause_cloud_consensus(&self) -> bool {
+        self.pause_cloud_consensus.unwrap_or(true)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if !(1..=100).contains(&self.activate_below_percent()) {
+            return Err("Power activate_below_percent must be between 1 and 100".to_string());
+        }
+        if !(1..=60).contains(&self.metrics_interval_multiplier()) {
+            return Err("Power metrics_interval_multiplier must be between 1 and 60".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct SecurityConfig {
+    // Reject guarded commands called without a session. When absent this is
+    // enforced as soon as any user holds the admin role; false turns it off
+    #[serde(default)]
+    pub enforce_command_permissions: Option<bool>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orchestrator_config_validation() {
+        let mut config = OrchestratorConfig::default();
+        assert!(config.validate().is_ok());
+
+        config.roles.insert(
+            "analyst".to_string(),
+            OrchestratorRoleConfig::new("openai", None, 2.5),
+        );
+        assert!(config.validate().unwrap_err().contains("temperature"));
+
+        config.roles.insert(
+            "analyst".to_string(),
+            OrchestratorRoleConfig::new("claude", None, 0.1),
+        );
+        assert!(config.validate().unwrap_err().contains("Unknown provider"));
+
+        config.roles.remove("analyst");
+        config.roles.remove("coordinator");
+        assert!(config.validate().unwrap_err().contains("coordinator"));
+    }
+
+    #[test]
+    fn voice_config_validation() {
+        let mut config = VoiceConfig {
+            language: Some("es-MX".to_string()),
+            detect_languages: vec!["en-US".to_string(), "cmn-Hans-CN".to_string()],
+            ..VoiceConfig::default()
+        };
+        assert!(config.validate().is_ok());
+
+        config.voices.insert("spanish".to_string(), "x".to_string());
+        assert!(config.validate().unwrap_err().contains("spanish"));
+
+        config.voices.clear();
+        config.detect_languages = vec!["fr".to_string(); 4];
+        assert!(config.validate().unwrap_err().contains("At most"));
+    }
+
+    #[test]
+    fn hotkey_config_bindings() {
+        let mut config = HotkeyConfig {
+            screenshot_question: Some(String::new()),
+            ..HotkeyConfig::default()
+        };
+        let bindings = config.bindings();
+        assert_eq!(bindings.len(), 2);
+        assert!(config.validate().is_ok());
+
+        config.panic_stop = Some("CmdOrCtrl+Shift+Space".to_string());
+        assert!(config.validate().unwrap_err().contains("panic_stop"));
+
+        config.enabled = false;
+        assert!(config.bindings().is_empty());
+    }
+
+    #[test]
+    fn api_limits_config_validation() {
+        let mut config = ApiLimitsConfig::with_defaults();
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            config.limits_for("www.virustotal.com").requests_per_minute,
+            Some(4)
+        );
+        assert_eq!(
+            config.limits_for("api.openai.com"),
+            &ApiLimitConfig::default()
+        );
+
+        config.hosts.insert(
+            "https://api.openai.com".to_string(),
+            ApiLimitConfig::default(),
+        );
+        assert!(config.validate().unwrap_err().contains("bare host"));
+
+        config.hosts.clear();
+        config.default.max_concurrent = Some(0);
+        assert!(config.validate().unwrap_err().contains("max_concurrent"));
+    }
+
+    #[test]
+    fn guardian_config_reports_every_issue() {
+        let mut config: GuardianConfig = serde_json::from_str(
+            r#"{"enabled": true, "monitor_interval_secs": 0, "folder_scan_max_workers": 0,
+                "quarantine_dir": " ", "yara_enabled": true}"#,
+        )
+        .unwrap();
+        let issues = config.issues();
+        let errors: Vec<&str> = issues
+            .iter()
+            .filter(|i| i.is_error())
+            .map(|i| i.field.as_str())
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                "guardian.monitor_interval_secs",
+                "guardian.folder_scan_max_workers",
+                "guardian.quarantine_dir"
+            ]
+        );
+        assert!(issues
+            .iter()
+            .any(|i| !i.is_error() && i.field == "guardian.yara_rules_paths"));
+
+        // Warnings alone do not fail validation
+        config.monitor_interval_secs = 5;
+        config.folder_scan_max_workers = Some(4);
+        config.quarantine_dir = None;
+        let issues = config.issues();
+        assert!(issues.iter().all(|i| !i.is_error()));
+        assert!(issues.iter().any(|i| i.field == "guardian.quarantine_dir"));
+    }
+
+    #[test]
+    fn network_config_validation() {
+        let mut config = NetworkConfig {
+            proxy_url: Some("socks5h://proxy.corp:1080".to_string()),
+            proxy_username: Some("alice".to_string()),
+            ..NetworkConfig::default()
+        };
+        assert!(config.validate().is_ok());
+
+        config.proxy_url = Some("ftp://proxy.corp".to_string());
+        assert!(config.validate().unwrap_err().contains("socks5h://"));
+
+        config.proxy_url = None;
+        assert!(config.validate().unwrap_err().contains("proxy_url"));
+
+        config.proxy_username = None;
+        config.ca_cert_paths = vec![" ".to_string()];
+        assert!(config.validate().unwrap_err().contains("CA certificate"));
+    }
+}
@@ -0,0 +1,385 @@
+//! Desktop context for copilot prompts: the foreground window and, when the
+//! user has opted in via `copilot.share_clipboard`, the clipboard text.
+//!
+//! Windows uses Win32 calls; Linux shells out to `xdotool` and `wl-paste` or
+//! `xclip`; macOS uses `osascript` and `pbpaste`. Anything that cannot be
+//! read is left out rather than reported as an error.
+
+use crate::config::CopilotConfig;
+use crate::i18n;
+use crate::privacy::PrivacyFilter;
+use crate::types::Context;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sysinfo::{Pid, System};
+
+/// Clipboard text beyond this many characters is cut off.
+pub const MAX_CLIPBOARD_CHARS: usize = 2000;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ActiveWindow {
+    pub title: String,
+    pub process_name: Option<String>,
+    pub pid: Option<u32>,
+}
+
+impl ActiveWindow {
+    /// `"<title> (<process>)"`, the form stored in [`Context::active_window`].
+    pub fn describe(&self) -> String {
+        match &self.process_name {
+            Some(name) if self.title.is_empty() => name.clone(),
+            Some(name) => format!("{} ({name})", self.title),
+            None => self.title.clone(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct DesktopContext {
+    pub active_window: Option<ActiveWindow>,
+    pub clipboard_text: Option<String>,
+}
+
+impl DesktopContext {
+    /// Read whatever `config` allows. Blocks on helper processes, so async
+    /// callers should use [`DesktopContext::collect_async`].
+    pub fn collect(config: &CopilotConfig) -> Self {
+        let active_window = if config.share_active_window {
+            platform::active_window().map(|mut window| {
+                if window.process_name.is_none() {
+                    window.process_name = window.pid.and_then(process_name);
+                }
+                window
+            })
+        } else {
+            None
+        };
+        let clipboard_text = if config.share_clipboard {
+            platform::clipboard_text().and_then(|text| truncate_clipboard(&text))
+        } else {
+            None
+        };
+        debug!(
+            "Desktop context: window={}, clipboard={} chars",
+            active_window.is_some(),
+            clipboard_text.as_ref().map_or(0, |t| t.chars().count())
+        );
+        Self {
+            active_window,
+            clipboard_text,
+        }
+    }
+
+    pub async fn collect_async(config: CopilotConfig) -> Self {
+        tokio::task::spawn_blocking(move || Self::collect(&config))
+            .await
+            .unwrap_or_default()
+    }
+
+    /// Pseudonymize the window title and clipboard before they reach a cloud
+    /// model; the caller restores the response with the same filter.
+    pub fn redact(&mut self, privacy: &PrivacyFilter) {
+        if let Some(window) = &mut self.active_window {
+            window.title = privacy.redact_text(&window.title);
+        }
+        if let Some(text) = &mut self.clipboard_text {
+            *text = privacy.redact_text(text);
+        }
+    }
+
+    pub fn into_context(self, system_status: Option<Value>) -> Context {
+        Context {
+            active_window: self.active_window.as_ref().map(ActiveWindow::describe),
+            clipboard_text: self.clipboard_text,
+            system_status,
+            recent_events: Vec::new(),
+            memory_context: None,
+        }
+    }
+}
+
+/// Prefix `prompt` with the desktop and memory context recorded in
+/// `context`, and ask for a reply in the active locale when it is not
+/// English.
+pub fn prompt_with_context(prompt: &str, context: &Context) -> String {
+    let mut preamble = String::new();
+    if let Some(window) = &context.active_window {
+        preamble.push_str(&format!("Active window: {window}\n"));
+    }
+    if let Some(text) = &context.clipboard_text {
+        preamble.push_str(&format!(
+            "Clipboard contents (shared by the user):\n\"\"\"\n{text}\n\"\"\"\n"
+        ));
+    }
+    if let Some(memories) = context.memory_context.as_ref().filter(|m| !m.is_empty()) {
+        preamble.push_str(&format!(
+            "Relevant memories (source, date recorded):\n{memories}\n"
+        ));
+    }
+    let prompt = i18n::with_reply_directive(prompt);
+    if preamble.is_empty() {
+        prompt
+    } else {
+        format!("[Desktop context]\n{preamble}\n[User request]\n{prompt}")
+    }
+}
+
+fn truncate_clipboard(text: &str) -> Option<String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    match text.char_indices().nth(MAX_CLIPBOARD_CHARS) {
+        Some((end, _)) => Some(format!("{}… [truncated]", &text[..end])),
+        None => Some(text.to_string()),
+    }
+}
+
+fn process_name(pid: u32) -> Option<String> {
+    let pid = Pid::from_u32(pid);
+    let mut sys = System::new();
+    if !sys.refresh_process(pid) {
+        return None;
+    }
+    sys.process(pid).map(|p| p.name().to_string())
+}
+
+/// Trimmed stdout of a helper tool, or `None` if it is missing, fails, prints
+/// nothing or does not finish within a second (e.g. a stuck clipboard owner).
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    use std::io::Read;
+    use std::process::{Command, Stdio};
+    use std::time::{Duration, Instant};
+
+    const TIMEOUT: Duration = Duration::from_secs(1);
+
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    let started = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if started.elapsed() < TIMEOUT => {
+                std::thread::sleep(Duration::from_millis(10))
+            }
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                debug!("{program} did not answer in time");
+                return None;
+            }
+        }
+    };
+    if !status.success() {
+        return None;
+    }
+    let mut output = String::new();
+    child.stdout.take()?.read_to_string(&mut output).ok()?;
+    let output = output.trim_end_matches(['\r', '\n']).to_string();
+    (!output.trim().is_empty()).then_some(output)
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{command_output, ActiveWindow};
+
+    pub fn active_window() -> Option<ActiveWindow> {
+        let title = command_output("xdotool", &["getactivewindow", "getwindowname"])?;
+        let pid = command_output("xdotool", &["getactivewindow", "getwindowpid"])
+            .and_then(|pid| pid.trim().parse().ok());
+        Some(ActiveWindow {
+            title,
+            process_name: None,
+            pid,
+        })
+    }
+
+    pub fn clipboard_text() -> Option<String> {
+        if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            if let Some(text) = command_output("wl-paste", &["--no-newline", "--type", "text"]) {
+                return Some(text);
+            }
+        }
+        command_output("xclip", &["-selection", "clipboard", "-o"])
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{command_output, ActiveWindow};
+
+    const FRONTMOST_SCRIPT: &str = r#"tell application "System Events"
+    set proc to first application process whose frontmost is true
+    set winTitle to ""
+    try
+        set winTitle to name of front window of proc
+    end try
+    return (name of proc) & linefeed & (unix id of proc) & linefeed & winTitle
+end tell"#;
+
+    pub fn active_window() -> Option<ActiveWindow> {
+        let output = command_output("osascript", &["-e", FRONTMOST_SCRIPT])?;
+        let mut lines = output.lines();
+        let process_name = lines.next().map(str::to_string);
+        let pid = lines.next().and_then(|pid| pid.trim().parse().ok());
+        let title = lines.next().unwrap_or_default().to_string();
+        Some(ActiveWindow {
+            title,
+            process_name,
+            pid,
+        })
+    }
+
+    pub fn clipboard_text() -> Option<String> {
+        command_output("pbpaste", &[])
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::ActiveWindow;
+    use std::ptr;
+    use winapi::um::winbase::{GlobalLock, GlobalUnlock};
+    use winapi::um::winuser::{
+        CloseClipboard, GetClipboardData, GetForegroundWindow, GetWindowTextLengthW,
+        GetWindowTextW, GetWindowThreadProcessId, OpenClipboard, CF_UNICODETEXT,
+    };
+
+    pub fn active_window() -> Option<ActiveWindow> {
+        unsafe {
+            let hwnd = GetForegroundWindow();
+            if hwnd.is_null() {
+                return None;
+            }
+            let len = GetWindowTextLengthW(hwnd).max(0) as usize;
+            let mut buf = vec![0u16; len + 1];
+            let copied = GetWindowTextW(hwnd, buf.as_mut_ptr(), buf.len() as i32).max(0) as usize;
+            let mut pid = 0u32;
+            GetWindowThreadProcessId(hwnd, &mut pid);
+            Some(ActiveWindow {
+                title: String::from_utf16_lossy(&buf[..copied]),
+                process_name: None,
+                pid: (pid != 0).then_some(pid),
+            })
+        }
+    }
+
+    pub fn clipboard_text() -> Option<String> {
+        unsafe {
+            if OpenClipboard(ptr::null_mut()) == 0 {
+                return None;
+            }
+            let handle = GetClipboardData(CF_UNICODETEXT);
+            let data = if handle.is_null() {
+                ptr::null_mut()
+            } else {
+                GlobalLock(handle) as *const u16
+            };
+            let text = if data.is_null() {
+                None
+            } else {
+                let mut len = 0;
+                while *data.add(len) != 0 {
+                    len += 1;
+                }
+                let text = String::from_utf16_lossy(std::slice::from_raw_parts(data, len));
+                GlobalUnlock(handle);
+                Some(text)
+            };
+            CloseClipboard();
+            text
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+mod platform {
+    use super::ActiveWindow;
+
+    pub fn active_window() -> Option<ActiveWindow> {
+        None
+    }
+
+    pub fn clipboard_text() -> Option<String> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::PrivacyConfig;
+
+    fn desktop() -> DesktopContext {
+        DesktopContext {
+            active_window: Some(ActiveWindow {
+                title: "report.xlsx - Excel".to_string(),
+                process_name: Some("EXCEL.EXE".to_string()),
+                pid: Some(4242),
+            }),
+            clipboard_text: Some("=SUM(A1:A9)".to_string()),
+        }
+    }
+
+    #[test]
+    fn prompt_includes_window_and_clipboard() {
+        let context = desktop().into_context(None);
+        assert_eq!(
+            context.active_window.as_deref(),
+            Some("report.xlsx - Excel (EXCEL.EXE)")
+        );
+
+        let prompt = prompt_with_context("Why is this formula wrong?", &context);
+        assert!(prompt.contains("Active window: report.xlsx - Excel (EXCEL.EXE)"));
+        assert!(prompt.contains("=SUM(A1:A9)"));
+        assert!(prompt.ends_with("[User request]\nWhy is this formula wrong?"));
+
+        let empty = DesktopContext::default().into_context(None);
+        assert_eq!(prompt_with_context("hello", &empty), "hello");
+
+        let mut with_memory = DesktopContext::default().into_context(None);
+        with_memory.memory_context = Some("- [1] (threat_report, 2024-05-01) ...".to_string());
+        assert!(prompt_with_context("hello", &with_memory)
+            .contains("Relevant memories (source, date recorded):\n- [1] (threat_report"));
+    }
+
+    #[test]
+    fn clipboard_is_trimmed_and_truncated() {
+        assert_eq!(truncate_clipboard("  \n "), None);
+        assert_eq!(truncate_clipboard(" text \n").as_deref(), Some("text"));
+
+        let long = "é".repeat(MAX_CLIPBOARD_CHARS + 10);
+        let truncated = truncate_clipboard(&long).unwrap();
+        assert!(truncated.ends_with("… [truncated]"));
+        assert_eq!(
+            truncated.chars().filter(|c| *c == 'é').count(),
+            MAX_CLIPBOARD_CHARS
+        );
+    }
+
+    #[test]
+    fn redaction_covers_title_and_clipboard() {
+        let privacy = PrivacyFilter::new(PrivacyConfig::default());
+        let mut context = DesktopContext {
+            active_window: Some(ActiveWindow {
+                title: "C:\\Users\\alice\\notes.txt - Notepad".to_string(),
+                process_name: Some("notepad.exe".to_string()),
+                pid: None,
+            }),
+            clipboard_text: Some("ping 10.1.2.3".to_string()),
+        };
+        context.redact(&privacy);
+
+        let window = context.active_window.unwrap();
+        assert!(!window.title.contains("alice"));
+        assert_eq!(window.process_name.as_deref(), Some("notepad.exe"));
+        assert!(!context.clipboard_text.unwrap().contains("10.1.2.3"));
+    }
+}
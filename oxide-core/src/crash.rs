@@ -0,0 +1,406 @@
+//! Crash reports for Oxide Pilot's own panics and native crashes.
+//!
+//! [`install`] chains a panic hook that writes a [`CrashReport`] — message,
+//! location, backtrace, the newest entries of the log ring buffer and basic
+//! system information — as JSON into the crash directory. On Windows an
+//! unhandled-exception filter also catches native crashes such as access
+//! violations, and both paths write a minidump next to the report.
+//!
+//! Reports stay on disk. The app imports them as incidents and may upload
+//! one on request, redacted by [`redacted`]. Nothing is sent automatically.
+
+use crate::config::PrivacyConfig;
+use crate::logging::{self, LogEntry};
+use crate::privacy::PrivacyFilter;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::backtrace::Backtrace;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+pub const CRASH_DIR: &str = "./data/crashes";
+/// Reports kept on disk; older ones are removed when a new one is written.
+pub const MAX_REPORTS: usize = 20;
+/// Log ring buffer entries copied into a report.
+const LOG_TAIL: usize = 200;
+
+static DIR: OnceLock<PathBuf> = OnceLock::new();
+static SINK: Mutex<Option<UnboundedSender<CrashReport>>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CrashKind {
+    Panic,
+    /// Unhandled native exception; the process was terminating
+    Native,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemInfo {
+    pub os: String,
+    pub os_version: Option<String>,
+    pub arch: String,
+    pub host_name: Option<String>,
+    pub cpu_count: usize,
+    pub total_memory: u64,
+    pub uptime_secs: u64,
+    pub pid: u32,
+}
+
+impl SystemInfo {
+    fn capture() -> Self {
+        let mut system = sysinfo::System::new();
+        system.refresh_memory();
+        Self {
+            os: sysinfo::System::name().unwrap_or_else(|| std::env::consts::OS.to_string()),
+            os_version: sysinfo::System::long_os_version(),
+            arch: std::env::consts::ARCH.to_string(),
+            host_name: sysinfo::System::host_name(),
+            cpu_count: std::thread::available_parallelism().map_or(1, |n| n.get()),
+            total_memory: system.total_memory(),
+            uptime_secs: sysinfo::System::uptime(),
+            pid: std::process::id(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrashReport {
+    pub id: String,
+    pub timestamp: DateTime<Utc>,
+    pub version: String,
+    pub kind: CrashKind,
+    pub message: String,
+    /// `file:line:column` of a panic
+    pub location: Option<String>,
+    pub thread: Option<String>,
+    /// Exception code of a native crash, e.g. `0xC0000005`
+    pub error_code: Option<String>,
+    pub backtrace: String,
+    /// Newest first
+    pub recent_logs: Vec<LogEntry>,
+    pub system: SystemInfo,
+    /// Minidump file written alongside, Windows only
+    pub minidump: Option<String>,
+    /// Imported into the incident table
+    #[serde(default)]
+    pub recorded: bool,
+    #[serde(default)]
+    pub uploaded_at: Option<DateTime<Utc>>,
+}
+
+/// Summary shown in the crash list.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrashSummary {
+    pub id: String,
+    pub timestamp: DateTime<Utc>,
+    pub kind: CrashKind,
+    pub message: String,
+    pub uploaded_at: Option<DateTime<Utc>>,
+}
+
+impl CrashReport {
+    fn new(kind: CrashKind, message: String) -> Self {
+        let thread = std::thread::current();
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            timestamp: Utc::now(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            kind,
+            message,
+            location: None,
+            thread: Some(thread.name().unwrap_or("unnamed").to_string()),
+            error_code: None,
+            backtrace: Backtrace::force_capture().to_string(),
+            recent_logs: logging::logger()
+                .map(|logger| logger.recent(None, None, LOG_TAIL))
+                .unwrap_or_default(),
+            system: SystemInfo::capture(),
+            minidump: None,
+            recorded: false,
+            uploaded_at: None,
+        }
+    }
+
+    fn from_panic(info: &std::panic::PanicHookInfo<'_>) -> Self {
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Box<dyn Any>".to_string());
+        let mut report = Self::new(CrashKind::Panic, message);
+        report.location = info
+            .location()
+            .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()));
+        report
+    }
+
+    pub fn summary(&self) -> CrashSummary {
+        CrashSummary {
+            id: self.id.clone(),
+            timestamp: self.timestamp,
+            kind: self.kind,
+            message: self.message.clone(),
+            uploaded_at: self.uploaded_at,
+        }
+    }
+
+    fn file_name(id: &str) -> String {
+        format!("crash-{id}.json")
+    }
+
+    /// Write the report into `dir`.
+    pub fn save(&self, dir: &Path) -> Result<PathBuf, String> {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {dir:?}: {e}"))?;
+        let path = dir.join(Self::file_name(&self.id));
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(&path, json).map_err(|e| format!("Failed to write {path:?}: {e}"))?;
+        Ok(path)
+    }
+}
+
+/// Install the panic hook (and on Windows the native crash handler) writing
+/// reports into `dir`. The previous panic hook still runs afterwards.
+pub fn install(dir: impl Into<PathBuf>) {
+    if DIR.set(dir.into()).is_err() {
+        return;
+    }
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let mut report = CrashReport::from_panic(info);
+        write_report(&mut report, std::ptr::null_mut());
+        previous(info);
+    }));
+    #[cfg(windows)]
+    native::install();
+}
+
+/// Reports written while the app runs, for importing them right away.
+pub fn subscribe() -> UnboundedReceiver<CrashReport> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    *SINK.lock().unwrap_or_else(|p| p.into_inner()) = Some(tx);
+    rx
+}
+
+/// Directory [`install`] writes to.
+pub fn dir() -> PathBuf {
+    DIR.get()
+        .cloned()
+        .unwrap_or_else(|| PathBuf::from(CRASH_DIR))
+}
+
+/// `exception` points at the native exception record, null for panics.
+fn write_report(report: &mut CrashReport, exception: *mut std::ffi::c_void) {
+    let dir = dir();
+    #[cfg(windows)]
+    {
+        report.minidump = native::write_minidump(&dir, &report.id, exception);
+    }
+    #[cfg(not(windows))]
+    let _ = exception;
+    match report.save(&dir) {
+        Ok(path) => {
+            log::error!("Crash report written to {path:?}: {}", report.message);
+            prune(&dir, MAX_REPORTS);
+        }
+        Err(e) => eprintln!("Failed to write crash report: {e}"),
+    }
+    if let Some(tx) = SINK.lock().unwrap_or_else(|p| p.into_inner()).as_ref() {
+        let _ = tx.send(report.clone());
+    }
+}
+
+/// Stored reports, newest first.
+pub fn list(dir: &Path) -> Vec<CrashReport> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut reports: Vec<CrashReport> = entries
+        .flatten()
+        .filter(|e| e.file_name().to_string_lossy().starts_with("crash-"))
+        .filter_map(|e| fs::read_to_string(e.path()).ok())
+        .filter_map(|json| serde_json::from_str(&json).ok())
+        .collect();
+    reports.sort_by_key(|r| std::cmp::Reverse(r.timestamp));
+    reports
+}
+
+pub fn load(dir: &Path, id: &str) -> Result<CrashReport, String> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_hexdigit() || c == '-') {
+        return Err(format!("Invalid crash report id '{id}'"));
+    }
+    let path = dir.join(CrashReport::file_name(id));
+    let json = fs::read_to_string(&path).map_err(|_| format!("Crash report '{id}' not found"))?;
+    serde_json::from_str(&json).map_err(|e| format!("Crash report '{id}' is unreadable: {e}"))
+}
+
+/// Remove all but the `keep` newest reports and their minidumps.
+fn prune(dir: &Path, keep: usize) {
+    for report in list(dir).into_iter().skip(keep) {
+        let _ = fs::remove_file(dir.join(CrashReport::file_name(&report.id)));
+        if let Some(minidump) = &report.minidump {
+            let _ = fs::remove_file(minidump);
+        }
+    }
+}
+
+/// Copy of `report` fit for leaving the machine: user names, host names,
+/// IP addresses and home-directory paths are pseudonymized whether or not
+/// privacy mode is on, and the minidump (raw process memory) is dropped.
+pub fn redacted(report: &CrashReport, privacy: &PrivacyConfig) -> serde_json::Value {
+    let filter = PrivacyFilter::new(PrivacyConfig {
+        enabled: true,
+        ..privacy.clone()
+    });
+    let mut value = serde_json::to_value(CrashReport {
+        minidump: None,
+        ..report.clone()
+    })
+    .unwrap_or_default();
+    filter.redact_value(&mut value);
+    value
+}
+
+#[cfg(windows)]
+mod native {
+    use super::{write_report, CrashKind, CrashReport};
+    use std::fs::File;
+    use std::os::windows::io::AsRawHandle;
+    use std::path::Path;
+    use winapi::shared::minwindef::{BOOL, DWORD};
+    use winapi::shared::ntdef::LONG;
+    use winapi::um::errhandlingapi::SetUnhandledExceptionFilter;
+    use winapi::um::processthreadsapi::{
+        GetCurrentProcess, GetCurrentProcessId, GetCurrentThreadId,
+    };
+    use winapi::um::winnt::{EXCEPTION_POINTERS, HANDLE};
+
+    /// Let the default handler (Windows Error Reporting) run afterwards
+    const EXCEPTION_CONTINUE_SEARCH: LONG = 0;
+    /// `MiniDumpWithThreadInfo` from dbghelp.h
+    const MINIDUMP_WITH_THREAD_INFO: DWORD = 0x1000;
+
+    /// dbghelp.h declares this under `#pragma pack(4)`
+    #[repr(C, packed(4))]
+    struct MinidumpExceptionInformation {
+        thread_id: DWORD,
+        exception_pointers: *mut EXCEPTION_POINTERS,
+        client_pointers: BOOL,
+    }
+
+    // winapi 0.3 has no dbghelp minidump bindings
+    #[link(name = "dbghelp")]
+    extern "system" {
+        fn MiniDumpWriteDump(
+            process: HANDLE,
+            process_id: DWORD,
+            file: HANDLE,
+            dump_type: DWORD,
+            exception_param: *mut MinidumpExceptionInformation,
+            user_stream_param: *mut std::ffi::c_void,
+            callback_param: *mut std::ffi::c_void,
+        ) -> BOOL;
+    }
+
+    pub fn install() {
+        unsafe {
+            SetUnhandledExceptionFilter(Some(on_exception));
+        }
+    }
+
+    unsafe extern "system" fn on_exception(pointers: *mut EXCEPTION_POINTERS) -> LONG {
+        let code = pointers
+            .as_ref()
+            .and_then(|p| p.ExceptionRecord.as_ref())
+            .map(|record| record.ExceptionCode);
+        let mut report = CrashReport::new(
+            CrashKind::Native,
+            format!(
+                "Unhandled exception {}",
+                code.map_or("(unknown)".to_string(), |c| format!("0x{c:08X}"))
+            ),
+        );
+        report.error_code = code.map(|c| format!("0x{c:08X}"));
+        write_report(&mut report, pointers.cast());
+        EXCEPTION_CONTINUE_SEARCH
+    }
+
+    /// Dump of this process; `exception` is an `EXCEPTION_POINTERS` or null.
+    pub fn write_minidump(
+        dir: &Path,
+        id: &str,
+        exception: *mut std::ffi::c_void,
+    ) -> Option<String> {
+        std::fs::create_dir_all(dir).ok()?;
+        let path = dir.join(format!("crash-{id}.dmp"));
+        let file = File::create(&path).ok()?;
+        let mut info = MinidumpExceptionInformation {
+            thread_id: unsafe { GetCurrentThreadId() },
+            exception_pointers: exception.cast(),
+            client_pointers: 0,
+        };
+        let written = unsafe {
+            MiniDumpWriteDump(
+                GetCurrentProcess(),
+                GetCurrentProcessId(),
+                file.as_raw_handle() as HANDLE,
+                MINIDUMP_WITH_THREAD_INFO,
+                if exception.is_null() {
+                    std::ptr::null_mut()
+                } else {
+                    &mut info
+                },
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            )
+        };
+        if written == 0 {
+            drop(file);
+            let _ = std::fs::remove_file(&path);
+            return None;
+        }
+        Some(path.to_string_lossy().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_round_trip_and_redact() {
+        let dir = std::env::temp_dir().join(format!("oxide-crash-{}", uuid::Uuid::new_v4()));
+        let mut report = CrashReport::new(CrashKind::Panic, "index out of bounds".to_string());
+        report.location = Some("/home/alice/oxide/src/main.rs:10:5".to_string());
+        report.system.host_name = Some("alice-laptop".to_string());
+        report.minidump = Some("crash.dmp".to_string());
+        report.save(&dir).unwrap();
+
+        let loaded = load(&dir, &report.id).unwrap();
+        assert_eq!(loaded.message, "index out of bounds");
+        assert!(!loaded.backtrace.is_empty());
+        assert_eq!(list(&dir).len(), 1);
+        assert!(load(&dir, "../secrets").is_err());
+
+        let privacy = PrivacyConfig {
+            enabled: false,
+            extra_terms: vec!["alice-laptop".to_string()],
+            ..Default::default()
+        };
+        let upload = redacted(&loaded, &privacy).to_string();
+        assert!(!upload.contains("alice"));
+        assert!(upload.contains("\"minidump\":null"));
+
+        for _ in 0..3 {
+            CrashReport::new(CrashKind::Panic, "again".to_string())
+                .save(&dir)
+                .unwrap();
+        }
+        prune(&dir, 2);
+        assert_eq!(list(&dir).len(), 2);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
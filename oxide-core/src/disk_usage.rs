@@ -0,0 +1,447 @@
+//! Disk usage analysis: where the space under a root goes.
+//!
+//! [`analyze`] walks the tree once, without following symlinks, and builds a
+//! size-ordered directory tree for treemap views, the directories and files
+//! holding the most data, and the stale temp files and old downloads that
+//! can be cleaned up. The cleanup candidates are also returned as
+//! [`Optimization`]s so the resource optimizer can offer them.
+
+use crate::performance::{stale_files, Optimization, OptimizationAction};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+const MB: f64 = 1024.0 * 1024.0;
+
+/// Limits and thresholds for one [`analyze`] run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskUsageOptions {
+    /// Levels of the directory tree kept in the report below the root
+    pub tree_depth: usize,
+    /// Largest subdirectories kept per tree node; the rest count as `other_bytes`
+    pub tree_children: usize,
+    /// Entries in the top directory, largest file and cleanup lists
+    pub top_entries: usize,
+    /// Files at least this large are suggested for review
+    pub large_file_mb: u64,
+    /// Downloads untouched for this many days count as old
+    pub old_download_days: u64,
+    /// Temp files untouched for this many days count as stale
+    pub temp_file_max_age_days: u64,
+    /// Stop walking after this many entries and mark the report truncated
+    pub max_entries: u64,
+}
+
+impl Default for DiskUsageOptions {
+    fn default() -> Self {
+        Self {
+            tree_depth: 3,
+            tree_children: 12,
+            top_entries: 20,
+            large_file_mb: 1024,
+            old_download_days: 90,
+            temp_file_max_age_days: 7,
+            max_entries: 5_000_000,
+        }
+    }
+}
+
+/// One directory in the treemap, with its largest subdirectories.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirNode {
+    pub name: String,
+    pub path: PathBuf,
+    /// Bytes of all files below this directory
+    pub bytes: u64,
+    pub files: u64,
+    /// Bytes not covered by `children`: files directly in this directory and
+    /// subdirectories left out of the tree
+    pub other_bytes: u64,
+    pub children: Vec<DirNode>,
+}
+
+/// A directory and the files stored directly in it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirUsage {
+    pub path: PathBuf,
+    pub bytes: u64,
+    pub files: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileUsage {
+    pub path: PathBuf,
+    pub bytes: u64,
+    pub modified: Option<DateTime<Utc>>,
+}
+
+/// Files in one directory untouched for longer than `older_than_days`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleanupCandidates {
+    pub dir: PathBuf,
+    pub older_than_days: u64,
+    pub files: u64,
+    pub bytes: u64,
+    /// The largest of the files
+    pub largest: Vec<FileUsage>,
+}
+
+/// Capacity of the volume holding the analyzed root.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeUsage {
+    pub mount_point: PathBuf,
+    pub total_bytes: u64,
+    pub available_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskUsageReport {
+    pub root: PathBuf,
+    pub scanned_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    pub volume: Option<VolumeUsage>,
+    pub total_bytes: u64,
+    pub files: u64,
+    pub directories: u64,
+    /// Entries that could not be read
+    pub skipped: u64,
+    /// The walk stopped at `max_entries`; sizes are lower bounds
+    pub truncated: bool,
+    pub tree: DirNode,
+    /// Directories holding the most data directly, largest first
+    pub top_directories: Vec<DirUsage>,
+    pub largest_files: Vec<FileUsage>,
+    pub stale_temp_files: Option<CleanupCandidates>,
+    pub old_downloads: Option<CleanupCandidates>,
+    /// Cleanup the resource optimizer can offer, largest first
+    pub suggestions: Vec<Optimization>,
+}
+
+/// Analyze the space used under `root`. Walks the whole tree; call from a
+/// blocking task.
+pub fn analyze(root: &Path, options: &DiskUsageOptions) -> Result<DiskUsageReport, String> {
+    let meta =
+        std::fs::metadata(root).map_err(|e| format!("Cannot read {}: {e}", root.display()))?;
+    if !meta.is_dir() {
+        return Err(format!("{} is not a directory", root.display()));
+    }
+
+    let started = Instant::now();
+    let now = SystemTime::now();
+    let mut walker = Walker {
+        options,
+        entries: 0,
+        directories: 0,
+        skipped: 0,
+        truncated: false,
+        largest_files: BinaryHeap::new(),
+        top_directories: BinaryHeap::new(),
+    };
+    let tree = walker.walk(root, 0);
+
+    let stale_temp_files = cleanup_candidates(
+        &std::env::temp_dir(),
+        options.temp_file_max_age_days,
+        options.top_entries,
+        now,
+    );
+    let old_downloads = downloads_dir().and_then(|dir| {
+        cleanup_candidates(&dir, options.old_download_days, options.top_entries, now)
+    });
+
+    let mut report = DiskUsageReport {
+        root: root.to_path_buf(),
+        scanned_at: Utc::now(),
+        duration_ms: started.elapsed().as_millis() as u64,
+        volume: volume_of(root),
+        total_bytes: tree.bytes,
+        files: tree.files,
+        directories: walker.directories,
+        skipped: walker.skipped,
+        truncated: walker.truncated,
+        tree,
+        top_directories: walker
+            .top_directories
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((bytes, files, path))| DirUsage { path, bytes, files })
+            .collect(),
+        largest_files: walker
+            .largest_files
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((bytes, path, modified))| FileUsage {
+                path,
+                bytes,
+                modified: modified.map(DateTime::<Utc>::from),
+            })
+            .collect(),
+        stale_temp_files,
+        old_downloads,
+        suggestions: Vec::new(),
+    };
+    report.suggestions = cleanup_suggestions(&report, options);
+    Ok(report)
+}
+
+/// Cleanup for the stale temp files, old downloads and large files in `report`.
+pub fn cleanup_suggestions(
+    report: &DiskUsageReport,
+    options: &DiskUsageOptions,
+) -> Vec<Optimization> {
+    let mut suggestions = Vec::new();
+    if let Some(temp) = &report.stale_temp_files {
+        suggestions.push(Optimization {
+            action: OptimizationAction::ClearTempFiles {
+                dir: temp.dir.clone(),
+                older_than_days: temp.older_than_days,
+            },
+            target: temp.dir.display().to_string(),
+            reason: format!(
+                "{} temp files untouched for over {} days",
+                temp.files, temp.older_than_days
+            ),
+            reclaimable_mb: temp.bytes as f64 / MB,
+        });
+    }
+    if let Some(downloads) = &report.old_downloads {
+        suggestions.push(Optimization {
+            action: OptimizationAction::ReviewOldDownloads {
+                dir: downloads.dir.clone(),
+                older_than_days: downloads.older_than_days,
+            },
+            target: downloads.dir.display().to_string(),
+            reason: format!(
+                "{} downloads untouched for over {} days",
+                downloads.files, downloads.older_than_days
+            ),
+            reclaimable_mb: downloads.bytes as f64 / MB,
+        });
+    }
+    let large_file_bytes = options.large_file_mb * 1024 * 1024;
+    suggestions.extend(
+        report
+            .largest_files
+            .iter()
+            .filter(|f| f.bytes >= large_file_bytes)
+            .map(|f| Optimization {
+                action: OptimizationAction::ReviewLargeFile {
+                    path: f.path.clone(),
+                },
+                target: f.path.display().to_string(),
+                reason: match f.modified {
+                    Some(modified) => format!(
+                        "{:.0} MB, last modified {}",
+                        f.bytes as f64 / MB,
+                        modified.format("%Y-%m-%d")
+                    ),
+                    None => format!("{:.0} MB", f.bytes as f64 / MB),
+                },
+                reclaimable_mb: f.bytes as f64 / MB,
+            }),
+    );
+    suggestions.sort_by(|a, b| b.reclaimable_mb.total_cmp(&a.reclaimable_mb));
+    suggestions
+}
+
+struct Walker<'a> {
+    options: &'a DiskUsageOptions,
+    entries: u64,
+    directories: u64,
+    skipped: u64,
+    truncated: bool,
+    // Min-heaps holding the `top_entries` largest seen so far
+    largest_files: BinaryHeap<Reverse<(u64, PathBuf, Option<SystemTime>)>>,
+    top_directories: BinaryHeap<Reverse<(u64, u64, PathBuf)>>,
+}
+
+impl Walker<'_> {
+    fn walk(&mut self, dir: &Path, depth: usize) -> DirNode {
+        self.directories += 1;
+        let mut node = DirNode {
+            name: dir
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| dir.display().to_string()),
+            path: dir.to_path_buf(),
+            bytes: 0,
+            files: 0,
+            other_bytes: 0,
+            children: Vec::new(),
+        };
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            self.skipped += 1;
+            return node;
+        };
+
+        let (mut own_bytes, mut own_files) = (0u64, 0u64);
+        for entry in entries {
+            if self.entries >= self.options.max_entries {
+                self.truncated = true;
+                break;
+            }
+            self.entries += 1;
+            let Ok(entry) = entry else {
+                self.skipped += 1;
+                continue;
+            };
+            let Ok(file_type) = entry.file_type() else {
+                self.skipped += 1;
+                continue;
+            };
+            if file_type.is_dir() {
+                let child = self.walk(&entry.path(), depth + 1);
+                node.bytes += child.bytes;
+                node.files += child.files;
+                node.children.push(child);
+            } else if file_type.is_file() {
+                let Ok(meta) = entry.metadata() else {
+                    self.skipped += 1;
+                    continue;
+                };
+                own_bytes += meta.len();
+                own_files += 1;
+                push_top(
+                    &mut self.largest_files,
+                    (meta.len(), entry.path(), meta.modified().ok()),
+                    self.options.top_entries,
+                );
+            }
+        }
+        node.bytes += own_bytes;
+        node.files += own_files;
+        if own_bytes > 0 {
+            push_top(
+                &mut self.top_directories,
+                (own_bytes, own_files, dir.to_path_buf()),
+                self.options.top_entries,
+            );
+        }
+
+        // Only the largest subdirectories stay in the tree
+        let keep = if depth < self.options.tree_depth {
+            self.options.tree_children
+        } else {
+            0
+        };
+        node.children.sort_by_key(|c| Reverse(c.bytes));
+        node.children.truncate(keep);
+        node.other_bytes = node.bytes - node.children.iter().map(|c| c.bytes).sum::<u64>();
+        node
+    }
+}
+
+fn push_top<T: Ord>(heap: &mut BinaryHeap<Reverse<T>>, item: T, limit: usize) {
+    if heap.len() < limit {
+        heap.push(Reverse(item));
+    } else if heap.peek().is_some_and(|Reverse(min)| item > *min) {
+        heap.pop();
+        heap.push(Reverse(item));
+    }
+}
+
+fn cleanup_candidates(
+    dir: &Path,
+    older_than_days: u64,
+    limit: usize,
+    now: SystemTime,
+) -> Option<CleanupCandidates> {
+    let max_age = Duration::from_secs(older_than_days * 24 * 60 * 60);
+    let mut stale = stale_files(dir, max_age, now);
+    if stale.is_empty() {
+        return None;
+    }
+    let bytes = stale.iter().map(|(_, len)| len).sum();
+    let files = stale.len() as u64;
+    stale.sort_by_key(|(_, len)| Reverse(*len));
+    let largest = stale
+        .into_iter()
+        .take(limit)
+        .map(|(path, bytes)| FileUsage {
+            modified: std::fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .ok()
+                .map(DateTime::<Utc>::from),
+            path,
+            bytes,
+        })
+        .collect();
+    Some(CleanupCandidates {
+        dir: dir.to_path_buf(),
+        older_than_days,
+        files,
+        bytes,
+        largest,
+    })
+}
+
+/// The current user's downloads folder, when it exists.
+fn downloads_dir() -> Option<PathBuf> {
+    let home = std::env::var_os(if cfg!(windows) { "USERPROFILE" } else { "HOME" })?;
+    let dir = PathBuf::from(home).join("Downloads");
+    dir.is_dir().then_some(dir)
+}
+
+/// The mounted volume whose mount point is the longest prefix of `root`.
+fn volume_of(root: &Path) -> Option<VolumeUsage> {
+    let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    let disks = sysinfo::Disks::new_with_refreshed_list();
+    disks
+        .list()
+        .iter()
+        .filter(|d| root.starts_with(d.mount_point()))
+        .max_by_key(|d| d.mount_point().as_os_str().len())
+        .map(|d| VolumeUsage {
+            mount_point: d.mount_point().to_path_buf(),
+            total_bytes: d.total_space(),
+            available_bytes: d.available_space(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_pruned_tree_and_top_lists() {
+        let dir = std::env::temp_dir().join(format!("oxide-disk-{}", uuid::Uuid::new_v4()));
+        let root = dir.as_path();
+        std::fs::create_dir_all(root.join("big/nested")).unwrap();
+        std::fs::create_dir_all(root.join("small")).unwrap();
+        std::fs::write(root.join("big/nested/a.bin"), vec![0u8; 4000]).unwrap();
+        std::fs::write(root.join("big/b.bin"), vec![0u8; 2000]).unwrap();
+        std::fs::write(root.join("small/c.txt"), vec![0u8; 100]).unwrap();
+        std::fs::write(root.join("d.txt"), vec![0u8; 10]).unwrap();
+
+        let options = DiskUsageOptions {
+            tree_depth: 1,
+            top_entries: 2,
+            large_file_mb: 0,
+            ..Default::default()
+        };
+        let report = analyze(root, &options).unwrap();
+        assert_eq!(report.total_bytes, 6110);
+        assert_eq!(report.files, 4);
+        assert_eq!(report.directories, 4);
+
+        // Root keeps its children, which keep none of theirs
+        assert_eq!(report.tree.children[0].name, "big");
+        assert_eq!(report.tree.children[0].bytes, 6000);
+        assert!(report.tree.children[0].children.is_empty());
+        assert_eq!(report.tree.children[0].other_bytes, 6000);
+        assert_eq!(report.tree.other_bytes, 10);
+
+        assert_eq!(report.largest_files.len(), 2);
+        assert_eq!(report.largest_files[0].bytes, 4000);
+        assert_eq!(report.top_directories[0].path, root.join("big/nested"));
+        assert!(report.suggestions.iter().any(|s| s.action
+            == OptimizationAction::ReviewLargeFile {
+                path: root.join("big/nested/a.bin")
+            }));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
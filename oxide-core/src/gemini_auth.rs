@@ -21,6 +21,10 @@ pub enum GeminiAuthError {
     AuthFailed(String),
     #[error("No authentication method configured")]
     NoAuthMethod,
+    #[error(transparent)]
+    Offline(#[from] crate::network::OfflineError),
+    #[error(transparent)]
+    Quota(#[from] crate::api_governor::QuotaError),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,6 +47,12 @@ const GEMINI_AUTH_SERVICE: &str = "oxide_pilot_gemini";
 const API_KEY_ENTRY: &str = "api_key";
 const AUTH_CONFIG_ENTRY: &str = "auth_config";
 
+struct ModelInfo {
+    id: String,
+    display_name: Option<String>,
+    supported_generation_methods: Vec<String>,
+}
+
 pub struct GeminiAuth {
     keyring_service: String,
 }
@@ -128,8 +138,10 @@ impl GeminiAuth {
 
     /// Test if an API key is valid
     async fn test_api_key(&self, api_key: &str) -> Result<(), GeminiAuthError> {
-        let client = reqwest::Client::new();
+        let client = crate::network::http_client();
         let url = format!("https://generativelanguage.googleapis.com/v1beta/models?key={api_key}");
+        crate::network::ensure_online(&url)?;
+        let _permit = crate::api_governor::acquire(&url).await?;
 
         let response = client.get(&url).send().await?;
 
@@ -259,14 +271,43 @@ impl GeminiAuth {
 
     /// Get available models (requires OAuth access token)
     pub async fn get_available_models(&self) -> Result<Vec<String>, GeminiAuthError> {
+        let model_names = self
+            .list_models()
+            .await?
+            .into_iter()
+            .map(|m| m.display_name.unwrap_or(m.id))
+            .collect();
+
+        Ok(model_names)
+    }
+
+    /// IDs of models that accept `generateContent` requests, e.g. "gemini-1.5-pro"
+    pub async fn get_generate_content_models(&self) -> Result<Vec<String>, GeminiAuthError> {
+        Ok(self
+            .list_models()
+            .await?
+            .into_iter()
+            .filter(|m| {
+                m.supported_generation_methods.is_empty()
+                    || m.supported_generation_methods
+                        .iter()
+                        .any(|method| method == "generateContent")
+            })
+            .map(|m| m.id)
+            .collect())
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>, GeminiAuthError> {
         // Prefer OAuth via google_auth
         let access_token = crate::google_auth::get_access_token()
             .await
             .map_err(|e| GeminiAuthError::AuthFailed(format!("OAuth access token error: {e}")))?
             .ok_or(GeminiAuthError::NoAuthMethod)?;
 
-        let client = reqwest::Client::new();
+        let client = crate::network::http_client();
         let url = "https://generativelanguage.googleapis.com/v1beta/models";
+        crate::network::ensure_online(url)?;
+        let _permit = crate::api_governor::acquire(url).await?;
 
         let response = client.get(url).bearer_auth(&access_token).send().await?;
 
@@ -287,20 +328,21 @@ impl GeminiAuth {
             name: String,
             #[serde(rename = "displayName")]
             display_name: Option<String>,
+            #[serde(rename = "supportedGenerationMethods", default)]
+            supported_generation_methods: Vec<String>,
         }
 
         let models_response: ModelsResponse = response.json().await?;
-        let model_names = models_response
+        Ok(models_response
             .models
             .into_iter()
-            .map(|m| {
+            .map(|m| ModelInfo {
                 // Extract just the model name (e.g., "gemini-1.5-pro" from "models/gemini-1.5-pro")
-                let name = m.name.split('/').next_back().unwrap_or(&m.name).to_string();
-                m.display_name.unwrap_or(name)
+                id: m.name.split('/').next_back().unwrap_or(&m.name).to_string(),
+                display_name: m.display_name,
+                supported_generation_methods: m.supported_generation_methods,
             })
-            .collect();
-
-        Ok(model_names)
+            .collect())
     }
 
     /// Send a message to Gemini API using OAuth (no API key)
@@ -308,6 +350,28 @@ impl GeminiAuth {
         &self,
         message: &str,
         model: Option<&str>,
+    ) -> Result<String, GeminiAuthError> {
+        self.send_message_with_schema(message, model, None).await
+    }
+
+    /// Send a message and constrain the reply to JSON matching `response_schema`
+    /// (OpenAPI-style schema as accepted by Gemini's `responseSchema`).
+    pub async fn send_message_with_schema(
+        &self,
+        message: &str,
+        model: Option<&str>,
+        response_schema: Option<serde_json::Value>,
+    ) -> Result<String, GeminiAuthError> {
+        let model_name = model.unwrap_or("gemini-1.5-flash");
+        let request = self.generate_content(message, model_name, response_schema);
+        crate::llm_audit::audited("gemini", Some(model_name), message, request).await
+    }
+
+    async fn generate_content(
+        &self,
+        message: &str,
+        model_name: &str,
+        response_schema: Option<serde_json::Value>,
     ) -> Result<String, GeminiAuthError> {
         // Prefer OAuth via google_auth
         let access_token = crate::google_auth::get_access_token()
@@ -315,15 +379,18 @@ impl GeminiAuth {
             .map_err(|e| GeminiAuthError::AuthFailed(format!("OAuth access token error: {e}")))?
             .ok_or(GeminiAuthError::NoAuthMethod)?;
 
-        let model_name = model.unwrap_or("gemini-1.5-flash");
-        let client = reqwest::Client::new();
+        let client = crate::network::http_client();
         let url = format!(
             "https://generativelanguage.googleapis.com/v1beta/models/{model_name}:generateContent"
         );
+        crate::network::ensure_online(&url)?;
+        let _permit = crate::api_governor::acquire(&url).await?;
 
         #[derive(Serialize)]
         struct GenerateRequest {
             contents: Vec<Content>,
+            #[serde(rename = "generationConfig", skip_serializing_if = "Option::is_none")]
+            generation_config: Option<serde_json::Value>,
         }
 
         #[derive(Serialize)]
@@ -342,6 +409,12 @@ impl GeminiAuth {
                     text: message.to_string(),
                 }],
             }],
+            generation_config: response_schema.map(|schema| {
+                serde_json::json!({
+                    "responseMimeType": "application/json",
+                    "responseSchema": schema,
+                })
+            }),
         };
 
         let response = client
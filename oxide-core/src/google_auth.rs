@@ -52,6 +52,8 @@ pub enum AuthError {
     TokenRefresh(String),
     #[error("Refresh token not found")]
     RefreshTokenNotFound,
+    #[error(transparent)]
+    Offline(#[from] crate::network::OfflineError),
 }
 
 const GOOGLE_AUTH_SERVICE_ID: &str = "oxide_pilot_google_auth";
@@ -182,6 +184,7 @@ pub async fn refresh_access_token() -> Result<String, AuthError> {
         Some(token_url),
     );
 
+    crate::network::ensure_online("OAuth token endpoint")?;
     let token_response = client
         .exchange_refresh_token(&oauth2::RefreshToken::new(refresh_token_str))
         .request_async(oauth2::reqwest::async_http_client)
@@ -333,6 +336,7 @@ async fn handle_redirect(
         return Err(AuthError::CsrfMismatch);
     }
 
+    crate::network::ensure_online("OAuth token endpoint")?;
     let token_response = client
         .exchange_code(AuthorizationCode::new(code.to_string()))
         .set_pkce_verifier(pkce_code_verifier)
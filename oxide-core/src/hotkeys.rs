@@ -0,0 +1,334 @@
+//! Global hotkey bindings: accelerator parsing and conflict detection.
+//!
+//! Accelerators use Tauri's syntax (`CmdOrCtrl+Shift+Space`). Before they
+//! are registered, bindings are checked for syntax, for two actions sharing
+//! a combo, and for combos the operating system keeps for itself, which
+//! would either fail to register or never reach the app.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// What a global hotkey does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HotkeyAction {
+    /// Record a voice request without saying the wake word
+    PushToTalk,
+    /// Capture the screen and open the chat to ask about it
+    ScreenshotQuestion,
+    /// Halt all RPA actions and cancel running jobs
+    PanicStop,
+}
+
+impl HotkeyAction {
+    pub const ALL: [HotkeyAction; 3] = [
+        HotkeyAction::PushToTalk,
+        HotkeyAction::ScreenshotQuestion,
+        HotkeyAction::PanicStop,
+    ];
+
+    pub fn default_accelerator(&self) -> &'static str {
+        match self {
+            HotkeyAction::PushToTalk => "CmdOrCtrl+Shift+Space",
+            HotkeyAction::ScreenshotQuestion => "CmdOrCtrl+Alt+Shift+S",
+            HotkeyAction::PanicStop => "CmdOrCtrl+Alt+Shift+X",
+        }
+    }
+}
+
+impl fmt::Display for HotkeyAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            HotkeyAction::PushToTalk => "push_to_talk",
+            HotkeyAction::ScreenshotQuestion => "screenshot_question",
+            HotkeyAction::PanicStop => "panic_stop",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Modifier keys held with an accelerator's key. `CmdOrCtrl` resolves to
+/// `meta` on macOS and `ctrl` elsewhere.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Modifiers {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    /// Command on macOS, the Windows key elsewhere
+    pub meta: bool,
+}
+
+impl Modifiers {
+    fn any(&self) -> bool {
+        self.ctrl || self.alt || self.shift || self.meta
+    }
+}
+
+/// A parsed, platform-resolved key combination
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Accelerator {
+    pub modifiers: Modifiers,
+    /// Canonical key name, e.g. `S`, `Space`, `F12`
+    pub key: String,
+}
+
+const NAMED_KEYS: [&str; 17] = [
+    "Space",
+    "Tab",
+    "Enter",
+    "Escape",
+    "Backspace",
+    "Delete",
+    "Insert",
+    "Home",
+    "End",
+    "PageUp",
+    "PageDown",
+    "Up",
+    "Down",
+    "Left",
+    "Right",
+    "Pause",
+    "PrintScreen",
+];
+
+fn canonical_key(token: &str) -> Option<String> {
+    let upper = token.to_ascii_uppercase();
+    if upper.len() == 1 && upper.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Some(upper);
+    }
+    if let Some(n) = upper.strip_prefix('F').and_then(|n| n.parse::<u8>().ok()) {
+        if (1..=24).contains(&n) {
+            return Some(format!("F{n}"));
+        }
+    }
+    let alias = match upper.as_str() {
+        "ESC" => "ESCAPE",
+        "RETURN" => "ENTER",
+        "DEL" => "DELETE",
+        "UPARROW" => "UP",
+        "DOWNARROW" => "DOWN",
+        "LEFTARROW" => "LEFT",
+        "RIGHTARROW" => "RIGHT",
+        other => other,
+    };
+    NAMED_KEYS
+        .iter()
+        .find(|name| name.eq_ignore_ascii_case(alias))
+        .map(|name| name.to_string())
+}
+
+impl Accelerator {
+    /// Parse an accelerator for the platform this build runs on.
+    pub fn parse(accelerator: &str) -> Result<Self, String> {
+        Self::parse_for(accelerator, cfg!(target_os = "macos"))
+    }
+
+    fn parse_for(accelerator: &str, macos: bool) -> Result<Self, String> {
+        let mut modifiers = Modifiers::default();
+        let mut key = None;
+        for token in accelerator.split('+').map(str::trim) {
+            match token.to_ascii_lowercase().as_str() {
+                "" => return Err(format!("Empty key in hotkey '{accelerator}'")),
+                "ctrl" | "control" => modifiers.ctrl = true,
+                "alt" | "option" => modifiers.alt = true,
+                "shift" => modifiers.shift = true,
+                "super" | "cmd" | "command" | "meta" | "win" => modifiers.meta = true,
+                "cmdorctrl" | "commandorcontrol" => {
+                    if macos {
+                        modifiers.meta = true;
+                    } else {
+                        modifiers.ctrl = true;
+                    }
+                }
+                _ if key.is_some() => {
+                    return Err(format!("Hotkey '{accelerator}' has more than one key"))
+                }
+                _ => {
+                    key = Some(canonical_key(token).ok_or_else(|| {
+                        format!("Unknown key '{token}' in hotkey '{accelerator}'")
+                    })?)
+                }
+            }
+        }
+        let key = key.ok_or_else(|| format!("Hotkey '{accelerator}' has no key"))?;
+
+        // Bare keys would be swallowed from every other application
+        let spare_function_key = key
+            .strip_prefix('F')
+            .and_then(|n| n.parse::<u8>().ok())
+            .is_some_and(|n| n >= 13);
+        if !modifiers.any() && !spare_function_key {
+            return Err(format!(
+                "Hotkey '{accelerator}' needs at least one modifier"
+            ));
+        }
+        Ok(Self { modifiers, key })
+    }
+
+    /// What the operating system uses this combo for, if anything.
+    pub fn reserved_by_system(&self) -> Option<&'static str> {
+        reserved_by(self, current_platform())
+    }
+}
+
+impl fmt::Display for Accelerator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let m = self.modifiers;
+        for (held, name) in [
+            (m.ctrl, "Ctrl"),
+            (m.alt, "Alt"),
+            (m.shift, "Shift"),
+            (m.meta, "Super"),
+        ] {
+            if held {
+                write!(f, "{name}+")?;
+            }
+        }
+        f.write_str(&self.key)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Platform {
+    Windows,
+    Mac,
+    Linux,
+}
+
+fn current_platform() -> Platform {
+    if cfg!(windows) {
+        Platform::Windows
+    } else if cfg!(target_os = "macos") {
+        Platform::Mac
+    } else {
+        Platform::Linux
+    }
+}
+
+/// `(platform, accelerator, purpose)` combos the system handles before any app
+const RESERVED: [(Platform, &str, &str); 19] = [
+    (
+        Platform::Windows,
+        "Ctrl+Alt+Delete",
+        "Windows security screen",
+    ),
+    (Platform::Windows, "Ctrl+Shift+Escape", "Task Manager"),
+    (Platform::Windows, "Ctrl+Escape", "Start menu"),
+    (Platform::Windows, "Alt+Tab", "window switching"),
+    (Platform::Windows, "Alt+F4", "closing windows"),
+    (Platform::Windows, "Super+L", "locking the screen"),
+    (Platform::Windows, "Super+D", "showing the desktop"),
+    (Platform::Windows, "Super+Tab", "Task View"),
+    (Platform::Mac, "Cmd+Tab", "app switching"),
+    (Platform::Mac, "Cmd+Q", "quitting apps"),
+    (Platform::Mac, "Cmd+Space", "Spotlight"),
+    (Platform::Mac, "Cmd+Shift+3", "screenshots"),
+    (Platform::Mac, "Cmd+Shift+4", "screenshots"),
+    (Platform::Mac, "Cmd+Shift+5", "screenshots"),
+    (Platform::Mac, "Cmd+Alt+Escape", "Force Quit"),
+    (Platform::Mac, "Ctrl+Cmd+Q", "locking the screen"),
+    (Platform::Linux, "Ctrl+Alt+Delete", "logging out"),
+    (Platform::Linux, "Alt+Tab", "window switching"),
+    (Platform::Linux, "Ctrl+Alt+L", "locking the screen"),
+];
+
+fn reserved_by(accelerator: &Accelerator, platform: Platform) -> Option<&'static str> {
+    RESERVED
+        .iter()
+        .filter(|(reserved, ..)| *reserved == platform)
+        .find(|(_, combo, _)| {
+            Accelerator::parse_for(combo, platform == Platform::Mac).as_ref() == Ok(accelerator)
+        })
+        .map(|(_, _, purpose)| *purpose)
+}
+
+/// A binding that cannot be registered as configured
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HotkeyConflict {
+    pub action: HotkeyAction,
+    pub accelerator: String,
+    pub reason: String,
+}
+
+/// Problems with `bindings`: bad syntax, a combo bound to two actions, or a
+/// combo the system reserves.
+pub fn find_conflicts(bindings: &[(HotkeyAction, String)]) -> Vec<HotkeyConflict> {
+    let mut conflicts = Vec::new();
+    let mut seen: Vec<(Accelerator, HotkeyAction)> = Vec::new();
+    for (action, accelerator) in bindings {
+        let conflict = |reason: String| HotkeyConflict {
+            action: *action,
+            accelerator: accelerator.clone(),
+            reason,
+        };
+        let parsed = match Accelerator::parse(accelerator) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                conflicts.push(conflict(e));
+                continue;
+            }
+        };
+        if let Some(purpose) = parsed.reserved_by_system() {
+            conflicts.push(conflict(format!(
+                "{parsed} is reserved by the system for {purpose}"
+            )));
+        } else if let Some((_, other)) = seen.iter().find(|(a, _)| *a == parsed) {
+            conflicts.push(conflict(format!("{parsed} is already bound to {other}")));
+        } else {
+            seen.push((parsed, *action));
+        }
+    }
+    conflicts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_resolves_accelerators() {
+        let ptt = Accelerator::parse_for("CmdOrCtrl+Shift+space", false).unwrap();
+        assert!(ptt.modifiers.ctrl && ptt.modifiers.shift && !ptt.modifiers.meta);
+        assert_eq!(ptt.to_string(), "Ctrl+Shift+Space");
+        let mac = Accelerator::parse_for("CmdOrCtrl+Shift+Space", true).unwrap();
+        assert!(mac.modifiers.meta && !mac.modifiers.ctrl);
+        assert_eq!(Accelerator::parse("ctrl+esc").unwrap().key, "Escape");
+        assert_eq!(Accelerator::parse("F13").unwrap().key, "F13");
+
+        assert!(Accelerator::parse("S").unwrap_err().contains("modifier"));
+        assert!(Accelerator::parse("Ctrl+A+B").is_err());
+        assert!(Accelerator::parse("Ctrl+Hyper").is_err());
+        assert!(Accelerator::parse("Ctrl++").is_err());
+    }
+
+    #[test]
+    fn detects_duplicate_and_reserved_bindings() {
+        let bindings: Vec<(HotkeyAction, String)> = HotkeyAction::ALL
+            .iter()
+            .map(|a| (*a, a.default_accelerator().to_string()))
+            .collect();
+        assert!(find_conflicts(&bindings).is_empty());
+
+        let clash = vec![
+            (HotkeyAction::PushToTalk, "Ctrl+Alt+Shift+S".to_string()),
+            (
+                HotkeyAction::ScreenshotQuestion,
+                "shift+alt+ctrl+s".to_string(),
+            ),
+        ];
+        let conflicts = find_conflicts(&clash);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].action, HotkeyAction::ScreenshotQuestion);
+        assert!(conflicts[0].reason.contains("push_to_talk"));
+
+        let task_manager = Accelerator::parse_for("Ctrl+Shift+Esc", false).unwrap();
+        assert_eq!(
+            reserved_by(&task_manager, Platform::Windows),
+            Some("Task Manager")
+        );
+        assert_eq!(reserved_by(&task_manager, Platform::Linux), None);
+        let spotlight = Accelerator::parse_for("Command+Space", true).unwrap();
+        assert_eq!(reserved_by(&spotlight, Platform::Mac), Some("Spotlight"));
+    }
+}
@@ -0,0 +1,386 @@
+//! Localized text for errors, notifications and LLM prompts.
+//!
+//! Messages are looked up by key in a per-locale catalog; a key missing from
+//! a catalog falls back to English, and a key missing from English is
+//! returned as-is. Placeholders are written `{name}` and filled by
+//! [`tf`]. The active locale is process-wide: it comes from the `locale`
+//! config setting, or the OS language when that is absent.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::RwLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    Es,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::En, Locale::Es];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Es => "es",
+        }
+    }
+
+    /// Name of the language in that language, for locale pickers.
+    pub fn native_name(&self) -> &'static str {
+        match self {
+            Locale::En => "English",
+            Locale::Es => "Español",
+        }
+    }
+
+    /// Match a language tag such as "es-MX", "es_ES.UTF-8" or "EN" on its
+    /// primary language.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let language = tag
+            .trim()
+            .split(['-', '_', '.', '@'])
+            .next()
+            .unwrap_or_default();
+        Self::ALL
+            .into_iter()
+            .find(|locale| locale.code().eq_ignore_ascii_case(language))
+    }
+
+    /// The OS language, if it is one we have a catalog for.
+    pub fn detect() -> Option<Self> {
+        // POSIX precedence; "C" and "POSIX" carry no language
+        ["LC_ALL", "LC_MESSAGES", "LANG", "LANGUAGE"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty() && value != "C" && value != "POSIX")
+            .or_else(platform::user_locale)
+            .and_then(|tag| Self::from_tag(&tag))
+    }
+
+    /// The configured locale, else the OS language, else English.
+    pub fn resolve(configured: Option<&str>) -> Self {
+        configured
+            .filter(|tag| !tag.trim().is_empty() && !tag.eq_ignore_ascii_case("auto"))
+            .and_then(Self::from_tag)
+            .or_else(Self::detect)
+            .unwrap_or_default()
+    }
+
+    /// Text for `key` in this locale.
+    pub fn text<'a>(&self, key: &'a str) -> &'a str {
+        lookup(catalog(*self), key)
+            .or_else(|| lookup(EN, key))
+            .unwrap_or(key)
+    }
+
+    /// Text for `key` with each `{name}` placeholder replaced.
+    pub fn format(&self, key: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+        let mut text = self.text(key).to_string();
+        for (name, value) in args {
+            text = text.replace(&format!("{{{name}}}"), &value.to_string());
+        }
+        text
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_tag(s).ok_or_else(|| {
+            let available: Vec<_> = Self::ALL.iter().map(Locale::code).collect();
+            format!(
+                "Unsupported locale '{s}'; available: {}",
+                available.join(", ")
+            )
+        })
+    }
+}
+
+static CURRENT: RwLock<Locale> = RwLock::new(Locale::En);
+
+/// The active locale.
+pub fn locale() -> Locale {
+    CURRENT.read().map(|l| *l).unwrap_or_default()
+}
+
+pub fn set_locale(locale: Locale) {
+    if let Ok(mut current) = CURRENT.write() {
+        *current = locale;
+    }
+}
+
+/// Text for `key` in the active locale.
+pub fn t(key: &str) -> &str {
+    locale().text(key)
+}
+
+/// Text for `key` in the active locale with its placeholders filled.
+pub fn tf(key: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+    locale().format(key, args)
+}
+
+/// A locale the frontend can offer
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct LocaleOption {
+    pub code: Locale,
+    pub name: &'static str,
+}
+
+/// Active locale, the OS language and the catalogs available
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct LocaleInfo {
+    pub locale: Locale,
+    pub detected: Option<Locale>,
+    pub available: Vec<LocaleOption>,
+}
+
+pub fn info() -> LocaleInfo {
+    LocaleInfo {
+        locale: locale(),
+        detected: Locale::detect(),
+        available: Locale::ALL
+            .into_iter()
+            .map(|code| LocaleOption {
+                code,
+                name: code.native_name(),
+            })
+            .collect(),
+    }
+}
+
+/// Instruction appended to LLM prompts so replies use the active locale.
+/// `None` for English, which models answer in by default.
+pub fn reply_directive() -> Option<&'static str> {
+    match locale() {
+        Locale::En => None,
+        other => Some(other.text("prompt.reply_language")),
+    }
+}
+
+/// `prompt` followed by the [`reply_directive`], if any.
+pub fn with_reply_directive(prompt: &str) -> String {
+    match reply_directive() {
+        Some(directive) => format!("{prompt}\n\n{directive}"),
+        None => prompt.to_string(),
+    }
+}
+
+fn catalog(locale: Locale) -> &'static [(&'static str, &'static str)] {
+    match locale {
+        Locale::En => EN,
+        Locale::Es => ES,
+    }
+}
+
+fn lookup(catalog: &'static [(&'static str, &'static str)], key: &str) -> Option<&'static str> {
+    catalog
+        .iter()
+        .find(|(candidate, _)| *candidate == key)
+        .map(|(_, text)| *text)
+}
+
+const EN: &[(&str, &str)] = &[
+    ("error.system_not_initialized", "System not initialized"),
+    ("error.system_already_running", "System is already running"),
+    ("error.system_not_running", "System is not running"),
+    (
+        "error.metrics_disabled",
+        "SurrealDB metrics feature not enabled",
+    ),
+    ("error.command_denied", "Command '{command}' denied: {reason}"),
+    (
+        "error.session_required",
+        "Command '{command}' requires a session with '{permission}' permission",
+    ),
+    (
+        "error.antivirus_disabled",
+        "Antivirus scanning is disabled in settings",
+    ),
+    ("error.no_images", "No images attached"),
+    (
+        "error.images_offline",
+        "Image questions need a cloud vision model; disable offline mode",
+    ),
+    (
+        "error.no_llm_providers",
+        "No LLM providers available (Gemini, Qwen, or OpenAI)",
+    ),
+    (
+        "notification.memory_growth",
+        "{name} grew from {from} to {to} on each of the last {days} days, peaking around {hour}:00 — restart it?",
+    ),
+    (
+        "notification.memory_growth.action",
+        "Restart {name} to free the memory it has built up",
+    ),
+    (
+        "notification.recurring_problem",
+        "{category} problems often come up around {hour}:00 — run a quick check now?",
+    ),
+    (
+        "notification.recurring_problem.action",
+        "Check this PC for {category} problems",
+    ),
+    (
+        "notification.habit",
+        "You usually run {command} around {hour}:00 — run it now?",
+    ),
+    (
+        "notification.repeated_threat",
+        "{name} triggered {count} high-severity alerts in the last day — investigate it?",
+    ),
+    (
+        "notification.repeated_threat.action",
+        "Investigate why {name} keeps triggering security alerts",
+    ),
+    (
+        "notification.webhook_test",
+        "Test notification: webhooks are working",
+    ),
+    (
+        "prompt.assistant",
+        "You are Oxide Pilot, a local system assistant. Respond concisely.",
+    ),
+    ("prompt.reply_language", "Reply in English."),
+];
+
+const ES: &[(&str, &str)] = &[
+    (
+        "error.system_not_initialized",
+        "El sistema no está inicializado",
+    ),
+    ("error.system_already_running", "El sistema ya está en ejecución"),
+    ("error.system_not_running", "El sistema no está en ejecución"),
+    (
+        "error.metrics_disabled",
+        "La función de métricas de SurrealDB no está habilitada",
+    ),
+    ("error.command_denied", "Comando '{command}' denegado: {reason}"),
+    (
+        "error.session_required",
+        "El comando '{command}' requiere una sesión con el permiso '{permission}'",
+    ),
+    (
+        "error.antivirus_disabled",
+        "El análisis antivirus está desactivado en la configuración",
+    ),
+    ("error.no_images", "No hay imágenes adjuntas"),
+    (
+        "error.images_offline",
+        "Las preguntas con imágenes necesitan un modelo de visión en la nube; desactiva el modo sin conexión",
+    ),
+    (
+        "error.no_llm_providers",
+        "No hay proveedores de LLM disponibles (Gemini, Qwen u OpenAI)",
+    ),
+    (
+        "notification.memory_growth",
+        "{name} pasó de {from} a {to} en cada uno de los últimos {days} días, con un pico hacia las {hour}:00. ¿Reiniciarlo?",
+    ),
+    (
+        "notification.memory_growth.action",
+        "Reinicia {name} para liberar la memoria que ha acumulado",
+    ),
+    (
+        "notification.recurring_problem",
+        "Los problemas de {category} suelen aparecer hacia las {hour}:00. ¿Hacer una comprobación rápida ahora?",
+    ),
+    (
+        "notification.recurring_problem.action",
+        "Comprueba si este PC tiene problemas de {category}",
+    ),
+    (
+        "notification.habit",
+        "Sueles ejecutar {command} hacia las {hour}:00. ¿Ejecutarlo ahora?",
+    ),
+    (
+        "notification.repeated_threat",
+        "{name} generó {count} alertas de gravedad alta en el último día. ¿Investigarlo?",
+    ),
+    (
+        "notification.repeated_threat.action",
+        "Investiga por qué {name} sigue generando alertas de seguridad",
+    ),
+    (
+        "notification.webhook_test",
+        "Notificación de prueba: los webhooks funcionan",
+    ),
+    (
+        "prompt.assistant",
+        "Eres Oxide Pilot, un asistente local del sistema. Responde de forma concisa.",
+    ),
+    ("prompt.reply_language", "Responde siempre en español."),
+];
+
+#[cfg(windows)]
+mod platform {
+    use winapi::um::winnls::GetUserDefaultLocaleName;
+
+    pub fn user_locale() -> Option<String> {
+        // LOCALE_NAME_MAX_LENGTH
+        let mut buffer = [0u16; 85];
+        let len = unsafe { GetUserDefaultLocaleName(buffer.as_mut_ptr(), buffer.len() as i32) };
+        (len > 1).then(|| String::from_utf16_lossy(&buffer[..len as usize - 1]))
+    }
+}
+
+#[cfg(not(windows))]
+mod platform {
+    pub fn user_locale() -> Option<String> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholders(text: &str) -> Vec<&str> {
+        let mut names: Vec<_> = text
+            .split('{')
+            .skip(1)
+            .filter_map(|rest| rest.split_once('}').map(|(name, _)| name))
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    #[test]
+    fn catalogs_cover_english_keys_with_matching_placeholders() {
+        for locale in Locale::ALL {
+            for (key, english) in EN {
+                let translated = lookup(catalog(locale), key)
+                    .unwrap_or_else(|| panic!("{locale} is missing {key}"));
+                assert_eq!(placeholders(translated), placeholders(english), "{key}");
+            }
+            assert_eq!(catalog(locale).len(), EN.len(), "{locale} has extra keys");
+        }
+    }
+
+    #[test]
+    fn resolves_tags_and_formats() {
+        assert_eq!(Locale::from_tag("es_MX.UTF-8"), Some(Locale::Es));
+        assert_eq!(Locale::from_tag("EN-gb"), Some(Locale::En));
+        assert_eq!(Locale::from_tag("fr-FR"), None);
+        assert_eq!(Locale::resolve(Some("es")), Locale::Es);
+        assert!("de".parse::<Locale>().unwrap_err().contains("en, es"));
+
+        let text = Locale::Es.format(
+            "error.command_denied",
+            &[("command", &"scan_file"), ("reason", &"sin sesión")],
+        );
+        assert_eq!(text, "Comando 'scan_file' denegado: sin sesión");
+        assert_eq!(Locale::Es.text("unknown.key"), "unknown.key");
+    }
+}
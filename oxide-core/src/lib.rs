@@ -1,16 +1,41 @@
+pub mod api_governor;
 pub mod auth;
+pub mod capabilities;
+pub mod cleanup;
 pub mod config;
 pub mod config_manager;
+pub mod context_provider;
+pub mod crash;
+pub mod disk_usage;
 pub mod encryption;
 pub mod gemini_auth;
 pub mod google_auth;
+pub mod hotkeys;
+pub mod i18n;
 pub mod input_validation;
+pub mod llm_audit;
+pub mod logging;
+pub mod mcp_tokens;
 pub mod metrics;
+pub mod network;
+pub mod onboarding;
 pub mod openai_auth;
 pub mod openai_client;
 pub mod openai_key;
 pub mod performance;
+pub mod power;
+pub mod privacy;
+pub mod profile;
+pub mod prometheus;
+pub mod prompts;
 pub mod qwen_auth;
+pub mod remote_credentials;
 pub mod security;
 pub mod security_manager;
+pub mod severity;
+pub mod smtp_credentials;
+pub mod tls;
 pub mod types;
+pub mod updates;
+pub mod webhook_credentials;
+pub mod webhooks;
@@ -0,0 +1,243 @@
+//! Audit trail of the prompts sent to LLM providers and their responses.
+//!
+//! Provider clients wrap each request in [`audited`], which records the
+//! prompt exactly as sent (after privacy redaction), the response or error
+//! and the timing. The purpose tag comes from the enclosing [`with_purpose`]
+//! scope. Entries go to the receiver returned by [`subscribe`], which the
+//! app drains into the `llm_audit` table; without a subscriber, or with
+//! auditing disabled, nothing is recorded.
+
+use crate::config::LlmAuditConfig;
+use chrono::{DateTime, Utc};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt::Display;
+use std::future::Future;
+use std::sync::{Mutex, RwLock};
+use std::time::Instant;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+/// Purpose of requests made outside any [`with_purpose`] scope
+pub const DEFAULT_PURPOSE: &str = "general";
+
+/// Days entries are kept when `retention_days` is not configured
+pub const DEFAULT_RETENTION_DAYS: u32 = 90;
+
+/// Most entries one query returns
+pub const MAX_QUERY_LIMIT: usize = 500;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LlmAuditEntry {
+    /// Set on entries read back from the database
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub timestamp: DateTime<Utc>,
+    pub provider: String,
+    #[serde(default)]
+    pub model: Option<String>,
+    pub purpose: String,
+    pub prompt: String,
+    #[serde(default)]
+    pub response: Option<String>,
+    #[serde(default)]
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// Filters for reading the audit trail; every field is optional.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct LlmAuditQuery {
+    #[serde(default)]
+    pub provider: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub purpose: Option<String>,
+    #[serde(default)]
+    pub since: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub until: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+impl LlmAuditQuery {
+    pub fn limit(&self) -> usize {
+        self.limit.unwrap_or(50).clamp(1, MAX_QUERY_LIMIT)
+    }
+}
+
+tokio::task_local! {
+    static PURPOSE: String;
+}
+
+/// Run `request` with LLM calls inside it tagged as `purpose`.
+pub async fn with_purpose<F: Future>(purpose: &str, request: F) -> F::Output {
+    PURPOSE.scope(purpose.to_string(), request).await
+}
+
+/// Purpose tag of the current task.
+pub fn current_purpose() -> String {
+    PURPOSE
+        .try_with(Clone::clone)
+        .unwrap_or_else(|_| DEFAULT_PURPOSE.to_string())
+}
+
+static SETTINGS: RwLock<LlmAuditConfig> = RwLock::new(LlmAuditConfig {
+    enabled: true,
+    retention_days: None,
+});
+
+static SINK: Mutex<Option<UnboundedSender<LlmAuditEntry>>> = Mutex::new(None);
+
+pub fn configure(config: &LlmAuditConfig) {
+    if let Ok(mut settings) = SETTINGS.write() {
+        *settings = config.clone();
+    }
+}
+
+/// Days entries are kept before the writer prunes them.
+pub fn retention_days() -> u32 {
+    settings().retention_days.unwrap_or(DEFAULT_RETENTION_DAYS)
+}
+
+pub fn settings() -> LlmAuditConfig {
+    SETTINGS
+        .read()
+        .map(|settings| settings.clone())
+        .unwrap_or_default()
+}
+
+/// Receive every entry recorded from now on, replacing any earlier
+/// subscriber.
+pub fn subscribe() -> UnboundedReceiver<LlmAuditEntry> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    *SINK.lock().unwrap_or_else(|p| p.into_inner()) = Some(tx);
+    rx
+}
+
+fn recording() -> bool {
+    settings().enabled
+        && SINK
+            .lock()
+            .map(|sink| sink.as_ref().is_some_and(|tx| !tx.is_closed()))
+            .unwrap_or(false)
+}
+
+pub fn record(entry: LlmAuditEntry) {
+    if !settings().enabled {
+        return;
+    }
+    let sink = SINK.lock().unwrap_or_else(|p| p.into_inner());
+    if let Some(tx) = sink.as_ref() {
+        if tx.send(entry).is_err() {
+            warn!("LLM audit entry dropped: the audit writer has stopped");
+        }
+    }
+}
+
+/// Send `request` to `provider` and record the exchange. `prompt` is the
+/// text as sent.
+pub async fn audited<T, E, F>(
+    provider: &str,
+    model: Option<&str>,
+    prompt: &str,
+    request: F,
+) -> Result<T, E>
+where
+    F: Future<Output = Result<T, E>>,
+    T: AsRef<str>,
+    E: Display,
+{
+    if !recording() {
+        return request.await;
+    }
+    let timestamp = Utc::now();
+    let started = Instant::now();
+    let result = request.await;
+    record(LlmAuditEntry {
+        id: None,
+        timestamp,
+        provider: provider.to_string(),
+        model: model.map(str::to_string),
+        purpose: current_purpose(),
+        prompt: prompt.to_string(),
+        response: result.as_ref().ok().map(|r| r.as_ref().to_string()),
+        error: result.as_ref().err().map(ToString::to_string),
+        duration_ms: started.elapsed().as_millis() as u64,
+    });
+    result
+}
+
+/// Chat messages as audit text, one `role: content` block each. Image
+/// parts are noted, not copied.
+pub fn messages_text(messages: &[Value]) -> String {
+    messages
+        .iter()
+        .map(|message| {
+            let role = message["role"].as_str().unwrap_or("unknown");
+            let content = match &message["content"] {
+                Value::String(text) => text.clone(),
+                Value::Array(parts) => parts
+                    .iter()
+                    .map(|part| match part["text"].as_str() {
+                        Some(text) => text.to_string(),
+                        None => format!("[{}]", part["type"].as_str().unwrap_or("attachment")),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                Value::Null => String::new(),
+                other => other.to_string(),
+            };
+            format!("{role}: {content}")
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn records_exchanges_with_the_scope_purpose() {
+        let mut rx = subscribe();
+        let reply: Result<String, String> = with_purpose("threat_consensus", async {
+            audited("qwen", Some("qwen-plus"), "prompt", async {
+                Ok("reply".to_string())
+            })
+            .await
+        })
+        .await;
+        assert_eq!(reply.unwrap(), "reply");
+        let failed: Result<String, String> =
+            audited("gemini", None, "other", async { Err("quota".to_string()) }).await;
+        assert!(failed.is_err());
+
+        let entry = rx.recv().await.unwrap();
+        assert_eq!(entry.purpose, "threat_consensus");
+        assert_eq!(entry.model.as_deref(), Some("qwen-plus"));
+        assert_eq!(entry.response.as_deref(), Some("reply"));
+        let entry = rx.recv().await.unwrap();
+        assert_eq!(entry.purpose, DEFAULT_PURPOSE);
+        assert_eq!(entry.error.as_deref(), Some("quota"));
+        assert_eq!(entry.response, None);
+    }
+
+    #[test]
+    fn messages_become_role_blocks() {
+        let messages = [
+            json!({"role": "system", "content": "Be brief."}),
+            json!({"role": "user", "content": [
+                {"type": "text", "text": "What is this?"},
+                {"type": "image_url", "image_url": {"url": "data:..."}}
+            ]}),
+        ];
+        assert_eq!(
+            messages_text(&messages),
+            "system: Be brief.\n\nuser: What is this?\n[image_url]"
+        );
+    }
+}
@@ -0,0 +1,358 @@
+//! Application log: JSON lines in size-rotated files plus an in-memory ring
+//! buffer that the UI queries through `get_recent_logs`.
+//!
+//! [`init`] installs [`AppLogger`] as the `log` backend. An optional console
+//! logger (env_logger in the desktop app) still receives every record it
+//! accepts, so `RUST_LOG` keeps working for terminal output.
+
+use chrono::{DateTime, Utc};
+use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Mutex, OnceLock};
+
+/// Name of the active log file; rotated files get `.1`, `.2`, ... appended.
+pub const LOG_FILE_NAME: &str = "oxide-pilot.log";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct LogEntry {
+    pub timestamp: DateTime<Utc>,
+    pub level: String,
+    /// Module path of the call site, e.g. `oxide_guardian::guardian`.
+    pub module: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct LogOptions {
+    pub dir: PathBuf,
+    /// Minimum level written to files and the ring buffer.
+    pub level: LevelFilter,
+    pub max_file_bytes: u64,
+    /// Files kept including the active one.
+    pub max_files: usize,
+    pub ring_capacity: usize,
+}
+
+impl Default for LogOptions {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::from("./data/logs"),
+            level: LevelFilter::Info,
+            max_file_bytes: 5 * 1024 * 1024,
+            max_files: 5,
+            ring_capacity: 2000,
+        }
+    }
+}
+
+impl LogOptions {
+    /// Defaults, overridable via `OXIDE_LOG_DIR` and `OXIDE_LOG_LEVEL`.
+    pub fn from_env() -> Self {
+        let mut options = Self::default();
+        if let Ok(dir) = std::env::var("OXIDE_LOG_DIR") {
+            if !dir.trim().is_empty() {
+                options.dir = PathBuf::from(dir.trim());
+            }
+        }
+        if let Some(level) = std::env::var("OXIDE_LOG_LEVEL")
+            .ok()
+            .and_then(|level| LevelFilter::from_str(level.trim()).ok())
+        {
+            options.level = level;
+        }
+        options
+    }
+}
+
+struct RotatingFile {
+    dir: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    file: File,
+    size: u64,
+}
+
+impl RotatingFile {
+    fn open(dir: &Path, max_bytes: u64, max_files: usize) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(LOG_FILE_NAME))?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            max_bytes,
+            max_files: max_files.max(1),
+            file,
+            size,
+        })
+    }
+
+    fn path(&self, index: usize) -> PathBuf {
+        rotated_path(&self.dir, index)
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        let _ = fs::remove_file(self.path(self.max_files - 1));
+        for index in (0..self.max_files - 1).rev() {
+            let from = self.path(index);
+            if from.exists() {
+                fs::rename(&from, self.path(index + 1))?;
+            }
+        }
+        self.file = File::create(self.path(0))?;
+        self.size = 0;
+        Ok(())
+    }
+
+    fn write_line(&mut self, line: &[u8]) -> io::Result<()> {
+        if self.size > 0 && self.size + line.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        self.file.write_all(line)?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+}
+
+fn rotated_path(dir: &Path, index: usize) -> PathBuf {
+    if index == 0 {
+        dir.join(LOG_FILE_NAME)
+    } else {
+        dir.join(format!("{LOG_FILE_NAME}.{index}"))
+    }
+}
+
+pub struct AppLogger {
+    options: LogOptions,
+    ring: Mutex<VecDeque<LogEntry>>,
+    file: Mutex<Option<RotatingFile>>,
+    console: Option<(Box<dyn Log>, LevelFilter)>,
+}
+
+impl AppLogger {
+    /// Without a writable log directory only the ring buffer is kept.
+    pub fn new(options: LogOptions, console: Option<(Box<dyn Log>, LevelFilter)>) -> Self {
+        let file = match RotatingFile::open(&options.dir, options.max_file_bytes, options.max_files)
+        {
+            Ok(file) => Some(file),
+            Err(e) => {
+                eprintln!(
+                    "Log files disabled, cannot open {}: {e}",
+                    options.dir.display()
+                );
+                None
+            }
+        };
+        Self {
+            ring: Mutex::new(VecDeque::with_capacity(options.ring_capacity)),
+            file: Mutex::new(file),
+            options,
+            console,
+        }
+    }
+
+    fn max_level(&self) -> LevelFilter {
+        let console = self.console.as_ref().map_or(LevelFilter::Off, |(_, l)| *l);
+        self.options.level.max(console)
+    }
+
+    fn record(&self, entry: LogEntry) {
+        if let Ok(mut file) = self.file.lock() {
+            if let Some(file) = file.as_mut() {
+                if let Ok(mut line) = serde_json::to_vec(&entry) {
+                    line.push(b'\n');
+                    let _ = file.write_line(&line);
+                }
+            }
+        }
+        if let Ok(mut ring) = self.ring.lock() {
+            if ring.len() >= self.options.ring_capacity {
+                ring.pop_front();
+            }
+            ring.push_back(entry);
+        }
+    }
+
+    /// Newest entries first, at `level` or more severe, whose module starts
+    /// with `module`.
+    pub fn recent(
+        &self,
+        level: Option<Level>,
+        module: Option<&str>,
+        limit: usize,
+    ) -> Vec<LogEntry> {
+        let Ok(ring) = self.ring.lock() else {
+            return Vec::new();
+        };
+        ring.iter()
+            .rev()
+            .filter(|entry| {
+                level.is_none_or(|level| Level::from_str(&entry.level).is_ok_and(|l| l <= level))
+            })
+            .filter(|entry| module.is_none_or(|m| entry.module.starts_with(m)))
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    /// Active and rotated log files, newest first.
+    pub fn files(&self) -> Vec<PathBuf> {
+        (0..self.options.max_files)
+            .map(|index| rotated_path(&self.options.dir, index))
+            .filter(|path| path.exists())
+            .collect()
+    }
+}
+
+impl Log for AppLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if let Some((console, level)) = &self.console {
+            if record.level() <= *level {
+                console.log(record);
+            }
+        }
+        if record.level() <= self.options.level {
+            self.record(LogEntry {
+                timestamp: Utc::now(),
+                level: record.level().to_string(),
+                module: record.target().to_string(),
+                message: record.args().to_string(),
+            });
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock() {
+            if let Some(file) = file.as_mut() {
+                let _ = file.file.flush();
+            }
+        }
+        if let Some((console, _)) = &self.console {
+            console.flush();
+        }
+    }
+}
+
+static LOGGER: OnceLock<AppLogger> = OnceLock::new();
+
+/// Install [`AppLogger`] as the global logger. Fails if a logger is already set.
+pub fn init(
+    options: LogOptions,
+    console: Option<(Box<dyn Log>, LevelFilter)>,
+) -> Result<(), SetLoggerError> {
+    let logger = LOGGER.get_or_init(|| AppLogger::new(options, console));
+    log::set_logger(logger)?;
+    log::set_max_level(logger.max_level());
+    Ok(())
+}
+
+/// The installed logger, if [`init`] has run.
+pub fn logger() -> Option<&'static AppLogger> {
+    LOGGER.get()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(name: &str) -> LogOptions {
+        let dir = std::env::temp_dir().join(format!("oxide-logs-{name}-{}", uuid::Uuid::new_v4()));
+        LogOptions {
+            dir,
+            level: LevelFilter::Debug,
+            max_file_bytes: 200,
+            max_files: 3,
+            ring_capacity: 4,
+        }
+    }
+
+    fn emit(logger: &AppLogger, level: Level, target: &str, message: &str) {
+        logger.log(
+            &Record::builder()
+                .level(level)
+                .target(target)
+                .args(format_args!("{message}"))
+                .build(),
+        );
+    }
+
+    #[test]
+    fn ring_buffer_filters_by_level_and_module() {
+        let opts = options("ring");
+        let dir = opts.dir.clone();
+        let logger = AppLogger::new(opts, None);
+        emit(&logger, Level::Trace, "oxide_guardian::guardian", "dropped");
+        emit(
+            &logger,
+            Level::Info,
+            "oxide_guardian::guardian",
+            "scan started",
+        );
+        emit(&logger, Level::Warn, "oxide_copilot::ai", "provider slow");
+        emit(
+            &logger,
+            Level::Error,
+            "oxide_guardian::scanner",
+            "scan failed",
+        );
+        emit(&logger, Level::Debug, "oxide_copilot::ai", "request sent");
+
+        let all = logger.recent(None, None, 10);
+        assert_eq!(all.len(), 4);
+        assert_eq!(all[0].message, "request sent");
+
+        let warnings = logger.recent(Some(Level::Warn), None, 10);
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].level, "ERROR");
+
+        let guardian = logger.recent(None, Some("oxide_guardian"), 1);
+        assert_eq!(guardian.len(), 1);
+        assert_eq!(guardian[0].message, "scan failed");
+
+        // Capacity 4: the oldest entry is evicted
+        emit(&logger, Level::Info, "oxide_core::config", "loaded");
+        assert!(logger
+            .recent(None, None, 10)
+            .iter()
+            .all(|e| e.message != "scan started"));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn files_rotate_and_keep_json_lines() {
+        let opts = options("rotate");
+        let dir = opts.dir.clone();
+        let logger = AppLogger::new(opts, None);
+        for i in 0..20 {
+            emit(
+                &logger,
+                Level::Info,
+                "oxide_core::test",
+                &format!("line {i}"),
+            );
+        }
+
+        let files = logger.files();
+        assert_eq!(files.len(), 3);
+        assert!(!rotated_path(&dir, 3).exists());
+
+        let active = fs::read_to_string(rotated_path(&dir, 0)).unwrap();
+        let last: LogEntry = serde_json::from_str(active.lines().last().unwrap()).unwrap();
+        assert_eq!(last.message, "line 19");
+        assert!(fs::metadata(rotated_path(&dir, 1)).unwrap().len() <= 200);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
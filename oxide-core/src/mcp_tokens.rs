@@ -0,0 +1,330 @@
+//! Per-client bearer tokens for the embedded MCP server.
+//!
+//! Each client gets its own token with a [`McpScope`] and an expiry, and can
+//! be rotated or revoked on its own. The secret is shown once when it is
+//! issued; the store keeps only its SHA-256 digest, so the token file holds
+//! nothing a reader could authenticate with. (The `SecurityManager` key is
+//! generated per run, so secrets encrypted with it would not survive a
+//! restart.) After a rotation the previous secret keeps working for
+//! [`ROTATION_GRACE_SECS`] so clients can switch over.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chrono::{DateTime, Duration, Utc};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+pub const TOKENS_PATH: &str = "./data/mcp_tokens.json";
+pub const DEFAULT_TTL_DAYS: u32 = 90;
+pub const MAX_TTL_DAYS: u32 = 365;
+/// How long the replaced secret stays valid after a rotation.
+pub const ROTATION_GRACE_SECS: i64 = 600;
+const TOKEN_PREFIX: &str = "oxmcp_";
+/// `last_used_at` is updated at most this often, to limit disk writes.
+const TOUCH_INTERVAL_SECS: i64 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum McpScope {
+    /// Health, event streams and metrics
+    ReadOnly,
+    /// Everything, including tool calls
+    ToolExecution,
+}
+
+impl McpScope {
+    pub fn allows(self, required: McpScope) -> bool {
+        self == McpScope::ToolExecution || required == McpScope::ReadOnly
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpToken {
+    pub id: String,
+    /// Name of the client the token was issued to
+    pub client: String,
+    pub scope: McpScope,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub rotated_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl McpToken {
+    pub fn active(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && now < self.expires_at
+    }
+}
+
+/// A newly issued or rotated token; `secret` is not stored anywhere.
+#[derive(Debug, Clone, Serialize)]
+pub struct IssuedMcpToken {
+    pub token: McpToken,
+    pub secret: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredToken {
+    #[serde(flatten)]
+    token: McpToken,
+    hash: String,
+    /// Digest of the secret replaced by the last rotation
+    previous_hash: Option<String>,
+    previous_valid_until: Option<DateTime<Utc>>,
+}
+
+impl StoredToken {
+    fn matches(&self, hash: &str, now: DateTime<Utc>) -> bool {
+        self.hash == hash
+            || (self.previous_hash.as_deref() == Some(hash)
+                && self.previous_valid_until.is_some_and(|until| now < until))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthError {
+    Unknown,
+    Expired,
+    Revoked,
+    /// Valid token without the scope the request needs
+    Forbidden,
+}
+
+pub struct McpTokenStore {
+    path: PathBuf,
+    tokens: Mutex<Vec<StoredToken>>,
+}
+
+impl McpTokenStore {
+    /// Tokens saved at `path`; a missing or unreadable file is an empty store.
+    pub fn load(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let tokens = fs::read_to_string(&path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            tokens: Mutex::new(tokens),
+        }
+    }
+
+    /// Whether any token was ever issued. Once one was, the server requires
+    /// authentication even after every token is revoked.
+    pub fn has_tokens(&self) -> bool {
+        !self.lock().is_empty()
+    }
+
+    pub fn list(&self) -> Vec<McpToken> {
+        self.lock().iter().map(|t| t.token.clone()).collect()
+    }
+
+    pub fn issue(
+        &self,
+        client: &str,
+        scope: McpScope,
+        ttl_days: Option<u32>,
+    ) -> Result<IssuedMcpToken, String> {
+        let client = client.trim();
+        if client.is_empty() {
+            return Err("Client name is required".to_string());
+        }
+        let ttl = ttl(ttl_days)?;
+        let now = Utc::now();
+        let secret = generate_secret();
+        let token = McpToken {
+            id: uuid::Uuid::new_v4().to_string(),
+            client: client.to_string(),
+            scope,
+            created_at: now,
+            expires_at: now + ttl,
+            rotated_at: None,
+            revoked_at: None,
+            last_used_at: None,
+        };
+        let mut tokens = self.lock();
+        tokens.push(StoredToken {
+            token: token.clone(),
+            hash: digest(&secret),
+            previous_hash: None,
+            previous_valid_until: None,
+        });
+        self.save(&tokens)?;
+        Ok(IssuedMcpToken { token, secret })
+    }
+
+    /// Replace the secret of an active token and extend its expiry.
+    pub fn rotate(&self, id: &str, ttl_days: Option<u32>) -> Result<IssuedMcpToken, String> {
+        let ttl = ttl(ttl_days)?;
+        let now = Utc::now();
+        let secret = generate_secret();
+        let mut tokens = self.lock();
+        let stored = find(&mut tokens, id)?;
+        if stored.token.revoked_at.is_some() {
+            return Err(format!("MCP token {id} is revoked"));
+        }
+        stored.previous_hash = Some(std::mem::replace(&mut stored.hash, digest(&secret)));
+        stored.previous_valid_until =
+            Some((now + Duration::seconds(ROTATION_GRACE_SECS)).min(stored.token.expires_at));
+        stored.token.rotated_at = Some(now);
+        stored.token.expires_at = now + ttl;
+        let token = stored.token.clone();
+        self.save(&tokens)?;
+        Ok(IssuedMcpToken { token, secret })
+    }
+
+    pub fn revoke(&self, id: &str) -> Result<McpToken, String> {
+        let mut tokens = self.lock();
+        let stored = find(&mut tokens, id)?;
+        stored.token.revoked_at.get_or_insert_with(Utc::now);
+        stored.previous_hash = None;
+        let token = stored.token.clone();
+        self.save(&tokens)?;
+        Ok(token)
+    }
+
+    /// The token `secret` belongs to, if it grants `required`.
+    pub fn authenticate(&self, secret: &str, required: McpScope) -> Result<McpToken, AuthError> {
+        let now = Utc::now();
+        let hash = digest(secret);
+        let mut tokens = self.lock();
+        let stored = tokens
+            .iter_mut()
+            .find(|t| t.matches(&hash, now))
+            .ok_or(AuthError::Unknown)?;
+        if stored.token.revoked_at.is_some() {
+            return Err(AuthError::Revoked);
+        }
+        if now >= stored.token.expires_at {
+            return Err(AuthError::Expired);
+        }
+        if !stored.token.scope.allows(required) {
+            return Err(AuthError::Forbidden);
+        }
+        let token = stored.token.clone();
+        if token
+            .last_used_at
+            .is_none_or(|at| now - at >= Duration::seconds(TOUCH_INTERVAL_SECS))
+        {
+            stored.token.last_used_at = Some(now);
+            if let Err(e) = self.save(&tokens) {
+                log::warn!("{e}");
+            }
+        }
+        Ok(token)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<StoredToken>> {
+        self.tokens.lock().unwrap_or_else(|p| p.into_inner())
+    }
+
+    fn save(&self, tokens: &[StoredToken]) -> Result<(), String> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create {dir:?}: {e}"))?;
+        }
+        let json = serde_json::to_string_pretty(tokens).map_err(|e| e.to_string())?;
+        let tmp = self.path.with_extension("json.tmp");
+        fs::write(&tmp, json)
+            .and_then(|_| fs::rename(&tmp, &self.path))
+            .map_err(|e| format!("Failed to save MCP tokens to {:?}: {e}", self.path))
+    }
+}
+
+fn find<'a>(tokens: &'a mut [StoredToken], id: &str) -> Result<&'a mut StoredToken, String> {
+    tokens
+        .iter_mut()
+        .find(|t| t.token.id == id)
+        .ok_or_else(|| format!("No MCP token with id {id}"))
+}
+
+fn ttl(days: Option<u32>) -> Result<Duration, String> {
+    match days.unwrap_or(DEFAULT_TTL_DAYS) {
+        0 => Err("Token lifetime must be at least one day".to_string()),
+        d if d > MAX_TTL_DAYS => Err(format!(
+            "Token lifetime must be at most {MAX_TTL_DAYS} days"
+        )),
+        d => Ok(Duration::days(i64::from(d))),
+    }
+}
+
+fn generate_secret() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    format!("{TOKEN_PREFIX}{}", URL_SAFE_NO_PAD.encode(bytes))
+}
+
+fn digest(secret: &str) -> String {
+    format!("{:x}", Sha256::digest(secret.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn issue_rotate_revoke_and_scopes() {
+        let dir = std::env::temp_dir().join(format!("oxide-mcp-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("mcp_tokens.json");
+        let store = McpTokenStore::load(&path);
+        assert!(!store.has_tokens());
+
+        let reader = store.issue("dashboard", McpScope::ReadOnly, None).unwrap();
+        assert!(reader.secret.starts_with(TOKEN_PREFIX));
+        assert!(store
+            .authenticate(&reader.secret, McpScope::ReadOnly)
+            .is_ok());
+        assert_eq!(
+            store
+                .authenticate(&reader.secret, McpScope::ToolExecution)
+                .unwrap_err(),
+            AuthError::Forbidden
+        );
+        assert!(!fs::read_to_string(&path).unwrap().contains(&reader.secret));
+
+        let agent = store
+            .issue("agent", McpScope::ToolExecution, Some(1))
+            .unwrap();
+        let rotated = store.rotate(&agent.token.id, None).unwrap();
+        assert!(rotated.token.expires_at > agent.token.expires_at);
+        // Both secrets work during the grace period
+        assert!(store
+            .authenticate(&agent.secret, McpScope::ToolExecution)
+            .is_ok());
+        assert!(store
+            .authenticate(&rotated.secret, McpScope::ToolExecution)
+            .is_ok());
+
+        store.revoke(&agent.token.id).unwrap();
+        assert_eq!(
+            store
+                .authenticate(&rotated.secret, McpScope::ReadOnly)
+                .unwrap_err(),
+            AuthError::Revoked
+        );
+        assert_eq!(
+            store
+                .authenticate("oxmcp_nope", McpScope::ReadOnly)
+                .unwrap_err(),
+            AuthError::Unknown
+        );
+        assert!(store.rotate(&agent.token.id, None).is_err());
+
+        let reloaded = McpTokenStore::load(&path);
+        assert_eq!(reloaded.list().len(), 2);
+        assert!(reloaded
+            .authenticate(&reader.secret, McpScope::ReadOnly)
+            .is_ok());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn lifetime_is_bounded() {
+        assert!(ttl(Some(0)).is_err());
+        assert!(ttl(Some(MAX_TTL_DAYS + 1)).is_err());
+        assert_eq!(ttl(None).unwrap(), Duration::days(90));
+    }
+}
@@ -0,0 +1,379 @@
+//! Process-wide switch for outbound network access.
+//!
+//! When offline mode is on, every call site that talks to a remote service
+//! (cloud LLMs, VirusTotal, embeddings, OAuth refresh) must fail fast via
+//! [`ensure_online`]. Clients built with [`http_client`] additionally route
+//! non-loopback requests to an unreachable proxy, so a forgotten check still
+//! cannot leak data. Loopback targets (local LLM servers) are always allowed.
+//!
+//! The same clients carry the transport settings from [`NetworkConfig`]: an
+//! HTTP or SOCKS proxy and extra trusted CA certificates. Until
+//! [`configure_transport`] runs, they are read from `OXIDE_PROXY_URL`,
+//! `OXIDE_PROXY_USERNAME`, `OXIDE_NO_PROXY` and `OXIDE_CA_CERTS`. Proxy
+//! changes apply to existing clients; certificates only to clients built
+//! afterwards.
+
+use crate::config::NetworkConfig;
+use chrono::{DateTime, Utc};
+use log::{info, warn};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock, RwLock};
+use thiserror::Error;
+
+/// Discard port on loopback; connections are refused immediately.
+const BLACKHOLE_PROXY: &str = "http://127.0.0.1:9";
+
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+static BLOCKED_REQUESTS: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Default)]
+struct StatusDetails {
+    changed_at: Option<DateTime<Utc>>,
+    last_blocked: Option<String>,
+}
+
+fn details() -> &'static Mutex<StatusDetails> {
+    static DETAILS: OnceLock<Mutex<StatusDetails>> = OnceLock::new();
+    DETAILS.get_or_init(|| Mutex::new(StatusDetails::default()))
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Offline mode is enabled; outbound request to {0} was blocked")]
+pub struct OfflineError(pub String);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkStatus {
+    pub offline_mode: bool,
+    pub blocked_requests: u64,
+    pub last_blocked: Option<String>,
+    pub changed_at: Option<DateTime<Utc>>,
+    // Configured proxy without credentials
+    pub proxy: Option<String>,
+    pub custom_ca_certificates: usize,
+}
+
+pub fn set_offline_mode(enabled: bool) {
+    let previous = OFFLINE.swap(enabled, Ordering::SeqCst);
+    if previous != enabled {
+        if let Ok(mut d) = details().lock() {
+            d.changed_at = Some(Utc::now());
+        }
+        info!(
+            "Offline mode {}",
+            if enabled { "enabled" } else { "disabled" }
+        );
+    }
+}
+
+pub fn is_offline() -> bool {
+    OFFLINE.load(Ordering::SeqCst)
+}
+
+pub fn status() -> NetworkStatus {
+    let (last_blocked, changed_at) = details()
+        .lock()
+        .map(|d| (d.last_blocked.clone(), d.changed_at))
+        .unwrap_or_default();
+    let (proxy, custom_ca_certificates) = {
+        let t = read_transport();
+        (
+            t.proxy.as_ref().map(|u| describe(u.as_str())),
+            t.ca_certificates.len(),
+        )
+    };
+    NetworkStatus {
+        offline_mode: is_offline(),
+        blocked_requests: BLOCKED_REQUESTS.load(Ordering::Relaxed),
+        last_blocked,
+        changed_at,
+        proxy,
+        custom_ca_certificates,
+    }
+}
+
+/// Fail with [`OfflineError`] if `target` (a URL or a service label) may not
+/// be contacted right now. Loopback URLs are always allowed.
+pub fn ensure_online(target: &str) -> Result<(), OfflineError> {
+    if !is_offline() || is_local_target(target) {
+        return Ok(());
+    }
+    let target = describe(target);
+    record_blocked(&target);
+    Err(OfflineError(target))
+}
+
+/// Strip query strings and credentials so API keys never reach logs.
+fn describe(target: &str) -> String {
+    match Url::parse(target) {
+        Ok(url) => format!(
+            "{}://{}{}",
+            url.scheme(),
+            url.host_str().unwrap_or_default(),
+            url.path()
+        ),
+        Err(_) => target.to_string(),
+    }
+}
+
+/// True for URLs pointing at localhost or a loopback address.
+pub fn is_local_target(target: &str) -> bool {
+    Url::parse(target)
+        .map(|u| is_local_url(&u))
+        .unwrap_or(false)
+}
+
+fn is_local_url(url: &Url) -> bool {
+    match url.host_str() {
+        Some(host) => {
+            let host = host.trim_start_matches('[').trim_end_matches(']');
+            host.eq_ignore_ascii_case("localhost")
+                || host
+                    .parse::<IpAddr>()
+                    .map(|ip| ip.is_loopback())
+                    .unwrap_or(false)
+        }
+        None => false,
+    }
+}
+
+fn record_blocked(target: &str) {
+    BLOCKED_REQUESTS.fetch_add(1, Ordering::Relaxed);
+    warn!("Offline mode: blocked outbound request to {target}");
+    if let Ok(mut d) = details().lock() {
+        d.last_blocked = Some(target.to_string());
+    }
+}
+
+#[derive(Default)]
+struct Transport {
+    // Credentials are embedded in the URL
+    proxy: Option<Url>,
+    no_proxy: Vec<String>,
+    ca_certificates: Vec<reqwest::Certificate>,
+}
+
+impl Transport {
+    fn from_config(config: &NetworkConfig) -> Result<Self, String> {
+        let proxy = match config.proxy_url.as_deref().map(str::trim) {
+            Some(raw) if !raw.is_empty() => {
+                let mut url =
+                    Url::parse(raw).map_err(|e| format!("Invalid proxy URL '{raw}': {e}"))?;
+                if let Some(user) = &config.proxy_username {
+                    let password = std::env::var("OXIDE_PROXY_PASSWORD").ok();
+                    url.set_username(user)
+                        .and_then(|_| url.set_password(password.as_deref()))
+                        .map_err(|_| format!("Proxy URL '{raw}' cannot carry credentials"))?;
+                }
+                Some(url)
+            }
+            _ => None,
+        };
+        let mut ca_certificates = Vec::new();
+        for path in &config.ca_cert_paths {
+            ca_certificates.extend(load_certificates(path)?);
+        }
+        Ok(Self {
+            proxy,
+            no_proxy: config
+                .no_proxy
+                .iter()
+                .map(|h| h.trim().trim_start_matches('.').to_ascii_lowercase())
+                .filter(|h| !h.is_empty())
+                .collect(),
+            ca_certificates,
+        })
+    }
+
+    fn bypasses_proxy(&self, url: &Url) -> bool {
+        let host = url.host_str().unwrap_or_default().to_ascii_lowercase();
+        self.no_proxy.iter().any(|entry| {
+            entry == "*"
+                || host == *entry
+                || host
+                    .strip_suffix(entry.as_str())
+                    .is_some_and(|rest| rest.ends_with('.'))
+        })
+    }
+}
+
+/// PEM bundles may hold several certificates; anything else is tried as DER.
+fn load_certificates(path: &str) -> Result<Vec<reqwest::Certificate>, String> {
+    let bytes =
+        std::fs::read(path).map_err(|e| format!("Failed to read CA certificate {path}: {e}"))?;
+    match reqwest::Certificate::from_pem_bundle(&bytes) {
+        Ok(certs) if !certs.is_empty() => Ok(certs),
+        _ => reqwest::Certificate::from_der(&bytes)
+            .map(|cert| vec![cert])
+            .map_err(|e| format!("Invalid CA certificate {path}: {e}")),
+    }
+}
+
+fn env_transport_config() -> NetworkConfig {
+    let var = |key: &str| std::env::var(key).ok().filter(|v| !v.trim().is_empty());
+    let list = |key: &str| {
+        var(key)
+            .map(|v| {
+                v.split([',', ';'])
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    NetworkConfig {
+        proxy_url: var("OXIDE_PROXY_URL"),
+        proxy_username: var("OXIDE_PROXY_USERNAME"),
+        no_proxy: list("OXIDE_NO_PROXY"),
+        ca_cert_paths: list("OXIDE_CA_CERTS"),
+    }
+}
+
+fn transport() -> &'static RwLock<Transport> {
+    static TRANSPORT: OnceLock<RwLock<Transport>> = OnceLock::new();
+    TRANSPORT.get_or_init(|| {
+        let transport = Transport::from_config(&env_transport_config()).unwrap_or_else(|e| {
+            warn!("Ignoring proxy/CA environment settings: {e}");
+            Transport::default()
+        });
+        RwLock::new(transport)
+    })
+}
+
+fn read_transport() -> std::sync::RwLockReadGuard<'static, Transport> {
+    transport()
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Apply proxy and CA settings. On error the previous settings stay active.
+pub fn configure_transport(config: &NetworkConfig) -> Result<(), String> {
+    let next = Transport::from_config(config)?;
+    info!(
+        "Outbound transport: proxy {}, {} custom CA certificate(s)",
+        next.proxy
+            .as_ref()
+            .map(|u| describe(u.as_str()))
+            .unwrap_or_else(|| "from environment".to_string()),
+        next.ca_certificates.len()
+    );
+    *transport()
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = next;
+    Ok(())
+}
+
+fn route(url: &Url) -> Option<Url> {
+    if is_local_url(url) {
+        return None;
+    }
+    if is_offline() {
+        record_blocked(&describe(url.as_str()));
+        return Url::parse(BLACKHOLE_PROXY).ok();
+    }
+    let t = read_transport();
+    if t.bypasses_proxy(url) {
+        None
+    } else if let Some(proxy) = &t.proxy {
+        Some(proxy.clone())
+    } else {
+        env_proxy(url)
+    }
+}
+
+/// Builder with the offline guard, proxy and custom CA certificates
+/// installed. Prefer this over `reqwest::Client::builder()` for anything that
+/// may leave the machine.
+pub fn client_builder() -> reqwest::ClientBuilder {
+    let mut builder = reqwest::Client::builder().proxy(reqwest::Proxy::custom(route));
+    for cert in &read_transport().ca_certificates {
+        builder = builder.add_root_certificate(cert.clone());
+    }
+    builder
+}
+
+/// Blocking counterpart of [`client_builder`].
+pub fn blocking_client_builder() -> reqwest::blocking::ClientBuilder {
+    let mut builder = reqwest::blocking::Client::builder().proxy(reqwest::Proxy::custom(route));
+    for cert in &read_transport().ca_certificates {
+        builder = builder.add_root_certificate(cert.clone());
+    }
+    builder
+}
+
+/// Drop-in replacement for `reqwest::Client::new()` that honours offline mode.
+pub fn http_client() -> reqwest::Client {
+    client_builder().build().unwrap_or_else(|e| {
+        warn!("Failed to build guarded HTTP client, falling back to default: {e}");
+        reqwest::Client::new()
+    })
+}
+
+/// Installing a custom proxy disables reqwest's own environment lookup, so
+/// honour the usual proxy variables here.
+fn env_proxy(url: &Url) -> Option<Url> {
+    let keys: &[&str] = match url.scheme() {
+        "https" => &["HTTPS_PROXY", "https_proxy", "ALL_PROXY", "all_proxy"],
+        _ => &["HTTP_PROXY", "http_proxy", "ALL_PROXY", "all_proxy"],
+    };
+    keys.iter()
+        .filter_map(|key| std::env::var(key).ok())
+        .find(|v| !v.trim().is_empty())
+        .and_then(|v| Url::parse(v.trim()).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loopback_targets_are_local() {
+        assert!(is_local_target("http://127.0.0.1:1234/v1"));
+        assert!(is_local_target("http://localhost:11434"));
+        assert!(is_local_target("http://[::1]:8080/"));
+        assert!(!is_local_target("https://api.openai.com/v1"));
+        assert!(!is_local_target("virustotal"));
+    }
+
+    #[test]
+    fn offline_mode_blocks_remote_targets() {
+        set_offline_mode(true);
+        let before = status().blocked_requests;
+        let err = ensure_online("https://www.virustotal.com/api/v3/files/x").unwrap_err();
+        assert!(err.to_string().contains("virustotal.com"));
+        let err = ensure_online("https://example.com/models?key=secret").unwrap_err();
+        assert_eq!(err.0, "https://example.com/models");
+        assert!(ensure_online("http://127.0.0.1:1234/v1").is_ok());
+        let status = status();
+        assert!(status.offline_mode);
+        assert!(status.blocked_requests > before);
+
+        set_offline_mode(false);
+        assert!(ensure_online("https://api.openai.com/v1").is_ok());
+    }
+
+    #[test]
+    fn proxy_bypass_matches_hosts_and_suffixes() {
+        let transport = Transport::from_config(&NetworkConfig {
+            proxy_url: Some("http://proxy.corp:3128".to_string()),
+            no_proxy: vec![".internal.corp".to_string(), "api.openai.com".to_string()],
+            ..NetworkConfig::default()
+        })
+        .unwrap();
+        let bypass = |u: &str| transport.bypasses_proxy(&Url::parse(u).unwrap());
+        assert!(bypass("https://api.openai.com/v1"));
+        assert!(bypass("https://llm.internal.corp/v1"));
+        assert!(!bypass("https://notinternal.corp/"));
+        assert!(!bypass("https://www.virustotal.com/api/v3"));
+
+        let err = Transport::from_config(&NetworkConfig {
+            ca_cert_paths: vec!["/nonexistent/oxide-ca.pem".to_string()],
+            ..NetworkConfig::default()
+        })
+        .err()
+        .unwrap();
+        assert!(err.contains("oxide-ca.pem"));
+    }
+}
@@ -0,0 +1,436 @@
+//! First-run setup wizard state.
+//!
+//! The desktop app detects what the machine offers ([`Capabilities`]),
+//! checks which AI credentials exist ([`credential_statuses`]) and derives a
+//! [`Recommendation`] from both. Wizard progress is persisted in
+//! `./data/onboarding.json` so the wizard is shown only until it finishes.
+
+use crate::config::OxidePilotConfig;
+use crate::gemini_auth::GeminiAuth;
+use crate::qwen_auth::QwenAuth;
+use crate::{openai_auth, openai_key};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub const ONBOARDING_STATE_PATH: &str = "./data/onboarding.json";
+
+/// Local runtimes probed during detection: name, CLI binary, default port.
+const LOCAL_RUNTIMES: [(&str, &str, u16); 2] =
+    [("ollama", "ollama", 11434), ("lm_studio", "lms", 1234)];
+
+const PORT_PROBE_TIMEOUT: Duration = Duration::from_millis(300);
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct LocalRuntime {
+    pub name: String,
+    /// CLI found on `PATH`
+    pub installed: bool,
+    /// Something is listening on the runtime's default port
+    pub running: bool,
+    pub endpoint: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    pub input_devices: Vec<String>,
+    pub gpus: Vec<String>,
+    pub local_runtimes: Vec<LocalRuntime>,
+    /// The OS credential store accepts reads
+    pub keyring: bool,
+}
+
+impl Capabilities {
+    pub fn has_microphone(&self) -> bool {
+        !self.input_devices.is_empty()
+    }
+
+    fn ollama(&self) -> Option<&LocalRuntime> {
+        self.local_runtimes
+            .iter()
+            .find(|r| r.name == "ollama" && (r.installed || r.running))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CredentialState {
+    Missing,
+    Configured,
+    Expired,
+    Error,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CredentialStatus {
+    pub provider: String,
+    pub state: CredentialState,
+    pub detail: String,
+}
+
+impl CredentialStatus {
+    /// Map a `get_auth_status` string of the auth modules to a state.
+    fn from_status<E: std::fmt::Display>(provider: &str, status: Result<String, E>) -> Self {
+        let (state, detail) = match status {
+            Ok(status) => {
+                let state = match status.as_str() {
+                    "Not authenticated" => CredentialState::Missing,
+                    s if s.contains("Expired") => CredentialState::Expired,
+                    s if s.contains("Error") || s.contains("Invalid") => CredentialState::Error,
+                    _ => CredentialState::Configured,
+                };
+                (state, status)
+            }
+            Err(e) => (CredentialState::Error, e.to_string()),
+        };
+        Self {
+            provider: provider.to_string(),
+            state,
+            detail,
+        }
+    }
+
+    fn is_configured(statuses: &[Self], provider: &str) -> bool {
+        statuses
+            .iter()
+            .any(|s| s.provider == provider && s.state == CredentialState::Configured)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum OnboardingStep {
+    Welcome,
+    Capabilities,
+    Credentials,
+    AiProvider,
+    Voice,
+    Guardian,
+    Finish,
+}
+
+impl OnboardingStep {
+    pub const ALL: [Self; 7] = [
+        Self::Welcome,
+        Self::Capabilities,
+        Self::Credentials,
+        Self::AiProvider,
+        Self::Voice,
+        Self::Guardian,
+        Self::Finish,
+    ];
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StepOutcome {
+    Completed,
+    Skipped,
+}
+
+/// Persisted wizard progress.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct OnboardingProgress {
+    #[serde(default)]
+    pub steps: BTreeMap<OnboardingStep, StepOutcome>,
+    /// Configuration chosen in the wizard, validated
+    #[serde(default)]
+    pub config: Option<OxidePilotConfig>,
+    #[serde(default)]
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl OnboardingProgress {
+    /// Saved progress, or a fresh wizard when none exists or it is unreadable.
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create {parent:?}: {e}"))?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(path, json).map_err(|e| format!("Failed to save onboarding state: {e}"))
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished_at.is_some()
+    }
+
+    /// First step that is neither completed nor skipped.
+    pub fn next_step(&self) -> Option<OnboardingStep> {
+        OnboardingStep::ALL
+            .into_iter()
+            .find(|step| !self.steps.contains_key(step))
+    }
+
+    /// Record `step`. A `config` replaces the stored one after validation.
+    /// `Finish` cannot be skipped and needs every earlier step and a config.
+    pub fn complete(
+        &mut self,
+        step: OnboardingStep,
+        outcome: StepOutcome,
+        config: Option<OxidePilotConfig>,
+    ) -> Result<(), String> {
+        if let Some(config) = config {
+            config.validate()?;
+            self.config = Some(config);
+        }
+        if step == OnboardingStep::Finish {
+            if outcome == StepOutcome::Skipped {
+                return Err("The finish step cannot be skipped".to_string());
+            }
+            if let Some(pending) = self.next_step().filter(|s| *s != OnboardingStep::Finish) {
+                return Err(format!("Step {pending:?} is not done yet"));
+            }
+            if self.config.is_none() {
+                return Err("Choose a configuration before finishing".to_string());
+            }
+            self.finished_at = Some(Utc::now());
+        }
+        self.steps.insert(step, outcome);
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Recommendation {
+    pub config: OxidePilotConfig,
+    /// Why settings were chosen and what the user may want to fix
+    pub notes: Vec<String>,
+}
+
+/// Configuration suggested for this machine. Keys are not part of the
+/// config: providers read them from the keyring or environment, so the
+/// Google and OpenAI entries only carry a placeholder.
+pub fn recommend(capabilities: &Capabilities, credentials: &[CredentialStatus]) -> Recommendation {
+    let mut notes = Vec::new();
+    let mut providers = serde_json::Map::new();
+
+    if CredentialStatus::is_configured(credentials, "gemini") {
+        providers.insert("google".into(), serde_json::json!({ "api_key": "keyring" }));
+    }
+    if CredentialStatus::is_configured(credentials, "openai") {
+        providers.insert("openai".into(), serde_json::json!({ "api_key": "keyring" }));
+    }
+    match capabilities.ollama() {
+        Some(ollama) => {
+            providers.insert(
+                "ollama".into(),
+                serde_json::json!({ "url": ollama.endpoint }),
+            );
+            if !ollama.running {
+                notes.push(
+                    "Ollama is installed but not running; start it with `ollama serve`".into(),
+                );
+            }
+            if capabilities.gpus.is_empty() {
+                notes.push(
+                    "No GPU detected: local models will run on the CPU and respond slowly".into(),
+                );
+            }
+        }
+        None if !capabilities.gpus.is_empty() => notes
+            .push("A GPU is available: installing Ollama enables private, offline answers".into()),
+        None => {}
+    }
+    if capabilities
+        .local_runtimes
+        .iter()
+        .any(|r| r.name == "lm_studio" && r.running)
+    {
+        notes.push("LM Studio is running; manage it from the Local LLM panel".into());
+    }
+
+    let copilot_enabled = !providers.is_empty();
+    if !copilot_enabled {
+        notes.push("No AI provider is available: the copilot stays off until one is set up".into());
+    }
+    if !capabilities.has_microphone() {
+        notes.push("No microphone found: the wake word is inactive, type questions instead".into());
+    }
+    if !capabilities.keyring {
+        notes.push(
+            "The OS credential store is unavailable: provide API keys through environment variables"
+                .into(),
+        );
+    }
+
+    let config = serde_json::json!({
+        "guardian": {
+            "enabled": true,
+            "monitor_interval_secs": 10,
+            "antivirus_enabled": true,
+            "yara_enabled": true,
+        },
+        "copilot": {
+            "enabled": copilot_enabled,
+            "wake_word": "Hey Oxide",
+        },
+        "ai_providers": providers,
+    });
+    let config = serde_json::from_value(config).expect("recommended config matches the schema");
+    Recommendation { config, notes }
+}
+
+fn find_on_path(binary: &str) -> Option<PathBuf> {
+    let names = if cfg!(windows) {
+        vec![format!("{binary}.exe"), binary.to_string()]
+    } else {
+        vec![binary.to_string()]
+    };
+    std::env::var_os("PATH").and_then(|paths| {
+        std::env::split_paths(&paths)
+            .flat_map(|dir| names.iter().map(move |name| dir.join(name)))
+            .find(|candidate| candidate.is_file())
+    })
+}
+
+async fn port_open(port: u16) -> bool {
+    matches!(
+        tokio::time::timeout(
+            PORT_PROBE_TIMEOUT,
+            tokio::net::TcpStream::connect(("127.0.0.1", port)),
+        )
+        .await,
+        Ok(Ok(_))
+    )
+}
+
+/// Ollama and LM Studio: installed CLI and listening server.
+pub async fn detect_local_runtimes() -> Vec<LocalRuntime> {
+    let mut runtimes = Vec::new();
+    for (name, binary, port) in LOCAL_RUNTIMES {
+        runtimes.push(LocalRuntime {
+            name: name.to_string(),
+            installed: find_on_path(binary).is_some(),
+            running: port_open(port).await,
+            endpoint: format!("http://127.0.0.1:{port}"),
+        });
+    }
+    runtimes
+}
+
+/// Whether the OS credential store can be read. A missing entry counts as
+/// available; a locked or absent store does not.
+pub fn keyring_available() -> bool {
+    keyring::Entry::new("oxide_pilot_onboarding", "probe")
+        .map(|entry| matches!(entry.get_password(), Ok(_) | Err(keyring::Error::NoEntry)))
+        .unwrap_or(false)
+}
+
+/// Credential state of each cloud provider, read locally without network
+/// requests.
+pub async fn credential_statuses() -> Vec<CredentialStatus> {
+    let gemini = match GeminiAuth::new().get_api_key_from_env_or_store().await {
+        Ok(Some(_)) => Ok("API key".to_string()),
+        Ok(None) => Ok("Not authenticated".to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+    let openai = match openai_key::get_api_key().await {
+        Ok(Some(key)) if !key.trim().is_empty() => Ok("API key".to_string()),
+        _ => openai_auth::get_auth_status()
+            .await
+            .map_err(|e| e.to_string()),
+    };
+    vec![
+        CredentialStatus::from_status("gemini", gemini),
+        CredentialStatus::from_status("openai", openai),
+        CredentialStatus::from_status("qwen", QwenAuth::new().get_auth_status().await),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(provider: &str, state: CredentialState) -> CredentialStatus {
+        CredentialStatus {
+            provider: provider.to_string(),
+            state,
+            detail: String::new(),
+        }
+    }
+
+    #[test]
+    fn recommendation_follows_capabilities_and_credentials() {
+        let bare = recommend(&Capabilities::default(), &[]);
+        assert!(!bare.config.copilot.enabled);
+        assert!(bare.config.validate().is_ok());
+        assert!(bare.notes.iter().any(|n| n.contains("No microphone")));
+
+        let capabilities = Capabilities {
+            input_devices: vec!["USB Mic".to_string()],
+            gpus: vec!["RTX 4070".to_string()],
+            local_runtimes: vec![LocalRuntime {
+                name: "ollama".to_string(),
+                installed: true,
+                running: true,
+                endpoint: "http://127.0.0.1:11434".to_string(),
+            }],
+            keyring: true,
+        };
+        let credentials = [
+            status("gemini", CredentialState::Configured),
+            status("openai", CredentialState::Expired),
+        ];
+        let rec = recommend(&capabilities, &credentials);
+        assert!(rec.config.copilot.enabled);
+        assert!(rec.config.ai_providers.google.is_some());
+        assert!(rec.config.ai_providers.openai.is_none());
+        assert_eq!(
+            rec.config.ai_providers.ollama.unwrap().url,
+            "http://127.0.0.1:11434"
+        );
+        assert!(rec.notes.is_empty());
+
+        assert_eq!(
+            CredentialStatus::from_status::<String>("qwen", Ok("OAuth Token Expired".into())).state,
+            CredentialState::Expired
+        );
+    }
+
+    #[test]
+    fn finish_requires_earlier_steps_and_a_config() {
+        let mut progress = OnboardingProgress::default();
+        assert_eq!(progress.next_step(), Some(OnboardingStep::Welcome));
+        assert!(progress
+            .complete(OnboardingStep::Finish, StepOutcome::Completed, None)
+            .is_err());
+
+        for step in &OnboardingStep::ALL[..6] {
+            let outcome = if *step == OnboardingStep::Voice {
+                StepOutcome::Skipped
+            } else {
+                StepOutcome::Completed
+            };
+            progress.complete(*step, outcome, None).unwrap();
+        }
+        assert_eq!(progress.next_step(), Some(OnboardingStep::Finish));
+        assert!(progress
+            .complete(OnboardingStep::Finish, StepOutcome::Completed, None)
+            .is_err());
+
+        let config = recommend(&Capabilities::default(), &[]).config;
+        progress
+            .complete(OnboardingStep::Finish, StepOutcome::Completed, Some(config))
+            .unwrap();
+        assert!(progress.is_finished());
+        assert_eq!(progress.next_step(), None);
+
+        let path =
+            std::env::temp_dir().join(format!("oxide-onboarding-{}.json", uuid::Uuid::new_v4()));
+        progress.save(&path).unwrap();
+        let loaded = OnboardingProgress::load(&path);
+        assert!(loaded.is_finished());
+        assert_eq!(loaded.steps[&OnboardingStep::Voice], StepOutcome::Skipped);
+        let _ = fs::remove_file(path);
+    }
+}
@@ -52,6 +52,8 @@ pub enum OpenAIAuthError {
     TokenRefresh(String),
     #[error("Refresh token not found")]
     RefreshTokenNotFound,
+    #[error(transparent)]
+    Offline(#[from] crate::network::OfflineError),
 }
 
 const OPENAI_AUTH_SERVICE_ID: &str = "oxide_pilot_openai";
@@ -182,6 +184,7 @@ pub async fn refresh_access_token() -> Result<String, OpenAIAuthError> {
         Some(token_url),
     );
 
+    crate::network::ensure_online("OAuth token endpoint")?;
     let token_response = client
         .exchange_refresh_token(&oauth2::RefreshToken::new(refresh_token_str))
         .request_async(oauth2::reqwest::async_http_client)
@@ -328,6 +331,7 @@ async fn handle_redirect(
         return Err(OpenAIAuthError::CsrfMismatch);
     }
 
+    crate::network::ensure_online("OAuth token endpoint")?;
     let token_response = client
         .exchange_code(AuthorizationCode::new(code.to_string()))
         .set_pkce_verifier(pkce_code_verifier)
@@ -1,6 +1,6 @@
 use crate::openai_key;
+use crate::types::ImageAttachment;
 use log::{error, info};
-use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
@@ -16,6 +16,10 @@ pub enum OpenAIClientError {
     Serialization(#[from] serde_json::Error),
     #[error("No response from API")]
     NoResponse,
+    #[error(transparent)]
+    Offline(#[from] crate::network::OfflineError),
+    #[error(transparent)]
+    Quota(#[from] crate::api_governor::QuotaError),
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -27,9 +31,12 @@ pub struct ChatMessage {
 #[derive(Serialize, Debug)]
 struct ChatCompletionRequest {
     model: String,
-    messages: Vec<ChatMessage>,
+    // Plain `ChatMessage`s, or content-part arrays when images are attached
+    messages: Vec<serde_json::Value>,
     temperature: Option<f32>,
     max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<serde_json::Value>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -49,6 +56,84 @@ pub async fn chat_completion(
     messages: Vec<ChatMessage>,
     temperature: Option<f32>,
     max_tokens: Option<u32>,
+) -> Result<String, OpenAIClientError> {
+    chat_completion_with_format(model, messages, temperature, max_tokens, None).await
+}
+
+/// Like [`chat_completion`], with an optional `response_format` for structured
+/// output (e.g. `{"type": "json_schema", "json_schema": {...}}`).
+pub async fn chat_completion_with_format(
+    model: &str,
+    messages: Vec<ChatMessage>,
+    temperature: Option<f32>,
+    max_tokens: Option<u32>,
+    response_format: Option<serde_json::Value>,
+) -> Result<String, OpenAIClientError> {
+    let messages = messages
+        .iter()
+        .map(serde_json::to_value)
+        .collect::<Result<Vec<_>, _>>()?;
+    send_chat(model, messages, temperature, max_tokens, response_format).await
+}
+
+/// Like [`chat_completion`], attaching `images` to the last user message.
+/// Requires a vision-capable model such as gpt-4o.
+pub async fn chat_completion_with_images(
+    model: &str,
+    messages: Vec<ChatMessage>,
+    images: &[ImageAttachment],
+    temperature: Option<f32>,
+    max_tokens: Option<u32>,
+) -> Result<String, OpenAIClientError> {
+    for image in images {
+        image.validate().map_err(OpenAIClientError::Api)?;
+    }
+    let messages = vision_messages(&messages, images)?;
+    send_chat(model, messages, temperature, max_tokens, None).await
+}
+
+fn vision_messages(
+    messages: &[ChatMessage],
+    images: &[ImageAttachment],
+) -> Result<Vec<serde_json::Value>, OpenAIClientError> {
+    let target = messages.iter().rposition(|m| m.role == "user");
+    messages
+        .iter()
+        .enumerate()
+        .map(|(i, message)| {
+            if Some(i) != target || images.is_empty() {
+                return Ok(serde_json::to_value(message)?);
+            }
+            let mut parts = vec![serde_json::json!({ "type": "text", "text": message.content })];
+            parts.extend(images.iter().map(|image| {
+                serde_json::json!({
+                    "type": "image_url",
+                    "image_url": { "url": image.data_url() }
+                })
+            }));
+            Ok(serde_json::json!({ "role": message.role, "content": parts }))
+        })
+        .collect()
+}
+
+async fn send_chat(
+    model: &str,
+    messages: Vec<serde_json::Value>,
+    temperature: Option<f32>,
+    max_tokens: Option<u32>,
+    response_format: Option<serde_json::Value>,
+) -> Result<String, OpenAIClientError> {
+    let prompt = crate::llm_audit::messages_text(&messages);
+    let request = request_chat(model, messages, temperature, max_tokens, response_format);
+    crate::llm_audit::audited("openai", Some(model), &prompt, request).await
+}
+
+async fn request_chat(
+    model: &str,
+    messages: Vec<serde_json::Value>,
+    temperature: Option<f32>,
+    max_tokens: Option<u32>,
+    response_format: Option<serde_json::Value>,
 ) -> Result<String, OpenAIClientError> {
     // Get API key from env or keyring
     let api_key = openai_key::get_api_key()
@@ -63,12 +148,15 @@ pub async fn chat_completion(
         .unwrap_or_else(|_| "https://api.openai.com/v1".to_string());
     let url = format!("{base_url}/chat/completions");
 
-    let client = Client::new();
+    crate::network::ensure_online(&url)?;
+    let _permit = crate::api_governor::acquire(&url).await?;
+    let client = crate::network::http_client();
     let request_body = ChatCompletionRequest {
         model: model.to_string(),
         messages,
         temperature,
         max_tokens,
+        response_format,
     };
 
     let response = client
@@ -126,4 +214,26 @@ mod tests {
         // Ensure we get a serde error kind
         let _ = err;
     }
+
+    #[test]
+    fn images_attach_to_last_user_message() {
+        let messages = vec![
+            ChatMessage {
+                role: "system".to_string(),
+                content: "Be brief".to_string(),
+            },
+            ChatMessage {
+                role: "user".to_string(),
+                content: "What is this dialog?".to_string(),
+            },
+        ];
+        let image = ImageAttachment::from_bytes(b"png", "image/png").unwrap();
+        let built = vision_messages(&messages, &[image]).unwrap();
+        assert_eq!(built[0]["content"], "Be brief");
+        assert_eq!(built[1]["content"][0]["text"], "What is this dialog?");
+        assert_eq!(
+            built[1]["content"][1]["image_url"]["url"],
+            "data:image/png;base64,cG5n"
+        );
+    }
 }
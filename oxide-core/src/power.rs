@@ -0,0 +1,155 @@
+//! Process-wide battery saver policy.
+//!
+//! A supervisor task reads the power source, turns it into a [`PowerPolicy`]
+//! with [`evaluate`] and publishes it through [`set_policy`]. Work that can
+//! wait for mains power reads [`policy`]: the metrics collector stretches its
+//! interval, Guardian postpones its scheduled baseline and integrity checks
+//! and threat consensus refuses to run via [`ensure_cloud_consensus`].
+//! Until a policy is published everything runs as on mains power.
+
+use crate::config::PowerConfig;
+use chrono::{DateTime, Utc};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::sync::{OnceLock, RwLock};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PowerSource {
+    /// Mains power, or a machine without a battery.
+    #[default]
+    Ac,
+    Battery,
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PowerPolicy {
+    pub source: PowerSource,
+    pub battery_percent: Option<f64>,
+    pub battery_saver: bool,
+    /// 1 outside battery saver.
+    pub metrics_interval_multiplier: u32,
+    pub defer_scheduled_scans: bool,
+    pub cloud_consensus_paused: bool,
+    /// When `battery_saver` last changed.
+    pub changed_at: Option<DateTime<Utc>>,
+}
+
+impl Default for PowerPolicy {
+    fn default() -> Self {
+        Self {
+            source: PowerSource::Ac,
+            battery_percent: None,
+            battery_saver: false,
+            metrics_interval_multiplier: 1,
+            defer_scheduled_scans: false,
+            cloud_consensus_paused: false,
+            changed_at: None,
+        }
+    }
+}
+
+fn current() -> &'static RwLock<PowerPolicy> {
+    static POLICY: OnceLock<RwLock<PowerPolicy>> = OnceLock::new();
+    POLICY.get_or_init(|| RwLock::new(PowerPolicy::default()))
+}
+
+/// Policy for `source` and the battery charge under `cfg`. An unknown
+/// source never starts battery saver.
+pub fn evaluate(
+    cfg: &PowerConfig,
+    source: PowerSource,
+    battery_percent: Option<f64>,
+) -> PowerPolicy {
+    let battery_saver = cfg.battery_aware()
+        && source == PowerSource::Battery
+        && battery_percent.is_none_or(|p| p <= cfg.activate_below_percent() as f64);
+    PowerPolicy {
+        source,
+        battery_percent,
+        battery_saver,
+        metrics_interval_multiplier: if battery_saver {
+            cfg.metrics_interval_multiplier()
+        } else {
+            1
+        },
+        defer_scheduled_scans: battery_saver && cfg.defer_scheduled_scans(),
+        cloud_consensus_paused: battery_saver && cfg.pause_cloud_consensus(),
+        changed_at: None,
+    }
+}
+
+/// Publish `policy`; returns whether battery saver turned on or off.
+pub fn set_policy(mut policy: PowerPolicy) -> bool {
+    let mut current = current().write().unwrap_or_else(|p| p.into_inner());
+    let toggled = current.battery_saver != policy.battery_saver;
+    policy.changed_at = if toggled {
+        Some(Utc::now())
+    } else {
+        current.changed_at
+    };
+    if toggled {
+        info!(
+            "Battery saver {}",
+            if policy.battery_saver {
+                "enabled"
+            } else {
+                "disabled"
+            }
+        );
+    }
+    *current = policy;
+    toggled
+}
+
+pub fn policy() -> PowerPolicy {
+    current().read().map(|p| p.clone()).unwrap_or_default()
+}
+
+/// Fail while battery saver pauses cloud threat consensus.
+pub fn ensure_cloud_consensus() -> Result<(), String> {
+    if policy().cloud_consensus_paused {
+        return Err(
+            "Battery saver is on; cloud threat consensus resumes on mains power".to_string(),
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn battery_saver_follows_source_and_threshold() {
+        let cfg = PowerConfig::default();
+        let saver = evaluate(&cfg, PowerSource::Battery, Some(80.0));
+        assert!(saver.battery_saver && saver.defer_scheduled_scans);
+        assert_eq!(saver.metrics_interval_multiplier, 4);
+        assert!(!evaluate(&cfg, PowerSource::Ac, Some(10.0)).battery_saver);
+        assert!(!evaluate(&cfg, PowerSource::Unknown, None).battery_saver);
+
+        let cfg = PowerConfig {
+            activate_below_percent: Some(30),
+            pause_cloud_consensus: Some(false),
+            ..Default::default()
+        };
+        assert!(!evaluate(&cfg, PowerSource::Battery, Some(31.0)).battery_saver);
+        let low = evaluate(&cfg, PowerSource::Battery, Some(30.0));
+        assert!(low.battery_saver && !low.cloud_consensus_paused);
+
+        let off = PowerConfig {
+            battery_aware: Some(false),
+            ..Default::default()
+        };
+        assert_eq!(
+            evaluate(&off, PowerSource::Battery, Some(5.0)),
+            PowerPolicy {
+                source: PowerSource::Battery,
+                battery_percent: Some(5.0),
+                ..Default::default()
+            }
+        );
+    }
+}
@@ -0,0 +1,581 @@
+//! Pseudonymization of personally identifying data before it is sent to cloud LLMs.
+//!
+//! Usernames, hostnames, IP addresses and the user segment of home-directory
+//! paths are replaced with stable tokens such as `[USER_1]` or `[HOST_1]`.
+//! The token mapping never leaves the process, so responses can be restored
+//! with [`PrivacyFilter::restore_text`] before they are shown locally.
+
+use crate::config::PrivacyConfig;
+use log::warn;
+use regex::{Regex, RegexBuilder};
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Home directory markers; the following path segment is the user name.
+const HOME_MARKERS: [&str; 3] = ["/home/", "/users/", "\\users\\"];
+
+/// Stands in for the user name in [`PrivacyFilter::mask_home_paths`].
+const MASKED_USER: &str = "<user>";
+
+/// Pseudonyms remembered before the oldest are forgotten. Tokens are never
+/// reused, so a forgotten one is just left as is by `restore_text`.
+const MAX_PSEUDONYMS: usize = 4096;
+
+/// Names that appear under home directories but don't identify anyone.
+const GENERIC_USERS: [&str; 6] = [
+    "public",
+    "default",
+    "shared",
+    "all users",
+    "default user",
+    "guest",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum PiiKind {
+    User,
+    Host,
+    Ip,
+    Term,
+}
+
+impl PiiKind {
+    fn label(&self) -> &'static str {
+        match self {
+            PiiKind::User => "USER",
+            PiiKind::Host => "HOST",
+            PiiKind::Ip => "IP",
+            PiiKind::Term => "TERM",
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct PseudonymMap {
+    /// lowercase original -> token
+    forward: HashMap<String, String>,
+    /// token -> original (as first seen)
+    reverse: HashMap<String, String>,
+    counters: HashMap<PiiKind, usize>,
+    /// Keys of `forward`, oldest first
+    order: VecDeque<String>,
+    /// Bumped whenever a user pseudonym is added or forgotten
+    users_version: u64,
+    /// Term matcher and the `users_version` it was built for
+    terms: Option<(u64, Arc<TermMatcher>)>,
+}
+
+impl PseudonymMap {
+    fn token_for(&mut self, kind: PiiKind, original: &str) -> String {
+        let key = original.to_lowercase();
+        if let Some(token) = self.forward.get(&key) {
+            return token.clone();
+        }
+        let counter = self.counters.entry(kind).or_insert(0);
+        *counter += 1;
+        let token = format!("[{}_{}]", kind.label(), counter);
+        self.forward.insert(key.clone(), token.clone());
+        self.reverse.insert(token.clone(), original.to_string());
+        self.order.push_back(key);
+        if kind == PiiKind::User {
+            self.users_version += 1;
+        }
+        while self.order.len() > MAX_PSEUDONYMS {
+            self.forget_oldest();
+        }
+        token
+    }
+
+    fn forget_oldest(&mut self) {
+        let Some(key) = self.order.pop_front() else {
+            return;
+        };
+        if let Some(token) = self.forward.remove(&key) {
+            self.reverse.remove(&token);
+            if original_kind(&token) == Some(PiiKind::User) {
+                self.users_version += 1;
+            }
+        }
+    }
+
+    fn users(&self) -> impl Iterator<Item = &String> {
+        self.reverse
+            .iter()
+            .filter(|(token, _)| original_kind(token) == Some(PiiKind::User))
+            .map(|(_, original)| original)
+    }
+}
+
+/// Every term to replace as one case-insensitive whole-word alternation.
+#[derive(Debug)]
+struct TermMatcher {
+    regex: Regex,
+    /// lowercase term -> kind
+    kinds: HashMap<String, PiiKind>,
+}
+
+impl TermMatcher {
+    /// `None` when there is nothing to match or the terms don't fit in one
+    /// regex, in which case callers replace them one by one.
+    fn new(mut terms: Vec<(PiiKind, String)>) -> Option<Self> {
+        if terms.is_empty() {
+            return None;
+        }
+        // Longest first so "alice-laptop" wins over "alice"
+        terms.sort_by_key(|(_, t)| std::cmp::Reverse(t.len()));
+        let alternation = terms
+            .iter()
+            .map(|(_, term)| regex::escape(term))
+            .collect::<Vec<_>>()
+            .join("|");
+        let regex = RegexBuilder::new(&format!(r"\b(?:{alternation})\b"))
+            .case_insensitive(true)
+            .build()
+            .map_err(|e| warn!("Privacy terms don't fit in one regex: {e}"))
+            .ok()?;
+        let mut kinds = HashMap::new();
+        for (kind, term) in terms {
+            kinds.entry(term.to_lowercase()).or_insert(kind);
+        }
+        Some(Self { regex, kinds })
+    }
+
+    fn replace(&self, input: &str, map: &mut PseudonymMap) -> String {
+        self.regex
+            .replace_all(input, |caps: &regex::Captures| {
+                let kind = self
+                    .kinds
+                    .get(&caps[0].to_lowercase())
+                    .copied()
+                    .unwrap_or(PiiKind::Term);
+                map.token_for(kind, &caps[0])
+            })
+            .into_owned()
+    }
+}
+
+fn ipv4_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"\b(?:(?:25[0-5]|2[0-4]\d|1\d\d|[1-9]?\d)\.){3}(?:25[0-5]|2[0-4]\d|1\d\d|[1-9]?\d)\b",
+        )
+        .expect("valid IPv4 regex")
+    })
+}
+
+fn ipv6_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        // Full form with at least five groups (so `12:30:45` timestamps are left
+        // alone), or a compressed form containing `::`.
+        Regex::new(concat!(
+            r"\b(?:[0-9a-fA-F]{1,4}:){4,7}[0-9a-fA-F]{1,4}\b",
+            r"|\b[0-9a-fA-F]{1,4}(?::[0-9a-fA-F]{1,4})*::(?:[0-9a-fA-F]{1,4}(?::[0-9a-fA-F]{1,4})*)?\b",
+        ))
+        .expect("valid IPv6 regex")
+    })
+}
+
+fn token_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\[(?:USER|HOST|IP|TERM)_\d+\]").expect("valid token regex"))
+}
+
+/// Loopback/unspecified addresses carry no identifying information.
+fn is_non_identifying_ip(ip: &str) -> bool {
+    ip.starts_with("127.") || ip == "0.0.0.0" || ip == "255.255.255.255"
+}
+
+/// Redacts PII using a reversible, process-local pseudonym mapping.
+///
+/// The configured terms are compiled into one regex up front; it is only
+/// rebuilt when users learned from home paths change.
+pub struct PrivacyFilter {
+    config: PrivacyConfig,
+    /// Terms to replace, already limited to the enabled kinds
+    known: Vec<(PiiKind, String)>,
+    map: Mutex<PseudonymMap>,
+}
+
+impl PrivacyFilter {
+    /// Builds a filter seeded with the local hostname and current user.
+    pub fn new(config: PrivacyConfig) -> Self {
+        let mut known = Vec::new();
+        if config.redact_usernames {
+            for var in ["USER", "USERNAME", "LOGNAME"] {
+                if let Ok(user) = std::env::var(var) {
+                    known.push((PiiKind::User, user));
+                }
+            }
+        }
+        if config.redact_hostnames {
+            if let Some(host) = sysinfo::System::host_name() {
+                known.push((PiiKind::Host, host));
+            }
+            for var in ["COMPUTERNAME", "HOSTNAME"] {
+                if let Ok(host) = std::env::var(var) {
+                    known.push((PiiKind::Host, host));
+                }
+            }
+        }
+        for term in &config.extra_terms {
+            known.push((PiiKind::Term, term.clone()));
+        }
+        Self::with_known(config, known)
+    }
+
+    fn with_known(config: PrivacyConfig, known: Vec<(PiiKind, String)>) -> Self {
+        let mut deduped: Vec<(PiiKind, String)> = Vec::new();
+        for (kind, value) in known {
+            let value = value.trim().to_string();
+            let enabled = match kind {
+                PiiKind::User => config.redact_usernames,
+                PiiKind::Host => config.redact_hostnames,
+                _ => true,
+            };
+            if enabled
+                && is_identifying_term(&value)
+                && !deduped.iter().any(|(_, v)| v.eq_ignore_ascii_case(&value))
+            {
+                deduped.push((kind, value));
+            }
+        }
+        let filter = Self {
+            config,
+            known: deduped,
+            map: Mutex::new(PseudonymMap::default()),
+        };
+        filter.term_matcher(&mut filter.map.lock().unwrap_or_else(|e| e.into_inner()));
+        filter
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Pseudonymize every string in a JSON value in place.
+    pub fn redact_value(&self, value: &mut Value) {
+        if !self.config.enabled {
+            return;
+        }
+        // First pass: learn usernames from home paths anywhere in the document so
+        // that bare mentions elsewhere are replaced consistently.
+        let mut learned = Vec::new();
+        collect_home_users(value, &mut learned);
+        let mut map = self.map.lock().unwrap_or_else(|e| e.into_inner());
+        if self.config.redact_paths {
+            for user in learned {
+                map.token_for(PiiKind::User, &user);
+            }
+        }
+        redact_value_with(value, &mut |s| self.redact_with(&mut map, s));
+    }
+
+    /// Pseudonymize a free-form string.
+    pub fn redact_text(&self, text: &str) -> String {
+        if !self.config.enabled {
+            return text.to_string();
+        }
+        let mut map = self.map.lock().unwrap_or_else(|e| e.into_inner());
+        self.redact_with(&mut map, text)
+    }
+
+    /// Replace pseudonym tokens with their original values.
+    pub fn restore_text(&self, text: &str) -> String {
+        let map = self.map.lock().unwrap_or_else(|e| e.into_inner());
+        token_regex()
+            .replace_all(text, |caps: &regex::Captures| {
+                map.reverse
+                    .get(&caps[0])
+                    .cloned()
+                    .unwrap_or_else(|| caps[0].to_string())
+            })
+            .into_owned()
+    }
+
+    /// Restore pseudonym tokens in every string of a JSON value.
+    pub fn restore_value(&self, value: &mut Value) {
+        redact_value_with(value, &mut |s| self.restore_text(s));
+    }
+
+    /// Replace the user segment of home-directory paths with `<user>` in
+    /// every string. For output that must never name the user, such as
+    /// diagnostics bundles or snapshots when the filter is disabled; nothing
+    /// is remembered, so it can't be restored.
+    pub fn mask_home_paths(value: &mut Value) {
+        redact_value_with(value, &mut |s| {
+            replace_home_users(s, |_| MASKED_USER.to_string())
+        });
+    }
+
+    /// Current token -> original mapping, for local display only.
+    pub fn mapping(&self) -> HashMap<String, String> {
+        self.map
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .reverse
+            .clone()
+    }
+
+    /// Known terms (configured user, hostname, extra terms) plus users
+    /// learned from paths.
+    fn terms(&self, map: &PseudonymMap) -> Vec<(PiiKind, String)> {
+        let mut terms = self.known.clone();
+        if self.config.redact_usernames {
+            for original in map.users() {
+                if !token_regex().is_match(original)
+                    && !terms.iter().any(|(_, t)| t.eq_ignore_ascii_case(original))
+                {
+                    terms.push((PiiKind::User, original.clone()));
+                }
+            }
+        }
+        terms
+    }
+
+    /// The compiled terms, rebuilt only when the learned users changed.
+    fn term_matcher(&self, map: &mut PseudonymMap) -> Option<Arc<TermMatcher>> {
+        if let Some((version, matcher)) = &map.terms {
+            if *version == map.users_version {
+                return Some(Arc::clone(matcher));
+            }
+        }
+        let matcher = Arc::new(TermMatcher::new(self.terms(map))?);
+        map.terms = Some((map.users_version, Arc::clone(&matcher)));
+        Some(matcher)
+    }
+
+    fn redact_with(&self, map: &mut PseudonymMap, input: &str) -> String {
+        let mut out = input.to_string();
+
+        if self.config.redact_paths {
+            out = pseudonymize_home_paths(&out, map);
+        }
+
+        match self.term_matcher(map) {
+            Some(matcher) => out = matcher.replace(&out, map),
+            None => {
+                let mut terms = self.terms(map);
+                terms.sort_by_key(|(_, t)| std::cmp::Reverse(t.len()));
+                for (kind, term) in terms {
+                    out = replace_term(&out, &term, |original| map.token_for(kind, original));
+                }
+            }
+        }
+
+        if self.config.redact_ips {
+            for re in [ipv4_regex(), ipv6_regex()] {
+                out = re
+                    .replace_all(&out, |caps: &regex::Captures| {
+                        let ip = &caps[0];
+                        if is_non_identifying_ip(ip) {
+                            ip.to_string()
+                        } else {
+                            map.token_for(PiiKind::Ip, ip)
+                        }
+                    })
+                    .into_owned();
+            }
+        }
+
+        out
+    }
+}
+
+fn original_kind(token: &str) -> Option<PiiKind> {
+    let label = token.trim_start_matches('[').split('_').next()?;
+    match label {
+        "USER" => Some(PiiKind::User),
+        "HOST" => Some(PiiKind::Host),
+        "IP" => Some(PiiKind::Ip),
+        "TERM" => Some(PiiKind::Term),
+        _ => None,
+    }
+}
+
+fn is_identifying_term(value: &str) -> bool {
+    value.len() >= 3
+        && !GENERIC_USERS
+            .iter()
+            .any(|generic| value.eq_ignore_ascii_case(generic))
+}
+
+fn redact_value_with(value: &mut Value, f: &mut dyn FnMut(&str) -> String) {
+    match value {
+        Value::String(s) => *s = f(s),
+        Value::Array(items) => items.iter_mut().for_each(|v| redact_value_with(v, f)),
+        Value::Object(map) => map.values_mut().for_each(|v| redact_value_with(v, f)),
+        _ => {}
+    }
+}
+
+/// Byte ranges of the user segment of every home-directory path in `input`.
+fn home_user_ranges(input: &str) -> Vec<(usize, usize)> {
+    // ASCII lowercasing keeps byte offsets aligned with `input`.
+    let lower = input.to_ascii_lowercase();
+    let mut ranges = Vec::new();
+    for marker in HOME_MARKERS {
+        let mut from = 0;
+        while let Some(pos) = lower[from..].find(marker) {
+            let start = from + pos + marker.len();
+            let len = input[start..]
+                .find(|c: char| matches!(c, '/' | '\\' | '"' | '\'') || c.is_whitespace())
+                .unwrap_or(input.len() - start);
+            let segment = &input[start..start + len];
+            if is_identifying_term(segment)
+                && segment != MASKED_USER
+                && !token_regex().is_match(segment)
+            {
+                ranges.push((start, start + len));
+            }
+            from = start + len;
+        }
+    }
+    ranges.sort_unstable();
+    ranges
+}
+
+fn collect_home_users(value: &Value, out: &mut Vec<String>) {
+    match value {
+        Value::String(s) => {
+            for (start, end) in home_user_ranges(s) {
+                out.push(s[start..end].to_string());
+            }
+        }
+        Value::Array(items) => items.iter().for_each(|v| collect_home_users(v, out)),
+        Value::Object(map) => map.values().for_each(|v| collect_home_users(v, out)),
+        _ => {}
+    }
+}
+
+fn pseudonymize_home_paths(input: &str, map: &mut PseudonymMap) -> String {
+    replace_home_users(input, |user| map.token_for(PiiKind::User, user))
+}
+
+fn replace_home_users(input: &str, mut replacement: impl FnMut(&str) -> String) -> String {
+    let ranges = home_user_ranges(input);
+    if ranges.is_empty() {
+        return input.to_string();
+    }
+    let mut out = String::with_capacity(input.len());
+    let mut cursor = 0;
+    for (start, end) in ranges {
+        if start < cursor {
+            continue;
+        }
+        out.push_str(&input[cursor..start]);
+        out.push_str(&replacement(&input[start..end]));
+        cursor = end;
+    }
+    out.push_str(&input[cursor..]);
+    out
+}
+
+/// Case-insensitive whole-word replacement of `term`.
+fn replace_term(input: &str, term: &str, mut token: impl FnMut(&str) -> String) -> String {
+    let pattern = format!(r"(?i)\b{}\b", regex::escape(term));
+    match Regex::new(&pattern) {
+        Ok(re) => re
+            .replace_all(input, |caps: &regex::Captures| token(&caps[0]))
+            .into_owned(),
+        Err(_) => input.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn filter(known: Vec<(PiiKind, &str)>) -> PrivacyFilter {
+        PrivacyFilter::with_known(
+            PrivacyConfig::default(),
+            known.into_iter().map(|(k, v)| (k, v.to_string())).collect(),
+        )
+    }
+
+    #[test]
+    fn test_pseudonymizes_paths_and_learned_users() {
+        let filter = filter(Vec::new());
+        let mut value = json!({
+            "command": "C:\\Users\\Alice\\AppData\\run.exe",
+            "note": "owned by alice",
+        });
+        filter.redact_value(&mut value);
+
+        assert_eq!(value["command"], "C:\\Users\\[USER_1]\\AppData\\run.exe");
+        assert_eq!(value["note"], "owned by [USER_1]");
+    }
+
+    #[test]
+    fn test_pseudonymizes_hosts_and_ips() {
+        let filter = filter(vec![(PiiKind::Host, "workstation-7")]);
+        let text = filter.redact_text("WORKSTATION-7 talked to 10.0.0.12 and 127.0.0.1");
+        assert_eq!(text, "[HOST_1] talked to [IP_1] and 127.0.0.1");
+    }
+
+    #[test]
+    fn test_mapping_is_stable_and_reversible() {
+        let filter = filter(vec![(PiiKind::User, "bob")]);
+        let first = filter.redact_text("/home/bob/x and bob");
+        let second = filter.redact_text("bob again");
+        assert_eq!(first, "/home/[USER_1]/x and [USER_1]");
+        assert_eq!(second, "[USER_1] again");
+        assert_eq!(filter.restore_text(&first), "/home/bob/x and bob");
+    }
+
+    #[test]
+    fn test_longest_term_wins() {
+        let filter = filter(vec![
+            (PiiKind::User, "alice"),
+            (PiiKind::Host, "alice-laptop"),
+        ]);
+        assert_eq!(
+            filter.redact_text("alice on ALICE-LAPTOP"),
+            "[USER_1] on [HOST_1]"
+        );
+    }
+
+    #[test]
+    fn test_oldest_pseudonyms_are_forgotten() {
+        let filter = filter(Vec::new());
+        let first = filter.redact_text("10.0.0.1");
+        for i in 0..MAX_PSEUDONYMS {
+            filter.redact_text(&format!("10.1.{}.{}", i / 256, i % 256));
+        }
+        assert_eq!(filter.mapping().len(), MAX_PSEUDONYMS);
+        // Left as the token, never restored to someone else
+        assert_eq!(filter.restore_text(&first), first);
+        assert_ne!(filter.redact_text("10.0.0.1"), first);
+    }
+
+    #[test]
+    fn test_masks_home_paths() {
+        let mut value = json!({
+            "processes": [{"command": "cp /Users/carol/a C:\\Users\\Bob\\b --flag"}],
+            "path": "/home/<user>/x",
+            "n": 1,
+        });
+        PrivacyFilter::mask_home_paths(&mut value);
+        assert_eq!(
+            value["processes"][0]["command"],
+            "cp /Users/<user>/a C:\\Users\\<user>\\b --flag"
+        );
+        assert_eq!(value["path"], "/home/<user>/x");
+    }
+
+    #[test]
+    fn test_disabled_filter_is_passthrough() {
+        let config = PrivacyConfig {
+            enabled: false,
+            ..PrivacyConfig::default()
+        };
+        let filter = PrivacyFilter::with_known(config, Vec::new());
+        assert_eq!(
+            filter.redact_text("/home/carol 10.1.2.3"),
+            "/home/carol 10.1.2.3"
+        );
+    }
+}
@@ -0,0 +1,190 @@
+//! Profiles keep the data of different people on a shared machine apart.
+//!
+//! Each profile has its own JSON memory directory and its own SurrealDB
+//! namespace. The `default` profile keeps the original locations
+//! (`oxide_data`, namespace `oxide`) so existing installs see their data
+//! unchanged. The profile picked with `switch_profile` is persisted in
+//! `./data/active_profile`.
+
+use crate::config::ProfileConfig;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+use thiserror::Error;
+
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Directory holding `active_profile`.
+pub const DATA_ROOT: &str = "./data";
+
+/// JSON memory root; other profiles live in `profiles/<name>` below it.
+pub const MEMORY_ROOT: &str = "oxide_data";
+
+const ACTIVE_PROFILE_FILE: &str = "active_profile";
+const SURREAL_NAMESPACE: &str = "oxide";
+const MAX_NAME_LEN: usize = 32;
+
+#[derive(Error, Debug)]
+pub enum ProfileError {
+    #[error("Invalid profile name '{0}': use 1-32 letters, digits or underscores")]
+    InvalidName(String),
+    #[error("Profile storage error: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// Lowercased `name` if it is usable as a directory and SurrealDB
+/// namespace suffix.
+pub fn normalize_name(name: &str) -> Result<String, ProfileError> {
+    let name = name.trim().to_ascii_lowercase();
+    let valid = !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Ok(name)
+    } else {
+        Err(ProfileError::InvalidName(name))
+    }
+}
+
+/// Profile name derived from the OS account (`USER` / `USERNAME`).
+pub fn os_user_profile() -> Option<String> {
+    let user = std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .ok()?;
+    let name: String = user
+        .trim()
+        .to_ascii_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .take(MAX_NAME_LEN)
+        .collect();
+    normalize_name(&name).ok()
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    pub name: String,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self {
+            name: DEFAULT_PROFILE.to_string(),
+        }
+    }
+}
+
+impl Profile {
+    pub fn new(name: &str) -> Result<Self, ProfileError> {
+        Ok(Self {
+            name: normalize_name(name)?,
+        })
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.name == DEFAULT_PROFILE
+    }
+
+    /// Directory of the JSON memory store.
+    pub fn memory_path(&self) -> String {
+        if self.is_default() {
+            MEMORY_ROOT.to_string()
+        } else {
+            format!("{MEMORY_ROOT}/profiles/{}", self.name)
+        }
+    }
+
+    pub fn surreal_namespace(&self) -> String {
+        if self.is_default() {
+            SURREAL_NAMESPACE.to_string()
+        } else {
+            format!("{SURREAL_NAMESPACE}_{}", self.name)
+        }
+    }
+}
+
+/// The active profile: `OXIDE_PROFILE`, then the persisted choice, then
+/// `profile.name` from the config, then the OS user when
+/// `profile.per_os_user` is set, then `default`. Invalid names are skipped.
+pub fn resolve(config: Option<&ProfileConfig>, data_root: &Path) -> Profile {
+    let persisted = fs::read_to_string(data_root.join(ACTIVE_PROFILE_FILE)).ok();
+    let candidates = [
+        std::env::var("OXIDE_PROFILE").ok(),
+        persisted,
+        config.and_then(|c| c.name.clone()),
+        config
+            .filter(|c| c.per_os_user)
+            .and_then(|_| os_user_profile()),
+    ];
+    candidates
+        .into_iter()
+        .flatten()
+        .find_map(|name| Profile::new(&name).ok())
+        .unwrap_or_default()
+}
+
+/// Remember `profile` for the next start.
+pub fn persist_active(data_root: &Path, profile: &Profile) -> Result<(), ProfileError> {
+    fs::create_dir_all(data_root)?;
+    fs::write(data_root.join(ACTIVE_PROFILE_FILE), &profile.name)?;
+    Ok(())
+}
+
+/// `default` plus every profile that has a memory directory under
+/// `memory_root`, sorted.
+pub fn list_profiles(memory_root: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(memory_root.join("profiles"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| normalize_name(&entry.file_name().to_string_lossy()).ok())
+        .collect();
+    names.push(DEFAULT_PROFILE.to_string());
+    names.sort();
+    names.dedup();
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_map_to_isolated_locations() {
+        assert_eq!(normalize_name(" Alice_2 ").unwrap(), "alice_2");
+        assert!(normalize_name("../etc").is_err());
+        assert!(normalize_name("").is_err());
+        assert!(normalize_name(&"a".repeat(33)).is_err());
+
+        let default = Profile::default();
+        assert_eq!(default.memory_path(), "oxide_data");
+        assert_eq!(default.surreal_namespace(), "oxide");
+
+        let alice = Profile::new("Alice").unwrap();
+        assert_eq!(alice.memory_path(), "oxide_data/profiles/alice");
+        assert_eq!(alice.surreal_namespace(), "oxide_alice");
+    }
+
+    #[test]
+    fn persisted_choice_overrides_config() {
+        let root = std::env::temp_dir().join(format!("oxide-profile-{}", uuid::Uuid::new_v4()));
+        let config = ProfileConfig {
+            name: Some("work".to_string()),
+            per_os_user: false,
+        };
+        // OXIDE_PROFILE is not set in tests
+        assert_eq!(resolve(Some(&config), &root).name, "work");
+        assert_eq!(resolve(None, &root), Profile::default());
+
+        persist_active(&root, &Profile::new("home").unwrap()).unwrap();
+        assert_eq!(resolve(Some(&config), &root).name, "home");
+
+        fs::create_dir_all(root.join("profiles/home")).unwrap();
+        fs::create_dir_all(root.join("profiles/Not Valid")).unwrap();
+        assert_eq!(list_profiles(&root), vec!["default", "home"]);
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,294 @@
+//! Process-wide metrics in the Prometheus text exposition format.
+//!
+//! Recording is always on and costs a mutex and a map lookup; the `/metrics`
+//! endpoint that serves [`render`] is enabled by the desktop app's
+//! `prometheus` feature. Metrics are declared as [`MetricDef`] constants so
+//! names, types and help texts live in one place.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use sysinfo::Disks;
+
+/// `Content-Type` of [`render`]'s output.
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Histogram buckets in seconds, sized for LLM round trips.
+pub const LATENCY_BUCKETS: [f64; 10] = [0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 20.0, 30.0, 60.0];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricKind {
+    Counter,
+    Gauge,
+    Histogram,
+}
+
+impl MetricKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Counter => "counter",
+            Self::Gauge => "gauge",
+            Self::Histogram => "histogram",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MetricDef {
+    pub name: &'static str,
+    pub help: &'static str,
+    pub kind: MetricKind,
+}
+
+pub const CPU_USAGE_PERCENT: MetricDef = MetricDef {
+    name: "oxide_cpu_usage_percent",
+    help: "Host CPU usage in percent",
+    kind: MetricKind::Gauge,
+};
+pub const MEMORY_USED_BYTES: MetricDef = MetricDef {
+    name: "oxide_memory_used_bytes",
+    help: "Host memory in use",
+    kind: MetricKind::Gauge,
+};
+pub const MEMORY_TOTAL_BYTES: MetricDef = MetricDef {
+    name: "oxide_memory_total_bytes",
+    help: "Host memory installed",
+    kind: MetricKind::Gauge,
+};
+pub const PROCESS_COUNT: MetricDef = MetricDef {
+    name: "oxide_processes",
+    help: "Processes currently running",
+    kind: MetricKind::Gauge,
+};
+pub const DISK_AVAILABLE_BYTES: MetricDef = MetricDef {
+    name: "oxide_disk_available_bytes",
+    help: "Free space per mounted disk",
+    kind: MetricKind::Gauge,
+};
+pub const DISK_TOTAL_BYTES: MetricDef = MetricDef {
+    name: "oxide_disk_total_bytes",
+    help: "Capacity per mounted disk",
+    kind: MetricKind::Gauge,
+};
+pub const SCANS_TOTAL: MetricDef = MetricDef {
+    name: "oxide_scans_total",
+    help: "Completed scans by kind and result",
+    kind: MetricKind::Counter,
+};
+pub const THREATS_TOTAL: MetricDef = MetricDef {
+    name: "oxide_threats_total",
+    help: "Threats recorded by Guardian by severity",
+    kind: MetricKind::Counter,
+};
+pub const LLM_REQUEST_SECONDS: MetricDef = MetricDef {
+    name: "oxide_llm_request_duration_seconds",
+    help: "LLM request latency by provider and outcome",
+    kind: MetricKind::Histogram,
+};
+pub const ERRORS_TOTAL: MetricDef = MetricDef {
+    name: "oxide_errors_total",
+    help: "Errors reported to the error monitor by type",
+    kind: MetricKind::Counter,
+};
+
+type Labels = Vec<(String, String)>;
+
+#[derive(Debug, Clone)]
+enum Series {
+    Value(f64),
+    Histogram {
+        // Per-bucket (non-cumulative) counts; cumulated when rendering
+        buckets: [u64; LATENCY_BUCKETS.len()],
+        sum: f64,
+        count: u64,
+    },
+}
+
+struct Family {
+    def: MetricDef,
+    series: BTreeMap<Labels, Series>,
+}
+
+fn registry() -> MutexGuard<'static, BTreeMap<&'static str, Family>> {
+    static REGISTRY: OnceLock<Mutex<BTreeMap<&'static str, Family>>> = OnceLock::new();
+    REGISTRY
+        .get_or_init(|| Mutex::new(BTreeMap::new()))
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn labels(pairs: &[(&str, &str)]) -> Labels {
+    let mut labels: Labels = pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    labels.sort();
+    labels
+}
+
+impl MetricDef {
+    fn update(&self, pairs: &[(&str, &str)], apply: impl FnOnce(&mut Series)) {
+        let mut registry = registry();
+        let family = registry.entry(self.name).or_insert_with(|| Family {
+            def: *self,
+            series: BTreeMap::new(),
+        });
+        let series = family
+            .series
+            .entry(labels(pairs))
+            .or_insert_with(|| match self.kind {
+                MetricKind::Histogram => Series::Histogram {
+                    buckets: [0; LATENCY_BUCKETS.len()],
+                    sum: 0.0,
+                    count: 0,
+                },
+                _ => Series::Value(0.0),
+            });
+        apply(series);
+    }
+
+    /// Add one to a counter.
+    pub fn inc(&self, pairs: &[(&str, &str)]) {
+        debug_assert_eq!(self.kind, MetricKind::Counter);
+        self.update(pairs, |series| {
+            if let Series::Value(v) = series {
+                *v += 1.0;
+            }
+        });
+    }
+
+    pub fn set(&self, pairs: &[(&str, &str)], value: f64) {
+        debug_assert_eq!(self.kind, MetricKind::Gauge);
+        self.update(pairs, |series| *series = Series::Value(value));
+    }
+
+    /// Record one histogram sample, in seconds.
+    pub fn observe(&self, pairs: &[(&str, &str)], value: f64) {
+        debug_assert_eq!(self.kind, MetricKind::Histogram);
+        self.update(pairs, |series| {
+            if let Series::Histogram {
+                buckets,
+                sum,
+                count,
+            } = series
+            {
+                if let Some(i) = LATENCY_BUCKETS.iter().position(|le| value <= *le) {
+                    buckets[i] += 1;
+                }
+                *sum += value;
+                *count += 1;
+            }
+        });
+    }
+}
+
+/// Refresh the per-disk gauges; called before each scrape.
+pub fn refresh_disk_gauges() {
+    for disk in Disks::new_with_refreshed_list().list() {
+        let mount = disk.mount_point().to_string_lossy();
+        let pairs = [("mount", mount.as_ref())];
+        DISK_AVAILABLE_BYTES.set(&pairs, disk.available_space() as f64);
+        DISK_TOTAL_BYTES.set(&pairs, disk.total_space() as f64);
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn format_labels(labels: &Labels, extra: Option<(&str, String)>) -> String {
+    let mut parts: Vec<String> = labels
+        .iter()
+        .map(|(k, v)| format!("{k}=\"{}\"", escape(v)))
+        .collect();
+    if let Some((k, v)) = extra {
+        parts.push(format!("{k}=\"{v}\""));
+    }
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", parts.join(","))
+    }
+}
+
+/// Every recorded metric in the Prometheus text format.
+pub fn render() -> String {
+    let registry = registry();
+    let mut out = String::new();
+    for family in registry.values() {
+        let name = family.def.name;
+        let _ = writeln!(out, "# HELP {name} {}", family.def.help);
+        let _ = writeln!(out, "# TYPE {name} {}", family.def.kind.as_str());
+        for (labels, series) in &family.series {
+            match series {
+                Series::Value(v) => {
+                    let _ = writeln!(out, "{name}{} {v}", format_labels(labels, None));
+                }
+                Series::Histogram {
+                    buckets,
+                    sum,
+                    count,
+                } => {
+                    let mut cumulative = 0;
+                    for (le, n) in LATENCY_BUCKETS.iter().zip(buckets) {
+                        cumulative += n;
+                        let bucket = format_labels(labels, Some(("le", le.to_string())));
+                        let _ = writeln!(out, "{name}_bucket{bucket} {cumulative}");
+                    }
+                    let inf = format_labels(labels, Some(("le", "+Inf".to_string())));
+                    let _ = writeln!(out, "{name}_bucket{inf} {count}");
+                    let plain = format_labels(labels, None);
+                    let _ = writeln!(out, "{name}_sum{plain} {sum}");
+                    let _ = writeln!(out, "{name}_count{plain} {count}");
+                }
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_COUNTER: MetricDef = MetricDef {
+        name: "oxide_test_events_total",
+        help: "Test counter",
+        kind: MetricKind::Counter,
+    };
+    const TEST_LATENCY: MetricDef = MetricDef {
+        name: "oxide_test_latency_seconds",
+        help: "Test histogram",
+        kind: MetricKind::Histogram,
+    };
+
+    #[test]
+    fn counters_and_histograms_render_in_exposition_format() {
+        TEST_COUNTER.inc(&[("kind", "file"), ("result", "clean")]);
+        TEST_COUNTER.inc(&[("result", "clean"), ("kind", "file")]);
+        TEST_COUNTER.inc(&[("kind", "file"), ("result", "say \"hi\"")]);
+        TEST_LATENCY.observe(&[("provider", "google")], 0.2);
+        TEST_LATENCY.observe(&[("provider", "google")], 3.0);
+        TEST_LATENCY.observe(&[("provider", "google")], 120.0);
+
+        let text = render();
+        assert!(text.contains("# TYPE oxide_test_events_total counter"));
+        assert!(text.contains("oxide_test_events_total{kind=\"file\",result=\"clean\"} 2"));
+        assert!(text.contains("result=\"say \\\"hi\\\"\"} 1"));
+        assert!(text.contains("# TYPE oxide_test_latency_seconds histogram"));
+        assert!(
+            text.contains("oxide_test_latency_seconds_bucket{provider=\"google\",le=\"0.1\"} 0")
+        );
+        assert!(
+            text.contains("oxide_test_latency_seconds_bucket{provider=\"google\",le=\"0.25\"} 1")
+        );
+        assert!(text.contains("oxide_test_latency_seconds_bucket{provider=\"google\",le=\"5\"} 2"));
+        assert!(
+            text.contains("oxide_test_latency_seconds_bucket{provider=\"google\",le=\"+Inf\"} 3")
+        );
+        assert!(text.contains("oxide_test_latency_seconds_count{provider=\"google\"} 3"));
+    }
+}
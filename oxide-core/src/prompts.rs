@@ -0,0 +1,646 @@
+//! Named, versioned templates for the prompts sent to LLMs.
+//!
+//! Every analysis path renders its prompts from here by name instead of
+//! embedding string literals. A template may have a provider-specific
+//! variant named `<name>@<provider>` (for example `system_analysis@qwen`),
+//! which is preferred for that provider. Built-in templates can be replaced
+//! inline through [`PromptConfig`] or by `<name>.txt` files in the prompts
+//! directory; the directory is watched and reloaded when files change.
+//!
+//! Placeholders are written `{name}`. Besides the variables a caller passes,
+//! `{hostname}`, `{os}`, `{date}` and `{locale}` are always available.
+//! Braces that do not wrap a lowercase identifier, such as JSON examples,
+//! are left alone, and so are placeholders without a value.
+
+use crate::config::{PromptConfig, PromptOverride};
+use crate::i18n;
+use crate::profile;
+use log::{info, warn};
+use notify::{recommended_watcher, RecommendedWatcher, RecursiveMode, Watcher};
+use regex::{Captures, Regex};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock, RwLock};
+
+pub const ANALYST_SYSTEM: &str = "analyst.system";
+pub const SYSTEM_ANALYSIS: &str = "system_analysis";
+pub const THREAT_ANALYSIS: &str = "threat_analysis";
+pub const THREAT_ANALYSIS_SYSTEM: &str = "threat_analysis.system";
+pub const ASSISTANT_SYSTEM: &str = "assistant.system";
+pub const ORCHESTRATOR_PLAN: &str = "orchestrator.plan";
+pub const ORCHESTRATOR_VALIDATE: &str = "orchestrator.validate";
+pub const ORCHESTRATOR_ANALYST: &str = "orchestrator.analyst";
+pub const ORCHESTRATOR_EXECUTOR: &str = "orchestrator.executor";
+pub const ORCHESTRATOR_INNOVATOR: &str = "orchestrator.innovator";
+pub const ORCHESTRATOR_SPECIALIST: &str = "orchestrator.specialist";
+
+/// Template with the system prompt for an orchestrator role, e.g.
+/// `role.analyst`; `role` is the lowercase role name.
+pub fn role_template(role: &str) -> String {
+    format!("role.{}", role.to_ascii_lowercase())
+}
+
+/// Template file extension in the prompts directory
+const FILE_EXTENSION: &str = "txt";
+
+struct Builtin {
+    name: &'static str,
+    version: u32,
+    text: &'static str,
+}
+
+const BUILTINS: &[Builtin] = &[
+    Builtin {
+        name: ANALYST_SYSTEM,
+        version: 1,
+        text: "You are an expert OS internals, performance, and security analyst. Respond concisely and technically.",
+    },
+    Builtin {
+        name: SYSTEM_ANALYSIS,
+        version: 1,
+        text: "You are an expert OS performance and security analyst. Given this JSON snapshot, produce a concise analysis with:
+- Key performance issues and likely root causes
+- Suspicious processes or threats (if any)
+- Immediate remediation steps (bulleted)
+- Risk score (0-100) and confidence.
+
+Snapshot:
+{snapshot}",
+    },
+    Builtin {
+        name: "system_analysis@qwen",
+        version: 1,
+        text: "Perform a deep technical analysis of this system snapshot focusing on:
+- Hot threads and blocking syscalls
+- Memory pressure, leaks, fragmentation indicators
+- Process anomalies (handles, CPU spikes, I/O)
+- Concrete remediation with commands and config changes.
+
+Snapshot:
+{snapshot}",
+    },
+    Builtin {
+        name: THREAT_ANALYSIS_SYSTEM,
+        version: 1,
+        text: "You are a concise, technical security analyst. JSON output only.",
+    },
+    Builtin {
+        name: THREAT_ANALYSIS,
+        version: 1,
+        text: "You are a security threat analyst. Analyze the JSON system snapshot and return STRICT JSON with keys:
+risk_score (0-100), confidence (0-1), findings[], indicators[], recommendations[], citations[]
+{indicator_hint}
+{technique_hint}
+JSON only, no prose.
+
+Snapshot:
+{snapshot}",
+    },
+    Builtin {
+        name: "threat_analysis@gemini",
+        version: 1,
+        text: r#"You are a security threat analyst. Analyze the following JSON system snapshot and produce a STRICT JSON object with this shape:
+{
+  "risk_score": number (0-100),
+  "confidence": number (0-1),
+  "findings": [{"id": string, "kind": "process|file|network|config", "severity": "low|medium|high|critical", "summary": string, "rationale": string, "indicators": string[], "techniques": string[]}],
+  "indicators": [{"kind": "hash|domain|path|proc", "value": string, "context": string}],
+  "recommendations": string[],
+  "citations": [{"title": string, "url": string, "snippet": string}]
+}
+
+Requirements:
+- Output MUST be a single JSON object only, no prose.
+- {indicator_hint}
+- {technique_hint}
+- {grounding} Use Google Search to verify suspicious indicators and include citations to authoritative sources (CVE pages, vendors, security writeups). If not available, still return the JSON.
+
+Snapshot:
+{snapshot}"#,
+    },
+    Builtin {
+        name: ASSISTANT_SYSTEM,
+        version: 1,
+        text: "You are an intelligent system assistant helping with system analysis and automation.",
+    },
+    Builtin {
+        name: "role.coordinator",
+        version: 1,
+        text: "You are the primary coordinator for system tasks. You analyze requests, create execution plans, and coordinate with other AI agents.",
+    },
+    Builtin {
+        name: "role.analyst",
+        version: 1,
+        text: "You are a technical analyst specializing in deep system analysis, performance optimization, and security assessment.",
+    },
+    Builtin {
+        name: "role.executor",
+        version: 1,
+        text: "You are a system executor responsible for carrying out system operations, commands, and automated tasks.",
+    },
+    Builtin {
+        name: "role.innovator",
+        version: 1,
+        text: "You are an innovator who provides creative solutions, alternative approaches, and optimization strategies.",
+    },
+    Builtin {
+        name: "role.validator",
+        version: 1,
+        text: "You are a validator who reviews and validates AI responses, ensuring quality, consistency, and safety.",
+    },
+    Builtin {
+        name: ORCHESTRATOR_PLAN,
+        version: 1,
+        text: "You are the primary coordinator for this system task. Analyze the following request and create a detailed execution plan:
+
+Task: {task}
+System State: {system_state}
+User Input: {user_input}
+
+Provide:
+1. Task breakdown into subtasks
+2. Required system operations
+3. Risk assessment
+4. Recommended approach
+5. Success criteria
+
+Format your response as a structured plan.",
+    },
+    Builtin {
+        name: ORCHESTRATOR_ANALYST,
+        version: 1,
+        text: "As a technical analyst, provide deep analysis of this task:
+
+Task: {task}
+Coordinator Plan: {coordinator_plan}
+System State: {system_state}
+
+Focus on:
+- Technical feasibility
+- Performance implications
+- Security considerations
+- Detailed implementation steps",
+    },
+    Builtin {
+        name: ORCHESTRATOR_EXECUTOR,
+        version: 1,
+        text: "As a system executor, provide execution details for this task:
+
+Task: {task}
+Coordinator Plan: {coordinator_plan}
+Available Functions: {available_functions}
+
+Focus on:
+- Specific commands to execute
+- System operations required
+- Error handling strategies
+- Monitoring and logging",
+    },
+    Builtin {
+        name: ORCHESTRATOR_INNOVATOR,
+        version: 1,
+        text: "As an innovator, provide creative solutions for this task:
+
+Task: {task}
+Coordinator Plan: {coordinator_plan}
+Constraints: {constraints}
+
+Focus on:
+- Alternative approaches
+- Creative solutions
+- Optimization opportunities
+- Future improvements",
+    },
+    Builtin {
+        name: ORCHESTRATOR_SPECIALIST,
+        version: 1,
+        text: "Provide your specialized analysis for this task:
+
+Task: {task}
+Coordinator Plan: {coordinator_plan}
+Context: {user_input}
+
+Provide detailed analysis from your perspective.",
+    },
+    Builtin {
+        name: ORCHESTRATOR_VALIDATE,
+        version: 1,
+        text: "You are the validator for this collaborative task. Review all responses and provide validation:
+
+Original Task: {task}
+Coordinator Plan: {coordinator_plan}
+Specialized Responses: {responses}
+
+Provide:
+1. Quality assessment of each response
+2. Consistency check across responses
+3. Risk identification
+4. Recommendations for improvement
+5. Final validation score (0-100)
+
+Format as a structured validation report.",
+    },
+];
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptSource {
+    Builtin,
+    Config,
+    File,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct PromptTemplate {
+    pub name: String,
+    pub version: u32,
+    pub source: PromptSource,
+    /// Template file, for file templates
+    pub path: Option<String>,
+    /// Placeholders used in the text, in order of first use
+    pub variables: Vec<String>,
+    pub text: String,
+}
+
+impl PromptTemplate {
+    fn new(
+        name: &str,
+        version: u32,
+        source: PromptSource,
+        path: Option<String>,
+        text: &str,
+    ) -> Self {
+        let mut variables: Vec<String> = Vec::new();
+        for caps in placeholder().captures_iter(text) {
+            if !variables.iter().any(|v| v == &caps[1]) {
+                variables.push(caps[1].to_string());
+            }
+        }
+        Self {
+            name: name.to_string(),
+            version,
+            source,
+            path,
+            variables,
+            text: text.to_string(),
+        }
+    }
+
+    /// The text with each `{name}` in `vars` replaced by its value. Values
+    /// are inserted as-is, so placeholders inside them are not expanded.
+    pub fn render(&self, vars: &[(&str, &str)]) -> String {
+        placeholder()
+            .replace_all(&self.text, |caps: &Captures| {
+                vars.iter()
+                    .find(|(name, _)| *name == &caps[1])
+                    .map(|(_, value)| value.to_string())
+                    .unwrap_or_else(|| caps[0].to_string())
+            })
+            .into_owned()
+    }
+}
+
+fn placeholder() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\{([a-z][a-z0-9_]*)\}").expect("valid placeholder regex"))
+}
+
+/// Template names are lowercase words joined by `.`, `_` or `-`, with an
+/// optional `@provider` suffix.
+pub fn validate_name(name: &str) -> Result<(), String> {
+    let valid = |part: &str| {
+        !part.is_empty()
+            && part
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "._-".contains(c))
+    };
+    let (base, provider) = match name.split_once('@') {
+        Some((base, provider)) => (base, Some(provider)),
+        None => (name, None),
+    };
+    if valid(base) && provider.is_none_or(valid) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Prompt template name '{name}' must be lowercase letters, digits, '.', '_' or '-', optionally followed by @provider"
+        ))
+    }
+}
+
+/// Split an optional leading `version: N` line off a template file.
+fn parse_file(contents: &str) -> (Option<u32>, &str) {
+    let contents = contents.trim_start_matches('\u{feff}');
+    if let Some((first, rest)) = contents.split_once('\n') {
+        if let Some(version) = first
+            .trim()
+            .strip_prefix("version:")
+            .and_then(|v| v.trim().parse().ok())
+        {
+            return (Some(version), rest.trim());
+        }
+    }
+    (None, contents.trim())
+}
+
+#[derive(Default)]
+struct Registry {
+    config: BTreeMap<String, PromptOverride>,
+    directory: Option<PathBuf>,
+    files: BTreeMap<String, PromptTemplate>,
+}
+
+impl Registry {
+    fn builtin_version(name: &str) -> Option<u32> {
+        BUILTINS.iter().find(|b| b.name == name).map(|b| b.version)
+    }
+
+    /// Override version when none is declared: one past the built-in one.
+    fn next_version(name: &str) -> u32 {
+        Self::builtin_version(name).map_or(1, |v| v + 1)
+    }
+
+    fn get(&self, name: &str) -> Option<PromptTemplate> {
+        if let Some(template) = self.files.get(name) {
+            return Some(template.clone());
+        }
+        if let Some(o) = self.config.get(name) {
+            let version = o.version.unwrap_or_else(|| Self::next_version(name));
+            return Some(PromptTemplate::new(
+                name,
+                version,
+                PromptSource::Config,
+                None,
+                &o.text,
+            ));
+        }
+        BUILTINS
+            .iter()
+            .find(|b| b.name == name)
+            .map(|b| PromptTemplate::new(b.name, b.version, PromptSource::Builtin, None, b.text))
+    }
+
+    fn resolve(&self, name: &str, provider: Option<&str>) -> Option<PromptTemplate> {
+        provider
+            .and_then(|p| self.get(&format!("{name}@{}", p.to_ascii_lowercase())))
+            .or_else(|| self.get(name))
+    }
+
+    fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = BUILTINS.iter().map(|b| b.name.to_string()).collect();
+        names.extend(self.config.keys().cloned());
+        names.extend(self.files.keys().cloned());
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Re-read the template files; a missing directory means no files.
+    fn load_files(&mut self) -> Result<(), String> {
+        let Some(dir) = &self.directory else {
+            self.files.clear();
+            return Ok(());
+        };
+        if !dir.is_dir() {
+            self.files.clear();
+            return Ok(());
+        }
+        let entries = fs::read_dir(dir)
+            .map_err(|e| format!("Failed to read prompts directory {}: {e}", dir.display()))?;
+        let mut files = BTreeMap::new();
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.extension().and_then(|e| e.to_str()) != Some(FILE_EXTENSION) {
+                continue;
+            }
+            let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            if let Err(e) = validate_name(name) {
+                warn!("Skipping prompt template {}: {e}", path.display());
+                continue;
+            }
+            let contents = match fs::read_to_string(&path) {
+                Ok(contents) => contents,
+                Err(e) => {
+                    warn!("Failed to read prompt template {}: {e}", path.display());
+                    continue;
+                }
+            };
+            let (version, text) = parse_file(&contents);
+            if text.is_empty() {
+                warn!("Skipping empty prompt template {}", path.display());
+                continue;
+            }
+            let version = version.unwrap_or_else(|| Self::next_version(name));
+            let template = PromptTemplate::new(
+                name,
+                version,
+                PromptSource::File,
+                Some(path.display().to_string()),
+                text,
+            );
+            files.insert(name.to_string(), template);
+        }
+        self.files = files;
+        Ok(())
+    }
+}
+
+fn registry() -> &'static RwLock<Registry> {
+    static REGISTRY: OnceLock<RwLock<Registry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(Registry::default()))
+}
+
+// Kept apart from the registry so dropping a watcher never waits on a
+// reload that holds the registry lock
+static WATCHER: Mutex<Option<(PathBuf, RecommendedWatcher)>> = Mutex::new(None);
+
+/// Default template directory under the data root
+pub fn default_directory() -> PathBuf {
+    Path::new(profile::DATA_ROOT).join("prompts")
+}
+
+/// Apply `config`, load the template files and watch their directory.
+pub fn configure(config: &PromptConfig) {
+    let directory = config
+        .directory
+        .as_deref()
+        .map(PathBuf::from)
+        .unwrap_or_else(default_directory);
+    {
+        let mut registry = registry().write().unwrap_or_else(|p| p.into_inner());
+        registry.config = config
+            .templates
+            .iter()
+            .map(|(name, o)| (name.clone(), o.clone()))
+            .collect();
+        registry.directory = Some(directory.clone());
+        if let Err(e) = registry.load_files() {
+            warn!("{e}");
+        }
+        info!(
+            "Prompt templates: {} config, {} file override(s)",
+            registry.config.len(),
+            registry.files.len()
+        );
+    }
+    watch(directory);
+}
+
+fn watch(directory: PathBuf) {
+    let mut current = WATCHER.lock().unwrap_or_else(|p| p.into_inner());
+    if current.as_ref().is_some_and(|(dir, _)| *dir == directory) {
+        return;
+    }
+    // Dropping the old watcher stops it
+    *current = None;
+    if !directory.is_dir() {
+        return;
+    }
+    let watcher = recommended_watcher(|res: notify::Result<notify::Event>| match res {
+        Ok(event) if !event.kind.is_access() => {
+            if let Err(e) = reload() {
+                warn!("Prompt template reload failed: {e}");
+            }
+        }
+        Ok(_) => {}
+        Err(e) => warn!("Prompt template watch error: {e}"),
+    })
+    .and_then(|mut watcher| {
+        watcher
+            .watch(&directory, RecursiveMode::NonRecursive)
+            .map(|()| watcher)
+    });
+    match watcher {
+        Ok(watcher) => *current = Some((directory, watcher)),
+        Err(e) => warn!(
+            "Not watching prompt templates in {}: {e}",
+            directory.display()
+        ),
+    }
+}
+
+/// Re-read the template files and return every effective template.
+pub fn reload() -> Result<Vec<PromptTemplate>, String> {
+    registry()
+        .write()
+        .unwrap_or_else(|p| p.into_inner())
+        .load_files()?;
+    Ok(list())
+}
+
+/// Every effective template, by name.
+pub fn list() -> Vec<PromptTemplate> {
+    let registry = registry().read().unwrap_or_else(|p| p.into_inner());
+    registry
+        .names()
+        .iter()
+        .filter_map(|name| registry.get(name))
+        .collect()
+}
+
+/// Effective template for `name`, preferring the `provider` variant.
+pub fn get(name: &str, provider: Option<&str>) -> Option<PromptTemplate> {
+    registry()
+        .read()
+        .unwrap_or_else(|p| p.into_inner())
+        .resolve(name, provider)
+}
+
+/// Render `name` for `provider` with `vars` plus the global variables.
+/// Unknown names render as empty text; callers pass the constants above.
+pub fn render(name: &str, provider: Option<&str>, vars: &[(&str, &str)]) -> String {
+    let Some(template) = get(name, provider) else {
+        warn!("Unknown prompt template '{name}'");
+        return String::new();
+    };
+    let hostname = sysinfo::System::host_name().unwrap_or_default();
+    let os = sysinfo::System::long_os_version().unwrap_or_else(|| std::env::consts::OS.into());
+    let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let mut all: Vec<(&str, &str)> = vars.to_vec();
+    all.extend([
+        ("hostname", hostname.as_str()),
+        ("os", os.as_str()),
+        ("date", date.as_str()),
+        ("locale", i18n::locale().code()),
+    ]);
+    template.render(&all)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_placeholders_and_leaves_json_alone() {
+        let template = Registry::default()
+            .get("threat_analysis@gemini")
+            .expect("built-in template");
+        assert_eq!(
+            template.variables,
+            ["indicator_hint", "technique_hint", "grounding", "snapshot"]
+        );
+        let text = template.render(&[("snapshot", "{role}"), ("grounding", "When possible,")]);
+        assert!(text.contains(r#"{"id": string, "kind""#));
+        assert!(text.contains("- When possible, Use Google Search"));
+        assert!(text.ends_with("Snapshot:\n{role}"));
+        assert!(text.contains("- {indicator_hint}"));
+
+        for builtin in BUILTINS {
+            assert!(validate_name(builtin.name).is_ok(), "{}", builtin.name);
+        }
+        assert!(validate_name("Threat Analysis").is_err());
+    }
+
+    #[test]
+    fn provider_variants_and_overrides() {
+        let dir = std::env::temp_dir().join(format!("oxide-prompts-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("analyst.system.txt"),
+            "version: 7\nYou analyze {hostname}.\n",
+        )
+        .unwrap();
+        fs::write(dir.join("threat_analysis@openai.txt"), "Rate {snapshot}").unwrap();
+        fs::write(dir.join("notes.md"), "ignored").unwrap();
+
+        let mut registry = Registry {
+            directory: Some(dir.clone()),
+            ..Default::default()
+        };
+        registry.config.insert(
+            ANALYST_SYSTEM.to_string(),
+            PromptOverride {
+                text: "From config".to_string(),
+                version: None,
+            },
+        );
+        assert_eq!(registry.get(ANALYST_SYSTEM).unwrap().version, 2);
+        registry.load_files().unwrap();
+
+        let analyst = registry.get(ANALYST_SYSTEM).unwrap();
+        assert_eq!(analyst.source, PromptSource::File);
+        assert_eq!(analyst.version, 7);
+        assert_eq!(analyst.text, "You analyze {hostname}.");
+
+        let openai = registry.resolve(THREAT_ANALYSIS, Some("OpenAI")).unwrap();
+        assert_eq!(
+            (openai.name.as_str(), openai.version),
+            ("threat_analysis@openai", 1)
+        );
+        let qwen = registry.resolve(THREAT_ANALYSIS, Some("qwen")).unwrap();
+        assert_eq!(qwen.name, THREAT_ANALYSIS);
+        assert_eq!(qwen.source, PromptSource::Builtin);
+        assert!(registry
+            .names()
+            .contains(&"threat_analysis@openai".to_string()));
+
+        fs::remove_dir_all(&dir).unwrap();
+        registry.load_files().unwrap();
+        assert_eq!(
+            registry.get(ANALYST_SYSTEM).unwrap().source,
+            PromptSource::Config
+        );
+    }
+}
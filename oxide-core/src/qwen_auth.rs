@@ -16,6 +16,8 @@ pub enum QwenAuthError {
     Env(String),
     #[error("Auth error: {0}")]
     Auth(String),
+    #[error(transparent)]
+    Offline(#[from] crate::network::OfflineError),
 }
 
 const QWEN_AUTH_SERVICE: &str = "oxide_pilot_qwen";
@@ -129,7 +131,8 @@ impl QwenAuth {
             interval: Option<u64>,
         }
 
-        let client = reqwest::Client::new();
+        crate::network::ensure_online(&device_url)?;
+        let client = crate::network::http_client();
         let res = client
             .post(&device_url)
             .form(&Req {
@@ -200,7 +203,8 @@ impl QwenAuth {
             error_description: Option<String>,
         }
 
-        let client = reqwest::Client::new();
+        crate::network::ensure_online(&token_url)?;
+        let client = crate::network::http_client();
         let res = client
             .post(&token_url)
             .form(&TokenReq {
@@ -0,0 +1,35 @@
+use keyring::Entry;
+use thiserror::Error;
+
+const REMOTE_SERVICE_ID: &str = "oxide_pilot_remote";
+
+#[derive(Error, Debug)]
+pub enum RemoteCredentialsError {
+    #[error("Keyring error: {0}")]
+    Keyring(#[from] keyring::Error),
+}
+
+/// MCP tokens for remote instances, stored per remote host id.
+fn entry(host_id: &str) -> Result<Entry, RemoteCredentialsError> {
+    Ok(Entry::new(REMOTE_SERVICE_ID, host_id)?)
+}
+
+pub fn store_token(host_id: &str, token: &str) -> Result<(), RemoteCredentialsError> {
+    entry(host_id)?.set_password(token)?;
+    Ok(())
+}
+
+pub fn get_token(host_id: &str) -> Result<Option<String>, RemoteCredentialsError> {
+    match entry(host_id)?.get_password() {
+        Ok(v) => Ok(Some(v)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub fn clear_token(host_id: &str) -> Result<(), RemoteCredentialsError> {
+    match entry(host_id)?.delete_password() {
+        Ok(_) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
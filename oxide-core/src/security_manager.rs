@@ -1,8 +1,16 @@
 use crate::encryption::{EncryptedData, EncryptionManager};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
 use chrono::{DateTime, Utc};
 use log::{error, info, warn};
+use rand::RngCore;
+use ring::pbkdf2;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::num::NonZeroU32;
+use std::path::PathBuf;
+use std::str::FromStr;
 use std::time::{Duration, SystemTime};
 use thiserror::Error;
 use tokio::sync::RwLock;
@@ -24,6 +32,178 @@ pub enum SecurityError {
     EncryptionError(String),
     #[error("Security policy violation: {0}")]
     PolicyViolation(String),
+    #[error("Storage error: {0}")]
+    Storage(String),
+}
+
+/// Where role assignments and their passphrase hashes are kept.
+pub const ROLES_PATH: &str = "./data/roles.json";
+/// Shortest passphrase accepted for a role above viewer.
+pub const MIN_PASSPHRASE_LEN: usize = 8;
+const PBKDF2_ITERATIONS: u32 = 100_000;
+const SALT_LEN: usize = 16;
+const HASH_LEN: usize = 32;
+
+/// Built-in roles, ordered from least to most privileged. Each role inherits
+/// every permission granted to the roles below it.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    #[default]
+    Viewer,
+    Operator,
+    Admin,
+}
+
+/// Wildcard permission that matches every permission.
+pub const PERMISSION_ALL: &str = "*";
+
+const VIEWER_PERMISSIONS: &[&str] = &["system.monitor", "config.view", "security.view"];
+const OPERATOR_PERMISSIONS: &[&str] = &[
+    "system.control",
+    "rpa.execute",
+    "scan.execute",
+    "data.access",
+];
+const ADMIN_PERMISSIONS: &[&str] = &["config.modify", "security.manage"];
+
+impl Role {
+    pub const ALL: [Role; 3] = [Role::Viewer, Role::Operator, Role::Admin];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Viewer => "viewer",
+            Role::Operator => "operator",
+            Role::Admin => "admin",
+        }
+    }
+
+    /// Permissions introduced by this role, excluding inherited ones.
+    fn own_permissions(&self) -> &'static [&'static str] {
+        match self {
+            Role::Viewer => VIEWER_PERMISSIONS,
+            Role::Operator => OPERATOR_PERMISSIONS,
+            Role::Admin => ADMIN_PERMISSIONS,
+        }
+    }
+
+    /// All permissions granted by this role, including inherited ones.
+    pub fn permissions(&self) -> Vec<&'static str> {
+        Role::ALL
+            .iter()
+            .filter(|role| self.includes(**role))
+            .flat_map(|role| role.own_permissions().iter().copied())
+            .collect()
+    }
+
+    /// Whether this role is at least as privileged as `other`.
+    pub fn includes(&self, other: Role) -> bool {
+        *self >= other
+    }
+
+    pub fn grants(&self, permission: &str) -> bool {
+        if *self == Role::Admin {
+            return true;
+        }
+        self.permissions()
+            .iter()
+            .any(|granted| permission_matches(granted, permission))
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Role {
+    type Err = SecurityError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Accept the legacy EncryptionManager role names as aliases.
+        match s.trim().to_ascii_lowercase().as_str() {
+            "viewer" | "readonly" => Ok(Role::Viewer),
+            "operator" | "user" => Ok(Role::Operator),
+            "admin" => Ok(Role::Admin),
+            other => Err(SecurityError::InvalidInput(format!(
+                "Unknown role: {other}"
+            ))),
+        }
+    }
+}
+
+/// A user's role and the passphrase that proves it. Viewer needs no
+/// passphrase; every other role is only granted to sessions that present it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RoleAssignment {
+    role: Role,
+    /// Base64 PBKDF2-HMAC-SHA256 salt and hash of the passphrase
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    salt: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    passphrase_hash: Option<String>,
+}
+
+impl RoleAssignment {
+    fn new(role: Role, passphrase: &str) -> Self {
+        let mut salt = [0u8; SALT_LEN];
+        rand::thread_rng().fill_bytes(&mut salt);
+        let mut hash = [0u8; HASH_LEN];
+        pbkdf2::derive(
+            pbkdf2::PBKDF2_HMAC_SHA256,
+            pbkdf2_iterations(),
+            &salt,
+            passphrase.as_bytes(),
+            &mut hash,
+        );
+        Self {
+            role,
+            salt: Some(STANDARD.encode(salt)),
+            passphrase_hash: Some(STANDARD.encode(hash)),
+        }
+    }
+
+    fn has_passphrase(&self) -> bool {
+        self.passphrase_hash.is_some()
+    }
+
+    fn verify(&self, passphrase: &str) -> bool {
+        let (Some(salt), Some(hash)) = (&self.salt, &self.passphrase_hash) else {
+            return false;
+        };
+        let (Ok(salt), Ok(hash)) = (STANDARD.decode(salt), STANDARD.decode(hash)) else {
+            return false;
+        };
+        pbkdf2::verify(
+            pbkdf2::PBKDF2_HMAC_SHA256,
+            pbkdf2_iterations(),
+            &salt,
+            passphrase.as_bytes(),
+            &hash,
+        )
+        .is_ok()
+    }
+}
+
+fn pbkdf2_iterations() -> NonZeroU32 {
+    NonZeroU32::new(PBKDF2_ITERATIONS).expect("iteration count is non-zero")
+}
+
+/// Returns true if `granted` covers `requested`. Supports the global `*`
+/// wildcard and namespace wildcards such as `system.*`.
+pub fn permission_matches(granted: &str, requested: &str) -> bool {
+    if granted == PERMISSION_ALL || granted == requested {
+        return true;
+    }
+    match granted.strip_suffix(".*") {
+        Some(prefix) => requested
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with('.')),
+        None => false,
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,12 +213,44 @@ pub struct SecuritySession {
     pub created_at: DateTime<Utc>,
     pub last_activity: DateTime<Utc>,
     pub expires_at: DateTime<Utc>,
+    /// Role proven when the session was created: the user's assigned role
+    /// if they gave its passphrase, viewer otherwise.
+    #[serde(default)]
+    pub role: Role,
+    /// Optional scope restriction. When non-empty, only permissions matched by
+    /// one of these entries (and granted by the role) are allowed.
     pub permissions: Vec<String>,
     pub ip_address: Option<String>,
     pub user_agent: Option<String>,
     pub is_active: bool,
 }
 
+impl SecuritySession {
+    pub fn has_permission(&self, permission: &str) -> bool {
+        if !self.role.grants(permission) {
+            return false;
+        }
+        self.permissions.is_empty()
+            || self
+                .permissions
+                .iter()
+                .any(|scope| permission_matches(scope, permission))
+    }
+
+    /// Effective permissions for this session after applying the scope.
+    pub fn effective_permissions(&self) -> Vec<String> {
+        if self.role == Role::Admin && self.permissions.is_empty() {
+            return vec![PERMISSION_ALL.to_string()];
+        }
+        self.role
+            .permissions()
+            .into_iter()
+            .filter(|p| self.has_permission(p))
+            .map(str::to_string)
+            .collect()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SecurityEvent {
     pub event_id: String,
@@ -66,6 +278,10 @@ pub enum SecurityEventType {
     ConfigurationChange,
     EncryptionFailure,
     PolicyViolation,
+    // A process was terminated, suspended or resumed
+    ProcessControl,
+    // An incident response playbook ran or raised an alert
+    PlaybookRun,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -100,6 +316,10 @@ pub struct SecurityPolicy {
     pub blocked_ip_addresses: Vec<String>,
     pub enable_audit_logging: bool,
     pub data_retention_days: u32,
+    /// Reject guarded commands that are invoked without a session. `None`
+    /// enforces it once any user holds the admin role.
+    #[serde(default)]
+    pub enforce_command_permissions: Option<bool>,
 }
 
 impl Default for SecurityPolicy {
@@ -114,6 +334,7 @@ impl Default for SecurityPolicy {
             blocked_ip_addresses: Vec::new(),
             enable_audit_logging: true,
             data_retention_days: 90,
+            enforce_command_permissions: None,
         }
     }
 }
@@ -122,8 +343,10 @@ pub struct SecurityManager {
     sessions: RwLock<HashMap<String, SecuritySession>>,
     security_events: RwLock<Vec<SecurityEvent>>,
     rate_limits: RwLock<HashMap<String, RateLimitEntry>>,
-    #[allow(dead_code)]
     failed_attempts: RwLock<HashMap<String, (u32, SystemTime)>>,
+    role_assignments: RwLock<HashMap<String, RoleAssignment>>,
+    /// File the role assignments are saved to; `None` keeps them in memory
+    roles_path: Option<PathBuf>,
     encryption_manager: EncryptionManager,
     policy: RwLock<SecurityPolicy>,
     rate_limit_config: RateLimitConfig,
@@ -139,6 +362,8 @@ impl SecurityManager {
             security_events: RwLock::new(Vec::new()),
             rate_limits: RwLock::new(HashMap::new()),
             failed_attempts: RwLock::new(HashMap::new()),
+            role_assignments: RwLock::new(HashMap::new()),
+            roles_path: None,
             encryption_manager,
             policy: RwLock::new(SecurityPolicy::default()),
             rate_limit_config: RateLimitConfig {
@@ -149,13 +374,49 @@ impl SecurityManager {
         })
     }
 
+    /// Keep role assignments in `path` and load the ones already saved there,
+    /// so roles (and the enforcement that follows an admin) survive restarts.
+    /// A missing file is an empty store; an unreadable one is an error rather
+    /// than silently dropping every role.
+    pub fn with_role_store(mut self, path: impl Into<PathBuf>) -> Result<Self, SecurityError> {
+        let path = path.into();
+        match fs::read_to_string(&path) {
+            Ok(json) => {
+                let assignments = serde_json::from_str(&json).map_err(|e| {
+                    SecurityError::Storage(format!("Invalid role assignments in {path:?}: {e}"))
+                })?;
+                *self.role_assignments.get_mut() = assignments;
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => {
+                return Err(SecurityError::Storage(format!(
+                    "Failed to read role assignments from {path:?}: {e}"
+                )))
+            }
+        }
+        self.roles_path = Some(path);
+        Ok(self)
+    }
+
+    /// Open a session for `user_id`. The session only gets the user's
+    /// assigned role when `passphrase` matches the one set with the role;
+    /// without a passphrase it is a viewer session. Wrong passphrases count
+    /// towards the policy's lockout.
     pub async fn create_session(
         &self,
         user_id: String,
+        passphrase: Option<&str>,
         permissions: Vec<String>,
         ip_address: Option<String>,
         user_agent: Option<String>,
     ) -> Result<SecuritySession, SecurityError> {
+        let role = match passphrase {
+            Some(passphrase) => {
+                self.authenticate(&user_id, passphrase, ip_address.clone())
+                    .await?
+            }
+            None => Role::Viewer,
+        };
         let policy = self.policy.read().await;
         let session_id = Uuid::new_v4().to_string();
         let now = Utc::now();
@@ -170,6 +431,7 @@ impl SecurityManager {
             created_at: now,
             last_activity: now,
             expires_at,
+            role,
             permissions,
             ip_address: ip_address.clone(),
             user_agent,
@@ -191,10 +453,69 @@ impl SecurityManager {
         )
         .await;
 
-        info!("Created new session for user: {}", session.user_id);
+        info!(
+            "Created new session for user: {} (role: {role})",
+            session.user_id
+        );
         Ok(session)
     }
 
+    /// The role `passphrase` proves for `user_id`.
+    async fn authenticate(
+        &self,
+        user_id: &str,
+        passphrase: &str,
+        ip_address: Option<String>,
+    ) -> Result<Role, SecurityError> {
+        let (max_attempts, lockout) = {
+            let policy = self.policy.read().await;
+            (policy.max_failed_attempts, policy.lockout_duration)
+        };
+        let now = SystemTime::now();
+        if let Some((count, last)) = self.failed_attempts.read().await.get(user_id) {
+            if *count >= max_attempts && now < *last + lockout {
+                return Err(SecurityError::AuthenticationFailed(
+                    "Too many failed attempts; try again later".to_string(),
+                ));
+            }
+        }
+
+        let role = self
+            .role_assignments
+            .read()
+            .await
+            .get(user_id)
+            .filter(|assignment| assignment.verify(passphrase))
+            .map(|assignment| assignment.role);
+        let Some(role) = role else {
+            let mut failed = self.failed_attempts.write().await;
+            let entry = failed.entry(user_id.to_string()).or_insert((0, now));
+            // Start counting again once an earlier lockout has passed
+            if now >= entry.1 + lockout {
+                entry.0 = 0;
+            }
+            entry.0 += 1;
+            entry.1 = now;
+            let attempts = entry.0;
+            drop(failed);
+            self.log_security_event(
+                SecurityEventType::LoginFailure,
+                SecuritySeverity::Medium,
+                Some(user_id.to_string()),
+                None,
+                "Wrong passphrase or no role passphrase set".to_string(),
+                HashMap::from([("attempts".to_string(), attempts.to_string())]),
+                ip_address,
+            )
+            .await;
+            return Err(SecurityError::AuthenticationFailed(
+                "Invalid user or passphrase".to_string(),
+            ));
+        };
+        self.failed_attempts.write().await.remove(user_id);
+        Ok(role)
+    }
+
     pub async fn validate_session(
         &self,
         session_id: &str,
@@ -241,9 +562,7 @@ impl SecurityManager {
     ) -> Result<bool, SecurityError> {
         let session = self.validate_session(session_id).await?;
 
-        let has_permission = self
-            .encryption_manager
-            .has_permission(&session.user_id, permission);
+        let has_permission = session.has_permission(permission);
 
         if !has_permission {
             self.log_security_event(
@@ -252,7 +571,10 @@ impl SecurityManager {
                 Some(session.user_id),
                 Some(session_id.to_string()),
                 format!("Permission denied for: {permission}"),
-                HashMap::from([("permission".to_string(), permission.to_string())]),
+                HashMap::from([
+                    ("permission".to_string(), permission.to_string()),
+                    ("role".to_string(), session.role.to_string()),
+                ]),
                 session.ip_address,
             )
             .await;
@@ -261,6 +583,169 @@ impl SecurityManager {
         Ok(has_permission)
     }
 
+    /// Like `check_permission`, but turns a denial into an error so callers
+    /// can use `?` to guard privileged operations.
+    pub async fn require_permission(
+        &self,
+        session_id: &str,
+        permission: &str,
+    ) -> Result<SecuritySession, SecurityError> {
+        if self.check_permission(session_id, permission).await? {
+            self.validate_session(session_id).await
+        } else {
+            Err(SecurityError::AuthorizationDenied(format!(
+                "missing permission: {permission}"
+            )))
+        }
+    }
+
+    /// Assign a role to a user. Roles above viewer need a passphrase, which
+    /// the user then gives to open a session with the role; `None` keeps the
+    /// passphrase already set. Active sessions are demoted to the new role if
+    /// it is lower, but never promoted: that takes a new session.
+    pub async fn assign_role(
+        &self,
+        user_id: &str,
+        role: Role,
+        passphrase: Option<&str>,
+    ) -> Result<(), SecurityError> {
+        if user_id.trim().is_empty() {
+            return Err(SecurityError::InvalidInput(
+                "user_id must not be empty".to_string(),
+            ));
+        }
+        if passphrase.is_some_and(|p| p.chars().count() < MIN_PASSPHRASE_LEN) {
+            return Err(SecurityError::InvalidInput(format!(
+                "Passphrase must be at least {MIN_PASSPHRASE_LEN} characters"
+            )));
+        }
+
+        let mut assignments = self.role_assignments.write().await;
+        let existing = assignments.get(user_id).cloned();
+        let assignment = match (passphrase, &existing) {
+            (Some(passphrase), _) => RoleAssignment::new(role, passphrase),
+            (None, Some(existing)) if existing.has_passphrase() => RoleAssignment {
+                role,
+                ..existing.clone()
+            },
+            (None, _) if role == Role::Viewer => RoleAssignment {
+                role,
+                salt: None,
+                passphrase_hash: None,
+            },
+            (None, _) => {
+                return Err(SecurityError::InvalidInput(format!(
+                    "A passphrase is required for the '{role}' role"
+                )))
+            }
+        };
+        assignments.insert(user_id.to_string(), assignment);
+        if let Err(e) = self.save_roles(&assignments) {
+            match existing {
+                Some(existing) => assignments.insert(user_id.to_string(), existing),
+                None => assignments.remove(user_id),
+            };
+            return Err(e);
+        }
+        drop(assignments);
+        let previous = existing.map(|a| a.role);
+        self.apply_role_to_sessions(user_id, role).await;
+
+        let mut metadata = HashMap::from([("role".to_string(), role.to_string())]);
+        if let Some(previous) = previous {
+            metadata.insert("previous_role".to_string(), previous.to_string());
+        }
+        self.log_security_event(
+            SecurityEventType::ConfigurationChange,
+            SecuritySeverity::Medium,
+            Some(user_id.to_string()),
+            None,
+            format!("Role '{role}' assigned"),
+            metadata,
+            None,
+        )
+        .await;
+
+        Ok(())
+    }
+
+    /// Remove a user's role assignment and passphrase, reverting them to the
+    /// default role.
+    pub async fn revoke_role(&self, user_id: &str) -> Result<Option<Role>, SecurityError> {
+        let mut assignments = self.role_assignments.write().await;
+        let removed = assignments.remove(user_id);
+        if let Some(removed) = &removed {
+            if let Err(e) = self.save_roles(&assignments) {
+                assignments.insert(user_id.to_string(), removed.clone());
+                return Err(e);
+            }
+        }
+        drop(assignments);
+        let previous = removed.map(|a| a.role);
+
+        if let Some(previous) = previous {
+            self.apply_role_to_sessions(user_id, Role::default()).await;
+            self.log_security_event(
+                SecurityEventType::ConfigurationChange,
+                SecuritySeverity::Medium,
+                Some(user_id.to_string()),
+                None,
+                format!("Role '{previous}' revoked"),
+                HashMap::from([("previous_role".to_string(), previous.to_string())]),
+                None,
+            )
+            .await;
+        }
+
+        Ok(previous)
+    }
+
+    pub async fn get_user_role(&self, user_id: &str) -> Role {
+        self.role_assignments
+            .read()
+            .await
+            .get(user_id)
+            .map(|a| a.role)
+            .unwrap_or_default()
+    }
+
+    pub async fn list_role_assignments(&self) -> HashMap<String, Role> {
+        self.role_assignments
+            .read()
+            .await
+            .iter()
+            .map(|(user, a)| (user.clone(), a.role))
+            .collect()
+    }
+
+    async fn apply_role_to_sessions(&self, user_id: &str, role: Role) {
+        let mut sessions = self.sessions.write().await;
+        for session in sessions.values_mut().filter(|s| s.user_id == user_id) {
+            session.role = session.role.min(role);
+        }
+    }
+
+    fn save_roles(
+        &self,
+        assignments: &HashMap<String, RoleAssignment>,
+    ) -> Result<(), SecurityError> {
+        let Some(path) = &self.roles_path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| SecurityError::Storage(format!("Failed to create {dir:?}: {e}")))?;
+        }
+        let json = serde_json::to_string_pretty(assignments)
+            .map_err(|e| SecurityError::Storage(e.to_string()))?;
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, json)
+            .and_then(|_| fs::rename(&tmp, path))
+            .map_err(|e| {
+                SecurityError::Storage(format!("Failed to save role assignments to {path:?}: {e}"))
+            })
+    }
+
     pub async fn check_rate_limit(&self, identifier: &str) -> Result<(), SecurityError> {
         let mut rate_limits = self.rate_limits.write().await;
         let now = SystemTime::now();
@@ -421,6 +906,48 @@ impl SecurityManager {
         self.policy.read().await.clone()
     }
 
+    /// Whether guarded commands must carry a session. An explicit policy
+    /// setting wins; otherwise this turns on once an admin has been assigned.
+    pub async fn command_permissions_enforced(&self) -> bool {
+        if let Some(enforce) = self.policy.read().await.enforce_command_permissions {
+            return enforce;
+        }
+        self.role_assignments
+            .read()
+            .await
+            .values()
+            .any(|assignment| assignment.role == Role::Admin)
+    }
+
+    pub async fn set_command_permission_enforcement(&self, enforce: Option<bool>) {
+        let previous = std::mem::replace(
+            &mut self.policy.write().await.enforce_command_permissions,
+            enforce,
+        );
+        if previous == enforce {
+            return;
+        }
+
+        let setting = |value: Option<bool>| match value {
+            Some(true) => "on",
+            Some(false) => "off",
+            None => "auto",
+        };
+        self.log_security_event(
+            SecurityEventType::ConfigurationChange,
+            SecuritySeverity::High,
+            None,
+            None,
+            format!(
+                "Command permission enforcement set to '{}'",
+                setting(enforce)
+            ),
+            HashMap::from([("previous".to_string(), setting(previous).to_string())]),
+            None,
+        )
+        .await;
+    }
+
     /// Decrypt data encrypted with the system's EncryptionManager.
     /// Returns plaintext bytes on success.
     pub fn decrypt_data(&self, encrypted: &EncryptedData) -> Result<Vec<u8>, SecurityError> {
@@ -429,3 +956,221 @@ impl SecurityManager {
             .map_err(|e| SecurityError::EncryptionError(e.to_string()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager() -> SecurityManager {
+        SecurityManager::new(&EncryptionManager::generate_key()).unwrap()
+    }
+
+    #[test]
+    fn test_role_hierarchy() {
+        assert!(Role::Admin.includes(Role::Operator));
+        assert!(Role::Operator.includes(Role::Viewer));
+        assert!(!Role::Viewer.includes(Role::Operator));
+
+        assert!(Role::Viewer.grants("system.monitor"));
+        assert!(!Role::Viewer.grants("system.control"));
+        assert!(Role::Operator.grants("system.monitor"));
+        assert!(Role::Operator.grants("rpa.execute"));
+        assert!(!Role::Operator.grants("security.manage"));
+        assert!(Role::Admin.grants("security.manage"));
+    }
+
+    #[test]
+    fn test_role_parsing() {
+        assert_eq!("Operator".parse::<Role>().unwrap(), Role::Operator);
+        assert_eq!("readonly".parse::<Role>().unwrap(), Role::Viewer);
+        assert!("root".parse::<Role>().is_err());
+    }
+
+    #[test]
+    fn test_permission_wildcards() {
+        assert!(permission_matches("*", "config.modify"));
+        assert!(permission_matches("system.*", "system.control"));
+        assert!(!permission_matches("system.*", "systemd.control"));
+        assert!(!permission_matches("config.view", "config.modify"));
+    }
+
+    #[tokio::test]
+    async fn test_session_role_needs_passphrase() {
+        let manager = manager();
+        manager
+            .assign_role("alice", Role::Operator, Some("correct horse"))
+            .await
+            .unwrap();
+
+        // Naming the user is not enough to get their role
+        let anonymous = manager
+            .create_session("alice".to_string(), None, Vec::new(), None, None)
+            .await
+            .unwrap();
+        assert_eq!(anonymous.role, Role::Viewer);
+        assert!(manager
+            .create_session("alice".to_string(), Some("wrong"), Vec::new(), None, None)
+            .await
+            .is_err());
+
+        let session = manager
+            .create_session(
+                "alice".to_string(),
+                Some("correct horse"),
+                Vec::new(),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(manager
+            .check_permission(&session.session_id, "system.control")
+            .await
+            .unwrap());
+
+        // A later assignment never promotes the anonymous session
+        manager
+            .assign_role("alice", Role::Admin, None)
+            .await
+            .unwrap();
+        assert!(!manager
+            .check_permission(&anonymous.session_id, "system.control")
+            .await
+            .unwrap());
+
+        manager.revoke_role("alice").await.unwrap();
+        let err = manager
+            .require_permission(&session.session_id, "system.control")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SecurityError::AuthorizationDenied(_)));
+        assert!(manager
+            .assign_role("alice", Role::Operator, None)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_failed_passphrases_lock_out() {
+        let manager = manager();
+        manager
+            .assign_role("mallory", Role::Admin, Some("long passphrase"))
+            .await
+            .unwrap();
+        for _ in 0..SecurityPolicy::default().max_failed_attempts {
+            let _ = manager
+                .create_session("mallory".to_string(), Some("guess"), Vec::new(), None, None)
+                .await;
+        }
+        assert!(manager
+            .create_session(
+                "mallory".to_string(),
+                Some("long passphrase"),
+                Vec::new(),
+                None,
+                None
+            )
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_role_assignments_survive_reload() {
+        let dir = std::env::temp_dir().join(format!("oxide-roles-{}", Uuid::new_v4()));
+        let path = dir.join("roles.json");
+        let key = EncryptionManager::generate_key();
+        let manager = SecurityManager::new(&key)
+            .unwrap()
+            .with_role_store(&path)
+            .unwrap();
+        manager
+            .assign_role("erin", Role::Admin, Some("admin passphrase"))
+            .await
+            .unwrap();
+        assert!(!fs::read_to_string(&path)
+            .unwrap()
+            .contains("admin passphrase"));
+
+        let reloaded = SecurityManager::new(&key)
+            .unwrap()
+            .with_role_store(&path)
+            .unwrap();
+        assert!(reloaded.command_permissions_enforced().await);
+        let session = reloaded
+            .create_session(
+                "erin".to_string(),
+                Some("admin passphrase"),
+                Vec::new(),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(session.role, Role::Admin);
+
+        fs::write(&path, "not json").unwrap();
+        assert!(SecurityManager::new(&key)
+            .unwrap()
+            .with_role_store(&path)
+            .is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_session_scope_restricts_role() {
+        let manager = manager();
+        manager
+            .assign_role("bob", Role::Admin, Some("bob passphrase"))
+            .await
+            .unwrap();
+        let session = manager
+            .create_session(
+                "bob".to_string(),
+                Some("bob passphrase"),
+                vec!["config.*".to_string()],
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert!(manager
+            .check_permission(&session.session_id, "config.modify")
+            .await
+            .unwrap());
+        assert!(!manager
+            .check_permission(&session.session_id, "security.manage")
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_command_enforcement_follows_admin_assignment() {
+        let manager = manager();
+        assert!(!manager.command_permissions_enforced().await);
+
+        manager
+            .assign_role("carol", Role::Operator, Some("carol passphrase"))
+            .await
+            .unwrap();
+        assert!(!manager.command_permissions_enforced().await);
+
+        manager
+            .assign_role("dave", Role::Admin, Some("dave passphrase"))
+            .await
+            .unwrap();
+        assert!(manager.command_permissions_enforced().await);
+
+        manager
+            .set_command_permission_enforcement(Some(false))
+            .await;
+        assert!(!manager.command_permissions_enforced().await);
+
+        manager.set_command_permission_enforcement(None).await;
+        manager.revoke_role("dave").await.unwrap();
+        assert!(!manager.command_permissions_enforced().await);
+
+        manager.set_command_permission_enforcement(Some(true)).await;
+        assert!(manager.command_permissions_enforced().await);
+    }
+}
@@ -0,0 +1,154 @@
+//! Shared severity levels and the risk scoring rubric.
+//!
+//! Every detection gets a risk score from 0 to 100, and its severity is
+//! the band the score falls in:
+//!
+//! | Score  | Severity | Meaning                                                        |
+//! |--------|----------|----------------------------------------------------------------|
+//! | 0-29   | Low      | Unusual but benign on most systems; logged, no action          |
+//! | 30-59  | Medium   | Suspicious behaviour, e.g. sustained resource abuse or drift   |
+//! | 60-79  | High     | A known-bad indicator: signature, IOC or AV verdict on a file  |
+//! | 80-100 | Critical | Malicious code running, e.g. a signature match in memory       |
+//!
+//! Detectors that only know a severity use [`Severity::score`], the middle
+//! of its band. Scores on a 0-1 scale (heuristic models) go through
+//! [`Severity::from_unit`]. Scores are clamped to 0-100 and NaN counts as 0.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    #[serde(alias = "Low")]
+    Low,
+    #[serde(alias = "Medium")]
+    Medium,
+    #[serde(alias = "High")]
+    High,
+    #[serde(alias = "Critical")]
+    Critical,
+}
+
+impl Severity {
+    pub const ALL: [Severity; 4] = [
+        Severity::Low,
+        Severity::Medium,
+        Severity::High,
+        Severity::Critical,
+    ];
+
+    /// The band a 0-100 risk score falls in.
+    pub fn from_score(score: f64) -> Self {
+        let score = clamp_score(score);
+        Self::ALL
+            .into_iter()
+            .rev()
+            .find(|severity| score >= severity.min_score())
+            .unwrap_or(Severity::Low)
+    }
+
+    /// The band a 0-1 score falls in.
+    pub fn from_unit(score: f64) -> Self {
+        Self::from_score(score * 100.0)
+    }
+
+    /// Lowest score in this band.
+    pub fn min_score(&self) -> f64 {
+        match self {
+            Severity::Low => 0.0,
+            Severity::Medium => 30.0,
+            Severity::High => 60.0,
+            Severity::Critical => 80.0,
+        }
+    }
+
+    /// Representative score for detectors that only know the severity.
+    pub fn score(&self) -> f64 {
+        match self {
+            Severity::Low => 15.0,
+            Severity::Medium => 45.0,
+            Severity::High => 70.0,
+            Severity::Critical => 90.0,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Severity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|severity| severity.as_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("Unknown severity '{s}'"))
+    }
+}
+
+/// Clamp a risk score to 0-100, mapping NaN to 0.
+pub fn clamp_score(score: f64) -> f64 {
+    if score.is_nan() {
+        0.0
+    } else {
+        score.clamp(0.0, 100.0)
+    }
+}
+
+/// Something with a risk score, such as a detection or a scan verdict.
+pub trait Scored {
+    /// Risk score from 0 to 100.
+    fn risk_score(&self) -> f64;
+
+    fn severity(&self) -> Severity {
+        Severity::from_score(self.risk_score())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores_map_to_bands() {
+        assert_eq!(Severity::from_score(0.0), Severity::Low);
+        assert_eq!(Severity::from_score(29.9), Severity::Low);
+        assert_eq!(Severity::from_score(30.0), Severity::Medium);
+        assert_eq!(Severity::from_score(65.0), Severity::High);
+        assert_eq!(Severity::from_score(80.0), Severity::Critical);
+        assert_eq!(Severity::from_score(250.0), Severity::Critical);
+        assert_eq!(Severity::from_score(f64::NAN), Severity::Low);
+        assert_eq!(Severity::from_unit(0.61), Severity::High);
+        for severity in Severity::ALL {
+            assert_eq!(Severity::from_score(severity.score()), severity);
+            assert_eq!(Severity::from_score(severity.min_score()), severity);
+        }
+    }
+
+    #[test]
+    fn parses_and_serializes_lowercase() {
+        assert_eq!("HIGH".parse::<Severity>().unwrap(), Severity::High);
+        assert!("severe".parse::<Severity>().is_err());
+        assert_eq!(
+            serde_json::to_string(&Severity::Critical).unwrap(),
+            "\"critical\""
+        );
+        let legacy: Severity = serde_json::from_str("\"Medium\"").unwrap();
+        assert_eq!(legacy, Severity::Medium);
+    }
+}
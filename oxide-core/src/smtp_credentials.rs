@@ -0,0 +1,46 @@
+use keyring::Entry;
+use thiserror::Error;
+
+const SMTP_SERVICE_ID: &str = "oxide_pilot_smtp";
+
+#[derive(Error, Debug)]
+pub enum SmtpCredentialsError {
+    #[error("Keyring error: {0}")]
+    Keyring(#[from] keyring::Error),
+}
+
+/// Passwords are stored per `username@host` so several relays can coexist.
+fn entry(host: &str, username: &str) -> Result<Entry, SmtpCredentialsError> {
+    Ok(Entry::new(SMTP_SERVICE_ID, &format!("{username}@{host}"))?)
+}
+
+pub fn store_password(
+    host: &str,
+    username: &str,
+    password: &str,
+) -> Result<(), SmtpCredentialsError> {
+    entry(host, username)?.set_password(password)?;
+    Ok(())
+}
+
+pub fn get_password(host: &str, username: &str) -> Result<Option<String>, SmtpCredentialsError> {
+    // Environment variable takes precedence for non-interactive setups
+    if let Ok(value) = std::env::var("OXIDE_SMTP_PASSWORD") {
+        if !value.is_empty() {
+            return Ok(Some(value));
+        }
+    }
+
+    match entry(host, username)?.get_password() {
+        Ok(v) => Ok(Some(v)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub fn clear_password(host: &str, username: &str) -> Result<(), SmtpCredentialsError> {
+    match entry(host, username)?.delete_password() {
+        Ok(_) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
@@ -0,0 +1,174 @@
+//! TLS certificates for the embedded MCP server.
+//!
+//! The server uses the certificate and key the user configured, or a
+//! self-signed certificate for `localhost` generated on first use and kept
+//! in [`TLS_DIR`]. Clients can't verify a self-signed certificate through a
+//! CA, so the SHA-256 [`fingerprint`] is shown in the app for pinning or a
+//! manual comparison.
+
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+pub const TLS_DIR: &str = "./data/tls";
+const CERT_FILE: &str = "mcp-cert.pem";
+const KEY_FILE: &str = "mcp-key.pem";
+
+/// Certificate and key in PEM form, with where they came from.
+#[derive(Debug, Clone)]
+pub struct TlsIdentity {
+    pub cert_pem: String,
+    pub key_pem: String,
+    pub cert_path: PathBuf,
+    /// Generated by the app rather than configured by the user
+    pub self_signed: bool,
+}
+
+impl TlsIdentity {
+    /// Read the configured pair, or the generated one in `dir`, creating it
+    /// if it does not exist yet.
+    pub fn load_or_generate(
+        cert_path: Option<&Path>,
+        key_path: Option<&Path>,
+        dir: &Path,
+    ) -> Result<Self, String> {
+        if let (Some(cert_path), Some(key_path)) = (cert_path, key_path) {
+            return Ok(Self {
+                cert_pem: read(cert_path)?,
+                key_pem: read(key_path)?,
+                cert_path: cert_path.to_path_buf(),
+                self_signed: false,
+            });
+        }
+        let cert_path = dir.join(CERT_FILE);
+        let key_path = dir.join(KEY_FILE);
+        if cert_path.exists() && key_path.exists() {
+            return Ok(Self {
+                cert_pem: read(&cert_path)?,
+                key_pem: read(&key_path)?,
+                cert_path,
+                self_signed: true,
+            });
+        }
+
+        let generated = rcgen::generate_simple_self_signed(vec![
+            "localhost".to_string(),
+            "127.0.0.1".to_string(),
+        ])
+        .map_err(|e| format!("Failed to generate a TLS certificate: {e}"))?;
+        let identity = Self {
+            cert_pem: generated.cert.pem(),
+            key_pem: generated.key_pair.serialize_pem(),
+            cert_path,
+            self_signed: true,
+        };
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {dir:?}: {e}"))?;
+        write_private(&key_path, &identity.key_pem)?;
+        fs::write(&identity.cert_path, &identity.cert_pem)
+            .map_err(|e| format!("Failed to write {:?}: {e}", identity.cert_path))?;
+        log::info!("Generated a self-signed MCP certificate in {dir:?}");
+        Ok(identity)
+    }
+
+    /// SHA-256 of the leaf certificate, as colon-separated hex pairs.
+    pub fn fingerprint(&self) -> Result<String, String> {
+        let leaf = certificates(&self.cert_pem)?.remove(0);
+        Ok(fingerprint(&leaf))
+    }
+
+    pub fn server_config(&self) -> Result<Arc<rustls::ServerConfig>, String> {
+        let certs = certificates(&self.cert_pem)?;
+        let key = rustls_pemfile::private_key(&mut self.key_pem.as_bytes())
+            .map_err(|e| format!("Invalid TLS private key: {e}"))?
+            .ok_or("The TLS key file contains no private key")?;
+        server_config(certs, key)
+    }
+}
+
+pub fn fingerprint(der: &[u8]) -> String {
+    Sha256::digest(der)
+        .iter()
+        .map(|b| format!("{b:02X}"))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+fn certificates(pem: &str) -> Result<Vec<CertificateDer<'static>>, String> {
+    let certs = rustls_pemfile::certs(&mut pem.as_bytes())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Invalid TLS certificate: {e}"))?;
+    if certs.is_empty() {
+        return Err("The TLS certificate file contains no certificate".to_string());
+    }
+    Ok(certs)
+}
+
+fn server_config(
+    certs: Vec<CertificateDer<'static>>,
+    key: PrivateKeyDer<'static>,
+) -> Result<Arc<rustls::ServerConfig>, String> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let mut config = rustls::ServerConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .map_err(|e| e.to_string())?
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(|e| format!("TLS certificate and key do not match: {e}"))?;
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(Arc::new(config))
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("Failed to read {path:?}: {e}"))
+}
+
+/// Write a file only the current user can read.
+fn write_private(path: &Path, contents: &str) -> Result<(), String> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(path)
+        .map_err(|e| format!("Failed to write {path:?}: {e}"))?;
+    std::io::Write::write_all(&mut file, contents.as_bytes())
+        .map_err(|e| format!("Failed to write {path:?}: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generates_once_and_reuses_the_certificate() {
+        let dir = std::env::temp_dir().join(format!("oxide-tls-{}", uuid::Uuid::new_v4()));
+        let first = TlsIdentity::load_or_generate(None, None, &dir).unwrap();
+        assert!(first.self_signed);
+        assert!(first.server_config().is_ok());
+
+        let fingerprint = first.fingerprint().unwrap();
+        assert_eq!(fingerprint.len(), 32 * 3 - 1);
+        let again = TlsIdentity::load_or_generate(None, None, &dir).unwrap();
+        assert_eq!(again.fingerprint().unwrap(), fingerprint);
+
+        let configured = TlsIdentity::load_or_generate(
+            Some(&dir.join(CERT_FILE)),
+            Some(&dir.join(KEY_FILE)),
+            Path::new("/nonexistent"),
+        )
+        .unwrap();
+        assert!(!configured.self_signed);
+        assert!(TlsIdentity::load_or_generate(
+            Some(&dir.join("missing.pem")),
+            Some(&dir.join(KEY_FILE)),
+            &dir
+        )
+        .is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
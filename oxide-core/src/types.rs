@@ -1,3 +1,4 @@
+use base64::{engine::general_purpose, Engine as _};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -31,6 +32,86 @@ pub struct AgentAction {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Context {
     pub active_window: Option<String>,
+    // Only set when the user has opted in to clipboard sharing
+    #[serde(default)]
+    pub clipboard_text: Option<String>,
     pub system_status: Option<serde_json::Value>,
     pub recent_events: Vec<SystemEvent>,
+    // Retrieved memories with source attributions, already trimmed to the
+    // provider's context budget
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_context: Option<String>,
+}
+
+/// Image sent alongside a prompt to a vision-capable model. Images are not
+/// pseudonymized, so only attach what the user chose to share.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ImageAttachment {
+    pub mime_type: String,
+    // Base64 (standard alphabet, padded)
+    pub data: String,
+}
+
+impl ImageAttachment {
+    pub const SUPPORTED_MIME_TYPES: [&'static str; 4] =
+        ["image/png", "image/jpeg", "image/webp", "image/gif"];
+    /// Inline request limit shared by Gemini and OpenAI.
+    pub const MAX_BYTES: usize = 20 * 1024 * 1024;
+
+    pub fn from_bytes(bytes: &[u8], mime_type: &str) -> Result<Self, String> {
+        let attachment = Self {
+            mime_type: mime_type.to_string(),
+            data: general_purpose::STANDARD.encode(bytes),
+        };
+        attachment.validate()?;
+        Ok(attachment)
+    }
+
+    /// Read an image file, inferring the MIME type from its extension.
+    pub fn from_file(path: &str) -> Result<Self, String> {
+        let extension = std::path::Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase)
+            .unwrap_or_default();
+        let mime_type = match extension.as_str() {
+            "png" => "image/png",
+            "jpg" | "jpeg" => "image/jpeg",
+            "webp" => "image/webp",
+            "gif" => "image/gif",
+            _ => return Err(format!("Unsupported image type for {path}")),
+        };
+        let bytes = std::fs::read(path).map_err(|e| format!("Failed to read {path}: {e}"))?;
+        Self::from_bytes(&bytes, mime_type)
+    }
+
+    /// Size of the decoded image in bytes.
+    pub fn decoded_len(&self) -> usize {
+        self.data.len() / 4 * 3
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if !Self::SUPPORTED_MIME_TYPES.contains(&self.mime_type.as_str()) {
+            return Err(format!(
+                "Unsupported image type '{}' (expected one of: {})",
+                self.mime_type,
+                Self::SUPPORTED_MIME_TYPES.join(", ")
+            ));
+        }
+        if self.data.is_empty() {
+            return Err("Image data must not be empty".to_string());
+        }
+        if self.decoded_len() > Self::MAX_BYTES {
+            return Err(format!(
+                "Image exceeds the {} MB inline limit",
+                Self::MAX_BYTES / (1024 * 1024)
+            ));
+        }
+        Ok(())
+    }
+
+    /// `data:` URL as used by OpenAI image content parts.
+    pub fn data_url(&self) -> String {
+        format!("data:{};base64,{}", self.mime_type, self.data)
+    }
 }
@@ -0,0 +1,398 @@
+//! Update checks against a signed release manifest.
+//!
+//! The update endpoint serves a [`SignedManifest`]: the release manifest as
+//! JSON text plus an ed25519 signature over exactly those bytes. Nothing in
+//! the manifest is trusted before the signature verifies. Each release
+//! names its channel, structured release notes and per-platform downloads:
+//! a full installer and optional deltas built against one earlier version.
+//! The SHA-256 pinned in the signed manifest is checked on every download.
+//!
+//! Release builds embed the signing key from `OXIDE_UPDATE_PUBLIC_KEY` at
+//! compile time. The key is deliberately not configurable, so editing the
+//! config cannot make the app trust someone else's releases; builds without
+//! it cannot check for updates.
+
+use crate::config::{UpdateChannel, UpdatesConfig};
+use crate::network;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use log::info;
+use ring::signature::{UnparsedPublicKey, ED25519};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+pub const UPDATES_DIR: &str = "./data/updates";
+pub const DEFAULT_CHECK_INTERVAL_HOURS: u32 = 24;
+
+const BUILTIN_PUBLIC_KEY: Option<&str> = option_env!("OXIDE_UPDATE_PUBLIC_KEY");
+
+/// What the update endpoint serves.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedManifest {
+    /// [`ReleaseManifest`] as JSON text
+    pub manifest: String,
+    /// Base64 ed25519 signature over the bytes of `manifest`
+    pub signature: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseManifest {
+    pub releases: Vec<Release>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Release {
+    pub version: String,
+    pub channel: UpdateChannel,
+    pub pub_date: DateTime<Utc>,
+    pub notes: ReleaseNotes,
+    /// Downloads per platform, keyed like [`current_target`]
+    pub platforms: HashMap<String, PlatformDownloads>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReleaseNotes {
+    pub summary: String,
+    #[serde(default)]
+    pub features: Vec<String>,
+    #[serde(default)]
+    pub fixes: Vec<String>,
+    #[serde(default)]
+    pub security: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlatformDownloads {
+    pub full: Artifact,
+    #[serde(default)]
+    pub deltas: Vec<DeltaArtifact>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Artifact {
+    pub url: String,
+    /// Hex SHA-256 of the file
+    pub sha256: String,
+    pub size: u64,
+}
+
+/// Download that only applies on top of version `from`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeltaArtifact {
+    pub from: String,
+    #[serde(flatten)]
+    pub artifact: Artifact,
+}
+
+/// Notes of one release between the running version and the update.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionNotes {
+    pub version: String,
+    pub channel: UpdateChannel,
+    pub pub_date: DateTime<Utc>,
+    pub notes: ReleaseNotes,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AvailableUpdate {
+    pub version: String,
+    pub channel: UpdateChannel,
+    pub pub_date: DateTime<Utc>,
+    /// Every release newer than the running one up to `version`, newest first
+    pub notes: Vec<VersionNotes>,
+    pub download: Artifact,
+    /// `download` is a delta against the running version
+    pub delta: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateCheck {
+    pub current_version: String,
+    pub channel: UpdateChannel,
+    pub checked_at: DateTime<Utc>,
+    pub update: Option<AvailableUpdate>,
+}
+
+/// Platform key of this build, e.g. `windows-x86_64`.
+pub fn current_target() -> String {
+    format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH)
+}
+
+/// Raw ed25519 public key from its base64 form.
+pub fn decode_public_key(key: &str) -> Result<Vec<u8>, String> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(key.trim())
+        .map_err(|e| format!("Update public key is not valid base64: {e}"))?;
+    if bytes.len() != 32 {
+        return Err(format!(
+            "Update public key must be 32 bytes, got {}",
+            bytes.len()
+        ));
+    }
+    Ok(bytes)
+}
+
+/// The manifest inside `signed`, once its signature verifies.
+pub fn verify_manifest(
+    signed: &SignedManifest,
+    public_key: &str,
+) -> Result<ReleaseManifest, String> {
+    let key = decode_public_key(public_key)?;
+    let signature = base64::engine::general_purpose::STANDARD
+        .decode(signed.signature.trim())
+        .map_err(|e| format!("Release signature is not valid base64: {e}"))?;
+    UnparsedPublicKey::new(&ED25519, key)
+        .verify(signed.manifest.as_bytes(), &signature)
+        .map_err(|_| "Release manifest signature verification failed".to_string())?;
+    serde_json::from_str(&signed.manifest).map_err(|e| format!("Invalid release manifest: {e}"))
+}
+
+/// Newest release on `channel` above `current` that ships for `target`.
+/// Beta also sees stable releases. Prefers a delta built against `current`.
+pub fn select_update(
+    manifest: &ReleaseManifest,
+    current: &str,
+    channel: UpdateChannel,
+    target: &str,
+) -> Result<Option<AvailableUpdate>, String> {
+    let current_version =
+        Version::parse(current).map_err(|e| format!("Invalid current version {current}: {e}"))?;
+    let mut newer: Vec<(Version, &Release)> = manifest
+        .releases
+        .iter()
+        .filter(|r| channel == UpdateChannel::Beta || r.channel == UpdateChannel::Stable)
+        .filter_map(|r| Version::parse(&r.version).ok().map(|v| (v, r)))
+        .filter(|(v, _)| *v > current_version)
+        .collect();
+    newer.sort_by(|a, b| b.0.cmp(&a.0));
+
+    let Some((_, release, downloads)) = newer
+        .iter()
+        .find_map(|(v, r)| r.platforms.get(target).map(|d| (v, *r, d)))
+    else {
+        return Ok(None);
+    };
+    let delta = downloads
+        .deltas
+        .iter()
+        .find(|d| Version::parse(&d.from).is_ok_and(|from| from == current_version));
+    let notes = newer
+        .iter()
+        .skip_while(|(_, r)| r.version != release.version)
+        .map(|(_, r)| VersionNotes {
+            version: r.version.clone(),
+            channel: r.channel,
+            pub_date: r.pub_date,
+            notes: r.notes.clone(),
+        })
+        .collect();
+    Ok(Some(AvailableUpdate {
+        version: release.version.clone(),
+        channel: release.channel,
+        pub_date: release.pub_date,
+        notes,
+        download: delta
+            .map(|d| d.artifact.clone())
+            .unwrap_or_else(|| downloads.full.clone()),
+        delta: delta.is_some(),
+    }))
+}
+
+/// Fetch and verify the manifest from the configured endpoint.
+pub async fn check(config: &UpdatesConfig, current: &str) -> Result<UpdateCheck, String> {
+    let endpoint = config
+        .endpoint
+        .as_deref()
+        .ok_or("No update endpoint is configured")?;
+    let public_key = BUILTIN_PUBLIC_KEY.ok_or("This build has no update signing key")?;
+    network::ensure_online(endpoint).map_err(|e| e.to_string())?;
+    let response = network::http_client()
+        .get(endpoint)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Update check failed: {e}"))?;
+    let signed: SignedManifest = response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid update response: {e}"))?;
+    let manifest = verify_manifest(&signed, public_key)?;
+    let update = select_update(&manifest, current, config.channel, &current_target())?;
+    if let Some(update) = &update {
+        info!(
+            "Update {} available ({:?} channel, {} bytes{})",
+            update.version,
+            update.channel,
+            update.download.size,
+            if update.delta { ", delta" } else { "" }
+        );
+    }
+    Ok(UpdateCheck {
+        current_version: current.to_string(),
+        channel: config.channel,
+        checked_at: Utc::now(),
+        update,
+    })
+}
+
+/// Download `update` into `dir/<version>/`, rejecting anything larger than
+/// announced or with a different SHA-256.
+pub async fn download(update: &AvailableUpdate, dir: &Path) -> Result<PathBuf, String> {
+    let artifact = &update.download;
+    let name = url::Url::parse(&artifact.url)
+        .ok()
+        .and_then(|u| u.path_segments()?.next_back().map(str::to_string))
+        .filter(|n| !n.is_empty() && n != "." && n != "..")
+        .ok_or_else(|| format!("Update URL has no file name: {}", artifact.url))?;
+    network::ensure_online(&artifact.url).map_err(|e| e.to_string())?;
+    let mut response = network::http_client()
+        .get(&artifact.url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Update download failed: {e}"))?;
+
+    let mut bytes = Vec::with_capacity(artifact.size.min(512 * 1024 * 1024) as usize);
+    let mut hasher = Sha256::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Update download failed: {e}"))?
+    {
+        if (bytes.len() + chunk.len()) as u64 > artifact.size {
+            return Err("Update download is larger than the signed manifest says".to_string());
+        }
+        hasher.update(&chunk);
+        bytes.extend_from_slice(&chunk);
+    }
+    verify_download(&bytes, &format!("{:x}", hasher.finalize()), artifact)?;
+
+    let dir = dir.join(&update.version);
+    tokio::fs::create_dir_all(&dir)
+        .await
+        .map_err(|e| format!("Failed to create {dir:?}: {e}"))?;
+    let path = dir.join(name);
+    tokio::fs::write(&path, &bytes)
+        .await
+        .map_err(|e| format!("Failed to write {path:?}: {e}"))?;
+    info!("Update {} downloaded to {path:?}", update.version);
+    Ok(path)
+}
+
+fn verify_download(bytes: &[u8], sha256: &str, artifact: &Artifact) -> Result<(), String> {
+    if bytes.len() as u64 != artifact.size {
+        return Err(format!(
+            "Update download is {} bytes, expected {}",
+            bytes.len(),
+            artifact.size
+        ));
+    }
+    if !sha256.eq_ignore_ascii_case(&artifact.sha256) {
+        return Err("Update download does not match the signed SHA-256".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+    use serde_json::json;
+
+    fn release(version: &str, channel: &str, deltas: serde_json::Value) -> serde_json::Value {
+        json!({
+            "version": version,
+            "channel": channel,
+            "pub_date": "2026-01-01T00:00:00Z",
+            "notes": { "summary": format!("Release {version}"), "fixes": ["Crash on start"] },
+            "platforms": {
+                "windows-x86_64": {
+                    "full": { "url": format!("https://x/{version}.msi"), "sha256": "aa", "size": 100 },
+                    "deltas": deltas
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn manifest_signature_must_verify() {
+        let rng = SystemRandom::new();
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+        let keys = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let public_key =
+            base64::engine::general_purpose::STANDARD.encode(keys.public_key().as_ref());
+        let manifest = json!({ "releases": [release("1.0.0", "stable", json!([]))] }).to_string();
+        let signed = SignedManifest {
+            signature: base64::engine::general_purpose::STANDARD
+                .encode(keys.sign(manifest.as_bytes()).as_ref()),
+            manifest,
+        };
+        assert_eq!(
+            verify_manifest(&signed, &public_key)
+                .unwrap()
+                .releases
+                .len(),
+            1
+        );
+
+        let tampered = SignedManifest {
+            manifest: signed.manifest.replace("1.0.0", "9.0.0"),
+            ..signed.clone()
+        };
+        assert!(verify_manifest(&tampered, &public_key)
+            .unwrap_err()
+            .contains("signature"));
+        assert!(decode_public_key("c2hvcnQ=").is_err());
+    }
+
+    #[test]
+    fn selects_newest_release_on_channel_with_delta() {
+        let manifest: ReleaseManifest = serde_json::from_value(json!({
+            "releases": [
+                release("0.9.0", "stable", json!([])),
+                release("1.1.0", "stable", json!([
+                    { "from": "1.0.0", "url": "https://x/1.0.0-1.1.0.msp", "sha256": "bb", "size": 10 }
+                ])),
+                release("1.0.5", "stable", json!([])),
+                release("1.2.0-beta.1", "beta", json!([])),
+            ]
+        }))
+        .unwrap();
+        let target = "windows-x86_64";
+
+        let stable = select_update(&manifest, "1.0.0", UpdateChannel::Stable, target)
+            .unwrap()
+            .unwrap();
+        assert_eq!(stable.version, "1.1.0");
+        assert!(stable.delta);
+        assert_eq!(stable.download.size, 10);
+        let versions: Vec<_> = stable.notes.iter().map(|n| n.version.as_str()).collect();
+        assert_eq!(versions, ["1.1.0", "1.0.5"]);
+
+        let beta = select_update(&manifest, "1.0.5", UpdateChannel::Beta, target)
+            .unwrap()
+            .unwrap();
+        assert_eq!(beta.version, "1.2.0-beta.1");
+        assert!(!beta.delta);
+
+        assert!(
+            select_update(&manifest, "1.1.0", UpdateChannel::Stable, target)
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            select_update(&manifest, "1.0.0", UpdateChannel::Stable, "linux-aarch64")
+                .unwrap()
+                .is_none()
+        );
+
+        let artifact = &stable.download;
+        assert!(verify_download(&[0; 10], "BB", artifact).is_ok());
+        assert!(verify_download(&[0; 10], "cc", artifact).is_err());
+        assert!(verify_download(&[0; 9], "bb", artifact).is_err());
+    }
+}
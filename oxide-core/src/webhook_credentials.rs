@@ -0,0 +1,38 @@
+use keyring::Entry;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+const WEBHOOK_SERVICE_ID: &str = "oxide_pilot_webhook";
+
+#[derive(Error, Debug)]
+pub enum WebhookCredentialsError {
+    #[error("Keyring error: {0}")]
+    Keyring(#[from] keyring::Error),
+}
+
+/// Signing keys are stored per endpoint, under a digest of the URL because
+/// chat webhook URLs embed tokens.
+fn entry(url: &str) -> Result<Entry, WebhookCredentialsError> {
+    let account = format!("{:x}", Sha256::digest(url.as_bytes()));
+    Ok(Entry::new(WEBHOOK_SERVICE_ID, &account)?)
+}
+
+pub fn store_secret(url: &str, secret: &str) -> Result<(), WebhookCredentialsError> {
+    entry(url)?.set_password(secret)?;
+    Ok(())
+}
+
+pub fn get_secret(url: &str) -> Result<Option<String>, WebhookCredentialsError> {
+    match entry(url)?.get_password() {
+        Ok(v) => Ok(Some(v)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub fn clear_secret(url: &str) -> Result<(), WebhookCredentialsError> {
+    match entry(url)?.delete_password() {
+        Ok(_) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
@@ -0,0 +1,357 @@
+//! Outbound webhooks for threats, scan results and consensus reports.
+//!
+//! Every enabled endpoint receives a POST for each event it subscribes to,
+//! shaped for its [`WebhookFormat`]. When the endpoint is `signed` the body
+//! is signed with its key from [`webhook_credentials`]:
+//! [`SIGNATURE_HEADER`] carries `sha256=<hex>`, the HMAC-SHA256
+//! of `<timestamp>.<body>` with the timestamp from [`TIMESTAMP_HEADER`], so
+//! receivers can reject replayed requests. Network errors, 429 and 5xx
+//! responses are retried with exponential backoff; other failures are not.
+
+use crate::config::{WebhookEndpoint, WebhookEvent, WebhookFormat, WebhooksConfig};
+use crate::network;
+use crate::webhook_credentials;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use log::{debug, warn};
+use reqwest::{StatusCode, Url};
+use serde::Serialize;
+use serde_json::{json, Value};
+use sha2::Sha256;
+use std::sync::RwLock;
+use std::time::Duration;
+
+pub const SIGNATURE_HEADER: &str = "X-Oxide-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-Oxide-Timestamp";
+pub const EVENT_HEADER: &str = "X-Oxide-Event";
+
+const DEFAULT_MAX_ATTEMPTS: u32 = 4;
+const BASE_DELAY: Duration = Duration::from_secs(1);
+const MAX_DELAY: Duration = Duration::from_secs(60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Serialize, Debug, Clone)]
+pub struct WebhookPayload {
+    pub id: String,
+    pub event: WebhookEvent,
+    pub timestamp: DateTime<Utc>,
+    /// One line for chat integrations
+    pub summary: String,
+    pub data: Value,
+}
+
+impl WebhookPayload {
+    pub fn new(event: WebhookEvent, summary: impl Into<String>, data: Value) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            event,
+            timestamp: Utc::now(),
+            summary: summary.into(),
+            data,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct DeliveryResult {
+    /// Host of the endpoint; full URLs often embed tokens
+    pub endpoint: String,
+    pub attempts: u32,
+    pub status: Option<u16>,
+    pub error: Option<String>,
+}
+
+impl DeliveryResult {
+    pub fn delivered(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// `sha256=<hex>` signature of `body` sent at `timestamp`.
+pub fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut message = format!("{timestamp}.").into_bytes();
+    message.extend_from_slice(body);
+    format!("sha256={}", hmac_sha256_hex(secret.as_bytes(), &message))
+}
+
+fn hmac_sha256_hex(key: &[u8], message: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(message);
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// Request body for `format`.
+pub fn render(format: WebhookFormat, payload: &WebhookPayload) -> Value {
+    let text = format!("[Oxide Pilot] {}", payload.summary);
+    match format {
+        WebhookFormat::Json => json!(payload),
+        WebhookFormat::Slack => json!({ "text": text }),
+        WebhookFormat::Discord => json!({ "content": text }),
+        WebhookFormat::Teams => json!({
+            "@type": "MessageCard",
+            "@context": "https://schema.org/extensions",
+            "summary": payload.summary,
+            "text": text,
+        }),
+    }
+}
+
+fn subscribed(endpoint: &WebhookEndpoint, event: WebhookEvent) -> bool {
+    endpoint.enabled
+        && (event == WebhookEvent::Test
+            || endpoint.events.is_empty()
+            || endpoint.events.contains(&event))
+}
+
+fn host_of(url: &str) -> String {
+    Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_else(|| "invalid URL".to_string())
+}
+
+/// Sends events to the configured endpoints.
+pub struct WebhookDispatcher {
+    config: RwLock<WebhooksConfig>,
+    client: reqwest::Client,
+}
+
+impl WebhookDispatcher {
+    /// Fails when the HTTP client cannot be built, e.g. with a bad proxy, so
+    /// webhooks never bypass the configured transport.
+    pub fn new(config: WebhooksConfig) -> Result<Self, String> {
+        let client = network::client_builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| format!("Failed to build the webhook HTTP client: {e}"))?;
+        Ok(Self {
+            config: RwLock::new(config),
+            client,
+        })
+    }
+
+    pub fn configure(&self, config: WebhooksConfig) {
+        if let Ok(mut current) = self.config.write() {
+            *current = config;
+        }
+    }
+
+    fn targets(&self, event: WebhookEvent) -> (Vec<WebhookEndpoint>, u32) {
+        let Ok(config) = self.config.read() else {
+            return (Vec::new(), DEFAULT_MAX_ATTEMPTS);
+        };
+        let endpoints = config
+            .endpoints
+            .iter()
+            .filter(|endpoint| subscribed(endpoint, event))
+            .cloned()
+            .collect();
+        (
+            endpoints,
+            config.max_attempts.unwrap_or(DEFAULT_MAX_ATTEMPTS),
+        )
+    }
+
+    /// Deliver `payload` in the background; failures are only logged.
+    pub fn dispatch(&self, payload: WebhookPayload) {
+        let (endpoints, max_attempts) = self.targets(payload.event);
+        for endpoint in endpoints {
+            let client = self.client.clone();
+            let payload = payload.clone();
+            tokio::spawn(async move {
+                let result = deliver(&client, &endpoint, &payload, max_attempts).await;
+                if let Some(error) = &result.error {
+                    warn!(
+                        "Webhook {:?} to {} failed after {} attempt(s): {error}",
+                        payload.event, result.endpoint, result.attempts
+                    );
+                }
+            });
+        }
+    }
+
+    /// Deliver `payload` to every subscribed endpoint and wait for the results.
+    pub async fn deliver_all(&self, payload: &WebhookPayload) -> Vec<DeliveryResult> {
+        let (endpoints, max_attempts) = self.targets(payload.event);
+        let mut results = Vec::with_capacity(endpoints.len());
+        for endpoint in &endpoints {
+            results.push(deliver(&self.client, endpoint, payload, max_attempts).await);
+        }
+        results
+    }
+}
+
+fn signing_key(url: &str) -> Result<String, String> {
+    webhook_credentials::get_secret(url)
+        .map_err(|e| format!("Cannot read the signing key: {e}"))?
+        .ok_or_else(|| "No signing key is stored for this endpoint".to_string())
+}
+
+async fn deliver(
+    client: &reqwest::Client,
+    endpoint: &WebhookEndpoint,
+    payload: &WebhookPayload,
+    max_attempts: u32,
+) -> DeliveryResult {
+    let mut result = DeliveryResult {
+        endpoint: host_of(&endpoint.url),
+        attempts: 0,
+        status: None,
+        error: None,
+    };
+    if let Err(e) = network::ensure_online(&endpoint.url) {
+        result.error = Some(e.to_string());
+        return result;
+    }
+    let body = match serde_json::to_vec(&render(endpoint.format, payload)) {
+        Ok(body) => body,
+        Err(e) => {
+            result.error = Some(e.to_string());
+            return result;
+        }
+    };
+    // Never fall back to an unsigned request when a signature is expected
+    let secret = match endpoint.signed.then(|| signing_key(&endpoint.url)) {
+        Some(Ok(secret)) => Some(secret),
+        Some(Err(e)) => {
+            result.error = Some(e);
+            return result;
+        }
+        None => None,
+    };
+
+    let mut delay = BASE_DELAY;
+    loop {
+        result.attempts += 1;
+        let timestamp = Utc::now().timestamp();
+        let mut request = client
+            .post(&endpoint.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, event_name(payload.event))
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .body(body.clone());
+        if let Some(secret) = &secret {
+            request = request.header(SIGNATURE_HEADER, sign(secret, timestamp, &body));
+        }
+        let retryable = match request.send().await {
+            Ok(response) if response.status().is_success() => {
+                result.status = Some(response.status().as_u16());
+                result.error = None;
+                debug!(
+                    "Webhook {:?} delivered to {}",
+                    payload.event, result.endpoint
+                );
+                return result;
+            }
+            Ok(response) => {
+                let status = response.status();
+                result.status = Some(status.as_u16());
+                result.error = Some(format!("HTTP {status}"));
+                status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+            }
+            Err(e) => {
+                result.error = Some(e.without_url().to_string());
+                true
+            }
+        };
+        if !retryable || result.attempts >= max_attempts {
+            return result;
+        }
+        tokio::time::sleep(delay).await;
+        delay = (delay * 2).min(MAX_DELAY);
+    }
+}
+
+fn event_name(event: WebhookEvent) -> &'static str {
+    match event {
+        WebhookEvent::ThreatDetected => "threat_detected",
+        WebhookEvent::ScanCompleted => "scan_completed",
+        WebhookEvent::ConsensusReport => "consensus_report",
+        WebhookEvent::Test => "test",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signs_with_hmac_sha256() {
+        // RFC 4231, test case 2
+        assert_eq!(
+            hmac_sha256_hex(b"Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        assert_eq!(
+            sign("Jefe", 1, b"body"),
+            format!("sha256={}", hmac_sha256_hex(b"Jefe", b"1.body"))
+        );
+    }
+
+    #[test]
+    fn renders_formats_and_filters_events() {
+        let payload = WebhookPayload::new(
+            WebhookEvent::ScanCompleted,
+            "Scan finished",
+            json!({ "malicious": 0 }),
+        );
+        assert_eq!(
+            render(WebhookFormat::Json, &payload)["event"],
+            "scan_completed"
+        );
+        assert_eq!(
+            render(WebhookFormat::Slack, &payload)["text"],
+            "[Oxide Pilot] Scan finished"
+        );
+        assert_eq!(
+            render(WebhookFormat::Discord, &payload)["content"],
+            "[Oxide Pilot] Scan finished"
+        );
+
+        let endpoint = WebhookEndpoint {
+            url: "https://hooks.example.com/T000/B000/secret".to_string(),
+            enabled: true,
+            signed: false,
+            secret: None,
+            events: vec![WebhookEvent::ThreatDetected],
+            format: WebhookFormat::Slack,
+        };
+        assert!(subscribed(&endpoint, WebhookEvent::ThreatDetected));
+        assert!(!subscribed(&endpoint, WebhookEvent::ScanCompleted));
+        assert!(subscribed(&endpoint, WebhookEvent::Test));
+        assert_eq!(host_of(&endpoint.url), "hooks.example.com");
+    }
+
+    #[tokio::test]
+    async fn signed_endpoints_need_a_stored_key() {
+        let endpoint = WebhookEndpoint {
+            url: "https://hooks.example.com/oxide-test-unsigned".to_string(),
+            enabled: true,
+            signed: true,
+            secret: None,
+            events: Vec::new(),
+            format: WebhookFormat::Json,
+        };
+        let payload = WebhookPayload::new(WebhookEvent::Test, "Test", json!({}));
+        let client = reqwest::Client::new();
+        let result = deliver(&client, &endpoint, &payload, 1).await;
+        assert_eq!(result.attempts, 0);
+        assert!(!result.delivered());
+
+        let plaintext = WebhooksConfig {
+            endpoints: vec![WebhookEndpoint {
+                signed: false,
+                secret: Some("hunter2".to_string()),
+                ..endpoint
+            }],
+            max_attempts: None,
+        };
+        assert!(plaintext.validate().unwrap_err().contains("plaintext"));
+        let json = serde_json::to_string(&plaintext.endpoints[0]).unwrap();
+        assert!(!json.contains("hunter2"));
+    }
+}
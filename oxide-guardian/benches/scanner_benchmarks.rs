@@ -0,0 +1,65 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use oxide_guardian::scanner::FileScanner;
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::time::Duration;
+
+/// Previous implementation: 64 KiB reads, both hashers on one thread
+fn baseline_hashes(path: &Path) -> (String, String) {
+    let mut file = File::open(path).unwrap();
+    let mut sha = Sha256::new();
+    let mut blake3_hasher = blake3::Hasher::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf).unwrap();
+        if n == 0 {
+            break;
+        }
+        sha.update(&buf[..n]);
+        blake3_hasher.update(&buf[..n]);
+    }
+    (
+        format!("{:x}", sha.finalize()),
+        blake3_hasher.finalize().to_hex().to_string(),
+    )
+}
+
+/// Benchmark: File hashing throughput, baseline vs chunked/mmap
+fn bench_file_hashing(c: &mut Criterion) {
+    let dir = tempfile::tempdir().unwrap();
+    let mut group = c.benchmark_group("file_hashing");
+
+    for size_mb in [1usize, 64] {
+        let path = dir.path().join(format!("{size_mb}mb.bin"));
+        let data: Vec<u8> = (0..size_mb * 1024 * 1024)
+            .map(|i| (i % 251) as u8)
+            .collect();
+        File::create(&path).unwrap().write_all(&data).unwrap();
+
+        group.throughput(Throughput::Bytes(data.len() as u64));
+        group.bench_with_input(BenchmarkId::new("baseline", size_mb), &path, |b, p| {
+            b.iter(|| black_box(baseline_hashes(p)));
+        });
+        group.bench_with_input(
+            BenchmarkId::new("compute_hashes", size_mb),
+            &path,
+            |b, p| {
+                b.iter(|| black_box(FileScanner::compute_hashes(p).unwrap()));
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default()
+        .measurement_time(Duration::from_secs(10))
+        .sample_size(20);
+    targets = bench_file_hashing
+}
+
+criterion_main!(benches);
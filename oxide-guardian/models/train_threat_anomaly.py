@@ -0,0 +1,91 @@
+"""Train the bundled threat anomaly model (threat_anomaly.json).
+
+Multinomial logistic regression over the four threat features, fitted on
+a synthetic baseline where the detector's anomaly score dominates and
+resource pressure shifts borderline cases. Re-run after changing the
+baseline; the output is deterministic.
+
+    python3 oxide-guardian/models/train_threat_anomaly.py
+"""
+
+import json
+import math
+import os
+import random
+
+FEATURES = ["cpu_usage", "memory_pressure", "network_score", "anomaly_score"]
+CLASSES = ["low", "medium", "high", "critical"]
+SAMPLES = 4000
+EPOCHS = 400
+LEARNING_RATE = 0.5
+L2 = 1e-3
+
+
+def baseline(rng):
+    rows = []
+    for _ in range(SAMPLES):
+        x = [rng.uniform(0, 100) for _ in FEATURES]
+        risk = 0.12 * x[0] + 0.08 * x[1] + 0.15 * x[2] + 0.65 * x[3]
+        risk += rng.gauss(0, 6)
+        label = 0 if risk < 30 else 1 if risk < 50 else 2 if risk < 68 else 3
+        rows.append((x, label))
+    return rows
+
+
+def softmax(z):
+    m = max(z)
+    e = [math.exp(v - m) for v in z]
+    s = sum(e)
+    return [v / s for v in e]
+
+
+def main():
+    rng = random.Random(2137)
+    rows = baseline(rng)
+    n, k, c = len(rows), len(FEATURES), len(CLASSES)
+    means = [sum(x[j] for x, _ in rows) / n for j in range(k)]
+    scales = [
+        math.sqrt(sum((x[j] - means[j]) ** 2 for x, _ in rows) / n) for j in range(k)
+    ]
+    data = [([(x[j] - means[j]) / scales[j] for j in range(k)], y) for x, y in rows]
+
+    weights = [[0.0] * k for _ in range(c)]
+    biases = [0.0] * c
+    for _ in range(EPOCHS):
+        gw = [[0.0] * k for _ in range(c)]
+        gb = [0.0] * c
+        for x, y in data:
+            p = softmax([biases[i] + sum(w * v for w, v in zip(weights[i], x)) for i in range(c)])
+            for i in range(c):
+                err = p[i] - (1.0 if i == y else 0.0)
+                gb[i] += err
+                for j in range(k):
+                    gw[i][j] += err * x[j]
+        for i in range(c):
+            biases[i] -= LEARNING_RATE * gb[i] / n
+            for j in range(k):
+                weights[i][j] -= LEARNING_RATE * (gw[i][j] / n + L2 * weights[i][j])
+
+    correct = 0
+    for x, y in data:
+        p = softmax([biases[i] + sum(w * v for w, v in zip(weights[i], x)) for i in range(c)])
+        correct += p.index(max(p)) == y
+    model = {
+        "version": "baseline-1",
+        "features": FEATURES,
+        "classes": CLASSES,
+        "means": [round(v, 4) for v in means],
+        "scales": [round(v, 4) for v in scales],
+        "weights": [[round(v, 4) for v in row] for row in weights],
+        "biases": [round(v, 4) for v in biases],
+        "training_accuracy": round(correct / n, 4),
+    }
+    path = os.path.join(os.path.dirname(__file__), "threat_anomaly.json")
+    with open(path, "w") as f:
+        json.dump(model, f, indent=2)
+        f.write("\n")
+    print(f"Wrote {path} (training accuracy {model['training_accuracy']})")
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,620 @@
+//! Forwarding of Guardian alerts to external log pipelines.
+//!
+//! An [`AlertSink`] delivers one [`Alert`]; [`AlertRouter`] holds the sinks
+//! configured in `GuardianConfig::alert_sinks`, each with its own minimum
+//! severity, and sends on a background thread so detection never waits on
+//! the network. Syslog messages follow RFC 5424, framed by octet counting
+//! over TCP (RFC 6587). The Windows Event Log sink writes to the
+//! Application log. The email sink batches alerts into digests so an
+//! outbreak produces one message rather than hundreds.
+
+use crate::guardian::{ThreatEvent, ThreatSeverity};
+use chrono::{DateTime, SecondsFormat, Utc};
+use lettre::message::{header::ContentType, Mailbox};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+use log::{info, warn};
+use oxide_core::config::{AlertSinkConfig, SmtpTls, SyslogProtocol};
+use oxide_core::smtp_credentials;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::net::{TcpStream, ToSocketAddrs, UdpSocket};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+pub const APP_NAME: &str = "oxide-pilot";
+const DEFAULT_EVENT_SOURCE: &str = "Oxide Pilot";
+/// Security/authorization messages
+const DEFAULT_FACILITY: u8 = 4;
+const DEFAULT_MIN_SEVERITY: ThreatSeverity = ThreatSeverity::High;
+const DEFAULT_EMAIL_MIN_SEVERITY: ThreatSeverity = ThreatSeverity::Critical;
+const DEFAULT_DIGEST_MINUTES: u64 = 10;
+/// Alerts listed in one digest; the rest are only counted
+const MAX_DIGEST_ALERTS: usize = 50;
+const SMTP_TIMEOUT: Duration = Duration::from_secs(20);
+/// Private enterprise number used for the structured data ID
+const SD_ID: &str = "oxide@32473";
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone)]
+pub struct Alert {
+    pub timestamp: DateTime<Utc>,
+    pub severity: ThreatSeverity,
+    /// Short machine-readable kind, e.g. `SuspiciousProcess`
+    pub kind: String,
+    pub message: String,
+    pub fields: BTreeMap<String, String>,
+}
+
+impl From<&ThreatEvent> for Alert {
+    fn from(threat: &ThreatEvent) -> Self {
+        let mut fields: BTreeMap<String, String> = threat
+            .details
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        fields.insert("threat_id".to_string(), threat.id.clone());
+        if let Some(pid) = threat.process_id {
+            fields.insert("pid".to_string(), pid.to_string());
+        }
+        if let Some(name) = &threat.process_name {
+            fields.insert("process".to_string(), name.clone());
+        }
+        Self {
+            timestamp: threat.timestamp,
+            severity: threat.severity.clone(),
+            kind: format!("{:?}", threat.threat_type),
+            message: threat.description.clone(),
+            fields,
+        }
+    }
+}
+
+pub trait AlertSink: Send + Sync {
+    fn name(&self) -> String;
+    fn send(&self, alert: &Alert) -> Result<(), String>;
+}
+
+pub struct SyslogSink {
+    address: String,
+    protocol: SyslogProtocol,
+    facility: u8,
+    hostname: String,
+}
+
+impl SyslogSink {
+    pub fn new(address: String, protocol: SyslogProtocol, facility: Option<u8>) -> Self {
+        Self {
+            address,
+            protocol,
+            facility: facility.unwrap_or(DEFAULT_FACILITY),
+            hostname: hostname::get()
+                .map(|h| h.to_string_lossy().to_string())
+                .unwrap_or_else(|_| "-".to_string()),
+        }
+    }
+
+    /// RFC 5424 message for `alert`.
+    pub fn format(&self, alert: &Alert) -> String {
+        let priority = u16::from(self.facility) * 8 + syslog_severity(&alert.severity);
+        let mut data = format!("[{SD_ID} severity=\"{:?}\"", alert.severity);
+        for (key, value) in &alert.fields {
+            data.push_str(&format!(" {}=\"{}\"", sd_name(key), sd_escape(value)));
+        }
+        data.push(']');
+        format!(
+            "<{priority}>1 {} {} {APP_NAME} {} {} {data} {}",
+            alert.timestamp.to_rfc3339_opts(SecondsFormat::Millis, true),
+            header_field(&self.hostname, 255),
+            std::process::id(),
+            header_field(&alert.kind, 32),
+            alert.message
+        )
+    }
+}
+
+impl AlertSink for SyslogSink {
+    fn name(&self) -> String {
+        format!("syslog {:?} {}", self.protocol, self.address)
+    }
+
+    fn send(&self, alert: &Alert) -> Result<(), String> {
+        let message = self.format(alert);
+        let addr = self
+            .address
+            .to_socket_addrs()
+            .map_err(|e| e.to_string())?
+            .next()
+            .ok_or("Syslog address did not resolve")?;
+        match self.protocol {
+            SyslogProtocol::Udp => {
+                let bind = if addr.is_ipv4() {
+                    "0.0.0.0:0"
+                } else {
+                    "[::]:0"
+                };
+                let socket = UdpSocket::bind(bind).map_err(|e| e.to_string())?;
+                socket
+                    .send_to(message.as_bytes(), addr)
+                    .map_err(|e| e.to_string())?;
+            }
+            SyslogProtocol::Tcp => {
+                let mut stream = TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT)
+                    .map_err(|e| e.to_string())?;
+                stream
+                    .set_write_timeout(Some(CONNECT_TIMEOUT))
+                    .map_err(|e| e.to_string())?;
+                stream
+                    .write_all(format!("{} {message}", message.len()).as_bytes())
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `(facility * 8) + severity` uses the RFC 5424 numeric severities.
+fn syslog_severity(severity: &ThreatSeverity) -> u16 {
+    match severity {
+        ThreatSeverity::Critical => 2,
+        ThreatSeverity::High => 3,
+        ThreatSeverity::Medium => 4,
+        ThreatSeverity::Low => 5,
+    }
+}
+
+/// Header fields are printable ASCII without spaces, `-` when empty.
+fn header_field(value: &str, max: usize) -> String {
+    let field: String = value
+        .chars()
+        .filter(|c| c.is_ascii_graphic())
+        .take(max)
+        .collect();
+    if field.is_empty() {
+        "-".to_string()
+    } else {
+        field
+    }
+}
+
+fn sd_name(key: &str) -> String {
+    key.chars()
+        .filter(|c| c.is_ascii_graphic() && !matches!(c, '=' | ']' | '"'))
+        .take(32)
+        .collect()
+}
+
+fn sd_escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace(']', "\\]")
+}
+
+pub struct EventLogSink {
+    source: String,
+}
+
+impl EventLogSink {
+    pub fn new(source: Option<String>) -> Self {
+        Self {
+            source: source.unwrap_or_else(|| DEFAULT_EVENT_SOURCE.to_string()),
+        }
+    }
+
+    fn text(alert: &Alert) -> String {
+        let mut text = format!("{} ({:?})\n\n{}", alert.kind, alert.severity, alert.message);
+        for (key, value) in &alert.fields {
+            text.push_str(&format!("\n{key}: {value}"));
+        }
+        text
+    }
+}
+
+impl AlertSink for EventLogSink {
+    fn name(&self) -> String {
+        format!("event log '{}'", self.source)
+    }
+
+    #[cfg(windows)]
+    fn send(&self, alert: &Alert) -> Result<(), String> {
+        use std::os::windows::ffi::OsStrExt;
+        use winapi::um::winbase::{DeregisterEventSource, RegisterEventSourceW, ReportEventW};
+        use winapi::um::winnt::{
+            EVENTLOG_ERROR_TYPE, EVENTLOG_INFORMATION_TYPE, EVENTLOG_WARNING_TYPE,
+        };
+
+        let wide = |s: &str| -> Vec<u16> {
+            std::ffi::OsStr::new(s)
+                .encode_wide()
+                .chain(std::iter::once(0))
+                .collect()
+        };
+        let source = wide(&self.source);
+        let text = wide(&Self::text(alert));
+        let (event_type, event_id) = match alert.severity {
+            ThreatSeverity::Critical => (EVENTLOG_ERROR_TYPE, 1004),
+            ThreatSeverity::High => (EVENTLOG_ERROR_TYPE, 1003),
+            ThreatSeverity::Medium => (EVENTLOG_WARNING_TYPE, 1002),
+            ThreatSeverity::Low => (EVENTLOG_INFORMATION_TYPE, 1001),
+        };
+        // SAFETY: both strings are NUL-terminated and outlive the calls; the
+        // handle is released before returning.
+        unsafe {
+            let handle = RegisterEventSourceW(std::ptr::null(), source.as_ptr());
+            if handle.is_null() {
+                return Err(format!(
+                    "RegisterEventSource failed: {}",
+                    std::io::Error::last_os_error()
+                ));
+            }
+            let mut strings = [text.as_ptr()];
+            let ok = ReportEventW(
+                handle,
+                event_type,
+                0,
+                event_id,
+                std::ptr::null_mut(),
+                1,
+                0,
+                strings.as_mut_ptr(),
+                std::ptr::null_mut(),
+            );
+            let error = std::io::Error::last_os_error();
+            DeregisterEventSource(handle);
+            if ok == 0 {
+                return Err(format!("ReportEvent failed: {error}"));
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(not(windows))]
+    fn send(&self, alert: &Alert) -> Result<(), String> {
+        let _ = Self::text(alert);
+        Err("The Windows Event Log is only available on Windows".to_string())
+    }
+}
+
+/// SMTP relay settings shared with the digest thread.
+struct SmtpSettings {
+    host: String,
+    port: Option<u16>,
+    tls: SmtpTls,
+    username: Option<String>,
+    from: String,
+    to: Vec<String>,
+}
+
+impl SmtpSettings {
+    fn deliver(&self, subject: String, body: String) -> Result<(), String> {
+        let mut message = Message::builder()
+            .from(self.from.parse::<Mailbox>().map_err(|e| e.to_string())?)
+            .subject(subject)
+            .header(ContentType::TEXT_PLAIN);
+        for to in &self.to {
+            message = message.to(to.parse::<Mailbox>().map_err(|e| e.to_string())?);
+        }
+        let message = message.body(body).map_err(|e| e.to_string())?;
+
+        let (builder, default_port) = match self.tls {
+            SmtpTls::StartTls => (SmtpTransport::starttls_relay(&self.host), 587),
+            SmtpTls::Implicit => (SmtpTransport::relay(&self.host), 465),
+        };
+        let mut builder = builder
+            .map_err(|e| e.to_string())?
+            .port(self.port.unwrap_or(default_port))
+            .timeout(Some(SMTP_TIMEOUT));
+        if let Some(username) = &self.username {
+            let password = smtp_credentials::get_password(&self.host, username)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("No SMTP password stored for {username}@{}", self.host))?;
+            builder = builder.credentials(Credentials::new(username.clone(), password));
+        }
+        builder.build().send(&message).map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
+
+/// Subject and plain-text body for one alert or a digest of several.
+fn render_email(alerts: &[Alert], hostname: &str) -> (String, String) {
+    let subject = match alerts {
+        [alert] => format!(
+            "[Oxide Pilot] {:?}: {} on {hostname}",
+            alert.severity, alert.kind
+        ),
+        _ => {
+            let highest = alerts.iter().map(|a| &a.severity).max();
+            format!(
+                "[Oxide Pilot] {} alerts on {hostname} (highest: {:?})",
+                alerts.len(),
+                highest.unwrap_or(&ThreatSeverity::Low)
+            )
+        }
+    };
+    let mut body = format!(
+        "Oxide Pilot raised {} alert(s) on {hostname}.\n",
+        alerts.len()
+    );
+    for alert in alerts.iter().take(MAX_DIGEST_ALERTS) {
+        body.push_str(&format!(
+            "\n[{}] {:?} {}\n{}\n",
+            alert.timestamp.to_rfc3339_opts(SecondsFormat::Secs, true),
+            alert.severity,
+            alert.kind,
+            alert.message
+        ));
+        for (key, value) in &alert.fields {
+            body.push_str(&format!("  {key}: {value}\n"));
+        }
+    }
+    if alerts.len() > MAX_DIGEST_ALERTS {
+        body.push_str(&format!(
+            "\n... and {} more; see the threat history in Oxide Pilot.\n",
+            alerts.len() - MAX_DIGEST_ALERTS
+        ));
+    }
+    (subject, body)
+}
+
+pub struct EmailSink {
+    settings: Arc<SmtpSettings>,
+    hostname: String,
+    digest: Duration,
+    pending: Arc<Mutex<Vec<Alert>>>,
+}
+
+impl EmailSink {
+    fn from_config(config: &AlertSinkConfig) -> Option<Self> {
+        let AlertSinkConfig::Email {
+            host,
+            port,
+            tls,
+            username,
+            from,
+            to,
+            digest_minutes,
+            ..
+        } = config
+        else {
+            return None;
+        };
+        Some(Self {
+            settings: Arc::new(SmtpSettings {
+                host: host.clone(),
+                port: *port,
+                tls: *tls,
+                username: username.clone(),
+                from: from.clone(),
+                to: to.clone(),
+            }),
+            hostname: hostname::get()
+                .map(|h| h.to_string_lossy().to_string())
+                .unwrap_or_else(|_| "unknown host".to_string()),
+            digest: Duration::from_secs(digest_minutes.unwrap_or(DEFAULT_DIGEST_MINUTES) * 60),
+            pending: Arc::new(Mutex::new(Vec::new())),
+        })
+    }
+
+    /// Send one email right away, bypassing the digest.
+    fn send_now(&self, alerts: &[Alert]) -> Result<(), String> {
+        let (subject, body) = render_email(alerts, &self.hostname);
+        self.settings.deliver(subject, body)
+    }
+}
+
+impl AlertSink for EmailSink {
+    fn name(&self) -> String {
+        format!("email via {}", self.settings.host)
+    }
+
+    fn send(&self, alert: &Alert) -> Result<(), String> {
+        if self.digest.is_zero() {
+            return self.send_now(std::slice::from_ref(alert));
+        }
+        let first = {
+            let mut pending = self.pending.lock().map_err(|e| e.to_string())?;
+            pending.push(alert.clone());
+            pending.len() == 1
+        };
+        // The first queued alert opens the digest window; later ones ride along
+        if first {
+            let settings = Arc::clone(&self.settings);
+            let pending = Arc::clone(&self.pending);
+            let hostname = self.hostname.clone();
+            let digest = self.digest;
+            thread::spawn(move || {
+                thread::sleep(digest);
+                let alerts = match pending.lock() {
+                    Ok(mut pending) => std::mem::take(&mut *pending),
+                    Err(_) => return,
+                };
+                let (subject, body) = render_email(&alerts, &hostname);
+                match settings.deliver(subject, body) {
+                    Ok(()) => info!("Emailed a digest of {} alert(s)", alerts.len()),
+                    Err(e) => warn!("Failed to email alert digest via {}: {e}", settings.host),
+                }
+            });
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct EmailTestResult {
+    /// SMTP host of the sink
+    pub host: String,
+    pub recipients: Vec<String>,
+    pub error: Option<String>,
+}
+
+/// Send a test message through every email sink in `sinks` and wait for
+/// the results.
+pub fn send_test_emails(sinks: &[AlertSinkConfig]) -> Vec<EmailTestResult> {
+    let alert = Alert {
+        timestamp: Utc::now(),
+        severity: ThreatSeverity::Low,
+        kind: "Test".to_string(),
+        message: "This is a test alert. Email alerts are configured correctly.".to_string(),
+        fields: BTreeMap::new(),
+    };
+    sinks
+        .iter()
+        .filter_map(EmailSink::from_config)
+        .map(|sink| EmailTestResult {
+            host: sink.settings.host.clone(),
+            recipients: sink.settings.to.clone(),
+            error: sink.send_now(std::slice::from_ref(&alert)).err(),
+        })
+        .collect()
+}
+
+struct Route {
+    min_severity: ThreatSeverity,
+    sink: Box<dyn AlertSink>,
+}
+
+/// The configured sinks; cheap to clone and replace on config changes.
+#[derive(Clone, Default)]
+pub struct AlertRouter {
+    routes: Arc<Vec<Route>>,
+}
+
+impl AlertRouter {
+    pub fn from_config(sinks: &[AlertSinkConfig]) -> Self {
+        let routes = sinks
+            .iter()
+            .filter_map(|config| {
+                let sink: Box<dyn AlertSink> = match config {
+                    AlertSinkConfig::Syslog {
+                        address,
+                        protocol,
+                        facility,
+                        ..
+                    } => Box::new(SyslogSink::new(address.clone(), *protocol, *facility)),
+                    AlertSinkConfig::EventLog { source, .. } => {
+                        if cfg!(not(windows)) {
+                            warn!("Ignoring Event Log alert sink: not running on Windows");
+                            return None;
+                        }
+                        Box::new(EventLogSink::new(source.clone()))
+                    }
+                    AlertSinkConfig::Email { .. } => Box::new(EmailSink::from_config(config)?),
+                };
+                let default_severity = match config {
+                    AlertSinkConfig::Email { .. } => DEFAULT_EMAIL_MIN_SEVERITY,
+                    _ => DEFAULT_MIN_SEVERITY,
+                };
+                let min_severity = config
+                    .min_severity()
+                    .and_then(|s| serde_json::from_value(s.to_ascii_lowercase().into()).ok())
+                    .unwrap_or(default_severity);
+                Some(Route { min_severity, sink })
+            })
+            .collect();
+        Self {
+            routes: Arc::new(routes),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+
+    /// Send `alert` to every sink whose minimum severity it meets, without
+    /// blocking the caller. Failures are logged.
+    pub fn forward(&self, alert: Alert) {
+        if !self
+            .routes
+            .iter()
+            .any(|route| alert.severity >= route.min_severity)
+        {
+            return;
+        }
+        let routes = Arc::clone(&self.routes);
+        thread::spawn(move || {
+            for route in routes.iter().filter(|r| alert.severity >= r.min_severity) {
+                if let Err(e) = route.sink.send(&alert) {
+                    warn!("Failed to forward alert to {}: {e}", route.sink.name());
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alert() -> Alert {
+        Alert {
+            timestamp: DateTime::parse_from_rfc3339("2024-05-01T10:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+            severity: ThreatSeverity::High,
+            kind: "SuspiciousProcess".to_string(),
+            message: "Encoded PowerShell".to_string(),
+            fields: BTreeMap::from([("cmd".to_string(), "a \"b\" [c]".to_string())]),
+        }
+    }
+
+    #[test]
+    fn formats_rfc5424_messages() {
+        let mut sink = SyslogSink::new("127.0.0.1:514".to_string(), SyslogProtocol::Udp, None);
+        sink.hostname = "host one".to_string();
+        let message = sink.format(&alert());
+        assert!(message.starts_with(&format!(
+            "<35>1 2024-05-01T10:00:00.000Z hostone oxide-pilot {} SuspiciousProcess ",
+            std::process::id()
+        )));
+        assert!(message.ends_with(
+            "[oxide@32473 severity=\"High\" cmd=\"a \\\"b\\\" [c\\]\"] Encoded PowerShell"
+        ));
+    }
+
+    #[test]
+    fn renders_email_digests() {
+        let alerts = vec![
+            alert(),
+            Alert {
+                severity: ThreatSeverity::Critical,
+                kind: "RemediationFailed".to_string(),
+                ..alert()
+            },
+        ];
+        let (subject, body) = render_email(&alerts[..1], "pc1");
+        assert_eq!(subject, "[Oxide Pilot] High: SuspiciousProcess on pc1");
+        assert!(body.contains("[2024-05-01T10:00:00Z] High SuspiciousProcess\nEncoded PowerShell"));
+
+        let (subject, body) = render_email(&alerts, "pc1");
+        assert_eq!(subject, "[Oxide Pilot] 2 alerts on pc1 (highest: Critical)");
+        assert!(body.contains("Critical RemediationFailed"));
+        assert!(body.contains("  cmd: a \"b\" [c]"));
+    }
+
+    #[test]
+    fn routes_alerts_by_severity_over_udp() {
+        let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+        receiver
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let router = AlertRouter::from_config(&[AlertSinkConfig::Syslog {
+            address: receiver.local_addr().unwrap().to_string(),
+            protocol: SyslogProtocol::Udp,
+            facility: Some(10),
+            min_severity: Some("critical".to_string()),
+        }]);
+
+        router.forward(alert());
+        router.forward(Alert {
+            severity: ThreatSeverity::Critical,
+            ..alert()
+        });
+        let mut buf = [0u8; 2048];
+        let len = receiver.recv(&mut buf).unwrap();
+        // Only the critical alert passes: facility 10 * 8 + severity 2
+        assert!(String::from_utf8_lossy(&buf[..len]).starts_with("<82>1 "));
+    }
+}
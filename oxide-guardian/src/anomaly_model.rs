@@ -0,0 +1,180 @@
+//! Local threat anomaly model.
+//!
+//! A multinomial logistic regression over the four threat features
+//! (`cpu_usage`, `memory_pressure`, `network_score`, `anomaly_score`, each
+//! 0-100), trained offline and bundled with the crate. It scores feature
+//! vectors when SurrealML is unavailable. `models/train_threat_anomaly.py`
+//! regenerates the bundled file.
+
+use oxide_memory::threat_training::ThreatScorer;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+const BUNDLED_MODEL: &str = include_str!("../models/threat_anomaly.json");
+
+/// Provider name reported in predictions
+pub const PROVIDER: &str = "local_model";
+
+/// Logistic regression weights as stored in the model file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnomalyModel {
+    pub version: String,
+    /// Feature keys, in weight order
+    pub features: Vec<String>,
+    /// Severity labels, lowest first
+    pub classes: Vec<String>,
+    /// Per-feature standardization applied before the weights
+    pub means: Vec<f64>,
+    pub scales: Vec<f64>,
+    /// One row of feature weights per class
+    pub weights: Vec<Vec<f64>>,
+    pub biases: Vec<f64>,
+}
+
+/// Result of [`AnomalyModel::predict`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AnomalyPrediction {
+    pub severity: String,
+    /// Expected severity on a 0-1 scale
+    pub score: f64,
+    /// Probability of the predicted class
+    pub confidence: f64,
+    /// Probability per class, in `classes` order
+    pub probabilities: Vec<f64>,
+}
+
+impl AnomalyModel {
+    /// The model shipped with this build.
+    pub fn bundled() -> Self {
+        Self::from_json(BUNDLED_MODEL).expect("bundled threat anomaly model is valid")
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let model: Self =
+            serde_json::from_str(json).map_err(|e| format!("Invalid anomaly model: {e}"))?;
+        model.validate()?;
+        Ok(model)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let k = self.features.len();
+        if k == 0 || self.classes.len() < 2 {
+            return Err("Anomaly model needs features and at least two classes".to_string());
+        }
+        if self.means.len() != k || self.scales.len() != k {
+            return Err("Anomaly model standardization does not match its features".to_string());
+        }
+        if self.scales.iter().any(|s| *s <= 0.0) {
+            return Err("Anomaly model scales must be positive".to_string());
+        }
+        if self.biases.len() != self.classes.len()
+            || self.weights.len() != self.classes.len()
+            || self.weights.iter().any(|row| row.len() != k)
+        {
+            return Err("Anomaly model weights do not match its classes and features".to_string());
+        }
+        Ok(())
+    }
+
+    /// Score a feature object; missing features count as 0 and values are
+    /// clamped to 0-100.
+    pub fn predict(&self, features: &Value) -> AnomalyPrediction {
+        let x: Vec<f64> = self
+            .features
+            .iter()
+            .zip(self.means.iter().zip(&self.scales))
+            .map(|(name, (mean, scale))| {
+                let value = features
+                    .get(name)
+                    .and_then(Value::as_f64)
+                    .unwrap_or(0.0)
+                    .clamp(0.0, 100.0);
+                (value - mean) / scale
+            })
+            .collect();
+        let logits: Vec<f64> = self
+            .weights
+            .iter()
+            .zip(&self.biases)
+            .map(|(row, bias)| bias + row.iter().zip(&x).map(|(w, v)| w * v).sum::<f64>())
+            .collect();
+        let probabilities = softmax(&logits);
+
+        let (best, confidence) = probabilities
+            .iter()
+            .copied()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap_or((0, 0.0));
+        let top = (self.classes.len() - 1) as f64;
+        let score = probabilities
+            .iter()
+            .enumerate()
+            .map(|(i, p)| p * i as f64 / top)
+            .sum();
+        AnomalyPrediction {
+            severity: self.classes[best].clone(),
+            score,
+            confidence,
+            probabilities,
+        }
+    }
+}
+
+impl ThreatScorer for AnomalyModel {
+    fn predict(&self, features: &Value) -> Value {
+        let prediction = AnomalyModel::predict(self, features);
+        json!({
+            "provider": PROVIDER,
+            "model_version": self.version,
+            "severity": prediction.severity,
+            "score": prediction.score,
+            "confidence": prediction.confidence,
+            "probabilities": self.classes.iter().zip(&prediction.probabilities)
+                .map(|(class, p)| (class.clone(), json!(p)))
+                .collect::<serde_json::Map<_, _>>(),
+        })
+    }
+}
+
+fn softmax(logits: &[f64]) -> Vec<f64> {
+    let max = logits.iter().copied().fold(f64::MIN, f64::max);
+    let exps: Vec<f64> = logits.iter().map(|z| (z - max).exp()).collect();
+    let sum: f64 = exps.iter().sum();
+    exps.iter().map(|e| e / sum).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_model_ranks_severity_by_anomaly() {
+        let model = AnomalyModel::bundled();
+        assert_eq!(model.classes, ["low", "medium", "high", "critical"]);
+
+        let quiet = model.predict(&json!({ "cpu_usage": 5.0, "anomaly_score": 2.0 }));
+        assert_eq!(quiet.severity, "low");
+        let flagged = model.predict(&json!({
+            "cpu_usage": 80.0,
+            "memory_pressure": 70.0,
+            "network_score": 90.0,
+            "anomaly_score": 100.0,
+        }));
+        assert_eq!(flagged.severity, "critical");
+        assert!(flagged.score > quiet.score);
+        assert!((flagged.probabilities.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+
+        let scored = ThreatScorer::predict(&model, &json!({ "anomaly_score": 100.0 }));
+        assert_eq!(scored["provider"], PROVIDER);
+        assert_eq!(scored["model_version"], model.version);
+    }
+
+    #[test]
+    fn rejects_mismatched_weights() {
+        let mut model = AnomalyModel::bundled();
+        model.weights[0].pop();
+        let json = serde_json::to_string(&model).unwrap();
+        assert!(AnomalyModel::from_json(&json).is_err());
+    }
+}
@@ -0,0 +1,500 @@
+//! MITRE ATT&CK enrichment for threats and consensus findings.
+//!
+//! A bundled table covers the Enterprise techniques a desktop agent is
+//! likely to see. [`map_threat`] tags a [`ThreatEvent`] from keyword rules
+//! over its description, process and details, falling back to a technique
+//! per [`ThreatType`]. Threats the rules cannot place can be classified by
+//! an LLM with [`classification_prompt`]; [`parse_technique_ids`] keeps only
+//! IDs from the table so the matrix stays consistent. [`summarize`] counts
+//! detections per tactic for the ATT&CK matrix view.
+
+use crate::guardian::{ThreatEvent, ThreatType};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use Tactic::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum Tactic {
+    Reconnaissance,
+    ResourceDevelopment,
+    InitialAccess,
+    Execution,
+    Persistence,
+    PrivilegeEscalation,
+    DefenseEvasion,
+    CredentialAccess,
+    Discovery,
+    LateralMovement,
+    Collection,
+    CommandAndControl,
+    Exfiltration,
+    Impact,
+}
+
+impl Tactic {
+    /// Kill-chain order, as the matrix is drawn.
+    pub const ALL: [Tactic; 14] = [
+        Tactic::Reconnaissance,
+        Tactic::ResourceDevelopment,
+        Tactic::InitialAccess,
+        Tactic::Execution,
+        Tactic::Persistence,
+        Tactic::PrivilegeEscalation,
+        Tactic::DefenseEvasion,
+        Tactic::CredentialAccess,
+        Tactic::Discovery,
+        Tactic::LateralMovement,
+        Tactic::Collection,
+        Tactic::CommandAndControl,
+        Tactic::Exfiltration,
+        Tactic::Impact,
+    ];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Tactic::Reconnaissance => "TA0043",
+            Tactic::ResourceDevelopment => "TA0042",
+            Tactic::InitialAccess => "TA0001",
+            Tactic::Execution => "TA0002",
+            Tactic::Persistence => "TA0003",
+            Tactic::PrivilegeEscalation => "TA0004",
+            Tactic::DefenseEvasion => "TA0005",
+            Tactic::CredentialAccess => "TA0006",
+            Tactic::Discovery => "TA0007",
+            Tactic::LateralMovement => "TA0008",
+            Tactic::Collection => "TA0009",
+            Tactic::CommandAndControl => "TA0011",
+            Tactic::Exfiltration => "TA0010",
+            Tactic::Impact => "TA0040",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Tactic::Reconnaissance => "Reconnaissance",
+            Tactic::ResourceDevelopment => "Resource Development",
+            Tactic::InitialAccess => "Initial Access",
+            Tactic::Execution => "Execution",
+            Tactic::Persistence => "Persistence",
+            Tactic::PrivilegeEscalation => "Privilege Escalation",
+            Tactic::DefenseEvasion => "Defense Evasion",
+            Tactic::CredentialAccess => "Credential Access",
+            Tactic::Discovery => "Discovery",
+            Tactic::LateralMovement => "Lateral Movement",
+            Tactic::Collection => "Collection",
+            Tactic::CommandAndControl => "Command and Control",
+            Tactic::Exfiltration => "Exfiltration",
+            Tactic::Impact => "Impact",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Technique {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub tactics: &'static [Tactic],
+}
+
+const fn technique(id: &'static str, name: &'static str, tactics: &'static [Tactic]) -> Technique {
+    Technique { id, name, tactics }
+}
+
+/// Bundled mapping table.
+pub const TECHNIQUES: &[Technique] = &[
+    technique("T1595", "Active Scanning", &[Reconnaissance]),
+    technique(
+        "T1588.002",
+        "Obtain Capabilities: Tool",
+        &[ResourceDevelopment],
+    ),
+    technique("T1566", "Phishing", &[InitialAccess]),
+    technique("T1189", "Drive-by Compromise", &[InitialAccess]),
+    technique(
+        "T1091",
+        "Replication Through Removable Media",
+        &[InitialAccess, LateralMovement],
+    ),
+    technique("T1059", "Command and Scripting Interpreter", &[Execution]),
+    technique("T1059.001", "PowerShell", &[Execution]),
+    technique("T1059.003", "Windows Command Shell", &[Execution]),
+    technique("T1059.005", "Visual Basic", &[Execution]),
+    technique("T1059.007", "JavaScript", &[Execution]),
+    technique("T1204.002", "User Execution: Malicious File", &[Execution]),
+    technique("T1047", "Windows Management Instrumentation", &[Execution]),
+    technique(
+        "T1053.005",
+        "Scheduled Task",
+        &[Execution, Persistence, PrivilegeEscalation],
+    ),
+    technique(
+        "T1547.001",
+        "Registry Run Keys / Startup Folder",
+        &[Persistence, PrivilegeEscalation],
+    ),
+    technique(
+        "T1543.003",
+        "Windows Service",
+        &[Persistence, PrivilegeEscalation],
+    ),
+    technique("T1176", "Browser Extensions", &[Persistence]),
+    technique(
+        "T1055",
+        "Process Injection",
+        &[DefenseEvasion, PrivilegeEscalation],
+    ),
+    technique(
+        "T1548.002",
+        "Bypass User Account Control",
+        &[PrivilegeEscalation, DefenseEvasion],
+    ),
+    technique(
+        "T1027",
+        "Obfuscated Files or Information",
+        &[DefenseEvasion],
+    ),
+    technique("T1036", "Masquerading", &[DefenseEvasion]),
+    technique("T1112", "Modify Registry", &[DefenseEvasion]),
+    technique("T1218", "System Binary Proxy Execution", &[DefenseEvasion]),
+    technique("T1218.005", "Mshta", &[DefenseEvasion]),
+    technique("T1218.010", "Regsvr32", &[DefenseEvasion]),
+    technique("T1218.011", "Rundll32", &[DefenseEvasion]),
+    technique("T1562.001", "Disable or Modify Tools", &[DefenseEvasion]),
+    technique("T1070.001", "Clear Windows Event Logs", &[DefenseEvasion]),
+    technique("T1564.003", "Hidden Window", &[DefenseEvasion]),
+    technique("T1003.001", "LSASS Memory", &[CredentialAccess]),
+    technique(
+        "T1555.003",
+        "Credentials from Web Browsers",
+        &[CredentialAccess],
+    ),
+    technique("T1056.001", "Keylogging", &[Collection, CredentialAccess]),
+    technique("T1082", "System Information Discovery", &[Discovery]),
+    technique("T1057", "Process Discovery", &[Discovery]),
+    technique("T1083", "File and Directory Discovery", &[Discovery]),
+    technique("T1021.001", "Remote Desktop Protocol", &[LateralMovement]),
+    technique("T1570", "Lateral Tool Transfer", &[LateralMovement]),
+    technique("T1113", "Screen Capture", &[Collection]),
+    technique("T1115", "Clipboard Data", &[Collection]),
+    technique("T1071.001", "Web Protocols", &[CommandAndControl]),
+    technique("T1090", "Proxy", &[CommandAndControl]),
+    technique("T1105", "Ingress Tool Transfer", &[CommandAndControl]),
+    technique("T1219", "Remote Access Software", &[CommandAndControl]),
+    technique("T1571", "Non-Standard Port", &[CommandAndControl]),
+    technique("T1041", "Exfiltration Over C2 Channel", &[Exfiltration]),
+    technique("T1567", "Exfiltration Over Web Service", &[Exfiltration]),
+    technique("T1486", "Data Encrypted for Impact", &[Impact]),
+    technique("T1489", "Service Stop", &[Impact]),
+    technique("T1490", "Inhibit System Recovery", &[Impact]),
+    technique("T1496", "Resource Hijacking", &[Impact]),
+    technique("T1565.001", "Stored Data Manipulation", &[Impact]),
+];
+
+/// Lower-case fragments and the technique they indicate. Rule ids of the
+/// built-in heuristics are matched like any other text.
+const KEYWORD_RULES: &[(&[&str], &str)] = &[
+    (&["powershell", "pwsh"], "T1059.001"),
+    (&["cmd.exe"], "T1059.003"),
+    (&["wscript", "cscript", ".vbs"], "T1059.005"),
+    (&["jscript"], "T1059.007"),
+    (&["-encodedcommand", "-enc ", "frombase64string"], "T1027"),
+    (&["-windowstyle hidden", "-w hidden"], "T1564.003"),
+    (&["mshta"], "T1218.005"),
+    (&["regsvr32"], "T1218.010"),
+    (&["rundll32"], "T1218.011"),
+    (
+        &[
+            "downloadstring",
+            "downloadfile",
+            "invoke-webrequest",
+            "certutil -urlcache",
+            "bitsadmin",
+        ],
+        "T1105",
+    ),
+    (&["schtasks", "scheduled task"], "T1053.005"),
+    (&["wmic"], "T1047"),
+    (&["lsass", "mimikatz", "sekurlsa"], "T1003.001"),
+    (
+        &[
+            "delete shadows",
+            "shadowcopy delete",
+            "wbadmin delete",
+            "bcdedit",
+        ],
+        "T1490",
+    ),
+    (&["ransom", ".locked", ".encrypt"], "T1486"),
+    (
+        &["xmrig", "coinminer", "cryptonight", "stratum+tcp"],
+        "T1496",
+    ),
+    (&["wevtutil cl", "clear-eventlog"], "T1070.001"),
+    (
+        &[
+            "set-mppreference",
+            "disableantispyware",
+            "disablerealtimemonitoring",
+        ],
+        "T1562.001",
+    ),
+    (&["currentversion\\run", "startup_item"], "T1547.001"),
+    (&["keylog"], "T1056.001"),
+    (&["anydesk", "teamviewer", "screenconnect"], "T1219"),
+    (&["mstsc"], "T1021.001"),
+    (
+        &["runs_from_writable_location", "office_spawns_shell"],
+        "T1204.002",
+    ),
+    (&["hosts_file"], "T1565.001"),
+    (&["browser_setting"], "T1176"),
+];
+
+pub fn find_technique(id: &str) -> Option<&'static Technique> {
+    TECHNIQUES.iter().find(|t| t.id.eq_ignore_ascii_case(id))
+}
+
+/// Techniques whose keywords appear in `text`, in table order.
+pub fn map_text(text: &str) -> Vec<String> {
+    let text = text.to_lowercase();
+    let mut ids: Vec<String> = Vec::new();
+    for (fragments, id) in KEYWORD_RULES {
+        if fragments.iter().any(|f| text.contains(f)) && !ids.iter().any(|i| i == id) {
+            ids.push((*id).to_string());
+        }
+    }
+    ids
+}
+
+/// Techniques for `event`: keyword matches, else a default for its type.
+pub fn map_threat(event: &ThreatEvent) -> Vec<String> {
+    let mut text = event.description.clone();
+    if let Some(name) = &event.process_name {
+        text.push(' ');
+        text.push_str(name);
+    }
+    for value in event.details.values() {
+        text.push(' ');
+        text.push_str(value);
+    }
+    let mut ids = map_text(&text);
+    if ids.is_empty() {
+        let fallback = match event.threat_type {
+            ThreatType::MalwareSignature | ThreatType::MaliciousFile => Some("T1204.002"),
+            ThreatType::SuspiciousProcess => Some("T1059"),
+            ThreatType::HighResourceUsage => Some("T1496"),
+            ThreatType::UnauthorizedNetworkAccess => Some("T1071.001"),
+            ThreatType::ConfigurationDrift => Some("T1543.003"),
+            ThreatType::SelfTampering => Some("T1562.001"),
+            ThreatType::FileSystemAnomaly => None,
+        };
+        ids.extend(fallback.map(str::to_string));
+    }
+    ids
+}
+
+/// Prompt asking an LLM for the techniques behind `description`.
+pub fn classification_prompt(description: &str) -> String {
+    let catalogue: Vec<String> = TECHNIQUES
+        .iter()
+        .map(|t| format!("{} {}", t.id, t.name))
+        .collect();
+    format!(
+        "Classify this security detection from a Windows desktop agent against MITRE ATT&CK. \
+         Reply with the matching technique IDs only, comma separated, most specific first, \
+         or NONE. Prefer IDs from this list: {}.\n\nDetection:\n{description}",
+        catalogue.join("; ")
+    )
+}
+
+/// Technique IDs from an LLM reply that exist in the table. Unknown
+/// sub-techniques fall back to their parent when the parent is listed.
+pub fn parse_technique_ids(text: &str) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut ids: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let starts_id = chars[i].eq_ignore_ascii_case(&'t')
+            && (i == 0 || !chars[i - 1].is_alphanumeric())
+            && chars.len() >= i + 5
+            && chars[i + 1..i + 5].iter().all(char::is_ascii_digit);
+        if !starts_id {
+            i += 1;
+            continue;
+        }
+        let mut end = i + 5;
+        if chars.len() >= end + 4
+            && chars[end] == '.'
+            && chars[end + 1..end + 4].iter().all(char::is_ascii_digit)
+        {
+            end += 4;
+        }
+        let candidate: String = chars[i + 1..end].iter().collect();
+        let candidate = format!("T{candidate}");
+        let known = find_technique(&candidate)
+            .or_else(|| find_technique(candidate.split('.').next().unwrap_or_default()));
+        if let Some(technique) = known {
+            if !ids.iter().any(|id| id == technique.id) {
+                ids.push(technique.id.to_string());
+            }
+        }
+        i = end;
+    }
+    ids
+}
+
+/// Keep the IDs of `ids` that are in the table, canonical and de-duplicated.
+pub fn normalize_ids(ids: &[String]) -> Vec<String> {
+    parse_technique_ids(&ids.join(" "))
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TechniqueCount {
+    pub id: String,
+    pub name: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TacticSummary {
+    pub id: &'static str,
+    pub name: &'static str,
+    /// Detections with at least one technique under this tactic
+    pub detections: usize,
+    /// Techniques seen, most frequent first
+    pub techniques: Vec<TechniqueCount>,
+    /// Techniques of this tactic in the bundled table
+    pub known_techniques: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AttackMatrixSummary {
+    pub detections: usize,
+    /// Detections without any technique
+    pub unmapped: usize,
+    pub tactics: Vec<TacticSummary>,
+}
+
+/// Per-tactic counts over `detections`, each the technique IDs of one
+/// threat or finding.
+pub fn summarize(detections: &[Vec<String>]) -> AttackMatrixSummary {
+    let mut per_tactic: BTreeMap<Tactic, (usize, BTreeMap<&'static str, usize>)> = BTreeMap::new();
+    let mut unmapped = 0;
+    for ids in detections {
+        let techniques: Vec<&Technique> = normalize_ids(ids)
+            .iter()
+            .filter_map(|id| find_technique(id))
+            .collect();
+        if techniques.is_empty() {
+            unmapped += 1;
+            continue;
+        }
+        let mut touched: Vec<Tactic> = Vec::new();
+        for technique in techniques {
+            for tactic in technique.tactics {
+                let entry = per_tactic.entry(*tactic).or_default();
+                *entry.1.entry(technique.id).or_default() += 1;
+                if !touched.contains(tactic) {
+                    touched.push(*tactic);
+                    entry.0 += 1;
+                }
+            }
+        }
+    }
+
+    let tactics = Tactic::ALL
+        .iter()
+        .map(|&tactic| {
+            let (detections, counts) = per_tactic.remove(&tactic).unwrap_or_default();
+            let mut techniques: Vec<TechniqueCount> = counts
+                .into_iter()
+                .map(|(id, count)| TechniqueCount {
+                    id: id.to_string(),
+                    name: find_technique(id)
+                        .map(|t| t.name)
+                        .unwrap_or_default()
+                        .to_string(),
+                    count,
+                })
+                .collect();
+            techniques.sort_by_key(|t| std::cmp::Reverse(t.count));
+            TacticSummary {
+                id: tactic.id(),
+                name: tactic.name(),
+                detections,
+                techniques,
+                known_techniques: TECHNIQUES
+                    .iter()
+                    .filter(|t| t.tactics.contains(&tactic))
+                    .count(),
+            }
+        })
+        .collect();
+    AttackMatrixSummary {
+        detections: detections.len(),
+        unmapped,
+        tactics,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::guardian::ThreatSeverity;
+    use chrono::Utc;
+    use std::collections::HashMap;
+
+    fn threat(threat_type: ThreatType, description: &str, command: &str) -> ThreatEvent {
+        ThreatEvent {
+            id: "t1".to_string(),
+            timestamp: Utc::now(),
+            threat_type,
+            severity: ThreatSeverity::High,
+            description: description.to_string(),
+            process_name: None,
+            process_id: None,
+            details: HashMap::from([("command".to_string(), command.to_string())]),
+            techniques: Vec::new(),
+            risk_score: 70.0,
+        }
+    }
+
+    #[test]
+    fn maps_threats_from_keywords_and_types() {
+        let encoded = threat(
+            ThreatType::SuspiciousProcess,
+            "Suspicious process behavior detected: powershell.exe (script_host_suspicious_args)",
+            "powershell.exe -WindowStyle Hidden -EncodedCommand SQBFAFgA",
+        );
+        assert_eq!(map_threat(&encoded), ["T1059.001", "T1027", "T1564.003"]);
+        assert_eq!(
+            map_threat(&threat(ThreatType::HighResourceUsage, "High CPU usage", "")),
+            ["T1496"]
+        );
+        assert!(map_threat(&threat(ThreatType::FileSystemAnomaly, "odd", "")).is_empty());
+
+        assert_eq!(
+            parse_technique_ids("T1059.001, t1003.001 and T1055.012; T9999, XT1027"),
+            ["T1059.001", "T1003.001", "T1055"]
+        );
+    }
+
+    #[test]
+    fn summarizes_detections_per_tactic() {
+        let summary = summarize(&[
+            vec!["T1059.001".to_string(), "T1053.005".to_string()],
+            vec!["T1053.005".to_string()],
+            vec!["T0000".to_string()],
+        ]);
+        assert_eq!(summary.detections, 3);
+        assert_eq!(summary.unmapped, 1);
+        let tactic = |id: &str| summary.tactics.iter().find(|t| t.id == id).unwrap();
+        let execution = tactic("TA0002");
+        assert_eq!(execution.detections, 2);
+        assert_eq!(execution.techniques[0].id, "T1053.005");
+        assert_eq!(execution.techniques[0].count, 2);
+        assert_eq!(tactic("TA0003").detections, 2);
+        assert_eq!(tactic("TA0040").detections, 0);
+        assert_eq!(summary.tactics.len(), 14);
+    }
+}
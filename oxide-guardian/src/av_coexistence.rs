@@ -0,0 +1,238 @@
+//! Detection of other antivirus products and their real-time protection.
+//!
+//! On Windows the products registered with Security Center
+//! (`root/SecurityCenter2` `AntiVirusProduct`) are listed; Server editions
+//! have no Security Center, so Microsoft Defender's own status is the
+//! fallback there. Elsewhere known AV daemons are looked for among the
+//! running processes.
+//!
+//! While another product's real-time protection is on, Guardian leaves the
+//! work that product already does to it: process memory is not YARA-scanned
+//! during monitoring and folder scans default to fewer workers, since every
+//! file they open is scanned a second time on access.
+
+#[cfg(windows)]
+use crate::startup::run_command;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Folder scan workers while another product scans files on access.
+pub const TUNED_FOLDER_SCAN_WORKERS: usize = 2;
+
+/// Daemons of AV products outside Windows: process name, product, whether
+/// the process means files are scanned on access.
+const KNOWN_DAEMONS: &[(&str, &str, bool)] = &[
+    ("clamonacc", "ClamAV", true),
+    ("clamd", "ClamAV", false),
+    ("wdavdaemon", "Microsoft Defender for Endpoint", true),
+    ("falcon-sensor", "CrowdStrike Falcon", true),
+    ("falcond", "CrowdStrike Falcon", true),
+    ("sophos_threat_detector", "Sophos", true),
+    ("savd", "Sophos", true),
+    ("esets_daemon", "ESET", true),
+    ("sentinelagent", "SentinelOne", true),
+    ("bdsecd", "Bitdefender", true),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AvProduct {
+    pub name: String,
+    pub realtime_enabled: bool,
+    /// `None` when the product does not report it
+    pub definitions_up_to_date: Option<bool>,
+    pub is_defender: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AvCoexistence {
+    pub products: Vec<AvProduct>,
+    /// Guardian work left to the other products, empty when nothing changed
+    pub adjustments: Vec<String>,
+    pub checked_at: DateTime<Utc>,
+}
+
+impl AvCoexistence {
+    /// Judge the detected `products`; with `tuning` off Guardian changes nothing.
+    pub fn new(products: Vec<AvProduct>, tuning: bool, now: DateTime<Utc>) -> Self {
+        let adjustments = if tuning && products.iter().any(|p| p.realtime_enabled) {
+            let mut adjustments = vec![format!(
+                "Folder scans default to {TUNED_FOLDER_SCAN_WORKERS} workers"
+            )];
+            if cfg!(feature = "yara-detection") {
+                adjustments.push("Process memory is not YARA-scanned during monitoring".into());
+            }
+            adjustments
+        } else {
+            Vec::new()
+        };
+        Self {
+            products,
+            adjustments,
+            checked_at: now,
+        }
+    }
+
+    pub fn tuned(&self) -> bool {
+        !self.adjustments.is_empty()
+    }
+
+    /// Workers for a folder scan when the config does not set them.
+    pub fn folder_scan_workers(&self, default: usize) -> usize {
+        if self.tuned() {
+            default.min(TUNED_FOLDER_SCAN_WORKERS)
+        } else {
+            default
+        }
+    }
+}
+
+/// Installed AV products; blocks while the OS is queried.
+pub fn detect() -> Vec<AvProduct> {
+    #[cfg(windows)]
+    {
+        let security_center = run_command(
+            "powershell",
+            &[
+                "-NoProfile",
+                "-NonInteractive",
+                "-Command",
+                "Get-CimInstance -Namespace root/SecurityCenter2 -ClassName AntiVirusProduct \
+                 | Select-Object displayName,productState | ConvertTo-Json -Compress",
+            ],
+        );
+        if let Some(products) = security_center
+            .map(|out| parse_security_center(&out))
+            .filter(|p| !p.is_empty())
+        {
+            return products;
+        }
+        run_command(
+            "powershell",
+            &[
+                "-NoProfile",
+                "-NonInteractive",
+                "-Command",
+                "Get-MpComputerStatus \
+                 | Select-Object RealTimeProtectionEnabled,AntivirusSignatureAge \
+                 | ConvertTo-Json -Compress",
+            ],
+        )
+        .and_then(|out| parse_defender_status(&out))
+        .into_iter()
+        .collect()
+    }
+    #[cfg(not(windows))]
+    {
+        use sysinfo::{ProcessExt, System, SystemExt};
+
+        let mut sys = System::new();
+        sys.refresh_processes();
+        detect_daemons(sys.processes().values().map(|p| p.name()))
+    }
+}
+
+/// Security Center `productState`: bits 12-15 hold the real-time state (1 is
+/// on), bits 4-7 the definitions state (0 is up to date).
+#[cfg_attr(not(windows), allow(dead_code))]
+fn decode_product_state(state: u64) -> (bool, bool) {
+    ((state >> 12) & 0xF == 1, (state >> 4) & 0xF == 0)
+}
+
+/// `ConvertTo-Json` output of the `AntiVirusProduct` instances; a single
+/// instance is an object rather than an array.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn parse_security_center(output: &str) -> Vec<AvProduct> {
+    let value: Value = match serde_json::from_str(output.trim()) {
+        Ok(value) => value,
+        Err(_) => return Vec::new(),
+    };
+    let instances = match value {
+        Value::Array(items) => items,
+        other => vec![other],
+    };
+    instances
+        .iter()
+        .filter_map(|item| {
+            let name = item.get("displayName")?.as_str()?.trim().to_string();
+            let (realtime_enabled, up_to_date) =
+                decode_product_state(item.get("productState")?.as_u64()?);
+            Some(AvProduct {
+                is_defender: name.to_ascii_lowercase().contains("defender"),
+                name,
+                realtime_enabled,
+                definitions_up_to_date: Some(up_to_date),
+            })
+        })
+        .collect()
+}
+
+/// `Get-MpComputerStatus` output; definitions older than a week count as out of date.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn parse_defender_status(output: &str) -> Option<AvProduct> {
+    let value: Value = serde_json::from_str(output.trim()).ok()?;
+    Some(AvProduct {
+        name: "Microsoft Defender Antivirus".to_string(),
+        realtime_enabled: value.get("RealTimeProtectionEnabled")?.as_bool()?,
+        definitions_up_to_date: value
+            .get("AntivirusSignatureAge")
+            .and_then(Value::as_u64)
+            .map(|days| days <= 7),
+        is_defender: true,
+    })
+}
+
+/// Products whose daemons are among `process_names`, one entry per product.
+#[cfg_attr(windows, allow(dead_code))]
+fn detect_daemons<'a>(process_names: impl Iterator<Item = &'a str>) -> Vec<AvProduct> {
+    let mut products: Vec<AvProduct> = Vec::new();
+    for name in process_names {
+        let name = name.to_ascii_lowercase();
+        let Some((_, product, realtime)) = KNOWN_DAEMONS.iter().find(|(d, _, _)| *d == name) else {
+            continue;
+        };
+        match products.iter_mut().find(|p| p.name == *product) {
+            Some(existing) => existing.realtime_enabled |= realtime,
+            None => products.push(AvProduct {
+                name: product.to_string(),
+                realtime_enabled: *realtime,
+                definitions_up_to_date: None,
+                is_defender: product.contains("Defender"),
+            }),
+        }
+    }
+    products
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_products_and_tunes_for_realtime() {
+        let output = r#"[{"displayName":"Windows Defender","productState":393472},
+            {"displayName":"ESET Security","productState":266256}]"#;
+        let products = parse_security_center(output);
+        assert_eq!(products.len(), 2);
+        assert!(products[0].is_defender && !products[0].realtime_enabled);
+        assert!(products[1].realtime_enabled);
+        assert_eq!(products[1].definitions_up_to_date, Some(false));
+        let single =
+            parse_security_center(r#"{"displayName":"Windows Defender","productState":397568}"#);
+        assert!(single[0].realtime_enabled);
+        assert_eq!(single[0].definitions_up_to_date, Some(true));
+
+        let now = Utc::now();
+        let coexistence = AvCoexistence::new(products.clone(), true, now);
+        assert_eq!(
+            coexistence.folder_scan_workers(8),
+            TUNED_FOLDER_SCAN_WORKERS
+        );
+        assert!(!AvCoexistence::new(products, false, now).tuned());
+
+        let daemons = detect_daemons(["bash", "clamd", "clamonacc"].into_iter());
+        assert_eq!(daemons.len(), 1);
+        assert!(daemons[0].realtime_enabled);
+        assert!(!AvCoexistence::new(detect_daemons(["clamd"].into_iter()), true, now).tuned());
+    }
+}
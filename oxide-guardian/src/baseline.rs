@@ -0,0 +1,623 @@
+//! Known-good baseline of persistence and configuration surfaces.
+//!
+//! A [`BaselineSnapshot`] records the autostart entries, the start mode of
+//! every service, the hosts file and a few browser settings that malware
+//! likes to change (homepage, startup pages, search engine, proxy,
+//! extensions). Guardian periodically captures the current state, diffs it
+//! against the stored baseline and turns each new difference into a threat
+//! event. Recording a new baseline accepts the current state as good.
+//!
+//! The first check on a machine without a baseline records one.
+
+use crate::guardian::{ThreatEvent, ThreatSeverity, ThreatType};
+use crate::startup::{self, run_command};
+use chrono::{DateTime, Utc};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub const BASELINE_PATH: &str = "./data/guardian_baseline.json";
+
+/// Firefox preferences worth watching.
+const FIREFOX_PREFS: &[&str] = &[
+    "browser.startup.homepage",
+    "browser.startup.page",
+    "browser.urlbar.placeholderName",
+    "network.proxy.type",
+    "network.proxy.http",
+    "network.proxy.autoconfig_url",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DriftCategory {
+    StartupItem,
+    Service,
+    HostsFile,
+    BrowserSetting,
+}
+
+impl DriftCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::StartupItem => "startup_item",
+            Self::Service => "service",
+            Self::HostsFile => "hosts_file",
+            Self::BrowserSetting => "browser_setting",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DriftChange {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DriftItem {
+    pub category: DriftCategory,
+    pub key: String,
+    pub change: DriftChange,
+    pub baseline: Option<String>,
+    pub current: Option<String>,
+}
+
+impl DriftItem {
+    fn fingerprint(&self) -> String {
+        format!(
+            "{}|{}|{}|{}",
+            self.category.as_str(),
+            self.key,
+            self.baseline.as_deref().unwrap_or_default(),
+            self.current.as_deref().unwrap_or_default()
+        )
+    }
+
+    /// New persistence and hosts entries rank highest, removals lowest.
+    pub fn severity(&self) -> ThreatSeverity {
+        match (self.category, self.change) {
+            (_, DriftChange::Removed) => ThreatSeverity::Low,
+            (DriftCategory::StartupItem | DriftCategory::HostsFile, _) => ThreatSeverity::High,
+            _ => ThreatSeverity::Medium,
+        }
+    }
+
+    pub fn to_threat(&self) -> ThreatEvent {
+        let threat_type = match self.category {
+            DriftCategory::StartupItem | DriftCategory::HostsFile => ThreatType::FileSystemAnomaly,
+            DriftCategory::Service | DriftCategory::BrowserSetting => {
+                ThreatType::ConfigurationDrift
+            }
+        };
+        let mut details = HashMap::from([
+            ("category".to_string(), self.category.as_str().to_string()),
+            ("key".to_string(), self.key.clone()),
+        ]);
+        if let Some(baseline) = &self.baseline {
+            details.insert("baseline".to_string(), baseline.clone());
+        }
+        if let Some(current) = &self.current {
+            details.insert("current".to_string(), current.clone());
+        }
+        ThreatEvent {
+            id: uuid::Uuid::new_v4().to_string(),
+            timestamp: Utc::now(),
+            threat_type,
+            severity: self.severity(),
+            description: format!(
+                "Baseline drift ({}, {:?}): {}",
+                self.category.as_str(),
+                self.change,
+                self.key
+            ),
+            process_name: None,
+            process_id: None,
+            details,
+            techniques: Vec::new(),
+            risk_score: self.severity().score(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BaselineSnapshot {
+    pub taken_at: DateTime<Utc>,
+    /// Startup item id to `name: command`, suffixed with `(disabled)` when off
+    pub startup_items: BTreeMap<String, String>,
+    /// Service name to start mode
+    pub services: BTreeMap<String, String>,
+    /// Hosts file: host name to its addresses
+    pub hosts: BTreeMap<String, String>,
+    /// `browser.setting` to value
+    pub browser_settings: BTreeMap<String, String>,
+}
+
+impl BaselineSnapshot {
+    /// Current state of this machine. Runs external tools; call it from a
+    /// blocking context.
+    pub fn capture() -> Self {
+        let startup_items = startup::list_startup_items(None)
+            .into_iter()
+            .map(|item| {
+                let value = if item.enabled {
+                    format!("{}: {}", item.name, item.command)
+                } else {
+                    format!("{}: {} (disabled)", item.name, item.command)
+                };
+                (item.id, value)
+            })
+            .collect();
+        let hosts = fs::read_to_string(hosts_path())
+            .map(|content| parse_hosts(&content))
+            .unwrap_or_default();
+        Self {
+            taken_at: Utc::now(),
+            startup_items,
+            services: services(),
+            hosts,
+            browser_settings: browser_settings(),
+        }
+    }
+
+    fn categories(&self) -> [(DriftCategory, &BTreeMap<String, String>); 4] {
+        [
+            (DriftCategory::StartupItem, &self.startup_items),
+            (DriftCategory::Service, &self.services),
+            (DriftCategory::HostsFile, &self.hosts),
+            (DriftCategory::BrowserSetting, &self.browser_settings),
+        ]
+    }
+
+    /// Differences from `self` (the baseline) to `current`.
+    pub fn diff(&self, current: &Self) -> Vec<DriftItem> {
+        let mut drift = Vec::new();
+        for ((category, before), (_, after)) in
+            self.categories().into_iter().zip(current.categories())
+        {
+            for (key, value) in after {
+                match before.get(key) {
+                    None => drift.push(DriftItem {
+                        category,
+                        key: key.clone(),
+                        change: DriftChange::Added,
+                        baseline: None,
+                        current: Some(value.clone()),
+                    }),
+                    Some(old) if old != value => drift.push(DriftItem {
+                        category,
+                        key: key.clone(),
+                        change: DriftChange::Changed,
+                        baseline: Some(old.clone()),
+                        current: Some(value.clone()),
+                    }),
+                    Some(_) => {}
+                }
+            }
+            for (key, value) in before {
+                if !after.contains_key(key) {
+                    drift.push(DriftItem {
+                        category,
+                        key: key.clone(),
+                        change: DriftChange::Removed,
+                        baseline: Some(value.clone()),
+                        current: None,
+                    });
+                }
+            }
+        }
+        drift
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DriftReport {
+    pub baseline_taken_at: DateTime<Utc>,
+    pub checked_at: DateTime<Utc>,
+    pub drift: Vec<DriftItem>,
+}
+
+fn hosts_path() -> PathBuf {
+    if cfg!(windows) {
+        let root = std::env::var("SystemRoot").unwrap_or_else(|_| "C:\\Windows".to_string());
+        Path::new(&root).join("System32\\drivers\\etc\\hosts")
+    } else {
+        PathBuf::from("/etc/hosts")
+    }
+}
+
+/// Host name to the addresses it is mapped to, comments and spacing removed.
+fn parse_hosts(content: &str) -> BTreeMap<String, String> {
+    let mut entries: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or_default();
+        let mut fields = line.split_whitespace();
+        let Some(address) = fields.next() else {
+            continue;
+        };
+        for host in fields {
+            entries
+                .entry(host.to_lowercase())
+                .or_default()
+                .insert(address.to_string());
+        }
+    }
+    entries
+        .into_iter()
+        .map(|(host, addresses)| (host, addresses.into_iter().collect::<Vec<_>>().join(" ")))
+        .collect()
+}
+
+fn services() -> BTreeMap<String, String> {
+    #[cfg(windows)]
+    let output = run_command(
+        "powershell",
+        &[
+            "-NoProfile",
+            "-Command",
+            "Get-CimInstance Win32_Service | ForEach-Object { $_.Name + ',' + $_.StartMode }",
+        ],
+    );
+    #[cfg(not(windows))]
+    let output = run_command(
+        "systemctl",
+        &[
+            "list-unit-files",
+            "--type=service",
+            "--no-legend",
+            "--no-pager",
+        ],
+    );
+    output.map(|out| parse_services(&out)).unwrap_or_default()
+}
+
+/// `name,mode` lines (Windows) or `systemctl list-unit-files` rows.
+fn parse_services(output: &str) -> BTreeMap<String, String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let (name, mode) = match line.split_once(',') {
+                Some((name, mode)) => (name.trim(), mode.trim()),
+                None => {
+                    let mut fields = line.split_whitespace();
+                    (fields.next()?, fields.next()?)
+                }
+            };
+            (!name.is_empty() && !mode.is_empty()).then(|| (name.to_string(), mode.to_lowercase()))
+        })
+        .collect()
+}
+
+/// Default-profile `Preferences` files of Chromium-based browsers.
+fn chromium_profiles() -> Vec<(&'static str, PathBuf)> {
+    let mut roots: Vec<(&str, PathBuf)> = Vec::new();
+    if cfg!(windows) {
+        if let Some(local) = std::env::var_os("LOCALAPPDATA").map(PathBuf::from) {
+            roots.push(("chrome", local.join("Google\\Chrome\\User Data")));
+            roots.push(("edge", local.join("Microsoft\\Edge\\User Data")));
+            roots.push((
+                "brave",
+                local.join("BraveSoftware\\Brave-Browser\\User Data"),
+            ));
+        }
+    } else if let Some(home) = std::env::var_os("HOME").map(PathBuf::from) {
+        let support = if cfg!(target_os = "macos") {
+            home.join("Library/Application Support")
+        } else {
+            home.join(".config")
+        };
+        let (chrome, edge, brave) = if cfg!(target_os = "macos") {
+            (
+                "Google/Chrome",
+                "Microsoft Edge",
+                "BraveSoftware/Brave-Browser",
+            )
+        } else {
+            (
+                "google-chrome",
+                "microsoft-edge",
+                "BraveSoftware/Brave-Browser",
+            )
+        };
+        roots.push(("chrome", support.join(chrome)));
+        roots.push(("chromium", support.join("chromium")));
+        roots.push(("edge", support.join(edge)));
+        roots.push(("brave", support.join(brave)));
+    }
+    roots
+        .into_iter()
+        .map(|(browser, root)| (browser, root.join("Default").join("Preferences")))
+        .filter(|(_, path)| path.is_file())
+        .collect()
+}
+
+fn firefox_profiles() -> Vec<PathBuf> {
+    let root = if cfg!(windows) {
+        std::env::var_os("APPDATA").map(|d| PathBuf::from(d).join("Mozilla\\Firefox\\Profiles"))
+    } else if cfg!(target_os = "macos") {
+        std::env::var_os("HOME")
+            .map(|h| PathBuf::from(h).join("Library/Application Support/Firefox/Profiles"))
+    } else {
+        std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".mozilla/firefox"))
+    };
+    root.and_then(|root| fs::read_dir(root).ok())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path().join("prefs.js"))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+fn browser_settings() -> BTreeMap<String, String> {
+    let mut settings = BTreeMap::new();
+    for (browser, path) in chromium_profiles() {
+        match fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+        {
+            Some(prefs) => settings.extend(chromium_settings(browser, &prefs)),
+            None => warn!("Unreadable browser preferences: {path:?}"),
+        }
+    }
+    for path in firefox_profiles() {
+        let profile = path
+            .parent()
+            .and_then(|p| p.file_name())
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if let Ok(content) = fs::read_to_string(&path) {
+            for (key, value) in firefox_settings(&content) {
+                settings.insert(format!("firefox.{profile}.{key}"), value);
+            }
+        }
+    }
+    settings
+}
+
+fn chromium_settings(browser: &str, prefs: &serde_json::Value) -> BTreeMap<String, String> {
+    let mut settings = BTreeMap::new();
+    let fields = [
+        ("homepage", "/homepage"),
+        ("restore_on_startup", "/session/restore_on_startup"),
+        ("startup_urls", "/session/startup_urls"),
+        (
+            "search_url",
+            "/default_search_provider_data/template_url_data/url",
+        ),
+        ("proxy", "/proxy"),
+    ];
+    for (name, pointer) in fields {
+        if let Some(value) = prefs.pointer(pointer).filter(|v| !v.is_null()) {
+            let value = match value.as_str() {
+                Some(s) => s.to_string(),
+                None => value.to_string(),
+            };
+            settings.insert(format!("{browser}.{name}"), value);
+        }
+    }
+    if let Some(extensions) = prefs
+        .pointer("/extensions/settings")
+        .and_then(|v| v.as_object())
+    {
+        for (id, ext) in extensions {
+            // Component extensions ship with the browser
+            if ext.get("location").and_then(|v| v.as_u64()) == Some(5) {
+                continue;
+            }
+            let name = ext
+                .pointer("/manifest/name")
+                .and_then(|v| v.as_str())
+                .unwrap_or("installed");
+            settings.insert(format!("{browser}.extension.{id}"), name.to_string());
+        }
+    }
+    settings
+}
+
+/// Watched `user_pref("key", value);` lines of a Firefox `prefs.js`.
+fn firefox_settings(content: &str) -> BTreeMap<String, String> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let inner = line.trim().strip_prefix("user_pref(")?.strip_suffix(");")?;
+            let (key, value) = inner.split_once(',')?;
+            let key = key.trim().trim_matches('"');
+            FIREFOX_PREFS
+                .contains(&key)
+                .then(|| (key.to_string(), value.trim().trim_matches('"').to_string()))
+        })
+        .collect()
+}
+
+/// Stored baseline plus the drift already turned into threats.
+pub struct BaselineMonitor {
+    path: PathBuf,
+    /// Fingerprints of drift already reported since the baseline was taken
+    reported: Mutex<HashSet<String>>,
+}
+
+impl BaselineMonitor {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            reported: Mutex::new(HashSet::new()),
+        }
+    }
+
+    pub fn load(&self) -> Option<BaselineSnapshot> {
+        let content = fs::read_to_string(&self.path).ok()?;
+        serde_json::from_str(&content)
+            .map_err(|e| warn!("Ignoring unreadable baseline {:?}: {e}", self.path))
+            .ok()
+    }
+
+    /// Store `snapshot` as the new known-good state.
+    pub fn save(&self, snapshot: &BaselineSnapshot) -> Result<(), String> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create {parent:?}: {e}"))?;
+        }
+        let json = serde_json::to_string_pretty(snapshot).map_err(|e| e.to_string())?;
+        fs::write(&self.path, json).map_err(|e| format!("Failed to save baseline: {e}"))?;
+        self.reported.lock().unwrap().clear();
+        info!(
+            "Baseline recorded: {} startup item(s), {} service(s), {} hosts entr(ies), {} browser setting(s)",
+            snapshot.startup_items.len(),
+            snapshot.services.len(),
+            snapshot.hosts.len(),
+            snapshot.browser_settings.len()
+        );
+        Ok(())
+    }
+
+    /// Capture the current state and make it the baseline.
+    pub fn record(&self) -> Result<BaselineSnapshot, String> {
+        let snapshot = BaselineSnapshot::capture();
+        self.save(&snapshot)?;
+        Ok(snapshot)
+    }
+
+    /// Drift of `current` from the stored baseline, or `None` without one.
+    pub fn drift(&self, current: &BaselineSnapshot) -> Option<DriftReport> {
+        let baseline = self.load()?;
+        Some(DriftReport {
+            baseline_taken_at: baseline.taken_at,
+            checked_at: current.taken_at,
+            drift: baseline.diff(current),
+        })
+    }
+
+    /// Drift in `report` that was not returned by an earlier call.
+    pub fn unreported(&self, report: &DriftReport) -> Vec<DriftItem> {
+        let mut reported = self.reported.lock().unwrap();
+        report
+            .drift
+            .iter()
+            .filter(|item| reported.insert(item.fingerprint()))
+            .cloned()
+            .collect()
+    }
+
+    /// Periodic check: records a baseline if there is none, otherwise
+    /// returns the threats for drift not reported before.
+    pub fn check(&self) -> Result<Vec<ThreatEvent>, String> {
+        let current = BaselineSnapshot::capture();
+        let Some(report) = self.drift(&current) else {
+            self.save(&current)?;
+            return Ok(Vec::new());
+        };
+        Ok(self
+            .unreported(&report)
+            .iter()
+            .map(DriftItem::to_threat)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(startup: &[(&str, &str)], hosts: &str) -> BaselineSnapshot {
+        BaselineSnapshot {
+            taken_at: Utc::now(),
+            startup_items: startup
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            services: BTreeMap::from([("sshd.service".to_string(), "enabled".to_string())]),
+            hosts: parse_hosts(hosts),
+            browser_settings: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn parses_settings_sources() {
+        let hosts = parse_hosts(
+            "127.0.0.1 localhost  # loopback\n::1 localhost\n# comment\n10.0.0.5 bank.example Mail.example\n",
+        );
+        assert_eq!(hosts.len(), 3);
+        assert_eq!(hosts["localhost"], "127.0.0.1 ::1");
+        assert_eq!(hosts["mail.example"], "10.0.0.5");
+
+        let services = parse_services(
+            "sshd.service   enabled enabled\ncups.service disabled enabled\nSpooler,Auto\n",
+        );
+        assert_eq!(services["cups.service"], "disabled");
+        assert_eq!(services["Spooler"], "auto");
+
+        let prefs = serde_json::json!({
+            "homepage": "https://start.example",
+            "session": { "restore_on_startup": 4, "startup_urls": ["https://a.example"] },
+            "extensions": { "settings": {
+                "abc": { "location": 1, "manifest": { "name": "Coupon Helper" } },
+                "builtin": { "location": 5 }
+            }}
+        });
+        let chrome = chromium_settings("chrome", &prefs);
+        assert_eq!(chrome["chrome.homepage"], "https://start.example");
+        assert_eq!(chrome["chrome.startup_urls"], "[\"https://a.example\"]");
+        assert_eq!(chrome["chrome.extension.abc"], "Coupon Helper");
+        assert!(!chrome.contains_key("chrome.extension.builtin"));
+
+        let firefox = firefox_settings(
+            "user_pref(\"browser.startup.homepage\", \"https://evil.example\");\nuser_pref(\"app.update.auto\", true);\n",
+        );
+        assert_eq!(firefox.len(), 1);
+        assert_eq!(firefox["browser.startup.homepage"], "https://evil.example");
+    }
+
+    #[test]
+    fn drift_is_reported_once_per_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let monitor = BaselineMonitor::new(dir.path().join("baseline.json"));
+        let baseline = snapshot(
+            &[("a1", "updater: /usr/bin/updater")],
+            "127.0.0.1 localhost\n",
+        );
+        assert!(monitor.drift(&baseline).is_none());
+        monitor.save(&baseline).unwrap();
+
+        let mut current = snapshot(
+            &[("b2", "miner: /tmp/.x/miner")],
+            "127.0.0.1 localhost\n6.6.6.6 bank.example\n",
+        );
+        current
+            .services
+            .insert("sshd.service".to_string(), "disabled".to_string());
+        let report = monitor.drift(&current).unwrap();
+        assert_eq!(report.drift.len(), 4);
+
+        let added = report
+            .drift
+            .iter()
+            .find(|d| d.key == "bank.example")
+            .unwrap();
+        assert_eq!(added.change, DriftChange::Added);
+        let threat = added.to_threat();
+        assert!(matches!(threat.threat_type, ThreatType::FileSystemAnomaly));
+        assert_eq!(threat.severity, ThreatSeverity::High);
+        let service = report
+            .drift
+            .iter()
+            .find(|d| d.category == DriftCategory::Service)
+            .unwrap();
+        assert_eq!(service.change, DriftChange::Changed);
+        assert!(matches!(
+            service.to_threat().threat_type,
+            ThreatType::ConfigurationDrift
+        ));
+
+        assert_eq!(monitor.unreported(&report).len(), 4);
+        assert!(monitor.unreported(&report).is_empty());
+        // Accepting the current state starts a fresh baseline
+        monitor.save(&current).unwrap();
+        assert!(monitor.drift(&current).unwrap().drift.is_empty());
+    }
+}
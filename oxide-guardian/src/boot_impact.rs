@@ -0,0 +1,382 @@
+//! Boot impact analysis: how long the desktop took to appear and how much
+//! CPU and disk each autostart entry used in the first minutes after boot.
+//!
+//! The metrics collector fills a [`BootCapture`] while the system is within
+//! [`BOOT_WINDOW_SECS`] of boot, so usage is only measured when Oxide Pilot
+//! itself starts with the system. [`analyze`] matches the captured processes
+//! to [`StartupItem`]s by executable name and rates each entry with the
+//! thresholds Windows uses for its "Startup impact" column.
+
+use crate::startup::{self, StartupItem, StartupSource};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Seconds after boot during which process usage is captured.
+pub const BOOT_WINDOW_SECS: i64 = 300;
+
+/// More than a second of CPU or 3 MB of disk I/O is a high impact.
+const HIGH_CPU_SECS: f64 = 1.0;
+const HIGH_DISK_BYTES: u64 = 3 * 1024 * 1024;
+/// Below 300 ms of CPU and 300 KB of disk I/O is a low impact.
+const MEDIUM_CPU_SECS: f64 = 0.3;
+const MEDIUM_DISK_BYTES: u64 = 300 * 1024;
+
+/// Processes whose start marks the desktop as ready.
+const DESKTOP_SHELLS: &[&str] = &[
+    "explorer.exe",
+    "gnome-shell",
+    "plasmashell",
+    "xfce4-session",
+    "cinnamon",
+    "mate-session",
+    "lxqt-session",
+    "sway",
+    "hyprland",
+];
+
+/// Launchers whose usage cannot be attributed to a single entry.
+const SHARED_HOSTS: &[&str] = &["svchost", "cmd", "sh", "bash", "env", "python", "python3"];
+
+/// Heaviest processes kept in the report besides the startup entries.
+const TOP_PROCESSES: usize = 15;
+
+/// Usage of one process name during the boot window.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProcessBootUsage {
+    pub name: String,
+    pub cpu_seconds: f64,
+    pub disk_read_bytes: u64,
+    pub disk_written_bytes: u64,
+}
+
+impl ProcessBootUsage {
+    fn disk_bytes(&self) -> u64 {
+        self.disk_read_bytes + self.disk_written_bytes
+    }
+}
+
+/// Per-process usage collected from boot until the end of the boot window.
+#[derive(Debug, Clone)]
+pub struct BootCapture {
+    pub boot_time: DateTime<Utc>,
+    /// First sample; usage before it is not seen.
+    pub started_at: DateTime<Utc>,
+    pub last_sample_at: DateTime<Utc>,
+    processes: HashMap<String, ProcessBootUsage>,
+}
+
+impl BootCapture {
+    /// A capture for the boot at `boot_time`, or `None` once its window has passed.
+    pub fn start(boot_time: DateTime<Utc>, now: DateTime<Utc>) -> Option<Self> {
+        (now - boot_time < Duration::seconds(BOOT_WINDOW_SECS)).then(|| Self {
+            boot_time,
+            started_at: now,
+            last_sample_at: now,
+            processes: HashMap::new(),
+        })
+    }
+
+    /// The boot window has ended; later samples are ignored.
+    pub fn complete(&self, now: DateTime<Utc>) -> bool {
+        now - self.boot_time >= Duration::seconds(BOOT_WINDOW_SECS)
+    }
+
+    /// Add one collection tick: name, CPU percent of one core, and bytes
+    /// read and written since the previous tick, per process. Ticks after
+    /// the one that crosses the end of the window are ignored.
+    pub fn record<'a>(
+        &mut self,
+        now: DateTime<Utc>,
+        usage: impl IntoIterator<Item = (&'a str, f32, u64, u64)>,
+    ) {
+        if self.complete(self.last_sample_at) {
+            return;
+        }
+        let elapsed = (now - self.last_sample_at).num_milliseconds().max(0) as f64 / 1000.0;
+        for (name, cpu_percent, read, written) in usage {
+            let entry =
+                self.processes
+                    .entry(name.to_string())
+                    .or_insert_with(|| ProcessBootUsage {
+                        name: name.to_string(),
+                        ..Default::default()
+                    });
+            entry.cpu_seconds += cpu_percent as f64 / 100.0 * elapsed;
+            entry.disk_read_bytes += read;
+            entry.disk_written_bytes += written;
+        }
+        self.last_sample_at = now;
+    }
+
+    fn usage_of(&self, executable: &str) -> Option<&ProcessBootUsage> {
+        self.processes
+            .values()
+            .find(|p| process_matches(&p.name, executable))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StartupImpact {
+    /// No capture of this boot, or the entry runs through a shared launcher.
+    NotMeasured,
+    /// The entry's process did not run during the boot window.
+    None,
+    Low,
+    Medium,
+    High,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupImpactEntry {
+    pub item: StartupItem,
+    pub impact: StartupImpact,
+    /// 0-100 for ranking: 10 points per CPU second, 3 per MB of disk I/O.
+    pub impact_score: u8,
+    pub cpu_seconds: f64,
+    pub disk_bytes: u64,
+    /// High impact and safe to turn off; offered through remediation.
+    pub suggest_disable: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BootImpactReport {
+    pub boot_time: DateTime<Utc>,
+    /// Boot until the desktop shell started.
+    pub time_to_desktop_secs: Option<u64>,
+    pub desktop_process: Option<String>,
+    pub window_secs: i64,
+    /// Usage was captured for this boot.
+    pub measured: bool,
+    /// Seconds after boot the capture started; earlier usage is missing.
+    pub capture_started_after_secs: Option<i64>,
+    /// Entries by impact, highest first.
+    pub items: Vec<StartupImpactEntry>,
+    /// Heaviest processes of the boot window, whether autostarted or not.
+    pub top_processes: Vec<ProcessBootUsage>,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Linux reports process names cut to 15 characters and Windows keeps the
+/// `.exe`, so both are normalised before comparing.
+fn process_matches(process: &str, executable: &str) -> bool {
+    let process = process.to_ascii_lowercase();
+    let process = process.strip_suffix(".exe").unwrap_or(&process);
+    process == executable || (process.len() == 15 && executable.starts_with(process))
+}
+
+/// Lowercase file stem of an entry's executable; `None` for shared launchers.
+fn executable_stem(item: &StartupItem) -> Option<String> {
+    let exe = startup::executable_of(&item.command).replace('\\', "/");
+    let stem = Path::new(&exe).file_stem()?.to_str()?.to_ascii_lowercase();
+    let shared = SHARED_HOSTS.contains(&stem.as_str())
+        || startup::SCRIPT_HOSTS.iter().any(|h| stem.contains(h));
+    (!stem.is_empty() && !shared).then_some(stem)
+}
+
+fn weight(cpu_seconds: f64, disk_bytes: u64) -> f64 {
+    cpu_seconds * 10.0 + disk_bytes as f64 / 1024.0 / 1024.0 * 3.0
+}
+
+fn rate(cpu_seconds: f64, disk_bytes: u64) -> StartupImpact {
+    if cpu_seconds > HIGH_CPU_SECS || disk_bytes > HIGH_DISK_BYTES {
+        StartupImpact::High
+    } else if cpu_seconds >= MEDIUM_CPU_SECS || disk_bytes >= MEDIUM_DISK_BYTES {
+        StartupImpact::Medium
+    } else {
+        StartupImpact::Low
+    }
+}
+
+/// Services, units and cron jobs may be needed by other software, so only
+/// per-user entries are suggested for disabling.
+fn disable_candidate(item: &StartupItem) -> bool {
+    item.enabled
+        && matches!(
+            item.source,
+            StartupSource::RegistryRun | StartupSource::ScheduledTask | StartupSource::XdgAutostart
+        )
+}
+
+/// Earliest desktop shell started after boot, with its delay in seconds.
+/// `processes` yields names and start times in seconds since the epoch.
+pub fn desktop_ready<'a>(
+    boot_time: DateTime<Utc>,
+    processes: impl IntoIterator<Item = (&'a str, u64)>,
+) -> Option<(String, u64)> {
+    let boot = boot_time.timestamp().max(0) as u64;
+    processes
+        .into_iter()
+        .filter(|(name, started)| {
+            *started >= boot && DESKTOP_SHELLS.contains(&name.to_ascii_lowercase().as_str())
+        })
+        .min_by_key(|(_, started)| *started)
+        .map(|(name, started)| (name.to_string(), started - boot))
+}
+
+/// Boot time and desktop shell of the running system.
+pub fn current_boot() -> (DateTime<Utc>, Option<(String, u64)>) {
+    use sysinfo::{ProcessExt, System, SystemExt};
+
+    let mut sys = System::new();
+    sys.refresh_processes();
+    let boot_time = DateTime::from_timestamp(sys.boot_time() as i64, 0).unwrap_or_else(Utc::now);
+    let desktop = desktop_ready(
+        boot_time,
+        sys.processes().values().map(|p| (p.name(), p.start_time())),
+    );
+    (boot_time, desktop)
+}
+
+/// Rate every startup entry against `capture` of the boot at `boot_time`.
+pub fn analyze(
+    items: Vec<StartupItem>,
+    boot_time: DateTime<Utc>,
+    capture: Option<&BootCapture>,
+    desktop: Option<(String, u64)>,
+    now: DateTime<Utc>,
+) -> BootImpactReport {
+    // A capture from an earlier boot says nothing about this one
+    let capture = capture.filter(|c| c.boot_time == boot_time);
+    let mut entries: Vec<StartupImpactEntry> = items
+        .into_iter()
+        .map(|item| {
+            let usage = capture.zip(executable_stem(&item));
+            let (impact, cpu_seconds, disk_bytes) = match usage {
+                None => (StartupImpact::NotMeasured, 0.0, 0),
+                Some((capture, stem)) => match capture.usage_of(&stem) {
+                    Some(p) => (
+                        rate(p.cpu_seconds, p.disk_bytes()),
+                        p.cpu_seconds,
+                        p.disk_bytes(),
+                    ),
+                    None => (StartupImpact::None, 0.0, 0),
+                },
+            };
+            StartupImpactEntry {
+                suggest_disable: impact == StartupImpact::High && disable_candidate(&item),
+                item,
+                impact,
+                impact_score: weight(cpu_seconds, disk_bytes).round().min(100.0) as u8,
+                cpu_seconds,
+                disk_bytes,
+            }
+        })
+        .collect();
+    entries.sort_by(|a, b| {
+        b.impact
+            .cmp(&a.impact)
+            .then(b.impact_score.cmp(&a.impact_score))
+            .then(a.item.name.cmp(&b.item.name))
+    });
+
+    let mut top_processes: Vec<ProcessBootUsage> = capture
+        .map(|c| c.processes.values().cloned().collect())
+        .unwrap_or_default();
+    top_processes.sort_by(|a, b| {
+        weight(b.cpu_seconds, b.disk_bytes()).total_cmp(&weight(a.cpu_seconds, a.disk_bytes()))
+    });
+    top_processes.truncate(TOP_PROCESSES);
+
+    BootImpactReport {
+        boot_time,
+        time_to_desktop_secs: desktop.as_ref().map(|(_, secs)| *secs),
+        desktop_process: desktop.map(|(name, _)| name),
+        window_secs: BOOT_WINDOW_SECS,
+        measured: capture.is_some(),
+        capture_started_after_secs: capture.map(|c| (c.started_at - boot_time).num_seconds()),
+        items: entries,
+        top_processes,
+        generated_at: now,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: &str, command: &str, source: StartupSource) -> StartupItem {
+        serde_json::from_value(serde_json::json!({
+            "id": id, "name": id, "source": source, "location": "",
+            "command": command, "enabled": true, "risk_score": 0,
+            "risk_level": "low", "reasons": [],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn rates_entries_from_boot_capture() {
+        let boot = Utc::now() - Duration::seconds(60);
+        let mut capture = BootCapture::start(boot, boot + Duration::seconds(20)).unwrap();
+        capture.record(
+            boot + Duration::seconds(30),
+            [
+                ("Updater.exe", 50.0, 4 * 1024 * 1024, 0),
+                ("chat-client-dae", 2.0, 1024, 0),
+                ("svchost.exe", 90.0, 0, 0),
+            ],
+        );
+        assert!(BootCapture::start(boot, boot + Duration::seconds(BOOT_WINDOW_SECS)).is_none());
+
+        let items = vec![
+            item(
+                "a",
+                r#""C:\Apps\Updater.exe" /silent"#,
+                StartupSource::RegistryRun,
+            ),
+            item(
+                "b",
+                "/usr/bin/chat-client-daemon",
+                StartupSource::XdgAutostart,
+            ),
+            item(
+                "c",
+                r"C:\Windows\system32\svchost.exe -k netsvcs",
+                StartupSource::Service,
+            ),
+            item("d", "/opt/backup/run", StartupSource::SystemdUnit),
+        ];
+        let desktop = desktop_ready(
+            boot,
+            [
+                ("explorer.exe", boot.timestamp() as u64 + 42),
+                ("bash", boot.timestamp() as u64 + 5),
+            ],
+        );
+        let report = analyze(items.clone(), boot, Some(&capture), desktop, Utc::now());
+        assert_eq!(report.time_to_desktop_secs, Some(42));
+        assert_eq!(report.capture_started_after_secs, Some(20));
+        let impacts: Vec<(&str, StartupImpact)> = report
+            .items
+            .iter()
+            .map(|e| (e.item.id.as_str(), e.impact))
+            .collect();
+        assert_eq!(
+            impacts,
+            vec![
+                ("a", StartupImpact::High),
+                ("b", StartupImpact::Low),
+                ("d", StartupImpact::None),
+                ("c", StartupImpact::NotMeasured),
+            ]
+        );
+        assert!(report.items[0].suggest_disable);
+        assert_eq!(report.items[0].impact_score, 62);
+        assert_eq!(report.top_processes[0].name, "svchost.exe");
+
+        let unmeasured = analyze(
+            items,
+            boot - Duration::hours(5),
+            Some(&capture),
+            None,
+            Utc::now(),
+        );
+        assert!(!unmeasured.measured);
+        assert!(unmeasured
+            .items
+            .iter()
+            .all(|e| e.impact == StartupImpact::NotMeasured && !e.suggest_disable));
+    }
+}
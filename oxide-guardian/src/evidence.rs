@@ -0,0 +1,592 @@
+//! Evidence bundles for escalating a threat to a security team.
+//!
+//! A bundle is a zip archive holding the threat, a snapshot of its process
+//! and parent, hashes of the file involved, the TCP connections of the
+//! process, and the metrics and log lines the caller gathered around the
+//! time of the threat. `manifest.json` lists every file with its size and
+//! SHA-256; a copy is written next to the archive.
+//!
+//! With a passphrase the archive is encrypted with AES-256-GCM under a key
+//! derived by PBKDF2-HMAC-SHA256. The `.zip.enc` file is [`ENCRYPTED_MAGIC`]
+//! followed by the salt, the nonce and the ciphertext; [`decrypt_bundle`]
+//! turns it back into the zip.
+
+use crate::guardian::ThreatEvent;
+use crate::scanner::FileScanner;
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use sysinfo::{Pid, PidExt, ProcessExt, System, SystemExt};
+
+pub const EVIDENCE_DIR: &str = "./data/evidence";
+pub const ENCRYPTED_MAGIC: &[u8; 6] = b"OXEV01";
+#[cfg(not(test))]
+const PBKDF2_ROUNDS: u32 = 600_000;
+// Keeps the round-trip test fast in debug builds
+#[cfg(test)]
+const PBKDF2_ROUNDS: u32 = 1_000;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const MAX_CONNECTIONS: usize = 500;
+
+/// Data gathered outside Guardian, around the time of the threat.
+#[derive(Debug, Clone, Default)]
+pub struct EvidenceSources {
+    pub metrics: Vec<Value>,
+    pub logs: Vec<Value>,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EvidenceStage {
+    Process,
+    Hashes,
+    Network,
+    Archive,
+    Encrypt,
+    Done,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct EvidenceProgress {
+    pub threat_id: String,
+    pub stage: EvidenceStage,
+    pub percent: u8,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ManifestEntry {
+    pub name: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EvidenceManifest {
+    pub threat_id: String,
+    pub created_at: DateTime<Utc>,
+    pub host: String,
+    pub encrypted: bool,
+    pub files: Vec<ManifestEntry>,
+    /// Sources that could not be collected, and why
+    pub warnings: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct EvidenceBundle {
+    pub archive_path: String,
+    pub manifest_path: String,
+    pub manifest: EvidenceManifest,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Connection {
+    pub local: String,
+    pub remote: String,
+    pub state: String,
+    pub pid: Option<u32>,
+}
+
+/// File the threat is about, from its details or its process.
+pub fn threat_file(threat: &ThreatEvent) -> Option<String> {
+    ["path", "file_path", "exe"]
+        .iter()
+        .find_map(|key| threat.details.get(*key).cloned())
+        .or_else(|| {
+            let pid = Pid::from_u32(threat.process_id?);
+            let mut sys = System::new();
+            sys.refresh_process(pid);
+            let exe = sys.process(pid)?.exe().to_string_lossy().to_string();
+            (!exe.is_empty()).then_some(exe)
+        })
+}
+
+/// Details of `pid` and its parent, if still running.
+pub fn process_snapshot(pid: u32) -> Option<Value> {
+    let mut sys = System::new();
+    sys.refresh_processes();
+    let describe = |pid: Pid| {
+        sys.process(pid).map(|p| {
+            json!({
+                "pid": pid.as_u32(),
+                "name": p.name(),
+                "exe": p.exe(),
+                "cmd": p.cmd(),
+                "cwd": p.cwd(),
+                "memory": p.memory(),
+                "cpu_usage": p.cpu_usage(),
+                "start_time": p.start_time(),
+                "run_time_secs": p.run_time(),
+            })
+        })
+    };
+    let process = sys.process(Pid::from_u32(pid))?;
+    Some(json!({
+        "process": describe(Pid::from_u32(pid)),
+        "parent": process.parent().and_then(describe),
+    }))
+}
+
+/// Write the evidence bundle for `threat` into `dir`. Raising `cancel`
+/// stops the build at the next stage; nothing is written until the last one.
+pub fn build_bundle(
+    threat: &ThreatEvent,
+    sources: EvidenceSources,
+    dir: &Path,
+    passphrase: Option<&str>,
+    cancel: &AtomicBool,
+    progress: impl Fn(EvidenceProgress),
+) -> Result<EvidenceBundle, String> {
+    let report = |stage, percent| {
+        if stage != EvidenceStage::Done && cancel.load(Ordering::SeqCst) {
+            return Err("Evidence collection cancelled".to_string());
+        }
+        progress(EvidenceProgress {
+            threat_id: threat.id.clone(),
+            stage,
+            percent,
+        });
+        Ok(())
+    };
+    let mut warnings = Vec::new();
+    let mut files: Vec<(String, Vec<u8>)> = vec![("threat.json".to_string(), to_json(threat)?)];
+
+    report(EvidenceStage::Process, 10)?;
+    match threat.process_id.map(|pid| (pid, process_snapshot(pid))) {
+        Some((_, Some(snapshot))) => files.push(("process.json".to_string(), to_json(&snapshot)?)),
+        Some((pid, None)) => warnings.push(format!("Process {pid} is no longer running")),
+        None => {}
+    }
+
+    report(EvidenceStage::Hashes, 30)?;
+    if let Some(path) = threat_file(threat) {
+        match FileScanner::compute_hashes(&path) {
+            Ok((hashes, size)) => files.push((
+                "hashes.json".to_string(),
+                to_json(&json!({ "path": path, "size": size, "hashes": hashes }))?,
+            )),
+            Err(e) => warnings.push(format!("Could not hash {path}: {e}")),
+        }
+    }
+
+    report(EvidenceStage::Network, 50)?;
+    match tcp_connections(threat.process_id) {
+        Ok(connections) => files.push(("network.json".to_string(), to_json(&connections)?)),
+        Err(e) => warnings.push(format!("Network connections unavailable: {e}")),
+    }
+    if !sources.metrics.is_empty() {
+        files.push(("metrics.json".to_string(), to_json(&sources.metrics)?));
+    }
+    if !sources.logs.is_empty() {
+        files.push(("logs.json".to_string(), to_json(&sources.logs)?));
+    }
+
+    report(EvidenceStage::Archive, 70)?;
+    let created_at = Utc::now();
+    let manifest = EvidenceManifest {
+        threat_id: threat.id.clone(),
+        created_at,
+        host: hostname::get()
+            .map(|h| h.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        encrypted: passphrase.is_some(),
+        files: files
+            .iter()
+            .map(|(name, bytes)| ManifestEntry {
+                name: name.clone(),
+                size: bytes.len() as u64,
+                sha256: hex_sha256(bytes),
+            })
+            .collect(),
+        warnings,
+    };
+    let manifest_json = to_json(&manifest)?;
+    files.push(("manifest.json".to_string(), manifest_json.clone()));
+    let mut archive = zip_files(&files)?;
+
+    let stem = format!(
+        "evidence_{}_{}",
+        created_at.format("%Y%m%dT%H%M%S"),
+        sanitize(&threat.id)
+    );
+    let archive_path = match passphrase {
+        Some(passphrase) => {
+            report(EvidenceStage::Encrypt, 85)?;
+            archive = encrypt(&archive, passphrase)?;
+            dir.join(format!("{stem}.zip.enc"))
+        }
+        None => dir.join(format!("{stem}.zip")),
+    };
+    let manifest_path = dir.join(format!("{stem}.manifest.json"));
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create {dir:?}: {e}"))?;
+    write_file(&archive_path, &archive)?;
+    write_file(&manifest_path, &manifest_json)?;
+
+    report(EvidenceStage::Done, 100)?;
+    Ok(EvidenceBundle {
+        archive_path: archive_path.display().to_string(),
+        manifest_path: manifest_path.display().to_string(),
+        manifest,
+    })
+}
+
+/// The zip archive inside an encrypted bundle.
+pub fn decrypt_bundle(data: &[u8], passphrase: &str) -> Result<Vec<u8>, String> {
+    let header = ENCRYPTED_MAGIC.len();
+    if data.len() < header + SALT_LEN + NONCE_LEN || !data.starts_with(ENCRYPTED_MAGIC) {
+        return Err("Not an encrypted evidence bundle".to_string());
+    }
+    let (salt, rest) = data[header..].split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+    cipher(passphrase, salt)
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "Wrong passphrase or corrupted bundle".to_string())
+}
+
+fn encrypt(plaintext: &[u8], passphrase: &str) -> Result<Vec<u8>, String> {
+    if passphrase.is_empty() {
+        return Err("Encryption passphrase must not be empty".to_string());
+    }
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut salt);
+    OsRng.fill_bytes(&mut nonce);
+    let ciphertext = cipher(passphrase, &salt)
+        .encrypt(Nonce::from_slice(&nonce), plaintext)
+        .map_err(|e| format!("Encryption failed: {e:?}"))?;
+    let mut out = ENCRYPTED_MAGIC.to_vec();
+    out.extend_from_slice(&salt);
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+fn cipher(passphrase: &str, salt: &[u8]) -> Aes256Gcm {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, PBKDF2_ROUNDS, &mut key);
+    Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key))
+}
+
+fn zip_files(files: &[(String, Vec<u8>)]) -> Result<Vec<u8>, String> {
+    let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let options =
+        zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    for (name, bytes) in files {
+        zip.start_file(name.as_str(), options)
+            .map_err(|e| e.to_string())?;
+        zip.write_all(bytes).map_err(|e| e.to_string())?;
+    }
+    Ok(zip.finish().map_err(|e| e.to_string())?.into_inner())
+}
+
+fn to_json(value: &impl Serialize) -> Result<Vec<u8>, String> {
+    serde_json::to_vec_pretty(value).map_err(|e| e.to_string())
+}
+
+fn hex_sha256(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+fn sanitize(id: &str) -> String {
+    id.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn write_file(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    fs::write(path, bytes).map_err(|e| format!("Failed to write {path:?}: {e}"))
+}
+
+/// TCP connections of `pid`, or of every process when `None`.
+pub fn tcp_connections(pid: Option<u32>) -> Result<Vec<Connection>, String> {
+    let mut connections = platform_connections(pid)?;
+    if let Some(pid) = pid {
+        connections.retain(|c| c.pid == Some(pid));
+    }
+    connections.truncate(MAX_CONNECTIONS);
+    Ok(connections)
+}
+
+#[cfg(target_os = "linux")]
+fn platform_connections(pid: Option<u32>) -> Result<Vec<Connection>, String> {
+    // /proc/net lists sockets by inode; the owning process holds the inode
+    // as a `socket:[inode]` link under /proc/<pid>/fd
+    let inodes: Option<Vec<String>> = pid.map(|pid| {
+        fs::read_dir(format!("/proc/{pid}/fd"))
+            .map(|entries| {
+                entries
+                    .filter_map(|e| fs::read_link(e.ok()?.path()).ok())
+                    .filter_map(|link| {
+                        let link = link.to_string_lossy().to_string();
+                        Some(
+                            link.strip_prefix("socket:[")?
+                                .strip_suffix(']')?
+                                .to_string(),
+                        )
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    });
+    let mut connections = Vec::new();
+    for path in ["/proc/net/tcp", "/proc/net/tcp6"] {
+        let Ok(content) = fs::read_to_string(path) else {
+            continue;
+        };
+        for (connection, inode) in parse_proc_net_tcp(&content) {
+            match (&inodes, pid) {
+                (Some(inodes), Some(pid)) if inodes.contains(&inode) => {
+                    connections.push(Connection {
+                        pid: Some(pid),
+                        ..connection
+                    })
+                }
+                (Some(_), _) => {}
+                (None, _) => connections.push(connection),
+            }
+        }
+    }
+    Ok(connections)
+}
+
+#[cfg(windows)]
+fn platform_connections(_pid: Option<u32>) -> Result<Vec<Connection>, String> {
+    let output = std::process::Command::new("netstat")
+        .args(["-ano", "-p", "tcp"])
+        .output()
+        .map_err(|e| format!("netstat failed: {e}"))?;
+    Ok(parse_netstat(&String::from_utf8_lossy(&output.stdout)))
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn platform_connections(pid: Option<u32>) -> Result<Vec<Connection>, String> {
+    let mut command = std::process::Command::new("lsof");
+    command.args(["-nP", "-iTCP"]);
+    if let Some(pid) = pid {
+        command.args(["-a", "-p", &pid.to_string()]);
+    }
+    let output = command.output().map_err(|e| format!("lsof failed: {e}"))?;
+    Ok(parse_lsof(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Connections and their socket inode from `/proc/net/tcp{,6}`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_proc_net_tcp(content: &str) -> Vec<(Connection, String)> {
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let state = match *fields.get(3)? {
+                "01" => "ESTABLISHED",
+                "02" => "SYN_SENT",
+                "03" => "SYN_RECV",
+                "04" => "FIN_WAIT1",
+                "05" => "FIN_WAIT2",
+                "06" => "TIME_WAIT",
+                "07" => "CLOSE",
+                "08" => "CLOSE_WAIT",
+                "09" => "LAST_ACK",
+                "0A" => "LISTEN",
+                "0B" => "CLOSING",
+                _ => "UNKNOWN",
+            };
+            let connection = Connection {
+                local: proc_address(fields.get(1)?)?,
+                remote: proc_address(fields.get(2)?)?,
+                state: state.to_string(),
+                pid: None,
+            };
+            Some((connection, fields.get(9)?.to_string()))
+        })
+        .collect()
+}
+
+/// `0100007F:0050` -> `127.0.0.1:80`. Addresses are 32-bit words in host
+/// (little-endian) order; IPv6 uses four of them.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn proc_address(field: &str) -> Option<String> {
+    let (address, port) = field.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+    let words = (0..address.len() / 8)
+        .map(|i| u32::from_str_radix(&address[i * 8..i * 8 + 8], 16).map(u32::swap_bytes))
+        .collect::<Result<Vec<_>, _>>()
+        .ok()?;
+    match words.as_slice() {
+        [word] => Some(format!("{}:{port}", std::net::Ipv4Addr::from(*word))),
+        [a, b, c, d] => {
+            let mut octets = [0u8; 16];
+            for (chunk, word) in octets.chunks_mut(4).zip([a, b, c, d]) {
+                chunk.copy_from_slice(&word.to_be_bytes());
+            }
+            Some(format!("[{}]:{port}", std::net::Ipv6Addr::from(octets)))
+        }
+        _ => None,
+    }
+}
+
+/// Rows of `netstat -ano -p tcp`: proto, local, remote, state, PID.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn parse_netstat(output: &str) -> Vec<Connection> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                [proto, local, remote, state, pid] if proto.eq_ignore_ascii_case("tcp") => {
+                    Some(Connection {
+                        local: local.to_string(),
+                        remote: remote.to_string(),
+                        state: state.to_string(),
+                        pid: pid.parse().ok(),
+                    })
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Rows of `lsof -nP -iTCP`; the last columns read
+/// `local->remote (STATE)` or `local (LISTEN)`.
+#[cfg_attr(any(target_os = "linux", windows), allow(dead_code))]
+fn parse_lsof(output: &str) -> Vec<Connection> {
+    output
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let pid = fields.get(1)?.parse().ok();
+            let state = fields.last()?.trim_matches(|c| c == '(' || c == ')');
+            let name = fields.get(fields.len().checked_sub(2)?)?;
+            let (local, remote) = name.split_once("->").unwrap_or((name, ""));
+            Some(Connection {
+                local: local.to_string(),
+                remote: remote.to_string(),
+                state: state.to_string(),
+                pid,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::guardian::{ThreatSeverity, ThreatType};
+    use std::collections::HashMap;
+    use std::io::Read;
+
+    #[test]
+    fn connection_listings_parse_on_every_platform() {
+        let proc_tcp = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n   0: 0100007F:0277 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 18842 1\n";
+        let parsed = parse_proc_net_tcp(proc_tcp);
+        assert_eq!(parsed[0].0.local, "127.0.0.1:631");
+        assert_eq!(parsed[0].0.state, "LISTEN");
+        assert_eq!(parsed[0].1, "18842");
+        assert_eq!(
+            proc_address("00000000000000000000000001000000:0050").unwrap(),
+            "[::1]:80"
+        );
+
+        let netstat = "\nActive Connections\n\n  Proto  Local Address          Foreign Address        State           PID\n  TCP    10.0.0.5:52144         93.184.216.34:443      ESTABLISHED     4242\n";
+        assert_eq!(
+            parse_netstat(netstat),
+            vec![Connection {
+                local: "10.0.0.5:52144".to_string(),
+                remote: "93.184.216.34:443".to_string(),
+                state: "ESTABLISHED".to_string(),
+                pid: Some(4242),
+            }]
+        );
+
+        let lsof = "COMMAND PID USER FD TYPE DEVICE SIZE/OFF NODE NAME\nminer 4242 me 5u IPv4 0x1 0t0 TCP 10.0.0.5:52144->93.184.216.34:443 (ESTABLISHED)\n";
+        let parsed = parse_lsof(lsof);
+        assert_eq!(parsed[0].remote, "93.184.216.34:443");
+        assert_eq!(parsed[0].pid, Some(4242));
+    }
+
+    #[test]
+    fn encrypted_bundle_round_trips_with_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let sample = dir.path().join("sample.bin");
+        fs::write(&sample, b"payload").unwrap();
+        let threat = ThreatEvent {
+            id: "threat/1".to_string(),
+            timestamp: Utc::now(),
+            threat_type: ThreatType::MaliciousFile,
+            severity: ThreatSeverity::High,
+            description: "Malicious file".to_string(),
+            process_name: None,
+            process_id: None,
+            details: HashMap::from([("path".to_string(), sample.display().to_string())]),
+            techniques: Vec::new(),
+            risk_score: 75.0,
+        };
+        let sources = EvidenceSources {
+            metrics: Vec::new(),
+            logs: vec![json!({ "message": "scan finished" })],
+        };
+        let stages = std::sync::Mutex::new(Vec::new());
+        let bundle = build_bundle(
+            &threat,
+            sources.clone(),
+            dir.path(),
+            Some("hunter2"),
+            &AtomicBool::new(false),
+            |p| stages.lock().unwrap().push(p.stage),
+        )
+        .unwrap();
+
+        assert!(bundle.archive_path.ends_with(".zip.enc"));
+        assert!(!bundle.archive_path.contains("threat/1"));
+        assert_eq!(stages.lock().unwrap().last(), Some(&EvidenceStage::Done));
+        let names: Vec<&str> = bundle
+            .manifest
+            .files
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect();
+        assert!(names.contains(&"hashes.json") && names.contains(&"logs.json"));
+
+        let encrypted = fs::read(&bundle.archive_path).unwrap();
+        assert!(decrypt_bundle(&encrypted, "wrong").is_err());
+        let archive = decrypt_bundle(&encrypted, "hunter2").unwrap();
+        let mut zip = zip::ZipArchive::new(std::io::Cursor::new(archive)).unwrap();
+        let mut manifest = String::new();
+        zip.by_name("manifest.json")
+            .unwrap()
+            .read_to_string(&mut manifest)
+            .unwrap();
+        assert!(manifest.contains("threat/1"));
+
+        let cancelled = tempfile::tempdir().unwrap();
+        let result = build_bundle(
+            &threat,
+            sources,
+            cancelled.path(),
+            None,
+            &AtomicBool::new(true),
+            |_| {},
+        );
+        assert!(result.is_err());
+        assert_eq!(fs::read_dir(cancelled.path()).unwrap().count(), 0);
+    }
+}
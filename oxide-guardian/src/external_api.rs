@@ -4,7 +4,9 @@ use std::time::Duration;
 
 pub fn virustotal_lookup(sha256: &str, api_key: &str) -> Result<ExternalVerdict, String> {
     let url = format!("https://www.virustotal.com/api/v3/files/{sha256}");
-    let client = reqwest::blocking::Client::builder()
+    oxide_core::network::ensure_online(&url).map_err(|e| e.to_string())?;
+    let _permit = oxide_core::api_governor::acquire_blocking(&url).map_err(|e| e.to_string())?;
+    let client = oxide_core::network::blocking_client_builder()
         .timeout(Duration::from_secs(10))
         .build()
         .map_err(|e| format!("Failed to build HTTP client: {e}"))?;
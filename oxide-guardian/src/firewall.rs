@@ -0,0 +1,719 @@
+//! Firewall rules that cut processes off the network.
+//!
+//! On Windows a rule blocks the program in both directions through the
+//! Windows Firewall (NetSecurity cmdlets). Linux firewalls cannot match
+//! packets by executable, so each running process is moved into its own
+//! cgroup and an nftables rule (iptables when `nft` is missing) drops that
+//! cgroup's traffic; only running processes can be isolated there.
+//!
+//! Every rule is tagged `oxide-pilot`: the Windows rule group, the nftables
+//! table and the iptables comment. [`FirewallManager::remove_all`] uses the
+//! tag to clean up even rules missing from the local registry.
+
+use crate::process_control::{self, ProcessIdentity};
+use chrono::{DateTime, Utc};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+use sysinfo::{PidExt, ProcessExt, System, SystemExt};
+
+pub const FIREWALL_RULES_PATH: &str = "./data/firewall_rules.json";
+pub const RULE_TAG: &str = "oxide-pilot";
+const WINDOWS_GROUP: &str = "Oxide Pilot";
+const NFT_TABLE: &str = "oxide_pilot";
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+/// Parent cgroup of isolated processes, one child per rule
+const CGROUP_PARENT: &str = "oxide-pilot-isolated";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FirewallBackendKind {
+    WindowsFirewall,
+    Nftables,
+    Iptables,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FirewallRule {
+    pub id: String,
+    /// Executable the rule blocks
+    pub program: Option<String>,
+    /// Processes cut off when the rule was created
+    #[serde(default)]
+    pub pids: Vec<u32>,
+    pub backend: FirewallBackendKind,
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// Cgroup each Linux process came from, restored on removal
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub original_cgroups: Vec<(u32, String)>,
+}
+
+/// Installs and removes tagged rules. Abstracted so tests don't touch the
+/// system firewall.
+pub trait FirewallBackend: Send + Sync {
+    fn kind(&self) -> FirewallBackendKind;
+    /// Install `rule`, recording anything needed to undo it on the rule.
+    fn apply(&self, rule: &mut FirewallRule) -> Result<(), String>;
+    fn remove(&self, rule: &FirewallRule) -> Result<(), String>;
+    /// Remove every tagged rule, including ones the registry lost.
+    fn remove_all(&self) -> Result<(), String>;
+}
+
+/// The firewall of this machine, `None` when no supported one is found.
+pub fn system_backend() -> Option<Box<dyn FirewallBackend>> {
+    if cfg!(windows) {
+        return Some(Box::new(WindowsFirewall));
+    }
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    if run("nft", &["--version"]).is_ok() {
+        Some(Box::new(LinuxFirewall(FirewallBackendKind::Nftables)))
+    } else if run("iptables", &["--version"]).is_ok() {
+        Some(Box::new(LinuxFirewall(FirewallBackendKind::Iptables)))
+    } else {
+        None
+    }
+}
+
+fn run(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run {program}: {e}"))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        Err(format!(
+            "{program} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+fn run_all(program: &str, commands: &[Vec<String>]) -> Result<(), String> {
+    for args in commands {
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        run(program, &args)?;
+    }
+    Ok(())
+}
+
+fn powershell(script: &str) -> Result<String, String> {
+    run(
+        "powershell",
+        &["-NoProfile", "-NonInteractive", "-Command", script],
+    )
+}
+
+fn ps_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Windows Firewall rule names of `id`, outbound then inbound.
+fn windows_rule_names(id: &str) -> [String; 2] {
+    [
+        format!("{RULE_TAG}-{id}-out"),
+        format!("{RULE_TAG}-{id}-in"),
+    ]
+}
+
+fn windows_add_script(id: &str, program: &str) -> String {
+    let [outbound, inbound] = windows_rule_names(id);
+    [(outbound, "Outbound"), (inbound, "Inbound")]
+        .iter()
+        .map(|(name, direction)| {
+            format!(
+                "New-NetFirewallRule -Name {} -DisplayName {} -Group {} -Direction {direction} \
+                 -Action Block -Program {} -ErrorAction Stop | Out-Null",
+                ps_quote(name),
+                ps_quote(&format!("Oxide Pilot: block {program}")),
+                ps_quote(WINDOWS_GROUP),
+                ps_quote(program),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+struct WindowsFirewall;
+
+impl FirewallBackend for WindowsFirewall {
+    fn kind(&self) -> FirewallBackendKind {
+        FirewallBackendKind::WindowsFirewall
+    }
+
+    fn apply(&self, rule: &mut FirewallRule) -> Result<(), String> {
+        let program = rule
+            .program
+            .as_deref()
+            .ok_or("The Windows Firewall blocks programs; no executable path is known")?;
+        powershell(&windows_add_script(&rule.id, program)).map(|_| ())
+    }
+
+    fn remove(&self, rule: &FirewallRule) -> Result<(), String> {
+        let names: Vec<String> = windows_rule_names(&rule.id)
+            .iter()
+            .map(|name| ps_quote(name))
+            .collect();
+        powershell(&format!(
+            "Remove-NetFirewallRule -Name {} -ErrorAction SilentlyContinue",
+            names.join(",")
+        ))
+        .map(|_| ())
+    }
+
+    fn remove_all(&self) -> Result<(), String> {
+        powershell(&format!(
+            "Remove-NetFirewallRule -Group {} -ErrorAction SilentlyContinue",
+            ps_quote(WINDOWS_GROUP)
+        ))
+        .map(|_| ())
+    }
+}
+
+fn cgroup_name(id: &str) -> String {
+    format!("{CGROUP_PARENT}/{id}")
+}
+
+fn rule_comment(id: &str) -> String {
+    format!("{RULE_TAG}:{id}")
+}
+
+fn nft_setup_commands() -> Vec<Vec<String>> {
+    let mut commands = vec![vec![
+        "add".to_string(),
+        "table".to_string(),
+        "inet".to_string(),
+        NFT_TABLE.to_string(),
+    ]];
+    for hook in ["output", "input"] {
+        commands.push(
+            [
+                "add",
+                "chain",
+                "inet",
+                NFT_TABLE,
+                hook,
+                &format!("{{ type filter hook {hook} priority 0 ; policy accept ; }}"),
+            ]
+            .map(str::to_string)
+            .to_vec(),
+        );
+    }
+    commands
+}
+
+fn nft_rule_commands(id: &str) -> Vec<Vec<String>> {
+    ["output", "input"]
+        .iter()
+        .map(|chain| {
+            [
+                "add",
+                "rule",
+                "inet",
+                NFT_TABLE,
+                chain,
+                "socket",
+                "cgroupv2",
+                "level",
+                "2",
+                &format!("\"{}\"", cgroup_name(id)),
+                "counter",
+                "drop",
+                "comment",
+                &format!("\"{}\"", rule_comment(id)),
+            ]
+            .map(str::to_string)
+            .to_vec()
+        })
+        .collect()
+}
+
+/// `(chain, handle)` of the rules tagged with `id` in `nft -a list table`
+/// output.
+fn nft_rule_handles(listing: &str, id: &str) -> Vec<(String, String)> {
+    let comment = format!("comment \"{}\"", rule_comment(id));
+    let mut chain = String::new();
+    let mut handles = Vec::new();
+    for line in listing.lines().map(str::trim) {
+        if let Some(rest) = line.strip_prefix("chain ") {
+            chain = rest
+                .split_whitespace()
+                .next()
+                .unwrap_or_default()
+                .to_string();
+        } else if line.contains(&comment) {
+            if let Some((_, handle)) = line.rsplit_once("# handle ") {
+                handles.push((chain.clone(), handle.trim().to_string()));
+            }
+        }
+    }
+    handles
+}
+
+fn iptables_rule_args(action: &str, id: &str) -> Vec<String> {
+    [
+        action,
+        "OUTPUT",
+        "-m",
+        "cgroup",
+        "--path",
+        &cgroup_name(id),
+        "-m",
+        "comment",
+        "--comment",
+        &rule_comment(id),
+        "-j",
+        "DROP",
+    ]
+    .map(str::to_string)
+    .to_vec()
+}
+
+/// Delete commands for the tagged rules in `iptables -S OUTPUT` output.
+fn iptables_tagged_deletes(listing: &str) -> Vec<Vec<String>> {
+    let tag = format!("{RULE_TAG}:");
+    listing
+        .lines()
+        .filter(|line| line.starts_with("-A ") && line.contains(&tag))
+        .map(|line| {
+            let mut args: Vec<String> = line
+                .split_whitespace()
+                .map(|arg| arg.trim_matches('"').to_string())
+                .collect();
+            args[0] = "-D".to_string();
+            args
+        })
+        .collect()
+}
+
+/// nftables or iptables matching a per-rule cgroup (v2).
+struct LinuxFirewall(FirewallBackendKind);
+
+impl LinuxFirewall {
+    fn cgroup_dir(id: &str) -> PathBuf {
+        PathBuf::from(CGROUP_ROOT).join(cgroup_name(id))
+    }
+
+    /// Move the rule's processes into its cgroup, remembering where they were.
+    /// On failure the processes already moved are returned.
+    fn isolate(rule: &mut FirewallRule) -> Result<(), String> {
+        let isolated = Self::move_into_cgroup(rule);
+        if isolated.is_err() {
+            Self::release(&rule.id, &rule.original_cgroups);
+        }
+        isolated
+    }
+
+    fn move_into_cgroup(rule: &mut FirewallRule) -> Result<(), String> {
+        if rule.pids.is_empty() {
+            return Err(
+                "Linux firewalls match processes, not files: the program must be running"
+                    .to_string(),
+            );
+        }
+        let dir = Self::cgroup_dir(&rule.id);
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create cgroup {dir:?}: {e}"))?;
+        for pid in &rule.pids {
+            let original = fs::read_to_string(format!("/proc/{pid}/cgroup"))
+                .ok()
+                .and_then(|content| {
+                    content
+                        .lines()
+                        .find_map(|line| line.strip_prefix("0::").map(str::to_string))
+                })
+                .unwrap_or_else(|| "/".to_string());
+            fs::write(dir.join("cgroup.procs"), pid.to_string())
+                .map_err(|e| format!("Failed to move process {pid} into {dir:?}: {e}"))?;
+            rule.original_cgroups.push((*pid, original));
+        }
+        Ok(())
+    }
+
+    /// Return the cgroup's processes to where they came from and delete it.
+    fn release(id: &str, original_cgroups: &[(u32, String)]) {
+        let dir = Self::cgroup_dir(id);
+        let procs = fs::read_to_string(dir.join("cgroup.procs")).unwrap_or_default();
+        for pid in procs
+            .lines()
+            .filter_map(|line| line.trim().parse::<u32>().ok())
+        {
+            let original = original_cgroups
+                .iter()
+                .find(|(p, _)| *p == pid)
+                .map(|(_, cgroup)| cgroup.trim_start_matches('/'))
+                .unwrap_or_default();
+            let mut target = PathBuf::from(CGROUP_ROOT).join(original);
+            if !target.join("cgroup.procs").exists() {
+                target = PathBuf::from(CGROUP_ROOT);
+            }
+            if let Err(e) = fs::write(target.join("cgroup.procs"), pid.to_string()) {
+                warn!("Failed to return process {pid} to {target:?}: {e}");
+            }
+        }
+        if dir.exists() {
+            if let Err(e) = fs::remove_dir(&dir) {
+                warn!("Failed to remove cgroup {dir:?}: {e}");
+            }
+        }
+    }
+}
+
+impl FirewallBackend for LinuxFirewall {
+    fn kind(&self) -> FirewallBackendKind {
+        self.0
+    }
+
+    fn apply(&self, rule: &mut FirewallRule) -> Result<(), String> {
+        Self::isolate(rule)?;
+        let installed = match self.0 {
+            FirewallBackendKind::Nftables => run_all("nft", &nft_setup_commands())
+                .and_then(|_| run_all("nft", &nft_rule_commands(&rule.id))),
+            _ => run_all("iptables", &[iptables_rule_args("-I", &rule.id)]),
+        };
+        if installed.is_err() {
+            Self::release(&rule.id, &rule.original_cgroups);
+        }
+        installed
+    }
+
+    fn remove(&self, rule: &FirewallRule) -> Result<(), String> {
+        let removed = match self.0 {
+            FirewallBackendKind::Nftables => {
+                let listing = run("nft", &["-a", "list", "table", "inet", NFT_TABLE])?;
+                let deletes: Vec<Vec<String>> = nft_rule_handles(&listing, &rule.id)
+                    .into_iter()
+                    .map(|(chain, handle)| {
+                        [
+                            "delete", "rule", "inet", NFT_TABLE, &chain, "handle", &handle,
+                        ]
+                        .map(str::to_string)
+                        .to_vec()
+                    })
+                    .collect();
+                run_all("nft", &deletes)
+            }
+            _ => run_all("iptables", &[iptables_rule_args("-D", &rule.id)]),
+        };
+        Self::release(&rule.id, &rule.original_cgroups);
+        removed
+    }
+
+    fn remove_all(&self) -> Result<(), String> {
+        let removed = match self.0 {
+            FirewallBackendKind::Nftables => {
+                // A missing table means there is nothing to remove
+                if run("nft", &["list", "table", "inet", NFT_TABLE]).is_ok() {
+                    run("nft", &["delete", "table", "inet", NFT_TABLE]).map(|_| ())
+                } else {
+                    Ok(())
+                }
+            }
+            _ => run("iptables", &["-S", "OUTPUT"])
+                .and_then(|listing| run_all("iptables", &iptables_tagged_deletes(&listing))),
+        };
+        let parent = PathBuf::from(CGROUP_ROOT).join(CGROUP_PARENT);
+        if let Ok(entries) = fs::read_dir(&parent) {
+            for entry in entries.flatten().filter(|e| e.path().is_dir()) {
+                Self::release(&entry.file_name().to_string_lossy(), &[]);
+            }
+        }
+        let _ = fs::remove_dir(parent);
+        removed
+    }
+}
+
+/// Running processes started from `program`.
+fn pids_for_program(program: &str) -> Vec<u32> {
+    let mut sys = System::new();
+    sys.refresh_processes();
+    sys.processes()
+        .iter()
+        .filter(|(_, process)| {
+            let exe = process.exe().to_string_lossy();
+            !exe.is_empty() && paths_match(&exe, program)
+        })
+        .map(|(pid, _)| pid.as_u32())
+        .filter(|pid| process_control::resolve_target(*pid, None).is_ok())
+        .collect()
+}
+
+fn paths_match(a: &str, b: &str) -> bool {
+    if cfg!(windows) {
+        a.eq_ignore_ascii_case(b)
+    } else {
+        a == b
+    }
+}
+
+/// Oxide Pilot's firewall rules, persisted as JSON so they can be listed
+/// and removed after a restart.
+pub struct FirewallManager {
+    path: PathBuf,
+    rules: Mutex<Vec<FirewallRule>>,
+    backend: OnceLock<Option<Box<dyn FirewallBackend>>>,
+}
+
+impl FirewallManager {
+    /// Registry at `path`; the system firewall is detected on first use.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let rules = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| {
+                serde_json::from_str(&content)
+                    .map_err(|e| warn!("Ignoring unreadable firewall registry {path:?}: {e}"))
+                    .ok()
+            })
+            .unwrap_or_default();
+        Self {
+            path,
+            rules: Mutex::new(rules),
+            backend: OnceLock::new(),
+        }
+    }
+
+    pub fn with_backend(path: impl Into<PathBuf>, backend: Box<dyn FirewallBackend>) -> Self {
+        let manager = Self::new(path);
+        let _ = manager.backend.set(Some(backend));
+        manager
+    }
+
+    fn backend(&self) -> Result<&dyn FirewallBackend, String> {
+        self.backend
+            .get_or_init(system_backend)
+            .as_deref()
+            .ok_or_else(|| "No supported firewall found on this system".to_string())
+    }
+
+    /// Cut `pid`, or every running process of `path`, off the network. A
+    /// program that is already blocked returns its existing rule. `expected`
+    /// is what the caller saw of `pid`, so a reused pid is refused.
+    pub fn block_process(
+        &self,
+        pid: Option<u32>,
+        path: Option<String>,
+        reason: Option<String>,
+        expected: Option<&ProcessIdentity>,
+    ) -> Result<FirewallRule, String> {
+        let (program, pids) = match (pid, path) {
+            (Some(pid), path) => {
+                let target = process_control::resolve_target(pid, expected)
+                    .map_err(|e| format!("Cannot isolate process {pid}: {e}"))?;
+                match (path, target.exe) {
+                    (Some(path), Some(exe)) if !paths_match(&path, &exe) => {
+                        return Err(format!(
+                            "Process {pid} runs {exe}, not {path}; refusing to isolate it"
+                        ))
+                    }
+                    (Some(path), None) => {
+                        return Err(format!(
+                            "Cannot confirm that process {pid} runs {path}; refusing to isolate it"
+                        ))
+                    }
+                    (_, exe) => (exe, vec![pid]),
+                }
+            }
+            (None, Some(path)) => {
+                let pids = pids_for_program(&path);
+                (Some(path), pids)
+            }
+            (None, None) => return Err("A process id or program path is required".to_string()),
+        };
+        let backend = self.backend()?;
+
+        let mut rules = self.rules.lock().unwrap();
+        let same_program = |rule: &FirewallRule| match (&rule.program, &program) {
+            (Some(a), Some(b)) => paths_match(a, b),
+            _ => false,
+        };
+        if let Some(existing) = rules
+            .iter()
+            .find(|rule| same_program(rule) && pids.iter().all(|pid| rule.pids.contains(pid)))
+        {
+            return Ok(existing.clone());
+        }
+
+        let mut rule = FirewallRule {
+            id: uuid::Uuid::new_v4().simple().to_string(),
+            program,
+            pids,
+            backend: backend.kind(),
+            reason,
+            created_at: Utc::now(),
+            original_cgroups: Vec::new(),
+        };
+        backend.apply(&mut rule)?;
+        info!(
+            "Firewall rule {} blocks {} (pids {:?})",
+            rule.id,
+            rule.program.as_deref().unwrap_or("unknown program"),
+            rule.pids
+        );
+        rules.push(rule.clone());
+        self.save(&rules)?;
+        Ok(rule)
+    }
+
+    pub fn rules(&self) -> Vec<FirewallRule> {
+        self.rules.lock().unwrap().clone()
+    }
+
+    pub fn remove_rule(&self, id: &str) -> Result<FirewallRule, String> {
+        let mut rules = self.rules.lock().unwrap();
+        let index = rules
+            .iter()
+            .position(|rule| rule.id == id)
+            .ok_or_else(|| format!("Firewall rule {id} not found"))?;
+        self.backend()?.remove(&rules[index])?;
+        let rule = rules.remove(index);
+        self.save(&rules)?;
+        info!("Firewall rule {id} removed");
+        Ok(rule)
+    }
+
+    /// Remove every tagged rule; returns how many the registry knew of.
+    pub fn remove_all(&self) -> Result<usize, String> {
+        let mut rules = self.rules.lock().unwrap();
+        self.backend()?.remove_all()?;
+        let removed = rules.len();
+        rules.clear();
+        self.save(&rules)?;
+        Ok(removed)
+    }
+
+    fn save(&self, rules: &[FirewallRule]) -> Result<(), String> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create {parent:?}: {e}"))?;
+        }
+        let json = serde_json::to_string_pretty(rules).map_err(|e| e.to_string())?;
+        fs::write(&self.path, json).map_err(|e| format!("Failed to save firewall rules: {e}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[derive(Default)]
+    struct FakeFirewall {
+        applied: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl FirewallBackend for FakeFirewall {
+        fn kind(&self) -> FirewallBackendKind {
+            FirewallBackendKind::Nftables
+        }
+        fn apply(&self, rule: &mut FirewallRule) -> Result<(), String> {
+            self.applied.lock().unwrap().push(rule.id.clone());
+            Ok(())
+        }
+        fn remove(&self, rule: &FirewallRule) -> Result<(), String> {
+            self.applied.lock().unwrap().retain(|id| *id != rule.id);
+            Ok(())
+        }
+        fn remove_all(&self) -> Result<(), String> {
+            self.applied.lock().unwrap().clear();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn rules_are_tagged_for_each_backend() {
+        let script = windows_add_script("abc", "C:\\Tools\\o'neil.exe");
+        assert!(script.contains("-Name 'oxide-pilot-abc-out'"));
+        assert!(script.contains("-Direction Inbound"));
+        assert!(script.contains("-Group 'Oxide Pilot'"));
+        assert!(script.contains("-Program 'C:\\Tools\\o''neil.exe'"));
+
+        let rule = nft_rule_commands("abc").remove(0).join(" ");
+        assert_eq!(
+            rule,
+            "add rule inet oxide_pilot output socket cgroupv2 level 2 \
+             \"oxide-pilot-isolated/abc\" counter drop comment \"oxide-pilot:abc\""
+        );
+        let listing = "table inet oxide_pilot { # handle 7\n\
+             \tchain output { # handle 1\n\
+             \t\tsocket cgroupv2 level 2 \"oxide-pilot-isolated/abc\" counter packets 0 bytes 0 drop comment \"oxide-pilot:abc\" # handle 4\n\
+             \t\tsocket cgroupv2 level 2 \"oxide-pilot-isolated/xyz\" counter packets 0 bytes 0 drop comment \"oxide-pilot:xyz\" # handle 5\n\
+             \t}\n\
+             \tchain input { # handle 2\n\
+             \t\tsocket cgroupv2 level 2 \"oxide-pilot-isolated/abc\" counter packets 0 bytes 0 drop comment \"oxide-pilot:abc\" # handle 6\n\
+             \t}\n}";
+        assert_eq!(
+            nft_rule_handles(listing, "abc"),
+            [
+                ("output".to_string(), "4".to_string()),
+                ("input".to_string(), "6".to_string())
+            ]
+        );
+
+        let deletes = iptables_tagged_deletes(
+            "-P OUTPUT ACCEPT\n\
+             -A OUTPUT -m cgroup --path oxide-pilot-isolated/abc -m comment --comment \"oxide-pilot:abc\" -j DROP\n\
+             -A OUTPUT -p tcp --dport 25 -j DROP\n",
+        );
+        assert_eq!(deletes, vec![iptables_rule_args("-D", "abc")]);
+    }
+
+    #[test]
+    fn manager_persists_and_removes_rules() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rules.json");
+        let fake = FakeFirewall::default();
+        let applied = Arc::clone(&fake.applied);
+        let manager = FirewallManager::with_backend(&path, Box::new(fake));
+
+        let pid = std::process::id();
+        assert!(manager.block_process(Some(pid), None, None, None).is_err());
+        assert!(manager.block_process(None, None, None, None).is_err());
+        let rule = manager
+            .block_process(
+                None,
+                Some("/opt/not-running/agent".into()),
+                Some("c2".into()),
+                None,
+            )
+            .unwrap();
+        assert!(rule.pids.is_empty());
+        let again = manager
+            .block_process(None, Some("/opt/not-running/agent".into()), None, None)
+            .unwrap();
+        assert_eq!(again.id, rule.id);
+        assert_eq!(applied.lock().unwrap().len(), 1);
+
+        let reloaded = FirewallManager::with_backend(&path, Box::new(FakeFirewall::default()));
+        assert_eq!(reloaded.rules().len(), 1);
+        manager.remove_rule(&rule.id).unwrap();
+        assert!(applied.lock().unwrap().is_empty());
+        assert!(manager.remove_rule(&rule.id).is_err());
+        assert!(FirewallManager::new(&path).rules().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn pid_must_run_the_given_program() {
+        let dir = tempfile::tempdir().unwrap();
+        let fake = FakeFirewall::default();
+        let applied = Arc::clone(&fake.applied);
+        let manager = FirewallManager::with_backend(dir.path().join("rules.json"), Box::new(fake));
+
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+        let blocked = manager.block_process(
+            Some(child.id()),
+            Some("/opt/other/agent".into()),
+            None,
+            None,
+        );
+        let _ = child.kill();
+        let _ = child.wait();
+        assert!(blocked.unwrap_err().contains("refusing"));
+        assert!(applied.lock().unwrap().is_empty());
+    }
+}
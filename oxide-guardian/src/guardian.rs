@@ -1,15 +1,39 @@
+use crate::alerts::{self, Alert, AlertRouter, EmailTestResult};
+use crate::attack;
+use crate::av_coexistence::{self, AvCoexistence};
+use crate::baseline::{BaselineMonitor, BaselineSnapshot, DriftReport, BASELINE_PATH};
 use crate::external_api;
+use crate::firewall::{FirewallManager, FIREWALL_RULES_PATH};
+use crate::integrity::{
+    self, IntegrityManifest, IntegrityMonitor, IntegrityReport, ProtectedKind, HEARTBEAT_PATH,
+    INTEGRITY_PATH,
+};
+use crate::ioc::{self, Ioc, IocImportReport, IocStore, IOC_PATH};
+#[cfg(feature = "yara-detection")]
+use crate::memory_scan::ScanLimiter;
+use crate::memory_scan::{self, MemoryScanReport};
 use crate::monitor::SystemMonitor;
+use crate::rules::{ProcessSample, RuleEngine, RuleMatch, RulesStatus};
 use crate::scanner::{ExternalVerdict, FileScanReport, FileScanner};
 use crate::signatures::SignatureDb;
+use crate::startup::{self, StartupItem};
 use chrono::{DateTime, Utc};
 use log::{error, info, warn};
+use oxide_core::capabilities::{self, Feature};
 use oxide_core::config::GuardianConfig;
+use oxide_core::power;
+use oxide_core::prometheus;
+use oxide_core::severity::{Scored, Severity};
 use oxide_core::types::SystemEvent;
 use std::collections::HashMap;
+use std::path::PathBuf;
+#[cfg(feature = "yara-detection")]
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
 #[cfg(target_os = "windows")]
 use winapi::um::processthreadsapi::{GetCurrentThread, SetThreadPriority};
 #[cfg(target_os = "windows")]
@@ -71,7 +95,7 @@ impl VtCache {
     }
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ThreatEvent {
     pub id: String,
     pub timestamp: DateTime<Utc>,
@@ -81,9 +105,19 @@ pub struct ThreatEvent {
     pub process_name: Option<String>,
     pub process_id: Option<u32>,
     pub details: HashMap<String, String>,
+    /// MITRE ATT&CK technique IDs, filled in when the threat is recorded
+    pub techniques: Vec<String>,
+    /// 0-100 on the rubric in `oxide_core::severity`; `severity` is its band
+    pub risk_score: f64,
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+impl Scored for ThreatEvent {
+    fn risk_score(&self) -> f64 {
+        self.risk_score
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub enum ThreatType {
     MalwareSignature,
     SuspiciousProcess,
@@ -91,21 +125,117 @@ pub enum ThreatType {
     UnauthorizedNetworkAccess,
     FileSystemAnomaly,
     MaliciousFile,
+    /// Services or browser settings changed since the recorded baseline
+    ConfigurationDrift,
+    /// Oxide Pilot's own files, data or process were tampered with
+    SelfTampering,
 }
 
+/// Guardian's share of the protection status card.
 #[derive(Debug, Clone, serde::Serialize)]
+pub struct GuardianProtection {
+    pub monitoring_enabled: bool,
+    pub monitor_interval_secs: u64,
+    /// End of the last completed monitoring pass.
+    pub last_monitor_cycle: Option<DateTime<Utc>>,
+    pub yara_available: bool,
+    pub signatures_path: Option<String>,
+    /// Hashes loaded from `signatures_path`; `None` if it failed to load.
+    pub signature_count: Option<usize>,
+    /// Modification time of the signature file.
+    pub signatures_updated_at: Option<DateTime<Utc>>,
+    /// Other AV products; `None` until the first check finishes.
+    pub antivirus: Option<AvCoexistence>,
+}
+
+impl GuardianProtection {
+    /// Age of the last pass after which monitoring counts as stalled. Allows
+    /// a slow pass (large process lists) first.
+    pub fn stall_after_secs(&self) -> u64 {
+        self.monitor_interval_secs * 3 + 30
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
 pub enum ThreatSeverity {
+    #[serde(alias = "low")]
     Low,
+    #[serde(alias = "medium")]
     Medium,
+    #[serde(alias = "high")]
     High,
+    #[serde(alias = "critical")]
     Critical,
 }
 
+impl ThreatSeverity {
+    /// The band a 0-100 risk score falls in.
+    pub fn from_score(score: f64) -> Self {
+        Severity::from_score(score).into()
+    }
+
+    /// Representative risk score for this severity.
+    pub fn score(&self) -> f64 {
+        Severity::from(self.clone()).score()
+    }
+}
+
+impl From<Severity> for ThreatSeverity {
+    fn from(severity: Severity) -> Self {
+        match severity {
+            Severity::Low => ThreatSeverity::Low,
+            Severity::Medium => ThreatSeverity::Medium,
+            Severity::High => ThreatSeverity::High,
+            Severity::Critical => ThreatSeverity::Critical,
+        }
+    }
+}
+
+impl From<ThreatSeverity> for Severity {
+    fn from(severity: ThreatSeverity) -> Self {
+        match severity {
+            ThreatSeverity::Low => Severity::Low,
+            ThreatSeverity::Medium => Severity::Medium,
+            ThreatSeverity::High => Severity::High,
+            ThreatSeverity::Critical => Severity::Critical,
+        }
+    }
+}
+
+/// CPU usage above this is reported as resource abuse
+const HIGH_CPU_PERCENT: f32 = 80.0;
+/// Signature match on a process command line
+#[cfg(feature = "yara-detection")]
+const YARA_COMMAND_SCORE: f64 = 70.0;
+/// Signature match in a running process's memory
+#[cfg(feature = "yara-detection")]
+const YARA_MEMORY_SCORE: f64 = 90.0;
+
+/// Medium-band score that rises as CPU usage approaches saturation.
+fn high_cpu_score(cpu_usage: f32) -> f64 {
+    let over = ((cpu_usage - HIGH_CPU_PERCENT) / (100.0 - HIGH_CPU_PERCENT)).clamp(0.0, 1.0);
+    let (low, high) = (
+        Severity::Medium.min_score(),
+        Severity::High.min_score() - 1.0,
+    );
+    low + f64::from(over) * (high - low)
+}
+
 pub struct ThreatDetector {
     #[cfg(feature = "yara-detection")]
     yara_rules: Arc<Mutex<Option<Rules>>>,
     process_baseline: Arc<Mutex<HashMap<String, ProcessBaseline>>>,
     threat_history: Arc<Mutex<Vec<ThreatEvent>>>,
+    rules: RuleEngine,
+    // Live feed of recorded threats for external consumers
+    threat_tx: broadcast::Sender<ThreatEvent>,
+    // Syslog / Event Log destinations from GuardianConfig::alert_sinks
+    alerts: Mutex<AlertRouter>,
+    #[cfg(feature = "yara-detection")]
+    memory_scan_limiter: Arc<Mutex<ScanLimiter>>,
+    // Set while another AV product's real-time protection covers processes
+    #[cfg(feature = "yara-detection")]
+    memory_scans_paused: AtomicBool,
 }
 
 #[derive(Debug, Clone)]
@@ -125,18 +255,33 @@ impl Default for ThreatDetector {
 
 impl ThreatDetector {
     pub fn new() -> Self {
+        Self::with_rules(RuleEngine::new(None))
+    }
+
+    pub fn with_rules(rules: RuleEngine) -> Self {
         let detector = Self {
             #[cfg(feature = "yara-detection")]
             yara_rules: Arc::new(Mutex::new(None)),
             process_baseline: Arc::new(Mutex::new(HashMap::new())),
             threat_history: Arc::new(Mutex::new(Vec::new())),
+            rules,
+            threat_tx: broadcast::channel(256).0,
+            alerts: Mutex::new(AlertRouter::default()),
+            #[cfg(feature = "yara-detection")]
+            memory_scan_limiter: Arc::new(Mutex::new(ScanLimiter::default())),
+            #[cfg(feature = "yara-detection")]
+            memory_scans_paused: AtomicBool::new(false),
         };
         #[cfg(feature = "yara-detection")]
         detector.load_yara_rules();
         detector
     }
 
-    pub fn record_threat(&self, event: ThreatEvent) {
+    pub fn record_threat(&self, mut event: ThreatEvent) {
+        if event.techniques.is_empty() {
+            event.techniques = attack::map_threat(&event);
+        }
+        self.publish(&event);
         let mut history = self.threat_history.lock().unwrap();
         history.push(event);
         if history.len() > 1000 {
@@ -146,7 +291,7 @@ impl ThreatDetector {
     }
 
     #[cfg(feature = "yara-detection")]
-    fn load_yara_rules(&mut self) {
+    fn load_yara_rules(&self) {
         let rules_str = r#"
 rule suspicious_powershell {
   strings:
@@ -191,9 +336,13 @@ rule suspicious_network_tool {
 
     pub fn analyze_processes(&self, processes: &[SystemEvent]) -> Vec<ThreatEvent> {
         let mut threats = Vec::new();
+        self.rules.reload_if_changed();
         #[cfg(feature = "yara-detection")]
         let yara_rules = self.yara_rules.lock().unwrap();
         let mut baseline = self.process_baseline.lock().unwrap();
+        // Heuristic hits whose memory gets a YARA pass after analysis
+        #[cfg(feature = "yara-detection")]
+        let mut memory_scan_queue: Vec<(u32, String)> = Vec::new();
 
         for event in processes {
             if event.event_type == "process_info" {
@@ -233,12 +382,13 @@ rule suspicious_network_tool {
                 entry.average_memory = (entry.average_memory + memory_usage) / 2;
 
                 // Check for suspicious resource usage
-                if cpu_usage > 80.0 {
+                if cpu_usage > HIGH_CPU_PERCENT {
+                    let risk_score = high_cpu_score(cpu_usage);
                     threats.push(ThreatEvent {
                         id: uuid::Uuid::new_v4().to_string(),
                         timestamp: Utc::now(),
                         threat_type: ThreatType::HighResourceUsage,
-                        severity: ThreatSeverity::Medium,
+                        severity: ThreatSeverity::from_score(risk_score),
                         description: format!("High CPU usage detected: {cpu_usage:.2}%"),
                         process_name: Some(process_name.clone()),
                         process_id,
@@ -246,13 +396,15 @@ rule suspicious_network_tool {
                             ("cpu_usage".to_string(), cpu_usage.to_string()),
                             ("memory_usage".to_string(), memory_usage.to_string()),
                         ]),
+                        techniques: Vec::new(),
+                        risk_score,
                     });
                 }
 
                 // YARA rule scanning
                 #[cfg(feature = "yara-detection")]
                 if let Some(rules) = yara_rules.as_ref() {
-                    if let Some(command) = event.details.get("command") {
+                    if let Some(command) = event.details.get("command").and_then(|v| v.as_str()) {
                         match rules.scan_mem(command.as_bytes(), 0) {
                             Ok(matches) => {
                                 if !matches.is_empty() {
@@ -261,17 +413,21 @@ rule suspicious_network_tool {
                                             id: uuid::Uuid::new_v4().to_string(),
                                             timestamp: Utc::now(),
                                             threat_type: ThreatType::MalwareSignature,
-                                            severity: ThreatSeverity::High,
+                                            severity: ThreatSeverity::from_score(
+                                                YARA_COMMAND_SCORE,
+                                            ),
                                             description: format!(
                                                 "YARA rule match: {}",
-                                                m.rule_name
+                                                m.identifier
                                             ),
                                             process_name: Some(process_name.clone()),
                                             process_id,
                                             details: HashMap::from([
-                                                ("rule_name".to_string(), m.rule_name.to_string()),
-                                                ("command".to_string(), command.clone()),
+                                                ("rule_name".to_string(), m.identifier.to_string()),
+                                                ("command".to_string(), command.to_string()),
                                             ]),
+                                            techniques: Vec::new(),
+                                            risk_score: YARA_COMMAND_SCORE,
                                         });
                                     }
                                 }
@@ -281,31 +437,50 @@ rule suspicious_network_tool {
                     }
                 }
 
-                // Heuristic analysis for suspicious processes
-                if self.is_suspicious_process(&process_name, &event.details) {
+                // Heuristic rules
+                let rule_matches = self
+                    .rules
+                    .evaluate(&ProcessSample::from_details(&event.details));
+                if let Some(top) = rule_matches.first() {
                     let mut details_map = HashMap::new();
                     if let Some(obj) = event.details.as_object() {
                         for (k, v) in obj {
                             details_map.insert(k.clone(), v.to_string());
                         }
                     }
+                    let rule_ids: Vec<&str> =
+                        rule_matches.iter().map(|m| m.rule_id.as_str()).collect();
+                    details_map.insert("rule_ids".to_string(), rule_ids.join(","));
+                    details_map.insert("matched".to_string(), top.matched.join(", "));
 
                     threats.push(ThreatEvent {
                         id: uuid::Uuid::new_v4().to_string(),
                         timestamp: Utc::now(),
                         threat_type: ThreatType::SuspiciousProcess,
-                        severity: ThreatSeverity::Medium,
+                        severity: top.severity.clone(),
                         description: format!(
-                            "Suspicious process behavior detected: {process_name}"
+                            "Suspicious process behavior detected: {process_name} ({})",
+                            top.rule_id
                         ),
                         process_name: Some(process_name.clone()),
                         process_id,
                         details: details_map,
+                        techniques: Vec::new(),
+                        risk_score: top.severity.score(),
                     });
+
+                    #[cfg(feature = "yara-detection")]
+                    if let Some(pid) = process_id {
+                        memory_scan_queue.push((pid, process_name.clone()));
+                    }
                 }
             }
         }
 
+        for threat in &threats {
+            self.publish(threat);
+        }
+
         // Store threats in history
         let mut history = self.threat_history.lock().unwrap();
         history.extend(threats.clone());
@@ -315,64 +490,161 @@ rule suspicious_network_tool {
             let len = history.len();
             history.drain(0..len - 1000);
         }
+        drop(history);
+
+        // Memory scans re-lock the rules, so release them first
+        #[cfg(feature = "yara-detection")]
+        {
+            drop(yara_rules);
+            drop(baseline);
+            if self.memory_scans_paused.load(Ordering::Relaxed) {
+                memory_scan_queue.clear();
+            }
+            for (pid, name) in memory_scan_queue {
+                match self.scan_memory(pid, Some(name), false) {
+                    Ok((_, events)) => threats.extend(events),
+                    Err(e) => info!("Skipping memory scan of {pid}: {e}"),
+                }
+            }
+        }
 
         threats
     }
 
-    fn is_suspicious_process(&self, process_name: &str, details: &serde_json::Value) -> bool {
-        // Check for suspicious process names
-        let suspicious_names = [
-            "cmd.exe",
-            "powershell.exe",
-            "wscript.exe",
-            "cscript.exe",
-            "regsvr32.exe",
-            "rundll32.exe",
-            "mshta.exe",
-        ];
+    /// Skip the YARA memory pass of monitoring, e.g. while another AV
+    /// product scans processes. On-demand scans still run.
+    pub fn set_memory_scans_paused(&self, paused: bool) {
+        #[cfg(feature = "yara-detection")]
+        self.memory_scans_paused.store(paused, Ordering::Relaxed);
+        #[cfg(not(feature = "yara-detection"))]
+        let _ = paused;
+    }
+
+    /// YARA scan of a process's memory. `on_demand` scans bypass the
+    /// per-process cooldown but share the global rate limit.
+    pub fn scan_process_memory(
+        &self,
+        pid: u32,
+        on_demand: bool,
+    ) -> Result<MemoryScanReport, String> {
+        let name = memory_scan::process_name(pid);
+        if name.is_none() {
+            return Err(format!("Process {pid} is not running"));
+        }
+        self.scan_memory(pid, name, on_demand)
+            .map(|(report, _)| report)
+    }
+
+    /// Runs the scan and records one threat event per matched rule.
+    #[cfg(feature = "yara-detection")]
+    fn scan_memory(
+        &self,
+        pid: u32,
+        process_name: Option<String>,
+        on_demand: bool,
+    ) -> Result<(MemoryScanReport, Vec<ThreatEvent>), String> {
+        self.memory_scan_limiter
+            .lock()
+            .unwrap()
+            .try_acquire(pid, on_demand, Instant::now())?;
+
+        let started = Instant::now();
+        let matched_rules = {
+            let rules = self.yara_rules.lock().unwrap();
+            let rules = rules.as_ref().ok_or("YARA rules are not loaded")?;
+            memory_scan::scan_process(rules, pid)?
+        };
+        let report = MemoryScanReport {
+            pid,
+            process_name,
+            matched_rules,
+            duration_ms: started.elapsed().as_millis() as u64,
+            scanned_at: Utc::now(),
+        };
 
-        if suspicious_names
+        let events: Vec<ThreatEvent> = report
+            .matched_rules
             .iter()
-            .any(|&name| process_name.to_lowercase().contains(name))
-        {
-            // Check for suspicious command line arguments
-            if let Some(command) = details.get("command").and_then(|v| v.as_str()) {
-                let suspicious_args = [
-                    "-encodedcommand",
-                    "-windowstyle hidden",
-                    "-noprofile",
-                    "invoke-expression",
-                    "downloadstring",
-                    "bypass",
-                ];
-
-                return suspicious_args
-                    .iter()
-                    .any(|&arg| command.to_lowercase().contains(arg));
-            }
+            .map(|rule| ThreatEvent {
+                id: uuid::Uuid::new_v4().to_string(),
+                timestamp: Utc::now(),
+                threat_type: ThreatType::MalwareSignature,
+                severity: ThreatSeverity::from_score(YARA_MEMORY_SCORE),
+                description: format!("YARA memory match: {rule}"),
+                process_name: report.process_name.clone(),
+                process_id: Some(pid),
+                details: HashMap::from([
+                    ("rule_name".to_string(), rule.clone()),
+                    ("source".to_string(), "process_memory".to_string()),
+                ]),
+                techniques: Vec::new(),
+                risk_score: YARA_MEMORY_SCORE,
+            })
+            .collect();
+        for event in &events {
+            warn!("{} in process {pid}", event.description);
+            self.record_threat(event.clone());
         }
+        Ok((report, events))
+    }
 
-        // Check for processes running from suspicious locations
-        if let Some(path) = details.get("exe").and_then(|v| v.as_str()) {
-            let suspicious_paths = [
-                "\\temp\\",
-                "\\appdata\\local\\temp\\",
-                "\\users\\public\\",
-                "\\programdata\\",
-                "\\windows\\temp\\",
-            ];
-
-            return suspicious_paths
-                .iter()
-                .any(|&path_part| path.to_lowercase().contains(path_part));
-        }
+    #[cfg(not(feature = "yara-detection"))]
+    fn scan_memory(
+        &self,
+        _pid: u32,
+        _process_name: Option<String>,
+        _on_demand: bool,
+    ) -> Result<(MemoryScanReport, Vec<ThreatEvent>), String> {
+        Err("YARA detection feature not enabled".to_string())
+    }
+
+    /// Heuristic rules matching `sample`, most severe first.
+    pub fn test_rules(&self, sample: &ProcessSample) -> Vec<RuleMatch> {
+        self.rules.evaluate(sample)
+    }
 
-        false
+    pub fn rules(&self) -> &RuleEngine {
+        &self.rules
     }
 
     pub fn get_threat_history(&self) -> Vec<ThreatEvent> {
         self.threat_history.lock().unwrap().clone()
     }
+
+    fn publish(&self, event: &ThreatEvent) {
+        let severity = Severity::from(event.severity.clone()).to_string();
+        prometheus::THREATS_TOTAL.inc(&[("severity", &severity)]);
+        self.forward_alert(Alert::from(event));
+        // No subscribers is not an error
+        let _ = self.threat_tx.send(event.clone());
+    }
+
+    pub fn set_alert_router(&self, router: AlertRouter) {
+        *self.alerts.lock().unwrap() = router;
+    }
+
+    /// Send `alert` to the configured alert sinks that accept its severity.
+    pub fn forward_alert(&self, alert: Alert) {
+        self.alerts.lock().unwrap().forward(alert);
+    }
+
+    /// Threats recorded from now on; slow receivers skip the oldest events.
+    pub fn subscribe_threats(&self) -> broadcast::Receiver<ThreatEvent> {
+        self.threat_tx.subscribe()
+    }
+
+    /// Whether the built-in YARA rules compiled; always false without the
+    /// `yara-detection` feature.
+    pub fn yara_rules_loaded(&self) -> bool {
+        #[cfg(feature = "yara-detection")]
+        {
+            self.yara_rules.lock().unwrap().is_some()
+        }
+        #[cfg(not(feature = "yara-detection"))]
+        {
+            false
+        }
+    }
 }
 
 pub struct Guardian {
@@ -381,21 +653,58 @@ pub struct Guardian {
     threat_detector: Arc<ThreatDetector>,
     file_scanner: Arc<Mutex<FileScanner>>,
     vt_cache: Arc<Mutex<VtCache>>,
+    iocs: Arc<IocStore>,
+    firewall: Arc<FirewallManager>,
+    last_monitor_cycle: Arc<Mutex<Option<DateTime<Utc>>>>,
+    monitor_thread: Mutex<Option<thread::JoinHandle<()>>>,
+    /// Bumped on restart so a replaced loop exits when it wakes up
+    monitor_generation: Arc<AtomicU64>,
+    baseline: Arc<BaselineMonitor>,
+    integrity: Arc<IntegrityMonitor>,
+    av_coexistence: Arc<Mutex<Option<AvCoexistence>>>,
 }
 
+/// Default seconds between baseline drift checks.
+const BASELINE_CHECK_INTERVAL_SECS: u64 = 3600;
+/// Default seconds between self-integrity checks.
+const INTEGRITY_CHECK_INTERVAL_SECS: u64 = 300;
+/// Seconds between heartbeat writes and liveness checks.
+const HEARTBEAT_INTERVAL_SECS: u64 = 30;
+/// Seconds between checks for other antivirus products.
+const AV_DETECTION_INTERVAL_SECS: u64 = 3600;
+
 impl Guardian {
     pub fn new(config: GuardianConfig) -> Self {
-        let scanner = Self::build_scanner(&config);
+        let iocs = IocStore::new(IOC_PATH);
+        let scanner = Self::build_scanner(&config, &iocs);
+        let rules = RuleEngine::new(config.heuristic_rules_path.as_ref().map(PathBuf::from));
+        let threat_detector = ThreatDetector::with_rules(rules);
+        threat_detector.set_alert_router(Self::build_alert_router(&config));
+        if !cfg!(feature = "yara-detection") {
+            capabilities::not_compiled(Feature::Yara, "yara-detection");
+        } else if threat_detector.yara_rules_loaded() {
+            capabilities::available(Feature::Yara);
+        } else {
+            capabilities::unavailable(Feature::Yara, "YARA rules failed to compile");
+        }
         Self {
             monitor: Arc::new(Mutex::new(SystemMonitor::new())),
             config: Arc::new(Mutex::new(config)),
-            threat_detector: Arc::new(ThreatDetector::new()),
+            threat_detector: Arc::new(threat_detector),
             file_scanner: Arc::new(Mutex::new(scanner)),
             // Cache VT verdicts for 24h with a modest cap to bound memory.
             vt_cache: Arc::new(Mutex::new(VtCache::new(
                 Duration::from_secs(24 * 60 * 60),
                 2048,
             ))),
+            iocs: Arc::new(iocs),
+            firewall: Arc::new(FirewallManager::new(FIREWALL_RULES_PATH)),
+            last_monitor_cycle: Arc::new(Mutex::new(None)),
+            monitor_thread: Mutex::new(None),
+            monitor_generation: Arc::new(AtomicU64::new(0)),
+            baseline: Arc::new(BaselineMonitor::new(BASELINE_PATH)),
+            integrity: Arc::new(IntegrityMonitor::new(INTEGRITY_PATH, HEARTBEAT_PATH)),
+            av_coexistence: Arc::new(Mutex::new(None)),
         }
     }
 
@@ -403,26 +712,136 @@ impl Guardian {
         let mut config = self.config.lock().unwrap();
         *config = new_config;
         info!("Guardian config updated.");
+        self.threat_detector
+            .rules()
+            .set_path(config.heuristic_rules_path.as_ref().map(PathBuf::from));
+        self.threat_detector
+            .set_alert_router(Self::build_alert_router(&config));
         // Rebuild scanner from new config
-        let scanner = Self::build_scanner(&config);
+        let scanner = Self::build_scanner(&config, &self.iocs);
         let mut fs = self.file_scanner.lock().unwrap();
         *fs = scanner;
+        // Apply a changed tuning switch to the products found last
+        let mut coexistence = self.av_coexistence.lock().unwrap();
+        if let Some(current) = coexistence.as_mut() {
+            let tuning = config.av_coexistence_tuning.unwrap_or(true);
+            *current = AvCoexistence::new(current.products.clone(), tuning, current.checked_at);
+            self.threat_detector
+                .set_memory_scans_paused(current.tuned());
+        }
+    }
+
+    fn build_alert_router(cfg: &GuardianConfig) -> AlertRouter {
+        AlertRouter::from_config(cfg.alert_sinks.as_deref().unwrap_or_default())
     }
 
-    fn build_scanner(cfg: &GuardianConfig) -> FileScanner {
-        let sigdb = cfg
+    /// Forward an incident that is not a recorded threat, such as a failed
+    /// playbook, to the configured alert sinks.
+    pub fn forward_alert(&self, alert: Alert) {
+        self.threat_detector.forward_alert(alert);
+    }
+
+    /// Send a test message through each configured email sink. Blocks until
+    /// every SMTP exchange finishes.
+    pub fn send_test_emails(&self) -> Vec<EmailTestResult> {
+        let sinks = self.config.lock().unwrap().alert_sinks.clone();
+        alerts::send_test_emails(sinks.as_deref().unwrap_or_default())
+    }
+
+    fn build_scanner(cfg: &GuardianConfig, iocs: &IocStore) -> FileScanner {
+        let mut sigdb = cfg
             .signatures_path
             .as_ref()
             .and_then(|p| SignatureDb::load_from_path(p).ok());
+        let hashes: Vec<Ioc> = iocs
+            .active(Utc::now())
+            .into_iter()
+            .filter(|ioc| ioc.kind.is_hash())
+            .collect();
+        if !hashes.is_empty() {
+            let db = sigdb.get_or_insert_with(SignatureDb::new);
+            for ioc in &hashes {
+                db.add_ioc(ioc);
+            }
+        }
         FileScanner::new(sigdb, cfg.max_file_size_mb)
     }
 
+    /// Import a STIX 2.1 bundle or CSV indicator list from a file or URL,
+    /// replacing what `source` (default: the URL host or file name)
+    /// delivered before. Indicators without their own expiry expire after
+    /// `expiry_days`. Blocks while the feed downloads.
+    pub fn import_ioc_feed(
+        &self,
+        path_or_url: &str,
+        source: Option<String>,
+        expiry_days: Option<u32>,
+    ) -> Result<IocImportReport, String> {
+        let source = source
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| ioc::default_source(path_or_url));
+        let content = ioc::read_feed(path_or_url)?;
+        let now = Utc::now();
+        let feed = ioc::parse_feed(
+            &content,
+            &source,
+            now,
+            expiry_days.unwrap_or(ioc::DEFAULT_EXPIRY_DAYS),
+        )?;
+        let report = self.iocs.import(&source, feed, now)?;
+        self.integrity.trust(IOC_PATH);
+        // Rebuild so hashes this source no longer lists drop out
+        let scanner = Self::build_scanner(&self.config.lock().unwrap(), &self.iocs);
+        *self.file_scanner.lock().unwrap() = scanner;
+        Ok(report)
+    }
+
+    /// Oxide Pilot's firewall rules, shared with remediation.
+    pub fn firewall(&self) -> Arc<FirewallManager> {
+        Arc::clone(&self.firewall)
+    }
+
+    /// Imported indicator matching a hash, domain, IP or URL.
+    pub fn ioc_reputation(&self, value: &str) -> Option<Ioc> {
+        self.iocs.reputation(value, Utc::now())
+    }
+
     pub fn start_monitoring(&self) {
+        self.spawn_monitor_loop();
+        self.start_baseline_checks();
+        self.start_integrity_checks();
+        self.start_av_detection();
+    }
+
+    /// Replace a monitoring loop that exited or stopped completing passes.
+    /// A stuck thread cannot be killed; it exits when it next wakes up.
+    pub fn restart_monitoring(&self) {
+        // A panic in the loop poisons the locks it held
+        self.monitor.clear_poison();
+        self.config.clear_poison();
+        self.last_monitor_cycle.clear_poison();
+        self.spawn_monitor_loop();
+    }
+
+    /// The monitoring thread has not exited.
+    pub fn monitoring_alive(&self) -> bool {
+        self.monitor_thread
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|handle| !handle.is_finished())
+    }
+
+    fn spawn_monitor_loop(&self) {
         let monitor_arc = Arc::clone(&self.monitor);
         let config_arc = Arc::clone(&self.config);
         let threat_detector_arc = Arc::clone(&self.threat_detector);
+        let last_cycle_arc = Arc::clone(&self.last_monitor_cycle);
+        let current_generation = Arc::clone(&self.monitor_generation);
+        let generation = current_generation.fetch_add(1, Ordering::SeqCst) + 1;
 
-        thread::spawn(move || {
+        let handle = thread::spawn(move || {
             #[cfg(target_os = "windows")]
             unsafe {
                 let thread = GetCurrentThread();
@@ -432,6 +851,10 @@ impl Guardian {
             }
 
             loop {
+                if current_generation.load(Ordering::SeqCst) != generation {
+                    info!("Replaced Guardian monitoring loop exiting");
+                    break;
+                }
                 let config = config_arc.lock().unwrap();
                 if !config.enabled {
                     info!("Guardian monitoring is disabled. Sleeping...");
@@ -469,16 +892,284 @@ impl Guardian {
                         info!("  Process: {} (PID: {:?})", process_name, threat.process_id);
                     }
                 }
+                drop(monitor);
+                *last_cycle_arc.lock().unwrap() = Some(Utc::now());
 
                 thread::sleep(Duration::from_secs(interval));
             }
         });
+        *self.monitor_thread.lock().unwrap() = Some(handle);
+    }
+
+    /// Diff the machine against the baseline on its own schedule; the
+    /// capture runs external tools and is far slower than a monitoring pass.
+    fn start_baseline_checks(&self) {
+        let config_arc = Arc::clone(&self.config);
+        let threat_detector_arc = Arc::clone(&self.threat_detector);
+        let baseline = Arc::clone(&self.baseline);
+
+        thread::spawn(move || {
+            // Let startup settle before the first capture
+            thread::sleep(Duration::from_secs(60));
+            loop {
+                let (enabled, interval) = {
+                    let config = config_arc.lock().unwrap();
+                    let interval = config
+                        .baseline_check_interval_secs
+                        .unwrap_or(BASELINE_CHECK_INTERVAL_SECS);
+                    (config.enabled && interval > 0, interval)
+                };
+                // Battery saver postpones the capture until mains power
+                if !enabled || power::policy().defer_scheduled_scans {
+                    thread::sleep(Duration::from_secs(60));
+                    continue;
+                }
+                match baseline.check() {
+                    Ok(threats) => {
+                        for threat in threats {
+                            warn!("{}", threat.description);
+                            threat_detector_arc.record_threat(threat);
+                        }
+                    }
+                    Err(e) => error!("Baseline check failed: {e}"),
+                }
+                thread::sleep(Duration::from_secs(interval));
+            }
+        });
+    }
+
+    /// Look for other AV products now and every hour, and leave the work
+    /// their real-time protection already does to them.
+    fn start_av_detection(&self) {
+        let config_arc = Arc::clone(&self.config);
+        let threat_detector_arc = Arc::clone(&self.threat_detector);
+        let coexistence_arc = Arc::clone(&self.av_coexistence);
+
+        thread::spawn(move || loop {
+            let products = av_coexistence::detect();
+            let tuning = config_arc
+                .lock()
+                .unwrap()
+                .av_coexistence_tuning
+                .unwrap_or(true);
+            let coexistence = AvCoexistence::new(products, tuning, Utc::now());
+            let mut current = coexistence_arc.lock().unwrap();
+            let was_tuned = current.as_ref().is_some_and(AvCoexistence::tuned);
+            if coexistence.tuned() && !was_tuned {
+                let active: Vec<&str> = coexistence
+                    .products
+                    .iter()
+                    .filter(|p| p.realtime_enabled)
+                    .map(|p| p.name.as_str())
+                    .collect();
+                info!(
+                    "Real-time protection of {} is on; {}",
+                    active.join(", "),
+                    coexistence.adjustments.join("; ")
+                );
+            } else if !coexistence.tuned() && was_tuned {
+                info!("No other real-time antivirus protection; Guardian defaults restored");
+            }
+            threat_detector_arc.set_memory_scans_paused(coexistence.tuned());
+            *current = Some(coexistence);
+            drop(current);
+            thread::sleep(Duration::from_secs(AV_DETECTION_INTERVAL_SECS));
+        });
+    }
+
+    /// Other AV products found by the last check; `None` until it finishes.
+    pub fn av_coexistence(&self) -> Option<AvCoexistence> {
+        self.av_coexistence.lock().unwrap().clone()
+    }
+
+    /// Check Oxide Pilot's own files on schedule, keep the heartbeat fresh
+    /// and watch for the monitoring loop or the whole process being stopped.
+    /// File checks wait for mains power while battery saver is on.
+    fn start_integrity_checks(&self) {
+        let config_arc = Arc::clone(&self.config);
+        let threat_detector_arc = Arc::clone(&self.threat_detector);
+        let last_cycle_arc = Arc::clone(&self.last_monitor_cycle);
+        let monitor = Arc::clone(&self.integrity);
+
+        thread::spawn(move || {
+            let boot_time = {
+                use sysinfo::SystemExt;
+                let secs = sysinfo::System::new().boot_time() as i64;
+                DateTime::<Utc>::from_timestamp(secs, 0).unwrap_or_else(Utc::now)
+            };
+            if let Some(threat) = monitor.begin_session(boot_time) {
+                error!("CRITICAL THREAT: {}", threat.description);
+                threat_detector_arc.record_threat(threat);
+            }
+            let tick = Duration::from_secs(HEARTBEAT_INTERVAL_SECS);
+            let mut last_check: Option<Instant> = None;
+            let mut stall_reported = false;
+            loop {
+                let awake_before = integrity::awake_time();
+                thread::sleep(tick);
+                monitor.beat();
+                let mut threats = Vec::new();
+                threats.extend(integrity::process_pause(
+                    tick,
+                    integrity::awake_time().saturating_sub(awake_before),
+                ));
+
+                let config = config_arc.lock().unwrap().clone();
+                let last_cycle = *last_cycle_arc.lock().unwrap();
+                match last_cycle.filter(|_| config.enabled).and_then(|cycle| {
+                    integrity::monitoring_stall(cycle, config.monitor_interval_secs, Utc::now())
+                }) {
+                    Some(threat) if !stall_reported => {
+                        stall_reported = true;
+                        threats.push(threat);
+                    }
+                    Some(_) => {}
+                    None => stall_reported = false,
+                }
+
+                let interval = config
+                    .integrity_check_interval_secs
+                    .unwrap_or(INTEGRITY_CHECK_INTERVAL_SECS);
+                if interval > 0
+                    && !power::policy().defer_scheduled_scans
+                    && last_check.is_none_or(|at| at.elapsed() >= Duration::from_secs(interval))
+                {
+                    last_check = Some(Instant::now());
+                    let mut paths = integrity::protected_paths(&config);
+                    paths.extend(monitor.extra_paths());
+                    match monitor.check(&paths) {
+                        Ok(found) => threats.extend(found),
+                        Err(e) => error!("Integrity check failed: {e}"),
+                    }
+                }
+
+                for threat in threats {
+                    error!("CRITICAL THREAT: {}", threat.description);
+                    threat_detector_arc.record_threat(threat);
+                }
+            }
+        });
+    }
+
+    /// Record a threat found outside the monitoring loop, such as by an
+    /// analysis of stored metrics.
+    pub fn record_threat(&self, event: ThreatEvent) {
+        self.threat_detector.record_threat(event);
     }
 
     pub fn get_threat_history(&self) -> Vec<ThreatEvent> {
         self.threat_detector.get_threat_history()
     }
 
+    pub fn subscribe_threats(&self) -> broadcast::Receiver<ThreatEvent> {
+        self.threat_detector.subscribe_threats()
+    }
+
+    pub fn yara_rules_loaded(&self) -> bool {
+        self.threat_detector.yara_rules_loaded()
+    }
+
+    /// Current state compared with the baseline; `None` before one exists.
+    pub fn baseline_drift(&self) -> Option<DriftReport> {
+        self.baseline.drift(&BaselineSnapshot::capture())
+    }
+
+    /// Accept the current state as the known-good baseline.
+    pub fn record_baseline(&self) -> Result<BaselineSnapshot, String> {
+        self.baseline.record()
+    }
+
+    /// Oxide Pilot's own files compared with the integrity manifest.
+    pub fn integrity_status(&self) -> Result<IntegrityReport, String> {
+        self.integrity.evaluate(&self.integrity_paths())
+    }
+
+    /// Accept the current state of Oxide Pilot's own files as known-good.
+    pub fn record_integrity(&self) -> Result<IntegrityManifest, String> {
+        self.integrity.record(&self.integrity_paths())
+    }
+
+    /// Also protect `path`, such as the audit database the app opens.
+    pub fn protect_path(&self, kind: ProtectedKind, path: &str) {
+        self.integrity.protect(kind, path);
+    }
+
+    fn integrity_paths(&self) -> Vec<integrity::ProtectedPath> {
+        let mut paths = integrity::protected_paths(&self.config.lock().unwrap());
+        paths.extend(self.integrity.extra_paths());
+        paths
+    }
+
+    pub fn heuristic_rules(&self) -> RulesStatus {
+        self.threat_detector.rules().status()
+    }
+
+    /// Re-read the heuristic ruleset file now instead of on the next pass.
+    pub fn reload_heuristic_rules(&self) -> Result<RulesStatus, String> {
+        self.threat_detector.rules().reload()?;
+        Ok(self.heuristic_rules())
+    }
+
+    pub fn test_heuristic_rules(&self, sample: &ProcessSample) -> Vec<RuleMatch> {
+        self.threat_detector.test_rules(sample)
+    }
+
+    /// Fingerprint of the signatures, imported IOCs, YARA and heuristic rules
+    /// scans run against. A clean verdict recorded under a different
+    /// fingerprint no longer vouches for the file.
+    pub fn detection_version(&self) -> String {
+        let config = self.config.lock().unwrap().clone();
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+        let signature_count = self
+            .file_scanner
+            .lock()
+            .unwrap()
+            .signature_count()
+            .unwrap_or(0);
+        hasher.update(&(signature_count as u64).to_le_bytes());
+        let files = config
+            .signatures_path
+            .iter()
+            .chain(config.heuristic_rules_path.iter())
+            .chain(config.yara_rules_paths.iter().flatten())
+            .map(String::as_str)
+            .chain([IOC_PATH]);
+        for path in files {
+            hasher.update(path.as_bytes());
+            if let Ok(meta) = std::fs::metadata(path) {
+                hasher.update(&meta.len().to_le_bytes());
+                let modified = meta
+                    .modified()
+                    .ok()
+                    .map(|t| DateTime::<Utc>::from(t).timestamp_millis())
+                    .unwrap_or_default();
+                hasher.update(&modified.to_le_bytes());
+            }
+        }
+        hasher.finalize().to_hex()[..16].to_string()
+    }
+
+    pub fn protection(&self) -> GuardianProtection {
+        let config = self.config.lock().unwrap().clone();
+        let signatures_updated_at = config
+            .signatures_path
+            .as_ref()
+            .and_then(|path| std::fs::metadata(path).ok())
+            .and_then(|meta| meta.modified().ok())
+            .map(DateTime::<Utc>::from);
+        GuardianProtection {
+            monitoring_enabled: config.enabled,
+            monitor_interval_secs: config.monitor_interval_secs,
+            last_monitor_cycle: *self.last_monitor_cycle.lock().unwrap(),
+            yara_available: self.yara_rules_loaded(),
+            signature_count: self.file_scanner.lock().unwrap().signature_count(),
+            signatures_path: config.signatures_path,
+            signatures_updated_at,
+            antivirus: self.av_coexistence(),
+        }
+    }
+
     pub fn get_system_status(&self) -> SystemStatus {
         let monitor = self.monitor.lock().unwrap();
         SystemStatus {
@@ -489,6 +1180,21 @@ impl Guardian {
         }
     }
 
+    /// Autostart entries scored against heuristics and the signature database.
+    pub fn startup_items(&self) -> Vec<StartupItem> {
+        let scanner = self.file_scanner.lock().unwrap();
+        startup::list_startup_items(Some(&scanner))
+    }
+
+    pub fn disable_startup_item(&self, id: &str) -> Result<String, String> {
+        startup::disable_startup_item(id)
+    }
+
+    pub fn scan_process_memory(&self, pid: u32) -> Result<MemoryScanReport, String> {
+        capabilities::require(Feature::Yara)?;
+        self.threat_detector.scan_process_memory(pid, true)
+    }
+
     pub fn scan_file(
         &self,
         path: &str,
@@ -507,17 +1213,11 @@ impl Guardian {
                     // Check cache first
                     let mut cache = self.vt_cache.lock().unwrap();
                     if let Some(v) = cache.get(&sha) {
-                        report.external_verdict = Some(v.clone());
-                        if v.malicious {
-                            report.malicious = true;
-                        }
+                        report.apply_external_verdict(v.clone());
                     } else {
                         match external_api::virustotal_lookup(&sha, &api_key) {
                             Ok(v) => {
-                                report.external_verdict = Some(v.clone());
-                                if v.malicious {
-                                    report.malicious = true;
-                                }
+                                report.apply_external_verdict(v.clone());
                                 cache.put(sha, v);
                             }
                             Err(e) => {
@@ -543,7 +1243,7 @@ impl Guardian {
                 id: uuid::Uuid::new_v4().to_string(),
                 timestamp: Utc::now(),
                 threat_type: ThreatType::MaliciousFile,
-                severity: ThreatSeverity::High,
+                severity: ThreatSeverity::from_score(report.risk_score),
                 description: format!("Malicious file detected: {}", report.path),
                 process_name: None,
                 process_id: None,
@@ -551,6 +1251,8 @@ impl Guardian {
                     ("sha256".to_string(), report.hashes.sha256.clone()),
                     ("blake3".to_string(), report.hashes.blake3.clone()),
                 ]),
+                techniques: Vec::new(),
+                risk_score: report.risk_score,
             };
             self.threat_detector.record_threat(event);
         }
@@ -559,7 +1261,7 @@ impl Guardian {
     }
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct SystemStatus {
     pub cpu_usage: f32,
     pub memory_usage: (u64, u64), // (used, total)
@@ -0,0 +1,340 @@
+//! GPU, thermal and battery readings for thermal-throttling diagnosis.
+//!
+//! GPU data comes from NVML via `nvidia-smi` when an NVIDIA driver is
+//! installed, and from the amdgpu sysfs interface on Linux. CPU temperature
+//! comes from sysinfo components; battery state from sysfs on Linux and
+//! `GetSystemPowerStatus` on Windows. Readings are cached briefly because
+//! dashboards poll every few seconds. [`power_source`] reads the same
+//! sources uncached for the battery saver policy.
+
+use oxide_core::power::PowerSource;
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use sysinfo::{ComponentExt, System, SystemExt};
+
+const CACHE_TTL: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GpuReading {
+    pub name: String,
+    pub utilization_percent: Option<f64>,
+    pub vram_used_mb: Option<f64>,
+    pub vram_total_mb: Option<f64>,
+    pub temperature_c: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatteryReading {
+    pub percent: f64,
+    pub charging: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HardwareReadings {
+    pub gpus: Vec<GpuReading>,
+    pub cpu_temperature_c: Option<f64>,
+    pub battery: Option<BatteryReading>,
+}
+
+/// Current readings, at most [`CACHE_TTL`] old.
+pub fn readings() -> HardwareReadings {
+    static CACHE: OnceLock<Mutex<Option<(Instant, HardwareReadings)>>> = OnceLock::new();
+    let cache = CACHE.get_or_init(|| Mutex::new(None));
+
+    if let Ok(guard) = cache.lock() {
+        if let Some((at, cached)) = guard.as_ref() {
+            if at.elapsed() < CACHE_TTL {
+                return cached.clone();
+            }
+        }
+    }
+
+    let fresh = HardwareReadings {
+        gpus: gpu_readings(),
+        cpu_temperature_c: cpu_temperature(),
+        battery: battery(),
+    };
+    if let Ok(mut guard) = cache.lock() {
+        *guard = Some((Instant::now(), fresh.clone()));
+    }
+    fresh
+}
+
+fn cpu_temperature() -> Option<f64> {
+    let mut sys = System::new();
+    sys.refresh_components_list();
+    let sensors: Vec<(String, f32)> = sys
+        .components()
+        .iter()
+        .map(|c| (c.label().to_string(), c.temperature()))
+        .collect();
+    pick_cpu_temperature(&sensors)
+}
+
+/// Prefer package/die sensors, then the hottest core sensor.
+fn pick_cpu_temperature(sensors: &[(String, f32)]) -> Option<f64> {
+    let valid = |t: f32| t.is_finite() && t > 0.0 && t < 150.0;
+    let hottest = |keys: &[&str]| {
+        sensors
+            .iter()
+            .filter(|(label, t)| {
+                let label = label.to_ascii_lowercase();
+                valid(*t) && keys.iter().any(|k| label.contains(k))
+            })
+            .map(|(_, t)| *t as f64)
+            .reduce(f64::max)
+    };
+    hottest(&["package", "tctl", "tdie", "cpu"]).or_else(|| hottest(&["core"]))
+}
+
+fn gpu_readings() -> Vec<GpuReading> {
+    let mut gpus = nvidia_smi().unwrap_or_default();
+    #[cfg(target_os = "linux")]
+    gpus.extend(amdgpu_sysfs());
+    gpus
+}
+
+fn nvidia_smi() -> Option<Vec<GpuReading>> {
+    let output = std::process::Command::new("nvidia-smi")
+        .args([
+            "--query-gpu=name,utilization.gpu,memory.used,memory.total,temperature.gpu",
+            "--format=csv,noheader,nounits",
+        ])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(parse_nvidia_smi(&String::from_utf8_lossy(&output.stdout)))
+}
+
+fn parse_nvidia_smi(output: &str) -> Vec<GpuReading> {
+    // Unsupported columns are reported as "[N/A]" or "[Not Supported]"
+    let number = |s: Option<&str>| s.and_then(|v| v.trim().parse::<f64>().ok());
+    output
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let mut cols = line.split(',');
+            GpuReading {
+                name: cols.next().unwrap_or_default().trim().to_string(),
+                utilization_percent: number(cols.next()),
+                vram_used_mb: number(cols.next()),
+                vram_total_mb: number(cols.next()),
+                temperature_c: number(cols.next()),
+            }
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn amdgpu_sysfs() -> Vec<GpuReading> {
+    use std::fs;
+    use std::path::Path;
+
+    let read_num =
+        |path: &Path| -> Option<f64> { fs::read_to_string(path).ok()?.trim().parse().ok() };
+    let Ok(cards) = fs::read_dir("/sys/class/drm") else {
+        return Vec::new();
+    };
+
+    let mut gpus = Vec::new();
+    for card in cards.flatten() {
+        let name = card.file_name().to_string_lossy().into_owned();
+        // Skip connectors such as card0-HDMI-A-1
+        if !name.starts_with("card") || name.contains('-') {
+            continue;
+        }
+        let device = card.path().join("device");
+        let Some(busy) = read_num(&device.join("gpu_busy_percent")) else {
+            continue;
+        };
+        let mb = |file: &str| read_num(&device.join(file)).map(|b| b / 1024.0 / 1024.0);
+        let temperature_c = fs::read_dir(device.join("hwmon"))
+            .ok()
+            .and_then(|mut dirs| dirs.next())
+            .and_then(|dir| dir.ok())
+            .and_then(|dir| read_num(&dir.path().join("temp1_input")))
+            .map(|millidegrees| millidegrees / 1000.0);
+        gpus.push(GpuReading {
+            name: format!("amdgpu {name}"),
+            utilization_percent: Some(busy),
+            vram_used_mb: mb("mem_info_vram_used"),
+            vram_total_mb: mb("mem_info_vram_total"),
+            temperature_c,
+        });
+    }
+    gpus
+}
+
+/// Current battery state, uncached; `None` without a battery.
+#[cfg(target_os = "linux")]
+pub fn battery() -> Option<BatteryReading> {
+    let supplies = std::fs::read_dir("/sys/class/power_supply").ok()?;
+    supplies.flatten().find_map(|supply| {
+        let path = supply.path();
+        let kind = std::fs::read_to_string(path.join("type")).ok()?;
+        if kind.trim() != "Battery" {
+            return None;
+        }
+        let capacity = std::fs::read_to_string(path.join("capacity")).ok()?;
+        let status = std::fs::read_to_string(path.join("status")).unwrap_or_default();
+        parse_sysfs_battery(&capacity, &status)
+    })
+}
+
+#[cfg(any(target_os = "linux", test))]
+fn parse_sysfs_battery(capacity: &str, status: &str) -> Option<BatteryReading> {
+    let percent = capacity.trim().parse::<f64>().ok()?;
+    let status = status.trim();
+    Some(BatteryReading {
+        percent,
+        charging: status == "Charging" || status == "Full",
+    })
+}
+
+#[cfg(target_os = "windows")]
+pub fn battery() -> Option<BatteryReading> {
+    use winapi::um::winbase::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    let mut status: SYSTEM_POWER_STATUS = unsafe { std::mem::zeroed() };
+    if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+        return None;
+    }
+    // 128 = no system battery, 255 = unknown status
+    if status.BatteryFlag & 128 != 0 || status.BatteryLifePercent == 255 {
+        return None;
+    }
+    Some(BatteryReading {
+        percent: status.BatteryLifePercent as f64,
+        charging: status.BatteryFlag & 8 != 0 || status.ACLineStatus == 1,
+    })
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+pub fn battery() -> Option<BatteryReading> {
+    None
+}
+
+/// Whether the machine runs on mains power or on its battery.
+#[cfg(target_os = "linux")]
+pub fn power_source() -> PowerSource {
+    let Ok(supplies) = std::fs::read_dir("/sys/class/power_supply") else {
+        return PowerSource::Ac;
+    };
+    let mains_online: Vec<bool> = supplies
+        .flatten()
+        .filter(|supply| {
+            std::fs::read_to_string(supply.path().join("type"))
+                .is_ok_and(|kind| kind.trim() == "Mains")
+        })
+        .map(|supply| {
+            std::fs::read_to_string(supply.path().join("online")).is_ok_and(|v| v.trim() == "1")
+        })
+        .collect();
+    source_from_supplies(&mains_online, battery().as_ref())
+}
+
+/// Mains adapters decide when present; otherwise a charging or full battery
+/// means mains power. Machines without a battery are always on mains.
+#[cfg(any(target_os = "linux", test))]
+fn source_from_supplies(mains_online: &[bool], battery: Option<&BatteryReading>) -> PowerSource {
+    match battery {
+        None => PowerSource::Ac,
+        Some(_) if mains_online.contains(&true) => PowerSource::Ac,
+        Some(_) if !mains_online.is_empty() => PowerSource::Battery,
+        Some(b) if b.charging => PowerSource::Ac,
+        Some(_) => PowerSource::Battery,
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn power_source() -> PowerSource {
+    use winapi::um::winbase::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    let mut status: SYSTEM_POWER_STATUS = unsafe { std::mem::zeroed() };
+    if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+        return PowerSource::Unknown;
+    }
+    // 128 = no system battery
+    if status.BatteryFlag & 128 != 0 {
+        return PowerSource::Ac;
+    }
+    match status.ACLineStatus {
+        0 => PowerSource::Battery,
+        1 => PowerSource::Ac,
+        _ => PowerSource::Unknown,
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+pub fn power_source() -> PowerSource {
+    PowerSource::Unknown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_package_temperature_over_cores() {
+        let sensors = vec![
+            ("coretemp Core 0".to_string(), 61.0),
+            ("coretemp Package id 0".to_string(), 58.0),
+            ("nvme Composite".to_string(), 70.0),
+        ];
+        assert_eq!(pick_cpu_temperature(&sensors), Some(58.0));
+
+        let cores = vec![
+            ("Core 0".to_string(), 50.0),
+            ("Core 1".to_string(), 64.0),
+            ("acpitz".to_string(), 90.0),
+        ];
+        assert_eq!(pick_cpu_temperature(&cores), Some(64.0));
+        assert_eq!(pick_cpu_temperature(&[("nvme".to_string(), 40.0)]), None);
+    }
+
+    #[test]
+    fn parses_nvidia_smi_csv() {
+        let gpus = parse_nvidia_smi(
+            "NVIDIA GeForce RTX 3070, 37, 2048, 8192, 66\nTesla T4, [N/A], 10, 15360, [N/A]\n",
+        );
+        assert_eq!(gpus.len(), 2);
+        assert_eq!(gpus[0].name, "NVIDIA GeForce RTX 3070");
+        assert_eq!(gpus[0].utilization_percent, Some(37.0));
+        assert_eq!(gpus[0].vram_total_mb, Some(8192.0));
+        assert_eq!(gpus[0].temperature_c, Some(66.0));
+        assert_eq!(gpus[1].utilization_percent, None);
+        assert_eq!(gpus[1].temperature_c, None);
+    }
+
+    #[test]
+    fn parses_sysfs_battery() {
+        let battery = parse_sysfs_battery("87\n", "Discharging\n").unwrap();
+        assert_eq!(battery.percent, 87.0);
+        assert!(!battery.charging);
+        assert!(parse_sysfs_battery("100", "Full").unwrap().charging);
+        assert!(parse_sysfs_battery("", "Charging").is_none());
+    }
+
+    #[test]
+    fn detects_power_source() {
+        let discharging = parse_sysfs_battery("60", "Discharging");
+        let not_charging = parse_sysfs_battery("80", "Not charging");
+        assert_eq!(source_from_supplies(&[], None), PowerSource::Ac);
+        assert_eq!(
+            source_from_supplies(&[], discharging.as_ref()),
+            PowerSource::Battery
+        );
+        // Held at a charge limit while plugged in
+        assert_eq!(
+            source_from_supplies(&[true], not_charging.as_ref()),
+            PowerSource::Ac
+        );
+        assert_eq!(
+            source_from_supplies(&[false], not_charging.as_ref()),
+            PowerSource::Battery
+        );
+    }
+}
@@ -0,0 +1,684 @@
+//! Self-protection: tampering with Oxide Pilot itself.
+//!
+//! An [`IntegrityMonitor`] keeps a manifest of Oxide Pilot's own files: the
+//! SHA-256 of its executable, configuration files and signature database,
+//! the entries of the quarantine and evidence directories, and whether the
+//! audit database exists. Each check compares the current state with the
+//! manifest. A modified or deleted file, an entry removed from quarantine
+//! or evidence, or a missing database becomes a Critical threat, reported
+//! once per change. Recording the manifest again accepts the current state,
+//! e.g. after replacing the signature database by hand.
+//!
+//! A heartbeat file shows whether the previous run was killed: it was never
+//! marked as shut down cleanly, yet the machine has not rebooted since it
+//! was last written. Guardian also reports its monitoring loop stalling and
+//! the whole process being suspended.
+//!
+//! The first check without a manifest records one. A new Oxide Pilot
+//! version re-records the executable hash instead of reporting it.
+
+use crate::evidence::EVIDENCE_DIR;
+use crate::guardian::{ThreatEvent, ThreatSeverity, ThreatType};
+use crate::ioc::IOC_PATH;
+use crate::scanner::FileScanner;
+use chrono::{DateTime, Utc};
+use log::{info, warn};
+use oxide_core::config::GuardianConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+pub const INTEGRITY_PATH: &str = "./data/integrity_manifest.json";
+pub const HEARTBEAT_PATH: &str = "./data/guardian_heartbeat.json";
+
+/// Version the executable hash in the manifest belongs to. Every crate in
+/// the workspace carries the app version.
+const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProtectedKind {
+    /// The Oxide Pilot executable
+    Binary,
+    /// Heuristic and YARA rules, `.env`
+    Config,
+    /// Signature database and imported indicator feeds
+    Signatures,
+    /// New entries are expected, removals are not
+    Quarantine,
+    /// Same as quarantine
+    Evidence,
+    /// Only its presence is checked; the contents change constantly
+    AuditDatabase,
+}
+
+impl ProtectedKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Binary => "binary",
+            Self::Config => "config",
+            Self::Signatures => "signatures",
+            Self::Quarantine => "quarantine",
+            Self::Evidence => "evidence",
+            Self::AuditDatabase => "audit_database",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProtectedPath {
+    pub kind: ProtectedKind,
+    pub path: String,
+}
+
+impl ProtectedPath {
+    pub fn new(kind: ProtectedKind, path: impl Into<String>) -> Self {
+        Self {
+            kind,
+            path: path.into(),
+        }
+    }
+
+    /// Current state, or `None` when it cannot be read right now.
+    fn capture(&self) -> Option<PathState> {
+        let path = Path::new(&self.path);
+        if !path.exists() {
+            return Some(PathState::Missing);
+        }
+        match self.kind {
+            ProtectedKind::AuditDatabase => Some(PathState::Present),
+            ProtectedKind::Quarantine | ProtectedKind::Evidence => {
+                let entries = fs::read_dir(path)
+                    .map_err(|e| warn!("Cannot list protected directory {path:?}: {e}"))
+                    .ok()?;
+                Some(PathState::Directory {
+                    entries: entries
+                        .flatten()
+                        .map(|entry| entry.file_name().to_string_lossy().to_string())
+                        .collect(),
+                })
+            }
+            _ => FileScanner::compute_hashes(path)
+                .map(|(hashes, _)| PathState::File {
+                    sha256: hashes.sha256,
+                })
+                .map_err(|e| warn!("Cannot hash protected file {path:?}: {e}"))
+                .ok(),
+        }
+    }
+}
+
+/// Files Guardian protects under `config`; the app adds the audit database
+/// with [`IntegrityMonitor::protect`].
+pub fn protected_paths(config: &GuardianConfig) -> Vec<ProtectedPath> {
+    let mut paths = Vec::new();
+    if let Ok(exe) = std::env::current_exe() {
+        paths.push(ProtectedPath::new(
+            ProtectedKind::Binary,
+            exe.to_string_lossy(),
+        ));
+    }
+    for path in config
+        .heuristic_rules_path
+        .iter()
+        .chain(config.yara_rules_paths.iter().flatten())
+    {
+        paths.push(ProtectedPath::new(ProtectedKind::Config, path.as_str()));
+    }
+    if Path::new(".env").is_file() {
+        paths.push(ProtectedPath::new(ProtectedKind::Config, ".env"));
+    }
+    for path in config
+        .signatures_path
+        .iter()
+        .map(String::as_str)
+        .chain([IOC_PATH])
+    {
+        paths.push(ProtectedPath::new(ProtectedKind::Signatures, path));
+    }
+    if let Some(dir) = &config.quarantine_dir {
+        paths.push(ProtectedPath::new(ProtectedKind::Quarantine, dir.as_str()));
+    }
+    paths.push(ProtectedPath::new(ProtectedKind::Evidence, EVIDENCE_DIR));
+    paths
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum PathState {
+    Missing,
+    Present,
+    File { sha256: String },
+    Directory { entries: BTreeSet<String> },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrityEntry {
+    pub kind: ProtectedKind,
+    pub state: PathState,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrityManifest {
+    pub recorded_at: DateTime<Utc>,
+    pub version: String,
+    /// Protected path to its known-good state
+    pub entries: BTreeMap<String, IntegrityEntry>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TamperChange {
+    Modified,
+    Deleted,
+    /// Entries removed from the quarantine or evidence directory
+    EntriesRemoved,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TamperFinding {
+    pub kind: ProtectedKind,
+    pub path: String,
+    pub change: TamperChange,
+    /// Names of the removed entries
+    #[serde(default)]
+    pub removed: Vec<String>,
+}
+
+impl TamperFinding {
+    fn fingerprint(&self) -> String {
+        format!(
+            "{}|{}|{:?}|{}",
+            self.kind.as_str(),
+            self.path,
+            self.change,
+            self.removed.join(",")
+        )
+    }
+
+    pub fn to_threat(&self) -> ThreatEvent {
+        let description = match self.change {
+            TamperChange::Modified => {
+                format!(
+                    "Oxide Pilot {} file was modified: {}",
+                    self.kind.as_str(),
+                    self.path
+                )
+            }
+            TamperChange::Deleted => {
+                format!(
+                    "Oxide Pilot {} data was deleted: {}",
+                    self.kind.as_str(),
+                    self.path
+                )
+            }
+            TamperChange::EntriesRemoved => format!(
+                "{} item(s) were removed from the Oxide Pilot {} directory {}",
+                self.removed.len(),
+                self.kind.as_str(),
+                self.path
+            ),
+        };
+        let mut details = HashMap::from([
+            ("kind".to_string(), self.kind.as_str().to_string()),
+            ("path".to_string(), self.path.clone()),
+        ]);
+        if !self.removed.is_empty() {
+            details.insert("removed".to_string(), self.removed.join(", "));
+        }
+        tamper_threat(description, details)
+    }
+}
+
+/// Result of comparing the protected paths with the manifest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrityReport {
+    pub manifest_recorded_at: DateTime<Utc>,
+    pub checked_at: DateTime<Utc>,
+    pub protected: Vec<ProtectedPath>,
+    pub findings: Vec<TamperFinding>,
+}
+
+/// Finding for `kind` going from `known` to `current`, and the state the
+/// manifest should take on when the change is expected (new files, new
+/// quarantine entries).
+fn compare(
+    kind: ProtectedKind,
+    known: &PathState,
+    current: &PathState,
+) -> (Option<TamperChange>, Option<PathState>) {
+    match (known, current) {
+        (a, b) if a == b => (None, None),
+        (PathState::Missing, _) => (None, Some(current.clone())),
+        (_, PathState::Missing) => (Some(TamperChange::Deleted), None),
+        (PathState::Directory { entries: old }, PathState::Directory { entries: new }) => {
+            let change = (!old.is_subset(new)).then_some(TamperChange::EntriesRemoved);
+            let adopted = (!new.is_subset(old)).then(|| PathState::Directory {
+                entries: old.union(new).cloned().collect(),
+            });
+            (change, adopted)
+        }
+        _ if kind == ProtectedKind::AuditDatabase => (None, Some(current.clone())),
+        _ => (Some(TamperChange::Modified), None),
+    }
+}
+
+fn tamper_threat(description: String, details: HashMap<String, String>) -> ThreatEvent {
+    ThreatEvent {
+        id: uuid::Uuid::new_v4().to_string(),
+        timestamp: Utc::now(),
+        threat_type: ThreatType::SelfTampering,
+        severity: ThreatSeverity::Critical,
+        description,
+        process_name: None,
+        process_id: None,
+        details,
+        techniques: Vec::new(),
+        risk_score: ThreatSeverity::Critical.score(),
+    }
+}
+
+/// Left by each run while Guardian is active.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Heartbeat {
+    pub pid: u32,
+    pub started_at: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    pub clean_shutdown: bool,
+}
+
+impl Heartbeat {
+    /// This run was killed rather than shut down: it never exited cleanly
+    /// and was still running after the machine last booted.
+    pub fn was_killed(&self, boot_time: DateTime<Utc>) -> bool {
+        !self.clean_shutdown && self.last_seen > boot_time && self.pid != std::process::id()
+    }
+
+    fn to_threat(&self) -> ThreatEvent {
+        tamper_threat(
+            format!(
+                "Oxide Pilot (PID {}) was terminated without shutting down; last seen {}",
+                self.pid,
+                self.last_seen.format("%Y-%m-%d %H:%M:%S UTC")
+            ),
+            HashMap::from([
+                ("pid".to_string(), self.pid.to_string()),
+                ("started_at".to_string(), self.started_at.to_rfc3339()),
+                ("last_seen".to_string(), self.last_seen.to_rfc3339()),
+            ]),
+        )
+    }
+}
+
+fn read_json<T: for<'de> Deserialize<'de>>(path: &Path) -> Option<T> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content)
+        .map_err(|e| warn!("Ignoring unreadable {path:?}: {e}"))
+        .ok()
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {parent:?}: {e}"))?;
+    }
+    let json = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    fs::write(path, json).map_err(|e| format!("Failed to write {path:?}: {e}"))
+}
+
+/// Mark this run's heartbeat as a clean shutdown; call on exit.
+pub fn mark_clean_shutdown(path: impl AsRef<Path>) {
+    let path = path.as_ref();
+    let Some(mut heartbeat) = read_json::<Heartbeat>(path) else {
+        return;
+    };
+    if heartbeat.pid != std::process::id() {
+        return;
+    }
+    heartbeat.clean_shutdown = true;
+    heartbeat.last_seen = Utc::now();
+    if let Err(e) = write_json(path, &heartbeat) {
+        warn!("Failed to record clean shutdown: {e}");
+    }
+}
+
+/// Threat for the monitoring loop not finishing a pass for longer than
+/// three intervals (at least two minutes).
+pub fn monitoring_stall(
+    last_cycle: DateTime<Utc>,
+    interval_secs: u64,
+    now: DateTime<Utc>,
+) -> Option<ThreatEvent> {
+    let limit = (interval_secs * 3).max(120);
+    let stalled = (now - last_cycle).num_seconds();
+    (stalled > limit as i64).then(|| {
+        tamper_threat(
+            format!(
+                "Guardian monitoring has been stopped for {stalled} s; it may have been suspended"
+            ),
+            HashMap::from([("last_cycle".to_string(), last_cycle.to_rfc3339())]),
+        )
+    })
+}
+
+/// Threat for the process having been paused: `awake` (time the machine
+/// was awake) passed while expecting a sleep of `expected`. Slack covers
+/// scheduling delays.
+pub fn process_pause(expected: Duration, awake: Duration) -> Option<ThreatEvent> {
+    const SLACK: Duration = Duration::from_secs(60);
+    let paused = awake.checked_sub(expected)?;
+    (paused > SLACK).then(|| {
+        tamper_threat(
+            format!(
+                "Oxide Pilot was suspended for {} s while the system was running",
+                paused.as_secs()
+            ),
+            HashMap::from([("paused_secs".to_string(), paused.as_secs().to_string())]),
+        )
+    })
+}
+
+/// Time the machine has been awake. Unlike wall-clock time it stands still
+/// while the system sleeps, so sleep is not mistaken for a suspended process.
+pub fn awake_time() -> Duration {
+    #[cfg(windows)]
+    {
+        let mut ticks: u64 = 0;
+        // 100 ns units, excluding time spent asleep or hibernating
+        unsafe { winapi::um::realtimeapiset::QueryUnbiasedInterruptTime(&mut ticks) };
+        Duration::from_nanos(ticks.saturating_mul(100))
+    }
+    #[cfg(not(windows))]
+    {
+        // CLOCK_MONOTONIC (Linux) and CLOCK_UPTIME_RAW (macOS) skip sleep
+        static START: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+        START.get_or_init(std::time::Instant::now).elapsed()
+    }
+}
+
+/// Stored manifest, the paths registered at runtime and the heartbeat.
+pub struct IntegrityMonitor {
+    path: PathBuf,
+    heartbeat_path: PathBuf,
+    extra: Mutex<Vec<ProtectedPath>>,
+    /// Fingerprints of findings already turned into threats
+    reported: Mutex<HashSet<String>>,
+    heartbeat: Mutex<Option<Heartbeat>>,
+}
+
+impl IntegrityMonitor {
+    pub fn new(path: impl Into<PathBuf>, heartbeat_path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            heartbeat_path: heartbeat_path.into(),
+            extra: Mutex::new(Vec::new()),
+            reported: Mutex::new(HashSet::new()),
+            heartbeat: Mutex::new(None),
+        }
+    }
+
+    /// Also protect `path`, e.g. the audit database once it is opened.
+    pub fn protect(&self, kind: ProtectedKind, path: impl Into<String>) {
+        let protected = ProtectedPath::new(kind, path);
+        let mut extra = self.extra.lock().unwrap();
+        if !extra.contains(&protected) {
+            extra.push(protected);
+        }
+    }
+
+    /// Paths registered with [`protect`](Self::protect).
+    pub fn extra_paths(&self) -> Vec<ProtectedPath> {
+        self.extra.lock().unwrap().clone()
+    }
+
+    pub fn load(&self) -> Option<IntegrityManifest> {
+        read_json(&self.path)
+    }
+
+    fn save(&self, manifest: &IntegrityManifest) -> Result<(), String> {
+        write_json(&self.path, manifest)
+    }
+
+    /// Accept the current state of `paths` as known-good.
+    pub fn record(&self, paths: &[ProtectedPath]) -> Result<IntegrityManifest, String> {
+        let manifest = IntegrityManifest {
+            recorded_at: Utc::now(),
+            version: APP_VERSION.to_string(),
+            entries: paths
+                .iter()
+                .filter_map(|p| {
+                    let state = p.capture()?;
+                    Some((
+                        p.path.clone(),
+                        IntegrityEntry {
+                            kind: p.kind,
+                            state,
+                        },
+                    ))
+                })
+                .collect(),
+        };
+        self.save(&manifest)?;
+        self.reported.lock().unwrap().clear();
+        info!(
+            "Integrity manifest recorded for {} protected path(s)",
+            manifest.entries.len()
+        );
+        Ok(manifest)
+    }
+
+    /// Re-record one path after Oxide Pilot changed it itself.
+    pub fn trust(&self, path: &str) {
+        let Some(mut manifest) = self.load() else {
+            return;
+        };
+        let Some(entry) = manifest.entries.get_mut(path) else {
+            return;
+        };
+        if let Some(state) = ProtectedPath::new(entry.kind, path).capture() {
+            entry.state = state;
+            if let Err(e) = self.save(&manifest) {
+                warn!("Failed to update the integrity manifest: {e}");
+            }
+        }
+    }
+
+    /// Compare `paths` with the manifest, recording one if there is none
+    /// and adopting expected changes.
+    pub fn evaluate(&self, paths: &[ProtectedPath]) -> Result<IntegrityReport, String> {
+        let Some(mut manifest) = self.load() else {
+            let manifest = self.record(paths)?;
+            return Ok(IntegrityReport {
+                manifest_recorded_at: manifest.recorded_at,
+                checked_at: manifest.recorded_at,
+                protected: paths.to_vec(),
+                findings: Vec::new(),
+            });
+        };
+        let upgraded = manifest.version != APP_VERSION;
+        let mut changed = upgraded;
+        let mut findings = Vec::new();
+        for protected in paths {
+            let Some(current) = protected.capture() else {
+                continue;
+            };
+            let Some(entry) = manifest.entries.get_mut(&protected.path) else {
+                manifest.entries.insert(
+                    protected.path.clone(),
+                    IntegrityEntry {
+                        kind: protected.kind,
+                        state: current,
+                    },
+                );
+                changed = true;
+                continue;
+            };
+            if upgraded && protected.kind == ProtectedKind::Binary {
+                info!("Oxide Pilot {APP_VERSION} installed; re-recording its executable hash");
+                entry.state = current;
+                continue;
+            }
+            let (change, adopted) = compare(protected.kind, &entry.state, &current);
+            if let Some(change) = change {
+                let removed = match (&entry.state, &current) {
+                    (
+                        PathState::Directory { entries: old },
+                        PathState::Directory { entries: new },
+                    ) => old.difference(new).cloned().collect(),
+                    _ => Vec::new(),
+                };
+                findings.push(TamperFinding {
+                    kind: protected.kind,
+                    path: protected.path.clone(),
+                    change,
+                    removed,
+                });
+            }
+            if let Some(state) = adopted {
+                entry.state = state;
+                changed = true;
+            }
+        }
+        if changed {
+            manifest.version = APP_VERSION.to_string();
+            self.save(&manifest)?;
+        }
+        Ok(IntegrityReport {
+            manifest_recorded_at: manifest.recorded_at,
+            checked_at: Utc::now(),
+            protected: paths.to_vec(),
+            findings,
+        })
+    }
+
+    /// Periodic check: threats for findings not reported before.
+    pub fn check(&self, paths: &[ProtectedPath]) -> Result<Vec<ThreatEvent>, String> {
+        let report = self.evaluate(paths)?;
+        let mut reported = self.reported.lock().unwrap();
+        Ok(report
+            .findings
+            .iter()
+            .filter(|finding| reported.insert(finding.fingerprint()))
+            .map(TamperFinding::to_threat)
+            .collect())
+    }
+
+    /// Start this run's heartbeat. Returns a threat if the previous run was
+    /// killed after the machine booted at `boot_time`.
+    pub fn begin_session(&self, boot_time: DateTime<Utc>) -> Option<ThreatEvent> {
+        let previous: Option<Heartbeat> = read_json(&self.heartbeat_path);
+        let now = Utc::now();
+        let heartbeat = Heartbeat {
+            pid: std::process::id(),
+            started_at: now,
+            last_seen: now,
+            clean_shutdown: false,
+        };
+        if let Err(e) = write_json(&self.heartbeat_path, &heartbeat) {
+            warn!("Failed to write the Guardian heartbeat: {e}");
+        }
+        *self.heartbeat.lock().unwrap() = Some(heartbeat);
+        previous
+            .filter(|previous| previous.was_killed(boot_time))
+            .map(|previous| previous.to_threat())
+    }
+
+    /// Refresh the heartbeat started by [`begin_session`](Self::begin_session).
+    pub fn beat(&self) {
+        let mut heartbeat = self.heartbeat.lock().unwrap();
+        let Some(heartbeat) = heartbeat.as_mut() else {
+            return;
+        };
+        heartbeat.last_seen = Utc::now();
+        if let Err(e) = write_json(&self.heartbeat_path, heartbeat) {
+            warn!("Failed to write the Guardian heartbeat: {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tampering_is_reported_once_and_new_entries_are_adopted() {
+        let dir = tempfile::tempdir().unwrap();
+        let signatures = dir.path().join("signatures.txt");
+        let quarantine = dir.path().join("quarantine");
+        fs::write(&signatures, "abc\n").unwrap();
+        fs::create_dir(&quarantine).unwrap();
+        fs::write(quarantine.join("1_dropper.exe"), "x").unwrap();
+        let paths = [
+            ProtectedPath::new(ProtectedKind::Signatures, signatures.to_string_lossy()),
+            ProtectedPath::new(ProtectedKind::Quarantine, quarantine.to_string_lossy()),
+            ProtectedPath::new(
+                ProtectedKind::AuditDatabase,
+                dir.path().join("oxide.db").to_string_lossy(),
+            ),
+        ];
+        let monitor =
+            IntegrityMonitor::new(dir.path().join("manifest.json"), dir.path().join("hb"));
+        assert!(monitor.check(&paths).unwrap().is_empty());
+
+        // Quarantining a file and creating the database are expected
+        fs::write(quarantine.join("2_miner.exe"), "y").unwrap();
+        fs::create_dir(dir.path().join("oxide.db")).unwrap();
+        assert!(monitor.check(&paths).unwrap().is_empty());
+
+        fs::write(&signatures, "").unwrap();
+        fs::remove_file(quarantine.join("1_dropper.exe")).unwrap();
+        fs::remove_dir(dir.path().join("oxide.db")).unwrap();
+        let threats = monitor.check(&paths).unwrap();
+        assert_eq!(threats.len(), 3);
+        assert!(threats
+            .iter()
+            .all(|t| t.severity == ThreatSeverity::Critical
+                && matches!(t.threat_type, ThreatType::SelfTampering)));
+        let removed = threats
+            .iter()
+            .find(|t| t.details["kind"] == "quarantine")
+            .unwrap();
+        assert_eq!(removed.details["removed"], "1_dropper.exe");
+        assert!(monitor.check(&paths).unwrap().is_empty());
+
+        // Accepting the current state clears the findings
+        monitor.record(&paths).unwrap();
+        assert!(monitor.evaluate(&paths).unwrap().findings.is_empty());
+    }
+
+    #[test]
+    fn detects_killed_runs_stalls_and_pauses() {
+        let boot = Utc::now() - chrono::Duration::hours(2);
+        let previous = Heartbeat {
+            pid: std::process::id().wrapping_add(1),
+            started_at: boot + chrono::Duration::minutes(5),
+            last_seen: boot + chrono::Duration::hours(1),
+            clean_shutdown: false,
+        };
+        assert!(previous.was_killed(boot));
+        // Power loss or reboot: the machine booted after the last heartbeat
+        assert!(!previous.was_killed(Utc::now()));
+        let clean = Heartbeat {
+            clean_shutdown: true,
+            ..previous.clone()
+        };
+        assert!(!clean.was_killed(boot));
+
+        let dir = tempfile::tempdir().unwrap();
+        let heartbeat = dir.path().join("heartbeat.json");
+        write_json(&heartbeat, &previous).unwrap();
+        let monitor = IntegrityMonitor::new(dir.path().join("manifest.json"), &heartbeat);
+        assert!(monitor.begin_session(boot).is_some());
+        mark_clean_shutdown(&heartbeat);
+        assert!(monitor.begin_session(boot).is_none());
+
+        let now = Utc::now();
+        assert!(monitoring_stall(now - chrono::Duration::seconds(60), 10, now).is_none());
+        assert!(monitoring_stall(now - chrono::Duration::seconds(600), 10, now).is_some());
+        let expected = Duration::from_secs(30);
+        assert!(process_pause(expected, Duration::from_secs(45)).is_none());
+        assert!(process_pause(expected, Duration::from_secs(400)).is_some());
+    }
+}
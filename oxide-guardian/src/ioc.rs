@@ -0,0 +1,588 @@
+//! Indicator-of-compromise feeds.
+//!
+//! Enterprises push their own intel as STIX 2.1 bundles or plain CSV lists
+//! of hashes, domains, IPs and URLs. Each import is tagged with its source
+//! and replaces what that source delivered before. Indicators expire at
+//! their STIX `valid_until`, a CSV `expires` column, or a default age.
+//! File hashes feed the [`SignatureDb`](crate::signatures::SignatureDb) so
+//! scans flag them locally; every live indicator answers reputation lookups
+//! through [`IocStore::reputation`].
+
+use chrono::{DateTime, Duration, Utc};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub const IOC_PATH: &str = "./data/ioc_feeds.json";
+pub const DEFAULT_EXPIRY_DAYS: u32 = 90;
+/// Largest feed accepted, from disk or the network
+const MAX_FEED_BYTES: u64 = 64 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IocKind {
+    Sha256,
+    Blake3,
+    Domain,
+    Ip,
+    Url,
+}
+
+impl IocKind {
+    pub fn is_hash(self) -> bool {
+        matches!(self, Self::Sha256 | Self::Blake3)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Ioc {
+    pub kind: IocKind,
+    /// Normalized value: lowercase hashes and domains
+    pub value: String,
+    /// Feed the indicator came from
+    pub source: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl Ioc {
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|at| at <= now)
+    }
+}
+
+/// Indicators read from one feed.
+#[derive(Debug, Clone, Default)]
+pub struct ParsedFeed {
+    pub indicators: Vec<Ioc>,
+    /// Entries of a kind we cannot use, e.g. MD5 hashes or email addresses
+    pub skipped: usize,
+}
+
+/// Kind and normalized form of `value`, `None` when it is not a hash,
+/// domain, IP or URL. 64-digit hex is taken as SHA-256.
+pub fn classify(value: &str) -> Option<(IocKind, String)> {
+    let value = value.trim().trim_matches(|c| c == '"' || c == '\'').trim();
+    if value.is_empty() {
+        return None;
+    }
+    if value.len() == 64 && value.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Some((IocKind::Sha256, value.to_lowercase()));
+    }
+    let lower = value.to_lowercase();
+    if lower.starts_with("http://") || lower.starts_with("https://") {
+        return Some((IocKind::Url, value.to_string()));
+    }
+    if let Ok(ip) = value.parse::<IpAddr>() {
+        return Some((IocKind::Ip, ip.to_string()));
+    }
+    let domain = lower.trim_end_matches('.');
+    let is_domain = domain.contains('.')
+        && !domain.starts_with('.')
+        && domain
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+        && domain
+            .rsplit('.')
+            .next()
+            .is_some_and(|tld| tld.len() >= 2 && tld.chars().all(|c| c.is_ascii_alphabetic()));
+    is_domain.then(|| (IocKind::Domain, domain.to_string()))
+}
+
+/// Read a STIX bundle or CSV list; anything starting with `{` is STIX.
+pub fn parse_feed(
+    content: &str,
+    source: &str,
+    now: DateTime<Utc>,
+    expiry_days: u32,
+) -> Result<ParsedFeed, String> {
+    let default_expiry = now + Duration::days(i64::from(expiry_days));
+    if content.trim_start().starts_with('{') {
+        parse_stix(content, source, default_expiry)
+    } else {
+        Ok(parse_csv(content, source, default_expiry))
+    }
+}
+
+/// Indicators of a STIX 2.1 bundle. Only equality comparisons on file
+/// hashes, domain names, IPs and URLs are understood; revoked indicators
+/// are dropped.
+pub fn parse_stix(
+    content: &str,
+    source: &str,
+    default_expiry: DateTime<Utc>,
+) -> Result<ParsedFeed, String> {
+    let bundle: serde_json::Value =
+        serde_json::from_str(content).map_err(|e| format!("Invalid STIX bundle: {e}"))?;
+    if bundle.get("type").and_then(|t| t.as_str()) != Some("bundle") {
+        return Err("Not a STIX bundle: missing \"type\": \"bundle\"".to_string());
+    }
+    let objects = bundle
+        .get("objects")
+        .and_then(|o| o.as_array())
+        .ok_or("STIX bundle has no objects")?;
+
+    let mut feed = ParsedFeed::default();
+    for object in objects {
+        if object.get("type").and_then(|t| t.as_str()) != Some("indicator")
+            || object.get("revoked").and_then(|r| r.as_bool()) == Some(true)
+        {
+            continue;
+        }
+        let pattern_type = object
+            .get("pattern_type")
+            .and_then(|t| t.as_str())
+            .unwrap_or("stix");
+        let Some(pattern) = object.get("pattern").and_then(|p| p.as_str()) else {
+            continue;
+        };
+        if pattern_type != "stix" {
+            feed.skipped += 1;
+            continue;
+        }
+        let expires_at = object
+            .get("valid_until")
+            .and_then(|v| v.as_str())
+            .and_then(|v| DateTime::parse_from_rfc3339(v).ok())
+            .map(|v| v.with_timezone(&Utc))
+            .unwrap_or(default_expiry);
+        let name = object
+            .get("name")
+            .and_then(|n| n.as_str())
+            .map(str::to_string);
+        for (path, value) in pattern_comparisons(pattern) {
+            match stix_kind(&path).and_then(|kind| normalize(kind, &value)) {
+                Some((kind, value)) => feed.indicators.push(Ioc {
+                    kind,
+                    value,
+                    source: source.to_string(),
+                    name: name.clone(),
+                    expires_at: Some(expires_at),
+                }),
+                None => feed.skipped += 1,
+            }
+        }
+    }
+    Ok(feed)
+}
+
+/// `(object path, value)` of each `path = 'value'` comparison in a STIX
+/// pattern such as `[file:hashes.'SHA-256' = '…' OR domain-name:value = '…']`.
+fn pattern_comparisons(pattern: &str) -> Vec<(String, String)> {
+    let mut comparisons = Vec::new();
+    let mut rest = pattern;
+    while let Some(eq) = rest.find('=') {
+        let path = rest[..eq]
+            .split_whitespace()
+            .last()
+            .unwrap_or_default()
+            .trim_start_matches(['[', '('])
+            .to_string();
+        let after = rest[eq + 1..].trim_start();
+        let Some(quoted) = after.strip_prefix('\'') else {
+            rest = &rest[eq + 1..];
+            continue;
+        };
+        let mut value = String::new();
+        let mut chars = quoted.char_indices();
+        let mut end = quoted.len();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\\' => value.extend(chars.next().map(|(_, c)| c)),
+                '\'' => {
+                    end = i + 1;
+                    break;
+                }
+                c => value.push(c),
+            }
+        }
+        comparisons.push((path, value));
+        rest = &quoted[end..];
+    }
+    comparisons
+}
+
+fn stix_kind(path: &str) -> Option<IocKind> {
+    let path = path.to_lowercase().replace(['\'', '"'], "");
+    match path.as_str() {
+        "file:hashes.sha-256" | "file:hashes.sha256" => Some(IocKind::Sha256),
+        "file:hashes.blake3" => Some(IocKind::Blake3),
+        "domain-name:value" => Some(IocKind::Domain),
+        "ipv4-addr:value" | "ipv6-addr:value" => Some(IocKind::Ip),
+        "url:value" => Some(IocKind::Url),
+        _ => None,
+    }
+}
+
+/// `value` normalized as `kind`, `None` when it does not look like one.
+fn normalize(kind: IocKind, value: &str) -> Option<(IocKind, String)> {
+    // Single-host CIDR blocks are plain addresses
+    let value = value
+        .trim()
+        .strip_suffix("/32")
+        .or_else(|| value.trim().strip_suffix("/128"))
+        .unwrap_or(value);
+    let (detected, normalized) = classify(value)?;
+    match (kind, detected) {
+        (IocKind::Blake3, IocKind::Sha256) => Some((IocKind::Blake3, normalized)),
+        (kind, detected) if kind == detected => Some((kind, normalized)),
+        _ => None,
+    }
+}
+
+/// Indicators of a CSV list. A header row naming a `value`, `indicator`,
+/// `ioc`, `hash`, `domain`, `ip` or `url` column selects it, along with
+/// optional `type`, `name`/`description` and `expires`/`valid_until`
+/// columns; without a header the first recognizable field of each row is
+/// used. `#` starts a comment line.
+pub fn parse_csv(content: &str, source: &str, default_expiry: DateTime<Utc>) -> ParsedFeed {
+    let mut rows = content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .peekable();
+
+    let mut columns = CsvColumns::default();
+    if let Some(header) = rows.peek() {
+        if let Some(parsed) = CsvColumns::from_header(header) {
+            columns = parsed;
+            rows.next();
+        }
+    }
+
+    let mut feed = ParsedFeed::default();
+    for row in rows {
+        let fields: Vec<&str> = row.split(',').map(|f| f.trim().trim_matches('"')).collect();
+        let field = |index: Option<usize>| {
+            index
+                .and_then(|i| fields.get(i))
+                .filter(|f| !f.is_empty())
+                .copied()
+        };
+        let classified = match columns.value {
+            Some(i) => fields.get(i).and_then(|v| classify(v)),
+            None => fields.iter().find_map(|v| classify(v)),
+        };
+        let Some((mut kind, value)) = classified else {
+            feed.skipped += 1;
+            continue;
+        };
+        if kind == IocKind::Sha256
+            && field(columns.kind).is_some_and(|t| t.eq_ignore_ascii_case("blake3"))
+        {
+            kind = IocKind::Blake3;
+        }
+        let expires_at = field(columns.expires)
+            .and_then(parse_date)
+            .unwrap_or(default_expiry);
+        feed.indicators.push(Ioc {
+            kind,
+            value,
+            source: source.to_string(),
+            name: field(columns.name).map(str::to_string),
+            expires_at: Some(expires_at),
+        });
+    }
+    feed
+}
+
+#[derive(Debug, Default)]
+struct CsvColumns {
+    value: Option<usize>,
+    kind: Option<usize>,
+    name: Option<usize>,
+    expires: Option<usize>,
+}
+
+impl CsvColumns {
+    /// Columns named by `line`, `None` when it is a data row.
+    fn from_header(line: &str) -> Option<Self> {
+        let mut columns = Self::default();
+        for (i, name) in line.split(',').enumerate() {
+            match name.trim().trim_matches('"').to_lowercase().as_str() {
+                "value" | "indicator" | "ioc" | "hash" | "sha256" | "domain" | "ip" | "url" => {
+                    columns.value.get_or_insert(i);
+                }
+                "type" | "kind" | "indicator_type" => columns.kind = Some(i),
+                "name" | "description" | "comment" => columns.name = Some(i),
+                "expires" | "expires_at" | "expiry" | "valid_until" => columns.expires = Some(i),
+                _ => {}
+            }
+        }
+        columns.value.is_some().then_some(columns)
+    }
+}
+
+/// RFC 3339 timestamp or `YYYY-MM-DD` (end of that day, UTC).
+fn parse_date(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(at) = DateTime::parse_from_rfc3339(value) {
+        return Some(at.with_timezone(&Utc));
+    }
+    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(23, 59, 59))
+        .map(|at| at.and_utc())
+}
+
+/// Contents of a local feed file or an `http(s)://` URL.
+pub fn read_feed(path_or_url: &str) -> Result<String, String> {
+    let lower = path_or_url.to_lowercase();
+    if !(lower.starts_with("http://") || lower.starts_with("https://")) {
+        let size = fs::metadata(path_or_url)
+            .map_err(|e| format!("Failed to read IOC feed {path_or_url}: {e}"))?
+            .len();
+        if size > MAX_FEED_BYTES {
+            return Err(format!("IOC feed is larger than {MAX_FEED_BYTES} bytes"));
+        }
+        return fs::read_to_string(path_or_url)
+            .map_err(|e| format!("Failed to read IOC feed {path_or_url}: {e}"));
+    }
+
+    oxide_core::network::ensure_online(path_or_url).map_err(|e| e.to_string())?;
+    let _permit =
+        oxide_core::api_governor::acquire_blocking(path_or_url).map_err(|e| e.to_string())?;
+    let client = oxide_core::network::blocking_client_builder()
+        .timeout(std::time::Duration::from_secs(60))
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {e}"))?;
+    let response = client
+        .get(path_or_url)
+        .send()
+        .map_err(|e| format!("IOC feed download failed: {e}"))?;
+    if !response.status().is_success() {
+        return Err(format!("IOC feed returned HTTP {}", response.status()));
+    }
+    if response.content_length().unwrap_or(0) > MAX_FEED_BYTES {
+        return Err(format!("IOC feed is larger than {MAX_FEED_BYTES} bytes"));
+    }
+    response
+        .text()
+        .map_err(|e| format!("Failed to read IOC feed: {e}"))
+}
+
+/// Source tag for a feed without an explicit one: the URL host or the file
+/// name without extension.
+pub fn default_source(path_or_url: &str) -> String {
+    let trimmed = path_or_url.trim();
+    if let Some((_, rest)) = trimmed.split_once("://") {
+        let host = rest.split(['/', '?', '#']).next().unwrap_or(rest);
+        return host.to_lowercase();
+    }
+    Path::new(trimmed)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| trimmed.to_string())
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IocImportReport {
+    pub source: String,
+    /// Indicators stored from this feed
+    pub imported: usize,
+    /// Indicators of the previous import from this source that were dropped
+    pub replaced: usize,
+    /// Entries that were not usable indicators
+    pub skipped: usize,
+    /// Entries that had already expired
+    pub expired: usize,
+    pub by_kind: BTreeMap<IocKind, usize>,
+    /// Live indicators across all sources
+    pub total: usize,
+}
+
+/// Imported indicators of every source, persisted as JSON.
+pub struct IocStore {
+    path: PathBuf,
+    indicators: Mutex<Vec<Ioc>>,
+}
+
+impl IocStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let indicators = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| {
+                serde_json::from_str(&content)
+                    .map_err(|e| warn!("Ignoring unreadable IOC store {path:?}: {e}"))
+                    .ok()
+            })
+            .unwrap_or_default();
+        Self {
+            path,
+            indicators: Mutex::new(indicators),
+        }
+    }
+
+    /// Replace the indicators of `feed`'s source with `feed`, dropping
+    /// expired ones from every source.
+    pub fn import(
+        &self,
+        source: &str,
+        feed: ParsedFeed,
+        now: DateTime<Utc>,
+    ) -> Result<IocImportReport, String> {
+        let mut indicators = self.indicators.lock().unwrap();
+        let before = indicators.len();
+        indicators.retain(|ioc| ioc.source != source);
+        let replaced = before - indicators.len();
+        indicators.retain(|ioc| !ioc.is_expired(now));
+
+        let mut report = IocImportReport {
+            source: source.to_string(),
+            imported: 0,
+            replaced,
+            skipped: feed.skipped,
+            expired: 0,
+            by_kind: BTreeMap::new(),
+            total: 0,
+        };
+        let mut seen = std::collections::HashSet::new();
+        for ioc in feed.indicators {
+            if ioc.is_expired(now) {
+                report.expired += 1;
+                continue;
+            }
+            if !seen.insert((ioc.kind, ioc.value.clone())) {
+                continue;
+            }
+            *report.by_kind.entry(ioc.kind).or_default() += 1;
+            report.imported += 1;
+            indicators.push(ioc);
+        }
+        report.total = indicators.len();
+        self.save(&indicators)?;
+        info!(
+            "Imported {} indicator(s) from {source} ({} replaced, {} skipped, {} expired)",
+            report.imported, report.replaced, report.skipped, report.expired
+        );
+        Ok(report)
+    }
+
+    /// Indicators that have not expired.
+    pub fn active(&self, now: DateTime<Utc>) -> Vec<Ioc> {
+        self.indicators
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|ioc| !ioc.is_expired(now))
+            .cloned()
+            .collect()
+    }
+
+    /// Live indicator matching `value` (a hash, domain, IP or URL). A
+    /// domain also matches its subdomains.
+    pub fn reputation(&self, value: &str, now: DateTime<Utc>) -> Option<Ioc> {
+        let (kind, value) = classify(value)?;
+        let indicators = self.indicators.lock().unwrap();
+        indicators
+            .iter()
+            .filter(|ioc| !ioc.is_expired(now))
+            .find(|ioc| match (kind, ioc.kind) {
+                (IocKind::Sha256, IocKind::Sha256 | IocKind::Blake3) => ioc.value == value,
+                (IocKind::Domain, IocKind::Domain) => {
+                    value == ioc.value || value.ends_with(&format!(".{}", ioc.value))
+                }
+                (IocKind::Url, IocKind::Url) => ioc.value.eq_ignore_ascii_case(&value),
+                (kind, ioc_kind) => kind == ioc_kind && ioc.value == value,
+            })
+            .cloned()
+    }
+
+    fn save(&self, indicators: &[Ioc]) -> Result<(), String> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create {parent:?}: {e}"))?;
+        }
+        let json = serde_json::to_string(indicators).map_err(|e| e.to_string())?;
+        fs::write(&self.path, json).map_err(|e| format!("Failed to save IOC store: {e}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = "E3B0C44298FC1C149AFBF4C8996FB92427AE41E4649B934CA495991B7852B855";
+
+    #[test]
+    fn parses_stix_bundles_and_csv_lists() {
+        let now = Utc::now();
+        let bundle = serde_json::json!({
+            "type": "bundle",
+            "id": "bundle--1",
+            "objects": [
+                {
+                    "type": "indicator",
+                    "name": "Loader",
+                    "pattern_type": "stix",
+                    "pattern": format!("[file:hashes.'SHA-256' = '{HASH}'] OR [domain-name:value = 'Evil.example.com']"),
+                    "valid_until": "2999-01-01T00:00:00Z"
+                },
+                {"type": "indicator", "pattern": "[file:hashes.MD5 = 'd41d8cd98f00b204e9800998ecf8427e']"},
+                {"type": "indicator", "revoked": true, "pattern": "[ipv4-addr:value = '10.0.0.1']"},
+                {"type": "malware", "name": "ignored"}
+            ]
+        });
+        let feed = parse_feed(&bundle.to_string(), "acme", now, 30).unwrap();
+        assert_eq!(feed.skipped, 1);
+        assert_eq!(feed.indicators.len(), 2);
+        assert_eq!(feed.indicators[0].kind, IocKind::Sha256);
+        assert_eq!(feed.indicators[0].value, HASH.to_lowercase());
+        assert_eq!(feed.indicators[0].name.as_deref(), Some("Loader"));
+        assert_eq!(feed.indicators[1].value, "evil.example.com");
+
+        let csv = "# weekly list\ntype,value,description,expires\n\
+                   blake3,{HASH},dropper,2000-01-01\n\
+                   ip,203.0.113.7,c2,\n\
+                   url,https://bad.example.net/payload,,\n\
+                   email,someone@example.com,,\n"
+            .replace("{HASH}", HASH);
+        let feed = parse_feed(&csv, "weekly", now, 30).unwrap();
+        assert_eq!(feed.skipped, 1);
+        let kinds: Vec<IocKind> = feed.indicators.iter().map(|i| i.kind).collect();
+        assert_eq!(kinds, [IocKind::Blake3, IocKind::Ip, IocKind::Url]);
+        assert!(feed.indicators[0].is_expired(now));
+        assert!(!feed.indicators[1].is_expired(now));
+
+        let bare = parse_feed(&format!("{HASH}\nbad.example.org\n"), "bare", now, 30).unwrap();
+        assert_eq!(bare.indicators.len(), 2);
+    }
+
+    #[test]
+    fn imports_replace_per_source_and_answer_reputation() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = IocStore::new(dir.path().join("iocs.json"));
+        let now = Utc::now();
+        let feed = |text: &str, source: &str| parse_feed(text, source, now, 30).unwrap();
+
+        let report = store
+            .import("a", feed("evil.example.com\n203.0.113.7\n", "a"), now)
+            .unwrap();
+        assert_eq!(report.imported, 2);
+        store.import("b", feed(HASH, "b"), now).unwrap();
+        assert_eq!(
+            store
+                .reputation("cdn.evil.example.com", now)
+                .map(|i| i.source),
+            Some("a".to_string())
+        );
+        assert!(store.reputation(&HASH.to_lowercase(), now).is_some());
+
+        let report = store
+            .import("a", feed("other.example.com\n", "a"), now)
+            .unwrap();
+        assert_eq!((report.replaced, report.total), (2, 2));
+        assert!(store.reputation("203.0.113.7", now).is_none());
+
+        let reloaded = IocStore::new(dir.path().join("iocs.json"));
+        assert_eq!(reloaded.active(now).len(), 2);
+        assert!(reloaded
+            .reputation("other.example.com", now + Duration::days(31))
+            .is_none());
+    }
+}
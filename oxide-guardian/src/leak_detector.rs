@@ -0,0 +1,174 @@
+//! Memory leak detection from per-process usage history.
+//!
+//! Each process name's summed memory over the window is fitted with a
+//! least-squares line. A name is flagged when the line rises faster than
+//! `min_growth_mb_per_hour`, fits the samples with an R² of at least
+//! `min_fit` (steady growth rather than spikes) and the history spans at
+//! least `min_duration_hours`.
+
+use crate::guardian::{ThreatEvent, ThreatSeverity, ThreatType};
+use chrono::{DateTime, Utc};
+use oxide_core::config::LeakDetectionConfig;
+use serde::Serialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SuspectedLeak {
+    pub process_name: String,
+    pub growth_mb_per_hour: f64,
+    /// R² of the fitted line
+    pub fit: f64,
+    /// Hours between the first and last sample
+    pub duration_hours: f64,
+    pub first_mb: f64,
+    pub current_mb: f64,
+    /// Hours until the growth uses up the memory that is available now;
+    /// `None` when available memory is unknown
+    pub hours_to_exhaustion: Option<f64>,
+    pub detected_at: DateTime<Utc>,
+}
+
+impl SuspectedLeak {
+    pub fn to_threat(&self) -> ThreatEvent {
+        let mut details = HashMap::from([
+            (
+                "growth_mb_per_hour".to_string(),
+                format!("{:.1}", self.growth_mb_per_hour),
+            ),
+            ("fit".to_string(), format!("{:.2}", self.fit)),
+            (
+                "duration_hours".to_string(),
+                format!("{:.1}", self.duration_hours),
+            ),
+            ("first_mb".to_string(), format!("{:.0}", self.first_mb)),
+            ("current_mb".to_string(), format!("{:.0}", self.current_mb)),
+        ]);
+        let exhaustion = match self.hours_to_exhaustion {
+            Some(hours) => {
+                details.insert("hours_to_exhaustion".to_string(), format!("{hours:.1}"));
+                format!(", memory exhausted in about {hours:.1} h")
+            }
+            None => String::new(),
+        };
+        ThreatEvent {
+            id: uuid::Uuid::new_v4().to_string(),
+            timestamp: self.detected_at,
+            threat_type: ThreatType::HighResourceUsage,
+            severity: ThreatSeverity::Medium,
+            description: format!(
+                "Possible memory leak in {}: {:.0} MB to {:.0} MB over {:.1} h ({:+.0} MB/h{exhaustion})",
+                self.process_name,
+                self.first_mb,
+                self.current_mb,
+                self.duration_hours,
+                self.growth_mb_per_hour
+            ),
+            process_name: Some(self.process_name.clone()),
+            process_id: None,
+            details,
+            techniques: Vec::new(),
+            risk_score: ThreatSeverity::Medium.score(),
+        }
+    }
+}
+
+/// Judge one process name's `(time, memory MB)` samples, oldest first.
+pub fn analyze(
+    process_name: &str,
+    samples: &[(DateTime<Utc>, f64)],
+    available_mb: Option<f64>,
+    cfg: &LeakDetectionConfig,
+    now: DateTime<Utc>,
+) -> Option<SuspectedLeak> {
+    let (first_at, first_mb) = *samples.first()?;
+    let (last_at, current_mb) = *samples.last()?;
+    let duration_hours = (last_at - first_at).num_milliseconds() as f64 / 3_600_000.0;
+    if duration_hours < cfg.min_duration_hours() {
+        return None;
+    }
+    let points: Vec<(f64, f64)> = samples
+        .iter()
+        .map(|(at, mb)| {
+            (
+                (*at - first_at).num_milliseconds() as f64 / 3_600_000.0,
+                *mb,
+            )
+        })
+        .collect();
+    let (slope, fit) = linear_fit(&points)?;
+    if slope < cfg.min_growth_mb_per_hour() || fit < cfg.min_fit() {
+        return None;
+    }
+    Some(SuspectedLeak {
+        process_name: process_name.to_string(),
+        growth_mb_per_hour: slope,
+        fit,
+        duration_hours,
+        first_mb,
+        current_mb,
+        hours_to_exhaustion: available_mb.map(|mb| mb.max(0.0) / slope),
+        detected_at: now,
+    })
+}
+
+/// Least-squares slope and R² of `(x, y)` points.
+fn linear_fit(points: &[(f64, f64)]) -> Option<(f64, f64)> {
+    let n = points.len() as f64;
+    if points.len() < 3 {
+        return None;
+    }
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let var_x: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    let var_y: f64 = points.iter().map(|(_, y)| (y - mean_y).powi(2)).sum();
+    if var_x <= f64::EPSILON {
+        return None;
+    }
+    let cov: f64 = points
+        .iter()
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum();
+    let slope = cov / var_x;
+    let fit = if var_y <= f64::EPSILON {
+        0.0
+    } else {
+        cov * cov / (var_x * var_y)
+    };
+    Some((slope, fit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series(
+        now: DateTime<Utc>,
+        hours: i64,
+        mb: impl Fn(i64) -> f64,
+    ) -> Vec<(DateTime<Utc>, f64)> {
+        (0..=hours * 4)
+            .map(|i| (now - chrono::Duration::minutes(15 * (hours * 4 - i)), mb(i)))
+            .collect()
+    }
+
+    #[test]
+    fn flags_steady_growth_only() {
+        let cfg = LeakDetectionConfig::default();
+        let now = Utc::now();
+
+        // 25 MB every 15 minutes with a little noise: 100 MB/h
+        let leaking = series(now, 4, |i| 400.0 + 25.0 * i as f64 + (i % 2) as f64 * 5.0);
+        let leak = analyze("leaky", &leaking, Some(2000.0), &cfg, now).unwrap();
+        assert!((leak.growth_mb_per_hour - 100.0).abs() < 5.0);
+        assert!((leak.hours_to_exhaustion.unwrap() - 20.0).abs() < 1.0);
+        assert_eq!(leak.to_threat().severity, ThreatSeverity::Medium);
+
+        // Flat, spiky and too-short histories are not leaks
+        let flat = series(now, 4, |_| 300.0);
+        assert!(analyze("flat", &flat, None, &cfg, now).is_none());
+        let spiky = series(now, 4, |i| if i % 4 == 0 { 900.0 } else { 300.0 });
+        assert!(analyze("spiky", &spiky, None, &cfg, now).is_none());
+        let short = series(now, 1, |i| 400.0 + 100.0 * i as f64);
+        assert!(analyze("short", &short, None, &cfg, now).is_none());
+    }
+}
@@ -1,11 +1,31 @@
+pub mod alerts;
+pub mod anomaly_model;
+pub mod attack;
+pub mod av_coexistence;
+pub mod baseline;
+pub mod boot_impact;
+pub mod evidence;
 pub mod external_api;
+pub mod firewall;
 pub mod guardian;
+pub mod hardware;
+pub mod integrity;
+pub mod ioc;
+pub mod leak_detector;
+pub mod memory_scan;
 pub mod monitor;
 pub mod optimizer;
+pub mod playbook;
+pub mod process_control;
 pub mod quarantine;
+pub mod remediation;
+pub mod rules;
+pub mod scan_filter;
+pub mod scan_path;
 pub mod scanner;
 pub mod security;
 pub mod signatures;
+pub mod startup;
 
 #[cfg(feature = "surrealdb-metrics")]
 pub mod metrics_collector;
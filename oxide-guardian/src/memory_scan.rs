@@ -0,0 +1,135 @@
+//! YARA scans of running process memory.
+//!
+//! Scans run on demand or automatically for processes the heuristics flag.
+//! Reading another process's memory is expensive, so every scan goes
+//! through a [`ScanLimiter`] with a per-process cooldown and a global budget.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, PidExt, ProcessExt, System, SystemExt};
+
+/// Automatic scans of the same process are at least this far apart.
+pub const PER_PROCESS_COOLDOWN: Duration = Duration::from_secs(10 * 60);
+/// At most this many scans (automatic and on-demand) per window.
+pub const MAX_SCANS_PER_WINDOW: usize = 5;
+pub const SCAN_WINDOW: Duration = Duration::from_secs(60);
+/// Upper bound for a single process scan.
+pub const SCAN_TIMEOUT_SECS: i32 = 30;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryScanReport {
+    pub pid: u32,
+    pub process_name: Option<String>,
+    /// Names of the YARA rules that matched.
+    pub matched_rules: Vec<String>,
+    pub duration_ms: u64,
+    pub scanned_at: DateTime<Utc>,
+}
+
+pub struct ScanLimiter {
+    cooldown: Duration,
+    max_per_window: usize,
+    window: Duration,
+    last_scan: HashMap<u32, Instant>,
+    recent: VecDeque<Instant>,
+}
+
+impl Default for ScanLimiter {
+    fn default() -> Self {
+        Self::new(PER_PROCESS_COOLDOWN, MAX_SCANS_PER_WINDOW, SCAN_WINDOW)
+    }
+}
+
+impl ScanLimiter {
+    pub fn new(cooldown: Duration, max_per_window: usize, window: Duration) -> Self {
+        Self {
+            cooldown,
+            max_per_window,
+            window,
+            last_scan: HashMap::new(),
+            recent: VecDeque::new(),
+        }
+    }
+
+    /// Reserve a scan slot for `pid`. On-demand scans skip the per-process
+    /// cooldown but still count against the global budget.
+    pub fn try_acquire(&mut self, pid: u32, on_demand: bool, now: Instant) -> Result<(), String> {
+        while self
+            .recent
+            .front()
+            .is_some_and(|t| now.duration_since(*t) >= self.window)
+        {
+            self.recent.pop_front();
+        }
+        if self.recent.len() >= self.max_per_window {
+            return Err(format!(
+                "Memory scan limit reached ({} per {}s); try again shortly",
+                self.max_per_window,
+                self.window.as_secs()
+            ));
+        }
+        if !on_demand {
+            if let Some(last) = self.last_scan.get(&pid) {
+                if now.duration_since(*last) < self.cooldown {
+                    return Err(format!("Process {pid} was scanned recently"));
+                }
+            }
+        }
+
+        self.recent.push_back(now);
+        self.last_scan.insert(pid, now);
+        // Forget processes whose cooldown has long expired
+        let cooldown = self.cooldown;
+        self.last_scan
+            .retain(|_, t| now.duration_since(*t) < cooldown);
+        Ok(())
+    }
+}
+
+pub fn process_name(pid: u32) -> Option<String> {
+    let pid = Pid::from_u32(pid);
+    let mut sys = System::new();
+    if !sys.refresh_process(pid) {
+        return None;
+    }
+    sys.process(pid).map(|p| p.name().to_string())
+}
+
+/// Scan the readable memory regions of `pid`; returns matched rule names.
+#[cfg(feature = "yara-detection")]
+pub fn scan_process(rules: &yara::Rules, pid: u32) -> Result<Vec<String>, String> {
+    rules
+        .scan_process(pid, SCAN_TIMEOUT_SECS)
+        .map(|matches| {
+            matches
+                .iter()
+                .map(|rule| rule.identifier.to_string())
+                .collect()
+        })
+        .map_err(|e| format!("YARA memory scan of process {pid} failed: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limiter_enforces_cooldown_and_budget() {
+        let mut limiter = ScanLimiter::new(Duration::from_secs(600), 2, Duration::from_secs(60));
+        let start = Instant::now();
+
+        assert!(limiter.try_acquire(10, false, start).is_ok());
+        // Automatic rescans of the same pid wait for the cooldown
+        assert!(limiter.try_acquire(10, false, start).is_err());
+        // On-demand scans bypass the cooldown but use the budget
+        assert!(limiter.try_acquire(10, true, start).is_ok());
+        assert!(limiter.try_acquire(11, true, start).is_err());
+
+        // Budget frees up once the window passes
+        let later = start + Duration::from_secs(61);
+        assert!(limiter.try_acquire(11, false, later).is_ok());
+        assert!(limiter.try_acquire(10, false, later).is_err());
+    }
+}
@@ -8,18 +8,24 @@
 //! - Memory usage (total, used, available, swap)
 //! - Disk I/O (read/write throughput, IOPS)
 //! - Network statistics (sent/received, active connections)
+//! - GPU utilization/VRAM, CPU temperature and battery state (see [`crate::hardware`])
 //! - Process graph (parent-child relationships)
+//! - Per-process CPU/memory samples, aggregated by process name
+//! - Per-process CPU and disk usage in the first minutes after boot (see [`crate::boot_impact`])
 //!
 //! # Collection Interval
-//! Default: 5 seconds (configurable)
+//! Default: 5 seconds (configurable), stretched while battery saver is on
+//! (see [`oxide_core::power`])
 //!
 //! # Storage
 //! All metrics are stored in SurrealDB with timestamp indexing for efficient time-range queries.
 
+use crate::boot_impact::BootCapture;
 use anyhow::{Context, Result};
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use oxide_core::power;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use sysinfo::{CpuExt, NetworkExt, NetworksExt, PidExt, ProcessExt, System, SystemExt};
 use tokio::sync::RwLock;
@@ -28,8 +34,8 @@ use tracing::{debug, error, info, warn};
 
 #[cfg(feature = "surrealdb-metrics")]
 use oxide_memory::{
-    AgentMemory, AgentType, DiskIO, MemorySource, MemoryUsage, NetworkStats, ProcessInfo,
-    ProcessStatus, SurrealBackend, SystemMetric,
+    AgentMemory, AgentType, BatteryStatus, DiskIO, GpuMetrics, MemorySource, MemoryUsage,
+    NetworkStats, ProcessInfo, ProcessSample, ProcessStatus, SurrealBackend, SystemMetric,
 };
 
 /// Configuration for metrics collector
@@ -43,10 +49,18 @@ pub struct MetricsConfig {
     pub cpu_alert_threshold: f64,
     /// Memory threshold for high-usage alerts (percentage)
     pub memory_alert_threshold: f64,
+    /// CPU temperature (°C) above which a thermal-throttling alert is raised
+    pub temperature_alert_threshold: f64,
     /// Enable disk I/O collection
     pub collect_disk_io: bool,
     /// Enable network statistics collection
     pub collect_network: bool,
+    /// Samples buffered before they are written in one batch
+    pub batch_size: usize,
+    /// Seconds between per-process usage samples
+    pub process_sample_interval_secs: u64,
+    /// Process names sampled each time, the heaviest by memory and by CPU
+    pub process_sample_limit: usize,
 }
 
 impl Default for MetricsConfig {
@@ -56,8 +70,12 @@ impl Default for MetricsConfig {
             collect_processes: true,
             cpu_alert_threshold: 90.0,
             memory_alert_threshold: 90.0,
+            temperature_alert_threshold: 90.0,
             collect_disk_io: true,
             collect_network: true,
+            batch_size: 12,
+            process_sample_interval_secs: 60,
+            process_sample_limit: 25,
         }
     }
 }
@@ -94,6 +112,14 @@ pub struct MetricsCollector {
     config: MetricsConfig,
     /// Process ID mapping (PID -> last seen timestamp)
     process_map: Arc<RwLock<HashMap<i32, chrono::DateTime<Utc>>>>,
+    /// Samples waiting for the next batch write
+    pending: Vec<SystemMetric>,
+    /// End of the last collection tick, for liveness checks
+    last_tick: Arc<Mutex<Option<DateTime<Utc>>>>,
+    /// When per-process usage was last sampled
+    last_process_sample: Option<DateTime<Utc>>,
+    /// Usage during the boot window; `None` when started after it
+    boot_capture: Arc<Mutex<Option<BootCapture>>>,
 }
 
 #[cfg(feature = "surrealdb-metrics")]
@@ -111,12 +137,65 @@ impl MetricsCollector {
 
         let mut system = System::new_all();
         system.refresh_all();
+        let boot_time =
+            DateTime::from_timestamp(system.boot_time() as i64, 0).unwrap_or_else(Utc::now);
 
         Self {
             backend,
             system: Arc::new(RwLock::new(system)),
             config,
             process_map: Arc::new(RwLock::new(HashMap::new())),
+            pending: Vec::new(),
+            last_tick: Arc::new(Mutex::new(None)),
+            last_process_sample: None,
+            boot_capture: Arc::new(Mutex::new(BootCapture::start(boot_time, Utc::now()))),
+        }
+    }
+
+    /// Seconds between collection ticks
+    pub fn interval_secs(&self) -> u64 {
+        self.config.interval_secs
+    }
+
+    /// Shared end time of the last tick. `start` holds the collector for as
+    /// long as it runs, so watchers read this handle instead.
+    pub fn heartbeat(&self) -> Arc<Mutex<Option<DateTime<Utc>>>> {
+        Arc::clone(&self.last_tick)
+    }
+
+    /// Shared capture of the boot window, read by the boot impact analysis.
+    pub fn boot_capture(&self) -> Arc<Mutex<Option<BootCapture>>> {
+        Arc::clone(&self.boot_capture)
+    }
+
+    /// Write buffered samples to the database.
+    ///
+    /// On failure the samples stay buffered for the next attempt, up to ten
+    /// batches; older samples are dropped beyond that.
+    pub async fn flush(&mut self) -> Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let batch = std::mem::take(&mut self.pending);
+        match self
+            .backend
+            .insert_system_metrics_batch(batch.clone())
+            .await
+        {
+            Ok(count) => {
+                debug!("Flushed {} buffered metrics", count);
+                Ok(())
+            }
+            Err(e) => {
+                self.pending = batch;
+                let cap = self.config.batch_size.max(1) * 10;
+                if self.pending.len() > cap {
+                    let excess = self.pending.len() - cap;
+                    self.pending.drain(..excess);
+                    warn!("Metrics buffer full; dropped {} oldest samples", excess);
+                }
+                Err(e).context("Failed to flush buffered metrics")
+            }
         }
     }
 
@@ -131,14 +210,24 @@ impl MetricsCollector {
     pub async fn start(&mut self) -> Result<()> {
         info!("Starting metrics collection loop");
         let mut ticker = interval(Duration::from_secs(self.config.interval_secs));
+        let mut skipped_ticks = 0;
 
         loop {
             ticker.tick().await;
 
-            if let Err(e) = self.collect_and_store().await {
-                error!("Failed to collect metrics: {:#}", e);
-                // Continue loop despite errors
+            // Battery saver collects on every n-th tick only; the heartbeat
+            // still advances so the watchdog does not see a stall
+            let multiplier = power::policy().metrics_interval_multiplier.max(1);
+            if skipped_ticks + 1 < multiplier {
+                skipped_ticks += 1;
+            } else {
+                skipped_ticks = 0;
+                if let Err(e) = self.collect_and_store().await {
+                    error!("Failed to collect metrics: {:#}", e);
+                    // Continue loop despite errors
+                }
             }
+            *self.last_tick.lock().unwrap_or_else(|p| p.into_inner()) = Some(Utc::now());
         }
     }
 
@@ -152,15 +241,17 @@ impl MetricsCollector {
             let mut sys = self.system.write().await;
             sys.refresh_all();
         }
+        self.record_boot_usage(timestamp).await;
 
         // Collect system-level metrics
         let metric = self.collect_system_metrics(timestamp).await?;
 
-        // Store in database
-        self.backend
-            .insert_system_metric(metric.clone())
-            .await
-            .context("Failed to store system metric")?;
+        // Live listeners get every sample; storage is written in batches
+        self.backend.publish_metric(metric.clone());
+        self.pending.push(metric.clone());
+        if self.pending.len() >= self.config.batch_size.max(1) {
+            self.flush().await?;
+        }
 
         // Check for alerts
         self.check_alerts(&metric).await;
@@ -170,6 +261,20 @@ impl MetricsCollector {
             if let Err(e) = self.collect_process_tree().await {
                 warn!("Failed to collect process tree: {:#}", e);
             }
+            let due = match self.last_process_sample {
+                Some(last) => {
+                    timestamp.signed_duration_since(last).num_seconds()
+                        >= self.config.process_sample_interval_secs as i64
+                }
+                None => true,
+            };
+            if due {
+                self.last_process_sample = Some(timestamp);
+                let samples = self.collect_process_samples(timestamp).await;
+                if let Err(e) = self.backend.insert_process_samples(samples).await {
+                    warn!("Failed to store process samples: {:#}", e);
+                }
+            }
         }
 
         debug!("Metrics collection completed successfully");
@@ -185,6 +290,8 @@ impl MetricsCollector {
 
         // CPU usage (global average)
         let cpu_usage = sys.global_cpu_info().cpu_usage() as f64;
+        // Per-core usage exposes single-core saturation the average hides
+        let core_usage: Vec<f64> = sys.cpus().iter().map(|c| c.cpu_usage() as f64).collect();
 
         // Memory usage
         let total_memory = sys.total_memory();
@@ -221,6 +328,24 @@ impl MetricsCollector {
             }
         };
 
+        // GPU, thermal and battery readings (cached, cheap to call every tick)
+        let hardware = crate::hardware::readings();
+        let gpus = hardware
+            .gpus
+            .into_iter()
+            .map(|gpu| GpuMetrics {
+                name: gpu.name,
+                utilization_percent: gpu.utilization_percent,
+                vram_used_mb: gpu.vram_used_mb,
+                vram_total_mb: gpu.vram_total_mb,
+                temperature_c: gpu.temperature_c,
+            })
+            .collect();
+        let battery = hardware.battery.map(|b| BatteryStatus {
+            percent: b.percent,
+            charging: b.charging,
+        });
+
         // Metadata
         let metadata = Some(serde_json::json!({
             "hostname": hostname::get()
@@ -236,9 +361,13 @@ impl MetricsCollector {
         Ok(SystemMetric {
             timestamp,
             cpu_usage,
+            core_usage,
             memory_usage,
             disk_io,
             network_stats,
+            gpus,
+            cpu_temperature_c: hardware.cpu_temperature_c,
+            battery,
             metadata,
         })
     }
@@ -339,6 +468,56 @@ impl MetricsCollector {
         Ok(())
     }
 
+    /// Add this tick's per-process CPU and disk usage while within the boot window.
+    async fn record_boot_usage(&self, timestamp: DateTime<Utc>) {
+        // The capture lock is not held across the await
+        let sys = self.system.read().await;
+        let mut capture = self.boot_capture.lock().unwrap_or_else(|p| p.into_inner());
+        let Some(capture) = capture.as_mut() else {
+            return;
+        };
+        if capture.complete(capture.last_sample_at) {
+            return;
+        }
+        capture.record(
+            timestamp,
+            sys.processes().values().map(|p| {
+                let disk = p.disk_usage();
+                (p.name(), p.cpu_usage(), disk.read_bytes, disk.written_bytes)
+            }),
+        );
+    }
+
+    /// Usage summed per process name, keeping the heaviest names by memory
+    /// and by CPU so the history of leaks and runaway processes is kept
+    /// without storing every short-lived process.
+    async fn collect_process_samples(&self, timestamp: DateTime<Utc>) -> Vec<ProcessSample> {
+        let sys = self.system.read().await;
+        let mut by_name: HashMap<&str, ProcessSample> = HashMap::new();
+        for process in sys.processes().values() {
+            let sample = by_name
+                .entry(process.name())
+                .or_insert_with(|| ProcessSample {
+                    timestamp,
+                    name: process.name().to_string(),
+                    instances: 0,
+                    cpu_percent: 0.0,
+                    memory_mb: 0.0,
+                });
+            sample.instances += 1;
+            sample.cpu_percent += process.cpu_usage() as f64;
+            sample.memory_mb += (process.memory() as f64) / 1024.0 / 1024.0;
+        }
+
+        let limit = self.config.process_sample_limit;
+        let mut samples: Vec<ProcessSample> = by_name.into_values().collect();
+        samples.sort_by(|a, b| b.memory_mb.total_cmp(&a.memory_mb));
+        let mut rest = samples.split_off(limit.min(samples.len()));
+        rest.sort_by(|a, b| b.cpu_percent.total_cmp(&a.cpu_percent));
+        samples.extend(rest.into_iter().take(limit).filter(|s| s.cpu_percent > 0.0));
+        samples
+    }
+
     /// Map sysinfo ProcessStatus to our enum
     fn map_process_status(&self, status: sysinfo::ProcessStatus) -> ProcessStatus {
         match status {
@@ -388,6 +567,29 @@ impl MetricsCollector {
                 error!("Failed to create alert memory: {:#}", e);
             }
         }
+
+        // High temperature alert (likely thermal throttling)
+        if let Some(temp) = metric.cpu_temperature_c {
+            if temp > self.config.temperature_alert_threshold {
+                warn!(
+                    "High CPU temperature detected: {:.1}°C (threshold: {:.1}°C)",
+                    temp, self.config.temperature_alert_threshold
+                );
+
+                if let Err(e) = self
+                    .create_alert_memory(
+                        &format!(
+                            "High CPU temperature: {temp:.1}°C at {:.2}% CPU (possible thermal throttling)",
+                            metric.cpu_usage
+                        ),
+                        metric.timestamp,
+                    )
+                    .await
+                {
+                    error!("Failed to create alert memory: {:#}", e);
+                }
+            }
+        }
     }
 
     /// Create agent memory for alert
@@ -463,8 +665,9 @@ mod tests {
 
         let mut collector = MetricsCollector::new(backend.clone(), config);
 
-        // Collect once
+        // Collect once and write the buffered sample
         collector.collect_and_store().await.unwrap();
+        collector.flush().await.unwrap();
 
         // Query metrics
         let metrics = backend
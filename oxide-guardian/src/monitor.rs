@@ -35,6 +35,10 @@ impl SystemMonitor {
     pub fn list_processes(&self) -> Vec<SystemEvent> {
         let mut events = Vec::new();
         for (pid, process) in self.sys.processes() {
+            let parent = process
+                .parent()
+                .and_then(|ppid| self.sys.process(ppid))
+                .map(|p| p.name().to_string());
             let event = SystemEvent {
                 id: Uuid::new_v4(),
                 timestamp: Utc::now(),
@@ -46,6 +50,8 @@ impl SystemMonitor {
                     "memory_usage": process.memory(),
                     "status": process.status().to_string(),
                     "command": process.cmd().join(" "),
+                    "exe": process.exe().to_string_lossy(),
+                    "parent": parent,
                 }),
             };
             events.push(event);
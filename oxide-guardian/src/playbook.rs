@@ -0,0 +1,337 @@
+//! Declarative incident response playbooks.
+//!
+//! Each playbook is a YAML (`.yaml`/`.yml`) or JSON file in the playbook
+//! directory with a trigger and ordered response steps. A trigger matches a
+//! threat when every condition given matches: `min_severity`, any of
+//! `yara_rule` (the matched rule name) and any of `process_name`, the last
+//! two as case-insensitive substring tests.
+//!
+//! ```yaml
+//! id: contain_miner
+//! name: Contain crypto miners
+//! auto_run: true
+//! trigger:
+//!   min_severity: high
+//!   process_name: [xmrig, minerd]
+//! steps:
+//!   - action: notify
+//!     message: Crypto miner contained
+//!   - action: isolate
+//!   - action: collect_evidence
+//!   - action: summarize
+//! ```
+//!
+//! Playbooks only run automatically when `auto_run` is set; otherwise they
+//! run on request. Executing the steps is left to the caller.
+
+use crate::guardian::{ThreatEvent, ThreatSeverity};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const PLAYBOOK_DIR: &str = "./data/playbooks";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Trigger {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_severity: Option<ThreatSeverity>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub yara_rule: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub process_name: Vec<String>,
+}
+
+fn matches_any(patterns: &[String], value: Option<&str>) -> bool {
+    if patterns.is_empty() {
+        return true;
+    }
+    let Some(value) = value.map(str::to_lowercase) else {
+        return false;
+    };
+    patterns
+        .iter()
+        .any(|pattern| value.contains(&pattern.to_lowercase()))
+}
+
+impl Trigger {
+    fn is_empty(&self) -> bool {
+        self.min_severity.is_none() && self.yara_rule.is_empty() && self.process_name.is_empty()
+    }
+
+    pub fn matches(&self, threat: &ThreatEvent) -> bool {
+        self.min_severity
+            .as_ref()
+            .is_none_or(|min| threat.severity >= *min)
+            && matches_any(
+                &self.yara_rule,
+                threat.details.get("rule_name").map(String::as_str),
+            )
+            && matches_any(&self.process_name, threat.process_name.as_deref())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum PlaybookStep {
+    /// Record an alert in the security audit log
+    Notify {
+        #[serde(default)]
+        message: Option<String>,
+    },
+    /// Suspend the threat's process
+    Isolate,
+    /// Scan the threat's file or process executable
+    Scan,
+    /// Save the threat and process details to the evidence directory
+    CollectEvidence,
+    /// Ask the AI providers to summarize the incident
+    Summarize {
+        #[serde(default)]
+        prompt: Option<String>,
+    },
+}
+
+impl PlaybookStep {
+    pub fn action(&self) -> &'static str {
+        match self {
+            Self::Notify { .. } => "notify",
+            Self::Isolate => "isolate",
+            Self::Scan => "scan",
+            Self::CollectEvidence => "collect_evidence",
+            Self::Summarize { .. } => "summarize",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Playbook {
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    // Run on every matching threat without being asked
+    #[serde(default)]
+    pub auto_run: bool,
+    #[serde(default)]
+    pub trigger: Trigger,
+    pub steps: Vec<PlaybookStep>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Playbook {
+    /// Parse `content`, as JSON when `path` ends in `.json`, YAML otherwise.
+    pub fn parse(content: &str, path: &Path) -> Result<Self, String> {
+        let is_json = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        let playbook: Self = if is_json {
+            serde_json::from_str(content).map_err(|e| format!("Invalid JSON playbook: {e}"))?
+        } else {
+            serde_yaml::from_str(content).map_err(|e| format!("Invalid YAML playbook: {e}"))?
+        };
+        playbook.validate()?;
+        Ok(playbook)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.id.trim().is_empty() {
+            return Err("Every playbook needs an id".to_string());
+        }
+        if self.steps.is_empty() {
+            return Err(format!("Playbook '{}' has no steps", self.id));
+        }
+        if self.auto_run && self.trigger.is_empty() {
+            return Err(format!(
+                "Playbook '{}' runs automatically and needs a trigger",
+                self.id
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn matches(&self, threat: &ThreatEvent) -> bool {
+        self.enabled && self.trigger.matches(threat)
+    }
+}
+
+/// Playbooks found in a directory, plus the files that failed to load.
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct PlaybookLibrary {
+    pub dir: String,
+    pub playbooks: Vec<Playbook>,
+    pub errors: Vec<String>,
+}
+
+fn is_playbook_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ["yaml", "yml", "json"].contains(&ext.to_lowercase().as_str()))
+}
+
+impl PlaybookLibrary {
+    /// Load every playbook in `dir`; a missing directory is an empty library.
+    pub fn load(dir: &Path) -> Self {
+        let mut library = Self {
+            dir: dir.display().to_string(),
+            ..Self::default()
+        };
+        let Ok(entries) = fs::read_dir(dir) else {
+            return library;
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| is_playbook_file(path))
+            .collect();
+        paths.sort();
+
+        let mut ids = HashSet::new();
+        for path in paths {
+            let parsed = fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|content| Playbook::parse(&content, &path));
+            match parsed {
+                Ok(playbook) if !ids.insert(playbook.id.clone()) => library.errors.push(format!(
+                    "{}: duplicate playbook id '{}'",
+                    path.display(),
+                    playbook.id
+                )),
+                Ok(playbook) => library.playbooks.push(playbook),
+                Err(e) => library.errors.push(format!("{}: {e}", path.display())),
+            }
+        }
+        library
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Playbook> {
+        self.playbooks.iter().find(|p| p.id == id)
+    }
+
+    /// Enabled playbooks that run on their own for `threat`.
+    pub fn auto_run_for<'a>(
+        &'a self,
+        threat: &'a ThreatEvent,
+    ) -> impl Iterator<Item = &'a Playbook> {
+        self.playbooks
+            .iter()
+            .filter(move |p| p.auto_run && p.matches(threat))
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StepStatus {
+    /// Dry run: what would have happened
+    Planned,
+    Succeeded,
+    Failed,
+    Skipped,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct StepResult {
+    pub step: PlaybookStep,
+    pub status: StepStatus,
+    pub detail: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct PlaybookRun {
+    pub playbook_id: String,
+    pub threat_id: Option<String>,
+    pub dry_run: bool,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub steps: Vec<StepResult>,
+}
+
+impl PlaybookRun {
+    pub fn succeeded(&self) -> bool {
+        self.steps.iter().all(|s| s.status != StepStatus::Failed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::guardian::ThreatType;
+    use std::collections::HashMap;
+
+    fn threat(severity: ThreatSeverity, process: &str) -> ThreatEvent {
+        ThreatEvent {
+            id: "t1".to_string(),
+            timestamp: Utc::now(),
+            threat_type: ThreatType::MalwareSignature,
+            risk_score: severity.score(),
+            severity,
+            description: "YARA rule match: CoinMiner".to_string(),
+            process_name: Some(process.to_string()),
+            process_id: None,
+            details: HashMap::from([("rule_name".to_string(), "CoinMiner_XMRig".to_string())]),
+            techniques: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn yaml_playbook_triggers_on_severity_rule_and_process() {
+        let yaml = r#"
+id: contain_miner
+auto_run: true
+trigger:
+  min_severity: high
+  yara_rule: [coinminer]
+  process_name: [xmrig]
+steps:
+  - action: notify
+    message: Miner found
+  - action: isolate
+  - action: summarize
+"#;
+        let playbook = Playbook::parse(yaml, Path::new("miner.yaml")).unwrap();
+        assert_eq!(playbook.steps.len(), 3);
+        assert_eq!(
+            playbook.steps[0],
+            PlaybookStep::Notify {
+                message: Some("Miner found".to_string())
+            }
+        );
+
+        assert!(playbook.matches(&threat(ThreatSeverity::Critical, "XMRig.exe")));
+        assert!(!playbook.matches(&threat(ThreatSeverity::Medium, "xmrig")));
+        assert!(!playbook.matches(&threat(ThreatSeverity::High, "notepad.exe")));
+
+        let json = r#"{"id": "bad", "auto_run": true, "steps": [{"action": "scan"}]}"#;
+        assert!(Playbook::parse(json, Path::new("bad.json"))
+            .unwrap_err()
+            .contains("needs a trigger"));
+    }
+
+    #[test]
+    fn library_reports_bad_and_duplicate_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let playbook = r#"{"id": "scan", "steps": [{"action": "scan"}]}"#;
+        fs::write(dir.path().join("a.json"), playbook).unwrap();
+        fs::write(dir.path().join("b.json"), playbook).unwrap();
+        fs::write(dir.path().join("c.yml"), "id: [").unwrap();
+        fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+
+        let library = PlaybookLibrary::load(dir.path());
+        assert_eq!(library.playbooks.len(), 1);
+        assert!(library.get("scan").is_some());
+        assert_eq!(library.errors.len(), 2);
+        assert!(library.errors[0].contains("duplicate"));
+
+        let threat = threat(ThreatSeverity::High, "xmrig");
+        assert_eq!(library.auto_run_for(&threat).count(), 0);
+        assert!(PlaybookLibrary::load(&dir.path().join("missing"))
+            .playbooks
+            .is_empty());
+    }
+}
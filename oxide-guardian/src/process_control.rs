@@ -0,0 +1,334 @@
+//! Terminate, suspend and resume processes with safety checks.
+//!
+//! Every operation first resolves the target and refuses processes the OS
+//! needs to stay up (kernel and session managers, service hosts, init), the
+//! app itself and its parent. Callers that picked the process earlier pass
+//! the [`ProcessIdentity`] they saw, so a pid the OS has since reused for
+//! another process is refused too. Authorization and audit logging are left
+//! to the caller.
+
+use serde::{Deserialize, Serialize};
+use sysinfo::{Pid, PidExt, ProcessExt, System, SystemExt};
+
+/// Lowercased names of processes that must never be stopped.
+const CRITICAL_PROCESSES: &[&str] = &[
+    // Windows
+    "system",
+    "registry",
+    "memory compression",
+    "smss.exe",
+    "csrss.exe",
+    "wininit.exe",
+    "winlogon.exe",
+    "services.exe",
+    "lsass.exe",
+    "lsaiso.exe",
+    "svchost.exe",
+    "dwm.exe",
+    "fontdrvhost.exe",
+    // Linux and macOS
+    "init",
+    "systemd",
+    "kthreadd",
+    "launchd",
+    "kernel_task",
+    "loginwindow",
+    "windowserver",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProcessAction {
+    Kill,
+    Suspend,
+    Resume,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessTarget {
+    pub pid: u32,
+    pub name: String,
+    pub exe: Option<String>,
+    /// Seconds since the Unix epoch
+    pub start_time: u64,
+}
+
+/// What a process was when it was picked. Only the fields that are set are
+/// compared, so callers pass whatever they know.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProcessIdentity {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub exe: Option<String>,
+    /// Seconds since the Unix epoch
+    #[serde(default)]
+    pub start_time: Option<u64>,
+}
+
+impl ProcessIdentity {
+    /// Identity of running process `pid`.
+    pub fn capture(pid: u32) -> Option<Self> {
+        let mut sys = System::new();
+        let target = Pid::from_u32(pid);
+        sys.refresh_process(target);
+        sys.process(target).map(|process| {
+            let exe = process.exe().to_string_lossy().to_string();
+            Self {
+                name: Some(process.name().to_string()),
+                exe: (!exe.is_empty()).then_some(exe),
+                start_time: Some(process.start_time()),
+            }
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.exe.is_none() && self.start_time.is_none()
+    }
+
+    /// Why `target` is not the process this identity describes, if it is not.
+    pub fn mismatch(&self, target: &ProcessTarget) -> Option<String> {
+        let label = format!("{} ({})", target.name, target.pid);
+        if self.start_time.is_some_and(|t| t != target.start_time) {
+            return Some(format!("{label} started after it was selected"));
+        }
+        if self.name.as_deref().is_some_and(|name| name != target.name) {
+            return Some(format!(
+                "{label} is not {}",
+                self.name.as_deref().unwrap_or_default()
+            ));
+        }
+        match (&self.exe, &target.exe) {
+            (Some(expected), Some(actual)) if !paths_equal(expected, actual) => {
+                Some(format!("{label} runs {actual}, not {expected}"))
+            }
+            (Some(expected), None) => Some(format!(
+                "{label} has no readable executable to compare with {expected}"
+            )),
+            _ => None,
+        }
+    }
+}
+
+fn paths_equal(a: &str, b: &str) -> bool {
+    if cfg!(windows) {
+        a.eq_ignore_ascii_case(b)
+    } else {
+        a == b
+    }
+}
+
+/// Why `pid`/`name` must not be touched, if it must not.
+pub fn protection_reason(pid: u32, name: &str) -> Option<String> {
+    // 0-4 are the idle, init and Windows System processes
+    if pid <= 4 {
+        return Some(format!("{name} ({pid}) is a core OS process"));
+    }
+    if pid == std::process::id() {
+        return Some("Oxide Pilot cannot stop itself".to_string());
+    }
+    let lower = name.to_lowercase();
+    if CRITICAL_PROCESSES.contains(&lower.as_str()) {
+        return Some(format!("{name} ({pid}) is a critical system process"));
+    }
+    None
+}
+
+fn own_parent(sys: &System) -> Option<Pid> {
+    sys.process(Pid::from_u32(std::process::id()))
+        .and_then(|p| p.parent())
+}
+
+/// Running process `pid`, or why it may not be acted on. With `expected`,
+/// a process that no longer matches it is refused as a reused pid.
+pub fn resolve_target(
+    pid: u32,
+    expected: Option<&ProcessIdentity>,
+) -> Result<ProcessTarget, String> {
+    let mut sys = System::new();
+    let target = Pid::from_u32(pid);
+    sys.refresh_process(target);
+    sys.refresh_process(Pid::from_u32(std::process::id()));
+    let process = sys
+        .process(target)
+        .ok_or_else(|| format!("Process {pid} is not running"))?;
+    let name = process.name().to_string();
+    if let Some(reason) = protection_reason(pid, &name) {
+        return Err(format!("Refusing to stop protected process: {reason}"));
+    }
+    if own_parent(&sys) == Some(target) {
+        return Err(format!(
+            "Refusing to stop {name} ({pid}): it launched Oxide Pilot"
+        ));
+    }
+    let exe = process.exe().to_string_lossy().to_string();
+    let target = ProcessTarget {
+        pid,
+        name,
+        exe: (!exe.is_empty()).then_some(exe),
+        start_time: process.start_time(),
+    };
+    if let Some(reason) = expected.and_then(|identity| identity.mismatch(&target)) {
+        return Err(format!(
+            "Refusing to act on process {pid}: the pid was reused ({reason})"
+        ));
+    }
+    Ok(target)
+}
+
+/// Run `action` on `pid` after the safety checks.
+pub fn control_process(
+    pid: u32,
+    action: ProcessAction,
+    expected: Option<&ProcessIdentity>,
+) -> Result<String, String> {
+    let target = resolve_target(pid, expected)?;
+    let label = format!("{} ({pid})", target.name);
+    match action {
+        ProcessAction::Kill => kill(pid, &label),
+        ProcessAction::Suspend => set_suspended(pid, true, &label),
+        ProcessAction::Resume => set_suspended(pid, false, &label),
+    }
+}
+
+pub fn kill_process(pid: u32, expected: Option<&ProcessIdentity>) -> Result<String, String> {
+    control_process(pid, ProcessAction::Kill, expected)
+}
+
+pub fn suspend_process(pid: u32, expected: Option<&ProcessIdentity>) -> Result<String, String> {
+    control_process(pid, ProcessAction::Suspend, expected)
+}
+
+pub fn resume_process(pid: u32, expected: Option<&ProcessIdentity>) -> Result<String, String> {
+    control_process(pid, ProcessAction::Resume, expected)
+}
+
+fn kill(pid: u32, label: &str) -> Result<String, String> {
+    let mut sys = System::new();
+    let pid = Pid::from_u32(pid);
+    sys.refresh_process(pid);
+    match sys.process(pid) {
+        Some(process) if process.kill() => Ok(format!("Terminated {label}")),
+        Some(_) => Err(format!("Failed to terminate {label}")),
+        None => Err(format!("{label} exited before it could be terminated")),
+    }
+}
+
+#[cfg(not(windows))]
+fn set_suspended(pid: u32, suspend: bool, label: &str) -> Result<String, String> {
+    use sysinfo::Signal;
+
+    let mut sys = System::new();
+    let pid = Pid::from_u32(pid);
+    sys.refresh_process(pid);
+    let process = sys
+        .process(pid)
+        .ok_or_else(|| format!("{label} is no longer running"))?;
+    let (signal, verb) = if suspend {
+        (Signal::Stop, "Suspended")
+    } else {
+        (Signal::Continue, "Resumed")
+    };
+    match process.kill_with(signal) {
+        Some(true) => Ok(format!("{verb} {label}")),
+        Some(false) => Err(format!("Failed to signal {label}")),
+        None => Err("Suspending processes is not supported on this platform".to_string()),
+    }
+}
+
+/// Windows has no process-wide suspend in the public API, so every thread
+/// of the process is suspended (or resumed) individually.
+#[cfg(windows)]
+fn set_suspended(pid: u32, suspend: bool, label: &str) -> Result<String, String> {
+    use winapi::um::handleapi::{CloseHandle, INVALID_HANDLE_VALUE};
+    use winapi::um::processthreadsapi::{OpenThread, ResumeThread, SuspendThread};
+    use winapi::um::tlhelp32::{
+        CreateToolhelp32Snapshot, Thread32First, Thread32Next, TH32CS_SNAPTHREAD, THREADENTRY32,
+    };
+    use winapi::um::winnt::THREAD_SUSPEND_RESUME;
+
+    let mut touched = 0usize;
+    // SAFETY: plain Win32 calls; every handle opened here is closed here
+    unsafe {
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPTHREAD, 0);
+        if snapshot == INVALID_HANDLE_VALUE {
+            return Err(format!("Failed to list threads of {label}"));
+        }
+        let mut entry: THREADENTRY32 = std::mem::zeroed();
+        entry.dwSize = std::mem::size_of::<THREADENTRY32>() as u32;
+        let mut more = Thread32First(snapshot, &mut entry) != 0;
+        while more {
+            if entry.th32OwnerProcessID == pid {
+                let thread = OpenThread(THREAD_SUSPEND_RESUME, 0, entry.th32ThreadID);
+                if !thread.is_null() {
+                    let previous = if suspend {
+                        SuspendThread(thread)
+                    } else {
+                        ResumeThread(thread)
+                    };
+                    if previous != u32::MAX {
+                        touched += 1;
+                    }
+                    CloseHandle(thread);
+                }
+            }
+            more = Thread32Next(snapshot, &mut entry) != 0;
+        }
+        CloseHandle(snapshot);
+    }
+    match (touched, suspend) {
+        (0, _) => Err(format!("Failed to access the threads of {label}")),
+        (n, true) => Ok(format!("Suspended {label} ({n} threads)")),
+        (n, false) => Ok(format!("Resumed {label} ({n} threads)")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn critical_processes_are_protected() {
+        assert!(protection_reason(4, "System").is_some());
+        assert!(protection_reason(1, "systemd").is_some());
+        assert!(protection_reason(812, "LSASS.EXE").is_some());
+        assert!(protection_reason(std::process::id(), "oxide-pilot").is_some());
+        assert!(protection_reason(4242, "miner.exe").is_none());
+
+        assert!(resolve_target(std::process::id(), None).is_err());
+        assert!(kill_process(999_999_999, None)
+            .unwrap_err()
+            .contains("not running"));
+    }
+
+    #[test]
+    fn reused_pids_are_detected() {
+        let target = ProcessTarget {
+            pid: 4242,
+            name: "miner".to_string(),
+            exe: Some("/tmp/miner".to_string()),
+            start_time: 1_700_000_000,
+        };
+        let seen = ProcessIdentity {
+            name: Some("miner".to_string()),
+            exe: Some("/tmp/miner".to_string()),
+            start_time: Some(1_700_000_000),
+        };
+        assert!(seen.mismatch(&target).is_none());
+        assert!(ProcessIdentity::default().mismatch(&target).is_none());
+
+        let restarted = ProcessIdentity {
+            start_time: Some(1_700_000_500),
+            ..seen.clone()
+        };
+        assert!(restarted.mismatch(&target).is_some());
+        let other_program = ProcessIdentity {
+            exe: Some("/usr/bin/editor".to_string()),
+            ..Default::default()
+        };
+        assert!(other_program.mismatch(&target).is_some());
+
+        let own = ProcessIdentity::capture(std::process::id()).unwrap();
+        assert!(own.start_time.is_some() && !own.is_empty());
+    }
+}
@@ -0,0 +1,625 @@
+//! Remediation engine: turns threat findings into concrete actions that only
+//! run after explicit user approval.
+//!
+//! Findings are mapped to [`RemediationKind`]s and queued as pending
+//! [`RemediationAction`]s. The UI lists the queue, and approving an action
+//! executes it through a [`RemediationExecutor`] and records the outcome.
+//! Process threats from Guardian's detector are linked the same way through
+//! [`RemediationEngine::propose_for_threat`]. Process actions record the
+//! process's identity when they are queued and refuse to run if the pid
+//! belongs to a different process by the time they are approved.
+
+use crate::firewall::FirewallManager;
+use crate::guardian::{ThreatEvent, ThreatSeverity, ThreatType};
+use crate::process_control::{self, ProcessAction, ProcessIdentity};
+use chrono::{DateTime, Utc};
+use log::{info, warn};
+use oxide_core::severity::Severity;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// Completed actions kept for the history view.
+const MAX_HISTORY: usize = 500;
+
+/// Input to the planner; mirrors the fields of a consensus finding.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemediationFinding {
+    pub id: String,
+    pub kind: String,
+    pub severity: String,
+    pub summary: String,
+    #[serde(default)]
+    pub indicators: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RemediationKind {
+    KillProcess {
+        pid: u32,
+        #[serde(default)]
+        process: ProcessIdentity,
+    },
+    SuspendProcess {
+        pid: u32,
+        #[serde(default)]
+        process: ProcessIdentity,
+    },
+    QuarantineFile {
+        path: String,
+    },
+    DisableStartupEntry {
+        name: String,
+    },
+    IsolateNetwork {
+        pid: u32,
+        #[serde(default)]
+        process: ProcessIdentity,
+    },
+}
+
+impl RemediationKind {
+    pub fn kill(pid: u32) -> Self {
+        Self::KillProcess {
+            pid,
+            process: ProcessIdentity::default(),
+        }
+    }
+
+    pub fn suspend(pid: u32) -> Self {
+        Self::SuspendProcess {
+            pid,
+            process: ProcessIdentity::default(),
+        }
+    }
+
+    pub fn isolate(pid: u32) -> Self {
+        Self::IsolateNetwork {
+            pid,
+            process: ProcessIdentity::default(),
+        }
+    }
+
+    /// Target pid and recorded identity of a process action.
+    fn process_mut(&mut self) -> Option<(u32, &mut ProcessIdentity)> {
+        match self {
+            Self::KillProcess { pid, process }
+            | Self::SuspendProcess { pid, process }
+            | Self::IsolateNetwork { pid, process } => Some((*pid, process)),
+            Self::QuarantineFile { .. } | Self::DisableStartupEntry { .. } => None,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        let process = |pid: &u32, identity: &ProcessIdentity| match &identity.name {
+            Some(name) => format!("process {name} ({pid})"),
+            None => format!("process {pid}"),
+        };
+        match self {
+            Self::KillProcess { pid, process: p } => format!("Terminate {}", process(pid, p)),
+            Self::SuspendProcess { pid, process: p } => format!("Suspend {}", process(pid, p)),
+            Self::QuarantineFile { path } => format!("Quarantine file {path}"),
+            Self::DisableStartupEntry { name } => format!("Disable startup entry {name}"),
+            Self::IsolateNetwork { pid, process: p } => {
+                format!("Block network access for {}", process(pid, p))
+            }
+        }
+    }
+}
+
+/// The identity recorded for `pid`; actions without one are refused rather
+/// than run against whatever holds the pid now.
+fn recorded(pid: u32, process: &ProcessIdentity) -> Result<&ProcessIdentity, String> {
+    if process.is_empty() {
+        Err(format!(
+            "Process {pid} was not running when the action was proposed"
+        ))
+    } else {
+        Ok(process)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ActionStatus {
+    Pending,
+    Rejected,
+    Succeeded,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemediationAction {
+    pub id: String,
+    pub kind: RemediationKind,
+    pub description: String,
+    pub finding_id: String,
+    pub severity: String,
+    pub rationale: String,
+    pub status: ActionStatus,
+    pub created_at: DateTime<Utc>,
+    pub decided_at: Option<DateTime<Utc>>,
+    pub reason: Option<String>,
+    pub outcome: Option<String>,
+}
+
+/// Performs approved actions. Abstracted so tests don't touch the system.
+pub trait RemediationExecutor {
+    fn execute(&self, kind: &RemediationKind) -> Result<String, String>;
+}
+
+/// Executes actions against the local machine.
+pub struct SystemExecutor {
+    pub quarantine_dir: Option<PathBuf>,
+    pub firewall: Option<Arc<FirewallManager>>,
+}
+
+impl RemediationExecutor for SystemExecutor {
+    fn execute(&self, kind: &RemediationKind) -> Result<String, String> {
+        match kind {
+            RemediationKind::KillProcess { pid, process } => process_control::control_process(
+                *pid,
+                ProcessAction::Kill,
+                Some(recorded(*pid, process)?),
+            ),
+            RemediationKind::SuspendProcess { pid, process } => process_control::control_process(
+                *pid,
+                ProcessAction::Suspend,
+                Some(recorded(*pid, process)?),
+            ),
+            RemediationKind::QuarantineFile { path } => {
+                let dir = self
+                    .quarantine_dir
+                    .as_ref()
+                    .ok_or("No quarantine directory configured")?;
+                crate::quarantine::move_to_quarantine(path, dir)
+                    .map(|dest| format!("Moved to {dest}"))
+            }
+            RemediationKind::DisableStartupEntry { name } => {
+                crate::startup::disable_startup_item(name)
+            }
+            RemediationKind::IsolateNetwork { pid, process } => {
+                let firewall = self.firewall.as_ref().ok_or("No firewall available")?;
+                firewall
+                    .block_process(
+                        Some(*pid),
+                        None,
+                        Some("Remediation".to_string()),
+                        Some(recorded(*pid, process)?),
+                    )
+                    .map(|rule| format!("Firewall rule {} blocks process {pid}", rule.id))
+            }
+        }
+    }
+}
+
+fn looks_like_path(value: &str) -> bool {
+    value.starts_with('/') || value.get(1..3) == Some(":\\")
+}
+
+/// Map a finding to candidate actions. Killing a process needs high
+/// severity; quarantine and startup changes need at least medium.
+pub fn plan_actions(finding: &RemediationFinding) -> Vec<RemediationKind> {
+    // Unknown labels from model output get the most cautious plan
+    let severity: Severity = finding.severity.parse().unwrap_or(Severity::Low);
+    let mut actions: Vec<RemediationKind> = Vec::new();
+
+    for indicator in &finding.indicators {
+        let indicator = indicator.trim();
+        // Network findings cut the process off before anything harsher
+        if finding.kind == "network" && severity >= Severity::Medium {
+            if let Some(("pid" | "proc", value)) = indicator.split_once(':') {
+                if let Ok(pid) = value.trim().parse::<u32>() {
+                    let action = RemediationKind::isolate(pid);
+                    if !actions.contains(&action) {
+                        actions.push(action);
+                    }
+                }
+            }
+        }
+        let action = match indicator.split_once(':') {
+            Some(("pid" | "proc", value)) if severity >= Severity::High => {
+                value.trim().parse::<u32>().ok().map(RemediationKind::kill)
+            }
+            Some(("path" | "file", value)) if severity >= Severity::Medium => {
+                Some(RemediationKind::QuarantineFile {
+                    path: value.trim().to_string(),
+                })
+            }
+            Some(("startup" | "autorun" | "service" | "task", value))
+                if severity >= Severity::Medium =>
+            {
+                Some(RemediationKind::DisableStartupEntry {
+                    name: value.trim().to_string(),
+                })
+            }
+            _ if finding.kind == "file"
+                && severity >= Severity::Medium
+                && looks_like_path(indicator) =>
+            {
+                Some(RemediationKind::QuarantineFile {
+                    path: indicator.to_string(),
+                })
+            }
+            _ => None,
+        };
+        if let Some(action) = action {
+            if !actions.contains(&action) {
+                actions.push(action);
+            }
+        }
+    }
+    actions
+}
+
+/// Actions suggested for a detector threat: suspending for medium
+/// severity; suspending, isolating from the network or terminating for high
+/// and critical. Unauthorized network access from medium severity up gets
+/// network isolation only. Resource spikes and threats without a process,
+/// or on a protected process, get no suggestion.
+pub fn plan_threat_actions(threat: &ThreatEvent) -> Vec<RemediationKind> {
+    let Some(pid) = threat.process_id else {
+        return Vec::new();
+    };
+    let network = match threat.threat_type {
+        ThreatType::SuspiciousProcess | ThreatType::MalwareSignature => false,
+        ThreatType::UnauthorizedNetworkAccess => true,
+        _ => return Vec::new(),
+    };
+    let name = threat.process_name.as_deref().unwrap_or_default();
+    if process_control::protection_reason(pid, name).is_some() {
+        return Vec::new();
+    }
+    match threat.severity {
+        ThreatSeverity::Low => Vec::new(),
+        _ if network => vec![RemediationKind::isolate(pid)],
+        ThreatSeverity::Medium => vec![RemediationKind::suspend(pid)],
+        ThreatSeverity::High | ThreatSeverity::Critical => vec![
+            RemediationKind::suspend(pid),
+            RemediationKind::isolate(pid),
+            RemediationKind::kill(pid),
+        ],
+    }
+}
+
+/// Fill in the identity of a process action's target as it runs now, unless
+/// it contradicts the name the finding already gave.
+fn record_identity(kind: &mut RemediationKind) {
+    if let Some((pid, process)) = kind.process_mut() {
+        if let Some(current) = ProcessIdentity::capture(pid) {
+            if process.name.is_none() || process.name == current.name {
+                *process = current;
+            }
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct RemediationEngine {
+    actions: Arc<Mutex<Vec<RemediationAction>>>,
+}
+
+impl RemediationEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue actions for `finding`, skipping ones already pending.
+    /// Returns the newly queued actions.
+    pub fn propose(&self, finding: &RemediationFinding) -> Vec<RemediationAction> {
+        self.queue(finding, plan_actions(finding))
+    }
+
+    /// Queue the suggestions of [`plan_threat_actions`], linked to the
+    /// threat by its id.
+    pub fn propose_for_threat(&self, threat: &ThreatEvent) -> Vec<RemediationAction> {
+        let finding = RemediationFinding {
+            id: threat.id.clone(),
+            kind: "process".to_string(),
+            severity: Severity::from(threat.severity.clone()).to_string(),
+            summary: threat.description.clone(),
+            indicators: threat
+                .process_id
+                .map(|pid| vec![format!("pid:{pid}")])
+                .unwrap_or_default(),
+        };
+        // The detector saw the process under this name; a pid that now
+        // belongs to something else keeps only the name and so never runs
+        let planned = plan_threat_actions(threat)
+            .into_iter()
+            .map(|mut kind| {
+                if let Some((_, process)) = kind.process_mut() {
+                    process.name = threat.process_name.clone();
+                }
+                kind
+            })
+            .collect();
+        self.queue(&finding, planned)
+    }
+
+    fn queue(
+        &self,
+        finding: &RemediationFinding,
+        planned: Vec<RemediationKind>,
+    ) -> Vec<RemediationAction> {
+        let Ok(mut actions) = self.actions.lock() else {
+            return Vec::new();
+        };
+
+        let mut added = Vec::new();
+        for mut kind in planned {
+            record_identity(&mut kind);
+            let duplicate = actions
+                .iter()
+                .any(|a| a.status == ActionStatus::Pending && a.kind == kind);
+            if duplicate {
+                continue;
+            }
+            let action = RemediationAction {
+                id: uuid::Uuid::new_v4().to_string(),
+                description: kind.describe(),
+                kind,
+                finding_id: finding.id.clone(),
+                severity: finding.severity.clone(),
+                rationale: finding.summary.clone(),
+                status: ActionStatus::Pending,
+                created_at: Utc::now(),
+                decided_at: None,
+                reason: None,
+                outcome: None,
+            };
+            info!("Remediation proposed: {}", action.description);
+            actions.push(action.clone());
+            added.push(action);
+        }
+        added
+    }
+
+    pub fn pending(&self) -> Vec<RemediationAction> {
+        self.actions
+            .lock()
+            .map(|a| {
+                a.iter()
+                    .filter(|a| a.status == ActionStatus::Pending)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Decided actions, newest first.
+    pub fn history(&self, limit: usize) -> Vec<RemediationAction> {
+        self.actions
+            .lock()
+            .map(|a| {
+                a.iter()
+                    .rev()
+                    .filter(|a| a.status != ActionStatus::Pending)
+                    .take(limit)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Approve (and execute) or reject a pending action.
+    pub fn respond(
+        &self,
+        action_id: &str,
+        approved: bool,
+        reason: Option<String>,
+        executor: &dyn RemediationExecutor,
+    ) -> Result<RemediationAction, String> {
+        let kind = {
+            let mut actions = self.actions.lock().map_err(|e| e.to_string())?;
+            let action = actions
+                .iter_mut()
+                .find(|a| a.id == action_id)
+                .ok_or_else(|| format!("Remediation action {action_id} not found"))?;
+            if action.status != ActionStatus::Pending {
+                return Err(format!(
+                    "Remediation action {action_id} was already decided"
+                ));
+            }
+            action.decided_at = Some(Utc::now());
+            action.reason = reason;
+            if !approved {
+                action.status = ActionStatus::Rejected;
+                info!("Remediation rejected: {}", action.description);
+                return Ok(action.clone());
+            }
+            action.kind.clone()
+        };
+
+        // Run without holding the lock; killing or moving files can be slow
+        let result = executor.execute(&kind);
+
+        let mut actions = self.actions.lock().map_err(|e| e.to_string())?;
+        let action = actions
+            .iter_mut()
+            .find(|a| a.id == action_id)
+            .ok_or_else(|| format!("Remediation action {action_id} not found"))?;
+        match result {
+            Ok(outcome) => {
+                info!("Remediation succeeded: {outcome}");
+                action.status = ActionStatus::Succeeded;
+                action.outcome = Some(outcome);
+            }
+            Err(error) => {
+                warn!("Remediation failed: {} - {error}", action.description);
+                action.status = ActionStatus::Failed;
+                action.outcome = Some(error);
+            }
+        }
+        let decided = action.clone();
+        Self::trim_history(&mut actions);
+        Ok(decided)
+    }
+
+    fn trim_history(actions: &mut Vec<RemediationAction>) {
+        let decided = actions
+            .iter()
+            .filter(|a| a.status != ActionStatus::Pending)
+            .count();
+        let mut excess = decided.saturating_sub(MAX_HISTORY);
+        actions.retain(|a| {
+            if excess > 0 && a.status != ActionStatus::Pending {
+                excess -= 1;
+                false
+            } else {
+                true
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FakeExecutor(Result<String, String>);
+
+    impl RemediationExecutor for FakeExecutor {
+        fn execute(&self, _kind: &RemediationKind) -> Result<String, String> {
+            self.0.clone()
+        }
+    }
+
+    fn finding(severity: &str, indicators: &[&str]) -> RemediationFinding {
+        RemediationFinding {
+            id: "f1".to_string(),
+            kind: "process".to_string(),
+            severity: severity.to_string(),
+            summary: "Suspicious miner".to_string(),
+            indicators: indicators.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn plans_actions_by_severity() {
+        let indicators = [
+            "pid:4242",
+            "path:/tmp/x.bin",
+            "startup:miner.service",
+            "domain:x.io",
+        ];
+        assert_eq!(
+            plan_actions(&finding("critical", &indicators)),
+            vec![
+                RemediationKind::kill(4242),
+                RemediationKind::QuarantineFile {
+                    path: "/tmp/x.bin".to_string()
+                },
+                RemediationKind::DisableStartupEntry {
+                    name: "miner.service".to_string()
+                },
+            ]
+        );
+        // Medium severity never kills processes
+        assert_eq!(plan_actions(&finding("medium", &indicators)).len(), 2);
+        assert!(plan_actions(&finding("low", &indicators)).is_empty());
+    }
+
+    #[test]
+    fn approval_executes_and_records_outcome() {
+        let engine = RemediationEngine::new();
+        let queued = engine.propose(&finding("high", &["pid:4242", "pid:4243"]));
+        assert_eq!(queued.len(), 2);
+        // Same finding again doesn't duplicate pending actions
+        assert!(engine.propose(&finding("high", &["pid:4242"])).is_empty());
+
+        let done = engine
+            .respond(
+                &queued[0].id,
+                true,
+                None,
+                &FakeExecutor(Ok("killed".into())),
+            )
+            .unwrap();
+        assert_eq!(done.status, ActionStatus::Succeeded);
+        assert_eq!(done.outcome.as_deref(), Some("killed"));
+
+        let rejected = engine
+            .respond(
+                &queued[1].id,
+                false,
+                Some("false positive".into()),
+                &FakeExecutor(Ok(String::new())),
+            )
+            .unwrap();
+        assert_eq!(rejected.status, ActionStatus::Rejected);
+
+        assert!(engine.pending().is_empty());
+        assert_eq!(engine.history(10).len(), 2);
+        assert!(engine
+            .respond(&queued[0].id, true, None, &FakeExecutor(Ok(String::new())))
+            .is_err());
+    }
+
+    #[test]
+    fn process_threats_suggest_linked_actions() {
+        let threat = |severity: ThreatSeverity, pid: Option<u32>, name: &str| ThreatEvent {
+            id: "t1".to_string(),
+            timestamp: Utc::now(),
+            threat_type: ThreatType::SuspiciousProcess,
+            risk_score: severity.score(),
+            severity,
+            description: "Encoded PowerShell".to_string(),
+            process_name: Some(name.to_string()),
+            process_id: pid,
+            details: Default::default(),
+            techniques: Vec::new(),
+        };
+        assert_eq!(
+            plan_threat_actions(&threat(ThreatSeverity::Medium, Some(4242), "ps.exe")),
+            vec![RemediationKind::suspend(4242)]
+        );
+        assert!(plan_threat_actions(&threat(ThreatSeverity::High, None, "ps.exe")).is_empty());
+        assert!(
+            plan_threat_actions(&threat(ThreatSeverity::Critical, Some(700), "lsass.exe"))
+                .is_empty()
+        );
+
+        let engine = RemediationEngine::new();
+        let queued = engine.propose_for_threat(&threat(ThreatSeverity::High, Some(4242), "x"));
+        assert_eq!(queued.len(), 3);
+        assert!(matches!(
+            queued[1].kind,
+            RemediationKind::IsolateNetwork { pid: 4242, .. }
+        ));
+        assert!(queued
+            .iter()
+            .all(|a| a.finding_id == "t1" && a.severity == "high"));
+    }
+
+    #[test]
+    fn failed_execution_is_recorded() {
+        let engine = RemediationEngine::new();
+        let queued = engine.propose(&finding("critical", &["pid:999999"]));
+        let done = engine
+            .respond(&queued[0].id, true, None, &FakeExecutor(Err("gone".into())))
+            .unwrap();
+        assert_eq!(done.status, ActionStatus::Failed);
+        assert_eq!(done.outcome.as_deref(), Some("gone"));
+    }
+
+    #[test]
+    fn process_actions_need_a_recorded_identity() {
+        let executor = SystemExecutor {
+            quarantine_dir: None,
+            firewall: None,
+        };
+        let err = executor
+            .execute(&RemediationKind::kill(999_999_999))
+            .unwrap_err();
+        assert!(err.contains("not running when the action was proposed"));
+
+        let mut kind = RemediationKind::kill(std::process::id());
+        record_identity(&mut kind);
+        assert!(matches!(
+            kind,
+            RemediationKind::KillProcess { process, .. } if process.start_time.is_some()
+        ));
+    }
+}
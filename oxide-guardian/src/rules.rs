@@ -0,0 +1,469 @@
+//! Data-driven process heuristics.
+//!
+//! A ruleset is a JSON or TOML file (picked by extension) with a list of
+//! rules. Each rule has a severity and conditions on the process name,
+//! command-line arguments, executable path, parent process name and signer.
+//! Within a condition any listed fragment may match; every condition given
+//! must match. Matching is a case-insensitive substring test.
+//!
+//! ```toml
+//! [[rules]]
+//! id = "encoded_powershell"
+//! description = "PowerShell with an encoded command"
+//! severity = "high"
+//! name = ["powershell.exe", "pwsh.exe"]
+//! args = ["-encodedcommand", "-enc "]
+//! ```
+//!
+//! Without a configured file the built-in ruleset is used. The file is
+//! re-read when its modification time changes; a file that fails to parse
+//! keeps the previous rules active and is reported in [`RulesStatus`].
+//! The process monitor does not read code signatures, so `signer`
+//! conditions only match samples that carry a signer.
+
+use crate::guardian::ThreatSeverity;
+use chrono::{DateTime, Utc};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::time::SystemTime;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Rule {
+    pub id: String,
+    #[serde(default)]
+    pub description: String,
+    pub severity: ThreatSeverity,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub name: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub path: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parent: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub signer: Vec<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Fragment of `patterns` found in `value`; an empty condition matches
+/// anything, a missing value matches nothing.
+fn match_condition(patterns: &[String], value: Option<&str>) -> Option<Option<String>> {
+    if patterns.is_empty() {
+        return Some(None);
+    }
+    let value = value?.to_lowercase();
+    patterns
+        .iter()
+        .find(|p| value.contains(&p.to_lowercase()))
+        .map(|p| Some(p.clone()))
+}
+
+impl Rule {
+    fn conditions(&self) -> [(&'static str, &[String]); 5] {
+        [
+            ("name", &self.name),
+            ("args", &self.args),
+            ("path", &self.path),
+            ("parent", &self.parent),
+            ("signer", &self.signer),
+        ]
+    }
+
+    /// `field=fragment` for every condition that matched, or `None` if
+    /// the rule does not apply.
+    fn evaluate(&self, sample: &ProcessSample) -> Option<Vec<String>> {
+        let mut matched = Vec::new();
+        for (field, patterns) in self.conditions() {
+            if let Some(fragment) = match_condition(patterns, sample.field(field))? {
+                matched.push(format!("{field}={fragment}"));
+            }
+        }
+        Some(matched)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Ruleset {
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+}
+
+impl Ruleset {
+    /// Parse `content`, as TOML when `path` ends in `.toml`, JSON otherwise.
+    pub fn parse(content: &str, path: &Path) -> Result<Self, String> {
+        let is_toml = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("toml"));
+        let ruleset: Self = if is_toml {
+            toml::from_str(content).map_err(|e| format!("Invalid TOML ruleset: {e}"))?
+        } else {
+            serde_json::from_str(content).map_err(|e| format!("Invalid JSON ruleset: {e}"))?
+        };
+        ruleset.validate()?;
+        Ok(ruleset)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content =
+            fs::read_to_string(path).map_err(|e| format!("Failed to read {path:?}: {e}"))?;
+        Self::parse(&content, path)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let mut ids = HashSet::new();
+        for rule in &self.rules {
+            if rule.id.trim().is_empty() {
+                return Err("Every rule needs an id".to_string());
+            }
+            if !ids.insert(rule.id.as_str()) {
+                return Err(format!("Duplicate rule id '{}'", rule.id));
+            }
+            if rule.conditions().iter().all(|(_, p)| p.is_empty()) {
+                return Err(format!("Rule '{}' has no conditions", rule.id));
+            }
+        }
+        Ok(())
+    }
+
+    /// The heuristics Guardian shipped with before rules were configurable.
+    pub fn builtin() -> Self {
+        let fragments = |items: &[&str]| items.iter().map(|s| s.to_string()).collect();
+        Self {
+            rules: vec![
+                Rule {
+                    id: "script_host_suspicious_args".to_string(),
+                    description: "Script host or LOLBin started with evasive arguments".to_string(),
+                    severity: ThreatSeverity::Medium,
+                    enabled: true,
+                    name: fragments(&[
+                        "cmd.exe",
+                        "powershell.exe",
+                        "wscript.exe",
+                        "cscript.exe",
+                        "regsvr32.exe",
+                        "rundll32.exe",
+                        "mshta.exe",
+                    ]),
+                    args: fragments(&[
+                        "-encodedcommand",
+                        "-windowstyle hidden",
+                        "-noprofile",
+                        "invoke-expression",
+                        "downloadstring",
+                        "bypass",
+                    ]),
+                    path: Vec::new(),
+                    parent: Vec::new(),
+                    signer: Vec::new(),
+                },
+                Rule {
+                    id: "runs_from_writable_location".to_string(),
+                    description: "Executable started from a temp or shared folder".to_string(),
+                    severity: ThreatSeverity::Medium,
+                    enabled: true,
+                    name: Vec::new(),
+                    args: Vec::new(),
+                    path: fragments(&[
+                        "\\temp\\",
+                        "\\appdata\\local\\temp\\",
+                        "\\users\\public\\",
+                        "\\programdata\\",
+                        "\\windows\\temp\\",
+                    ]),
+                    parent: Vec::new(),
+                    signer: Vec::new(),
+                },
+            ],
+        }
+    }
+}
+
+/// The process attributes rules look at.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ProcessSample {
+    pub name: String,
+    /// Full command line
+    #[serde(default)]
+    pub args: Option<String>,
+    /// Executable path
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Parent process name
+    #[serde(default)]
+    pub parent: Option<String>,
+    #[serde(default)]
+    pub signer: Option<String>,
+}
+
+impl ProcessSample {
+    /// Sample from a monitor `process_info` event.
+    pub fn from_details(details: &serde_json::Value) -> Self {
+        let text = |key: &str| {
+            details
+                .get(key)
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+        };
+        Self {
+            name: text("name").unwrap_or_default(),
+            args: text("command"),
+            path: text("exe"),
+            parent: text("parent"),
+            signer: text("signer"),
+        }
+    }
+
+    fn field(&self, field: &str) -> Option<&str> {
+        match field {
+            "name" => Some(&self.name),
+            "args" => self.args.as_deref(),
+            "path" => self.path.as_deref(),
+            "parent" => self.parent.as_deref(),
+            "signer" => self.signer.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RuleMatch {
+    pub rule_id: String,
+    pub description: String,
+    pub severity: ThreatSeverity,
+    /// `field=fragment` for each condition that matched
+    pub matched: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RulesStatus {
+    /// Ruleset file, `None` for the built-in rules
+    pub path: Option<String>,
+    pub loaded_at: DateTime<Utc>,
+    /// Why the file could not be (re)loaded; the previous rules stay active
+    pub last_error: Option<String>,
+    pub rules: Vec<Rule>,
+}
+
+struct EngineState {
+    path: Option<PathBuf>,
+    ruleset: Ruleset,
+    modified: Option<SystemTime>,
+    loaded_at: DateTime<Utc>,
+    last_error: Option<String>,
+}
+
+pub struct RuleEngine {
+    state: RwLock<EngineState>,
+}
+
+fn modified_at(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+impl RuleEngine {
+    /// Engine reading `path`, or the built-in rules when `path` is `None`
+    /// or cannot be loaded.
+    pub fn new(path: Option<PathBuf>) -> Self {
+        let engine = Self {
+            state: RwLock::new(EngineState {
+                path: None,
+                ruleset: Ruleset::builtin(),
+                modified: None,
+                loaded_at: Utc::now(),
+                last_error: None,
+            }),
+        };
+        engine.set_path(path);
+        engine
+    }
+
+    /// Switch to another ruleset file; `None` restores the built-in rules.
+    pub fn set_path(&self, path: Option<PathBuf>) {
+        {
+            let mut state = self.state.write().unwrap();
+            if state.path == path && state.modified.is_some() {
+                return;
+            }
+            state.path = path.clone();
+            state.modified = None;
+            state.last_error = None;
+            if path.is_none() {
+                state.ruleset = Ruleset::builtin();
+                state.loaded_at = Utc::now();
+                return;
+            }
+        }
+        let _ = self.reload();
+    }
+
+    /// Re-read the ruleset file. On failure the active rules are kept.
+    pub fn reload(&self) -> Result<usize, String> {
+        let mut state = self.state.write().unwrap();
+        let Some(path) = state.path.clone() else {
+            return Ok(state.ruleset.rules.len());
+        };
+        state.modified = modified_at(&path);
+        match Ruleset::load(&path) {
+            Ok(ruleset) => {
+                info!(
+                    "Loaded {} heuristic rule(s) from {path:?}",
+                    ruleset.rules.len()
+                );
+                state.ruleset = ruleset;
+                state.loaded_at = Utc::now();
+                state.last_error = None;
+                Ok(state.ruleset.rules.len())
+            }
+            Err(e) => {
+                error!("Keeping previous heuristic rules: {e}");
+                state.last_error = Some(e.clone());
+                Err(e)
+            }
+        }
+    }
+
+    /// Reload when the file's modification time changed since the last read.
+    pub fn reload_if_changed(&self) {
+        let changed = {
+            let state = self.state.read().unwrap();
+            state
+                .path
+                .as_deref()
+                .is_some_and(|path| modified_at(path) != state.modified)
+        };
+        if changed {
+            let _ = self.reload();
+        }
+    }
+
+    /// Enabled rules matching `sample`, most severe first.
+    pub fn evaluate(&self, sample: &ProcessSample) -> Vec<RuleMatch> {
+        let state = self.state.read().unwrap();
+        let mut matches: Vec<RuleMatch> = state
+            .ruleset
+            .rules
+            .iter()
+            .filter(|rule| rule.enabled)
+            .filter_map(|rule| {
+                rule.evaluate(sample).map(|matched| RuleMatch {
+                    rule_id: rule.id.clone(),
+                    description: rule.description.clone(),
+                    severity: rule.severity.clone(),
+                    matched,
+                })
+            })
+            .collect();
+        matches.sort_by(|a, b| b.severity.cmp(&a.severity));
+        matches
+    }
+
+    pub fn status(&self) -> RulesStatus {
+        let state = self.state.read().unwrap();
+        RulesStatus {
+            path: state.path.as_ref().map(|p| p.display().to_string()),
+            loaded_at: state.loaded_at,
+            last_error: state.last_error.clone(),
+            rules: state.ruleset.rules.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(name: &str, args: &str) -> ProcessSample {
+        ProcessSample {
+            name: name.to_string(),
+            args: Some(args.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn builtin_rules_keep_previous_heuristics() {
+        let engine = RuleEngine::new(None);
+        let hits = engine.evaluate(&sample(
+            "PowerShell.exe",
+            "powershell -NoProfile -EncodedCommand AAA",
+        ));
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].rule_id, "script_host_suspicious_args");
+        assert_eq!(hits[0].matched[0], "name=powershell.exe");
+
+        assert!(engine
+            .evaluate(&sample("powershell.exe", "powershell Get-Date"))
+            .is_empty());
+
+        let dropped = ProcessSample {
+            name: "updater.exe".to_string(),
+            path: Some("C:\\Users\\Public\\updater.exe".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            engine.evaluate(&dropped)[0].rule_id,
+            "runs_from_writable_location"
+        );
+    }
+
+    #[test]
+    fn file_rules_reload_and_survive_bad_edits() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rules.toml");
+        fs::write(
+            &path,
+            r#"
+[[rules]]
+id = "office_spawns_shell"
+severity = "high"
+name = ["cmd.exe"]
+parent = ["winword.exe"]
+
+[[rules]]
+id = "contoso_updater"
+severity = "low"
+name = ["updater"]
+signer = ["contoso"]
+"#,
+        )
+        .unwrap();
+        let engine = RuleEngine::new(Some(path.clone()));
+        assert_eq!(engine.status().rules.len(), 2);
+
+        let mut child = sample("cmd.exe", "cmd /c whoami");
+        assert!(engine.evaluate(&child).is_empty());
+        child.parent = Some("WINWORD.EXE".to_string());
+        let hits = engine.evaluate(&child);
+        assert_eq!(hits[0].rule_id, "office_spawns_shell");
+        assert_eq!(hits[0].matched, vec!["name=cmd.exe", "parent=winword.exe"]);
+        // Without a known signer, signer conditions never match
+        assert!(engine.evaluate(&sample("updater", "")).is_empty());
+
+        fs::write(&path, "[[rules]]\nid = \"broken\"\nseverity = \"high\"\n").unwrap();
+        assert!(engine.reload().unwrap_err().contains("no conditions"));
+        let status = engine.status();
+        assert_eq!(status.rules.len(), 2);
+        assert!(status.last_error.is_some());
+
+        let json = dir.path().join("rules.json");
+        fs::write(
+            &json,
+            r#"{"rules":[{"id":"nc","severity":"Critical","name":["nc.exe"]}]}"#,
+        )
+        .unwrap();
+        engine.set_path(Some(json));
+        assert_eq!(engine.evaluate(&sample("nc.exe", ""))[0].rule_id, "nc");
+        assert!(engine.status().last_error.is_none());
+    }
+}
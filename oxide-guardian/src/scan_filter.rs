@@ -0,0 +1,353 @@
+//! Include/exclude filtering for folder scans.
+//!
+//! Extensions and globs are checked first since they only need the path.
+//! Content rules read the first few hundred bytes of the file and classify
+//! them by magic number, so an extensionless ELF binary still counts as an
+//! executable and a renamed JPEG is still media.
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use oxide_core::config::ScanFilterConfig;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// Bytes read for content sniffing
+const SNIFF_LEN: usize = 512;
+
+/// Extensions that are scripts even though their content is plain text
+const SCRIPT_EXTENSIONS: &[&str] = &[
+    "ps1",
+    "psm1",
+    "bat",
+    "cmd",
+    "vbs",
+    "vbe",
+    "js",
+    "jse",
+    "wsf",
+    "hta",
+    "sh",
+    "bash",
+    "py",
+    "pl",
+    "rb",
+    "php",
+    "lua",
+    "applescript",
+    "scpt",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentCategory {
+    Executable,
+    Script,
+    Archive,
+    Document,
+    Image,
+    Audio,
+    Video,
+    Other,
+}
+
+impl ContentCategory {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "executable" => Self::Executable,
+            "script" => Self::Script,
+            "archive" => Self::Archive,
+            "document" => Self::Document,
+            "image" => Self::Image,
+            "audio" => Self::Audio,
+            "video" => Self::Video,
+            _ => return None,
+        })
+    }
+}
+
+/// Sniffed content of a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SniffedContent {
+    pub category: ContentCategory,
+    pub mime: Option<&'static str>,
+}
+
+/// Classify a file header, falling back to the extension for text scripts.
+pub fn sniff_bytes(header: &[u8], extension: Option<&str>) -> SniffedContent {
+    if let Some(kind) = infer::get(header) {
+        let category = match kind.matcher_type() {
+            infer::MatcherType::App => ContentCategory::Executable,
+            infer::MatcherType::Archive => ContentCategory::Archive,
+            infer::MatcherType::Audio => ContentCategory::Audio,
+            infer::MatcherType::Book | infer::MatcherType::Doc => ContentCategory::Document,
+            infer::MatcherType::Image => ContentCategory::Image,
+            infer::MatcherType::Video => ContentCategory::Video,
+            infer::MatcherType::Text if kind.mime_type() == "text/x-shellscript" => {
+                ContentCategory::Script
+            }
+            _ => ContentCategory::Other,
+        };
+        return SniffedContent {
+            category,
+            mime: Some(kind.mime_type()),
+        };
+    }
+    let category = if header.starts_with(b"#!")
+        || extension.is_some_and(|ext| SCRIPT_EXTENSIONS.contains(&ext))
+    {
+        ContentCategory::Script
+    } else {
+        ContentCategory::Other
+    };
+    SniffedContent {
+        category,
+        mime: None,
+    }
+}
+
+pub fn sniff_file(path: &Path) -> Option<SniffedContent> {
+    let mut header = Vec::with_capacity(SNIFF_LEN);
+    File::open(path)
+        .ok()?
+        .take(SNIFF_LEN as u64)
+        .read_to_end(&mut header)
+        .ok()?;
+    Some(sniff_bytes(&header, extension_of(path).as_deref()))
+}
+
+fn extension_of(path: &Path) -> Option<String> {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+}
+
+#[derive(Debug, Clone)]
+enum ContentRule {
+    Category(ContentCategory),
+    /// Exact MIME type, or a `type/` prefix from `type/*`
+    Mime(String),
+}
+
+impl ContentRule {
+    fn parse(raw: &str) -> Result<Self, String> {
+        let raw = raw.trim().to_ascii_lowercase();
+        if let Some(prefix) = raw.strip_suffix('*') {
+            return Ok(Self::Mime(prefix.to_string()));
+        }
+        if raw.contains('/') {
+            return Ok(Self::Mime(raw));
+        }
+        ContentCategory::parse(&raw)
+            .map(Self::Category)
+            .ok_or_else(|| format!("Unknown scan content type '{raw}'"))
+    }
+
+    fn matches(&self, content: &SniffedContent) -> bool {
+        match self {
+            Self::Category(category) => content.category == *category,
+            Self::Mime(mime) if mime.ends_with('/') => {
+                content.mime.is_some_and(|m| m.starts_with(mime.as_str()))
+            }
+            Self::Mime(mime) => content.mime == Some(mime.as_str()),
+        }
+    }
+}
+
+/// Compiled [`ScanFilterConfig`].
+#[derive(Debug, Clone, Default)]
+pub struct ScanFilter {
+    include_extensions: HashSet<String>,
+    exclude_extensions: HashSet<String>,
+    include_content: Vec<ContentRule>,
+    exclude_content: Vec<ContentRule>,
+    include_globs: Option<GlobSet>,
+    exclude_globs: Option<GlobSet>,
+}
+
+impl ScanFilter {
+    pub fn new(config: &ScanFilterConfig) -> Result<Self, String> {
+        let extensions = |list: &[String]| {
+            list.iter()
+                .map(|ext| ext.trim().trim_start_matches('.').to_ascii_lowercase())
+                .filter(|ext| !ext.is_empty())
+                .collect::<HashSet<_>>()
+        };
+        let content = |list: &[String]| {
+            list.iter()
+                .map(|raw| ContentRule::parse(raw))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        Ok(Self {
+            include_extensions: extensions(&config.include_extensions),
+            exclude_extensions: extensions(&config.exclude_extensions),
+            include_content: content(&config.include_content)?,
+            exclude_content: content(&config.exclude_content)?,
+            include_globs: glob_set(&config.include_globs)?,
+            exclude_globs: glob_set(&config.exclude_globs)?,
+        })
+    }
+
+    fn has_includes(&self) -> bool {
+        !self.include_extensions.is_empty()
+            || !self.include_content.is_empty()
+            || self.include_globs.is_some()
+    }
+
+    /// Whether a folder scan should look at `path`.
+    pub fn allows(&self, path: &Path) -> bool {
+        self.allows_with(path, sniff_file)
+    }
+
+    fn allows_with(
+        &self,
+        path: &Path,
+        sniff: impl FnOnce(&Path) -> Option<SniffedContent>,
+    ) -> bool {
+        let extension = extension_of(path);
+        let ext_in = |set: &HashSet<String>| extension.as_ref().is_some_and(|e| set.contains(e));
+        if ext_in(&self.exclude_extensions)
+            || self
+                .exclude_globs
+                .as_ref()
+                .is_some_and(|g| g.is_match(path))
+        {
+            return false;
+        }
+
+        let mut included = !self.has_includes()
+            || ext_in(&self.include_extensions)
+            || self
+                .include_globs
+                .as_ref()
+                .is_some_and(|g| g.is_match(path));
+        let needs_content =
+            !self.exclude_content.is_empty() || (!included && !self.include_content.is_empty());
+        if !needs_content {
+            return included;
+        }
+
+        // Unreadable files stay in so the scan reports the error
+        let Some(content) = sniff(path) else {
+            return true;
+        };
+        if self
+            .exclude_content
+            .iter()
+            .any(|rule| rule.matches(&content))
+        {
+            return false;
+        }
+        if !included {
+            included = self
+                .include_content
+                .iter()
+                .any(|rule| rule.matches(&content));
+        }
+        included
+    }
+}
+
+fn glob_set(patterns: &[String]) -> Result<Option<GlobSet>, String> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob =
+            Glob::new(pattern.trim()).map_err(|e| format!("Invalid scan glob '{pattern}': {e}"))?;
+        builder.add(glob);
+    }
+    builder
+        .build()
+        .map(Some)
+        .map_err(|e| format!("Invalid scan globs: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // infer wants a full 64-bit ELF header before it calls it one
+    const ELF: &[u8] = &[
+        0x7f, b'E', b'L', b'F', 2, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0,
+    ];
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+
+    fn filter(config: ScanFilterConfig) -> ScanFilter {
+        ScanFilter::new(&config).unwrap()
+    }
+
+    #[test]
+    fn sniffs_content_categories() {
+        assert_eq!(sniff_bytes(ELF, None).category, ContentCategory::Executable);
+        assert_eq!(
+            sniff_bytes(b"MZ\x90\0", Some("dat")).category,
+            ContentCategory::Executable
+        );
+        assert_eq!(sniff_bytes(PNG, Some("txt")).mime, Some("image/png"));
+        assert_eq!(
+            sniff_bytes(b"#!/bin/sh\necho", None).category,
+            ContentCategory::Script
+        );
+        assert_eq!(
+            sniff_bytes(b"Write-Host hi", Some("ps1")).category,
+            ContentCategory::Script
+        );
+        assert_eq!(
+            sniff_bytes(b"hello", Some("txt")).category,
+            ContentCategory::Other
+        );
+    }
+
+    #[test]
+    fn applies_include_and_exclude_rules() {
+        let sniffed = |bytes: &'static [u8]| {
+            move |path: &Path| Some(sniff_bytes(bytes, extension_of(path).as_deref()))
+        };
+
+        // Only executables and scripts
+        let only_code = filter(ScanFilterConfig {
+            include_content: vec!["executable".into(), "script".into()],
+            ..Default::default()
+        });
+        assert!(only_code.allows_with(Path::new("/usr/bin/tool"), sniffed(ELF)));
+        assert!(only_code.allows_with(Path::new("C:/x/run.ps1"), sniffed(b"Get-Item")));
+        assert!(!only_code.allows_with(Path::new("/home/u/a.png"), sniffed(PNG)));
+
+        // Skip media, renamed or not, and anything under node_modules
+        let skip_media = filter(ScanFilterConfig {
+            exclude_content: vec!["image/*".into(), "video".into()],
+            exclude_globs: vec!["**/node_modules/**".into()],
+            ..Default::default()
+        });
+        assert!(!skip_media.allows_with(Path::new("/home/u/holiday.dat"), sniffed(PNG)));
+        assert!(skip_media.allows_with(Path::new("/home/u/setup.exe"), sniffed(b"MZ\x90\0")));
+        assert!(!skip_media.allows_with(
+            Path::new("/p/node_modules/x/index.js"),
+            |_: &Path| -> Option<SniffedContent> { panic!("globs are checked first") }
+        ));
+
+        // Extension rules never need to read the file
+        let exts = filter(ScanFilterConfig {
+            include_extensions: vec![".EXE".into(), "dll".into()],
+            exclude_extensions: vec!["dll".into()],
+            ..Default::default()
+        });
+        let no_read = |_: &Path| -> Option<SniffedContent> { panic!("no content rules") };
+        assert!(exts.allows_with(Path::new("a/Setup.exe"), no_read));
+        assert!(!exts.allows_with(Path::new("a/lib.dll"), no_read));
+        assert!(!exts.allows_with(Path::new("a/readme.md"), no_read));
+
+        assert!(ScanFilter::new(&ScanFilterConfig {
+            include_content: vec!["spreadsheet".into()],
+            ..Default::default()
+        })
+        .is_err());
+        assert!(ScanFilter::new(&ScanFilterConfig {
+            exclude_globs: vec!["[".into()],
+            ..Default::default()
+        })
+        .is_err());
+    }
+}
@@ -0,0 +1,230 @@
+//! Path handling for scans of long paths and network shares.
+//!
+//! Windows limits classic paths to 260 characters. The extended-length form
+//! (`\\?\C:\...`, `\\?\UNC\server\share\...`) lifts the limit but turns off
+//! the usual normalization, so [`extended_path`] resolves `.`/`..` and
+//! slashes itself. Events and scan history use [`display_path`] so users
+//! see the path they typed.
+//!
+//! Network shares answer every metadata call with a round trip. Scans of
+//! them probe latency once, size their worker pool from it, and put
+//! timeouts around directory listings and file reads.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Upper bound for latency-scaled worker pools
+pub const MAX_NETWORK_WORKERS: usize = 32;
+
+/// Filesystem types that live on another machine
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+const NETWORK_FS_TYPES: &[&str] = &[
+    "nfs",
+    "nfs4",
+    "cifs",
+    "smb3",
+    "smbfs",
+    "afs",
+    "9p",
+    "ceph",
+    "glusterfs",
+    "davfs",
+    "fuse.sshfs",
+    "fuse.rclone",
+];
+
+/// `path` in a form the OS accepts at any length. Relative and
+/// already-extended paths are returned unchanged; a no-op off Windows.
+pub fn extended_path(path: &Path) -> PathBuf {
+    if cfg!(windows) {
+        if let Some(extended) = extend_windows(&path.to_string_lossy()) {
+            return PathBuf::from(extended);
+        }
+    }
+    path.to_path_buf()
+}
+
+fn extend_windows(path: &str) -> Option<String> {
+    if path.starts_with(r"\\?\") || path.starts_with(r"\\.\") {
+        return None;
+    }
+    let path = path.replace('/', "\\");
+    let (prefix, rest) = if let Some(unc) = path.strip_prefix(r"\\") {
+        // \\server\share stays the root; `..` cannot climb above it
+        let mut parts = unc.splitn(3, '\\');
+        let server = parts.next().filter(|s| !s.is_empty())?;
+        let share = parts.next().filter(|s| !s.is_empty())?;
+        (
+            format!(r"\\?\UNC\{server}\{share}"),
+            parts.next().unwrap_or(""),
+        )
+    } else {
+        let bytes = path.as_bytes();
+        if bytes.len() < 3 || !bytes[0].is_ascii_alphabetic() || &bytes[1..3] != b":\\" {
+            return None;
+        }
+        (format!(r"\\?\{}", &path[..2]), &path[3..])
+    };
+
+    let mut parts: Vec<&str> = Vec::new();
+    for part in rest.split('\\') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    if parts.is_empty() {
+        return Some(format!(r"{prefix}\"));
+    }
+    Some(format!(r"{prefix}\{}", parts.join("\\")))
+}
+
+/// Undo [`extended_path`] for display.
+pub fn display_path(path: &str) -> String {
+    if let Some(unc) = path.strip_prefix(r"\\?\UNC\") {
+        format!(r"\\{unc}")
+    } else if let Some(local) = path.strip_prefix(r"\\?\") {
+        local.to_string()
+    } else {
+        path.to_string()
+    }
+}
+
+/// Whether `path` is on a network share: a UNC path or a mapped drive on
+/// Windows, an NFS/SMB/SSHFS mount on Linux.
+pub fn is_network_path(path: &Path) -> bool {
+    let shown = display_path(&path.to_string_lossy());
+    if shown.starts_with(r"\\") && !shown.starts_with(r"\\.\") {
+        return true;
+    }
+    is_remote_mount(&shown)
+}
+
+#[cfg(windows)]
+fn is_remote_mount(path: &str) -> bool {
+    use std::os::windows::ffi::OsStrExt;
+    use winapi::um::fileapi::GetDriveTypeW;
+    use winapi::um::winbase::DRIVE_REMOTE;
+
+    let bytes = path.as_bytes();
+    if bytes.len() < 2 || bytes[1] != b':' {
+        return false;
+    }
+    let root: Vec<u16> = std::ffi::OsStr::new(&format!(r"{}\", &path[..2]))
+        .encode_wide()
+        .chain(Some(0))
+        .collect();
+    // SAFETY: `root` is a NUL-terminated UTF-16 string
+    unsafe { GetDriveTypeW(root.as_ptr()) == DRIVE_REMOTE }
+}
+
+#[cfg(target_os = "linux")]
+fn is_remote_mount(path: &str) -> bool {
+    let path = std::fs::canonicalize(path).unwrap_or_else(|_| PathBuf::from(path));
+    std::fs::read_to_string("/proc/mounts")
+        .ok()
+        .and_then(|mounts| mount_fs_type(&mounts, &path).map(str::to_string))
+        .is_some_and(|fs| NETWORK_FS_TYPES.contains(&fs.as_str()))
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+fn is_remote_mount(_path: &str) -> bool {
+    false
+}
+
+/// Filesystem type of the mount containing `path`, from /proc/mounts text.
+#[cfg_attr(not(any(target_os = "linux", test)), allow(dead_code))]
+fn mount_fs_type<'a>(mounts: &'a str, path: &Path) -> Option<&'a str> {
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let _source = fields.next()?;
+            // Spaces in mount points are escaped as \040
+            let mount_point = fields.next()?.replace("\\040", " ");
+            let fs_type = fields.next()?;
+            path.starts_with(&mount_point)
+                .then_some((mount_point.len(), fs_type))
+        })
+        .max_by_key(|(len, _)| *len)
+        .map(|(_, fs_type)| fs_type)
+}
+
+/// Median time to stat `dir` and list its first entry, a rough round-trip
+/// time for the share.
+pub fn probe_latency(dir: &Path) -> Option<Duration> {
+    let mut samples = Vec::with_capacity(3);
+    for _ in 0..3 {
+        let started = Instant::now();
+        std::fs::metadata(dir).ok()?;
+        let _ = std::fs::read_dir(dir).ok()?.next();
+        samples.push(started.elapsed());
+    }
+    samples.sort();
+    Some(samples[1])
+}
+
+/// Workers for a scan of a share with the given round-trip latency. Those
+/// scans wait on the network rather than the disk or CPU, so more requests
+/// in flight hide the latency; the cap spares the file server.
+pub fn network_workers(base: usize, latency: Duration) -> usize {
+    let factor = (latency.as_millis() / 10).clamp(1, 4) as usize;
+    (base * factor).clamp(1, MAX_NETWORK_WORKERS.max(base))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extends_and_displays_windows_paths() {
+        assert_eq!(
+            extend_windows(r"C:\Users\me\..\me/Downloads\.\setup.exe").as_deref(),
+            Some(r"\\?\C:\Users\me\Downloads\setup.exe")
+        );
+        assert_eq!(
+            extend_windows(r"\\files\public\..\..\team\a.doc").as_deref(),
+            Some(r"\\?\UNC\files\public\team\a.doc")
+        );
+        assert_eq!(extend_windows(r"D:\").as_deref(), Some(r"\\?\D:\"));
+        assert_eq!(extend_windows(r"\\?\C:\x"), None);
+        assert_eq!(extend_windows(r"relative\dir"), None);
+        assert_eq!(extend_windows(r"\\server"), None);
+
+        assert_eq!(
+            display_path(r"\\?\UNC\files\public\a.doc"),
+            r"\\files\public\a.doc"
+        );
+        assert_eq!(display_path(r"\\?\C:\x"), r"C:\x");
+        assert_eq!(display_path("/home/me"), "/home/me");
+        assert!(is_network_path(Path::new(r"\\?\UNC\files\public")));
+        assert!(is_network_path(Path::new(r"\\files\public")));
+    }
+
+    #[test]
+    fn detects_network_mounts_and_sizes_workers() {
+        let mounts = "/dev/sda1 / ext4 rw 0 0\n\
+                      //nas/media /mnt/my\\040nas cifs rw 0 0\n\
+                      nas:/export /mnt/my\\040nas/nfs nfs4 rw 0 0\n";
+        assert_eq!(mount_fs_type(mounts, Path::new("/home/me")), Some("ext4"));
+        assert_eq!(
+            mount_fs_type(mounts, Path::new("/mnt/my nas/a")),
+            Some("cifs")
+        );
+        assert_eq!(
+            mount_fs_type(mounts, Path::new("/mnt/my nas/nfs/b")),
+            Some("nfs4")
+        );
+
+        assert_eq!(network_workers(8, Duration::from_millis(1)), 8);
+        assert_eq!(network_workers(8, Duration::from_millis(25)), 16);
+        assert_eq!(
+            network_workers(8, Duration::from_millis(400)),
+            MAX_NETWORK_WORKERS
+        );
+        assert_eq!(network_workers(64, Duration::from_millis(400)), 64);
+    }
+}
@@ -1,18 +1,76 @@
 use crate::quarantine;
+use crate::scan_path;
 use crate::signatures::SignatureDb;
 use blake3;
+use log::debug;
+use memmap2::Mmap;
+use oxide_core::severity::{self, Scored, Severity};
 use sha2::{Digest, Sha256};
 use std::fs::File;
-use std::io::{BufReader, Read};
+use std::io::{BufReader, ErrorKind, Read};
 use std::path::Path;
 
-#[derive(Debug, Clone, serde::Serialize)]
+/// Files at least this large are memory-mapped instead of streamed
+const MMAP_THRESHOLD: u64 = 16 * 1024 * 1024;
+/// Read size when streaming
+const CHUNK_SIZE: usize = 1024 * 1024;
+/// Below this, splitting BLAKE3 across threads costs more than it saves
+const PARALLEL_THRESHOLD: usize = 128 * 1024;
+/// Hash found in the local signature database or an IOC feed
+const LOCAL_MATCH_SCORE: f64 = 75.0;
+/// External verdict from a single engine
+const EXTERNAL_MATCH_SCORE: f64 = 60.0;
+/// Added for each further engine that flags the file
+const PER_ENGINE_SCORE: f64 = 5.0;
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct FileHashes {
     pub sha256: String,
     pub blake3: String,
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+impl FileHashes {
+    fn hashers() -> (Sha256, blake3::Hasher) {
+        (Sha256::new(), blake3::Hasher::new())
+    }
+
+    fn finish(sha: Sha256, blake3_hasher: blake3::Hasher) -> Self {
+        Self {
+            sha256: format!("{:x}", sha.finalize()),
+            blake3: blake3_hasher.finalize().to_hex().to_string(),
+        }
+    }
+}
+
+/// Hash `bytes` with SHA-256 on one thread while BLAKE3 uses the rest.
+fn hash_parallel(
+    bytes: &[u8],
+    (mut sha, mut blake3_hasher): (Sha256, blake3::Hasher),
+) -> FileHashes {
+    rayon::join(
+        || sha.update(bytes),
+        || {
+            blake3_hasher.update_rayon(bytes);
+        },
+    );
+    FileHashes::finish(sha, blake3_hasher)
+}
+
+/// Fill `buf` unless the end of the file comes first; returns bytes read.
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> Result<usize, String> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(format!("Failed to read file: {e}")),
+        }
+    }
+    Ok(filled)
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct FileScanReport {
     pub path: String,
     pub size: u64,
@@ -20,9 +78,47 @@ pub struct FileScanReport {
     pub local_match: Option<String>,
     pub external_verdict: Option<ExternalVerdict>,
     pub malicious: bool,
+    /// 0-100 on the rubric in `oxide_core::severity`, 0 for clean files
+    pub risk_score: f64,
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+impl FileScanReport {
+    /// Record a verdict from an external lookup and re-score the file.
+    pub fn apply_external_verdict(&mut self, verdict: ExternalVerdict) {
+        self.malicious |= verdict.malicious;
+        self.external_verdict = Some(verdict);
+        self.risk_score = self.score();
+    }
+
+    /// Severity of the verdict, `None` for clean files.
+    pub fn threat_severity(&self) -> Option<Severity> {
+        self.malicious.then(|| self.severity())
+    }
+
+    fn score(&self) -> f64 {
+        let local = if self.local_match.is_some() {
+            LOCAL_MATCH_SCORE
+        } else {
+            0.0
+        };
+        let external = match &self.external_verdict {
+            Some(v) if v.malicious => {
+                let extra = v.engine_detections.len().saturating_sub(1) as f64;
+                EXTERNAL_MATCH_SCORE + extra * PER_ENGINE_SCORE
+            }
+            _ => 0.0,
+        };
+        severity::clamp_score(local.max(external))
+    }
+}
+
+impl Scored for FileScanReport {
+    fn risk_score(&self) -> f64 {
+        self.risk_score
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ExternalVerdict {
     pub malicious: bool,
     pub engine_detections: Vec<(String, String)>,
@@ -42,61 +138,108 @@ impl FileScanner {
         }
     }
 
+    /// Hashes in the loaded signature database, `None` when none is loaded.
+    pub fn signature_count(&self) -> Option<usize> {
+        self.sigdb.as_ref().map(SignatureDb::len)
+    }
+
     pub fn compute_hashes<P: AsRef<Path>>(path: P) -> Result<(FileHashes, u64), String> {
+        Self::compute_hashes_limited(path, None)
+    }
+
+    /// Hash `path`, failing before reading anything when it is larger than
+    /// `max_bytes` (or while reading, if it grows past the limit). Large
+    /// files are memory-mapped; SHA-256 and BLAKE3 run side by side and
+    /// BLAKE3 spreads each chunk across threads.
+    pub fn compute_hashes_limited<P: AsRef<Path>>(
+        path: P,
+        max_bytes: Option<u64>,
+    ) -> Result<(FileHashes, u64), String> {
         let file = File::open(&path).map_err(|e| format!("Failed to open file: {e}"))?;
         let metadata = file
             .metadata()
             .map_err(|e| format!("Failed to read metadata: {e}"))?;
         let size = metadata.len();
-        let mut reader = BufReader::new(file);
+        if max_bytes.is_some_and(|limit| size > limit) {
+            return Err("File exceeds max_file_size limit".to_string());
+        }
 
-        let mut sha = Sha256::new();
-        let mut blake3_hasher = blake3::Hasher::new();
-        let mut buf = [0u8; 64 * 1024];
+        if size >= MMAP_THRESHOLD {
+            // SAFETY: the map is read-only and dropped before returning. A
+            // file truncated underneath it can fault, the same trade-off
+            // every mmap-based scanner makes; locked or special files fail
+            // to map and are streamed instead.
+            match unsafe { Mmap::map(&file) } {
+                Ok(map) => return Ok((hash_parallel(&map, FileHashes::hashers()), size)),
+                Err(e) => debug!("mmap unavailable, streaming instead: {e}"),
+            }
+        }
+        Self::hash_streaming(file, max_bytes).map(|hashes| (hashes, size))
+    }
+
+    fn hash_streaming(file: File, max_bytes: Option<u64>) -> Result<FileHashes, String> {
+        let mut reader = BufReader::with_capacity(CHUNK_SIZE, file);
+        let (mut sha, mut blake3_hasher) = FileHashes::hashers();
+        let mut buf = vec![0u8; CHUNK_SIZE];
+        let mut total: u64 = 0;
 
         loop {
-            let n = reader
-                .read(&mut buf)
-                .map_err(|e| format!("Failed to read file: {e}"))?;
+            let n = read_full(&mut reader, &mut buf)?;
             if n == 0 {
                 break;
             }
-            sha.update(&buf[..n]);
-            blake3_hasher.update(&buf[..n]);
-        }
-
-        let sha256 = format!("{:x}", sha.finalize());
-        let blake3 = blake3_hasher.finalize().to_hex().to_string();
-
-        Ok((FileHashes { sha256, blake3 }, size))
-    }
-
-    pub fn scan_local<P: AsRef<Path>>(&self, path: P) -> Result<FileScanReport, String> {
-        if let Some(limit) = self.max_file_size_bytes {
-            let md = std::fs::metadata(&path).map_err(|e| format!("Failed to stat file: {e}"))?;
-            if md.len() > limit {
+            total += n as u64;
+            if max_bytes.is_some_and(|limit| total > limit) {
                 return Err("File exceeds max_file_size limit".to_string());
             }
+            let chunk = &buf[..n];
+            if n >= PARALLEL_THRESHOLD {
+                rayon::join(
+                    || sha.update(chunk),
+                    || {
+                        blake3_hasher.update_rayon(chunk);
+                    },
+                );
+            } else {
+                sha.update(chunk);
+                blake3_hasher.update(chunk);
+            }
         }
+        Ok(FileHashes::finish(sha, blake3_hasher))
+    }
 
-        let (hashes, size) = Self::compute_hashes(&path)?;
+    pub fn scan_local<P: AsRef<Path>>(&self, path: P) -> Result<FileScanReport, String> {
+        let (hashes, size) = Self::compute_hashes_limited(
+            scan_path::extended_path(path.as_ref()),
+            self.max_file_size_bytes,
+        )?;
         let mut local_match = None;
         if let Some(db) = &self.sigdb {
-            if db.contains_sha256(&hashes.sha256) {
-                local_match = Some("sha256".to_string());
+            let matched = if db.contains_sha256(&hashes.sha256) {
+                Some(("sha256", &hashes.sha256))
             } else if db.contains_blake3(&hashes.blake3) {
-                local_match = Some("blake3".to_string());
-            }
+                Some(("blake3", &hashes.blake3))
+            } else {
+                None
+            };
+            // IOC feed hits name their feed
+            local_match = matched.map(|(kind, hash)| match db.source_of(hash) {
+                Some(source) => format!("ioc:{source}"),
+                None => kind.to_string(),
+            });
         }
 
-        Ok(FileScanReport {
+        let mut report = FileScanReport {
             path: path.as_ref().to_string_lossy().to_string(),
             size,
             hashes,
             local_match: local_match.clone(),
             external_verdict: None,
             malicious: local_match.is_some(),
-        })
+            risk_score: 0.0,
+        };
+        report.risk_score = report.score();
+        Ok(report)
     }
 
     pub fn quarantine_if_malicious<P: AsRef<Path>>(
@@ -113,3 +256,75 @@ impl FileScanner {
         Ok(None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn streamed_and_mapped_hashes_agree() {
+        let dir = tempfile::tempdir().unwrap();
+        let small = dir.path().join("small.bin");
+        fs_write(&small, b"abc");
+        let (hashes, size) = FileScanner::compute_hashes(&small).unwrap();
+        assert_eq!(size, 3);
+        assert_eq!(
+            hashes.sha256,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(hashes.blake3, blake3::hash(b"abc").to_hex().to_string());
+
+        // Above the mmap threshold and not a multiple of the chunk size
+        let data: Vec<u8> = (0..MMAP_THRESHOLD as usize + 12_345)
+            .map(|i| (i % 251) as u8)
+            .collect();
+        let large = dir.path().join("large.bin");
+        fs_write(&large, &data);
+        let (mapped, _) = FileScanner::compute_hashes(&large).unwrap();
+        let streamed = FileScanner::hash_streaming(File::open(&large).unwrap(), None).unwrap();
+        assert_eq!(mapped.sha256, streamed.sha256);
+        assert_eq!(mapped.blake3, streamed.blake3);
+        assert_eq!(mapped.blake3, blake3::hash(&data).to_hex().to_string());
+        assert_eq!(mapped.sha256, format!("{:x}", Sha256::digest(&data)));
+
+        assert!(FileScanner::compute_hashes_limited(&large, Some(1024)).is_err());
+        assert!(FileScanner::hash_streaming(File::open(&large).unwrap(), Some(1024)).is_err());
+    }
+
+    #[test]
+    fn verdicts_are_scored_on_the_shared_rubric() {
+        let mut report = FileScanReport {
+            path: "sample.exe".to_string(),
+            size: 3,
+            hashes: FileHashes::finish(Sha256::new(), blake3::Hasher::new()),
+            local_match: None,
+            external_verdict: None,
+            malicious: false,
+            risk_score: 0.0,
+        };
+        assert_eq!(report.score(), 0.0);
+        assert_eq!(report.threat_severity(), None);
+
+        let engines = |n: usize| ExternalVerdict {
+            malicious: n > 0,
+            engine_detections: (0..n)
+                .map(|i| (format!("av{i}"), "Trojan".into()))
+                .collect(),
+            reference: None,
+        };
+        report.apply_external_verdict(engines(1));
+        assert_eq!(report.threat_severity(), Some(Severity::High));
+        report.apply_external_verdict(engines(20));
+        assert_eq!(report.risk_score, 100.0);
+        assert_eq!(report.threat_severity(), Some(Severity::Critical));
+
+        report.external_verdict = None;
+        report.local_match = Some("sha256".to_string());
+        assert_eq!(Severity::from_score(report.score()), Severity::High);
+    }
+
+    fn fs_write(path: &Path, bytes: &[u8]) {
+        File::create(path).unwrap().write_all(bytes).unwrap();
+    }
+}
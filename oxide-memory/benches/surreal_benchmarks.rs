@@ -0,0 +1,116 @@
+use chrono::Utc;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use oxide_memory::benchmark::{pseudo_embedding, sample_memory, sample_metric};
+use oxide_memory::SurrealBackend;
+use std::time::Duration;
+use tokio::runtime::Runtime;
+
+const CORPUS_SIZES: [usize; 3] = [100, 1000, 5000];
+
+/// Backend on a fresh temp database seeded with `size` metrics and memories
+fn seeded_backend(rt: &Runtime, size: usize) -> (tempfile::TempDir, SurrealBackend) {
+    let dir = tempfile::TempDir::new().unwrap();
+    let backend = rt.block_on(async {
+        let backend = SurrealBackend::new(dir.path().join("bench.db"))
+            .await
+            .unwrap();
+        let dim = backend.embedding_dimension();
+        let now = Utc::now();
+        let metrics = (0..size).map(|i| sample_metric(i, now)).collect();
+        let memories = (0..size).map(|i| sample_memory(i, dim)).collect();
+        backend.insert_system_metrics_batch(metrics).await.unwrap();
+        backend.insert_agent_memories_batch(memories).await.unwrap();
+        backend
+    });
+    (dir, backend)
+}
+
+/// Benchmark: single metric inserts
+fn bench_metric_inserts(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let (_dir, backend) = seeded_backend(&rt, 0);
+    let mut i = 0usize;
+
+    c.bench_function("metric_insert", |b| {
+        b.iter(|| {
+            i += 1;
+            rt.block_on(backend.insert_system_metric(sample_metric(i, Utc::now())))
+                .unwrap();
+        });
+    });
+}
+
+/// Benchmark: bulk metric inserts (target > 1000/s)
+fn bench_metric_bulk_inserts(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let (_dir, backend) = seeded_backend(&rt, 0);
+    let mut group = c.benchmark_group("metric_bulk_insert");
+
+    for batch in [100usize, 1000] {
+        group.throughput(Throughput::Elements(batch as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(batch), &batch, |b, &batch| {
+            b.iter(|| {
+                let now = Utc::now();
+                let metrics = (0..batch).map(|i| sample_metric(i, now)).collect();
+                rt.block_on(backend.insert_system_metrics_batch(metrics))
+                    .unwrap();
+            });
+        });
+    }
+
+    group.finish();
+}
+
+/// Benchmark: 5-minute time-range query (target < 5 ms)
+fn bench_time_range_queries(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let mut group = c.benchmark_group("time_range_query");
+
+    for size in CORPUS_SIZES {
+        let (_dir, backend) = seeded_backend(&rt, size);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, _| {
+            b.iter(|| {
+                let end = Utc::now();
+                let start = end - chrono::Duration::minutes(5);
+                black_box(
+                    rt.block_on(backend.query_metrics_by_time(start, end))
+                        .unwrap(),
+                );
+            });
+        });
+    }
+
+    group.finish();
+}
+
+/// Benchmark: KNN vector search, top 5 (target < 20 ms)
+fn bench_vector_search(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let mut group = c.benchmark_group("vector_search");
+
+    for size in CORPUS_SIZES {
+        let (_dir, backend) = seeded_backend(&rt, size);
+        let query = pseudo_embedding(u64::MAX, backend.embedding_dimension());
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, _| {
+            b.iter(|| {
+                black_box(
+                    rt.block_on(backend.vector_search(query.clone(), "copilot", 5))
+                        .unwrap(),
+                );
+            });
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default()
+        .measurement_time(Duration::from_secs(10))
+        .sample_size(50);
+    targets = bench_metric_inserts, bench_metric_bulk_inserts, bench_time_range_queries,
+        bench_vector_search
+}
+
+criterion_main!(benches);
@@ -0,0 +1,299 @@
+//! Self-benchmark for [`SurrealBackend`] against the documented performance
+//! targets (see the `surreal_backend` module docs).
+//!
+//! Runs against a scratch database in the temp directory so the live store
+//! is never touched. The same helpers back the criterion benches in
+//! `benches/surreal_benchmarks.rs`.
+
+use crate::surreal_backend::{
+    AgentMemory, AgentType, DiskIO, MemorySource, MemoryUsage, NetworkStats, SurrealBackend,
+    SystemMetric,
+};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Time-range query latency target (embedded mode)
+pub const TARGET_QUERY_MS: f64 = 5.0;
+/// KNN vector search latency target
+pub const TARGET_VECTOR_SEARCH_MS: f64 = 20.0;
+/// Bulk metric insert throughput target
+pub const TARGET_INSERTS_PER_SEC: f64 = 1000.0;
+
+/// Spacing between seeded metrics, matching the collector's default interval
+const METRIC_INTERVAL_SECS: i64 = 5;
+/// Dashboards query the last few minutes of metrics
+const QUERY_WINDOW_MINUTES: i64 = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkOptions {
+    /// Corpus sizes to measure, run in ascending order on one database
+    pub corpus_sizes: Vec<usize>,
+    /// Queries and searches timed per corpus size
+    pub queries_per_size: usize,
+}
+
+impl Default for BenchmarkOptions {
+    fn default() -> Self {
+        Self {
+            corpus_sizes: vec![100, 1000],
+            queries_per_size: 20,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkResult {
+    /// `metric_bulk_insert`, `time_range_query` or `vector_search`
+    pub operation: String,
+    pub corpus_size: usize,
+    pub iterations: usize,
+    pub mean_ms: f64,
+    pub p95_ms: f64,
+    pub throughput_per_sec: f64,
+    /// Human-readable target, e.g. "< 5 ms"
+    pub target: String,
+    pub met: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkReport {
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    pub embedding_dimension: usize,
+    pub results: Vec<BenchmarkResult>,
+    pub all_targets_met: bool,
+}
+
+/// Run the benchmark on a fresh scratch database.
+pub async fn run_benchmark(options: &BenchmarkOptions) -> Result<BenchmarkReport> {
+    let scratch = std::env::temp_dir().join(format!("oxide-memory-bench-{}", uuid::Uuid::new_v4()));
+    let outcome = run_in(&scratch, options).await;
+    // RocksDB holds files open until the backend is dropped inside run_in
+    let _ = tokio::fs::remove_dir_all(&scratch).await;
+    outcome
+}
+
+async fn run_in(scratch: &std::path::Path, options: &BenchmarkOptions) -> Result<BenchmarkReport> {
+    let started_at = Utc::now();
+    let clock = Instant::now();
+    let backend = SurrealBackend::new(scratch.join("bench.db"))
+        .await
+        .context("Failed to open scratch benchmark database")?;
+    let dim = backend.embedding_dimension();
+
+    let mut sizes = options.corpus_sizes.clone();
+    sizes.sort_unstable();
+    sizes.dedup();
+    let queries = options.queries_per_size.max(1);
+
+    let mut results = Vec::new();
+    let mut seeded = 0usize;
+    for size in sizes {
+        // Grow both corpora up to `size`, timing the bulk metric insert
+        let now = Utc::now();
+        let metrics: Vec<SystemMetric> = (seeded..size).map(|i| sample_metric(i, now)).collect();
+        let memories: Vec<AgentMemory> = (seeded..size).map(|i| sample_memory(i, dim)).collect();
+        seeded = seeded.max(size);
+
+        if !metrics.is_empty() {
+            let count = metrics.len();
+            let t = Instant::now();
+            backend.insert_system_metrics_batch(metrics).await?;
+            let elapsed = t.elapsed();
+            backend.insert_agent_memories_batch(memories).await?;
+
+            // Per-record latency is only meaningful as an average for bulk writes
+            let per_record_ms = elapsed.as_secs_f64() * 1000.0 / count as f64;
+            let throughput = count as f64 / elapsed.as_secs_f64().max(f64::EPSILON);
+            results.push(BenchmarkResult {
+                operation: "metric_bulk_insert".to_string(),
+                corpus_size: size,
+                iterations: count,
+                mean_ms: per_record_ms,
+                p95_ms: per_record_ms,
+                throughput_per_sec: throughput,
+                target: format!("> {TARGET_INSERTS_PER_SEC:.0}/s"),
+                met: throughput >= TARGET_INSERTS_PER_SEC,
+            });
+        }
+
+        let mut query_times = Vec::with_capacity(queries);
+        for _ in 0..queries {
+            let end = Utc::now();
+            let start = end - chrono::Duration::minutes(QUERY_WINDOW_MINUTES);
+            let t = Instant::now();
+            backend.query_metrics_by_time(start, end).await?;
+            query_times.push(t.elapsed());
+        }
+        let (mean_ms, p95_ms) = summarize(&query_times);
+        results.push(BenchmarkResult {
+            operation: "time_range_query".to_string(),
+            corpus_size: size,
+            iterations: queries,
+            mean_ms,
+            p95_ms,
+            throughput_per_sec: throughput(&query_times),
+            target: format!("< {TARGET_QUERY_MS:.0} ms"),
+            met: p95_ms < TARGET_QUERY_MS,
+        });
+
+        let mut search_times = Vec::with_capacity(queries);
+        for q in 0..queries {
+            let embedding = pseudo_embedding(u64::MAX - q as u64, dim);
+            let t = Instant::now();
+            backend.vector_search(embedding, "copilot", 5).await?;
+            search_times.push(t.elapsed());
+        }
+        let (mean_ms, p95_ms) = summarize(&search_times);
+        results.push(BenchmarkResult {
+            operation: "vector_search".to_string(),
+            corpus_size: size,
+            iterations: queries,
+            mean_ms,
+            p95_ms,
+            throughput_per_sec: throughput(&search_times),
+            target: format!("< {TARGET_VECTOR_SEARCH_MS:.0} ms"),
+            met: p95_ms < TARGET_VECTOR_SEARCH_MS,
+        });
+    }
+
+    let all_targets_met = results.iter().all(|r| r.met);
+    info!(
+        "Memory benchmark finished: {} measurements, targets {}",
+        results.len(),
+        if all_targets_met { "met" } else { "missed" }
+    );
+    Ok(BenchmarkReport {
+        started_at,
+        duration_ms: clock.elapsed().as_millis() as u64,
+        embedding_dimension: dim,
+        results,
+        all_targets_met,
+    })
+}
+
+/// Mean and nearest-rank p95 in milliseconds.
+pub fn summarize(samples: &[Duration]) -> (f64, f64) {
+    if samples.is_empty() {
+        return (0.0, 0.0);
+    }
+    let mut ms: Vec<f64> = samples.iter().map(|d| d.as_secs_f64() * 1000.0).collect();
+    ms.sort_by(f64::total_cmp);
+    let mean = ms.iter().sum::<f64>() / ms.len() as f64;
+    let rank = ((0.95 * ms.len() as f64).ceil() as usize).clamp(1, ms.len());
+    (mean, ms[rank - 1])
+}
+
+fn throughput(samples: &[Duration]) -> f64 {
+    let total: f64 = samples.iter().map(Duration::as_secs_f64).sum();
+    if total > 0.0 {
+        samples.len() as f64 / total
+    } else {
+        0.0
+    }
+}
+
+/// Metric `i` of a seeded series, spaced one collector interval apart
+/// going back from `now`.
+pub fn sample_metric(i: usize, now: DateTime<Utc>) -> SystemMetric {
+    let load = (i % 100) as f64;
+    SystemMetric {
+        timestamp: now - chrono::Duration::seconds(i as i64 * METRIC_INTERVAL_SECS),
+        cpu_usage: load,
+        core_usage: vec![load, load / 2.0, load / 3.0, load / 4.0],
+        memory_usage: MemoryUsage {
+            total_mb: 16384.0,
+            used_mb: 8192.0,
+            available_mb: 8192.0,
+            percent: 50.0,
+        },
+        disk_io: DiskIO {
+            read_mb_per_sec: 1.0,
+            write_mb_per_sec: 1.0,
+            iops: 10,
+        },
+        network_stats: NetworkStats {
+            sent_mb_per_sec: 0.5,
+            recv_mb_per_sec: 0.5,
+            connections_active: 5,
+        },
+        gpus: Vec::new(),
+        cpu_temperature_c: None,
+        battery: None,
+        metadata: None,
+    }
+}
+
+/// Agent memory `i` with a deterministic embedding of dimension `dim`.
+pub fn sample_memory(i: usize, dim: usize) -> AgentMemory {
+    AgentMemory {
+        agent_type: AgentType::Copilot,
+        content: format!("benchmark memory {i}"),
+        embedding: pseudo_embedding(i as u64, dim),
+        timestamp: Utc::now(),
+        source: MemorySource::UserQuery,
+        metadata: None,
+    }
+}
+
+/// Deterministic unit vector derived from `seed` (xorshift64*).
+pub fn pseudo_embedding(seed: u64, dim: usize) -> Vec<f64> {
+    let mut state = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+    let mut v: Vec<f64> = (0..dim)
+        .map(|_| {
+            state ^= state >> 12;
+            state ^= state << 25;
+            state ^= state >> 27;
+            let x = state.wrapping_mul(0x2545_F491_4F6C_DD1D);
+            (x >> 11) as f64 / (1u64 << 53) as f64 - 0.5
+        })
+        .collect();
+    let norm = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+    if norm > 0.0 {
+        v.iter_mut().for_each(|x| *x /= norm);
+    }
+    v
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarize_reports_mean_and_p95() {
+        let samples: Vec<Duration> = (1..=20).map(Duration::from_millis).collect();
+        let (mean, p95) = summarize(&samples);
+        assert!((mean - 10.5).abs() < 1e-6);
+        assert!((p95 - 19.0).abs() < 1e-6);
+        assert_eq!(summarize(&[]), (0.0, 0.0));
+    }
+
+    #[test]
+    fn pseudo_embeddings_are_deterministic_unit_vectors() {
+        let a = pseudo_embedding(7, 64);
+        assert_eq!(a, pseudo_embedding(7, 64));
+        assert_ne!(a, pseudo_embedding(8, 64));
+        let norm: f64 = a.iter().map(|x| x * x).sum::<f64>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn small_benchmark_reports_every_operation() {
+        let report = run_benchmark(&BenchmarkOptions {
+            corpus_sizes: vec![10, 5],
+            queries_per_size: 2,
+        })
+        .await
+        .unwrap();
+
+        // 3 operations per corpus size, sizes sorted ascending
+        assert_eq!(report.results.len(), 6);
+        assert_eq!(report.results[0].corpus_size, 5);
+        assert_eq!(report.results[0].operation, "metric_bulk_insert");
+        assert_eq!(report.results[3].iterations, 5);
+        assert_eq!(report.all_targets_met, report.results.iter().all(|r| r.met));
+    }
+}
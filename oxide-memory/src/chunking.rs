@@ -0,0 +1,232 @@
+//! Splitting long texts into embeddable chunks.
+//!
+//! Embedding APIs reject inputs past their token limit and dilute the
+//! meaning of very long ones, so agent memory stores long texts as
+//! overlapping chunks. Each chunk records its byte offset in the parent
+//! text, which lets [`reassemble`] rebuild the original exactly.
+
+use oxide_core::config::MemoryChunkingConfig;
+
+/// One piece of a longer text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextChunk {
+    pub index: usize,
+    /// Byte offset of `text` in the parent
+    pub offset: usize,
+    pub text: String,
+}
+
+/// Split `text` into chunks of at most `chunk_size` characters, each
+/// starting with up to `chunk_overlap` characters of its predecessor. Texts
+/// that fit, or chunking disabled, yield a single chunk.
+pub fn chunk_text(text: &str, config: &MemoryChunkingConfig) -> Vec<TextChunk> {
+    let size = config.chunk_size().max(1);
+    if !config.enabled || text.chars().count() <= size {
+        return vec![TextChunk {
+            index: 0,
+            offset: 0,
+            text: text.to_string(),
+        }];
+    }
+    let overlap = config.chunk_overlap();
+    if config.sentence_aware {
+        pack(text, &sentence_units(text, size), size, overlap)
+    } else {
+        fixed_windows(text, size, size - overlap)
+    }
+}
+
+/// Byte ranges covering `text` that end after sentence punctuation or a
+/// newline; anything longer than `size` characters is split at words.
+fn sentence_units(text: &str, size: usize) -> Vec<(usize, usize)> {
+    let mut units = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let next = chars.peek().map(|&(_, n)| n);
+        let boundary = c == '\n'
+            || (matches!(c, '.' | '!' | '?' | '。') && next.is_none_or(char::is_whitespace));
+        if boundary {
+            // Trailing whitespace stays with the sentence it follows
+            let mut end = i + c.len_utf8();
+            while let Some(&(j, w)) = chars.peek() {
+                if w == '\n' || !w.is_whitespace() {
+                    break;
+                }
+                end = j + w.len_utf8();
+                chars.next();
+            }
+            units.extend(split_long(text, start, end, size));
+            start = end;
+        }
+    }
+    if start < text.len() {
+        units.extend(split_long(text, start, text.len(), size));
+    }
+    units
+}
+
+/// Split `text[start..end]` into pieces of at most `size` characters,
+/// preferring to break after whitespace.
+fn split_long(text: &str, start: usize, end: usize, size: usize) -> Vec<(usize, usize)> {
+    let mut pieces = Vec::new();
+    let mut from = start;
+    while text[from..end].chars().count() > size {
+        let limit = text[from..end]
+            .char_indices()
+            .nth(size)
+            .map_or(end, |(i, _)| from + i);
+        let cut = text[from..limit]
+            .char_indices()
+            .rev()
+            .find(|&(i, c)| c.is_whitespace() && i > 0)
+            .map_or(limit, |(i, c)| from + i + c.len_utf8());
+        pieces.push((from, cut));
+        from = cut;
+    }
+    if from < end {
+        pieces.push((from, end));
+    }
+    pieces
+}
+
+/// Windows of `size` characters starting every `step` characters.
+fn fixed_windows(text: &str, size: usize, step: usize) -> Vec<TextChunk> {
+    let bounds: Vec<usize> = text
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain(Some(text.len()))
+        .collect();
+    let chars = bounds.len() - 1;
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    loop {
+        let end = (start + size).min(chars);
+        chunks.push(TextChunk {
+            index: chunks.len(),
+            offset: bounds[start],
+            text: text[bounds[start]..bounds[end]].to_string(),
+        });
+        if end == chars {
+            break;
+        }
+        start += step;
+    }
+    chunks
+}
+
+/// Greedily pack consecutive units into chunks of at most `size`
+/// characters, starting each new chunk with the trailing units of the last
+/// one that fit in `overlap`.
+fn pack(text: &str, units: &[(usize, usize)], size: usize, overlap: usize) -> Vec<TextChunk> {
+    let chars = |(s, e): (usize, usize)| text[s..e].chars().count();
+    let mut chunks = Vec::new();
+    let mut first = 0;
+    while first < units.len() {
+        let mut last = first;
+        let mut len = chars(units[first]);
+        while last + 1 < units.len() && len + chars(units[last + 1]) <= size {
+            last += 1;
+            len += chars(units[last]);
+        }
+        let (offset, end) = (units[first].0, units[last].1);
+        if !text[offset..end].trim().is_empty() {
+            chunks.push(TextChunk {
+                index: chunks.len(),
+                offset,
+                text: text[offset..end].to_string(),
+            });
+        }
+        if last + 1 == units.len() {
+            break;
+        }
+
+        // Back up over whole units while they fit in the overlap, but
+        // always move forward past the previous start
+        let mut next = last + 1;
+        let mut carried = 0;
+        while next > first + 1 && carried + chars(units[next - 1]) <= overlap {
+            next -= 1;
+            carried += chars(units[next]);
+        }
+        first = next;
+    }
+    chunks
+}
+
+/// Rebuild a text from its chunks, in any order, dropping the overlap.
+/// Gaps from missing chunks are left as is.
+pub fn reassemble(chunks: &[TextChunk]) -> String {
+    let mut sorted: Vec<&TextChunk> = chunks.iter().collect();
+    sorted.sort_by_key(|c| (c.offset, c.index));
+    let mut out = String::new();
+    for chunk in sorted {
+        let covered = out.len().saturating_sub(chunk.offset);
+        if let Some(rest) = chunk.text.get(covered..) {
+            out.push_str(rest);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(size: usize, overlap: usize, sentence_aware: bool) -> MemoryChunkingConfig {
+        MemoryChunkingConfig {
+            chunk_size: Some(size),
+            chunk_overlap: Some(overlap),
+            sentence_aware,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn splits_at_sentences_with_overlap() {
+        let text = "Disk C: is almost full. Temp files use 12 GB! \
+                    Browser cache uses 3 GB.\nUpdate cache uses 5 GB. Done?";
+        let chunks = chunk_text(text, &config(50, 25, true));
+        assert!(chunks.len() > 1);
+        for (i, chunk) in chunks.iter().enumerate() {
+            assert_eq!(chunk.index, i);
+            assert!(chunk.text.chars().count() <= 50, "{chunk:?}");
+            assert_eq!(
+                &text[chunk.offset..chunk.offset + chunk.text.len()],
+                chunk.text
+            );
+        }
+        // Chunks end at sentence or line boundaries
+        assert_eq!(
+            chunks[0].text,
+            "Disk C: is almost full. Temp files use 12 GB! "
+        );
+        assert!(chunks[1].text.starts_with("Temp files use 12 GB!"));
+        assert_eq!(reassemble(&chunks), text);
+
+        let mut shuffled = chunks.clone();
+        shuffled.reverse();
+        assert_eq!(reassemble(&shuffled), text);
+
+        assert_eq!(chunk_text("short", &config(50, 10, true)).len(), 1);
+        let off = MemoryChunkingConfig {
+            enabled: false,
+            ..config(5, 0, true)
+        };
+        assert_eq!(chunk_text(text, &off)[0].text, text);
+    }
+
+    #[test]
+    fn splits_long_runs_and_fixed_windows() {
+        // One 'sentence' far over the limit, with multi-byte characters
+        let text = "événement ".repeat(30);
+        for sentence_aware in [true, false] {
+            let chunks = chunk_text(&text, &config(40, 10, sentence_aware));
+            assert!(chunks.iter().all(|c| c.text.chars().count() <= 40));
+            assert_eq!(reassemble(&chunks), text);
+        }
+        let fixed = chunk_text(&"x".repeat(100), &config(40, 10, false));
+        let offsets: Vec<usize> = fixed.iter().map(|c| c.offset).collect();
+        assert_eq!(offsets, vec![0, 30, 60]);
+    }
+}
@@ -0,0 +1,110 @@
+//! Grouping of old agent memories for consolidation.
+//!
+//! Memories are clustered greedily: the oldest unassigned memory seeds a
+//! cluster and every other unassigned memory at least `threshold` cosine
+//! similar to the seed joins it, up to `max_cluster` members. Each cluster
+//! of two or more is then summarized by an LLM with [`summary_prompt`] and
+//! replaces its members in the store.
+
+/// Characters of each memory included in the summary prompt.
+const MAX_MEMORY_CHARS: usize = 1_000;
+
+pub fn cosine_similarity(a: &[f64], b: &[f64]) -> f64 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let dot: f64 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f64>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f64>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+/// Indices of `embeddings` grouped into clusters, in input order. Memories
+/// without a similar neighbour come back as single-element clusters.
+pub fn cluster_by_similarity(
+    embeddings: &[Vec<f64>],
+    threshold: f64,
+    max_cluster: usize,
+) -> Vec<Vec<usize>> {
+    let mut assigned = vec![false; embeddings.len()];
+    let mut clusters = Vec::new();
+    for seed in 0..embeddings.len() {
+        if assigned[seed] {
+            continue;
+        }
+        assigned[seed] = true;
+        let mut cluster = vec![seed];
+        for candidate in seed + 1..embeddings.len() {
+            if cluster.len() >= max_cluster {
+                break;
+            }
+            if !assigned[candidate]
+                && cosine_similarity(&embeddings[seed], &embeddings[candidate]) >= threshold
+            {
+                assigned[candidate] = true;
+                cluster.push(candidate);
+            }
+        }
+        clusters.push(cluster);
+    }
+    clusters
+}
+
+/// Element-wise mean, used when no embedding provider can embed a summary.
+pub fn centroid(embeddings: &[&[f64]]) -> Vec<f64> {
+    let Some(first) = embeddings.first() else {
+        return Vec::new();
+    };
+    let mut sum = vec![0.0; first.len()];
+    for embedding in embeddings {
+        for (total, value) in sum.iter_mut().zip(embedding.iter()) {
+            *total += value;
+        }
+    }
+    sum.iter().map(|v| v / embeddings.len() as f64).collect()
+}
+
+/// Prompt asking for one compact memory that preserves the facts of
+/// `memories`, oldest first.
+pub fn summary_prompt(memories: &[&str]) -> String {
+    let mut prompt = String::from(
+        "The following notes were stored by a desktop security and assistant agent. \
+         Merge them into one compact memory of at most 150 words. Keep concrete facts \
+         such as process names, file paths, user preferences, dates and outcomes; drop \
+         repetition. Reply with the memory text only.\n",
+    );
+    for (i, memory) in memories.iter().enumerate() {
+        let memory: String = memory.chars().take(MAX_MEMORY_CHARS).collect();
+        prompt.push_str(&format!("\n{}. {}", i + 1, memory.trim()));
+    }
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clusters_similar_embeddings_around_seeds() {
+        let embeddings = vec![
+            vec![1.0, 0.0, 0.0],
+            vec![0.0, 1.0, 0.0],
+            vec![0.95, 0.05, 0.0],
+            vec![0.9, 0.1, 0.0],
+            vec![0.0, 0.0, 1.0],
+        ];
+        assert_eq!(
+            cluster_by_similarity(&embeddings, 0.9, 20),
+            vec![vec![0, 2, 3], vec![1], vec![4]]
+        );
+        assert_eq!(
+            cluster_by_similarity(&embeddings, 0.9, 2),
+            vec![vec![0, 2], vec![1], vec![3], vec![4]]
+        );
+        assert_eq!(centroid(&[&[1.0, 0.0], &[0.0, 1.0]]), vec![0.5, 0.5]);
+    }
+}
@@ -0,0 +1,226 @@
+//! Token budget for memories injected into LLM prompts.
+//!
+//! Retrieval can return more text than a prompt should carry, and smaller
+//! local models have far less room than cloud ones. The budgeter ranks
+//! retrieved entries by relevance and age, keeps what fits in the
+//! provider's budget, and formats each with the source it came from so the
+//! model (and the log) can tell where a statement originated.
+
+use crate::memory::MemoryEntry;
+use chrono::{DateTime, Utc};
+use log::info;
+use oxide_core::config::ContextBudgetConfig;
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// Memory older than this counts half as much at equal relevance
+const RECENCY_HALF_LIFE_DAYS: f64 = 30.0;
+
+/// Entries are not truncated below this many tokens; they are dropped
+const MIN_ENTRY_TOKENS: usize = 24;
+
+/// Rough token count, about four characters per token for English text.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// One memory that made it into a prompt
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InjectedMemory {
+    pub id: String,
+    pub source: String,
+    pub relevance: f32,
+    pub tokens: usize,
+    pub truncated: bool,
+}
+
+/// Memory context selected for one LLM call
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct InjectedContext {
+    pub provider: String,
+    pub budget_tokens: usize,
+    pub used_tokens: usize,
+    pub included: Vec<InjectedMemory>,
+    /// Entries left out for relevance, duplication or budget
+    pub dropped: usize,
+    /// Attributed memory lines, empty when nothing was selected
+    #[serde(skip)]
+    pub text: String,
+}
+
+pub struct ContextBudgeter {
+    config: ContextBudgetConfig,
+}
+
+impl ContextBudgeter {
+    pub fn new(config: ContextBudgetConfig) -> Self {
+        Self { config }
+    }
+
+    /// Rank `entries`, keep what fits in `provider`'s budget, and log the
+    /// selection.
+    pub fn select(&self, provider: &str, entries: Vec<MemoryEntry>) -> InjectedContext {
+        let injected = self.select_at(Utc::now(), provider, entries);
+        info!(
+            "Injecting {} memories ({}/{} tokens, {} dropped) for {}: [{}]",
+            injected.included.len(),
+            injected.used_tokens,
+            injected.budget_tokens,
+            injected.dropped,
+            injected.provider,
+            injected
+                .included
+                .iter()
+                .map(|m| format!("{} from {}", m.id, m.source))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        injected
+    }
+
+    fn select_at(
+        &self,
+        now: DateTime<Utc>,
+        provider: &str,
+        entries: Vec<MemoryEntry>,
+    ) -> InjectedContext {
+        let budget = self.config.tokens_for(provider);
+        let min_relevance = self
+            .config
+            .min_relevance
+            .unwrap_or(ContextBudgetConfig::DEFAULT_MIN_RELEVANCE);
+        let total = entries.len();
+
+        let mut ranked: Vec<(f64, MemoryEntry)> = entries
+            .into_iter()
+            .filter(|e| e.relevance_score >= min_relevance && !e.content.trim().is_empty())
+            .map(|e| (rank(&e, now), e))
+            .collect();
+        ranked.sort_by(|a, b| {
+            b.0.total_cmp(&a.0)
+                .then_with(|| b.1.timestamp.cmp(&a.1.timestamp))
+        });
+
+        let mut injected = InjectedContext {
+            provider: provider.to_string(),
+            budget_tokens: budget,
+            ..Default::default()
+        };
+        let mut seen = HashSet::new();
+        let mut lines = Vec::new();
+        for (_, entry) in ranked {
+            let content = entry.content.trim();
+            if !seen.insert(content.to_string()) {
+                continue;
+            }
+            let source = source_of(&entry);
+            let header = format!(
+                "- [{}] ({source}, {}) ",
+                lines.len() + 1,
+                entry.timestamp.format("%Y-%m-%d")
+            );
+            let remaining = budget.saturating_sub(injected.used_tokens);
+            let header_tokens = estimate_tokens(&header);
+            if remaining < header_tokens + MIN_ENTRY_TOKENS.min(estimate_tokens(content)) {
+                break;
+            }
+
+            let room = (remaining - header_tokens) * 4;
+            let (body, truncated) = if content.chars().count() <= room {
+                (content.to_string(), false)
+            } else {
+                let cut: String = content.chars().take(room.saturating_sub(1)).collect();
+                (format!("{}…", cut.trim_end()), true)
+            };
+            let line = format!("{header}{}", body.replace('\n', " "));
+            let tokens = estimate_tokens(&line);
+            injected.used_tokens += tokens;
+            injected.included.push(InjectedMemory {
+                id: entry.id.clone(),
+                source,
+                relevance: entry.relevance_score,
+                tokens,
+                truncated,
+            });
+            lines.push(line);
+            if truncated {
+                break;
+            }
+        }
+
+        injected.dropped = total - injected.included.len();
+        injected.text = lines.join("\n");
+        injected
+    }
+}
+
+/// Relevance weighted by age: full weight when new, half after the
+/// half-life, never below a quarter so old but exact matches survive.
+fn rank(entry: &MemoryEntry, now: DateTime<Utc>) -> f64 {
+    let age_days = (now - entry.timestamp).num_seconds().max(0) as f64 / 86_400.0;
+    let recency = 0.5f64.powf(age_days / RECENCY_HALF_LIFE_DAYS);
+    entry.relevance_score as f64 * (0.25 + 0.75 * recency)
+}
+
+fn source_of(entry: &MemoryEntry) -> String {
+    entry
+        .metadata
+        .get("source")
+        .cloned()
+        .unwrap_or_else(|| format!("{:?}", entry.entry_type))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MemoryEntryType;
+    use std::collections::HashMap;
+
+    fn entry(id: &str, content: &str, relevance: f32, age_days: i64) -> MemoryEntry {
+        MemoryEntry {
+            id: id.to_string(),
+            timestamp: Utc::now() - chrono::Duration::days(age_days),
+            entry_type: MemoryEntryType::ThreatDetection,
+            content: content.to_string(),
+            metadata: HashMap::new(),
+            relevance_score: relevance,
+            tags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn ranks_dedupes_and_fits_the_budget() {
+        let budgeter = ContextBudgeter::new(ContextBudgetConfig {
+            default_tokens: Some(60),
+            provider_tokens: HashMap::from([("ollama".to_string(), 10)]),
+            ..Default::default()
+        });
+        let mut sourced = entry("b", "Blocked powershell download cradle", 0.8, 0);
+        sourced
+            .metadata
+            .insert("source".to_string(), "threat_report".to_string());
+        let entries = vec![
+            entry("a", "Old but relevant: svchost in temp folder", 0.9, 120),
+            sourced,
+            entry("dup", "Blocked powershell download cradle", 0.7, 0),
+            entry("low", "Unrelated", 0.1, 0),
+            entry("long", &"x".repeat(400), 0.6, 0),
+        ];
+
+        let injected = budgeter.select_at(Utc::now(), "OpenAI", entries.clone());
+        let ids: Vec<&str> = injected.included.iter().map(|m| m.id.as_str()).collect();
+        // The old entry ranks below fresh ones; the long one is cut to fit
+        // and fills the budget
+        assert_eq!(ids, vec!["b", "long"]);
+        assert!(injected.used_tokens <= 60);
+        assert!(injected.included[1].truncated);
+        assert_eq!(injected.dropped, 3);
+        assert!(injected.text.starts_with("- [1] (threat_report, "));
+        assert!(injected.text.contains("(ThreatDetection, "));
+
+        // A tiny budget drops everything rather than injecting fragments
+        let small = budgeter.select_at(Utc::now(), "Ollama", entries);
+        assert!(small.included.is_empty());
+        assert_eq!(small.text, "");
+    }
+}
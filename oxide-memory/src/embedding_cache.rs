@@ -0,0 +1,162 @@
+//! Embedding cache keyed by model and content hash.
+//!
+//! Ingestion embeds the same strings over and over (repeated log lines,
+//! identical process summaries). The backend checks this in-memory LRU
+//! first, then the persistent `embedding_cache` table, and only calls the
+//! embedding API on a miss in both.
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+/// In-memory entries kept when `OXIDE_EMBEDDINGS_CACHE_SIZE` is unset
+pub const DEFAULT_CACHE_SIZE: usize = 4096;
+
+/// Cache key for `text` embedded by `model`; the model id should include
+/// anything that changes the vector, such as the output dimension.
+pub fn content_key(model: &str, text: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(model.as_bytes());
+    hasher.update([0u8]);
+    hasher.update(text.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct EmbeddingCacheStats {
+    pub entries: usize,
+    pub capacity: usize,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+#[derive(Default)]
+struct Lru {
+    tick: u64,
+    entries: HashMap<String, (Arc<Vec<f64>>, u64)>,
+    /// Last-use tick to key, oldest first
+    order: BTreeMap<u64, String>,
+    hits: u64,
+    misses: u64,
+}
+
+impl Lru {
+    fn touch(&mut self, key: &str) -> Option<Arc<Vec<f64>>> {
+        self.tick += 1;
+        let tick = self.tick;
+        let (vector, used) = self.entries.get_mut(key)?;
+        self.order.remove(used);
+        *used = tick;
+        self.order.insert(tick, key.to_string());
+        Some(vector.clone())
+    }
+}
+
+/// Bounded least-recently-used map from [`content_key`] to vector.
+pub struct EmbeddingCache {
+    capacity: usize,
+    inner: Mutex<Lru>,
+}
+
+impl EmbeddingCache {
+    /// A cache holding up to `capacity` vectors; 0 disables it.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inner: Mutex::new(Lru::default()),
+        }
+    }
+
+    /// Capacity from `OXIDE_EMBEDDINGS_CACHE_SIZE`.
+    pub fn from_env() -> Self {
+        let capacity = std::env::var("OXIDE_EMBEDDINGS_CACHE_SIZE")
+            .ok()
+            .and_then(|v| v.trim().parse::<usize>().ok())
+            .unwrap_or(DEFAULT_CACHE_SIZE);
+        Self::new(capacity)
+    }
+
+    pub fn get(&self, key: &str) -> Option<Vec<f64>> {
+        if self.capacity == 0 {
+            return None;
+        }
+        let mut lru = self.inner.lock().ok()?;
+        match lru.touch(key) {
+            Some(vector) => {
+                lru.hits += 1;
+                Some(vector.as_ref().clone())
+            }
+            None => {
+                lru.misses += 1;
+                None
+            }
+        }
+    }
+
+    pub fn insert(&self, key: String, vector: Vec<f64>) {
+        if self.capacity == 0 {
+            return;
+        }
+        let Ok(mut lru) = self.inner.lock() else {
+            return;
+        };
+        if lru.touch(&key).is_some() {
+            if let Some(entry) = lru.entries.get_mut(&key) {
+                entry.0 = Arc::new(vector);
+            }
+            return;
+        }
+        while lru.entries.len() >= self.capacity {
+            let Some((_, oldest)) = lru.order.pop_first() else {
+                break;
+            };
+            lru.entries.remove(&oldest);
+        }
+        let tick = lru.tick;
+        lru.order.insert(tick, key.clone());
+        lru.entries.insert(key, (Arc::new(vector), tick));
+    }
+
+    pub fn stats(&self) -> EmbeddingCacheStats {
+        let lru = self.inner.lock();
+        lru.map(|lru| EmbeddingCacheStats {
+            entries: lru.entries.len(),
+            capacity: self.capacity,
+            hits: lru.hits,
+            misses: lru.misses,
+        })
+        .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let cache = EmbeddingCache::new(2);
+        let a = content_key("openai:text-embedding-3-small:1536", "disk full");
+        let b = content_key("openai:text-embedding-3-small:1536", "cpu spike");
+        let c = content_key("openai:text-embedding-3-small:1536", "new process");
+        assert_eq!(a.len(), 64);
+        assert_ne!(a, content_key("local:http://x:1536", "disk full"));
+
+        cache.insert(a.clone(), vec![1.0]);
+        cache.insert(b.clone(), vec![2.0]);
+        // Reading `a` makes `b` the eviction candidate
+        assert_eq!(cache.get(&a), Some(vec![1.0]));
+        cache.insert(c.clone(), vec![3.0]);
+        assert_eq!(cache.get(&b), None);
+        assert_eq!(cache.get(&a), Some(vec![1.0]));
+        assert_eq!(cache.get(&c), Some(vec![3.0]));
+
+        let stats = cache.stats();
+        assert_eq!((stats.entries, stats.hits, stats.misses), (2, 3, 1));
+
+        let disabled = EmbeddingCache::new(0);
+        disabled.insert(a.clone(), vec![1.0]);
+        assert_eq!(disabled.get(&a), None);
+    }
+}
@@ -0,0 +1,318 @@
+//! Mirror of this machine's metrics, threats and scan summaries on a
+//! central SurrealDB shared by a fleet.
+//!
+//! Unlike pointing the whole backend at a remote server (see
+//! [`crate::surreal_connection`]), the uplink keeps the local store and
+//! copies records to the central server in batches, each tagged with the
+//! machine id. Records wait in an [`UplinkQueue`] while the server is
+//! unreachable; the queue is spooled to disk so offline periods and
+//! restarts lose nothing until it holds more than its limit, at which point
+//! the oldest records are dropped.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+
+pub const SPOOL_PATH: &str = "./data/fleet_spool.jsonl";
+pub const MACHINE_ID_PATH: &str = "./data/machine_id";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FleetRecordKind {
+    Metric,
+    Threat,
+    Scan,
+}
+
+impl FleetRecordKind {
+    pub const ALL: [FleetRecordKind; 3] = [
+        FleetRecordKind::Metric,
+        FleetRecordKind::Threat,
+        FleetRecordKind::Scan,
+    ];
+
+    /// Table on the central server.
+    pub fn table(self) -> &'static str {
+        match self {
+            FleetRecordKind::Metric => "fleet_metrics",
+            FleetRecordKind::Threat => "fleet_threats",
+            FleetRecordKind::Scan => "fleet_scans",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FleetRecord {
+    pub kind: FleetRecordKind,
+    pub machine_id: String,
+    pub hostname: Option<String>,
+    pub recorded_at: DateTime<Utc>,
+    pub payload: serde_json::Value,
+}
+
+/// Records waiting for the central server, oldest first.
+pub struct UplinkQueue {
+    records: VecDeque<FleetRecord>,
+    max: usize,
+    dropped: u64,
+}
+
+impl UplinkQueue {
+    pub fn new(max: usize) -> Self {
+        Self {
+            records: VecDeque::new(),
+            max: max.max(1),
+            dropped: 0,
+        }
+    }
+
+    /// A queue holding what an earlier run spooled to `path`. Lines that no
+    /// longer parse are skipped.
+    pub fn load_spool(path: &Path, max: usize) -> Self {
+        let mut queue = Self::new(max);
+        if let Ok(file) = fs::File::open(path) {
+            BufReader::new(file)
+                .lines()
+                .map_while(Result::ok)
+                .filter_map(|line| serde_json::from_str(&line).ok())
+                .for_each(|record| queue.push(record));
+        }
+        queue
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Records dropped because the queue was full.
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    pub fn push(&mut self, record: FleetRecord) {
+        self.records.push_back(record);
+        self.trim();
+    }
+
+    /// Up to `n` of the oldest records.
+    pub fn take_batch(&mut self, n: usize) -> Vec<FleetRecord> {
+        let n = n.min(self.records.len());
+        self.records.drain(..n).collect()
+    }
+
+    /// Put back a batch that could not be sent, ahead of newer records.
+    pub fn requeue(&mut self, batch: Vec<FleetRecord>) {
+        for record in batch.into_iter().rev() {
+            self.records.push_front(record);
+        }
+        self.trim();
+    }
+
+    /// Write the queue to `path` as JSON lines; an empty queue removes it.
+    pub fn spool(&self, path: &Path) -> Result<(), String> {
+        if self.records.is_empty() {
+            return match fs::remove_file(path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    Err(format!("Failed to remove {path:?}: {e}"))
+                }
+                _ => Ok(()),
+            };
+        }
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create {dir:?}: {e}"))?;
+        }
+        let tmp = path.with_extension("jsonl.tmp");
+        let write = || -> std::io::Result<()> {
+            let mut file = std::io::BufWriter::new(fs::File::create(&tmp)?);
+            for record in &self.records {
+                serde_json::to_writer(&mut file, record)?;
+                file.write_all(b"\n")?;
+            }
+            file.flush()?;
+            drop(file);
+            fs::rename(&tmp, path)
+        };
+        write().map_err(|e| format!("Failed to spool fleet records to {path:?}: {e}"))
+    }
+
+    fn trim(&mut self) {
+        while self.records.len() > self.max {
+            self.records.pop_front();
+            self.dropped += 1;
+        }
+    }
+}
+
+/// Stable id for this machine, generated on first use.
+pub fn load_machine_id(path: &Path) -> Result<String, String> {
+    if let Ok(id) = fs::read_to_string(path) {
+        let id = id.trim();
+        if !id.is_empty() {
+            return Ok(id.to_string());
+        }
+    }
+    let id = uuid::Uuid::new_v4().to_string();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {dir:?}: {e}"))?;
+    }
+    fs::write(path, &id).map_err(|e| format!("Failed to write {path:?}: {e}"))?;
+    Ok(id)
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UplinkStatus {
+    pub enabled: bool,
+    pub machine_id: Option<String>,
+    /// Central server, without credentials
+    pub endpoint: Option<String>,
+    pub connected: bool,
+    pub queued: usize,
+    pub sent: u64,
+    pub dropped: u64,
+    pub last_flush_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
+
+#[cfg(feature = "surrealdb")]
+pub use writer::FleetWriter;
+
+#[cfg(feature = "surrealdb")]
+mod writer {
+    use super::{FleetRecord, FleetRecordKind};
+    use crate::surreal_connection::SurrealConnection;
+    use anyhow::{Context, Result};
+    use surrealdb::engine::any::Any;
+    use surrealdb::Surreal;
+
+    /// Writes batches to the central server, reconnecting after failures.
+    pub struct FleetWriter {
+        connection: SurrealConnection,
+        namespace: String,
+        database: String,
+        db: Option<Surreal<Any>>,
+    }
+
+    impl FleetWriter {
+        pub fn new(connection: SurrealConnection, namespace: String, database: String) -> Self {
+            Self {
+                connection,
+                namespace,
+                database,
+                db: None,
+            }
+        }
+
+        pub fn endpoint(&self) -> String {
+            self.connection.describe()
+        }
+
+        pub fn connected(&self) -> bool {
+            self.db.is_some()
+        }
+
+        /// Write `batch`, one transaction per record kind. On failure the
+        /// connection is dropped and the whole batch should be retried.
+        pub async fn write(&mut self, batch: &[FleetRecord]) -> Result<()> {
+            let result = self.try_write(batch).await;
+            if result.is_err() {
+                self.db = None;
+            }
+            result
+        }
+
+        async fn try_write(&mut self, batch: &[FleetRecord]) -> Result<()> {
+            if self.db.is_none() {
+                let db = self
+                    .connection
+                    .open(&self.namespace, &self.database)
+                    .await?;
+                db.use_ns(&self.namespace)
+                    .use_db(&self.database)
+                    .await
+                    .context("Failed to select the fleet namespace")?;
+                self.db = Some(db);
+            }
+            let Some(db) = &self.db else {
+                return Ok(());
+            };
+            for kind in FleetRecordKind::ALL {
+                let rows: Vec<&FleetRecord> = batch.iter().filter(|r| r.kind == kind).collect();
+                if rows.is_empty() {
+                    continue;
+                }
+                let rows = serde_json::to_value(rows).context("Failed to serialize records")?;
+                db.query(
+                    r#"
+                    BEGIN TRANSACTION;
+                    FOR $r IN $rows {
+                        CREATE type::table($table) CONTENT {
+                            machine_id: $r.machine_id,
+                            hostname: $r.hostname,
+                            recorded_at: <datetime> $r.recorded_at,
+                            payload: $r.payload
+                        };
+                    };
+                    COMMIT TRANSACTION;
+                    "#,
+                )
+                .bind(("rows", rows))
+                .bind(("table", kind.table()))
+                .await
+                .with_context(|| format!("Failed to write {}", kind.table()))?
+                .check()
+                .with_context(|| format!("Writing {} was rejected", kind.table()))?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(n: u64) -> FleetRecord {
+        FleetRecord {
+            kind: FleetRecordKind::Metric,
+            machine_id: "m1".to_string(),
+            hostname: None,
+            recorded_at: Utc::now(),
+            payload: serde_json::json!({ "n": n }),
+        }
+    }
+
+    #[test]
+    fn queue_keeps_order_drops_oldest_and_survives_spooling() {
+        let mut queue = UplinkQueue::new(3);
+        (1..=4).for_each(|n| queue.push(record(n)));
+        assert_eq!(queue.dropped(), 1);
+
+        let batch = queue.take_batch(2);
+        assert_eq!(batch[0].payload["n"], 2);
+        queue.push(record(5));
+        queue.requeue(batch);
+        // 2, 3 went back in front of 4 and 5; 2 no longer fits
+        assert_eq!(queue.len(), 3);
+        assert_eq!(queue.dropped(), 2);
+        assert_eq!(queue.take_batch(1)[0].payload["n"], 3);
+
+        let dir = std::env::temp_dir().join(format!("oxide-fleet-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("spool.jsonl");
+        queue.spool(&path).unwrap();
+        let restored = UplinkQueue::load_spool(&path, 10);
+        assert_eq!(restored.len(), 2);
+        UplinkQueue::new(10).spool(&path).unwrap();
+        assert!(!path.exists());
+
+        let id = load_machine_id(&dir.join("machine_id")).unwrap();
+        assert_eq!(load_machine_id(&dir.join("machine_id")).unwrap(), id);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,15 +1,44 @@
 pub mod backend;
+pub mod chunking;
+pub mod consolidation;
+pub mod context_budget;
+pub mod embedding_cache;
+pub mod fleet_uplink;
 pub mod memory;
+pub mod metrics_query;
+pub mod patterns;
+pub mod threat_training;
 
+#[cfg(feature = "surrealdb")]
+pub mod benchmark;
+#[cfg(feature = "surrealdb")]
+pub mod migration;
+#[cfg(feature = "surrealdb")]
+pub mod shared_backend;
 #[cfg(feature = "surrealdb")]
 pub mod surreal_backend;
+#[cfg(feature = "surrealdb")]
+pub mod surreal_connection;
 
 // Re-export key types for convenience
 pub use backend::{BackendSearchItem, MemoryBackend};
+pub use embedding_cache::EmbeddingCacheStats;
+pub use threat_training::{ClassMetrics, LabelSource, ModelEvaluation, ThreatScorer};
 
+#[cfg(feature = "surrealdb")]
+pub use migration::{MemoryBackendKind, MigrationReport};
+#[cfg(feature = "surrealdb")]
+pub use shared_backend::SharedSurrealBackend;
 #[cfg(feature = "surrealdb")]
 pub use surreal_backend::{
-    AgentMemory, AgentType, DiskIO, IncidentInfo, IncidentSeverity, MemorySource, MemoryUsage,
-    MitigationStatus, NetworkStats, ProcessInfo, ProcessStatus, ResolutionStatus, SurrealBackend,
-    SystemMetric, ThreatInfo, ThreatSeverity, ThreatTrainingSample,
+    diff_scan_verdicts, AgentMemory, AgentMemoryEntry, AgentMemoryPage, AgentType, BackupInfo,
+    BatteryStatus, CompactionReport, ConsensusRunEntry, ConsensusRunRecord, ConsolidationCandidate,
+    DiskIO, GpuMetrics, IncidentInfo, IncidentSeverity, MaintenanceProgress, MemoryExportFilter,
+    MemorySort, MemorySource, MemoryTransferReport, MemoryUsage, MitigationStatus, NetworkStats,
+    ProcessInfo, ProcessSample, ProcessStatus, ProcessTrend, ReEmbedPolicy, ResolutionStatus,
+    ScanCacheEntry, ScanDetails, ScanDiff, ScanFileChange, ScanFileVerdict, ScanRunSummary,
+    SurrealBackend, SystemMetric, ThreatInfo, ThreatPrediction, ThreatRecord, ThreatSeverity,
+    ThreatTrainingSample, VectorIndexReport,
 };
+#[cfg(feature = "surrealdb")]
+pub use surreal_connection::{SurrealConnection, SurrealCredentials};
@@ -1,6 +1,8 @@
 use crate::backend::MemoryBackend;
-use chrono::{DateTime, Utc};
+use crate::patterns;
+use chrono::{DateTime, Local, Timelike, Utc};
 use log::{info, warn};
+use oxide_core::capabilities::{self, Feature};
 use oxide_core::types::{Interaction, SystemEvent};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -39,15 +41,22 @@ pub struct UserPattern {
     pub last_occurrence: DateTime<Utc>,
     pub confidence: f32,
     pub description: String,
+    // Occurrence count decayed over time, see `patterns::HALF_LIFE_DAYS`
+    #[serde(default)]
+    pub weight: f64,
+    // Occurrences per local hour of day
+    #[serde(default)]
+    pub hours: Vec<u32>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PatternType {
     ApplicationUsage,
     TimeBasedActivity,
     CommandSequence,
     ErrorResolution,
     SystemConfiguration,
+    TopicInterest,
 }
 
 #[derive(Debug, Clone)]
@@ -90,6 +99,19 @@ impl MemoryManager {
         manager
     }
 
+    /// An empty manager storing under `storage_path` that shares this
+    /// manager's backend. Used to give each profile its own JSON store.
+    pub fn with_storage_path(&self, storage_path: String) -> Self {
+        let mut manager = Self::new(Some(storage_path));
+        manager.max_entries = self.max_entries;
+        manager.backend = self.backend.clone();
+        manager
+    }
+
+    pub fn storage_path(&self) -> &str {
+        &self.storage_path
+    }
+
     /// Attach or replace the external backend after construction.
     pub fn set_backend(&mut self, backend: Arc<dyn MemoryBackend>) {
         self.backend = Some(backend);
@@ -104,6 +126,17 @@ impl MemoryManager {
         // Load existing memory from disk
         self.load_from_disk().await?;
 
+        if self.backend.is_some() {
+            capabilities::available(Feature::VectorSearch);
+        } else if cfg!(feature = "surrealdb") {
+            capabilities::degraded(
+                Feature::VectorSearch,
+                "No vector database connected; memory uses keyword matching",
+            );
+        } else {
+            capabilities::not_compiled(Feature::VectorSearch, "surrealdb-metrics");
+        }
+
         info!(
             "Memory manager initialized with storage path: {}",
             self.storage_path
@@ -358,30 +391,66 @@ impl MemoryManager {
     }
 
     async fn analyze_user_patterns(&self, interaction: &Interaction) -> Result<(), String> {
-        // Analyze user patterns (simplified implementation)
-        let pattern_id = format!("user_pattern_{}", chrono::Utc::now().timestamp());
-
-        let pattern = UserPattern {
-            pattern_id: pattern_id.clone(),
-            pattern_type: PatternType::ApplicationUsage,
-            frequency: 1,
-            last_occurrence: interaction.timestamp,
-            confidence: 0.5,
-            description: format!(
-                "User interaction pattern: {}",
-                interaction.user_input.chars().take(50).collect::<String>()
-            ),
-        };
+        let hour = interaction.timestamp.with_timezone(&Local).hour();
+        let observations = patterns::observe(
+            &interaction.user_input,
+            interaction.context.active_window.as_deref(),
+            hour,
+        );
 
         let mut patterns = self.user_patterns.lock().await;
-        patterns.insert(pattern_id, pattern);
+        for observation in observations {
+            patterns
+                .entry(observation.pattern_id.clone())
+                .or_insert_with(|| {
+                    UserPattern::from_observation(observation, interaction.timestamp)
+                })
+                .record(interaction.timestamp, hour);
+        }
+        patterns::prune(&mut patterns, Utc::now());
 
         Ok(())
     }
 
+    /// All patterns, most confident first, with confidence as of now.
     pub async fn get_user_patterns(&self) -> Vec<UserPattern> {
+        let now = Utc::now();
+        let patterns = self.user_patterns.lock().await;
+        let mut patterns: Vec<UserPattern> = patterns
+            .values()
+            .cloned()
+            .map(|mut pattern| {
+                pattern.confidence = pattern.confidence_at(now);
+                pattern
+            })
+            .collect();
+        patterns.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+        patterns
+    }
+
+    /// Up to `limit` patterns worth preloading as context for a request made
+    /// at `now`: confident habits, favouring those usual at this time of day.
+    pub async fn relevant_patterns(&self, now: DateTime<Utc>, limit: usize) -> Vec<UserPattern> {
+        const MIN_SCORE: f32 = 0.25;
+        let hour = now.with_timezone(&Local).hour();
         let patterns = self.user_patterns.lock().await;
-        patterns.values().cloned().collect()
+        let mut scored: Vec<(f32, UserPattern)> = patterns
+            .values()
+            .filter(|pattern| pattern.pattern_type != PatternType::TimeBasedActivity)
+            .map(|pattern| (pattern.preload_score(now, hour), pattern))
+            .filter(|(score, _)| *score >= MIN_SCORE)
+            .map(|(score, pattern)| {
+                let mut pattern = pattern.clone();
+                pattern.confidence = pattern.confidence_at(now);
+                (score, pattern)
+            })
+            .collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+        scored
+            .into_iter()
+            .take(limit)
+            .map(|(_, pattern)| pattern)
+            .collect()
     }
 
     async fn save_to_disk(&self) -> Result<(), String> {
@@ -434,7 +503,9 @@ impl MemoryManager {
             match fs::read_to_string(&patterns_file).await {
                 Ok(content) => {
                     match serde_json::from_str::<HashMap<String, UserPattern>>(&content) {
-                        Ok(loaded_patterns) => {
+                        Ok(mut loaded_patterns) => {
+                            // One entry per message, written before patterns were mined
+                            loaded_patterns.retain(|id, _| !id.starts_with("user_pattern_"));
                             let mut patterns = self.user_patterns.lock().await;
                             *patterns = loaded_patterns;
                             info!("Loaded {} user patterns from disk", patterns.len());
@@ -0,0 +1,305 @@
+//! Guard for model-written SurrealQL over stored telemetry.
+//!
+//! The `query_metrics` copilot tool asks an LLM to turn a question into a
+//! single `SELECT`. [`prepare`] only lets that statement through if it reads
+//! [`QUERYABLE_TABLES`] with side-effect-free functions, and appends a row
+//! cap and a timeout before it reaches the database.
+
+use thiserror::Error;
+
+/// Tables a generated query may read.
+pub const QUERYABLE_TABLES: [&str; 3] = ["system_metrics", "process", "threat"];
+
+/// Upper bound on rows returned to the model.
+pub const MAX_ROWS: usize = 200;
+
+/// Server-side limit applied to every generated query.
+pub const QUERY_TIMEOUT: &str = "5s";
+
+/// Schema summary handed to the model alongside the question.
+pub const SCHEMA_SUMMARY: &str = r#"system_metrics (one row every few seconds):
+  timestamp datetime, cpu_usage float (0-100), core_usage array<float>,
+  memory_usage { total_mb, used_mb, available_mb, percent },
+  disk_io { read_mb_per_sec, write_mb_per_sec, iops },
+  network_stats { sent_mb_per_sec, recv_mb_per_sec, connections_active },
+  gpus array<{ name, utilization_percent, vram_used_mb, vram_total_mb, temperature_c }>,
+  cpu_temperature_c option<float>, battery option<{ percent, charging }>
+process (snapshot per process):
+  pid int, name string, exe_path option<string>, cmd array<string>,
+  start_time datetime, end_time option<datetime>, cpu_percent float,
+  memory_mb float, threads int, status 'running'|'sleeping'|'stopped'|'zombie'
+threat (Guardian detections):
+  severity 'low'|'medium'|'high'|'critical', yara_rule option<string>,
+  heuristic_score option<float>, risk_score option<float> (0-100),
+  timestamp datetime, indicators array<string>,
+  mitigation_status 'detected'|'quarantined'|'deleted'|'whitelisted'|'investigating'"#;
+
+/// Function namespaces a generated query may call.
+const FUNCTION_NAMESPACES: [&str; 7] = [
+    "array", "count", "duration", "math", "string", "time", "type",
+];
+
+/// `type::` functions that build record links into arbitrary tables.
+const RECORD_FUNCTIONS: [&str; 3] = ["record", "table", "thing"];
+
+const FORBIDDEN_KEYWORDS: [&str; 29] = [
+    "ACCESS", "ALTER", "BEGIN", "CANCEL", "COMMIT", "CREATE", "DEFINE", "DELETE", "EXPLAIN",
+    "FETCH", "FOR", "IF", "INFO", "INSERT", "KILL", "LET", "LIVE", "OPTION", "PARALLEL", "REBUILD",
+    "RELATE", "REMOVE", "RETURN", "SHOW", "SLEEP", "TIMEOUT", "UPDATE", "UPSERT", "USE",
+];
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum QueryError {
+    #[error("Query is empty")]
+    Empty,
+    #[error("Only a single SELECT statement is allowed")]
+    NotSelect,
+    #[error("Unterminated string literal")]
+    UnterminatedString,
+    #[error("'{0}' is not allowed in telemetry queries")]
+    Forbidden(String),
+    #[error("Table '{0}' is not queryable; use one of system_metrics, process, threat")]
+    Table(String),
+    #[error("Function namespace '{0}::' is not allowed")]
+    Function(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Literal,
+    Punct(&'static str),
+    Other(char),
+}
+
+fn tokenize(query: &str) -> Result<Vec<Token>, QueryError> {
+    const MULTI: [&str; 7] = ["<->", "::", "->", "<-", "--", "//", "/*"];
+
+    let mut tokens = Vec::new();
+    let chars: Vec<char> = query.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '\'' || c == '"' {
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    None => return Err(QueryError::UnterminatedString),
+                    Some('\\') => i += 2,
+                    Some(&q) if q == c => break,
+                    Some(_) => i += 1,
+                }
+            }
+            i += 1;
+            tokens.push(Token::Literal);
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while chars
+                .get(i)
+                .is_some_and(|c| c.is_ascii_alphanumeric() || *c == '_')
+            {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else if c.is_ascii_digit() {
+            // Numbers and durations such as 1.5, 24h or 7d
+            while chars
+                .get(i)
+                .is_some_and(|c| c.is_ascii_alphanumeric() || *c == '.' || *c == '_')
+            {
+                i += 1;
+            }
+            tokens.push(Token::Literal);
+        } else if let Some(op) = MULTI.iter().find(|op| {
+            op.chars()
+                .enumerate()
+                .all(|(k, ch)| chars.get(i + k) == Some(&ch))
+        }) {
+            i += op.len();
+            tokens.push(Token::Punct(op));
+        } else {
+            i += 1;
+            tokens.push(Token::Other(c));
+        }
+    }
+    Ok(tokens)
+}
+
+fn is_queryable(table: &str) -> bool {
+    QUERYABLE_TABLES
+        .iter()
+        .any(|t| t.eq_ignore_ascii_case(table))
+}
+
+fn check(tokens: &[Token]) -> Result<(), QueryError> {
+    match tokens.first() {
+        Some(Token::Ident(first)) if first.eq_ignore_ascii_case("SELECT") => {}
+        Some(_) => return Err(QueryError::NotSelect),
+        None => return Err(QueryError::Empty),
+    }
+
+    for (i, token) in tokens.iter().enumerate() {
+        let next = tokens.get(i + 1);
+        match token {
+            Token::Punct(op) if *op != "::" => {
+                return Err(QueryError::Forbidden((*op).to_string()));
+            }
+            Token::Other(c @ (';' | '$' | '#' | '`' | '⟨' | '⟩')) => {
+                return Err(if *c == ';' {
+                    QueryError::NotSelect
+                } else {
+                    QueryError::Forbidden(c.to_string())
+                });
+            }
+            Token::Ident(word) => {
+                let upper = word.to_ascii_uppercase();
+                if FORBIDDEN_KEYWORDS.contains(&upper.as_str()) {
+                    return Err(QueryError::Forbidden(upper));
+                }
+                let after_namespace = i > 0 && tokens[i - 1] == Token::Punct("::");
+                match next {
+                    Some(Token::Punct("::")) if !after_namespace => {
+                        let namespace = word.to_ascii_lowercase();
+                        if !FUNCTION_NAMESPACES.contains(&namespace.as_str()) {
+                            return Err(QueryError::Function(namespace));
+                        }
+                        if let Some(Token::Ident(function)) = tokens.get(i + 2) {
+                            let function = function.to_ascii_lowercase();
+                            if namespace == "type" && RECORD_FUNCTIONS.contains(&function.as_str())
+                            {
+                                return Err(QueryError::Forbidden(format!("type::{function}")));
+                            }
+                        }
+                    }
+                    // Record ids such as `agent_memory:abc` read other tables
+                    Some(Token::Other(':')) if !is_queryable(word) => {
+                        return Err(QueryError::Table(word.clone()));
+                    }
+                    _ => {}
+                }
+                if upper == "FROM" {
+                    check_sources(&tokens[i + 1..])?;
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Every source after `FROM` must be a subquery or a queryable table.
+fn check_sources(tokens: &[Token]) -> Result<(), QueryError> {
+    let mut rest = tokens;
+    if let Some(Token::Ident(word)) = rest.first() {
+        if word.eq_ignore_ascii_case("ONLY") {
+            rest = &rest[1..];
+        }
+    }
+    loop {
+        match rest.first() {
+            Some(Token::Other('(')) => return Ok(()),
+            Some(Token::Ident(table)) if is_queryable(table) => {}
+            Some(Token::Ident(table)) => return Err(QueryError::Table(table.clone())),
+            _ => return Err(QueryError::NotSelect),
+        }
+        match rest.get(1) {
+            Some(Token::Other(',')) => rest = &rest[2..],
+            _ => return Ok(()),
+        }
+    }
+}
+
+/// Validate a generated query and return the statement to execute, with a
+/// `LIMIT` (unless the query has one) and a `TIMEOUT` appended.
+pub fn prepare(query: &str) -> Result<String, QueryError> {
+    let query = query.trim().trim_end_matches(';').trim_end();
+    let tokens = tokenize(query)?;
+    check(&tokens)?;
+
+    let has_limit = tokens
+        .iter()
+        .any(|t| matches!(t, Token::Ident(w) if w.eq_ignore_ascii_case("LIMIT")));
+    let mut statement = query.to_string();
+    if !has_limit {
+        statement.push_str(&format!(" LIMIT {MAX_ROWS}"));
+    }
+    statement.push_str(&format!(" TIMEOUT {QUERY_TIMEOUT}"));
+    Ok(statement)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aggregate_selects_are_prepared() {
+        let statement = prepare(
+            "SELECT math::max(cpu_usage) AS peak_cpu FROM system_metrics \
+             WHERE timestamp >= d'2026-10-15T17:00:00Z' AND timestamp < time::now() - 1h GROUP ALL;",
+        )
+        .unwrap();
+        assert!(statement.ends_with("GROUP ALL LIMIT 200 TIMEOUT 5s"));
+
+        let statement =
+            prepare("SELECT name, cpu_percent FROM process ORDER BY cpu_percent DESC LIMIT 5")
+                .unwrap();
+        assert!(statement.ends_with("LIMIT 5 TIMEOUT 5s"));
+
+        assert!(prepare(
+            "SELECT count() AS n FROM (SELECT * FROM threat WHERE severity = 'delete; drop') GROUP ALL"
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn writes_and_extra_statements_are_rejected() {
+        assert_eq!(prepare("DELETE system_metrics"), Err(QueryError::NotSelect));
+        assert_eq!(
+            prepare("SELECT * FROM threat; DELETE threat"),
+            Err(QueryError::NotSelect)
+        );
+        assert!(matches!(
+            prepare("SELECT * FROM (UPDATE process SET name = 'x')"),
+            Err(QueryError::Forbidden(k)) if k == "UPDATE"
+        ));
+        assert!(matches!(
+            prepare("SELECT * FROM threat -- comment"),
+            Err(QueryError::Forbidden(_))
+        ));
+        assert_eq!(prepare("  "), Err(QueryError::Empty));
+        assert_eq!(
+            prepare("SELECT * FROM threat WHERE note = 'open"),
+            Err(QueryError::UnterminatedString)
+        );
+    }
+
+    #[test]
+    fn other_tables_and_functions_are_rejected() {
+        assert_eq!(
+            prepare("SELECT * FROM agent_memory"),
+            Err(QueryError::Table("agent_memory".to_string()))
+        );
+        assert_eq!(
+            prepare("SELECT * FROM threat, incident"),
+            Err(QueryError::Table("incident".to_string()))
+        );
+        assert_eq!(
+            prepare("SELECT agent_memory:abc.content FROM threat"),
+            Err(QueryError::Table("agent_memory".to_string()))
+        );
+        assert_eq!(
+            prepare("SELECT http::get('https://example.com') FROM threat"),
+            Err(QueryError::Function("http".to_string()))
+        );
+        assert!(matches!(
+            prepare("SELECT type::thing('agent_memory', 'a') FROM threat"),
+            Err(QueryError::Forbidden(_))
+        ));
+        assert!(matches!(
+            prepare("SELECT ->spawns->process FROM process"),
+            Err(QueryError::Forbidden(_))
+        ));
+    }
+}
@@ -0,0 +1,296 @@
+//! Moving memory history between backends.
+//!
+//! The JSON backend keeps everything in `memory.json` and `patterns.json`
+//! with no embeddings. Migrating to SurrealDB maps each entry to an
+//! [`AgentMemory`], embeds its content with the backend's provider and
+//! bulk-inserts the records. Every record is tagged with the run's
+//! `migration_id`, so a failed or cancelled run deletes what it already
+//! wrote and the target is left as it was.
+
+use crate::memory::{MemoryEntry, MemoryEntryType, UserPattern};
+use crate::surreal_backend::{
+    AgentMemory, AgentType, MaintenanceProgress, MemorySource, SurrealBackend,
+};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use log::{info, warn};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Records embedded and inserted per progress update
+const MIGRATION_CHUNK_SIZE: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryBackendKind {
+    /// `memory.json` / `patterns.json` in the profile's memory directory
+    Json,
+    Surreal,
+}
+
+/// Result of [`migrate_memory_backend`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MigrationReport {
+    /// Stored in each migrated record's metadata
+    pub migration_id: String,
+    pub memories: usize,
+    pub patterns: usize,
+    /// Records stored with a zero vector because embedding failed; they are
+    /// kept but do not show up in similarity search
+    pub unembedded: usize,
+    pub duration_ms: u64,
+}
+
+/// History read from a JSON backend directory
+#[derive(Debug, Clone, Default)]
+pub struct LegacyMemory {
+    pub entries: HashMap<String, MemoryEntry>,
+    pub patterns: HashMap<String, UserPattern>,
+}
+
+impl LegacyMemory {
+    /// Read `memory.json` and `patterns.json` from `dir`; missing files
+    /// count as empty.
+    pub async fn load(dir: &Path) -> Result<Self> {
+        Ok(Self {
+            entries: load_json(&dir.join("memory.json"))
+                .await?
+                .unwrap_or_default(),
+            patterns: load_json(&dir.join("patterns.json"))
+                .await?
+                .unwrap_or_default(),
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty() && self.patterns.is_empty()
+    }
+
+    /// Memories and then patterns as agent memories without embeddings,
+    /// oldest first within each group.
+    pub fn records(&self, migration_id: &str) -> Vec<AgentMemory> {
+        let mut entries: Vec<(&String, &MemoryEntry)> = self.entries.iter().collect();
+        entries.sort_by_key(|(id, e)| (e.timestamp, *id));
+        let mut patterns: Vec<(&String, &UserPattern)> = self.patterns.iter().collect();
+        patterns.sort_by_key(|(id, p)| (p.last_occurrence, *id));
+
+        let memories = entries.into_iter().map(|(id, entry)| {
+            let (agent_type, source) = map_entry_type(&entry.entry_type);
+            AgentMemory {
+                agent_type,
+                content: entry.content.clone(),
+                embedding: Vec::new(),
+                timestamp: entry.timestamp,
+                source,
+                metadata: Some(memory_metadata(id, entry, migration_id)),
+            }
+        });
+        let patterns = patterns.into_iter().map(|(id, pattern)| AgentMemory {
+            agent_type: AgentType::Copilot,
+            content: format!(
+                "{:?} pattern (frequency {}, confidence {:.2}) - {}",
+                pattern.pattern_type, pattern.frequency, pattern.confidence, pattern.description
+            ),
+            embedding: Vec::new(),
+            timestamp: pattern.last_occurrence,
+            source: MemorySource::UserQuery,
+            metadata: Some(json!({
+                "pattern_id": id,
+                "pattern_type": format!("{:?}", pattern.pattern_type),
+                "frequency": pattern.frequency,
+                "confidence": pattern.confidence,
+                "last_occurrence": pattern.last_occurrence,
+                "migration_id": migration_id,
+            })),
+        });
+        memories.chain(patterns).collect()
+    }
+}
+
+/// Copy the history in `json_dir` into `target`, re-embedding every record.
+///
+/// Only JSON to SurrealDB is supported. The JSON files are left untouched,
+/// so the old backend keeps working until the app is switched over. If any
+/// batch fails, or `cancel` is raised between batches, the records this run
+/// inserted are deleted again.
+pub async fn migrate_memory_backend(
+    from: MemoryBackendKind,
+    to: MemoryBackendKind,
+    json_dir: &Path,
+    target: &SurrealBackend,
+    cancel: &AtomicBool,
+    progress: impl Fn(MaintenanceProgress) + Send + Sync,
+) -> Result<MigrationReport> {
+    if (from, to) != (MemoryBackendKind::Json, MemoryBackendKind::Surreal) {
+        anyhow::bail!("Migrating memory from {from:?} to {to:?} is not supported");
+    }
+    let started = std::time::Instant::now();
+    progress(MaintenanceProgress::new("migrate", "loading", 0));
+    let legacy = LegacyMemory::load(json_dir).await?;
+
+    let mut report = MigrationReport {
+        migration_id: uuid::Uuid::new_v4().to_string(),
+        memories: legacy.entries.len(),
+        patterns: legacy.patterns.len(),
+        ..Default::default()
+    };
+    let records = legacy.records(&report.migration_id);
+    let total = records.len().max(1);
+    info!(
+        "Migrating {} memories and {} patterns from {:?} (migration {})",
+        report.memories, report.patterns, json_dir, report.migration_id
+    );
+
+    let mut done = 0usize;
+    let outcome: Result<()> = async {
+        for chunk in records.chunks(MIGRATION_CHUNK_SIZE) {
+            if cancel.load(Ordering::SeqCst) {
+                anyhow::bail!("Migration cancelled");
+            }
+            let mut batch = Vec::with_capacity(chunk.len());
+            for record in chunk {
+                let mut record = record.clone();
+                record.embedding = target
+                    .embed_text(&record.content)
+                    .await
+                    .unwrap_or_else(|_| vec![0.0; target.embedding_dimension()]);
+                if record.embedding.iter().all(|v| *v == 0.0) {
+                    report.unembedded += 1;
+                }
+                batch.push(record);
+            }
+            target.insert_agent_memories_batch(batch).await?;
+            done += chunk.len();
+            progress(MaintenanceProgress::new(
+                "migrate",
+                "importing",
+                (5 + done * 90 / total) as u8,
+            ));
+        }
+        Ok(())
+    }
+    .await;
+
+    if let Err(e) = outcome {
+        warn!(
+            "Memory migration {} failed, rolling back: {e:#}",
+            report.migration_id
+        );
+        progress(MaintenanceProgress::new("migrate", "rolling back", 95));
+        return match target.delete_migrated_memories(&report.migration_id).await {
+            Ok(removed) => Err(e.context(format!(
+                "Migration failed; removed the {removed} records it had imported"
+            ))),
+            Err(rollback_err) => Err(e.context(format!(
+                "Migration failed and rollback failed ({rollback_err:#}); delete agent memories with migration_id {}",
+                report.migration_id
+            ))),
+        };
+    }
+
+    report.duration_ms = started.elapsed().as_millis() as u64;
+    info!(
+        "Migrated {} records in {} ms ({} without embeddings)",
+        done, report.duration_ms, report.unembedded
+    );
+    progress(MaintenanceProgress::new("migrate", "done", 100));
+    Ok(report)
+}
+
+async fn load_json<T: DeserializeOwned>(path: &Path) -> Result<Option<T>> {
+    if !tokio::fs::try_exists(path).await.unwrap_or(false) {
+        return Ok(None);
+    }
+    let contents = tokio::fs::read_to_string(path)
+        .await
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let parsed = serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+    Ok(Some(parsed))
+}
+
+fn map_entry_type(entry_type: &MemoryEntryType) -> (AgentType, MemorySource) {
+    match entry_type {
+        MemoryEntryType::SystemEvent | MemoryEntryType::ThreatDetection => {
+            (AgentType::Guardian, MemorySource::SystemLog)
+        }
+        MemoryEntryType::SystemOptimization => {
+            (AgentType::Guardian, MemorySource::PerformanceAnalysis)
+        }
+        MemoryEntryType::UserInteraction
+        | MemoryEntryType::KnowledgeBase
+        | MemoryEntryType::UserPattern => (AgentType::Copilot, MemorySource::UserQuery),
+    }
+}
+
+fn memory_metadata(entry_id: &str, entry: &MemoryEntry, migration_id: &str) -> Value {
+    let mut map = Map::new();
+    map.insert("legacy_id".to_string(), json!(entry_id));
+    map.insert(
+        "entry_type".to_string(),
+        json!(format!("{:?}", entry.entry_type)),
+    );
+    map.insert(
+        "relevance_score".to_string(),
+        json!(entry.relevance_score as f64),
+    );
+    if !entry.tags.is_empty() {
+        map.insert("tags".to_string(), json!(entry.tags));
+    }
+    if !entry.metadata.is_empty() {
+        map.insert("metadata".to_string(), json!(entry.metadata));
+    }
+    map.insert("migration_id".to_string(), json!(migration_id));
+    map.insert("migrated_at".to_string(), json!(Utc::now()));
+    Value::Object(map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::PatternType;
+
+    #[test]
+    fn maps_legacy_entries_and_patterns() {
+        let now = Utc::now();
+        let mut legacy = LegacyMemory::default();
+        legacy.entries.insert(
+            "e1".to_string(),
+            MemoryEntry {
+                id: "e1".to_string(),
+                timestamp: now,
+                entry_type: MemoryEntryType::ThreatDetection,
+                content: "Blocked trojan in Downloads".to_string(),
+                metadata: HashMap::new(),
+                relevance_score: 0.9,
+                tags: vec!["threat".to_string()],
+            },
+        );
+        legacy.patterns.insert(
+            "p1".to_string(),
+            serde_json::from_value(json!({
+                "pattern_id": "p1",
+                "pattern_type": PatternType::ApplicationUsage,
+                "frequency": 3,
+                "last_occurrence": now,
+                "confidence": 0.5,
+                "description": "Opens VS Code in the morning",
+            }))
+            .unwrap(),
+        );
+
+        let records = legacy.records("m-1");
+        assert_eq!(records.len(), 2);
+        assert!(matches!(records[0].agent_type, AgentType::Guardian));
+        assert!(matches!(records[0].source, MemorySource::SystemLog));
+        let meta = records[0].metadata.as_ref().unwrap();
+        assert_eq!(meta["legacy_id"], "e1");
+        assert_eq!(meta["migration_id"], "m-1");
+        assert!(records[1].content.contains("Opens VS Code"));
+        assert_eq!(records[1].metadata.as_ref().unwrap()["migration_id"], "m-1");
+    }
+}
@@ -0,0 +1,342 @@
+//! Mining of user behaviour patterns from chat interactions.
+//!
+//! Each interaction is reduced to observations: the slash command or the
+//! leading topic keywords, the local hour, the active application and any
+//! recurring problem category it mentions. An observation updates the
+//! pattern with the same id. A pattern's weight is an occurrence count that
+//! halves every [`HALF_LIFE_DAYS`], so confidence follows recent behaviour
+//! and habits the user dropped fade out.
+
+use crate::memory::{PatternType, UserPattern};
+use chrono::{DateTime, Utc};
+
+pub const HALF_LIFE_DAYS: f64 = 14.0;
+/// Patterns kept per store; the weakest are forgotten first
+pub const MAX_PATTERNS: usize = 500;
+/// Decayed weight at which confidence reaches 0.5
+const CONFIDENCE_PIVOT: f64 = 3.0;
+/// Patterns decayed below this weight are forgotten
+const MIN_WEIGHT: f64 = 0.05;
+const MAX_TOPICS: usize = 3;
+
+const STOP_WORDS: &[&str] = &[
+    "the", "and", "but", "for", "with", "you", "your", "can", "could", "would", "should", "how",
+    "what", "why", "when", "where", "which", "who", "this", "that", "these", "those", "there",
+    "please", "help", "does", "did", "are", "was", "were", "have", "has", "had", "not", "any",
+    "all", "its", "it's", "from", "into", "about", "just", "some", "get", "make", "tell", "show",
+    "need", "want", "like", "know", "also", "then", "than", "them", "they", "i'm", "my", "me",
+    "today", "now", "again", "still", "really", "very", "much", "one",
+];
+
+/// Problem categories and the words that indicate them.
+const PROBLEM_CATEGORIES: &[(&str, &[&str])] = &[
+    (
+        "performance",
+        &[
+            "slow", "lag", "laggy", "freeze", "frozen", "hang", "hangs", "cpu", "ram", "fps",
+        ],
+    ),
+    (
+        "network",
+        &[
+            "wifi",
+            "wi-fi",
+            "internet",
+            "network",
+            "dns",
+            "connection",
+            "offline",
+            "vpn",
+        ],
+    ),
+    (
+        "security",
+        &[
+            "virus",
+            "malware",
+            "threat",
+            "suspicious",
+            "hacked",
+            "ransomware",
+            "phishing",
+        ],
+    ),
+    (
+        "crash",
+        &[
+            "crash",
+            "crashed",
+            "crashes",
+            "crashing",
+            "bsod",
+            "exception",
+            "error",
+        ],
+    ),
+    ("storage", &["disk", "storage", "space", "drive"]),
+    ("battery", &["battery", "charging", "charger", "power"]),
+    (
+        "audio",
+        &[
+            "audio",
+            "sound",
+            "microphone",
+            "mic",
+            "speaker",
+            "headphones",
+        ],
+    ),
+    (
+        "updates",
+        &["update", "updates", "upgrade", "driver", "drivers"],
+    ),
+];
+
+/// One fact about an interaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Observation {
+    pub pattern_id: String,
+    pub pattern_type: PatternType,
+    pub description: String,
+}
+
+impl Observation {
+    fn new(pattern_type: PatternType, pattern_id: String, description: String) -> Self {
+        Self {
+            pattern_id,
+            pattern_type,
+            description,
+        }
+    }
+}
+
+/// Observations for a message sent at local `hour` while `active_window`
+/// (in [`oxide_core::types::Context::active_window`] form) was focused.
+pub fn observe(input: &str, active_window: Option<&str>, hour: u32) -> Vec<Observation> {
+    let mut observations = vec![Observation::new(
+        PatternType::TimeBasedActivity,
+        format!("hour:{hour:02}"),
+        format!("Active between {hour:02}:00 and {:02}:00", (hour + 1) % 24),
+    )];
+
+    let words = words(input);
+    if let Some(command) = input.trim_start().strip_prefix('/') {
+        let command = command
+            .split_whitespace()
+            .next()
+            .unwrap_or("")
+            .to_lowercase();
+        if !command.is_empty() {
+            observations.push(Observation::new(
+                PatternType::CommandSequence,
+                format!("command:/{command}"),
+                format!("Uses the /{command} command"),
+            ));
+        }
+    } else {
+        let mut topics: Vec<&str> = Vec::new();
+        for word in &words {
+            if word.len() > 2 && !STOP_WORDS.contains(&word.as_str()) && !topics.contains(&&**word)
+            {
+                topics.push(word);
+            }
+            if topics.len() == MAX_TOPICS {
+                break;
+            }
+        }
+        for topic in topics {
+            observations.push(Observation::new(
+                PatternType::TopicInterest,
+                format!("topic:{topic}"),
+                format!("Asks about \"{topic}\""),
+            ));
+        }
+    }
+
+    for (category, markers) in PROBLEM_CATEGORIES {
+        if words.iter().any(|word| markers.contains(&word.as_str())) {
+            observations.push(Observation::new(
+                PatternType::ErrorResolution,
+                format!("problem:{category}"),
+                format!("Recurring {category} problems"),
+            ));
+        }
+    }
+
+    if let Some(app) = active_window.and_then(application_name) {
+        observations.push(Observation::new(
+            PatternType::ApplicationUsage,
+            format!("app:{}", app.to_lowercase()),
+            format!("Works in {app}"),
+        ));
+    }
+    observations
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|word| {
+            word.trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase()
+        })
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+/// Process name of a `"<title> (<process>)"` window, or the whole string
+/// when it carries no process.
+fn application_name(window: &str) -> Option<&str> {
+    let window = window.trim();
+    let name = match window.strip_suffix(')').and_then(|w| w.rsplit_once('(')) {
+        Some((_, process)) => process.trim(),
+        None => window,
+    };
+    (!name.is_empty()).then_some(name)
+}
+
+fn decay(weight: f64, since: DateTime<Utc>, now: DateTime<Utc>) -> f64 {
+    let days = (now - since).num_seconds().max(0) as f64 / 86_400.0;
+    weight * 0.5f64.powf(days / HALF_LIFE_DAYS)
+}
+
+fn confidence_for(weight: f64) -> f32 {
+    (weight / (weight + CONFIDENCE_PIVOT)) as f32
+}
+
+impl UserPattern {
+    pub fn from_observation(observation: Observation, at: DateTime<Utc>) -> Self {
+        Self {
+            pattern_id: observation.pattern_id,
+            pattern_type: observation.pattern_type,
+            frequency: 0,
+            last_occurrence: at,
+            confidence: 0.0,
+            description: observation.description,
+            weight: 0.0,
+            hours: vec![0; 24],
+        }
+    }
+
+    /// Count one more occurrence at `at`, local hour `hour`.
+    pub fn record(&mut self, at: DateTime<Utc>, hour: u32) {
+        self.weight = decay(self.weight, self.last_occurrence, at) + 1.0;
+        self.frequency += 1;
+        self.last_occurrence = self.last_occurrence.max(at);
+        self.confidence = confidence_for(self.weight);
+        if self.hours.len() != 24 {
+            self.hours = vec![0; 24];
+        }
+        self.hours[hour as usize % 24] += 1;
+    }
+
+    /// Weight as of `now`.
+    pub fn weight_at(&self, now: DateTime<Utc>) -> f64 {
+        decay(self.weight, self.last_occurrence, now)
+    }
+
+    /// Confidence as of `now`, lower the longer the pattern has not recurred.
+    pub fn confidence_at(&self, now: DateTime<Utc>) -> f32 {
+        confidence_for(self.weight_at(now))
+    }
+
+    /// Local hour with the most occurrences.
+    pub fn peak_hour(&self) -> Option<u32> {
+        self.hours
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .max_by_key(|(_, count)| **count)
+            .map(|(hour, _)| hour as u32)
+    }
+
+    /// Share of occurrences within an hour of local `hour`.
+    fn share_near(&self, hour: u32) -> f64 {
+        let total: u32 = self.hours.iter().sum();
+        if total == 0 {
+            return 0.0;
+        }
+        let near: u32 = [23, 0, 1]
+            .iter()
+            .map(|offset| self.hours[(hour as usize + offset) % 24])
+            .sum();
+        f64::from(near) / f64::from(total)
+    }
+
+    /// How useful the pattern is as context for a request made now at local
+    /// `hour`: its confidence, boosted when it usually occurs at this time.
+    pub fn preload_score(&self, now: DateTime<Utc>, hour: u32) -> f32 {
+        if self.hours.len() != 24 {
+            return self.confidence_at(now) * 0.5;
+        }
+        self.confidence_at(now) * (0.5 + 0.5 * self.share_near(hour) as f32)
+    }
+}
+
+/// Drop patterns that decayed away and keep at most [`MAX_PATTERNS`].
+pub fn prune(patterns: &mut std::collections::HashMap<String, UserPattern>, now: DateTime<Utc>) {
+    patterns.retain(|_, pattern| pattern.weight_at(now) >= MIN_WEIGHT);
+    if patterns.len() > MAX_PATTERNS {
+        let mut weights: Vec<(String, f64)> = patterns
+            .iter()
+            .map(|(id, pattern)| (id.clone(), pattern.weight_at(now)))
+            .collect();
+        weights.sort_by(|a, b| a.1.total_cmp(&b.1));
+        for (id, _) in weights.into_iter().take(patterns.len() - MAX_PATTERNS) {
+            patterns.remove(&id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn observes_topics_problems_commands_and_apps() {
+        let ids: Vec<String> = observe(
+            "Why is my wifi so slow today?",
+            Some("Inbox - Outlook (OUTLOOK.EXE)"),
+            9,
+        )
+        .into_iter()
+        .map(|o| o.pattern_id)
+        .collect();
+        assert_eq!(
+            ids,
+            [
+                "hour:09",
+                "topic:wifi",
+                "topic:slow",
+                "problem:performance",
+                "problem:network",
+                "app:outlook.exe",
+            ]
+        );
+
+        let ids: Vec<String> = observe("/scan C:\\Users", None, 23)
+            .into_iter()
+            .map(|o| o.pattern_id)
+            .collect();
+        assert_eq!(ids, ["hour:23", "command:/scan"]);
+    }
+
+    #[test]
+    fn confidence_grows_with_use_and_decays_with_age() {
+        let start = Utc::now() - Duration::days(60);
+        let observation = observe("/scan", None, 17).remove(1);
+        let mut pattern = UserPattern::from_observation(observation, start);
+        pattern.record(start, 17);
+        let once = pattern.confidence;
+        for day in 1..6 {
+            pattern.record(start + Duration::days(day), 17);
+        }
+        assert!(pattern.confidence > once);
+        assert_eq!(pattern.frequency, 6);
+        assert_eq!(pattern.peak_hour(), Some(17));
+
+        let later = start + Duration::days(5 + 2 * HALF_LIFE_DAYS as i64);
+        assert!(pattern.confidence_at(later) < pattern.confidence / 2.0);
+        assert!(pattern.preload_score(later, 17) > pattern.preload_score(later, 5));
+    }
+}
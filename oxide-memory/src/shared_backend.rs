@@ -0,0 +1,97 @@
+//! One [`SurrealBackend`] per process.
+//!
+//! Embedded RocksDB takes an exclusive lock on its directory, so opening a
+//! second handle on the same path fails. The desktop app therefore keeps a
+//! single [`SharedSurrealBackend`] and hands its `Arc<SurrealBackend>` to the
+//! system, the metrics collector and the Tauri commands. The connection is
+//! opened on first use, not at startup.
+
+use anyhow::Result;
+use log::warn;
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+
+use crate::surreal_backend::SurrealBackend;
+use crate::surreal_connection::SurrealConnection;
+
+pub struct SharedSurrealBackend {
+    default_connection: SurrealConnection,
+    namespace: String,
+    /// The open backend and the connection it was opened with
+    backend: OnceCell<(SurrealConnection, Arc<SurrealBackend>)>,
+}
+
+impl SharedSurrealBackend {
+    /// Nothing is opened until [`get`](Self::get) or
+    /// [`get_or_connect`](Self::get_or_connect) is awaited.
+    pub fn new(default_connection: SurrealConnection, namespace: impl Into<String>) -> Self {
+        Self {
+            default_connection,
+            namespace: namespace.into(),
+            backend: OnceCell::new(),
+        }
+    }
+
+    /// The backend, connecting with the default connection if needed.
+    pub async fn get(&self) -> Result<Arc<SurrealBackend>> {
+        match self.get_if_connected() {
+            Some(backend) => Ok(backend),
+            None => self.get_or_connect(self.default_connection.clone()).await,
+        }
+    }
+
+    /// The backend, connecting with `connection` if nothing is open yet.
+    /// Concurrent callers share one connection attempt; a failed attempt is
+    /// not cached, so the next call tries again.
+    pub async fn get_or_connect(
+        &self,
+        connection: SurrealConnection,
+    ) -> Result<Arc<SurrealBackend>> {
+        let (opened_with, backend) = self
+            .backend
+            .get_or_try_init(|| async {
+                let backend =
+                    SurrealBackend::connect_in_namespace(connection.clone(), &self.namespace)
+                        .await?;
+                Ok::<_, anyhow::Error>((connection.clone(), Arc::new(backend)))
+            })
+            .await?;
+        if *opened_with != connection {
+            warn!(
+                "SurrealDB is already open at {}; ignoring {}",
+                opened_with.describe(),
+                connection.describe()
+            );
+        }
+        Ok(Arc::clone(backend))
+    }
+
+    /// The backend if a connection has been opened, without connecting.
+    pub fn get_if_connected(&self) -> Option<Arc<SurrealBackend>> {
+        self.backend.get().map(|(_, backend)| Arc::clone(backend))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn concurrent_callers_share_one_handle() {
+        let temp_dir = TempDir::new().unwrap();
+        let connection = SurrealConnection::Embedded(temp_dir.path().join("test.db"));
+        let shared = SharedSurrealBackend::new(connection, "oxide");
+        assert!(shared.get_if_connected().is_none());
+
+        // A second RocksDB handle on the same path would fail to open
+        let (a, b) = tokio::join!(shared.get(), shared.get());
+        let (a, b) = (a.unwrap(), b.unwrap());
+        assert!(Arc::ptr_eq(&a, &b));
+        assert!(Arc::ptr_eq(&a, &shared.get_if_connected().unwrap()));
+
+        let other = SurrealConnection::Embedded(temp_dir.path().join("other.db"));
+        let c = shared.get_or_connect(other).await.unwrap();
+        assert!(Arc::ptr_eq(&a, &c));
+    }
+}
@@ -38,18 +38,29 @@
 //! - Vector search (KNN): <20ms
 //! - Bulk inserts: >1000/sec
 //! - Memory footprint: ~30MB idle
+//!
+//! Fleet deployments can point the backend at a remote SurrealDB server or a
+//! TiKV cluster instead; see [`crate::surreal_connection`].
+//!
+//! Checked at runtime by [`crate::benchmark::run_benchmark`] and with
+//! `cargo bench -p oxide-memory`.
 
 use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
-use oxide_core::openai_key;
+use oxide_core::config::{ConsolidationRetention, MemoryChunkingConfig};
+use oxide_core::disk_usage::DiskUsageReport;
+use oxide_core::llm_audit::{LlmAuditEntry, LlmAuditQuery};
+use oxide_core::severity::Severity;
+use oxide_core::{api_governor, network, openai_key};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
-use surrealdb::engine::local::{Db, RocksDb};
+use surrealdb::engine::any::Any;
 use surrealdb::sql::Thing;
 use surrealdb::Surreal;
 use tokio::sync::broadcast::Receiver;
@@ -57,8 +68,14 @@ use tokio::sync::{broadcast, RwLock};
 use tracing::{debug, info, warn};
 
 use crate::backend::{BackendSearchItem, MemoryBackend};
-
-/// SurrealDB namespace for Oxide Pilot
+use crate::chunking::{chunk_text, reassemble, TextChunk};
+use crate::embedding_cache::{self, EmbeddingCache, EmbeddingCacheStats};
+use crate::metrics_query;
+use crate::surreal_connection::SurrealConnection;
+use crate::threat_training::{self, LabelSource, ModelEvaluation, ThreatScorer};
+
+/// SurrealDB namespace of the default profile; other profiles get their own
+/// (see `oxide_core::profile`)
 const NAMESPACE: &str = "oxide";
 
 /// SurrealDB database name
@@ -67,12 +84,32 @@ const DATABASE: &str = "memory";
 /// Default embedding dimension for vector search (OpenAI text-embedding-3-small)
 const DEFAULT_EMBEDDING_DIM: usize = 1536;
 
-/// Default HNSW parameters for vector index (reserved for future use)
-#[allow(dead_code)]
+/// Default HNSW parameters for vector index
 const HNSW_M: usize = 12; // Connectivity parameter (higher = better recall, more memory)
-#[allow(dead_code)]
 const HNSW_EF_CONSTRUCTION: usize = 200; // Construction quality (higher = better index, slower build)
 
+/// Memories re-embedded per query while rebuilding the vector index
+const REINDEX_PAGE_SIZE: usize = 100;
+/// Stored memories used as queries when measuring recall after a rebuild
+const REINDEX_RECALL_SAMPLES: usize = 20;
+/// Neighbours compared per recall query
+const REINDEX_RECALL_K: usize = 10;
+
+/// A core counts as a hotspot when it runs at least this busy...
+const CORE_HOTSPOT_USAGE: f64 = 90.0;
+/// ...while sitting this many points above the all-core average
+const CORE_IMBALANCE_GAP: f64 = 40.0;
+/// Minimum share of samples in the window for a hotspot to count as sustained
+const CORE_HOTSPOT_MIN_SHARE: f64 = 0.25;
+const CORE_HOTSPOT_MIN_SAMPLES: i64 = 3;
+
+/// Records per transaction in the batch insert APIs
+const BATCH_CHUNK_SIZE: usize = 500;
+/// Per-line problems kept in a [`MemoryTransferReport`]
+const MAX_TRANSFER_ERRORS: usize = 20;
+/// Largest page [`SurrealBackend::list_agent_memories`] returns
+pub const MAX_MEMORY_PAGE: usize = 200;
+
 // ============================================================================
 // Data Models
 // ============================================================================
@@ -84,12 +121,24 @@ pub struct SystemMetric {
     pub timestamp: DateTime<Utc>,
     /// CPU usage percentage (0-100)
     pub cpu_usage: f64,
+    /// Per-core CPU usage percentages (0-100), indexed by logical core
+    #[serde(default)]
+    pub core_usage: Vec<f64>,
     /// Memory usage details
     pub memory_usage: MemoryUsage,
     /// Disk I/O statistics
     pub disk_io: DiskIO,
     /// Network statistics
     pub network_stats: NetworkStats,
+    /// GPU adapters that expose utilization (NVML, amdgpu)
+    #[serde(default)]
+    pub gpus: Vec<GpuMetrics>,
+    /// CPU package temperature in °C, when a sensor is readable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_temperature_c: Option<f64>,
+    /// Battery state on portable machines
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub battery: Option<BatteryStatus>,
     /// Additional metadata (hostname, OS version, etc.)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<Value>,
@@ -117,6 +166,21 @@ pub struct NetworkStats {
     pub connections_active: i32,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GpuMetrics {
+    pub name: String,
+    pub utilization_percent: Option<f64>,
+    pub vram_used_mb: Option<f64>,
+    pub vram_total_mb: Option<f64>,
+    pub temperature_c: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatteryStatus {
+    pub percent: f64,
+    pub charging: bool,
+}
+
 /// Process information node in the process graph
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessInfo {
@@ -154,6 +218,67 @@ pub enum ProcessStatus {
     Zombie,
 }
 
+/// Usage of every process sharing a name at one collection tick
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessSample {
+    pub timestamp: DateTime<Utc>,
+    /// Process name, e.g. `chrome.exe`
+    pub name: String,
+    /// Processes with this name that were running
+    pub instances: u32,
+    /// CPU usage summed over the instances; may exceed 100 on multi-core
+    pub cpu_percent: f64,
+    /// Memory summed over the instances, in MB
+    pub memory_mb: f64,
+}
+
+/// Usage history of one process name from [`SurrealBackend::query_process_trend`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessTrend {
+    pub name: String,
+    /// Oldest first
+    pub samples: Vec<ProcessSample>,
+    /// Least-squares growth over the window; `None` with fewer than two
+    /// samples. Steady memory growth hints at a leak.
+    pub memory_mb_per_hour: Option<f64>,
+    pub cpu_percent_per_hour: Option<f64>,
+}
+
+impl ProcessTrend {
+    pub fn new(name: String, samples: Vec<ProcessSample>) -> Self {
+        Self {
+            memory_mb_per_hour: slope_per_hour(&samples, |s| s.memory_mb),
+            cpu_percent_per_hour: slope_per_hour(&samples, |s| s.cpu_percent),
+            name,
+            samples,
+        }
+    }
+}
+
+/// Least-squares slope of `value` against sample time, per hour.
+fn slope_per_hour(samples: &[ProcessSample], value: impl Fn(&ProcessSample) -> f64) -> Option<f64> {
+    let first = samples.first()?.timestamp;
+    let points: Vec<(f64, f64)> = samples
+        .iter()
+        .map(|s| {
+            let hours = (s.timestamp - first).num_milliseconds() as f64 / 3_600_000.0;
+            (hours, value(s))
+        })
+        .collect();
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let var_x: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    if var_x <= f64::EPSILON {
+        return None;
+    }
+    let cov: f64 = points
+        .iter()
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum();
+    Some(cov / var_x)
+}
+
 /// Threat detection from YARA or heuristics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ThreatInfo {
@@ -165,6 +290,9 @@ pub struct ThreatInfo {
     /// Heuristic score (0-1) if no YARA match
     #[serde(skip_serializing_if = "Option::is_none")]
     pub heuristic_score: Option<f64>,
+    /// 0-100 on the rubric in `oxide_core::severity`; `severity` is its band
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub risk_score: Option<f64>,
     /// Detection timestamp
     pub timestamp: DateTime<Utc>,
     /// Process chain involved
@@ -175,6 +303,9 @@ pub struct ThreatInfo {
     pub indicators: Vec<String>,
     /// Mitigation status
     pub mitigation_status: MitigationStatus,
+    /// MITRE ATT&CK technique IDs
+    #[serde(default)]
+    pub techniques: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -186,7 +317,35 @@ pub enum ThreatSeverity {
     Critical,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl ThreatSeverity {
+    pub fn as_str(&self) -> &'static str {
+        Severity::from(self.clone()).as_str()
+    }
+}
+
+impl From<Severity> for ThreatSeverity {
+    fn from(severity: Severity) -> Self {
+        match severity {
+            Severity::Low => ThreatSeverity::Low,
+            Severity::Medium => ThreatSeverity::Medium,
+            Severity::High => ThreatSeverity::High,
+            Severity::Critical => ThreatSeverity::Critical,
+        }
+    }
+}
+
+impl From<ThreatSeverity> for Severity {
+    fn from(severity: ThreatSeverity) -> Self {
+        match severity {
+            ThreatSeverity::Low => Severity::Low,
+            ThreatSeverity::Medium => Severity::Medium,
+            ThreatSeverity::High => Severity::High,
+            ThreatSeverity::Critical => Severity::Critical,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum MitigationStatus {
     Detected,
@@ -196,6 +355,40 @@ pub enum MitigationStatus {
     Investigating,
 }
 
+impl MitigationStatus {
+    pub const ALL: [MitigationStatus; 5] = [
+        MitigationStatus::Detected,
+        MitigationStatus::Investigating,
+        MitigationStatus::Quarantined,
+        MitigationStatus::Whitelisted,
+        MitigationStatus::Deleted,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MitigationStatus::Detected => "detected",
+            MitigationStatus::Quarantined => "quarantined",
+            MitigationStatus::Deleted => "deleted",
+            MitigationStatus::Whitelisted => "whitelisted",
+            MitigationStatus::Investigating => "investigating",
+        }
+    }
+
+    /// Analyst workflow: detected → investigating → quarantined /
+    /// whitelisted / deleted. Quarantined files can still be released or
+    /// deleted, and whitelisted threats can be reopened. Deleted is final.
+    pub fn can_transition_to(&self, next: MitigationStatus) -> bool {
+        use MitigationStatus::*;
+        match self {
+            Detected => next != Detected,
+            Investigating => matches!(next, Quarantined | Whitelisted | Deleted),
+            Quarantined => matches!(next, Whitelisted | Deleted),
+            Whitelisted => next == Investigating,
+            Deleted => false,
+        }
+    }
+}
+
 /// System incident (crash, error, exception)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IncidentInfo {
@@ -246,6 +439,58 @@ pub struct ThreatTrainingSample {
     pub anomaly_score: f64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<Value>,
+    /// Set on samples collected from recorded events
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label_source: Option<LabelSource>,
+    /// `threat:<id>` or `consensus_run:<id>` the sample was built from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_id: Option<String>,
+}
+
+/// A stored threat as listed in the analyst workflow
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThreatRecord {
+    pub id: String,
+    pub severity: ThreatSeverity,
+    pub yara_rule: Option<String>,
+    pub heuristic_score: Option<f64>,
+    pub risk_score: Option<f64>,
+    pub timestamp: DateTime<Utc>,
+    pub indicators: Vec<String>,
+    pub mitigation_status: MitigationStatus,
+    pub status_updated_at: Option<DateTime<Utc>>,
+    pub status_note: Option<String>,
+    pub techniques: Vec<String>,
+}
+
+/// Output of [`SurrealBackend::ml_predict_threat`]: whatever the SurrealML
+/// model returns, or the local scorer's object naming its `provider`.
+pub type ThreatPrediction = Value;
+
+/// One threat consensus run, stored for later audit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsensusRunRecord {
+    pub risk_score: f64,
+    pub confidence: f64,
+    pub providers: Vec<String>,
+    /// Snapshot as sent to the providers (already pseudonymized)
+    pub input: Value,
+    /// Per-provider reports, weights, models and errors
+    pub provider_reports: Vec<Value>,
+    /// Final aggregated report
+    pub report: Value,
+}
+
+/// A stored consensus run as listed for audit, without its input snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsensusRunEntry {
+    pub id: String,
+    pub timestamp: DateTime<Utc>,
+    pub risk_score: f64,
+    pub confidence: f64,
+    pub providers: Vec<String>,
+    pub provider_reports: Vec<Value>,
+    pub report: Value,
 }
 
 /// Agent memory with vector embeddings for semantic search
@@ -282,6 +527,242 @@ pub enum MemorySource {
     PerformanceAnalysis,
 }
 
+/// Summary of one folder scan run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanRunSummary {
+    pub id: String,
+    pub root: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    /// `completed` or `cancelled`
+    pub status: String,
+    pub total: u64,
+    pub scanned: u64,
+    pub malicious: u64,
+    pub errors: u64,
+    pub duration_ms: u64,
+}
+
+/// Verdict for one file of a scan run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScanFileVerdict {
+    pub path: String,
+    #[serde(default)]
+    pub sha256: Option<String>,
+    pub malicious: bool,
+    /// Local signature that matched
+    #[serde(default)]
+    pub local_match: Option<String>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanDetails {
+    pub summary: ScanRunSummary,
+    pub files: Vec<ScanFileVerdict>,
+}
+
+/// A file that is malicious in both scans but whose content or match changed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanFileChange {
+    pub before: ScanFileVerdict,
+    pub after: ScanFileVerdict,
+}
+
+/// Malicious-file differences between two scans of the same root
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScanDiff {
+    /// Malicious in the newer scan but not in the older one
+    pub new_malicious: Vec<ScanFileVerdict>,
+    /// Malicious in the older scan, now clean or gone
+    pub removed_malicious: Vec<ScanFileVerdict>,
+    pub changed_malicious: Vec<ScanFileChange>,
+}
+
+/// A file a folder scan verified clean, skipped by later scans while its
+/// content and the detection rules are unchanged
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScanCacheEntry {
+    pub path: String,
+    pub size: u64,
+    /// Modification time, milliseconds since the Unix epoch
+    pub mtime_ms: i64,
+    pub sha256: String,
+    /// Whether the verdict included a cloud lookup
+    pub cloud_verified: bool,
+    /// Fingerprint of the signatures, IOCs and rules the file was checked
+    /// against; empty for entries recorded before it was tracked
+    #[serde(default)]
+    pub detection_version: String,
+    pub verified_at: DateTime<Utc>,
+}
+
+impl ScanCacheEntry {
+    /// Whether this entry may still describe the file on disk and covers the
+    /// checks the current scan asks for with the current detection rules.
+    /// Size and mtime are only a pre-filter, since an mtime can be restored
+    /// after an in-place edit; confirm a hit with [`Self::content_matches`].
+    pub fn matches(
+        &self,
+        size: u64,
+        mtime_ms: i64,
+        use_cloud: bool,
+        detection_version: &str,
+    ) -> bool {
+        self.size == size
+            && self.mtime_ms == mtime_ms
+            && (self.cloud_verified || !use_cloud)
+            && self.detection_version == detection_version
+    }
+
+    /// Whether the file's current SHA-256 is the content that was verified.
+    pub fn content_matches(&self, sha256: &str) -> bool {
+        self.sha256.eq_ignore_ascii_case(sha256)
+    }
+}
+
+/// Progress update for long-running maintenance (backup, restore, compact,
+/// migrate)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceProgress {
+    /// `backup`, `restore`, `compact` or `migrate`
+    pub operation: String,
+    pub stage: String,
+    pub percent: u8,
+}
+
+impl MaintenanceProgress {
+    pub(crate) fn new(operation: &str, stage: &str, percent: u8) -> Self {
+        Self {
+            operation: operation.to_string(),
+            stage: stage.to_string(),
+            percent,
+        }
+    }
+}
+
+/// Result of [`SurrealBackend::backup_database`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupInfo {
+    pub path: String,
+    pub size_bytes: u64,
+    pub created_at: DateTime<Utc>,
+    pub duration_ms: u64,
+}
+
+/// Which agent memories [`SurrealBackend::export_agent_memory`] writes
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MemoryExportFilter {
+    #[serde(default)]
+    pub agent_type: Option<AgentType>,
+    #[serde(default)]
+    pub since: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub until: Option<DateTime<Utc>>,
+    /// Newest records are kept when the limit cuts the export short
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// Order of [`SurrealBackend::list_agent_memories`] results
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemorySort {
+    #[default]
+    NewestFirst,
+    OldestFirst,
+}
+
+/// An agent memory as listed for review, without its embedding
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentMemoryEntry {
+    pub id: String,
+    pub agent_type: AgentType,
+    pub content: String,
+    pub timestamp: DateTime<Utc>,
+    pub source: MemorySource,
+    #[serde(default)]
+    pub metadata: Option<Value>,
+}
+
+/// One page of [`SurrealBackend::list_agent_memories`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentMemoryPage {
+    pub items: Vec<AgentMemoryEntry>,
+    /// Memories matching the filters across all pages
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+/// An old agent memory that [`SurrealBackend::store_consolidated_memory`]
+/// may fold into a summary
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsolidationCandidate {
+    pub id: String,
+    pub agent_type: AgentType,
+    pub source: MemorySource,
+    pub content: String,
+    pub embedding: Vec<f64>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// When [`SurrealBackend::import_agent_memory`] recomputes embeddings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReEmbedPolicy {
+    /// Keep stored embeddings; skip records with the wrong dimension
+    #[default]
+    Never,
+    /// Re-embed only records whose dimension does not match this install
+    Mismatched,
+    /// Re-embed every record, e.g. after switching embedding providers
+    Always,
+}
+
+/// Result of an agent memory export or import
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MemoryTransferReport {
+    pub path: String,
+    /// Records written (export) or stored (import)
+    pub records: usize,
+    pub re_embedded: usize,
+    pub skipped: usize,
+    /// First few per-line problems, e.g. malformed JSON or wrong dimension
+    pub errors: Vec<String>,
+    pub duration_ms: u64,
+}
+
+/// Result of [`SurrealBackend::compact_database`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CompactionReport {
+    /// Metrics deleted by the retention cutoff
+    pub removed_metrics: u64,
+    /// `table.index` names that were rebuilt
+    pub rebuilt_indexes: Vec<String>,
+    pub duration_ms: u64,
+}
+
+/// Result of [`SurrealBackend::rebuild_vector_index`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VectorIndexReport {
+    pub previous_dimension: usize,
+    pub dimension: usize,
+    pub memories: usize,
+    /// Memories whose stored vector had the wrong dimension and was recomputed
+    pub re_embedded: usize,
+    /// Re-embedded memories left with a zero vector because embedding failed
+    pub unembedded: usize,
+    /// Whether the HNSW index exists; without it search scans every memory
+    pub index_ready: bool,
+    /// Share of the exact top-k neighbours that search returned, averaged
+    /// over stored memories used as queries
+    pub recall: Option<f64>,
+    pub avg_latency_ms: Option<f64>,
+    pub duration_ms: u64,
+}
+
 // ============================================================================
 // SurrealDB Backend Implementation
 // ============================================================================
@@ -308,13 +789,21 @@ pub enum MemorySource {
 /// ```
 pub struct SurrealBackend {
     /// SurrealDB instance wrapped in Arc<RwLock> for thread-safe access
-    db: Arc<RwLock<Surreal<Db>>>,
+    db: Arc<RwLock<Surreal<Any>>>,
     /// Optional embedding service (OpenAI or local endpoint)
     embedding_service: Option<Arc<EmbeddingService>>,
-    /// Expected embedding dimensionality
-    embedding_dim: usize,
+    /// Expected embedding dimensionality; changed by `rebuild_vector_index`
+    embedding_dim: AtomicUsize,
     /// Broadcast channel for realtime metric updates
     metrics_tx: broadcast::Sender<SystemMetric>,
+    /// Namespace currently selected on `db`
+    namespace: std::sync::RwLock<String>,
+    /// Recently used embeddings, in front of the `embedding_cache` table
+    embedding_cache: EmbeddingCache,
+    /// How `add_texts` splits long texts
+    chunking: std::sync::RwLock<MemoryChunkingConfig>,
+    /// Local threat model used when SurrealML cannot predict
+    threat_scorer: std::sync::RwLock<Option<Arc<dyn ThreatScorer>>>,
 }
 
 #[derive(Clone)]
@@ -417,12 +906,22 @@ impl EmbeddingService {
             .filter(|v| *v > 0)
             .unwrap_or(30);
 
-        Client::builder()
+        network::client_builder()
             .timeout(Duration::from_secs(timeout_secs))
             .build()
             .context("Failed to construct embeddings HTTP client")
     }
 
+    /// Identifies the vectors this service produces, for cache keys.
+    fn cache_id(&self) -> String {
+        match &self.provider {
+            EmbeddingProvider::OpenAI { model, .. } => format!("openai:{model}"),
+            EmbeddingProvider::Local {
+                endpoint, model, ..
+            } => format!("local:{endpoint}:{}", model.as_deref().unwrap_or_default()),
+        }
+    }
+
     fn describe(&self) -> String {
         match &self.provider {
             EmbeddingProvider::OpenAI { model, .. } => format!("OpenAI ({model})"),
@@ -438,10 +937,12 @@ impl EmbeddingService {
         }
     }
 
-    async fn embed(&self, text: &str) -> Result<Vec<f64>> {
+    /// Embed `text`; `dim` is requested from models that can shorten
+    /// their output, other providers' vectors are normalized by the caller.
+    async fn embed(&self, text: &str, dim: usize) -> Result<Vec<f64>> {
         match &self.provider {
             EmbeddingProvider::OpenAI { base_url, model } => {
-                self.embed_openai(base_url, model, text).await
+                self.embed_openai(base_url, model, text, dim).await
             }
             EmbeddingProvider::Local {
                 endpoint,
@@ -454,17 +955,31 @@ impl EmbeddingService {
         }
     }
 
-    async fn embed_openai(&self, base_url: &str, model: &str, text: &str) -> Result<Vec<f64>> {
+    async fn embed_openai(
+        &self,
+        base_url: &str,
+        model: &str,
+        text: &str,
+        dim: usize,
+    ) -> Result<Vec<f64>> {
         let api_key = openai_key::get_api_key()
             .await
             .map_err(|e| anyhow!("Failed to read OpenAI API key: {e}"))?
             .ok_or_else(|| anyhow!("OpenAI API key not configured"))?;
 
         let url = format!("{}/embeddings", base_url.trim_end_matches('/'));
-        let payload = serde_json::json!({
+        network::ensure_online(&url)?;
+        let _permit = api_governor::acquire(&url).await?;
+        let mut payload = serde_json::json!({
             "input": text,
             "model": model,
         });
+        // The text-embedding-3 models can return shorter vectors natively
+        if model.starts_with("text-embedding-3")
+            && embedding_dimension_for_model(model) != Some(dim)
+        {
+            payload["dimensions"] = serde_json::json!(dim);
+        }
 
         let response = self
             .client
@@ -504,6 +1019,8 @@ impl EmbeddingService {
         text: &str,
     ) -> Result<Vec<f64>> {
         let url = format!("{}/embeddings", endpoint.trim_end_matches('/'));
+        network::ensure_online(&url)?;
+        let _permit = api_governor::acquire(&url).await?;
         let mut payload = serde_json::json!({
             "input": [text],
         });
@@ -615,15 +1132,7 @@ fn extract_feature(features: &Value, key: &str, default: f64) -> f64 {
 }
 
 fn categorize_severity(normalized_score: f64) -> &'static str {
-    if normalized_score < 0.3 {
-        "low"
-    } else if normalized_score < 0.6 {
-        "medium"
-    } else if normalized_score < 0.8 {
-        "high"
-    } else {
-        "critical"
-    }
+    Severity::from_unit(normalized_score).as_str()
 }
 
 fn fallback_threat_prediction(features: &Value) -> Value {
@@ -658,41 +1167,53 @@ impl SurrealBackend {
     /// let backend = SurrealBackend::new("./data/oxide-memory.db").await?;
     /// ```
     pub async fn new(db_path: impl AsRef<Path>) -> Result<Self> {
-        let path = db_path.as_ref();
-        info!("Initializing SurrealDB backend at: {:?}", path);
+        Self::connect(SurrealConnection::Embedded(db_path.as_ref().to_path_buf())).await
+    }
 
-        // Create database directory if it doesn't exist
-        if let Some(parent) = path.parent() {
-            tokio::fs::create_dir_all(parent)
-                .await
-                .context("Failed to create database directory")?;
-        }
+    /// Initialize SurrealDB backend for any [`SurrealConnection`]: embedded
+    /// RocksDB, a remote server (WS/HTTP) or a TiKV cluster
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let conn = SurrealConnection::parse("wss://surreal.fleet.local", SurrealCredentials::from_env());
+    /// let backend = SurrealBackend::connect(conn).await?;
+    /// ```
+    pub async fn connect(connection: SurrealConnection) -> Result<Self> {
+        Self::connect_in_namespace(connection, NAMESPACE).await
+    }
 
-        // Initialize embedded RocksDB instance
-        let db = Surreal::new::<RocksDb>(path)
-            .await
-            .context("Failed to initialize SurrealDB")?;
+    /// Like [`connect`](Self::connect), but selects `namespace` instead of
+    /// the default one so each profile's records stay separate.
+    pub async fn connect_in_namespace(
+        connection: SurrealConnection,
+        namespace: &str,
+    ) -> Result<Self> {
+        info!(
+            "Initializing SurrealDB backend at: {}",
+            connection.describe()
+        );
 
-        // Note: Embedded RocksDB doesn't require authentication in SurrealDB 2.x
-        // Credentials are only needed for network connections (WS/HTTP)
+        // Embedded engines need no authentication; remote servers sign in
+        // with the connection's credentials
+        let db = connection.open(namespace, DATABASE).await?;
 
         debug!(
             "Selecting namespace '{}' and database '{}'",
-            NAMESPACE, DATABASE
+            namespace, DATABASE
         );
-        db.use_ns(NAMESPACE)
+        db.use_ns(namespace)
             .use_db(DATABASE)
             .await
             .context("Failed to select namespace/database")?;
 
         // Initialize schema (idempotent)
+        let (embedding_service, embedding_dim) = EmbeddingService::from_env().await?;
         info!("Initializing database schema");
-        Self::init_schema(&db)
+        Self::init_schema(&db, embedding_dim)
             .await
             .context("Failed to initialize schema")?;
 
         info!("SurrealDB backend initialized successfully");
-        let (embedding_service, embedding_dim) = EmbeddingService::from_env().await?;
         let (metrics_tx, _) = broadcast::channel(512);
 
         if let Some(service) = &embedding_service {
@@ -709,56 +1230,186 @@ impl SurrealBackend {
         Ok(Self {
             db: Arc::new(RwLock::new(db)),
             embedding_service,
-            embedding_dim,
+            embedding_dim: AtomicUsize::new(embedding_dim),
             metrics_tx,
+            namespace: std::sync::RwLock::new(namespace.to_string()),
+            embedding_cache: EmbeddingCache::from_env(),
+            chunking: std::sync::RwLock::new(MemoryChunkingConfig::default()),
+            threat_scorer: std::sync::RwLock::new(None),
         })
     }
 
+    /// Namespace the backend currently reads and writes.
+    pub fn namespace(&self) -> String {
+        self.namespace
+            .read()
+            .map(|ns| ns.clone())
+            .unwrap_or_else(|_| NAMESPACE.to_string())
+    }
+
+    /// Switch every later query to `namespace`, creating its schema on first
+    /// use. Queries already running finish against the previous namespace.
+    pub async fn use_namespace(&self, namespace: &str) -> Result<()> {
+        let db = self.db.write().await;
+        db.use_ns(namespace)
+            .use_db(DATABASE)
+            .await
+            .with_context(|| format!("Failed to select namespace '{namespace}'"))?;
+        Self::init_schema(&db, self.embedding_dimension())
+            .await
+            .context("Failed to initialize schema")?;
+        if let Ok(mut current) = self.namespace.write() {
+            *current = namespace.to_string();
+        }
+        info!("SurrealDB namespace switched to '{namespace}'");
+        Ok(())
+    }
+
+    /// Replace the chunking settings used by later `add_texts` calls.
+    pub fn set_chunking(&self, config: MemoryChunkingConfig) {
+        if let Ok(mut current) = self.chunking.write() {
+            *current = config;
+        }
+    }
+
+    /// Score threats with `scorer` whenever SurrealML is unavailable,
+    /// instead of the built-in weighted sum.
+    pub fn set_threat_scorer(&self, scorer: Arc<dyn ThreatScorer>) {
+        if let Ok(mut current) = self.threat_scorer.write() {
+            *current = Some(scorer);
+        }
+    }
+
+    fn local_threat_prediction(&self, features: &Value) -> Value {
+        let scorer = self.threat_scorer.read().ok().and_then(|s| s.clone());
+        match scorer {
+            Some(scorer) => scorer.predict(features),
+            None => fallback_threat_prediction(features),
+        }
+    }
+
+    fn chunking_config(&self) -> MemoryChunkingConfig {
+        self.chunking
+            .read()
+            .map(|config| config.clone())
+            .unwrap_or_default()
+    }
+
     /// Returns the configured embedding dimensionality.
     pub fn embedding_dimension(&self) -> usize {
-        self.embedding_dim
+        self.embedding_dim.load(Ordering::Relaxed)
     }
 
     /// Generate an embedding vector for the provided text using the configured provider.
     ///
-    /// Falls back to a zero-vector when the provider is unavailable or an error occurs.
+    /// Identical text is served from the embedding cache (memory, then the
+    /// `embedding_cache` table) keyed by content hash and model. Falls back
+    /// to a zero-vector when the provider is unavailable or an error occurs.
     pub async fn embed_text(&self, text: &str) -> Result<Vec<f64>, String> {
+        let dim = self.embedding_dimension();
         if text.trim().is_empty() {
-            return Ok(vec![0.0; self.embedding_dim]);
+            return Ok(vec![0.0; dim]);
         }
 
         if let Some(service) = &self.embedding_service {
-            match service.embed(text).await {
+            let key =
+                embedding_cache::content_key(&format!("{}:{}", service.cache_id(), dim), text);
+            if let Some(vector) = self.embedding_cache.get(&key) {
+                return Ok(vector);
+            }
+            match self.load_cached_embedding(&key).await {
+                Ok(Some(vector)) => {
+                    self.embedding_cache.insert(key, vector.clone());
+                    return Ok(vector);
+                }
+                Ok(None) => {}
+                Err(e) => debug!("Embedding cache lookup failed: {e:#}"),
+            }
+
+            match service.embed(text, dim).await {
                 Ok(vector) => {
-                    if vector.len() == self.embedding_dim {
-                        Ok(vector)
+                    let vector = if vector.len() == dim {
+                        vector
                     } else {
                         warn!(
                             "Embedding dimension mismatch (expected {}, got {}). Normalizing vector.",
-                            self.embedding_dim,
+                            dim,
                             vector.len()
                         );
-                        Ok(normalize_embedding(vector, self.embedding_dim))
+                        normalize_embedding(vector, dim)
+                    };
+                    if let Err(e) = self
+                        .store_cached_embedding(&key, &service.cache_id(), &vector)
+                        .await
+                    {
+                        debug!("Failed to persist embedding cache entry: {e:#}");
                     }
+                    self.embedding_cache.insert(key, vector.clone());
+                    Ok(vector)
                 }
                 Err(err) => {
                     warn!(
                         "Embedding generation failed: {:#}. Falling back to zero vector.",
                         err
                     );
-                    Ok(vec![0.0; self.embedding_dim])
+                    Ok(vec![0.0; dim])
                 }
             }
         } else {
             debug!("Embedding service not configured; returning zero-vector embedding.");
-            Ok(vec![0.0; self.embedding_dim])
+            Ok(vec![0.0; dim])
         }
     }
 
+    async fn load_cached_embedding(&self, key: &str) -> Result<Option<Vec<f64>>> {
+        let db = self.db.read().await;
+        let mut result = db
+            .query(
+                r#"
+                UPDATE type::thing('embedding_cache', $key) SET used_at = time::now()
+                    RETURN VALUE embedding;
+                "#,
+            )
+            .bind(("key", key.to_string()))
+            .await
+            .context("Failed to query embedding cache")?;
+        let found: Option<Vec<f64>> = result
+            .take(0)
+            .context("Failed to extract cached embedding")?;
+        Ok(found.filter(|v| v.len() == self.embedding_dimension()))
+    }
+
+    async fn store_cached_embedding(&self, key: &str, model: &str, vector: &[f64]) -> Result<()> {
+        let db = self.db.read().await;
+        db.query(
+            r#"
+            UPSERT type::thing('embedding_cache', $key) CONTENT {
+                model: $model,
+                embedding: $embedding,
+                created_at: time::now(),
+                used_at: time::now()
+            };
+            "#,
+        )
+        .bind(("key", key.to_string()))
+        .bind(("model", model.to_string()))
+        .bind(("embedding", vector.to_vec()))
+        .await
+        .context("Failed to store cached embedding")?
+        .check()
+        .context("Embedding cache write was rejected")?;
+        Ok(())
+    }
+
+    /// Hits and size of the in-memory embedding cache.
+    pub fn embedding_cache_stats(&self) -> EmbeddingCacheStats {
+        self.embedding_cache.stats()
+    }
+
     /// Initialize all database tables, indices, and constraints
     ///
     /// This is idempotent - safe to call multiple times.
-    async fn init_schema(db: &Surreal<Db>) -> Result<()> {
+    async fn init_schema(db: &Surreal<Any>, embedding_dim: usize) -> Result<()> {
         // System metrics table (time-series data)
         db.query(
             r#"
@@ -773,6 +1424,8 @@ impl SurrealBackend {
                 ASSERT $value >= 0 AND $value <= 100
                 COMMENT "CPU usage percentage (0-100)";
 
+            DEFINE FIELD IF NOT EXISTS core_usage ON system_metrics TYPE array<float> DEFAULT [];
+
             DEFINE FIELD IF NOT EXISTS memory_usage ON system_metrics TYPE object;
             DEFINE FIELD IF NOT EXISTS memory_usage.total_mb ON system_metrics TYPE float;
             DEFINE FIELD IF NOT EXISTS memory_usage.used_mb ON system_metrics TYPE float;
@@ -789,6 +1442,19 @@ impl SurrealBackend {
             DEFINE FIELD IF NOT EXISTS network_stats.recv_mb_per_sec ON system_metrics TYPE float;
             DEFINE FIELD IF NOT EXISTS network_stats.connections_active ON system_metrics TYPE int;
 
+            DEFINE FIELD IF NOT EXISTS gpus ON system_metrics TYPE array<object> DEFAULT [];
+            DEFINE FIELD IF NOT EXISTS gpus[*].name ON system_metrics TYPE string;
+            DEFINE FIELD IF NOT EXISTS gpus[*].utilization_percent ON system_metrics TYPE option<float>;
+            DEFINE FIELD IF NOT EXISTS gpus[*].vram_used_mb ON system_metrics TYPE option<float>;
+            DEFINE FIELD IF NOT EXISTS gpus[*].vram_total_mb ON system_metrics TYPE option<float>;
+            DEFINE FIELD IF NOT EXISTS gpus[*].temperature_c ON system_metrics TYPE option<float>;
+
+            DEFINE FIELD IF NOT EXISTS cpu_temperature_c ON system_metrics TYPE option<float>;
+
+            DEFINE FIELD IF NOT EXISTS battery ON system_metrics TYPE option<object>;
+            DEFINE FIELD IF NOT EXISTS battery.percent ON system_metrics TYPE float;
+            DEFINE FIELD IF NOT EXISTS battery.charging ON system_metrics TYPE bool;
+
             DEFINE FIELD IF NOT EXISTS metadata ON system_metrics TYPE option<object>;
 
             DEFINE INDEX IF NOT EXISTS idx_timestamp ON system_metrics FIELDS timestamp;
@@ -819,6 +1485,18 @@ impl SurrealBackend {
             DEFINE INDEX IF NOT EXISTS idx_pid ON process FIELDS pid UNIQUE;
             DEFINE INDEX IF NOT EXISTS idx_name ON process FIELDS name;
             DEFINE INDEX IF NOT EXISTS idx_start_time ON process FIELDS start_time;
+
+            DEFINE TABLE IF NOT EXISTS process_sample SCHEMAFULL
+                COMMENT "Periodic CPU/memory usage per process name";
+
+            DEFINE FIELD IF NOT EXISTS timestamp ON process_sample TYPE datetime;
+            DEFINE FIELD IF NOT EXISTS name ON process_sample TYPE string ASSERT $value != "";
+            DEFINE FIELD IF NOT EXISTS instances ON process_sample TYPE int;
+            DEFINE FIELD IF NOT EXISTS cpu_percent ON process_sample TYPE float;
+            DEFINE FIELD IF NOT EXISTS memory_mb ON process_sample TYPE float;
+
+            DEFINE INDEX IF NOT EXISTS idx_name_timestamp ON process_sample FIELDS name, timestamp;
+            DEFINE INDEX IF NOT EXISTS idx_timestamp ON process_sample FIELDS timestamp;
             "#,
         )
         .await
@@ -851,12 +1529,16 @@ impl SurrealBackend {
                 DEFAULT 'medium';
             DEFINE FIELD IF NOT EXISTS yara_rule ON threat TYPE option<string>;
             DEFINE FIELD IF NOT EXISTS heuristic_score ON threat TYPE option<float>;
+            DEFINE FIELD IF NOT EXISTS risk_score ON threat TYPE option<float>;
             DEFINE FIELD IF NOT EXISTS timestamp ON threat TYPE datetime;
             DEFINE FIELD IF NOT EXISTS process_chain ON threat TYPE array<record<process>>;
             DEFINE FIELD IF NOT EXISTS indicators ON threat TYPE array<string>;
             DEFINE FIELD IF NOT EXISTS mitigation_status ON threat TYPE string
                 ASSERT $value INSIDE ['detected', 'quarantined', 'deleted', 'whitelisted', 'investigating']
                 DEFAULT 'detected';
+            DEFINE FIELD IF NOT EXISTS status_updated_at ON threat TYPE option<datetime>;
+            DEFINE FIELD IF NOT EXISTS status_note ON threat TYPE option<string>;
+            DEFINE FIELD IF NOT EXISTS techniques ON threat TYPE option<array<string>>;
 
             DEFINE INDEX IF NOT EXISTS idx_severity ON threat FIELDS severity;
             DEFINE INDEX IF NOT EXISTS idx_timestamp ON threat FIELDS timestamp;
@@ -904,62 +1586,169 @@ impl SurrealBackend {
             DEFINE FIELD IF NOT EXISTS source ON agent_memory TYPE string
                 ASSERT $value INSIDE ['system_log', 'user_query', 'threat_report', 'performance_analysis'];
             DEFINE FIELD IF NOT EXISTS metadata ON agent_memory TYPE option<object>;
+            DEFINE FIELD IF NOT EXISTS consolidated_from ON agent_memory TYPE option<array<string>>;
 
             DEFINE INDEX IF NOT EXISTS idx_agent_type ON agent_memory FIELDS agent_type;
+
+            DEFINE TABLE IF NOT EXISTS agent_memory_archive SCHEMALESS
+                COMMENT "Raw agent memories replaced by consolidated summaries";
             "#,
         )
         .await
         .context("Failed to create agent_memory table")?;
 
-        // Attempt to enable HNSW vector index support. Not all SurrealDB builds expose it,
-        // so treat failures as warnings rather than hard errors.
-        match db
-            .query(format!(
-                r#"
-                DEFINE INDEX IF NOT EXISTS idx_embedding ON agent_memory
-                    FIELDS embedding
-                    HNSW DIMENSION {DEFAULT_EMBEDDING_DIM} DIST COSINE EF {HNSW_EF_CONSTRUCTION} M {HNSW_M};
-                "#
-            ))
-            .await
-        {
-            Ok(_) => info!("HNSW vector index ready on agent_memory.embedding"),
-            Err(err) => warn!(
-                "HNSW index creation skipped (feature may be unavailable on this build): {:#}",
-                err
-            ),
-        };
-
-        // Supervised training dataset for SurrealML threat analytics
+        // Folder scan history: one scan_run per scan, one scan_verdict per file
         db.query(
             r#"
-            DEFINE TABLE IF NOT EXISTS threat_training SCHEMAFULL
-                COMMENT "Training samples for threat risk predictions";
+            DEFINE TABLE IF NOT EXISTS scan_run SCHEMAFULL
+                COMMENT "Folder scan summaries";
+
+            DEFINE FIELD IF NOT EXISTS root ON scan_run TYPE string;
+            DEFINE FIELD IF NOT EXISTS started_at ON scan_run TYPE datetime;
+            DEFINE FIELD IF NOT EXISTS finished_at ON scan_run TYPE datetime;
+            DEFINE FIELD IF NOT EXISTS status ON scan_run TYPE string
+                ASSERT $value INSIDE ['completed', 'cancelled'];
+            DEFINE FIELD IF NOT EXISTS total ON scan_run TYPE int;
+            DEFINE FIELD IF NOT EXISTS scanned ON scan_run TYPE int;
+            DEFINE FIELD IF NOT EXISTS malicious ON scan_run TYPE int;
+            DEFINE FIELD IF NOT EXISTS errors ON scan_run TYPE int;
+            DEFINE FIELD IF NOT EXISTS duration_ms ON scan_run TYPE int;
+
+            DEFINE INDEX IF NOT EXISTS idx_root ON scan_run FIELDS root;
+            DEFINE INDEX IF NOT EXISTS idx_started_at ON scan_run FIELDS started_at;
+
+            DEFINE TABLE IF NOT EXISTS scan_verdict SCHEMAFULL
+                COMMENT "Per-file verdicts of folder scans";
+
+            DEFINE FIELD IF NOT EXISTS scan_id ON scan_verdict TYPE string;
+            DEFINE FIELD IF NOT EXISTS path ON scan_verdict TYPE string;
+            DEFINE FIELD IF NOT EXISTS sha256 ON scan_verdict TYPE option<string>;
+            DEFINE FIELD IF NOT EXISTS malicious ON scan_verdict TYPE bool;
+            DEFINE FIELD IF NOT EXISTS local_match ON scan_verdict TYPE option<string>;
+            DEFINE FIELD IF NOT EXISTS error ON scan_verdict TYPE option<string>;
+
+            DEFINE INDEX IF NOT EXISTS idx_scan_id ON scan_verdict FIELDS scan_id;
+
+            DEFINE TABLE IF NOT EXISTS scan_cache SCHEMAFULL
+                COMMENT "Files verified clean, keyed by path";
+
+            DEFINE FIELD IF NOT EXISTS path ON scan_cache TYPE string;
+            DEFINE FIELD IF NOT EXISTS size ON scan_cache TYPE int;
+            DEFINE FIELD IF NOT EXISTS mtime_ms ON scan_cache TYPE int;
+            DEFINE FIELD IF NOT EXISTS sha256 ON scan_cache TYPE string;
+            DEFINE FIELD IF NOT EXISTS cloud_verified ON scan_cache TYPE bool;
+            DEFINE FIELD IF NOT EXISTS detection_version ON scan_cache TYPE string DEFAULT "";
+            DEFINE FIELD IF NOT EXISTS verified_at ON scan_cache TYPE datetime;
+
+            DEFINE INDEX IF NOT EXISTS idx_path ON scan_cache FIELDS path UNIQUE;
+            DEFINE INDEX IF NOT EXISTS idx_verified_at ON scan_cache FIELDS verified_at;
+            "#,
+        )
+        .await
+        .context("Failed to create scan history tables")?;
 
-            DEFINE FIELD IF NOT EXISTS severity ON threat_training TYPE string
-                ASSERT $value INSIDE ['low','medium','high','critical'];
-            DEFINE FIELD IF NOT EXISTS cpu_usage ON threat_training TYPE float;
-            DEFINE FIELD IF NOT EXISTS memory_pressure ON threat_training TYPE float;
-            DEFINE FIELD IF NOT EXISTS network_score ON threat_training TYPE float;
-            DEFINE FIELD IF NOT EXISTS anomaly_score ON threat_training TYPE float;
-            DEFINE FIELD IF NOT EXISTS metadata ON threat_training TYPE option<object>;
+        // Latest disk usage analysis per root; reports nest deeply, so the table is schemaless
+        db.query(
+            r#"
+            DEFINE TABLE IF NOT EXISTS disk_usage SCHEMALESS
+                COMMENT "Most recent disk usage report for each analyzed root, keyed by root";
             "#,
         )
         .await
-        .context("Failed to create threat_training table")?;
+        .context("Failed to create disk_usage table")?;
 
-        if let Err(err) = db
-            .query(
-                r#"
-                DEFINE MODEL IF NOT EXISTS threat_risk_model
-                    ON threat_training
-                    TARGET severity
-                    FEATURES cpu_usage, memory_pressure, network_score, anomaly_score
-                    TYPE BAYES;
-                "#,
-            )
-            .await
-        {
+        // Embeddings keyed by hash of model and content
+        db.query(
+            r#"
+            DEFINE TABLE IF NOT EXISTS embedding_cache SCHEMAFULL
+                COMMENT "Provider embeddings reused for identical content";
+
+            DEFINE FIELD IF NOT EXISTS model ON embedding_cache TYPE string;
+            DEFINE FIELD IF NOT EXISTS embedding ON embedding_cache TYPE array<float>;
+            DEFINE FIELD IF NOT EXISTS created_at ON embedding_cache TYPE datetime;
+            DEFINE FIELD IF NOT EXISTS used_at ON embedding_cache TYPE datetime;
+
+            DEFINE INDEX IF NOT EXISTS idx_used_at ON embedding_cache FIELDS used_at;
+            "#,
+        )
+        .await
+        .context("Failed to create embedding_cache table")?;
+
+        // Threat consensus audit trail. Provider payloads vary, so the table is schemaless.
+        db.query(
+            r#"
+            DEFINE TABLE IF NOT EXISTS consensus_run SCHEMALESS
+                COMMENT "Threat consensus runs: inputs, provider reports, final score";
+
+            DEFINE FIELD IF NOT EXISTS timestamp ON consensus_run TYPE datetime;
+            DEFINE FIELD IF NOT EXISTS risk_score ON consensus_run TYPE float;
+            DEFINE FIELD IF NOT EXISTS confidence ON consensus_run TYPE float;
+            DEFINE FIELD IF NOT EXISTS providers ON consensus_run TYPE array<string>;
+
+            DEFINE INDEX IF NOT EXISTS idx_timestamp ON consensus_run FIELDS timestamp;
+            "#,
+        )
+        .await
+        .context("Failed to create consensus_run table")?;
+
+        // Prompts and responses exchanged with external LLMs, for compliance review
+        db.query(
+            r#"
+            DEFINE TABLE IF NOT EXISTS llm_audit SCHEMAFULL
+                COMMENT "Prompts sent to external LLMs (after redaction) and their responses";
+
+            DEFINE FIELD IF NOT EXISTS timestamp ON llm_audit TYPE datetime;
+            DEFINE FIELD IF NOT EXISTS provider ON llm_audit TYPE string;
+            DEFINE FIELD IF NOT EXISTS model ON llm_audit TYPE option<string>;
+            DEFINE FIELD IF NOT EXISTS purpose ON llm_audit TYPE string;
+            DEFINE FIELD IF NOT EXISTS prompt ON llm_audit TYPE string;
+            DEFINE FIELD IF NOT EXISTS response ON llm_audit TYPE option<string>;
+            DEFINE FIELD IF NOT EXISTS error ON llm_audit TYPE option<string>;
+            DEFINE FIELD IF NOT EXISTS duration_ms ON llm_audit TYPE int;
+
+            DEFINE INDEX IF NOT EXISTS idx_timestamp ON llm_audit FIELDS timestamp;
+            DEFINE INDEX IF NOT EXISTS idx_provider ON llm_audit FIELDS provider;
+            DEFINE INDEX IF NOT EXISTS idx_purpose ON llm_audit FIELDS purpose;
+            "#,
+        )
+        .await
+        .context("Failed to create llm_audit table")?;
+
+        // Attempt to enable HNSW vector index support. Not all SurrealDB builds expose it,
+        // so treat failures as warnings rather than hard errors.
+        if let Err(err) = Self::define_vector_index(db, embedding_dim, false).await {
+            warn!(
+                "HNSW index creation skipped (feature may be unavailable on this build): {:#}",
+                err
+            );
+        }
+
+        // Supervised training dataset for SurrealML threat analytics
+        db.query(
+            r#"
+            DEFINE TABLE IF NOT EXISTS threat_training SCHEMAFULL
+                COMMENT "Training samples for threat risk predictions";
+
+            DEFINE FIELD IF NOT EXISTS severity ON threat_training TYPE string
+                ASSERT $value INSIDE ['low','medium','high','critical'];
+            DEFINE FIELD IF NOT EXISTS cpu_usage ON threat_training TYPE float;
+            DEFINE FIELD IF NOT EXISTS memory_pressure ON threat_training TYPE float;
+            DEFINE FIELD IF NOT EXISTS network_score ON threat_training TYPE float;
+            DEFINE FIELD IF NOT EXISTS anomaly_score ON threat_training TYPE float;
+            DEFINE FIELD IF NOT EXISTS metadata ON threat_training TYPE option<object>;
+            DEFINE FIELD IF NOT EXISTS label_source ON threat_training TYPE option<string>;
+            DEFINE FIELD IF NOT EXISTS source_id ON threat_training TYPE option<string>;
+
+            DEFINE INDEX IF NOT EXISTS idx_source ON threat_training FIELDS source_id;
+
+            DEFINE TABLE IF NOT EXISTS threat_model_eval SCHEMALESS
+                COMMENT "Holdout evaluation after each threat model retraining";
+            "#,
+        )
+        .await
+        .context("Failed to create threat_training table")?;
+
+        if let Err(err) = Self::define_threat_model(db, false).await {
             warn!(
                 "SurrealML model definition skipped (may require enterprise build): {:#}",
                 err
@@ -973,6 +1762,7 @@ impl SurrealBackend {
                     SELECT math::mean(cpu_usage) AS avg_cpu,
                            math::max(cpu_usage) AS peak_cpu,
                            math::mean(memory_usage.percent) AS avg_mem_percent,
+                           math::max(cpu_temperature_c) AS peak_cpu_temp_c,
                            time::floor(timestamp, 1h) AS hour_bucket,
                            count() AS samples
                     FROM system_metrics
@@ -1042,16 +1832,31 @@ impl SurrealBackend {
             CREATE system_metrics SET
                 timestamp = d'{}',
                 cpu_usage = {},
+                core_usage = {},
                 memory_usage = {},
                 disk_io = {},
                 network_stats = {},
+                gpus = {},
+                cpu_temperature_c = {},
+                battery = {},
                 metadata = {}
             "#,
             metric.timestamp.to_rfc3339(),
             metric.cpu_usage,
+            serde_json::to_string(&metric.core_usage).unwrap(),
             serde_json::to_string(&metric.memory_usage).unwrap(),
             serde_json::to_string(&metric.disk_io).unwrap(),
             serde_json::to_string(&metric.network_stats).unwrap(),
+            serde_json::to_string(&metric.gpus).unwrap(),
+            metric
+                .cpu_temperature_c
+                .map(|t| t.to_string())
+                .unwrap_or_else(|| "NONE".to_string()),
+            metric
+                .battery
+                .as_ref()
+                .map(|b| serde_json::to_string(b).unwrap())
+                .unwrap_or_else(|| "NONE".to_string()),
             metric
                 .metadata
                 .map(|m| serde_json::to_string(&m).unwrap())
@@ -1070,6 +1875,134 @@ impl SurrealBackend {
         Ok(Thing::from(("system_metrics", "dummy")))
     }
 
+    /// Insert many system metrics using bound parameters, one transaction
+    /// per [`BATCH_CHUNK_SIZE`] records.
+    ///
+    /// Much faster than calling [`Self::insert_system_metric`] in a loop;
+    /// used by the metrics collector to flush its buffer. Unlike the single
+    /// insert this does not notify [`Self::subscribe_metrics`] listeners, so
+    /// bulk loads of old data never reach realtime views; use
+    /// [`Self::publish_metric`] for live samples. Returns the number of
+    /// metrics written.
+    pub async fn insert_system_metrics_batch(&self, metrics: Vec<SystemMetric>) -> Result<usize> {
+        if metrics.is_empty() {
+            return Ok(0);
+        }
+        debug!("Batch inserting {} system metrics", metrics.len());
+
+        let db = self.db.read().await;
+        for chunk in metrics.chunks(BATCH_CHUNK_SIZE) {
+            let rows = serde_json::to_value(chunk).context("Failed to serialize metrics")?;
+            db.query(
+                r#"
+                BEGIN TRANSACTION;
+                FOR $m IN $rows {
+                    CREATE system_metrics CONTENT {
+                        timestamp: <datetime> $m.timestamp,
+                        cpu_usage: $m.cpu_usage,
+                        core_usage: $m.core_usage ?? [],
+                        memory_usage: $m.memory_usage,
+                        disk_io: $m.disk_io,
+                        network_stats: $m.network_stats,
+                        gpus: $m.gpus ?? [],
+                        cpu_temperature_c: $m.cpu_temperature_c,
+                        battery: $m.battery,
+                        metadata: $m.metadata
+                    };
+                };
+                COMMIT TRANSACTION;
+                "#,
+            )
+            .bind(("rows", rows))
+            .await
+            .context("Failed to batch insert system metrics")?
+            .check()
+            .context("Batch insert of system metrics was rejected")?;
+        }
+        Ok(metrics.len())
+    }
+
+    /// Insert per-process samples in batched transactions
+    pub async fn insert_process_samples(&self, samples: Vec<ProcessSample>) -> Result<usize> {
+        if samples.is_empty() {
+            return Ok(0);
+        }
+        let db = self.db.read().await;
+        for chunk in samples.chunks(BATCH_CHUNK_SIZE) {
+            let rows =
+                serde_json::to_value(chunk).context("Failed to serialize process samples")?;
+            db.query(
+                r#"
+                BEGIN TRANSACTION;
+                FOR $p IN $rows {
+                    CREATE process_sample CONTENT {
+                        timestamp: <datetime> $p.timestamp,
+                        name: $p.name,
+                        instances: $p.instances,
+                        cpu_percent: $p.cpu_percent,
+                        memory_mb: $p.memory_mb
+                    };
+                };
+                COMMIT TRANSACTION;
+                "#,
+            )
+            .bind(("rows", rows))
+            .await
+            .context("Failed to insert process samples")?
+            .check()
+            .context("Process sample insert was rejected")?;
+        }
+        Ok(samples.len())
+    }
+
+    /// Every process sample of the last `hours`, ordered by name and then
+    /// time, for analyses across all processes.
+    pub async fn query_process_samples(&self, hours: i64) -> Result<Vec<ProcessSample>> {
+        let db = self.db.read().await;
+        let mut result = db
+            .query(
+                r#"
+                SELECT timestamp, name, instances, cpu_percent, memory_mb
+                FROM process_sample
+                WHERE timestamp >= time::now() - type::duration(string::concat($hours, "h"))
+                ORDER BY name ASC, timestamp ASC
+                "#,
+            )
+            .bind(("hours", hours))
+            .await
+            .context("Failed to query process samples")?;
+
+        let samples: Vec<ProcessSample> = result
+            .take(0)
+            .context("Failed to extract process samples")?;
+        Ok(samples)
+    }
+
+    /// CPU and memory history of processes named `name` over the last
+    /// `hours`, for charting leaks and creeping usage.
+    pub async fn query_process_trend(&self, name: &str, hours: i64) -> Result<ProcessTrend> {
+        let db = self.db.read().await;
+        let mut result = db
+            .query(
+                r#"
+                SELECT timestamp, name, instances, cpu_percent, memory_mb
+                FROM process_sample
+                WHERE name = $name
+                  AND timestamp >= time::now() - type::duration(string::concat($hours, "h"))
+                ORDER BY timestamp ASC
+                "#,
+            )
+            .bind(("name", name.to_string()))
+            .bind(("hours", hours))
+            .await
+            .context("Failed to query process trend")?;
+
+        let samples: Vec<ProcessSample> = result
+            .take(0)
+            .context("Failed to extract process samples")?;
+        Ok(ProcessTrend::new(name.to_string(), samples))
+    }
+
     /// Query system metrics within time range
     ///
     /// # Arguments
@@ -1198,10 +2131,10 @@ impl SurrealBackend {
             agent_type, limit
         );
 
-        if query_embedding.len() != self.embedding_dim {
+        if query_embedding.len() != self.embedding_dimension() {
             anyhow::bail!(
                 "Invalid embedding dimension: expected {}, got {}",
-                self.embedding_dim,
+                self.embedding_dimension(),
                 query_embedding.len()
             );
         }
@@ -1283,6 +2216,11 @@ impl SurrealBackend {
         self.metrics_tx.subscribe()
     }
 
+    /// Notify realtime subscribers of a live sample without storing it.
+    pub fn publish_metric(&self, metric: SystemMetric) {
+        let _ = self.metrics_tx.send(metric);
+    }
+
     /// Upsert a threat training sample to enrich SurrealML datasets.
     pub async fn upsert_threat_training_sample(&self, sample: ThreatTrainingSample) -> Result<()> {
         let payload =
@@ -1297,8 +2235,9 @@ impl SurrealBackend {
         Ok(())
     }
 
-    /// Predict threat severity using SurrealML (with heuristic fallback if unavailable).
-    pub async fn ml_predict_threat(&self, features: Value) -> Result<Value> {
+    /// Predict threat severity using SurrealML, falling back to the local
+    /// threat scorer (or the heuristic without one) if unavailable.
+    pub async fn ml_predict_threat(&self, features: Value) -> Result<ThreatPrediction> {
         let db = self.db.read().await;
         match db
             .query(
@@ -1313,14 +2252,14 @@ impl SurrealBackend {
                 let prediction: Option<Value> = result
                     .take(0)
                     .context("Failed to extract SurrealML prediction")?;
-                Ok(prediction.unwrap_or_else(|| fallback_threat_prediction(&features)))
+                Ok(prediction.unwrap_or_else(|| self.local_threat_prediction(&features)))
             }
             Err(err) => {
                 warn!(
-                    "SurrealML prediction failed; using heuristic fallback: {:#}",
+                    "SurrealML prediction failed; using local fallback: {:#}",
                     err
                 );
-                Ok(fallback_threat_prediction(&features))
+                Ok(self.local_threat_prediction(&features))
             }
         }
     }
@@ -1373,120 +2312,2260 @@ impl SurrealBackend {
         Ok(rows)
     }
 
-    /// Insert agent memory with embedding
-    pub async fn insert_agent_memory(&self, memory: AgentMemory) -> Result<Thing> {
-        if memory.embedding.len() != self.embedding_dim {
-            anyhow::bail!(
-                "Invalid embedding dimension: expected {}, got {}",
-                self.embedding_dim,
-                memory.embedding.len()
-            );
-        }
+    /// Detect sustained single-core hotspots that the global CPU average hides.
+    ///
+    /// For each sample, the busiest core is compared with the all-core
+    /// average. Cores that are the hotspot in enough samples of the window are
+    /// returned with their sample count, share of the window and mean usage,
+    /// most persistent first.
+    pub async fn query_core_imbalance(&self, hours: i64) -> Result<Vec<Value>> {
+        let db = self.db.read().await;
+        let mut result = db
+            .query(
+                r#"
+                LET $since = time::now() - type::duration(string::concat($hours, "h"));
+                LET $total = count(
+                    SELECT id FROM system_metrics
+                    WHERE timestamp >= $since AND array::len(core_usage) > 1
+                );
+                SELECT *, <float> samples / $total AS share
+                FROM (
+                    SELECT core,
+                           count() AS samples,
+                           math::mean(hottest_usage) AS avg_core_usage,
+                           math::mean(cpu_usage) AS avg_cpu_usage,
+                           time::min(timestamp) AS first_seen,
+                           time::max(timestamp) AS last_seen
+                    FROM (
+                        SELECT timestamp,
+                               cpu_usage,
+                               math::max(core_usage) AS hottest_usage,
+                               array::find_index(core_usage, math::max(core_usage)) AS core
+                        FROM system_metrics
+                        WHERE timestamp >= $since AND array::len(core_usage) > 1
+                    )
+                    WHERE hottest_usage >= $hot AND hottest_usage - cpu_usage >= $gap
+                    GROUP BY core
+                )
+                WHERE samples >= $min_samples AND <float> samples / $total >= $min_share
+                ORDER BY samples DESC
+                "#,
+            )
+            .bind(("hours", hours))
+            .bind(("hot", CORE_HOTSPOT_USAGE))
+            .bind(("gap", CORE_IMBALANCE_GAP))
+            .bind(("min_samples", CORE_HOTSPOT_MIN_SAMPLES))
+            .bind(("min_share", CORE_HOTSPOT_MIN_SHARE))
+            .await
+            .context("Failed to query core imbalance")?;
 
-        debug!(
-            "Inserting agent memory: agent_type={:?}, source={:?}",
-            memory.agent_type, memory.source
-        );
+        let rows: Vec<Value> = result.take(2).context("Failed to extract core imbalance")?;
+        Ok(rows)
+    }
 
+    /// Record a system incident, such as a crash of Oxide Pilot itself.
+    pub async fn insert_incident(&self, incident: &IncidentInfo) -> Result<()> {
         let db = self.db.read().await;
-
-        // Use query with datetime conversion to avoid serialization issues
-        let query = format!(
+        let record = serde_json::to_value(incident).context("Failed to serialize incident")?;
+        db.query(
             r#"
-            CREATE agent_memory SET
-                agent_type = '{}',
-                content = {},
-                embedding = {},
-                timestamp = d'{}',
-                source = '{}',
-                metadata = {}
+            CREATE incident CONTENT {
+                description: $incident.description,
+                timestamp: <datetime> $incident.timestamp,
+                severity: $incident.severity,
+                error_code: $incident.error_code,
+                stack_trace: $incident.stack_trace,
+                resolution_status: $incident.resolution_status,
+                related_processes: []
+            };
             "#,
-            format!("{:?}", memory.agent_type).to_lowercase(),
-            serde_json::to_string(&memory.content).unwrap(),
-            serde_json::to_string(&memory.embedding).unwrap(),
-            memory.timestamp.to_rfc3339(),
-            format!("{:?}", memory.source).to_lowercase(),
-            memory
-                .metadata
-                .map(|m| serde_json::to_string(&m).unwrap())
-                .unwrap_or_else(|| "NONE".to_string())
-        );
+        )
+        .bind(("incident", record))
+        .await
+        .context("Failed to insert incident")?
+        .check()
+        .context("Incident insert was rejected")?;
+        Ok(())
+    }
 
-        let _result = db
-            .query(query)
+    /// Fetch the most recent incidents (newest first).
+    pub async fn query_recent_incidents(&self, limit: usize) -> Result<Vec<Value>> {
+        let db = self.db.read().await;
+        let mut result = db
+            .query(
+                r#"
+                SELECT description,
+                       timestamp,
+                       severity,
+                       error_code,
+                       resolution_status
+                FROM incident
+                ORDER BY timestamp DESC
+                LIMIT $limit
+                "#,
+            )
+            .bind(("limit", limit as i64))
             .await
-            .context("Failed to insert agent memory")?;
+            .context("Failed to query recent incidents")?;
 
-        // For now, just return a dummy Thing since the insertion worked
-        // TODO: Fix deserialization issue with Thing
-        Ok(Thing::from(("agent_memory", "dummy")))
+        let rows: Vec<Value> = result
+            .take(0)
+            .context("Failed to extract recent incidents")?;
+        Ok(rows)
     }
-}
 
-// ============================================================================
-// MemoryBackend Trait Implementation
-// ============================================================================
+    /// Ping the database engine; used by the self-diagnostics command.
+    pub async fn health_check(&self) -> Result<()> {
+        let db = self.db.read().await;
+        db.health().await.context("SurrealDB health check failed")
+    }
 
-#[async_trait]
-impl MemoryBackend for SurrealBackend {
-    async fn add_texts(
-        &self,
-        items: Vec<(String, Vec<String>)>,
-        metadata: Value,
-    ) -> Result<(), String> {
-        debug!("Adding {} text items to agent memory", items.len());
+    /// Run a model-generated `SELECT` once [`metrics_query::prepare`] has
+    /// confirmed it only reads the telemetry tables. Returns at most
+    /// [`metrics_query::MAX_ROWS`] rows.
+    pub async fn query_readonly(&self, query: &str) -> Result<Vec<Value>> {
+        let statement = metrics_query::prepare(query)?;
+        debug!("Running read-only telemetry query: {}", statement);
 
-        for (source_tag, texts) in items {
-            let agent_type = infer_agent_type(&source_tag);
-            let memory_source = infer_memory_source(&source_tag);
+        let db = self.db.read().await;
+        let mut result = db
+            .query(statement)
+            .await
+            .context("Failed to run telemetry query")?;
 
-            for text in texts {
-                let embedding = self.embed_text(&text).await?;
-                let mut metadata_value = build_metadata_with_source(&metadata, &source_tag);
-
-                // Ensure metadata is optional (avoid storing explicit null)
-                let memory_metadata = match &metadata_value {
-                    Value::Null => None,
-                    Value::Object(_) => Some(metadata_value),
-                    _ => {
-                        // build_metadata_with_source ensures object unless metadata itself is null,
-                        // but handle other cases defensively.
-                        metadata_value = serde_json::json!({
-                            "legacy_metadata": metadata,
-                            "source_tag": source_tag,
-                        });
-                        Some(metadata_value)
-                    }
-                };
+        let mut rows: Vec<Value> = result
+            .take(0)
+            .context("Failed to extract telemetry query rows")?;
+        rows.truncate(metrics_query::MAX_ROWS);
+        Ok(rows)
+    }
 
-                let memory = AgentMemory {
-                    agent_type: agent_type.clone(),
-                    content: text.clone(),
-                    embedding,
-                    timestamp: Utc::now(),
-                    source: memory_source.clone(),
-                    metadata: memory_metadata,
-                };
+    /// Record a detected threat; returns its id for
+    /// [`Self::update_threat_status`].
+    pub async fn insert_threat(&self, threat: ThreatInfo) -> Result<String> {
+        let id = uuid::Uuid::new_v4().simple().to_string();
+        let db = self.db.read().await;
+        db.query(
+            r#"
+            CREATE type::thing('threat', $id) CONTENT {
+                severity: $t.severity,
+                yara_rule: $t.yara_rule,
+                heuristic_score: $t.heuristic_score,
+                risk_score: $t.risk_score,
+                timestamp: <datetime> $t.timestamp,
+                process_chain: $t.process_chain,
+                indicators: $t.indicators,
+                mitigation_status: $t.mitigation_status,
+                techniques: $t.techniques
+            };
+            "#,
+        )
+        .bind(("id", id.clone()))
+        .bind(("t", threat))
+        .await
+        .context("Failed to insert threat")?
+        .check()
+        .context("Threat insert was rejected")?;
+        Ok(id)
+    }
 
-                self.insert_agent_memory(memory)
-                    .await
-                    .map_err(|e| format!("Failed to insert agent memory: {e}"))?;
-            }
+    /// Recent threats, newest first, optionally only those in `status`.
+    /// Each row carries its `id` for status updates.
+    pub async fn query_threats(
+        &self,
+        status: Option<MitigationStatus>,
+        limit: usize,
+    ) -> Result<Vec<ThreatRecord>> {
+        let filter = if status.is_some() {
+            "WHERE mitigation_status = $status"
+        } else {
+            ""
+        };
+        let db = self.db.read().await;
+        let mut query = db
+            .query(format!(
+                r#"
+                SELECT meta::id(id) AS id,
+                       severity,
+                       yara_rule,
+                       heuristic_score,
+                       risk_score,
+                       timestamp,
+                       indicators,
+                       mitigation_status,
+                       status_updated_at,
+                       status_note,
+                       (techniques ?? []) AS techniques
+                FROM threat
+                {filter}
+                ORDER BY timestamp DESC
+                LIMIT $limit
+                "#
+            ))
+            .bind(("limit", limit as i64));
+        if let Some(status) = status {
+            query = query.bind(("status", status.as_str()));
         }
+        let mut result = query.await.context("Failed to query threats")?;
+        let rows: Vec<ThreatRecord> = result.take(0).context("Failed to extract threats")?;
+        Ok(rows)
+    }
 
-        Ok(())
+    /// Threats nobody has acknowledged yet (still `detected`): how many, and
+    /// when the oldest of them was recorded.
+    pub async fn unacknowledged_threats(&self) -> Result<(usize, Option<DateTime<Utc>>)> {
+        let db = self.db.read().await;
+        let mut result = db
+            .query(
+                r#"
+                SELECT count() AS n FROM threat WHERE mitigation_status = 'detected' GROUP ALL;
+                SELECT VALUE timestamp FROM threat WHERE mitigation_status = 'detected'
+                    ORDER BY timestamp ASC LIMIT 1;
+                "#,
+            )
+            .await
+            .context("Failed to query unacknowledged threats")?;
+        let counts: Vec<Value> = result.take(0)?;
+        let oldest: Vec<DateTime<Utc>> = result.take(1)?;
+        let count = counts
+            .first()
+            .and_then(|row| row.get("n"))
+            .and_then(Value::as_u64)
+            .unwrap_or(0) as usize;
+        Ok((count, oldest.into_iter().next()))
     }
 
-    async fn search(&self, query: String, top_k: usize) -> Result<Vec<BackendSearchItem>, String> {
-        debug!("Searching for: '{}' (top_k={})", query, top_k);
+    /// ATT&CK technique IDs of every threat and consensus finding recorded
+    /// since `since`, one list per detection (empty when it was not mapped).
+    pub async fn technique_detections(&self, since: DateTime<Utc>) -> Result<Vec<Vec<String>>> {
+        let db = self.db.read().await;
+        let mut result = db
+            .query(
+                r#"
+                SELECT VALUE techniques FROM threat WHERE timestamp >= <datetime> $since;
+                SELECT VALUE report.findings FROM consensus_run
+                    WHERE timestamp >= <datetime> $since;
+                "#,
+            )
+            .bind(("since", since.to_rfc3339()))
+            .await
+            .context("Failed to query threat techniques")?;
+        let threats: Vec<Option<Vec<String>>> = result.take(0)?;
+        let runs: Vec<Option<Vec<Value>>> = result.take(1)?;
+
+        let mut detections: Vec<Vec<String>> =
+            threats.into_iter().map(Option::unwrap_or_default).collect();
+        for finding in runs.into_iter().flatten().flatten() {
+            let techniques = finding
+                .get("techniques")
+                .and_then(Value::as_array)
+                .map(|ids| {
+                    ids.iter()
+                        .filter_map(Value::as_str)
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default();
+            detections.push(techniques);
+        }
+        Ok(detections)
+    }
 
-        let query_embedding = self.embed_text(&query).await?;
+    /// Move a threat to `status`, enforcing
+    /// [`MitigationStatus::can_transition_to`]. `threat_id` may include the
+    /// `threat:` table prefix.
+    pub async fn update_threat_status(
+        &self,
+        threat_id: &str,
+        status: MitigationStatus,
+        note: Option<String>,
+    ) -> Result<()> {
+        let id = threat_id
+            .strip_prefix("threat:")
+            .unwrap_or(threat_id)
+            .to_string();
+        let allowed_from: Vec<&str> = MitigationStatus::ALL
+            .iter()
+            .filter(|from| from.can_transition_to(status))
+            .map(MitigationStatus::as_str)
+            .collect();
 
-        self.vector_search(query_embedding, "guardian", top_k)
+        let db = self.db.read().await;
+        let mut result = db
+            .query(
+                r#"
+                SELECT VALUE mitigation_status FROM type::thing('threat', $id);
+                UPDATE type::thing('threat', $id) SET
+                    mitigation_status = $status,
+                    status_updated_at = time::now(),
+                    status_note = $note
+                WHERE mitigation_status INSIDE $allowed_from
+                RETURN VALUE meta::id(id);
+                "#,
+            )
+            .bind(("id", id.clone()))
+            .bind(("status", status.as_str()))
+            .bind(("note", note))
+            .bind(("allowed_from", allowed_from))
             .await
-            .map_err(|e| format!("Vector search failed: {e}"))
+            .context("Failed to update threat status")?;
+
+        let previous: Option<MitigationStatus> = result.take(0)?;
+        let updated: Vec<String> = result.take(1)?;
+        match previous {
+            None => anyhow::bail!("Threat {id} not found"),
+            Some(_) if !updated.is_empty() => {
+                info!("Threat {id} marked {}", status.as_str());
+                Ok(())
+            }
+            Some(previous) => anyhow::bail!(
+                "Threat {id} cannot move from {} to {}",
+                previous.as_str(),
+                status.as_str()
+            ),
+        }
     }
-}
+
+    /// Mark detected threats as under investigation. Threats already past
+    /// the detected stage are left alone; returns how many changed.
+    pub async fn bulk_acknowledge_threats(&self, threat_ids: &[String]) -> Result<usize> {
+        let ids: Vec<Thing> = threat_ids
+            .iter()
+            .map(|id| Thing::from(("threat", id.strip_prefix("threat:").unwrap_or(id))))
+            .collect();
+        if ids.is_empty() {
+            return Ok(0);
+        }
+
+        let db = self.db.read().await;
+        let mut result = db
+            .query(
+                r#"
+                UPDATE $ids SET
+                    mitigation_status = 'investigating',
+                    status_updated_at = time::now()
+                WHERE mitigation_status = 'detected'
+                RETURN VALUE meta::id(id);
+                "#,
+            )
+            .bind(("ids", ids))
+            .await
+            .context("Failed to acknowledge threats")?;
+        let updated: Vec<String> = result.take(0)?;
+        info!("Acknowledged {} threats", updated.len());
+        Ok(updated.len())
+    }
+
+    /// Persist a folder scan summary and its per-file verdicts.
+    pub async fn insert_scan_run(
+        &self,
+        summary: &ScanRunSummary,
+        verdicts: Vec<ScanFileVerdict>,
+    ) -> Result<()> {
+        let db = self.db.read().await;
+        let record = serde_json::to_value(summary).context("Failed to serialize scan run")?;
+        db.query(
+            r#"
+            CREATE type::thing('scan_run', $run.id) CONTENT {
+                root: $run.root,
+                started_at: <datetime> $run.started_at,
+                finished_at: <datetime> $run.finished_at,
+                status: $run.status,
+                total: $run.total,
+                scanned: $run.scanned,
+                malicious: $run.malicious,
+                errors: $run.errors,
+                duration_ms: $run.duration_ms
+            };
+            "#,
+        )
+        .bind(("run", record))
+        .await
+        .context("Failed to insert scan run")?
+        .check()
+        .context("Scan run insert was rejected")?;
+
+        for chunk in verdicts.chunks(BATCH_CHUNK_SIZE) {
+            let rows = serde_json::to_value(chunk).context("Failed to serialize verdicts")?;
+            db.query(
+                r#"
+                BEGIN TRANSACTION;
+                FOR $v IN $rows {
+                    CREATE scan_verdict CONTENT {
+                        scan_id: $scan_id,
+                        path: $v.path,
+                        sha256: $v.sha256,
+                        malicious: $v.malicious,
+                        local_match: $v.local_match,
+                        error: $v.error
+                    };
+                };
+                COMMIT TRANSACTION;
+                "#,
+            )
+            .bind(("scan_id", summary.id.clone()))
+            .bind(("rows", rows))
+            .await
+            .context("Failed to insert scan verdicts")?
+            .check()
+            .context("Scan verdict insert was rejected")?;
+        }
+        Ok(())
+    }
+
+    /// Scan runs, newest first, optionally only those of `root`.
+    pub async fn list_scan_history(
+        &self,
+        root: Option<String>,
+        limit: usize,
+    ) -> Result<Vec<ScanRunSummary>> {
+        let filter = if root.is_some() {
+            "WHERE root = $root"
+        } else {
+            ""
+        };
+        let db = self.db.read().await;
+        let mut result = db
+            .query(format!(
+                "SELECT *, meta::id(id) AS id FROM scan_run {filter} \
+                 ORDER BY started_at DESC LIMIT $limit"
+            ))
+            .bind(("root", root))
+            .bind(("limit", limit as i64))
+            .await
+            .context("Failed to query scan history")?;
+        let runs: Vec<ScanRunSummary> = result.take(0).context("Failed to extract scan runs")?;
+        Ok(runs)
+    }
+
+    /// Summary and per-file verdicts of one scan run.
+    pub async fn get_scan_details(&self, scan_id: &str) -> Result<Option<ScanDetails>> {
+        let db = self.db.read().await;
+        let mut result = db
+            .query(
+                r#"
+                SELECT *, meta::id(id) AS id FROM type::thing('scan_run', $scan_id);
+                SELECT path, sha256, malicious, local_match, error
+                    FROM scan_verdict WHERE scan_id = $scan_id ORDER BY path;
+                "#,
+            )
+            .bind(("scan_id", scan_id.to_string()))
+            .await
+            .context("Failed to query scan details")?;
+        let summary: Option<ScanRunSummary> = result.take(0)?;
+        let files: Vec<ScanFileVerdict> = result.take(1)?;
+        Ok(summary.map(|summary| ScanDetails { summary, files }))
+    }
+
+    /// Compare two scans of the same root; `base_id` is the older scan.
+    pub async fn diff_scans(&self, base_id: &str, target_id: &str) -> Result<ScanDiff> {
+        let base = self
+            .get_scan_details(base_id)
+            .await?
+            .ok_or_else(|| anyhow!("Scan {base_id} not found"))?;
+        let target = self
+            .get_scan_details(target_id)
+            .await?
+            .ok_or_else(|| anyhow!("Scan {target_id} not found"))?;
+        if base.summary.root != target.summary.root {
+            anyhow::bail!(
+                "Scans cover different roots ({} vs {})",
+                base.summary.root,
+                target.summary.root
+            );
+        }
+        Ok(diff_scan_verdicts(&base.files, &target.files))
+    }
+
+    /// Scan cache entries among `paths` verified at or after `fresh_since`.
+    pub async fn scan_cache_lookup(
+        &self,
+        paths: &[String],
+        fresh_since: DateTime<Utc>,
+    ) -> Result<Vec<ScanCacheEntry>> {
+        let db = self.db.read().await;
+        let mut entries = Vec::new();
+        for chunk in paths.chunks(BATCH_CHUNK_SIZE) {
+            let mut result = db
+                .query(
+                    r#"
+                    SELECT path, size, mtime_ms, sha256, cloud_verified,
+                        (detection_version ?? "") AS detection_version, verified_at
+                        FROM scan_cache
+                        WHERE path INSIDE $paths AND verified_at >= <datetime> $since;
+                    "#,
+                )
+                .bind(("paths", chunk.to_vec()))
+                .bind(("since", fresh_since.to_rfc3339()))
+                .await
+                .context("Failed to query scan cache")?;
+            let found: Vec<ScanCacheEntry> = result
+                .take(0)
+                .context("Failed to extract scan cache entries")?;
+            entries.extend(found);
+        }
+        Ok(entries)
+    }
+
+    /// Record files verified clean and forget `stale` paths, e.g. files
+    /// that turned out malicious.
+    pub async fn update_scan_cache(
+        &self,
+        clean: Vec<ScanCacheEntry>,
+        stale: Vec<String>,
+    ) -> Result<()> {
+        let db = self.db.read().await;
+        for chunk in clean.chunks(BATCH_CHUNK_SIZE) {
+            let rows = serde_json::to_value(chunk).context("Failed to serialize scan cache")?;
+            db.query(
+                r#"
+                BEGIN TRANSACTION;
+                FOR $e IN $rows {
+                    UPSERT type::thing('scan_cache', $e.path) CONTENT {
+                        path: $e.path,
+                        size: $e.size,
+                        mtime_ms: $e.mtime_ms,
+                        sha256: $e.sha256,
+                        cloud_verified: $e.cloud_verified,
+                        detection_version: $e.detection_version,
+                        verified_at: <datetime> $e.verified_at
+                    };
+                };
+                COMMIT TRANSACTION;
+                "#,
+            )
+            .bind(("rows", rows))
+            .await
+            .context("Failed to update scan cache")?
+            .check()
+            .context("Scan cache update was rejected")?;
+        }
+        for chunk in stale.chunks(BATCH_CHUNK_SIZE) {
+            db.query("DELETE scan_cache WHERE path INSIDE $paths;")
+                .bind(("paths", chunk.to_vec()))
+                .await
+                .context("Failed to invalidate scan cache")?
+                .check()
+                .context("Scan cache invalidation was rejected")?;
+        }
+        Ok(())
+    }
+
+    /// Drop scan cache entries verified before `before` or against other
+    /// detection rules than `detection_version`.
+    pub async fn prune_scan_cache(
+        &self,
+        before: DateTime<Utc>,
+        detection_version: &str,
+    ) -> Result<()> {
+        let db = self.db.read().await;
+        db.query(
+            "DELETE scan_cache WHERE verified_at < <datetime> $before \
+                OR detection_version != $version;",
+        )
+        .bind(("before", before.to_rfc3339()))
+        .bind(("version", detection_version.to_string()))
+        .await
+        .context("Failed to prune scan cache")?
+        .check()
+        .context("Scan cache prune was rejected")?;
+        Ok(())
+    }
+
+    /// Replace the cached disk usage report for the report's root.
+    pub async fn store_disk_usage(&self, report: &DiskUsageReport) -> Result<()> {
+        let payload =
+            serde_json::to_value(report).context("Failed to serialize disk usage report")?;
+        let db = self.db.read().await;
+        db.query("UPSERT type::thing('disk_usage', $root) CONTENT $payload;")
+            .bind(("root", report.root.to_string_lossy().into_owned()))
+            .bind(("payload", payload))
+            .await
+            .context("Failed to store disk usage report")?
+            .check()
+            .context("Disk usage report was rejected")?;
+        Ok(())
+    }
+
+    /// The cached disk usage report for `root`, if it was analyzed before.
+    pub async fn cached_disk_usage(&self, root: &str) -> Result<Option<DiskUsageReport>> {
+        let db = self.db.read().await;
+        let mut result = db
+            .query("SELECT * OMIT id FROM type::thing('disk_usage', $root)")
+            .bind(("root", root.to_string()))
+            .await
+            .context("Failed to query disk usage cache")?;
+        let report: Option<DiskUsageReport> = result
+            .take(0)
+            .context("Failed to extract disk usage report")?;
+        Ok(report)
+    }
+
+    /// Store a threat consensus run for audit.
+    pub async fn insert_consensus_run(&self, run: ConsensusRunRecord) -> Result<()> {
+        let db = self.db.read().await;
+        db.query(
+            r#"
+            CREATE consensus_run SET
+                timestamp = time::now(),
+                risk_score = $risk_score,
+                confidence = $confidence,
+                providers = $providers,
+                input = $input,
+                provider_reports = $provider_reports,
+                report = $report
+            "#,
+        )
+        .bind(("risk_score", run.risk_score))
+        .bind(("confidence", run.confidence))
+        .bind(("providers", run.providers))
+        .bind(("input", run.input))
+        .bind(("provider_reports", run.provider_reports))
+        .bind(("report", run.report))
+        .await
+        .context("Failed to store consensus run")?;
+
+        Ok(())
+    }
+
+    /// Fetch the most recent consensus runs (newest first), without inputs.
+    pub async fn query_consensus_runs(&self, limit: usize) -> Result<Vec<ConsensusRunEntry>> {
+        let db = self.db.read().await;
+        let mut result = db
+            .query(
+                r#"
+                SELECT meta::id(id) AS id,
+                       timestamp,
+                       risk_score,
+                       confidence,
+                       providers,
+                       provider_reports,
+                       report
+                FROM consensus_run
+                ORDER BY timestamp DESC
+                LIMIT $limit
+                "#,
+            )
+            .bind(("limit", limit as i64))
+            .await
+            .context("Failed to query consensus runs")?;
+
+        let rows: Vec<ConsensusRunEntry> =
+            result.take(0).context("Failed to extract consensus runs")?;
+        Ok(rows)
+    }
+
+    /// Append one prompt/response exchange to the LLM audit trail.
+    pub async fn insert_llm_audit(&self, entry: &LlmAuditEntry) -> Result<()> {
+        let db = self.db.read().await;
+        let record = serde_json::to_value(entry).context("Failed to serialize LLM audit entry")?;
+        db.query(
+            r#"
+            CREATE llm_audit CONTENT {
+                timestamp: <datetime> $entry.timestamp,
+                provider: $entry.provider,
+                model: $entry.model,
+                purpose: $entry.purpose,
+                prompt: $entry.prompt,
+                response: $entry.response,
+                error: $entry.error,
+                duration_ms: $entry.duration_ms
+            };
+            "#,
+        )
+        .bind(("entry", record))
+        .await
+        .context("Failed to insert LLM audit entry")?
+        .check()
+        .context("LLM audit insert was rejected")?;
+        Ok(())
+    }
+
+    /// LLM audit entries matching `query`, newest first.
+    pub async fn query_llm_audit(&self, query: &LlmAuditQuery) -> Result<Vec<LlmAuditEntry>> {
+        let conditions: Vec<&str> = [
+            query.provider.as_ref().map(|_| "provider = $provider"),
+            query.model.as_ref().map(|_| "model = $model"),
+            query.purpose.as_ref().map(|_| "purpose = $purpose"),
+            query.since.map(|_| "timestamp >= <datetime> $since"),
+            query.until.map(|_| "timestamp <= <datetime> $until"),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        let filter = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+        let db = self.db.read().await;
+        let mut result = db
+            .query(format!(
+                "SELECT *, meta::id(id) AS id FROM llm_audit {filter} \
+                 ORDER BY timestamp DESC LIMIT $limit"
+            ))
+            .bind(("provider", query.provider.clone()))
+            .bind(("model", query.model.clone()))
+            .bind(("purpose", query.purpose.clone()))
+            .bind(("since", query.since.map(|t| t.to_rfc3339())))
+            .bind(("until", query.until.map(|t| t.to_rfc3339())))
+            .bind(("limit", query.limit() as i64))
+            .await
+            .context("Failed to query LLM audit trail")?;
+        let entries: Vec<LlmAuditEntry> = result
+            .take(0)
+            .context("Failed to extract LLM audit entries")?;
+        Ok(entries)
+    }
+
+    /// Drop LLM audit entries recorded before `before`; returns how many.
+    pub async fn prune_llm_audit(&self, before: DateTime<Utc>) -> Result<usize> {
+        let db = self.db.read().await;
+        let mut result = db
+            .query("DELETE llm_audit WHERE timestamp < <datetime> $before RETURN BEFORE;")
+            .bind(("before", before.to_rfc3339()))
+            .await
+            .context("Failed to prune LLM audit trail")?;
+        let removed: Vec<Value> = result.take(0).context("LLM audit prune was rejected")?;
+        Ok(removed.len())
+    }
+
+    /// Insert agent memory with embedding
+    pub async fn insert_agent_memory(&self, memory: AgentMemory) -> Result<Thing> {
+        if memory.embedding.len() != self.embedding_dimension() {
+            anyhow::bail!(
+                "Invalid embedding dimension: expected {}, got {}",
+                self.embedding_dimension(),
+                memory.embedding.len()
+            );
+        }
+
+        debug!(
+            "Inserting agent memory: agent_type={:?}, source={:?}",
+            memory.agent_type, memory.source
+        );
+
+        let db = self.db.read().await;
+
+        // Use query with datetime conversion to avoid serialization issues
+        let query = format!(
+            r#"
+            CREATE agent_memory SET
+                agent_type = '{}',
+                content = {},
+                embedding = {},
+                timestamp = d'{}',
+                source = '{}',
+                metadata = {}
+            "#,
+            format!("{:?}", memory.agent_type).to_lowercase(),
+            serde_json::to_string(&memory.content).unwrap(),
+            serde_json::to_string(&memory.embedding).unwrap(),
+            memory.timestamp.to_rfc3339(),
+            format!("{:?}", memory.source).to_lowercase(),
+            memory
+                .metadata
+                .map(|m| serde_json::to_string(&m).unwrap())
+                .unwrap_or_else(|| "NONE".to_string())
+        );
+
+        let _result = db
+            .query(query)
+            .await
+            .context("Failed to insert agent memory")?;
+
+        // For now, just return a dummy Thing since the insertion worked
+        // TODO: Fix deserialization issue with Thing
+        Ok(Thing::from(("agent_memory", "dummy")))
+    }
+
+    /// Insert many agent memories using bound parameters, one transaction
+    /// per [`BATCH_CHUNK_SIZE`] records. Fails before writing anything if
+    /// any embedding has the wrong dimension.
+    pub async fn insert_agent_memories_batch(&self, memories: Vec<AgentMemory>) -> Result<usize> {
+        if let Some(bad) = memories
+            .iter()
+            .find(|m| m.embedding.len() != self.embedding_dimension())
+        {
+            anyhow::bail!(
+                "Invalid embedding dimension: expected {}, got {}",
+                self.embedding_dimension(),
+                bad.embedding.len()
+            );
+        }
+        if memories.is_empty() {
+            return Ok(0);
+        }
+        debug!("Batch inserting {} agent memories", memories.len());
+
+        let db = self.db.read().await;
+        for chunk in memories.chunks(BATCH_CHUNK_SIZE) {
+            let rows = serde_json::to_value(chunk).context("Failed to serialize memories")?;
+            db.query(
+                r#"
+                BEGIN TRANSACTION;
+                FOR $m IN $rows {
+                    CREATE agent_memory CONTENT {
+                        agent_type: $m.agent_type,
+                        content: $m.content,
+                        embedding: $m.embedding,
+                        timestamp: <datetime> $m.timestamp,
+                        source: $m.source,
+                        metadata: $m.metadata
+                    };
+                };
+                COMMIT TRANSACTION;
+                "#,
+            )
+            .bind(("rows", rows))
+            .await
+            .context("Failed to batch insert agent memories")?
+            .check()
+            .context("Batch insert of agent memories was rejected")?;
+        }
+        Ok(memories.len())
+    }
+
+    // ------------------------------------------------------------------------
+    // Backup, Restore & Compaction
+    // ------------------------------------------------------------------------
+
+    /// Export the whole database as a SurrealQL script.
+    ///
+    /// A SurrealQL export works for every connection mode (a RocksDB
+    /// checkpoint would only cover embedded stores) and doubles as a
+    /// migration format between machines. The file is written next to
+    /// `path` first and renamed into place, so a failed export never
+    /// truncates an earlier backup.
+    pub async fn backup_database(
+        &self,
+        path: impl AsRef<Path>,
+        progress: impl Fn(MaintenanceProgress) + Send + Sync,
+    ) -> Result<BackupInfo> {
+        let path = path.as_ref();
+        let started = std::time::Instant::now();
+        progress(MaintenanceProgress::new("backup", "preparing", 0));
+
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .context("Failed to create backup directory")?;
+        }
+        let partial = path.with_extension("partial");
+
+        progress(MaintenanceProgress::new("backup", "exporting", 10));
+        let db = self.db.read().await;
+        if let Err(e) = db.export(&partial).await {
+            let _ = tokio::fs::remove_file(&partial).await;
+            return Err(anyhow!(e)).context("Failed to export database");
+        }
+        drop(db);
+
+        progress(MaintenanceProgress::new("backup", "finalizing", 90));
+        tokio::fs::rename(&partial, path)
+            .await
+            .context("Failed to move backup into place")?;
+        let size_bytes = tokio::fs::metadata(path)
+            .await
+            .context("Failed to read backup size")?
+            .len();
+
+        info!("Database backed up to {:?} ({size_bytes} bytes)", path);
+        progress(MaintenanceProgress::new("backup", "done", 100));
+        Ok(BackupInfo {
+            path: path.display().to_string(),
+            size_bytes,
+            created_at: Utc::now(),
+            duration_ms: started.elapsed().as_millis() as u64,
+        })
+    }
+
+    /// Replace the database contents with a backup made by
+    /// [`Self::backup_database`].
+    ///
+    /// The current contents are exported to a safety file first; if the
+    /// import fails they are re-imported, so a bad backup never leaves the
+    /// store empty. Other queries wait for the restore to finish.
+    pub async fn restore_database(
+        &self,
+        path: impl AsRef<Path>,
+        progress: impl Fn(MaintenanceProgress) + Send + Sync,
+    ) -> Result<()> {
+        let path = path.as_ref();
+        progress(MaintenanceProgress::new("restore", "validating", 0));
+        let size = tokio::fs::metadata(path)
+            .await
+            .with_context(|| format!("Backup file {:?} is not readable", path))?
+            .len();
+        if size == 0 {
+            anyhow::bail!("Backup file {:?} is empty", path);
+        }
+
+        let db = self.db.write().await;
+        let namespace = self.namespace();
+
+        progress(MaintenanceProgress::new(
+            "restore",
+            "saving current data",
+            10,
+        ));
+        let safety = std::env::temp_dir().join(format!(
+            "oxide-restore-safety-{}.surql",
+            uuid::Uuid::new_v4()
+        ));
+        db.export(&safety)
+            .await
+            .context("Failed to save current data before restore")?;
+
+        progress(MaintenanceProgress::new("restore", "clearing", 30));
+        let outcome = async {
+            Self::clear_database(&db, &namespace).await?;
+            progress(MaintenanceProgress::new("restore", "importing", 50));
+            db.import(path).await.context("Failed to import backup")?;
+            progress(MaintenanceProgress::new("restore", "upgrading schema", 90));
+            Self::init_schema(&db, self.embedding_dimension()).await
+        }
+        .await;
+
+        if let Err(e) = outcome {
+            warn!("Restore from {:?} failed, rolling back: {e:#}", path);
+            let rollback = async {
+                Self::clear_database(&db, &namespace).await?;
+                db.import(&safety).await.context("Failed to re-import")?;
+                Self::init_schema(&db, self.embedding_dimension()).await
+            }
+            .await;
+            return match rollback {
+                Ok(()) => {
+                    let _ = tokio::fs::remove_file(&safety).await;
+                    Err(e.context("Restore failed; previous data was kept"))
+                }
+                Err(rollback_err) => Err(e.context(format!(
+                    "Restore failed and rollback failed ({rollback_err:#}); previous data is saved at {}",
+                    safety.display()
+                ))),
+            };
+        }
+
+        let _ = tokio::fs::remove_file(&safety).await;
+        info!("Database restored from {:?}", path);
+        progress(MaintenanceProgress::new("restore", "done", 100));
+        Ok(())
+    }
+
+    /// Drop every table, function and index in the Oxide database of
+    /// `namespace`.
+    async fn clear_database(db: &Surreal<Any>, namespace: &str) -> Result<()> {
+        db.query(format!("REMOVE DATABASE IF EXISTS {DATABASE};"))
+            .await
+            .context("Failed to clear database")?
+            .check()
+            .context("Clearing the database was rejected")?;
+        // Re-select so later statements recreate the database
+        db.use_ns(namespace)
+            .use_db(DATABASE)
+            .await
+            .context("Failed to select namespace/database")?;
+        Ok(())
+    }
+
+    /// Delete metrics and cached embeddings older than `retention_days`
+    /// (when given) and rebuild every index so deleted entries stop taking
+    /// space and lookups stay fast. Storage engines reclaim the freed pages
+    /// in the background.
+    pub async fn compact_database(
+        &self,
+        retention_days: Option<u32>,
+        progress: impl Fn(MaintenanceProgress) + Send + Sync,
+    ) -> Result<CompactionReport> {
+        let started = std::time::Instant::now();
+        let mut report = CompactionReport::default();
+        let db = self.db.read().await;
+
+        if let Some(days) = retention_days {
+            progress(MaintenanceProgress::new(
+                "compact",
+                "pruning old metrics",
+                0,
+            ));
+            let cutoff = Utc::now() - chrono::Duration::days(days as i64);
+            let mut response = db
+                .query(
+                    r#"
+                    SELECT count() AS n FROM system_metrics
+                        WHERE timestamp < <datetime> $cutoff GROUP ALL;
+                    DELETE system_metrics WHERE timestamp < <datetime> $cutoff;
+                    DELETE process_sample WHERE timestamp < <datetime> $cutoff;
+                    "#,
+                )
+                .bind(("cutoff", cutoff.to_rfc3339()))
+                .await
+                .context("Failed to prune old metrics")?;
+            let counted: Option<Value> = response.take(0)?;
+            report.removed_metrics = counted
+                .and_then(|row| row.get("n").and_then(Value::as_u64))
+                .unwrap_or(0);
+            db.query("DELETE embedding_cache WHERE used_at < <datetime> $cutoff;")
+                .bind(("cutoff", cutoff.to_rfc3339()))
+                .await
+                .context("Failed to prune embedding cache")?;
+        }
+
+        progress(MaintenanceProgress::new("compact", "listing indexes", 30));
+        let mut response = db
+            .query("INFO FOR DB;")
+            .await
+            .context("Failed to read database info")?;
+        let info: Option<Value> = response.take(0)?;
+        let tables: Vec<String> = info
+            .as_ref()
+            .and_then(|i| i.get("tables"))
+            .and_then(Value::as_object)
+            .map(|t| t.keys().cloned().collect())
+            .unwrap_or_default();
+
+        let mut indexes = Vec::new();
+        for table in &tables {
+            let mut response = db
+                .query(format!("INFO FOR TABLE {table};"))
+                .await
+                .with_context(|| format!("Failed to read info for table {table}"))?;
+            let info: Option<Value> = response.take(0)?;
+            if let Some(names) = info
+                .as_ref()
+                .and_then(|i| i.get("indexes"))
+                .and_then(Value::as_object)
+            {
+                indexes.extend(names.keys().map(|name| (table.clone(), name.clone())));
+            }
+        }
+
+        let total = indexes.len().max(1);
+        for (done, (table, index)) in indexes.into_iter().enumerate() {
+            let percent = 40 + (done * 60 / total) as u8;
+            progress(MaintenanceProgress::new(
+                "compact",
+                &format!("rebuilding {table}.{index}"),
+                percent,
+            ));
+            match db
+                .query(format!("REBUILD INDEX IF EXISTS {index} ON {table};"))
+                .await
+                .and_then(|r| r.check())
+            {
+                Ok(_) => report.rebuilt_indexes.push(format!("{table}.{index}")),
+                Err(e) => warn!("Skipping rebuild of {table}.{index}: {e}"),
+            }
+        }
+
+        report.duration_ms = started.elapsed().as_millis() as u64;
+        info!(
+            "Database compacted: {} metrics removed, {} indexes rebuilt",
+            report.removed_metrics,
+            report.rebuilt_indexes.len()
+        );
+        progress(MaintenanceProgress::new("compact", "done", 100));
+        Ok(report)
+    }
+
+    /// Define the HNSW index on `agent_memory.embedding`, replacing an
+    /// existing one when `replace` is set.
+    async fn define_vector_index(db: &Surreal<Any>, dim: usize, replace: bool) -> Result<()> {
+        if replace {
+            db.query("REMOVE INDEX IF EXISTS idx_embedding ON agent_memory;")
+                .await
+                .and_then(|r| r.check())
+                .context("Failed to remove vector index")?;
+        }
+        db.query(format!(
+            r#"
+            DEFINE INDEX IF NOT EXISTS idx_embedding ON agent_memory
+                FIELDS embedding
+                HNSW DIMENSION {dim} DIST COSINE EF {HNSW_EF_CONSTRUCTION} M {HNSW_M};
+            "#
+        ))
+        .await
+        .and_then(|r| r.check())
+        .context("Failed to define vector index")?;
+        info!("HNSW vector index ready on agent_memory.embedding (dimension={dim})");
+        Ok(())
+    }
+
+    /// Re-create the vector index, re-embedding memories whose vectors do
+    /// not match the dimension first.
+    ///
+    /// With `new_dim` the backend switches to that dimension and every
+    /// memory is re-embedded; that needs an embedding provider. The new
+    /// dimension lasts until restart unless `OXIDE_EMBEDDINGS_DIM` is set to
+    /// match. Without it, only memories stored at another dimension (or
+    /// missed by a skipped index) are repaired. Recall and latency are then
+    /// measured by querying with a sample of stored memories.
+    pub async fn rebuild_vector_index(
+        &self,
+        new_dim: Option<usize>,
+        progress: impl Fn(MaintenanceProgress) + Send + Sync,
+    ) -> Result<VectorIndexReport> {
+        let started = std::time::Instant::now();
+        progress(MaintenanceProgress::new("reindex", "checking", 0));
+        let previous_dimension = self.embedding_dimension();
+        let dimension = new_dim.unwrap_or(previous_dimension);
+        if dimension == 0 {
+            anyhow::bail!("Embedding dimension must be greater than 0");
+        }
+        if dimension != previous_dimension && self.embedding_service.is_none() {
+            anyhow::bail!(
+                "Changing the embedding dimension requires an embedding provider; none is configured"
+            );
+        }
+
+        progress(MaintenanceProgress::new("reindex", "removing index", 5));
+        self.db
+            .read()
+            .await
+            .query("REMOVE INDEX IF EXISTS idx_embedding ON agent_memory;")
+            .await
+            .and_then(|r| r.check())
+            .context("Failed to remove vector index")?;
+        self.embedding_dim.store(dimension, Ordering::Relaxed);
+        if dimension != previous_dimension {
+            warn!(
+                "Embedding dimension changed from {previous_dimension} to {dimension}; set OXIDE_EMBEDDINGS_DIM={dimension} to keep it after restart"
+            );
+        }
+
+        let mut report = VectorIndexReport {
+            previous_dimension,
+            dimension,
+            memories: self.count_agent_memories(None).await?,
+            ..Default::default()
+        };
+        let stale = self.count_agent_memories(Some(dimension)).await?.max(1);
+
+        loop {
+            let mut response = self
+                .db
+                .read()
+                .await
+                .query(
+                    "SELECT meta::id(id) AS id, content FROM agent_memory \
+                     WHERE array::len(embedding) != $dim LIMIT $limit;",
+                )
+                .bind(("dim", dimension as i64))
+                .bind(("limit", REINDEX_PAGE_SIZE as i64))
+                .await
+                .context("Failed to list memories to re-embed")?;
+            let page: Vec<Value> = response.take(0)?;
+            if page.is_empty() {
+                break;
+            }
+            for row in page {
+                let id = row.get("id").and_then(Value::as_str).unwrap_or_default();
+                let content = row
+                    .get("content")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default();
+                let embedding = self
+                    .embed_text(content)
+                    .await
+                    .unwrap_or_else(|_| vec![0.0; dimension]);
+                if embedding.iter().all(|v| *v == 0.0) {
+                    report.unembedded += 1;
+                }
+                self.db
+                    .read()
+                    .await
+                    .query("UPDATE type::thing('agent_memory', $id) SET embedding = $embedding;")
+                    .bind(("id", id.to_string()))
+                    .bind(("embedding", embedding))
+                    .await
+                    .and_then(|r| r.check())
+                    .with_context(|| format!("Failed to store new embedding for {id}"))?;
+                report.re_embedded += 1;
+            }
+            progress(MaintenanceProgress::new(
+                "reindex",
+                "re-embedding",
+                (10 + (report.re_embedded * 70 / stale).min(70)) as u8,
+            ));
+        }
+
+        progress(MaintenanceProgress::new("reindex", "building index", 85));
+        match Self::define_vector_index(&*self.db.read().await, dimension, true).await {
+            Ok(()) => report.index_ready = true,
+            Err(e) => warn!("Vector index not rebuilt, search will scan all memories: {e:#}"),
+        }
+
+        progress(MaintenanceProgress::new("reindex", "measuring recall", 92));
+        if let Some((recall, latency_ms)) = self.sample_recall().await? {
+            report.recall = Some(recall);
+            report.avg_latency_ms = Some(latency_ms);
+        }
+
+        report.duration_ms = started.elapsed().as_millis() as u64;
+        info!(
+            "Vector index rebuilt at dimension {dimension}: {} memories, {} re-embedded, recall {:?}",
+            report.memories, report.re_embedded, report.recall
+        );
+        progress(MaintenanceProgress::new("reindex", "done", 100));
+        Ok(report)
+    }
+
+    /// Agent memories, or with `not_dim` only those whose embedding has
+    /// another length.
+    async fn count_agent_memories(&self, not_dim: Option<usize>) -> Result<usize> {
+        let filter = if not_dim.is_some() {
+            "WHERE array::len(embedding) != $dim "
+        } else {
+            ""
+        };
+        let mut response = self
+            .db
+            .read()
+            .await
+            .query(format!(
+                "SELECT count() AS n FROM agent_memory {filter}GROUP ALL;"
+            ))
+            .bind(("dim", not_dim.unwrap_or_default() as i64))
+            .await
+            .context("Failed to count agent memories")?;
+        let row: Option<Value> = response.take(0)?;
+        Ok(row
+            .and_then(|r| r.get("n").and_then(Value::as_u64))
+            .unwrap_or(0) as usize)
+    }
+
+    /// Average recall of [`Self::vector_search`] against an exact cosine
+    /// ranking, and its average latency in milliseconds, using stored
+    /// memories as queries. `None` when no memory has a usable vector.
+    async fn sample_recall(&self) -> Result<Option<(f64, f64)>> {
+        #[derive(Deserialize)]
+        struct Sample {
+            agent_type: String,
+            embedding: Vec<f64>,
+        }
+        #[derive(Deserialize)]
+        struct Neighbour {
+            content: String,
+        }
+
+        let dim = self.embedding_dimension();
+        let samples: Vec<Sample> = self
+            .db
+            .read()
+            .await
+            .query(
+                "SELECT agent_type, embedding FROM agent_memory \
+                 WHERE array::len(embedding) = $dim LIMIT $limit;",
+            )
+            .bind(("dim", dim as i64))
+            .bind(("limit", (REINDEX_RECALL_SAMPLES * 2) as i64))
+            .await
+            .context("Failed to sample agent memories")?
+            .take(0)?;
+
+        let mut recall_sum = 0.0;
+        let mut latency_ms = 0.0;
+        let mut queries = 0usize;
+        for sample in samples
+            .into_iter()
+            .filter(|s| s.embedding.iter().any(|v| *v != 0.0))
+            .take(REINDEX_RECALL_SAMPLES)
+        {
+            let exact: Vec<Neighbour> = self
+                .db
+                .read()
+                .await
+                .query(
+                    "SELECT content, vector::similarity::cosine(embedding, $query_vec) AS score \
+                     FROM agent_memory WHERE agent_type = $agent_type \
+                     ORDER BY score DESC LIMIT $limit;",
+                )
+                .bind(("query_vec", sample.embedding.clone()))
+                .bind(("agent_type", sample.agent_type.clone()))
+                .bind(("limit", REINDEX_RECALL_K as i64))
+                .await
+                .context("Failed to run exact neighbour query")?
+                .take(0)?;
+            if exact.is_empty() {
+                continue;
+            }
+
+            let timer = std::time::Instant::now();
+            let found = self
+                .vector_search(sample.embedding, &sample.agent_type, REINDEX_RECALL_K)
+                .await?;
+            latency_ms += timer.elapsed().as_secs_f64() * 1000.0;
+
+            let hits = exact
+                .iter()
+                .filter(|n| found.iter().any(|f| f.text == n.content))
+                .count();
+            recall_sum += hits as f64 / exact.len() as f64;
+            queries += 1;
+        }
+
+        Ok((queries > 0).then(|| (recall_sum / queries as f64, latency_ms / queries as f64)))
+    }
+
+    // ------------------------------------------------------------------------
+    // Agent Memory Browsing
+    // ------------------------------------------------------------------------
+
+    /// One page of agent memories matching the filters, with the total
+    /// count for pagination. `time_range` is inclusive; `limit` is capped at
+    /// [`MAX_MEMORY_PAGE`].
+    pub async fn list_agent_memories(
+        &self,
+        agent_type: Option<AgentType>,
+        source: Option<MemorySource>,
+        time_range: Option<(DateTime<Utc>, DateTime<Utc>)>,
+        sort: MemorySort,
+        offset: usize,
+        limit: usize,
+    ) -> Result<AgentMemoryPage> {
+        let limit = limit.clamp(1, MAX_MEMORY_PAGE);
+        let mut conditions = Vec::new();
+        if agent_type.is_some() {
+            conditions.push("agent_type = $agent_type");
+        }
+        if source.is_some() {
+            conditions.push("source = $source");
+        }
+        if time_range.is_some() {
+            conditions.push("timestamp >= <datetime> $since AND timestamp <= <datetime> $until");
+        }
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+        let direction = match sort {
+            MemorySort::NewestFirst => "DESC",
+            MemorySort::OldestFirst => "ASC",
+        };
+
+        let db = self.db.read().await;
+        let mut query = db
+            .query(format!(
+                "SELECT meta::id(id) AS id, agent_type, content, timestamp, source, metadata \
+                 FROM agent_memory {where_clause} ORDER BY timestamp {direction} \
+                 LIMIT $limit START $start; \
+                 SELECT count() AS n FROM agent_memory {where_clause} GROUP ALL;"
+            ))
+            .bind(("limit", limit as i64))
+            .bind(("start", offset as i64));
+        if let Some(agent_type) = agent_type {
+            query = query.bind(("agent_type", agent_type));
+        }
+        if let Some(source) = source {
+            query = query.bind(("source", source));
+        }
+        if let Some((since, until)) = time_range {
+            query = query
+                .bind(("since", since.to_rfc3339()))
+                .bind(("until", until.to_rfc3339()));
+        }
+        let mut response = query.await.context("Failed to list agent memories")?;
+        let items: Vec<AgentMemoryEntry> = response
+            .take(0)
+            .context("Failed to extract agent memories")?;
+        let count: Option<Value> = response.take(1)?;
+        let total = count
+            .and_then(|row| row.get("n").and_then(Value::as_u64))
+            .unwrap_or(0) as usize;
+        Ok(AgentMemoryPage {
+            items,
+            total,
+            offset,
+            limit,
+        })
+    }
+
+    /// Delete one agent memory; returns false if `memory_id` did not exist.
+    pub async fn delete_agent_memory(&self, memory_id: &str) -> Result<bool> {
+        let id = memory_id
+            .strip_prefix("agent_memory:")
+            .unwrap_or(memory_id)
+            .to_string();
+        let db = self.db.read().await;
+        let mut result = db
+            .query("DELETE type::thing('agent_memory', $id) RETURN BEFORE")
+            .bind(("id", id.clone()))
+            .await
+            .context("Failed to delete agent memory")?;
+        let deleted: Vec<Value> = result.take(0)?;
+        if !deleted.is_empty() {
+            info!("Deleted agent memory {id}");
+        }
+        Ok(!deleted.is_empty())
+    }
+
+    /// Delete the agent memories written by one run of
+    /// [`crate::migration::migrate_memory_backend`].
+    pub async fn delete_migrated_memories(&self, migration_id: &str) -> Result<usize> {
+        let db = self.db.read().await;
+        let mut result = db
+            .query("DELETE agent_memory WHERE metadata.migration_id = $migration_id RETURN BEFORE")
+            .bind(("migration_id", migration_id.to_string()))
+            .await
+            .context("Failed to delete migrated agent memories")?;
+        let deleted: Vec<Value> = result.take(0)?;
+        info!(
+            "Deleted {} agent memories from migration {migration_id}",
+            deleted.len()
+        );
+        Ok(deleted.len())
+    }
+
+    // ------------------------------------------------------------------------
+    // Agent Memory Consolidation
+    // ------------------------------------------------------------------------
+
+    /// Raw memories older than `older_than`, oldest first. Summaries made by
+    /// earlier consolidation runs are not returned.
+    pub async fn consolidation_candidates(
+        &self,
+        older_than: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<ConsolidationCandidate>> {
+        let db = self.db.read().await;
+        let mut result = db
+            .query(
+                r#"
+                SELECT meta::id(id) AS id, agent_type, source, content, embedding, timestamp
+                FROM agent_memory
+                WHERE timestamp < <datetime> $cutoff AND consolidated_from IS NONE
+                ORDER BY timestamp ASC
+                LIMIT $limit
+                "#,
+            )
+            .bind(("cutoff", older_than.to_rfc3339()))
+            .bind(("limit", limit as i64))
+            .await
+            .context("Failed to query consolidation candidates")?;
+        let candidates: Vec<ConsolidationCandidate> = result
+            .take(0)
+            .context("Failed to extract consolidation candidates")?;
+        Ok(candidates)
+    }
+
+    /// Store `summary` in place of the memories in `original_ids`, which
+    /// are archived (without embeddings) or deleted. One transaction, so a
+    /// failure leaves the originals untouched.
+    pub async fn store_consolidated_memory(
+        &self,
+        summary: AgentMemory,
+        original_ids: &[String],
+        originals: ConsolidationRetention,
+    ) -> Result<()> {
+        if summary.embedding.len() != self.embedding_dimension() {
+            anyhow::bail!(
+                "Invalid embedding dimension: expected {}, got {}",
+                self.embedding_dimension(),
+                summary.embedding.len()
+            );
+        }
+        let ids: Vec<String> = original_ids
+            .iter()
+            .map(|id| id.strip_prefix("agent_memory:").unwrap_or(id).to_string())
+            .collect();
+        let things: Vec<Thing> = ids
+            .iter()
+            .map(|id| Thing::from(("agent_memory", id.as_str())))
+            .collect();
+        let archive = match originals {
+            ConsolidationRetention::Archive => {
+                r#"
+                FOR $old IN (SELECT * FROM $things) {
+                    CREATE agent_memory_archive CONTENT {
+                        original_id: meta::id($old.id),
+                        agent_type: $old.agent_type,
+                        content: $old.content,
+                        timestamp: $old.timestamp,
+                        source: $old.source,
+                        metadata: $old.metadata,
+                        archived_at: time::now()
+                    };
+                };
+                "#
+            }
+            ConsolidationRetention::Delete => "",
+        };
+
+        let db = self.db.read().await;
+        db.query(format!(
+            r#"
+            BEGIN TRANSACTION;
+            CREATE agent_memory CONTENT {{
+                agent_type: $m.agent_type,
+                content: $m.content,
+                embedding: $m.embedding,
+                timestamp: <datetime> $m.timestamp,
+                source: $m.source,
+                metadata: $m.metadata,
+                consolidated_from: $ids
+            }};
+            {archive}
+            DELETE $things;
+            COMMIT TRANSACTION;
+            "#
+        ))
+        .bind((
+            "m",
+            serde_json::to_value(&summary).context("Failed to serialize summary")?,
+        ))
+        .bind(("ids", ids))
+        .bind(("things", things))
+        .await
+        .context("Failed to store consolidated memory")?
+        .check()
+        .context("Consolidated memory was rejected")?;
+        debug!("Consolidated {} agent memories", original_ids.len());
+        Ok(())
+    }
+
+    /// Rebuild a text that `add_texts` stored as chunks, from the
+    /// `parent_id` in any of its chunks' metadata. `None` when no chunks
+    /// remain.
+    pub async fn get_chunked_document(&self, parent_id: &str) -> Result<Option<String>> {
+        #[derive(Deserialize)]
+        struct ChunkRow {
+            content: String,
+            index: usize,
+            offset: usize,
+        }
+
+        let db = self.db.read().await;
+        let mut result = db
+            .query(
+                r#"
+                SELECT content, metadata.chunk_index AS index, metadata.chunk_offset AS offset
+                    FROM agent_memory
+                    WHERE metadata.parent_id = $parent_id;
+                "#,
+            )
+            .bind(("parent_id", parent_id.to_string()))
+            .await
+            .context("Failed to query memory chunks")?;
+        let rows: Vec<ChunkRow> = result.take(0).context("Failed to extract memory chunks")?;
+        if rows.is_empty() {
+            return Ok(None);
+        }
+        let chunks: Vec<TextChunk> = rows
+            .into_iter()
+            .map(|row| TextChunk {
+                index: row.index,
+                offset: row.offset,
+                text: row.content,
+            })
+            .collect();
+        Ok(Some(reassemble(&chunks)))
+    }
+
+    // ------------------------------------------------------------------------
+    // Agent Memory Export & Import
+    // ------------------------------------------------------------------------
+
+    /// Write agent memories matching `filter` to `path` as JSON Lines, one
+    /// [`AgentMemory`] per line, oldest first.
+    pub async fn export_agent_memory(
+        &self,
+        filter: &MemoryExportFilter,
+        path: impl AsRef<Path>,
+    ) -> Result<MemoryTransferReport> {
+        use tokio::io::AsyncWriteExt;
+
+        let path = path.as_ref();
+        let started = std::time::Instant::now();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .context("Failed to create export directory")?;
+        }
+
+        let mut conditions = Vec::new();
+        if filter.agent_type.is_some() {
+            conditions.push("agent_type = $agent_type");
+        }
+        if filter.since.is_some() {
+            conditions.push("timestamp >= <datetime> $since");
+        }
+        if filter.until.is_some() {
+            conditions.push("timestamp <= <datetime> $until");
+        }
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+        let total = filter.limit.unwrap_or(usize::MAX);
+        // With a limit, skip the oldest records so the newest ones remain
+        let offset = if filter.limit.is_some() {
+            let db = self.db.read().await;
+            let query = format!("SELECT count() AS n FROM agent_memory {where_clause} GROUP ALL");
+            let mut response = bind_export_filter(db.query(query), filter)
+                .await
+                .context("Failed to count agent memories")?;
+            let count: Option<Value> = response.take(0)?;
+            let count = count
+                .and_then(|row| row.get("n").and_then(Value::as_u64))
+                .unwrap_or(0) as usize;
+            count.saturating_sub(total)
+        } else {
+            0
+        };
+
+        let partial = path.with_extension("partial");
+        let file = tokio::fs::File::create(&partial)
+            .await
+            .context("Failed to create export file")?;
+        let mut writer = tokio::io::BufWriter::new(file);
+        let mut report = MemoryTransferReport {
+            path: path.display().to_string(),
+            ..Default::default()
+        };
+
+        let outcome: Result<()> = async {
+            let db = self.db.read().await;
+            while report.records < total {
+                let page = BATCH_CHUNK_SIZE.min(total - report.records);
+                let query = format!(
+                    "SELECT agent_type, content, embedding, timestamp, source, metadata \
+                     FROM agent_memory {where_clause} ORDER BY timestamp ASC \
+                     LIMIT $limit START $start"
+                );
+                let mut response = bind_export_filter(db.query(query), filter)
+                    .bind(("limit", page as i64))
+                    .bind(("start", (offset + report.records) as i64))
+                    .await
+                    .context("Failed to read agent memories")?;
+                let memories: Vec<AgentMemory> = response
+                    .take(0)
+                    .context("Failed to extract agent memories")?;
+                for memory in &memories {
+                    let mut line =
+                        serde_json::to_string(memory).context("Failed to serialize memory")?;
+                    line.push('\n');
+                    writer.write_all(line.as_bytes()).await?;
+                }
+                report.records += memories.len();
+                if memories.len() < page {
+                    break;
+                }
+            }
+            writer.flush().await?;
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = outcome {
+            let _ = tokio::fs::remove_file(&partial).await;
+            return Err(e.context("Agent memory export failed"));
+        }
+        tokio::fs::rename(&partial, path)
+            .await
+            .context("Failed to move export into place")?;
+
+        report.duration_ms = started.elapsed().as_millis() as u64;
+        info!("Exported {} agent memories to {:?}", report.records, path);
+        Ok(report)
+    }
+
+    /// Load agent memories from a JSON Lines file written by
+    /// [`Self::export_agent_memory`].
+    ///
+    /// Embeddings must match this install's dimension; `re_embed` decides
+    /// whether mismatched (or all) records are re-embedded from their
+    /// content instead of skipped. Malformed lines are skipped and reported.
+    pub async fn import_agent_memory(
+        &self,
+        path: impl AsRef<Path>,
+        re_embed: ReEmbedPolicy,
+    ) -> Result<MemoryTransferReport> {
+        use tokio::io::AsyncBufReadExt;
+
+        let path = path.as_ref();
+        let started = std::time::Instant::now();
+        if re_embed != ReEmbedPolicy::Never && self.embedding_service.is_none() {
+            anyhow::bail!("Re-embedding requires an embedding provider; none is configured");
+        }
+
+        let file = tokio::fs::File::open(path)
+            .await
+            .with_context(|| format!("Failed to open {:?}", path))?;
+        let mut lines = tokio::io::BufReader::new(file).lines();
+        let mut report = MemoryTransferReport {
+            path: path.display().to_string(),
+            ..Default::default()
+        };
+        let skip = |report: &mut MemoryTransferReport, message: String| {
+            report.skipped += 1;
+            if report.errors.len() < MAX_TRANSFER_ERRORS {
+                report.errors.push(message);
+            }
+        };
+
+        let mut pending = Vec::with_capacity(BATCH_CHUNK_SIZE);
+        let mut line_no = 0usize;
+        while let Some(line) = lines
+            .next_line()
+            .await
+            .context("Failed to read import file")?
+        {
+            line_no += 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let mut memory: AgentMemory = match serde_json::from_str(&line) {
+                Ok(memory) => memory,
+                Err(e) => {
+                    skip(&mut report, format!("line {line_no}: {e}"));
+                    continue;
+                }
+            };
+
+            let mismatched = memory.embedding.len() != self.embedding_dimension();
+            let refresh = match re_embed {
+                ReEmbedPolicy::Never => false,
+                ReEmbedPolicy::Mismatched => mismatched,
+                ReEmbedPolicy::Always => true,
+            };
+            if refresh {
+                match self.embed_text(&memory.content).await {
+                    Ok(embedding) => {
+                        memory.embedding = embedding;
+                        report.re_embedded += 1;
+                    }
+                    Err(e) => {
+                        skip(
+                            &mut report,
+                            format!("line {line_no}: re-embedding failed: {e}"),
+                        );
+                        continue;
+                    }
+                }
+            } else if mismatched {
+                skip(
+                    &mut report,
+                    format!(
+                        "line {line_no}: embedding dimension {} does not match {}",
+                        memory.embedding.len(),
+                        self.embedding_dimension()
+                    ),
+                );
+                continue;
+            }
+
+            pending.push(memory);
+            if pending.len() >= BATCH_CHUNK_SIZE {
+                report.records += self
+                    .insert_agent_memories_batch(std::mem::take(&mut pending))
+                    .await?;
+            }
+        }
+        report.records += self.insert_agent_memories_batch(pending).await?;
+
+        report.duration_ms = started.elapsed().as_millis() as u64;
+        info!(
+            "Imported {} agent memories from {:?} ({} re-embedded, {} skipped)",
+            report.records, path, report.re_embedded, report.skipped
+        );
+        Ok(report)
+    }
+
+    // ------------------------------------------------------------------------
+    // Threat Training
+    // ------------------------------------------------------------------------
+
+    async fn define_threat_model(db: &Surreal<Any>, replace: bool) -> Result<()> {
+        if replace {
+            db.query("REMOVE MODEL IF EXISTS threat_risk_model;")
+                .await
+                .and_then(|r| r.check())
+                .context("Failed to remove threat model")?;
+        }
+        db.query(
+            r#"
+            DEFINE MODEL IF NOT EXISTS threat_risk_model
+                ON threat_training
+                TARGET severity
+                FEATURES cpu_usage, memory_pressure, network_score, anomaly_score
+                TYPE BAYES;
+            "#,
+        )
+        .await
+        .and_then(|r| r.check())
+        .context("Failed to define threat model")?;
+        Ok(())
+    }
+
+    /// Training sample labelled `severity` for an event at `at`. Features
+    /// come from the latest system metrics sample in the ten minutes before
+    /// the event, or are zero when none was recorded.
+    async fn training_sample_at(
+        &self,
+        at: DateTime<Utc>,
+        severity: &str,
+        anomaly_score: f64,
+        label_source: LabelSource,
+        source_id: String,
+    ) -> Result<ThreatTrainingSample> {
+        let db = self.db.read().await;
+        let mut result = db
+            .query(
+                r#"
+                SELECT cpu_usage, memory_usage.percent AS memory_percent, network_stats
+                FROM system_metrics
+                WHERE timestamp >= <datetime> $from AND timestamp <= <datetime> $at
+                ORDER BY timestamp DESC
+                LIMIT 1
+                "#,
+            )
+            .bind(("from", (at - chrono::Duration::minutes(10)).to_rfc3339()))
+            .bind(("at", at.to_rfc3339()))
+            .await
+            .context("Failed to query metrics for training sample")?;
+        let metrics: Option<Value> = result.take(0)?;
+        let metric = |key: &str| {
+            metrics
+                .as_ref()
+                .and_then(|m| m.pointer(key))
+                .and_then(Value::as_f64)
+                .unwrap_or(0.0)
+        };
+
+        Ok(ThreatTrainingSample {
+            severity: severity.to_string(),
+            cpu_usage: metric("/cpu_usage"),
+            memory_pressure: metric("/memory_percent"),
+            network_score: threat_training::network_score(
+                metric("/network_stats/sent_mb_per_sec"),
+                metric("/network_stats/recv_mb_per_sec"),
+                metric("/network_stats/connections_active") as u64,
+            ),
+            anomaly_score: anomaly_score.clamp(0.0, 100.0),
+            metadata: None,
+            label_source: Some(label_source),
+            source_id: Some(source_id),
+        })
+    }
+
+    /// Store a collected sample under a key derived from its source, so
+    /// collecting or labelling the same event again replaces it.
+    async fn store_training_sample(
+        &self,
+        key: String,
+        sample: &ThreatTrainingSample,
+    ) -> Result<()> {
+        let payload =
+            serde_json::to_value(sample).context("Failed to serialize threat training sample")?;
+        let db = self.db.read().await;
+        db.query("UPSERT type::thing('threat_training', $key) CONTENT $payload")
+            .bind(("key", key))
+            .bind(("payload", payload))
+            .await
+            .and_then(|r| r.check())
+            .context("Failed to store threat training sample")?;
+        Ok(())
+    }
+
+    /// Threat row fields used for training, or `None` if it does not exist.
+    async fn threat_for_training(&self, id: &str) -> Result<Option<Value>> {
+        let db = self.db.read().await;
+        let mut result = db
+            .query(
+                r#"
+                SELECT meta::id(id) AS id, severity, yara_rule, heuristic_score,
+                       risk_score, <string> timestamp AS timestamp, mitigation_status
+                FROM type::thing('threat', $id)
+                "#,
+            )
+            .bind(("id", id.to_string()))
+            .await
+            .context("Failed to query threat")?;
+        Ok(result.take(0)?)
+    }
+
+    async fn threat_training_sample(
+        &self,
+        threat: &Value,
+        severity: &str,
+        label_source: LabelSource,
+    ) -> Result<(String, ThreatTrainingSample)> {
+        let id = threat
+            .get("id")
+            .and_then(Value::as_str)
+            .ok_or_else(|| anyhow!("Threat row without id"))?;
+        // A YARA match is certain; other detections carry their score
+        let anomaly_score = if threat.get("yara_rule").is_some_and(|r| !r.is_null()) {
+            100.0
+        } else if let Some(score) = threat.get("risk_score").and_then(Value::as_f64) {
+            score
+        } else {
+            threat
+                .get("heuristic_score")
+                .and_then(Value::as_f64)
+                .unwrap_or(0.0)
+                * 100.0
+        };
+        let sample = self
+            .training_sample_at(
+                row_timestamp(threat),
+                severity,
+                anomaly_score,
+                label_source,
+                format!("threat:{id}"),
+            )
+            .await?;
+        Ok((format!("threat_{id}"), sample))
+    }
+
+    /// Record an analyst's verdict on a threat as a training sample. This
+    /// replaces any label the threat had from its resolution.
+    pub async fn label_threat(
+        &self,
+        threat_id: &str,
+        severity: ThreatSeverity,
+    ) -> Result<ThreatTrainingSample> {
+        let id = threat_id.strip_prefix("threat:").unwrap_or(threat_id);
+        let threat = self
+            .threat_for_training(id)
+            .await?
+            .ok_or_else(|| anyhow!("Threat {id} not found"))?;
+        let (key, sample) = self
+            .threat_training_sample(&threat, severity.as_str(), LabelSource::Analyst)
+            .await?;
+        self.store_training_sample(key, &sample).await?;
+        info!("Threat {id} labelled {} by analyst", severity.as_str());
+        Ok(sample)
+    }
+
+    /// Turn resolved threats and consensus runs at or above
+    /// `consensus_min_confidence` into training samples. Events that
+    /// already have a sample are skipped; returns how many were added.
+    pub async fn collect_threat_training_samples(
+        &self,
+        consensus_min_confidence: f64,
+    ) -> Result<usize> {
+        let (known, threats, runs) = {
+            let db = self.db.read().await;
+            let mut result = db
+                .query(
+                    r#"
+                    SELECT VALUE source_id FROM threat_training WHERE source_id != NONE;
+                    SELECT meta::id(id) AS id, severity, yara_rule, heuristic_score,
+                           <string> timestamp AS timestamp, mitigation_status
+                    FROM threat
+                    WHERE mitigation_status INSIDE ['quarantined', 'deleted', 'whitelisted'];
+                    SELECT meta::id(id) AS id, <string> timestamp AS timestamp,
+                           risk_score, confidence
+                    FROM consensus_run
+                    WHERE confidence >= $min_confidence;
+                    "#,
+                )
+                .bind(("min_confidence", consensus_min_confidence))
+                .await
+                .context("Failed to query labelled events")?;
+            let known: Vec<String> = result.take(0)?;
+            let threats: Vec<Value> = result.take(1)?;
+            let runs: Vec<Value> = result.take(2)?;
+            (
+                known.into_iter().collect::<std::collections::HashSet<_>>(),
+                threats,
+                runs,
+            )
+        };
+
+        let mut added = 0;
+        for threat in &threats {
+            let id = threat.get("id").and_then(Value::as_str).unwrap_or_default();
+            if known.contains(&format!("threat:{id}")) {
+                continue;
+            }
+            let status = threat
+                .get("mitigation_status")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            let severity = threat
+                .get("severity")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            let Some(label) = threat_training::resolution_label(status, severity) else {
+                continue;
+            };
+            let (key, sample) = self
+                .threat_training_sample(threat, label, LabelSource::Resolution)
+                .await?;
+            self.store_training_sample(key, &sample).await?;
+            added += 1;
+        }
+
+        for run in &runs {
+            let Some(id) = run.get("id").and_then(Value::as_str) else {
+                continue;
+            };
+            let source_id = format!("consensus_run:{id}");
+            if known.contains(&source_id) {
+                continue;
+            }
+            let risk_score = run.get("risk_score").and_then(Value::as_f64).unwrap_or(0.0);
+            let sample = self
+                .training_sample_at(
+                    row_timestamp(run),
+                    threat_training::severity_for_score(risk_score),
+                    risk_score,
+                    LabelSource::Consensus,
+                    source_id,
+                )
+                .await?;
+            self.store_training_sample(format!("consensus_{id}"), &sample)
+                .await?;
+            added += 1;
+        }
+
+        info!(
+            "Collected {added} threat training samples ({} resolved threats, {} confident consensus runs seen)",
+            threats.len(),
+            runs.len()
+        );
+        Ok(added)
+    }
+
+    /// Every training sample, in a stable order.
+    pub async fn threat_training_samples(&self) -> Result<Vec<ThreatTrainingSample>> {
+        let db = self.db.read().await;
+        let mut result = db
+            .query("SELECT * FROM threat_training ORDER BY id")
+            .await
+            .context("Failed to query threat training samples")?;
+        let samples: Vec<ThreatTrainingSample> = result
+            .take(0)
+            .context("Failed to extract threat training samples")?;
+        Ok(samples)
+    }
+
+    /// Re-define the threat model over the current samples, then score its
+    /// predictions on the holdout samples and store the evaluation.
+    ///
+    /// When SurrealML is unavailable the local fallback is evaluated
+    /// instead, which the evaluation's `predictor` records. The SurrealML
+    /// model is defined over the whole table, so its holdout scores are
+    /// optimistic.
+    pub async fn retrain_threat_model(&self, min_samples: usize) -> Result<ModelEvaluation> {
+        let samples = self.threat_training_samples().await?;
+        if samples.len() < min_samples {
+            anyhow::bail!(
+                "Only {} threat training samples; at least {min_samples} are needed to retrain",
+                samples.len()
+            );
+        }
+
+        {
+            let db = self.db.read().await;
+            if let Err(err) = Self::define_threat_model(&db, true).await {
+                warn!("SurrealML model retraining skipped: {:#}", err);
+            }
+        }
+
+        let mut pairs = Vec::new();
+        let mut predictor = "surrealml".to_string();
+        for (i, sample) in samples.iter().enumerate() {
+            if !threat_training::is_holdout(i) {
+                continue;
+            }
+            let features = json!({
+                "cpu_usage": sample.cpu_usage,
+                "memory_pressure": sample.memory_pressure,
+                "network_score": sample.network_score,
+                "anomaly_score": sample.anomaly_score,
+            });
+            let prediction = self.ml_predict_threat(features).await?;
+            // Fallback predictions name their provider; SurrealML rows do not
+            if let Some(provider) = prediction.get("provider").and_then(Value::as_str) {
+                predictor = provider.to_string();
+            }
+            if let Some(predicted) = threat_training::predicted_severity(&prediction) {
+                pairs.push((sample.severity.clone(), predicted));
+            }
+        }
+
+        let (accuracy, per_class) = threat_training::evaluate(&pairs);
+        let evaluation = ModelEvaluation {
+            evaluated_at: Utc::now(),
+            samples: samples.len(),
+            evaluated: pairs.len(),
+            accuracy,
+            per_class,
+            predictor,
+        };
+        let payload = serde_json::to_value(&evaluation)
+            .context("Failed to serialize threat model evaluation")?;
+        let db = self.db.read().await;
+        db.query("CREATE threat_model_eval CONTENT $payload")
+            .bind(("payload", payload))
+            .await
+            .and_then(|r| r.check())
+            .context("Failed to store threat model evaluation")?;
+        info!(
+            "Threat model retrained on {} samples: accuracy {:.2} over {} holdout samples ({})",
+            evaluation.samples, evaluation.accuracy, evaluation.evaluated, evaluation.predictor
+        );
+        Ok(evaluation)
+    }
+
+    /// Evaluation from the most recent retraining, if any.
+    pub async fn latest_threat_model_evaluation(&self) -> Result<Option<ModelEvaluation>> {
+        let db = self.db.read().await;
+        let mut result = db
+            .query("SELECT * FROM threat_model_eval ORDER BY evaluated_at DESC LIMIT 1")
+            .await
+            .context("Failed to query threat model evaluations")?;
+        let evaluation: Option<ModelEvaluation> = result
+            .take(0)
+            .context("Failed to extract threat model evaluation")?;
+        Ok(evaluation)
+    }
+}
+
+/// Malicious-file differences between an older (`base`) and newer
+/// (`target`) set of verdicts, matched by path.
+pub fn diff_scan_verdicts(base: &[ScanFileVerdict], target: &[ScanFileVerdict]) -> ScanDiff {
+    use std::collections::HashMap;
+
+    let base_by_path: HashMap<&str, &ScanFileVerdict> =
+        base.iter().map(|v| (v.path.as_str(), v)).collect();
+    let target_by_path: HashMap<&str, &ScanFileVerdict> =
+        target.iter().map(|v| (v.path.as_str(), v)).collect();
+
+    let mut diff = ScanDiff::default();
+    for after in target.iter().filter(|v| v.malicious) {
+        match base_by_path.get(after.path.as_str()) {
+            Some(before) if before.malicious => {
+                if before.sha256 != after.sha256 || before.local_match != after.local_match {
+                    diff.changed_malicious.push(ScanFileChange {
+                        before: (*before).clone(),
+                        after: after.clone(),
+                    });
+                }
+            }
+            _ => diff.new_malicious.push(after.clone()),
+        }
+    }
+    diff.removed_malicious = base
+        .iter()
+        .filter(|before| before.malicious)
+        .filter(|before| {
+            !target_by_path
+                .get(before.path.as_str())
+                .is_some_and(|after| after.malicious)
+        })
+        .cloned()
+        .collect();
+    diff
+}
+
+/// `timestamp` of a row selected as a string, or now if it is missing.
+fn row_timestamp(row: &Value) -> DateTime<Utc> {
+    row.get("timestamp")
+        .and_then(Value::as_str)
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .map_or_else(Utc::now, |t| t.with_timezone(&Utc))
+}
+
+/// Bind the parameters referenced by the export filter's WHERE clause.
+fn bind_export_filter<'r>(
+    mut query: surrealdb::method::Query<'r, Any>,
+    filter: &MemoryExportFilter,
+) -> surrealdb::method::Query<'r, Any> {
+    if let Some(agent_type) = &filter.agent_type {
+        query = query.bind(("agent_type", agent_type.clone()));
+    }
+    if let Some(since) = filter.since {
+        query = query.bind(("since", since.to_rfc3339()));
+    }
+    if let Some(until) = filter.until {
+        query = query.bind(("until", until.to_rfc3339()));
+    }
+    query
+}
+
+// ============================================================================
+// MemoryBackend Trait Implementation
+// ============================================================================
+
+#[async_trait]
+impl MemoryBackend for SurrealBackend {
+    async fn add_texts(
+        &self,
+        items: Vec<(String, Vec<String>)>,
+        metadata: Value,
+    ) -> Result<(), String> {
+        debug!("Adding {} text items to agent memory", items.len());
+
+        for (source_tag, texts) in items {
+            let agent_type = infer_agent_type(&source_tag);
+            let memory_source = infer_memory_source(&source_tag);
+
+            for text in texts {
+                let chunks = chunk_text(&text, &self.chunking_config());
+                let chunk_count = chunks.len();
+                let parent_id = (chunk_count > 1).then(|| uuid::Uuid::new_v4().to_string());
+
+                for chunk in chunks {
+                    let embedding = self.embed_text(&chunk.text).await?;
+                    let mut metadata_value = build_metadata_with_source(&metadata, &source_tag);
+                    if let (Some(parent_id), Value::Object(map)) = (&parent_id, &mut metadata_value)
+                    {
+                        map.insert("parent_id".to_string(), json!(parent_id));
+                        map.insert("chunk_index".to_string(), json!(chunk.index));
+                        map.insert("chunk_count".to_string(), json!(chunk_count));
+                        map.insert("chunk_offset".to_string(), json!(chunk.offset));
+                    }
+
+                    // Ensure metadata is optional (avoid storing explicit null)
+                    let memory_metadata = match &metadata_value {
+                        Value::Null => None,
+                        Value::Object(_) => Some(metadata_value),
+                        _ => {
+                            // build_metadata_with_source ensures object unless metadata itself is null,
+                            // but handle other cases defensively.
+                            metadata_value = serde_json::json!({
+                                "legacy_metadata": metadata,
+                                "source_tag": source_tag,
+                            });
+                            Some(metadata_value)
+                        }
+                    };
+
+                    let memory = AgentMemory {
+                        agent_type: agent_type.clone(),
+                        content: chunk.text,
+                        embedding,
+                        timestamp: Utc::now(),
+                        source: memory_source.clone(),
+                        metadata: memory_metadata,
+                    };
+
+                    self.insert_agent_memory(memory)
+                        .await
+                        .map_err(|e| format!("Failed to insert agent memory: {e}"))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn search(&self, query: String, top_k: usize) -> Result<Vec<BackendSearchItem>, String> {
+        debug!("Searching for: '{}' (top_k={})", query, top_k);
+
+        let query_embedding = self.embed_text(&query).await?;
+
+        self.vector_search(query_embedding, "guardian", top_k)
+            .await
+            .map_err(|e| format!("Vector search failed: {e}"))
+    }
+}
 
 // ============================================================================
 // Tests
@@ -1520,6 +4599,7 @@ mod tests {
         let metric = SystemMetric {
             timestamp: Utc::now(),
             cpu_usage: 75.5,
+            core_usage: vec![100.0, 80.0, 62.0, 60.0],
             memory_usage: MemoryUsage {
                 total_mb: 16384.0,
                 used_mb: 8192.0,
@@ -1536,6 +4616,15 @@ mod tests {
                 recv_mb_per_sec: 2.0,
                 connections_active: 10,
             },
+            gpus: vec![GpuMetrics {
+                name: "Test GPU".to_string(),
+                utilization_percent: Some(40.0),
+                vram_used_mb: Some(1024.0),
+                vram_total_mb: Some(8192.0),
+                temperature_c: None,
+            }],
+            cpu_temperature_c: Some(55.0),
+            battery: None,
             metadata: None,
         };
 
@@ -1552,6 +4641,768 @@ mod tests {
 
         assert_eq!(metrics.len(), 1);
         assert!((metrics[0].cpu_usage - 75.5).abs() < 0.01);
+        assert_eq!(metrics[0].core_usage.len(), 4);
+        assert_eq!(metrics[0].gpus.len(), 1);
+        assert_eq!(metrics[0].gpus[0].utilization_percent, Some(40.0));
+        assert_eq!(metrics[0].cpu_temperature_c, Some(55.0));
+        assert!(metrics[0].battery.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_process_trend() {
+        let temp_dir = TempDir::new().unwrap();
+        let backend = SurrealBackend::new(temp_dir.path().join("test.db"))
+            .await
+            .unwrap();
+
+        // A leaking process gains 100 MB per hour; another name is ignored
+        let now = Utc::now();
+        let samples = (0..4)
+            .flat_map(|i| {
+                let timestamp = now - chrono::Duration::minutes(90 - 30 * i);
+                [
+                    ProcessSample {
+                        timestamp,
+                        name: "leaky".to_string(),
+                        instances: 2,
+                        cpu_percent: 10.0,
+                        memory_mb: 500.0 + 50.0 * i as f64,
+                    },
+                    ProcessSample {
+                        timestamp,
+                        name: "steady".to_string(),
+                        instances: 1,
+                        cpu_percent: 1.0,
+                        memory_mb: 64.0,
+                    },
+                ]
+            })
+            .collect();
+        backend.insert_process_samples(samples).await.unwrap();
+
+        let trend = backend.query_process_trend("leaky", 24).await.unwrap();
+        assert_eq!(trend.samples.len(), 4);
+        assert!(trend.samples[0].timestamp < trend.samples[3].timestamp);
+        assert!((trend.memory_mb_per_hour.unwrap() - 100.0).abs() < 0.01);
+        assert!(trend.cpu_percent_per_hour.unwrap().abs() < 0.01);
+
+        let recent = backend.query_process_trend("leaky", 1).await.unwrap();
+        assert_eq!(recent.samples.len(), 2);
+        assert!(backend
+            .query_process_trend("missing", 24)
+            .await
+            .unwrap()
+            .memory_mb_per_hour
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_backup_restore_and_compact() {
+        let temp_dir = TempDir::new().unwrap();
+        let backend = SurrealBackend::new(temp_dir.path().join("test.db"))
+            .await
+            .unwrap();
+        let now = Utc::now();
+        let metrics = (0..10)
+            .map(|i| crate::benchmark::sample_metric(i, now))
+            .collect();
+        backend.insert_system_metrics_batch(metrics).await.unwrap();
+
+        let backup_path = temp_dir.path().join("backups").join("oxide.surql");
+        let stages = std::sync::Mutex::new(Vec::new());
+        let info = backend
+            .backup_database(&backup_path, |p| stages.lock().unwrap().push(p.percent))
+            .await
+            .unwrap();
+        assert!(info.size_bytes > 0);
+        assert_eq!(stages.lock().unwrap().last(), Some(&100));
+
+        // Data written after the backup disappears on restore
+        backend
+            .insert_system_metric(crate::benchmark::sample_metric(0, now))
+            .await
+            .unwrap();
+        backend
+            .restore_database(&backup_path, |_| {})
+            .await
+            .unwrap();
+        let window = (
+            now - chrono::Duration::hours(1),
+            now + chrono::Duration::minutes(1),
+        );
+        let restored = backend
+            .query_metrics_by_time(window.0, window.1)
+            .await
+            .unwrap();
+        assert_eq!(restored.len(), 10);
+
+        // A broken backup is rejected without losing data
+        let broken = temp_dir.path().join("broken.surql");
+        std::fs::write(&broken, "THIS IS NOT SURREALQL;").unwrap();
+        assert!(backend.restore_database(&broken, |_| {}).await.is_err());
+        assert_eq!(
+            backend
+                .query_metrics_by_time(window.0, window.1)
+                .await
+                .unwrap()
+                .len(),
+            10
+        );
+
+        // Sample metrics are 5 s apart, so nothing is older than a day
+        let report = backend.compact_database(Some(1), |_| {}).await.unwrap();
+        assert_eq!(report.removed_metrics, 0);
+        assert!(report
+            .rebuilt_indexes
+            .contains(&"system_metrics.idx_timestamp".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_rebuild_vector_index() {
+        let temp_dir = TempDir::new().unwrap();
+        let backend = SurrealBackend::new(temp_dir.path().join("test.db"))
+            .await
+            .unwrap();
+        let dim = backend.embedding_dimension();
+        let memories: Vec<AgentMemory> = (0..5)
+            .map(|i| crate::benchmark::sample_memory(i, dim))
+            .collect();
+        backend.insert_agent_memories_batch(memories).await.unwrap();
+
+        let report = backend.rebuild_vector_index(None, |_| {}).await.unwrap();
+        assert_eq!((report.dimension, report.previous_dimension), (dim, dim));
+        assert_eq!(report.memories, 5);
+        assert_eq!(report.re_embedded, 0);
+        if let Some(recall) = report.recall {
+            assert!((0.0..=1.0).contains(&recall));
+        }
+
+        assert!(backend.rebuild_vector_index(Some(0), |_| {}).await.is_err());
+        assert_eq!(backend.embedding_dimension(), dim);
+    }
+
+    #[tokio::test]
+    async fn test_agent_memory_export_import() {
+        let temp_dir = TempDir::new().unwrap();
+        let backend = SurrealBackend::new(temp_dir.path().join("test.db"))
+            .await
+            .unwrap();
+        let dim = backend.embedding_dimension();
+        let mut memories: Vec<AgentMemory> = (0..5)
+            .map(|i| crate::benchmark::sample_memory(i, dim))
+            .collect();
+        memories[0].agent_type = AgentType::Guardian;
+        backend.insert_agent_memories_batch(memories).await.unwrap();
+
+        let path = temp_dir.path().join("export").join("memories.jsonl");
+        let filter = MemoryExportFilter {
+            agent_type: Some(AgentType::Copilot),
+            limit: Some(3),
+            ..Default::default()
+        };
+        let exported = backend.export_agent_memory(&filter, &path).await.unwrap();
+        assert_eq!(exported.records, 3);
+
+        // Append a malformed line and a record from an install with another dimension
+        let mut foreign = crate::benchmark::sample_memory(9, dim + 1);
+        foreign.content = "foreign".to_string();
+        let mut contents = std::fs::read_to_string(&path).unwrap();
+        contents.push_str("{not json}\n");
+        contents.push_str(&serde_json::to_string(&foreign).unwrap());
+        contents.push('\n');
+        std::fs::write(&path, contents).unwrap();
+
+        let other = SurrealBackend::new(temp_dir.path().join("other.db"))
+            .await
+            .unwrap();
+        let imported = other
+            .import_agent_memory(&path, ReEmbedPolicy::Never)
+            .await
+            .unwrap();
+        assert_eq!(imported.records, 3);
+        assert_eq!(imported.skipped, 2);
+        assert_eq!(imported.errors.len(), 2);
+
+        let roundtrip = temp_dir.path().join("roundtrip.jsonl");
+        let all = other
+            .export_agent_memory(&MemoryExportFilter::default(), &roundtrip)
+            .await
+            .unwrap();
+        assert_eq!(all.records, 3);
+    }
+
+    #[test]
+    fn test_mitigation_status_transitions() {
+        use MitigationStatus::*;
+        assert!(Detected.can_transition_to(Investigating));
+        assert!(Detected.can_transition_to(Quarantined));
+        assert!(Investigating.can_transition_to(Whitelisted));
+        assert!(!Investigating.can_transition_to(Detected));
+        assert!(Whitelisted.can_transition_to(Investigating));
+        assert!(!Deleted.can_transition_to(Investigating));
+        assert!(!Detected.can_transition_to(Detected));
+    }
+
+    #[tokio::test]
+    async fn test_threat_status_workflow() {
+        let temp_dir = TempDir::new().unwrap();
+        let backend = SurrealBackend::new(temp_dir.path().join("test.db"))
+            .await
+            .unwrap();
+        let threat = || ThreatInfo {
+            severity: ThreatSeverity::High,
+            yara_rule: Some("Suspicious_Packer".to_string()),
+            heuristic_score: None,
+            risk_score: None,
+            timestamp: Utc::now(),
+            process_chain: Vec::new(),
+            indicators: vec!["packed binary".to_string()],
+            mitigation_status: MitigationStatus::Detected,
+            techniques: vec!["T1027.002".to_string()],
+        };
+        let first = backend.insert_threat(threat()).await.unwrap();
+        let second = backend.insert_threat(threat()).await.unwrap();
+
+        backend
+            .update_threat_status(
+                &format!("threat:{first}"),
+                MitigationStatus::Investigating,
+                None,
+            )
+            .await
+            .unwrap();
+        // Only the still-detected threat is acknowledged
+        let acknowledged = backend
+            .bulk_acknowledge_threats(&[first.clone(), second.clone()])
+            .await
+            .unwrap();
+        assert_eq!(acknowledged, 1);
+
+        backend
+            .update_threat_status(
+                &first,
+                MitigationStatus::Quarantined,
+                Some("sample sent to lab".to_string()),
+            )
+            .await
+            .unwrap();
+        assert!(backend
+            .update_threat_status(&first, MitigationStatus::Detected, None)
+            .await
+            .is_err());
+        assert!(backend
+            .update_threat_status("missing", MitigationStatus::Deleted, None)
+            .await
+            .is_err());
+
+        let quarantined = backend
+            .query_threats(Some(MitigationStatus::Quarantined), 10)
+            .await
+            .unwrap();
+        assert_eq!(quarantined.len(), 1);
+        assert_eq!(quarantined[0].id, first);
+        assert_eq!(
+            quarantined[0].status_note.as_deref(),
+            Some("sample sent to lab")
+        );
+
+        let detections = backend
+            .technique_detections(Utc::now() - chrono::Duration::hours(1))
+            .await
+            .unwrap();
+        assert_eq!(detections, vec![vec!["T1027.002".to_string()]; 2]);
+    }
+
+    #[tokio::test]
+    async fn test_threat_training_pipeline() {
+        let temp_dir = TempDir::new().unwrap();
+        let backend = SurrealBackend::new(temp_dir.path().join("test.db"))
+            .await
+            .unwrap();
+        let now = Utc::now();
+        backend
+            .insert_system_metric(crate::benchmark::sample_metric(1, now))
+            .await
+            .unwrap();
+        let threat = |status| ThreatInfo {
+            severity: ThreatSeverity::High,
+            yara_rule: None,
+            heuristic_score: Some(0.7),
+            risk_score: Some(70.0),
+            timestamp: now,
+            process_chain: Vec::new(),
+            indicators: Vec::new(),
+            mitigation_status: status,
+            techniques: Vec::new(),
+        };
+        let quarantined = backend
+            .insert_threat(threat(MitigationStatus::Quarantined))
+            .await
+            .unwrap();
+        backend
+            .insert_threat(threat(MitigationStatus::Whitelisted))
+            .await
+            .unwrap();
+        let open = backend
+            .insert_threat(threat(MitigationStatus::Detected))
+            .await
+            .unwrap();
+        for confidence in [0.9, 0.2] {
+            backend
+                .insert_consensus_run(ConsensusRunRecord {
+                    risk_score: 85.0,
+                    confidence,
+                    providers: vec!["local".to_string()],
+                    input: json!({}),
+                    provider_reports: Vec::new(),
+                    report: json!({}),
+                })
+                .await
+                .unwrap();
+        }
+
+        // Two resolved threats and the confident run; the open threat waits
+        assert_eq!(
+            backend.collect_threat_training_samples(0.7).await.unwrap(),
+            3
+        );
+        assert_eq!(
+            backend.collect_threat_training_samples(0.7).await.unwrap(),
+            0
+        );
+
+        let labelled = backend
+            .label_threat(&format!("threat:{open}"), ThreatSeverity::Critical)
+            .await
+            .unwrap();
+        assert_eq!(labelled.label_source, Some(LabelSource::Analyst));
+        assert_eq!(labelled.anomaly_score, 70.0);
+        assert_eq!(labelled.network_score, 10.0);
+        backend
+            .label_threat(&quarantined, ThreatSeverity::Medium)
+            .await
+            .unwrap();
+        assert!(backend
+            .label_threat("missing", ThreatSeverity::Low)
+            .await
+            .is_err());
+
+        let samples = backend.threat_training_samples().await.unwrap();
+        assert_eq!(samples.len(), 4);
+        let severities: Vec<&str> = samples.iter().map(|s| s.severity.as_str()).collect();
+        assert!(severities.contains(&"critical") && severities.contains(&"medium"));
+        assert!(!severities.contains(&"high"));
+
+        assert!(backend.retrain_threat_model(10).await.is_err());
+        let evaluation = backend.retrain_threat_model(4).await.unwrap();
+        assert_eq!(evaluation.samples, 4);
+        let latest = backend
+            .latest_threat_model_evaluation()
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(latest.samples, 4);
+        assert_eq!(latest.predictor, evaluation.predictor);
+    }
+
+    fn verdict(path: &str, sha256: &str, malicious: bool) -> ScanFileVerdict {
+        ScanFileVerdict {
+            path: path.to_string(),
+            sha256: Some(sha256.to_string()),
+            malicious,
+            local_match: malicious.then(|| "EICAR".to_string()),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_diff_scan_verdicts() {
+        let base = vec![
+            verdict("a.exe", "1", true),
+            verdict("b.exe", "2", true),
+            verdict("c.exe", "3", true),
+            verdict("d.txt", "4", false),
+        ];
+        let target = vec![
+            verdict("a.exe", "1", true),
+            verdict("b.exe", "2b", true),
+            verdict("d.txt", "4b", true),
+            verdict("e.dll", "5", true),
+        ];
+        let diff = diff_scan_verdicts(&base, &target);
+        let paths = |v: &[ScanFileVerdict]| v.iter().map(|f| f.path.clone()).collect::<Vec<_>>();
+        assert_eq!(paths(&diff.new_malicious), vec!["d.txt", "e.dll"]);
+        assert_eq!(paths(&diff.removed_malicious), vec!["c.exe"]);
+        assert_eq!(diff.changed_malicious.len(), 1);
+        assert_eq!(diff.changed_malicious[0].after.path, "b.exe");
+    }
+
+    #[tokio::test]
+    async fn test_scan_history_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let backend = SurrealBackend::new(temp_dir.path().join("test.db"))
+            .await
+            .unwrap();
+        let summary = |id: &str, malicious: u64| ScanRunSummary {
+            id: id.to_string(),
+            root: "/home/user/Downloads".to_string(),
+            started_at: Utc::now(),
+            finished_at: Utc::now(),
+            status: "completed".to_string(),
+            total: 2,
+            scanned: 2,
+            malicious,
+            errors: 0,
+            duration_ms: 12,
+        };
+        backend
+            .insert_scan_run(
+                &summary("first", 1),
+                vec![verdict("a.exe", "1", true), verdict("b.txt", "2", false)],
+            )
+            .await
+            .unwrap();
+        backend
+            .insert_scan_run(
+                &summary("second", 1),
+                vec![verdict("a.exe", "1", false), verdict("b.txt", "2", true)],
+            )
+            .await
+            .unwrap();
+
+        let history = backend
+            .list_scan_history(Some("/home/user/Downloads".to_string()), 10)
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].id, "second");
+
+        let details = backend.get_scan_details("first").await.unwrap().unwrap();
+        assert_eq!(details.files.len(), 2);
+        assert!(backend.get_scan_details("missing").await.unwrap().is_none());
+
+        let diff = backend.diff_scans("first", "second").await.unwrap();
+        assert_eq!(diff.new_malicious.len(), 1);
+        assert_eq!(diff.removed_malicious.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_scan_cache_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let backend = SurrealBackend::new(temp_dir.path().join("test.db"))
+            .await
+            .unwrap();
+        let entry = |path: &str, verified_at: DateTime<Utc>| ScanCacheEntry {
+            path: path.to_string(),
+            size: 42,
+            mtime_ms: 1_700_000_000_000,
+            sha256: "ab".repeat(32),
+            cloud_verified: false,
+            detection_version: "v1".to_string(),
+            verified_at,
+        };
+        let now = Utc::now();
+        let old = now - chrono::Duration::days(3);
+        backend
+            .update_scan_cache(
+                vec![entry("C:\\a.exe", now), entry("/tmp/b.bin", old)],
+                vec![],
+            )
+            .await
+            .unwrap();
+
+        let paths = vec!["C:\\a.exe".to_string(), "/tmp/b.bin".to_string()];
+        let fresh = backend
+            .scan_cache_lookup(&paths, now - chrono::Duration::days(1))
+            .await
+            .unwrap();
+        assert_eq!(fresh.len(), 1);
+        assert!(fresh[0].matches(42, 1_700_000_000_000, false, "v1"));
+        assert!(!fresh[0].matches(42, 1_700_000_000_000, true, "v1"));
+        assert!(!fresh[0].matches(43, 1_700_000_000_000, false, "v1"));
+        assert!(!fresh[0].matches(42, 1_700_000_000_000, false, "v2"));
+        assert!(fresh[0].content_matches(&"AB".repeat(32)));
+        assert!(!fresh[0].content_matches(&"cd".repeat(32)));
+
+        // New rules drop every entry checked against the old ones
+        backend
+            .prune_scan_cache(old - chrono::Duration::days(1), "v2")
+            .await
+            .unwrap();
+        assert!(backend
+            .scan_cache_lookup(&paths, old)
+            .await
+            .unwrap()
+            .is_empty());
+        backend
+            .update_scan_cache(
+                vec![entry("C:\\a.exe", now), entry("/tmp/b.bin", old)],
+                vec![],
+            )
+            .await
+            .unwrap();
+
+        backend
+            .update_scan_cache(vec![], vec!["C:\\a.exe".to_string()])
+            .await
+            .unwrap();
+        backend
+            .prune_scan_cache(now - chrono::Duration::days(1), "v1")
+            .await
+            .unwrap();
+        let all = backend.scan_cache_lookup(&paths, old).await.unwrap();
+        assert!(all.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_llm_audit_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let backend = SurrealBackend::new(temp_dir.path().join("test.db"))
+            .await
+            .unwrap();
+        let entry = |provider: &str, purpose: &str, timestamp: DateTime<Utc>| LlmAuditEntry {
+            id: None,
+            timestamp,
+            provider: provider.to_string(),
+            model: Some("model-x".to_string()),
+            purpose: purpose.to_string(),
+            prompt: "user: hello".to_string(),
+            response: Some("hi".to_string()),
+            error: None,
+            duration_ms: 120,
+        };
+        let now = Utc::now();
+        let old = now - chrono::Duration::days(100);
+        for e in [
+            entry("qwen", "threat_consensus", now),
+            entry("gemini", "chat", now),
+            entry("qwen", "chat", old),
+        ] {
+            backend.insert_llm_audit(&e).await.unwrap();
+        }
+
+        let qwen = backend
+            .query_llm_audit(&LlmAuditQuery {
+                provider: Some("qwen".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(qwen.len(), 2);
+        assert_eq!(qwen[0].purpose, "threat_consensus");
+        assert!(qwen[0].id.is_some());
+
+        let removed = backend
+            .prune_llm_audit(now - chrono::Duration::days(90))
+            .await
+            .unwrap();
+        assert_eq!(removed, 1);
+        let all = backend
+            .query_llm_audit(&LlmAuditQuery::default())
+            .await
+            .unwrap();
+        assert_eq!(all.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_batch_inserts() {
+        let temp_dir = TempDir::new().unwrap();
+        let backend = SurrealBackend::new(temp_dir.path().join("test.db"))
+            .await
+            .unwrap();
+        let now = Utc::now();
+
+        let metrics: Vec<SystemMetric> = (0..1200)
+            .map(|i| crate::benchmark::sample_metric(i, now))
+            .collect();
+        assert_eq!(
+            backend.insert_system_metrics_batch(metrics).await.unwrap(),
+            1200
+        );
+        let stored = backend
+            .query_metrics_by_time(now - chrono::Duration::hours(2), now)
+            .await
+            .unwrap();
+        assert_eq!(stored.len(), 1200);
+
+        let dim = backend.embedding_dimension();
+        let memories = (0..10)
+            .map(|i| crate::benchmark::sample_memory(i, dim))
+            .collect();
+        assert_eq!(
+            backend.insert_agent_memories_batch(memories).await.unwrap(),
+            10
+        );
+        let mut wrong = crate::benchmark::sample_memory(0, dim);
+        wrong.embedding.pop();
+        assert!(backend
+            .insert_agent_memories_batch(vec![wrong])
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_list_and_delete_agent_memories() {
+        let temp_dir = TempDir::new().unwrap();
+        let backend = SurrealBackend::new(temp_dir.path().join("test.db"))
+            .await
+            .unwrap();
+        let dim = backend.embedding_dimension();
+        let now = Utc::now();
+        let memories = (0..5)
+            .map(|i| AgentMemory {
+                agent_type: if i < 3 {
+                    AgentType::Guardian
+                } else {
+                    AgentType::Copilot
+                },
+                source: if i < 3 {
+                    MemorySource::ThreatReport
+                } else {
+                    MemorySource::UserQuery
+                },
+                timestamp: now - chrono::Duration::minutes(i as i64),
+                ..crate::benchmark::sample_memory(i, dim)
+            })
+            .collect();
+        backend.insert_agent_memories_batch(memories).await.unwrap();
+
+        let page = backend
+            .list_agent_memories(
+                Some(AgentType::Guardian),
+                None,
+                None,
+                MemorySort::NewestFirst,
+                1,
+                1,
+            )
+            .await
+            .unwrap();
+        assert_eq!(page.total, 3);
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].content, "benchmark memory 1");
+
+        let page = backend
+            .list_agent_memories(
+                None,
+                Some(MemorySource::UserQuery),
+                Some((now - chrono::Duration::seconds(210), now)),
+                MemorySort::OldestFirst,
+                0,
+                10,
+            )
+            .await
+            .unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.items[0].content, "benchmark memory 3");
+
+        assert!(backend
+            .delete_agent_memory(&page.items[0].id)
+            .await
+            .unwrap());
+        assert!(!backend
+            .delete_agent_memory(&page.items[0].id)
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_consolidation_replaces_originals() {
+        let temp_dir = TempDir::new().unwrap();
+        let backend = SurrealBackend::new(temp_dir.path().join("test.db"))
+            .await
+            .unwrap();
+        let dim = backend.embedding_dimension();
+        let old = Utc::now() - chrono::Duration::days(60);
+        let memories = (0..3)
+            .map(|i| AgentMemory {
+                timestamp: old,
+                ..crate::benchmark::sample_memory(i, dim)
+            })
+            .collect();
+        backend.insert_agent_memories_batch(memories).await.unwrap();
+
+        let cutoff = Utc::now() - chrono::Duration::days(30);
+        let candidates = backend.consolidation_candidates(cutoff, 10).await.unwrap();
+        assert_eq!(candidates.len(), 3);
+        let ids: Vec<String> = candidates[..2].iter().map(|c| c.id.clone()).collect();
+        backend
+            .store_consolidated_memory(
+                AgentMemory {
+                    content: "summary".to_string(),
+                    timestamp: old,
+                    ..crate::benchmark::sample_memory(9, dim)
+                },
+                &ids,
+                ConsolidationRetention::Archive,
+            )
+            .await
+            .unwrap();
+
+        // The summary is not a candidate again; one raw memory remains
+        let candidates = backend.consolidation_candidates(cutoff, 10).await.unwrap();
+        assert_eq!(candidates.len(), 1);
+        let page = backend
+            .list_agent_memories(None, None, None, MemorySort::NewestFirst, 0, 10)
+            .await
+            .unwrap();
+        assert_eq!(page.total, 2);
+        let db = backend.db.read().await;
+        let mut result = db
+            .query("SELECT count() AS n FROM agent_memory_archive GROUP ALL")
+            .await
+            .unwrap();
+        let archived: Option<Value> = result.take(0).unwrap();
+        assert_eq!(archived.unwrap()["n"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_core_imbalance_detects_single_core_hotspot() {
+        let temp_dir = TempDir::new().unwrap();
+        let backend = SurrealBackend::new(temp_dir.path().join("test.db"))
+            .await
+            .unwrap();
+
+        for i in 0..4 {
+            // Core 2 pegged while the average stays low
+            let core_usage = if i < 3 {
+                vec![5.0, 8.0, 99.0, 4.0]
+            } else {
+                vec![30.0, 25.0, 35.0, 28.0]
+            };
+            let cpu_usage = core_usage.iter().sum::<f64>() / core_usage.len() as f64;
+            let metric = SystemMetric {
+                timestamp: Utc::now() - chrono::Duration::seconds(i * 5),
+                cpu_usage,
+                core_usage,
+                memory_usage: MemoryUsage {
+                    total_mb: 16384.0,
+                    used_mb: 4096.0,
+                    available_mb: 12288.0,
+                    percent: 25.0,
+                },
+                disk_io: DiskIO {
+                    read_mb_per_sec: 0.0,
+                    write_mb_per_sec: 0.0,
+                    iops: 0,
+                },
+                network_stats: NetworkStats {
+                    sent_mb_per_sec: 0.0,
+                    recv_mb_per_sec: 0.0,
+                    connections_active: 0,
+                },
+                gpus: Vec::new(),
+                cpu_temperature_c: None,
+                battery: None,
+                metadata: None,
+            };
+            backend.insert_system_metric(metric).await.unwrap();
+        }
+
+        let hotspots = backend.query_core_imbalance(1).await.unwrap();
+        assert_eq!(hotspots.len(), 1);
+        assert_eq!(hotspots[0]["core"], 2);
+        assert_eq!(hotspots[0]["samples"], 3);
     }
 
     #[tokio::test]
@@ -0,0 +1,237 @@
+//! Connection factory for [`crate::SurrealBackend`].
+//!
+//! The backend runs embedded on RocksDB by default. Fleet deployments can
+//! instead point every agent at a central SurrealDB server (`ws://`,
+//! `wss://`, `http://`, `https://`) or at a TiKV cluster (`tikv://`) so
+//! metrics and memories are stored in one place.
+//!
+//! Protocols beyond embedded RocksDB and WebSocket need the matching crate
+//! feature: `surreal-http` for HTTP(S), `surreal-tikv` for TiKV.
+
+use anyhow::{anyhow, bail, Context, Result};
+use log::{info, warn};
+use oxide_core::network;
+use std::path::PathBuf;
+use surrealdb::engine::any::{self, Any};
+use surrealdb::opt::auth::{Database, Root};
+use surrealdb::Surreal;
+
+/// Where the backend's data lives.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SurrealConnection {
+    /// Embedded RocksDB at a local path
+    Embedded(PathBuf),
+    /// Remote SurrealDB server over WebSocket or HTTP
+    Remote {
+        endpoint: String,
+        credentials: Option<SurrealCredentials>,
+    },
+    /// Embedded engine storing data in a TiKV cluster; PD endpoints are
+    /// tried in order until one answers
+    TiKv { endpoints: Vec<String> },
+}
+
+/// Sign-in for remote servers. Passwords never come from the config file.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SurrealCredentials {
+    pub username: String,
+    pub password: String,
+    /// Sign in as a database user of the Oxide namespace/database instead of
+    /// a root user
+    pub database_user: bool,
+}
+
+impl std::fmt::Debug for SurrealCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SurrealCredentials")
+            .field("username", &self.username)
+            .field("password", &"<redacted>")
+            .field("database_user", &self.database_user)
+            .finish()
+    }
+}
+
+impl SurrealCredentials {
+    /// Read `OXIDE_SURREAL_USER` / `OXIDE_SURREAL_PASS`; set
+    /// `OXIDE_SURREAL_DB_USER=1` for database-level users.
+    pub fn from_env() -> Option<Self> {
+        let username = std::env::var("OXIDE_SURREAL_USER").ok()?;
+        let password = std::env::var("OXIDE_SURREAL_PASS").ok()?;
+        if username.trim().is_empty() {
+            return None;
+        }
+        let database_user = std::env::var("OXIDE_SURREAL_DB_USER")
+            .map(|v| matches!(v.trim(), "1" | "true" | "yes"))
+            .unwrap_or(false);
+        Some(Self {
+            username: username.trim().to_string(),
+            password,
+            database_user,
+        })
+    }
+}
+
+impl SurrealConnection {
+    /// Classify an endpoint string: `ws(s)://` and `http(s)://` are remote
+    /// servers, `tikv://` a TiKV cluster, and anything else (optionally
+    /// prefixed with `rocksdb://`) a local path.
+    pub fn parse(endpoint: &str, credentials: Option<SurrealCredentials>) -> Self {
+        let endpoint = endpoint.trim();
+        let lower = endpoint.to_ascii_lowercase();
+        if ["ws://", "wss://", "http://", "https://"]
+            .iter()
+            .any(|scheme| lower.starts_with(scheme))
+        {
+            SurrealConnection::Remote {
+                endpoint: endpoint.trim_end_matches('/').to_string(),
+                credentials,
+            }
+        } else if let Some(rest) = endpoint.strip_prefix("tikv://") {
+            SurrealConnection::TiKv {
+                endpoints: rest
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|e| !e.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            }
+        } else {
+            let path = endpoint.strip_prefix("rocksdb://").unwrap_or(endpoint);
+            SurrealConnection::Embedded(PathBuf::from(path))
+        }
+    }
+
+    /// `OXIDE_SURREAL_URL` when set, otherwise `OXIDE_DB_PATH`, otherwise
+    /// `default_path`.
+    pub fn from_env(default_path: &str) -> Self {
+        let endpoint = std::env::var("OXIDE_SURREAL_URL")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .or_else(|| std::env::var("OXIDE_DB_PATH").ok())
+            .unwrap_or_else(|| default_path.to_string());
+        Self::parse(&endpoint, SurrealCredentials::from_env())
+    }
+
+    /// Endpoint for logs; never includes credentials.
+    pub fn describe(&self) -> String {
+        match self {
+            SurrealConnection::Embedded(path) => format!("rocksdb://{}", path.display()),
+            SurrealConnection::Remote { endpoint, .. } => endpoint.clone(),
+            SurrealConnection::TiKv { endpoints } => format!("tikv://{}", endpoints.join(",")),
+        }
+    }
+
+    /// Open the connection and sign in when credentials are set. Namespace
+    /// and database selection is left to the caller.
+    pub(crate) async fn open(&self, namespace: &str, database: &str) -> Result<Surreal<Any>> {
+        match self {
+            SurrealConnection::Embedded(path) => {
+                if let Some(parent) = path.parent() {
+                    tokio::fs::create_dir_all(parent)
+                        .await
+                        .context("Failed to create database directory")?;
+                }
+                any::connect(format!("rocksdb://{}", path.display()))
+                    .await
+                    .context("Failed to initialize SurrealDB")
+            }
+            SurrealConnection::Remote {
+                endpoint,
+                credentials,
+            } => {
+                network::ensure_online(endpoint)?;
+                let db = any::connect(endpoint.as_str())
+                    .await
+                    .with_context(|| format!("Failed to connect to SurrealDB at {endpoint}"))?;
+                match credentials {
+                    Some(creds) if creds.database_user => {
+                        db.signin(Database {
+                            namespace,
+                            database,
+                            username: &creds.username,
+                            password: &creds.password,
+                        })
+                        .await
+                        .context("SurrealDB database sign-in failed")?;
+                    }
+                    Some(creds) => {
+                        db.signin(Root {
+                            username: &creds.username,
+                            password: &creds.password,
+                        })
+                        .await
+                        .context("SurrealDB root sign-in failed")?;
+                    }
+                    None => warn!("Connecting to {endpoint} without credentials"),
+                }
+                Ok(db)
+            }
+            SurrealConnection::TiKv { endpoints } => {
+                if endpoints.is_empty() {
+                    bail!("TiKV mode requires at least one PD endpoint");
+                }
+                let mut last_error = None;
+                for pd in endpoints {
+                    match any::connect(format!("tikv://{pd}")).await {
+                        Ok(db) => {
+                            info!("Connected to TiKV cluster via {pd}");
+                            return Ok(db);
+                        }
+                        Err(e) => {
+                            warn!("TiKV endpoint {pd} unavailable: {e}");
+                            last_error = Some(e);
+                        }
+                    }
+                }
+                Err(anyhow!(
+                    "No TiKV endpoint reachable: {}",
+                    last_error.map(|e| e.to_string()).unwrap_or_default()
+                ))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_endpoint_kinds() {
+        assert_eq!(
+            SurrealConnection::parse("./data/oxide.db", None),
+            SurrealConnection::Embedded(PathBuf::from("./data/oxide.db"))
+        );
+        assert_eq!(
+            SurrealConnection::parse("rocksdb:///var/lib/oxide", None),
+            SurrealConnection::Embedded(PathBuf::from("/var/lib/oxide"))
+        );
+        assert_eq!(
+            SurrealConnection::parse("wss://surreal.fleet.local/", None),
+            SurrealConnection::Remote {
+                endpoint: "wss://surreal.fleet.local".to_string(),
+                credentials: None,
+            }
+        );
+        assert_eq!(
+            SurrealConnection::parse("tikv://pd1:2379, pd2:2379", None),
+            SurrealConnection::TiKv {
+                endpoints: vec!["pd1:2379".to_string(), "pd2:2379".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn credentials_are_redacted() {
+        let conn = SurrealConnection::parse(
+            "http://10.0.0.5:8000",
+            Some(SurrealCredentials {
+                username: "oxide".to_string(),
+                password: "hunter2".to_string(),
+                database_user: false,
+            }),
+        );
+        assert!(!format!("{conn:?}").contains("hunter2"));
+        assert_eq!(conn.describe(), "http://10.0.0.5:8000");
+    }
+}
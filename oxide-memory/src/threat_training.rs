@@ -0,0 +1,190 @@
+//! Turning real detections into threat model training data.
+//!
+//! Resolved threats and confident consensus runs become
+//! [`ThreatTrainingSample`](crate::surreal_backend::ThreatTrainingSample)s:
+//! the label comes from the analyst, the mitigation outcome or the
+//! consensus verdict, and the features from the system metrics recorded
+//! around the event. After retraining, every fifth sample is held out and
+//! the model's predictions on those are scored here.
+
+use chrono::{DateTime, Utc};
+use oxide_core::severity::Severity;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Severity labels accepted by the `threat_training` table
+pub const SEVERITY_LABELS: [&str; 4] = ["low", "medium", "high", "critical"];
+
+/// Traffic in MB/s that counts as a full network score
+const NETWORK_FULL_MB_PER_SEC: f64 = 10.0;
+
+/// Open connections that count as a full network score
+const NETWORK_FULL_CONNECTIONS: f64 = 500.0;
+
+/// One in this many samples is held out for evaluation
+const HOLDOUT_EVERY: usize = 5;
+
+/// Where a training label came from, most trusted first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LabelSource {
+    /// Set explicitly through `label_threat`
+    Analyst,
+    /// Derived from how the threat was mitigated
+    Resolution,
+    /// Risk score of a confident consensus run
+    Consensus,
+}
+
+/// Severity label for a 0-100 risk score on the shared rubric.
+pub fn severity_for_score(score: f64) -> &'static str {
+    Severity::from_score(score).as_str()
+}
+
+/// Network activity on the 0-100 scale of the other features.
+pub fn network_score(sent_mb_per_sec: f64, recv_mb_per_sec: f64, connections: u64) -> f64 {
+    let traffic = (sent_mb_per_sec + recv_mb_per_sec) / NETWORK_FULL_MB_PER_SEC;
+    let conns = connections as f64 / NETWORK_FULL_CONNECTIONS;
+    (traffic.max(conns) * 100.0).clamp(0.0, 100.0)
+}
+
+/// Label implied by a threat's mitigation: quarantined or deleted threats
+/// keep their detected severity, whitelisted ones were false positives.
+/// Threats still open carry no label.
+pub fn resolution_label(mitigation_status: &str, severity: &str) -> Option<&'static str> {
+    match mitigation_status {
+        "quarantined" | "deleted" => SEVERITY_LABELS.into_iter().find(|s| *s == severity),
+        "whitelisted" => Some("low"),
+        _ => None,
+    }
+}
+
+/// Severity from a model prediction, which is either the heuristic
+/// `{ severity, .. }` object or a SurrealML `{ prediction }` row.
+pub fn predicted_severity(prediction: &Value) -> Option<String> {
+    let value = match prediction.get("prediction") {
+        Some(Value::String(s)) => Some(s.as_str()),
+        Some(inner) => inner.get("severity").and_then(Value::as_str),
+        None => prediction.get("severity").and_then(Value::as_str),
+    }?;
+    let value = value.trim().to_ascii_lowercase();
+    SEVERITY_LABELS.contains(&value.as_str()).then_some(value)
+}
+
+/// Local threat model that scores a feature vector without SurrealML.
+pub trait ThreatScorer: Send + Sync {
+    /// Prediction object with `provider`, `severity`, `score` (0-1) and
+    /// `confidence`, in the shape of the heuristic fallback.
+    fn predict(&self, features: &Value) -> Value;
+}
+
+/// Whether sample `index` belongs to the evaluation holdout.
+pub fn is_holdout(index: usize) -> bool {
+    index % HOLDOUT_EVERY == HOLDOUT_EVERY - 1
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClassMetrics {
+    pub precision: f64,
+    pub recall: f64,
+    /// Holdout samples with this label
+    pub support: usize,
+}
+
+/// Holdout scores of the threat model after a retraining run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelEvaluation {
+    pub evaluated_at: DateTime<Utc>,
+    /// Training samples available when the model was retrained
+    pub samples: usize,
+    /// Holdout samples the model returned a usable prediction for
+    pub evaluated: usize,
+    pub accuracy: f64,
+    pub per_class: BTreeMap<String, ClassMetrics>,
+    /// `surrealml`, or the fallback's provider when SurrealML was
+    /// unavailable
+    pub predictor: String,
+}
+
+/// Accuracy and per-class precision and recall over
+/// `(actual, predicted)` label pairs.
+pub fn evaluate(pairs: &[(String, String)]) -> (f64, BTreeMap<String, ClassMetrics>) {
+    let correct = pairs.iter().filter(|(a, p)| a == p).count();
+    let accuracy = if pairs.is_empty() {
+        0.0
+    } else {
+        correct as f64 / pairs.len() as f64
+    };
+    let ratio = |num: usize, den: usize| {
+        if den == 0 {
+            0.0
+        } else {
+            num as f64 / den as f64
+        }
+    };
+
+    let per_class = SEVERITY_LABELS
+        .iter()
+        .map(|label| {
+            let hits = pairs
+                .iter()
+                .filter(|(a, p)| a == label && p == label)
+                .count();
+            let predicted = pairs.iter().filter(|(_, p)| p == label).count();
+            let support = pairs.iter().filter(|(a, _)| a == label).count();
+            let metrics = ClassMetrics {
+                precision: ratio(hits, predicted),
+                recall: ratio(hits, support),
+                support,
+            };
+            (label.to_string(), metrics)
+        })
+        .collect();
+    (accuracy, per_class)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn labels_and_features() {
+        assert_eq!(severity_for_score(10.0), "low");
+        assert_eq!(severity_for_score(65.0), "high");
+        assert_eq!(severity_for_score(250.0), "critical");
+        assert_eq!(network_score(2.0, 3.0, 10), 50.0);
+        assert_eq!(network_score(0.0, 0.0, 1000), 100.0);
+
+        assert_eq!(resolution_label("quarantined", "high"), Some("high"));
+        assert_eq!(resolution_label("whitelisted", "critical"), Some("low"));
+        assert_eq!(resolution_label("investigating", "high"), None);
+
+        let heuristic = json!({ "provider": "heuristic", "severity": "medium" });
+        assert_eq!(predicted_severity(&heuristic).as_deref(), Some("medium"));
+        let surreal = json!({ "prediction": "Critical" });
+        assert_eq!(predicted_severity(&surreal).as_deref(), Some("critical"));
+        assert_eq!(predicted_severity(&json!({ "prediction": 3 })), None);
+    }
+
+    #[test]
+    fn scores_predictions_per_class() {
+        let pairs: Vec<(String, String)> = [
+            ("high", "high"),
+            ("high", "medium"),
+            ("low", "low"),
+            ("medium", "medium"),
+        ]
+        .iter()
+        .map(|(a, p)| (a.to_string(), p.to_string()))
+        .collect();
+        let (accuracy, per_class) = evaluate(&pairs);
+        assert_eq!(accuracy, 0.75);
+        assert_eq!(per_class["high"].recall, 0.5);
+        assert_eq!(per_class["high"].precision, 1.0);
+        assert_eq!(per_class["medium"].precision, 0.5);
+        assert_eq!(per_class["critical"].support, 0);
+        assert_eq!((0..10).filter(|i| is_holdout(*i)).count(), 2);
+    }
+}
@@ -0,0 +1,130 @@
+//! Clipboard text access for RPA actions.
+//!
+//! Uses the platform's clipboard tools (`wl-copy`/`xclip`, `pbcopy`,
+//! PowerShell) so no windowing toolkit has to be linked in.
+
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+
+/// Current clipboard text, or `None` if it holds no text or the clipboard
+/// tool is unavailable.
+pub fn read_text() -> Option<String> {
+    platform::read_text()
+}
+
+/// Replace the clipboard contents with `text`.
+pub fn write_text(text: &str) -> Result<(), String> {
+    platform::write_text(text)
+}
+
+fn run_read(program: &str, args: &[&str]) -> Option<String> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    let mut out = String::new();
+    child.stdout.take()?.read_to_string(&mut out).ok()?;
+    child.wait().ok()?.success().then_some(out)
+}
+
+fn run_write(program: &str, args: &[&str], text: &str) -> Result<(), String> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to start {program}: {e}"))?;
+    child
+        .stdin
+        .take()
+        .ok_or_else(|| format!("{program} has no stdin"))?
+        .write_all(text.as_bytes())
+        .map_err(|e| format!("Failed to write to {program}: {e}"))?;
+    let status = child
+        .wait()
+        .map_err(|e| format!("Failed to wait for {program}: {e}"))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("{program} exited with {status}"))
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{run_read, run_write};
+
+    fn wayland() -> bool {
+        std::env::var_os("WAYLAND_DISPLAY").is_some()
+    }
+
+    pub fn read_text() -> Option<String> {
+        if wayland() {
+            if let Some(text) = run_read("wl-paste", &["--no-newline", "--type", "text"]) {
+                return Some(text);
+            }
+        }
+        run_read("xclip", &["-selection", "clipboard", "-o"])
+    }
+
+    pub fn write_text(text: &str) -> Result<(), String> {
+        if wayland() && run_write("wl-copy", &[], text).is_ok() {
+            return Ok(());
+        }
+        run_write("xclip", &["-selection", "clipboard", "-i"], text)
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{run_read, run_write};
+
+    pub fn read_text() -> Option<String> {
+        run_read("pbpaste", &[])
+    }
+
+    pub fn write_text(text: &str) -> Result<(), String> {
+        run_write("pbcopy", &[], text)
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::{run_read, run_write};
+
+    pub fn read_text() -> Option<String> {
+        run_read(
+            "powershell",
+            &["-NoProfile", "-Command", "Get-Clipboard -Raw"],
+        )
+        .map(|text| text.strip_suffix("\r\n").unwrap_or(&text).to_string())
+    }
+
+    pub fn write_text(text: &str) -> Result<(), String> {
+        run_write(
+            "powershell",
+            &[
+                "-NoProfile",
+                "-Command",
+                "[Console]::InputEncoding = [Text.Encoding]::UTF8; \
+                 Set-Clipboard -Value ([Console]::In.ReadToEnd())",
+            ],
+            text,
+        )
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+mod platform {
+    pub fn read_text() -> Option<String> {
+        None
+    }
+
+    pub fn write_text(_text: &str) -> Result<(), String> {
+        Err("Clipboard access is not supported on this platform".to_string())
+    }
+}
@@ -0,0 +1,294 @@
+//! Time-boxed permission grants.
+//!
+//! A grant adds permissions on top of the [`PermissionPolicy`] for a limited
+//! time, optionally only while automation targets specific applications.
+//! Denials in the policy still win. Expired grants are dropped on the next
+//! check or sweep, and every removal is announced as a [`GrantEvent`].
+//!
+//! [`PermissionPolicy`]: crate::permissions::PermissionPolicy
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+
+use crate::permissions::{Permission, PermissionError, PermissionProfile};
+
+const EVENT_CAPACITY: usize = 64;
+
+/// Permissions granted until `expires_at`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PermissionGrant {
+    pub id: String,
+    /// Profile the permissions came from, if any
+    pub profile: Option<PermissionProfile>,
+    pub permissions: HashSet<Permission>,
+    /// Applications the grant is limited to; empty means any application
+    pub applications: Vec<String>,
+    pub granted_at: DateTime<Utc>,
+    /// `None` keeps the grant until it is revoked
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl PermissionGrant {
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|at| at <= now)
+    }
+
+    /// Whether the grant covers `permission` while `application` is the
+    /// automation target. Scoped grants never apply to an unknown target.
+    pub fn covers(&self, permission: Permission, application: Option<&str>) -> bool {
+        if !self.permissions.contains(&permission) {
+            return false;
+        }
+        if self.applications.is_empty() {
+            return true;
+        }
+        application.is_some_and(|app| {
+            self.applications
+                .iter()
+                .any(|scoped| scoped.eq_ignore_ascii_case(app))
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RevocationReason {
+    Expired,
+    Revoked,
+}
+
+/// Change to the active grants
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum GrantEvent {
+    Granted {
+        grant: PermissionGrant,
+    },
+    Revoked {
+        grant: PermissionGrant,
+        reason: RevocationReason,
+    },
+}
+
+/// Active grants shared by clones of the manager
+#[derive(Clone)]
+pub struct GrantManager {
+    grants: Arc<Mutex<Vec<PermissionGrant>>>,
+    events: broadcast::Sender<GrantEvent>,
+}
+
+impl Default for GrantManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GrantManager {
+    pub fn new() -> Self {
+        let (events, _) = broadcast::channel(EVENT_CAPACITY);
+        Self {
+            grants: Arc::new(Mutex::new(Vec::new())),
+            events,
+        }
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Vec<PermissionGrant>>, PermissionError> {
+        self.grants
+            .lock()
+            .map_err(|e| PermissionError::InvalidConfig(e.to_string()))
+    }
+
+    /// Receive grant and revocation events
+    pub fn subscribe(&self) -> broadcast::Receiver<GrantEvent> {
+        self.events.subscribe()
+    }
+
+    /// Whether both managers share the same grants
+    pub fn same_as(&self, other: &GrantManager) -> bool {
+        Arc::ptr_eq(&self.grants, &other.grants)
+    }
+
+    /// Grant `permissions` for `ttl`, limited to `applications` when not
+    /// empty.
+    pub fn grant(
+        &self,
+        permissions: HashSet<Permission>,
+        applications: Vec<String>,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<PermissionGrant, PermissionError> {
+        self.add(None, permissions, applications, ttl)
+    }
+
+    /// Grant every permission of `profile`.
+    pub fn grant_profile(
+        &self,
+        profile: PermissionProfile,
+        applications: Vec<String>,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<PermissionGrant, PermissionError> {
+        self.add(Some(profile), profile.permissions(), applications, ttl)
+    }
+
+    fn add(
+        &self,
+        profile: Option<PermissionProfile>,
+        permissions: HashSet<Permission>,
+        applications: Vec<String>,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<PermissionGrant, PermissionError> {
+        if permissions.is_empty() {
+            return Err(PermissionError::InvalidConfig(
+                "A grant needs at least one permission".to_string(),
+            ));
+        }
+        if ttl.is_some_and(|ttl| ttl <= chrono::Duration::zero()) {
+            return Err(PermissionError::InvalidConfig(
+                "Grant duration must be positive".to_string(),
+            ));
+        }
+        let applications: Vec<String> = applications
+            .into_iter()
+            .map(|app| app.trim().to_string())
+            .filter(|app| !app.is_empty())
+            .collect();
+
+        let now = Utc::now();
+        let grant = PermissionGrant {
+            id: uuid::Uuid::new_v4().to_string(),
+            profile,
+            permissions,
+            applications,
+            granted_at: now,
+            expires_at: ttl.map(|ttl| now + ttl),
+        };
+        self.lock()?.push(grant.clone());
+        let _ = self.events.send(GrantEvent::Granted {
+            grant: grant.clone(),
+        });
+        Ok(grant)
+    }
+
+    /// Revoke a grant before it expires.
+    pub fn revoke(&self, grant_id: &str) -> Result<PermissionGrant, PermissionError> {
+        let grant = {
+            let mut grants = self.lock()?;
+            let index = grants
+                .iter()
+                .position(|g| g.id == grant_id)
+                .ok_or_else(|| PermissionError::NotPermitted(format!("No grant {grant_id}")))?;
+            grants.remove(index)
+        };
+        let _ = self.events.send(GrantEvent::Revoked {
+            grant: grant.clone(),
+            reason: RevocationReason::Revoked,
+        });
+        Ok(grant)
+    }
+
+    /// Drop grants that expired by `now`, announcing each one.
+    pub fn expire(&self, now: DateTime<Utc>) -> Result<Vec<PermissionGrant>, PermissionError> {
+        let expired: Vec<PermissionGrant> = {
+            let mut grants = self.lock()?;
+            let (expired, active) = grants.drain(..).partition(|g| g.is_expired(now));
+            *grants = active;
+            expired
+        };
+        for grant in &expired {
+            let _ = self.events.send(GrantEvent::Revoked {
+                grant: grant.clone(),
+                reason: RevocationReason::Expired,
+            });
+        }
+        Ok(expired)
+    }
+
+    /// Grants that have not expired
+    pub fn active(&self) -> Result<Vec<PermissionGrant>, PermissionError> {
+        self.expire(Utc::now())?;
+        Ok(self.lock()?.clone())
+    }
+
+    /// Whether an active grant covers `permission` for `application`.
+    pub fn allows(
+        &self,
+        permission: Permission,
+        application: Option<&str>,
+    ) -> Result<bool, PermissionError> {
+        self.expire(Utc::now())?;
+        Ok(self
+            .lock()?
+            .iter()
+            .any(|g| g.covers(permission, application)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scoped_grant_and_expiry() {
+        let manager = GrantManager::new();
+        let mut events = manager.subscribe();
+
+        let grant = manager
+            .grant_profile(
+                PermissionProfile::InputOnly,
+                vec!["Notepad.exe".to_string()],
+                Some(chrono::Duration::minutes(5)),
+            )
+            .unwrap();
+        assert!(manager
+            .allows(Permission::KeyboardType, Some("notepad.exe"))
+            .unwrap());
+        assert!(!manager
+            .allows(Permission::KeyboardType, Some("cmd.exe"))
+            .unwrap());
+        assert!(!manager.allows(Permission::KeyboardType, None).unwrap());
+        assert!(!manager
+            .allows(Permission::ScreenCapture, Some("notepad.exe"))
+            .unwrap());
+
+        let expired = manager
+            .expire(Utc::now() + chrono::Duration::minutes(6))
+            .unwrap();
+        assert_eq!(expired.len(), 1);
+        assert!(manager.active().unwrap().is_empty());
+
+        assert!(matches!(events.try_recv(), Ok(GrantEvent::Granted { .. })));
+        match events.try_recv() {
+            Ok(GrantEvent::Revoked {
+                grant: revoked,
+                reason,
+            }) => {
+                assert_eq!(revoked.id, grant.id);
+                assert_eq!(reason, RevocationReason::Expired);
+            }
+            other => panic!("expected revocation, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_revoke_and_validation() {
+        let manager = GrantManager::new();
+        let grant = manager
+            .grant(HashSet::from([Permission::FileRead]), Vec::new(), None)
+            .unwrap();
+        assert!(manager.allows(Permission::FileRead, None).unwrap());
+        manager.revoke(&grant.id).unwrap();
+        assert!(!manager.allows(Permission::FileRead, None).unwrap());
+        assert!(manager.revoke(&grant.id).is_err());
+
+        assert!(manager.grant(HashSet::new(), Vec::new(), None).is_err());
+        assert!(manager
+            .grant(
+                HashSet::from([Permission::FileRead]),
+                Vec::new(),
+                Some(chrono::Duration::zero()),
+            )
+            .is_err());
+    }
+}
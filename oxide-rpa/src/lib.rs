@@ -1,6 +1,10 @@
 pub mod audit;
+pub mod clipboard;
 pub mod confirmation;
+pub mod grants;
+pub mod ocr;
 pub mod permissions;
 pub mod rollback;
 pub mod rpa;
 pub mod secure_rpa;
+pub mod window_manager;
@@ -0,0 +1,245 @@
+//! Text recognition on screen captures.
+//!
+//! Runs the Tesseract CLI on a PNG piped through stdin, so OCR works
+//! wherever `tesseract` is installed without linking it in. Used to give the
+//! Copilot the text on screen and to check the screen after an RPA action.
+
+use image::{imageops, GrayImage, ImageBuffer, ImageOutputFormat, Rgba};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Captures narrower than this are upscaled, which helps with UI-sized text
+const MIN_OCR_WIDTH: u32 = 1000;
+
+/// Words below this confidence (0-100) are dropped from the text
+const MIN_WORD_CONFIDENCE: f32 = 30.0;
+
+/// Area of the primary screen, in pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScreenRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// A recognized word with its box in capture coordinates
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OcrWord {
+    pub text: String,
+    /// 0-100
+    pub confidence: f32,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OcrResult {
+    /// Recognized lines joined with newlines
+    pub text: String,
+    pub words: Vec<OcrWord>,
+    /// Mean word confidence, 0-100
+    pub confidence: f32,
+}
+
+impl OcrResult {
+    /// Whether `expected` appears in the text, ignoring case and how the
+    /// words were split across spaces and lines.
+    pub fn contains(&self, expected: &str) -> bool {
+        let expected = normalize(expected);
+        !expected.is_empty() && normalize(&self.text).contains(&expected)
+    }
+}
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Tesseract invocation settings
+#[derive(Debug, Clone)]
+pub struct OcrEngine {
+    binary: String,
+    /// Tesseract language codes, e.g. `eng` or `eng+spa`
+    language: String,
+}
+
+impl Default for OcrEngine {
+    fn default() -> Self {
+        Self {
+            binary: "tesseract".to_string(),
+            language: "eng".to_string(),
+        }
+    }
+}
+
+impl OcrEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_language(mut self, language: impl Into<String>) -> Self {
+        self.language = language.into();
+        self
+    }
+
+    pub fn with_binary(mut self, binary: impl Into<String>) -> Self {
+        self.binary = binary.into();
+        self
+    }
+
+    /// Whether the Tesseract binary can be run
+    pub fn is_available(&self) -> bool {
+        Command::new(&self.binary)
+            .arg("--version")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    }
+
+    /// Recognize the text in `image`.
+    pub fn recognize(&self, image: &ImageBuffer<Rgba<u8>, Vec<u8>>) -> Result<OcrResult, String> {
+        let (prepared, scale) = prepare(image);
+        let mut png = Vec::new();
+        image::DynamicImage::ImageLuma8(prepared)
+            .write_to(&mut std::io::Cursor::new(&mut png), ImageOutputFormat::Png)
+            .map_err(|e| format!("Failed to encode image for OCR: {e}"))?;
+
+        let mut child = Command::new(&self.binary)
+            .args(["stdin", "stdout", "-l", &self.language, "tsv"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to start {}: {e}", self.binary))?;
+        child
+            .stdin
+            .take()
+            .ok_or("OCR process has no stdin")?
+            .write_all(&png)
+            .map_err(|e| format!("Failed to send image to OCR: {e}"))?;
+        let output = child
+            .wait_with_output()
+            .map_err(|e| format!("OCR process failed: {e}"))?;
+        if !output.status.success() {
+            return Err(format!(
+                "{} exited with {}: {}",
+                self.binary,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        Ok(parse_tsv(&String::from_utf8_lossy(&output.stdout), scale))
+    }
+}
+
+/// Grayscale and, for small captures, upscale. Returns the factor to divide
+/// word boxes by.
+fn prepare(image: &ImageBuffer<Rgba<u8>, Vec<u8>>) -> (GrayImage, u32) {
+    let gray = imageops::grayscale(image);
+    if gray.width() >= MIN_OCR_WIDTH || gray.width() == 0 {
+        return (gray, 1);
+    }
+    let scale = MIN_OCR_WIDTH.div_ceil(gray.width()).min(4);
+    let resized = imageops::resize(
+        &gray,
+        gray.width() * scale,
+        gray.height() * scale,
+        imageops::FilterType::CatmullRom,
+    );
+    (resized, scale)
+}
+
+/// Parse Tesseract's TSV output into words and line-joined text.
+fn parse_tsv(tsv: &str, scale: u32) -> OcrResult {
+    let scale = scale.max(1);
+    let mut words = Vec::new();
+    let mut lines: Vec<String> = Vec::new();
+    let mut current_line = None;
+
+    // level page block par line word left top width height conf text
+    for row in tsv.lines().skip(1) {
+        let cols: Vec<&str> = row.splitn(12, '\t').collect();
+        if cols.len() < 12 || cols[0] != "5" {
+            continue;
+        }
+        let text = cols[11].trim();
+        let confidence: f32 = cols[10].parse().unwrap_or(-1.0);
+        if text.is_empty() || confidence < MIN_WORD_CONFIDENCE {
+            continue;
+        }
+        let num = |i: usize| cols[i].parse::<u32>().unwrap_or(0) / scale;
+
+        let line_key = (cols[1], cols[2], cols[3], cols[4]);
+        if current_line != Some(line_key) {
+            current_line = Some(line_key);
+            lines.push(String::new());
+        }
+        if let Some(line) = lines.last_mut() {
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(text);
+        }
+
+        words.push(OcrWord {
+            text: text.to_string(),
+            confidence,
+            x: num(6),
+            y: num(7),
+            width: num(8),
+            height: num(9),
+        });
+    }
+
+    let confidence = if words.is_empty() {
+        0.0
+    } else {
+        words.iter().map(|w| w.confidence).sum::<f32>() / words.len() as f32
+    };
+    OcrResult {
+        text: lines.join("\n"),
+        words,
+        confidence,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TSV: &str = "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext
+1\t1\t0\t0\t0\t0\t0\t0\t800\t200\t-1\t
+4\t1\t1\t1\t1\t0\t20\t20\t300\t30\t-1\t
+5\t1\t1\t1\t1\t1\t20\t20\t120\t30\t96.5\tDocument
+5\t1\t1\t1\t1\t2\t150\t20\t90\t30\t93.1\tsaved.
+5\t1\t1\t1\t2\t1\t20\t80\t40\t30\t12.0\t~
+5\t1\t1\t1\t2\t2\t70\t80\t60\t30\t91.0\tClose
+";
+
+    #[test]
+    fn test_parse_tsv() {
+        let result = parse_tsv(TSV, 2);
+        assert_eq!(result.text, "Document saved.\nClose");
+        assert_eq!(result.words.len(), 3);
+        assert_eq!(result.words[1].x, 75);
+        assert!(result.contains("document   SAVED"));
+        assert!(!result.contains("Saved successfully"));
+        assert!(!result.contains("  "));
+    }
+
+    #[test]
+    fn test_prepare_upscales_small_captures() {
+        let image = ImageBuffer::from_pixel(300, 40, Rgba([255, 255, 255, 255]));
+        let (prepared, scale) = prepare(&image);
+        assert_eq!(scale, 4);
+        assert_eq!(prepared.dimensions(), (1200, 160));
+    }
+}
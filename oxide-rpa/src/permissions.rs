@@ -34,6 +34,13 @@ pub enum Permission {
     ScreenCaptureArea,
     ScreenAnalyze,
 
+    // Clipboard permissions
+    ClipboardWrite,
+
+    // Window permissions
+    WindowManage,
+    WindowClose,
+
     // File system permissions
     FileRead,
     FileWrite,
@@ -55,6 +62,29 @@ pub enum RiskLevel {
 }
 
 impl Permission {
+    /// Every permission, in declaration order
+    pub const ALL: [Permission; 19] = [
+        Permission::MouseMove,
+        Permission::MouseClick,
+        Permission::MouseScroll,
+        Permission::MouseDrag,
+        Permission::KeyboardType,
+        Permission::KeyboardPress,
+        Permission::KeyboardHotkey,
+        Permission::ScreenCapture,
+        Permission::ScreenCaptureArea,
+        Permission::ScreenAnalyze,
+        Permission::ClipboardWrite,
+        Permission::WindowManage,
+        Permission::WindowClose,
+        Permission::FileRead,
+        Permission::FileWrite,
+        Permission::FileDelete,
+        Permission::SystemCommand,
+        Permission::ProcessControl,
+        Permission::NetworkAccess,
+    ];
+
     /// Get the risk level associated with this permission
     pub fn risk_level(&self) -> RiskLevel {
         match self {
@@ -64,8 +94,11 @@ impl Permission {
             Permission::MouseClick
             | Permission::MouseScroll
             | Permission::KeyboardType
-            | Permission::ScreenAnalyze => RiskLevel::Medium,
+            | Permission::ScreenAnalyze
+            | Permission::ClipboardWrite
+            | Permission::WindowManage => RiskLevel::Medium,
             Permission::MouseDrag
+            | Permission::WindowClose
             | Permission::KeyboardPress
             | Permission::KeyboardHotkey
             | Permission::FileRead => RiskLevel::High,
@@ -83,6 +116,41 @@ impl Permission {
     }
 }
 
+/// Bundles of permissions granted together
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionProfile {
+    /// Mouse and keyboard input, no screen or file access
+    InputOnly,
+    /// Screen capture and analysis, no input
+    ScreenReadOnly,
+    /// Every permission
+    Full,
+}
+
+impl PermissionProfile {
+    /// Permissions included in this profile
+    pub fn permissions(&self) -> HashSet<Permission> {
+        match self {
+            PermissionProfile::InputOnly => HashSet::from([
+                Permission::MouseMove,
+                Permission::MouseClick,
+                Permission::MouseScroll,
+                Permission::MouseDrag,
+                Permission::KeyboardType,
+                Permission::KeyboardPress,
+                Permission::KeyboardHotkey,
+            ]),
+            PermissionProfile::ScreenReadOnly => HashSet::from([
+                Permission::ScreenCapture,
+                Permission::ScreenCaptureArea,
+                Permission::ScreenAnalyze,
+            ]),
+            PermissionProfile::Full => HashSet::from(Permission::ALL),
+        }
+    }
+}
+
 /// Permission policy configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PermissionPolicy {
@@ -125,6 +193,9 @@ impl PermissionPolicy {
                 Permission::ScreenCapture,
                 Permission::ScreenCaptureArea,
                 Permission::ScreenAnalyze,
+                Permission::ClipboardWrite,
+                Permission::WindowManage,
+                Permission::WindowClose,
                 Permission::FileRead,
             ]),
             denied: HashSet::new(),
@@ -220,6 +291,20 @@ mod tests {
         assert!(!policy.is_allowed(Permission::FileRead));
     }
 
+    #[test]
+    fn test_profiles() {
+        let input = PermissionProfile::InputOnly.permissions();
+        assert!(input.contains(&Permission::KeyboardType));
+        assert!(!input.contains(&Permission::ScreenCapture));
+        let screen = PermissionProfile::ScreenReadOnly.permissions();
+        assert!(screen.contains(&Permission::ScreenAnalyze));
+        assert!(!screen.contains(&Permission::MouseClick));
+        assert_eq!(
+            PermissionProfile::Full.permissions().len(),
+            Permission::ALL.len()
+        );
+    }
+
     #[test]
     fn test_confirmation_requirements() {
         let policy = PermissionPolicy::default();
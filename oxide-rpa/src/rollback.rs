@@ -11,6 +11,8 @@ pub enum RollbackError {
     Failed(String),
     #[error("Action not reversible: {0}")]
     NotReversible(String),
+    #[error("Checkpoint not found: {0}")]
+    CheckpointNotFound(String),
 }
 
 /// Represents an action that can be rolled back
@@ -50,6 +52,43 @@ pub enum ActionType {
     SystemCommand {
         command: String,
     },
+    /// Move or rename; `to` did not exist beforehand
+    FileMove {
+        from: String,
+        to: String,
+    },
+    /// Clipboard text replaced; `previous` is `None` if it held no text
+    ClipboardSet {
+        previous: Option<String>,
+    },
+    WindowState {
+        window_id: String,
+        before: WindowPlacement,
+        after: WindowPlacement,
+    },
+}
+
+/// Position, size and show state of a top-level window
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowPlacement {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub minimized: bool,
+    pub maximized: bool,
+}
+
+/// A named point in the action history that [`RollbackManager::take_since`]
+/// can return to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub id: String,
+    pub label: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Actions recorded before the checkpoint
+    #[serde(skip)]
+    position: u64,
 }
 
 impl ActionType {
@@ -62,6 +101,9 @@ impl ActionType {
             ActionType::FileWrite { .. } => true,
             ActionType::FileDelete { .. } => true,
             ActionType::SystemCommand { .. } => false, // Commands can't be undone
+            ActionType::FileMove { .. } => true,
+            ActionType::ClipboardSet { .. } => true,
+            ActionType::WindowState { .. } => true,
         }
     }
 
@@ -75,15 +117,43 @@ impl ActionType {
                 Some(format!("Restore previous content of {path}"))
             }
             ActionType::FileDelete { path, .. } => Some(format!("Restore deleted file {path}")),
+            ActionType::FileMove { from, to } => Some(format!("Move {to} back to {from}")),
+            ActionType::ClipboardSet { .. } => Some("Restore previous clipboard text".to_string()),
+            ActionType::WindowState { window_id, .. } => {
+                Some(format!("Restore previous placement of window {window_id}"))
+            }
             _ => None,
         }
     }
 }
 
+#[derive(Default)]
+struct History {
+    actions: VecDeque<ReversibleAction>,
+    /// Actions recorded and not rolled back, including evicted ones
+    recorded: u64,
+    checkpoints: Vec<Checkpoint>,
+}
+
+impl History {
+    /// Drop checkpoints whose position was rolled back past.
+    fn prune_checkpoints(&mut self) {
+        let recorded = self.recorded;
+        self.checkpoints.retain(|c| c.position <= recorded);
+    }
+
+    fn pop(&mut self) -> Option<ReversibleAction> {
+        let action = self.actions.pop_back()?;
+        self.recorded -= 1;
+        self.prune_checkpoints();
+        Some(action)
+    }
+}
+
 /// Manages rollback of RPA actions
 #[derive(Clone)]
 pub struct RollbackManager {
-    actions: Arc<Mutex<VecDeque<ReversibleAction>>>,
+    history: Arc<Mutex<History>>,
     max_history: usize,
 }
 
@@ -96,43 +166,53 @@ impl Default for RollbackManager {
 impl RollbackManager {
     pub fn new(max_history: usize) -> Self {
         Self {
-            actions: Arc::new(Mutex::new(VecDeque::with_capacity(max_history))),
+            history: Arc::new(Mutex::new(History {
+                actions: VecDeque::with_capacity(max_history),
+                ..Default::default()
+            })),
             max_history,
         }
     }
 
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, History>, RollbackError> {
+        self.history
+            .lock()
+            .map_err(|e| RollbackError::Failed(e.to_string()))
+    }
+
     /// Record an action for potential rollback
     pub fn record(&self, action: ReversibleAction) -> Result<(), RollbackError> {
-        let mut actions = self
-            .actions
-            .lock()
-            .map_err(|e| RollbackError::Failed(e.to_string()))?;
+        let mut history = self.lock()?;
 
-        if actions.len() >= self.max_history {
-            actions.pop_front();
+        if history.actions.len() >= self.max_history {
+            history.actions.pop_front();
         }
 
-        actions.push_back(action);
+        history.actions.push_back(action);
+        history.recorded += 1;
+        Ok(())
+    }
+
+    /// Put actions taken out by [`Self::take_since`] back, oldest first,
+    /// e.g. after reverting them failed part way.
+    pub fn restore(&self, actions: Vec<ReversibleAction>) -> Result<(), RollbackError> {
+        for action in actions {
+            self.record(action)?;
+        }
         Ok(())
     }
 
     /// Get the last action without removing it
     pub fn peek_last(&self) -> Result<Option<ReversibleAction>, RollbackError> {
-        let actions = self
-            .actions
-            .lock()
-            .map_err(|e| RollbackError::Failed(e.to_string()))?;
-        Ok(actions.back().cloned())
+        let history = self.lock()?;
+        Ok(history.actions.back().cloned())
     }
 
     /// Rollback the last action
     pub fn rollback_last(&self) -> Result<ReversibleAction, RollbackError> {
-        let mut actions = self
-            .actions
-            .lock()
-            .map_err(|e| RollbackError::Failed(e.to_string()))?;
+        let mut history = self.lock()?;
 
-        let action = actions.pop_back().ok_or(RollbackError::NoActions)?;
+        let action = history.pop().ok_or(RollbackError::NoActions)?;
 
         if !action.action_type.is_reversible() {
             return Err(RollbackError::NotReversible(format!(
@@ -163,22 +243,70 @@ impl RollbackManager {
         Ok(rolled_back)
     }
 
+    /// Mark the current point in the history.
+    pub fn checkpoint(&self, label: &str) -> Result<Checkpoint, RollbackError> {
+        let mut history = self.lock()?;
+        let checkpoint = Checkpoint {
+            id: uuid::Uuid::new_v4().to_string(),
+            label: label.to_string(),
+            created_at: chrono::Utc::now(),
+            position: history.recorded,
+        };
+        history.checkpoints.push(checkpoint.clone());
+        Ok(checkpoint)
+    }
+
+    /// Checkpoints that can still be rolled back to, oldest first
+    pub fn checkpoints(&self) -> Result<Vec<Checkpoint>, RollbackError> {
+        let history = self.lock()?;
+        let oldest = history.recorded - history.actions.len() as u64;
+        Ok(history
+            .checkpoints
+            .iter()
+            .filter(|c| c.position >= oldest)
+            .cloned()
+            .collect())
+    }
+
+    /// Remove and return every action recorded after `checkpoint_id`,
+    /// newest first, reversible or not. The checkpoint itself stays, so it
+    /// can be returned to again. Fails if actions after it were already
+    /// evicted from the history.
+    pub fn take_since(&self, checkpoint_id: &str) -> Result<Vec<ReversibleAction>, RollbackError> {
+        let mut history = self.lock()?;
+        let position = history
+            .checkpoints
+            .iter()
+            .find(|c| c.id == checkpoint_id)
+            .map(|c| c.position)
+            .ok_or_else(|| RollbackError::CheckpointNotFound(checkpoint_id.to_string()))?;
+        if position < history.recorded - history.actions.len() as u64 {
+            return Err(RollbackError::Failed(format!(
+                "Actions after checkpoint {checkpoint_id} are no longer in the history"
+            )));
+        }
+
+        let mut taken = Vec::new();
+        while history.recorded > position {
+            match history.pop() {
+                Some(action) => taken.push(action),
+                None => break,
+            }
+        }
+        Ok(taken)
+    }
+
     /// Get all recorded actions
     pub fn get_history(&self) -> Result<Vec<ReversibleAction>, RollbackError> {
-        let actions = self
-            .actions
-            .lock()
-            .map_err(|e| RollbackError::Failed(e.to_string()))?;
-        Ok(actions.iter().cloned().collect())
+        let history = self.lock()?;
+        Ok(history.actions.iter().cloned().collect())
     }
 
     /// Get reversible actions only
     pub fn get_reversible_history(&self) -> Result<Vec<ReversibleAction>, RollbackError> {
-        let actions = self
+        let history = self.lock()?;
+        Ok(history
             .actions
-            .lock()
-            .map_err(|e| RollbackError::Failed(e.to_string()))?;
-        Ok(actions
             .iter()
             .filter(|a| a.action_type.is_reversible())
             .cloned()
@@ -187,21 +315,16 @@ impl RollbackManager {
 
     /// Clear all history
     pub fn clear(&self) -> Result<(), RollbackError> {
-        let mut actions = self
-            .actions
-            .lock()
-            .map_err(|e| RollbackError::Failed(e.to_string()))?;
-        actions.clear();
+        let mut history = self.lock()?;
+        *history = History::default();
         Ok(())
     }
 
     /// Get count of reversible actions
     pub fn reversible_count(&self) -> Result<usize, RollbackError> {
-        let actions = self
+        let history = self.lock()?;
+        Ok(history
             .actions
-            .lock()
-            .map_err(|e| RollbackError::Failed(e.to_string()))?;
-        Ok(actions
             .iter()
             .filter(|a| a.action_type.is_reversible())
             .count())
@@ -321,6 +444,52 @@ mod tests {
         assert_eq!(history.len(), 3);
     }
 
+    #[test]
+    fn test_take_since_checkpoint() {
+        let manager = RollbackManager::new(4);
+        let mv = |i| {
+            create_test_action(ActionType::FileMove {
+                from: format!("a{i}"),
+                to: format!("b{i}"),
+            })
+        };
+
+        manager.record(mv(0)).unwrap();
+        let checkpoint = manager.checkpoint("before batch").unwrap();
+        manager.record(mv(1)).unwrap();
+        manager
+            .record(create_test_action(ActionType::ClipboardSet {
+                previous: None,
+            }))
+            .unwrap();
+        let later = manager.checkpoint("after clipboard").unwrap();
+        manager.record(mv(2)).unwrap();
+
+        let taken = manager.take_since(&checkpoint.id).unwrap();
+        assert_eq!(taken.len(), 3);
+        assert!(matches!(&taken[0].action_type, ActionType::FileMove { from, .. } if from == "a2"));
+        assert_eq!(manager.get_history().unwrap().len(), 1);
+        // Rolling back past a checkpoint drops it; the target stays
+        let ids: Vec<String> = manager
+            .checkpoints()
+            .unwrap()
+            .into_iter()
+            .map(|c| c.id)
+            .collect();
+        assert_eq!(ids, vec![checkpoint.id.clone()]);
+        assert!(matches!(
+            manager.take_since(&later.id),
+            Err(RollbackError::CheckpointNotFound(_))
+        ));
+
+        // Once actions after it are evicted, the checkpoint is unreachable
+        for i in 3..8 {
+            manager.record(mv(i)).unwrap();
+        }
+        assert!(manager.checkpoints().unwrap().is_empty());
+        assert!(manager.take_since(&checkpoint.id).is_err());
+    }
+
     #[test]
     fn test_reversible_count() {
         let manager = RollbackManager::new(10);
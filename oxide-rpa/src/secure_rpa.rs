@@ -1,13 +1,20 @@
 use log::{info, warn};
 use rdev::{Button, Key};
-use std::sync::Arc;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
 use thiserror::Error;
 
 use crate::audit::{AuditEntry, AuditLogger};
+use crate::clipboard;
 use crate::confirmation::{ConfirmationManager, ConfirmationRequest};
+use crate::grants::GrantManager;
+use crate::ocr::{OcrEngine, OcrResult, ScreenRegion};
 use crate::permissions::{Permission, PermissionPolicy};
-use crate::rollback::{ActionType, ReversibleAction, RollbackManager};
+use crate::rollback::{ActionType, Checkpoint, ReversibleAction, RollbackManager, WindowPlacement};
 use crate::rpa::{KeyboardController, MouseController, ScreenCapture};
+use crate::window_manager::{self, WindowAction, WindowInfo, WindowQuery};
 
 #[derive(Error, Debug)]
 pub enum SecureRPAError {
@@ -25,15 +32,45 @@ pub enum SecureRPAError {
     ConfirmationError(#[from] crate::confirmation::ConfirmationError),
 }
 
+/// Outcome of [`SecureRPAController::rollback_to`]
+#[derive(Debug, Clone, Serialize)]
+pub struct RollbackReport {
+    pub checkpoint_id: String,
+    /// Reverted actions, newest first
+    pub reverted: Vec<ReversibleAction>,
+    /// Clicks, typing and other actions that cannot be undone
+    pub skipped: Vec<ReversibleAction>,
+}
+
+/// Outcome of [`SecureRPAController::verify_screen_text`]
+#[derive(Debug, Clone, Serialize)]
+pub struct TextVerification {
+    pub expected: String,
+    pub found: bool,
+    /// Screen reads made before the text appeared or time ran out
+    pub attempts: u32,
+    /// Text read on the last attempt
+    pub text: String,
+}
+
+/// Pause between screen reads while waiting for expected text
+const VERIFY_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(750);
+
 /// Secure RPA controller with permissions, audit logging, and rollback
 pub struct SecureRPAController {
     mouse: MouseController,
     keyboard: KeyboardController,
     screen: ScreenCapture,
+    ocr: OcrEngine,
     policy: Arc<PermissionPolicy>,
+    grants: GrantManager,
+    /// Application the automation is aimed at, for app-scoped grants
+    target_application: RwLock<Option<String>>,
     audit: AuditLogger,
     rollback: RollbackManager,
     confirmation: ConfirmationManager,
+    /// Set by [`halt`](Self::halt); every action is refused until resumed
+    halted: AtomicBool,
 }
 
 impl SecureRPAController {
@@ -42,10 +79,14 @@ impl SecureRPAController {
             mouse: MouseController::new(),
             keyboard: KeyboardController::new(),
             screen: ScreenCapture::new(),
+            ocr: OcrEngine::default(),
             policy: Arc::new(policy),
+            grants: GrantManager::new(),
+            target_application: RwLock::new(None),
             audit: AuditLogger::default(),
             rollback: RollbackManager::default(),
             confirmation: ConfirmationManager::new(),
+            halted: AtomicBool::new(false),
         }
     }
 
@@ -59,6 +100,11 @@ impl SecureRPAController {
         self
     }
 
+    pub fn with_ocr(mut self, ocr: OcrEngine) -> Self {
+        self.ocr = ocr;
+        self
+    }
+
     /// Get reference to audit logger
     pub fn audit(&self) -> &AuditLogger {
         &self.audit
@@ -74,11 +120,66 @@ impl SecureRPAController {
         &self.confirmation
     }
 
+    /// Get reference to time-boxed permission grants
+    pub fn grants(&self) -> &GrantManager {
+        &self.grants
+    }
+
     /// Update permission policy
     pub fn update_policy(&mut self, policy: PermissionPolicy) {
         self.policy = Arc::new(policy);
     }
 
+    /// Set the application subsequent actions target
+    pub fn set_target_application(&self, application: Option<String>) {
+        if let Ok(mut target) = self.target_application.write() {
+            *target = application;
+        }
+    }
+
+    pub fn target_application(&self) -> Option<String> {
+        self.target_application
+            .read()
+            .ok()
+            .and_then(|target| target.clone())
+    }
+
+    /// Whether the policy or an active grant allows `permission` for the
+    /// current target application. Policy denials override grants.
+    pub fn is_permitted(&self, permission: Permission) -> bool {
+        if self.policy.denied.contains(&permission) {
+            return false;
+        }
+        if self.policy.is_allowed(permission) {
+            return true;
+        }
+        let target = self.target_application();
+        self.grants
+            .allows(permission, target.as_deref())
+            .unwrap_or(false)
+    }
+
+    /// Emergency stop: refuse every action until [`resume`](Self::resume),
+    /// drop pending confirmations and revoke all grants.
+    pub fn halt(&self) -> Result<(), SecureRPAError> {
+        self.halted.store(true, Ordering::SeqCst);
+        warn!("RPA halted");
+        self.confirmation.clear_pending()?;
+        for grant in self.grants.active().unwrap_or_default() {
+            let _ = self.grants.revoke(&grant.id);
+        }
+        Ok(())
+    }
+
+    pub fn resume(&self) {
+        self.halted.store(false, Ordering::SeqCst);
+        info!("RPA resumed");
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.halted.load(Ordering::SeqCst)
+    }
+
     /// Check permission and request confirmation if needed
     async fn check_permission_and_confirm(
         &self,
@@ -86,8 +187,14 @@ impl SecureRPAController {
         action: &str,
         description: &str,
     ) -> Result<bool, SecureRPAError> {
+        if self.is_halted() {
+            return Err(SecureRPAError::PermissionDenied(format!(
+                "Action '{action}' refused: RPA is halted"
+            )));
+        }
+
         // Check if permission is allowed
-        if !self.policy.is_allowed(permission) {
+        if !self.is_permitted(permission) {
             warn!("Permission denied for action: {action}");
             return Err(SecureRPAError::PermissionDenied(format!(
                 "Action '{action}' requires {permission:?} permission"
@@ -274,33 +381,420 @@ impl SecureRPAController {
         result.map_err(SecureRPAError::OperationFailed)
     }
 
+    /// Recognize the text in a screen region, or the whole screen, with
+    /// security checks
+    pub async fn read_screen_text(
+        &self,
+        region: Option<ScreenRegion>,
+    ) -> Result<OcrResult, SecureRPAError> {
+        let action = match region {
+            Some(r) => format!(
+                "read_screen_text({}, {}, {}x{})",
+                r.x, r.y, r.width, r.height
+            ),
+            None => "read_screen_text".to_string(),
+        };
+        let confirmed = self
+            .check_permission_and_confirm(
+                Permission::ScreenAnalyze,
+                &action,
+                "Read the text on screen",
+            )
+            .await?;
+
+        let result = self.recognize_region(region).await;
+        self.log_audit(
+            &action,
+            Permission::ScreenAnalyze,
+            confirmed,
+            result.as_ref().map(|_| ()).map_err(|e| e.clone()),
+        );
+        result.map_err(SecureRPAError::OperationFailed)
+    }
+
+    /// Read the screen until `expected` appears or `timeout` passes, to
+    /// confirm an action had the intended effect ("the dialog says Saved").
+    /// A miss is reported in the result and recorded as a failure in the
+    /// audit log.
+    pub async fn verify_screen_text(
+        &self,
+        expected: &str,
+        region: Option<ScreenRegion>,
+        timeout: std::time::Duration,
+    ) -> Result<TextVerification, SecureRPAError> {
+        let action = format!("verify_screen_text({expected})");
+        let confirmed = self
+            .check_permission_and_confirm(
+                Permission::ScreenAnalyze,
+                &action,
+                &format!("Check the screen for \"{expected}\""),
+            )
+            .await?;
+
+        let deadline = tokio::time::Instant::now() + timeout;
+        let mut verification = TextVerification {
+            expected: expected.to_string(),
+            found: false,
+            attempts: 0,
+            text: String::new(),
+        };
+        let read = loop {
+            verification.attempts += 1;
+            match self.recognize_region(region).await {
+                Ok(result) => {
+                    verification.found = result.contains(expected);
+                    verification.text = result.text;
+                }
+                Err(e) => break Err(e),
+            }
+            if verification.found || tokio::time::Instant::now() + VERIFY_POLL_INTERVAL > deadline {
+                break Ok(());
+            }
+            tokio::time::sleep(VERIFY_POLL_INTERVAL).await;
+        };
+
+        let outcome = read.clone().and_then(|_| {
+            if verification.found {
+                Ok(())
+            } else {
+                Err(format!("Text \"{expected}\" not found on screen"))
+            }
+        });
+        self.log_audit(&action, Permission::ScreenAnalyze, confirmed, outcome);
+        read.map_err(SecureRPAError::OperationFailed)?;
+        Ok(verification)
+    }
+
+    async fn recognize_region(&self, region: Option<ScreenRegion>) -> Result<OcrResult, String> {
+        let image = match region {
+            Some(r) => {
+                self.screen
+                    .capture_area(r.x, r.y, r.width, r.height)
+                    .await?
+            }
+            None => self.screen.capture_screen().await?,
+        };
+        let ocr = self.ocr.clone();
+        tokio::task::spawn_blocking(move || ocr.recognize(&image))
+            .await
+            .map_err(|e| format!("OCR task failed: {e}"))?
+    }
+
+    /// Move or rename a file with security checks. Fails if `to` already
+    /// exists, so the move can always be reversed.
+    pub async fn move_file(&self, from: &str, to: &str) -> Result<(), SecureRPAError> {
+        let action = format!("move_file({from}, {to})");
+        let confirmed = self
+            .check_permission_and_confirm(
+                Permission::FileWrite,
+                &action,
+                &format!("Move {from} to {to}"),
+            )
+            .await?;
+
+        let result = move_path(from, to);
+        self.log_audit(&action, Permission::FileWrite, confirmed, result.clone());
+        result.map_err(SecureRPAError::OperationFailed)?;
+        info!("Moved {from} to {to}");
+
+        let reversible = ReversibleAction {
+            id: uuid::Uuid::new_v4().to_string(),
+            action_type: ActionType::FileMove {
+                from: from.to_string(),
+                to: to.to_string(),
+            },
+            state_before: serde_json::json!({ "path": from }),
+            state_after: serde_json::json!({ "path": to }),
+            timestamp: chrono::Utc::now(),
+        };
+        let _ = self.rollback.record(reversible);
+
+        Ok(())
+    }
+
+    /// Replace the clipboard text with security checks; the previous text
+    /// is kept for rollback.
+    pub async fn set_clipboard(&self, text: &str) -> Result<(), SecureRPAError> {
+        let action = "set_clipboard".to_string();
+        let confirmed = self
+            .check_permission_and_confirm(
+                Permission::ClipboardWrite,
+                &action,
+                &format!("Replace clipboard with {} characters", text.chars().count()),
+            )
+            .await?;
+
+        let previous = clipboard::read_text();
+        let result = clipboard::write_text(text);
+        self.log_audit(
+            &action,
+            Permission::ClipboardWrite,
+            confirmed,
+            result.clone(),
+        );
+        result.map_err(SecureRPAError::OperationFailed)?;
+
+        let reversible = ReversibleAction {
+            id: uuid::Uuid::new_v4().to_string(),
+            action_type: ActionType::ClipboardSet { previous },
+            state_before: serde_json::Value::Null,
+            state_after: serde_json::json!({ "chars": text.chars().count() }),
+            timestamp: chrono::Utc::now(),
+        };
+        let _ = self.rollback.record(reversible);
+
+        Ok(())
+    }
+
+    /// List visible top-level windows with security checks
+    pub async fn list_windows(&self) -> Result<Vec<WindowInfo>, SecureRPAError> {
+        let action = "list_windows".to_string();
+        let confirmed = self
+            .check_permission_and_confirm(Permission::ScreenAnalyze, &action, "List open windows")
+            .await?;
+
+        let result = window_manager::list_windows();
+        self.log_audit(
+            &action,
+            Permission::ScreenAnalyze,
+            confirmed,
+            result.as_ref().map(|_| ()).map_err(|e| e.clone()),
+        );
+        result.map_err(SecureRPAError::OperationFailed)
+    }
+
+    /// Focus, minimize, maximize, restore or close the window best matching
+    /// `query`. Focusing makes its process the target application for
+    /// app-scoped grants.
+    pub async fn window_action(
+        &self,
+        query: &WindowQuery,
+        window_action: WindowAction,
+    ) -> Result<WindowInfo, SecureRPAError> {
+        let permission = match window_action {
+            WindowAction::Close => Permission::WindowClose,
+            _ => Permission::WindowManage,
+        };
+        let action = format!("window_{window_action:?}({query})").to_lowercase();
+        let confirmed = self
+            .check_permission_and_confirm(
+                permission,
+                &action,
+                &format!("{window_action:?} window matching {query}"),
+            )
+            .await?;
+
+        let result = window_manager::find_window(query)
+            .and_then(|window| window_manager::perform(&window.id, window_action).map(|_| window));
+        self.log_audit(
+            &action,
+            permission,
+            confirmed,
+            result.as_ref().map(|_| ()).map_err(|e| e.clone()),
+        );
+        let window = result.map_err(SecureRPAError::OperationFailed)?;
+        info!(
+            "{window_action:?} window '{}' ({})",
+            window.title, window.id
+        );
+
+        let before = window.placement.clone();
+        let mut after = before.clone();
+        match window_action {
+            WindowAction::Minimize => after.minimized = true,
+            WindowAction::Maximize => {
+                after.minimized = false;
+                after.maximized = true;
+            }
+            WindowAction::Restore => {
+                after.minimized = false;
+                after.maximized = false;
+            }
+            WindowAction::Focus => {
+                after.minimized = false;
+                self.set_target_application(Some(window.process_name.clone()));
+            }
+            WindowAction::Close => {}
+        }
+        if window_action != WindowAction::Close && after != before {
+            self.record_window_state(&window.id, before, after);
+        }
+
+        Ok(window)
+    }
+
+    /// Move and resize the window best matching `query`
+    pub async fn move_window(
+        &self,
+        query: &WindowQuery,
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+    ) -> Result<WindowInfo, SecureRPAError> {
+        let action = format!("move_window({query}, {x}, {y}, {width}x{height})");
+        let confirmed = self
+            .check_permission_and_confirm(
+                Permission::WindowManage,
+                &action,
+                &format!("Move window matching {query} to ({x}, {y}) at {width}x{height}"),
+            )
+            .await?;
+
+        let after = WindowPlacement {
+            x,
+            y,
+            width,
+            height,
+            minimized: false,
+            maximized: false,
+        };
+        let result = window_manager::find_window(query)
+            .and_then(|window| window_manager::set_placement(&window.id, &after).map(|_| window));
+        self.log_audit(
+            &action,
+            Permission::WindowManage,
+            confirmed,
+            result.as_ref().map(|_| ()).map_err(|e| e.clone()),
+        );
+        let window = result.map_err(SecureRPAError::OperationFailed)?;
+
+        self.record_window_state(&window.id, window.placement.clone(), after);
+        Ok(window)
+    }
+
+    fn record_window_state(
+        &self,
+        window_id: &str,
+        before: WindowPlacement,
+        after: WindowPlacement,
+    ) {
+        let reversible = ReversibleAction {
+            id: uuid::Uuid::new_v4().to_string(),
+            action_type: ActionType::WindowState {
+                window_id: window_id.to_string(),
+                before,
+                after,
+            },
+            state_before: serde_json::Value::Null,
+            state_after: serde_json::Value::Null,
+            timestamp: chrono::Utc::now(),
+        };
+        let _ = self.rollback.record(reversible);
+    }
+
+    /// Mark the current point in the action history for [`Self::rollback_to`].
+    pub fn create_checkpoint(&self, label: &str) -> Result<Checkpoint, SecureRPAError> {
+        let checkpoint = self.rollback.checkpoint(label)?;
+        info!("RPA checkpoint '{label}' created ({})", checkpoint.id);
+        Ok(checkpoint)
+    }
+
     /// Rollback last action
     pub async fn rollback_last(&self) -> Result<(), SecureRPAError> {
         let action = self.rollback.rollback_last()?;
 
         info!("Rolling back action: {:?}", action.action_type);
 
-        // Execute rollback based on action type
-        match action.action_type {
+        self.revert(&action)
+    }
+
+    /// Undo every action recorded since `checkpoint_id`, newest first.
+    /// Actions that cannot be undone are skipped. If reverting one fails,
+    /// it and the older actions are put back in the history.
+    pub async fn rollback_to(&self, checkpoint_id: &str) -> Result<RollbackReport, SecureRPAError> {
+        let mut pending = self.rollback.take_since(checkpoint_id)?;
+        let mut report = RollbackReport {
+            checkpoint_id: checkpoint_id.to_string(),
+            reverted: Vec::new(),
+            skipped: Vec::new(),
+        };
+
+        // `pending` is newest first; take from the front
+        pending.reverse();
+        while let Some(action) = pending.pop() {
+            if !action.action_type.is_reversible() {
+                report.skipped.push(action);
+                continue;
+            }
+            if let Err(e) = self.revert(&action) {
+                let failed_id = action.id.clone();
+                pending.push(action);
+                self.rollback.restore(pending)?;
+                return Err(SecureRPAError::OperationFailed(format!(
+                    "Rolled back {} actions, then failed on {failed_id}: {e}",
+                    report.reverted.len()
+                )));
+            }
+            report.reverted.push(action);
+        }
+
+        info!(
+            "Rolled back to checkpoint {checkpoint_id}: {} reverted, {} skipped",
+            report.reverted.len(),
+            report.skipped.len()
+        );
+        Ok(report)
+    }
+
+    /// Apply the inverse of `action`.
+    fn revert(&self, action: &ReversibleAction) -> Result<(), SecureRPAError> {
+        match &action.action_type {
             ActionType::MouseMove { from_x, from_y, .. } => {
-                self.mouse.move_to(from_x, from_y);
+                self.mouse.move_to(*from_x, *from_y);
                 info!("Mouse position restored to ({from_x}, {from_y})");
             }
-            _ => {
-                warn!(
-                    "Rollback not implemented for action type: {:?}",
-                    action.action_type
-                );
+            ActionType::FileMove { from, to } => {
+                move_path(to, from).map_err(SecureRPAError::OperationFailed)?;
+                info!("Moved {to} back to {from}");
+            }
+            ActionType::FileDelete { path, content } => {
+                if Path::new(path).exists() {
+                    return Err(SecureRPAError::OperationFailed(format!(
+                        "Cannot restore {path}: a file already exists there"
+                    )));
+                }
+                std::fs::write(path, content).map_err(|e| {
+                    SecureRPAError::OperationFailed(format!("Failed to restore {path}: {e}"))
+                })?;
+                info!("Restored deleted file {path}");
+            }
+            ActionType::ClipboardSet { previous } => {
+                clipboard::write_text(previous.as_deref().unwrap_or_default())
+                    .map_err(SecureRPAError::OperationFailed)?;
+                info!("Clipboard text restored");
+            }
+            ActionType::WindowState {
+                window_id, before, ..
+            } => {
+                window_manager::set_placement(window_id, before)
+                    .map_err(SecureRPAError::OperationFailed)?;
+                info!("Window {window_id} placement restored");
+            }
+            other => {
+                warn!("Rollback not implemented for action type: {other:?}");
+                return Err(SecureRPAError::OperationFailed(format!(
+                    "Rollback is not available for {other:?}"
+                )));
             }
         }
-
         Ok(())
     }
 }
 
+/// Rename `from` to `to`, refusing to overwrite an existing `to`.
+fn move_path(from: &str, to: &str) -> Result<(), String> {
+    if Path::new(to).exists() {
+        return Err(format!("Destination {to} already exists"));
+    }
+    std::fs::rename(from, to).map_err(|e| format!("Failed to move {from} to {to}: {e}"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::permissions::PermissionProfile;
+    use std::collections::HashSet;
 
     #[tokio::test]
     async fn test_permission_denied() {
@@ -323,6 +817,97 @@ mod tests {
         assert!(!entries.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_rollback_to_checkpoint_reverts_moves() {
+        let dir = std::env::temp_dir().join(format!("oxide-rpa-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = |name: &str| dir.join(name).to_string_lossy().to_string();
+        std::fs::write(path("a.txt"), "report").unwrap();
+        std::fs::write(path("taken.txt"), "other").unwrap();
+
+        let mut policy = PermissionPolicy::permissive();
+        policy.grant(Permission::FileWrite);
+        policy.require_confirmation = false;
+        let controller = SecureRPAController::new(policy);
+
+        let checkpoint = controller.create_checkpoint("before renames").unwrap();
+        controller
+            .move_file(&path("a.txt"), &path("b.txt"))
+            .await
+            .unwrap();
+        controller
+            .move_file(&path("b.txt"), &path("c.txt"))
+            .await
+            .unwrap();
+        // Never overwrites, so the history stays reversible
+        assert!(controller
+            .move_file(&path("c.txt"), &path("taken.txt"))
+            .await
+            .is_err());
+
+        let report = controller.rollback_to(&checkpoint.id).await.unwrap();
+        assert_eq!(report.reverted.len(), 2);
+        assert!(report.skipped.is_empty());
+        assert_eq!(std::fs::read_to_string(path("a.txt")).unwrap(), "report");
+        assert!(!Path::new(&path("c.txt")).exists());
+        assert!(controller.rollback().get_history().unwrap().is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_grants_extend_policy() {
+        let controller = SecureRPAController::new(PermissionPolicy::restrictive());
+        assert!(!controller.is_permitted(Permission::KeyboardType));
+
+        let grant = controller
+            .grants()
+            .grant_profile(
+                PermissionProfile::InputOnly,
+                vec!["notepad".to_string()],
+                Some(chrono::Duration::minutes(10)),
+            )
+            .unwrap();
+        assert!(!controller.is_permitted(Permission::KeyboardType));
+        controller.set_target_application(Some("Notepad".to_string()));
+        assert!(controller.is_permitted(Permission::KeyboardType));
+
+        // Policy denials still win
+        controller
+            .grants()
+            .grant(HashSet::from([Permission::FileWrite]), Vec::new(), None)
+            .unwrap();
+        assert!(!controller.is_permitted(Permission::FileWrite));
+
+        controller.grants().revoke(&grant.id).unwrap();
+        assert!(!controller.is_permitted(Permission::KeyboardType));
+    }
+
+    #[tokio::test]
+    async fn test_halt_blocks_actions_and_revokes_grants() {
+        let mut policy = PermissionPolicy::restrictive();
+        policy.require_confirmation = false;
+        let controller = SecureRPAController::new(policy);
+        controller
+            .grants()
+            .grant(
+                HashSet::from([Permission::ClipboardWrite]),
+                Vec::new(),
+                None,
+            )
+            .unwrap();
+        assert!(controller.is_permitted(Permission::ClipboardWrite));
+
+        controller.halt().unwrap();
+        assert!(controller.is_halted());
+        assert!(controller.grants().active().unwrap().is_empty());
+        let refused = controller.set_clipboard("x").await.unwrap_err();
+        assert!(refused.to_string().contains("halted"));
+
+        controller.resume();
+        assert!(!controller.is_halted());
+    }
+
     #[test]
     fn test_policy_update() {
         let policy = PermissionPolicy::restrictive();
@@ -0,0 +1,600 @@
+//! Top-level window listing and control.
+//!
+//! Windows are driven through user32 from PowerShell, X11 through `wmctrl`
+//! (plus `xprop` and `xdotool` where available), and macOS through System
+//! Events, so no windowing toolkit has to be linked in. Window ids are
+//! opaque strings that stay valid until the window closes.
+
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+use std::str::FromStr;
+
+use crate::rollback::WindowPlacement;
+
+/// A visible top-level window
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WindowInfo {
+    pub id: String,
+    pub title: String,
+    /// Executable name without extension, e.g. `Code` or `firefox`
+    pub process_name: String,
+    pub pid: Option<u32>,
+    pub placement: WindowPlacement,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowAction {
+    /// Bring to the front, restoring it if minimized
+    Focus,
+    Minimize,
+    Maximize,
+    /// Leave the minimized or maximized state
+    Restore,
+    /// Ask the window to close; the application may prompt to save
+    Close,
+}
+
+impl FromStr for WindowAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "focus" | "activate" | "front" => Ok(WindowAction::Focus),
+            "minimize" | "minimise" => Ok(WindowAction::Minimize),
+            "maximize" | "maximise" => Ok(WindowAction::Maximize),
+            "restore" => Ok(WindowAction::Restore),
+            "close" => Ok(WindowAction::Close),
+            _ => Err(format!("Unknown window action: {s}")),
+        }
+    }
+}
+
+/// Selects windows by title and/or process name, case-insensitively
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WindowQuery {
+    /// Substring of the window title
+    pub title: Option<String>,
+    /// Substring of the process name; a trailing `.exe` is ignored
+    pub process: Option<String>,
+}
+
+impl WindowQuery {
+    pub fn is_empty(&self) -> bool {
+        self.title.as_deref().is_none_or(|t| t.trim().is_empty())
+            && self.process.as_deref().is_none_or(|p| p.trim().is_empty())
+    }
+
+    pub fn matches(&self, window: &WindowInfo) -> bool {
+        let title_ok = self
+            .title
+            .as_deref()
+            .is_none_or(|t| contains_ignore_case(&window.title, t));
+        let process_ok = self
+            .process
+            .as_deref()
+            .is_none_or(|p| contains_ignore_case(&window.process_name, strip_exe(p.trim())));
+        title_ok && process_ok
+    }
+
+    /// Best matching window: exact title or process name first, then the
+    /// first partial match in z-order.
+    pub fn best_match<'a>(&self, windows: &'a [WindowInfo]) -> Option<&'a WindowInfo> {
+        if self.is_empty() {
+            return None;
+        }
+        let exact = |w: &&WindowInfo| {
+            self.title
+                .as_deref()
+                .is_some_and(|t| w.title.eq_ignore_ascii_case(t.trim()))
+                || self
+                    .process
+                    .as_deref()
+                    .is_some_and(|p| w.process_name.eq_ignore_ascii_case(strip_exe(p.trim())))
+        };
+        let mut matching = windows.iter().filter(|w| self.matches(w));
+        let first = matching.clone().next();
+        matching.find(exact).or(first)
+    }
+}
+
+impl std::fmt::Display for WindowQuery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.title, &self.process) {
+            (Some(t), Some(p)) => write!(f, "title '{t}' in process '{p}'"),
+            (Some(t), None) => write!(f, "title '{t}'"),
+            (None, Some(p)) => write!(f, "process '{p}'"),
+            (None, None) => write!(f, "any window"),
+        }
+    }
+}
+
+fn contains_ignore_case(haystack: &str, needle: &str) -> bool {
+    haystack
+        .to_lowercase()
+        .contains(&needle.trim().to_lowercase())
+}
+
+fn strip_exe(name: &str) -> &str {
+    name.strip_suffix(".exe")
+        .or_else(|| name.strip_suffix(".EXE"))
+        .unwrap_or(name)
+}
+
+/// Visible top-level windows, front to back where the platform reports it.
+pub fn list_windows() -> Result<Vec<WindowInfo>, String> {
+    platform::list_windows()
+}
+
+/// The window [`WindowQuery::best_match`] picks among the open windows.
+pub fn find_window(query: &WindowQuery) -> Result<WindowInfo, String> {
+    if query.is_empty() {
+        return Err("Specify a window title or process name".to_string());
+    }
+    let windows = list_windows()?;
+    query
+        .best_match(&windows)
+        .cloned()
+        .ok_or_else(|| format!("No window matches {query}"))
+}
+
+pub fn perform(window_id: &str, action: WindowAction) -> Result<(), String> {
+    platform::perform(window_id, action)
+}
+
+/// Move and resize a window, then apply the placement's show state.
+pub fn set_placement(window_id: &str, placement: &WindowPlacement) -> Result<(), String> {
+    platform::set_placement(window_id, placement)
+}
+
+fn run(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to start {program}: {e}"))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        Err(format!(
+            "{program} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::{run, WindowAction, WindowInfo};
+    use crate::rollback::WindowPlacement;
+    use serde::Deserialize;
+
+    const USER32: &str = r#"
+Add-Type -TypeDefinition @"
+using System;
+using System.Collections.Generic;
+using System.Runtime.InteropServices;
+using System.Text;
+public static class OxideWin {
+    public delegate bool EnumProc(IntPtr h, IntPtr p);
+    public struct RECT { public int Left, Top, Right, Bottom; }
+    [DllImport("user32.dll")] public static extern bool EnumWindows(EnumProc f, IntPtr p);
+    [DllImport("user32.dll")] public static extern bool IsWindowVisible(IntPtr h);
+    [DllImport("user32.dll")] public static extern int GetWindowTextLength(IntPtr h);
+    [DllImport("user32.dll", CharSet = CharSet.Unicode)] public static extern int GetWindowText(IntPtr h, StringBuilder s, int n);
+    [DllImport("user32.dll")] public static extern uint GetWindowThreadProcessId(IntPtr h, out uint pid);
+    [DllImport("user32.dll")] public static extern bool GetWindowRect(IntPtr h, out RECT r);
+    [DllImport("user32.dll")] public static extern bool IsIconic(IntPtr h);
+    [DllImport("user32.dll")] public static extern bool IsZoomed(IntPtr h);
+    [DllImport("user32.dll")] public static extern bool ShowWindow(IntPtr h, int cmd);
+    [DllImport("user32.dll")] public static extern bool SetForegroundWindow(IntPtr h);
+    [DllImport("user32.dll")] public static extern bool MoveWindow(IntPtr h, int x, int y, int w, int ht, bool repaint);
+    [DllImport("user32.dll")] public static extern bool PostMessage(IntPtr h, uint msg, IntPtr w, IntPtr l);
+    public static string Title(IntPtr h) {
+        var sb = new StringBuilder(GetWindowTextLength(h) + 1);
+        GetWindowText(h, sb, sb.Capacity);
+        return sb.ToString();
+    }
+    public static List<IntPtr> Visible() {
+        var list = new List<IntPtr>();
+        EnumWindows((h, p) => { if (IsWindowVisible(h) && GetWindowTextLength(h) > 0) list.Add(h); return true; }, IntPtr.Zero);
+        return list;
+    }
+}
+"@
+"#;
+
+    const SW_MAXIMIZE: i32 = 3;
+    const SW_MINIMIZE: i32 = 6;
+    const SW_RESTORE: i32 = 9;
+
+    #[derive(Deserialize)]
+    struct RawWindow {
+        id: i64,
+        title: String,
+        process: Option<String>,
+        pid: u32,
+        x: i32,
+        y: i32,
+        width: i64,
+        height: i64,
+        minimized: bool,
+        maximized: bool,
+    }
+
+    fn powershell(script: &str) -> Result<String, String> {
+        let script = format!("{USER32}\n{script}");
+        run("powershell", &["-NoProfile", "-Command", &script])
+    }
+
+    fn handle(window_id: &str) -> Result<i64, String> {
+        window_id
+            .parse()
+            .map_err(|_| format!("Invalid window id: {window_id}"))
+    }
+
+    pub fn list_windows() -> Result<Vec<WindowInfo>, String> {
+        let out = powershell(
+            r#"
+$rows = foreach ($h in [OxideWin]::Visible()) {
+    $r = New-Object OxideWin+RECT
+    [void][OxideWin]::GetWindowRect($h, [ref]$r)
+    [uint32]$procId = 0
+    [void][OxideWin]::GetWindowThreadProcessId($h, [ref]$procId)
+    [PSCustomObject]@{
+        id = $h.ToInt64(); title = [OxideWin]::Title($h); pid = $procId
+        process = (Get-Process -Id $procId -ErrorAction SilentlyContinue).ProcessName
+        x = $r.Left; y = $r.Top; width = $r.Right - $r.Left; height = $r.Bottom - $r.Top
+        minimized = [OxideWin]::IsIconic($h); maximized = [OxideWin]::IsZoomed($h)
+    }
+}
+ConvertTo-Json -Compress -InputObject @($rows)
+"#,
+        )?;
+        let raw: Vec<RawWindow> = serde_json::from_str(out.trim())
+            .map_err(|e| format!("Unexpected window list output: {e}"))?;
+        Ok(raw
+            .into_iter()
+            .map(|w| WindowInfo {
+                id: w.id.to_string(),
+                title: w.title,
+                process_name: w.process.unwrap_or_default(),
+                pid: Some(w.pid),
+                placement: WindowPlacement {
+                    x: w.x,
+                    y: w.y,
+                    width: w.width.max(0) as u32,
+                    height: w.height.max(0) as u32,
+                    minimized: w.minimized,
+                    maximized: w.maximized,
+                },
+            })
+            .collect())
+    }
+
+    pub fn perform(window_id: &str, action: WindowAction) -> Result<(), String> {
+        let h = handle(window_id)?;
+        let call = match action {
+            WindowAction::Focus => format!(
+                "if ([OxideWin]::IsIconic($h)) {{ [void][OxideWin]::ShowWindow($h, {SW_RESTORE}) }}; \
+                 [void][OxideWin]::SetForegroundWindow($h)"
+            ),
+            WindowAction::Minimize => format!("[void][OxideWin]::ShowWindow($h, {SW_MINIMIZE})"),
+            WindowAction::Maximize => format!("[void][OxideWin]::ShowWindow($h, {SW_MAXIMIZE})"),
+            WindowAction::Restore => format!("[void][OxideWin]::ShowWindow($h, {SW_RESTORE})"),
+            // WM_CLOSE
+            WindowAction::Close => "[void][OxideWin]::PostMessage($h, 0x10, [IntPtr]::Zero, [IntPtr]::Zero)"
+                .to_string(),
+        };
+        powershell(&format!("$h = [IntPtr]{h}; {call}")).map(|_| ())
+    }
+
+    pub fn set_placement(window_id: &str, p: &WindowPlacement) -> Result<(), String> {
+        let h = handle(window_id)?;
+        let show = if p.minimized {
+            SW_MINIMIZE
+        } else if p.maximized {
+            SW_MAXIMIZE
+        } else {
+            SW_RESTORE
+        };
+        powershell(&format!(
+            "$h = [IntPtr]{h}; [void][OxideWin]::ShowWindow($h, {SW_RESTORE}); \
+             [void][OxideWin]::MoveWindow($h, {}, {}, {}, {}, $true); \
+             [void][OxideWin]::ShowWindow($h, {show})",
+            p.x, p.y, p.width, p.height
+        ))
+        .map(|_| ())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{run, WindowAction, WindowInfo};
+    use crate::rollback::WindowPlacement;
+
+    const MAXIMIZED: &str = "maximized_vert,maximized_horz";
+
+    /// Parse one line of `wmctrl -lpGx`:
+    /// `id desktop pid x y width height wm_class host title...`
+    pub(super) fn parse_wmctrl_line(line: &str) -> Option<WindowInfo> {
+        let mut fields = line.split_whitespace();
+        let id = fields.next()?.to_string();
+        let desktop: i64 = fields.next()?.parse().ok()?;
+        let pid: u32 = fields.next()?.parse().ok()?;
+        let x = fields.next()?.parse().ok()?;
+        let y = fields.next()?.parse().ok()?;
+        let width = fields.next()?.parse().ok()?;
+        let height = fields.next()?.parse().ok()?;
+        let class = fields.next()?;
+        let _host = fields.next()?;
+        let title = fields.collect::<Vec<_>>().join(" ");
+        // Desktop -1 holds panels and docks
+        if desktop < 0 || title.is_empty() {
+            return None;
+        }
+        let process_name = std::fs::read_to_string(format!("/proc/{pid}/comm"))
+            .map(|comm| comm.trim().to_string())
+            .unwrap_or_else(|_| class.rsplit('.').next().unwrap_or(class).to_string());
+        Some(WindowInfo {
+            id,
+            title,
+            process_name,
+            pid: (pid > 0).then_some(pid),
+            placement: WindowPlacement {
+                x,
+                y,
+                width,
+                height,
+                minimized: false,
+                maximized: false,
+            },
+        })
+    }
+
+    fn net_wm_state(window_id: &str) -> String {
+        run("xprop", &["-id", window_id, "_NET_WM_STATE"]).unwrap_or_default()
+    }
+
+    pub fn list_windows() -> Result<Vec<WindowInfo>, String> {
+        let out = run("wmctrl", &["-lpGx"])?;
+        Ok(out
+            .lines()
+            .filter_map(parse_wmctrl_line)
+            .map(|mut w| {
+                let state = net_wm_state(&w.id);
+                w.placement.minimized = state.contains("_NET_WM_STATE_HIDDEN");
+                w.placement.maximized = state.contains("_NET_WM_STATE_MAXIMIZED_VERT")
+                    && state.contains("_NET_WM_STATE_MAXIMIZED_HORZ");
+                w
+            })
+            .collect())
+    }
+
+    pub fn perform(window_id: &str, action: WindowAction) -> Result<(), String> {
+        match action {
+            WindowAction::Focus => run("wmctrl", &["-ia", window_id]),
+            WindowAction::Minimize => run("xdotool", &["windowminimize", window_id]),
+            WindowAction::Maximize => run(
+                "wmctrl",
+                &["-ir", window_id, "-b", &format!("add,{MAXIMIZED}")],
+            ),
+            WindowAction::Restore => {
+                run(
+                    "wmctrl",
+                    &["-ir", window_id, "-b", &format!("remove,{MAXIMIZED}")],
+                )?;
+                run("wmctrl", &["-ia", window_id])
+            }
+            WindowAction::Close => run("wmctrl", &["-ic", window_id]),
+        }
+        .map(|_| ())
+    }
+
+    pub fn set_placement(window_id: &str, p: &WindowPlacement) -> Result<(), String> {
+        perform(window_id, WindowAction::Restore)?;
+        let geometry = format!("0,{},{},{},{}", p.x, p.y, p.width, p.height);
+        run("wmctrl", &["-ir", window_id, "-e", &geometry])?;
+        if p.minimized {
+            perform(window_id, WindowAction::Minimize)
+        } else if p.maximized {
+            perform(window_id, WindowAction::Maximize)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{run, WindowAction, WindowInfo};
+    use crate::rollback::WindowPlacement;
+
+    const LIST: &str = r#"
+set out to ""
+tell application "System Events"
+    repeat with p in (processes whose visible is true)
+        set i to 0
+        repeat with w in windows of p
+            set i to i + 1
+            set {x, y} to position of w
+            set {ww, hh} to size of w
+            set out to out & (unix id of p) & ":" & i & tab & (unix id of p) & tab & (name of p) & tab & x & tab & y & tab & ww & tab & hh & tab & (value of attribute "AXMinimized" of w) & tab & (name of w) & linefeed
+        end repeat
+    end repeat
+end tell
+return out
+"#;
+
+    fn osascript(script: &str) -> Result<String, String> {
+        run("osascript", &["-e", script])
+    }
+
+    /// Window ids are `pid:index` within the owning process
+    fn target(window_id: &str) -> Result<String, String> {
+        let (pid, index) = window_id
+            .split_once(':')
+            .ok_or_else(|| format!("Invalid window id: {window_id}"))?;
+        let pid: u32 = pid
+            .parse()
+            .map_err(|_| format!("Invalid window id: {window_id}"))?;
+        let index: u32 = index
+            .parse()
+            .map_err(|_| format!("Invalid window id: {window_id}"))?;
+        Ok(format!(
+            "window {index} of (first process whose unix id is {pid})"
+        ))
+    }
+
+    pub fn list_windows() -> Result<Vec<WindowInfo>, String> {
+        let out = osascript(LIST)?;
+        Ok(out
+            .lines()
+            .filter_map(|line| {
+                let f: Vec<&str> = line.splitn(9, '\t').collect();
+                if f.len() < 9 || f[8].is_empty() {
+                    return None;
+                }
+                Some(WindowInfo {
+                    id: f[0].to_string(),
+                    pid: f[1].parse().ok(),
+                    process_name: f[2].to_string(),
+                    placement: WindowPlacement {
+                        x: f[3].parse().ok()?,
+                        y: f[4].parse().ok()?,
+                        width: f[5].parse().ok()?,
+                        height: f[6].parse().ok()?,
+                        minimized: f[7] == "true",
+                        maximized: false,
+                    },
+                    title: f[8].to_string(),
+                })
+            })
+            .collect())
+    }
+
+    pub fn perform(window_id: &str, action: WindowAction) -> Result<(), String> {
+        let w = target(window_id)?;
+        let body = match action {
+            WindowAction::Focus => format!(
+                "set value of attribute \"AXMinimized\" of {w} to false\n\
+                 perform action \"AXRaise\" of {w}\n\
+                 set frontmost of (first process whose unix id is {}) to true",
+                window_id.split(':').next().unwrap_or_default()
+            ),
+            WindowAction::Minimize => format!("set value of attribute \"AXMinimized\" of {w} to true"),
+            WindowAction::Restore => format!("set value of attribute \"AXMinimized\" of {w} to false"),
+            WindowAction::Maximize => {
+                return Err("Maximizing windows is not supported on macOS".to_string())
+            }
+            WindowAction::Close => format!(
+                "perform action \"AXPress\" of (first button of {w} whose subrole is \"AXCloseButton\")"
+            ),
+        };
+        osascript(&format!(
+            "tell application \"System Events\"\n{body}\nend tell"
+        ))
+        .map(|_| ())
+    }
+
+    pub fn set_placement(window_id: &str, p: &WindowPlacement) -> Result<(), String> {
+        let w = target(window_id)?;
+        osascript(&format!(
+            "tell application \"System Events\"\n\
+             set value of attribute \"AXMinimized\" of {w} to false\n\
+             set position of {w} to {{{}, {}}}\n\
+             set size of {w} to {{{}, {}}}\n\
+             set value of attribute \"AXMinimized\" of {w} to {}\n\
+             end tell",
+            p.x, p.y, p.width, p.height, p.minimized
+        ))
+        .map(|_| ())
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+mod platform {
+    use super::{WindowAction, WindowInfo};
+    use crate::rollback::WindowPlacement;
+
+    const UNSUPPORTED: &str = "Window management is not supported on this platform";
+
+    pub fn list_windows() -> Result<Vec<WindowInfo>, String> {
+        Err(UNSUPPORTED.to_string())
+    }
+
+    pub fn perform(_window_id: &str, _action: WindowAction) -> Result<(), String> {
+        Err(UNSUPPORTED.to_string())
+    }
+
+    pub fn set_placement(_window_id: &str, _placement: &WindowPlacement) -> Result<(), String> {
+        Err(UNSUPPORTED.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(id: &str, title: &str, process: &str) -> WindowInfo {
+        WindowInfo {
+            id: id.to_string(),
+            title: title.to_string(),
+            process_name: process.to_string(),
+            pid: None,
+            placement: WindowPlacement {
+                x: 0,
+                y: 0,
+                width: 800,
+                height: 600,
+                minimized: false,
+                maximized: false,
+            },
+        }
+    }
+
+    #[test]
+    fn test_query_prefers_exact_matches() {
+        let windows = vec![
+            window("1", "notes.md - Visual Studio Code", "Code"),
+            window("2", "Code review - Firefox", "firefox"),
+            window("3", "Visual Studio Code", "Code"),
+        ];
+        let by_process = WindowQuery {
+            title: None,
+            process: Some("code.exe".to_string()),
+        };
+        assert_eq!(by_process.best_match(&windows).unwrap().id, "1");
+
+        let by_title = WindowQuery {
+            title: Some("visual studio code".to_string()),
+            process: None,
+        };
+        assert_eq!(by_title.best_match(&windows).unwrap().id, "3");
+
+        let both = WindowQuery {
+            title: Some("code".to_string()),
+            process: Some("firefox".to_string()),
+        };
+        assert_eq!(both.best_match(&windows).unwrap().id, "2");
+        assert!(WindowQuery::default().best_match(&windows).is_none());
+        assert_eq!(
+            "Minimise".parse::<WindowAction>(),
+            Ok(WindowAction::Minimize)
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_parse_wmctrl_line() {
+        let line = "0x04200007  0 0      10   40   1280 720  code.Code  host notes.md - Visual Studio Code";
+        let w = platform::parse_wmctrl_line(line).unwrap();
+        assert_eq!(w.id, "0x04200007");
+        assert_eq!(w.title, "notes.md - Visual Studio Code");
+        assert_eq!(w.process_name, "Code");
+        assert_eq!(w.pid, None);
+        assert_eq!((w.placement.x, w.placement.width), (10, 1280));
+
+        let dock = "0x01e00003 -1 812    0    0    1920 32   plank.Plank  host plank";
+        assert!(platform::parse_wmctrl_line(dock).is_none());
+    }
+}
@@ -0,0 +1,228 @@
+//! Speech languages for transcription and synthesis.
+//!
+//! Recognition is sent the primary language plus, when auto-detection is on,
+//! up to three alternatives; Google reports which one it heard. Replies are
+//! spoken in that language with the configured voice for it, or a built-in
+//! default.
+
+use oxide_core::config::{is_language_tag, VoiceConfig, MAX_DETECT_LANGUAGES};
+use oxide_core::i18n::Locale;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// A language with a known TTS voice
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpeechLanguage {
+    pub code: &'static str,
+    pub name: &'static str,
+    pub voice: &'static str,
+}
+
+pub const LANGUAGES: &[SpeechLanguage] = &[
+    SpeechLanguage {
+        code: "en-US",
+        name: "English (US)",
+        voice: "en-US-Wavenet-D",
+    },
+    SpeechLanguage {
+        code: "en-GB",
+        name: "English (UK)",
+        voice: "en-GB-Wavenet-B",
+    },
+    SpeechLanguage {
+        code: "es-ES",
+        name: "Español (España)",
+        voice: "es-ES-Wavenet-B",
+    },
+    SpeechLanguage {
+        code: "es-US",
+        name: "Español (Latinoamérica)",
+        voice: "es-US-Wavenet-B",
+    },
+    SpeechLanguage {
+        code: "fr-FR",
+        name: "Français",
+        voice: "fr-FR-Wavenet-B",
+    },
+    SpeechLanguage {
+        code: "de-DE",
+        name: "Deutsch",
+        voice: "de-DE-Wavenet-B",
+    },
+    SpeechLanguage {
+        code: "it-IT",
+        name: "Italiano",
+        voice: "it-IT-Wavenet-C",
+    },
+    SpeechLanguage {
+        code: "pt-BR",
+        name: "Português (Brasil)",
+        voice: "pt-BR-Wavenet-B",
+    },
+    SpeechLanguage {
+        code: "ja-JP",
+        name: "日本語",
+        voice: "ja-JP-Wavenet-C",
+    },
+];
+
+/// Built-in language for `tag`: an exact match, else the first with the
+/// same primary language ("es-MX" -> "es-ES").
+pub fn find(tag: &str) -> Option<&'static SpeechLanguage> {
+    let primary = primary_language(tag);
+    LANGUAGES
+        .iter()
+        .find(|language| language.code.eq_ignore_ascii_case(tag))
+        .or_else(|| {
+            LANGUAGES
+                .iter()
+                .find(|language| primary_language(language.code).eq_ignore_ascii_case(primary))
+        })
+}
+
+fn primary_language(tag: &str) -> &str {
+    tag.split(['-', '_']).next().unwrap_or_default()
+}
+
+/// Speech tag for a message locale
+pub fn for_locale(locale: Locale) -> &'static str {
+    match locale {
+        Locale::En => "en-US",
+        Locale::Es => "es-ES",
+    }
+}
+
+/// Canonical form of `tag`: built-in codes keep their casing, a bare
+/// language becomes its built-in regional tag, and other well-formed tags
+/// pass through for Google to judge.
+pub fn normalize(tag: &str) -> Result<String, String> {
+    let tag = tag.trim().replace('_', "-");
+    if !is_language_tag(&tag) {
+        return Err(format!(
+            "Voice language '{tag}' must be a tag such as 'en-US' or 'es'"
+        ));
+    }
+    Ok(match find(&tag) {
+        Some(language) if language.code.eq_ignore_ascii_case(&tag) || !tag.contains('-') => {
+            language.code.to_string()
+        }
+        _ => tag,
+    })
+}
+
+/// Languages used for one recognition or synthesis call
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct VoiceLanguageSettings {
+    pub language: String,
+    pub auto_detect: bool,
+    pub detect_languages: Vec<String>,
+    pub voices: HashMap<String, String>,
+}
+
+impl VoiceLanguageSettings {
+    /// Settings from `config`, with `fallback` as the primary language when
+    /// none is configured.
+    pub fn from_config(config: &VoiceConfig, fallback: &str) -> Self {
+        let language = config
+            .language
+            .as_deref()
+            .and_then(|tag| normalize(tag).ok())
+            .unwrap_or_else(|| fallback.to_string());
+        Self {
+            language,
+            auto_detect: config.auto_detect,
+            detect_languages: config
+                .detect_languages
+                .iter()
+                .filter_map(|tag| normalize(tag).ok())
+                .collect(),
+            voices: config.voices.clone(),
+        }
+    }
+
+    /// Other languages recognition may pick, never including the primary.
+    pub fn alternatives(&self) -> Vec<String> {
+        if !self.auto_detect {
+            return Vec::new();
+        }
+        let candidates: Vec<String> = if self.detect_languages.is_empty() {
+            Locale::ALL
+                .iter()
+                .map(|locale| for_locale(*locale).to_string())
+                .collect()
+        } else {
+            self.detect_languages.clone()
+        };
+        let mut alternatives: Vec<String> = Vec::new();
+        for tag in candidates {
+            let duplicate = tag.eq_ignore_ascii_case(&self.language)
+                || alternatives.iter().any(|t| t.eq_ignore_ascii_case(&tag));
+            if !duplicate {
+                alternatives.push(tag);
+            }
+        }
+        alternatives.truncate(MAX_DETECT_LANGUAGES);
+        alternatives
+    }
+
+    /// TTS voice for `tag`: the configured one, else the built-in one for
+    /// that language. `None` lets Google choose from the language alone.
+    pub fn voice_for(&self, tag: &str) -> Option<String> {
+        self.voices
+            .iter()
+            .find(|(language, _)| language.eq_ignore_ascii_case(tag))
+            .map(|(_, voice)| voice.clone())
+            .or_else(|| find(tag).map(|language| language.voice.to_string()))
+    }
+}
+
+/// Voice language settings as shown to the user
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct VoiceLanguageStatus {
+    #[serde(flatten)]
+    pub settings: VoiceLanguageSettings,
+    /// Languages recognition will also listen for
+    pub alternatives: Vec<String>,
+    pub last_detected: Option<String>,
+    pub available: &'static [SpeechLanguage],
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_and_finds_voices() {
+        assert_eq!(normalize("es").unwrap(), "es-ES");
+        assert_eq!(normalize("es_mx").unwrap(), "es-mx");
+        assert_eq!(normalize("EN-us").unwrap(), "en-US");
+        assert!(normalize("english").is_err());
+
+        let mut config = VoiceConfig::default();
+        config
+            .voices
+            .insert("es-MX".to_string(), "es-US-Neural2-A".to_string());
+        let settings = VoiceLanguageSettings::from_config(&config, "en-US");
+        assert_eq!(settings.voice_for("es-mx").unwrap(), "es-US-Neural2-A");
+        assert_eq!(settings.voice_for("es-AR").unwrap(), "es-ES-Wavenet-B");
+        assert_eq!(settings.voice_for("nl-NL"), None);
+    }
+
+    #[test]
+    fn alternatives_skip_the_primary_language() {
+        let mut config = VoiceConfig {
+            language: Some("es-ES".to_string()),
+            ..VoiceConfig::default()
+        };
+        let settings = VoiceLanguageSettings::from_config(&config, "en-US");
+        assert_eq!(settings.alternatives(), vec!["en-US".to_string()]);
+
+        config.detect_languages = vec!["fr".to_string(), "es-ES".to_string()];
+        let settings = VoiceLanguageSettings::from_config(&config, "en-US");
+        assert_eq!(settings.alternatives(), vec!["fr-FR".to_string()]);
+
+        config.auto_detect = false;
+        let settings = VoiceLanguageSettings::from_config(&config, "en-US");
+        assert!(settings.alternatives().is_empty());
+    }
+}
@@ -1,11 +1,14 @@
 use crate::audio::{AudioManager, VoiceActivityDetector};
+use crate::language::{VoiceLanguageSettings, VoiceLanguageStatus, LANGUAGES};
 use async_trait::async_trait;
 use base64::{engine::general_purpose, Engine as _};
 use log::{info, warn};
 use oxide_core::google_auth::get_access_token;
+use oxide_core::{api_governor, network};
 use reqwest::Client;
-use serde_json::json;
-use std::sync::{Arc, Mutex};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex, RwLock};
 // use std::thread; // Reserved for future use
 use std::time::Duration;
 use tokio::sync::mpsc;
@@ -93,29 +96,78 @@ impl WakeWordDetector {
     }
 }
 
+/// Recognized speech and the language it was heard in
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Transcription {
+    pub text: String,
+    /// BCP-47 tag reported by the recognizer, if any
+    pub language: Option<String>,
+}
+
+impl Transcription {
+    /// Transcript and detected language from a `speech:recognize` response;
+    /// each result covers a consecutive stretch of audio.
+    fn from_response(response: &Value) -> Self {
+        let results = response["results"].as_array().cloned().unwrap_or_default();
+        let text = results
+            .iter()
+            .filter_map(|result| result["alternatives"][0]["transcript"].as_str())
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let language = results
+            .iter()
+            .find_map(|result| result["languageCode"].as_str())
+            .filter(|tag| !tag.is_empty())
+            .map(str::to_string);
+        Self { text, language }
+    }
+}
+
 #[async_trait]
 pub trait STTProvider {
-    async fn transcribe_audio(&self, audio_data: Vec<u8>) -> Result<String, String>;
+    /// Transcribe `audio_data` spoken in `language` or one of `alternatives`.
+    async fn transcribe_audio(
+        &self,
+        audio_data: Vec<u8>,
+        language: &str,
+        alternatives: &[String],
+    ) -> Result<Transcription, String>;
 }
 
 pub struct GoogleSTTProvider {
     http_client: Client,
-    language_code: String,
 }
 
 impl GoogleSTTProvider {
-    pub fn new(language_code: Option<String>) -> Self {
+    pub fn new() -> Self {
         Self {
-            http_client: Client::new(),
-            language_code: language_code.unwrap_or_else(|| "en-US".to_string()),
+            http_client: network::http_client(),
         }
     }
 }
 
+impl Default for GoogleSTTProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[async_trait]
 impl STTProvider for GoogleSTTProvider {
-    async fn transcribe_audio(&self, audio_data: Vec<u8>) -> Result<String, String> {
-        info!("Transcribing audio with Google STT...");
+    async fn transcribe_audio(
+        &self,
+        audio_data: Vec<u8>,
+        language: &str,
+        alternatives: &[String],
+    ) -> Result<Transcription, String> {
+        info!("Transcribing audio with Google STT ({language}, alternatives {alternatives:?})...");
+        let url = "https://speech.googleapis.com/v1/speech:recognize";
+        network::ensure_online(url).map_err(|e| e.to_string())?;
+        let _permit = api_governor::acquire(url)
+            .await
+            .map_err(|e| e.to_string())?;
 
         let access_token = get_access_token()
             .await
@@ -126,7 +178,8 @@ impl STTProvider for GoogleSTTProvider {
             "config": {
                 "encoding": "WEBM_OPUS",
                 "sampleRateHertz": 16000,
-                "languageCode": self.language_code,
+                "languageCode": language,
+                "alternativeLanguageCodes": alternatives,
                 "enableAutomaticPunctuation": true
             },
             "audio": {
@@ -136,7 +189,7 @@ impl STTProvider for GoogleSTTProvider {
 
         let response = self
             .http_client
-            .post("https://speech.googleapis.com/v1/speech:recognize")
+            .post(url)
             .bearer_auth(&access_token)
             .json(&request_body)
             .send()
@@ -153,42 +206,41 @@ impl STTProvider for GoogleSTTProvider {
             .await
             .map_err(|e| format!("Failed to parse response: {e}"))?;
 
-        if let Some(results) = response_json["results"].as_array() {
-            if let Some(first_result) = results.first() {
-                if let Some(alternatives) = first_result["alternatives"].as_array() {
-                    if let Some(first_alternative) = alternatives.first() {
-                        if let Some(transcript) = first_alternative["transcript"].as_str() {
-                            info!("Transcription successful: {transcript}");
-                            return Ok(transcript.to_string());
-                        }
-                    }
-                }
-            }
+        let transcription = Transcription::from_response(&response_json);
+        if transcription.text.is_empty() {
+            warn!("No transcription found in response");
+        } else {
+            info!(
+                "Transcription successful ({}): {}",
+                transcription.language.as_deref().unwrap_or(language),
+                transcription.text
+            );
         }
-
-        warn!("No transcription found in response");
-        Ok("".to_string())
+        Ok(transcription)
     }
 }
 
 #[async_trait]
 pub trait TTSProvider {
-    async fn synthesize_speech(&self, text: &str) -> Result<Vec<u8>, String>;
+    /// Speak `text` in `language`, with `voice` or the provider's default
+    /// voice for that language.
+    async fn synthesize_speech(
+        &self,
+        text: &str,
+        language: &str,
+        voice: Option<&str>,
+    ) -> Result<Vec<u8>, String>;
 }
 
 pub struct GoogleTTSProvider {
     http_client: Client,
-    language_code: String,
-    voice_name: String,
     speaking_rate: f32,
 }
 
 impl GoogleTTSProvider {
-    pub fn new(language_code: Option<String>, voice_name: Option<String>) -> Self {
+    pub fn new() -> Self {
         Self {
-            http_client: Client::new(),
-            language_code: language_code.unwrap_or_else(|| "en-US".to_string()),
-            voice_name: voice_name.unwrap_or_else(|| "en-US-Wavenet-D".to_string()),
+            http_client: network::http_client(),
             speaking_rate: 1.0,
         }
     }
@@ -199,24 +251,41 @@ impl GoogleTTSProvider {
     }
 }
 
+impl Default for GoogleTTSProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[async_trait]
 impl TTSProvider for GoogleTTSProvider {
-    async fn synthesize_speech(&self, text: &str) -> Result<Vec<u8>, String> {
-        info!("Synthesizing speech with Google TTS: {}", text);
+    async fn synthesize_speech(
+        &self,
+        text: &str,
+        language: &str,
+        voice: Option<&str>,
+    ) -> Result<Vec<u8>, String> {
+        info!("Synthesizing speech with Google TTS ({language}): {text}");
+        let url = "https://texttospeech.googleapis.com/v1/text:synthesize";
+        network::ensure_online(url).map_err(|e| e.to_string())?;
+        let _permit = api_governor::acquire(url)
+            .await
+            .map_err(|e| e.to_string())?;
 
         let access_token = get_access_token()
             .await
             .map_err(|e| format!("Failed to get access token: {e}"))?
             .ok_or("No access token available")?;
 
+        let mut voice_params = json!({ "languageCode": language });
+        if let Some(voice) = voice {
+            voice_params["name"] = json!(voice);
+        }
         let request_body = json!({
             "input": {
                 "text": text
             },
-            "voice": {
-                "languageCode": self.language_code,
-                "name": self.voice_name
-            },
+            "voice": voice_params,
             "audioConfig": {
                 "audioEncoding": "MP3",
                 "speakingRate": self.speaking_rate
@@ -225,7 +294,7 @@ impl TTSProvider for GoogleTTSProvider {
 
         let response = self
             .http_client
-            .post("https://texttospeech.googleapis.com/v1/text:synthesize")
+            .post(url)
             .bearer_auth(&access_token)
             .json(&request_body)
             .send()
@@ -260,6 +329,9 @@ pub struct VoiceProcessor {
     wake_word_detector: WakeWordDetector,
     stt_provider: Box<dyn STTProvider + Send + Sync>,
     tts_provider: Box<dyn TTSProvider + Send + Sync>,
+    languages: RwLock<VoiceLanguageSettings>,
+    // Language of the last transcription, so the reply is spoken in it
+    last_detected: Mutex<Option<String>>,
 }
 
 impl VoiceProcessor {
@@ -267,14 +339,45 @@ impl VoiceProcessor {
         wake_words: Vec<String>,
         stt_provider: Box<dyn STTProvider + Send + Sync>,
         tts_provider: Box<dyn TTSProvider + Send + Sync>,
+        languages: VoiceLanguageSettings,
     ) -> Result<Self, String> {
         Ok(Self {
             wake_word_detector: WakeWordDetector::new(wake_words)?,
             stt_provider,
             tts_provider,
+            languages: RwLock::new(languages),
+            last_detected: Mutex::new(None),
         })
     }
 
+    pub fn languages(&self) -> VoiceLanguageSettings {
+        self.languages.read().unwrap().clone()
+    }
+
+    pub fn set_languages(&self, languages: VoiceLanguageSettings) {
+        info!(
+            "Voice language set to {} (auto-detect: {})",
+            languages.language, languages.auto_detect
+        );
+        *self.languages.write().unwrap() = languages;
+        *self.last_detected.lock().unwrap() = None;
+    }
+
+    /// Language the last transcription was heard in, if it was detected.
+    pub fn last_detected_language(&self) -> Option<String> {
+        self.last_detected.lock().unwrap().clone()
+    }
+
+    pub fn language_status(&self) -> VoiceLanguageStatus {
+        let settings = self.languages();
+        VoiceLanguageStatus {
+            alternatives: settings.alternatives(),
+            settings,
+            last_detected: self.last_detected_language(),
+            available: LANGUAGES,
+        }
+    }
+
     pub async fn start_listening(&self) -> Result<mpsc::Receiver<String>, String> {
         self.wake_word_detector.start_detection().await
     }
@@ -283,12 +386,34 @@ impl VoiceProcessor {
         self.wake_word_detector.stop_detection().await
     }
 
-    pub async fn transcribe_audio(&self, audio_data: Vec<u8>) -> Result<String, String> {
-        self.stt_provider.transcribe_audio(audio_data).await
+    pub async fn transcribe_audio(&self, audio_data: Vec<u8>) -> Result<Transcription, String> {
+        let (language, alternatives) = {
+            let languages = self.languages.read().unwrap();
+            (languages.language.clone(), languages.alternatives())
+        };
+        let transcription = self
+            .stt_provider
+            .transcribe_audio(audio_data, &language, &alternatives)
+            .await?;
+        if !transcription.text.is_empty() {
+            *self.last_detected.lock().unwrap() = transcription.language.clone();
+        }
+        Ok(transcription)
     }
 
+    /// Speak `text` in the language last heard, else the primary language.
     pub async fn synthesize_speech(&self, text: &str) -> Result<Vec<u8>, String> {
-        self.tts_provider.synthesize_speech(text).await
+        let (language, voice) = {
+            let languages = self.languages.read().unwrap();
+            let language = self
+                .last_detected_language()
+                .unwrap_or_else(|| languages.language.clone());
+            let voice = languages.voice_for(&language);
+            (language, voice)
+        };
+        self.tts_provider
+            .synthesize_speech(text, &language, voice.as_deref())
+            .await
     }
 
     pub async fn record_audio(&self, duration_secs: f32) -> Result<Vec<u8>, String> {
@@ -326,3 +451,25 @@ impl VoiceProcessor {
             .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transcription_joins_results_and_reads_the_language() {
+        let response = json!({
+            "results": [
+                {"alternatives": [{"transcript": "hola, "}], "languageCode": "es-es"},
+                {"alternatives": [{"transcript": "¿qué tal?"}], "languageCode": "es-es"}
+            ]
+        });
+        let transcription = Transcription::from_response(&response);
+        assert_eq!(transcription.text, "hola, ¿qué tal?");
+        assert_eq!(transcription.language.as_deref(), Some("es-es"));
+        assert_eq!(
+            Transcription::from_response(&json!({})),
+            Transcription::default()
+        );
+    }
+}
@@ -0,0 +1,886 @@
+#!/usr/bin/env node
+// Generate src/lib/bindings.ts from the Tauri commands registered in
+// src-tauri/src/main.rs: one entry per command with its argument and result
+// types, the payload of every event in src-tauri/src/events.rs, plus
+// TypeScript definitions for every Rust type they reference.
+//
+//   node scripts/generate-bindings.mjs          write the bindings
+//   node scripts/generate-bindings.mjs --check  fail if they are out of date
+//   npm run test:bindings                       run the generator's tests
+//
+// The Rust sources are read as text, so this needs no build of the backend.
+// That is why this is not ts-rs or specta: both export from compiled code,
+// which here means building RocksDB and the Tauri toolchain on every
+// frontend check, and neither types Tauri 1 commands (tauri-specta needs
+// Tauri 2).
+// It fails when a referenced type cannot be found, or when a command returns
+// bare `serde_json::Value`; results that really are free-form JSON go through
+// a named `type` alias so the binding says what they are.
+
+import { existsSync, readdirSync, readFileSync, writeFileSync } from "node:fs";
+import { dirname, join, relative, resolve } from "node:path";
+import { fileURLToPath } from "node:url";
+
+const FRONTEND = resolve(dirname(fileURLToPath(import.meta.url)), "..");
+const ROOT = resolve(FRONTEND, "..");
+const TAURI_SRC = join(ROOT, "src-tauri", "src");
+const OUTPUT = join(FRONTEND, "src", "lib", "bindings.ts");
+
+// Workspace crates by the name they are imported under
+const CRATES = {
+  crate: TAURI_SRC,
+  oxide_core: join(ROOT, "oxide-core", "src"),
+  oxide_copilot: join(ROOT, "oxide-copilot", "src"),
+  oxide_guardian: join(ROOT, "oxide-guardian", "src"),
+  oxide_memory: join(ROOT, "oxide-memory", "src"),
+  oxide_rpa: join(ROOT, "oxide-rpa", "src"),
+  oxide_voice: join(ROOT, "oxide-voice", "src"),
+};
+
+const STRING_TYPES = new Set([
+  "String",
+  "str",
+  "char",
+  "PathBuf",
+  "Path",
+  "OsString",
+  "Uuid",
+  "DateTime",
+  "NaiveDateTime",
+  "NaiveDate",
+  "Thing",
+]);
+const NUMBER_TYPES = new Set([
+  "u8",
+  "u16",
+  "u32",
+  "u64",
+  "u128",
+  "usize",
+  "i8",
+  "i16",
+  "i32",
+  "i64",
+  "i128",
+  "isize",
+  "f32",
+  "f64",
+]);
+const LIST_TYPES = new Set(["Vec", "VecDeque", "HashSet", "BTreeSet"]);
+const MAP_TYPES = new Set(["HashMap", "BTreeMap", "IndexMap"]);
+const WRAPPER_TYPES = new Set(["Box", "Arc", "Rc", "Cow"]);
+const UNKNOWN_TYPES = new Set(["Value", "JsonValue", "Map"]);
+// Parameters Tauri injects rather than reading from the invoke payload
+const INJECTED = /^(tauri::)?(State|AppHandle|Window|Invoke|InvokeMessage)\b/;
+
+// ---------------------------------------------------------------- source text
+
+const fileCache = new Map();
+
+/** Source with comments removed and string contents blanked. */
+function cleaned(file) {
+  if (!fileCache.has(file)) {
+    const source = existsSync(file) ? readFileSync(file, "utf8") : "";
+    fileCache.set(file, stripComments(source));
+  }
+  return fileCache.get(file);
+}
+
+function stripComments(src) {
+  let out = "";
+  let i = 0;
+  while (i < src.length) {
+    const c = src[i];
+    const next = src[i + 1];
+    if (c === "/" && next === "/") {
+      while (i < src.length && src[i] !== "\n") i++;
+    } else if (c === "/" && next === "*") {
+      let depth = 1;
+      i += 2;
+      while (i < src.length && depth > 0) {
+        if (src[i] === "/" && src[i + 1] === "*") {
+          depth++;
+          i += 2;
+        } else if (src[i] === "*" && src[i + 1] === "/") {
+          depth--;
+          i += 2;
+        } else i++;
+      }
+    } else if (isRawString(src, i)) {
+      const hashes = src.slice(i + 1).match(/^#*/)[0];
+      const close = `"${hashes}`;
+      const end = src.indexOf(close, i + 2 + hashes.length);
+      out += '""';
+      i = end < 0 ? src.length : end + close.length;
+    } else if (c === '"') {
+      i++;
+      let content = "";
+      while (i < src.length && src[i] !== '"') {
+        if (src[i] === "\\") i++;
+        else content += src[i];
+        i++;
+      }
+      // Keep attribute strings such as rename = "..." readable
+      out += `"${content.includes("\n") ? "" : content}"`;
+      i++;
+    } else if (isCharLiteral(src, i)) {
+      const end = src.indexOf("'", next === "\\" ? i + 3 : i + 2);
+      out += "' '";
+      i = end + 1;
+    } else {
+      out += c;
+      i++;
+    }
+  }
+  return out;
+}
+
+function isRawString(src, i) {
+  return (
+    src[i] === "r" &&
+    /^r#*"/.test(src.slice(i, i + 8)) &&
+    !/\w/.test(src[i - 1])
+  );
+}
+
+/** A `'x'` or `'\n'` literal, as opposed to a lifetime. */
+function isCharLiteral(src, i) {
+  if (src[i] !== "'") return false;
+  if (src[i + 2] === "'") return true;
+  return src[i + 1] === "\\" && src.indexOf("'", i + 2) < i + 12;
+}
+
+/** Contents between the bracket at `start` and the one closing it. */
+function inner(text, start) {
+  return text.slice(start + 1, closing(text, start) - 1);
+}
+
+/** Drop a leading `pub` or `pub(crate)`. */
+function stripVisibility(text) {
+  return text.replace(/^pub(\([^)]*\))?\s+/, "");
+}
+
+/** Index just past the bracket that closes the one at `start`. */
+function closing(text, start) {
+  const open = text[start];
+  const close = { "(": ")", "[": "]", "{": "}", "<": ">" }[open];
+  let depth = 0;
+  for (let i = start; i < text.length; i++) {
+    const c = text[i];
+    if (c === open) depth++;
+    else if (c === close && !(close === ">" && text[i - 1] === "-")) {
+      depth--;
+      if (depth === 0) return i + 1;
+    }
+  }
+  return text.length;
+}
+
+/** Split on `sep` outside any brackets. */
+function splitTop(text, sep = ",") {
+  const parts = [];
+  let depth = 0;
+  let current = "";
+  for (let i = 0; i < text.length; i++) {
+    const c = text[i];
+    if ("([{<".includes(c)) depth++;
+    else if (")]}".includes(c) || (c === ">" && text[i - 1] !== "-")) depth--;
+    if (c === sep && depth === 0) {
+      parts.push(current);
+      current = "";
+    } else current += c;
+  }
+  parts.push(current);
+  return parts.map((p) => p.trim()).filter(Boolean);
+}
+
+/** Leading `#[...]` attributes of `text` and the rest. */
+function takeAttributes(text) {
+  const attrs = [];
+  let rest = text.trim();
+  while (rest.startsWith("#[")) {
+    const end = closing(rest, 1);
+    attrs.push(rest.slice(2, end - 1));
+    rest = rest.slice(end).trim();
+  }
+  return { attrs, rest };
+}
+
+/** Attributes written immediately before `index`. */
+function attributesBefore(text, index) {
+  const attrs = [];
+  let i = index;
+  for (;;) {
+    const before = text.slice(0, i).trimEnd();
+    if (!before.endsWith("]")) break;
+    let depth = 0;
+    let j = before.length - 1;
+    for (; j >= 0; j--) {
+      if (before[j] === "]") depth++;
+      else if (before[j] === "[") {
+        depth--;
+        if (depth === 0) break;
+      }
+    }
+    if (before[j - 1] !== "#") break;
+    attrs.unshift(before.slice(j + 1, before.length - 1));
+    i = j - 1;
+  }
+  return attrs;
+}
+
+/** `key = "value"` pairs and bare flags inside `serde(...)` attributes. */
+function serdeOptions(attrs) {
+  const options = {};
+  for (const attr of attrs) {
+    const m = attr.match(/^serde\s*\(([\s\S]*)\)$/);
+    if (!m) continue;
+    for (const part of splitTop(m[1])) {
+      const kv = part.match(/^(\w+)\s*=\s*"([^"]*)"$/);
+      // `rename_all(serialize = "...")`: the serialized form is what we type
+      const split = part.match(/^(\w+)\s*\([\s\S]*\bserialize\s*=\s*"([^"]*)"/);
+      if (kv) options[kv[1]] = kv[2];
+      else if (split) options[split[1]] = split[2];
+      else options[part.replace(/\(.*$/s, "").trim()] = true;
+    }
+  }
+  return options;
+}
+
+// ------------------------------------------------------------ name resolution
+
+const useCache = new Map();
+
+/** Names brought into scope by `use` in `file`, mapped to their full paths. */
+function imports(file) {
+  if (useCache.has(file)) return useCache.get(file);
+  const scope = new Map();
+  const text = cleaned(file);
+  for (const m of text.matchAll(/\buse\s+([\s\S]*?);/g)) {
+    for (const { path, alias } of expandUse(m[1].replace(/\s+/g, ""))) {
+      scope.set(alias, path);
+    }
+  }
+  useCache.set(file, scope);
+  return scope;
+}
+
+function expandUse(tree, prefix = "") {
+  const brace = tree.indexOf("{");
+  if (brace < 0) {
+    const [path, alias] = tree.split("as");
+    const full = prefix + path;
+    const segments = full.split("::");
+    const last = segments[segments.length - 1];
+    if (last === "self") {
+      segments.pop();
+      return [{ path: segments.join("::"), alias: alias ?? segments.at(-1) }];
+    }
+    return [{ path: full, alias: alias ?? last }];
+  }
+  const head = tree.slice(0, brace);
+  return splitTop(inner(tree, brace)).flatMap((part) =>
+    expandUse(part, prefix + head),
+  );
+}
+
+/** The module file a `::` path (minus its item) points at, from `file`. */
+function moduleFile(segments, file) {
+  const [first, ...rest] = segments;
+  let dir;
+  if (first === "crate") dir = crateRoot(file);
+  else if (first === "self") dir = moduleDir(file);
+  else if (first === "super") dir = dirname(moduleDir(file));
+  else if (CRATES[first]) dir = CRATES[first];
+  else return null;
+  if (rest.length === 0) {
+    if (first === "self" || first === "super") return null;
+    const main = join(dir, "main.rs");
+    return existsSync(main) ? main : join(dir, "lib.rs");
+  }
+  let current = null;
+  for (const segment of rest) {
+    if (segment === "super") {
+      dir = dirname(dir);
+      continue;
+    }
+    const asFile = join(dir, `${segment}.rs`);
+    const asDir = join(dir, segment, "mod.rs");
+    if (existsSync(asFile)) current = asFile;
+    else if (existsSync(asDir)) current = asDir;
+    else return null;
+    dir = join(dir, segment);
+  }
+  return current;
+}
+
+function crateRoot(file) {
+  for (const dir of Object.values(CRATES)) {
+    if (file.startsWith(dir)) return dir;
+  }
+  return dirname(file);
+}
+
+function moduleDir(file) {
+  const base = file.replace(/(mod|lib|main)\.rs$/, "");
+  return base.endsWith(".rs") ? base.slice(0, -3) : dirname(file);
+}
+
+const definitionCache = new Map();
+
+/** Where `name` is defined as seen from `file`, following imports. */
+function resolveDefinition(name, file, depth = 0) {
+  const key = `${file}::${name}`;
+  if (definitionCache.has(key)) return definitionCache.get(key);
+  let found = null;
+  if (depth < 6) {
+    found = findDefinition(name, file);
+    if (!found) {
+      const path = imports(file).get(name);
+      if (path) {
+        const segments = path.split("::");
+        const item = segments.pop();
+        const target = moduleFile(segments, file);
+        if (target) found = resolveDefinition(item, target, depth + 1);
+      }
+    }
+    if (!found) {
+      // Glob re-exports such as `pub use types::*;`
+      const globs = cleaned(file).matchAll(/\bpub\s+use\s+([\w:]+)::\*\s*;/g);
+      for (const m of globs) {
+        const target = moduleFile(m[1].split("::"), file);
+            if (target && target !== file) {
+          found = resolveDefinition(name, target, depth + 1);
+        }
+        if (found) break;
+      }
+    }
+  }
+  if (!found && depth === 0) found = searchWorkspace(name);
+  definitionCache.set(key, found);
+  return found;
+}
+
+const VISIBILITY = "(?:pub(?:\\([^)]*\\))?\\s+)?";
+const DEFINITION = (name) =>
+  new RegExp(`${VISIBILITY}\\b(struct|enum|type)\\s+${name}\\b`, "g");
+
+function findDefinition(name, file) {
+  const text = cleaned(file);
+  for (const m of text.matchAll(DEFINITION(name))) {
+    const attrs = attributesBefore(text, m.index);
+    if (attrs.some((a) => /^cfg\s*\(\s*test\s*\)/.test(a))) continue;
+    const index = m.index + m[0].length;
+    return { name, kind: m[1], file, text, index, attrs };
+  }
+  return null;
+}
+
+let workspaceFiles = null;
+
+function searchWorkspace(name) {
+  if (!workspaceFiles) {
+    workspaceFiles = Object.values(CRATES).flatMap((dir) => rustFiles(dir));
+  }
+  for (const file of workspaceFiles) {
+    const found = findDefinition(name, file);
+    if (found) return found;
+  }
+  return null;
+}
+
+function rustFiles(dir) {
+  if (!existsSync(dir)) return [];
+  return readdirSync(dir, { withFileTypes: true })
+    .sort((a, b) => a.name.localeCompare(b.name))
+    .flatMap((entry) => {
+      const path = join(dir, entry.name);
+      if (entry.isDirectory()) {
+        return entry.name === "bin" ? [] : rustFiles(path);
+      }
+      return entry.name.endsWith(".rs") ? [path] : [];
+    });
+}
+
+// ------------------------------------------------------------- type mapping
+
+const definitions = new Map(); // ts name -> rendered definition
+const emitted = new Map(); // file::name -> ts name
+const pending = [];
+const problems = [];
+
+function tsName(def) {
+  const key = `${def.file}::${def.name}`;
+  if (emitted.has(key)) return emitted.get(key);
+  let name = def.name;
+  if ([...emitted.values()].includes(name)) {
+    // Same name in another module: qualify with the crate
+    const crate = relative(ROOT, def.file)
+      .split(/[\\/]/)[0]
+      .replace(/^oxide-/, "");
+    name = rename(crate.replaceAll("-", "_"), "PascalCase") + def.name;
+  }
+  emitted.set(key, name);
+  pending.push({ def, name });
+  return name;
+}
+
+/** Parse a Rust type into `{ name, args }`, tuples into `{ tuple }`. */
+function parseType(text) {
+  const t = text
+    .trim()
+    .replace(/^&\s*('\w+\s*)?(mut\s+)?/, "")
+    .replace(/^dyn\s+/, "");
+  if (t.startsWith("(")) {
+    return { tuple: splitTop(inner(t, 0)).map(parseType) };
+  }
+  if (t.startsWith("[")) {
+    const [element] = splitTop(inner(t, 0), ";");
+    return { name: "Vec", args: [parseType(element)] };
+  }
+  const lt = t.indexOf("<");
+  const path = (lt < 0 ? t : t.slice(0, lt)).trim();
+  const args =
+    lt < 0
+      ? []
+      : splitTop(inner(t, lt))
+          .filter((a) => !a.startsWith("'"))
+          .map(parseType);
+  return { name: path.split("::").at(-1), path, args };
+}
+
+function toTs(type, ctx) {
+  if (type.tuple) {
+    return type.tuple.length === 0 ? "null" : tsTuple(type.tuple, ctx);
+  }
+  const { name, args } = type;
+  if (ctx.generics?.includes(name)) return name;
+  if (STRING_TYPES.has(name)) return "string";
+  if (NUMBER_TYPES.has(name)) return "number";
+  if (name === "bool") return "boolean";
+  if (UNKNOWN_TYPES.has(name)) return "unknown";
+  if (name === "Duration") return "{ secs: number; nanos: number }";
+  if (name === "Option") return `${toTs(args[0], ctx)} | null`;
+  if (name === "Result") return toTs(args[0], ctx);
+  if (WRAPPER_TYPES.has(name)) return toTs(args[args.length - 1], ctx);
+  if (LIST_TYPES.has(name)) return `${wrap(toTs(args[0], ctx))}[]`;
+  if (MAP_TYPES.has(name)) return `Record<string, ${toTs(args[1], ctx)}>`;
+  const def = resolveDefinition(name, ctx.file);
+  if (!def) {
+    problems.push(`cannot resolve ${type.path} in ${relative(ROOT, ctx.file)}`);
+    return "unknown";
+  }
+  const generic = args.length ? `<${tsList(args, ctx)}>` : "";
+  return tsName(def) + generic;
+}
+
+function tsList(types, ctx) {
+  return types.map((t) => toTs(t, ctx)).join(", ");
+}
+
+function tsTuple(types, ctx) {
+  return types.length === 1 ? toTs(types[0], ctx) : `[${tsList(types, ctx)}]`;
+}
+
+function wrap(ts) {
+  return /[|&\s]/.test(ts) && !/^\{.*\}$/.test(ts) ? `(${ts})` : ts;
+}
+
+function rename(name, rule) {
+  const words = name.includes("_")
+    ? name.split("_").filter(Boolean)
+    : name.split(/(?=[A-Z])/);
+  const lower = words.map((w) => w.toLowerCase());
+  const cap = (w) => w.charAt(0).toUpperCase() + w.slice(1);
+  switch (rule) {
+    case "lowercase":
+      return name.toLowerCase();
+    case "UPPERCASE":
+      return name.toUpperCase();
+    case "PascalCase":
+      return lower.map(cap).join("");
+    case "camelCase":
+      return lower[0] + lower.slice(1).map(cap).join("");
+    case "snake_case":
+      return lower.join("_");
+    case "SCREAMING_SNAKE_CASE":
+      return lower.join("_").toUpperCase();
+    case "kebab-case":
+      return lower.join("-");
+    case "SCREAMING-KEBAB-CASE":
+      return lower.join("-").toUpperCase();
+    default:
+      return name;
+  }
+}
+
+function quoteKey(key) {
+  return /^[A-Za-z_$][\w$]*$/.test(key) ? key : JSON.stringify(key);
+}
+
+/**
+ * Object type for named fields; `flatten` fields are intersected. A
+ * flattened `Option` adds all of its fields or none, so they are `Partial`.
+ */
+function renderFields(body, container, ctx, indent) {
+  const lines = [];
+  const flattened = [];
+  for (const part of splitTop(body)) {
+    const { attrs, rest } = takeAttributes(part);
+    const field = stripVisibility(rest).match(/^(\w+)\s*:\s*([\s\S]+)$/);
+    if (!field) continue;
+    const options = serdeOptions(attrs);
+    if (options.skip || options.skip_serializing) continue;
+    const type = parseType(field[2]);
+    if (options.flatten) {
+      flattened.push(
+        type.name === "Option"
+          ? `Partial<${toTs(type.args[0], ctx)}>`
+          : wrap(toTs(type, ctx)),
+      );
+      continue;
+    }
+    const key = options.rename ?? rename(field[1], container.rename_all);
+    const optional = options.skip_serializing_if || options.default;
+    // Absent rather than null, so the Option is already covered by `?`
+    const unwrapped = optional && type.name === "Option" ? type.args[0] : type;
+    const ts = toTs(unwrapped, ctx);
+    lines.push(`${indent}  ${quoteKey(key)}${optional ? "?" : ""}: ${ts};`);
+  }
+  if (lines.length === 0) {
+    return flattened.length ? flattened.join(" & ") : "Record<string, never>";
+  }
+  const object = `{\n${lines.join("\n")}\n${indent}}`;
+  return [object, ...flattened].join(" & ");
+}
+
+function renderDefinition({ def, name }) {
+  const { kind, text, index, attrs, file } = def;
+  const container = serdeOptions(attrs);
+  let rest = text.slice(index).trimStart();
+  let generics = [];
+  if (rest.startsWith("<")) {
+    const end = closing(rest, 0);
+    generics = splitTop(inner(rest, 0))
+      .filter((g) => !g.startsWith("'"))
+      .map((g) => g.split(/[:=]/)[0].trim());
+    rest = rest.slice(end).trimStart();
+  }
+  const ctx = { file, generics };
+  const params = generics.length ? `<${generics.join(", ")}>` : "";
+  const head = `export type ${name}${params} =`;
+  if (kind === "type") {
+    const target = rest.replace(/^=\s*/, "").split(";")[0];
+    return `${head} ${toTs(parseType(target), ctx)};`;
+  }
+  rest = rest.replace(/^where[\s\S]*?(?=[{(;])/, "");
+  if (kind === "struct") {
+    if (rest.startsWith(";")) return `${head} null;`;
+    if (rest.startsWith("(")) {
+      const fields = splitTop(inner(rest, 0)).map((f) =>
+        parseType(stripVisibility(takeAttributes(f).rest)),
+      );
+      return `${head} ${tsTuple(fields, ctx)};`;
+    }
+    const body = inner(rest, 0);
+    if (container.transparent) {
+      const [only] = splitTop(body);
+      const field = stripVisibility(takeAttributes(only).rest);
+      const type = parseType(field.slice(field.indexOf(":") + 1));
+      return `${head} ${toTs(type, ctx)};`;
+    }
+    return `${head} ${renderFields(body, container, ctx, "")};`;
+  }
+  // enum
+  const body = inner(rest, 0);
+  const variants = [];
+  for (const part of splitTop(body)) {
+    const { attrs: vattrs, rest: variant } = takeAttributes(part);
+    const options = serdeOptions(vattrs);
+    if (options.skip || options.skip_serializing) continue;
+    const m = variant.match(/^(\w+)\s*([\s\S]*)$/);
+    if (!m) continue;
+    const tag = options.rename ?? rename(m[1], container.rename_all);
+    const shape = m[2].replace(/=[\s\S]*$/, "").trim();
+    let content = null;
+    if (shape.startsWith("(")) {
+      const fields = splitTop(inner(shape, 0)).map((f) =>
+        parseType(takeAttributes(f).rest),
+      );
+      content = tsTuple(fields, ctx);
+    } else if (shape.startsWith("{")) {
+      // A variant's own `rename_all` wins over `rename_all_fields`
+      const fieldRules = {
+        rename_all: options.rename_all ?? container.rename_all_fields,
+      };
+      content = renderFields(inner(shape, 0), fieldRules, ctx, "  ");
+    }
+    const literal = JSON.stringify(tag);
+    const tagKey = quoteKey(container.tag ?? "");
+    const contentKey = quoteKey(container.content ?? "");
+    if (container.untagged || options.untagged) {
+      variants.push(content ?? "null");
+    } else if (container.tag && container.content) {
+      variants.push(
+        content === null
+          ? `{ ${tagKey}: ${literal} }`
+          : `{ ${tagKey}: ${literal}; ${contentKey}: ${content} }`,
+      );
+    } else if (container.tag) {
+      const tagged = `{ ${tagKey}: ${literal} }`;
+      variants.push(content === null ? tagged : `(${tagged} & ${content})`);
+    } else {
+      variants.push(
+        content === null ? literal : `{ ${quoteKey(tag)}: ${content} }`,
+      );
+    }
+  }
+  if (variants.length === 0) return `${head} never;`;
+  return `${head}\n${variants.map((v) => `  | ${v}`).join("\n")};`;
+}
+
+function renderPending() {
+  // Rendering a definition can discover more types
+  for (let i = 0; i < pending.length; i++) {
+    if (!definitions.has(pending[i].name)) {
+      definitions.set(pending[i].name, renderDefinition(pending[i]));
+    }
+  }
+}
+
+/**
+ * Definitions of the types `names` from `file` and of everything they
+ * reference, keyed by TypeScript name. Used by the tests.
+ */
+export function renderTypes(file, names) {
+  for (const name of names) {
+    const def = findDefinition(name, file);
+    if (!def) throw new Error(`${name} is not defined in ${file}`);
+    tsName(def);
+  }
+  renderPending();
+  if (problems.length > 0) throw new Error(problems.join("\n"));
+  return definitions;
+}
+
+// ------------------------------------------------------------------ commands
+
+/** `[path, name]` of every command in `generate_handler!`. */
+function registeredCommands() {
+  const main = cleaned(join(TAURI_SRC, "main.rs"));
+  const start = main.indexOf("generate_handler!");
+  const open = main.indexOf("[", start);
+  return splitTop(inner(main, open)).map((entry) => {
+    const segments = entry.replace(/\s+/g, "").split("::");
+    return { module: segments.slice(0, -1), name: segments.at(-1) };
+  });
+}
+
+function commandFile(module) {
+  if (module.length === 0) return join(TAURI_SRC, "main.rs");
+  return moduleFile(["crate", ...module], join(TAURI_SRC, "main.rs"));
+}
+
+/** `fn name` definitions in `text` with the attributes above them. */
+function functionsNamed(text, name) {
+  const pattern = new RegExp(
+    `(${VISIBILITY}(?:async\\s+)?)fn\\s+${name}\\s*[<(]`,
+    "g",
+  );
+  return [...text.matchAll(pattern)].map((m) => ({
+    index: m.index + m[1].length,
+    attrs: attributesBefore(text, m.index),
+  }));
+}
+
+function parseCommand({ module, name }) {
+  const file = commandFile(module);
+  const text = cleaned(file);
+  const isCommand = (a) => a.startsWith("tauri::command");
+  const candidates = functionsNamed(text, name).filter((c) =>
+    c.attrs.some(isCommand),
+  );
+  if (candidates.length === 0) {
+    throw new Error(`Command ${name} not found in ${relative(ROOT, file)}`);
+  }
+  // Prefer the definition compiled with the default features
+  const chosen =
+    candidates.find((c) => !c.attrs.some((a) => /cfg\s*\(\s*not/.test(a))) ??
+    candidates[0];
+  const commandAttr = chosen.attrs
+    .find(isCommand)
+    .replace(/^tauri::command\s*\(?|\)$/g, "");
+  const argRule =
+    serdeOptions([`serde(${commandAttr})`]).rename_all ?? "camelCase";
+
+  let rest = text.slice(chosen.index).replace(/^fn\s+\w+\s*/, "");
+  let generics = [];
+  if (rest.startsWith("<")) {
+    generics = splitTop(inner(rest, 0)).map((g) => g.split(":")[0].trim());
+    rest = rest.slice(closing(rest, 0)).trimStart();
+  }
+  const params = splitTop(inner(rest, 0));
+  const returns = rest
+    .slice(closing(rest, 0))
+    .match(/^\s*->\s*([\s\S]*?)\s*(\bwhere\b|\{)/);
+
+  const ctx = { file, generics };
+  const args = [];
+  for (const param of params) {
+    const p = takeAttributes(param).rest.match(
+      /^(?:mut\s+)?(\w+)\s*:\s*([\s\S]+)$/,
+    );
+    if (!p || INJECTED.test(p[2].trim())) continue;
+    const type = parseType(p[2]);
+    const optional = type.name === "Option";
+    args.push({
+      key: rename(p[1], argRule),
+      optional,
+      ts: toTs(optional ? type.args[0] : type, ctx),
+    });
+  }
+  const resultType = returns ? parseType(returns[1]) : null;
+  if (resultType && untyped(resultType)) {
+    problems.push(
+      `${name} in ${relative(ROOT, file)} returns untyped JSON; return a struct or a named alias`,
+    );
+  }
+  const result = resultType ? toTs(resultType, ctx) : "null";
+  return { name, args, result };
+}
+
+/** `Value` itself, or inside `Result`, `Option`, a list or a wrapper. */
+function untyped(type) {
+  if (type.tuple) return false;
+  if (UNKNOWN_TYPES.has(type.name)) return true;
+  const containers = ["Result", "Option", ...LIST_TYPES, ...WRAPPER_TYPES];
+  return containers.includes(type.name) && untyped(type.args[0]);
+}
+
+// -------------------------------------------------------------------- events
+
+const EVENTS_FILE = join(TAURI_SRC, "events.rs");
+
+/**
+ * Schema version and `[name, payload type]` of every event in `ALL`, from
+ * the `impl AppEvent for ...` blocks in events.rs.
+ */
+function parseEvents() {
+  const text = cleaned(EVENTS_FILE);
+  const constants = new Map(
+    [...text.matchAll(/\bconst\s+(\w+)\s*:\s*&str\s*=\s*"([^"]*)"/g)].map(
+      (m) => [m[1], m[2]],
+    ),
+  );
+  const all = text.match(/\bconst\s+ALL\s*:[^=]*=\s*\[/);
+  const names = all
+    ? splitTop(inner(text, all.index + all[0].length - 1))
+    : [];
+  const version = text.match(/\bconst\s+SCHEMA_VERSION\s*:\s*u32\s*=\s*(\d+)/);
+  if (!version) problems.push("events.rs has no SCHEMA_VERSION");
+
+  const payloads = new Map();
+  const ctx = { file: EVENTS_FILE, generics: [] };
+  for (const m of text.matchAll(/\bimpl\s+AppEvent\s+for\s+([\w:]+)\s*\{/g)) {
+    const open = m.index + m[0].length - 1;
+    const ts = toTs(parseType(m[1]), ctx);
+    // A payload may pick between several events, as scan summaries do
+    for (const word of inner(text, open).match(/\b[A-Z][A-Z0-9_]+\b/g) ?? []) {
+      if (constants.has(word)) payloads.set(word, ts);
+    }
+  }
+  const events = names.map((constant) => {
+    if (!payloads.has(constant)) {
+      problems.push(`event ${constant} in events.rs has no AppEvent payload`);
+    }
+    return [constants.get(constant) ?? constant, payloads.get(constant)];
+  });
+  return { version: version?.[1] ?? "0", events };
+}
+
+// -------------------------------------------------------------------- output
+
+function renderArgs(args) {
+  if (args.length === 0) return "Record<string, never>";
+  const fields = args.map(({ key, optional, ts }) =>
+    optional ? `${quoteKey(key)}?: ${ts} | null` : `${quoteKey(key)}: ${ts}`,
+  );
+  return `{ ${fields.join("; ")} }`;
+}
+
+function render(commands, { version, events }) {
+  const commandLines = commands.map(
+    ({ name, args, result }) =>
+      `  ${name}: { args: ${renderArgs(args)}; result: ${result} };`,
+  );
+  const eventLines = events.map(
+    ([name, payload]) => `  ${quoteKey(name)}: ${payload};`,
+  );
+  renderPending();
+  const types = [...definitions.keys()]
+    .sort()
+    .map((name) => definitions.get(name));
+
+  return `// Generated by scripts/generate-bindings.mjs from the commands registered
+// in src-tauri/src/main.rs and the events in src-tauri/src/events.rs. Do not
+// edit by hand; run \`npm run bindings\`.
+import { tauriInvoke } from "$lib/utils/tauri";
+
+${types.join("\n\n")}
+
+/** SCHEMA_VERSION of the event payloads below */
+export const EVENT_SCHEMA_VERSION = ${version};
+
+/** Payload of every event the backend emits, by event name */
+export interface EventPayloads {
+${eventLines.join("\n")}
+}
+
+/** Arguments and result of every registered Tauri command */
+export interface Commands {
+${commandLines.join("\n")}
+}
+
+export type CommandName = keyof Commands;
+
+/** Invoke a command with its arguments and result typed by the backend. */
+export function invokeCommand<K extends CommandName>(
+  command: K,
+  ...args: Record<string, never> extends Commands[K]["args"]
+    ? [args?: Commands[K]["args"]]
+    : [args: Commands[K]["args"]]
+): Promise<Commands[K]["result"]> {
+  return tauriInvoke<Commands[K]["result"]>(command, args[0]);
+}
+`;
+}
+
+function main() {
+  const commands = registeredCommands().map(parseCommand);
+  const output = render(commands, parseEvents());
+  const target = relative(FRONTEND, OUTPUT);
+
+  if (problems.length > 0) {
+    console.error(`Cannot generate ${target}:`);
+    for (const problem of new Set(problems)) console.error(`  ${problem}`);
+    process.exit(1);
+  }
+
+  if (process.argv.includes("--check")) {
+    const current = existsSync(OUTPUT) ? readFileSync(OUTPUT, "utf8") : "";
+    if (current !== output) {
+      console.error(`${target} is out of date; run \`npm run bindings\``);
+      process.exit(1);
+    }
+    console.log(`${target} is up to date (${commands.length} commands)`);
+  } else {
+    writeFileSync(OUTPUT, output);
+    console.log(
+      `Wrote ${target}: ${commands.length} commands, ${definitions.size} types`,
+    );
+  }
+}
+
+// Run as a script, not when imported by the tests
+const invoked = process.argv[1] && resolve(process.argv[1]);
+if (invoked === fileURLToPath(import.meta.url)) main();
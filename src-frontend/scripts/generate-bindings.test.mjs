@@ -0,0 +1,125 @@
+// Tests for the serde attributes generate-bindings.mjs has to get right.
+// Run with `npm run test:bindings`.
+
+import assert from "node:assert/strict";
+import { mkdtempSync, writeFileSync } from "node:fs";
+import { tmpdir } from "node:os";
+import { join } from "node:path";
+import { test } from "node:test";
+import { renderTypes } from "./generate-bindings.mjs";
+
+/** Render `names` from a Rust file holding `source`. */
+function render(source, names) {
+  const file = join(mkdtempSync(join(tmpdir(), "bindings-")), "types.rs");
+  writeFileSync(file, source);
+  return renderTypes(file, names);
+}
+
+test("flattened fields are intersected", () => {
+  const types = render(
+    `
+    pub struct Query { pub text: String }
+
+    #[derive(Serialize)]
+    pub struct Page {
+        pub total_count: u32,
+        #[serde(flatten)]
+        pub query: Query,
+        // All of its fields or none of them
+        #[serde(flatten)]
+        pub previous: Option<Query>,
+    }
+
+    #[derive(Serialize)]
+    pub struct Only {
+        #[serde(flatten)]
+        pub query: Query,
+    }
+    `,
+    ["Page", "Only"],
+  );
+  assert.equal(
+    types.get("Page"),
+    "export type Page = {\n  total_count: number;\n} & Query & Partial<Query>;",
+  );
+  // Not `Record<string, never> & Query`, which would make every field never
+  assert.equal(types.get("Only"), "export type Only = Query;");
+});
+
+test("untagged variants are their bare content", () => {
+  const types = render(
+    `
+    #[derive(Serialize)]
+    #[serde(untagged)]
+    pub enum Reading {
+        Missing,
+        Count(u32),
+        Pair(u32, String),
+        Named { label: String },
+    }
+
+    pub struct Custom { pub name: String }
+
+    #[derive(Serialize)]
+    #[serde(tag = "kind", rename_all = "snake_case")]
+    pub enum Source {
+        Builtin,
+        #[serde(untagged)]
+        Other(Custom),
+    }
+    `,
+    ["Reading", "Source"],
+  );
+  assert.equal(
+    types.get("Reading"),
+    [
+      "export type Reading =",
+      "  | null",
+      "  | number",
+      "  | [number, string]",
+      "  | {",
+      "    label: string;",
+      "  };",
+    ].join("\n"),
+  );
+  assert.equal(
+    types.get("Source"),
+    'export type Source =\n  | { kind: "builtin" }\n  | Custom;',
+  );
+});
+
+test("rename_all_fields renames struct variant fields", () => {
+  const types = render(
+    `
+    #[derive(Serialize)]
+    #[serde(tag = "kind", rename_all = "snake_case", rename_all_fields = "camelCase")]
+    pub enum Action {
+        KillProcess { process_id: u32 },
+        // The variant's own rule wins
+        #[serde(rename_all = "kebab-case")]
+        OpenUrl { target_url: String },
+    }
+
+    #[derive(Serialize)]
+    #[serde(rename_all(serialize = "camelCase", deserialize = "snake_case"))]
+    pub struct Split { pub start_time: u64 }
+    `,
+    ["Action", "Split"],
+  );
+  assert.equal(
+    types.get("Action"),
+    [
+      "export type Action =",
+      '  | ({ kind: "kill_process" } & {',
+      "    processId: number;",
+      "  })",
+      '  | ({ kind: "open_url" } & {',
+      '    "target-url": string;',
+      "  });",
+    ].join("\n"),
+  );
+  assert.equal(
+    types.get("Split"),
+    "export type Split = {\n  startTime: number;\n};",
+  );
+});
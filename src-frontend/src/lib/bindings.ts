@@ -0,0 +1,2689 @@
+// Generated by scripts/generate-bindings.mjs from the commands registered
+// in src-tauri/src/main.rs and the events in src-tauri/src/events.rs. Do not
+// edit by hand; run `npm run bindings`.
+import { tauriInvoke } from "$lib/utils/tauri";
+
+export type AIProvidersConfig = {
+  google: GoogleConfig | null;
+  openai: OpenAIConfig | null;
+  anthropic: AnthropicConfig | null;
+  azure_openai: AzureOpenAIConfig | null;
+  ollama: OllamaConfig | null;
+};
+
+export type ActionStatus =
+  | "pending"
+  | "rejected"
+  | "succeeded"
+  | "failed";
+
+export type ActionType =
+  | { MouseMove: {
+    from_x: number;
+    from_y: number;
+    to_x: number;
+    to_y: number;
+  } }
+  | { MouseClick: {
+    x: number;
+    y: number;
+    button: string;
+  } }
+  | { KeyboardType: {
+    text: string;
+  } }
+  | { FileWrite: {
+    path: string;
+    content_hash: string;
+  } }
+  | { FileDelete: {
+    path: string;
+    content: number[];
+  } }
+  | { SystemCommand: {
+    command: string;
+  } }
+  | { FileMove: {
+    from: string;
+    to: string;
+  } }
+  | { ClipboardSet: {
+    previous: string | null;
+  } }
+  | { WindowState: {
+    window_id: string;
+    before: WindowPlacement;
+    after: WindowPlacement;
+  } };
+
+export type AgentMemoryEntry = {
+  id: string;
+  agent_type: AgentType;
+  content: string;
+  timestamp: string;
+  source: MemorySource;
+  metadata?: unknown;
+};
+
+export type AgentMemoryPage = {
+  items: AgentMemoryEntry[];
+  total: number;
+  offset: number;
+  limit: number;
+};
+
+export type AgentType =
+  | "guardian"
+  | "copilot";
+
+export type AlertSinkConfig =
+  | ({ type: "syslog" } & {
+    address: string;
+    protocol?: SyslogProtocol;
+    facility?: number;
+    min_severity?: string;
+  })
+  | ({ type: "event_log" } & {
+    source?: string;
+    min_severity?: string;
+  })
+  | ({ type: "email" } & {
+    host: string;
+    port?: number;
+    tls?: SmtpTls;
+    username?: string;
+    from: string;
+    to: string[];
+    digest_minutes?: number;
+    min_severity?: string;
+  });
+
+export type AnthropicConfig = {
+  api_key: string;
+};
+
+export type ApiLimitConfig = {
+  requests_per_minute?: number;
+  max_concurrent?: number;
+  daily_quota?: number;
+};
+
+export type ApiLimitsConfig = {
+  default?: ApiLimitConfig;
+  hosts?: Record<string, ApiLimitConfig>;
+};
+
+export type ApiQuotaStatus = {
+  host: string;
+  requests_per_minute: number | null;
+  max_concurrent: number | null;
+  daily_quota: number | null;
+  used_today: number;
+  remaining_today: number | null;
+  requests_last_minute: number;
+  in_flight: number;
+  throttled: number;
+  rejected: number;
+  resets_at: string;
+};
+
+export type AppliedOptimization = {
+  outcome: string;
+} & Optimization;
+
+export type Artifact = {
+  url: string;
+  sha256: string;
+  size: number;
+};
+
+export type AttackMatrixSummary = {
+  detections: number;
+  unmapped: number;
+  tactics: TacticSummary[];
+};
+
+export type AuditEntry = {
+  id: string;
+  timestamp: string;
+  action: string;
+  permission: Permission;
+  user_confirmed: boolean;
+  success: boolean;
+  error: string | null;
+  metadata: unknown;
+};
+
+export type AuditStats = {
+  total: number;
+  successful: number;
+  failed: number;
+  confirmed: number;
+};
+
+export type AuthStatus =
+  | "success"
+  | "error";
+
+export type AvProduct = {
+  name: string;
+  realtime_enabled: boolean;
+  definitions_up_to_date: boolean | null;
+  is_defender: boolean;
+};
+
+export type AvailableUpdate = {
+  version: string;
+  channel: UpdateChannel;
+  pub_date: string;
+  notes: VersionNotes[];
+  download: Artifact;
+  delta: boolean;
+};
+
+export type AzureOpenAIConfig = {
+  api_key: string;
+  endpoint: string;
+};
+
+export type BackendSearchItem = {
+  text: string;
+  score: number;
+  source: string | null;
+  meta: unknown | null;
+};
+
+export type BackupInfo = {
+  path: string;
+  size_bytes: number;
+  created_at: string;
+  duration_ms: number;
+};
+
+export type BaselineSnapshot = {
+  taken_at: string;
+  startup_items: Record<string, string>;
+  services: Record<string, string>;
+  hosts: Record<string, string>;
+  browser_settings: Record<string, string>;
+};
+
+export type BatteryReading = {
+  percent: number;
+  charging: boolean;
+};
+
+export type BatteryStatus = {
+  percent: number;
+  charging: boolean;
+};
+
+export type BenchmarkReport = {
+  started_at: string;
+  duration_ms: number;
+  embedding_dimension: number;
+  results: BenchmarkResult[];
+  all_targets_met: boolean;
+};
+
+export type BenchmarkResult = {
+  operation: string;
+  corpus_size: number;
+  iterations: number;
+  mean_ms: number;
+  p95_ms: number;
+  throughput_per_sec: number;
+  target: string;
+  met: boolean;
+};
+
+export type BootImpactReport = {
+  boot_time: string;
+  time_to_desktop_secs: number | null;
+  desktop_process: string | null;
+  window_secs: number;
+  measured: boolean;
+  capture_started_after_secs: number | null;
+  items: StartupImpactEntry[];
+  top_processes: ProcessBootUsage[];
+  generated_at: string;
+};
+
+export type CacheStats = {
+  enabled: boolean;
+  entries: number;
+  bytes: number;
+  hits: number;
+  misses: number;
+};
+
+export type Capabilities = {
+  input_devices: string[];
+  gpus: string[];
+  local_runtimes: LocalRuntime[];
+  keyring: boolean;
+};
+
+export type Capability = {
+  feature: Feature;
+  state: CapabilityState;
+  compiled: boolean;
+  reason: string | null;
+  updated_at: string | null;
+};
+
+export type CapabilityState =
+  | "available"
+  | "degraded"
+  | "unavailable";
+
+export type CheckStatus =
+  | "skipped"
+  | "pass"
+  | "warn"
+  | "fail";
+
+export type Checkpoint = {
+  id: string;
+  label: string;
+  created_at: string;
+};
+
+export type ClassMetrics = {
+  precision: number;
+  recall: number;
+  support: number;
+};
+
+export type CleanupCandidates = {
+  dir: string;
+  older_than_days: number;
+  files: number;
+  bytes: number;
+  largest: FileUsage[];
+};
+
+export type CleanupCategory =
+  | "temp"
+  | "browser"
+  | "package_manager";
+
+export type CleanupConfig = {
+  undo_retention_days?: number;
+  temp_min_age_days?: number;
+  exclude?: string[];
+};
+
+export type CleanupItem = {
+  id: string;
+  category: CleanupCategory;
+  name: string;
+  path: string;
+  min_age_days: number;
+  files: number;
+  bytes: number;
+};
+
+export type CleanupPreview = {
+  generated_at: string;
+  items: CleanupItem[];
+  total_bytes: number;
+};
+
+export type CleanupRun = {
+  files: StagedFile[];
+} & CleanupRunSummary;
+
+export type CleanupRunItem = {
+  id: string;
+  category: CleanupCategory;
+  name: string;
+  files: number;
+  bytes: number;
+  failed: number;
+};
+
+export type CleanupRunSummary = {
+  id: string;
+  executed_at: string;
+  expires_at: string;
+  items: CleanupRunItem[];
+  reclaimed_bytes: number;
+  failed: number;
+};
+
+export type CogneeConfig = {
+  enabled: boolean;
+  url: string;
+  token: EncryptedData | null;
+};
+
+export type CompactionReport = {
+  removed_metrics: number;
+  rebuilt_indexes: string[];
+  duration_ms: number;
+};
+
+export type ComponentFailure = {
+  component: string;
+  error: string;
+};
+
+export type ConfigIssue = {
+  field: string;
+  message: string;
+  severity: ConfigIssueSeverity;
+};
+
+export type ConfigIssueSeverity =
+  | "error"
+  | "warning";
+
+export type ConfirmationRequest = {
+  id: string;
+  action: string;
+  permission: Permission;
+  risk_level: RiskLevel;
+  description: string;
+  metadata: unknown;
+  timeout_seconds: number;
+};
+
+export type ConsensusConfig = {
+  timeout_secs?: number;
+  persist_runs?: boolean;
+  providers?: Record<string, ConsensusProviderConfig>;
+};
+
+export type ConsensusProviderConfig = {
+  enabled?: boolean;
+  weight?: number;
+  model?: string;
+  timeout_secs?: number;
+};
+
+export type ConsensusRunEntry = {
+  id: string;
+  timestamp: string;
+  risk_score: number;
+  confidence: number;
+  providers: string[];
+  provider_reports: unknown[];
+  report: unknown;
+};
+
+export type ConsolidationReport = {
+  examined: number;
+  summaries: number;
+  consolidated: number;
+  errors: string[];
+  duration_ms: number;
+};
+
+export type ConsolidationRetention =
+  | "archive"
+  | "delete";
+
+export type ContextBudgetConfig = {
+  default_tokens?: number;
+  provider_tokens?: Record<string, number>;
+  min_relevance?: number;
+};
+
+export type CopilotConfig = {
+  enabled: boolean;
+  wake_word: string;
+  share_active_window?: boolean;
+  share_clipboard?: boolean;
+};
+
+export type CrashKind =
+  | "panic"
+  | "native";
+
+export type CrashReport = {
+  id: string;
+  timestamp: string;
+  version: string;
+  kind: CrashKind;
+  message: string;
+  location: string | null;
+  thread: string | null;
+  error_code: string | null;
+  backtrace: string;
+  recent_logs: LogEntry[];
+  system: SystemInfo;
+  minidump: string | null;
+  recorded?: boolean;
+  uploaded_at?: string;
+};
+
+export type CrashReportConfig = {
+  upload_url?: string;
+};
+
+export type CrashSummary = {
+  id: string;
+  timestamp: string;
+  kind: CrashKind;
+  message: string;
+  uploaded_at: string | null;
+};
+
+export type CredentialState =
+  | "missing"
+  | "configured"
+  | "expired"
+  | "error";
+
+export type CredentialStatus = {
+  provider: string;
+  state: CredentialState;
+  detail: string;
+};
+
+export type DeliveryResult = {
+  endpoint: string;
+  attempts: number;
+  status: number | null;
+  error: string | null;
+};
+
+export type DeviceAuthStart = {
+  device_code: string;
+  user_code: string;
+  verification_uri: string;
+  expires_in: number;
+  interval: number | null;
+};
+
+export type DiagnosticCheck = {
+  name: string;
+  status: CheckStatus;
+  detail: string;
+};
+
+export type DirNode = {
+  name: string;
+  path: string;
+  bytes: number;
+  files: number;
+  other_bytes: number;
+  children: DirNode[];
+};
+
+export type DirUsage = {
+  path: string;
+  bytes: number;
+  files: number;
+};
+
+export type DiskIO = {
+  read_mb_per_sec: number;
+  write_mb_per_sec: number;
+  iops: number;
+};
+
+export type DiskUsageReport = {
+  root: string;
+  scanned_at: string;
+  duration_ms: number;
+  volume: VolumeUsage | null;
+  total_bytes: number;
+  files: number;
+  directories: number;
+  skipped: number;
+  truncated: boolean;
+  tree: DirNode;
+  top_directories: DirUsage[];
+  largest_files: FileUsage[];
+  stale_temp_files: CleanupCandidates | null;
+  old_downloads: CleanupCandidates | null;
+  suggestions: Optimization[];
+};
+
+export type DriftCategory =
+  | "startup_item"
+  | "service"
+  | "hosts_file"
+  | "browser_setting";
+
+export type DriftChange =
+  | "added"
+  | "removed"
+  | "changed";
+
+export type DriftItem = {
+  category: DriftCategory;
+  key: string;
+  change: DriftChange;
+  baseline: string | null;
+  current: string | null;
+};
+
+export type DriftReport = {
+  baseline_taken_at: string;
+  checked_at: string;
+  drift: DriftItem[];
+};
+
+export type EmailTestResult = {
+  host: string;
+  recipients: string[];
+  error: string | null;
+};
+
+export type EncryptedData = {
+  ciphertext: string;
+  nonce: string;
+  associated_data: string | null;
+};
+
+export type ErrorResponse = {
+  error_type: string;
+  message: string;
+  severity: ErrorSeverity;
+  timestamp: string;
+  context: unknown | null;
+  recovery_suggestions: string[];
+};
+
+export type ErrorSeverity =
+  | "Low"
+  | "Medium"
+  | "High"
+  | "Critical";
+
+export type ErrorStats = {
+  error_counts: Record<string, number>;
+  total_errors: number;
+  recent_errors_count: number;
+  last_error: ErrorResponse | null;
+};
+
+export type EventSchema = {
+  version: number;
+  events: string[];
+};
+
+export type EvidenceBundle = {
+  archive_path: string;
+  manifest_path: string;
+  manifest: EvidenceManifest;
+};
+
+export type EvidenceManifest = {
+  threat_id: string;
+  created_at: string;
+  host: string;
+  encrypted: boolean;
+  files: ManifestEntry[];
+  warnings: string[];
+};
+
+export type EvidenceProgress = {
+  threat_id: string;
+  stage: EvidenceStage;
+  percent: number;
+};
+
+export type EvidenceStage =
+  | "process"
+  | "hashes"
+  | "network"
+  | "archive"
+  | "encrypt"
+  | "done";
+
+export type ExternalVerdict = {
+  malicious: boolean;
+  engine_detections: ([string, string])[];
+  reference: string | null;
+};
+
+export type Feature =
+  | "yara"
+  | "vector_search"
+  | "cloud_llm"
+  | "voice"
+  | "rpa";
+
+export type FileHashes = {
+  sha256: string;
+  blake3: string;
+};
+
+export type FileScanReport = {
+  path: string;
+  size: number;
+  hashes: FileHashes;
+  local_match: string | null;
+  external_verdict: ExternalVerdict | null;
+  malicious: boolean;
+  risk_score: number;
+};
+
+export type FileUsage = {
+  path: string;
+  bytes: number;
+  modified: string | null;
+};
+
+export type FirewallBackendKind =
+  | "windows_firewall"
+  | "nftables"
+  | "iptables";
+
+export type FirewallRule = {
+  id: string;
+  program: string | null;
+  pids?: number[];
+  backend: FirewallBackendKind;
+  reason: string | null;
+  created_at: string;
+  original_cgroups?: ([number, string])[];
+};
+
+export type FleetUplinkConfig = {
+  enabled?: boolean;
+  url: string;
+  username?: string;
+  database_user?: boolean;
+  namespace?: string;
+  database?: string;
+  machine_id?: string;
+  batch_size?: number;
+  flush_interval_secs?: number;
+  max_queued?: number;
+};
+
+export type FolderScanProgress = {
+  scan_id: string;
+  discovered?: number;
+  filtered?: number;
+  scanned?: number;
+  total?: number;
+  malicious?: number;
+  errors?: number;
+  cache_hits?: number;
+  current_file?: string;
+  local_match?: string;
+  external_verdict?: ExternalVerdict;
+  rate?: ScanRate;
+  worker_utilization?: number[];
+  error?: string;
+};
+
+export type FolderScanStarted = {
+  scan_id: string;
+  root: string;
+  network: boolean;
+  latency_ms: number | null;
+  workers: number;
+};
+
+export type FolderScanSummary = {
+  scan_id: string;
+  scanned: number;
+  total: number;
+  malicious: number;
+  errors: number;
+  duration_ms: number;
+  filtered?: number;
+  bytes_scanned?: number;
+  worker_utilization?: number[];
+  cache?: ScanCacheStats;
+};
+
+export type GoogleAuthComplete = {
+  status: AuthStatus;
+  provider: string;
+  timestamp?: number;
+  message?: string;
+};
+
+export type GoogleConfig = {
+  api_key: string;
+  model?: string;
+  vision_model?: string;
+  api_version?: string;
+  max_retries?: number;
+};
+
+export type GpuMetrics = {
+  name: string;
+  utilization_percent: number | null;
+  vram_used_mb: number | null;
+  vram_total_mb: number | null;
+  temperature_c: number | null;
+};
+
+export type GpuReading = {
+  name: string;
+  utilization_percent: number | null;
+  vram_used_mb: number | null;
+  vram_total_mb: number | null;
+  temperature_c: number | null;
+};
+
+export type GrantEvent =
+  | ({ kind: "granted" } & {
+    grant: PermissionGrant;
+  })
+  | ({ kind: "revoked" } & {
+    grant: PermissionGrant;
+    reason: RevocationReason;
+  });
+
+export type GuardianConfig = {
+  enabled: boolean;
+  monitor_interval_secs: number;
+  antivirus_enabled: boolean | null;
+  signatures_path: string | null;
+  quarantine_dir: string | null;
+  max_file_size_mb: number | null;
+  virustotal_api_key: EncryptedData | null;
+  hybrid_analysis_api_key: EncryptedData | null;
+  game_booster_enabled: boolean | null;
+  vt_cache_ttl_secs: number | null;
+  vt_cache_max_entries: number | null;
+  folder_scan_max_workers: number | null;
+  folder_scan_max_depth: number | null;
+  scan_cache_ttl_hours?: number;
+  network_scan_timeout_secs?: number;
+  yara_enabled: boolean | null;
+  yara_rules_paths: string[] | null;
+  heuristic_rules_path?: string;
+  baseline_check_interval_secs?: number;
+  integrity_check_interval_secs?: number;
+  playbooks_dir?: string;
+  alert_sinks?: AlertSinkConfig[];
+  scan_filter?: ScanFilterConfig;
+  leak_detection?: LeakDetectionConfig;
+  av_coexistence_tuning?: boolean;
+};
+
+export type GuardianStatus = {
+  status: string;
+  timestamp?: string;
+  cpu_usage?: number;
+  memory_usage?: MemoryUsage;
+  disk_io?: DiskIO;
+  network_stats?: NetworkStats;
+  message?: string;
+};
+
+export type HardwareReadings = {
+  gpus: GpuReading[];
+  cpu_temperature_c: number | null;
+  battery: BatteryReading | null;
+};
+
+export type HighCpuProcessesResponse = {
+  processes: unknown[];
+  count: number;
+};
+
+export type HistogramBucket = {
+  le_ms: number | null;
+  count: number;
+};
+
+export type HotkeyAction =
+  | "push_to_talk"
+  | "screenshot_question"
+  | "panic_stop";
+
+export type HotkeyConfig = {
+  enabled?: boolean;
+  push_to_talk?: string;
+  screenshot_question?: string;
+  panic_stop?: string;
+};
+
+export type HotkeyStatus = {
+  action: HotkeyAction;
+  accelerator: string;
+  registered: boolean;
+  error: string | null;
+};
+
+export type HotkeyTriggered = HotkeyAction;
+
+export type HourlyMetricsRow = {
+  avg_cpu: number;
+  peak_cpu: number;
+  avg_mem_percent: number;
+  hour_bucket: string;
+  samples: number;
+};
+
+export type ImageAttachment = {
+  mime_type: string;
+  data: string;
+};
+
+export type IntegrityEntry = {
+  kind: ProtectedKind;
+  state: PathState;
+};
+
+export type IntegrityManifest = {
+  recorded_at: string;
+  version: string;
+  entries: Record<string, IntegrityEntry>;
+};
+
+export type IntegrityReport = {
+  manifest_recorded_at: string;
+  checked_at: string;
+  protected: ProtectedPath[];
+  findings: TamperFinding[];
+};
+
+export type IocImportReport = {
+  source: string;
+  imported: number;
+  replaced: number;
+  skipped: number;
+  expired: number;
+  by_kind: Record<string, number>;
+  total: number;
+};
+
+export type IssuedMcpToken = {
+  token: McpToken;
+  secret: string;
+};
+
+export type JobInfo = {
+  job_id: string;
+  kind: string;
+  state: JobState;
+  stage: string | null;
+  progress: number | null;
+  created_at: string;
+  started_at: string | null;
+  finished_at: string | null;
+  result: unknown | null;
+  error: string | null;
+};
+
+export type JobState =
+  | "queued"
+  | "running"
+  | "completed"
+  | "failed"
+  | "cancelled";
+
+export type LabelSource =
+  | "analyst"
+  | "resolution"
+  | "consensus";
+
+export type LeakDetectionConfig = {
+  enabled?: boolean;
+  check_interval_mins?: number;
+  window_hours?: number;
+  min_duration_hours?: number;
+  min_growth_mb_per_hour?: number;
+  min_fit?: number;
+};
+
+export type LlmAuditConfig = {
+  enabled?: boolean;
+  retention_days?: number;
+};
+
+export type LlmAuditEntry = {
+  id?: string;
+  timestamp: string;
+  provider: string;
+  model?: string;
+  purpose: string;
+  prompt: string;
+  response?: string;
+  error?: string;
+  duration_ms: number;
+};
+
+export type LlmAuditQuery = {
+  provider?: string;
+  model?: string;
+  purpose?: string;
+  since?: string;
+  until?: string;
+  limit?: number;
+};
+
+export type LlmCacheConfig = {
+  enabled?: boolean;
+  ttl_secs?: number;
+  max_entries?: number;
+  max_bytes?: number;
+  max_temperature?: number;
+};
+
+export type LocalApiConfig = {
+  enabled: boolean;
+  port: number;
+  token: EncryptedData | null;
+};
+
+export type LocalApiStatus = {
+  running: boolean;
+  base_url?: string;
+};
+
+export type LocalLlmServerStatus = {
+  running: boolean;
+  port: number | null;
+  message: string | null;
+};
+
+export type LocalRuntime = {
+  name: string;
+  installed: boolean;
+  running: boolean;
+  endpoint: string;
+};
+
+export type Locale =
+  | "en"
+  | "es";
+
+export type LocaleInfo = {
+  locale: Locale;
+  detected: Locale | null;
+  available: LocaleOption[];
+};
+
+export type LocaleOption = {
+  code: Locale;
+  name: string;
+};
+
+export type LogEntry = {
+  timestamp: string;
+  level: string;
+  module: string;
+  message: string;
+};
+
+export type MaintenanceProgress = {
+  operation: string;
+  stage: string;
+  percent: number;
+};
+
+export type ManifestEntry = {
+  name: string;
+  size: number;
+  sha256: string;
+};
+
+export type McpConfig = {
+  enabled: boolean;
+  port: number;
+  password: EncryptedData | null;
+  tls?: McpTlsConfig;
+  allow_remote?: boolean;
+};
+
+export type McpScope =
+  | "read_only"
+  | "tool_execution";
+
+export type McpStatus = {
+  running: boolean;
+  addr?: string;
+  url?: string;
+  tls?: TlsStatus;
+  password_enabled?: boolean;
+  active_tokens?: number;
+  streaming_enabled?: boolean;
+};
+
+export type McpTlsConfig = {
+  enabled: boolean;
+  cert_path: string | null;
+  key_path: string | null;
+};
+
+export type McpToken = {
+  id: string;
+  client: string;
+  scope: McpScope;
+  created_at: string;
+  expires_at: string;
+  rotated_at: string | null;
+  revoked_at: string | null;
+  last_used_at: string | null;
+};
+
+export type MemoryBackendKind =
+  | "json"
+  | "surreal";
+
+export type MemoryChunkingConfig = {
+  enabled?: boolean;
+  chunk_size?: number;
+  chunk_overlap?: number;
+  sentence_aware?: boolean;
+};
+
+export type MemoryConsolidationConfig = {
+  enabled?: boolean;
+  interval_hours?: number;
+  min_age_days?: number;
+  similarity_threshold?: number;
+  max_cluster_size?: number;
+  originals?: ConsolidationRetention;
+};
+
+export type MemoryExportFilter = {
+  agent_type?: AgentType;
+  since?: string;
+  until?: string;
+  limit?: number;
+};
+
+export type MemoryListFilter = {
+  agent_type?: AgentType;
+  source?: MemorySource;
+  since?: string;
+  until?: string;
+  sort?: MemorySort;
+};
+
+export type MemoryProcessSample = {
+  timestamp: string;
+  name: string;
+  instances: number;
+  cpu_percent: number;
+  memory_mb: number;
+};
+
+export type MemoryScanReport = {
+  pid: number;
+  process_name: string | null;
+  matched_rules: string[];
+  duration_ms: number;
+  scanned_at: string;
+};
+
+export type MemorySearchResponse = {
+  results: BackendSearchItem[];
+  count: number;
+};
+
+export type MemorySort =
+  | "newest_first"
+  | "oldest_first";
+
+export type MemorySource =
+  | "system_log"
+  | "user_query"
+  | "threat_report"
+  | "performance_analysis";
+
+export type MemoryStats = {
+  total_entries: number;
+  total_patterns: number;
+  storage_path: string;
+  max_entries: number;
+};
+
+export type MemoryThreatSeverity =
+  | "low"
+  | "medium"
+  | "high"
+  | "critical";
+
+export type MemoryTransferReport = {
+  path: string;
+  records: number;
+  re_embedded: number;
+  skipped: number;
+  errors: string[];
+  duration_ms: number;
+};
+
+export type MemoryUsage = {
+  total_mb: number;
+  used_mb: number;
+  available_mb: number;
+  percent: number;
+};
+
+export type MetricsResponse = {
+  metrics: SystemMetric[];
+  count: number;
+};
+
+export type MetricsSummaryResponse = {
+  avg_cpu: number;
+  max_cpu: number;
+  avg_memory_percent: number;
+  max_memory_percent: number;
+  sample_count: number;
+  window_start: string | null;
+  window_end: string | null;
+};
+
+export type MigrationReport = {
+  migration_id: string;
+  memories: number;
+  patterns: number;
+  unembedded: number;
+  duration_ms: number;
+};
+
+export type MitigationStatus =
+  | "detected"
+  | "quarantined"
+  | "deleted"
+  | "whitelisted"
+  | "investigating";
+
+export type ModelEvaluation = {
+  evaluated_at: string;
+  samples: number;
+  evaluated: number;
+  accuracy: number;
+  per_class: Record<string, ClassMetrics>;
+  predictor: string;
+};
+
+export type NetworkConfig = {
+  proxy_url?: string;
+  proxy_username?: string;
+  no_proxy?: string[];
+  ca_cert_paths?: string[];
+};
+
+export type NetworkInfo = {
+  total_bytes_received: number;
+  total_bytes_transmitted: number;
+  active_interfaces: number;
+};
+
+export type NetworkStats = {
+  sent_mb_per_sec: number;
+  recv_mb_per_sec: number;
+  connections_active: number;
+};
+
+export type NetworkStatus = {
+  offline_mode: boolean;
+  blocked_requests: number;
+  last_blocked: string | null;
+  changed_at: string | null;
+  proxy: string | null;
+  custom_ca_certificates: number;
+};
+
+export type OcrResult = {
+  text: string;
+  words: OcrWord[];
+  confidence: number;
+};
+
+export type OcrWord = {
+  text: string;
+  confidence: number;
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+};
+
+export type OllamaConfig = {
+  url: string;
+};
+
+export type OnboardingState = {
+  finished: boolean;
+  current_step: OnboardingStep | null;
+  steps: StepState[];
+  capabilities: Capabilities;
+  credentials: CredentialStatus[];
+  recommended_config: OxidePilotConfig;
+  notes: string[];
+  config: OxidePilotConfig | null;
+};
+
+export type OnboardingStep =
+  | "welcome"
+  | "capabilities"
+  | "credentials"
+  | "ai_provider"
+  | "voice"
+  | "guardian"
+  | "finish";
+
+export type OpenAIConfig = {
+  api_key: string;
+  model?: string;
+  temperature?: number;
+};
+
+export type OperationProfile = {
+  name: string;
+  total_calls: number;
+  window_size: number;
+  min_ms: number;
+  max_ms: number;
+  mean_ms: number;
+  p50_ms: number;
+  p95_ms: number;
+  p99_ms: number;
+  histogram: HistogramBucket[];
+};
+
+export type Optimization = {
+  action: OptimizationAction;
+  target: string;
+  reason: string;
+  reclaimable_mb: number;
+};
+
+export type OptimizationAction =
+  | ({ type: "trim_working_set" } & {
+    pid: number;
+  })
+  | ({ type: "lower_priority" } & {
+    pid: number;
+  })
+  | ({ type: "clear_temp_files" } & {
+    dir: string;
+    older_than_days: number;
+  })
+  | ({ type: "close_idle_process" } & {
+    pid: number;
+  })
+  | ({ type: "review_old_downloads" } & {
+    dir: string;
+    older_than_days: number;
+  })
+  | ({ type: "review_large_file" } & {
+    path: string;
+  });
+
+export type OptimizationReport = {
+  applied: AppliedOptimization[];
+  suggested: Optimization[];
+  failed: AppliedOptimization[];
+};
+
+export type OrchestratorConfig = {
+  roles?: Record<string, OrchestratorRoleConfig>;
+};
+
+export type OrchestratorRoleConfig = {
+  provider: string;
+  model?: string;
+  temperature: number;
+  max_tokens?: number;
+};
+
+export type OxidePilotConfig = {
+  guardian: GuardianConfig;
+  copilot: CopilotConfig;
+  ai_providers: AIProvidersConfig;
+  cognee: CogneeConfig | null;
+  surreal: SurrealDbConfig | null;
+  mcp: McpConfig | null;
+  local_api?: LocalApiConfig;
+  webhooks?: WebhooksConfig;
+  privacy: PrivacyConfig | null;
+  offline_mode: boolean | null;
+  network?: NetworkConfig;
+  consensus: ConsensusConfig | null;
+  orchestrator?: OrchestratorConfig;
+  api_limits?: ApiLimitsConfig;
+  profile?: ProfileConfig;
+  suggestions?: SuggestionsConfig;
+  llm_cache?: LlmCacheConfig;
+  context_budget?: ContextBudgetConfig;
+  hotkeys?: HotkeyConfig;
+  locale?: string;
+  voice?: VoiceConfig;
+  prompts?: PromptConfig;
+  llm_audit?: LlmAuditConfig;
+  updates?: UpdatesConfig;
+  crash_reports?: CrashReportConfig;
+  cleanup?: CleanupConfig;
+  power?: PowerConfig;
+  security?: SecurityConfig;
+};
+
+export type PanicStopReport = {
+  rpa_halted: boolean;
+  cancelled_jobs: string[];
+};
+
+export type PathState =
+  | { state: "missing" }
+  | { state: "present" }
+  | ({ state: "file" } & {
+    sha256: string;
+  })
+  | ({ state: "directory" } & {
+    entries: string[];
+  });
+
+export type PatternType =
+  | "ApplicationUsage"
+  | "TimeBasedActivity"
+  | "CommandSequence"
+  | "ErrorResolution"
+  | "SystemConfiguration"
+  | "TopicInterest";
+
+export type PerformanceMetrics = {
+  cpu_usage_percent: number;
+  memory_usage_mb: number;
+  memory_usage_percent: number;
+  active_threads: number;
+  uptime_seconds: number;
+  api_calls_count: number;
+  avg_response_time_ms: number;
+  cache_hit_rate: number;
+};
+
+export type PerformanceReport = {
+  hardware: HardwareReadings;
+  core_imbalance: unknown[];
+} & PerformanceMetrics;
+
+export type Permission =
+  | "MouseMove"
+  | "MouseClick"
+  | "MouseScroll"
+  | "MouseDrag"
+  | "KeyboardType"
+  | "KeyboardPress"
+  | "KeyboardHotkey"
+  | "ScreenCapture"
+  | "ScreenCaptureArea"
+  | "ScreenAnalyze"
+  | "ClipboardWrite"
+  | "WindowManage"
+  | "WindowClose"
+  | "FileRead"
+  | "FileWrite"
+  | "FileDelete"
+  | "SystemCommand"
+  | "ProcessControl"
+  | "NetworkAccess";
+
+export type PermissionGrant = {
+  id: string;
+  profile: PermissionProfile | null;
+  permissions: Permission[];
+  applications: string[];
+  granted_at: string;
+  expires_at: string | null;
+};
+
+export type PermissionProfile =
+  | "input_only"
+  | "screen_read_only"
+  | "full";
+
+export type PlanExecutionReport = {
+  steps: PlanProgress[];
+  completed: number;
+  failed: number;
+  awaiting_confirmation: number | null;
+  timed_out: boolean;
+  cancelled?: boolean;
+  duration_ms: number;
+};
+
+export type PlanProgress = {
+  step: number;
+  total_steps: number;
+  description: string;
+  function: string | null;
+  status: PlanStepStatus;
+  output?: unknown;
+  error?: string;
+};
+
+export type PlanStepStatus =
+  | "running"
+  | "completed"
+  | "failed"
+  | "skipped"
+  | "awaiting_confirmation"
+  | "blocked"
+  | "timed_out";
+
+export type Playbook = {
+  id: string;
+  name?: string;
+  description?: string;
+  enabled?: boolean;
+  auto_run?: boolean;
+  trigger?: Trigger;
+  steps: PlaybookStep[];
+};
+
+export type PlaybookLibrary = {
+  dir: string;
+  playbooks: Playbook[];
+  errors: string[];
+};
+
+export type PlaybookRun = {
+  playbook_id: string;
+  threat_id: string | null;
+  dry_run: boolean;
+  started_at: string;
+  finished_at: string;
+  steps: StepResult[];
+};
+
+export type PlaybookStep =
+  | ({ action: "notify" } & {
+    message?: string;
+  })
+  | { action: "isolate" }
+  | { action: "scan" }
+  | { action: "collect_evidence" }
+  | ({ action: "summarize" } & {
+    prompt?: string;
+  });
+
+export type PollResult = {
+  status: string;
+  message: string | null;
+};
+
+export type PowerConfig = {
+  battery_aware?: boolean;
+  activate_below_percent?: number;
+  metrics_interval_multiplier?: number;
+  defer_scheduled_scans?: boolean;
+  pause_cloud_consensus?: boolean;
+};
+
+export type PowerPolicy = {
+  source: PowerSource;
+  battery_percent: number | null;
+  battery_saver: boolean;
+  metrics_interval_multiplier: number;
+  defer_scheduled_scans: boolean;
+  cloud_consensus_paused: boolean;
+  changed_at: string | null;
+};
+
+export type PowerSource =
+  | "ac"
+  | "battery"
+  | "unknown";
+
+export type PrivacyConfig = {
+  enabled?: boolean;
+  redact_paths?: boolean;
+  redact_hostnames?: boolean;
+  redact_ips?: boolean;
+  redact_usernames?: boolean;
+  extra_terms?: string[];
+};
+
+export type ProcessBootUsage = {
+  name: string;
+  cpu_seconds: number;
+  disk_read_bytes: number;
+  disk_written_bytes: number;
+};
+
+export type ProcessHotspot = {
+  name: string;
+  avg_cpu: number;
+  peak_cpu: number;
+  avg_memory_mb: number;
+  samples: number;
+};
+
+export type ProcessIdentity = {
+  name?: string;
+  exe?: string;
+  start_time?: number;
+};
+
+export type ProcessInfo = {
+  name: string;
+  pid: string;
+  cpu_usage: number;
+  memory_mb: number;
+  command: string;
+  is_suspicious: boolean;
+  suspicion_reasons: string[];
+};
+
+export type ProcessSample = {
+  name: string;
+  args?: string;
+  path?: string;
+  parent?: string;
+  signer?: string;
+};
+
+export type ProcessTrend = {
+  name: string;
+  samples: MemoryProcessSample[];
+  memory_mb_per_hour: number | null;
+  cpu_percent_per_hour: number | null;
+};
+
+export type ProfileConfig = {
+  name?: string;
+  per_os_user?: boolean;
+};
+
+export type ProfilesInfo = {
+  active: string;
+  profiles: string[];
+};
+
+export type PromptConfig = {
+  directory?: string;
+  templates?: Record<string, PromptOverride>;
+};
+
+export type PromptOverride = {
+  text: string;
+  version?: number;
+};
+
+export type PromptSource =
+  | "builtin"
+  | "config"
+  | "file";
+
+export type PromptTemplate = {
+  name: string;
+  version: number;
+  source: PromptSource;
+  path: string | null;
+  variables: string[];
+  text: string;
+};
+
+export type ProtectedKind =
+  | "binary"
+  | "config"
+  | "signatures"
+  | "quarantine"
+  | "evidence"
+  | "audit_database";
+
+export type ProtectedPath = {
+  kind: ProtectedKind;
+  path: string;
+};
+
+export type ProtectionState =
+  | "protected"
+  | "attention_required"
+  | "at_risk";
+
+export type ProtectionStatus = {
+  state: ProtectionState;
+  issues: string[];
+  monitoring_enabled: boolean;
+  realtime_monitoring_active: boolean;
+  last_monitor_cycle: string | null;
+  yara_available: boolean;
+  virustotal_configured: boolean;
+  signatures_path: string | null;
+  signature_count: number | null;
+  signature_age_hours: number | null;
+  background_tasks: TaskHealth[];
+  antivirus_products: AvProduct[];
+  av_adjustments: string[];
+  power: PowerPolicy;
+  last_scan_at: string | null;
+  unacknowledged_threats: number | null;
+  oldest_unacknowledged_threat_at: string | null;
+  checked_at: string;
+};
+
+export type RPAInitConfig = {
+  policy_type: string;
+  max_audit_entries: number | null;
+  max_rollback_history: number | null;
+};
+
+export type ReEmbedPolicy =
+  | "never"
+  | "mismatched"
+  | "always";
+
+export type ReleaseNotes = {
+  summary: string;
+  features?: string[];
+  fixes?: string[];
+  security?: string[];
+};
+
+export type RemediationAction = {
+  id: string;
+  kind: RemediationKind;
+  description: string;
+  finding_id: string;
+  severity: string;
+  rationale: string;
+  status: ActionStatus;
+  created_at: string;
+  decided_at: string | null;
+  reason: string | null;
+  outcome: string | null;
+};
+
+export type RemediationKind =
+  | ({ type: "kill_process" } & {
+    pid: number;
+    process?: ProcessIdentity;
+  })
+  | ({ type: "suspend_process" } & {
+    pid: number;
+    process?: ProcessIdentity;
+  })
+  | ({ type: "quarantine_file" } & {
+    path: string;
+  })
+  | ({ type: "disable_startup_entry" } & {
+    name: string;
+  })
+  | ({ type: "isolate_network" } & {
+    pid: number;
+    process?: ProcessIdentity;
+  });
+
+export type RemoteHost = {
+  id: string;
+  name: string;
+  url: string;
+  fingerprint: string;
+  added_at: string;
+  last_seen_at: string | null;
+};
+
+export type RemoteProbe = {
+  url: string;
+  fingerprint: string;
+};
+
+export type RemoteStatus = {
+  hostname: string | null;
+  version: string;
+  protection: ProtectionStatus;
+  system: SystemStatus;
+  recent_threats: ThreatEvent[];
+  pending_remediations: RemediationAction[];
+};
+
+export type ReversibleAction = {
+  id: string;
+  action_type: ActionType;
+  state_before: unknown;
+  state_after: unknown;
+  timestamp: string;
+};
+
+export type RevocationReason =
+  | "expired"
+  | "revoked";
+
+export type RiskLevel =
+  | "Low"
+  | "Medium"
+  | "High"
+  | "Critical";
+
+export type Role =
+  | "viewer"
+  | "operator"
+  | "admin";
+
+export type RollbackReport = {
+  checkpoint_id: string;
+  reverted: ReversibleAction[];
+  skipped: ReversibleAction[];
+};
+
+export type Rule = {
+  id: string;
+  description?: string;
+  severity: ThreatSeverity;
+  enabled?: boolean;
+  name?: string[];
+  args?: string[];
+  path?: string[];
+  parent?: string[];
+  signer?: string[];
+};
+
+export type RuleMatch = {
+  rule_id: string;
+  description: string;
+  severity: ThreatSeverity;
+  matched: string[];
+};
+
+export type RulesStatus = {
+  path: string | null;
+  loaded_at: string;
+  last_error: string | null;
+  rules: Rule[];
+};
+
+export type ScanCacheStats = {
+  enabled: boolean;
+  force_full_rescan: boolean;
+  hits: number;
+  misses: number;
+};
+
+export type ScanDetails = {
+  summary: ScanRunSummary;
+  files: ScanFileVerdict[];
+};
+
+export type ScanDiff = {
+  new_malicious: ScanFileVerdict[];
+  removed_malicious: ScanFileVerdict[];
+  changed_malicious: ScanFileChange[];
+};
+
+export type ScanFileChange = {
+  before: ScanFileVerdict;
+  after: ScanFileVerdict;
+};
+
+export type ScanFileVerdict = {
+  path: string;
+  sha256?: string;
+  malicious: boolean;
+  local_match?: string;
+  error?: string;
+};
+
+export type ScanFilterConfig = {
+  include_extensions?: string[];
+  exclude_extensions?: string[];
+  include_content?: string[];
+  exclude_content?: string[];
+  include_globs?: string[];
+  exclude_globs?: string[];
+};
+
+export type ScanRate = {
+  files_per_sec: number;
+  bytes_per_sec: number;
+  bytes_scanned: number;
+  bytes_total: number;
+  eta_secs: number | null;
+};
+
+export type ScanRunSummary = {
+  id: string;
+  root: string;
+  started_at: string;
+  finished_at: string;
+  status: string;
+  total: number;
+  scanned: number;
+  malicious: number;
+  errors: number;
+  duration_ms: number;
+};
+
+export type ScreenRegion = {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+};
+
+export type ScreenshotQuestion = ImageAttachment;
+
+export type SecurityConfig = {
+  enforce_command_permissions?: boolean;
+};
+
+export type SecurityDiagnosticReport = {
+  timestamp: string;
+  system_info: SrcTauriSystemInfo;
+  total_processes: number;
+  high_cpu_processes: ProcessInfo[];
+  suspicious_processes: ProcessInfo[];
+  network_info: NetworkInfo;
+  threat_level: ThreatLevel;
+  threat_score: number;
+  recommendations: string[];
+};
+
+export type SecurityEvent = {
+  event_id: string;
+  event_type: SecurityEventType;
+  severity: SecuritySeverity;
+  user_id: string | null;
+  session_id: string | null;
+  timestamp: string;
+  description: string;
+  metadata: Record<string, string>;
+  ip_address: string | null;
+};
+
+export type SecurityEventType =
+  | "LoginAttempt"
+  | "LoginSuccess"
+  | "LoginFailure"
+  | "Logout"
+  | "SessionExpired"
+  | "PermissionDenied"
+  | "RateLimitExceeded"
+  | "SuspiciousActivity"
+  | "DataAccess"
+  | "ConfigurationChange"
+  | "EncryptionFailure"
+  | "PolicyViolation"
+  | "ProcessControl"
+  | "PlaybookRun";
+
+export type SecurityPolicy = {
+  session_timeout: { secs: number; nanos: number };
+  max_failed_attempts: number;
+  lockout_duration: { secs: number; nanos: number };
+  require_strong_passwords: boolean;
+  enable_two_factor: boolean;
+  allowed_ip_ranges: string[];
+  blocked_ip_addresses: string[];
+  enable_audit_logging: boolean;
+  data_retention_days: number;
+  enforce_command_permissions?: boolean;
+};
+
+export type SecuritySeverity =
+  | "Low"
+  | "Medium"
+  | "High"
+  | "Critical";
+
+export type SelfDiagnosticsReport = {
+  generated_at: string;
+  app_version: string;
+  platform: string;
+  overall: CheckStatus;
+  checks: DiagnosticCheck[];
+  features: Record<string, boolean>;
+  bundle_path: string | null;
+};
+
+export type SlashCommandOutput = {
+  command: string;
+  summary: string;
+  data: unknown;
+};
+
+export type SmtpTls =
+  | "start_tls"
+  | "implicit";
+
+export type SpeechLanguage = {
+  code: string;
+  name: string;
+  voice: string;
+};
+
+export type SrcTauriSystemInfo = {
+  cpu_usage: number;
+  memory_used_gb: number;
+  memory_total_gb: number;
+  memory_percent: number;
+};
+
+export type StagedFile = {
+  original: string;
+  staged: string;
+  bytes: number;
+};
+
+export type StartupImpact =
+  | "not_measured"
+  | "none"
+  | "low"
+  | "medium"
+  | "high";
+
+export type StartupImpactEntry = {
+  item: StartupItem;
+  impact: StartupImpact;
+  impact_score: number;
+  cpu_seconds: number;
+  disk_bytes: number;
+  suggest_disable: boolean;
+};
+
+export type StartupItem = {
+  id: string;
+  name: string;
+  source: StartupSource;
+  location: string;
+  command: string;
+  enabled: boolean;
+  risk_score: number;
+  risk_level: string;
+  reasons: string[];
+};
+
+export type StartupPhase =
+  | "initializing"
+  | "ready"
+  | "degraded";
+
+export type StartupSource =
+  | "registry_run"
+  | "scheduled_task"
+  | "service"
+  | "systemd_unit"
+  | "cron"
+  | "xdg_autostart";
+
+export type StartupStatus = {
+  phase: StartupPhase;
+  step: string | null;
+  attempt: number;
+  failures: ComponentFailure[];
+  started_at: string;
+  finished_at: string | null;
+};
+
+export type StepOutcome =
+  | "completed"
+  | "skipped";
+
+export type StepResult = {
+  step: PlaybookStep;
+  status: StepStatus;
+  detail: string;
+};
+
+export type StepState = {
+  step: OnboardingStep;
+  outcome: StepOutcome | null;
+};
+
+export type StepStatus =
+  | "planned"
+  | "succeeded"
+  | "failed"
+  | "skipped";
+
+export type Suggestion = {
+  id: string;
+  kind: SuggestionKind;
+  subject: string;
+  message: string;
+  prompt: string | null;
+  score: number;
+  created_at: string;
+};
+
+export type SuggestionKind =
+  | "memory_growth"
+  | "recurring_problem"
+  | "repeated_threat"
+  | "habit";
+
+export type SuggestionsConfig = {
+  enabled?: boolean;
+  interval_minutes?: number;
+  memory_growth_days?: number;
+};
+
+export type SupervisedTask =
+  | "guardian_monitoring"
+  | "metrics_collector"
+  | "threat_remediation";
+
+export type SurrealDbConfig = {
+  enabled?: boolean;
+  db_path: string | null;
+  collect_metrics?: boolean;
+  metrics_interval_secs?: number;
+  distributed?: boolean;
+  tikv_endpoints?: string[];
+  remote_url?: string;
+  username?: string;
+  uplink?: FleetUplinkConfig;
+  enable_js_functions?: boolean;
+  enable_computed_views?: boolean;
+  consolidation?: MemoryConsolidationConfig;
+  chunking?: MemoryChunkingConfig;
+  threat_training?: ThreatTrainingConfig;
+};
+
+export type SuspectedLeak = {
+  process_name: string;
+  growth_mb_per_hour: number;
+  fit: number;
+  duration_hours: number;
+  first_mb: number;
+  current_mb: number;
+  hours_to_exhaustion: number | null;
+  detected_at: string;
+};
+
+export type SyslogProtocol =
+  | "udp"
+  | "tcp";
+
+export type SystemHealth = {
+  status: string;
+  cpu_usage: number;
+  memory_percent: number;
+  memory_used_gb: number;
+  memory_total_gb: number;
+  timestamp: string;
+};
+
+export type SystemInfo = {
+  os: string;
+  os_version: string | null;
+  arch: string;
+  host_name: string | null;
+  cpu_count: number;
+  total_memory: number;
+  uptime_secs: number;
+  pid: number;
+};
+
+export type SystemMetric = {
+  timestamp: string;
+  cpu_usage: number;
+  core_usage?: number[];
+  memory_usage: MemoryUsage;
+  disk_io: DiskIO;
+  network_stats: NetworkStats;
+  gpus?: GpuMetrics[];
+  cpu_temperature_c?: number;
+  battery?: BatteryStatus;
+  metadata?: unknown;
+};
+
+export type SystemSnapshot = unknown;
+
+export type SystemStatus = {
+  cpu_usage: number;
+  memory_usage: [number, number];
+  process_count: number;
+  threat_count: number;
+};
+
+export type TacticSummary = {
+  id: string;
+  name: string;
+  detections: number;
+  techniques: TechniqueCount[];
+  known_techniques: number;
+};
+
+export type TamperChange =
+  | "modified"
+  | "deleted"
+  | "entries_removed";
+
+export type TamperFinding = {
+  kind: ProtectedKind;
+  path: string;
+  change: TamperChange;
+  removed?: string[];
+};
+
+export type TaskHealth = {
+  task: SupervisedTask;
+  healthy: boolean;
+  last_heartbeat: string | null;
+  restarts: number;
+  last_restart_at: string | null;
+  last_failure: string | null;
+  next_restart_at: string | null;
+};
+
+export type TaskRestart = {
+  task: SupervisedTask;
+  reason: string;
+  restarts: number;
+  at: string;
+};
+
+export type TechniqueCount = {
+  id: string;
+  name: string;
+  count: number;
+};
+
+export type TextVerification = {
+  expected: string;
+  found: boolean;
+  attempts: number;
+  text: string;
+};
+
+export type ThreatEvent = {
+  id: string;
+  timestamp: string;
+  threat_type: ThreatType;
+  severity: ThreatSeverity;
+  description: string;
+  process_name: string | null;
+  process_id: number | null;
+  details: Record<string, string>;
+  techniques: string[];
+  risk_score: number;
+};
+
+export type ThreatLevel =
+  | "Clean"
+  | "Low"
+  | "Medium"
+  | "High"
+  | "Critical";
+
+export type ThreatPrediction = unknown;
+
+export type ThreatRecord = {
+  id: string;
+  severity: MemoryThreatSeverity;
+  yara_rule: string | null;
+  heuristic_score: number | null;
+  risk_score: number | null;
+  timestamp: string;
+  indicators: string[];
+  mitigation_status: MitigationStatus;
+  status_updated_at: string | null;
+  status_note: string | null;
+  techniques: string[];
+};
+
+export type ThreatSeverity =
+  | "Low"
+  | "Medium"
+  | "High"
+  | "Critical";
+
+export type ThreatTrainingConfig = {
+  enabled?: boolean;
+  interval_hours?: number;
+  consensus_min_confidence?: number;
+  min_samples?: number;
+};
+
+export type ThreatTrainingSample = {
+  severity: string;
+  cpu_usage: number;
+  memory_pressure: number;
+  network_score: number;
+  anomaly_score: number;
+  metadata?: unknown;
+  label_source?: LabelSource;
+  source_id?: string;
+};
+
+export type ThreatType =
+  | "MalwareSignature"
+  | "SuspiciousProcess"
+  | "HighResourceUsage"
+  | "UnauthorizedNetworkAccess"
+  | "FileSystemAnomaly"
+  | "MaliciousFile"
+  | "ConfigurationDrift"
+  | "SelfTampering";
+
+export type TimeRange = {
+  start: string;
+  end: string;
+};
+
+export type TlsStatus = {
+  fingerprint: string;
+  self_signed: boolean;
+  cert_path: string;
+};
+
+export type TrainingRunReport = {
+  collected: number;
+  total_samples: number;
+  evaluation: ModelEvaluation | null;
+  duration_ms: number;
+};
+
+export type Trigger = {
+  min_severity?: ThreatSeverity;
+  yara_rule?: string[];
+  process_name?: string[];
+};
+
+export type UndoReport = {
+  run_id: string;
+  restored_files: number;
+  restored_bytes: number;
+  skipped: number;
+  failed: number;
+};
+
+export type UpdateChannel =
+  | "stable"
+  | "beta";
+
+export type UpdateCheck = {
+  current_version: string;
+  channel: UpdateChannel;
+  checked_at: string;
+  update: AvailableUpdate | null;
+};
+
+export type UpdatesConfig = {
+  enabled?: boolean;
+  channel?: UpdateChannel;
+  endpoint?: string;
+  check_interval_hours?: number;
+};
+
+export type UplinkStatus = {
+  enabled: boolean;
+  machine_id: string | null;
+  endpoint: string | null;
+  connected: boolean;
+  queued: number;
+  sent: number;
+  dropped: number;
+  last_flush_at: string | null;
+  last_error: string | null;
+};
+
+export type UserPattern = {
+  pattern_id: string;
+  pattern_type: PatternType;
+  frequency: number;
+  last_occurrence: string;
+  confidence: number;
+  description: string;
+  weight?: number;
+  hours?: number[];
+};
+
+export type VectorIndexReport = {
+  previous_dimension: number;
+  dimension: number;
+  memories: number;
+  re_embedded: number;
+  unembedded: number;
+  index_ready: boolean;
+  recall: number | null;
+  avg_latency_ms: number | null;
+  duration_ms: number;
+};
+
+export type VersionNotes = {
+  version: string;
+  channel: UpdateChannel;
+  pub_date: string;
+  notes: ReleaseNotes;
+};
+
+export type VoiceConfig = {
+  language?: string;
+  auto_detect?: boolean;
+  detect_languages?: string[];
+  voices?: Record<string, string>;
+};
+
+export type VoiceLanguageSettings = {
+  language: string;
+  auto_detect: boolean;
+  detect_languages: string[];
+  voices: Record<string, string>;
+};
+
+export type VoiceLanguageStatus = {
+  alternatives: string[];
+  last_detected: string | null;
+  available: SpeechLanguage[];
+} & VoiceLanguageSettings;
+
+export type VolumeUsage = {
+  mount_point: string;
+  total_bytes: number;
+  available_bytes: number;
+};
+
+export type WebhookEndpoint = {
+  url: string;
+  enabled?: boolean;
+  signed?: boolean;
+  events?: WebhookEvent[];
+  format?: WebhookFormat;
+};
+
+export type WebhookEvent =
+  | "threat_detected"
+  | "scan_completed"
+  | "consensus_report"
+  | "test";
+
+export type WebhookFormat =
+  | "json"
+  | "slack"
+  | "discord"
+  | "teams";
+
+export type WebhooksConfig = {
+  endpoints?: WebhookEndpoint[];
+  max_attempts?: number;
+};
+
+export type WindowInfo = {
+  id: string;
+  title: string;
+  process_name: string;
+  pid: number | null;
+  placement: WindowPlacement;
+};
+
+export type WindowPlacement = {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+  minimized: boolean;
+  maximized: boolean;
+};
+
+/** SCHEMA_VERSION of the event payloads below */
+export const EVENT_SCHEMA_VERSION = 1;
+
+/** Payload of every event the backend emits, by event name */
+export interface EventPayloads {
+  folder_scan_started: FolderScanStarted;
+  folder_scan_progress: FolderScanProgress;
+  folder_scan_completed: FolderScanSummary;
+  folder_scan_cancelled: FolderScanSummary;
+  google_auth_complete: GoogleAuthComplete;
+  "plan://progress": PlanProgress;
+  "job://update": JobInfo;
+  "suggestions://new": Suggestion;
+  "evidence://progress": EvidenceProgress;
+  "startup://status": StartupStatus;
+  "rpa://grant": GrantEvent;
+  "guardian://metrics": SystemMetric;
+  "memory://maintenance": MaintenanceProgress;
+  "hotkey://triggered": HotkeyTriggered;
+  "hotkey://screenshot_question": ScreenshotQuestion;
+  "hotkey://panic_stop": PanicStopReport;
+  "updates://available": UpdateCheck;
+  "watchdog://restart": TaskRestart;
+}
+
+/** Arguments and result of every registered Tauri command */
+export interface Commands {
+  send_notification: { args: { title: string; body: string }; result: null };
+  set_google_api_key: { args: { apiKey: string }; result: null };
+  set_google_client_credentials: { args: { clientId: string; clientSecret: string }; result: null };
+  authenticate_google_command: { args: Record<string, never>; result: string };
+  get_available_models: { args: Record<string, never>; result: string[] };
+  send_message_to_gemini: { args: { message: string; model?: string | null }; result: string };
+  check_auth_from_env: { args: Record<string, never>; result: string };
+  initialize_system: { args: { config: OxidePilotConfig }; result: null };
+  handle_user_input_command: { args: { userInput: string; sessionId?: string | null }; result: string };
+  run_slash_command: { args: { input: string; sessionId?: string | null }; result: SlashCommandOutput };
+  handle_user_input_with_images: { args: { userInput: string; images?: ImageAttachment[] | null; imagePaths?: string[] | null; captureScreen?: boolean | null; sessionId?: string | null }; result: string };
+  run_collaborative_analysis: { args: { userInput: string; taskType?: string | null }; result: string };
+  get_orchestrator_config: { args: Record<string, never>; result: OrchestratorConfig };
+  execute_plan: { args: { plan: unknown; constraints?: Record<string, unknown> | null; confirmedSteps?: number[] | null; startStep?: number | null; sessionId?: string | null }; result: PlanExecutionReport };
+  run_multi_agent_analysis: { args: { geminiModel?: string | null; qwenModel?: string | null }; result: string };
+  run_threat_consensus: { args: Record<string, never>; result: string };
+  start_collaborative_analysis: { args: { userInput: string; taskType?: string | null }; result: string };
+  start_multi_agent_analysis: { args: { geminiModel?: string | null; qwenModel?: string | null }; result: string };
+  start_threat_consensus: { args: Record<string, never>; result: string };
+  get_job: { args: { jobId: string }; result: JobInfo };
+  list_jobs: { args: { kind?: string | null }; result: JobInfo[] };
+  cancel_job: { args: { jobId: string; sessionId?: string | null }; result: JobInfo };
+  get_threat_recommendations: { args: Record<string, never>; result: string[] };
+  get_consensus_history: { args: { limit?: number | null }; result: ConsensusRunEntry[] };
+  query_llm_audit: { args: { query?: LlmAuditQuery | null; sessionId?: string | null }; result: LlmAuditEntry[] };
+  get_pending_remediations: { args: Record<string, never>; result: RemediationAction[] };
+  respond_remediation: { args: { actionId: string; approved: boolean; reason?: string | null; sessionId?: string | null }; result: RemediationAction };
+  get_remediation_history: { args: { limit?: number | null }; result: RemediationAction[] };
+  get_startup_items: { args: Record<string, never>; result: StartupItem[] };
+  get_boot_impact: { args: Record<string, never>; result: BootImpactReport };
+  kill_process: { args: { pid: number; startTime?: number | null; exe?: string | null; sessionId: string }; result: string };
+  suspend_process: { args: { pid: number; startTime?: number | null; exe?: string | null; sessionId: string }; result: string };
+  resume_process: { args: { pid: number; startTime?: number | null; exe?: string | null; sessionId: string }; result: string };
+  view_baseline_drift: { args: Record<string, never>; result: DriftReport | null };
+  record_baseline: { args: { sessionId?: string | null }; result: BaselineSnapshot };
+  get_integrity_status: { args: { sessionId?: string | null }; result: IntegrityReport };
+  record_integrity_manifest: { args: { sessionId?: string | null }; result: IntegrityManifest };
+  import_ioc_feed: { args: { pathOrUrl: string; source?: string | null; expiryDays?: number | null; sessionId?: string | null }; result: IocImportReport };
+  block_process_network: { args: { pid?: number | null; path?: string | null; startTime?: number | null; reason?: string | null; sessionId?: string | null }; result: FirewallRule };
+  list_oxide_firewall_rules: { args: Record<string, never>; result: FirewallRule[] };
+  remove_rule: { args: { ruleId: string; sessionId?: string | null }; result: FirewallRule };
+  list_playbooks: { args: Record<string, never>; result: PlaybookLibrary };
+  run_playbook: { args: { playbookId: string; threatId?: string | null; dryRun?: boolean | null; sessionId?: string | null }; result: PlaybookRun };
+  collect_evidence: { args: { threatId: string; passphrase?: string | null; sessionId?: string | null }; result: EvidenceBundle };
+  check_for_updates: { args: Record<string, never>; result: UpdateCheck };
+  list_crash_reports: { args: Record<string, never>; result: CrashSummary[] };
+  get_crash_report: { args: { id: string; redacted?: boolean | null }; result: CrashReport };
+  upload_crash_report: { args: { id: string; sessionId?: string | null }; result: CrashSummary };
+  preview_cleanup: { args: Record<string, never>; result: CleanupPreview };
+  run_cleanup: { args: { itemIds: string[]; sessionId?: string | null }; result: CleanupRun };
+  list_cleanup_runs: { args: Record<string, never>; result: CleanupRunSummary[] };
+  undo_cleanup: { args: { runId: string; sessionId?: string | null }; result: UndoReport };
+  purge_cleanup_run: { args: { runId: string; sessionId?: string | null }; result: number };
+  install_update: { args: { sessionId?: string | null }; result: null };
+  get_heuristic_rules: { args: Record<string, never>; result: RulesStatus };
+  reload_heuristic_rules: { args: { sessionId?: string | null }; result: RulesStatus };
+  test_heuristic_rules: { args: { sample: ProcessSample }; result: RuleMatch[] };
+  scan_process_memory: { args: { pid: number; sessionId?: string | null }; result: MemoryScanReport };
+  disable_startup_item: { args: { itemId: string; sessionId?: string | null }; result: string };
+  get_system_status: { args: Record<string, never>; result: SystemStatus };
+  scan_file_command: { args: { path: string; useCloud: boolean; quarantine: boolean }; result: FileScanReport };
+  start_folder_scan: { args: { root: string; useCloud: boolean; quarantine: boolean; forceFullRescan?: boolean | null; filter?: ScanFilterConfig | null }; result: string };
+  cancel_folder_scan: { args: { scanId: string }; result: null };
+  is_virustotal_configured: { args: Record<string, never>; result: boolean };
+  get_threat_history: { args: Record<string, never>; result: ThreatEvent[] };
+  get_memory_stats: { args: Record<string, never>; result: MemoryStats };
+  get_user_patterns: { args: { sessionId?: string | null }; result: UserPattern[] };
+  get_suggestions: { args: Record<string, never>; result: Suggestion[] };
+  accept_suggestion: { args: { suggestionId: string }; result: Suggestion };
+  dismiss_suggestion: { args: { suggestionId: string }; result: null };
+  get_attack_matrix_summary: { args: { days?: number | null }; result: AttackMatrixSummary };
+  update_system_config: { args: { config: OxidePilotConfig; sessionId?: string | null }; result: null };
+  set_offline_mode: { args: { enabled: boolean; sessionId?: string | null }; result: NetworkStatus };
+  get_locale: { args: Record<string, never>; result: LocaleInfo };
+  set_locale: { args: { locale: string; sessionId?: string | null }; result: LocaleInfo };
+  get_voice_language: { args: Record<string, never>; result: VoiceLanguageStatus };
+  set_voice_language: { args: { language: string; autoDetect?: boolean | null; sessionId?: string | null }; result: VoiceLanguageStatus };
+  list_prompt_templates: { args: Record<string, never>; result: PromptTemplate[] };
+  reload_prompt_templates: { args: { sessionId?: string | null }; result: PromptTemplate[] };
+  get_network_status: { args: Record<string, never>; result: NetworkStatus };
+  get_api_quota_status: { args: Record<string, never>; result: ApiQuotaStatus[] };
+  get_llm_cache_stats: { args: Record<string, never>; result: CacheStats };
+  clear_llm_cache: { args: Record<string, never>; result: null };
+  validate_config: { args: { config: OxidePilotConfig }; result: ConfigIssue[] };
+  get_system_config: { args: Record<string, never>; result: OxidePilotConfig };
+  get_event_schema: { args: Record<string, never>; result: EventSchema };
+  get_capabilities: { args: Record<string, never>; result: Record<string, Capability> };
+  get_hotkeys: { args: Record<string, never>; result: HotkeyStatus[] };
+  update_hotkeys: { args: { hotkeys: HotkeyConfig; sessionId?: string | null }; result: HotkeyStatus[] };
+  panic_stop: { args: Record<string, never>; result: PanicStopReport };
+  record_audio: { args: { durationSecs: number }; result: number[] };
+  play_audio: { args: { audioData: number[] }; result: null };
+  get_audio_devices: { args: Record<string, never>; result: [string[], string[]] };
+  get_input_volume: { args: Record<string, never>; result: number };
+  get_performance_metrics: { args: Record<string, never>; result: PerformanceReport };
+  get_performance_score: { args: Record<string, never>; result: number };
+  optimize_performance: { args: { sessionId?: string | null }; result: OptimizationReport };
+  analyze_disk_usage: { args: { root: string; refresh?: boolean | null }; result: DiskUsageReport };
+  get_error_statistics: { args: Record<string, never>; result: ErrorStats };
+  get_recent_errors: { args: { limit?: number | null }; result: ErrorResponse[] };
+  get_recent_logs: { args: { level?: string | null; module?: string | null; limit?: number | null }; result: LogEntry[] };
+  get_startup_status: { args: Record<string, never>; result: StartupStatus };
+  get_onboarding_state: { args: Record<string, never>; result: OnboardingState };
+  complete_onboarding_step: { args: { step: OnboardingStep; skipped?: boolean | null; config?: OxidePilotConfig | null }; result: OnboardingState };
+  list_profiles: { args: Record<string, never>; result: ProfilesInfo };
+  switch_profile: { args: { name: string; sessionId?: string | null }; result: ProfilesInfo };
+  clear_performance_alerts: { args: { sessionId?: string | null }; result: null };
+  get_operation_profiles: { args: Record<string, never>; result: OperationProfile[] };
+  set_performance_monitoring: { args: { enabled: boolean; sessionId?: string | null }; result: null };
+  validate_input: { args: { fieldName: string; value: string }; result: string };
+  create_security_session: { args: { userId: string; passphrase?: string | null; permissions: string[]; ipAddress?: string | null; userAgent?: string | null }; result: string };
+  validate_security_session: { args: { sessionId: string }; result: boolean };
+  check_security_permission: { args: { sessionId: string; permission: string }; result: boolean };
+  get_session_permissions: { args: { sessionId: string }; result: string[] };
+  assign_security_role: { args: { userId: string; role: string; passphrase?: string | null; sessionId?: string | null }; result: null };
+  revoke_security_role: { args: { userId: string; sessionId?: string | null }; result: Role | null };
+  get_security_role_assignments: { args: { sessionId?: string | null }; result: Record<string, Role> };
+  get_security_events: { args: { limit?: number | null }; result: SecurityEvent[] };
+  get_security_policy: { args: Record<string, never>; result: SecurityPolicy };
+  set_command_permission_enforcement: { args: { enforce?: boolean | null; sessionId?: string | null }; result: boolean };
+  check_rate_limit: { args: { identifier: string }; result: null };
+  initialize_auth_manager: { args: Record<string, never>; result: null };
+  get_auth_token: { args: Record<string, never>; result: string };
+  get_auth_status: { args: Record<string, never>; result: string };
+  clear_auth: { args: Record<string, never>; result: null };
+  clear_google_auth: { args: Record<string, never>; result: null };
+  startup_check: { args: Record<string, never>; result: string };
+  get_system_snapshot: { args: Record<string, never>; result: SystemSnapshot };
+  run_system_analysis: { args: { model?: string | null }; result: string };
+  run_multi_agent_analysis: { args: { geminiModel?: string | null; qwenModel?: string | null }; result: string };
+  local_llm_server_start: { args: { port?: number | null; cors?: boolean | null }; result: string };
+  local_llm_server_stop: { args: Record<string, never>; result: string };
+  local_llm_server_status: { args: Record<string, never>; result: LocalLlmServerStatus };
+  local_llm_ls: { args: Record<string, never>; result: string };
+  local_llm_get: { args: { modelSpec: string; gguf?: boolean | null; yes?: boolean | null }; result: string };
+  local_llm_load: { args: { modelKey: string; identifier?: string | null; contextLen?: number | null; gpu?: string | null; ttlSecs?: number | null }; result: string };
+  local_llm_chat: { args: { baseUrl?: string | null; apiKey?: string | null; model?: string | null; systemPrompt?: string | null; userPrompt: string }; result: string };
+  qwen_start_device_auth: { args: Record<string, never>; result: DeviceAuthStart };
+  qwen_poll_device_auth: { args: { deviceCode: string }; result: PollResult };
+  qwen_get_auth_status: { args: Record<string, never>; result: string };
+  qwen_clear_auth: { args: Record<string, never>; result: null };
+  openai_set_api_key: { args: { apiKey: string }; result: null };
+  openai_start_oauth: { args: { clientId: string; clientSecret: string }; result: string };
+  openai_get_auth_status: { args: Record<string, never>; result: string };
+  openai_clear_auth: { args: Record<string, never>; result: null };
+  open_url: { args: { url: string }; result: null };
+  mcp_start: { args: { portOverride?: number | null; passwordOverride?: string | null }; result: string };
+  mcp_stop: { args: Record<string, never>; result: string };
+  mcp_status: { args: Record<string, never>; result: McpStatus };
+  create_mcp_token: { args: { client: string; scope: McpScope; ttlDays?: number | null; sessionId?: string | null }; result: IssuedMcpToken };
+  list_mcp_tokens: { args: Record<string, never>; result: McpToken[] };
+  rotate_mcp_token: { args: { id: string; ttlDays?: number | null; sessionId?: string | null }; result: IssuedMcpToken };
+  revoke_mcp_token: { args: { id: string; sessionId?: string | null }; result: McpToken };
+  probe_remote_host: { args: { url: string }; result: RemoteProbe };
+  add_remote_host: { args: { name: string; url: string; fingerprint: string; token: string; sessionId?: string | null }; result: RemoteHost };
+  list_remote_hosts: { args: Record<string, never>; result: RemoteHost[] };
+  remove_remote_host: { args: { id: string; sessionId?: string | null }; result: RemoteHost };
+  get_remote_status: { args: { id: string }; result: RemoteStatus };
+  remote_scan_file: { args: { id: string; path: string; useCloud: boolean; quarantine: boolean; sessionId?: string | null }; result: FileScanReport };
+  remote_respond_remediation: { args: { id: string; actionId: string; approved: boolean; reason?: string | null; sessionId?: string | null }; result: RemediationAction };
+  local_api_start: { args: { portOverride?: number | null; tokenOverride?: string | null; sessionId?: string | null }; result: string };
+  local_api_stop: { args: { sessionId?: string | null }; result: boolean };
+  local_api_status: { args: Record<string, never>; result: LocalApiStatus };
+  test_webhooks: { args: Record<string, never>; result: DeliveryResult[] };
+  test_alert_email: { args: Record<string, never>; result: EmailTestResult[] };
+  set_smtp_password: { args: { host: string; username: string; password: string; sessionId?: string | null }; result: null };
+  set_webhook_secret: { args: { url: string; secret: string; sessionId?: string | null }; result: null };
+  rpa_initialize: { args: { config: RPAInitConfig }; result: string };
+  rpa_shutdown: { args: Record<string, never>; result: string };
+  rpa_grant_permission: { args: { permission: string; durationSecs?: number | null; applications?: string[] | null; sessionId?: string | null }; result: PermissionGrant };
+  rpa_grant_profile: { args: { profile: PermissionProfile; durationSecs?: number | null; applications?: string[] | null; sessionId?: string | null }; result: PermissionGrant };
+  rpa_revoke_grant: { args: { grantId: string; sessionId?: string | null }; result: null };
+  rpa_list_grants: { args: Record<string, never>; result: PermissionGrant[] };
+  rpa_set_target_application: { args: { application?: string | null }; result: null };
+  rpa_check_permission: { args: { permission: string }; result: boolean };
+  rpa_move_mouse: { args: { x: number; y: number }; result: null };
+  rpa_click_mouse: { args: { button: string }; result: null };
+  rpa_scroll_mouse: { args: { deltaX: number; deltaY: number }; result: null };
+  rpa_type_text: { args: { text: string }; result: null };
+  rpa_press_key: { args: { key: string }; result: null };
+  rpa_capture_screen: { args: Record<string, never>; result: number[] };
+  rpa_read_screen_text: { args: { region?: ScreenRegion | null }; result: OcrResult };
+  rpa_verify_screen_text: { args: { expected: string; region?: ScreenRegion | null; timeoutMs?: number | null }; result: TextVerification };
+  rpa_resume: { args: Record<string, never>; result: null };
+  rpa_move_file: { args: { from: string; to: string }; result: null };
+  rpa_set_clipboard: { args: { text: string }; result: null };
+  rpa_list_windows: { args: Record<string, never>; result: WindowInfo[] };
+  rpa_window_action: { args: { action: string; title?: string | null; process?: string | null }; result: WindowInfo };
+  rpa_move_window: { args: { title?: string | null; process?: string | null; x: number; y: number; width: number; height: number }; result: WindowInfo };
+  rpa_get_audit_entries: { args: Record<string, never>; result: AuditEntry[] };
+  rpa_get_audit_stats: { args: Record<string, never>; result: AuditStats };
+  rpa_get_failed_actions: { args: Record<string, never>; result: AuditEntry[] };
+  rpa_get_rollback_history: { args: Record<string, never>; result: ReversibleAction[] };
+  rpa_rollback_last: { args: Record<string, never>; result: null };
+  rpa_get_reversible_count: { args: Record<string, never>; result: number };
+  rpa_create_checkpoint: { args: { label: string }; result: Checkpoint };
+  rpa_list_checkpoints: { args: Record<string, never>; result: Checkpoint[] };
+  rpa_rollback_to: { args: { checkpointId: string }; result: RollbackReport };
+  rpa_get_pending_confirmations: { args: Record<string, never>; result: ConfirmationRequest[] };
+  rpa_respond_confirmation: { args: { requestId: string; approved: boolean; reason?: string | null }; result: null };
+  rpa_add_auto_approve: { args: { permission: string }; result: null };
+  get_system_metrics: { args: { timeRange: TimeRange }; result: MetricsResponse };
+  get_recent_metrics: { args: { hours: number }; result: MetricsResponse };
+  get_metrics_summary: { args: { hours?: number | null }; result: MetricsSummaryResponse };
+  get_hourly_metrics: { args: { hours?: number | null }; result: HourlyMetricsRow[] };
+  get_process_hotspots: { args: { hours?: number | null }; result: ProcessHotspot[] };
+  get_process_trend: { args: { name: string; hours?: number | null }; result: ProcessTrend };
+  get_suspected_leaks: { args: Record<string, never>; result: SuspectedLeak[] };
+  get_high_cpu_processes: { args: { threshold: number; hours: number }; result: HighCpuProcessesResponse };
+  search_agent_memory: { args: { query: string; limit: number }; result: MemorySearchResponse };
+  get_guardian_status: { args: Record<string, never>; result: GuardianStatus };
+  predict_threat_risk: { args: { featureVector: unknown }; result: ThreatPrediction };
+  submit_threat_training_sample: { args: { sample: ThreatTrainingSample }; result: null };
+  label_threat: { args: { threatId: string; severity: ThreatSeverity; sessionId?: string | null }; result: ThreatTrainingSample };
+  retrain_threat_model: { args: { sessionId?: string | null }; result: TrainingRunReport };
+  get_threat_model_evaluation: { args: Record<string, never>; result: ModelEvaluation | null };
+  get_fleet_uplink_status: { args: Record<string, never>; result: UplinkStatus };
+  subscribe_guardian_metrics: { args: Record<string, never>; result: null };
+  run_memory_benchmark: { args: { corpusSizes?: number[] | null; queriesPerSize?: number | null }; result: BenchmarkReport };
+  backup_database: { args: { path: string; sessionId?: string | null }; result: BackupInfo };
+  restore_database: { args: { path: string; sessionId?: string | null }; result: null };
+  compact_database: { args: { retentionDays?: number | null; sessionId?: string | null }; result: CompactionReport };
+  rebuild_vector_index: { args: { newDim?: number | null; sessionId?: string | null }; result: VectorIndexReport };
+  export_agent_memory: { args: { path: string; filter?: MemoryExportFilter | null; sessionId?: string | null }; result: MemoryTransferReport };
+  import_agent_memory: { args: { path: string; reEmbed?: ReEmbedPolicy | null; sessionId?: string | null }; result: MemoryTransferReport };
+  migrate_memory_backend: { args: { from: MemoryBackendKind; to: MemoryBackendKind; sessionId?: string | null }; result: MigrationReport };
+  list_agent_memories: { args: { filter?: MemoryListFilter | null; offset?: number | null; limit?: number | null; sessionId?: string | null }; result: AgentMemoryPage };
+  delete_agent_memory: { args: { memoryId: string; sessionId?: string | null }; result: boolean };
+  consolidate_agent_memory: { args: { sessionId?: string | null }; result: ConsolidationReport };
+  list_threats: { args: { status?: MitigationStatus | null; limit?: number | null }; result: ThreatRecord[] };
+  update_threat_status: { args: { threatId: string; status: MitigationStatus; note?: string | null; sessionId?: string | null }; result: null };
+  bulk_acknowledge_threats: { args: { threatIds: string[]; sessionId?: string | null }; result: number };
+  list_scan_history: { args: { root?: string | null; limit?: number | null }; result: ScanRunSummary[] };
+  get_scan_details: { args: { scanId: string }; result: ScanDetails };
+  diff_scans: { args: { baseScanId: string; targetScanId: string }; result: ScanDiff };
+  get_protection_status: { args: Record<string, never>; result: ProtectionStatus };
+  run_security_diagnostic: { args: Record<string, never>; result: SecurityDiagnosticReport };
+  get_last_security_scan: { args: Record<string, never>; result: SecurityDiagnosticReport | null };
+  get_system_health: { args: Record<string, never>; result: SystemHealth };
+  run_self_diagnostics: { args: { bundlePath?: string | null; sessionId?: string | null }; result: SelfDiagnosticsReport };
+}
+
+export type CommandName = keyof Commands;
+
+/** Invoke a command with its arguments and result typed by the backend. */
+export function invokeCommand<K extends CommandName>(
+  command: K,
+  ...args: Record<string, never> extends Commands[K]["args"]
+    ? [args?: Commands[K]["args"]]
+    : [args: Commands[K]["args"]]
+): Promise<Commands[K]["result"]> {
+  return tauriInvoke<Commands[K]["result"]>(command, args[0]);
+}
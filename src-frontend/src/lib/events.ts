@@ -0,0 +1,17 @@
+// Typed listeners for the events the backend emits. The payload types are
+// generated from src-tauri/src/events.rs into bindings.ts.
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type { EventPayloads } from "./bindings";
+
+export { EVENT_SCHEMA_VERSION } from "./bindings";
+export type { EventPayloads };
+
+export type EventName = keyof EventPayloads;
+
+/** Listen for a backend event with its payload typed by name. */
+export function listenEvent<K extends EventName>(
+  name: K,
+  handler: (payload: EventPayloads[K]) => void,
+): Promise<UnlistenFn> {
+  return listen<EventPayloads[K]>(name, (event) => handler(event.payload));
+}
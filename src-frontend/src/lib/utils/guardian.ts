@@ -1,5 +1,5 @@
-import { listen } from "@tauri-apps/api/event";
 import { invoke } from "@tauri-apps/api/tauri";
+import { listenEvent } from "$lib/events";
 
 export interface SystemMetric {
   timestamp: string;
@@ -20,7 +20,7 @@ export interface SystemMetric {
     recv_mb_per_sec: number;
     connections_active: number;
   };
-  metadata?: Record<string, unknown>;
+  metadata?: unknown;
 }
 
 export interface MetricsSummary {
@@ -56,6 +56,8 @@ export interface ThreatTrainingSample {
   network_score: number;
   anomaly_score: number;
   metadata?: Record<string, unknown>;
+  label_source?: "analyst" | "resolution" | "consensus";
+  source_id?: string;
 }
 
 export interface ThreatPrediction {
@@ -63,6 +65,9 @@ export interface ThreatPrediction {
   severity: string;
   score: number;
   confidence?: number;
+  /** Set by the bundled local model */
+  model_version?: string;
+  probabilities?: Record<string, number>;
 }
 
 export async function getGuardianStatus() {
@@ -106,14 +111,1137 @@ export async function submitThreatTrainingSample(
   return invoke("submit_threat_training_sample", { sample });
 }
 
+export interface ClassMetrics {
+  precision: number;
+  recall: number;
+  support: number;
+}
+
+export interface ModelEvaluation {
+  evaluated_at: string;
+  samples: number;
+  evaluated: number;
+  accuracy: number;
+  per_class: Record<string, ClassMetrics>;
+  predictor: string;
+}
+
+export interface TrainingRunReport {
+  collected: number;
+  total_samples: number;
+  evaluation: ModelEvaluation | null;
+  duration_ms: number;
+}
+
+export async function labelThreat(
+  threatId: string,
+  severity: Lowercase<ThreatSeverity>,
+  sessionId?: string,
+): Promise<ThreatTrainingSample> {
+  return invoke("label_threat", { threatId, severity, sessionId });
+}
+
+export async function retrainThreatModel(
+  sessionId?: string,
+): Promise<TrainingRunReport> {
+  return invoke("retrain_threat_model", { sessionId });
+}
+
+export async function getThreatModelEvaluation(): Promise<ModelEvaluation | null> {
+  return invoke("get_threat_model_evaluation");
+}
+
 export async function subscribeGuardianMetrics(
   onMetric: (metric: SystemMetric) => void,
 ): Promise<() => void> {
   await invoke("subscribe_guardian_metrics");
-  const unlisten = await listen<SystemMetric>("guardian://metrics", (event) => {
-    onMetric(event.payload);
+  const unlisten = await listenEvent("guardian://metrics", (payload) => {
+    onMetric(payload);
   });
   return () => {
     unlisten();
   };
 }
+
+export interface MaintenanceProgress {
+  /** "backup", "restore", "compact", "migrate" or "reindex" */
+  operation: string;
+  stage: string;
+  percent: number;
+}
+
+export interface BackupInfo {
+  path: string;
+  size_bytes: number;
+  created_at: string;
+  duration_ms: number;
+}
+
+export interface CompactionReport {
+  removed_metrics: number;
+  rebuilt_indexes: string[];
+  duration_ms: number;
+}
+
+async function withMaintenanceProgress<T>(
+  onProgress: ((progress: MaintenanceProgress) => void) | undefined,
+  run: () => Promise<T>,
+): Promise<T> {
+  const unlisten = onProgress
+    ? await listenEvent("memory://maintenance", (payload) => {
+        onProgress(payload);
+      })
+    : undefined;
+  try {
+    return await run();
+  } finally {
+    unlisten?.();
+  }
+}
+
+export async function backupDatabase(
+  path: string,
+  onProgress?: (progress: MaintenanceProgress) => void,
+  sessionId?: string,
+): Promise<BackupInfo> {
+  return withMaintenanceProgress(onProgress, () =>
+    invoke("backup_database", { path, sessionId }),
+  );
+}
+
+export async function restoreDatabase(
+  path: string,
+  onProgress?: (progress: MaintenanceProgress) => void,
+  sessionId?: string,
+): Promise<void> {
+  return withMaintenanceProgress(onProgress, () =>
+    invoke("restore_database", { path, sessionId }),
+  );
+}
+
+export async function compactDatabase(
+  retentionDays?: number,
+  onProgress?: (progress: MaintenanceProgress) => void,
+  sessionId?: string,
+): Promise<CompactionReport> {
+  return withMaintenanceProgress(onProgress, () =>
+    invoke("compact_database", { retentionDays, sessionId }),
+  );
+}
+
+export interface VectorIndexReport {
+  previous_dimension: number;
+  dimension: number;
+  memories: number;
+  re_embedded: number;
+  unembedded: number;
+  index_ready: boolean;
+  // Share of exact top-k neighbours found, 0..1; null with no usable vectors
+  recall: number | null;
+  avg_latency_ms: number | null;
+  duration_ms: number;
+}
+
+// Omit newDim to repair the index at the current dimension
+export async function rebuildVectorIndex(
+  newDim?: number,
+  onProgress?: (progress: MaintenanceProgress) => void,
+  sessionId?: string,
+): Promise<VectorIndexReport> {
+  return withMaintenanceProgress(onProgress, () =>
+    invoke("rebuild_vector_index", { newDim, sessionId }),
+  );
+}
+
+export interface MemoryExportFilter {
+  agent_type?: "guardian" | "copilot";
+  since?: string;
+  until?: string;
+  limit?: number;
+}
+
+export type ReEmbedPolicy = "never" | "mismatched" | "always";
+
+export interface MemoryTransferReport {
+  path: string;
+  records: number;
+  re_embedded: number;
+  skipped: number;
+  errors: string[];
+  duration_ms: number;
+}
+
+export async function exportAgentMemory(
+  path: string,
+  filter?: MemoryExportFilter,
+  sessionId?: string,
+): Promise<MemoryTransferReport> {
+  return invoke("export_agent_memory", { path, filter, sessionId });
+}
+
+export async function importAgentMemory(
+  path: string,
+  reEmbed: ReEmbedPolicy = "never",
+  sessionId?: string,
+): Promise<MemoryTransferReport> {
+  return invoke("import_agent_memory", { path, reEmbed, sessionId });
+}
+
+export type MemoryBackendKind = "json" | "surreal";
+
+export interface MigrationReport {
+  migration_id: string;
+  memories: number;
+  patterns: number;
+  // Stored with a zero vector because embedding failed
+  unembedded: number;
+  duration_ms: number;
+}
+
+// Copies the active profile's JSON memory into SurrealDB; a failed run is rolled back
+export async function migrateMemoryBackend(
+  from: MemoryBackendKind = "json",
+  to: MemoryBackendKind = "surreal",
+  onProgress?: (progress: MaintenanceProgress) => void,
+  sessionId?: string,
+): Promise<MigrationReport> {
+  return withMaintenanceProgress(onProgress, () =>
+    invoke("migrate_memory_backend", { from, to, sessionId }),
+  );
+}
+
+export type MemorySource = "system_log" | "user_query" | "threat_report" | "performance_analysis";
+
+export interface MemoryListFilter {
+  agent_type?: "guardian" | "copilot";
+  source?: MemorySource;
+  since?: string;
+  until?: string;
+  sort?: "newest_first" | "oldest_first";
+}
+
+export interface AgentMemoryEntry {
+  id: string;
+  agent_type: "guardian" | "copilot";
+  content: string;
+  timestamp: string;
+  source: MemorySource;
+  metadata?: Record<string, unknown> | null;
+}
+
+export interface AgentMemoryPage {
+  items: AgentMemoryEntry[];
+  total: number;
+  offset: number;
+  limit: number;
+}
+
+export async function listAgentMemories(
+  filter?: MemoryListFilter,
+  offset = 0,
+  limit = 50,
+  sessionId?: string,
+): Promise<AgentMemoryPage> {
+  return invoke("list_agent_memories", { filter, offset, limit, sessionId });
+}
+
+/** Returns false if the memory no longer exists. */
+export async function deleteAgentMemory(memoryId: string, sessionId?: string): Promise<boolean> {
+  return invoke("delete_agent_memory", { memoryId, sessionId });
+}
+
+export type MitigationStatus =
+  | "detected"
+  | "investigating"
+  | "quarantined"
+  | "whitelisted"
+  | "deleted";
+
+export interface ThreatRecord {
+  id: string;
+  severity: "low" | "medium" | "high" | "critical";
+  yara_rule?: string | null;
+  heuristic_score?: number | null;
+  timestamp: string;
+  indicators: string[];
+  mitigation_status: MitigationStatus;
+  status_updated_at?: string | null;
+  status_note?: string | null;
+}
+
+export async function listThreats(
+  status?: MitigationStatus,
+  limit?: number,
+): Promise<ThreatRecord[]> {
+  return invoke("list_threats", { status, limit });
+}
+
+export async function updateThreatStatus(
+  threatId: string,
+  status: MitigationStatus,
+  note?: string,
+  sessionId?: string,
+): Promise<void> {
+  return invoke("update_threat_status", { threatId, status, note, sessionId });
+}
+
+export async function bulkAcknowledgeThreats(
+  threatIds: string[],
+  sessionId?: string,
+): Promise<number> {
+  return invoke("bulk_acknowledge_threats", { threatIds, sessionId });
+}
+
+export interface ScanRunSummary {
+  id: string;
+  root: string;
+  started_at: string;
+  finished_at: string;
+  status: "completed" | "cancelled";
+  total: number;
+  scanned: number;
+  malicious: number;
+  errors: number;
+  duration_ms: number;
+}
+
+export interface ScanFileVerdict {
+  path: string;
+  sha256?: string | null;
+  malicious: boolean;
+  local_match?: string | null;
+  error?: string | null;
+}
+
+export interface ScanDetails {
+  summary: ScanRunSummary;
+  files: ScanFileVerdict[];
+}
+
+export interface ScanDiff {
+  new_malicious: ScanFileVerdict[];
+  removed_malicious: ScanFileVerdict[];
+  changed_malicious: { before: ScanFileVerdict; after: ScanFileVerdict }[];
+}
+
+export async function listScanHistory(
+  root?: string,
+  limit?: number,
+): Promise<ScanRunSummary[]> {
+  return invoke("list_scan_history", { root, limit });
+}
+
+export async function getScanDetails(scanId: string): Promise<ScanDetails> {
+  return invoke("get_scan_details", { scanId });
+}
+
+export async function diffScans(
+  baseScanId: string,
+  targetScanId: string,
+): Promise<ScanDiff> {
+  return invoke("diff_scans", { baseScanId, targetScanId });
+}
+
+export type PlanStepStatus =
+  | "running"
+  | "completed"
+  | "failed"
+  | "skipped"
+  | "awaiting_confirmation"
+  | "blocked"
+  | "timed_out";
+
+export interface PlanProgress {
+  step: number;
+  total_steps: number;
+  description: string;
+  function?: string | null;
+  status: PlanStepStatus;
+  output?: unknown;
+  error?: string;
+}
+
+export interface PlanExecutionReport {
+  steps: PlanProgress[];
+  completed: number;
+  failed: number;
+  awaiting_confirmation?: number | null;
+  timed_out: boolean;
+  duration_ms: number;
+}
+
+export interface ExecutePlanOptions {
+  constraints?: Record<string, unknown>;
+  confirmedSteps?: number[];
+  startStep?: number;
+  sessionId?: string;
+}
+
+export async function executePlan(
+  plan: unknown,
+  options: ExecutePlanOptions = {},
+  onProgress?: (progress: PlanProgress) => void,
+): Promise<PlanExecutionReport> {
+  const unlisten = onProgress
+    ? await listenEvent("plan://progress", (payload) => {
+        onProgress(payload);
+      })
+    : undefined;
+  try {
+    return await invoke("execute_plan", { plan, ...options });
+  } finally {
+    unlisten?.();
+  }
+}
+
+export type JobState =
+  | "queued"
+  | "running"
+  | "completed"
+  | "failed"
+  | "cancelled";
+
+export interface JobInfo {
+  job_id: string;
+  /** e.g. "folder_scan", "collaborative_analysis", "threat_consensus" */
+  kind: string;
+  state: JobState;
+  stage?: string | null;
+  /** 0..1 when the job can estimate it */
+  progress?: number | null;
+  created_at: string;
+  started_at?: string | null;
+  finished_at?: string | null;
+  result?: unknown;
+  error?: string | null;
+}
+
+export async function startCollaborativeAnalysis(
+  userInput: string,
+  taskType?: string,
+): Promise<string> {
+  return invoke("start_collaborative_analysis", { userInput, taskType });
+}
+
+export async function startMultiAgentAnalysis(
+  geminiModel?: string,
+  qwenModel?: string,
+): Promise<string> {
+  return invoke("start_multi_agent_analysis", { geminiModel, qwenModel });
+}
+
+export async function startThreatConsensus(): Promise<string> {
+  return invoke("start_threat_consensus");
+}
+
+export async function getJob(jobId: string): Promise<JobInfo> {
+  return invoke("get_job", { jobId });
+}
+
+export async function listJobs(kind?: string): Promise<JobInfo[]> {
+  return invoke("list_jobs", { kind });
+}
+
+export async function cancelJob(
+  jobId: string,
+  sessionId?: string,
+): Promise<JobInfo> {
+  return invoke("cancel_job", { jobId, sessionId });
+}
+
+export async function subscribeJobs(
+  onUpdate: (job: JobInfo) => void,
+): Promise<() => void> {
+  const unlisten = await listenEvent("job://update", (payload) => {
+    onUpdate(payload);
+  });
+  return () => {
+    unlisten();
+  };
+}
+
+export interface ApiQuotaStatus {
+  host: string;
+  requests_per_minute: number | null;
+  max_concurrent: number | null;
+  daily_quota: number | null;
+  used_today: number;
+  remaining_today: number | null;
+  requests_last_minute: number;
+  in_flight: number;
+  throttled: number;
+  rejected: number;
+  resets_at: string;
+}
+
+export async function getApiQuotaStatus(): Promise<ApiQuotaStatus[]> {
+  return invoke("get_api_quota_status");
+}
+
+export interface LlmCacheStats {
+  enabled: boolean;
+  entries: number;
+  bytes: number;
+  hits: number;
+  misses: number;
+}
+
+export async function getLlmCacheStats(): Promise<LlmCacheStats> {
+  return invoke("get_llm_cache_stats");
+}
+
+export async function clearLlmCache(): Promise<void> {
+  return invoke("clear_llm_cache");
+}
+
+export interface ConfigIssue {
+  // Dotted path such as "guardian.quarantine_dir", or a section name
+  field: string;
+  message: string;
+  severity: "error" | "warning";
+}
+
+// Checks a full config without applying it
+export async function validateConfig(
+  config: Record<string, unknown>,
+): Promise<ConfigIssue[]> {
+  return invoke("validate_config", { config });
+}
+
+export interface ImageAttachment {
+  mime_type: string;
+  // Base64 without the data: prefix
+  data: string;
+}
+
+export async function askAboutImages(
+  userInput: string,
+  options: {
+    images?: ImageAttachment[];
+    imagePaths?: string[];
+    captureScreen?: boolean;
+    sessionId?: string;
+  },
+): Promise<string> {
+  return invoke("handle_user_input_with_images", {
+    userInput,
+    images: options.images,
+    imagePaths: options.imagePaths,
+    captureScreen: options.captureScreen,
+    sessionId: options.sessionId,
+  });
+}
+
+export type CheckStatus = "skipped" | "pass" | "warn" | "fail";
+
+export interface DiagnosticCheck {
+  name: string;
+  status: CheckStatus;
+  detail: string;
+}
+
+export interface SelfDiagnosticsReport {
+  generated_at: string;
+  app_version: string;
+  platform: string;
+  overall: CheckStatus;
+  checks: DiagnosticCheck[];
+  features: Record<string, boolean>;
+  bundle_path: string | null;
+}
+
+// Pass bundlePath to also write a redacted zip for bug reports
+export async function runSelfDiagnostics(
+  bundlePath?: string,
+  sessionId?: string,
+): Promise<SelfDiagnosticsReport> {
+  return invoke("run_self_diagnostics", { bundlePath, sessionId });
+}
+
+export type LogLevel = "ERROR" | "WARN" | "INFO" | "DEBUG" | "TRACE";
+
+export interface LogEntry {
+  timestamp: string;
+  level: LogLevel;
+  module: string;
+  message: string;
+}
+
+// Newest first; level is a minimum severity, module a prefix like "oxide_guardian"
+export async function getRecentLogs(
+  level?: LogLevel,
+  module?: string,
+  limit?: number,
+): Promise<LogEntry[]> {
+  return invoke("get_recent_logs", { level, module, limit });
+}
+
+export type ProtectionState = "protected" | "attention_required" | "at_risk";
+
+export type SupervisedTask =
+  | "guardian_monitoring"
+  | "metrics_collector"
+  | "threat_remediation";
+
+export interface TaskHealth {
+  task: SupervisedTask;
+  healthy: boolean;
+  last_heartbeat: string | null;
+  restarts: number;
+  last_restart_at: string | null;
+  last_failure: string | null;
+  next_restart_at: string | null;
+}
+
+export interface ProtectionStatus {
+  state: ProtectionState;
+  issues: string[];
+  monitoring_enabled: boolean;
+  realtime_monitoring_active: boolean;
+  last_monitor_cycle: string | null;
+  yara_available: boolean;
+  virustotal_configured: boolean;
+  signatures_path: string | null;
+  signature_count: number | null;
+  signature_age_hours: number | null;
+  background_tasks: TaskHealth[];
+  // null when the SurrealDB backend is disabled
+  last_scan_at: string | null;
+  unacknowledged_threats: number | null;
+  oldest_unacknowledged_threat_at: string | null;
+  checked_at: string;
+}
+
+export async function getProtectionStatus(): Promise<ProtectionStatus> {
+  return invoke("get_protection_status");
+}
+
+export interface ProfilesInfo {
+  active: string;
+  profiles: string[];
+}
+
+export async function listProfiles(): Promise<ProfilesInfo> {
+  return invoke("list_profiles");
+}
+
+// Names are 1-32 letters, digits or underscores; a new name creates the profile
+export async function switchProfile(
+  name: string,
+  sessionId?: string,
+): Promise<ProfilesInfo> {
+  return invoke("switch_profile", { name, sessionId });
+}
+
+export type StartupPhase = "initializing" | "ready" | "degraded";
+
+export interface StartupStatus {
+  phase: StartupPhase;
+  step: string | null;
+  attempt: number;
+  // Components that failed to start; the app keeps running without them
+  failures: { component: string; error: string }[];
+  started_at: string;
+  finished_at: string | null;
+}
+
+export async function getStartupStatus(): Promise<StartupStatus> {
+  return invoke("get_startup_status");
+}
+
+export async function subscribeStartupStatus(
+  onUpdate: (status: StartupStatus) => void,
+): Promise<() => void> {
+  const unlisten = await listenEvent("startup://status", (payload) => {
+    onUpdate(payload);
+  });
+  return () => {
+    unlisten();
+  };
+}
+
+export type OnboardingStep =
+  | "welcome"
+  | "capabilities"
+  | "credentials"
+  | "ai_provider"
+  | "voice"
+  | "guardian"
+  | "finish";
+
+export type CredentialState = "missing" | "configured" | "expired" | "error";
+
+export interface OnboardingState {
+  finished: boolean;
+  current_step: OnboardingStep | null;
+  steps: { step: OnboardingStep; outcome: "completed" | "skipped" | null }[];
+  capabilities: {
+    input_devices: string[];
+    gpus: string[];
+    local_runtimes: {
+      name: string;
+      installed: boolean;
+      running: boolean;
+      endpoint: string;
+    }[];
+    keyring: boolean;
+  };
+  credentials: { provider: string; state: CredentialState; detail: string }[];
+  recommended_config: Record<string, unknown>;
+  notes: string[];
+  config: Record<string, unknown> | null;
+}
+
+export async function getOnboardingState(): Promise<OnboardingState> {
+  return invoke("get_onboarding_state");
+}
+
+// "finish" needs a valid config, either passed here or saved by an earlier step
+export async function completeOnboardingStep(
+  step: OnboardingStep,
+  skipped?: boolean,
+  config?: Record<string, unknown>,
+): Promise<OnboardingState> {
+  return invoke("complete_onboarding_step", { step, skipped, config });
+}
+
+export type ThreatSeverity = "Low" | "Medium" | "High" | "Critical";
+
+export interface HeuristicRule {
+  id: string;
+  description: string;
+  severity: ThreatSeverity;
+  enabled: boolean;
+  // Case-insensitive fragments; any may match within a field, all fields must match
+  name?: string[];
+  args?: string[];
+  path?: string[];
+  parent?: string[];
+  signer?: string[];
+}
+
+export interface HeuristicRulesStatus {
+  path: string | null;
+  loaded_at: string;
+  last_error: string | null;
+  rules: HeuristicRule[];
+}
+
+export interface ProcessSample {
+  name: string;
+  args?: string;
+  path?: string;
+  parent?: string;
+  signer?: string;
+}
+
+export interface RuleMatch {
+  rule_id: string;
+  description: string;
+  severity: ThreatSeverity;
+  matched: string[];
+}
+
+export async function getHeuristicRules(): Promise<HeuristicRulesStatus> {
+  return invoke("get_heuristic_rules");
+}
+
+export async function reloadHeuristicRules(
+  sessionId?: string,
+): Promise<HeuristicRulesStatus> {
+  return invoke("reload_heuristic_rules", { sessionId });
+}
+
+export async function testHeuristicRules(
+  sample: ProcessSample,
+): Promise<RuleMatch[]> {
+  return invoke("test_heuristic_rules", { sample });
+}
+
+// Process control always needs a session with "system.control"; critical
+// system processes and Oxide Pilot itself are refused. `expected` is what
+// the caller saw of the process (start time in Unix seconds, executable),
+// so a pid reused by another process is refused too.
+export interface ExpectedProcess {
+  startTime?: number;
+  exe?: string;
+}
+
+export async function killProcess(
+  pid: number,
+  sessionId: string,
+  expected: ExpectedProcess = {},
+): Promise<string> {
+  return invoke("kill_process", { pid, sessionId, ...expected });
+}
+
+export async function suspendProcess(
+  pid: number,
+  sessionId: string,
+  expected: ExpectedProcess = {},
+): Promise<string> {
+  return invoke("suspend_process", { pid, sessionId, ...expected });
+}
+
+export async function resumeProcess(
+  pid: number,
+  sessionId: string,
+  expected: ExpectedProcess = {},
+): Promise<string> {
+  return invoke("resume_process", { pid, sessionId, ...expected });
+}
+
+export type DriftCategory =
+  | "startup_item"
+  | "service"
+  | "hosts_file"
+  | "browser_setting";
+
+export interface DriftItem {
+  category: DriftCategory;
+  key: string;
+  change: "added" | "removed" | "changed";
+  baseline: string | null;
+  current: string | null;
+}
+
+export interface DriftReport {
+  baseline_taken_at: string;
+  checked_at: string;
+  drift: DriftItem[];
+}
+
+// Null until a baseline exists; Guardian records one on its first check
+export async function viewBaselineDrift(): Promise<DriftReport | null> {
+  return invoke("view_baseline_drift");
+}
+
+export interface BaselineSnapshot {
+  taken_at: string;
+  startup_items: Record<string, string>;
+  services: Record<string, string>;
+  hosts: Record<string, string>;
+  browser_settings: Record<string, string>;
+}
+
+export async function recordBaseline(
+  sessionId?: string,
+): Promise<BaselineSnapshot> {
+  return invoke("record_baseline", { sessionId });
+}
+
+export interface SlashCommandOutput {
+  command: string;
+  summary: string;
+  data: unknown;
+}
+
+// `input` is the full text, e.g. "/scan C:\\file.exe"; "/help" lists commands
+export async function runSlashCommand(
+  input: string,
+  sessionId?: string,
+): Promise<SlashCommandOutput> {
+  return invoke("run_slash_command", { input, sessionId });
+}
+
+export type PlaybookStep =
+  | { action: "notify"; message?: string | null }
+  | { action: "isolate" }
+  | { action: "scan" }
+  | { action: "collect_evidence" }
+  | { action: "summarize"; prompt?: string | null };
+
+export interface Playbook {
+  id: string;
+  name: string;
+  description: string;
+  enabled: boolean;
+  auto_run: boolean;
+  trigger: {
+    min_severity?: "Low" | "Medium" | "High" | "Critical";
+    yara_rule?: string[];
+    process_name?: string[];
+  };
+  steps: PlaybookStep[];
+}
+
+export interface PlaybookLibrary {
+  dir: string;
+  playbooks: Playbook[];
+  // Files that failed to load, with the reason
+  errors: string[];
+}
+
+export async function listPlaybooks(): Promise<PlaybookLibrary> {
+  return invoke("list_playbooks");
+}
+
+export interface PlaybookRun {
+  playbook_id: string;
+  threat_id: string | null;
+  dry_run: boolean;
+  started_at: string;
+  finished_at: string;
+  steps: {
+    step: PlaybookStep;
+    status: "planned" | "succeeded" | "failed" | "skipped";
+    detail: string;
+  }[];
+}
+
+export async function runPlaybook(
+  playbookId: string,
+  threatId?: string,
+  dryRun = false,
+  sessionId?: string,
+): Promise<PlaybookRun> {
+  return invoke("run_playbook", { playbookId, threatId, dryRun, sessionId });
+}
+
+export interface EvidenceManifest {
+  threat_id: string;
+  created_at: string;
+  host: string;
+  encrypted: boolean;
+  files: { name: string; size: number; sha256: string }[];
+  warnings: string[];
+}
+
+export interface EvidenceBundle {
+  archive_path: string;
+  manifest_path: string;
+  manifest: EvidenceManifest;
+}
+
+/** Payload of `evidence://progress` events. */
+export interface EvidenceProgress {
+  threat_id: string;
+  stage: "process" | "hashes" | "network" | "archive" | "encrypt" | "done";
+  percent: number;
+}
+
+export async function collectEvidence(
+  threatId: string,
+  passphrase?: string,
+  sessionId?: string,
+): Promise<EvidenceBundle> {
+  return invoke("collect_evidence", { threatId, passphrase, sessionId });
+}
+
+/** Start the OpenAI-compatible local API; resolves to its base URL. */
+export async function startLocalApi(
+  portOverride?: number,
+  tokenOverride?: string,
+  sessionId?: string,
+): Promise<string> {
+  return invoke("local_api_start", { portOverride, tokenOverride, sessionId });
+}
+
+export async function stopLocalApi(sessionId?: string): Promise<boolean> {
+  return invoke("local_api_stop", { sessionId });
+}
+
+export async function getLocalApiStatus(): Promise<{ running: boolean; base_url?: string }> {
+  return invoke("local_api_status");
+}
+
+export interface WebhookDeliveryResult {
+  endpoint: string;
+  attempts: number;
+  status: number | null;
+  error: string | null;
+}
+
+/** Send a test event to every enabled webhook. */
+export async function testWebhooks(): Promise<WebhookDeliveryResult[]> {
+  return invoke("test_webhooks");
+}
+
+/** Store the signing key of a `signed` webhook endpoint; an empty secret removes it. */
+export async function setWebhookSecret(
+  url: string,
+  secret: string,
+  sessionId?: string,
+): Promise<void> {
+  return invoke("set_webhook_secret", { url, secret, sessionId });
+}
+
+export interface EmailTestResult {
+  host: string;
+  recipients: string[];
+  error: string | null;
+}
+
+/** Send a test message through every configured email alert sink. */
+export async function testAlertEmail(): Promise<EmailTestResult[]> {
+  return invoke("test_alert_email");
+}
+
+/** Store the SMTP password for an email alert sink; an empty password removes it. */
+export async function setSmtpPassword(
+  host: string,
+  username: string,
+  password: string,
+  sessionId?: string,
+): Promise<void> {
+  return invoke("set_smtp_password", { host, username, password, sessionId });
+}
+
+export interface ConsolidationReport {
+  examined: number;
+  summaries: number;
+  consolidated: number;
+  errors: string[];
+  duration_ms: number;
+}
+
+/** Summarize clusters of old agent memories now instead of waiting for the schedule. */
+export async function consolidateAgentMemory(sessionId?: string): Promise<ConsolidationReport> {
+  return invoke("consolidate_agent_memory", { sessionId });
+}
+
+export type PatternType =
+  | "ApplicationUsage"
+  | "TimeBasedActivity"
+  | "CommandSequence"
+  | "ErrorResolution"
+  | "SystemConfiguration"
+  | "TopicInterest";
+
+export interface UserPattern {
+  pattern_id: string;
+  pattern_type: PatternType;
+  frequency: number;
+  last_occurrence: string;
+  confidence: number;
+  description: string;
+  weight: number;
+  /** Occurrences per local hour of day */
+  hours: number[];
+}
+
+/** Habits mined from chat history, most confident first. */
+export async function getUserPatterns(sessionId?: string): Promise<UserPattern[]> {
+  return invoke("get_user_patterns", { sessionId });
+}
+
+export type SuggestionKind = "memory_growth" | "recurring_problem" | "repeated_threat" | "habit";
+
+export interface Suggestion {
+  id: string;
+  kind: SuggestionKind;
+  subject: string;
+  message: string;
+  /** Chat request to send when the user accepts */
+  prompt: string | null;
+  score: number;
+  created_at: string;
+}
+
+/** Pending proactive suggestions; new ones also arrive as `suggestions://new` events. */
+export async function getSuggestions(): Promise<Suggestion[]> {
+  return invoke("get_suggestions");
+}
+
+/** Record acceptance and return the suggestion so its prompt can be sent. */
+export async function acceptSuggestion(suggestionId: string): Promise<Suggestion> {
+  return invoke("accept_suggestion", { suggestionId });
+}
+
+export async function dismissSuggestion(suggestionId: string): Promise<void> {
+  return invoke("dismiss_suggestion", { suggestionId });
+}
+
+export interface TechniqueCount {
+  id: string;
+  name: string;
+  count: number;
+}
+
+export interface TacticSummary {
+  id: string;
+  name: string;
+  /** Detections with at least one technique under this tactic */
+  detections: number;
+  /** Techniques seen, most frequent first */
+  techniques: TechniqueCount[];
+  /** Techniques of this tactic in the bundled mapping table */
+  known_techniques: number;
+}
+
+export interface AttackMatrixSummary {
+  detections: number;
+  /** Detections without any ATT&CK technique */
+  unmapped: number;
+  tactics: TacticSummary[];
+}
+
+/** Threat detections per MITRE ATT&CK tactic over the last `days` (default 30). */
+export async function getAttackMatrixSummary(days?: number): Promise<AttackMatrixSummary> {
+  return invoke("get_attack_matrix_summary", { days });
+}
+
+export type IocKind = "sha256" | "blake3" | "domain" | "ip" | "url";
+
+export interface IocImportReport {
+  source: string;
+  /** Indicators stored from this feed */
+  imported: number;
+  /** Indicators of the previous import from this source that were dropped */
+  replaced: number;
+  /** Entries that were not usable indicators */
+  skipped: number;
+  /** Entries that had already expired */
+  expired: number;
+  by_kind: Partial<Record<IocKind, number>>;
+  /** Live indicators across all sources */
+  total: number;
+}
+
+/** Import a STIX 2.1 bundle or CSV indicator list from a file path or URL. */
+export async function importIocFeed(
+  pathOrUrl: string,
+  options: { source?: string; expiryDays?: number; sessionId?: string } = {},
+): Promise<IocImportReport> {
+  return invoke("import_ioc_feed", {
+    pathOrUrl,
+    source: options.source,
+    expiryDays: options.expiryDays,
+    sessionId: options.sessionId,
+  });
+}
+
+export type FirewallBackendKind = "windows_firewall" | "nftables" | "iptables";
+
+export interface FirewallRule {
+  id: string;
+  /** Executable the rule blocks */
+  program: string | null;
+  /** Processes cut off when the rule was created */
+  pids: number[];
+  backend: FirewallBackendKind;
+  reason: string | null;
+  created_at: string;
+}
+
+/** Block network access for a running process or a program path. */
+export async function blockProcessNetwork(
+  target: { pid?: number; path?: string; startTime?: number },
+  options: { reason?: string; sessionId?: string } = {},
+): Promise<FirewallRule> {
+  return invoke("block_process_network", {
+    pid: target.pid,
+    path: target.path,
+    startTime: target.startTime,
+    reason: options.reason,
+    sessionId: options.sessionId,
+  });
+}
+
+export async function listOxideFirewallRules(): Promise<FirewallRule[]> {
+  return invoke("list_oxide_firewall_rules");
+}
+
+export async function removeFirewallRule(ruleId: string, sessionId?: string): Promise<FirewallRule> {
+  return invoke("remove_rule", { ruleId, sessionId });
+}
@@ -0,0 +1,153 @@
+//! Commands for the temp and cache cleanup in `oxide_core::cleanup`, and
+//! the hourly purge of runs whose undo window has ended.
+//!
+//! `run_cleanup` takes item ids rather than paths: the items are looked up
+//! in a fresh preview, so only known cache locations can ever be cleaned.
+
+use crate::command_guard;
+use crate::oxide_system::OxideSystem;
+use chrono::Utc;
+use log::{info, warn};
+use oxide_core::cleanup::{self, CleanupPreview, CleanupRun, CleanupRunSummary, UndoReport};
+use oxide_core::config::CleanupConfig;
+use oxide_core::i18n;
+use std::path::Path;
+use tauri::State;
+
+const PURGE_INTERVAL_SECS: u64 = 60 * 60;
+
+async fn current_config(system: &OxideSystem) -> CleanupConfig {
+    system.get_config().await.cleanup.unwrap_or_default()
+}
+
+async fn blocking<T: Send + 'static>(
+    task: impl FnOnce() -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    tokio::task::spawn_blocking(task)
+        .await
+        .map_err(|e| format!("Cleanup task join error: {e}"))?
+}
+
+/// What a cleanup would remove, per location.
+#[tauri::command]
+pub async fn preview_cleanup(state: State<'_, crate::AppState>) -> Result<CleanupPreview, String> {
+    let system = state
+        .oxide_system
+        .read()
+        .await
+        .clone()
+        .ok_or(i18n::t("error.system_not_initialized"))?;
+    let cfg = current_config(&system).await;
+    blocking(move || Ok(cleanup::preview(&cfg))).await
+}
+
+/// Move the files of the previewed items `item_ids` to the staging area.
+#[tauri::command]
+pub async fn run_cleanup(
+    item_ids: Vec<String>,
+    session_id: Option<String>,
+    state: State<'_, crate::AppState>,
+) -> Result<CleanupRun, String> {
+    let system = state
+        .oxide_system
+        .read()
+        .await
+        .clone()
+        .ok_or(i18n::t("error.system_not_initialized"))?;
+    command_guard::authorize_command(&system, session_id.as_deref(), "run_cleanup").await?;
+    let cfg = current_config(&system).await;
+    let run = blocking(move || {
+        let items: Vec<_> = cleanup::preview(&cfg)
+            .items
+            .into_iter()
+            .filter(|item| item_ids.contains(&item.id))
+            .collect();
+        if items.is_empty() {
+            return Err("Nothing to clean for the selected items".to_string());
+        }
+        cleanup::execute(
+            &items,
+            Path::new(cleanup::STAGING_DIR),
+            cfg.undo_retention_days(),
+        )
+    })
+    .await?;
+    info!(
+        "Cleanup {} moved {} files ({:.1} MB) to staging, {} failed",
+        run.summary.id,
+        run.files.len(),
+        run.summary.reclaimed_bytes as f64 / 1024.0 / 1024.0,
+        run.summary.failed
+    );
+    Ok(run)
+}
+
+/// Cleanup runs that can still be undone, newest first.
+#[tauri::command]
+pub async fn list_cleanup_runs() -> Result<Vec<CleanupRunSummary>, String> {
+    blocking(|| Ok(cleanup::list_runs(Path::new(cleanup::STAGING_DIR)))).await
+}
+
+#[tauri::command]
+pub async fn undo_cleanup(
+    run_id: String,
+    session_id: Option<String>,
+    state: State<'_, crate::AppState>,
+) -> Result<UndoReport, String> {
+    let system = state
+        .oxide_system
+        .read()
+        .await
+        .clone()
+        .ok_or(i18n::t("error.system_not_initialized"))?;
+    command_guard::authorize_command(&system, session_id.as_deref(), "undo_cleanup").await?;
+    let report = blocking(move || cleanup::undo(Path::new(cleanup::STAGING_DIR), &run_id)).await?;
+    info!(
+        "Cleanup {} undone: {} files restored, {} skipped, {} failed",
+        report.run_id, report.restored_files, report.skipped, report.failed
+    );
+    Ok(report)
+}
+
+/// Delete a run's staged files now instead of at expiry; returns the bytes freed.
+#[tauri::command]
+pub async fn purge_cleanup_run(
+    run_id: String,
+    session_id: Option<String>,
+    state: State<'_, crate::AppState>,
+) -> Result<u64, String> {
+    let system = state
+        .oxide_system
+        .read()
+        .await
+        .clone()
+        .ok_or(i18n::t("error.system_not_initialized"))?;
+    command_guard::authorize_command(&system, session_id.as_deref(), "purge_cleanup_run").await?;
+    blocking(move || cleanup::purge(Path::new(cleanup::STAGING_DIR), &run_id)).await
+}
+
+/// Purge expired runs now and every hour while the system runs.
+pub fn spawn(system: OxideSystem) {
+    tokio::spawn(async move {
+        loop {
+            match blocking(|| {
+                Ok(cleanup::purge_expired(
+                    Path::new(cleanup::STAGING_DIR),
+                    Utc::now(),
+                ))
+            })
+            .await
+            {
+                Ok(purged) if !purged.is_empty() => {
+                    info!("Purged {} expired cleanup run(s)", purged.len())
+                }
+                Ok(_) => {}
+                Err(e) => warn!("Cleanup purge failed: {e}"),
+            }
+            tokio::time::sleep(std::time::Duration::from_secs(PURGE_INTERVAL_SECS)).await;
+            if !system.is_running().await {
+                break;
+            }
+        }
+    });
+}
@@ -0,0 +1,218 @@
+//! Role-based authorization for Tauri commands.
+//!
+//! Guarded commands accept an optional `session_id` and call
+//! [`authorize_command`] before doing any work. The permission each command
+//! needs is declared once in [`COMMAND_PERMISSIONS`].
+
+use crate::oxide_system::OxideSystem;
+use oxide_core::i18n;
+use oxide_core::security_manager::SecurityManager;
+
+/// Permission required by each guarded command.
+pub const COMMAND_PERMISSIONS: &[(&str, &str)] = &[
+    ("update_system_config", "config.modify"),
+    ("set_offline_mode", "config.modify"),
+    ("set_locale", "config.modify"),
+    ("set_voice_language", "config.modify"),
+    ("reload_prompt_templates", "config.modify"),
+    ("update_hotkeys", "config.modify"),
+    ("optimize_performance", "system.control"),
+    ("run_cleanup", "system.control"),
+    ("undo_cleanup", "system.control"),
+    ("purge_cleanup_run", "system.control"),
+    ("set_performance_monitoring", "system.control"),
+    ("clear_performance_alerts", "system.control"),
+    ("respond_remediation", "system.control"),
+    ("disable_startup_item", "system.control"),
+    ("kill_process", "system.control"),
+    ("suspend_process", "system.control"),
+    ("block_process_network", "system.control"),
+    ("remove_rule", "system.control"),
+    ("resume_process", "system.control"),
+    ("record_baseline", "system.control"),
+    ("get_integrity_status", "system.control"),
+    ("record_integrity_manifest", "system.control"),
+    ("install_update", "system.control"),
+    ("upload_crash_report", "data.access"),
+    ("run_playbook", "system.control"),
+    ("local_api_start", "system.control"),
+    ("local_api_stop", "system.control"),
+    ("set_smtp_password", "config.modify"),
+    ("set_webhook_secret", "config.modify"),
+    ("scan_process_memory", "scan.execute"),
+    ("reload_heuristic_rules", "config.modify"),
+    ("import_ioc_feed", "config.modify"),
+    ("backup_database", "system.control"),
+    ("restore_database", "system.control"),
+    ("compact_database", "system.control"),
+    ("rebuild_vector_index", "system.control"),
+    ("export_agent_memory", "data.access"),
+    ("collect_evidence", "data.access"),
+    ("query_llm_audit", "data.access"),
+    ("import_agent_memory", "data.access"),
+    ("migrate_memory_backend", "data.access"),
+    ("list_agent_memories", "data.access"),
+    ("delete_agent_memory", "data.access"),
+    ("consolidate_agent_memory", "data.access"),
+    ("get_user_patterns", "data.access"),
+    ("handle_user_input_with_images", "data.access"),
+    ("switch_profile", "data.access"),
+    ("update_threat_status", "system.control"),
+    ("bulk_acknowledge_threats", "system.control"),
+    ("label_threat", "system.control"),
+    ("retrain_threat_model", "system.control"),
+    ("execute_plan", "system.control"),
+    ("cancel_job", "system.control"),
+    ("assign_security_role", "security.manage"),
+    ("revoke_security_role", "security.manage"),
+    ("set_command_permission_enforcement", "security.manage"),
+    ("rpa_grant_permission", "security.manage"),
+    ("rpa_grant_profile", "security.manage"),
+    ("rpa_revoke_grant", "security.manage"),
+    ("create_mcp_token", "security.manage"),
+    ("rotate_mcp_token", "security.manage"),
+    ("revoke_mcp_token", "security.manage"),
+    ("add_remote_host", "config.modify"),
+    ("remove_remote_host", "config.modify"),
+    ("remote_scan_file", "scan.execute"),
+    ("remote_respond_remediation", "system.control"),
+    ("get_security_role_assignments", "security.view"),
+    ("run_self_diagnostics", "config.view"),
+];
+
+pub fn required_permission(command: &str) -> Option<&'static str> {
+    COMMAND_PERMISSIONS
+        .iter()
+        .find(|(name, _)| *name == command)
+        .map(|(_, permission)| *permission)
+}
+
+/// Check that `session_id` may invoke `command`.
+///
+/// Calls without a session are allowed only while command permission
+/// enforcement is off. It is on by default once any user holds the admin
+/// role, and `security.enforce_command_permissions` overrides that.
+pub async fn authorize_command(
+    system: &OxideSystem,
+    session_id: Option<&str>,
+    command: &str,
+) -> Result<(), String> {
+    authorize(system.security_manager(), session_id, command).await
+}
+
+async fn authorize(
+    security: &SecurityManager,
+    session_id: Option<&str>,
+    command: &str,
+) -> Result<(), String> {
+    let Some(permission) = required_permission(command) else {
+        return Ok(());
+    };
+
+    match session_id {
+        Some(session_id) => security
+            .require_permission(session_id, permission)
+            .await
+            .map(|_| ())
+            .map_err(|e| {
+                i18n::tf(
+                    "error.command_denied",
+                    &[("command", &command), ("reason", &e.to_string())],
+                )
+            }),
+        None if security.command_permissions_enforced().await => Err(i18n::tf(
+            "error.session_required",
+            &[("command", &command), ("permission", &permission)],
+        )),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxide_core::encryption::EncryptionManager;
+    use oxide_core::security_manager::Role;
+
+    #[test]
+    fn guarded_commands_map_to_role_permissions() {
+        assert_eq!(
+            required_permission("update_system_config"),
+            Some("config.modify")
+        );
+        assert_eq!(required_permission("get_system_status"), None);
+        for command in [
+            "rpa_grant_permission",
+            "rpa_grant_profile",
+            "rpa_revoke_grant",
+        ] {
+            assert_eq!(required_permission(command), Some("security.manage"));
+        }
+
+        let known = Role::Admin.permissions();
+        for (command, permission) in COMMAND_PERMISSIONS {
+            assert!(
+                known.contains(permission),
+                "{command} requires '{permission}' which no role grants"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn sessionless_calls_are_rejected_when_enforced() {
+        let security = SecurityManager::new(&EncryptionManager::generate_key()).unwrap();
+        assert!(authorize(&security, None, "assign_security_role")
+            .await
+            .is_ok());
+
+        security
+            .assign_role("admin", Role::Admin, Some("admin passphrase"))
+            .await
+            .unwrap();
+        assert!(authorize(&security, None, "assign_security_role")
+            .await
+            .is_err());
+        assert!(authorize(&security, None, "get_system_status")
+            .await
+            .is_ok());
+
+        let session = security
+            .create_session(
+                "admin".to_string(),
+                Some("admin passphrase"),
+                Vec::new(),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(
+            authorize(&security, Some(&session.session_id), "assign_security_role")
+                .await
+                .is_ok()
+        );
+
+        security
+            .set_command_permission_enforcement(Some(false))
+            .await;
+        assert!(authorize(&security, None, "assign_security_role")
+            .await
+            .is_ok());
+
+        security
+            .set_command_permission_enforcement(Some(true))
+            .await;
+        let viewer = security
+            .create_session("guest".to_string(), None, Vec::new(), None, None)
+            .await
+            .unwrap();
+        assert!(authorize(&security, None, "update_system_config")
+            .await
+            .is_err());
+        assert!(
+            authorize(&security, Some(&viewer.session_id), "update_system_config")
+                .await
+                .is_err()
+        );
+    }
+}
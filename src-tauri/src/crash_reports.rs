@@ -0,0 +1,159 @@
+//! Crash reports written by `oxide_core::crash`: importing them as
+//! incidents so the app can analyze its own instability, and the commands
+//! to list, preview and upload them.
+//!
+//! Reports left by an earlier run are imported when the system starts;
+//! panics while it runs are imported as they happen. Uploads send only the
+//! redacted form shown by `get_crash_report` with `redacted: true`.
+
+use crate::command_guard;
+use crate::oxide_system::OxideSystem;
+use chrono::Utc;
+use log::info;
+#[cfg(feature = "surrealdb-metrics")]
+use log::warn;
+#[cfg(feature = "surrealdb-metrics")]
+use oxide_core::crash::CrashKind;
+use oxide_core::crash::{self, CrashReport, CrashSummary};
+use oxide_core::{i18n, network};
+#[cfg(feature = "surrealdb-metrics")]
+use oxide_memory::{IncidentInfo, IncidentSeverity, ResolutionStatus};
+use tauri::State;
+
+#[cfg(feature = "surrealdb-metrics")]
+fn to_incident(report: &CrashReport) -> IncidentInfo {
+    let location = report
+        .location
+        .as_deref()
+        .map(|l| format!(" at {l}"))
+        .unwrap_or_default();
+    IncidentInfo {
+        description: format!(
+            "Oxide Pilot {} crashed: {}{location}",
+            report.version, report.message
+        ),
+        timestamp: report.timestamp,
+        severity: match report.kind {
+            CrashKind::Native => IncidentSeverity::Critical,
+            CrashKind::Panic => IncidentSeverity::Error,
+        },
+        error_code: Some(
+            report
+                .error_code
+                .clone()
+                .unwrap_or_else(|| "PANIC".to_string()),
+        ),
+        stack_trace: Some(report.backtrace.clone()),
+        resolution_status: ResolutionStatus::Open,
+        related_processes: Vec::new(),
+    }
+}
+
+/// Import unrecorded reports now and new ones as they are written.
+#[cfg(feature = "surrealdb-metrics")]
+pub fn spawn(system: OxideSystem) {
+    let mut reports = crash::subscribe();
+    tokio::spawn(async move {
+        let pending: Vec<CrashReport> = crash::list(&crash::dir())
+            .into_iter()
+            .filter(|r| !r.recorded)
+            .collect();
+        if !pending.is_empty() {
+            info!("Importing {} crash report(s) as incidents", pending.len());
+        }
+        for report in pending {
+            record(&system, report).await;
+        }
+        while let Some(report) = reports.recv().await {
+            record(&system, report).await;
+        }
+    });
+}
+
+#[cfg(feature = "surrealdb-metrics")]
+async fn record(system: &OxideSystem, mut report: CrashReport) {
+    let Some(backend) = system.surreal_backend() else {
+        return;
+    };
+    if let Err(e) = backend.insert_incident(&to_incident(&report)).await {
+        warn!("Failed to record crash {} as an incident: {e:#}", report.id);
+        return;
+    }
+    report.recorded = true;
+    if let Err(e) = report.save(&crash::dir()) {
+        warn!("Failed to mark crash {} as recorded: {e}", report.id);
+    }
+}
+
+/// Crash reports on disk, newest first.
+#[tauri::command]
+pub async fn list_crash_reports() -> Result<Vec<CrashSummary>, String> {
+    Ok(crash::list(&crash::dir())
+        .iter()
+        .map(CrashReport::summary)
+        .collect())
+}
+
+/// One crash report; with `redacted` exactly what an upload would send.
+#[tauri::command]
+pub async fn get_crash_report(
+    id: String,
+    redacted: Option<bool>,
+    state: State<'_, crate::AppState>,
+) -> Result<CrashReport, String> {
+    let report = crash::load(&crash::dir(), &id)?;
+    if !redacted.unwrap_or(false) {
+        return Ok(report);
+    }
+    let system = state
+        .oxide_system
+        .read()
+        .await
+        .clone()
+        .ok_or(i18n::t("error.system_not_initialized"))?;
+    serde_json::from_value(crash::redacted(&report, &privacy_config(&system).await))
+        .map_err(|e| format!("Redacted crash report is malformed: {e}"))
+}
+
+/// Send the redacted report to the configured crash endpoint.
+#[tauri::command]
+pub async fn upload_crash_report(
+    id: String,
+    session_id: Option<String>,
+    state: State<'_, crate::AppState>,
+) -> Result<CrashSummary, String> {
+    let system = state
+        .oxide_system
+        .read()
+        .await
+        .clone()
+        .ok_or(i18n::t("error.system_not_initialized"))?;
+    command_guard::authorize_command(&system, session_id.as_deref(), "upload_crash_report").await?;
+    let upload_url = system
+        .get_config()
+        .await
+        .crash_reports
+        .and_then(|c| c.upload_url)
+        .ok_or("No crash report upload URL is configured")?;
+    let dir = crash::dir();
+    let mut report = crash::load(&dir, &id)?;
+    let body = crash::redacted(&report, &privacy_config(&system).await);
+
+    network::ensure_online(&upload_url).map_err(|e| e.to_string())?;
+    network::http_client()
+        .post(&upload_url)
+        .json(&body)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Crash report upload failed: {e}"))?;
+    info!("Crash report {id} uploaded");
+
+    report.uploaded_at = Some(Utc::now());
+    report.save(&dir)?;
+    Ok(report.summary())
+}
+
+async fn privacy_config(system: &OxideSystem) -> oxide_core::config::PrivacyConfig {
+    system.get_config().await.privacy.unwrap_or_default()
+}
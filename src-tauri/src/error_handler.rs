@@ -1,8 +1,8 @@
 use log::{error, info, warn};
 use oxide_copilot::errors::CopilotError;
 use oxide_core::google_auth::AuthError;
+use oxide_core::prometheus;
 use serde::{Deserialize, Serialize};
-use serde_json::json;
 use std::fmt;
 use thiserror::Error;
 
@@ -363,6 +363,15 @@ where
     ))
 }
 
+/// Error counts by kind and the most recent error.
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorStats {
+    pub error_counts: std::collections::HashMap<String, u64>,
+    pub total_errors: u64,
+    pub recent_errors_count: usize,
+    pub last_error: Option<ErrorResponse>,
+}
+
 /// Error monitoring and metrics collection
 pub struct ErrorMonitor {
     error_counts: std::sync::Arc<std::sync::Mutex<std::collections::HashMap<String, u64>>>,
@@ -382,6 +391,8 @@ impl ErrorMonitor {
     }
 
     pub fn record_error(&self, error_response: &ErrorResponse) {
+        prometheus::ERRORS_TOTAL.inc(&[("type", &error_response.error_type)]);
+
         // Update error counts
         if let Ok(mut counts) = self.error_counts.lock() {
             *counts.entry(error_response.error_type.clone()).or_insert(0) += 1;
@@ -396,7 +407,7 @@ impl ErrorMonitor {
         }
     }
 
-    pub fn get_error_stats(&self) -> Result<serde_json::Value, OxideError> {
+    pub fn get_error_stats(&self) -> Result<ErrorStats, OxideError> {
         let counts = self
             .error_counts
             .lock()
@@ -407,12 +418,12 @@ impl ErrorMonitor {
             .lock()
             .map_err(|_| OxideError::Internal("Failed to lock last errors".to_string()))?;
 
-        Ok(json!({
-            "error_counts": *counts,
-            "total_errors": counts.values().sum::<u64>(),
-            "recent_errors_count": last_errors.len(),
-            "last_error": last_errors.back()
-        }))
+        Ok(ErrorStats {
+            error_counts: counts.clone(),
+            total_errors: counts.values().sum(),
+            recent_errors_count: last_errors.len(),
+            last_error: last_errors.back().cloned(),
+        })
     }
 
     pub fn get_recent_errors(&self, limit: usize) -> Result<Vec<ErrorResponse>, OxideError> {
@@ -0,0 +1,369 @@
+//! Typed payloads for every event the backend emits to the frontend.
+//!
+//! Each payload names its own event through [`AppEvent`], so a call site
+//! cannot pair an event with the wrong shape. `npm run bindings` generates
+//! the TypeScript payload types from the `AppEvent` impls below into
+//! `src-frontend/src/lib/bindings.ts`; bump [`SCHEMA_VERSION`] whenever a
+//! payload changes in a way existing listeners would misread.
+
+use crate::hotkey_manager::PanicStopReport;
+use crate::job_manager::JobInfo;
+use crate::scan_progress::ScanRate;
+use crate::startup::StartupStatus;
+use crate::suggestions::Suggestion;
+use crate::watchdog::TaskRestart;
+use log::debug;
+use oxide_copilot::plan_executor::PlanProgress;
+use oxide_core::hotkeys::HotkeyAction;
+use oxide_core::types::ImageAttachment;
+use oxide_core::updates::UpdateCheck;
+use oxide_guardian::evidence::EvidenceProgress;
+use oxide_guardian::scanner::ExternalVerdict;
+use oxide_rpa::grants::GrantEvent;
+use serde::Serialize;
+#[cfg(feature = "surrealdb-metrics")]
+use tauri::Window;
+use tauri::{AppHandle, Manager};
+
+/// Version of the payload shapes below
+pub const SCHEMA_VERSION: u32 = 1;
+
+pub const FOLDER_SCAN_STARTED: &str = "folder_scan_started";
+pub const FOLDER_SCAN_PROGRESS: &str = "folder_scan_progress";
+pub const FOLDER_SCAN_COMPLETED: &str = "folder_scan_completed";
+pub const FOLDER_SCAN_CANCELLED: &str = "folder_scan_cancelled";
+pub const GOOGLE_AUTH_COMPLETE: &str = "google_auth_complete";
+pub const PLAN_PROGRESS: &str = "plan://progress";
+pub const JOB_UPDATE: &str = "job://update";
+pub const SUGGESTION_NEW: &str = "suggestions://new";
+pub const EVIDENCE_PROGRESS: &str = "evidence://progress";
+pub const STARTUP_STATUS: &str = "startup://status";
+pub const RPA_GRANT: &str = "rpa://grant";
+pub const GUARDIAN_METRICS: &str = "guardian://metrics";
+pub const MEMORY_MAINTENANCE: &str = "memory://maintenance";
+pub const HOTKEY_TRIGGERED: &str = "hotkey://triggered";
+pub const HOTKEY_SCREENSHOT_QUESTION: &str = "hotkey://screenshot_question";
+pub const HOTKEY_PANIC_STOP: &str = "hotkey://panic_stop";
+pub const UPDATE_AVAILABLE: &str = "updates://available";
+pub const WATCHDOG_RESTART: &str = "watchdog://restart";
+
+/// Every event name, for the schema the frontend checks against
+pub const ALL: [&str; 18] = [
+    FOLDER_SCAN_STARTED,
+    FOLDER_SCAN_PROGRESS,
+    FOLDER_SCAN_COMPLETED,
+    FOLDER_SCAN_CANCELLED,
+    GOOGLE_AUTH_COMPLETE,
+    PLAN_PROGRESS,
+    JOB_UPDATE,
+    SUGGESTION_NEW,
+    EVIDENCE_PROGRESS,
+    STARTUP_STATUS,
+    RPA_GRANT,
+    GUARDIAN_METRICS,
+    MEMORY_MAINTENANCE,
+    HOTKEY_TRIGGERED,
+    HOTKEY_SCREENSHOT_QUESTION,
+    HOTKEY_PANIC_STOP,
+    UPDATE_AVAILABLE,
+    WATCHDOG_RESTART,
+];
+
+/// A payload that knows which event carries it
+pub trait AppEvent: Serialize {
+    fn name(&self) -> &'static str;
+}
+
+/// Send `event` to every window.
+pub fn emit<E: AppEvent>(app: &AppHandle, event: &E) {
+    if let Err(e) = app.emit_all(event.name(), event) {
+        debug!("Failed to emit {}: {e}", event.name());
+    }
+}
+
+/// Send `event` to one window; fails once the window is gone.
+#[cfg(feature = "surrealdb-metrics")]
+pub fn emit_to<E: AppEvent>(window: &Window, event: &E) -> Result<(), String> {
+    window
+        .emit(event.name(), event)
+        .map_err(|e| format!("Failed to emit {}: {e}", event.name()))
+}
+
+/// Names and version of the event schema
+#[derive(Serialize, Debug, Clone)]
+pub struct EventSchema {
+    pub version: u32,
+    pub events: Vec<&'static str>,
+}
+
+pub fn schema() -> EventSchema {
+    EventSchema {
+        version: SCHEMA_VERSION,
+        events: ALL.to_vec(),
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct FolderScanStarted {
+    pub scan_id: String,
+    pub root: String,
+    pub network: bool,
+    /// Round trip to a network share, measured before scanning
+    pub latency_ms: Option<u64>,
+    pub workers: usize,
+}
+
+impl AppEvent for FolderScanStarted {
+    fn name(&self) -> &'static str {
+        FOLDER_SCAN_STARTED
+    }
+}
+
+/// Incremental folder scan state; only the fields that changed are set.
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct FolderScanProgress {
+    pub scan_id: String,
+    /// Files found while walking the tree
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discovered: Option<usize>,
+    /// Files skipped by the scan filter
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filtered: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scanned: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub malicious: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub errors: Option<usize>,
+    /// Files skipped because a recent clean verdict is cached
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_hits: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_match: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_verdict: Option<ExternalVerdict>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate: Option<ScanRate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub worker_utilization: Option<Vec<f64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl AppEvent for FolderScanProgress {
+    fn name(&self) -> &'static str {
+        FOLDER_SCAN_PROGRESS
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ScanCacheStats {
+    pub enabled: bool,
+    pub force_full_rescan: bool,
+    pub hits: usize,
+    pub misses: usize,
+}
+
+/// Final folder scan counts, sent as completed or cancelled
+#[derive(Serialize, Debug, Clone)]
+pub struct FolderScanSummary {
+    #[serde(skip)]
+    pub cancelled: bool,
+    pub scan_id: String,
+    pub scanned: usize,
+    pub total: usize,
+    pub malicious: usize,
+    pub errors: usize,
+    pub duration_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filtered: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bytes_scanned: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub worker_utilization: Option<Vec<f64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache: Option<ScanCacheStats>,
+}
+
+impl AppEvent for FolderScanSummary {
+    fn name(&self) -> &'static str {
+        if self.cancelled {
+            FOLDER_SCAN_CANCELLED
+        } else {
+            FOLDER_SCAN_COMPLETED
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthStatus {
+    Success,
+    Error,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct GoogleAuthComplete {
+    pub status: AuthStatus,
+    pub provider: &'static str,
+    /// Unix seconds, set on success
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl AppEvent for GoogleAuthComplete {
+    fn name(&self) -> &'static str {
+        GOOGLE_AUTH_COMPLETE
+    }
+}
+
+impl AppEvent for PlanProgress {
+    fn name(&self) -> &'static str {
+        PLAN_PROGRESS
+    }
+}
+
+impl AppEvent for JobInfo {
+    fn name(&self) -> &'static str {
+        JOB_UPDATE
+    }
+}
+
+impl AppEvent for Suggestion {
+    fn name(&self) -> &'static str {
+        SUGGESTION_NEW
+    }
+}
+
+impl AppEvent for EvidenceProgress {
+    fn name(&self) -> &'static str {
+        EVIDENCE_PROGRESS
+    }
+}
+
+impl AppEvent for StartupStatus {
+    fn name(&self) -> &'static str {
+        STARTUP_STATUS
+    }
+}
+
+impl AppEvent for GrantEvent {
+    fn name(&self) -> &'static str {
+        RPA_GRANT
+    }
+}
+
+#[cfg(feature = "surrealdb-metrics")]
+impl AppEvent for oxide_memory::SystemMetric {
+    fn name(&self) -> &'static str {
+        GUARDIAN_METRICS
+    }
+}
+
+#[cfg(feature = "surrealdb-metrics")]
+impl AppEvent for oxide_memory::MaintenanceProgress {
+    fn name(&self) -> &'static str {
+        MEMORY_MAINTENANCE
+    }
+}
+
+/// A global hotkey was pressed; sent before its action runs
+#[derive(Serialize, Debug, Clone, Copy)]
+#[serde(transparent)]
+pub struct HotkeyTriggered(pub HotkeyAction);
+
+impl AppEvent for HotkeyTriggered {
+    fn name(&self) -> &'static str {
+        HOTKEY_TRIGGERED
+    }
+}
+
+/// Screen capture to ask the assistant about
+#[derive(Serialize, Debug, Clone)]
+#[serde(transparent)]
+pub struct ScreenshotQuestion(pub ImageAttachment);
+
+impl AppEvent for ScreenshotQuestion {
+    fn name(&self) -> &'static str {
+        HOTKEY_SCREENSHOT_QUESTION
+    }
+}
+
+impl AppEvent for PanicStopReport {
+    fn name(&self) -> &'static str {
+        HOTKEY_PANIC_STOP
+    }
+}
+
+impl AppEvent for UpdateCheck {
+    fn name(&self) -> &'static str {
+        UPDATE_AVAILABLE
+    }
+}
+
+impl AppEvent for TaskRestart {
+    fn name(&self) -> &'static str {
+        WATCHDOG_RESTART
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frontend_bindings_list_every_event() {
+        let bindings = include_str!("../../src-frontend/src/lib/bindings.ts");
+        assert!(bindings.contains(&format!("EVENT_SCHEMA_VERSION = {SCHEMA_VERSION};")));
+        for name in ALL {
+            let keyed = |key: String| bindings.contains(&format!("  {key}: "));
+            assert!(
+                keyed(name.to_string()) || keyed(format!("\"{name}\"")),
+                "{name} missing"
+            );
+        }
+    }
+
+    #[test]
+    fn payloads_keep_their_wire_shape() {
+        let progress = FolderScanProgress {
+            scan_id: "s1".to_string(),
+            discovered: Some(10),
+            filtered: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_value(&progress).unwrap(),
+            serde_json::json!({"scan_id": "s1", "discovered": 10, "filtered": 2})
+        );
+
+        let summary = FolderScanSummary {
+            cancelled: true,
+            scan_id: "s1".to_string(),
+            scanned: 0,
+            total: 10,
+            malicious: 0,
+            errors: 0,
+            duration_ms: 5,
+            filtered: None,
+            bytes_scanned: None,
+            worker_utilization: None,
+            cache: None,
+        };
+        assert_eq!(summary.name(), FOLDER_SCAN_CANCELLED);
+        assert!(serde_json::to_value(&summary)
+            .unwrap()
+            .get("cancelled")
+            .is_none());
+        assert_eq!(
+            serde_json::to_value(HotkeyTriggered(HotkeyAction::PanicStop)).unwrap(),
+            "panic_stop"
+        );
+    }
+}
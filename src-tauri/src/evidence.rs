@@ -0,0 +1,134 @@
+//! Evidence bundles for escalating a threat to a security team.
+//!
+//! Gathers the metrics and log lines recorded around the threat and hands
+//! them to `oxide_guardian::evidence`, which adds the process, file and
+//! network details and writes the archive. The `collect_evidence` command
+//! runs as an `evidence_export` job; progress is also emitted as
+//! `evidence://progress` events.
+
+use crate::command_guard;
+use crate::events;
+use crate::job_manager::CancelMode;
+use crate::oxide_system::OxideSystem;
+use chrono::{Duration, Utc};
+use log::warn;
+use oxide_core::{i18n, logging};
+use oxide_guardian::evidence::{
+    self, EvidenceBundle, EvidenceProgress, EvidenceSources, EVIDENCE_DIR,
+};
+use oxide_guardian::guardian::ThreatEvent;
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use tauri::State;
+
+/// How far before the threat metrics and logs are collected.
+const WINDOW_MINUTES: i64 = 15;
+
+/// Log entries searched for related lines.
+const LOG_SCAN_LIMIT: usize = 2000;
+
+/// Build the evidence bundle for `threat`, reporting progress to `progress`.
+/// Raising `cancel` stops the build before the archive is written.
+pub async fn collect_bundle(
+    system: &OxideSystem,
+    threat: &ThreatEvent,
+    passphrase: Option<String>,
+    cancel: Arc<AtomicBool>,
+    progress: impl Fn(EvidenceProgress) + Send + 'static,
+) -> Result<EvidenceBundle, String> {
+    let start = threat.timestamp - Duration::minutes(WINDOW_MINUTES);
+    let metrics = system
+        .metrics_between(start, Utc::now())
+        .await
+        .unwrap_or_else(|e| {
+            warn!("Evidence for {} has no metrics: {e}", threat.id);
+            Vec::new()
+        });
+    let sources = EvidenceSources {
+        metrics,
+        logs: related_logs(threat, start),
+    };
+    let threat = threat.clone();
+    tokio::task::spawn_blocking(move || {
+        evidence::build_bundle(
+            &threat,
+            sources,
+            Path::new(EVIDENCE_DIR),
+            passphrase.as_deref(),
+            &cancel,
+            progress,
+        )
+    })
+    .await
+    .map_err(|e| format!("Evidence task join error: {e}"))?
+}
+
+/// Warnings and errors since `start`, plus any line naming the threat or
+/// its process.
+fn related_logs(threat: &ThreatEvent, start: chrono::DateTime<Utc>) -> Vec<serde_json::Value> {
+    let Some(logger) = logging::logger() else {
+        return Vec::new();
+    };
+    let mut needles = vec![threat.id.clone()];
+    needles.extend(threat.process_id.map(|pid| pid.to_string()));
+    needles.extend(threat.process_name.clone());
+    logger
+        .recent(None, None, LOG_SCAN_LIMIT)
+        .into_iter()
+        .filter(|entry| entry.timestamp >= start)
+        .filter(|entry| {
+            matches!(entry.level.as_str(), "WARN" | "ERROR")
+                || needles.iter().any(|n| entry.message.contains(n.as_str()))
+        })
+        .filter_map(|entry| serde_json::to_value(entry).ok())
+        .collect()
+}
+
+/// Collect an evidence bundle for a recorded threat. With a passphrase the
+/// archive is encrypted.
+#[tauri::command]
+pub async fn collect_evidence(
+    threat_id: String,
+    passphrase: Option<String>,
+    session_id: Option<String>,
+    state: State<'_, crate::AppState>,
+    app: tauri::AppHandle,
+) -> Result<EvidenceBundle, String> {
+    let system_guard = state.oxide_system.read().await;
+    if let Some(system) = system_guard.as_ref() {
+        let system_clone = system.clone();
+        drop(system_guard);
+        command_guard::authorize_command(&system_clone, session_id.as_deref(), "collect_evidence")
+            .await?;
+        let threat = system_clone
+            .find_threat(&threat_id)
+            .ok_or_else(|| format!("Threat '{threat_id}' not found"))?;
+        let notify = crate::job_notifier(app.clone());
+        state
+            .jobs
+            .run(
+                "evidence_export",
+                CancelMode::Cooperative,
+                notify,
+                move |job| async move {
+                    let reporter = job.clone();
+                    collect_bundle(
+                        &system_clone,
+                        &threat,
+                        passphrase,
+                        job.cancel_flag(),
+                        move |progress| {
+                            reporter.stage(&format!("{:?}", progress.stage).to_lowercase());
+                            reporter.progress(f32::from(progress.percent) / 100.0);
+                            events::emit(&app, &progress);
+                        },
+                    )
+                    .await
+                },
+            )
+            .await
+    } else {
+        Err(i18n::t("error.system_not_initialized").to_string())
+    }
+}
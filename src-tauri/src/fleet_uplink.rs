@@ -0,0 +1,227 @@
+//! Fleet uplink: mirrors metric samples, threats and folder scan summaries
+//! to the central server set in `surreal.uplink`.
+//!
+//! Records are queued as they happen and flushed every
+//! `flush_interval_secs`. While the server is unreachable the queue is
+//! spooled to disk after each failed flush and on shutdown, and picked up
+//! again by the next run.
+
+use crate::oxide_system::OxideSystem;
+use chrono::Utc;
+use log::{info, warn};
+use oxide_core::config::FleetUplinkConfig;
+use oxide_memory::fleet_uplink::{
+    self, FleetRecord, FleetRecordKind, FleetWriter, UplinkQueue, UplinkStatus,
+};
+use oxide_memory::{SurrealConnection, SurrealCredentials};
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+pub struct FleetUplink {
+    machine_id: Option<String>,
+    hostname: Option<String>,
+    queue: Mutex<UplinkQueue>,
+    status: Mutex<UplinkStatus>,
+}
+
+impl FleetUplink {
+    /// An uplink for `cfg`, holding whatever an earlier run spooled. Without
+    /// an enabled config nothing is queued.
+    pub fn new(cfg: Option<&FleetUplinkConfig>) -> Self {
+        let cfg = cfg.filter(|c| c.enabled);
+        let machine_id = cfg.and_then(|c| match &c.machine_id {
+            Some(id) => Some(id.trim().to_string()),
+            None => fleet_uplink::load_machine_id(Path::new(fleet_uplink::MACHINE_ID_PATH))
+                .map_err(|e| warn!("Fleet uplink disabled: {e}"))
+                .ok(),
+        });
+        let max_queued = cfg.map_or(FleetUplinkConfig::DEFAULT_MAX_QUEUED, |c| c.max_queued());
+        let queue = match machine_id {
+            Some(_) => UplinkQueue::load_spool(Path::new(fleet_uplink::SPOOL_PATH), max_queued),
+            None => UplinkQueue::new(max_queued),
+        };
+        if !queue.is_empty() {
+            info!("Fleet uplink resumed with {} spooled records", queue.len());
+        }
+        let status = UplinkStatus {
+            enabled: machine_id.is_some(),
+            machine_id: machine_id.clone(),
+            queued: queue.len(),
+            ..Default::default()
+        };
+        Self {
+            machine_id,
+            hostname: sysinfo::System::host_name(),
+            queue: Mutex::new(queue),
+            status: Mutex::new(status),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.machine_id.is_some()
+    }
+
+    /// Queue `payload` for the next flush; a no-op while disabled.
+    pub fn record<T: Serialize>(&self, kind: FleetRecordKind, payload: &T) {
+        let Some(machine_id) = &self.machine_id else {
+            return;
+        };
+        let payload = match serde_json::to_value(payload) {
+            Ok(payload) => payload,
+            Err(e) => {
+                warn!("Fleet uplink skipped a {kind:?} record: {e}");
+                return;
+            }
+        };
+        if let Ok(mut queue) = self.queue.lock() {
+            queue.push(FleetRecord {
+                kind,
+                machine_id: machine_id.clone(),
+                hostname: self.hostname.clone(),
+                recorded_at: Utc::now(),
+                payload,
+            });
+        }
+    }
+
+    pub fn status(&self) -> UplinkStatus {
+        let mut status = self.status.lock().map(|s| s.clone()).unwrap_or_default();
+        if let Ok(queue) = self.queue.lock() {
+            status.queued = queue.len();
+            status.dropped = queue.dropped();
+        }
+        status
+    }
+
+    /// Send everything queued in batches. On the first failure the batch
+    /// goes back to the front of the queue and the queue is spooled.
+    async fn flush(&self, writer: &mut FleetWriter, batch_size: usize) {
+        let mut sent = 0u64;
+        let mut error = None;
+        loop {
+            let batch = match self.queue.lock() {
+                Ok(mut queue) => queue.take_batch(batch_size),
+                Err(_) => break,
+            };
+            if batch.is_empty() {
+                break;
+            }
+            match writer.write(&batch).await {
+                Ok(()) => sent += batch.len() as u64,
+                Err(e) => {
+                    if let Ok(mut queue) = self.queue.lock() {
+                        queue.requeue(batch);
+                    }
+                    error = Some(format!("{e:#}"));
+                    break;
+                }
+            }
+        }
+        self.spool();
+        if let Ok(mut status) = self.status.lock() {
+            status.endpoint = Some(writer.endpoint());
+            status.connected = writer.connected();
+            status.sent += sent;
+            if sent > 0 {
+                status.last_flush_at = Some(Utc::now());
+            }
+            if let Some(e) = &error {
+                if status.last_error.as_ref() != Some(e) {
+                    warn!("Fleet uplink flush failed, keeping records queued: {e}");
+                }
+            }
+            status.last_error = error;
+        }
+    }
+
+    fn spool(&self) {
+        if let Ok(queue) = self.queue.lock() {
+            if let Err(e) = queue.spool(Path::new(fleet_uplink::SPOOL_PATH)) {
+                warn!("{e}");
+            }
+        }
+    }
+}
+
+/// Forward metrics and threats into the uplink and flush it on schedule
+/// while the system runs.
+pub fn spawn(system: OxideSystem) {
+    let uplink = system.fleet_uplink();
+    if !uplink.enabled() {
+        return;
+    }
+    if let Some(metrics) = system.subscribe_metrics() {
+        forward(metrics, FleetRecordKind::Metric, system.clone());
+    }
+    forward(
+        system.subscribe_threats(),
+        FleetRecordKind::Threat,
+        system.clone(),
+    );
+
+    tokio::spawn(async move {
+        let Some(cfg) = current_config(&system).await else {
+            return;
+        };
+        let credentials = std::env::var("OXIDE_FLEET_PASS")
+            .ok()
+            .zip(cfg.username.clone())
+            .map(|(password, username)| SurrealCredentials {
+                username,
+                password,
+                database_user: cfg.database_user,
+            });
+        let connection = SurrealConnection::parse(&cfg.url, credentials);
+        let mut writer = FleetWriter::new(
+            connection,
+            cfg.namespace().to_string(),
+            cfg.database().to_string(),
+        );
+        info!(
+            "Fleet uplink to {} every {}s",
+            writer.endpoint(),
+            cfg.flush_interval_secs()
+        );
+        loop {
+            tokio::time::sleep(Duration::from_secs(cfg.flush_interval_secs())).await;
+            if !system.is_running().await {
+                uplink.spool();
+                break;
+            }
+            uplink.flush(&mut writer, cfg.batch_size()).await;
+        }
+    });
+}
+
+fn forward<T>(mut receiver: broadcast::Receiver<T>, kind: FleetRecordKind, system: OxideSystem)
+where
+    T: Serialize + Clone + Send + 'static,
+{
+    tokio::spawn(async move {
+        let uplink = system.fleet_uplink();
+        loop {
+            match receiver.recv().await {
+                Ok(item) => uplink.record(kind, &item),
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("Fleet uplink missed {skipped} {kind:?} records")
+                }
+                Err(RecvError::Closed) => break,
+            }
+            if !system.is_running().await {
+                break;
+            }
+        }
+    });
+}
+
+async fn current_config(system: &OxideSystem) -> Option<FleetUplinkConfig> {
+    system
+        .get_config()
+        .await
+        .surreal
+        .and_then(|surreal| surreal.uplink)
+        .filter(|uplink| uplink.enabled)
+}
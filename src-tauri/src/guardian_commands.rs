@@ -3,11 +3,12 @@
 //! This module exposes Guardian functionality to the Tauri frontend,
 //! including metrics queries, process analysis, and threat detection.
 
+use oxide_core::i18n;
 use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "surrealdb-metrics")]
 use oxide_memory::{
-    BackendSearchItem, SurrealBackend, SystemMetric, ThreatTrainingSample,
+    BackendSearchItem, SharedSurrealBackend, SurrealBackend, SystemMetric, ThreatTrainingSample,
 };
 #[cfg(feature = "surrealdb-metrics")]
 use std::sync::Arc;
@@ -19,12 +20,30 @@ use log::{debug, warn};
 use serde_json::from_value;
 #[cfg(feature = "surrealdb-metrics")]
 use tauri::{async_runtime, State, Window};
+#[cfg(feature = "surrealdb-metrics")]
+use crate::events;
+#[cfg(feature = "surrealdb-metrics")]
+use crate::job_manager::{CancelMode, JobHandle};
+#[cfg(feature = "surrealdb-metrics")]
+use tauri::Manager;
 
 /// Shared state for Guardian commands
 #[allow(dead_code)]
 pub struct GuardianState {
+    /// Same handle as `AppState`'s, so only one connection is ever opened
     #[cfg(feature = "surrealdb-metrics")]
-    pub backend: Arc<SurrealBackend>,
+    pub backend: Arc<SharedSurrealBackend>,
+}
+
+#[cfg(feature = "surrealdb-metrics")]
+impl GuardianState {
+    /// The SurrealDB backend, connecting on first use.
+    pub async fn backend(&self) -> Result<Arc<SurrealBackend>, String> {
+        self.backend
+            .get()
+            .await
+            .map_err(|e| format!("SurrealDB backend unavailable: {e:#}"))
+    }
 }
 
 /// Time range for metrics queries
@@ -112,7 +131,8 @@ pub async fn get_system_metrics(
         .with_timezone(&Utc);
 
     let metrics = state
-        .backend
+        .backend()
+        .await?
         .query_metrics_by_time(start, end)
         .await
         .map_err(|e| format!("Failed to query metrics: {e}"))?;
@@ -134,7 +154,8 @@ pub async fn get_recent_metrics(
     let start = end - Duration::hours(hours);
 
     let metrics = state
-        .backend
+        .backend()
+        .await?
         .query_metrics_by_time(start, end)
         .await
         .map_err(|e| format!("Failed to query metrics: {e}"))?;
@@ -157,7 +178,8 @@ pub async fn get_metrics_summary(
     let start = end - Duration::hours(window_hours);
 
     let metrics = state
-        .backend
+        .backend()
+        .await?
         .query_metrics_by_time(start, end)
         .await
         .map_err(|e| format!("Failed to query metrics: {e}"))?;
@@ -209,7 +231,8 @@ pub async fn get_hourly_metrics(
 ) -> Result<Vec<HourlyMetricsRow>, String> {
     let lookback = hours.unwrap_or(24).max(1);
     let raw = state
-        .backend
+        .backend()
+        .await?
         .query_hourly_metrics(lookback)
         .await
         .map_err(|e| format!("Failed to query hourly metrics: {e}"))?;
@@ -233,7 +256,8 @@ pub async fn get_process_hotspots(
 ) -> Result<Vec<ProcessHotspot>, String> {
     let lookback = hours.unwrap_or(8).max(1);
     let raw = state
-        .backend
+        .backend()
+        .await?
         .query_process_hotspots(lookback)
         .await
         .map_err(|e| format!("Failed to query process hotspots: {e}"))?;
@@ -248,6 +272,46 @@ pub async fn get_process_hotspots(
     Ok(hotspots)
 }
 
+/// CPU and memory history of one process name, with growth per hour.
+#[cfg(feature = "surrealdb-metrics")]
+#[tauri::command]
+pub async fn get_process_trend(
+    state: State<'_, GuardianState>,
+    name: String,
+    hours: Option<i64>,
+) -> Result<oxide_memory::ProcessTrend, String> {
+    state
+        .backend()
+        .await?
+        .query_process_trend(&name, hours.unwrap_or(24).max(1))
+        .await
+        .map_err(|e| format!("Failed to query process trend: {e}"))
+}
+
+/// Processes whose memory grew steadily over the leak detection window,
+/// judged now with the configured thresholds.
+#[cfg(feature = "surrealdb-metrics")]
+#[tauri::command]
+pub async fn get_suspected_leaks(
+    state: State<'_, GuardianState>,
+    app_state: State<'_, crate::AppState>,
+) -> Result<Vec<oxide_guardian::leak_detector::SuspectedLeak>, String> {
+    let system = app_state
+        .oxide_system
+        .read()
+        .await
+        .clone()
+        .ok_or(i18n::t("error.system_not_initialized"))?;
+    let cfg = system
+        .get_config()
+        .await
+        .guardian
+        .leak_detection
+        .unwrap_or_default();
+    let backend = state.backend().await?;
+    crate::leak_detection::run(&backend, &cfg, crate::leak_detection::available_mb(&system)).await
+}
+
 /// Get processes with high CPU usage
 #[cfg(feature = "surrealdb-metrics")]
 #[tauri::command]
@@ -259,7 +323,8 @@ pub async fn get_high_cpu_processes(
     debug!("Fetching high CPU processes: threshold={threshold:.2}%, hours={hours}");
 
     let processes = state
-        .backend
+        .backend()
+        .await?
         .query_high_cpu_processes(threshold, hours)
         .await
         .map_err(|e| format!("Failed to query high CPU processes: {e}"))?;
@@ -273,6 +338,7 @@ pub async fn get_high_cpu_processes(
 #[tauri::command]
 pub async fn search_agent_memory(
     state: State<'_, GuardianState>,
+    app_state: State<'_, crate::AppState>,
     query: String,
     limit: usize,
 ) -> Result<MemorySearchResponse, String> {
@@ -280,65 +346,100 @@ pub async fn search_agent_memory(
 
     // Use the MemoryBackend trait method
     use oxide_memory::MemoryBackend;
+    let started = std::time::Instant::now();
     let results = state
-        .backend
+        .backend()
+        .await?
         .search(query, limit)
         .await
         .map_err(|e| format!("Failed to search memory: {e}"))?;
+    if let Some(system) = app_state.oxide_system.read().await.as_ref() {
+        system.record_operation("vector_search", started.elapsed());
+    }
 
     let count = results.len();
     Ok(MemorySearchResponse { results, count })
 }
 
+/// Latest metrics sample with a health verdict; without recent metrics
+/// only `status` (`no_data`) and `message` are set.
+#[cfg(feature = "surrealdb-metrics")]
+#[derive(Debug, Serialize)]
+pub struct GuardianStatus {
+    /// `healthy`, `caution`, `warning` or `no_data`
+    pub status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_usage: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_usage: Option<oxide_memory::MemoryUsage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disk_io: Option<oxide_memory::DiskIO>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network_stats: Option<oxide_memory::NetworkStats>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
 /// Get current system status summary
 #[cfg(feature = "surrealdb-metrics")]
 #[tauri::command]
 pub async fn get_guardian_status(
     state: State<'_, GuardianState>,
-) -> Result<serde_json::Value, String> {
+) -> Result<GuardianStatus, String> {
     debug!("Fetching system status");
 
     let end = Utc::now();
     let start = end - Duration::minutes(5);
 
     let metrics = state
-        .backend
+        .backend()
+        .await?
         .query_metrics_by_time(start, end)
         .await
         .map_err(|e| format!("Failed to query metrics: {e}"))?;
 
-    if let Some(latest) = metrics.first() {
-        Ok(serde_json::json!({
-            "timestamp": latest.timestamp,
-            "cpu_usage": latest.cpu_usage,
-            "memory_usage": latest.memory_usage,
-            "disk_io": latest.disk_io,
-            "network_stats": latest.network_stats,
-            "status": if latest.cpu_usage > 90.0 || latest.memory_usage.percent > 90.0 {
-                "warning"
-            } else if latest.cpu_usage > 70.0 || latest.memory_usage.percent > 70.0 {
-                "caution"
-            } else {
-                "healthy"
-            },
-        }))
+    if let Some(latest) = metrics.into_iter().next() {
+        let status = if latest.cpu_usage > 90.0 || latest.memory_usage.percent > 90.0 {
+            "warning"
+        } else if latest.cpu_usage > 70.0 || latest.memory_usage.percent > 70.0 {
+            "caution"
+        } else {
+            "healthy"
+        };
+        Ok(GuardianStatus {
+            status,
+            timestamp: Some(latest.timestamp),
+            cpu_usage: Some(latest.cpu_usage),
+            memory_usage: Some(latest.memory_usage),
+            disk_io: Some(latest.disk_io),
+            network_stats: Some(latest.network_stats),
+            message: None,
+        })
     } else {
-        Ok(serde_json::json!({
-            "status": "no_data",
-            "message": "No recent metrics available"
-        }))
+        Ok(GuardianStatus {
+            status: "no_data",
+            timestamp: None,
+            cpu_usage: None,
+            memory_usage: None,
+            disk_io: None,
+            network_stats: None,
+            message: Some("No recent metrics available".to_string()),
+        })
     }
 }
 
-/// Predict threat risk score using SurrealML (with backend heuristic fallback).
+/// Predict threat risk score using SurrealML, falling back to the bundled local model.
 #[cfg(feature = "surrealdb-metrics")]
 #[tauri::command]
 pub async fn predict_threat_risk(
     state: State<'_, GuardianState>,
     feature_vector: serde_json::Value,
-) -> Result<serde_json::Value, String> {
+) -> Result<oxide_memory::ThreatPrediction, String> {
     state
-        .backend
+        .backend()
+        .await?
         .ml_predict_threat(feature_vector)
         .await
         .map_err(|e| format!("Failed to run threat risk prediction: {e}"))
@@ -352,12 +453,87 @@ pub async fn submit_threat_training_sample(
     sample: ThreatTrainingSample,
 ) -> Result<(), String> {
     state
-        .backend
+        .backend()
+        .await?
         .upsert_threat_training_sample(sample)
         .await
         .map_err(|e| format!("Failed to store training sample: {e}"))
 }
 
+/// Record an analyst's severity verdict on a threat as a training label.
+#[cfg(feature = "surrealdb-metrics")]
+#[tauri::command]
+pub async fn label_threat(
+    state: State<'_, GuardianState>,
+    app_state: State<'_, crate::AppState>,
+    threat_id: String,
+    severity: oxide_memory::ThreatSeverity,
+    session_id: Option<String>,
+) -> Result<ThreatTrainingSample, String> {
+    authorize_memory_command(&app_state, session_id.as_deref(), "label_threat").await?;
+    state
+        .backend()
+        .await?
+        .label_threat(&threat_id, severity)
+        .await
+        .map_err(|e| format!("Failed to label threat: {e:#}"))
+}
+
+/// Collect training samples and retrain the threat model now with the
+/// configured settings, even if the periodic job is disabled.
+#[cfg(feature = "surrealdb-metrics")]
+#[tauri::command]
+pub async fn retrain_threat_model(
+    state: State<'_, GuardianState>,
+    app_state: State<'_, crate::AppState>,
+    session_id: Option<String>,
+) -> Result<crate::threat_training::TrainingRunReport, String> {
+    authorize_memory_command(&app_state, session_id.as_deref(), "retrain_threat_model").await?;
+    let system = app_state
+        .oxide_system
+        .read()
+        .await
+        .clone()
+        .ok_or(i18n::t("error.system_not_initialized"))?;
+    let cfg = system
+        .get_config()
+        .await
+        .surreal
+        .and_then(|surreal| surreal.threat_training)
+        .unwrap_or_default();
+    let backend = state.backend().await?;
+    crate::threat_training::run(&backend, &cfg).await
+}
+
+/// Holdout metrics from the most recent threat model retraining.
+#[cfg(feature = "surrealdb-metrics")]
+#[tauri::command]
+pub async fn get_threat_model_evaluation(
+    state: State<'_, GuardianState>,
+) -> Result<Option<oxide_memory::ModelEvaluation>, String> {
+    state
+        .backend()
+        .await?
+        .latest_threat_model_evaluation()
+        .await
+        .map_err(|e| format!("Failed to load threat model evaluation: {e}"))
+}
+
+/// Queue and connection state of the fleet uplink.
+#[cfg(feature = "surrealdb-metrics")]
+#[tauri::command]
+pub async fn get_fleet_uplink_status(
+    app_state: State<'_, crate::AppState>,
+) -> Result<oxide_memory::fleet_uplink::UplinkStatus, String> {
+    let system = app_state
+        .oxide_system
+        .read()
+        .await
+        .clone()
+        .ok_or(i18n::t("error.system_not_initialized"))?;
+    Ok(system.fleet_uplink().status())
+}
+
 /// Subscribe frontend listeners to realtime metric updates.
 #[cfg(feature = "surrealdb-metrics")]
 #[tauri::command]
@@ -365,12 +541,12 @@ pub async fn subscribe_guardian_metrics(
     state: State<'_, GuardianState>,
     window: Window,
 ) -> Result<(), String> {
-    let mut receiver = state.backend.subscribe_metrics();
+    let mut receiver = state.backend().await?.subscribe_metrics();
     async_runtime::spawn(async move {
         loop {
             match receiver.recv().await {
                 Ok(metric) => {
-                    if window.emit("guardian://metrics", &metric).is_err() {
+                    if events::emit_to(&window, &metric).is_err() {
                         break;
                     }
                 }
@@ -384,35 +560,514 @@ pub async fn subscribe_guardian_metrics(
     Ok(())
 }
 
+/// Benchmark the memory backend against its performance targets on a
+/// scratch database. Corpus sizes are capped to keep the run short.
+#[cfg(feature = "surrealdb-metrics")]
+#[tauri::command]
+pub async fn run_memory_benchmark(
+    corpus_sizes: Option<Vec<usize>>,
+    queries_per_size: Option<usize>,
+) -> Result<oxide_memory::benchmark::BenchmarkReport, String> {
+    use oxide_memory::benchmark::{run_benchmark, BenchmarkOptions};
+
+    let defaults = BenchmarkOptions::default();
+    let options = BenchmarkOptions {
+        corpus_sizes: corpus_sizes
+            .map(|sizes| sizes.into_iter().map(|n| n.clamp(1, 10_000)).collect())
+            .unwrap_or(defaults.corpus_sizes),
+        queries_per_size: queries_per_size
+            .map(|n| n.clamp(1, 200))
+            .unwrap_or(defaults.queries_per_size),
+    };
+    debug!("Running memory benchmark: {options:?}");
+    run_benchmark(&options)
+        .await
+        .map_err(|e| format!("Memory benchmark failed: {e:#}"))
+}
+
+/// Check a guarded memory command against the caller's session.
+#[cfg(feature = "surrealdb-metrics")]
+async fn authorize_memory_command(
+    app_state: &crate::AppState,
+    session_id: Option<&str>,
+    command: &str,
+) -> Result<(), String> {
+    let system = app_state.oxide_system.read().await.clone();
+    match system {
+        Some(system) => {
+            crate::command_guard::authorize_command(&system, session_id, command).await
+        }
+        None => Err(i18n::t("error.system_not_initialized").to_string()),
+    }
+}
+
+/// Receives a maintenance operation's progress updates
+#[cfg(feature = "surrealdb-metrics")]
+type MaintenanceProgressSink = Box<dyn Fn(oxide_memory::MaintenanceProgress) + Send + Sync>;
+
+/// Run a database maintenance operation as a job of `kind`. Its progress
+/// updates the job and is still emitted to `window` as before.
+#[cfg(feature = "surrealdb-metrics")]
+async fn maintenance_job<T, F, Fut>(
+    app_state: &crate::AppState,
+    window: Window,
+    kind: &str,
+    mode: CancelMode,
+    run: F,
+) -> Result<T, String>
+where
+    T: Serialize + Send + 'static,
+    F: FnOnce(JobHandle, MaintenanceProgressSink) -> Fut + Send + 'static,
+    Fut: std::future::Future<Output = Result<T, String>> + Send + 'static,
+{
+    let notify = crate::job_notifier(window.app_handle());
+    app_state
+        .jobs
+        .run(kind, mode, notify, move |job| {
+            let reporter = job.clone();
+            let progress: MaintenanceProgressSink = Box::new(move |progress| {
+                reporter.stage(&progress.stage);
+                reporter.progress(f32::from(progress.percent) / 100.0);
+                let _ = events::emit_to(&window, &progress);
+            });
+            run(job, progress)
+        })
+        .await
+}
+
+/// Export the memory database to a SurrealQL file at `path`.
+#[cfg(feature = "surrealdb-metrics")]
+#[tauri::command]
+pub async fn backup_database(
+    state: State<'_, GuardianState>,
+    app_state: State<'_, crate::AppState>,
+    window: Window,
+    path: String,
+    session_id: Option<String>,
+) -> Result<oxide_memory::BackupInfo, String> {
+    authorize_memory_command(&app_state, session_id.as_deref(), "backup_database").await?;
+    let backend = state.backend().await?;
+    // Aborting leaves at most a `.partial` file next to `path`
+    maintenance_job(
+        &app_state,
+        window,
+        "database_backup",
+        CancelMode::Abort,
+        move |_, progress| async move {
+            backend
+                .backup_database(&path, progress)
+                .await
+                .map_err(|e| format!("Database backup failed: {e:#}"))
+        },
+    )
+    .await
+}
+
+/// Replace the memory database with a backup made by `backup_database`.
+/// Once the restore has started it runs to completion; cancelling only
+/// takes effect while it is queued.
+#[cfg(feature = "surrealdb-metrics")]
+#[tauri::command]
+pub async fn restore_database(
+    state: State<'_, GuardianState>,
+    app_state: State<'_, crate::AppState>,
+    window: Window,
+    path: String,
+    session_id: Option<String>,
+) -> Result<(), String> {
+    authorize_memory_command(&app_state, session_id.as_deref(), "restore_database").await?;
+    let backend = state.backend().await?;
+    maintenance_job(
+        &app_state,
+        window,
+        "database_restore",
+        CancelMode::Cooperative,
+        move |_, progress| async move {
+            backend
+                .restore_database(&path, progress)
+                .await
+                .map_err(|e| format!("Database restore failed: {e:#}"))
+        },
+    )
+    .await
+}
+
+/// Prune metrics older than `retention_days` and rebuild indexes.
+#[cfg(feature = "surrealdb-metrics")]
+#[tauri::command]
+pub async fn compact_database(
+    state: State<'_, GuardianState>,
+    app_state: State<'_, crate::AppState>,
+    window: Window,
+    retention_days: Option<u32>,
+    session_id: Option<String>,
+) -> Result<oxide_memory::CompactionReport, String> {
+    authorize_memory_command(&app_state, session_id.as_deref(), "compact_database").await?;
+    let backend = state.backend().await?;
+    maintenance_job(
+        &app_state,
+        window,
+        "database_compaction",
+        CancelMode::Abort,
+        move |_, progress| async move {
+            backend
+                .compact_database(retention_days.filter(|d| *d > 0), progress)
+                .await
+                .map_err(|e| format!("Database compaction failed: {e:#}"))
+        },
+    )
+    .await
+}
+
+/// Re-create the agent memory vector index, optionally switching to
+/// `new_dim` and re-embedding every memory, and report recall and latency.
+/// Like a restore, a started rebuild is not interrupted.
+#[cfg(feature = "surrealdb-metrics")]
+#[tauri::command]
+pub async fn rebuild_vector_index(
+    state: State<'_, GuardianState>,
+    app_state: State<'_, crate::AppState>,
+    window: Window,
+    new_dim: Option<usize>,
+    session_id: Option<String>,
+) -> Result<oxide_memory::VectorIndexReport, String> {
+    authorize_memory_command(&app_state, session_id.as_deref(), "rebuild_vector_index").await?;
+    let backend = state.backend().await?;
+    maintenance_job(
+        &app_state,
+        window,
+        "vector_index_rebuild",
+        CancelMode::Cooperative,
+        move |_, progress| async move {
+            backend
+                .rebuild_vector_index(new_dim, progress)
+                .await
+                .map_err(|e| format!("Vector index rebuild failed: {e:#}"))
+        },
+    )
+    .await
+}
+
+/// Export agent memories (content, embeddings, metadata) to a JSONL file.
+#[cfg(feature = "surrealdb-metrics")]
+#[tauri::command]
+pub async fn export_agent_memory(
+    state: State<'_, GuardianState>,
+    app_state: State<'_, crate::AppState>,
+    path: String,
+    filter: Option<oxide_memory::MemoryExportFilter>,
+    session_id: Option<String>,
+) -> Result<oxide_memory::MemoryTransferReport, String> {
+    authorize_memory_command(&app_state, session_id.as_deref(), "export_agent_memory").await?;
+    state
+        .backend()
+        .await?
+        .export_agent_memory(&filter.unwrap_or_default(), &path)
+        .await
+        .map_err(|e| format!("Agent memory export failed: {e:#}"))
+}
+
+/// Import agent memories from a JSONL file made by `export_agent_memory`.
+#[cfg(feature = "surrealdb-metrics")]
+#[tauri::command]
+pub async fn import_agent_memory(
+    state: State<'_, GuardianState>,
+    app_state: State<'_, crate::AppState>,
+    path: String,
+    re_embed: Option<oxide_memory::ReEmbedPolicy>,
+    session_id: Option<String>,
+) -> Result<oxide_memory::MemoryTransferReport, String> {
+    authorize_memory_command(&app_state, session_id.as_deref(), "import_agent_memory").await?;
+    state
+        .backend()
+        .await?
+        .import_agent_memory(&path, re_embed.unwrap_or_default())
+        .await
+        .map_err(|e| format!("Agent memory import failed: {e:#}"))
+}
+
+/// Copy the active profile's JSON memory history into the SurrealDB
+/// backend, re-embedding every record. Progress is emitted like the other
+/// maintenance operations; a failed or cancelled run removes what it
+/// imported.
+#[cfg(feature = "surrealdb-metrics")]
+#[tauri::command]
+pub async fn migrate_memory_backend(
+    state: State<'_, GuardianState>,
+    app_state: State<'_, crate::AppState>,
+    window: Window,
+    from: oxide_memory::MemoryBackendKind,
+    to: oxide_memory::MemoryBackendKind,
+    session_id: Option<String>,
+) -> Result<oxide_memory::MigrationReport, String> {
+    authorize_memory_command(&app_state, session_id.as_deref(), "migrate_memory_backend").await?;
+    let system = app_state.oxide_system.read().await.clone();
+    let json_dir = match system {
+        Some(system) => system.active_profile().await.memory_path(),
+        None => return Err(i18n::t("error.system_not_initialized").to_string()),
+    };
+    let backend = state.backend().await?;
+    maintenance_job(
+        &app_state,
+        window,
+        "memory_migration",
+        CancelMode::Cooperative,
+        move |job, progress| async move {
+            let cancel = job.cancel_flag();
+            oxide_memory::migration::migrate_memory_backend(
+                from,
+                to,
+                std::path::Path::new(&json_dir),
+                &backend,
+                &cancel,
+                progress,
+            )
+            .await
+            .map_err(|e| format!("Memory migration failed: {e:#}"))
+        },
+    )
+    .await
+}
+
+/// Filters for [`list_agent_memories`]; all optional
+#[cfg(feature = "surrealdb-metrics")]
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MemoryListFilter {
+    #[serde(default)]
+    pub agent_type: Option<oxide_memory::AgentType>,
+    #[serde(default)]
+    pub source: Option<oxide_memory::MemorySource>,
+    #[serde(default)]
+    pub since: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub until: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub sort: oxide_memory::MemorySort,
+}
+
+/// Page through stored agent memories for review.
+#[cfg(feature = "surrealdb-metrics")]
+#[tauri::command]
+pub async fn list_agent_memories(
+    state: State<'_, GuardianState>,
+    app_state: State<'_, crate::AppState>,
+    filter: Option<MemoryListFilter>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    session_id: Option<String>,
+) -> Result<oxide_memory::AgentMemoryPage, String> {
+    authorize_memory_command(&app_state, session_id.as_deref(), "list_agent_memories").await?;
+    let filter = filter.unwrap_or_default();
+    let time_range = (filter.since.is_some() || filter.until.is_some()).then(|| {
+        (
+            filter.since.unwrap_or(DateTime::UNIX_EPOCH),
+            filter.until.unwrap_or_else(Utc::now),
+        )
+    });
+    state
+        .backend()
+        .await?
+        .list_agent_memories(
+            filter.agent_type,
+            filter.source,
+            time_range,
+            filter.sort,
+            offset.unwrap_or(0),
+            limit.unwrap_or(50),
+        )
+        .await
+        .map_err(|e| format!("Failed to list agent memories: {e:#}"))
+}
+
+/// Delete one agent memory by id.
+#[cfg(feature = "surrealdb-metrics")]
+#[tauri::command]
+pub async fn delete_agent_memory(
+    state: State<'_, GuardianState>,
+    app_state: State<'_, crate::AppState>,
+    memory_id: String,
+    session_id: Option<String>,
+) -> Result<bool, String> {
+    authorize_memory_command(&app_state, session_id.as_deref(), "delete_agent_memory").await?;
+    state
+        .backend()
+        .await?
+        .delete_agent_memory(&memory_id)
+        .await
+        .map_err(|e| format!("Failed to delete agent memory: {e:#}"))
+}
+
+/// Run memory consolidation now with the configured settings (or the
+/// defaults), even if the periodic job is disabled.
+#[cfg(feature = "surrealdb-metrics")]
+#[tauri::command]
+pub async fn consolidate_agent_memory(
+    state: State<'_, GuardianState>,
+    app_state: State<'_, crate::AppState>,
+    session_id: Option<String>,
+) -> Result<crate::memory_consolidation::ConsolidationReport, String> {
+    authorize_memory_command(&app_state, session_id.as_deref(), "consolidate_agent_memory")
+        .await?;
+    let system = app_state
+        .oxide_system
+        .read()
+        .await
+        .clone()
+        .ok_or(i18n::t("error.system_not_initialized"))?;
+    let cfg = system
+        .get_config()
+        .await
+        .surreal
+        .and_then(|surreal| surreal.consolidation)
+        .unwrap_or_default();
+    let backend = state.backend().await?;
+    crate::memory_consolidation::consolidate(&system, &backend, &cfg).await
+}
+
+/// List recorded threats, newest first, optionally filtered by status.
+#[cfg(feature = "surrealdb-metrics")]
+#[tauri::command]
+pub async fn list_threats(
+    state: State<'_, GuardianState>,
+    status: Option<oxide_memory::MitigationStatus>,
+    limit: Option<usize>,
+) -> Result<Vec<oxide_memory::ThreatRecord>, String> {
+    state
+        .backend()
+        .await?
+        .query_threats(status, limit.unwrap_or(100).clamp(1, 1000))
+        .await
+        .map_err(|e| format!("Failed to query threats: {e}"))
+}
+
+/// Move a threat through the analyst workflow.
+#[cfg(feature = "surrealdb-metrics")]
+#[tauri::command]
+pub async fn update_threat_status(
+    state: State<'_, GuardianState>,
+    app_state: State<'_, crate::AppState>,
+    threat_id: String,
+    status: oxide_memory::MitigationStatus,
+    note: Option<String>,
+    session_id: Option<String>,
+) -> Result<(), String> {
+    authorize_memory_command(&app_state, session_id.as_deref(), "update_threat_status").await?;
+    state
+        .backend()
+        .await?
+        .update_threat_status(&threat_id, status, note)
+        .await
+        .map_err(|e| format!("Failed to update threat status: {e}"))
+}
+
+/// Mark detected threats as under investigation.
+#[cfg(feature = "surrealdb-metrics")]
+#[tauri::command]
+pub async fn bulk_acknowledge_threats(
+    state: State<'_, GuardianState>,
+    app_state: State<'_, crate::AppState>,
+    threat_ids: Vec<String>,
+    session_id: Option<String>,
+) -> Result<usize, String> {
+    authorize_memory_command(&app_state, session_id.as_deref(), "bulk_acknowledge_threats")
+        .await?;
+    state
+        .backend()
+        .await?
+        .bulk_acknowledge_threats(&threat_ids)
+        .await
+        .map_err(|e| format!("Failed to acknowledge threats: {e}"))
+}
+
+/// Past folder scans, newest first, optionally for one root folder.
+#[cfg(feature = "surrealdb-metrics")]
+#[tauri::command]
+pub async fn list_scan_history(
+    state: State<'_, GuardianState>,
+    root: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<oxide_memory::ScanRunSummary>, String> {
+    state
+        .backend()
+        .await?
+        .list_scan_history(root, limit.unwrap_or(50).clamp(1, 500))
+        .await
+        .map_err(|e| format!("Failed to query scan history: {e}"))
+}
+
+/// Summary and per-file verdicts of one folder scan.
+#[cfg(feature = "surrealdb-metrics")]
+#[tauri::command]
+pub async fn get_scan_details(
+    state: State<'_, GuardianState>,
+    scan_id: String,
+) -> Result<oxide_memory::ScanDetails, String> {
+    state
+        .backend()
+        .await?
+        .get_scan_details(&scan_id)
+        .await
+        .map_err(|e| format!("Failed to query scan details: {e}"))?
+        .ok_or_else(|| format!("Scan {scan_id} not found"))
+}
+
+/// New, removed and changed malicious files between two scans of one root.
+#[cfg(feature = "surrealdb-metrics")]
+#[tauri::command]
+pub async fn diff_scans(
+    state: State<'_, GuardianState>,
+    base_scan_id: String,
+    target_scan_id: String,
+) -> Result<oxide_memory::ScanDiff, String> {
+    state
+        .backend()
+        .await?
+        .diff_scans(&base_scan_id, &target_scan_id)
+        .await
+        .map_err(|e| format!("Failed to compare scans: {e}"))
+}
+
 // Stub implementations when surrealdb feature is disabled
 #[cfg(not(feature = "surrealdb-metrics"))]
 #[tauri::command]
 pub async fn get_system_metrics(_time_range: TimeRange) -> Result<String, String> {
-    Err("SurrealDB metrics feature not enabled".to_string())
+    Err(i18n::t("error.metrics_disabled").to_string())
 }
 
 #[cfg(not(feature = "surrealdb-metrics"))]
 #[tauri::command]
 pub async fn get_recent_metrics(_hours: i64) -> Result<String, String> {
-    Err("SurrealDB metrics feature not enabled".to_string())
+    Err(i18n::t("error.metrics_disabled").to_string())
 }
 
 #[cfg(not(feature = "surrealdb-metrics"))]
 #[tauri::command]
 pub async fn get_metrics_summary(_hours: Option<i64>) -> Result<String, String> {
-    Err("SurrealDB metrics feature not enabled".to_string())
+    Err(i18n::t("error.metrics_disabled").to_string())
 }
 
 #[cfg(not(feature = "surrealdb-metrics"))]
 #[tauri::command]
 pub async fn get_hourly_metrics(_hours: Option<i64>) -> Result<String, String> {
-    Err("SurrealDB metrics feature not enabled".to_string())
+    Err(i18n::t("error.metrics_disabled").to_string())
 }
 
 #[cfg(not(feature = "surrealdb-metrics"))]
 #[tauri::command]
 pub async fn get_process_hotspots(_hours: Option<i64>) -> Result<String, String> {
-    Err("SurrealDB metrics feature not enabled".to_string())
+    Err(i18n::t("error.metrics_disabled").to_string())
+}
+
+#[cfg(not(feature = "surrealdb-metrics"))]
+#[tauri::command]
+pub async fn get_process_trend(_name: String, _hours: Option<i64>) -> Result<String, String> {
+    Err(i18n::t("error.metrics_disabled").to_string())
+}
+
+#[cfg(not(feature = "surrealdb-metrics"))]
+#[tauri::command]
+pub async fn get_suspected_leaks() -> Result<Vec<serde_json::Value>, String> {
+    Err(i18n::t("error.metrics_disabled").to_string())
 }
 
 #[cfg(not(feature = "surrealdb-metrics"))]
@@ -421,7 +1076,7 @@ pub async fn get_high_cpu_processes(
     _threshold: f64,
     _hours: i64,
 ) -> Result<HighCpuProcessesResponse, String> {
-    Err("SurrealDB metrics feature not enabled".to_string())
+    Err(i18n::t("error.metrics_disabled").to_string())
 }
 
 #[cfg(not(feature = "surrealdb-metrics"))]
@@ -430,13 +1085,13 @@ pub async fn search_agent_memory(
     _query: String,
     _limit: usize,
 ) -> Result<String, String> {
-    Err("SurrealDB metrics feature not enabled".to_string())
+    Err(i18n::t("error.metrics_disabled").to_string())
 }
 
 #[cfg(not(feature = "surrealdb-metrics"))]
 #[tauri::command]
 pub async fn get_guardian_status() -> Result<serde_json::Value, String> {
-    Err("SurrealDB metrics feature not enabled".to_string())
+    Err(i18n::t("error.metrics_disabled").to_string())
 }
 
 #[cfg(not(feature = "surrealdb-metrics"))]
@@ -444,7 +1099,7 @@ pub async fn get_guardian_status() -> Result<serde_json::Value, String> {
 pub async fn predict_threat_risk(
     _feature_vector: serde_json::Value,
 ) -> Result<serde_json::Value, String> {
-    Err("SurrealDB metrics feature not enabled".to_string())
+    Err(i18n::t("error.metrics_disabled").to_string())
 }
 
 #[cfg(not(feature = "surrealdb-metrics"))]
@@ -452,11 +1107,193 @@ pub async fn predict_threat_risk(
 pub async fn submit_threat_training_sample(
     _sample: serde_json::Value,
 ) -> Result<(), String> {
-    Err("SurrealDB metrics feature not enabled".to_string())
+    Err(i18n::t("error.metrics_disabled").to_string())
+}
+
+#[cfg(not(feature = "surrealdb-metrics"))]
+#[tauri::command]
+pub async fn label_threat(
+    _threat_id: String,
+    _severity: String,
+    _session_id: Option<String>,
+) -> Result<serde_json::Value, String> {
+    Err(i18n::t("error.metrics_disabled").to_string())
+}
+
+#[cfg(not(feature = "surrealdb-metrics"))]
+#[tauri::command]
+pub async fn retrain_threat_model(_session_id: Option<String>) -> Result<String, String> {
+    Err(i18n::t("error.metrics_disabled").to_string())
+}
+
+#[cfg(not(feature = "surrealdb-metrics"))]
+#[tauri::command]
+pub async fn get_threat_model_evaluation() -> Result<Option<serde_json::Value>, String> {
+    Err(i18n::t("error.metrics_disabled").to_string())
+}
+
+#[cfg(not(feature = "surrealdb-metrics"))]
+#[tauri::command]
+pub async fn get_fleet_uplink_status() -> Result<serde_json::Value, String> {
+    Err(i18n::t("error.metrics_disabled").to_string())
 }
 
 #[cfg(not(feature = "surrealdb-metrics"))]
 #[tauri::command]
 pub async fn subscribe_guardian_metrics(_window: tauri::Window) -> Result<(), String> {
-    Err("SurrealDB metrics feature not enabled".to_string())
+    Err(i18n::t("error.metrics_disabled").to_string())
+}
+
+#[cfg(not(feature = "surrealdb-metrics"))]
+#[tauri::command]
+pub async fn run_memory_benchmark(
+    _corpus_sizes: Option<Vec<usize>>,
+    _queries_per_size: Option<usize>,
+) -> Result<String, String> {
+    Err(i18n::t("error.metrics_disabled").to_string())
+}
+
+#[cfg(not(feature = "surrealdb-metrics"))]
+#[tauri::command]
+pub async fn backup_database(
+    _path: String,
+    _session_id: Option<String>,
+) -> Result<String, String> {
+    Err(i18n::t("error.metrics_disabled").to_string())
+}
+
+#[cfg(not(feature = "surrealdb-metrics"))]
+#[tauri::command]
+pub async fn restore_database(
+    _path: String,
+    _session_id: Option<String>,
+) -> Result<(), String> {
+    Err(i18n::t("error.metrics_disabled").to_string())
+}
+
+#[cfg(not(feature = "surrealdb-metrics"))]
+#[tauri::command]
+pub async fn compact_database(
+    _retention_days: Option<u32>,
+    _session_id: Option<String>,
+) -> Result<String, String> {
+    Err(i18n::t("error.metrics_disabled").to_string())
+}
+
+#[cfg(not(feature = "surrealdb-metrics"))]
+#[tauri::command]
+pub async fn rebuild_vector_index(
+    _new_dim: Option<usize>,
+    _session_id: Option<String>,
+) -> Result<String, String> {
+    Err(i18n::t("error.metrics_disabled").to_string())
+}
+
+#[cfg(not(feature = "surrealdb-metrics"))]
+#[tauri::command]
+pub async fn export_agent_memory(
+    _path: String,
+    _filter: Option<serde_json::Value>,
+    _session_id: Option<String>,
+) -> Result<String, String> {
+    Err(i18n::t("error.metrics_disabled").to_string())
+}
+
+#[cfg(not(feature = "surrealdb-metrics"))]
+#[tauri::command]
+pub async fn import_agent_memory(
+    _path: String,
+    _re_embed: Option<String>,
+    _session_id: Option<String>,
+) -> Result<String, String> {
+    Err(i18n::t("error.metrics_disabled").to_string())
+}
+
+#[cfg(not(feature = "surrealdb-metrics"))]
+#[tauri::command]
+pub async fn migrate_memory_backend(
+    _from: String,
+    _to: String,
+    _session_id: Option<String>,
+) -> Result<String, String> {
+    Err(i18n::t("error.metrics_disabled").to_string())
+}
+
+#[cfg(not(feature = "surrealdb-metrics"))]
+#[tauri::command]
+pub async fn list_agent_memories(
+    _filter: Option<serde_json::Value>,
+    _offset: Option<usize>,
+    _limit: Option<usize>,
+    _session_id: Option<String>,
+) -> Result<String, String> {
+    Err(i18n::t("error.metrics_disabled").to_string())
+}
+
+#[cfg(not(feature = "surrealdb-metrics"))]
+#[tauri::command]
+pub async fn delete_agent_memory(
+    _memory_id: String,
+    _session_id: Option<String>,
+) -> Result<bool, String> {
+    Err(i18n::t("error.metrics_disabled").to_string())
+}
+
+#[cfg(not(feature = "surrealdb-metrics"))]
+#[tauri::command]
+pub async fn consolidate_agent_memory(_session_id: Option<String>) -> Result<String, String> {
+    Err(i18n::t("error.metrics_disabled").to_string())
+}
+
+#[cfg(not(feature = "surrealdb-metrics"))]
+#[tauri::command]
+pub async fn list_threats(
+    _status: Option<String>,
+    _limit: Option<usize>,
+) -> Result<Vec<serde_json::Value>, String> {
+    Err(i18n::t("error.metrics_disabled").to_string())
+}
+
+#[cfg(not(feature = "surrealdb-metrics"))]
+#[tauri::command]
+pub async fn update_threat_status(
+    _threat_id: String,
+    _status: String,
+    _note: Option<String>,
+    _session_id: Option<String>,
+) -> Result<(), String> {
+    Err(i18n::t("error.metrics_disabled").to_string())
+}
+
+#[cfg(not(feature = "surrealdb-metrics"))]
+#[tauri::command]
+pub async fn bulk_acknowledge_threats(
+    _threat_ids: Vec<String>,
+    _session_id: Option<String>,
+) -> Result<usize, String> {
+    Err(i18n::t("error.metrics_disabled").to_string())
+}
+
+#[cfg(not(feature = "surrealdb-metrics"))]
+#[tauri::command]
+pub async fn list_scan_history(
+    _root: Option<String>,
+    _limit: Option<usize>,
+) -> Result<Vec<serde_json::Value>, String> {
+    Err(i18n::t("error.metrics_disabled").to_string())
+}
+
+#[cfg(not(feature = "surrealdb-metrics"))]
+#[tauri::command]
+pub async fn get_scan_details(_scan_id: String) -> Result<serde_json::Value, String> {
+    Err(i18n::t("error.metrics_disabled").to_string())
+}
+
+#[cfg(not(feature = "surrealdb-metrics"))]
+#[tauri::command]
+pub async fn diff_scans(
+    _base_scan_id: String,
+    _target_scan_id: String,
+) -> Result<serde_json::Value, String> {
+    Err(i18n::t("error.metrics_disabled").to_string())
 }
@@ -0,0 +1,174 @@
+//! Global hotkeys registered through Tauri's shortcut manager.
+//!
+//! Bindings come from [`HotkeyConfig`] and are re-registered as a set
+//! whenever the config changes. Combos that clash with another action or
+//! with the operating system are reported instead of registered, and so are
+//! combos the OS refuses because another application already owns them.
+//! Each press emits `hotkey://triggered` before the action runs.
+
+use crate::events::{self, HotkeyTriggered, ScreenshotQuestion};
+use crate::AppState;
+use log::{info, warn};
+use oxide_core::config::HotkeyConfig;
+use oxide_core::hotkeys::{self, HotkeyAction};
+use oxide_core::i18n;
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, GlobalShortcutManager, Manager};
+
+#[derive(Serialize, Debug, Clone)]
+pub struct HotkeyStatus {
+    pub action: HotkeyAction,
+    pub accelerator: String,
+    pub registered: bool,
+    /// Why the hotkey is not registered
+    pub error: Option<String>,
+}
+
+/// What a panic-stop halted
+#[derive(Serialize, Debug, Clone)]
+pub struct PanicStopReport {
+    /// False when RPA was not initialized
+    pub rpa_halted: bool,
+    pub cancelled_jobs: Vec<String>,
+}
+
+/// Registration state of the configured hotkeys
+#[derive(Default)]
+pub struct HotkeyManager {
+    statuses: Mutex<Vec<HotkeyStatus>>,
+}
+
+impl HotkeyManager {
+    pub fn statuses(&self) -> Vec<HotkeyStatus> {
+        self.statuses
+            .lock()
+            .map(|statuses| statuses.clone())
+            .unwrap_or_default()
+    }
+
+    /// Replace every registered hotkey with the bindings in `config`.
+    pub fn apply(&self, app: &AppHandle, config: &HotkeyConfig) -> Vec<HotkeyStatus> {
+        let mut shortcuts = app.global_shortcut_manager();
+        if let Err(e) = shortcuts.unregister_all() {
+            warn!("Failed to unregister hotkeys: {e}");
+        }
+
+        let bindings = config.bindings();
+        let conflicts = hotkeys::find_conflicts(&bindings);
+        let statuses: Vec<HotkeyStatus> = bindings
+            .into_iter()
+            .map(|(action, accelerator)| {
+                let error = match conflicts.iter().find(|c| c.action == action) {
+                    Some(conflict) => Some(conflict.reason.clone()),
+                    None => {
+                        let handle = app.clone();
+                        shortcuts
+                            .register(&accelerator, move || {
+                                let app = handle.clone();
+                                tauri::async_runtime::spawn(handle_press(app, action));
+                            })
+                            .err()
+                            .map(|e| {
+                                format!("Could not register, another application may use it: {e}")
+                            })
+                    }
+                };
+                match &error {
+                    Some(e) => warn!("Hotkey {action} ({accelerator}) not registered: {e}"),
+                    None => info!("Hotkey {action} registered as {accelerator}"),
+                }
+                HotkeyStatus {
+                    action,
+                    accelerator,
+                    registered: error.is_none(),
+                    error,
+                }
+            })
+            .collect();
+
+        if let Ok(mut current) = self.statuses.lock() {
+            *current = statuses.clone();
+        }
+        statuses
+    }
+}
+
+async fn handle_press(app: AppHandle, action: HotkeyAction) {
+    info!("Hotkey pressed: {action}");
+    events::emit(&app, &HotkeyTriggered(action));
+    let state = app.state::<AppState>().inner().clone();
+    let result = match action {
+        HotkeyAction::PushToTalk => push_to_talk(&state).await,
+        HotkeyAction::ScreenshotQuestion => screenshot_question(&app, &state).await,
+        HotkeyAction::PanicStop => {
+            panic_stop(&app, &state).await;
+            Ok(())
+        }
+    };
+    if let Err(e) = result {
+        warn!("Hotkey {action} failed: {e}");
+    }
+}
+
+async fn push_to_talk(state: &AppState) -> Result<(), String> {
+    let system = state
+        .oxide_system
+        .read()
+        .await
+        .clone()
+        .ok_or(i18n::t("error.system_not_initialized"))?;
+    system.trigger_voice_input().await
+}
+
+/// Capture the screen, bring the window forward and hand the capture to the
+/// chat so the user can type a question about it.
+async fn screenshot_question(app: &AppHandle, state: &AppState) -> Result<(), String> {
+    let system = state
+        .oxide_system
+        .read()
+        .await
+        .clone()
+        .ok_or(i18n::t("error.system_not_initialized"))?;
+    let attachment = system.capture_screen_attachment().await?;
+    if let Some(window) = app.get_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+    events::emit(app, &ScreenshotQuestion(attachment));
+    Ok(())
+}
+
+/// Halt RPA and cancel every running job.
+pub async fn panic_stop(app: &AppHandle, state: &AppState) -> PanicStopReport {
+    let rpa_halted = match state.rpa_state.read().await.as_ref() {
+        Some(controller) => match controller.halt() {
+            Ok(()) => true,
+            Err(e) => {
+                warn!("RPA halt incomplete: {e}");
+                controller.is_halted()
+            }
+        },
+        None => false,
+    };
+    let cancelled_jobs: Vec<String> = state
+        .jobs
+        .list(None)
+        .into_iter()
+        .filter(|job| !job.state.is_finished())
+        .filter_map(|job| state.jobs.cancel(&job.job_id).ok())
+        .map(|job| job.job_id)
+        .collect();
+    warn!(
+        "Panic stop: RPA halted: {rpa_halted}, {} job(s) cancelled",
+        cancelled_jobs.len()
+    );
+
+    let report = PanicStopReport {
+        rpa_halted,
+        cancelled_jobs,
+    };
+    events::emit(app, &report);
+    report
+}
@@ -0,0 +1,626 @@
+//! Background job manager shared by every long-running operation: folder
+//! scans, LLM analyses, database maintenance, evidence export, plan runs and
+//! the metrics collector.
+//!
+//! Every job gets an id right away and moves through
+//! `queued → running → completed | failed | cancelled`. At most
+//! `max_concurrent` jobs run at once; the rest wait in `queued`. Services
+//! started with [`JobManager::spawn_service`] run outside that limit. Jobs
+//! report a stage and an optional progress fraction through their
+//! [`JobHandle`]. Commands that still return their result directly use
+//! [`JobManager::run`], which spawns the job and waits for it.
+//!
+//! Cancellation depends on how a job was spawned: [`CancelMode::Abort`]
+//! aborts the task, dropping any in-flight HTTP request, while
+//! [`CancelMode::Cooperative`] only raises the handle's cancel flag so the
+//! job can stop cleanly and record partial results.
+//!
+//! Job records are written to a JSON file on every state change. Jobs that
+//! were queued or running when the app exited come back as failed. Services
+//! are left out, since they start again with the app.
+
+use chrono::{DateTime, Utc};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{oneshot, Semaphore};
+use tokio::task::AbortHandle;
+
+/// Job records file, relative to the working directory like the database
+pub const DEFAULT_JOBS_PATH: &str = "./data/jobs.json";
+/// Jobs allowed to run at the same time
+pub const DEFAULT_MAX_CONCURRENT_JOBS: usize = 4;
+/// Finished jobs retained for `get_job`/`list_jobs`
+pub const MAX_FINISHED_JOBS: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl JobState {
+    pub fn is_finished(self) -> bool {
+        matches!(
+            self,
+            JobState::Completed | JobState::Failed | JobState::Cancelled
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CancelMode {
+    /// Abort the task immediately
+    Abort,
+    /// Raise the cancel flag and let the job finish on its own
+    Cooperative,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobInfo {
+    pub job_id: String,
+    /// e.g. `folder_scan`, `threat_consensus`, `database_backup`,
+    /// `metrics_collection`
+    pub kind: String,
+    pub state: JobState,
+    /// Latest stage reported by the job
+    pub stage: Option<String>,
+    /// Completion fraction in `0.0..=1.0` when the job can estimate it
+    pub progress: Option<f32>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub result: Option<Value>,
+    pub error: Option<String>,
+}
+
+type Notify = Arc<dyn Fn(&JobInfo) + Send + Sync>;
+
+struct JobEntry {
+    info: JobInfo,
+    mode: CancelMode,
+    cancel: Arc<AtomicBool>,
+    abort: Option<AbortHandle>,
+    notify: Notify,
+    /// Written to the job records; false for services
+    persist: bool,
+}
+
+/// Passed to a running job for progress reporting and cancellation checks.
+#[derive(Clone)]
+pub struct JobHandle {
+    manager: Arc<JobManager>,
+    job_id: String,
+    cancel: Arc<AtomicBool>,
+}
+
+impl JobHandle {
+    pub fn id(&self) -> &str {
+        &self.job_id
+    }
+
+    /// Flag raised by `cancel_job`, for loops that poll it directly
+    pub fn cancel_flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.cancel)
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.load(Ordering::SeqCst)
+    }
+
+    pub fn stage(&self, stage: &str) {
+        self.manager.update(&self.job_id, false, |info| {
+            info.stage = Some(stage.to_string());
+        });
+    }
+
+    pub fn progress(&self, fraction: f32) {
+        self.manager.update(&self.job_id, false, |info| {
+            info.progress = Some(fraction.clamp(0.0, 1.0));
+        });
+    }
+}
+
+pub struct JobManager {
+    jobs: Mutex<HashMap<String, JobEntry>>,
+    slots: Arc<Semaphore>,
+    store: Option<PathBuf>,
+    store_lock: Mutex<()>,
+}
+
+impl JobManager {
+    /// Load job records from `store` (when set), marking unfinished ones as
+    /// interrupted.
+    pub fn new(max_concurrent: usize, store: Option<PathBuf>) -> Self {
+        let mut jobs = HashMap::new();
+        if let Some(path) = store.as_ref() {
+            match std::fs::read(path) {
+                Ok(bytes) => match serde_json::from_slice::<Vec<JobInfo>>(&bytes) {
+                    Ok(records) => {
+                        for mut info in records {
+                            if !info.state.is_finished() {
+                                info.state = JobState::Failed;
+                                info.error = Some("Interrupted by application restart".into());
+                                info.finished_at = Some(Utc::now());
+                            }
+                            let noop: Notify = Arc::new(|_| {});
+                            jobs.insert(
+                                info.job_id.clone(),
+                                JobEntry {
+                                    info,
+                                    mode: CancelMode::Abort,
+                                    cancel: Arc::new(AtomicBool::new(false)),
+                                    abort: None,
+                                    notify: noop,
+                                    persist: true,
+                                },
+                            );
+                        }
+                    }
+                    Err(e) => warn!("Ignoring unreadable job records {}: {e}", path.display()),
+                },
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => warn!("Failed to read job records {}: {e}", path.display()),
+            }
+        }
+        Self {
+            jobs: Mutex::new(jobs),
+            slots: Arc::new(Semaphore::new(max_concurrent.max(1))),
+            store,
+            store_lock: Mutex::new(()),
+        }
+    }
+
+    /// Queue `run` as a job of `kind`. `notify` receives every change.
+    pub fn spawn<F, Fut>(
+        self: &Arc<Self>,
+        kind: &str,
+        mode: CancelMode,
+        notify: impl Fn(&JobInfo) + Send + Sync + 'static,
+        run: F,
+    ) -> String
+    where
+        F: FnOnce(JobHandle) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<Value, String>> + Send + 'static,
+    {
+        self.start(kind, mode, Arc::new(notify), true, run)
+    }
+
+    /// Start a long-lived service of `kind`, such as the metrics collector.
+    /// It runs at once without taking one of the `max_concurrent` slots and
+    /// stays `running` until it stops or is cancelled.
+    pub fn spawn_service<F, Fut>(self: &Arc<Self>, kind: &str, mode: CancelMode, run: F) -> String
+    where
+        F: FnOnce(JobHandle) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<Value, String>> + Send + 'static,
+    {
+        self.start(kind, mode, Arc::new(|_: &JobInfo| {}), false, run)
+    }
+
+    /// Run `run` as a job and wait for its result, for commands that return
+    /// it directly rather than a job id. Fails if the job is cancelled
+    /// before it produces a result.
+    pub async fn run<T, F, Fut>(
+        self: &Arc<Self>,
+        kind: &str,
+        mode: CancelMode,
+        notify: impl Fn(&JobInfo) + Send + Sync + 'static,
+        run: F,
+    ) -> Result<T, String>
+    where
+        T: Serialize + Send + 'static,
+        F: FnOnce(JobHandle) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<T, String>> + Send + 'static,
+    {
+        let (tx, rx) = oneshot::channel();
+        let job_id = self.spawn(kind, mode, notify, move |job| async move {
+            let outcome = run(job).await;
+            let recorded = match &outcome {
+                Ok(result) => serde_json::to_value(result).map_err(|e| e.to_string()),
+                Err(e) => Err(e.clone()),
+            };
+            let _ = tx.send(outcome);
+            recorded
+        });
+        rx.await
+            .unwrap_or_else(|_| Err(format!("Job {job_id} was cancelled")))
+    }
+
+    fn start<F, Fut>(
+        self: &Arc<Self>,
+        kind: &str,
+        mode: CancelMode,
+        notify: Notify,
+        queued: bool,
+        run: F,
+    ) -> String
+    where
+        F: FnOnce(JobHandle) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<Value, String>> + Send + 'static,
+    {
+        let job_id = uuid::Uuid::new_v4().to_string();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let info = JobInfo {
+            job_id: job_id.clone(),
+            kind: kind.to_string(),
+            state: JobState::Queued,
+            stage: None,
+            progress: None,
+            created_at: Utc::now(),
+            started_at: None,
+            finished_at: None,
+            result: None,
+            error: None,
+        };
+        notify(&info);
+        {
+            let mut jobs = self.jobs.lock().unwrap();
+            Self::prune(&mut jobs);
+            jobs.insert(
+                job_id.clone(),
+                JobEntry {
+                    info,
+                    mode,
+                    cancel: Arc::clone(&cancel),
+                    abort: None,
+                    notify,
+                    persist: queued,
+                },
+            );
+        }
+        if queued {
+            self.persist();
+        }
+
+        let handle = JobHandle {
+            manager: Arc::clone(self),
+            job_id: job_id.clone(),
+            cancel,
+        };
+        let manager = Arc::clone(self);
+        let slots = Arc::clone(&self.slots);
+        let task = tokio::spawn(async move {
+            let _permit = if queued {
+                let Ok(permit) = slots.acquire_owned().await else {
+                    return;
+                };
+                Some(permit)
+            } else {
+                None
+            };
+            if handle.is_cancelled() {
+                return;
+            }
+            manager.update(&handle.job_id, true, |info| {
+                info.state = JobState::Running;
+                info.started_at = Some(Utc::now());
+            });
+
+            let job_id = handle.job_id.clone();
+            let cancel = handle.cancel_flag();
+            let outcome = run(handle).await;
+            manager.update(&job_id, true, |info| {
+                info.finished_at = Some(Utc::now());
+                // Cooperative jobs keep their partial result when cancelled
+                let cancelled = cancel.load(Ordering::SeqCst);
+                match outcome {
+                    Ok(result) => {
+                        info.state = if cancelled {
+                            JobState::Cancelled
+                        } else {
+                            info.progress = Some(1.0);
+                            JobState::Completed
+                        };
+                        info.result = Some(result);
+                    }
+                    Err(e) => {
+                        info.state = if cancelled {
+                            JobState::Cancelled
+                        } else {
+                            JobState::Failed
+                        };
+                        info.error = Some(e);
+                    }
+                }
+            });
+        });
+
+        if let Some(entry) = self.jobs.lock().unwrap().get_mut(&job_id) {
+            if !entry.info.state.is_finished() {
+                entry.abort = Some(task.abort_handle());
+            }
+        }
+        job_id
+    }
+
+    /// Cancel a queued or running job. Cooperative jobs stay `running` until
+    /// they notice the flag; everything else is cancelled immediately.
+    pub fn cancel(&self, job_id: &str) -> Result<JobInfo, String> {
+        let (info, notify, finished) = {
+            let mut jobs = self.jobs.lock().unwrap();
+            let entry = jobs
+                .get_mut(job_id)
+                .ok_or_else(|| "Unknown job_id".to_string())?;
+            if entry.info.state.is_finished() {
+                return Err(format!("Job {job_id} already finished"));
+            }
+            entry.cancel.store(true, Ordering::SeqCst);
+            let immediate = entry.mode == CancelMode::Abort || entry.info.state == JobState::Queued;
+            if immediate {
+                if let Some(abort) = entry.abort.take() {
+                    abort.abort();
+                }
+                entry.info.state = JobState::Cancelled;
+                entry.info.finished_at = Some(Utc::now());
+            } else {
+                entry.info.stage = Some("Cancelling".to_string());
+            }
+            (entry.info.clone(), Arc::clone(&entry.notify), immediate)
+        };
+        notify(&info);
+        if finished {
+            self.persist();
+        }
+        Ok(info)
+    }
+
+    /// Whether `job_id` was started with [`Self::spawn_service`].
+    pub fn is_service(&self, job_id: &str) -> bool {
+        self.jobs
+            .lock()
+            .unwrap()
+            .get(job_id)
+            .is_some_and(|entry| !entry.persist)
+    }
+
+    pub fn get(&self, job_id: &str) -> Option<JobInfo> {
+        self.jobs
+            .lock()
+            .unwrap()
+            .get(job_id)
+            .map(|entry| entry.info.clone())
+    }
+
+    /// Retained jobs, newest first, optionally limited to one kind.
+    pub fn list(&self, kind: Option<&str>) -> Vec<JobInfo> {
+        let mut jobs: Vec<JobInfo> = self
+            .jobs
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|entry| kind.is_none_or(|k| entry.info.kind == k))
+            .map(|entry| entry.info.clone())
+            .collect();
+        jobs.sort_by_key(|job| std::cmp::Reverse(job.created_at));
+        jobs
+    }
+
+    /// Apply `change` to an unfinished job and notify; updates that arrive
+    /// after a job finished (e.g. from an aborted task) are dropped.
+    /// State transitions are persisted, stage/progress updates are not.
+    fn update(&self, job_id: &str, transition: bool, change: impl FnOnce(&mut JobInfo)) {
+        let (info, notify) = {
+            let mut jobs = self.jobs.lock().unwrap();
+            let Some(entry) = jobs.get_mut(job_id) else {
+                return;
+            };
+            if entry.info.state.is_finished() {
+                return;
+            }
+            change(&mut entry.info);
+            if entry.info.state.is_finished() {
+                entry.abort = None;
+            }
+            (entry.info.clone(), Arc::clone(&entry.notify))
+        };
+        notify(&info);
+        if transition {
+            self.persist();
+        }
+    }
+
+    fn persist(&self) {
+        let Some(path) = self.store.as_ref() else {
+            return;
+        };
+        // Snapshot under the store lock so a slower writer can't replace a
+        // newer snapshot with an older one
+        let _guard = self.store_lock.lock().unwrap();
+        let records: Vec<JobInfo> = self
+            .jobs
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|entry| entry.persist)
+            .map(|entry| entry.info.clone())
+            .collect();
+        let write = || -> std::io::Result<()> {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let tmp = path.with_extension("json.partial");
+            std::fs::write(&tmp, serde_json::to_vec(&records)?)?;
+            std::fs::rename(&tmp, path)
+        };
+        if let Err(e) = write() {
+            warn!("Failed to persist job records to {}: {e}", path.display());
+        }
+    }
+
+    fn prune(jobs: &mut HashMap<String, JobEntry>) {
+        let mut finished: Vec<(DateTime<Utc>, String)> = jobs
+            .values()
+            .filter(|entry| entry.info.state.is_finished())
+            .map(|entry| (entry.info.created_at, entry.info.job_id.clone()))
+            .collect();
+        if finished.len() < MAX_FINISHED_JOBS {
+            return;
+        }
+        finished.sort();
+        let excess = finished.len() + 1 - MAX_FINISHED_JOBS;
+        for (_, job_id) in finished.into_iter().take(excess) {
+            jobs.remove(&job_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn jobs_queue_complete_and_cancel() {
+        let jobs = Arc::new(JobManager::new(1, None));
+
+        let done = jobs.spawn(
+            "threat_consensus",
+            CancelMode::Abort,
+            |_| {},
+            |job| async move {
+                job.stage("Querying models");
+                Ok(json!({}))
+            },
+        );
+        let stalled = jobs.spawn(
+            "collaborative_analysis",
+            CancelMode::Abort,
+            |_| {},
+            |job| async move {
+                job.stage("Waiting for coordinator");
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                Ok(json!("never"))
+            },
+        );
+        let queued = jobs.spawn(
+            "folder_scan",
+            CancelMode::Cooperative,
+            |_| {},
+            |_| async move { Ok(json!(null)) },
+        );
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let finished = jobs.get(&done).unwrap();
+        assert_eq!(finished.state, JobState::Completed);
+        assert_eq!(finished.stage.as_deref(), Some("Querying models"));
+        assert_eq!(finished.result, Some(json!({})));
+        assert!(jobs.cancel(&done).is_err());
+
+        // One slot: the folder scan waits behind the stalled analysis
+        assert_eq!(jobs.get(&queued).unwrap().state, JobState::Queued);
+        let cancelled = jobs.cancel(&stalled).unwrap();
+        assert_eq!(cancelled.state, JobState::Cancelled);
+        assert_eq!(cancelled.stage.as_deref(), Some("Waiting for coordinator"));
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(jobs.get(&queued).unwrap().state, JobState::Completed);
+        assert_eq!(jobs.list(Some("folder_scan")).len(), 1);
+        assert!(jobs.cancel("missing").is_err());
+    }
+
+    #[tokio::test]
+    async fn cooperative_cancel_and_restart_recovery() {
+        let dir = std::env::temp_dir().join(format!("oxide-jobs-{}", uuid::Uuid::new_v4()));
+        let store = dir.join("jobs.json");
+        let jobs = Arc::new(JobManager::new(2, Some(store.clone())));
+
+        let scan = jobs.spawn(
+            "folder_scan",
+            CancelMode::Cooperative,
+            |_| {},
+            |job| async move {
+                while !job.is_cancelled() {
+                    tokio::time::sleep(Duration::from_millis(5)).await;
+                }
+                Ok(json!({ "scanned": 3 }))
+            },
+        );
+        let stuck = jobs.spawn(
+            "threat_consensus",
+            CancelMode::Abort,
+            |_| {},
+            |_| async move {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                Ok(json!(null))
+            },
+        );
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        assert_eq!(jobs.cancel(&scan).unwrap().state, JobState::Running);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(jobs.get(&scan).unwrap().state, JobState::Cancelled);
+
+        // A fresh manager sees the still-running job as interrupted
+        let reloaded = JobManager::new(2, Some(store));
+        let interrupted = reloaded.get(&stuck).unwrap();
+        assert_eq!(interrupted.state, JobState::Failed);
+        assert!(interrupted.error.unwrap().contains("restart"));
+        let scan = reloaded.get(&scan).unwrap();
+        assert_eq!(scan.state, JobState::Cancelled);
+        assert_eq!(scan.result, Some(json!({ "scanned": 3 })));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn run_waits_for_result_and_services_skip_queue() {
+        let dir = std::env::temp_dir().join(format!("oxide-jobs-{}", uuid::Uuid::new_v4()));
+        let store = dir.join("jobs.json");
+        let jobs = Arc::new(JobManager::new(1, Some(store.clone())));
+
+        let collector = jobs.spawn_service("metrics_collection", CancelMode::Abort, |_| async {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            Ok(json!(null))
+        });
+        // The service does not hold the only slot
+        let report: Vec<u32> = jobs
+            .run(
+                "database_backup",
+                CancelMode::Abort,
+                |_| {},
+                |job| async move {
+                    job.progress(0.5);
+                    Ok(vec![1, 2])
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(report, vec![1, 2]);
+        assert_eq!(jobs.get(&collector).unwrap().state, JobState::Running);
+        assert!(jobs.is_service(&collector));
+
+        let runner = Arc::clone(&jobs);
+        let stalled = tokio::spawn(async move {
+            runner
+                .run(
+                    "vector_index_rebuild",
+                    CancelMode::Abort,
+                    |_| {},
+                    |_| async {
+                        tokio::time::sleep(Duration::from_secs(60)).await;
+                        Ok(())
+                    },
+                )
+                .await
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let job = jobs.list(Some("vector_index_rebuild")).remove(0);
+        assert!(!jobs.is_service(&job.job_id));
+        jobs.cancel(&job.job_id).unwrap();
+        assert!(stalled.await.unwrap().unwrap_err().contains("cancelled"));
+
+        let reloaded = JobManager::new(1, Some(store));
+        assert!(reloaded.get(&collector).is_none());
+        assert_eq!(reloaded.list(Some("database_backup")).len(), 1);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,99 @@
+//! Periodic memory leak checks over the recorded per-process history.
+//!
+//! Every `check_interval_mins` each process name's memory over the last
+//! `window_hours` is fitted by [`oxide_guardian::leak_detector`]. Suspected
+//! leaks are recorded as Medium threats, which also reach the configured
+//! alert sinks; a process is reported again only after a day.
+
+use crate::oxide_system::OxideSystem;
+use chrono::{DateTime, Duration, Utc};
+use log::warn;
+use oxide_core::config::LeakDetectionConfig;
+use oxide_guardian::leak_detector::{self, SuspectedLeak};
+use oxide_memory::SurrealBackend;
+use std::collections::HashMap;
+
+/// Hours before the same process is reported again.
+const REPORT_AGAIN_AFTER_HOURS: i64 = 24;
+
+/// Suspected leaks in the recorded history, fastest growing first.
+pub async fn run(
+    backend: &SurrealBackend,
+    cfg: &LeakDetectionConfig,
+    available_mb: Option<f64>,
+) -> Result<Vec<SuspectedLeak>, String> {
+    let samples = backend
+        .query_process_samples(cfg.window_hours() as i64)
+        .await
+        .map_err(|e| format!("Failed to load process history: {e:#}"))?;
+
+    let now = Utc::now();
+    let mut by_name: Vec<(String, Vec<(DateTime<Utc>, f64)>)> = Vec::new();
+    for sample in samples {
+        match by_name.last_mut() {
+            Some((name, series)) if *name == sample.name => {
+                series.push((sample.timestamp, sample.memory_mb))
+            }
+            _ => by_name.push((sample.name, vec![(sample.timestamp, sample.memory_mb)])),
+        }
+    }
+    let mut leaks: Vec<SuspectedLeak> = by_name
+        .iter()
+        .filter_map(|(name, series)| leak_detector::analyze(name, series, available_mb, cfg, now))
+        .collect();
+    leaks.sort_by(|a, b| b.growth_mb_per_hour.total_cmp(&a.growth_mb_per_hour));
+    Ok(leaks)
+}
+
+/// Memory not in use right now, from Guardian's last refresh.
+pub fn available_mb(system: &OxideSystem) -> Option<f64> {
+    let (used, total) = system.get_system_status().memory_usage;
+    (total > 0).then(|| total.saturating_sub(used) as f64 / 1024.0 / 1024.0)
+}
+
+/// Run [`run`] on the configured schedule while the system runs. The config
+/// is re-read before each run so changes apply without a restart.
+pub fn spawn(system: OxideSystem) {
+    tokio::spawn(async move {
+        let mut reported: HashMap<String, DateTime<Utc>> = HashMap::new();
+        loop {
+            let mins = current_config(&system)
+                .await
+                .map(|c| c.check_interval_mins())
+                .unwrap_or(LeakDetectionConfig::DEFAULT_CHECK_INTERVAL_MINS);
+            tokio::time::sleep(std::time::Duration::from_secs(mins * 60)).await;
+            if !system.is_running().await {
+                break;
+            }
+            let cfg = current_config(&system).await.unwrap_or_default();
+            if !cfg.enabled {
+                continue;
+            }
+            let Some(backend) = system.surreal_backend() else {
+                continue;
+            };
+            let leaks = match run(&backend, &cfg, available_mb(&system)).await {
+                Ok(leaks) => leaks,
+                Err(e) => {
+                    warn!("Leak detection failed: {e}");
+                    continue;
+                }
+            };
+            let now = Utc::now();
+            reported.retain(|_, at| now - *at < Duration::hours(REPORT_AGAIN_AFTER_HOURS));
+            for leak in leaks {
+                if reported.contains_key(&leak.process_name) {
+                    continue;
+                }
+                let threat = leak.to_threat();
+                warn!("MEDIUM THREAT: {}", threat.description);
+                system.record_threat(threat);
+                reported.insert(leak.process_name, now);
+            }
+        }
+    });
+}
+
+async fn current_config(system: &OxideSystem) -> Option<LeakDetectionConfig> {
+    system.get_config().await.guardian.leak_detection
+}
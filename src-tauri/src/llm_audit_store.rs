@@ -0,0 +1,51 @@
+//! Persists the LLM audit trail recorded by `oxide_core::llm_audit` into
+//! the `llm_audit` table and prunes entries past the retention period.
+
+use crate::oxide_system::OxideSystem;
+use chrono::{Duration, Utc};
+use log::{info, warn};
+use oxide_core::llm_audit;
+
+const PRUNE_INTERVAL_HOURS: u64 = 24;
+
+/// Store every audited exchange while the system runs, and prune old
+/// entries once a day. A later call replaces the earlier writer.
+pub fn spawn(system: OxideSystem) {
+    let mut entries = llm_audit::subscribe();
+    let writer = system.clone();
+    tokio::spawn(async move {
+        while let Some(entry) = entries.recv().await {
+            let Some(backend) = writer.surreal_backend() else {
+                continue;
+            };
+            if let Err(e) = backend.insert_llm_audit(&entry).await {
+                warn!(
+                    "Failed to store LLM audit entry for {} ({}): {e:#}",
+                    entry.provider, entry.purpose
+                );
+            }
+        }
+    });
+
+    tokio::spawn(async move {
+        loop {
+            if !system.is_running().await {
+                break;
+            }
+            if let Some(backend) = system.surreal_backend() {
+                let days = i64::from(llm_audit::retention_days());
+                match backend
+                    .prune_llm_audit(Utc::now() - Duration::days(days))
+                    .await
+                {
+                    Ok(0) => {}
+                    Ok(removed) => {
+                        info!("Pruned {removed} LLM audit entries older than {days} days")
+                    }
+                    Err(e) => warn!("Failed to prune the LLM audit trail: {e:#}"),
+                }
+            }
+            tokio::time::sleep(std::time::Duration::from_secs(PRUNE_INTERVAL_HOURS * 3600)).await;
+        }
+    });
+}
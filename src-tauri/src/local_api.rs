@@ -0,0 +1,316 @@
+//! OpenAI-compatible chat API for other local tools.
+//!
+//! Serves `GET /v1/models` and `POST /v1/chat/completions` on localhost so
+//! editors and scripts can point an OpenAI client at Oxide Pilot. Answers
+//! come from the copilot's AI providers with the current system status
+//! injected; tools are not offered to API callers. Every request needs the
+//! configured bearer token. With `"stream": true` the whole answer is sent
+//! as a single chunk, since providers return complete responses.
+
+use crate::oxide_system::OxideSystem;
+use axum::{
+    extract::State,
+    http::{header::AUTHORIZATION, HeaderMap, StatusCode},
+    response::{
+        sse::{Event, Sse},
+        IntoResponse, Response,
+    },
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::Utc;
+use futures_util::stream;
+use log::{info, warn};
+use oxide_core::types::{Context, Interaction};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use subtle::ConstantTimeEq;
+use tokio::{sync::oneshot, task::JoinHandle};
+
+pub const DEFAULT_PORT: u16 = 7997;
+
+/// Model id reported to clients; the request's `model` is ignored.
+const MODEL_ID: &str = "oxide-pilot";
+
+#[derive(Clone)]
+struct ApiState {
+    system: OxideSystem,
+    token: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct ChatCompletionRequest {
+    messages: Vec<ChatMessage>,
+    #[serde(default)]
+    stream: bool,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct ChatMessage {
+    role: String,
+    /// A string, or an array of `{"type": "text", "text": ...}` parts
+    #[serde(default)]
+    content: Value,
+}
+
+impl ChatMessage {
+    fn text(&self) -> String {
+        match &self.content {
+            Value::String(text) => text.clone(),
+            Value::Array(parts) => parts
+                .iter()
+                .filter_map(|part| part.get("text").and_then(Value::as_str))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            _ => String::new(),
+        }
+    }
+}
+
+/// The conversation as the copilot sees it.
+#[derive(Debug, PartialEq)]
+struct Conversation {
+    /// Joined `system` and `developer` messages
+    instructions: Option<String>,
+    history: Vec<(String, String)>,
+    input: String,
+}
+
+/// Split OpenAI messages into instructions, completed user/assistant turns
+/// and the final user message. Consecutive user messages are merged; tool
+/// messages are dropped.
+fn conversation(messages: &[ChatMessage]) -> Result<Conversation, String> {
+    let mut instructions = Vec::new();
+    let mut history = Vec::new();
+    let mut pending: Option<String> = None;
+    for message in messages {
+        let text = message.text();
+        match message.role.as_str() {
+            "system" | "developer" => instructions.push(text),
+            "user" => {
+                pending = Some(match pending.take() {
+                    Some(previous) => format!("{previous}\n\n{text}"),
+                    None => text,
+                })
+            }
+            "assistant" => history.push((pending.take().unwrap_or_default(), text)),
+            _ => {}
+        }
+    }
+    let input = pending
+        .filter(|input| !input.trim().is_empty())
+        .ok_or("The last message must be a non-empty user message")?;
+    Ok(Conversation {
+        instructions: (!instructions.is_empty()).then(|| instructions.join("\n\n")),
+        history,
+        input,
+    })
+}
+
+fn interaction(user_input: String, agent_response: String) -> Interaction {
+    Interaction {
+        id: uuid::Uuid::new_v4(),
+        timestamp: Utc::now(),
+        user_input,
+        agent_response,
+        context: Context {
+            active_window: None,
+            clipboard_text: None,
+            system_status: None,
+            recent_events: Vec::new(),
+            memory_context: None,
+        },
+    }
+}
+
+fn api_error(status: StatusCode, kind: &str, message: impl Into<String>) -> Response {
+    let body = json!({ "error": { "message": message.into(), "type": kind } });
+    (status, Json(body)).into_response()
+}
+
+// Compared in constant time so response timing doesn't leak the token.
+fn authorized(headers: &HeaderMap, token: &str) -> bool {
+    headers
+        .get(AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|v| v.trim().strip_prefix("Bearer "))
+        .is_some_and(|t| bool::from(t.trim().as_bytes().ct_eq(token.as_bytes())))
+}
+
+async fn list_models(State(state): State<ApiState>, headers: HeaderMap) -> Response {
+    if !authorized(&headers, &state.token) {
+        return api_error(
+            StatusCode::UNAUTHORIZED,
+            "invalid_request_error",
+            "Invalid token",
+        );
+    }
+    Json(json!({
+        "object": "list",
+        "data": [{ "id": MODEL_ID, "object": "model", "created": 0, "owned_by": "oxide-pilot" }],
+    }))
+    .into_response()
+}
+
+async fn chat_completions(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Json(request): Json<ChatCompletionRequest>,
+) -> Response {
+    if !authorized(&headers, &state.token) {
+        warn!("Rejected local API request with an invalid token");
+        return api_error(
+            StatusCode::UNAUTHORIZED,
+            "invalid_request_error",
+            "Invalid token",
+        );
+    }
+    let conversation = match conversation(&request.messages) {
+        Ok(conversation) => conversation,
+        Err(e) => return api_error(StatusCode::BAD_REQUEST, "invalid_request_error", e),
+    };
+    let history = conversation
+        .history
+        .into_iter()
+        .map(|(user, assistant)| interaction(user, assistant))
+        .collect();
+    let answer = match state
+        .system
+        .api_chat_completion(conversation.instructions, history, conversation.input)
+        .await
+    {
+        Ok(answer) => answer,
+        Err(e) => return api_error(StatusCode::BAD_GATEWAY, "api_error", e),
+    };
+
+    let id = format!("chatcmpl-{}", uuid::Uuid::new_v4().simple());
+    let created = Utc::now().timestamp();
+    if !request.stream {
+        return Json(json!({
+            "id": id,
+            "object": "chat.completion",
+            "created": created,
+            "model": MODEL_ID,
+            "choices": [{
+                "index": 0,
+                "message": { "role": "assistant", "content": answer },
+                "finish_reason": "stop",
+            }],
+        }))
+        .into_response();
+    }
+    let chunk = |delta: Value, finish_reason: Option<&str>| {
+        json!({
+            "id": id,
+            "object": "chat.completion.chunk",
+            "created": created,
+            "model": MODEL_ID,
+            "choices": [{ "index": 0, "delta": delta, "finish_reason": finish_reason }],
+        })
+        .to_string()
+    };
+    let events = [
+        chunk(json!({ "role": "assistant", "content": answer }), None),
+        chunk(json!({}), Some("stop")),
+        "[DONE]".to_string(),
+    ]
+    .map(|data| Ok::<Event, Infallible>(Event::default().data(data)));
+    Sse::new(stream::iter(events)).into_response()
+}
+
+/// What `local_api_status` reports.
+#[derive(Serialize)]
+pub struct LocalApiStatus {
+    pub running: bool,
+    /// OpenAI-compatible base URL while running
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+}
+
+pub struct LocalApiHandle {
+    addr: SocketAddr,
+    shutdown: Option<oneshot::Sender<()>>,
+    task: Option<JoinHandle<()>>,
+}
+
+impl LocalApiHandle {
+    pub async fn start(
+        port: u16,
+        token: String,
+        system: OxideSystem,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        if token.trim().is_empty() {
+            return Err("The local API requires an access token".into());
+        }
+        let addr = SocketAddr::from(([127, 0, 0, 1], port));
+        let (tx, rx) = oneshot::channel::<()>();
+        let app = Router::new()
+            .route("/v1/models", get(list_models))
+            .route("/v1/chat/completions", post(chat_completions))
+            .with_state(ApiState { system, token });
+
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        let server = axum::serve(listener, app).with_graceful_shutdown(async move {
+            let _ = rx.await;
+        });
+        let task = tokio::spawn(async move {
+            if let Err(err) = server.await {
+                warn!("Local API server error: {err}");
+            }
+        });
+        info!("Local API listening on http://{addr}/v1");
+
+        Ok(Self {
+            addr,
+            shutdown: Some(tx),
+            task: Some(task),
+        })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    pub async fn stop(&mut self) {
+        if let Some(tx) = self.shutdown.take() {
+            let _ = tx.send(());
+        }
+        if let Some(task) = self.task.take() {
+            let _ = task.await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(role: &str, content: Value) -> ChatMessage {
+        ChatMessage {
+            role: role.to_string(),
+            content,
+        }
+    }
+
+    #[test]
+    fn maps_openai_messages_to_a_conversation() {
+        let messages = [
+            message("system", json!("Be brief.")),
+            message("user", json!("hi")),
+            message("assistant", json!("hello")),
+            message("user", json!([{ "type": "text", "text": "why is my" }])),
+            message("user", json!("fan loud?")),
+        ];
+        assert_eq!(
+            conversation(&messages).unwrap(),
+            Conversation {
+                instructions: Some("Be brief.".to_string()),
+                history: vec![("hi".to_string(), "hello".to_string())],
+                input: "why is my\n\nfan loud?".to_string(),
+            }
+        );
+        assert!(conversation(&messages[..3]).is_err());
+    }
+}
@@ -165,7 +165,12 @@ pub async fn chat_completion(
         temperature: Some(0.2),
     };
 
-    let client = reqwest::Client::new();
+    // Loopback servers stay reachable in offline mode; remote ones do not
+    oxide_core::network::ensure_online(&url).map_err(|e| e.to_string())?;
+    let _permit = oxide_core::api_governor::acquire(&url)
+        .await
+        .map_err(|e| e.to_string())?;
+    let client = oxide_core::network::http_client();
     let mut req = client.post(url).header("Content-Type", "application/json");
     if let Some(key) = api_key {
         req = req.header("Authorization", format!("Bearer {key}"));
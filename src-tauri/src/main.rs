@@ -3,63 +3,155 @@
     windows_subsystem = "windows"
 )]
 
+mod cleanup;
+mod command_guard;
+mod crash_reports;
 mod error_handler;
+mod events;
+mod evidence;
+#[cfg(feature = "surrealdb-metrics")]
+mod fleet_uplink;
 mod guardian_commands;
+mod hotkey_manager;
+mod job_manager;
+#[cfg(feature = "surrealdb-metrics")]
+mod leak_detection;
+#[cfg(feature = "surrealdb-metrics")]
+mod llm_audit_store;
+mod local_api;
 mod local_llm;
 mod mcp_server;
+#[cfg(feature = "surrealdb-metrics")]
+mod memory_consolidation;
+mod onboarding;
 mod oxide_system;
+mod plan_actions;
+mod playbooks;
+mod power_policy;
+mod protection_status;
+mod remote;
 mod rpa_commands;
+mod scan_progress;
 mod security_diagnostic;
+mod self_diagnostics;
+mod slash_commands;
+mod startup;
+mod suggestions;
+mod system_snapshot;
+#[cfg(feature = "surrealdb-metrics")]
+mod telemetry_query;
 mod threat_consensus;
+#[cfg(feature = "surrealdb-metrics")]
+mod threat_training;
+mod updates;
+mod watchdog;
 
 #[cfg(test)]
 mod rpa_integration_test;
 
-use crate::mcp_server::McpServerHandle;
+use crate::local_api::{LocalApiHandle, LocalApiStatus};
+use crate::mcp_server::{McpServerConfig, McpServerHandle, McpStatus};
 use error_handler::{
     retry_with_backoff, ErrorHandler, OxideError, RetryConfig, GLOBAL_ERROR_MONITOR,
 };
+use hotkey_manager::{HotkeyStatus, PanicStopReport};
 use log::{error, info, warn};
 use oxide_copilot::auth_manager::AuthManager;
-use oxide_core::config::OxidePilotConfig;
+use oxide_copilot::response_cache::CacheStats;
+use oxide_core::api_governor::ApiQuotaStatus;
+use oxide_core::capabilities::{self, Capability, Feature};
+use oxide_core::config::{
+    ConfigIssue, HotkeyConfig, OxidePilotConfig, ScanFilterConfig, WebhookEvent,
+};
+use oxide_core::crash;
 use oxide_core::google_auth;
+use oxide_core::i18n::{self, LocaleInfo};
+use oxide_core::llm_audit::{self, LlmAuditEntry, LlmAuditQuery};
+use oxide_core::logging::{self, LogEntry};
+use oxide_core::mcp_tokens::{self, IssuedMcpToken, McpScope, McpToken, McpTokenStore};
+use oxide_core::network::NetworkStatus;
 use oxide_core::openai_auth;
 use oxide_core::openai_key;
+use oxide_core::profile;
+use oxide_core::prompts::{self, PromptTemplate};
 use oxide_core::qwen_auth::{DeviceAuthStart, PollResult, QwenAuth};
+use oxide_core::security_manager::{Role, SecurityError};
+use oxide_core::smtp_credentials;
+use oxide_core::tls::{self, TlsIdentity};
+use oxide_core::types::ImageAttachment;
+use oxide_core::webhook_credentials;
+use oxide_core::webhooks::{DeliveryResult, WebhookPayload};
+use oxide_guardian::alerts::EmailTestResult;
+use oxide_guardian::attack::AttackMatrixSummary;
+use oxide_guardian::baseline::{BaselineSnapshot, DriftReport};
+use oxide_guardian::boot_impact::BootImpactReport;
+use oxide_guardian::firewall::FirewallRule;
 use oxide_guardian::guardian::{SystemStatus, ThreatEvent};
-use oxide_guardian::scanner::FileScanReport;
-use oxide_memory::memory::MemoryStats;
+use oxide_guardian::integrity::{self, IntegrityManifest, IntegrityReport, HEARTBEAT_PATH};
+use oxide_guardian::ioc::IocImportReport;
+use oxide_guardian::memory_scan::MemoryScanReport;
+use oxide_guardian::playbook::{PlaybookLibrary, PlaybookRun};
+use oxide_guardian::process_control::{ProcessAction, ProcessIdentity};
+use oxide_guardian::remediation::{RemediationAction, RemediationFinding};
+use oxide_guardian::rules::{ProcessSample, RuleMatch, RulesStatus};
+use oxide_guardian::scan_filter::ScanFilter;
+use oxide_guardian::scan_path;
+use oxide_guardian::scanner::{FileScanReport, FileScanner};
+use oxide_guardian::startup::StartupItem;
+use oxide_memory::memory::{MemoryStats, UserPattern};
 #[cfg(feature = "surrealdb-metrics")]
-use oxide_memory::SurrealBackend;
+use oxide_memory::{SharedSurrealBackend, SurrealBackend};
 use oxide_system::OxideSystem;
+use oxide_voice::language::VoiceLanguageStatus;
+use scan_progress::{ScanRateEstimator, WorkerUtilization, DEFAULT_RATE_WINDOW};
 use serde_json::json;
+use slash_commands::{SlashCommand, SlashCommandOutput};
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::VecDeque;
-use std::path::PathBuf;
-use std::sync::{
-    atomic::{AtomicBool, Ordering},
-    Arc,
-};
+use std::path::{Path, PathBuf};
+use std::sync::{atomic::Ordering, Arc};
 use std::time::Instant;
+use suggestions::{Suggestion, SuggestionResponse};
 use tauri::{Manager, State};
-use tokio::sync::{mpsc, Mutex, RwLock};
+use tokio::sync::{broadcast, mpsc, Mutex, RwLock};
 
 // Define a struct to hold the application state with async-safe mutexes
+#[derive(Clone)]
 pub struct AppState {
     oxide_system: Arc<RwLock<Option<OxideSystem>>>,
     auth_manager: Arc<RwLock<Option<AuthManager>>>,
     mcp_server: Arc<RwLock<Option<McpServerHandle>>>,
-    // Track folder scan cancellation flags by scan_id
-    folder_scan_cancels: Arc<RwLock<HashMap<String, Arc<AtomicBool>>>>,
+    // Per-client MCP bearer tokens
+    mcp_tokens: Arc<McpTokenStore>,
+    // Other instances managed in remote mode
+    remote_hosts: Arc<remote::RemoteHosts>,
+    // OpenAI-compatible API for other local tools
+    local_api: Arc<RwLock<Option<LocalApiHandle>>>,
     // RPA controller state
     rpa_state: Arc<RwLock<Option<oxide_rpa::secure_rpa::SecureRPAController>>>,
-    // Guardian state
+    // The one SurrealDB handle, shared with OxideSystem and guardian commands
     #[cfg(feature = "surrealdb-metrics")]
-    guardian_state: Arc<guardian_commands::GuardianState>,
-    #[cfg(feature = "surrealdb-metrics")]
-    surreal_backend: Arc<SurrealBackend>,
+    shared_backend: Arc<SharedSurrealBackend>,
     // Security diagnostic state
     security_diagnostic_state: Arc<security_diagnostic::SecurityDiagnosticState>,
+    // Background jobs: scans, analyses, maintenance and services
+    jobs: Arc<job_manager::JobManager>,
+    // Progress of the async startup pipeline
+    startup: Arc<RwLock<startup::StartupStatus>>,
+    // Global push-to-talk, screenshot and panic-stop hotkeys
+    hotkeys: Arc<hotkey_manager::HotkeyManager>,
+}
+
+impl AppState {
+    /// The shared SurrealDB backend, connecting on first use.
+    #[cfg(feature = "surrealdb-metrics")]
+    async fn surreal_backend(&self) -> Result<Arc<SurrealBackend>, String> {
+        self.shared_backend
+            .get()
+            .await
+            .map_err(|e| format!("SurrealDB backend unavailable: {e:#}"))
+    }
 }
 
 // ==============================
@@ -76,9 +168,8 @@ async fn local_llm_server_stop() -> Result<String, String> {
 }
 
 #[tauri::command]
-async fn local_llm_server_status() -> Result<serde_json::Value, String> {
-    let status = local_llm::server_status().await?;
-    serde_json::to_value(status).map_err(|e| e.to_string())
+async fn local_llm_server_status() -> Result<local_llm::LocalLlmServerStatus, String> {
+    local_llm::server_status().await
 }
 
 #[tauri::command]
@@ -136,32 +227,54 @@ async fn local_llm_chat(
     .await
 }
 
-// Call Qwen Chat Completions API using stored OAuth token
+// Call Qwen Chat Completions API using stored OAuth token; repeated prompts
+// are answered from the LLM response cache
 async fn qwen_chat_completion(prompt: &str, model: Option<String>) -> Result<String, String> {
+    let model_name = model
+        .or_else(|| std::env::var("QWEN_MODEL").ok())
+        .unwrap_or_else(|| "qwen-plus".to_string());
+    let system = prompts::render(prompts::ANALYST_SYSTEM, Some("qwen"), &[]);
+    // The system prompt is a template, so it is part of the cache key
+    let cache_key = format!("{system}\n\n{prompt}");
+    oxide_copilot::response_cache::global()
+        .get_or_fetch("qwen", &model_name, Some(0.2), &cache_key, || {
+            qwen_chat_request(&system, prompt, &model_name)
+        })
+        .await
+}
+
+async fn qwen_chat_request(system: &str, prompt: &str, model_name: &str) -> Result<String, String> {
+    let sent = format!("system: {system}\n\nuser: {prompt}");
+    let request = post_qwen_chat(system, prompt, model_name);
+    llm_audit::audited("qwen", Some(model_name), &sent, request).await
+}
+
+async fn post_qwen_chat(system: &str, prompt: &str, model_name: &str) -> Result<String, String> {
     // Resolve config
     let base =
         std::env::var("QWEN_API_BASE").map_err(|_| "Missing env QWEN_API_BASE".to_string())?;
     let path = std::env::var("QWEN_CHAT_COMPLETIONS_PATH")
         .unwrap_or_else(|_| "/v1/chat/completions".to_string());
     let url = format!("{base}{path}");
-    let model_name = model
-        .or_else(|| std::env::var("QWEN_MODEL").ok())
-        .unwrap_or_else(|| "qwen-plus".to_string());
 
     // Auth header from stored OAuth token
     let qauth = QwenAuth::new();
     let auth_header = qauth.get_auth_header().await.map_err(|e| e.to_string())?;
+    oxide_core::network::ensure_online(&url).map_err(|e| e.to_string())?;
+    let _permit = oxide_core::api_governor::acquire(&url)
+        .await
+        .map_err(|e| e.to_string())?;
 
     let body = serde_json::json!({
         "model": model_name,
         "messages": [
-            {"role": "system", "content": "You are an expert OS internals, performance, and security analyst. Respond concisely and technically."},
+            {"role": "system", "content": system},
             {"role": "user", "content": prompt}
         ],
         "temperature": 0.2
     });
 
-    let client = reqwest::Client::new();
+    let client = oxide_core::network::http_client();
     let resp = client
         .post(&url)
         .header("Authorization", auth_header)
@@ -203,7 +316,18 @@ async fn run_collaborative_analysis(
     user_input: String,
     task_type: Option<String>,
 ) -> Result<String, String> {
-    let snapshot_val = get_system_snapshot(state).await?;
+    collaborative_analysis(&state, user_input, task_type, &|_| {}).await
+}
+
+async fn collaborative_analysis(
+    state: &AppState,
+    user_input: String,
+    task_type: Option<String>,
+    stage: &(dyn Fn(&str) + Send + Sync),
+) -> Result<String, String> {
+    let orchestrator_config = current_orchestrator_config(state).await;
+    stage("Collecting system snapshot");
+    let snapshot_val = collect_system_snapshot(state).await?;
 
     // Create collaborative context
     let context = oxide_copilot::llm_orchestrator::CollaborativeContext {
@@ -211,22 +335,11 @@ async fn run_collaborative_analysis(
         system_state: snapshot_val,
         user_input,
         conversation_history: vec![],
-        available_functions: vec![
-            "scan_file".to_string(),
-            "get_system_status".to_string(),
-            "run_system_analysis".to_string(),
-            "get_threat_history".to_string(),
-        ],
-        constraints: {
-            let mut map = std::collections::HashMap::new();
-            map.insert("max_execution_time".to_string(), serde_json::json!(300));
-            map.insert("security_level".to_string(), serde_json::json!("high"));
-            map.insert(
-                "performance_impact".to_string(),
-                serde_json::json!("minimal"),
-            );
-            map
-        },
+        available_functions: plan_actions::PLAN_FUNCTIONS
+            .iter()
+            .map(|name| name.to_string())
+            .collect(),
+        constraints: default_plan_constraints(),
     };
 
     // Create and configure the orchestrator
@@ -234,31 +347,19 @@ async fn run_collaborative_analysis(
 
     // Add collaborative providers
     use oxide_copilot::collaborative_providers::CollaborativeProviderFactory;
-    use oxide_copilot::llm_orchestrator::{LLMConfig, LLMRole};
-
-    let providers = CollaborativeProviderFactory::create_default_setup();
-    for (name, provider, role) in providers {
-        let config = LLMConfig {
-            provider: name.clone(),
-            model: Some("default".to_string()),
-            role: role.clone(),
-            temperature: match role {
-                LLMRole::Coordinator => 0.3,
-                LLMRole::Analyst => 0.1,
-                LLMRole::Executor => 0.2,
-                LLMRole::Innovator => 0.7,
-                LLMRole::Validator => 0.1,
-            },
-            max_tokens: Some(2048),
-            system_prompt: format!("You are a {role} for the Oxide Pilot system."),
-        };
+
+    let providers = CollaborativeProviderFactory::create_from_config(&orchestrator_config)
+        .map_err(|e| format!("Invalid orchestrator configuration: {e}"))?;
+    for (name, provider, config) in providers {
         orchestrator.add_provider(name, provider, config);
     }
 
     // Execute collaborative task
     let task = "Analyze system performance and security, provide recommendations, and create an execution plan";
 
-    match orchestrator.execute_collaborative_task(task, context).await {
+    stage("Running collaborative analysis");
+    let request = orchestrator.execute_collaborative_task(task, context);
+    match llm_audit::with_purpose("collaborative_analysis", request).await {
         Ok(result) => {
             let response = serde_json::json!({
                 "success": true,
@@ -279,6 +380,78 @@ async fn run_collaborative_analysis(
     }
 }
 
+/// Constraints handed to collaborative analysis and, unless overridden, to
+/// the plan runner.
+fn default_plan_constraints() -> HashMap<String, serde_json::Value> {
+    HashMap::from([
+        ("max_execution_time".to_string(), json!(300)),
+        ("security_level".to_string(), json!("high")),
+        ("performance_impact".to_string(), json!("minimal")),
+    ])
+}
+
+/// Run an `execution_plan` from collaborative analysis as a `plan_execution`
+/// job, streaming step progress as `plan://progress` events. Destructive
+/// steps pause the run until their index is passed in `confirmed_steps`;
+/// resume with `start_step` set to the paused step. Cancelling the job skips
+/// the steps not yet started.
+#[tauri::command]
+async fn execute_plan(
+    plan: serde_json::Value,
+    constraints: Option<HashMap<String, serde_json::Value>>,
+    confirmed_steps: Option<Vec<usize>>,
+    start_step: Option<usize>,
+    session_id: Option<String>,
+    state: State<'_, AppState>,
+    app: tauri::AppHandle,
+) -> Result<oxide_copilot::plan_executor::PlanExecutionReport, String> {
+    use oxide_copilot::plan_executor::{parse_plan, PlanConstraints, PlanExecutor, PlanRunOptions};
+
+    let system_guard = state.oxide_system.read().await;
+    let Some(system) = system_guard.as_ref() else {
+        return Err(i18n::t("error.system_not_initialized").to_string());
+    };
+    let system_clone = system.clone();
+    drop(system_guard);
+    command_guard::authorize_command(&system_clone, session_id.as_deref(), "execute_plan").await?;
+
+    let constraints =
+        PlanConstraints::from_map(&constraints.unwrap_or_else(default_plan_constraints));
+    let notify = job_notifier(app.clone());
+    let report = state
+        .jobs
+        .run(
+            "plan_execution",
+            job_manager::CancelMode::Cooperative,
+            notify,
+            move |job| async move {
+                let registry = plan_actions::plan_registry(&system_clone);
+                let steps = parse_plan(&plan, &registry);
+                if steps.is_empty() {
+                    return Err("Execution plan has no steps".to_string());
+                }
+                let options = PlanRunOptions {
+                    confirmed_steps: confirmed_steps.unwrap_or_default().into_iter().collect(),
+                    start_step: start_step.unwrap_or(0),
+                    cancel: Some(job.cancel_flag()),
+                };
+                let report = PlanExecutor::new(&registry, constraints)
+                    .run(&steps, &options, |progress| {
+                        job.progress((progress.step + 1) as f32 / progress.total_steps as f32);
+                        events::emit(&app, &progress);
+                    })
+                    .await;
+                Ok(report)
+            },
+        )
+        .await?;
+    info!(
+        "Execution plan finished: {} completed, {} failed, awaiting confirmation: {:?}, cancelled: {}",
+        report.completed, report.failed, report.awaiting_confirmation, report.cancelled
+    );
+    Ok(report)
+}
+
 // Legacy multi-agent orchestration (kept for backward compatibility)
 #[tauri::command]
 async fn run_multi_agent_analysis(
@@ -286,41 +459,43 @@ async fn run_multi_agent_analysis(
     gemini_model: Option<String>,
     qwen_model: Option<String>,
 ) -> Result<String, String> {
-    let snapshot_val = get_system_snapshot(state).await?;
+    multi_agent_analysis(&state, gemini_model, qwen_model, &|_| {}).await
+}
+
+async fn multi_agent_analysis(
+    state: &AppState,
+    gemini_model: Option<String>,
+    qwen_model: Option<String>,
+    stage: &(dyn Fn(&str) + Send + Sync),
+) -> Result<String, String> {
+    stage("Collecting system snapshot");
+    let snapshot_val = collect_system_snapshot(state).await?;
     let snapshot_str =
         serde_json::to_string_pretty(&snapshot_val).unwrap_or_else(|_| snapshot_val.to_string());
 
     // Prompts for each agent
-    let gemini_prompt = format!(
-        "You are an expert OS performance and security analyst. Given this JSON snapshot, produce a concise analysis with:\n\
-        - Key performance issues and likely root causes\n\
-        - Suspicious processes or threats (if any)\n\
-        - Immediate remediation steps (bulleted)\n\
-        - Risk score (0-100) and confidence.\n\nSnapshot:\n{snapshot_str}"
-    );
-
-    let qwen_prompt = format!(
-        "Perform a deep technical analysis of this system snapshot focusing on:\n\
-        - Hot threads and blocking syscalls\n\
-        - Memory pressure, leaks, fragmentation indicators\n\
-        - Process anomalies (handles, CPU spikes, I/O)\n\
-        - Concrete remediation with commands and config changes.\n\nSnapshot:\n{snapshot_str}"
-    );
+    let vars = [("snapshot", snapshot_str.as_str())];
+    let gemini_prompt = prompts::render(prompts::SYSTEM_ANALYSIS, Some("gemini"), &vars);
+    let qwen_prompt = prompts::render(prompts::SYSTEM_ANALYSIS, Some("qwen"), &vars);
 
     use oxide_core::gemini_auth::GeminiAuth;
     let gauth = GeminiAuth::new();
     let _ = gauth.init_from_env().await; // best-effort API key init
 
     // Run both analyses concurrently
-    let (g_res, q_res) = tokio::join!(
-        async {
-            gauth
-                .send_message(&gemini_prompt, gemini_model.as_deref())
-                .await
-                .map_err(|e| e.to_string())
-        },
-        async { qwen_chat_completion(&qwen_prompt, qwen_model).await }
-    );
+    stage("Querying Gemini and Qwen");
+    let analyses = async {
+        tokio::join!(
+            async {
+                gauth
+                    .send_message(&gemini_prompt, gemini_model.as_deref())
+                    .await
+                    .map_err(|e| e.to_string())
+            },
+            async { qwen_chat_completion(&qwen_prompt, qwen_model).await }
+        )
+    };
+    let (g_res, q_res) = llm_audit::with_purpose("multi_agent_analysis", analyses).await;
 
     let result = serde_json::json!({
         "gemini_summary": g_res.as_deref().unwrap_or("Gemini analysis failed"),
@@ -356,28 +531,32 @@ async fn set_google_client_credentials(
 async fn authenticate_google_command(app: tauri::AppHandle) -> Result<String, String> {
     match google_auth::authenticate_google().await {
         Ok(token) => {
-            let _ = app.emit_all(
-                "google_auth_complete",
-                json!({
-                    "status": "success",
-                    "provider": "google",
-                    "timestamp": std::time::SystemTime::now()
-                        .duration_since(std::time::UNIX_EPOCH)
-                        .map(|d| d.as_secs())
-                        .unwrap_or(0),
-                }),
+            events::emit(
+                &app,
+                &events::GoogleAuthComplete {
+                    status: events::AuthStatus::Success,
+                    provider: "google",
+                    timestamp: Some(
+                        std::time::SystemTime::now()
+                            .duration_since(std::time::UNIX_EPOCH)
+                            .map(|d| d.as_secs())
+                            .unwrap_or(0),
+                    ),
+                    message: None,
+                },
             );
             Ok(token)
         }
         Err(e) => {
             error!("Google authentication failed: {e}");
-            let _ = app.emit_all(
-                "google_auth_complete",
-                json!({
-                    "status": "error",
-                    "provider": "google",
-                    "message": e.to_string(),
-                }),
+            events::emit(
+                &app,
+                &events::GoogleAuthComplete {
+                    status: events::AuthStatus::Error,
+                    provider: "google",
+                    timestamp: None,
+                    message: Some(e.to_string()),
+                },
             );
             Err(e.to_string())
         }
@@ -387,12 +566,15 @@ async fn authenticate_google_command(app: tauri::AppHandle) -> Result<String, St
 #[tauri::command]
 async fn initialize_system(
     config: OxidePilotConfig,
+    app: tauri::AppHandle,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
     info!("Initializing Oxide System...");
 
     #[cfg(feature = "surrealdb-metrics")]
-    let surreal_backend = state.surreal_backend.clone();
+    let surreal_backend = state.shared_backend.clone();
+    #[cfg(feature = "surrealdb-metrics")]
+    let jobs = Arc::clone(&state.jobs);
 
     // Use retry mechanism for system initialization
     let retry_config = RetryConfig {
@@ -407,11 +589,15 @@ async fn initialize_system(
             let config_clone = config.clone();
             #[cfg(feature = "surrealdb-metrics")]
             let surreal_backend_clone = surreal_backend.clone();
+            #[cfg(feature = "surrealdb-metrics")]
+            let jobs = Arc::clone(&jobs);
             Box::pin(async move {
                 let system = OxideSystem::new(
                     config_clone,
                     #[cfg(feature = "surrealdb-metrics")]
                     Some(surreal_backend_clone),
+                    #[cfg(feature = "surrealdb-metrics")]
+                    jobs,
                 )
                     .await
                     .map_err(OxideError::SystemInit)?;
@@ -425,9 +611,22 @@ async fn initialize_system(
 
     match result {
         Ok(system) => {
+            let local_api_enabled = config.local_api.as_ref().is_some_and(|c| c.enabled);
             let mut system_lock = state.oxide_system.write().await;
-            *system_lock = Some(system);
+            *system_lock = Some(system.clone());
+            drop(system_lock);
             info!("Oxide System initialized and started");
+            state
+                .hotkeys
+                .apply(&app, &config.hotkeys.clone().unwrap_or_default());
+            updates::spawn(app.clone(), system.clone());
+            forward_events(app.clone(), system.subscribe_task_restarts());
+            forward_events(app, system.subscribe_suggestions());
+            if local_api_enabled {
+                if let Err(e) = start_local_api(&state, system, None, None).await {
+                    warn!("Local API not started: {e}");
+                }
+            }
             Ok(())
         }
         Err(error) => {
@@ -445,12 +644,38 @@ async fn initialize_system(
 #[tauri::command]
 async fn handle_user_input_command(
     user_input: String,
+    session_id: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<String, String> {
+    // Slash commands run locally and never reach an LLM
+    match SlashCommand::parse(&user_input) {
+        // Usage errors are an answer, not a failure
+        Some(Err(usage)) => return Ok(usage),
+        Some(Ok(_)) => {
+            return run_slash_command(user_input, session_id, state)
+                .await
+                .map(|output| output.summary)
+        }
+        None => {}
+    }
+
+    // Offline mode: cloud LLMs are blocked, so answer with the local model only
+    if oxide_core::network::is_offline() {
+        return local_llm_chat(
+            None,
+            None,
+            None,
+            Some(i18n::t("prompt.assistant").to_string()),
+            user_input,
+        )
+        .await
+        .map_err(|e| format!("Offline mode is enabled and the local LLM is unavailable: {e}"));
+    }
+
     // First, try to use the collaborative LLM system if available
     if let Ok(collaborative_result) = run_collaborative_analysis(
         state.clone(),
-        user_input.clone(),
+        i18n::with_reply_directive(&user_input),
         Some("user_query".to_string()),
     )
     .await
@@ -503,16 +728,83 @@ async fn handle_user_input_command(
             }
         }
     } else {
-        let error = OxideError::SystemInit("System not initialized".to_string());
+        let error = OxideError::SystemInit(i18n::t("error.system_not_initialized").to_string());
         let response = ErrorHandler::handle_error_with_monitoring(error, None);
         Err(serde_json::to_string(&response).unwrap_or_else(|_| "Serialization error".to_string()))
     }
 }
 
+/// Ask the copilot about images: attachments from the UI, image files on
+/// disk and, optionally, a fresh screenshot of the primary screen.
+#[tauri::command]
+async fn handle_user_input_with_images(
+    user_input: String,
+    images: Option<Vec<ImageAttachment>>,
+    image_paths: Option<Vec<String>>,
+    capture_screen: Option<bool>,
+    session_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    if oxide_core::network::is_offline() {
+        return Err(i18n::t("error.images_offline").to_string());
+    }
+    let system_guard = state.oxide_system.read().await;
+    let Some(system) = system_guard.as_ref() else {
+        return Err(i18n::t("error.system_not_initialized").to_string());
+    };
+    let system_clone = system.clone();
+    drop(system_guard);
+    command_guard::authorize_command(
+        &system_clone,
+        session_id.as_deref(),
+        "handle_user_input_with_images",
+    )
+    .await?;
+
+    let mut attachments = images.unwrap_or_default();
+    for image in &attachments {
+        image.validate()?;
+    }
+    for path in image_paths.unwrap_or_default() {
+        attachments.push(ImageAttachment::from_file(&path)?);
+    }
+    if capture_screen.unwrap_or(false) {
+        attachments.push(system_clone.capture_screen_attachment().await?);
+    }
+    if attachments.is_empty() {
+        return Err(i18n::t("error.no_images").to_string());
+    }
+    system_clone
+        .handle_input_with_images(user_input, attachments)
+        .await
+}
+
+/// Parse and run a slash command such as `/status` or `/scan <path>`,
+/// returning the structured result.
+#[tauri::command]
+async fn run_slash_command(
+    input: String,
+    session_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<SlashCommandOutput, String> {
+    let command = SlashCommand::parse(&input)
+        .ok_or_else(|| "Slash commands start with '/'".to_string())??;
+    let system_guard = state.oxide_system.read().await;
+    if let Some(system) = system_guard.as_ref() {
+        let system_clone = system.clone();
+        drop(system_guard);
+        slash_commands::execute(&system_clone, command, session_id.as_deref()).await
+    } else {
+        Err(i18n::t("error.system_not_initialized").to_string())
+    }
+}
+
 #[tauri::command]
 async fn get_system_status(state: State<'_, AppState>) -> Result<SystemStatus, String> {
     let system = state.oxide_system.read().await;
-    let system = system.as_ref().ok_or("System not initialized")?;
+    let system = system
+        .as_ref()
+        .ok_or(i18n::t("error.system_not_initialized"))?;
     Ok(system.get_system_status())
 }
 
@@ -523,9 +815,20 @@ async fn scan_file_command(
     quarantine: bool,
     state: State<'_, AppState>,
 ) -> Result<FileScanReport, String> {
-    let system = state.oxide_system.read().await;
-    let system = system.as_ref().ok_or("System not initialized")?;
-    system.scan_file(path, use_cloud, quarantine).await
+    let system = state.oxide_system.read().await.clone();
+    let system = system.ok_or(i18n::t("error.system_not_initialized"))?;
+    let report = system.scan_file(path, use_cloud, quarantine).await?;
+    let verdict = if report.malicious {
+        "malicious"
+    } else {
+        "clean"
+    };
+    system.notify_webhooks(WebhookPayload::new(
+        WebhookEvent::ScanCompleted,
+        format!("Scanned {}: {verdict}", report.path),
+        serde_json::to_value(&report).unwrap_or_default(),
+    ));
+    Ok(report)
 }
 
 #[tauri::command]
@@ -533,273 +836,605 @@ async fn start_folder_scan(
     root: String,
     use_cloud: bool,
     quarantine: bool,
+    force_full_rescan: Option<bool>,
+    filter: Option<ScanFilterConfig>,
     state: State<'_, AppState>,
     app: tauri::AppHandle,
 ) -> Result<String, String> {
     // Ensure system exists
     let system_guard = state.oxide_system.read().await;
     let Some(system) = system_guard.as_ref() else {
-        return Err("System not initialized".to_string());
+        return Err(i18n::t("error.system_not_initialized").to_string());
     };
     let system_clone = system.clone();
     drop(system_guard);
 
     // Resolve config for limits
     let cfg = system_clone.get_config().await;
-    let max_workers = cfg.guardian.folder_scan_max_workers.unwrap_or(8).max(1);
+    // Fewer workers by default while another AV scans every opened file
+    let default_workers = system_clone
+        .av_coexistence()
+        .map_or(8, |av| av.folder_scan_workers(8));
+    let max_workers = cfg
+        .guardian
+        .folder_scan_max_workers
+        .unwrap_or(default_workers)
+        .max(1);
     let max_depth = cfg.guardian.folder_scan_max_depth.unwrap_or(usize::MAX);
     let max_file_size_bytes: Option<u64> = cfg.guardian.max_file_size_mb.map(|mb| mb * 1024 * 1024);
-
-    // Create cancel flag and scan id
-    let scan_id = uuid::Uuid::new_v4().to_string();
-    let cancel_flag = Arc::new(AtomicBool::new(false));
-    {
-        let mut cancels = state.folder_scan_cancels.write().await;
-        cancels.insert(scan_id.clone(), cancel_flag.clone());
-    }
-
-    let root_path = PathBuf::from(root.clone());
-    let app_clone = app.clone();
-    let state_clone = AppState {
-        oxide_system: state.oxide_system.clone(),
-        auth_manager: state.auth_manager.clone(),
-        mcp_server: state.mcp_server.clone(),
-        folder_scan_cancels: state.folder_scan_cancels.clone(),
-        rpa_state: state.rpa_state.clone(),
-        #[cfg(feature = "surrealdb-metrics")]
-        guardian_state: state.guardian_state.clone(),
-        #[cfg(feature = "surrealdb-metrics")]
-        surreal_backend: state.surreal_backend.clone(),
-        security_diagnostic_state: state.security_diagnostic_state.clone(),
+    let cache_ttl_hours = cfg.guardian.scan_cache_ttl_hours.unwrap_or(24);
+    let cache_enabled = cfg!(feature = "surrealdb-metrics") && cache_ttl_hours > 0;
+    // Cached clean verdicts only count if the rules have not changed since
+    #[cfg(feature = "surrealdb-metrics")]
+    let detection_version = system_clone.detection_version();
+    let force_full_rescan = force_full_rescan.unwrap_or(false);
+    // A filter passed with the request replaces the configured default
+    let scan_filter = match filter.or(cfg.guardian.scan_filter) {
+        Some(filter) if !filter.is_empty() => {
+            filter.validate()?;
+            Some(ScanFilter::new(&filter)?)
+        }
+        _ => None,
     };
 
-    // Clone scan_id for the async task
-    let scan_id_for_task = scan_id.clone();
+    let root_path = scan_path::extended_path(Path::new(&root));
+    let network_timeout =
+        std::time::Duration::from_secs(cfg.guardian.network_scan_timeout_secs.unwrap_or(60));
+    let app_clone = app.clone();
+    #[cfg(feature = "surrealdb-metrics")]
+    let surreal_backend = state.shared_backend.clone();
     let root_for_task = root.clone();
 
-    // Spawn background task
-    tokio::spawn(async move {
-        let start = Instant::now();
-        let _ = app_clone.emit_all(
-            "folder_scan_started",
-            serde_json::json!({
-                "scan_id": scan_id_for_task,
-                "root": root_for_task,
-            }),
-        );
+    // Run as a cooperative job so cancelled scans still report and persist
+    // what they scanned; the job id doubles as the scan id
+    let scan_id = state.jobs.spawn(
+        "folder_scan",
+        job_manager::CancelMode::Cooperative,
+        job_notifier(app),
+        move |job| async move {
+            let scan_id_for_task = job.id().to_string();
+            let cancel_flag = job.cancel_flag();
+            let start = Instant::now();
+            #[cfg(feature = "surrealdb-metrics")]
+            let started_at = chrono::Utc::now();
+
+            // Network shares: probe latency to size the worker pool, and time
+            // out listings and reads instead of hanging on a dead server
+            let network = scan_path::is_network_path(&root_path);
+            let (max_workers, latency, io_timeout) = if network {
+                let probe_root = root_path.clone();
+                let latency = tokio::time::timeout(
+                    network_timeout,
+                    tokio::task::spawn_blocking(move || scan_path::probe_latency(&probe_root)),
+                )
+                .await
+                .ok()
+                .and_then(|probe| probe.ok())
+                .flatten();
+                let workers =
+                    latency.map_or(max_workers, |l| scan_path::network_workers(max_workers, l));
+                (workers, latency, Some(network_timeout))
+            } else {
+                (max_workers, None, None)
+            };
+            events::emit(
+                &app_clone,
+                &events::FolderScanStarted {
+                    scan_id: scan_id_for_task.clone(),
+                    root: root_for_task.clone(),
+                    network,
+                    latency_ms: latency.map(|l| l.as_millis() as u64),
+                    workers: max_workers,
+                },
+            );
 
-        // Discover files breadth-first up to max_depth, respecting cancellation
-        let mut files: Vec<PathBuf> = Vec::new();
-        let mut q: VecDeque<(PathBuf, usize)> = VecDeque::new();
-        q.push_back((root_path.clone(), 0));
+            // Discover files breadth-first up to max_depth, respecting cancellation
+            job.stage("Discovering files");
+            let mut files: Vec<(PathBuf, Option<(u64, i64)>)> = Vec::new();
+            let mut filtered = 0usize;
+            let mut q: VecDeque<(PathBuf, usize)> = VecDeque::new();
+            q.push_back((root_path.clone(), 0));
 
-        while let Some((dir, depth)) = q.pop_front() {
-            if cancel_flag.load(Ordering::SeqCst) {
-                break;
-            }
-            match std::fs::read_dir(&dir) {
-                Ok(read_dir) => {
-                    for entry in read_dir.flatten() {
-                        if cancel_flag.load(Ordering::SeqCst) {
-                            break;
-                        }
-                        let path = entry.path();
-                        match entry.file_type() {
-                            Ok(ft) if ft.is_dir() => {
+            while let Some((dir, depth)) = q.pop_front() {
+                if cancel_flag.load(Ordering::SeqCst) {
+                    break;
+                }
+                let listing = {
+                    let dir = dir.clone();
+                    with_timeout(io_timeout, async move {
+                        tokio::task::spawn_blocking(move || list_dir(&dir))
+                            .await
+                            .map_err(|e| format!("read_dir task join error: {e}"))?
+                            .map_err(|e| e.to_string())
+                    })
+                    .await
+                };
+                match listing {
+                    Ok(entries) => {
+                        for (path, is_dir, meta) in entries {
+                            if cancel_flag.load(Ordering::SeqCst) {
+                                break;
+                            }
+                            if is_dir {
                                 if depth < max_depth {
                                     q.push_back((path, depth + 1));
                                 }
+                                continue;
                             }
-                            Ok(ft) if ft.is_file() => {
-                                // size filter
-                                if let Some(limit) = max_file_size_bytes {
-                                    if let Ok(meta) = entry.metadata() {
-                                        if meta.len() > limit {
-                                            continue;
-                                        }
-                                    }
+                            // size filter
+                            if let (Some(limit), Some(meta)) =
+                                (max_file_size_bytes, meta.as_ref())
+                            {
+                                if meta.len() > limit {
+                                    continue;
                                 }
-                                files.push(path);
                             }
-                            _ => {}
+                            if scan_filter.as_ref().is_some_and(|f| !f.allows(&path)) {
+                                filtered += 1;
+                                continue;
+                            }
+                            files.push((path, meta.as_ref().and_then(file_stamp)));
                         }
                     }
-                }
-                Err(e) => {
-                    let _ = app_clone.emit_all(
-                        "folder_scan_progress",
-                        serde_json::json!({
-                            "scan_id": scan_id_for_task,
-                            "error": format!("read_dir error at {}: {}", dir.display(), e),
-                        }),
-                    );
+                    Err(e) => {
+                        events::emit(
+                            &app_clone,
+                            &events::FolderScanProgress {
+                                scan_id: scan_id_for_task.clone(),
+                                error: Some(format!(
+                                    "read_dir error at {}: {}",
+                                    scan_path::display_path(&dir.to_string_lossy()),
+                                    e
+                                )),
+                                ..Default::default()
+                            },
+                        );
+                    }
                 }
             }
-        }
 
-        let total = files.len();
-        let _ = app_clone.emit_all(
-            "folder_scan_progress",
-            serde_json::json!({
-                "scan_id": scan_id_for_task,
-                "discovered": total,
-            }),
-        );
-
-        if cancel_flag.load(Ordering::SeqCst) {
-            let _ = app_clone.emit_all(
-                "folder_scan_cancelled",
-                serde_json::json!({
-                    "scan_id": scan_id_for_task,
-                    "scanned": 0,
-                    "total": total,
-                    "malicious": 0,
-                    "errors": 0,
-                    "duration_ms": start.elapsed().as_millis(),
-                }),
+            let total = files.len();
+            events::emit(
+                &app_clone,
+                &events::FolderScanProgress {
+                    scan_id: scan_id_for_task.clone(),
+                    discovered: Some(total),
+                    filtered: Some(filtered),
+                    ..Default::default()
+                },
             );
-            let mut cancels = state_clone.folder_scan_cancels.write().await;
-            cancels.remove(&scan_id_for_task);
-            return;
-        }
 
-        // Scan concurrently with a worker pool using mpsc
-        let (tx, rx) = mpsc::channel::<String>(std::cmp::max(1, total));
-        for path in files {
             if cancel_flag.load(Ordering::SeqCst) {
-                break;
+                let summary = events::FolderScanSummary {
+                    cancelled: true,
+                    scan_id: scan_id_for_task,
+                    scanned: 0,
+                    total,
+                    malicious: 0,
+                    errors: 0,
+                    duration_ms: start.elapsed().as_millis() as u64,
+                    filtered: None,
+                    bytes_scanned: None,
+                    worker_utilization: None,
+                    cache: None,
+                };
+                events::emit(&app_clone, &summary);
+                return serde_json::to_value(&summary).map_err(|e| e.to_string());
             }
-            let _ = tx.send(path.to_string_lossy().to_string()).await;
-        }
-        drop(tx);
-
-        let rx = Arc::new(Mutex::new(rx));
-        let scanned_c = Arc::new(std::sync::atomic::AtomicUsize::new(0));
-        let malicious_c = Arc::new(std::sync::atomic::AtomicUsize::new(0));
-        let errors_c = Arc::new(std::sync::atomic::AtomicUsize::new(0));
-
-        let mut handles = Vec::new();
-        for _ in 0..max_workers {
-            let rx = rx.clone();
-            let cancel_chk = cancel_flag.clone();
-            let app_emit = app_clone.clone();
-            let sys = system_clone.clone();
-            let scanned_c = scanned_c.clone();
-            let malicious_c = malicious_c.clone();
-            let errors_c = errors_c.clone();
-            let scan_id_cl = scan_id_for_task.clone();
-            handles.push(tokio::spawn(async move {
-                loop {
-                    if cancel_chk.load(Ordering::SeqCst) {
-                        break;
+
+            // Skip files verified clean within the TTL whose content and
+            // detection rules have not changed since
+            #[cfg(feature = "surrealdb-metrics")]
+            let mut cached_verdicts: Vec<oxide_memory::ScanFileVerdict> = Vec::new();
+            #[cfg(feature = "surrealdb-metrics")]
+            if cache_enabled && !force_full_rescan {
+                job.stage("Checking scan cache");
+                let paths: Vec<String> = files
+                    .iter()
+                    .map(|(path, _)| scan_path::display_path(&path.to_string_lossy()))
+                    .collect();
+                let fresh_since =
+                    chrono::Utc::now() - chrono::Duration::hours(cache_ttl_hours as i64);
+                let cached = match surreal_backend.get().await {
+                    Ok(backend) => backend.scan_cache_lookup(&paths, fresh_since).await,
+                    Err(e) => Err(e),
+                };
+                match cached {
+                    Ok(entries) => {
+                        let entries: HashMap<String, oxide_memory::ScanCacheEntry> = entries
+                            .into_iter()
+                            .map(|entry| (entry.path.clone(), entry))
+                            .collect();
+                        // Size and mtime only pick the candidates: an mtime
+                        // can be set back after patching a file in place, so
+                        // each one is re-hashed and must still hold the
+                        // content that was verified
+                        let candidates: Vec<(PathBuf, oxide_memory::ScanCacheEntry)> = files
+                            .iter()
+                            .filter_map(|(path, stamp)| {
+                                let key = scan_path::display_path(&path.to_string_lossy());
+                                let (size, mtime_ms) = (*stamp)?;
+                                entries
+                                    .get(&key)
+                                    .filter(|entry| {
+                                        entry.matches(
+                                            size,
+                                            mtime_ms,
+                                            use_cloud,
+                                            &detection_version,
+                                        )
+                                    })
+                                    .map(|entry| (path.clone(), entry.clone()))
+                            })
+                            .collect();
+                        let unchanged: HashMap<String, String> =
+                            tokio::task::spawn_blocking(move || {
+                                candidates
+                                    .into_iter()
+                                    .filter(|(path, entry)| {
+                                        FileScanner::compute_hashes(path).is_ok_and(
+                                            |(hashes, _)| entry.content_matches(&hashes.sha256),
+                                        )
+                                    })
+                                    .map(|(_, entry)| (entry.path, entry.sha256))
+                                    .collect()
+                            })
+                            .await
+                            .unwrap_or_default();
+                        files.retain(|(path, _)| {
+                            let key = scan_path::display_path(&path.to_string_lossy());
+                            match unchanged.get(&key) {
+                                Some(sha256) => {
+                                    cached_verdicts.push(oxide_memory::ScanFileVerdict {
+                                        path: key,
+                                        sha256: Some(sha256.clone()),
+                                        malicious: false,
+                                        local_match: None,
+                                        error: None,
+                                    });
+                                    false
+                                }
+                                None => true,
+                            }
+                        });
                     }
-                    let next = {
-                        let mut guard = rx.lock().await;
-                        guard.recv().await
-                    };
-                    let Some(path_str) = next else {
-                        break;
+                    Err(e) => warn!("Scan cache unavailable, scanning every file: {e:#}"),
+                }
+            }
+            #[cfg(feature = "surrealdb-metrics")]
+            let stamps: HashMap<String, (u64, i64)> = files
+                .iter()
+                .filter_map(|(path, stamp)| {
+                    stamp.map(|stamp| (scan_path::display_path(&path.to_string_lossy()), stamp))
+                })
+                .collect();
+            let cache_hits = total - files.len();
+            if cache_hits > 0 {
+                job.progress(cache_hits as f32 / total as f32);
+                events::emit(
+                    &app_clone,
+                    &events::FolderScanProgress {
+                        scan_id: scan_id_for_task.clone(),
+                        scanned: Some(cache_hits),
+                        total: Some(total),
+                        cache_hits: Some(cache_hits),
+                        ..Default::default()
+                    },
+                );
+            }
+            job.stage("Scanning");
+
+            // Rates and ETA cover the files actually scanned, not cache hits
+            let bytes_total: u64 = files.iter().filter_map(|(_, stamp)| stamp.map(|s| s.0)).sum();
+            let estimator = Arc::new(std::sync::Mutex::new(ScanRateEstimator::new(
+                files.len() as u64,
+                bytes_total,
+                DEFAULT_RATE_WINDOW,
+            )));
+            let utilization = Arc::new(WorkerUtilization::new(max_workers));
+            let bytes_c = Arc::new(std::sync::atomic::AtomicU64::new(0));
+
+            // Scan concurrently with a worker pool using mpsc
+            let (tx, rx) = mpsc::channel::<(String, u64)>(std::cmp::max(1, total));
+            for (path, stamp) in files {
+                if cancel_flag.load(Ordering::SeqCst) {
+                    break;
+                }
+                let size = stamp.map(|s| s.0).unwrap_or(0);
+                let _ = tx.send((path.to_string_lossy().to_string(), size)).await;
+            }
+            drop(tx);
+
+            let rx = Arc::new(Mutex::new(rx));
+            let scanned_c = Arc::new(std::sync::atomic::AtomicUsize::new(cache_hits));
+            let malicious_c = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+            let errors_c = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+            // Per-file verdicts for the scan history
+            #[cfg(feature = "surrealdb-metrics")]
+            let verdicts = Arc::new(std::sync::Mutex::new(Vec::with_capacity(total)));
+
+            let mut handles = Vec::new();
+            for worker in 0..max_workers {
+                let rx = rx.clone();
+                let cancel_chk = cancel_flag.clone();
+                let app_emit = app_clone.clone();
+                let sys = system_clone.clone();
+                let scanned_c = scanned_c.clone();
+                let malicious_c = malicious_c.clone();
+                let errors_c = errors_c.clone();
+                let scan_id_cl = scan_id_for_task.clone();
+                let job = job.clone();
+                let estimator = estimator.clone();
+                let utilization = utilization.clone();
+                let bytes_c = bytes_c.clone();
+                #[cfg(feature = "surrealdb-metrics")]
+                let verdicts = verdicts.clone();
+                handles.push(tokio::spawn(async move {
+                    let observe = |scanned: usize, size: u64| {
+                        let bytes = bytes_c.fetch_add(size, Ordering::SeqCst) + size;
+                        estimator.lock().ok().map(|mut estimator| {
+                            estimator.observe(Instant::now(), (scanned - cache_hits) as u64, bytes)
+                        })
                     };
-                    if cancel_chk.load(Ordering::SeqCst) {
-                        break;
-                    }
+                    loop {
+                        if cancel_chk.load(Ordering::SeqCst) {
+                            break;
+                        }
+                        let next = {
+                            let mut guard = rx.lock().await;
+                            guard.recv().await
+                        };
+                        let Some((path_str, size)) = next else {
+                            break;
+                        };
+                        if cancel_chk.load(Ordering::SeqCst) {
+                            break;
+                        }
 
-                    let res = sys.scan_file(path_str.clone(), use_cloud, quarantine).await;
-                    match res {
-                        Ok(report) => {
-                            let s = scanned_c.fetch_add(1, Ordering::SeqCst) + 1;
-                            if report.malicious {
-                                malicious_c.fetch_add(1, Ordering::SeqCst);
+                        let shown = scan_path::display_path(&path_str);
+                        let busy_since = Instant::now();
+                        let res = with_timeout(
+                            io_timeout,
+                            sys.scan_file(path_str.clone(), use_cloud, quarantine),
+                        )
+                        .await;
+                        utilization.record(worker, busy_since.elapsed());
+                        #[cfg(feature = "surrealdb-metrics")]
+                        if let Ok(mut verdicts) = verdicts.lock() {
+                            verdicts.push(match &res {
+                                Ok(report) => oxide_memory::ScanFileVerdict {
+                                    path: shown.clone(),
+                                    sha256: Some(report.hashes.sha256.clone()),
+                                    malicious: report.malicious,
+                                    local_match: report.local_match.clone(),
+                                    error: None,
+                                },
+                                Err(err) => oxide_memory::ScanFileVerdict {
+                                    path: shown.clone(),
+                                    sha256: None,
+                                    malicious: false,
+                                    local_match: None,
+                                    error: Some(err.clone()),
+                                },
+                            });
+                        }
+                        match res {
+                            Ok(report) => {
+                                let s = scanned_c.fetch_add(1, Ordering::SeqCst) + 1;
+                                job.progress(s as f32 / total as f32);
+                                let rate = observe(s, size);
+                                if report.malicious {
+                                    malicious_c.fetch_add(1, Ordering::SeqCst);
+                                }
+                                let m = malicious_c.load(Ordering::SeqCst);
+                                let e = errors_c.load(Ordering::SeqCst);
+                                events::emit(
+                                    &app_emit,
+                                    &events::FolderScanProgress {
+                                        scan_id: scan_id_cl.clone(),
+                                        scanned: Some(s),
+                                        total: Some(total),
+                                        malicious: Some(m),
+                                        errors: Some(e),
+                                        current_file: Some(shown),
+                                        local_match: report.local_match,
+                                        external_verdict: report.external_verdict,
+                                        rate,
+                                        worker_utilization: Some(utilization.snapshot()),
+                                        ..Default::default()
+                                    },
+                                );
                             }
-                            let m = malicious_c.load(Ordering::SeqCst);
-                            let e = errors_c.load(Ordering::SeqCst);
-                            let _ = app_emit.emit_all(
-                                "folder_scan_progress",
-                                serde_json::json!({
-                                    "scan_id": scan_id_cl,
-                                    "scanned": s,
-                                    "total": total,
-                                    "malicious": m,
-                                    "errors": e,
-                                    "current_file": path_str,
-                                    "local_match": report.local_match,
-                                    "external_verdict": report.external_verdict,
-                                }),
-                            );
+                            Err(err) => {
+                                let s = scanned_c.fetch_add(1, Ordering::SeqCst) + 1;
+                                job.progress(s as f32 / total as f32);
+                                let rate = observe(s, size);
+                                let e = errors_c.fetch_add(1, Ordering::SeqCst) + 1;
+                                let m = malicious_c.load(Ordering::SeqCst);
+                                events::emit(
+                                    &app_emit,
+                                    &events::FolderScanProgress {
+                                        scan_id: scan_id_cl.clone(),
+                                        scanned: Some(s),
+                                        total: Some(total),
+                                        malicious: Some(m),
+                                        errors: Some(e),
+                                        current_file: Some(shown),
+                                        error: Some(err),
+                                        rate,
+                                        worker_utilization: Some(utilization.snapshot()),
+                                        ..Default::default()
+                                    },
+                                );
+                            }
+                        }
+                    }
+                }));
+            }
+
+            for h in handles {
+                let _ = h.await;
+            }
+
+            let scanned = scanned_c.load(Ordering::SeqCst);
+            let malicious = malicious_c.load(Ordering::SeqCst);
+            let errors = errors_c.load(Ordering::SeqCst);
+
+            #[cfg(feature = "surrealdb-metrics")]
+            {
+                let summary = oxide_memory::ScanRunSummary {
+                    id: scan_id_for_task.clone(),
+                    root: root_for_task.clone(),
+                    started_at,
+                    finished_at: chrono::Utc::now(),
+                    status: if cancel_flag.load(Ordering::SeqCst) {
+                        "cancelled".to_string()
+                    } else {
+                        "completed".to_string()
+                    },
+                    total: total as u64,
+                    scanned: scanned as u64,
+                    malicious: malicious as u64,
+                    errors: errors as u64,
+                    duration_ms: start.elapsed().as_millis() as u64,
+                };
+                let mut verdicts = verdicts
+                    .lock()
+                    .map(|mut v| std::mem::take(&mut *v))
+                    .unwrap_or_default();
+
+                // Cache fresh clean verdicts; forget files found malicious
+                let verified_at = chrono::Utc::now();
+                let mut clean = Vec::new();
+                let mut stale = Vec::new();
+                for v in &verdicts {
+                    match (v.malicious, &v.sha256, stamps.get(&v.path)) {
+                        (true, _, _) => stale.push(v.path.clone()),
+                        (false, Some(sha256), Some(&(size, mtime_ms))) if v.error.is_none() => {
+                            clean.push(oxide_memory::ScanCacheEntry {
+                                path: v.path.clone(),
+                                size,
+                                mtime_ms,
+                                sha256: sha256.clone(),
+                                cloud_verified: use_cloud,
+                                detection_version: detection_version.clone(),
+                                verified_at,
+                            })
                         }
-                        Err(err) => {
-                            let s = scanned_c.fetch_add(1, Ordering::SeqCst) + 1;
-                            let e = errors_c.fetch_add(1, Ordering::SeqCst) + 1;
-                            let m = malicious_c.load(Ordering::SeqCst);
-                            let _ = app_emit.emit_all(
-                                "folder_scan_progress",
-                                serde_json::json!({
-                                    "scan_id": scan_id_cl,
-                                    "scanned": s,
-                                    "total": total,
-                                    "malicious": m,
-                                    "errors": e,
-                                    "current_file": path_str,
-                                    "error": err,
-                                }),
-                            );
+                        _ => {}
+                    }
+                }
+                verdicts.extend(cached_verdicts);
+
+                let persisted = match surreal_backend.get().await {
+                    Ok(backend) => {
+                        if cache_enabled {
+                            let expired_before =
+                                verified_at - chrono::Duration::hours(cache_ttl_hours as i64);
+                            let refreshed = match backend.update_scan_cache(clean, stale).await {
+                                Ok(()) => {
+                                    backend
+                                        .prune_scan_cache(expired_before, &detection_version)
+                                        .await
+                                }
+                                Err(e) => Err(e),
+                            };
+                            if let Err(e) = refreshed {
+                                warn!("Failed to update scan cache for {}: {e:#}", summary.id);
+                            }
                         }
+                        backend.insert_scan_run(&summary, verdicts).await
                     }
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = persisted {
+                    warn!("Failed to persist scan history for {}: {e:#}", summary.id);
                 }
-            }));
-        }
-
-        for h in handles {
-            let _ = h.await;
-        }
+                system_clone.record_fleet_scan(&summary);
+            }
 
-        let scanned = scanned_c.load(Ordering::SeqCst);
-        let malicious = malicious_c.load(Ordering::SeqCst);
-        let errors = errors_c.load(Ordering::SeqCst);
-
-        // Emit final event
-        if cancel_flag.load(Ordering::SeqCst) {
-            let _ = app_clone.emit_all(
-                "folder_scan_cancelled",
-                serde_json::json!({
-                    "scan_id": scan_id_for_task,
-                    "scanned": scanned,
-                    "total": total,
-                    "malicious": malicious,
-                    "errors": errors,
-                    "duration_ms": start.elapsed().as_millis(),
-                }),
-            );
-        } else {
-            let _ = app_clone.emit_all(
-                "folder_scan_completed",
-                serde_json::json!({
-                    "scan_id": scan_id_for_task,
-                    "scanned": scanned,
-                    "total": total,
-                    "malicious": malicious,
-                    "errors": errors,
-                    "duration_ms": start.elapsed().as_millis(),
+            // Emit final event
+            let summary = events::FolderScanSummary {
+                cancelled: cancel_flag.load(Ordering::SeqCst),
+                scan_id: scan_id_for_task,
+                scanned,
+                total,
+                malicious,
+                errors,
+                duration_ms: start.elapsed().as_millis() as u64,
+                filtered: Some(filtered),
+                bytes_scanned: Some(bytes_c.load(Ordering::SeqCst)),
+                worker_utilization: Some(utilization.snapshot()),
+                cache: Some(events::ScanCacheStats {
+                    enabled: cache_enabled,
+                    force_full_rescan,
+                    hits: cache_hits,
+                    misses: total - cache_hits,
                 }),
-            );
+            };
+            events::emit(&app_clone, &summary);
+            let outcome = if summary.cancelled {
+                "cancelled"
+            } else {
+                "completed"
+            };
+            let summary = serde_json::to_value(&summary).map_err(|e| e.to_string())?;
+            system_clone.notify_webhooks(WebhookPayload::new(
+                WebhookEvent::ScanCompleted,
+                format!(
+                    "Folder scan of {root_for_task} {outcome}: {malicious} malicious of {scanned} files, {errors} errors"
+                ),
+                summary.clone(),
+            ));
+            Ok(summary)
+        },
+    );
+
+    Ok(scan_id)
+}
+
+/// Directories and regular files directly under `dir`, with file metadata.
+fn list_dir(dir: &Path) -> std::io::Result<Vec<(PathBuf, bool, Option<std::fs::Metadata>)>> {
+    let mut entries = Vec::new();
+    for entry in std::fs::read_dir(dir)?.flatten() {
+        match entry.file_type() {
+            Ok(ft) if ft.is_dir() => entries.push((entry.path(), true, None)),
+            Ok(ft) if ft.is_file() => entries.push((entry.path(), false, entry.metadata().ok())),
+            _ => {}
         }
+    }
+    Ok(entries)
+}
 
-        // Cleanup cancel flag
-        let mut cancels = state_clone.folder_scan_cancels.write().await;
-        cancels.remove(&scan_id_for_task);
-    });
+/// Await `fut`, failing once `timeout` elapses when one is set.
+async fn with_timeout<T>(
+    timeout: Option<std::time::Duration>,
+    fut: impl std::future::Future<Output = Result<T, String>>,
+) -> Result<T, String> {
+    match timeout {
+        Some(limit) => tokio::time::timeout(limit, fut)
+            .await
+            .unwrap_or_else(|_| Err(format!("Timed out after {}s", limit.as_secs()))),
+        None => fut.await,
+    }
+}
 
-    Ok(scan_id)
+/// Size and modification time (ms since the Unix epoch) used to tell
+/// whether a cached scan verdict still applies.
+fn file_stamp(meta: &std::fs::Metadata) -> Option<(u64, i64)> {
+    let mtime = meta
+        .modified()
+        .ok()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?;
+    Some((meta.len(), mtime.as_millis() as i64))
 }
 
 #[tauri::command]
 async fn cancel_folder_scan(scan_id: String, state: State<'_, AppState>) -> Result<(), String> {
-    let cancels = state.folder_scan_cancels.write().await;
-    if let Some(flag) = cancels.get(&scan_id) {
-        flag.store(true, Ordering::SeqCst);
-        Ok(())
-    } else {
-        Err("Unknown scan_id".to_string())
+    match state.jobs.get(&scan_id) {
+        Some(job) if job.kind == "folder_scan" => state.jobs.cancel(&scan_id).map(|_| ()),
+        _ => Err("Unknown scan_id".to_string()),
     }
 }
 
@@ -812,7 +1447,7 @@ async fn is_virustotal_configured(state: State<'_, AppState>) -> Result<bool, St
         drop(system_guard);
         Ok(system_clone.has_virustotal_key().await)
     } else {
-        Err("System not initialized".to_string())
+        Err(i18n::t("error.system_not_initialized").to_string())
     }
 }
 
@@ -822,7 +1457,7 @@ async fn get_threat_history(state: State<'_, AppState>) -> Result<Vec<ThreatEven
     if let Some(system) = system_guard.as_ref() {
         Ok(system.get_threat_history())
     } else {
-        Err("System not initialized".to_string())
+        Err(i18n::t("error.system_not_initialized").to_string())
     }
 }
 
@@ -835,101 +1470,471 @@ async fn get_memory_stats(state: State<'_, AppState>) -> Result<MemoryStats, Str
         drop(system_guard); // Explicitly drop the guard
         Ok(system_clone.get_memory_stats().await)
     } else {
-        Err("System not initialized".to_string())
+        Err(i18n::t("error.system_not_initialized").to_string())
     }
 }
 
 #[tauri::command]
-async fn update_system_config(
-    config: OxidePilotConfig,
+async fn get_user_patterns(
+    session_id: Option<String>,
     state: State<'_, AppState>,
-) -> Result<(), String> {
+) -> Result<Vec<UserPattern>, String> {
     let system_guard = state.oxide_system.read().await;
     if let Some(system) = system_guard.as_ref() {
-        // Clone the system reference to avoid holding the lock across await
         let system_clone = system.clone();
-        drop(system_guard); // Explicitly drop the guard
-        system_clone.update_config(config).await
+        drop(system_guard);
+        command_guard::authorize_command(&system_clone, session_id.as_deref(), "get_user_patterns")
+            .await?;
+        Ok(system_clone.get_user_patterns().await)
     } else {
-        Err("System not initialized".to_string())
+        Err(i18n::t("error.system_not_initialized").to_string())
     }
 }
 
+/// Push newly queued suggestions to the UI as `suggestions://new` events.
+fn forward_events<E>(app: tauri::AppHandle, mut receiver: broadcast::Receiver<E>)
+where
+    E: events::AppEvent + Clone + Send + 'static,
+{
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => {
+                    events::emit(&app, &event);
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
 #[tauri::command]
-async fn get_system_config(state: State<'_, AppState>) -> Result<OxidePilotConfig, String> {
+async fn get_suggestions(state: State<'_, AppState>) -> Result<Vec<Suggestion>, String> {
     let system_guard = state.oxide_system.read().await;
     if let Some(system) = system_guard.as_ref() {
-        // Clone the system reference to avoid holding the lock across await
         let system_clone = system.clone();
-        drop(system_guard); // Explicitly drop the guard
-        Ok(system_clone.get_config().await)
+        drop(system_guard);
+        Ok(system_clone.pending_suggestions().await)
     } else {
-        Err("System not initialized".to_string())
+        Err(i18n::t("error.system_not_initialized").to_string())
     }
 }
 
+/// Record acceptance; the UI then sends the suggestion's prompt to the chat.
 #[tauri::command]
-async fn record_audio(duration_secs: f32, state: State<'_, AppState>) -> Result<Vec<u8>, String> {
+async fn accept_suggestion(
+    suggestion_id: String,
+    state: State<'_, AppState>,
+) -> Result<Suggestion, String> {
     let system_guard = state.oxide_system.read().await;
     if let Some(system) = system_guard.as_ref() {
-        // Clone the system reference to avoid holding the lock across await
         let system_clone = system.clone();
-        drop(system_guard); // Explicitly drop the guard
-        system_clone.record_audio(duration_secs).await
+        drop(system_guard);
+        system_clone
+            .respond_to_suggestion(&suggestion_id, SuggestionResponse::Accepted)
+            .await
     } else {
-        Err("System not initialized".to_string())
+        Err(i18n::t("error.system_not_initialized").to_string())
     }
 }
 
 #[tauri::command]
-async fn play_audio(audio_data: Vec<u8>, state: State<'_, AppState>) -> Result<(), String> {
+async fn dismiss_suggestion(
+    suggestion_id: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
     let system_guard = state.oxide_system.read().await;
     if let Some(system) = system_guard.as_ref() {
-        // Clone the system reference to avoid holding the lock across await
         let system_clone = system.clone();
-        drop(system_guard); // Explicitly drop the guard
-        system_clone.play_audio(&audio_data).await
+        drop(system_guard);
+        system_clone
+            .respond_to_suggestion(&suggestion_id, SuggestionResponse::Dismissed)
+            .await
+            .map(|_| ())
     } else {
-        Err("System not initialized".to_string())
+        Err(i18n::t("error.system_not_initialized").to_string())
     }
 }
 
+/// Threat detections per MITRE ATT&CK tactic over the last `days` (30 by
+/// default).
 #[tauri::command]
-async fn get_audio_devices(
+async fn get_attack_matrix_summary(
+    days: Option<u32>,
     state: State<'_, AppState>,
-) -> Result<(Vec<String>, Vec<String>), String> {
+) -> Result<AttackMatrixSummary, String> {
     let system_guard = state.oxide_system.read().await;
     if let Some(system) = system_guard.as_ref() {
-        // Clone the system reference to avoid holding the lock across await
         let system_clone = system.clone();
-        drop(system_guard); // Explicitly drop the guard
-        Ok(system_clone.get_audio_devices().await)
+        drop(system_guard);
+        system_clone
+            .attack_matrix_summary(days.unwrap_or(30).clamp(1, 365))
+            .await
     } else {
-        Err("System not initialized".to_string())
+        Err(i18n::t("error.system_not_initialized").to_string())
     }
 }
 
 #[tauri::command]
-async fn get_input_volume(state: State<'_, AppState>) -> Result<f32, String> {
-    let system_guard = state.oxide_system.read().await;
-    if let Some(system) = system_guard.as_ref() {
-        // Clone the system reference to avoid holding the lock across await
-        let system_clone = system.clone();
+async fn update_system_config(
+    config: OxidePilotConfig,
+    session_id: Option<String>,
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let system_guard = state.oxide_system.read().await;
+    if let Some(system) = system_guard.as_ref() {
+        // Clone the system reference to avoid holding the lock across await
+        let system_clone = system.clone();
+        drop(system_guard); // Explicitly drop the guard
+        command_guard::authorize_command(
+            &system_clone,
+            session_id.as_deref(),
+            "update_system_config",
+        )
+        .await?;
+        let hotkeys = config.hotkeys.clone().unwrap_or_default();
+        system_clone.update_config(config).await?;
+        state.hotkeys.apply(&app, &hotkeys);
+        Ok(())
+    } else {
+        Err(i18n::t("error.system_not_initialized").to_string())
+    }
+}
+
+/// Version and names of the events the backend emits.
+#[tauri::command]
+fn get_event_schema() -> events::EventSchema {
+    events::schema()
+}
+
+/// Which optional features work, so the UI can hide the ones that do not.
+#[tauri::command]
+fn get_capabilities() -> BTreeMap<Feature, Capability> {
+    capabilities::snapshot()
+}
+
+/// Configured global hotkeys and whether each one is registered.
+#[tauri::command]
+async fn get_hotkeys(state: State<'_, AppState>) -> Result<Vec<HotkeyStatus>, String> {
+    Ok(state.hotkeys.statuses())
+}
+
+/// Change the global hotkeys. Conflicting bindings are rejected before
+/// anything is re-registered.
+#[tauri::command]
+async fn update_hotkeys(
+    hotkeys: HotkeyConfig,
+    session_id: Option<String>,
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<HotkeyStatus>, String> {
+    let system_guard = state.oxide_system.read().await;
+    if let Some(system) = system_guard.as_ref() {
+        let system_clone = system.clone();
+        drop(system_guard);
+        command_guard::authorize_command(&system_clone, session_id.as_deref(), "update_hotkeys")
+            .await?;
+        let mut config = system_clone.get_config().await;
+        config.hotkeys = Some(hotkeys.clone());
+        system_clone.update_config(config).await?;
+        Ok(state.hotkeys.apply(&app, &hotkeys))
+    } else {
+        Err(i18n::t("error.system_not_initialized").to_string())
+    }
+}
+
+/// Halt RPA and cancel running jobs, as the panic-stop hotkey does.
+#[tauri::command]
+async fn panic_stop(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<PanicStopReport, String> {
+    Ok(hotkey_manager::panic_stop(&app, &state).await)
+}
+
+#[tauri::command]
+async fn set_offline_mode(
+    enabled: bool,
+    session_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<NetworkStatus, String> {
+    let system_guard = state.oxide_system.read().await;
+    if let Some(system) = system_guard.as_ref() {
+        let system_clone = system.clone();
+        drop(system_guard);
+        command_guard::authorize_command(&system_clone, session_id.as_deref(), "set_offline_mode")
+            .await?;
+        Ok(system_clone.set_offline_mode(enabled).await)
+    } else {
+        Err(i18n::t("error.system_not_initialized").to_string())
+    }
+}
+
+/// Active message language, the OS language and the available catalogs.
+#[tauri::command]
+async fn get_locale() -> Result<LocaleInfo, String> {
+    Ok(i18n::info())
+}
+
+#[tauri::command]
+async fn set_locale(
+    locale: String,
+    session_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<LocaleInfo, String> {
+    let locale = locale.parse::<i18n::Locale>()?;
+    let system_guard = state.oxide_system.read().await;
+    if let Some(system) = system_guard.as_ref() {
+        let system_clone = system.clone();
+        drop(system_guard);
+        command_guard::authorize_command(&system_clone, session_id.as_deref(), "set_locale")
+            .await?;
+        Ok(system_clone.set_locale(locale).await)
+    } else {
+        // Before initialization there is no config to keep it in
+        i18n::set_locale(locale);
+        Ok(i18n::info())
+    }
+}
+
+/// Speech recognition and synthesis languages, and the last one detected.
+#[tauri::command]
+async fn get_voice_language(state: State<'_, AppState>) -> Result<VoiceLanguageStatus, String> {
+    let system = state
+        .oxide_system
+        .read()
+        .await
+        .clone()
+        .ok_or(i18n::t("error.system_not_initialized"))?;
+    Ok(system.voice_language_status())
+}
+
+#[tauri::command]
+async fn set_voice_language(
+    language: String,
+    auto_detect: Option<bool>,
+    session_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<VoiceLanguageStatus, String> {
+    let system = state
+        .oxide_system
+        .read()
+        .await
+        .clone()
+        .ok_or(i18n::t("error.system_not_initialized"))?;
+    command_guard::authorize_command(&system, session_id.as_deref(), "set_voice_language").await?;
+    system.set_voice_language(&language, auto_detect).await
+}
+
+/// Effective LLM prompt templates, built-in or overridden.
+#[tauri::command]
+async fn list_prompt_templates() -> Result<Vec<PromptTemplate>, String> {
+    Ok(prompts::list())
+}
+
+/// Re-read the prompt template files without waiting for the watcher.
+#[tauri::command]
+async fn reload_prompt_templates(
+    session_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<PromptTemplate>, String> {
+    if let Some(system) = state.oxide_system.read().await.clone() {
+        command_guard::authorize_command(&system, session_id.as_deref(), "reload_prompt_templates")
+            .await?;
+    }
+    prompts::reload()
+}
+
+#[tauri::command]
+async fn get_network_status() -> Result<NetworkStatus, String> {
+    Ok(oxide_core::network::status())
+}
+
+#[tauri::command]
+async fn get_api_quota_status() -> Result<Vec<ApiQuotaStatus>, String> {
+    Ok(oxide_core::api_governor::status())
+}
+
+#[tauri::command]
+async fn get_llm_cache_stats() -> Result<CacheStats, String> {
+    Ok(oxide_copilot::response_cache::global().stats())
+}
+
+#[tauri::command]
+async fn clear_llm_cache() -> Result<(), String> {
+    oxide_copilot::response_cache::global().clear();
+    Ok(())
+}
+
+#[derive(serde::Serialize)]
+struct ProfilesInfo {
+    active: String,
+    profiles: Vec<String>,
+}
+
+#[tauri::command]
+async fn list_profiles(state: State<'_, AppState>) -> Result<ProfilesInfo, String> {
+    let system = state.oxide_system.read().await.clone();
+    let active = match system {
+        Some(system) => system.active_profile().await,
+        None => profile::resolve(None, Path::new(profile::DATA_ROOT)),
+    };
+    let mut profiles = profile::list_profiles(Path::new(profile::MEMORY_ROOT));
+    if !profiles.contains(&active.name) {
+        profiles.push(active.name.clone());
+        profiles.sort();
+    }
+    Ok(ProfilesInfo {
+        active: active.name,
+        profiles,
+    })
+}
+
+/// Make `name` the active profile, creating it on first use, and remember
+/// the choice across restarts.
+#[tauri::command]
+async fn switch_profile(
+    name: String,
+    session_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<ProfilesInfo, String> {
+    let target = profile::Profile::new(&name).map_err(|e| e.to_string())?;
+    let system = state.oxide_system.read().await.clone();
+    match system {
+        Some(system) => {
+            command_guard::authorize_command(&system, session_id.as_deref(), "switch_profile")
+                .await?;
+            system.switch_profile(target.clone()).await?;
+        }
+        None => {
+            // Not initialized yet: OxideSystem::new picks the persisted profile
+            #[cfg(feature = "surrealdb-metrics")]
+            state
+                .surreal_backend()
+                .await?
+                .use_namespace(&target.surreal_namespace())
+                .await
+                .map_err(|e| format!("Failed to switch database namespace: {e:#}"))?;
+        }
+    }
+    profile::persist_active(Path::new(profile::DATA_ROOT), &target).map_err(|e| e.to_string())?;
+    list_profiles(state).await
+}
+
+/// Field-level errors and warnings for a config the settings UI is about to
+/// save; nothing is applied.
+#[tauri::command]
+async fn validate_config(config: OxidePilotConfig) -> Result<Vec<ConfigIssue>, String> {
+    Ok(config.validate_fields())
+}
+
+#[tauri::command]
+async fn get_system_config(state: State<'_, AppState>) -> Result<OxidePilotConfig, String> {
+    let system_guard = state.oxide_system.read().await;
+    if let Some(system) = system_guard.as_ref() {
+        // Clone the system reference to avoid holding the lock across await
+        let system_clone = system.clone();
+        drop(system_guard); // Explicitly drop the guard
+        Ok(system_clone.get_config().await)
+    } else {
+        Err(i18n::t("error.system_not_initialized").to_string())
+    }
+}
+
+#[tauri::command]
+async fn record_audio(duration_secs: f32, state: State<'_, AppState>) -> Result<Vec<u8>, String> {
+    let system_guard = state.oxide_system.read().await;
+    if let Some(system) = system_guard.as_ref() {
+        // Clone the system reference to avoid holding the lock across await
+        let system_clone = system.clone();
+        drop(system_guard); // Explicitly drop the guard
+        system_clone.record_audio(duration_secs).await
+    } else {
+        Err(i18n::t("error.system_not_initialized").to_string())
+    }
+}
+
+#[tauri::command]
+async fn play_audio(audio_data: Vec<u8>, state: State<'_, AppState>) -> Result<(), String> {
+    let system_guard = state.oxide_system.read().await;
+    if let Some(system) = system_guard.as_ref() {
+        // Clone the system reference to avoid holding the lock across await
+        let system_clone = system.clone();
+        drop(system_guard); // Explicitly drop the guard
+        system_clone.play_audio(&audio_data).await
+    } else {
+        Err(i18n::t("error.system_not_initialized").to_string())
+    }
+}
+
+#[tauri::command]
+async fn get_audio_devices(
+    state: State<'_, AppState>,
+) -> Result<(Vec<String>, Vec<String>), String> {
+    let system_guard = state.oxide_system.read().await;
+    if let Some(system) = system_guard.as_ref() {
+        // Clone the system reference to avoid holding the lock across await
+        let system_clone = system.clone();
+        drop(system_guard); // Explicitly drop the guard
+        Ok(system_clone.get_audio_devices().await)
+    } else {
+        Err(i18n::t("error.system_not_initialized").to_string())
+    }
+}
+
+#[tauri::command]
+async fn get_input_volume(state: State<'_, AppState>) -> Result<f32, String> {
+    let system_guard = state.oxide_system.read().await;
+    if let Some(system) = system_guard.as_ref() {
+        // Clone the system reference to avoid holding the lock across await
+        let system_clone = system.clone();
         drop(system_guard); // Explicitly drop the guard
         system_clone.get_input_volume().await
     } else {
-        Err("System not initialized".to_string())
+        Err(i18n::t("error.system_not_initialized").to_string())
     }
 }
 
+/// Performance metrics with the current hardware readings.
+#[derive(serde::Serialize)]
+struct PerformanceReport {
+    #[serde(flatten)]
+    metrics: oxide_core::performance::PerformanceMetrics,
+    hardware: oxide_guardian::hardware::HardwareReadings,
+    /// Per-core imbalance over the last hour
+    #[cfg(feature = "surrealdb-metrics")]
+    core_imbalance: Vec<serde_json::Value>,
+}
+
 #[tauri::command]
-async fn get_performance_metrics(state: State<'_, AppState>) -> Result<serde_json::Value, String> {
+async fn get_performance_metrics(state: State<'_, AppState>) -> Result<PerformanceReport, String> {
     let system_guard = state.oxide_system.read().await;
     if let Some(system) = system_guard.as_ref() {
         let metrics = system.get_performance_metrics().await;
-        serde_json::to_value(metrics).map_err(|e| e.to_string())
+        let hardware = tokio::task::spawn_blocking(oxide_guardian::hardware::readings)
+            .await
+            .map_err(|e| format!("Hardware metrics task join error: {e}"))?;
+        #[cfg(feature = "surrealdb-metrics")]
+        let core_imbalance = match state.surreal_backend().await {
+            Ok(backend) => backend.query_core_imbalance(1).await.unwrap_or_else(|e| {
+                warn!("Core imbalance query failed: {e:#}");
+                Vec::new()
+            }),
+            Err(e) => {
+                warn!("Core imbalance query failed: {e}");
+                Vec::new()
+            }
+        };
+        Ok(PerformanceReport {
+            metrics,
+            hardware,
+            #[cfg(feature = "surrealdb-metrics")]
+            core_imbalance,
+        })
     } else {
-        Err("System not initialized".to_string())
+        Err(i18n::t("error.system_not_initialized").to_string())
     }
 }
 
@@ -939,25 +1944,53 @@ async fn get_performance_score(state: State<'_, AppState>) -> Result<f32, String
     if let Some(system) = system_guard.as_ref() {
         Ok(system.get_performance_score().await)
     } else {
-        Err("System not initialized".to_string())
+        Err(i18n::t("error.system_not_initialized").to_string())
     }
 }
 
 #[tauri::command]
-async fn optimize_performance(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+async fn optimize_performance(
+    state: State<'_, AppState>,
+    session_id: Option<String>,
+) -> Result<oxide_core::performance::OptimizationReport, String> {
     let system_guard = state.oxide_system.read().await;
     if let Some(system) = system_guard.as_ref() {
         // Clone the system reference to avoid holding the lock across await
         let system_clone = system.clone();
         drop(system_guard); // Explicitly drop the guard
-        Ok(system_clone.optimize_performance().await)
+        command_guard::authorize_command(
+            &system_clone,
+            session_id.as_deref(),
+            "optimize_performance",
+        )
+        .await?;
+        system_clone.optimize_performance().await
     } else {
-        Err("System not initialized".to_string())
+        Err(i18n::t("error.system_not_initialized").to_string())
     }
 }
 
+/// Disk usage breakdown of `root`, from the cache when it is recent unless
+/// `refresh` is set.
+#[tauri::command]
+async fn analyze_disk_usage(
+    root: String,
+    refresh: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<oxide_core::disk_usage::DiskUsageReport, String> {
+    let system = state
+        .oxide_system
+        .read()
+        .await
+        .clone()
+        .ok_or(i18n::t("error.system_not_initialized"))?;
+    system
+        .analyze_disk_usage(&root, refresh.unwrap_or(false))
+        .await
+}
+
 #[tauri::command]
-async fn get_error_statistics() -> Result<serde_json::Value, String> {
+async fn get_error_statistics() -> Result<error_handler::ErrorStats, String> {
     GLOBAL_ERROR_MONITOR
         .get_error_stats()
         .map_err(|e| e.to_string())
@@ -973,6 +2006,31 @@ async fn get_recent_errors(
         .map_err(|e| e.to_string())
 }
 
+/// Newest application log entries, optionally at `level` or above and from
+/// modules starting with `module` (e.g. "oxide_guardian").
+#[tauri::command]
+async fn get_recent_logs(
+    level: Option<String>,
+    module: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<LogEntry>, String> {
+    let level = level
+        .filter(|level| !level.trim().is_empty())
+        .map(|level| {
+            level
+                .trim()
+                .parse::<log::Level>()
+                .map_err(|_| format!("Unknown log level '{level}'"))
+        })
+        .transpose()?;
+    let logger = logging::logger().ok_or("Logging not initialized")?;
+    Ok(logger.recent(
+        level,
+        module.as_deref().filter(|m| !m.is_empty()),
+        limit.unwrap_or(200),
+    ))
+}
+
 // TODO: Implement PerformanceAlert type and get_performance_alerts method
 // #[tauri::command]
 // async fn get_performance_alerts(state: State<'_, AppState>) -> Result<Vec<oxide_core::performance::PerformanceAlert>, String> {
@@ -980,44 +2038,56 @@ async fn get_recent_errors(
 //     if let Some(system) = system_guard.as_ref() {
 //         Ok(system.get_performance_alerts().await)
 //     } else {
-//         Err("System not initialized".to_string())
+//         Err(i18n::t("error.system_not_initialized").to_string())
 //     }
 // }
 
 #[tauri::command]
-async fn clear_performance_alerts(state: State<'_, AppState>) -> Result<(), String> {
+async fn clear_performance_alerts(
+    state: State<'_, AppState>,
+    session_id: Option<String>,
+) -> Result<(), String> {
     let system_guard = state.oxide_system.read().await;
     if let Some(system) = system_guard.as_ref() {
+        command_guard::authorize_command(system, session_id.as_deref(), "clear_performance_alerts")
+            .await?;
         system.clear_performance_alerts().await;
         Ok(())
     } else {
-        Err("System not initialized".to_string())
+        Err(i18n::t("error.system_not_initialized").to_string())
     }
 }
 
-// TODO: Implement get_operation_profiles method
-// #[tauri::command]
-// async fn get_operation_profiles(state: State<'_, AppState>) -> Result<serde_json::Value, String> {
-//     let system_guard = state.oxide_system.read().await;
-//     if let Some(system) = system_guard.as_ref() {
-//         let profiles = system.get_operation_profiles().await;
-//         serde_json::to_value(profiles).map_err(|e| e.to_string())
-//     } else {
-//         Err("System not initialized".to_string())
-//     }
-// }
+#[tauri::command]
+async fn get_operation_profiles(
+    state: State<'_, AppState>,
+) -> Result<Vec<oxide_core::performance::OperationProfile>, String> {
+    let system_guard = state.oxide_system.read().await;
+    if let Some(system) = system_guard.as_ref() {
+        Ok(system.get_operation_profiles())
+    } else {
+        Err(i18n::t("error.system_not_initialized").to_string())
+    }
+}
 
 #[tauri::command]
 async fn set_performance_monitoring(
     state: State<'_, AppState>,
     enabled: bool,
+    session_id: Option<String>,
 ) -> Result<(), String> {
     let system_guard = state.oxide_system.read().await;
     if let Some(system) = system_guard.as_ref() {
+        command_guard::authorize_command(
+            system,
+            session_id.as_deref(),
+            "set_performance_monitoring",
+        )
+        .await?;
         system.set_performance_monitoring(enabled).await;
         Ok(())
     } else {
-        Err("System not initialized".to_string())
+        Err(i18n::t("error.system_not_initialized").to_string())
     }
 }
 
@@ -1031,7 +2101,7 @@ async fn validate_input(
     if let Some(system) = system_guard.as_ref() {
         system.validate_input(&field_name, &value).await
     } else {
-        Err("System not initialized".to_string())
+        Err(i18n::t("error.system_not_initialized").to_string())
     }
 }
 
@@ -1039,6 +2109,7 @@ async fn validate_input(
 async fn create_security_session(
     state: State<'_, AppState>,
     user_id: String,
+    passphrase: Option<String>,
     permissions: Vec<String>,
     ip_address: Option<String>,
     user_agent: Option<String>,
@@ -1046,10 +2117,16 @@ async fn create_security_session(
     let system_guard = state.oxide_system.read().await;
     if let Some(system) = system_guard.as_ref() {
         system
-            .create_security_session(user_id, permissions, ip_address, user_agent)
+            .create_security_session(
+                user_id,
+                passphrase.as_deref(),
+                permissions,
+                ip_address,
+                user_agent,
+            )
             .await
     } else {
-        Err("System not initialized".to_string())
+        Err(i18n::t("error.system_not_initialized").to_string())
     }
 }
 
@@ -1062,7 +2139,7 @@ async fn validate_security_session(
     if let Some(system) = system_guard.as_ref() {
         system.validate_security_session(&session_id).await
     } else {
-        Err("System not initialized".to_string())
+        Err(i18n::t("error.system_not_initialized").to_string())
     }
 }
 
@@ -1078,69 +2155,179 @@ async fn check_security_permission(
             .check_security_permission(&session_id, &permission)
             .await
     } else {
-        Err("System not initialized".to_string())
+        Err(i18n::t("error.system_not_initialized").to_string())
     }
 }
 
 #[tauri::command]
-async fn get_security_events(
+async fn get_session_permissions(
     state: State<'_, AppState>,
-    limit: Option<usize>,
-) -> Result<Vec<oxide_core::security_manager::SecurityEvent>, String> {
+    session_id: String,
+) -> Result<Vec<String>, String> {
     let system_guard = state.oxide_system.read().await;
     if let Some(system) = system_guard.as_ref() {
-        Ok(system.get_security_events(limit).await)
+        system.get_session_permissions(&session_id).await
     } else {
-        Err("System not initialized".to_string())
+        Err(i18n::t("error.system_not_initialized").to_string())
     }
 }
 
 #[tauri::command]
-async fn get_security_policy(
+async fn assign_security_role(
     state: State<'_, AppState>,
-) -> Result<oxide_core::security_manager::SecurityPolicy, String> {
+    user_id: String,
+    role: String,
+    passphrase: Option<String>,
+    session_id: Option<String>,
+) -> Result<(), String> {
+    let role: Role = role.parse().map_err(|e: SecurityError| e.to_string())?;
     let system_guard = state.oxide_system.read().await;
     if let Some(system) = system_guard.as_ref() {
-        Ok(system.get_security_policy().await)
+        let system_clone = system.clone();
+        drop(system_guard);
+        command_guard::authorize_command(
+            &system_clone,
+            session_id.as_deref(),
+            "assign_security_role",
+        )
+        .await?;
+        system_clone
+            .assign_security_role(&user_id, role, passphrase.as_deref())
+            .await
     } else {
-        Err("System not initialized".to_string())
+        Err(i18n::t("error.system_not_initialized").to_string())
     }
 }
 
 #[tauri::command]
-async fn check_rate_limit(state: State<'_, AppState>, identifier: String) -> Result<(), String> {
+async fn revoke_security_role(
+    state: State<'_, AppState>,
+    user_id: String,
+    session_id: Option<String>,
+) -> Result<Option<Role>, String> {
     let system_guard = state.oxide_system.read().await;
     if let Some(system) = system_guard.as_ref() {
-        system.check_rate_limit(&identifier).await
+        let system_clone = system.clone();
+        drop(system_guard);
+        command_guard::authorize_command(
+            &system_clone,
+            session_id.as_deref(),
+            "revoke_security_role",
+        )
+        .await?;
+        system_clone.revoke_security_role(&user_id).await
     } else {
-        Err("System not initialized".to_string())
+        Err(i18n::t("error.system_not_initialized").to_string())
     }
 }
 
 #[tauri::command]
-async fn initialize_auth_manager(state: State<'_, AppState>) -> Result<(), String> {
-    let auth_manager = AuthManager::new().map_err(|e| e.to_string())?;
-    let mut auth_guard = state.auth_manager.write().await;
-    *auth_guard = Some(auth_manager);
-    Ok(())
-}
-
-#[tauri::command]
-async fn get_auth_token(state: State<'_, AppState>) -> Result<String, String> {
-    let mut auth_guard = state.auth_manager.write().await;
-    if let Some(auth_manager) = auth_guard.as_mut() {
-        auth_manager
-            .get_auth_token()
-            .await
-            .map_err(|e| e.to_string())
+async fn get_security_role_assignments(
+    state: State<'_, AppState>,
+    session_id: Option<String>,
+) -> Result<HashMap<String, Role>, String> {
+    let system_guard = state.oxide_system.read().await;
+    if let Some(system) = system_guard.as_ref() {
+        let system_clone = system.clone();
+        drop(system_guard);
+        command_guard::authorize_command(
+            &system_clone,
+            session_id.as_deref(),
+            "get_security_role_assignments",
+        )
+        .await?;
+        Ok(system_clone.get_security_role_assignments().await)
     } else {
-        Err("Auth manager not initialized".to_string())
+        Err(i18n::t("error.system_not_initialized").to_string())
     }
 }
 
 #[tauri::command]
-async fn get_auth_status(state: State<'_, AppState>) -> Result<String, String> {
-    let auth_guard = state.auth_manager.read().await;
+async fn get_security_events(
+    state: State<'_, AppState>,
+    limit: Option<usize>,
+) -> Result<Vec<oxide_core::security_manager::SecurityEvent>, String> {
+    let system_guard = state.oxide_system.read().await;
+    if let Some(system) = system_guard.as_ref() {
+        Ok(system.get_security_events(limit).await)
+    } else {
+        Err(i18n::t("error.system_not_initialized").to_string())
+    }
+}
+
+#[tauri::command]
+async fn get_security_policy(
+    state: State<'_, AppState>,
+) -> Result<oxide_core::security_manager::SecurityPolicy, String> {
+    let system_guard = state.oxide_system.read().await;
+    if let Some(system) = system_guard.as_ref() {
+        Ok(system.get_security_policy().await)
+    } else {
+        Err(i18n::t("error.system_not_initialized").to_string())
+    }
+}
+
+/// Require a session on guarded commands (`Some(true)`), stop requiring one
+/// (`Some(false)`), or follow the default of enforcing once an admin exists
+/// (`None`). Returns whether enforcement is now in effect.
+#[tauri::command]
+async fn set_command_permission_enforcement(
+    state: State<'_, AppState>,
+    enforce: Option<bool>,
+    session_id: Option<String>,
+) -> Result<bool, String> {
+    let system_guard = state.oxide_system.read().await;
+    if let Some(system) = system_guard.as_ref() {
+        let system_clone = system.clone();
+        drop(system_guard);
+        command_guard::authorize_command(
+            &system_clone,
+            session_id.as_deref(),
+            "set_command_permission_enforcement",
+        )
+        .await?;
+        Ok(system_clone
+            .set_command_permission_enforcement(enforce)
+            .await)
+    } else {
+        Err(i18n::t("error.system_not_initialized").to_string())
+    }
+}
+
+#[tauri::command]
+async fn check_rate_limit(state: State<'_, AppState>, identifier: String) -> Result<(), String> {
+    let system_guard = state.oxide_system.read().await;
+    if let Some(system) = system_guard.as_ref() {
+        system.check_rate_limit(&identifier).await
+    } else {
+        Err(i18n::t("error.system_not_initialized").to_string())
+    }
+}
+
+#[tauri::command]
+async fn initialize_auth_manager(state: State<'_, AppState>) -> Result<(), String> {
+    let auth_manager = AuthManager::new().map_err(|e| e.to_string())?;
+    let mut auth_guard = state.auth_manager.write().await;
+    *auth_guard = Some(auth_manager);
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_auth_token(state: State<'_, AppState>) -> Result<String, String> {
+    let mut auth_guard = state.auth_manager.write().await;
+    if let Some(auth_manager) = auth_guard.as_mut() {
+        auth_manager
+            .get_auth_token()
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("Auth manager not initialized".to_string())
+    }
+}
+
+#[tauri::command]
+async fn get_auth_status(state: State<'_, AppState>) -> Result<String, String> {
+    let auth_guard = state.auth_manager.read().await;
     if let Some(auth_manager) = auth_guard.as_ref() {
         auth_manager.get_auth_status().map_err(|e| e.to_string())
     } else {
@@ -1181,7 +2368,7 @@ async fn send_message_to_gemini(message: String, model: Option<String>) -> Resul
     // Try to initialize from environment first
     let _ = auth.init_from_env().await;
 
-    auth.send_message(&message, model.as_deref())
+    llm_audit::with_purpose("chat", auth.send_message(&message, model.as_deref()))
         .await
         .map_err(|e| {
             error!("Failed to send message to Gemini: {e}");
@@ -1223,174 +2410,1044 @@ async fn startup_check(state: State<'_, AppState>) -> Result<String, String> {
 }
 
 #[tauri::command]
-async fn qwen_start_device_auth() -> Result<DeviceAuthStart, String> {
-    let auth = QwenAuth::new();
-    auth.start_device_auth().await.map_err(|e| e.to_string())
+async fn qwen_start_device_auth() -> Result<DeviceAuthStart, String> {
+    let auth = QwenAuth::new();
+    auth.start_device_auth().await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn qwen_poll_device_auth(device_code: String) -> Result<PollResult, String> {
+    let auth = QwenAuth::new();
+    auth.poll_device_once(&device_code)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn qwen_get_auth_status() -> Result<String, String> {
+    let auth = QwenAuth::new();
+    auth.get_auth_status().await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn qwen_clear_auth() -> Result<(), String> {
+    let auth = QwenAuth::new();
+    auth.clear_auth().await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn openai_start_oauth(client_id: String, client_secret: String) -> Result<String, String> {
+    // Store credentials first
+    openai_auth::store_client_credentials(&client_id, &client_secret)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // Start OAuth flow
+    match openai_auth::authenticate_openai().await {
+        Ok(token) => Ok(token),
+        Err(e) => {
+            error!("OpenAI OAuth failed: {e}");
+            Err(e.to_string())
+        }
+    }
+}
+
+#[tauri::command]
+async fn openai_set_api_key(api_key: String) -> Result<(), String> {
+    match openai_key::store_api_key(&api_key).await {
+        Ok(()) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[tauri::command]
+async fn openai_get_auth_status() -> Result<String, String> {
+    // Prefer API key if present (env or keyring)
+    match openai_key::get_api_key().await {
+        Ok(Some(key)) if !key.trim().is_empty() => return Ok("API Key".to_string()),
+        Ok(_) => { /* fall through */ }
+        Err(e) => warn!("Failed to read OpenAI API key: {e}"),
+    }
+
+    // Fallback to OAuth status
+    openai_auth::get_auth_status().await.map_err(|e| {
+        error!("OpenAI auth status check failed: {e}");
+        e.to_string()
+    })
+}
+
+#[tauri::command]
+async fn openai_clear_auth() -> Result<(), String> {
+    let mut errors: Vec<String> = Vec::new();
+    if let Err(e) = openai_key::clear_api_key().await {
+        errors.push(format!("key: {e}"));
+    }
+    if let Err(e) = openai_auth::clear_auth().await {
+        errors.push(format!("oauth: {e}"));
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Failed to clear some OpenAI auth items: {}",
+            errors.join(", ")
+        ))
+    }
+}
+
+// Collect a comprehensive snapshot of the current system state for analysis
+#[tauri::command]
+async fn get_system_snapshot(
+    state: State<'_, AppState>,
+) -> Result<system_snapshot::SystemSnapshot, String> {
+    collect_system_snapshot(&state).await
+}
+
+async fn collect_system_snapshot(
+    state: &AppState,
+) -> Result<system_snapshot::SystemSnapshot, String> {
+    let system_guard = state.oxide_system.read().await;
+    if let Some(system) = system_guard.as_ref() {
+        let system_clone = system.clone();
+        drop(system_guard);
+
+        let options = system_snapshot::SnapshotOptions::from_env();
+
+        // Gather pieces in parallel where possible
+        let status = system_clone.get_system_status();
+        let mut threats = system_clone.get_threat_history();
+        let keep_from = threats.len().saturating_sub(options.recent_limit);
+        threats.drain(..keep_from);
+        let memory_stats = system_clone.get_memory_stats().await;
+        let perf_metrics = system_clone.get_performance_metrics().await;
+
+        let perf_metrics_val = serde_json::to_value(perf_metrics).map_err(|e| e.to_string())?;
+
+        let (processes, network) = {
+            let mut monitor = state.security_diagnostic_state.monitor.write().await;
+            monitor.refresh_system();
+            (
+                system_snapshot::top_processes(&monitor, options.top_n),
+                system_snapshot::network_summary(&monitor),
+            )
+        };
+
+        let hardware = tokio::task::spawn_blocking(oxide_guardian::hardware::readings)
+            .await
+            .unwrap_or_default();
+        let startup_items = match system_clone.get_startup_items().await {
+            Ok(items) => system_snapshot::startup_summary(&items, options.top_n),
+            Err(e) => {
+                warn!("Snapshot: startup inventory unavailable: {e}");
+                json!([])
+            }
+        };
+
+        #[cfg(feature = "surrealdb-metrics")]
+        let (hourly_metrics, incidents) = match state.surreal_backend().await {
+            Ok(backend) => system_snapshot::backend_sections(&backend, &options).await,
+            Err(e) => {
+                warn!("Snapshot: {e}");
+                (json!([]), json!([]))
+            }
+        };
+        #[cfg(not(feature = "surrealdb-metrics"))]
+        let (hourly_metrics, incidents) = (json!([]), json!([]));
+
+        let mut snapshot = json!({
+            "status": status,
+            "threats": threats,
+            "memory": memory_stats,
+            "performance": perf_metrics_val,
+            "processes": processes,
+            "network": network,
+            "hardware": hardware,
+            "startup_items": startup_items,
+            "hourly_metrics": hourly_metrics,
+            "incidents": incidents,
+            "collected_at_unix": std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+        });
+        let privacy = system_clone.privacy_filter().await;
+        if privacy.is_enabled() {
+            privacy.redact_value(&mut snapshot);
+        } else {
+            oxide_core::privacy::PrivacyFilter::mask_home_paths(&mut snapshot);
+        }
+        system_snapshot::enforce_size_budget(&mut snapshot, options.max_bytes);
+        Ok(snapshot)
+    } else {
+        Err(i18n::t("error.system_not_initialized").to_string())
+    }
+}
+
+// Orchestrate system analysis: collect snapshot and summarize with Gemini
+#[tauri::command]
+async fn run_system_analysis(
+    state: State<'_, AppState>,
+    model: Option<String>,
+) -> Result<String, String> {
+    let privacy = current_privacy_filter(&state).await?;
+    let snapshot = collect_system_snapshot(&state).await?;
+
+    // Build an analyst-style prompt for Gemini
+    let prompt = prompts::render(
+        prompts::SYSTEM_ANALYSIS,
+        Some("gemini"),
+        &[("snapshot", &snapshot.to_string())],
+    );
+
+    use oxide_core::gemini_auth::GeminiAuth;
+    let auth = GeminiAuth::new();
+    let request = auth.send_message(&privacy.redact_text(&prompt), model.as_deref());
+    llm_audit::with_purpose("system_analysis", request)
+        .await
+        .map(|analysis| privacy.restore_text(&analysis))
+        .map_err(|e| {
+            error!("System analysis via Gemini failed: {e}");
+            e.to_string()
+        })
+}
+
+/// Privacy filter of the running system, used around cloud LLM calls.
+async fn current_privacy_filter(
+    state: &AppState,
+) -> Result<Arc<oxide_core::privacy::PrivacyFilter>, String> {
+    let system_guard = state.oxide_system.read().await;
+    match system_guard.as_ref() {
+        Some(system) => Ok(system.privacy_filter().await),
+        None => Err(i18n::t("error.system_not_initialized").to_string()),
+    }
+}
+
+// Run autonomous threat consensus without external VT. Uses both LLMs if available; if only one is available, uses that one.
+// Gemini search will be always enabled when Gemini is used (no env toggles).
+#[tauri::command]
+async fn run_threat_consensus(state: State<'_, AppState>) -> Result<String, String> {
+    threat_consensus_report(&state, &|_| {}).await
+}
+
+async fn threat_consensus_report(
+    state: &AppState,
+    stage: &(dyn Fn(&str) + Send + Sync),
+) -> Result<String, String> {
+    let privacy = current_privacy_filter(state).await?;
+    let settings = current_consensus_config(state).await;
+    stage("Collecting system snapshot");
+    let snapshot = collect_system_snapshot(state).await?;
+    stage("Querying consensus models");
+    let run = threat_consensus::run_consensus(snapshot, true, &privacy, &settings)
+        .await
+        .map_err(|e| {
+            error!("Threat consensus failed: {e}");
+            e
+        })?;
+    stage("Recording results");
+    record_consensus_run(state, &settings, &run).await;
+    notify_consensus(state, &run.report).await;
+    queue_remediations(state, &run.report).await;
+    serde_json::to_string(&run.report).map_err(|e| e.to_string())
+}
+
+/// Emits every job change as a `job://update` event.
+fn job_notifier(app: tauri::AppHandle) -> impl Fn(&job_manager::JobInfo) + Send + Sync + 'static {
+    move |info| {
+        events::emit(&app, info);
+    }
+}
+
+/// The analysis commands return JSON text; jobs store it parsed.
+fn analysis_result(output: String) -> serde_json::Value {
+    serde_json::from_str(&output).unwrap_or(serde_json::Value::String(output))
+}
+
+/// Background variant of `run_collaborative_analysis`; returns the job id.
+#[tauri::command]
+async fn start_collaborative_analysis(
+    user_input: String,
+    task_type: Option<String>,
+    state: State<'_, AppState>,
+    app: tauri::AppHandle,
+) -> Result<String, String> {
+    let job_state = state.inner().clone();
+    Ok(state.jobs.spawn(
+        "collaborative_analysis",
+        job_manager::CancelMode::Abort,
+        job_notifier(app),
+        move |job| async move {
+            let stage = |s: &str| job.stage(s);
+            collaborative_analysis(&job_state, user_input, task_type, &stage)
+                .await
+                .map(analysis_result)
+        },
+    ))
+}
+
+/// Background variant of `run_multi_agent_analysis`; returns the job id.
+#[tauri::command]
+async fn start_multi_agent_analysis(
+    gemini_model: Option<String>,
+    qwen_model: Option<String>,
+    state: State<'_, AppState>,
+    app: tauri::AppHandle,
+) -> Result<String, String> {
+    let job_state = state.inner().clone();
+    Ok(state.jobs.spawn(
+        "multi_agent_analysis",
+        job_manager::CancelMode::Abort,
+        job_notifier(app),
+        move |job| async move {
+            let stage = |s: &str| job.stage(s);
+            multi_agent_analysis(&job_state, gemini_model, qwen_model, &stage)
+                .await
+                .map(analysis_result)
+        },
+    ))
+}
+
+/// Background variant of `run_threat_consensus`; returns the job id.
+#[tauri::command]
+async fn start_threat_consensus(
+    state: State<'_, AppState>,
+    app: tauri::AppHandle,
+) -> Result<String, String> {
+    let job_state = state.inner().clone();
+    Ok(state.jobs.spawn(
+        "threat_consensus",
+        job_manager::CancelMode::Abort,
+        job_notifier(app),
+        move |job| async move {
+            let stage = |s: &str| job.stage(s);
+            threat_consensus_report(&job_state, &stage)
+                .await
+                .map(analysis_result)
+        },
+    ))
+}
+
+#[tauri::command]
+async fn get_job(
+    job_id: String,
+    state: State<'_, AppState>,
+) -> Result<job_manager::JobInfo, String> {
+    state
+        .jobs
+        .get(&job_id)
+        .ok_or_else(|| "Unknown job_id".to_string())
+}
+
+/// Retained jobs, newest first; `kind` filters e.g. to `folder_scan`.
+#[tauri::command]
+async fn list_jobs(
+    kind: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<job_manager::JobInfo>, String> {
+    Ok(state.jobs.list(kind.as_deref()))
+}
+
+/// Cancel a queued or running job. Services such as the metrics collector
+/// are stopped by their owner, not from here.
+#[tauri::command]
+async fn cancel_job(
+    job_id: String,
+    session_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<job_manager::JobInfo, String> {
+    let system = state
+        .oxide_system
+        .read()
+        .await
+        .clone()
+        .ok_or(i18n::t("error.system_not_initialized"))?;
+    command_guard::authorize_command(&system, session_id.as_deref(), "cancel_job").await?;
+    if state.jobs.is_service(&job_id) {
+        return Err(format!(
+            "Job {job_id} is a background service and cannot be cancelled"
+        ));
+    }
+    state.jobs.cancel(&job_id)
+}
+
+#[tauri::command]
+async fn get_threat_recommendations(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    let privacy = current_privacy_filter(&state).await?;
+    let settings = current_consensus_config(&state).await;
+    let snapshot = collect_system_snapshot(&state).await?;
+    let run = threat_consensus::run_consensus(snapshot, true, &privacy, &settings)
+        .await
+        .map_err(|e| {
+            error!("Threat consensus (recommendations) failed: {e}");
+            e
+        })?;
+    record_consensus_run(&state, &settings, &run).await;
+    notify_consensus(&state, &run.report).await;
+    queue_remediations(&state, &run.report).await;
+    Ok(threat_consensus::recommendations_from_report(&run.report))
+}
+
+/// Recent consensus runs (newest first) for audit views.
+#[cfg(feature = "surrealdb-metrics")]
+#[tauri::command]
+async fn get_consensus_history(
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<oxide_memory::ConsensusRunEntry>, String> {
+    state
+        .surreal_backend()
+        .await?
+        .query_consensus_runs(limit.unwrap_or(20).clamp(1, 200))
+        .await
+        .map_err(|e| format!("Failed to query consensus runs: {e}"))
+}
+
+#[cfg(not(feature = "surrealdb-metrics"))]
+#[tauri::command]
+async fn get_consensus_history(_limit: Option<usize>) -> Result<Vec<serde_json::Value>, String> {
+    Err(i18n::t("error.metrics_disabled").to_string())
+}
+
+/// Prompts and responses exchanged with external LLMs, newest first.
+#[tauri::command]
+async fn query_llm_audit(
+    query: Option<LlmAuditQuery>,
+    session_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<LlmAuditEntry>, String> {
+    let system = state
+        .oxide_system
+        .read()
+        .await
+        .clone()
+        .ok_or(i18n::t("error.system_not_initialized"))?;
+    command_guard::authorize_command(&system, session_id.as_deref(), "query_llm_audit").await?;
+    #[cfg(feature = "surrealdb-metrics")]
+    {
+        state
+            .surreal_backend()
+            .await?
+            .query_llm_audit(&query.unwrap_or_default())
+            .await
+            .map_err(|e| format!("Failed to query the LLM audit trail: {e:#}"))
+    }
+    #[cfg(not(feature = "surrealdb-metrics"))]
+    {
+        let _ = query;
+        Err(i18n::t("error.metrics_disabled").to_string())
+    }
+}
+
+/// Effective collaborative-analysis role assignments: the configured ones,
+/// or the built-in defaults.
+async fn current_orchestrator_config(state: &AppState) -> oxide_core::config::OrchestratorConfig {
+    let system_guard = state.oxide_system.read().await;
+    match system_guard.as_ref() {
+        Some(system) => system.get_config().await.orchestrator.unwrap_or_default(),
+        None => Default::default(),
+    }
+}
+
+#[tauri::command]
+async fn get_orchestrator_config(
+    state: State<'_, AppState>,
+) -> Result<oxide_core::config::OrchestratorConfig, String> {
+    Ok(current_orchestrator_config(&state).await)
+}
+
+async fn current_consensus_config(state: &AppState) -> oxide_core::config::ConsensusConfig {
+    let system_guard = state.oxide_system.read().await;
+    match system_guard.as_ref() {
+        Some(system) => system.get_config().await.consensus.unwrap_or_default(),
+        None => Default::default(),
+    }
+}
+
+/// Persist a consensus run for audit; failures are logged, not surfaced.
+async fn record_consensus_run(
+    state: &AppState,
+    settings: &oxide_core::config::ConsensusConfig,
+    run: &threat_consensus::ConsensusRun,
+) {
+    if !settings.persist_runs {
+        return;
+    }
+    #[cfg(feature = "surrealdb-metrics")]
+    match state.surreal_backend().await {
+        Ok(backend) => {
+            if let Err(e) = backend.insert_consensus_run(run.to_record()).await {
+                warn!("Failed to persist consensus run: {e:#}");
+            }
+        }
+        Err(e) => warn!("Failed to persist consensus run: {e}"),
+    }
+    #[cfg(not(feature = "surrealdb-metrics"))]
+    let _ = (state, run);
+}
+
+async fn notify_consensus(state: &AppState, report: &threat_consensus::ThreatReport) {
+    let Some(system) = state.oxide_system.read().await.clone() else {
+        return;
+    };
+    system.notify_webhooks(WebhookPayload::new(
+        WebhookEvent::ConsensusReport,
+        format!(
+            "Threat consensus: risk {:.0}/100 with {} finding(s)",
+            report.risk_score,
+            report.findings.len()
+        ),
+        serde_json::to_value(report).unwrap_or_default(),
+    ));
+}
+
+/// Send a test event to every enabled webhook.
+#[tauri::command]
+async fn test_webhooks(state: State<'_, AppState>) -> Result<Vec<DeliveryResult>, String> {
+    let system_guard = state.oxide_system.read().await;
+    if let Some(system) = system_guard.as_ref() {
+        let system_clone = system.clone();
+        drop(system_guard);
+        Ok(system_clone.test_webhooks().await)
+    } else {
+        Err(i18n::t("error.system_not_initialized").to_string())
+    }
+}
+
+/// Send a test message through every configured email alert sink.
+#[tauri::command]
+async fn test_alert_email(state: State<'_, AppState>) -> Result<Vec<EmailTestResult>, String> {
+    let system_guard = state.oxide_system.read().await;
+    if let Some(system) = system_guard.as_ref() {
+        let system_clone = system.clone();
+        drop(system_guard);
+        system_clone.send_test_emails().await
+    } else {
+        Err(i18n::t("error.system_not_initialized").to_string())
+    }
+}
+
+/// Store the SMTP password for an email alert sink in the OS keyring; an
+/// empty password removes it.
+#[tauri::command]
+async fn set_smtp_password(
+    host: String,
+    username: String,
+    password: String,
+    session_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let system_guard = state.oxide_system.read().await;
+    if let Some(system) = system_guard.as_ref() {
+        let system_clone = system.clone();
+        drop(system_guard);
+        command_guard::authorize_command(&system_clone, session_id.as_deref(), "set_smtp_password")
+            .await?;
+        let result = if password.is_empty() {
+            smtp_credentials::clear_password(&host, &username)
+        } else {
+            smtp_credentials::store_password(&host, &username, &password)
+        };
+        result.map_err(|e| e.to_string())
+    } else {
+        Err(i18n::t("error.system_not_initialized").to_string())
+    }
+}
+
+/// Store the signing key for a webhook endpoint in the OS keyring; an empty
+/// secret removes it.
+#[tauri::command]
+async fn set_webhook_secret(
+    url: String,
+    secret: String,
+    session_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let system = state
+        .oxide_system
+        .read()
+        .await
+        .clone()
+        .ok_or_else(|| i18n::t("error.system_not_initialized").to_string())?;
+    command_guard::authorize_command(&system, session_id.as_deref(), "set_webhook_secret").await?;
+    let result = if secret.is_empty() {
+        webhook_credentials::clear_secret(&url)
+    } else {
+        webhook_credentials::store_secret(&url, &secret)
+    };
+    result.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn scan_process_memory(
+    pid: u32,
+    session_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<MemoryScanReport, String> {
+    let system_guard = state.oxide_system.read().await;
+    if let Some(system) = system_guard.as_ref() {
+        let system_clone = system.clone();
+        drop(system_guard);
+        command_guard::authorize_command(
+            &system_clone,
+            session_id.as_deref(),
+            "scan_process_memory",
+        )
+        .await?;
+        system_clone.scan_process_memory(pid).await
+    } else {
+        Err(i18n::t("error.system_not_initialized").to_string())
+    }
+}
+
+/// Differences between the machine and the recorded known-good baseline.
+#[tauri::command]
+async fn view_baseline_drift(state: State<'_, AppState>) -> Result<Option<DriftReport>, String> {
+    let system_guard = state.oxide_system.read().await;
+    if let Some(system) = system_guard.as_ref() {
+        let system_clone = system.clone();
+        drop(system_guard);
+        system_clone.baseline_drift().await
+    } else {
+        Err(i18n::t("error.system_not_initialized").to_string())
+    }
+}
+
+/// Accept the current state as the new baseline, clearing reported drift.
+#[tauri::command]
+async fn record_baseline(
+    session_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<BaselineSnapshot, String> {
+    let system_guard = state.oxide_system.read().await;
+    if let Some(system) = system_guard.as_ref() {
+        let system_clone = system.clone();
+        drop(system_guard);
+        command_guard::authorize_command(&system_clone, session_id.as_deref(), "record_baseline")
+            .await?;
+        system_clone.record_baseline().await
+    } else {
+        Err(i18n::t("error.system_not_initialized").to_string())
+    }
+}
+
+/// Oxide Pilot's executable, config, signatures, quarantine and audit data
+/// compared with the recorded integrity manifest.
+#[tauri::command]
+async fn get_integrity_status(
+    session_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<IntegrityReport, String> {
+    let system = state
+        .oxide_system
+        .read()
+        .await
+        .clone()
+        .ok_or(i18n::t("error.system_not_initialized"))?;
+    command_guard::authorize_command(&system, session_id.as_deref(), "get_integrity_status")
+        .await?;
+    system.integrity_status().await
+}
+
+/// Accept the current state of Oxide Pilot's own files as known-good,
+/// e.g. after replacing the signature database by hand.
+#[tauri::command]
+async fn record_integrity_manifest(
+    session_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<IntegrityManifest, String> {
+    let system = state
+        .oxide_system
+        .read()
+        .await
+        .clone()
+        .ok_or(i18n::t("error.system_not_initialized"))?;
+    command_guard::authorize_command(&system, session_id.as_deref(), "record_integrity_manifest")
+        .await?;
+    system.record_integrity().await
+}
+
+/// Block network access for a running process (`pid`) or a program
+/// (`path`). On Linux only running processes can be blocked. `start_time`
+/// is when the caller saw `pid` start, to refuse a reused pid.
+#[tauri::command]
+async fn block_process_network(
+    pid: Option<u32>,
+    path: Option<String>,
+    start_time: Option<u64>,
+    reason: Option<String>,
+    session_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<FirewallRule, String> {
+    let system_guard = state.oxide_system.read().await;
+    if let Some(system) = system_guard.as_ref() {
+        let system_clone = system.clone();
+        drop(system_guard);
+        command_guard::authorize_command(
+            &system_clone,
+            session_id.as_deref(),
+            "block_process_network",
+        )
+        .await?;
+        let path = path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty());
+        let expected = expected_process(start_time, None);
+        system_clone
+            .block_process_network(pid, path, reason, expected)
+            .await
+    } else {
+        Err(i18n::t("error.system_not_initialized").to_string())
+    }
+}
+
+/// Firewall rules created by Oxide Pilot.
+#[tauri::command]
+async fn list_oxide_firewall_rules(
+    state: State<'_, AppState>,
+) -> Result<Vec<FirewallRule>, String> {
+    let system_guard = state.oxide_system.read().await;
+    if let Some(system) = system_guard.as_ref() {
+        Ok(system.firewall_rules())
+    } else {
+        Err(i18n::t("error.system_not_initialized").to_string())
+    }
+}
+
+/// Remove one of Oxide Pilot's firewall rules.
+#[tauri::command]
+async fn remove_rule(
+    rule_id: String,
+    session_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<FirewallRule, String> {
+    let system_guard = state.oxide_system.read().await;
+    if let Some(system) = system_guard.as_ref() {
+        let system_clone = system.clone();
+        drop(system_guard);
+        command_guard::authorize_command(&system_clone, session_id.as_deref(), "remove_rule")
+            .await?;
+        system_clone.remove_firewall_rule(rule_id).await
+    } else {
+        Err(i18n::t("error.system_not_initialized").to_string())
+    }
+}
+
+/// Import a STIX 2.1 bundle or CSV indicator list from a file path or URL.
+/// Re-importing a `source` replaces its earlier indicators.
+#[tauri::command]
+async fn import_ioc_feed(
+    path_or_url: String,
+    source: Option<String>,
+    expiry_days: Option<u32>,
+    session_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<IocImportReport, String> {
+    let system_guard = state.oxide_system.read().await;
+    if let Some(system) = system_guard.as_ref() {
+        let system_clone = system.clone();
+        drop(system_guard);
+        command_guard::authorize_command(&system_clone, session_id.as_deref(), "import_ioc_feed")
+            .await?;
+        if path_or_url.trim().is_empty() {
+            return Err("Feed path or URL is required".to_string());
+        }
+        system_clone
+            .import_ioc_feed(path_or_url.trim().to_string(), source, expiry_days)
+            .await
+    } else {
+        Err(i18n::t("error.system_not_initialized").to_string())
+    }
+}
+
+/// Playbooks in the configured directory, with any files that failed to load.
+#[tauri::command]
+async fn list_playbooks(state: State<'_, AppState>) -> Result<PlaybookLibrary, String> {
+    let system_guard = state.oxide_system.read().await;
+    if let Some(system) = system_guard.as_ref() {
+        let system_clone = system.clone();
+        drop(system_guard);
+        Ok(system_clone.playbook_library().await)
+    } else {
+        Err(i18n::t("error.system_not_initialized").to_string())
+    }
 }
 
+/// Run a playbook, against a recorded threat when `threat_id` is given.
+/// Dry runs report what each step would do and need no permission.
 #[tauri::command]
-async fn qwen_poll_device_auth(device_code: String) -> Result<PollResult, String> {
-    let auth = QwenAuth::new();
-    auth.poll_device_once(&device_code)
-        .await
-        .map_err(|e| e.to_string())
+async fn run_playbook(
+    playbook_id: String,
+    threat_id: Option<String>,
+    dry_run: Option<bool>,
+    session_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<PlaybookRun, String> {
+    let system_guard = state.oxide_system.read().await;
+    if let Some(system) = system_guard.as_ref() {
+        let system_clone = system.clone();
+        drop(system_guard);
+        let dry_run = dry_run.unwrap_or(false);
+        if !dry_run {
+            command_guard::authorize_command(&system_clone, session_id.as_deref(), "run_playbook")
+                .await?;
+        }
+        let library = system_clone.playbook_library().await;
+        let playbook = library
+            .get(&playbook_id)
+            .ok_or_else(|| format!("Playbook '{playbook_id}' not found"))?;
+        let threat = match threat_id {
+            Some(id) => Some(
+                system_clone
+                    .find_threat(&id)
+                    .ok_or_else(|| format!("Threat '{id}' not found"))?,
+            ),
+            None => None,
+        };
+        Ok(playbooks::run_playbook(
+            &system_clone,
+            playbook,
+            threat.as_ref(),
+            dry_run,
+            session_id.as_deref(),
+        )
+        .await)
+    } else {
+        Err(i18n::t("error.system_not_initialized").to_string())
+    }
 }
 
 #[tauri::command]
-async fn qwen_get_auth_status() -> Result<String, String> {
-    let auth = QwenAuth::new();
-    auth.get_auth_status().await.map_err(|e| e.to_string())
+async fn get_heuristic_rules(state: State<'_, AppState>) -> Result<RulesStatus, String> {
+    let system_guard = state.oxide_system.read().await;
+    if let Some(system) = system_guard.as_ref() {
+        Ok(system.heuristic_rules())
+    } else {
+        Err(i18n::t("error.system_not_initialized").to_string())
+    }
 }
 
 #[tauri::command]
-async fn qwen_clear_auth() -> Result<(), String> {
-    let auth = QwenAuth::new();
-    auth.clear_auth().await.map_err(|e| e.to_string())
+async fn reload_heuristic_rules(
+    session_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<RulesStatus, String> {
+    let system_guard = state.oxide_system.read().await;
+    if let Some(system) = system_guard.as_ref() {
+        let system_clone = system.clone();
+        drop(system_guard);
+        command_guard::authorize_command(
+            &system_clone,
+            session_id.as_deref(),
+            "reload_heuristic_rules",
+        )
+        .await?;
+        system_clone.reload_heuristic_rules()
+    } else {
+        Err(i18n::t("error.system_not_initialized").to_string())
+    }
 }
 
+/// Which heuristic rules a process with these attributes would trigger.
 #[tauri::command]
-async fn openai_start_oauth(client_id: String, client_secret: String) -> Result<String, String> {
-    // Store credentials first
-    openai_auth::store_client_credentials(&client_id, &client_secret)
+async fn test_heuristic_rules(
+    sample: ProcessSample,
+    state: State<'_, AppState>,
+) -> Result<Vec<RuleMatch>, String> {
+    let system_guard = state.oxide_system.read().await;
+    if let Some(system) = system_guard.as_ref() {
+        Ok(system.test_heuristic_rules(&sample))
+    } else {
+        Err(i18n::t("error.system_not_initialized").to_string())
+    }
+}
+
+/// Shared body of the process control commands. A session is always
+/// required, even when command permissions are not enforced. `start_time`
+/// and `exe` are what the caller saw of the process; when given, a pid that
+/// now belongs to another process is refused.
+async fn control_process(
+    state: &AppState,
+    pid: u32,
+    action: ProcessAction,
+    expected: ProcessIdentity,
+    session_id: String,
+) -> Result<String, String> {
+    let command = match action {
+        ProcessAction::Kill => "kill_process",
+        ProcessAction::Suspend => "suspend_process",
+        ProcessAction::Resume => "resume_process",
+    };
+    let system = state
+        .oxide_system
+        .read()
         .await
-        .map_err(|e| e.to_string())?;
+        .clone()
+        .ok_or(i18n::t("error.system_not_initialized"))?;
+    command_guard::authorize_command(&system, Some(&session_id), command).await?;
+    system
+        .control_process(pid, action, expected, &session_id)
+        .await
+}
 
-    // Start OAuth flow
-    match openai_auth::authenticate_openai().await {
-        Ok(token) => Ok(token),
-        Err(e) => {
-            error!("OpenAI OAuth failed: {e}");
-            Err(e.to_string())
-        }
+fn expected_process(start_time: Option<u64>, exe: Option<String>) -> ProcessIdentity {
+    ProcessIdentity {
+        name: None,
+        exe: exe.filter(|e| !e.trim().is_empty()),
+        start_time,
     }
 }
 
 #[tauri::command]
-async fn openai_set_api_key(api_key: String) -> Result<(), String> {
-    match openai_key::store_api_key(&api_key).await {
-        Ok(()) => Ok(()),
-        Err(e) => Err(e.to_string()),
-    }
+async fn kill_process(
+    pid: u32,
+    start_time: Option<u64>,
+    exe: Option<String>,
+    session_id: String,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let expected = expected_process(start_time, exe);
+    control_process(&state, pid, ProcessAction::Kill, expected, session_id).await
 }
 
 #[tauri::command]
-async fn openai_get_auth_status() -> Result<String, String> {
-    // Prefer API key if present (env or keyring)
-    match openai_key::get_api_key().await {
-        Ok(Some(key)) if !key.trim().is_empty() => return Ok("API Key".to_string()),
-        Ok(_) => { /* fall through */ }
-        Err(e) => warn!("Failed to read OpenAI API key: {e}"),
-    }
+async fn suspend_process(
+    pid: u32,
+    start_time: Option<u64>,
+    exe: Option<String>,
+    session_id: String,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let expected = expected_process(start_time, exe);
+    control_process(&state, pid, ProcessAction::Suspend, expected, session_id).await
+}
 
-    // Fallback to OAuth status
-    openai_auth::get_auth_status().await.map_err(|e| {
-        error!("OpenAI auth status check failed: {e}");
-        e.to_string()
-    })
+#[tauri::command]
+async fn resume_process(
+    pid: u32,
+    start_time: Option<u64>,
+    exe: Option<String>,
+    session_id: String,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let expected = expected_process(start_time, exe);
+    control_process(&state, pid, ProcessAction::Resume, expected, session_id).await
 }
 
 #[tauri::command]
-async fn openai_clear_auth() -> Result<(), String> {
-    let mut errors: Vec<String> = Vec::new();
-    if let Err(e) = openai_key::clear_api_key().await {
-        errors.push(format!("key: {e}"));
-    }
-    if let Err(e) = openai_auth::clear_auth().await {
-        errors.push(format!("oauth: {e}"));
-    }
-    if errors.is_empty() {
-        Ok(())
+async fn get_startup_items(state: State<'_, AppState>) -> Result<Vec<StartupItem>, String> {
+    let system_guard = state.oxide_system.read().await;
+    if let Some(system) = system_guard.as_ref() {
+        let system_clone = system.clone();
+        drop(system_guard);
+        system_clone.get_startup_items().await
     } else {
-        Err(format!(
-            "Failed to clear some OpenAI auth items: {}",
-            errors.join(", ")
-        ))
+        Err(i18n::t("error.system_not_initialized").to_string())
     }
 }
 
-// Collect a comprehensive snapshot of the current system state for analysis
+/// Boot impact of the startup entries; slow ones are queued as remediations.
 #[tauri::command]
-async fn get_system_snapshot(state: State<'_, AppState>) -> Result<serde_json::Value, String> {
+async fn get_boot_impact(state: State<'_, AppState>) -> Result<BootImpactReport, String> {
     let system_guard = state.oxide_system.read().await;
     if let Some(system) = system_guard.as_ref() {
         let system_clone = system.clone();
         drop(system_guard);
-
-        // Gather pieces in parallel where possible
-        let status = system_clone.get_system_status();
-        let threats = system_clone.get_threat_history();
-        let memory_stats = system_clone.get_memory_stats().await;
-        let perf_metrics = system_clone.get_performance_metrics().await;
-
-        let perf_metrics_val = serde_json::to_value(perf_metrics).map_err(|e| e.to_string())?;
-
-        let snapshot = json!({
-            "status": status,
-            "threats": threats,
-            "memory": memory_stats,
-            "performance": perf_metrics_val,
-            "collected_at_unix": std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .map(|d| d.as_secs())
-                .unwrap_or(0),
-        });
-        Ok(snapshot)
+        system_clone.boot_impact().await
     } else {
-        Err("System not initialized".to_string())
+        Err(i18n::t("error.system_not_initialized").to_string())
     }
 }
 
-// Orchestrate system analysis: collect snapshot and summarize with Gemini
 #[tauri::command]
-async fn run_system_analysis(
+async fn disable_startup_item(
+    item_id: String,
+    session_id: Option<String>,
     state: State<'_, AppState>,
-    model: Option<String>,
 ) -> Result<String, String> {
-    let snapshot = get_system_snapshot(state).await?;
-
-    // Build an analyst-style prompt for Gemini
-    let prompt = format!(
-        "You are an expert OS performance and security analyst. Given this JSON snapshot, produce a concise analysis with:\n\
-        - Key performance issues and likely root causes\n\
-        - Suspicious processes or threats (if any)\n\
-        - Immediate remediation steps (bulleted)\n\
-        - Risk score (0-100) and confidence.\n\nSnapshot:\n{snapshot}"
-    );
+    let system_guard = state.oxide_system.read().await;
+    if let Some(system) = system_guard.as_ref() {
+        let system_clone = system.clone();
+        drop(system_guard);
+        command_guard::authorize_command(
+            &system_clone,
+            session_id.as_deref(),
+            "disable_startup_item",
+        )
+        .await?;
+        system_clone.disable_startup_item(item_id).await
+    } else {
+        Err(i18n::t("error.system_not_initialized").to_string())
+    }
+}
 
-    use oxide_core::gemini_auth::GeminiAuth;
-    let auth = GeminiAuth::new();
-    auth.send_message(&prompt, model.as_deref())
-        .await
-        .map_err(|e| {
-            error!("System analysis via Gemini failed: {e}");
-            e.to_string()
+/// Turn consensus findings into pending remediation actions.
+async fn queue_remediations(state: &AppState, report: &threat_consensus::ThreatReport) {
+    let system_guard = state.oxide_system.read().await;
+    let Some(system) = system_guard.as_ref() else {
+        return;
+    };
+    let findings: Vec<RemediationFinding> = report
+        .findings
+        .iter()
+        .map(|f| RemediationFinding {
+            id: f.id.clone(),
+            kind: f.kind.clone(),
+            severity: f.severity.clone(),
+            summary: f.summary.clone(),
+            indicators: f.indicators.clone(),
         })
+        .collect();
+    let queued = system.propose_remediations(&findings);
+    if !queued.is_empty() {
+        info!("Queued {} remediation action(s) for approval", queued.len());
+    }
 }
 
-// Run autonomous threat consensus without external VT. Uses both LLMs if available; if only one is available, uses that one.
-// Gemini search will be always enabled when Gemini is used (no env toggles).
 #[tauri::command]
-async fn run_threat_consensus(state: State<'_, AppState>) -> Result<String, String> {
-    let snapshot = get_system_snapshot(state).await?;
-    let report = threat_consensus::run_consensus(snapshot, true)
-        .await
-        .map_err(|e| {
-            error!("Threat consensus failed: {e}");
-            e
-        })?;
-    serde_json::to_string(&report).map_err(|e| e.to_string())
+async fn get_pending_remediations(
+    state: State<'_, AppState>,
+) -> Result<Vec<RemediationAction>, String> {
+    let system_guard = state.oxide_system.read().await;
+    match system_guard.as_ref() {
+        Some(system) => Ok(system.pending_remediations()),
+        None => Err(i18n::t("error.system_not_initialized").to_string()),
+    }
 }
 
 #[tauri::command]
-async fn get_threat_recommendations(state: State<'_, AppState>) -> Result<Vec<String>, String> {
-    let snapshot = get_system_snapshot(state).await?;
-    let report = threat_consensus::run_consensus(snapshot, true)
-        .await
-        .map_err(|e| {
-            error!("Threat consensus (recommendations) failed: {e}");
-            e
-        })?;
-    Ok(threat_consensus::recommendations_from_report(&report))
+async fn respond_remediation(
+    action_id: String,
+    approved: bool,
+    reason: Option<String>,
+    session_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<RemediationAction, String> {
+    let system_guard = state.oxide_system.read().await;
+    if let Some(system) = system_guard.as_ref() {
+        let system_clone = system.clone();
+        drop(system_guard);
+        command_guard::authorize_command(
+            &system_clone,
+            session_id.as_deref(),
+            "respond_remediation",
+        )
+        .await?;
+        system_clone
+            .respond_remediation(action_id, approved, reason)
+            .await
+    } else {
+        Err(i18n::t("error.system_not_initialized").to_string())
+    }
+}
+
+#[tauri::command]
+async fn get_remediation_history(
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<RemediationAction>, String> {
+    let system_guard = state.oxide_system.read().await;
+    match system_guard.as_ref() {
+        Some(system) => Ok(system.remediation_history(limit.unwrap_or(50).clamp(1, 500))),
+        None => Err(i18n::t("error.system_not_initialized").to_string()),
+    }
 }
 
 #[tauri::command]
@@ -1407,8 +3464,9 @@ async fn mcp_start(
         }
     }
 
-    // Resolve port/password from override or config
-    let (port, password): (u16, Option<String>) = {
+    // Resolve port/password/TLS from override or config
+    let localhost = std::net::Ipv4Addr::LOCALHOST;
+    let (config, system): (McpServerConfig, Option<OxideSystem>) = {
         // Try to read from current system config if available
         let system_guard = state.oxide_system.read().await;
         if let Some(system) = system_guard.as_ref() {
@@ -1417,6 +3475,15 @@ async fn mcp_start(
             let resolved_port = port_override
                 .or_else(|| from_cfg.as_ref().map(|m| m.port))
                 .unwrap_or(7999);
+            let tls = match from_cfg.as_ref().and_then(|m| m.tls.clone()) {
+                Some(t) if t.enabled => Some(TlsIdentity::load_or_generate(
+                    t.cert_path.as_deref().map(Path::new),
+                    t.key_path.as_deref().map(Path::new),
+                    Path::new(tls::TLS_DIR),
+                )?),
+                _ => None,
+            };
+            let allow_remote = from_cfg.as_ref().is_some_and(|m| m.allow_remote);
             let resolved_pwd = if let Some(p) = password_override {
                 Some(p)
             } else if let Some(enc) = from_cfg.and_then(|m| m.password) {
@@ -1429,21 +3496,42 @@ async fn mcp_start(
             } else {
                 None
             };
-            (resolved_port, resolved_pwd)
+            if allow_remote && tls.is_none() {
+                return Err("Remote MCP access requires TLS".to_string());
+            }
+            if allow_remote && resolved_pwd.is_none() && !state.mcp_tokens.has_tokens() {
+                return Err("Create an MCP token before allowing remote connections".to_string());
+            }
+            let ip = if allow_remote {
+                std::net::Ipv4Addr::UNSPECIFIED
+            } else {
+                localhost
+            };
+            let config = McpServerConfig {
+                addr: (ip, resolved_port).into(),
+                password: resolved_pwd,
+                tls,
+            };
+            (config, Some(system.clone()))
         } else {
-            (port_override.unwrap_or(7999), password_override)
+            let config = McpServerConfig {
+                addr: (localhost, port_override.unwrap_or(7999)).into(),
+                password: password_override,
+                tls: None,
+            };
+            (config, None)
         }
     };
 
-    let handle = McpServerHandle::start(port, password)
+    let handle = McpServerHandle::start(config, state.mcp_tokens.clone(), system)
         .await
         .map_err(|e| e.to_string())?;
-    let addr = handle.addr();
+    let url = handle.url();
 
     let mut mcp = state.mcp_server.write().await;
     *mcp = Some(handle);
 
-    Ok(format!("mcp_started: http://{addr}"))
+    Ok(format!("mcp_started: {url}"))
 }
 
 #[tauri::command]
@@ -1458,48 +3546,217 @@ async fn mcp_stop(state: State<'_, AppState>) -> Result<String, String> {
 }
 
 #[tauri::command]
-async fn mcp_status(state: State<'_, AppState>) -> Result<serde_json::Value, String> {
+async fn mcp_status(state: State<'_, AppState>) -> Result<McpStatus, String> {
     let mcp = state.mcp_server.read().await;
     if let Some(handle) = mcp.as_ref() {
-        Ok(serde_json::json!({
-            "running": true,
-            "addr": handle.addr().to_string(),
-            "password_enabled": handle.password_enabled(),
-        }))
+        let now = chrono::Utc::now();
+        let active_tokens = state
+            .mcp_tokens
+            .list()
+            .iter()
+            .filter(|t| t.active(now))
+            .count();
+        Ok(McpStatus {
+            running: true,
+            addr: Some(handle.addr().to_string()),
+            url: Some(handle.url()),
+            tls: handle.tls().cloned(),
+            password_enabled: Some(handle.password_enabled()),
+            active_tokens: Some(active_tokens),
+            streaming_enabled: Some(handle.password_enabled() || state.mcp_tokens.has_tokens()),
+        })
     } else {
-        Ok(serde_json::json!({"running": false}))
+        Ok(McpStatus::default())
+    }
+}
+
+/// Issue a bearer token for one MCP client. The secret is only returned here.
+#[tauri::command]
+async fn create_mcp_token(
+    client: String,
+    scope: McpScope,
+    ttl_days: Option<u32>,
+    session_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<IssuedMcpToken, String> {
+    let system = state
+        .oxide_system
+        .read()
+        .await
+        .clone()
+        .ok_or(i18n::t("error.system_not_initialized"))?;
+    command_guard::authorize_command(&system, session_id.as_deref(), "create_mcp_token").await?;
+    let issued = state.mcp_tokens.issue(&client, scope, ttl_days)?;
+    info!(
+        "Issued MCP token {} for {}",
+        issued.token.id, issued.token.client
+    );
+    Ok(issued)
+}
+
+#[tauri::command]
+async fn list_mcp_tokens(state: State<'_, AppState>) -> Result<Vec<McpToken>, String> {
+    Ok(state.mcp_tokens.list())
+}
+
+/// New secret for an MCP token; the old one works for a short grace period.
+#[tauri::command]
+async fn rotate_mcp_token(
+    id: String,
+    ttl_days: Option<u32>,
+    session_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<IssuedMcpToken, String> {
+    let system = state
+        .oxide_system
+        .read()
+        .await
+        .clone()
+        .ok_or(i18n::t("error.system_not_initialized"))?;
+    command_guard::authorize_command(&system, session_id.as_deref(), "rotate_mcp_token").await?;
+    let rotated = state.mcp_tokens.rotate(&id, ttl_days)?;
+    info!("Rotated MCP token {id}");
+    Ok(rotated)
+}
+
+#[tauri::command]
+async fn revoke_mcp_token(
+    id: String,
+    session_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<McpToken, String> {
+    let system = state
+        .oxide_system
+        .read()
+        .await
+        .clone()
+        .ok_or(i18n::t("error.system_not_initialized"))?;
+    command_guard::authorize_command(&system, session_id.as_deref(), "revoke_mcp_token").await?;
+    let revoked = state.mcp_tokens.revoke(&id)?;
+    info!("Revoked MCP token {id}");
+    Ok(revoked)
+}
+
+/// Start the local API from the configured port and token, replacing a
+/// running instance.
+async fn start_local_api(
+    state: &AppState,
+    system: OxideSystem,
+    port_override: Option<u16>,
+    token_override: Option<String>,
+) -> Result<String, String> {
+    if let Some(mut handle) = state.local_api.write().await.take() {
+        handle.stop().await;
+    }
+    let from_cfg = system.get_config().await.local_api;
+    let port = port_override
+        .or_else(|| from_cfg.as_ref().map(|c| c.port))
+        .unwrap_or(local_api::DEFAULT_PORT);
+    let token = match (token_override, from_cfg.and_then(|c| c.token)) {
+        (Some(token), _) => token,
+        (None, Some(enc)) => {
+            let bytes = system
+                .decrypt_data(&enc)
+                .map_err(|e| format!("Failed to decrypt local API token: {e}"))?;
+            String::from_utf8(bytes)
+                .map_err(|_| "Decrypted local API token is not valid UTF-8".to_string())?
+        }
+        (None, None) => return Err("Set a local API token first".to_string()),
+    };
+    let handle = LocalApiHandle::start(port, token, system)
+        .await
+        .map_err(|e| e.to_string())?;
+    let addr = handle.addr();
+    *state.local_api.write().await = Some(handle);
+    Ok(format!("http://{addr}/v1"))
+}
+
+/// Start the OpenAI-compatible API; returns its base URL.
+#[tauri::command]
+async fn local_api_start(
+    state: State<'_, AppState>,
+    port_override: Option<u16>,
+    token_override: Option<String>,
+    session_id: Option<String>,
+) -> Result<String, String> {
+    let system = state
+        .oxide_system
+        .read()
+        .await
+        .clone()
+        .ok_or_else(|| i18n::t("error.system_not_initialized").to_string())?;
+    command_guard::authorize_command(&system, session_id.as_deref(), "local_api_start").await?;
+    start_local_api(&state, system, port_override, token_override).await
+}
+
+#[tauri::command]
+async fn local_api_stop(
+    state: State<'_, AppState>,
+    session_id: Option<String>,
+) -> Result<bool, String> {
+    let system = state
+        .oxide_system
+        .read()
+        .await
+        .clone()
+        .ok_or_else(|| i18n::t("error.system_not_initialized").to_string())?;
+    command_guard::authorize_command(&system, session_id.as_deref(), "local_api_stop").await?;
+    match state.local_api.write().await.take() {
+        Some(mut handle) => {
+            handle.stop().await;
+            Ok(true)
+        }
+        None => Ok(false),
     }
 }
 
+#[tauri::command]
+async fn local_api_status(state: State<'_, AppState>) -> Result<LocalApiStatus, String> {
+    let local_api = state.local_api.read().await;
+    Ok(LocalApiStatus {
+        running: local_api.is_some(),
+        base_url: local_api
+            .as_ref()
+            .map(|handle| format!("http://{}/v1", handle.addr())),
+    })
+}
+
 fn main() {
     // Load environment variables from .env file
     dotenv::dotenv().ok();
 
-    // Initialize logging
-    env_logger::init();
+    // Initialize logging: RUST_LOG still drives the console, while rotating
+    // JSON files and the in-app viewer get OXIDE_LOG_LEVEL (default info)
+    let console = env_logger::Builder::from_default_env().build();
+    let console_level = console.filter();
+    if let Err(e) = logging::init(
+        logging::LogOptions::from_env(),
+        Some((Box::new(console), console_level)),
+    ) {
+        eprintln!("Failed to initialize logging: {e}");
+    }
+    // After logging, so crash reports include the recent log lines
+    crash::install(crash::CRASH_DIR);
 
     info!("Starting Oxide Pilot Application");
+    // OS language until the config is loaded
+    i18n::set_locale(i18n::Locale::resolve(None));
 
-    // Initialize Guardian backend if feature is enabled
+    // One SurrealDB handle for the whole app, opened by the startup pipeline:
+    // embedded RocksDB allows a single handle per directory
     #[cfg(feature = "surrealdb-metrics")]
-    let surreal_backend: Arc<SurrealBackend> = {
-        let db_path =
-            std::env::var("OXIDE_DB_PATH").unwrap_or_else(|_| "./data/oxide.db".to_string());
-
-        let backend = tokio::runtime::Runtime::new()
-            .expect("Failed to create runtime")
-            .block_on(async {
-                SurrealBackend::new(&db_path)
-                    .await
-                    .expect("Failed to initialize SurrealDB backend")
-            });
-
-        Arc::new(backend)
+    let shared_backend = {
+        // OXIDE_SURREAL_URL selects a remote server or TiKV cluster
+        let connection = oxide_memory::SurrealConnection::from_env("./data/oxide.db");
+        // The config's profile is applied later by OxideSystem::new
+        let namespace = profile::resolve(None, Path::new(profile::DATA_ROOT)).surreal_namespace();
+        Arc::new(SharedSurrealBackend::new(connection, namespace))
     };
 
-    #[cfg(feature = "surrealdb-metrics")]
-    let guardian_state =
-        Arc::new(guardian_commands::GuardianState { backend: surreal_backend.clone() });
+    let guardian_state = guardian_commands::GuardianState {
+        #[cfg(feature = "surrealdb-metrics")]
+        backend: shared_backend.clone(),
+    };
 
     // Initialize Security Diagnostic State
     let security_diagnostic_state = security_diagnostic::SecurityDiagnosticState::new();
@@ -1509,15 +3766,29 @@ fn main() {
             oxide_system: Arc::new(RwLock::new(None)),
             auth_manager: Arc::new(RwLock::new(None)),
             mcp_server: Arc::new(RwLock::new(None)),
-            folder_scan_cancels: Arc::new(RwLock::new(HashMap::new())),
+            mcp_tokens: Arc::new(McpTokenStore::load(mcp_tokens::TOKENS_PATH)),
+            remote_hosts: Arc::new(remote::RemoteHosts::load(remote::HOSTS_PATH)),
+            local_api: Arc::new(RwLock::new(None)),
             rpa_state: Arc::new(RwLock::new(None)),
             #[cfg(feature = "surrealdb-metrics")]
-            guardian_state,
-            #[cfg(feature = "surrealdb-metrics")]
-            surreal_backend,
+            shared_backend,
             security_diagnostic_state: Arc::new(security_diagnostic_state),
+            jobs: Arc::new(job_manager::JobManager::new(
+                job_manager::DEFAULT_MAX_CONCURRENT_JOBS,
+                Some(PathBuf::from(job_manager::DEFAULT_JOBS_PATH)),
+            )),
+            startup: Arc::new(RwLock::new(startup::StartupStatus::default())),
+            hotkeys: Arc::new(hotkey_manager::HotkeyManager::default()),
         })
         .manage(security_diagnostic::SecurityDiagnosticState::new())
+        .manage(guardian_state)
+        .setup(|app| {
+            // Open the window first; slow initialization reports progress
+            // through startup://status
+            let state = app.state::<AppState>().inner().clone();
+            tauri::async_runtime::spawn(startup::run(app.handle(), state));
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             send_notification,
             set_google_api_key,
@@ -1528,10 +3799,56 @@ fn main() {
             check_auth_from_env,
             initialize_system,
             handle_user_input_command,
+            run_slash_command,
+            handle_user_input_with_images,
             run_collaborative_analysis,
+            get_orchestrator_config,
+            execute_plan,
             run_multi_agent_analysis,
             run_threat_consensus,
+            start_collaborative_analysis,
+            start_multi_agent_analysis,
+            start_threat_consensus,
+            get_job,
+            list_jobs,
+            cancel_job,
             get_threat_recommendations,
+            get_consensus_history,
+            query_llm_audit,
+            get_pending_remediations,
+            respond_remediation,
+            get_remediation_history,
+            get_startup_items,
+            get_boot_impact,
+            kill_process,
+            suspend_process,
+            resume_process,
+            view_baseline_drift,
+            record_baseline,
+            get_integrity_status,
+            record_integrity_manifest,
+            import_ioc_feed,
+            block_process_network,
+            list_oxide_firewall_rules,
+            remove_rule,
+            list_playbooks,
+            run_playbook,
+            evidence::collect_evidence,
+            updates::check_for_updates,
+            crash_reports::list_crash_reports,
+            crash_reports::get_crash_report,
+            crash_reports::upload_crash_report,
+            cleanup::preview_cleanup,
+            cleanup::run_cleanup,
+            cleanup::list_cleanup_runs,
+            cleanup::undo_cleanup,
+            cleanup::purge_cleanup_run,
+            updates::install_update,
+            get_heuristic_rules,
+            reload_heuristic_rules,
+            test_heuristic_rules,
+            scan_process_memory,
+            disable_startup_item,
             get_system_status,
             scan_file_command,
             start_folder_scan,
@@ -1539,8 +3856,30 @@ fn main() {
             is_virustotal_configured,
             get_threat_history,
             get_memory_stats,
+            get_user_patterns,
+            get_suggestions,
+            accept_suggestion,
+            dismiss_suggestion,
+            get_attack_matrix_summary,
             update_system_config,
+            set_offline_mode,
+            get_locale,
+            set_locale,
+            get_voice_language,
+            set_voice_language,
+            list_prompt_templates,
+            reload_prompt_templates,
+            get_network_status,
+            get_api_quota_status,
+            get_llm_cache_stats,
+            clear_llm_cache,
+            validate_config,
             get_system_config,
+            get_event_schema,
+            get_capabilities,
+            get_hotkeys,
+            update_hotkeys,
+            panic_stop,
             record_audio,
             play_audio,
             get_audio_devices,
@@ -1548,18 +3887,30 @@ fn main() {
             get_performance_metrics,
             get_performance_score,
             optimize_performance,
+            analyze_disk_usage,
             get_error_statistics,
             get_recent_errors,
+            get_recent_logs,
+            startup::get_startup_status,
+            onboarding::get_onboarding_state,
+            onboarding::complete_onboarding_step,
+            list_profiles,
+            switch_profile,
             // get_performance_alerts, // TODO: Implement missing methods
             clear_performance_alerts,
-            // get_operation_profiles, // TODO: Implement missing methods
+            get_operation_profiles,
             set_performance_monitoring,
             validate_input,
             create_security_session,
             validate_security_session,
             check_security_permission,
+            get_session_permissions,
+            assign_security_role,
+            revoke_security_role,
+            get_security_role_assignments,
             get_security_events,
             get_security_policy,
+            set_command_permission_enforcement,
             check_rate_limit,
             initialize_auth_manager,
             get_auth_token,
@@ -1590,10 +3941,32 @@ fn main() {
             mcp_start,
             mcp_stop,
             mcp_status,
+            create_mcp_token,
+            list_mcp_tokens,
+            rotate_mcp_token,
+            revoke_mcp_token,
+            remote::probe_remote_host,
+            remote::add_remote_host,
+            remote::list_remote_hosts,
+            remote::remove_remote_host,
+            remote::get_remote_status,
+            remote::remote_scan_file,
+            remote::remote_respond_remediation,
+            local_api_start,
+            local_api_stop,
+            local_api_status,
+            test_webhooks,
+            test_alert_email,
+            set_smtp_password,
+            set_webhook_secret,
             // RPA Commands
             rpa_commands::rpa_initialize,
             rpa_commands::rpa_shutdown,
             rpa_commands::rpa_grant_permission,
+            rpa_commands::rpa_grant_profile,
+            rpa_commands::rpa_revoke_grant,
+            rpa_commands::rpa_list_grants,
+            rpa_commands::rpa_set_target_application,
             rpa_commands::rpa_check_permission,
             rpa_commands::rpa_move_mouse,
             rpa_commands::rpa_click_mouse,
@@ -1601,12 +3974,23 @@ fn main() {
             rpa_commands::rpa_type_text,
             rpa_commands::rpa_press_key,
             rpa_commands::rpa_capture_screen,
+            rpa_commands::rpa_read_screen_text,
+            rpa_commands::rpa_verify_screen_text,
+            rpa_commands::rpa_resume,
+            rpa_commands::rpa_move_file,
+            rpa_commands::rpa_set_clipboard,
+            rpa_commands::rpa_list_windows,
+            rpa_commands::rpa_window_action,
+            rpa_commands::rpa_move_window,
             rpa_commands::rpa_get_audit_entries,
             rpa_commands::rpa_get_audit_stats,
             rpa_commands::rpa_get_failed_actions,
             rpa_commands::rpa_get_rollback_history,
             rpa_commands::rpa_rollback_last,
             rpa_commands::rpa_get_reversible_count,
+            rpa_commands::rpa_create_checkpoint,
+            rpa_commands::rpa_list_checkpoints,
+            rpa_commands::rpa_rollback_to,
             rpa_commands::rpa_get_pending_confirmations,
             rpa_commands::rpa_respond_confirmation,
             rpa_commands::rpa_add_auto_approve,
@@ -1616,19 +4000,50 @@ fn main() {
             guardian_commands::get_metrics_summary,
             guardian_commands::get_hourly_metrics,
             guardian_commands::get_process_hotspots,
+            guardian_commands::get_process_trend,
+            guardian_commands::get_suspected_leaks,
             guardian_commands::get_high_cpu_processes,
             guardian_commands::search_agent_memory,
             guardian_commands::get_guardian_status,
             guardian_commands::predict_threat_risk,
             guardian_commands::submit_threat_training_sample,
+            guardian_commands::label_threat,
+            guardian_commands::retrain_threat_model,
+            guardian_commands::get_threat_model_evaluation,
+            guardian_commands::get_fleet_uplink_status,
             guardian_commands::subscribe_guardian_metrics,
+            guardian_commands::run_memory_benchmark,
+            guardian_commands::backup_database,
+            guardian_commands::restore_database,
+            guardian_commands::compact_database,
+            guardian_commands::rebuild_vector_index,
+            guardian_commands::export_agent_memory,
+            guardian_commands::import_agent_memory,
+            guardian_commands::migrate_memory_backend,
+            guardian_commands::list_agent_memories,
+            guardian_commands::delete_agent_memory,
+            guardian_commands::consolidate_agent_memory,
+            guardian_commands::list_threats,
+            guardian_commands::update_threat_status,
+            guardian_commands::bulk_acknowledge_threats,
+            guardian_commands::list_scan_history,
+            guardian_commands::get_scan_details,
+            guardian_commands::diff_scans,
+            protection_status::get_protection_status,
             // Security Diagnostic Commands
             security_diagnostic::run_security_diagnostic,
             security_diagnostic::get_last_security_scan,
-            security_diagnostic::get_system_health
+            security_diagnostic::get_system_health,
+            self_diagnostics::run_self_diagnostics
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|_, event| {
+            // Anything else ending the process is reported on the next start
+            if let tauri::RunEvent::Exit = event {
+                integrity::mark_clean_shutdown(HEARTBEAT_PATH);
+            }
+        });
 }
 
 #[tauri::command]
@@ -1,20 +1,288 @@
+use crate::oxide_system::OxideSystem;
+use crate::protection_status::ProtectionStatus;
 use axum::{
     body::Body,
-    http::{header::AUTHORIZATION, Request, StatusCode},
+    extract::{Path, Query, State},
+    http::{header::AUTHORIZATION, Method, Request, StatusCode},
     middleware::Next,
-    response::{IntoResponse, Response},
-    routing::get,
-    Router,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    routing::{get, post},
+    Extension, Json, Router,
 };
+use chrono::Utc;
+use futures_util::stream::{self, BoxStream, StreamExt};
+use log::{info, warn};
+use oxide_core::mcp_tokens::{AuthError, McpScope, McpTokenStore};
+use oxide_core::power;
+use oxide_core::tls::TlsIdentity;
+use oxide_guardian::guardian::{SystemStatus, ThreatEvent};
+use oxide_guardian::remediation::RemediationAction;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::convert::Infallible;
 use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::{self, error::RecvError};
 use tokio::{sync::oneshot, task::JoinHandle};
 
+type EventStream = BoxStream<'static, Result<Event, Infallible>>;
+
+/// Feeds served as Server-Sent Events under `/stream` for external
+/// dashboards. Streaming needs authentication: browsers' `EventSource`
+/// cannot send headers, so the token is also accepted as `?access_token=`.
+/// With the `prometheus` feature, `/metrics` is served from the same state.
+#[derive(Clone)]
+struct ServerState {
+    system: Option<OxideSystem>,
+    /// Legacy shared password; grants every scope
+    password: Option<String>,
+    tokens: Arc<McpTokenStore>,
+}
+
+impl ServerState {
+    /// Requests need a password or token once either was ever set up.
+    fn auth_enabled(&self) -> bool {
+        self.password.is_some() || self.tokens.has_tokens()
+    }
+
+    /// The authenticated caller, named after its token's client.
+    fn authorize(&self, token: Option<&str>, required: McpScope) -> Result<Caller, Response> {
+        if !self.auth_enabled() {
+            return Ok(Caller("local".to_string()));
+        }
+        let Some(token) = token else {
+            return Err((StatusCode::UNAUTHORIZED, "Unauthorized").into_response());
+        };
+        if self.password.as_deref() == Some(token) {
+            return Ok(Caller("password".to_string()));
+        }
+        match self.tokens.authenticate(token, required) {
+            Ok(token) => Ok(Caller(token.client)),
+            Err(AuthError::Forbidden) => Err((
+                StatusCode::FORBIDDEN,
+                "Token scope does not allow this request",
+            )
+                .into_response()),
+            Err(AuthError::Expired) => {
+                Err((StatusCode::UNAUTHORIZED, "Token expired").into_response())
+            }
+            Err(AuthError::Unknown | AuthError::Revoked) => {
+                Err((StatusCode::UNAUTHORIZED, "Unauthorized").into_response())
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Caller(String);
+
+/// Reads and streams are read-only; anything else executes tools.
+fn required_scope(method: &Method) -> McpScope {
+    if *method == Method::GET || *method == Method::HEAD {
+        McpScope::ReadOnly
+    } else {
+        McpScope::ToolExecution
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Feed {
+    Metrics,
+    Threats,
+    All,
+}
+
+/// One SSE event per broadcast item, named `event`. A `lagged` event
+/// reports how many items a slow client missed.
+fn sse_events<T>(receiver: broadcast::Receiver<T>, event: &'static str) -> EventStream
+where
+    T: Serialize + Clone + Send + 'static,
+{
+    stream::unfold(receiver, move |mut receiver| async move {
+        let sse = match receiver.recv().await {
+            Ok(item) => Event::default()
+                .event(event)
+                .json_data(&item)
+                .unwrap_or_else(|e| Event::default().comment(format!("serialization failed: {e}"))),
+            Err(RecvError::Lagged(skipped)) => {
+                Event::default().event("lagged").data(skipped.to_string())
+            }
+            Err(RecvError::Closed) => return None,
+        };
+        Some((Ok(sse), receiver))
+    })
+    .boxed()
+}
+
+fn feed_streams(system: &OxideSystem, feed: Feed) -> Vec<EventStream> {
+    let mut streams = Vec::new();
+    #[cfg(feature = "surrealdb-metrics")]
+    if feed != Feed::Threats {
+        if let Some(receiver) = system.subscribe_metrics() {
+            streams.push(sse_events(receiver, "metric"));
+        }
+    }
+    if feed != Feed::Metrics {
+        streams.push(sse_events(system.subscribe_threats(), "threat"));
+    }
+    streams
+}
+
+async fn stream_feed(state: ServerState, feed: Feed) -> Response {
+    if !state.auth_enabled() {
+        return (
+            StatusCode::FORBIDDEN,
+            "Set an MCP password or create an MCP token to enable event streaming",
+        )
+            .into_response();
+    }
+    let Some(system) = state.system else {
+        return (StatusCode::SERVICE_UNAVAILABLE, "System not initialized").into_response();
+    };
+    let streams = feed_streams(&system, feed);
+    if streams.is_empty() {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Metrics streaming requires the SurrealDB backend",
+        )
+            .into_response();
+    }
+    Sse::new(stream::select_all(streams))
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+/// What another instance in remote mode shows for this one.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RemoteStatus {
+    pub hostname: Option<String>,
+    pub version: String,
+    pub protection: ProtectionStatus,
+    pub system: SystemStatus,
+    /// Newest first
+    pub recent_threats: Vec<ThreatEvent>,
+    pub pending_remediations: Vec<RemediationAction>,
+}
+
+/// Threats included in a [`RemoteStatus`].
+const REMOTE_THREATS: usize = 50;
+
+#[derive(Deserialize)]
+struct RemoteScanRequest {
+    path: String,
+    #[serde(default)]
+    use_cloud: bool,
+    #[serde(default)]
+    quarantine: bool,
+}
+
+#[derive(Deserialize)]
+struct RemoteRemediationRequest {
+    approved: bool,
+    reason: Option<String>,
+}
+
+/// The system behind remote management routes; like streaming, these are
+/// refused while the server runs without authentication.
+fn remote_system(state: ServerState) -> Result<OxideSystem, Response> {
+    if !state.auth_enabled() {
+        return Err((
+            StatusCode::FORBIDDEN,
+            "Set an MCP password or create an MCP token to enable remote management",
+        )
+            .into_response());
+    }
+    state
+        .system
+        .ok_or_else(|| (StatusCode::SERVICE_UNAVAILABLE, "System not initialized").into_response())
+}
+
+async fn remote_status(State(state): State<ServerState>) -> Response {
+    let system = match remote_system(state) {
+        Ok(system) => system,
+        Err(rejection) => return rejection,
+    };
+    let mut recent_threats = system.get_threat_history();
+    recent_threats.reverse();
+    recent_threats.truncate(REMOTE_THREATS);
+    Json(RemoteStatus {
+        hostname: sysinfo::System::host_name(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        protection: ProtectionStatus::new(
+            system.guardian_protection(),
+            system.has_virustotal_key().await,
+            None,
+            system.task_health(),
+            power::policy(),
+            Utc::now(),
+        ),
+        system: system.get_system_status(),
+        recent_threats,
+        pending_remediations: system.pending_remediations(),
+    })
+    .into_response()
+}
+
+async fn remote_scan(
+    State(state): State<ServerState>,
+    Extension(caller): Extension<Caller>,
+    Json(request): Json<RemoteScanRequest>,
+) -> Response {
+    let system = match remote_system(state) {
+        Ok(system) => system,
+        Err(rejection) => return rejection,
+    };
+    info!("Remote scan of {} requested by {}", request.path, caller.0);
+    match system
+        .scan_file(request.path, request.use_cloud, request.quarantine)
+        .await
+    {
+        Ok(report) => Json(report).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, e).into_response(),
+    }
+}
+
+async fn remote_remediation(
+    State(state): State<ServerState>,
+    Extension(caller): Extension<Caller>,
+    Path(action_id): Path<String>,
+    Json(request): Json<RemoteRemediationRequest>,
+) -> Response {
+    let system = match remote_system(state) {
+        Ok(system) => system,
+        Err(rejection) => return rejection,
+    };
+    info!(
+        "Remote {} of remediation {action_id} by {}",
+        if request.approved {
+            "approval"
+        } else {
+            "rejection"
+        },
+        caller.0
+    );
+    let reason = Some(match request.reason {
+        Some(reason) => format!("{reason} (remote: {})", caller.0),
+        None => format!("Remote decision by {}", caller.0),
+    });
+    match system
+        .respond_remediation(action_id, request.approved, reason)
+        .await
+    {
+        Ok(action) => Json(action).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, e).into_response(),
+    }
+}
+
 #[derive(Clone)]
-#[allow(dead_code)] // Reserved for future use
 pub struct McpServerConfig {
     pub addr: SocketAddr,
     pub password: Option<String>,
+    pub tls: Option<TlsIdentity>,
 }
 
 pub struct McpServerHandle {
@@ -22,65 +290,182 @@ pub struct McpServerHandle {
     _shutdown: Option<oneshot::Sender<()>>,
     task: Option<JoinHandle<()>>,
     password_set: bool,
+    tls: Option<TlsStatus>,
+}
+
+/// What clients need to verify the server's certificate.
+#[derive(Serialize, Clone)]
+pub struct TlsStatus {
+    /// SHA-256 of the certificate, colon-separated hex
+    pub fingerprint: String,
+    pub self_signed: bool,
+    pub cert_path: String,
+}
+
+/// What `mcp_status` reports; only `running` is set while stopped.
+#[derive(Serialize, Default)]
+pub struct McpStatus {
+    pub running: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub addr: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls: Option<TlsStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active_tokens: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub streaming_enabled: Option<bool>,
 }
 
 #[allow(dead_code)] // Some methods reserved for future use
+/// Prometheus scrape target; host gauges are refreshed on every scrape.
+#[cfg(feature = "prometheus")]
+async fn prometheus_metrics(State(state): State<ServerState>) -> Response {
+    use axum::http::header::CONTENT_TYPE;
+    use oxide_core::prometheus;
+
+    if let Some(system) = &state.system {
+        let status = system.get_system_status();
+        prometheus::CPU_USAGE_PERCENT.set(&[], f64::from(status.cpu_usage));
+        prometheus::MEMORY_USED_BYTES.set(&[], status.memory_usage.0 as f64);
+        prometheus::MEMORY_TOTAL_BYTES.set(&[], status.memory_usage.1 as f64);
+        prometheus::PROCESS_COUNT.set(&[], status.process_count as f64);
+    }
+    let _ = tokio::task::spawn_blocking(prometheus::refresh_disk_gauges).await;
+    (
+        [(CONTENT_TYPE, prometheus::CONTENT_TYPE)],
+        prometheus::render(),
+    )
+        .into_response()
+}
+
 impl McpServerHandle {
     pub async fn start(
-        port: u16,
-        password: Option<String>,
+        config: McpServerConfig,
+        tokens: Arc<McpTokenStore>,
+        system: Option<OxideSystem>,
     ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
-        let addr: SocketAddr = SocketAddr::from(([127, 0, 0, 1], port));
+        let McpServerConfig {
+            addr,
+            password,
+            tls,
+        } = config;
         let (tx, rx) = oneshot::channel::<()>();
 
-        // Build router with simple auth middleware wrapper
-        let pwd = password.clone();
+        let password_set = password.is_some();
+        let streams = ServerState {
+            system,
+            password,
+            tokens,
+        };
+        let auth = streams.clone();
         let app = Router::new()
             .route("/health", get(|| async { "ok" }))
             .route("/", get(|| async { "Oxide MCP server running" }))
-            .layer(axum::middleware::from_fn(
-                move |req: Request<Body>, next: Next| {
-                    let pwd = pwd.clone();
-                    async move {
-                        // If a password is configured, enforce simple Bearer auth
-                        if let Some(expected) = pwd.as_ref() {
-                            let authorized = req
-                                .headers()
-                                .get(AUTHORIZATION)
-                                .and_then(|h| h.to_str().ok())
-                                .map(|v| v.trim())
-                                .filter(|v| v.starts_with("Bearer "))
-                                .map(|v| v.trim_start_matches("Bearer ").to_string())
-                                .map(|token| token == *expected)
-                                .unwrap_or(false);
-                            if !authorized {
-                                return Ok::<Response, Infallible>(
-                                    (StatusCode::UNAUTHORIZED, "Unauthorized").into_response(),
-                                );
-                            }
+            .route(
+                "/stream",
+                get(|State(state): State<ServerState>| stream_feed(state, Feed::All)),
+            )
+            .route(
+                "/stream/metrics",
+                get(|State(state): State<ServerState>| stream_feed(state, Feed::Metrics)),
+            )
+            .route(
+                "/stream/threats",
+                get(|State(state): State<ServerState>| stream_feed(state, Feed::Threats)),
+            )
+            .route("/remote/status", get(remote_status))
+            .route("/remote/scan", post(remote_scan))
+            .route("/remote/remediation/:action_id", post(remote_remediation));
+        #[cfg(feature = "prometheus")]
+        let app = app.route("/metrics", get(prometheus_metrics));
+        let app = app.with_state(streams).layer(axum::middleware::from_fn(
+            move |req: Request<Body>, next: Next| {
+                let auth = auth.clone();
+                async move {
+                    let mut req = req;
+                    let bearer = req
+                        .headers()
+                        .get(AUTHORIZATION)
+                        .and_then(|h| h.to_str().ok())
+                        .map(|v| v.trim())
+                        .filter(|v| v.starts_with("Bearer "))
+                        .map(|v| v.trim_start_matches("Bearer ").to_string());
+                    let token = bearer.or_else(|| {
+                        Query::<HashMap<String, String>>::try_from_uri(req.uri())
+                            .ok()
+                            .and_then(|Query(mut params)| params.remove("access_token"))
+                    });
+                    match auth.authorize(token.as_deref(), required_scope(req.method())) {
+                        Ok(caller) => {
+                            req.extensions_mut().insert(caller);
+                        }
+                        Err(rejection) => {
+                            warn!(
+                                "Rejected MCP request to {} ({})",
+                                req.uri().path(),
+                                rejection.status()
+                            );
+                            return Ok::<Response, Infallible>(rejection);
                         }
-                        let res = next.run(req).await;
-                        Ok::<Response, Infallible>(res)
                     }
-                },
-            ));
+                    let res = next.run(req).await;
+                    Ok::<Response, Infallible>(res)
+                }
+            },
+        ));
 
-        let listener = tokio::net::TcpListener::bind(addr).await?;
-        let server = axum::serve(listener, app).with_graceful_shutdown(async move {
-            let _ = rx.await;
-        });
+        let tls_config = tls.as_ref().map(TlsIdentity::server_config).transpose()?;
+        let tls_status = match &tls {
+            Some(identity) => Some(TlsStatus {
+                fingerprint: identity.fingerprint()?,
+                self_signed: identity.self_signed,
+                cert_path: identity.cert_path.display().to_string(),
+            }),
+            None => None,
+        };
 
-        let handle: JoinHandle<()> = tokio::spawn(async move {
-            if let Err(err) = server.await {
-                eprintln!("MCP HTTP server error: {err}");
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        let handle: JoinHandle<()> = match tls_config {
+            Some(config) => {
+                let server_handle = axum_server::Handle::new();
+                let shutdown = server_handle.clone();
+                tokio::spawn(async move {
+                    let _ = rx.await;
+                    shutdown.graceful_shutdown(Some(Duration::from_secs(5)));
+                });
+                let server = axum_server::from_tcp_rustls(
+                    listener.into_std()?,
+                    axum_server::tls_rustls::RustlsConfig::from_config(config),
+                )
+                .handle(server_handle);
+                tokio::spawn(async move {
+                    if let Err(err) = server.serve(app.into_make_service()).await {
+                        eprintln!("MCP HTTPS server error: {err}");
+                    }
+                })
             }
-        });
+            None => {
+                let server = axum::serve(listener, app).with_graceful_shutdown(async move {
+                    let _ = rx.await;
+                });
+                tokio::spawn(async move {
+                    if let Err(err) = server.await {
+                        eprintln!("MCP HTTP server error: {err}");
+                    }
+                })
+            }
+        };
 
         Ok(Self {
             addr,
             _shutdown: Some(tx),
             task: Some(handle),
-            password_set: password.is_some(),
+            password_set,
+            tls: tls_status,
         })
     }
 
@@ -93,6 +478,13 @@ impl McpServerHandle {
     pub fn password_enabled(&self) -> bool {
         self.password_set
     }
+    pub fn url(&self) -> String {
+        let scheme = if self.tls.is_some() { "https" } else { "http" };
+        format!("{scheme}://{}", self.addr)
+    }
+    pub fn tls(&self) -> Option<&TlsStatus> {
+        self.tls.as_ref()
+    }
 
     pub async fn stop(&mut self) {
         if let Some(tx) = self._shutdown.take() {
@@ -0,0 +1,180 @@
+//! Background consolidation of old agent memories.
+//!
+//! Every `interval_hours` the raw memories older than `min_age_days` are
+//! grouped by agent and source, clustered by embedding similarity, and each
+//! cluster is replaced by one LLM-written summary that lists the ids it came
+//! from. The originals are archived or deleted per
+//! [`MemoryConsolidationConfig::originals`].
+
+use crate::oxide_system::OxideSystem;
+use chrono::{Duration, Utc};
+use log::{info, warn};
+use oxide_core::config::MemoryConsolidationConfig;
+use oxide_memory::consolidation::{centroid, cluster_by_similarity, summary_prompt};
+use oxide_memory::{AgentMemory, ConsolidationCandidate, SurrealBackend};
+use serde::Serialize;
+use serde_json::json;
+use std::collections::BTreeMap;
+
+const DEFAULT_INTERVAL_HOURS: u64 = 24;
+const DEFAULT_MIN_AGE_DAYS: u32 = 30;
+const DEFAULT_SIMILARITY: f64 = 0.8;
+const DEFAULT_MAX_CLUSTER: usize = 20;
+/// Memories examined per run; the rest wait for the next one
+const MAX_CANDIDATES: usize = 2_000;
+/// Failed summaries after which a run gives up, e.g. when offline
+const MAX_FAILURES: usize = 3;
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct ConsolidationReport {
+    pub examined: usize,
+    /// Summaries written
+    pub summaries: usize,
+    /// Raw memories archived or deleted
+    pub consolidated: usize,
+    pub errors: Vec<String>,
+    pub duration_ms: u64,
+}
+
+/// Run one consolidation pass.
+pub async fn consolidate(
+    system: &OxideSystem,
+    backend: &SurrealBackend,
+    cfg: &MemoryConsolidationConfig,
+) -> Result<ConsolidationReport, String> {
+    let started = std::time::Instant::now();
+    let cutoff =
+        Utc::now() - Duration::days(i64::from(cfg.min_age_days.unwrap_or(DEFAULT_MIN_AGE_DAYS)));
+    let candidates = backend
+        .consolidation_candidates(cutoff, MAX_CANDIDATES)
+        .await
+        .map_err(|e| format!("Failed to load memories for consolidation: {e:#}"))?;
+    let mut report = ConsolidationReport {
+        examined: candidates.len(),
+        ..Default::default()
+    };
+
+    // Summaries never mix agents or sources
+    let mut groups: BTreeMap<String, Vec<ConsolidationCandidate>> = BTreeMap::new();
+    for candidate in candidates {
+        let key = format!("{:?}/{:?}", candidate.agent_type, candidate.source);
+        groups.entry(key).or_default().push(candidate);
+    }
+
+    'groups: for members in groups.values() {
+        let embeddings: Vec<Vec<f64>> = members.iter().map(|m| m.embedding.clone()).collect();
+        let clusters = cluster_by_similarity(
+            &embeddings,
+            cfg.similarity_threshold.unwrap_or(DEFAULT_SIMILARITY),
+            cfg.max_cluster_size.unwrap_or(DEFAULT_MAX_CLUSTER),
+        );
+        for cluster in clusters.iter().filter(|c| c.len() >= 2) {
+            let cluster: Vec<&ConsolidationCandidate> =
+                cluster.iter().map(|&i| &members[i]).collect();
+            match summarize_cluster(system, backend, &cluster, cfg).await {
+                Ok(()) => {
+                    report.summaries += 1;
+                    report.consolidated += cluster.len();
+                }
+                Err(e) => {
+                    report.errors.push(e);
+                    if report.errors.len() >= MAX_FAILURES {
+                        break 'groups;
+                    }
+                }
+            }
+        }
+    }
+
+    report.duration_ms = started.elapsed().as_millis() as u64;
+    info!(
+        "Memory consolidation: {} memories folded into {} summaries ({} examined)",
+        report.consolidated, report.summaries, report.examined
+    );
+    Ok(report)
+}
+
+async fn summarize_cluster(
+    system: &OxideSystem,
+    backend: &SurrealBackend,
+    cluster: &[&ConsolidationCandidate],
+    cfg: &MemoryConsolidationConfig,
+) -> Result<(), String> {
+    let contents: Vec<&str> = cluster.iter().map(|m| m.content.as_str()).collect();
+    let summary = system
+        .summarize_incident(&summary_prompt(&contents))
+        .await
+        .map_err(|e| format!("Summary failed: {e}"))?;
+    let summary = summary.trim().to_string();
+    if summary.is_empty() {
+        return Err("The AI provider returned an empty summary".to_string());
+    }
+
+    // Without an embedding provider the summary sits at its members' centre
+    let mut embedding = backend.embed_text(&summary).await?;
+    if embedding.iter().all(|v| *v == 0.0) {
+        let members: Vec<&[f64]> = cluster.iter().map(|m| m.embedding.as_slice()).collect();
+        embedding = centroid(&members);
+    }
+    let first = cluster[0];
+    let last = cluster
+        .iter()
+        .map(|m| m.timestamp)
+        .max()
+        .unwrap_or(first.timestamp);
+    let ids: Vec<String> = cluster.iter().map(|m| m.id.clone()).collect();
+    backend
+        .store_consolidated_memory(
+            AgentMemory {
+                agent_type: first.agent_type.clone(),
+                content: summary,
+                embedding,
+                timestamp: last,
+                source: first.source.clone(),
+                metadata: Some(json!({
+                    "consolidated_count": cluster.len(),
+                    "period_start": first.timestamp,
+                    "period_end": last,
+                })),
+            },
+            &ids,
+            cfg.originals,
+        )
+        .await
+        .map_err(|e| format!("Failed to store summary: {e:#}"))
+}
+
+/// Run [`consolidate`] on the configured schedule while the system runs.
+/// The config is re-read before each run so changes apply without a
+/// restart.
+pub fn spawn(system: OxideSystem) {
+    tokio::spawn(async move {
+        loop {
+            let hours = current_config(&system)
+                .await
+                .and_then(|c| c.interval_hours)
+                .unwrap_or(DEFAULT_INTERVAL_HOURS);
+            tokio::time::sleep(std::time::Duration::from_secs(hours * 3600)).await;
+            if !system.is_running().await {
+                break;
+            }
+            let Some(cfg) = current_config(&system).await.filter(|c| c.enabled) else {
+                continue;
+            };
+            let Some(backend) = system.surreal_backend() else {
+                continue;
+            };
+            if let Err(e) = consolidate(&system, &backend, &cfg).await {
+                warn!("Memory consolidation failed: {e}");
+            }
+        }
+    });
+}
+
+async fn current_config(system: &OxideSystem) -> Option<MemoryConsolidationConfig> {
+    system
+        .get_config()
+        .await
+        .surreal
+        .and_then(|surreal| surreal.consolidation)
+}
@@ -0,0 +1,114 @@
+//! First-run onboarding wizard.
+//!
+//! The wizard runs before `initialize_system`, so nothing here needs the
+//! `OxideSystem`. Detection, the recommended configuration and the persisted
+//! progress live in `oxide_core::onboarding`; this module adds the hardware
+//! probes that need the voice and guardian crates.
+
+use oxide_core::config::OxidePilotConfig;
+use oxide_core::onboarding::{
+    self, Capabilities, CredentialStatus, OnboardingProgress, OnboardingStep, StepOutcome,
+    ONBOARDING_STATE_PATH,
+};
+use oxide_voice::audio::AudioManager;
+use serde::Serialize;
+use std::path::Path;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct OnboardingState {
+    pub finished: bool,
+    /// First step not yet completed or skipped; `None` once finished
+    pub current_step: Option<OnboardingStep>,
+    pub steps: Vec<StepState>,
+    pub capabilities: Capabilities,
+    pub credentials: Vec<CredentialStatus>,
+    pub recommended_config: OxidePilotConfig,
+    pub notes: Vec<String>,
+    /// Configuration saved by an earlier step, if any
+    pub config: Option<OxidePilotConfig>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct StepState {
+    pub step: OnboardingStep,
+    pub outcome: Option<StepOutcome>,
+}
+
+async fn detect_capabilities() -> Capabilities {
+    let input_devices = match AudioManager::new() {
+        Ok(audio) => audio.list_input_devices().await,
+        Err(e) => {
+            log::warn!("Audio detection failed: {e}");
+            Vec::new()
+        }
+    };
+    let gpus = tokio::task::spawn_blocking(|| {
+        oxide_guardian::hardware::readings()
+            .gpus
+            .into_iter()
+            .map(|gpu| gpu.name)
+            .collect()
+    })
+    .await
+    .unwrap_or_default();
+    let keyring = tokio::task::spawn_blocking(onboarding::keyring_available)
+        .await
+        .unwrap_or(false);
+
+    Capabilities {
+        input_devices,
+        gpus,
+        local_runtimes: onboarding::detect_local_runtimes().await,
+        keyring,
+    }
+}
+
+async fn build_state(progress: OnboardingProgress) -> OnboardingState {
+    let (capabilities, credentials) =
+        tokio::join!(detect_capabilities(), onboarding::credential_statuses());
+    let recommendation = onboarding::recommend(&capabilities, &credentials);
+    OnboardingState {
+        finished: progress.is_finished(),
+        current_step: progress.next_step(),
+        steps: OnboardingStep::ALL
+            .into_iter()
+            .map(|step| StepState {
+                step,
+                outcome: progress.steps.get(&step).copied(),
+            })
+            .collect(),
+        capabilities,
+        credentials,
+        recommended_config: recommendation.config,
+        notes: recommendation.notes,
+        config: progress.config,
+    }
+}
+
+/// Wizard progress plus fresh capability and credential detection.
+#[tauri::command]
+pub async fn get_onboarding_state() -> Result<OnboardingState, String> {
+    let progress = OnboardingProgress::load(Path::new(ONBOARDING_STATE_PATH));
+    Ok(build_state(progress).await)
+}
+
+/// Mark `step` completed (or skipped) and optionally store the chosen
+/// configuration. Completing `finish` requires a valid configuration; the
+/// frontend then passes it to `initialize_system`.
+#[tauri::command]
+pub async fn complete_onboarding_step(
+    step: OnboardingStep,
+    skipped: Option<bool>,
+    config: Option<OxidePilotConfig>,
+) -> Result<OnboardingState, String> {
+    let path = Path::new(ONBOARDING_STATE_PATH);
+    let mut progress = OnboardingProgress::load(path);
+    let outcome = if skipped.unwrap_or(false) {
+        StepOutcome::Skipped
+    } else {
+        StepOutcome::Completed
+    };
+    progress.complete(step, outcome, config)?;
+    progress.save(path)?;
+    Ok(build_state(progress).await)
+}
@@ -1,103 +1,231 @@
-use chrono::Utc;
+use crate::cleanup;
+#[cfg(feature = "surrealdb-metrics")]
+use crate::crash_reports;
+#[cfg(feature = "surrealdb-metrics")]
+use crate::fleet_uplink::{self, FleetUplink};
+#[cfg(feature = "surrealdb-metrics")]
+use crate::job_manager::{CancelMode, JobManager};
+#[cfg(feature = "surrealdb-metrics")]
+use crate::leak_detection;
+#[cfg(feature = "surrealdb-metrics")]
+use crate::llm_audit_store;
+#[cfg(feature = "surrealdb-metrics")]
+use crate::memory_consolidation;
+#[cfg(feature = "surrealdb-metrics")]
+use crate::threat_training;
+use crate::playbooks;
+use crate::power_policy;
+use crate::suggestions::{
+    self, Suggestion, SuggestionEngine, SuggestionInputs, SuggestionResponse,
+};
+#[cfg(feature = "surrealdb-metrics")]
+use crate::telemetry_query::QueryMetricsFunction;
+use crate::watchdog::{self, Probe, SupervisedTask, TaskHealth, TaskRestart, Watchdog};
+use chrono::{DateTime, Utc};
 #[allow(unused_imports)]
 use log::{debug, error, info, warn};
 use oxide_copilot::ai::AIOrchestrator;
 use oxide_copilot::copilot::CopilotAgent;
 use oxide_copilot::functions::FunctionRegistry;
-use oxide_core::config::OxidePilotConfig;
-use oxide_core::performance::PerformanceMonitor;
-// TODO: Implement PerformanceTimer and ResourceOptimizer
-// use oxide_core::performance::{PerformanceTimer, ResourceOptimizer};
+use oxide_copilot::response_cache;
+use oxide_core::api_governor;
+use oxide_core::capabilities::{self, Feature};
+use oxide_core::config::{ApiLimitsConfig, OxidePilotConfig, SuggestionsConfig, WebhookEvent};
+use oxide_core::context_provider::DesktopContext;
+use oxide_core::disk_usage::{self, DiskUsageOptions, DiskUsageReport};
+use oxide_core::i18n::{self, Locale, LocaleInfo};
+use oxide_core::llm_audit;
+use oxide_core::performance::{
+    OperationProfile, Optimization, OptimizationReport, PerformanceMonitor, PerformanceTimer,
+    ResourceOptimizer,
+};
+use oxide_core::network::{self, NetworkStatus};
+use oxide_core::privacy::PrivacyFilter;
+use oxide_core::profile::{self, Profile};
+use oxide_core::prometheus;
+use oxide_core::prompts;
 use oxide_core::input_validation::InputValidator;
-use oxide_core::security_manager::{SecurityEvent, SecurityManager, SecurityPolicy};
-use oxide_core::types::{Context, Interaction};
-use oxide_guardian::guardian::{Guardian, SystemStatus, ThreatEvent};
+use oxide_core::security_manager::{
+    self, Role, SecurityEvent, SecurityEventType, SecurityManager, SecurityPolicy, SecuritySeverity,
+};
+use oxide_core::types::{ImageAttachment, Interaction};
+use oxide_core::webhooks::{DeliveryResult, WebhookDispatcher, WebhookPayload};
+use oxide_guardian::alerts::{Alert, EmailTestResult};
+#[cfg(feature = "surrealdb-metrics")]
+use oxide_guardian::anomaly_model::AnomalyModel;
+use oxide_guardian::attack::{self, AttackMatrixSummary};
+use oxide_guardian::av_coexistence::AvCoexistence;
+use oxide_guardian::baseline::{BaselineSnapshot, DriftReport};
+use oxide_guardian::boot_impact::{self, BootCapture, BootImpactReport};
+use oxide_guardian::firewall::FirewallRule;
+use oxide_guardian::guardian::{
+    Guardian, GuardianProtection, SystemStatus, ThreatEvent, ThreatSeverity,
+};
+use oxide_guardian::integrity::{IntegrityManifest, IntegrityReport};
+use oxide_guardian::ioc::IocImportReport;
 #[cfg(feature = "surrealdb-metrics")]
 use oxide_guardian::{MetricsCollector as GuardianMetricsCollector, MetricsConfig as GuardianMetricsConfig};
+use oxide_guardian::memory_scan::MemoryScanReport;
+use oxide_guardian::playbook::{PlaybookLibrary, PLAYBOOK_DIR};
+use oxide_guardian::process_control::{self, ProcessAction, ProcessIdentity};
+use oxide_guardian::remediation::{
+    ActionStatus, RemediationAction, RemediationEngine, RemediationFinding, RemediationKind,
+    SystemExecutor,
+};
+use oxide_guardian::rules::{ProcessSample, RuleMatch, RulesStatus};
 use oxide_guardian::scanner::FileScanReport;
-use oxide_memory::memory::{ContextQuery, MemoryManager, MemoryStats};
+use oxide_guardian::startup::StartupItem;
+use oxide_memory::context_budget::ContextBudgeter;
+use oxide_memory::memory::{ContextQuery, MemoryManager, MemoryStats, UserPattern};
 #[cfg(feature = "surrealdb-metrics")]
 use oxide_memory::MemoryBackend;
 #[cfg(feature = "surrealdb-metrics")]
-use oxide_memory::SurrealBackend;
+use oxide_memory::{
+    MitigationStatus, SharedSurrealBackend, SurrealBackend, SurrealConnection, SurrealCredentials,
+    SystemMetric, ThreatInfo, ThreatSeverity as StoredThreatSeverity,
+};
+use oxide_voice::language::{self as voice_language, VoiceLanguageSettings, VoiceLanguageStatus};
 use oxide_voice::voice::{GoogleSTTProvider, GoogleTTSProvider, VoiceProcessor};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::{mpsc, Mutex};
-#[cfg(feature = "surrealdb-metrics")]
+use tokio::sync::{broadcast, mpsc, Mutex, Notify, RwLock};
 use tokio::task::JoinHandle;
 // use std::env; // Reserved for future use
 // use crate::cognee_supervisor::CogneeSupervisor; // Reserved for future use
 
+/// The metrics collector, run as a `metrics_collection` service job.
 #[cfg(feature = "surrealdb-metrics")]
 struct MetricsRuntime {
     collector: Arc<Mutex<GuardianMetricsCollector>>,
-    task: Mutex<Option<JoinHandle<()>>>,
+    jobs: Arc<JobManager>,
+    job_id: Mutex<Option<String>>,
+    heartbeat: Arc<std::sync::Mutex<Option<DateTime<Utc>>>>,
+    stall_after: chrono::Duration,
+    boot_capture: Arc<std::sync::Mutex<Option<BootCapture>>>,
 }
 
 #[cfg(feature = "surrealdb-metrics")]
 impl MetricsRuntime {
-    fn new(collector: GuardianMetricsCollector) -> Self {
+    fn new(collector: GuardianMetricsCollector, jobs: Arc<JobManager>) -> Self {
+        let heartbeat = collector.heartbeat();
+        let stall_after = chrono::Duration::seconds(collector.interval_secs() as i64 * 3 + 30);
+        let boot_capture = collector.boot_capture();
         Self {
             collector: Arc::new(Mutex::new(collector)),
-            task: Mutex::new(None),
+            jobs,
+            job_id: Mutex::new(None),
+            heartbeat,
+            stall_after,
+            boot_capture,
         }
     }
 
     async fn start(&self) {
-        let mut task_guard = self.task.lock().await;
-        if task_guard.is_some() {
+        let mut job_guard = self.job_id.lock().await;
+        if job_guard.is_some() {
             return;
         }
 
         let collector = Arc::clone(&self.collector);
-        let handle = tokio::spawn(async move {
-            let mut guard = collector.lock().await;
-            if let Err(err) = guard.start().await {
-                error!("Guardian metrics collector terminated: {:#}", err);
-            }
-        });
+        let job_id = self.jobs.spawn_service(
+            "metrics_collection",
+            CancelMode::Abort,
+            move |_| async move {
+                let mut guard = collector.lock().await;
+                guard
+                    .start()
+                    .await
+                    .map(|()| serde_json::Value::Null)
+                    .map_err(|err| {
+                        error!("Guardian metrics collector terminated: {:#}", err);
+                        format!("{err:#}")
+                    })
+            },
+        );
 
-        *task_guard = Some(handle);
+        *job_guard = Some(job_id);
+    }
+
+    async fn probe(&self) -> Probe {
+        let job = self
+            .job_id
+            .lock()
+            .await
+            .as_ref()
+            .map(|id| self.jobs.get(id));
+        match job {
+            None => Probe::Disabled,
+            Some(info) if info.is_none_or(|info| info.state.is_finished()) => Probe::Exited,
+            Some(_) => Probe::Heartbeat {
+                last: *self.heartbeat.lock().unwrap_or_else(|p| p.into_inner()),
+                stall_after: self.stall_after,
+            },
+        }
+    }
+
+    /// Replace the collection task; buffered samples stay in the collector.
+    async fn restart(&self) {
+        if let Some(job_id) = self.job_id.lock().await.take() {
+            let _ = self.jobs.cancel(&job_id);
+        }
+        self.start().await;
     }
 
     async fn stop(&self) {
-        let mut task_guard = self.task.lock().await;
-        if let Some(handle) = task_guard.take() {
-            handle.abort();
-            if let Err(err) = handle.await {
-                if !err.is_cancelled() {
-                    error!("Guardian metrics collector join error: {err}");
-                }
-            }
+        if let Some(job_id) = self.job_id.lock().await.take() {
+            let _ = self.jobs.cancel(&job_id);
+        }
+        // Persist samples still waiting for a batch write; the lock is free
+        // once the aborted job has dropped its guard
+        if let Err(err) = self.collector.lock().await.flush().await {
+            warn!("Failed to flush buffered metrics on stop: {:#}", err);
         }
     }
 }
 
+/// Minutes a cached disk usage report is served before the root is rescanned.
+#[cfg(feature = "surrealdb-metrics")]
+const DISK_USAGE_CACHE_MINS: i64 = 60;
+
 #[derive(Clone)]
 pub struct OxideSystem {
     config: Arc<Mutex<OxidePilotConfig>>,
     guardian: Arc<Guardian>,
     copilot: Arc<CopilotAgent>,
-    memory_manager: Arc<MemoryManager>,
+    memory_manager: Arc<RwLock<Arc<MemoryManager>>>,
+    profile: Arc<RwLock<Profile>>,
     voice_processor: Arc<VoiceProcessor>,
     performance_monitor: Arc<PerformanceMonitor>,
-    // TODO: Implement ResourceOptimizer
-    // resource_optimizer: Arc<Mutex<ResourceOptimizer>>,
+    resource_optimizer: Arc<ResourceOptimizer>,
+    // Cleanup suggested by the latest disk usage analysis
+    disk_cleanup: Arc<std::sync::Mutex<Vec<Optimization>>>,
     security_manager: Arc<SecurityManager>,
     input_validator: Arc<InputValidator>,
+    privacy_filter: Arc<RwLock<Arc<PrivacyFilter>>>,
+    remediation: RemediationEngine,
+    webhooks: Arc<WebhookDispatcher>,
+    suggestions: Arc<SuggestionEngine>,
+    // Push-to-talk presses, handled like a detected wake word
+    push_to_talk: Arc<Notify>,
     is_running: Arc<Mutex<bool>>,
+    watchdog: Arc<Watchdog>,
+    remediation_task: Arc<std::sync::Mutex<Option<JoinHandle<()>>>>,
     #[cfg(feature = "surrealdb-metrics")]
     surreal_backend: Option<Arc<SurrealBackend>>,
     #[cfg(feature = "surrealdb-metrics")]
     metrics_runtime: Option<Arc<MetricsRuntime>>,
+    #[cfg(feature = "surrealdb-metrics")]
+    fleet_uplink: Arc<FleetUplink>,
 }
 
 #[allow(dead_code)] // Some methods reserved for future use
 impl OxideSystem {
     pub async fn new(
         config: OxidePilotConfig,
-        #[cfg(feature = "surrealdb-metrics")] surreal_backend: Option<Arc<SurrealBackend>>,
+        #[cfg(feature = "surrealdb-metrics")] surreal_backend: Option<Arc<SharedSurrealBackend>>,
+        #[cfg(feature = "surrealdb-metrics")] jobs: Arc<JobManager>,
     ) -> Result<Self, String> {
         info!("Initializing Oxide Pilot System...");
 
@@ -108,6 +236,16 @@ impl OxideSystem {
 
         // Load environment (.env support)
         let _ = dotenv::dotenv();
+        if let Some(network_config) = &config.network {
+            network::configure_transport(network_config)?;
+        }
+
+        let active_profile = profile::resolve(
+            config.profile.as_ref(),
+            std::path::Path::new(profile::DATA_ROOT),
+        );
+        let memory_path = active_profile.memory_path();
+        info!("Active profile: {}", active_profile.name);
 
         #[cfg(feature = "surrealdb-metrics")]
         let (
@@ -116,6 +254,7 @@ impl OxideSystem {
             surreal_metrics_enabled,
             surreal_metrics_interval,
             surreal_db_path,
+            surreal_local_path,
         ) = {
             let parse_bool = |value: &str| {
                 matches!(
@@ -163,28 +302,73 @@ impl OxideSystem {
                 .or_else(|| std::env::var("OXIDE_DB_PATH").ok())
                 .unwrap_or_else(|| "./data/oxide.db".to_string());
 
-            let mut backend = surreal_backend;
+            // TiKV cluster, then remote server (config or OXIDE_SURREAL_URL),
+            // then the embedded store at db_path
+            let connection = match surreal_cfg.as_ref() {
+                Some(cfg) if cfg.distributed => SurrealConnection::TiKv {
+                    endpoints: cfg.tikv_endpoints.clone().unwrap_or_default(),
+                },
+                Some(cfg) if cfg.remote_url.is_some() => {
+                    let credentials = SurrealCredentials::from_env().map(|mut creds| {
+                        if let Some(user) = &cfg.username {
+                            creds.username = user.clone();
+                        }
+                        creds
+                    });
+                    SurrealConnection::parse(
+                        cfg.remote_url.as_deref().unwrap_or_default(),
+                        credentials,
+                    )
+                }
+                _ => SurrealConnection::from_env(&db_path),
+            };
+            let db_path = connection.describe();
+            // Only an embedded store has files Guardian can protect
+            let local_path = match &connection {
+                SurrealConnection::Embedded(path) => Some(path.to_string_lossy().to_string()),
+                _ => None,
+            };
+
+            let mut backend = None;
 
             if should_enable {
-                if backend.is_none() {
-                    match SurrealBackend::new(&db_path).await {
-                        Ok(instance) => {
-                            info!("Initialized SurrealDB backend at {}", db_path);
-                            backend = Some(Arc::new(instance));
-                        }
-                        Err(e) => {
-                            warn!(
-                                "Failed to initialize SurrealDB backend at {}: {}",
-                                db_path, e
-                            );
+                let namespace = active_profile.surreal_namespace();
+                // Without the app's shared handle (tools, tests) open a private one
+                let shared = surreal_backend.unwrap_or_else(|| {
+                    Arc::new(SharedSurrealBackend::new(
+                        connection.clone(),
+                        namespace.clone(),
+                    ))
+                });
+                match shared.get_or_connect(connection).await {
+                    Ok(instance) => {
+                        info!("Using SurrealDB backend at {}", db_path);
+                        if instance.namespace() != namespace {
+                            if let Err(e) = instance.use_namespace(&namespace).await {
+                                warn!(
+                                    "Failed to select profile namespace '{}': {:#}",
+                                    namespace, e
+                                );
+                            }
                         }
+                        instance.set_chunking(
+                            surreal_cfg
+                                .as_ref()
+                                .and_then(|c| c.chunking.clone())
+                                .unwrap_or_default(),
+                        );
+                        instance.set_threat_scorer(Arc::new(AnomalyModel::bundled()));
+                        backend = Some(instance);
+                    }
+                    Err(e) => {
+                        warn!(
+                            "Failed to initialize SurrealDB backend at {}: {}",
+                            db_path, e
+                        );
                     }
-                } else {
-                    info!("Using shared SurrealDB backend at {}", db_path);
                 }
             } else {
                 info!("SurrealDB backend disabled by configuration/environment");
-                backend = None;
             }
 
             let memory_backend = backend
@@ -208,6 +392,7 @@ impl OxideSystem {
                 metrics_enabled,
                 metrics_interval,
                 db_path,
+                local_path,
             )
         };
 
@@ -240,6 +425,7 @@ impl OxideSystem {
 
                 Some(Arc::new(MetricsRuntime::new(
                     GuardianMetricsCollector::new(backend_arc.clone(), metrics_config),
+                    jobs,
                 )))
             } else {
                 warn!("Metrics collection enabled but Surreal backend is unavailable");
@@ -290,7 +476,7 @@ impl OxideSystem {
                                 Ok(Ok(())) => {
                                     info!("Memory backend: Cognee ({}). Fallback: JSON", base_url);
                                     Arc::new(MemoryManager::with_cognee(
-                                        Some("oxide_data".to_string()),
+                                        Some(memory_path.clone()),
                                         base_url,
                                         token,
                                     ))
@@ -340,22 +526,18 @@ impl OxideSystem {
                                         Ok(Ok(())) => {
                                             info!("Cognee sidecar started and healthy. Selecting Cognee backend.");
                                             Arc::new(MemoryManager::with_cognee(
-                                                Some("oxide_data".to_string()),
+                                                Some(memory_path.clone()),
                                                 base_url,
                                                 token,
                                             ))
                                         }
                                         Ok(Err(e2)) => {
                                             warn!("Failed to start Cognee sidecar: {}. Falling back to JSON backend.", e2);
-                                            Arc::new(MemoryManager::new(Some(
-                                                "oxide_data".to_string(),
-                                            )))
+                                            Arc::new(MemoryManager::new(Some(memory_path.clone())))
                                         }
                                         Err(_) => {
                                             warn!("Timed out starting Cognee sidecar. Falling back to JSON backend.");
-                                            Arc::new(MemoryManager::new(Some(
-                                                "oxide_data".to_string(),
-                                            )))
+                                            Arc::new(MemoryManager::new(Some(memory_path.clone())))
                                         }
                                     }
                                 }
@@ -403,22 +585,18 @@ impl OxideSystem {
                                         Ok(Ok(())) => {
                                             info!("Cognee sidecar started and healthy. Selecting Cognee backend.");
                                             Arc::new(MemoryManager::with_cognee(
-                                                Some("oxide_data".to_string()),
+                                                Some(memory_path.clone()),
                                                 base_url,
                                                 token,
                                             ))
                                         }
                                         Ok(Err(e2)) => {
                                             warn!("Failed to start Cognee sidecar: {}. Falling back to JSON backend.", e2);
-                                            Arc::new(MemoryManager::new(Some(
-                                                "oxide_data".to_string(),
-                                            )))
+                                            Arc::new(MemoryManager::new(Some(memory_path.clone())))
                                         }
                                         Err(_) => {
                                             warn!("Timed out starting Cognee sidecar. Falling back to JSON backend.");
-                                            Arc::new(MemoryManager::new(Some(
-                                                "oxide_data".to_string(),
-                                            )))
+                                            Arc::new(MemoryManager::new(Some(memory_path.clone())))
                                         }
                                     }
                                 }
@@ -426,12 +604,12 @@ impl OxideSystem {
                         }
                         Err(e) => {
                             warn!("Failed to initialize Cognee supervisor: {}. Falling back to JSON backend.", e);
-                            Arc::new(MemoryManager::new(Some("oxide_data".to_string())))
+                            Arc::new(MemoryManager::new(Some(memory_path.clone())))
                         }
                     }
                 } else {
                     info!("Memory backend: JSON (Cognee disabled)");
-                    Arc::new(MemoryManager::new(Some("oxide_data".to_string())))
+                    Arc::new(MemoryManager::new(Some(memory_path.clone())))
                 }
             }
             #[cfg(not(feature = "cognee"))]
@@ -439,20 +617,20 @@ impl OxideSystem {
                 #[cfg(feature = "surrealdb-metrics")]
                 {
                     if let Some(backend) = surreal_memory_backend.clone() {
-                        info!("Memory backend: SurrealDB (embedded) [{}]", surreal_db_path);
+                        info!("Memory backend: SurrealDB [{}]", surreal_db_path);
                         Arc::new(MemoryManager::with_backend(
-                            Some("oxide_data".to_string()),
+                            Some(memory_path.clone()),
                             backend,
                         ))
                     } else {
                         info!("Memory backend: JSON (SurrealDB disabled or unavailable)");
-                        Arc::new(MemoryManager::new(Some("oxide_data".to_string())))
+                        Arc::new(MemoryManager::new(Some(memory_path.clone())))
                     }
                 }
                 #[cfg(not(feature = "surrealdb-metrics"))]
                 {
                     info!("Memory backend: JSON (binary built without Cognee feature)");
-                    Arc::new(MemoryManager::new(Some("oxide_data".to_string())))
+                    Arc::new(MemoryManager::new(Some(memory_path.clone())))
                 }
             }
         };
@@ -460,12 +638,28 @@ impl OxideSystem {
 
         // Initialize Guardian Agent
         let guardian = Arc::new(Guardian::new(config.guardian.clone()));
+        #[cfg(feature = "surrealdb-metrics")]
+        if let Some(path) = &surreal_local_path {
+            guardian.protect_path(
+                oxide_guardian::integrity::ProtectedKind::AuditDatabase,
+                path,
+            );
+        }
 
         // Initialize AI Orchestrator
         let ai_orchestrator = Arc::new(AIOrchestrator::new(config.ai_providers.clone()));
 
         // Initialize Function Registry
-        let function_registry = Arc::new(FunctionRegistry::new());
+        #[allow(unused_mut)]
+        let mut function_registry = FunctionRegistry::new();
+        #[cfg(feature = "surrealdb-metrics")]
+        if let Some(backend) = &surreal_backend_arc {
+            function_registry.register_function(Box::new(QueryMetricsFunction::new(
+                Arc::clone(backend),
+                Arc::clone(&ai_orchestrator),
+            )));
+        }
+        let function_registry = Arc::new(function_registry);
 
         // Initialize Copilot Agent
         let copilot = Arc::new(CopilotAgent::new(
@@ -476,45 +670,100 @@ impl OxideSystem {
 
         // Initialize Voice Processor
         let wake_words = vec![config.copilot.wake_word.clone()];
-        let stt_provider = Box::new(GoogleSTTProvider::new(Some("en-US".to_string())));
-        let tts_provider = Box::new(GoogleTTSProvider::new(Some("en-US".to_string()), None));
-        let voice_processor =
-            Arc::new(VoiceProcessor::new(wake_words, stt_provider, tts_provider)?);
+        let stt_provider = Box::new(GoogleSTTProvider::new());
+        let tts_provider = Box::new(GoogleTTSProvider::new());
+        let voice_processor = Arc::new(
+            VoiceProcessor::new(
+                wake_words,
+                stt_provider,
+                tts_provider,
+                voice_settings(&config),
+            )
+            .inspect_err(|e| capabilities::unavailable(Feature::Voice, e.clone()))?,
+        );
 
         let input_devices = voice_processor.get_input_devices().await;
         let output_devices = voice_processor.get_output_devices().await;
         info!("Audio devices - Input: {input_devices:?}, Output: {output_devices:?}");
+        if input_devices.is_empty() {
+            capabilities::degraded(
+                Feature::Voice,
+                "No microphone found; replies can still be spoken",
+            );
+        } else {
+            capabilities::available(Feature::Voice);
+        }
 
         // Initialize Performance Monitor
         let performance_monitor = Arc::new(PerformanceMonitor::new());
-        // TODO: Implement ResourceOptimizer
-        // let resource_optimizer = Arc::new(Mutex::new(ResourceOptimizer::new(Arc::clone(
-        //     &performance_monitor,
-        // ))));
+        let resource_optimizer = Arc::new(ResourceOptimizer::default());
 
         // Initialize security components
         let encryption_key = oxide_core::encryption::EncryptionManager::generate_key();
         let security_manager = Arc::new(
             SecurityManager::new(&encryption_key)
+                .and_then(|manager| manager.with_role_store(security_manager::ROLES_PATH))
                 .map_err(|e| format!("Failed to initialize security manager: {e}"))?,
         );
+        security_manager
+            .set_command_permission_enforcement(
+                config
+                    .security
+                    .as_ref()
+                    .and_then(|s| s.enforce_command_permissions),
+            )
+            .await;
         let input_validator = Arc::new(InputValidator::new());
+        let privacy_filter = Arc::new(PrivacyFilter::new(
+            config.privacy.clone().unwrap_or_default(),
+        ));
+        network::set_offline_mode(config.is_offline());
+        i18n::set_locale(Locale::resolve(config.locale.as_deref()));
+        api_governor::configure(
+            &config
+                .api_limits
+                .clone()
+                .unwrap_or_else(ApiLimitsConfig::with_defaults),
+        );
+        response_cache::global().configure(config.llm_cache.clone().unwrap_or_default());
+        prompts::configure(&config.prompts.clone().unwrap_or_default());
+        llm_audit::configure(&config.llm_audit.clone().unwrap_or_default());
+
+        let webhooks = Arc::new(WebhookDispatcher::new(
+            config.webhooks.clone().unwrap_or_default(),
+        )?);
+
+        #[cfg(feature = "surrealdb-metrics")]
+        let fleet_uplink = Arc::new(FleetUplink::new(
+            config.surreal.as_ref().and_then(|s| s.uplink.as_ref()),
+        ));
 
         let system = Self {
             config: Arc::new(Mutex::new(config)),
             guardian,
             copilot,
-            memory_manager,
+            memory_manager: Arc::new(RwLock::new(memory_manager)),
+            profile: Arc::new(RwLock::new(active_profile)),
             voice_processor,
             performance_monitor,
-            // resource_optimizer,
+            resource_optimizer,
+            disk_cleanup: Arc::new(std::sync::Mutex::new(Vec::new())),
             security_manager,
             input_validator,
+            privacy_filter: Arc::new(RwLock::new(privacy_filter)),
+            remediation: RemediationEngine::new(),
+            webhooks,
+            suggestions: Arc::new(SuggestionEngine::new()),
+            push_to_talk: Arc::new(Notify::new()),
             is_running: Arc::new(Mutex::new(false)),
+            watchdog: Arc::new(Watchdog::new(Utc::now())),
+            remediation_task: Arc::new(std::sync::Mutex::new(None)),
             #[cfg(feature = "surrealdb-metrics")]
             surreal_backend: surreal_backend_arc,
             #[cfg(feature = "surrealdb-metrics")]
             metrics_runtime,
+            #[cfg(feature = "surrealdb-metrics")]
+            fleet_uplink,
         };
 
         info!("Oxide Pilot System initialized successfully");
@@ -527,14 +776,29 @@ impl OxideSystem {
         {
             let mut running = self.is_running.lock().await;
             if *running {
-                return Err("System is already running".to_string());
+                return Err(i18n::t("error.system_already_running").to_string());
             }
             *running = true;
         }
 
         // Start Guardian monitoring
         self.guardian.start_monitoring();
+        self.spawn_threat_remediation();
         info!("Guardian Agent started");
+        suggestions::spawn(self.clone());
+        cleanup::spawn(self.clone());
+        power_policy::spawn(self.clone());
+
+        #[cfg(feature = "surrealdb-metrics")]
+        if self.surreal_backend.is_some() {
+            memory_consolidation::spawn(self.clone());
+            threat_training::spawn(self.clone());
+            leak_detection::spawn(self.clone());
+            llm_audit_store::spawn(self.clone());
+            crash_reports::spawn(self.clone());
+        }
+        #[cfg(feature = "surrealdb-metrics")]
+        fleet_uplink::spawn(self.clone());
 
         #[cfg(feature = "surrealdb-metrics")]
         match (&self.surreal_backend, &self.metrics_runtime) {
@@ -547,9 +811,11 @@ impl OxideSystem {
             }
             _ => {}
         }
+        watchdog::spawn(self.clone());
 
         // Start voice processing
-        let voice_receiver = self.voice_processor.start_listening().await?;
+        let wake_words = self.voice_processor.start_listening().await?;
+        let voice_receiver = self.merge_push_to_talk(wake_words);
         info!("Voice processing started");
 
         // Start main system loop
@@ -558,6 +824,45 @@ impl OxideSystem {
         Ok(())
     }
 
+    pub async fn is_running(&self) -> bool {
+        *self.is_running.lock().await
+    }
+
+    /// Record and answer a voice request now, as if the wake word was heard.
+    pub async fn trigger_voice_input(&self) -> Result<(), String> {
+        if !self.is_running().await {
+            return Err(i18n::t("error.system_not_running").to_string());
+        }
+        self.push_to_talk.notify_one();
+        Ok(())
+    }
+
+    /// Feed push-to-talk presses into the wake word stream, so both start
+    /// the same record-transcribe-answer cycle.
+    fn merge_push_to_talk(&self, mut wake_words: mpsc::Receiver<String>) -> mpsc::Receiver<String> {
+        let (tx, rx) = mpsc::channel(8);
+        let push_to_talk = Arc::clone(&self.push_to_talk);
+        tokio::spawn(async move {
+            let mut listening = true;
+            loop {
+                let word = tokio::select! {
+                    word = wake_words.recv(), if listening => match word {
+                        Some(word) => word,
+                        None => {
+                            listening = false;
+                            continue;
+                        }
+                    },
+                    _ = push_to_talk.notified() => "push-to-talk".to_string(),
+                };
+                if tx.send(word).await.is_err() {
+                    break;
+                }
+            }
+        });
+        rx
+    }
+
     pub async fn stop(&self) -> Result<(), String> {
         info!("Stopping Oxide Pilot System...");
 
@@ -579,11 +884,124 @@ impl OxideSystem {
         Ok(())
     }
 
+    /// Queue suggested remediations and run `auto_run` playbooks for every
+    /// threat the detector reports while the system is running.
+    fn spawn_threat_remediation(&self) {
+        let mut threats = self.guardian.subscribe_threats();
+        let engine = self.remediation.clone();
+        let is_running = Arc::clone(&self.is_running);
+        let system = self.clone();
+        let handle = tokio::spawn(async move {
+            loop {
+                match threats.recv().await {
+                    Ok(threat) => {
+                        engine.propose_for_threat(&threat);
+                        system.notify_webhooks(WebhookPayload::new(
+                            WebhookEvent::ThreatDetected,
+                            format!("[{:?}] {}", threat.severity, threat.description),
+                            serde_json::to_value(&threat).unwrap_or_default(),
+                        ));
+                        #[cfg(feature = "surrealdb-metrics")]
+                        {
+                            let system = system.clone();
+                            let threat = threat.clone();
+                            tokio::spawn(async move {
+                                system.persist_threat(threat).await;
+                            });
+                        }
+                        let system = system.clone();
+                        tokio::spawn(async move {
+                            playbooks::run_auto_playbooks(&system, &threat).await;
+                        });
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Remediation linking skipped {skipped} threat(s)");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+                if !*is_running.lock().await {
+                    break;
+                }
+            }
+        });
+        *self
+            .remediation_task
+            .lock()
+            .unwrap_or_else(|p| p.into_inner()) = Some(handle);
+    }
+
+    pub fn watchdog(&self) -> &Watchdog {
+        &self.watchdog
+    }
+
+    pub fn subscribe_task_restarts(&self) -> broadcast::Receiver<TaskRestart> {
+        self.watchdog.subscribe()
+    }
+
+    /// Supervised background tasks and their restarts.
+    pub fn task_health(&self) -> Vec<TaskHealth> {
+        self.watchdog.health()
+    }
+
+    pub async fn probe_task(&self, task: SupervisedTask) -> Probe {
+        match task {
+            SupervisedTask::GuardianMonitoring => {
+                let protection = self.guardian.protection();
+                if !protection.monitoring_enabled {
+                    Probe::Disabled
+                } else if !self.guardian.monitoring_alive() {
+                    Probe::Exited
+                } else {
+                    Probe::Heartbeat {
+                        last: protection.last_monitor_cycle,
+                        stall_after: chrono::Duration::seconds(protection.stall_after_secs() as i64),
+                    }
+                }
+            }
+            SupervisedTask::MetricsCollector => {
+                #[cfg(feature = "surrealdb-metrics")]
+                if let Some(runtime) = &self.metrics_runtime {
+                    return runtime.probe().await;
+                }
+                Probe::Disabled
+            }
+            SupervisedTask::ThreatRemediation => {
+                match self
+                    .remediation_task
+                    .lock()
+                    .unwrap_or_else(|p| p.into_inner())
+                    .as_ref()
+                {
+                    None => Probe::Disabled,
+                    Some(handle) if handle.is_finished() => Probe::Exited,
+                    Some(_) => Probe::Running,
+                }
+            }
+        }
+    }
+
+    pub async fn restart_task(&self, task: SupervisedTask) {
+        match task {
+            SupervisedTask::GuardianMonitoring => self.guardian.restart_monitoring(),
+            #[cfg(feature = "surrealdb-metrics")]
+            SupervisedTask::MetricsCollector => {
+                if let Some(runtime) = &self.metrics_runtime {
+                    runtime.restart().await;
+                }
+            }
+            #[cfg(not(feature = "surrealdb-metrics"))]
+            SupervisedTask::MetricsCollector => {}
+            SupervisedTask::ThreatRemediation => self.spawn_threat_remediation(),
+        }
+    }
+
     async fn start_main_loop(&self, mut voice_receiver: mpsc::Receiver<String>) {
         let is_running = Arc::clone(&self.is_running);
         let copilot = Arc::clone(&self.copilot);
         let memory_manager = Arc::clone(&self.memory_manager);
         let voice_processor: Arc<VoiceProcessor> = Arc::clone(&self.voice_processor);
+        let config = Arc::clone(&self.config);
+        let privacy_filter = Arc::clone(&self.privacy_filter);
 
         tokio::spawn(async move {
             info!("Main system loop started");
@@ -605,21 +1023,26 @@ impl OxideSystem {
                                     info!("Recorded {} bytes of audio", audio_data.len());
                                     match voice_processor.transcribe_audio(audio_data).await {
                                 Ok(transcription) => {
+                                    if let Some(language) = &transcription.language {
+                                        info!("Speech language detected: {language}");
+                                    }
+                                    let transcription = transcription.text;
                                     if !transcription.is_empty() {
                                         info!("User said: {transcription}");
 
                                         // Process user input with Copilot
-                                        let context = Context {
-                                            active_window: None,
-                                            system_status: Some(serde_json::json!({
+                                        let privacy = privacy_filter.read().await.clone();
+                                        let context = desktop_context(&config, &privacy)
+                                            .await
+                                            .into_context(Some(serde_json::json!({
                                                 "source": "voice_input",
                                                 "timestamp": Utc::now()
-                                            })),
-                                            recent_events: Vec::new(),
-                                        };
+                                            })));
 
-                                        match copilot.handle_user_input(transcription.clone(), context.clone()).await {
+                                        let request = copilot.handle_user_input(transcription.clone(), context.clone());
+                                        match llm_audit::with_purpose("voice_chat", request).await {
                                             Ok(response) => {
+                                                let response = privacy.restore_text(&response);
                                                 info!("Copilot response: {response}");
 
                                                 // Store interaction in memory
@@ -631,7 +1054,8 @@ impl OxideSystem {
                                                     context,
                                                 };
 
-                                                if let Err(e) = memory_manager.store_interaction(interaction).await {
+                                                let memory = memory_manager.read().await.clone();
+                                                if let Err(e) = memory.store_interaction(interaction).await {
                                                     error!("Failed to store interaction: {e}");
                                                 }
 
@@ -663,7 +1087,8 @@ impl OxideSystem {
                     // Periodic system maintenance
                     _ = tokio::time::sleep(Duration::from_secs(60)) => {
                         // Perform periodic maintenance tasks
-                        Self::perform_maintenance(&memory_manager).await;
+                        let memory = memory_manager.read().await.clone();
+                        Self::perform_maintenance(&memory).await;
                     }
                 }
             }
@@ -688,11 +1113,19 @@ impl OxideSystem {
     }
 
     pub async fn handle_text_input(&self, input: String) -> Result<String, String> {
-        // TODO: Implement PerformanceTimer
-        // let _timer = PerformanceTimer::new(
-        //     "handle_text_input".to_string(),
-        //     Arc::clone(&self.performance_monitor),
-        // );
+        self.handle_input_with_images(input, Vec::new()).await
+    }
+
+    /// Text input with images attached, answered by a vision-capable provider.
+    pub async fn handle_input_with_images(
+        &self,
+        input: String,
+        images: Vec<ImageAttachment>,
+    ) -> Result<String, String> {
+        let _timer = PerformanceTimer::new(
+            "handle_text_input",
+            Arc::clone(&self.performance_monitor),
+        );
         info!("Handling text input: {input}");
 
         // Build context from memory
@@ -704,25 +1137,54 @@ impl OxideSystem {
             min_relevance: 0.3,
         };
 
-        let relevant_memories = self.memory_manager.retrieve_context(&context_query).await?;
+        let memory = self.memory_manager().await;
+        let relevant_memories = memory.retrieve_context(&context_query).await?;
+        let habits: Vec<String> = memory
+            .relevant_patterns(Utc::now(), 5)
+            .await
+            .into_iter()
+            .map(|pattern| pattern.description)
+            .collect();
 
-        let context = Context {
-            active_window: None,
-            system_status: Some(serde_json::json!({
-                "memory_entries": relevant_memories.len(),
+        // Trim retrieved memories to what the answering provider can take
+        let provider = self
+            .copilot
+            .current_provider_name()
+            .await
+            .unwrap_or_default();
+        let budget = self
+            .config
+            .lock()
+            .await
+            .context_budget
+            .clone()
+            .unwrap_or_default();
+        let injected = ContextBudgeter::new(budget).select(&provider, relevant_memories);
+
+        let privacy = self.privacy_filter().await;
+        let mut context = desktop_context(&self.config, &privacy)
+            .await
+            .into_context(Some(serde_json::json!({
+                "memory_entries": injected.included.len(),
+                "memory_tokens": injected.used_tokens,
+                "user_patterns": habits,
                 "timestamp": Utc::now()
-            })),
-            recent_events: Vec::new(),
-        };
+            })));
+        if !injected.text.is_empty() {
+            context.memory_context = Some(privacy.redact_text(&injected.text));
+        }
 
         // Process with Copilot
-        let response = self
-            .copilot
-            .handle_user_input(input.clone(), context.clone())
+        let request =
+            self.copilot
+                .handle_user_input_with_images(input.clone(), images, context.clone());
+        let response = llm_audit::with_purpose("chat", request)
             .await
+            .map(|response| privacy.restore_text(&response))
             .map_err(|e| e.to_string())?;
 
-        // Store interaction
+        // Store interaction; the injected memories are already stored
+        context.memory_context = None;
         let interaction = Interaction {
             id: uuid::Uuid::new_v4(),
             timestamp: Utc::now(),
@@ -731,11 +1193,18 @@ impl OxideSystem {
             context,
         };
 
-        self.memory_manager.store_interaction(interaction).await?;
+        memory.store_interaction(interaction).await?;
 
         Ok(response)
     }
 
+    pub async fn capture_screen_attachment(&self) -> Result<ImageAttachment, String> {
+        self.copilot
+            .capture_screen()
+            .await
+            .map_err(|e| e.to_string())
+    }
+
     pub fn get_system_status(&self) -> SystemStatus {
         self.guardian.get_system_status()
     }
@@ -744,12 +1213,256 @@ impl OxideSystem {
         self.guardian.get_threat_history()
     }
 
+    pub fn record_threat(&self, event: ThreatEvent) {
+        self.guardian.record_threat(event);
+    }
+
+    pub fn subscribe_threats(&self) -> broadcast::Receiver<ThreatEvent> {
+        self.guardian.subscribe_threats()
+    }
+
+    pub fn yara_rules_loaded(&self) -> bool {
+        self.guardian.yara_rules_loaded()
+    }
+
+    pub fn guardian_protection(&self) -> GuardianProtection {
+        self.guardian.protection()
+    }
+
+    /// Other antivirus products and how Guardian adapted to them.
+    pub fn av_coexistence(&self) -> Option<AvCoexistence> {
+        self.guardian.av_coexistence()
+    }
+
+    /// Fingerprint of the detection rules file scans currently run against.
+    pub fn detection_version(&self) -> String {
+        self.guardian.detection_version()
+    }
+
+    /// Drift from the recorded baseline; `None` until one is recorded.
+    pub async fn baseline_drift(&self) -> Result<Option<DriftReport>, String> {
+        let guardian = self.guardian.clone();
+        tokio::task::spawn_blocking(move || guardian.baseline_drift())
+            .await
+            .map_err(|e| format!("Baseline task join error: {e}"))
+    }
+
+    pub async fn record_baseline(&self) -> Result<BaselineSnapshot, String> {
+        let guardian = self.guardian.clone();
+        tokio::task::spawn_blocking(move || guardian.record_baseline())
+            .await
+            .map_err(|e| format!("Baseline task join error: {e}"))?
+    }
+
+    /// Oxide Pilot's own files compared with the integrity manifest.
+    pub async fn integrity_status(&self) -> Result<IntegrityReport, String> {
+        let guardian = self.guardian.clone();
+        tokio::task::spawn_blocking(move || guardian.integrity_status())
+            .await
+            .map_err(|e| format!("Integrity task join error: {e}"))?
+    }
+
+    pub async fn record_integrity(&self) -> Result<IntegrityManifest, String> {
+        let guardian = self.guardian.clone();
+        tokio::task::spawn_blocking(move || guardian.record_integrity())
+            .await
+            .map_err(|e| format!("Integrity task join error: {e}"))?
+    }
+
+    /// Cut a running process, or every process of a program, off the
+    /// network with a tagged firewall rule.
+    pub async fn block_process_network(
+        &self,
+        pid: Option<u32>,
+        path: Option<String>,
+        reason: Option<String>,
+        expected: ProcessIdentity,
+    ) -> Result<FirewallRule, String> {
+        let firewall = self.guardian.firewall();
+        tokio::task::spawn_blocking(move || {
+            firewall.block_process(pid, path, reason, Some(&expected))
+        })
+        .await
+        .map_err(|e| format!("Firewall task join error: {e}"))?
+    }
+
+    pub fn firewall_rules(&self) -> Vec<FirewallRule> {
+        self.guardian.firewall().rules()
+    }
+
+    pub async fn remove_firewall_rule(&self, rule_id: String) -> Result<FirewallRule, String> {
+        let firewall = self.guardian.firewall();
+        tokio::task::spawn_blocking(move || firewall.remove_rule(&rule_id))
+            .await
+            .map_err(|e| format!("Firewall task join error: {e}"))?
+    }
+
+    /// Import an IOC feed into the signature database and reputation store.
+    pub async fn import_ioc_feed(
+        &self,
+        path_or_url: String,
+        source: Option<String>,
+        expiry_days: Option<u32>,
+    ) -> Result<IocImportReport, String> {
+        let guardian = self.guardian.clone();
+        tokio::task::spawn_blocking(move || {
+            guardian.import_ioc_feed(&path_or_url, source, expiry_days)
+        })
+        .await
+        .map_err(|e| format!("IOC import task join error: {e}"))?
+    }
+
+    pub fn heuristic_rules(&self) -> RulesStatus {
+        self.guardian.heuristic_rules()
+    }
+
+    pub fn reload_heuristic_rules(&self) -> Result<RulesStatus, String> {
+        self.guardian.reload_heuristic_rules()
+    }
+
+    pub fn test_heuristic_rules(&self, sample: &ProcessSample) -> Vec<RuleMatch> {
+        self.guardian.test_heuristic_rules(sample)
+    }
+
+    #[cfg(feature = "surrealdb-metrics")]
+    pub fn surreal_backend(&self) -> Option<Arc<SurrealBackend>> {
+        self.surreal_backend.clone()
+    }
+
+    /// Live metric samples; `None` when the SurrealDB backend is unavailable.
+    #[cfg(feature = "surrealdb-metrics")]
+    pub fn subscribe_metrics(&self) -> Option<broadcast::Receiver<SystemMetric>> {
+        self.surreal_backend
+            .as_ref()
+            .map(|backend| backend.subscribe_metrics())
+    }
+
+    #[cfg(feature = "surrealdb-metrics")]
+    pub fn fleet_uplink(&self) -> Arc<FleetUplink> {
+        Arc::clone(&self.fleet_uplink)
+    }
+
+    /// Queue a finished folder scan for the fleet server, if one is set up.
+    #[cfg(feature = "surrealdb-metrics")]
+    pub fn record_fleet_scan(&self, summary: &oxide_memory::ScanRunSummary) {
+        self.fleet_uplink
+            .record(oxide_memory::fleet_uplink::FleetRecordKind::Scan, summary);
+    }
+
+    /// Stored metric samples between `start` and `end`, as JSON; empty when
+    /// metrics are not recorded.
+    pub async fn metrics_between(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<serde_json::Value>, String> {
+        #[cfg(feature = "surrealdb-metrics")]
+        if let Some(backend) = &self.surreal_backend {
+            let metrics = backend
+                .query_metrics_by_time(start, end)
+                .await
+                .map_err(|e| format!("Failed to query metrics: {e}"))?;
+            return metrics
+                .iter()
+                .map(|metric| serde_json::to_value(metric).map_err(|e| e.to_string()))
+                .collect();
+        }
+        let _ = (start, end);
+        Ok(Vec::new())
+    }
+
     pub async fn get_memory_stats(&self) -> MemoryStats {
-        self.memory_manager.get_memory_stats().await
+        self.memory_manager().await.get_memory_stats().await
+    }
+
+    pub async fn get_user_patterns(&self) -> Vec<UserPattern> {
+        self.memory_manager().await.get_user_patterns().await
+    }
+
+    /// Sample process memory and queue any new suggestions.
+    pub async fn evaluate_suggestions(
+        &self,
+        cfg: &SuggestionsConfig,
+    ) -> Result<Vec<Suggestion>, String> {
+        let memory = self.memory_manager().await;
+        let process_memory = tokio::task::spawn_blocking(suggestions::sample_process_memory)
+            .await
+            .map_err(|e| format!("Process sampling task join error: {e}"))?;
+        let inputs = SuggestionInputs {
+            patterns: memory.get_user_patterns().await,
+            threats: self.guardian.get_threat_history(),
+            process_memory,
+        };
+        Ok(self
+            .suggestions
+            .evaluate(memory.storage_path(), inputs, cfg)
+            .await)
+    }
+
+    pub async fn pending_suggestions(&self) -> Vec<Suggestion> {
+        let memory = self.memory_manager().await;
+        self.suggestions.pending(memory.storage_path()).await
+    }
+
+    pub async fn respond_to_suggestion(
+        &self,
+        id: &str,
+        response: SuggestionResponse,
+    ) -> Result<Suggestion, String> {
+        let memory = self.memory_manager().await;
+        self.suggestions
+            .respond(memory.storage_path(), id, response)
+            .await
+    }
+
+    pub fn subscribe_suggestions(&self) -> broadcast::Receiver<Suggestion> {
+        self.suggestions.subscribe()
+    }
+
+    /// Memory store of the active profile.
+    pub async fn memory_manager(&self) -> Arc<MemoryManager> {
+        self.memory_manager.read().await.clone()
+    }
+
+    pub async fn active_profile(&self) -> Profile {
+        self.profile.read().await.clone()
+    }
+
+    /// Make `target` the active profile: later interactions are stored in
+    /// and recalled from its memory directory and SurrealDB namespace, and
+    /// the copilot forgets the previous profile's conversation.
+    pub async fn switch_profile(&self, target: Profile) -> Result<(), String> {
+        if *self.profile.read().await == target {
+            return Ok(());
+        }
+
+        let memory = Arc::new(
+            self.memory_manager()
+                .await
+                .with_storage_path(target.memory_path()),
+        );
+        memory.initialize().await?;
+
+        #[cfg(feature = "surrealdb-metrics")]
+        if let Some(backend) = &self.surreal_backend {
+            backend
+                .use_namespace(&target.surreal_namespace())
+                .await
+                .map_err(|e| format!("Failed to switch database namespace: {e:#}"))?;
+        }
+        *self.memory_manager.write().await = memory;
+        self.copilot.clear_conversation_history().await;
+
+        info!("Switched to profile '{}'", target.name);
+        *self.profile.write().await = target;
+        Ok(())
     }
 
     pub async fn update_config(&self, new_config: OxidePilotConfig) -> Result<(), String> {
         new_config.validate()?;
+        if let Some(network_config) = &new_config.network {
+            network::configure_transport(network_config)?;
+        }
 
         {
             let mut config = self.config.lock().await;
@@ -757,6 +1470,44 @@ impl OxideSystem {
         }
 
         // Update individual components
+        *self.privacy_filter.write().await = Arc::new(PrivacyFilter::new(
+            new_config.privacy.clone().unwrap_or_default(),
+        ));
+        network::set_offline_mode(new_config.is_offline());
+        i18n::set_locale(Locale::resolve(new_config.locale.as_deref()));
+        let voice = voice_settings(&new_config);
+        if voice != self.voice_processor.languages() {
+            self.voice_processor.set_languages(voice);
+        }
+        api_governor::configure(
+            &new_config
+                .api_limits
+                .clone()
+                .unwrap_or_else(ApiLimitsConfig::with_defaults),
+        );
+        response_cache::global().configure(new_config.llm_cache.clone().unwrap_or_default());
+        prompts::configure(&new_config.prompts.clone().unwrap_or_default());
+        llm_audit::configure(&new_config.llm_audit.clone().unwrap_or_default());
+        self.security_manager
+            .set_command_permission_enforcement(
+                new_config
+                    .security
+                    .as_ref()
+                    .and_then(|s| s.enforce_command_permissions),
+            )
+            .await;
+        #[cfg(feature = "surrealdb-metrics")]
+        if let Some(backend) = &self.surreal_backend {
+            backend.set_chunking(
+                new_config
+                    .surreal
+                    .as_ref()
+                    .and_then(|c| c.chunking.clone())
+                    .unwrap_or_default(),
+            );
+        }
+        self.webhooks
+            .configure(new_config.webhooks.clone().unwrap_or_default());
         self.guardian.update_config(new_config.guardian);
         self.copilot.update_config(new_config.copilot).await;
 
@@ -768,6 +1519,61 @@ impl OxideSystem {
         self.config.lock().await.clone()
     }
 
+    /// Toggle offline mode and persist it in the in-memory config.
+    pub async fn set_offline_mode(&self, enabled: bool) -> NetworkStatus {
+        {
+            let mut config = self.config.lock().await;
+            config.offline_mode = Some(enabled);
+        }
+        network::set_offline_mode(enabled);
+        network::status()
+    }
+
+    /// Switch the message and reply language and keep it in the in-memory
+    /// config.
+    pub async fn set_locale(&self, locale: Locale) -> LocaleInfo {
+        {
+            let mut config = self.config.lock().await;
+            config.locale = Some(locale.code().to_string());
+        }
+        i18n::set_locale(locale);
+        i18n::info()
+    }
+
+    pub fn voice_language_status(&self) -> VoiceLanguageStatus {
+        self.voice_processor.language_status()
+    }
+
+    /// Set the primary speech language and, optionally, auto-detection, and
+    /// keep them in the in-memory config.
+    pub async fn set_voice_language(
+        &self,
+        language: &str,
+        auto_detect: Option<bool>,
+    ) -> Result<VoiceLanguageStatus, String> {
+        let language = voice_language::normalize(language)?;
+        let settings = {
+            let mut config = self.config.lock().await;
+            let voice = config.voice.get_or_insert_with(Default::default);
+            voice.language = Some(language);
+            if let Some(auto_detect) = auto_detect {
+                voice.auto_detect = auto_detect;
+            }
+            voice_settings(&config)
+        };
+        self.voice_processor.set_languages(settings);
+        Ok(self.voice_processor.language_status())
+    }
+
+    pub fn network_status(&self) -> NetworkStatus {
+        network::status()
+    }
+
+    /// Filter used to pseudonymize data before it is sent to cloud LLMs.
+    pub async fn privacy_filter(&self) -> Arc<PrivacyFilter> {
+        self.privacy_filter.read().await.clone()
+    }
+
     pub async fn record_audio(&self, duration_secs: f32) -> Result<Vec<u8>, String> {
         self.voice_processor.record_audio(duration_secs).await
     }
@@ -802,11 +1608,93 @@ impl OxideSystem {
         self.performance_monitor.get_performance_score().await
     }
 
-    pub async fn optimize_performance(&self) -> Vec<String> {
-        // TODO: Implement ResourceOptimizer
-        // let optimizer = self.resource_optimizer.lock().await;
-        // optimizer.optimize_if_needed().await
-        vec!["Performance optimization not yet implemented".to_string()]
+    pub async fn optimize_performance(&self) -> Result<OptimizationReport, String> {
+        let optimizer = Arc::clone(&self.resource_optimizer);
+        let mut report = tokio::task::spawn_blocking(move || optimizer.optimize())
+            .await
+            .map_err(|e| format!("Optimization task join error: {e}"))?;
+        // Disk cleanup is only suggested; the optimizer's own temp sweep is not repeated
+        let disk_cleanup = self
+            .disk_cleanup
+            .lock()
+            .map(|c| c.clone())
+            .unwrap_or_default();
+        for optimization in disk_cleanup {
+            let seen = report
+                .applied
+                .iter()
+                .chain(&report.failed)
+                .map(|a| &a.optimization)
+                .chain(&report.suggested)
+                .any(|o| o.action == optimization.action);
+            if !seen {
+                report.suggested.push(optimization);
+            }
+        }
+        info!(
+            "Resource optimization: {} applied, {} suggested, {} failed",
+            report.applied.len(),
+            report.suggested.len(),
+            report.failed.len()
+        );
+        Ok(report)
+    }
+
+    /// Disk usage under `root`. A report cached within
+    /// `DISK_USAGE_CACHE_MINS` is returned unless `refresh` is set; its
+    /// cleanup suggestions are offered by [`Self::optimize_performance`].
+    pub async fn analyze_disk_usage(
+        &self,
+        root: &str,
+        refresh: bool,
+    ) -> Result<DiskUsageReport, String> {
+        #[cfg(feature = "surrealdb-metrics")]
+        if !refresh {
+            if let Some(backend) = &self.surreal_backend {
+                match backend.cached_disk_usage(root).await {
+                    Ok(Some(report))
+                        if Utc::now() - report.scanned_at
+                            < chrono::Duration::minutes(DISK_USAGE_CACHE_MINS) =>
+                    {
+                        self.set_disk_cleanup(&report);
+                        return Ok(report);
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!("Disk usage cache lookup failed: {e:#}"),
+                }
+            }
+        }
+        #[cfg(not(feature = "surrealdb-metrics"))]
+        let _ = refresh;
+
+        let path = std::path::PathBuf::from(root);
+        let report = tokio::task::spawn_blocking(move || {
+            disk_usage::analyze(&path, &DiskUsageOptions::default())
+        })
+        .await
+        .map_err(|e| format!("Disk usage task join error: {e}"))??;
+        info!(
+            "Disk usage of {}: {:.1} GB in {} files, {} cleanup suggestions",
+            root,
+            report.total_bytes as f64 / 1024.0 / 1024.0 / 1024.0,
+            report.files,
+            report.suggestions.len()
+        );
+
+        #[cfg(feature = "surrealdb-metrics")]
+        if let Some(backend) = &self.surreal_backend {
+            if let Err(e) = backend.store_disk_usage(&report).await {
+                warn!("Failed to cache disk usage report: {e:#}");
+            }
+        }
+        self.set_disk_cleanup(&report);
+        Ok(report)
+    }
+
+    fn set_disk_cleanup(&self, report: &DiskUsageReport) {
+        if let Ok(mut cleanup) = self.disk_cleanup.lock() {
+            *cleanup = report.suggestions.clone();
+        }
     }
 
     // TODO: Implement PerformanceAlert and PerformanceProfile types
@@ -819,9 +1707,14 @@ impl OxideSystem {
         // self.performance_monitor.clear_alerts().await
     }
 
-    // pub async fn get_operation_profiles(&self) -> std::collections::HashMap<String, oxide_core::performance::PerformanceProfile> {
-    //     self.performance_monitor.get_operation_profiles().await
-    // }
+    pub fn get_operation_profiles(&self) -> Vec<OperationProfile> {
+        self.performance_monitor.get_operation_profiles()
+    }
+
+    /// Record an operation timed outside `OxideSystem` (e.g. backend commands).
+    pub fn record_operation(&self, name: &str, duration: std::time::Duration) {
+        self.performance_monitor.record_operation(name, duration);
+    }
 
     pub async fn set_performance_monitoring(&self, _enabled: bool) {
         // TODO: Implement set_monitoring_enabled method
@@ -835,15 +1728,24 @@ impl OxideSystem {
         use_cloud: bool,
         quarantine: bool,
     ) -> Result<FileScanReport, String> {
+        let _timer = PerformanceTimer::new("scan_file", Arc::clone(&self.performance_monitor));
         // Check antivirus feature toggle (defaults to enabled if not set)
         let av_enabled = {
             let cfg = self.config.lock().await;
             cfg.guardian.antivirus_enabled.unwrap_or(true)
         };
         if !av_enabled {
-            return Err("Antivirus scanning is disabled in settings".to_string());
+            return Err(i18n::t("error.antivirus_disabled").to_string());
         }
 
+        // Offline mode degrades cloud scans to local signatures only
+        let use_cloud = if use_cloud && network::is_offline() {
+            info!("Offline mode: skipping VirusTotal lookup for {path}");
+            false
+        } else {
+            use_cloud
+        };
+
         // Optional rate limiting for cloud lookups
         if use_cloud {
             self.security_manager
@@ -890,9 +1792,429 @@ impl OxideSystem {
         // Offload blocking scan (file IO + potential blocking HTTP) to a blocking thread
         let guardian = self.guardian.clone();
         let path_cloned = path.clone();
-        tokio::task::spawn_blocking(move || guardian.scan_file(&path_cloned, vt_key, quarantine))
+        let result = tokio::task::spawn_blocking(move || {
+            guardian.scan_file(&path_cloned, vt_key, quarantine)
+        })
+        .await
+        .map_err(|e| format!("Scan task join error: {e}"))?;
+        record_scan("file", result.as_ref().map(|report| report.malicious));
+        result
+    }
+
+    /// On-demand YARA scan of a running process's memory.
+    pub async fn scan_process_memory(&self, pid: u32) -> Result<MemoryScanReport, String> {
+        let guardian = self.guardian.clone();
+        let result = tokio::task::spawn_blocking(move || guardian.scan_process_memory(pid))
+            .await
+            .map_err(|e| format!("Memory scan task join error: {e}"))?;
+        record_scan(
+            "memory",
+            result
+                .as_ref()
+                .map(|report| !report.matched_rules.is_empty()),
+        );
+        result
+    }
+
+    /// Autostart entries with risk scores, riskiest first.
+    pub async fn get_startup_items(&self) -> Result<Vec<StartupItem>, String> {
+        let guardian = self.guardian.clone();
+        tokio::task::spawn_blocking(move || guardian.startup_items())
+            .await
+            .map_err(|e| format!("Startup inventory task join error: {e}"))
+    }
+
+    pub async fn disable_startup_item(&self, item_id: String) -> Result<String, String> {
+        let guardian = self.guardian.clone();
+        tokio::task::spawn_blocking(move || guardian.disable_startup_item(&item_id))
+            .await
+            .map_err(|e| format!("Startup item task join error: {e}"))?
+    }
+
+    /// Time to desktop and the boot-time usage of each startup entry.
+    /// High-impact entries that are safe to turn off are queued as
+    /// remediation actions; nothing is disabled without approval.
+    pub async fn boot_impact(&self) -> Result<BootImpactReport, String> {
+        #[cfg(feature = "surrealdb-metrics")]
+        let capture = self.metrics_runtime.as_ref().and_then(|runtime| {
+            runtime
+                .boot_capture
+                .lock()
+                .unwrap_or_else(|p| p.into_inner())
+                .clone()
+        });
+        #[cfg(not(feature = "surrealdb-metrics"))]
+        let capture: Option<BootCapture> = None;
+        let guardian = self.guardian.clone();
+        let report = tokio::task::spawn_blocking(move || {
+            let (boot_time, desktop) = boot_impact::current_boot();
+            boot_impact::analyze(
+                guardian.startup_items(),
+                boot_time,
+                capture.as_ref(),
+                desktop,
+                Utc::now(),
+            )
+        })
+        .await
+        .map_err(|e| format!("Boot impact task join error: {e}"))?;
+
+        // One finding per boot, so a rejected suggestion is not offered again
+        let finding_id = format!("boot-impact-{}", report.boot_time.timestamp());
+        let decided = self.remediation.history(usize::MAX);
+        let findings: Vec<RemediationFinding> = report
+            .items
+            .iter()
+            .filter(|entry| entry.suggest_disable)
+            .filter(|entry| {
+                !decided.iter().any(|a| {
+                    a.finding_id == finding_id
+                        && a.kind
+                            == RemediationKind::DisableStartupEntry {
+                                name: entry.item.id.clone(),
+                            }
+                })
+            })
+            .map(|entry| RemediationFinding {
+                id: finding_id.clone(),
+                kind: "startup".to_string(),
+                severity: "medium".to_string(),
+                summary: format!(
+                    "{} used {:.1} s of CPU and {:.1} MB of disk I/O in the first {} minutes after boot",
+                    entry.item.name,
+                    entry.cpu_seconds,
+                    entry.disk_bytes as f64 / 1024.0 / 1024.0,
+                    report.window_secs / 60
+                ),
+                indicators: vec![format!("startup:{}", entry.item.id)],
+            })
+            .collect();
+        let queued = self.propose_remediations(&findings);
+        if !queued.is_empty() {
+            info!("Suggested disabling {} slow startup item(s)", queued.len());
+        }
+        Ok(report)
+    }
+
+    /// Queue remediation actions for consensus findings. Nothing runs until
+    /// the user approves an action via [`Self::respond_remediation`].
+    pub fn propose_remediations(&self, findings: &[RemediationFinding]) -> Vec<RemediationAction> {
+        findings
+            .iter()
+            .flat_map(|finding| self.remediation.propose(finding))
+            .collect()
+    }
+
+    pub fn pending_remediations(&self) -> Vec<RemediationAction> {
+        self.remediation.pending()
+    }
+
+    pub fn remediation_history(&self, limit: usize) -> Vec<RemediationAction> {
+        self.remediation.history(limit)
+    }
+
+    /// Approve (and execute) or reject a pending remediation action.
+    pub async fn respond_remediation(
+        &self,
+        action_id: String,
+        approved: bool,
+        reason: Option<String>,
+    ) -> Result<RemediationAction, String> {
+        let quarantine_dir = {
+            let cfg = self.config.lock().await;
+            cfg.guardian.quarantine_dir.clone().map(Into::into)
+        };
+        let engine = self.remediation.clone();
+        let firewall = self.guardian.firewall();
+        // Killing processes and moving files are blocking operations
+        let action = tokio::task::spawn_blocking(move || {
+            let executor = SystemExecutor {
+                quarantine_dir,
+                firewall: Some(firewall),
+            };
+            engine.respond(&action_id, approved, reason, &executor)
+        })
+        .await
+        .map_err(|e| format!("Remediation task join error: {e}"))??;
+        if action.status == ActionStatus::Failed {
+            self.guardian.forward_alert(Alert {
+                timestamp: Utc::now(),
+                severity: ThreatSeverity::Critical,
+                kind: "RemediationFailed".to_string(),
+                message: format!(
+                    "{}: {}",
+                    action.description,
+                    action.outcome.as_deref().unwrap_or("unknown error")
+                ),
+                fields: BTreeMap::from([
+                    ("action_id".to_string(), action.id.clone()),
+                    ("finding_id".to_string(), action.finding_id.clone()),
+                ]),
+            });
+        }
+        Ok(action)
+    }
+
+    /// Send a test message through every configured email alert sink.
+    pub async fn send_test_emails(&self) -> Result<Vec<EmailTestResult>, String> {
+        let guardian = Arc::clone(&self.guardian);
+        tokio::task::spawn_blocking(move || guardian.send_test_emails())
             .await
-            .map_err(|e| format!("Scan task join error: {e}"))?
+            .map_err(|e| format!("Test email task join error: {e}"))
+    }
+
+    /// Kill, suspend or resume `pid` on behalf of the session's user, if it
+    /// still matches `expected`. The attempt is written to the security
+    /// audit log whatever its outcome.
+    pub async fn control_process(
+        &self,
+        pid: u32,
+        action: ProcessAction,
+        expected: ProcessIdentity,
+        session_id: &str,
+    ) -> Result<String, String> {
+        let session = self
+            .security_manager
+            .validate_session(session_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        let result = tokio::task::spawn_blocking(move || {
+            process_control::control_process(pid, action, Some(&expected))
+        })
+        .await
+        .map_err(|e| format!("Process control task join error: {e}"))?;
+
+        let (severity, outcome) = match &result {
+            Ok(message) => (SecuritySeverity::High, message.clone()),
+            Err(error) => (SecuritySeverity::Medium, error.clone()),
+        };
+        self.security_manager
+            .log_security_event(
+                SecurityEventType::ProcessControl,
+                severity,
+                Some(session.user_id),
+                Some(session_id.to_string()),
+                format!("{action:?} process {pid}: {outcome}"),
+                HashMap::from([
+                    ("pid".to_string(), pid.to_string()),
+                    ("action".to_string(), format!("{action:?}").to_lowercase()),
+                    ("succeeded".to_string(), result.is_ok().to_string()),
+                ]),
+                None,
+            )
+            .await;
+        result
+    }
+
+    /// Playbooks in the configured directory, re-read on every call so
+    /// edits apply without a restart.
+    pub async fn playbook_library(&self) -> PlaybookLibrary {
+        let dir = {
+            let config = self.config.lock().await;
+            config.guardian.playbooks_dir.clone()
+        }
+        .unwrap_or_else(|| PLAYBOOK_DIR.to_string());
+        tokio::task::spawn_blocking(move || PlaybookLibrary::load(Path::new(&dir)))
+            .await
+            .unwrap_or_default()
+    }
+
+    pub fn find_threat(&self, threat_id: &str) -> Option<ThreatEvent> {
+        self.guardian
+            .get_threat_history()
+            .into_iter()
+            .find(|threat| threat.id == threat_id)
+    }
+
+    /// Ask the AI providers about an incident; identifiers are pseudonymized
+    /// on the way out and restored in the answer.
+    pub async fn summarize_incident(&self, prompt: &str) -> Result<String, String> {
+        let privacy = self.privacy_filter().await;
+        self.copilot
+            .summarize(&privacy.redact_text(prompt))
+            .await
+            .map(|summary| privacy.restore_text(&summary))
+            .map_err(|e| e.to_string())
+    }
+
+    /// Store `threat` in SurrealDB with its ATT&CK techniques. Severe
+    /// threats the bundled table could not map are classified by the AI
+    /// providers first, unless offline.
+    #[cfg(feature = "surrealdb-metrics")]
+    async fn persist_threat(&self, mut threat: ThreatEvent) {
+        let Some(backend) = self.surreal_backend() else {
+            return;
+        };
+        if threat.techniques.is_empty()
+            && matches!(
+                threat.severity,
+                ThreatSeverity::High | ThreatSeverity::Critical
+            )
+            && !network::is_offline()
+        {
+            let detection = serde_json::to_string(&threat).unwrap_or_default();
+            match self
+                .summarize_incident(&attack::classification_prompt(&detection))
+                .await
+            {
+                Ok(reply) => threat.techniques = attack::parse_technique_ids(&reply),
+                Err(e) => debug!("ATT&CK classification of {} failed: {e}", threat.id),
+            }
+        }
+
+        let severity: oxide_core::severity::Severity = threat.severity.clone().into();
+        let mut indicators = vec![threat.description.clone()];
+        indicators.extend(threat.process_name.clone());
+        let info = ThreatInfo {
+            severity: StoredThreatSeverity::from(severity),
+            yara_rule: threat.details.get("rule_name").cloned(),
+            heuristic_score: None,
+            risk_score: Some(threat.risk_score),
+            timestamp: threat.timestamp,
+            process_chain: Vec::new(),
+            indicators,
+            mitigation_status: MitigationStatus::Detected,
+            techniques: threat.techniques,
+        };
+        if let Err(e) = backend.insert_threat(info).await {
+            warn!("Failed to store threat {}: {e:#}", threat.id);
+        }
+    }
+
+    /// Detections per ATT&CK tactic over the last `days`: stored threats and
+    /// consensus findings when SurrealDB is available, else the Guardian's
+    /// in-memory threat history.
+    pub async fn attack_matrix_summary(&self, days: u32) -> Result<AttackMatrixSummary, String> {
+        let since = Utc::now() - chrono::Duration::days(i64::from(days));
+        #[cfg(feature = "surrealdb-metrics")]
+        if let Some(backend) = self.surreal_backend() {
+            let detections = backend
+                .technique_detections(since)
+                .await
+                .map_err(|e| format!("Failed to load threat techniques: {e:#}"))?;
+            return Ok(attack::summarize(&detections));
+        }
+        let detections: Vec<Vec<String>> = self
+            .get_threat_history()
+            .into_iter()
+            .filter(|threat| threat.timestamp >= since)
+            .map(|threat| threat.techniques)
+            .collect();
+        Ok(attack::summarize(&detections))
+    }
+
+    /// Answer a chat from the local API. The current system status and
+    /// recent threats are prepended to `input`; the app's own conversation
+    /// and memory are not touched.
+    pub async fn api_chat_completion(
+        &self,
+        instructions: Option<String>,
+        history: Vec<Interaction>,
+        input: String,
+    ) -> Result<String, String> {
+        let _timer =
+            PerformanceTimer::new("api_chat_completion", Arc::clone(&self.performance_monitor));
+        let status = self.get_system_status();
+        let mut system_context = format!(
+            "[Oxide Pilot system status]\nCPU {:.1}%, memory {}/{} MB, {} processes, {} threats recorded\n",
+            status.cpu_usage,
+            status.memory_usage.0 / (1024 * 1024),
+            status.memory_usage.1 / (1024 * 1024),
+            status.process_count,
+            status.threat_count
+        );
+        for threat in self.get_threat_history().iter().rev().take(3) {
+            system_context.push_str(&format!(
+                "- [{:?}] {} ({})\n",
+                threat.severity,
+                threat.description,
+                threat.timestamp.format("%Y-%m-%d %H:%M")
+            ));
+        }
+        if let Some(instructions) = instructions {
+            system_context.push_str(&format!("\n[Instructions]\n{instructions}\n"));
+        }
+
+        let privacy = self.privacy_filter().await;
+        let history: Vec<Interaction> = history
+            .into_iter()
+            .map(|interaction| Interaction {
+                user_input: privacy.redact_text(&interaction.user_input),
+                agent_response: privacy.redact_text(&interaction.agent_response),
+                ..interaction
+            })
+            .collect();
+        let prompt = privacy.redact_text(&format!("{system_context}\n{input}"));
+        let context = desktop_context(&self.config, &privacy)
+            .await
+            .into_context(Some(serde_json::to_value(&status).unwrap_or_default()));
+        let request = self.copilot.complete(&prompt, &history, &context);
+        llm_audit::with_purpose("local_api", request)
+            .await
+            .map(|response| privacy.restore_text(&response))
+            .map_err(|e| e.to_string())
+    }
+
+    /// Send `payload` to the configured webhooks in the background.
+    pub fn notify_webhooks(&self, payload: WebhookPayload) {
+        self.webhooks.dispatch(payload);
+    }
+
+    /// Send a test event to every enabled webhook and wait for the results.
+    pub async fn test_webhooks(&self) -> Vec<DeliveryResult> {
+        let payload = WebhookPayload::new(
+            WebhookEvent::Test,
+            i18n::t("notification.webhook_test"),
+            serde_json::json!({ "version": env!("CARGO_PKG_VERSION") }),
+        );
+        self.webhooks.deliver_all(&payload).await
+    }
+
+    pub async fn log_playbook_event(
+        &self,
+        severity: SecuritySeverity,
+        description: String,
+        metadata: HashMap<String, String>,
+        session_id: Option<&str>,
+    ) {
+        let user_id = match session_id {
+            Some(id) => self
+                .security_manager
+                .validate_session(id)
+                .await
+                .ok()
+                .map(|session| session.user_id),
+            None => None,
+        };
+        // Critical incidents also reach syslog / the Event Log
+        let alert_severity = match severity {
+            SecuritySeverity::Critical => Some(ThreatSeverity::Critical),
+            SecuritySeverity::High => Some(ThreatSeverity::High),
+            _ => None,
+        };
+        if let Some(alert_severity) = alert_severity {
+            self.guardian.forward_alert(Alert {
+                timestamp: Utc::now(),
+                severity: alert_severity,
+                kind: "PlaybookRun".to_string(),
+                message: description.clone(),
+                fields: metadata
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect(),
+            });
+        }
+        self.security_manager
+            .log_security_event(
+                SecurityEventType::PlaybookRun,
+                severity,
+                user_id,
+                session_id.map(str::to_string),
+                description,
+                metadata,
+                None,
+            )
+            .await;
     }
 
     /// Returns true if a VirusTotal API key is configured via env or encrypted config.
@@ -928,13 +2250,14 @@ impl OxideSystem {
     pub async fn create_security_session(
         &self,
         user_id: String,
+        passphrase: Option<&str>,
         permissions: Vec<String>,
         ip_address: Option<String>,
         user_agent: Option<String>,
     ) -> Result<String, String> {
         let session = self
             .security_manager
-            .create_session(user_id, permissions, ip_address, user_agent)
+            .create_session(user_id, passphrase, permissions, ip_address, user_agent)
             .await
             .map_err(|e| e.to_string())?;
 
@@ -959,6 +2282,49 @@ impl OxideSystem {
             .map_err(|e| e.to_string())
     }
 
+    pub async fn require_security_permission(
+        &self,
+        session_id: &str,
+        permission: &str,
+    ) -> Result<(), String> {
+        self.security_manager
+            .require_permission(session_id, permission)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn get_session_permissions(&self, session_id: &str) -> Result<Vec<String>, String> {
+        self.security_manager
+            .validate_session(session_id)
+            .await
+            .map(|session| session.effective_permissions())
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn assign_security_role(
+        &self,
+        user_id: &str,
+        role: Role,
+        passphrase: Option<&str>,
+    ) -> Result<(), String> {
+        self.security_manager
+            .assign_role(user_id, role, passphrase)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn revoke_security_role(&self, user_id: &str) -> Result<Option<Role>, String> {
+        self.security_manager
+            .revoke_role(user_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn get_security_role_assignments(&self) -> HashMap<String, Role> {
+        self.security_manager.list_role_assignments().await
+    }
+
     pub async fn invalidate_security_session(&self, session_id: &str) -> Result<(), String> {
         self.security_manager
             .invalidate_session(session_id)
@@ -970,8 +2336,24 @@ impl OxideSystem {
         self.security_manager.get_security_events(limit).await
     }
 
-    pub async fn update_security_policy(&self, policy: SecurityPolicy) {
-        self.security_manager.update_security_policy(policy).await
+    pub fn security_manager(&self) -> &SecurityManager {
+        &self.security_manager
+    }
+
+    /// Turn session enforcement for guarded commands on, off or back to
+    /// automatic, and keep it in the in-memory config.
+    pub async fn set_command_permission_enforcement(&self, enforce: Option<bool>) -> bool {
+        {
+            let mut config = self.config.lock().await;
+            config
+                .security
+                .get_or_insert_with(Default::default)
+                .enforce_command_permissions = enforce;
+        }
+        self.security_manager
+            .set_command_permission_enforcement(enforce)
+            .await;
+        self.security_manager.command_permissions_enforced().await
     }
 
     pub async fn get_security_policy(&self) -> SecurityPolicy {
@@ -1000,6 +2382,33 @@ impl OxideSystem {
     }
 }
 
+fn record_scan<E>(kind: &str, detected: Result<bool, E>) {
+    let result = match detected {
+        Ok(true) => "detected",
+        Ok(false) => "clean",
+        Err(_) => "error",
+    };
+    prometheus::SCANS_TOTAL.inc(&[("kind", kind), ("result", result)]);
+}
+
+/// Speech languages from `config`, defaulting to the message locale's.
+fn voice_settings(config: &OxidePilotConfig) -> VoiceLanguageSettings {
+    let fallback = voice_language::for_locale(Locale::resolve(config.locale.as_deref()));
+    VoiceLanguageSettings::from_config(&config.voice.clone().unwrap_or_default(), fallback)
+}
+
+/// Foreground window and opted-in clipboard text, pseudonymized with the
+/// active privacy filter so the copilot response can be restored with it.
+async fn desktop_context(
+    config: &Mutex<OxidePilotConfig>,
+    privacy: &PrivacyFilter,
+) -> DesktopContext {
+    let copilot_config = config.lock().await.copilot.clone();
+    let mut desktop = DesktopContext::collect_async(copilot_config).await;
+    desktop.redact(privacy);
+    desktop
+}
+
 // Default configuration for easy setup
 // Note: OxidePilotConfig is defined in oxide-core, so we can't implement Default here
 // This implementation should be moved to oxide-core where OxidePilotConfig is defined
@@ -0,0 +1,145 @@
+//! Guardian actions exposed to the execution plan runner.
+//!
+//! Collaborative analysis advertises these names in `available_functions`;
+//! registering them next to the copilot functions lets
+//! [`oxide_copilot::plan_executor`] run the plan it produced.
+
+use crate::oxide_system::OxideSystem;
+use async_trait::async_trait;
+use oxide_copilot::functions::{ExecutableFunction, FunctionRegistry};
+use serde_json::{json, Value};
+
+/// Names listed in the collaborative context, in the order they are offered.
+pub const PLAN_FUNCTIONS: [&str; 4] = [
+    "scan_file",
+    "get_system_status",
+    "run_system_analysis",
+    "get_threat_history",
+];
+
+/// Copilot functions plus the Guardian actions bound to `system`.
+pub fn plan_registry(system: &OxideSystem) -> FunctionRegistry {
+    let mut registry = FunctionRegistry::new();
+    registry.register_function(Box::new(ScanFileAction(system.clone())));
+    registry.register_function(Box::new(SystemStatusAction(system.clone())));
+    registry.register_function(Box::new(SystemAnalysisAction(system.clone())));
+    registry.register_function(Box::new(ThreatHistoryAction(system.clone())));
+    registry
+}
+
+fn to_value<T: serde::Serialize>(value: T) -> Result<Value, String> {
+    serde_json::to_value(value).map_err(|e| format!("Failed to serialize result: {e}"))
+}
+
+struct ScanFileAction(OxideSystem);
+
+#[async_trait]
+impl ExecutableFunction for ScanFileAction {
+    fn name(&self) -> &str {
+        "scan_file"
+    }
+
+    fn description(&self) -> &str {
+        "Scans a file for malware, optionally quarantining it."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": { "type": "string" },
+                "use_cloud": { "type": "boolean" },
+                "quarantine": { "type": "boolean" }
+            },
+            "required": ["path"]
+        })
+    }
+
+    /// Quarantining moves the file away from its owner.
+    fn is_destructive(&self, args: &Value) -> bool {
+        args["quarantine"].as_bool().unwrap_or(false)
+    }
+
+    async fn execute(&self, args: Value) -> Result<Value, String> {
+        let path = args["path"]
+            .as_str()
+            .ok_or("Missing 'path' argument for scan_file")?
+            .to_string();
+        let use_cloud = args["use_cloud"].as_bool().unwrap_or(false);
+        let quarantine = args["quarantine"].as_bool().unwrap_or(false);
+        to_value(self.0.scan_file(path, use_cloud, quarantine).await?)
+    }
+}
+
+struct SystemStatusAction(OxideSystem);
+
+#[async_trait]
+impl ExecutableFunction for SystemStatusAction {
+    fn name(&self) -> &str {
+        "get_system_status"
+    }
+
+    fn description(&self) -> &str {
+        "Returns current CPU, memory and process information."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({})
+    }
+
+    async fn execute(&self, _args: Value) -> Result<Value, String> {
+        to_value(self.0.get_system_status())
+    }
+}
+
+struct SystemAnalysisAction(OxideSystem);
+
+#[async_trait]
+impl ExecutableFunction for SystemAnalysisAction {
+    fn name(&self) -> &str {
+        "run_system_analysis"
+    }
+
+    fn description(&self) -> &str {
+        "Collects performance metrics and the overall performance score."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({})
+    }
+
+    async fn execute(&self, _args: Value) -> Result<Value, String> {
+        Ok(json!({
+            "metrics": to_value(self.0.get_performance_metrics().await)?,
+            "performance_score": self.0.get_performance_score().await,
+        }))
+    }
+}
+
+struct ThreatHistoryAction(OxideSystem);
+
+#[async_trait]
+impl ExecutableFunction for ThreatHistoryAction {
+    fn name(&self) -> &str {
+        "get_threat_history"
+    }
+
+    fn description(&self) -> &str {
+        "Returns recently detected threats."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": { "limit": { "type": "integer" } }
+        })
+    }
+
+    async fn execute(&self, args: Value) -> Result<Value, String> {
+        let limit = args["limit"].as_u64().unwrap_or(20) as usize;
+        let mut threats = self.0.get_threat_history();
+        let keep_from = threats.len().saturating_sub(limit);
+        threats.drain(..keep_from);
+        to_value(threats)
+    }
+}
@@ -0,0 +1,213 @@
+//! Runs the incident response playbooks defined in
+//! `oxide_guardian::playbook`.
+//!
+//! Steps run in order and a failed step does not stop the ones after it,
+//! so evidence is still collected when isolation fails. A dry run reports
+//! what each step would do without touching the system. Every real run is
+//! written to the security audit log.
+
+use crate::evidence;
+use crate::oxide_system::OxideSystem;
+use chrono::Utc;
+use log::{info, warn};
+use oxide_core::security_manager::SecuritySeverity;
+use oxide_guardian::evidence::{threat_file, EVIDENCE_DIR};
+use oxide_guardian::guardian::ThreatEvent;
+use oxide_guardian::playbook::{Playbook, PlaybookRun, PlaybookStep, StepResult, StepStatus};
+use oxide_guardian::process_control::{self, ProcessIdentity};
+use std::collections::HashMap;
+
+const DEFAULT_SUMMARY_PROMPT: &str = "Summarize this security incident for a non-expert in \
+     three or four sentences: what happened, how serious it is, what has been done and what \
+     they should do next.";
+
+/// Run `playbook`, against `threat` when given.
+pub async fn run_playbook(
+    system: &OxideSystem,
+    playbook: &Playbook,
+    threat: Option<&ThreatEvent>,
+    dry_run: bool,
+    session_id: Option<&str>,
+) -> PlaybookRun {
+    let started_at = Utc::now();
+    let mut steps: Vec<StepResult> = Vec::new();
+    for step in &playbook.steps {
+        let needs_threat = !matches!(step, PlaybookStep::Notify { .. });
+        let (status, detail) = if needs_threat && threat.is_none() {
+            (StepStatus::Skipped, "Needs a threat to act on".to_string())
+        } else if dry_run {
+            (StepStatus::Planned, describe(step, threat))
+        } else {
+            match execute(system, playbook, step, threat, &steps, session_id).await {
+                Ok(detail) => (StepStatus::Succeeded, detail),
+                Err(e) => (StepStatus::Failed, e),
+            }
+        };
+        steps.push(StepResult {
+            step: step.clone(),
+            status,
+            detail,
+        });
+    }
+    let run = PlaybookRun {
+        playbook_id: playbook.id.clone(),
+        threat_id: threat.map(|t| t.id.clone()),
+        dry_run,
+        started_at,
+        finished_at: Utc::now(),
+        steps,
+    };
+    if !dry_run {
+        audit(system, &run, session_id).await;
+    }
+    run
+}
+
+/// Run every `auto_run` playbook whose trigger matches `threat`.
+pub async fn run_auto_playbooks(system: &OxideSystem, threat: &ThreatEvent) {
+    let library = system.playbook_library().await;
+    for playbook in library.auto_run_for(threat) {
+        info!(
+            "Running playbook '{}' for threat {}",
+            playbook.id, threat.id
+        );
+        let run = run_playbook(system, playbook, Some(threat), false, None).await;
+        if !run.succeeded() {
+            warn!("Playbook '{}' had failing steps", playbook.id);
+        }
+    }
+}
+
+fn describe(step: &PlaybookStep, threat: Option<&ThreatEvent>) -> String {
+    let process = threat
+        .and_then(|t| t.process_id.map(|pid| (pid, t.process_name.clone())))
+        .map(|(pid, name)| format!("{} ({pid})", name.unwrap_or_default()));
+    match step {
+        PlaybookStep::Notify { message } => format!(
+            "Would raise an alert: {}",
+            message.as_deref().unwrap_or("playbook triggered")
+        ),
+        PlaybookStep::Isolate => match process {
+            Some(process) => format!("Would suspend {process}"),
+            None => "Threat has no process to suspend".to_string(),
+        },
+        PlaybookStep::Scan => match threat.and_then(threat_file) {
+            Some(path) => format!("Would scan {path}"),
+            None => "Threat has no file to scan".to_string(),
+        },
+        PlaybookStep::CollectEvidence => format!("Would save evidence to {EVIDENCE_DIR}"),
+        PlaybookStep::Summarize { .. } => "Would ask the AI providers for a summary".to_string(),
+    }
+}
+
+async fn execute(
+    system: &OxideSystem,
+    playbook: &Playbook,
+    step: &PlaybookStep,
+    threat: Option<&ThreatEvent>,
+    previous: &[StepResult],
+    session_id: Option<&str>,
+) -> Result<String, String> {
+    match step {
+        PlaybookStep::Notify { message } => {
+            let message = message
+                .clone()
+                .unwrap_or_else(|| format!("Playbook '{}' triggered", playbook.id));
+            let description = match threat {
+                Some(threat) => format!("{message}: {}", threat.description),
+                None => message,
+            };
+            warn!("Playbook alert: {description}");
+            system
+                .log_playbook_event(
+                    SecuritySeverity::High,
+                    description.clone(),
+                    HashMap::from([("playbook_id".to_string(), playbook.id.clone())]),
+                    session_id,
+                )
+                .await;
+            Ok(description)
+        }
+        PlaybookStep::Isolate => {
+            let threat = threat.ok_or("Threat has no process to suspend")?;
+            let pid = threat
+                .process_id
+                .ok_or("Threat has no process to suspend")?;
+            // Only suspend the process the detector saw, not a reused pid
+            let expected = ProcessIdentity {
+                name: threat.process_name.clone(),
+                ..Default::default()
+            };
+            tokio::task::spawn_blocking(move || {
+                process_control::suspend_process(pid, Some(&expected))
+            })
+            .await
+            .map_err(|e| format!("Process control task join error: {e}"))?
+        }
+        PlaybookStep::Scan => {
+            let threat = threat.cloned();
+            let path = tokio::task::spawn_blocking(move || threat.as_ref().and_then(threat_file))
+                .await
+                .map_err(|e| format!("Scan task join error: {e}"))?
+                .ok_or("Threat has no file to scan")?;
+            let report = system.scan_file(path, false, false).await?;
+            Ok(if report.malicious {
+                format!("{} is malicious", report.path)
+            } else {
+                format!("{} matched no signatures", report.path)
+            })
+        }
+        PlaybookStep::CollectEvidence => {
+            let threat = threat.ok_or("Needs a threat to act on")?;
+            let bundle =
+                evidence::collect_bundle(system, threat, None, Default::default(), |_| {}).await?;
+            Ok(format!("Saved evidence to {}", bundle.archive_path))
+        }
+        PlaybookStep::Summarize { prompt } => {
+            let incident = serde_json::json!({
+                "threat": threat,
+                "actions": previous,
+            });
+            let prompt = format!(
+                "{}\n\nIncident:\n{incident}",
+                prompt.as_deref().unwrap_or(DEFAULT_SUMMARY_PROMPT)
+            );
+            system.summarize_incident(&prompt).await
+        }
+    }
+}
+
+async fn audit(system: &OxideSystem, run: &PlaybookRun, session_id: Option<&str>) {
+    let failed = run
+        .steps
+        .iter()
+        .filter(|s| s.status == StepStatus::Failed)
+        .count();
+    let description = format!(
+        "Playbook '{}' ran {} step(s), {failed} failed",
+        run.playbook_id,
+        run.steps.len()
+    );
+    let mut metadata = HashMap::from([
+        ("playbook_id".to_string(), run.playbook_id.clone()),
+        (
+            "steps".to_string(),
+            run.steps
+                .iter()
+                .map(|s| format!("{}:{:?}", s.step.action(), s.status).to_lowercase())
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+    ]);
+    if let Some(threat_id) = &run.threat_id {
+        metadata.insert("threat_id".to_string(), threat_id.clone());
+    }
+    let severity = if failed > 0 {
+        SecuritySeverity::High
+    } else {
+        SecuritySeverity::Medium
+    };
+    system
+        .log_playbook_event(severity, description, metadata, session_id)
+        .await;
+}
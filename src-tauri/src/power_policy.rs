@@ -0,0 +1,39 @@
+//! Battery saver supervisor.
+//!
+//! Reads the power source every [`CHECK_INTERVAL_SECS`] and publishes the
+//! resulting [`oxide_core::power`] policy. The config is re-read on every
+//! check, so threshold changes apply without a restart.
+
+use crate::oxide_system::OxideSystem;
+use log::warn;
+use oxide_core::power;
+use oxide_guardian::hardware;
+
+const CHECK_INTERVAL_SECS: u64 = 30;
+
+/// Evaluate the policy now and every [`CHECK_INTERVAL_SECS`] while the
+/// system runs.
+pub fn spawn(system: OxideSystem) {
+    tokio::spawn(async move {
+        loop {
+            let cfg = system.get_config().await.power.unwrap_or_default();
+            let reading = tokio::task::spawn_blocking(|| {
+                (
+                    hardware::power_source(),
+                    hardware::battery().map(|b| b.percent),
+                )
+            })
+            .await;
+            match reading {
+                Ok((source, percent)) => {
+                    power::set_policy(power::evaluate(&cfg, source, percent));
+                }
+                Err(e) => warn!("Power source check failed: {e}"),
+            }
+            tokio::time::sleep(std::time::Duration::from_secs(CHECK_INTERVAL_SECS)).await;
+            if !system.is_running().await {
+                break;
+            }
+        }
+    });
+}
@@ -0,0 +1,377 @@
+//! Aggregate protection status for the dashboard's status card.
+//!
+//! Combines Guardian's monitoring, signature and YARA state with the
+//! VirusTotal configuration, the watchdog's view of the background tasks
+//! and, when the SurrealDB backend is enabled, the last folder scan and the
+//! backlog of unacknowledged threats. The overall state and the list of
+//! issues are derived in [`ProtectionStatus::new`]; the battery saver policy
+//! is reported as is, and the work it postpones is not counted as an issue.
+
+use crate::watchdog::{SupervisedTask, TaskHealth};
+use chrono::{DateTime, Duration, Utc};
+use oxide_core::i18n;
+use oxide_core::power::{self, PowerPolicy};
+use oxide_guardian::av_coexistence::AvProduct;
+use oxide_guardian::guardian::GuardianProtection;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+/// Signature files older than this are reported as outdated.
+pub const SIGNATURES_STALE_DAYS: i64 = 7;
+
+/// Without a folder scan for this long, a scan is recommended.
+pub const SCAN_OVERDUE_DAYS: i64 = 7;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProtectionState {
+    Protected,
+    AttentionRequired,
+    AtRisk,
+}
+
+/// Scan and threat history from the database.
+#[derive(Debug, Clone, Default)]
+pub struct ScanActivity {
+    pub last_scan_at: Option<DateTime<Utc>>,
+    pub unacknowledged_threats: usize,
+    pub oldest_unacknowledged_threat_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProtectionStatus {
+    pub state: ProtectionState,
+    /// Why the state is not `protected`, most severe first.
+    pub issues: Vec<String>,
+    pub monitoring_enabled: bool,
+    /// The monitoring loop completed a pass within the last few intervals.
+    pub realtime_monitoring_active: bool,
+    pub last_monitor_cycle: Option<DateTime<Utc>>,
+    pub yara_available: bool,
+    pub virustotal_configured: bool,
+    pub signatures_path: Option<String>,
+    pub signature_count: Option<usize>,
+    pub signature_age_hours: Option<i64>,
+    /// Supervised background tasks with their restart counts.
+    pub background_tasks: Vec<TaskHealth>,
+    /// Other antivirus products; empty until Guardian's first check.
+    pub antivirus_products: Vec<AvProduct>,
+    /// Guardian work left to those products.
+    pub av_adjustments: Vec<String>,
+    /// Power source and what battery saver currently turns down.
+    pub power: PowerPolicy,
+    /// The fields below are `None` without the SurrealDB backend.
+    pub last_scan_at: Option<DateTime<Utc>>,
+    pub unacknowledged_threats: Option<usize>,
+    pub oldest_unacknowledged_threat_at: Option<DateTime<Utc>>,
+    pub checked_at: DateTime<Utc>,
+}
+
+impl ProtectionStatus {
+    pub fn new(
+        guardian: GuardianProtection,
+        virustotal_configured: bool,
+        activity: Option<ScanActivity>,
+        background_tasks: Vec<TaskHealth>,
+        power: PowerPolicy,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let stall_after = Duration::seconds(guardian.stall_after_secs() as i64);
+        let realtime_monitoring_active = guardian.monitoring_enabled
+            && guardian
+                .last_monitor_cycle
+                .is_some_and(|at| now - at <= stall_after);
+        let signature_age_hours = guardian
+            .signatures_updated_at
+            .map(|at| (now - at).num_hours());
+
+        let mut at_risk = Vec::new();
+        let mut attention = Vec::new();
+        if !guardian.monitoring_enabled {
+            at_risk.push("Real-time monitoring is turned off".to_string());
+        } else if !realtime_monitoring_active {
+            at_risk.push(match guardian.last_monitor_cycle {
+                Some(at) => format!(
+                    "Monitoring has not completed a pass since {}",
+                    at.to_rfc3339()
+                ),
+                None => "Monitoring has not completed a pass yet".to_string(),
+            });
+        }
+        for task in background_tasks.iter().filter(|t| !t.healthy) {
+            let issue = format!(
+                "{} failed ({}) and is being restarted",
+                task.task.label(),
+                task.last_failure.as_deref().unwrap_or("unknown cause")
+            );
+            if task.task == SupervisedTask::GuardianMonitoring {
+                at_risk.push(issue);
+            } else {
+                attention.push(issue);
+            }
+        }
+        if let Some(activity) = &activity {
+            if activity.unacknowledged_threats > 0 {
+                attention.push(format!(
+                    "{} threat(s) waiting for review",
+                    activity.unacknowledged_threats
+                ));
+            }
+            match activity.last_scan_at {
+                Some(at) if now - at <= Duration::days(SCAN_OVERDUE_DAYS) => {}
+                Some(_) => attention.push(format!(
+                    "No folder scan in the last {SCAN_OVERDUE_DAYS} days"
+                )),
+                None => attention.push("No folder scan has been run yet".to_string()),
+            }
+        }
+        if guardian.signatures_path.is_some() {
+            if guardian.signature_count.is_none() {
+                attention.push("Signature database failed to load".to_string());
+            } else if signature_age_hours.is_some_and(|h| h > SIGNATURES_STALE_DAYS * 24) {
+                attention.push(format!(
+                    "Signature database is older than {SIGNATURES_STALE_DAYS} days"
+                ));
+            }
+        }
+        if cfg!(feature = "yara-detection") && !guardian.yara_available {
+            attention.push("YARA rules failed to compile".to_string());
+        }
+        let (antivirus_products, av_adjustments) = guardian
+            .antivirus
+            .map(|av| (av.products, av.adjustments))
+            .unwrap_or_default();
+        let realtime: Vec<&AvProduct> = antivirus_products
+            .iter()
+            .filter(|p| p.realtime_enabled)
+            .collect();
+        if realtime.is_empty() && cfg!(windows) && !antivirus_products.is_empty() {
+            attention.push("No antivirus has real-time protection turned on".to_string());
+        }
+        for product in realtime
+            .iter()
+            .filter(|p| p.definitions_up_to_date == Some(false))
+        {
+            attention.push(format!(
+                "{} virus definitions are out of date",
+                product.name
+            ));
+        }
+        if realtime.len() > 1 {
+            let names: Vec<&str> = realtime.iter().map(|p| p.name.as_str()).collect();
+            attention.push(format!(
+                "Several antivirus products scan in real time and may conflict: {}",
+                names.join(", ")
+            ));
+        }
+
+        let state = if !at_risk.is_empty() {
+            ProtectionState::AtRisk
+        } else if !attention.is_empty() {
+            ProtectionState::AttentionRequired
+        } else {
+            ProtectionState::Protected
+        };
+        at_risk.extend(attention);
+
+        Self {
+            state,
+            issues: at_risk,
+            monitoring_enabled: guardian.monitoring_enabled,
+            realtime_monitoring_active,
+            last_monitor_cycle: guardian.last_monitor_cycle,
+            yara_available: guardian.yara_available,
+            virustotal_configured,
+            signatures_path: guardian.signatures_path,
+            signature_count: guardian.signature_count,
+            signature_age_hours,
+            background_tasks,
+            antivirus_products,
+            av_adjustments,
+            power,
+            last_scan_at: activity.as_ref().and_then(|a| a.last_scan_at),
+            unacknowledged_threats: activity.as_ref().map(|a| a.unacknowledged_threats),
+            oldest_unacknowledged_threat_at: activity
+                .and_then(|a| a.oldest_unacknowledged_threat_at),
+            checked_at: now,
+        }
+    }
+}
+
+#[cfg(feature = "surrealdb-metrics")]
+async fn scan_activity(state: &crate::AppState) -> Result<Option<ScanActivity>, String> {
+    // Report what Guardian knows even when the database cannot be opened
+    let backend = match state.surreal_backend().await {
+        Ok(backend) => backend,
+        Err(e) => {
+            log::warn!("Protection status without scan history: {e}");
+            return Ok(None);
+        }
+    };
+    let last_scan_at = backend
+        .list_scan_history(None, 1)
+        .await
+        .map_err(|e| format!("Failed to read scan history: {e:#}"))?
+        .first()
+        .map(|run| run.finished_at);
+    let (unacknowledged_threats, oldest_unacknowledged_threat_at) = backend
+        .unacknowledged_threats()
+        .await
+        .map_err(|e| format!("Failed to read threats: {e:#}"))?;
+    Ok(Some(ScanActivity {
+        last_scan_at,
+        unacknowledged_threats,
+        oldest_unacknowledged_threat_at,
+    }))
+}
+
+#[cfg(not(feature = "surrealdb-metrics"))]
+async fn scan_activity(_state: &crate::AppState) -> Result<Option<ScanActivity>, String> {
+    Ok(None)
+}
+
+/// Everything the protection status card shows, in one call.
+#[tauri::command]
+pub async fn get_protection_status(
+    state: State<'_, crate::AppState>,
+) -> Result<ProtectionStatus, String> {
+    let system = state
+        .oxide_system
+        .read()
+        .await
+        .clone()
+        .ok_or(i18n::t("error.system_not_initialized"))?;
+    let guardian = system.guardian_protection();
+    let virustotal_configured = system.has_virustotal_key().await;
+    let activity = scan_activity(&state).await?;
+    Ok(ProtectionStatus::new(
+        guardian,
+        virustotal_configured,
+        activity,
+        system.task_health(),
+        power::policy(),
+        Utc::now(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxide_guardian::av_coexistence::AvCoexistence;
+
+    fn healthy(now: DateTime<Utc>) -> GuardianProtection {
+        GuardianProtection {
+            monitoring_enabled: true,
+            monitor_interval_secs: 10,
+            last_monitor_cycle: Some(now - Duration::seconds(5)),
+            yara_available: cfg!(feature = "yara-detection"),
+            signatures_path: Some("signatures.json".to_string()),
+            signature_count: Some(1200),
+            signatures_updated_at: Some(now - Duration::days(1)),
+            antivirus: None,
+        }
+    }
+
+    #[test]
+    fn healthy_guardian_is_protected() {
+        let now = Utc::now();
+        let activity = ScanActivity {
+            last_scan_at: Some(now - Duration::days(2)),
+            ..Default::default()
+        };
+        let status = ProtectionStatus::new(
+            healthy(now),
+            false,
+            Some(activity),
+            Vec::new(),
+            PowerPolicy::default(),
+            now,
+        );
+        assert_eq!(status.state, ProtectionState::Protected);
+        assert!(status.issues.is_empty());
+        assert!(status.realtime_monitoring_active);
+        assert_eq!(status.signature_age_hours, Some(24));
+        assert_eq!(status.unacknowledged_threats, Some(0));
+    }
+
+    #[test]
+    fn stalled_monitoring_outranks_threat_backlog() {
+        let now = Utc::now();
+        let mut guardian = healthy(now);
+        guardian.last_monitor_cycle = Some(now - Duration::minutes(10));
+        guardian.signatures_updated_at = Some(now - Duration::days(30));
+        let activity = ScanActivity {
+            last_scan_at: None,
+            unacknowledged_threats: 3,
+            oldest_unacknowledged_threat_at: Some(now - Duration::days(4)),
+        };
+
+        let status = ProtectionStatus::new(
+            guardian,
+            true,
+            Some(activity),
+            Vec::new(),
+            PowerPolicy::default(),
+            now,
+        );
+        assert_eq!(status.state, ProtectionState::AtRisk);
+        assert!(!status.realtime_monitoring_active);
+        assert!(status.issues[0].starts_with("Monitoring has not completed a pass"));
+        assert!(status
+            .issues
+            .contains(&"3 threat(s) waiting for review".to_string()));
+        assert!(status
+            .issues
+            .iter()
+            .any(|i| i.contains("older than 7 days")));
+
+        let mut disabled = healthy(now);
+        disabled.monitoring_enabled = false;
+        let status = ProtectionStatus::new(
+            disabled,
+            true,
+            None,
+            Vec::new(),
+            PowerPolicy::default(),
+            now,
+        );
+        assert_eq!(status.issues, vec!["Real-time monitoring is turned off"]);
+        assert_eq!(status.unacknowledged_threats, None);
+    }
+
+    #[test]
+    fn reports_outdated_and_overlapping_antivirus() {
+        let now = Utc::now();
+        let product = |name: &str, up_to_date| AvProduct {
+            name: name.to_string(),
+            realtime_enabled: true,
+            definitions_up_to_date: Some(up_to_date),
+            is_defender: false,
+        };
+        let mut guardian = healthy(now);
+        guardian.antivirus = Some(AvCoexistence::new(
+            vec![product("ESET Security", false), product("Avast", true)],
+            true,
+            now,
+        ));
+
+        let status = ProtectionStatus::new(
+            guardian,
+            false,
+            None,
+            Vec::new(),
+            PowerPolicy::default(),
+            now,
+        );
+        assert_eq!(status.state, ProtectionState::AttentionRequired);
+        assert_eq!(
+            status.issues,
+            vec![
+                "ESET Security virus definitions are out of date",
+                "Several antivirus products scan in real time and may conflict: ESET Security, Avast",
+            ]
+        );
+        assert_eq!(status.antivirus_products.len(), 2);
+        assert!(!status.av_adjustments.is_empty());
+    }
+}
@@ -0,0 +1,413 @@
+//! Remote mode: watch and manage another Oxide Pilot's Guardian on the LAN.
+//!
+//! The other instance serves MCP with TLS and `allow_remote`, and issues a
+//! token for this one. Authentication goes both ways: the token identifies
+//! this instance to the remote and limits what it may do, and this instance
+//! pins the remote's certificate fingerprint (shown in its MCP status)
+//! instead of trusting a CA. Tokens are kept in the OS keyring.
+
+use crate::command_guard;
+use crate::mcp_server::RemoteStatus;
+use chrono::{DateTime, Utc};
+use log::info;
+use oxide_core::{i18n, network, remote_credentials, tls};
+use oxide_guardian::remediation::RemediationAction;
+use oxide_guardian::scanner::FileScanReport;
+use reqwest::{Method, StatusCode};
+use rustls::client::{ServerCertVerified, ServerCertVerifier};
+use rustls::{Certificate, ClientConfig, ServerName};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use tauri::State;
+
+pub const HOSTS_PATH: &str = "./data/remote_hosts.json";
+const TIMEOUT_SECS: u64 = 30;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RemoteHost {
+    pub id: String,
+    pub name: String,
+    /// `https://host:port` of the remote MCP server
+    pub url: String,
+    /// Pinned SHA-256 certificate fingerprint
+    pub fingerprint: String,
+    pub added_at: DateTime<Utc>,
+    pub last_seen_at: Option<DateTime<Utc>>,
+}
+
+/// The certificate a remote presented, for comparison before pinning it.
+#[derive(Serialize, Debug, Clone)]
+pub struct RemoteProbe {
+    pub url: String,
+    pub fingerprint: String,
+}
+
+/// Remote hosts this instance manages, saved as JSON.
+pub struct RemoteHosts {
+    path: PathBuf,
+    hosts: Mutex<Vec<RemoteHost>>,
+}
+
+impl RemoteHosts {
+    pub fn load(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let hosts = fs::read_to_string(&path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            hosts: Mutex::new(hosts),
+        }
+    }
+
+    pub fn list(&self) -> Vec<RemoteHost> {
+        self.lock().clone()
+    }
+
+    fn get(&self, id: &str) -> Result<RemoteHost, String> {
+        self.lock()
+            .iter()
+            .find(|h| h.id == id)
+            .cloned()
+            .ok_or_else(|| format!("No remote host with id {id}"))
+    }
+
+    fn insert(&self, host: RemoteHost) -> Result<(), String> {
+        let mut hosts = self.lock();
+        hosts.push(host);
+        self.save(&hosts)
+    }
+
+    fn remove(&self, id: &str) -> Result<RemoteHost, String> {
+        let mut hosts = self.lock();
+        let index = hosts
+            .iter()
+            .position(|h| h.id == id)
+            .ok_or_else(|| format!("No remote host with id {id}"))?;
+        let host = hosts.remove(index);
+        self.save(&hosts)?;
+        Ok(host)
+    }
+
+    fn touch(&self, id: &str) {
+        let mut hosts = self.lock();
+        if let Some(host) = hosts.iter_mut().find(|h| h.id == id) {
+            host.last_seen_at = Some(Utc::now());
+        }
+        if let Err(e) = self.save(&hosts) {
+            log::warn!("{e}");
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<RemoteHost>> {
+        self.hosts.lock().unwrap_or_else(|p| p.into_inner())
+    }
+
+    fn save(&self, hosts: &[RemoteHost]) -> Result<(), String> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create {dir:?}: {e}"))?;
+        }
+        let json = serde_json::to_string_pretty(hosts).map_err(|e| e.to_string())?;
+        fs::write(&self.path, json)
+            .map_err(|e| format!("Failed to save remote hosts to {:?}: {e}", self.path))
+    }
+}
+
+/// Accepts only the certificate with the pinned fingerprint. Without a pin
+/// (when probing) it accepts any and records the fingerprint it saw.
+struct PinnedCertificate {
+    pinned: Option<String>,
+    seen: Mutex<Option<String>>,
+}
+
+impl PinnedCertificate {
+    fn new(pinned: Option<String>) -> Arc<Self> {
+        Arc::new(Self {
+            pinned,
+            seen: Mutex::new(None),
+        })
+    }
+
+    fn seen(&self) -> Option<String> {
+        self.seen.lock().unwrap_or_else(|p| p.into_inner()).clone()
+    }
+}
+
+impl ServerCertVerifier for PinnedCertificate {
+    fn verify_server_cert(
+        &self,
+        end_entity: &Certificate,
+        _intermediates: &[Certificate],
+        _server_name: &ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let fingerprint = tls::fingerprint(&end_entity.0);
+        *self.seen.lock().unwrap_or_else(|p| p.into_inner()) = Some(fingerprint.clone());
+        match &self.pinned {
+            Some(pinned) if !pinned.trim().eq_ignore_ascii_case(&fingerprint) => {
+                Err(rustls::Error::General(format!(
+                    "certificate fingerprint {fingerprint} does not match the pinned one"
+                )))
+            }
+            _ => Ok(ServerCertVerified::assertion()),
+        }
+    }
+}
+
+fn client(verifier: Arc<PinnedCertificate>) -> Result<reqwest::Client, String> {
+    let config = ClientConfig::builder()
+        .with_safe_defaults()
+        .with_custom_certificate_verifier(verifier)
+        .with_no_client_auth();
+    reqwest::Client::builder()
+        .use_preconfigured_tls(config)
+        .timeout(Duration::from_secs(TIMEOUT_SECS))
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// reqwest hides the TLS failure in the error's sources.
+fn describe(error: &reqwest::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = std::error::Error::source(error);
+    while let Some(cause) = source {
+        message = format!("{message}: {cause}");
+        source = cause.source();
+    }
+    message
+}
+
+fn normalize_url(url: &str) -> Result<String, String> {
+    let url = url.trim().trim_end_matches('/');
+    if !url.starts_with("https://") {
+        return Err("Remote hosts must be reached over https://".to_string());
+    }
+    Ok(url.to_string())
+}
+
+async fn call<T: DeserializeOwned>(
+    host: &RemoteHost,
+    method: Method,
+    path: &str,
+    body: Option<serde_json::Value>,
+) -> Result<T, String> {
+    network::ensure_online(&host.url).map_err(|e| e.to_string())?;
+    let token = remote_credentials::get_token(&host.id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("No token is stored for {}", host.name))?;
+    let client = client(PinnedCertificate::new(Some(host.fingerprint.clone())))?;
+    let mut request = client
+        .request(method, format!("{}{path}", host.url))
+        .bearer_auth(token);
+    if let Some(body) = body {
+        request = request.json(&body);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("{} is unreachable: {}", host.name, describe(&e)))?;
+    let status = response.status();
+    if status.is_success() {
+        return response
+            .json()
+            .await
+            .map_err(|e| format!("{} sent an unexpected response: {e}", host.name));
+    }
+    let text = response.text().await.unwrap_or_default();
+    Err(match status {
+        StatusCode::UNAUTHORIZED => format!(
+            "{} rejected the token; it may have expired or been revoked",
+            host.name
+        ),
+        _ => format!("{} returned {status}: {text}", host.name),
+    })
+}
+
+/// Fetch the certificate fingerprint a remote presents, without sending a
+/// token, so it can be compared with the one the remote shows.
+#[tauri::command]
+pub async fn probe_remote_host(url: String) -> Result<RemoteProbe, String> {
+    let url = normalize_url(&url)?;
+    network::ensure_online(&url).map_err(|e| e.to_string())?;
+    let verifier = PinnedCertificate::new(None);
+    let result = client(verifier.clone())?
+        .get(format!("{url}/health"))
+        .send()
+        .await;
+    match verifier.seen() {
+        Some(fingerprint) => Ok(RemoteProbe { url, fingerprint }),
+        None => Err(match result {
+            Err(e) => format!("{url} is unreachable: {}", describe(&e)),
+            Ok(_) => format!("{url} did not present a certificate"),
+        }),
+    }
+}
+
+/// Pin a remote and store its token, after checking that both work.
+#[tauri::command]
+pub async fn add_remote_host(
+    name: String,
+    url: String,
+    fingerprint: String,
+    token: String,
+    session_id: Option<String>,
+    state: State<'_, crate::AppState>,
+) -> Result<RemoteHost, String> {
+    let system = state
+        .oxide_system
+        .read()
+        .await
+        .clone()
+        .ok_or(i18n::t("error.system_not_initialized"))?;
+    command_guard::authorize_command(&system, session_id.as_deref(), "add_remote_host").await?;
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Remote host name is required".to_string());
+    }
+    let mut host = RemoteHost {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: name.to_string(),
+        url: normalize_url(&url)?,
+        fingerprint: fingerprint.trim().to_uppercase(),
+        added_at: Utc::now(),
+        last_seen_at: None,
+    };
+    remote_credentials::store_token(&host.id, token.trim()).map_err(|e| e.to_string())?;
+    if let Err(e) = call::<RemoteStatus>(&host, Method::GET, "/remote/status", None).await {
+        let _ = remote_credentials::clear_token(&host.id);
+        return Err(e);
+    }
+    host.last_seen_at = Some(Utc::now());
+    state.remote_hosts.insert(host.clone())?;
+    info!("Added remote host {} at {}", host.name, host.url);
+    Ok(host)
+}
+
+#[tauri::command]
+pub async fn list_remote_hosts(
+    state: State<'_, crate::AppState>,
+) -> Result<Vec<RemoteHost>, String> {
+    Ok(state.remote_hosts.list())
+}
+
+#[tauri::command]
+pub async fn remove_remote_host(
+    id: String,
+    session_id: Option<String>,
+    state: State<'_, crate::AppState>,
+) -> Result<RemoteHost, String> {
+    let system = state
+        .oxide_system
+        .read()
+        .await
+        .clone()
+        .ok_or(i18n::t("error.system_not_initialized"))?;
+    command_guard::authorize_command(&system, session_id.as_deref(), "remove_remote_host").await?;
+    let host = state.remote_hosts.remove(&id)?;
+    remote_credentials::clear_token(&id).map_err(|e| e.to_string())?;
+    info!("Removed remote host {}", host.name);
+    Ok(host)
+}
+
+/// Protection status, system status, recent threats and pending
+/// remediations of a remote.
+#[tauri::command]
+pub async fn get_remote_status(
+    id: String,
+    state: State<'_, crate::AppState>,
+) -> Result<RemoteStatus, String> {
+    let host = state.remote_hosts.get(&id)?;
+    let status = call(&host, Method::GET, "/remote/status", None).await?;
+    state.remote_hosts.touch(&id);
+    Ok(status)
+}
+
+/// Scan a file on the remote; needs a token with the tool execution scope.
+#[tauri::command]
+pub async fn remote_scan_file(
+    id: String,
+    path: String,
+    use_cloud: bool,
+    quarantine: bool,
+    session_id: Option<String>,
+    state: State<'_, crate::AppState>,
+) -> Result<FileScanReport, String> {
+    let system = state
+        .oxide_system
+        .read()
+        .await
+        .clone()
+        .ok_or(i18n::t("error.system_not_initialized"))?;
+    command_guard::authorize_command(&system, session_id.as_deref(), "remote_scan_file").await?;
+    let host = state.remote_hosts.get(&id)?;
+    let body = serde_json::json!({
+        "path": path,
+        "use_cloud": use_cloud,
+        "quarantine": quarantine,
+    });
+    call(&host, Method::POST, "/remote/scan", Some(body)).await
+}
+
+/// Approve or reject a pending remediation on the remote.
+#[tauri::command]
+pub async fn remote_respond_remediation(
+    id: String,
+    action_id: String,
+    approved: bool,
+    reason: Option<String>,
+    session_id: Option<String>,
+    state: State<'_, crate::AppState>,
+) -> Result<RemediationAction, String> {
+    let system = state
+        .oxide_system
+        .read()
+        .await
+        .clone()
+        .ok_or(i18n::t("error.system_not_initialized"))?;
+    command_guard::authorize_command(&system, session_id.as_deref(), "remote_respond_remediation")
+        .await?;
+    let host = state.remote_hosts.get(&id)?;
+    let body = serde_json::json!({ "approved": approved, "reason": reason });
+    let path = format!("/remote/remediation/{action_id}");
+    call(&host, Method::POST, &path, Some(body)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_pinned_certificate_is_accepted() {
+        let cert = Certificate(b"not really DER".to_vec());
+        let fingerprint = tls::fingerprint(&cert.0);
+        let name = ServerName::try_from("192.168.1.20").unwrap();
+        let verify = |verifier: &PinnedCertificate| {
+            verifier.verify_server_cert(
+                &cert,
+                &[],
+                &name,
+                &mut std::iter::empty(),
+                &[],
+                SystemTime::now(),
+            )
+        };
+
+        let probe = PinnedCertificate::new(None);
+        assert!(verify(&probe).is_ok());
+        assert_eq!(probe.seen(), Some(fingerprint.clone()));
+        assert!(verify(&PinnedCertificate::new(Some(fingerprint.to_lowercase()))).is_ok());
+        assert!(verify(&PinnedCertificate::new(Some("00:11".to_string()))).is_err());
+        assert!(normalize_url("http://192.168.1.20:7999").is_err());
+        assert_eq!(
+            normalize_url(" https://192.168.1.20:7999/ ").unwrap(),
+            "https://192.168.1.20:7999"
+        );
+    }
+}
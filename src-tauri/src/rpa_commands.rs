@@ -1,11 +1,27 @@
+use crate::command_guard;
+use crate::events;
+use oxide_core::capabilities::{self, Feature};
+use oxide_core::i18n;
 use oxide_rpa::audit::{AuditEntry, AuditStats};
 use oxide_rpa::confirmation::ConfirmationRequest;
-use oxide_rpa::permissions::{Permission, PermissionPolicy};
-use oxide_rpa::rollback::ReversibleAction;
-use oxide_rpa::secure_rpa::SecureRPAController;
+use oxide_rpa::grants::{GrantManager, PermissionGrant};
+use oxide_rpa::ocr::{OcrResult, ScreenRegion};
+use oxide_rpa::permissions::{Permission, PermissionPolicy, PermissionProfile};
+use oxide_rpa::rollback::{Checkpoint, ReversibleAction};
+use oxide_rpa::secure_rpa::{RollbackReport, SecureRPAController, TextVerification};
+use oxide_rpa::window_manager::{WindowAction, WindowInfo, WindowQuery};
 use serde::{Deserialize, Serialize};
 use tauri::State;
 
+/// Grants without an explicit duration last this long
+const DEFAULT_GRANT_SECS: i64 = 3600;
+
+/// How long `rpa_verify_screen_text` waits for the text by default
+const DEFAULT_VERIFY_TIMEOUT_MS: u64 = 5000;
+
+/// How often expired grants are swept so revocations are announced promptly
+const GRANT_SWEEP_SECS: u64 = 5;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RPAInitConfig {
     pub policy_type: String, // "default", "permissive", "restrictive"
@@ -20,6 +36,7 @@ pub struct RPAInitConfig {
 #[tauri::command]
 pub async fn rpa_initialize(
     config: RPAInitConfig,
+    app: tauri::AppHandle,
     state: State<'_, crate::AppState>,
 ) -> Result<String, String> {
     let policy = match config.policy_type.as_str() {
@@ -38,8 +55,11 @@ pub async fn rpa_initialize(
         controller = controller.with_rollback_size(rollback_size);
     }
 
+    spawn_grant_watch(app, controller.grants().clone(), state.rpa_state.clone());
+
     let mut state_lock = state.rpa_state.write().await;
     *state_lock = Some(controller);
+    capabilities::available(Feature::Rpa);
 
     Ok("RPA system initialized successfully".to_string())
 }
@@ -48,9 +68,19 @@ pub async fn rpa_initialize(
 pub async fn rpa_shutdown(state: State<'_, crate::AppState>) -> Result<String, String> {
     let mut state_lock = state.rpa_state.write().await;
     *state_lock = None;
+    capabilities::unavailable(Feature::Rpa, "RPA was shut down");
     Ok("RPA system shutdown successfully".to_string())
 }
 
+/// Accept actions again after a panic-stop. Revoked grants stay revoked.
+#[tauri::command]
+pub async fn rpa_resume(state: State<'_, crate::AppState>) -> Result<(), String> {
+    let state_lock = state.rpa_state.read().await;
+    let controller = state_lock.as_ref().ok_or("RPA not initialized")?;
+    controller.resume();
+    Ok(())
+}
+
 // ==============================
 // Permission Management Commands
 // ==============================
@@ -58,14 +88,104 @@ pub async fn rpa_shutdown(state: State<'_, crate::AppState>) -> Result<String, S
 #[tauri::command]
 pub async fn rpa_grant_permission(
     permission: String,
+    duration_secs: Option<i64>,
+    applications: Option<Vec<String>>,
+    session_id: Option<String>,
+    state: State<'_, crate::AppState>,
+) -> Result<PermissionGrant, String> {
+    let perm = parse_permission(&permission)?;
+    let system = state
+        .oxide_system
+        .read()
+        .await
+        .clone()
+        .ok_or(i18n::t("error.system_not_initialized"))?;
+    command_guard::authorize_command(&system, session_id.as_deref(), "rpa_grant_permission")
+        .await?;
+
+    let state_lock = state.rpa_state.read().await;
+    let controller = state_lock.as_ref().ok_or("RPA not initialized")?;
+
+    controller
+        .grants()
+        .grant(
+            [perm].into(),
+            applications.unwrap_or_default(),
+            Some(grant_ttl(duration_secs)),
+        )
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn rpa_grant_profile(
+    profile: PermissionProfile,
+    duration_secs: Option<i64>,
+    applications: Option<Vec<String>>,
+    session_id: Option<String>,
+    state: State<'_, crate::AppState>,
+) -> Result<PermissionGrant, String> {
+    let system = state
+        .oxide_system
+        .read()
+        .await
+        .clone()
+        .ok_or(i18n::t("error.system_not_initialized"))?;
+    command_guard::authorize_command(&system, session_id.as_deref(), "rpa_grant_profile").await?;
+    let state_lock = state.rpa_state.read().await;
+    let controller = state_lock.as_ref().ok_or("RPA not initialized")?;
+
+    controller
+        .grants()
+        .grant_profile(
+            profile,
+            applications.unwrap_or_default(),
+            Some(grant_ttl(duration_secs)),
+        )
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn rpa_revoke_grant(
+    grant_id: String,
+    session_id: Option<String>,
     state: State<'_, crate::AppState>,
 ) -> Result<(), String> {
-    let _perm = parse_permission(&permission)?;
+    let system = state
+        .oxide_system
+        .read()
+        .await
+        .clone()
+        .ok_or(i18n::t("error.system_not_initialized"))?;
+    command_guard::authorize_command(&system, session_id.as_deref(), "rpa_revoke_grant").await?;
+    let state_lock = state.rpa_state.read().await;
+    let controller = state_lock.as_ref().ok_or("RPA not initialized")?;
 
+    controller
+        .grants()
+        .revoke(&grant_id)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn rpa_list_grants(
+    state: State<'_, crate::AppState>,
+) -> Result<Vec<PermissionGrant>, String> {
     let state_lock = state.rpa_state.read().await;
-    let _controller = state_lock.as_ref().ok_or("RPA not initialized")?;
+    let controller = state_lock.as_ref().ok_or("RPA not initialized")?;
+
+    controller.grants().active().map_err(|e| e.to_string())
+}
 
-    // Note: This requires making policy mutable - for now return info
+#[tauri::command]
+pub async fn rpa_set_target_application(
+    application: Option<String>,
+    state: State<'_, crate::AppState>,
+) -> Result<(), String> {
+    let state_lock = state.rpa_state.read().await;
+    let controller = state_lock.as_ref().ok_or("RPA not initialized")?;
+
+    controller.set_target_application(application.filter(|app| !app.trim().is_empty()));
     Ok(())
 }
 
@@ -74,13 +194,12 @@ pub async fn rpa_check_permission(
     permission: String,
     state: State<'_, crate::AppState>,
 ) -> Result<bool, String> {
-    let _perm = parse_permission(&permission)?;
+    let perm = parse_permission(&permission)?;
 
     let state_lock = state.rpa_state.read().await;
-    let _controller = state_lock.as_ref().ok_or("RPA not initialized")?;
+    let controller = state_lock.as_ref().ok_or("RPA not initialized")?;
 
-    // Access policy through controller (would need getter method)
-    Ok(true) // Placeholder
+    Ok(controller.is_permitted(perm))
 }
 
 // ==============================
@@ -180,6 +299,124 @@ pub async fn rpa_capture_screen(state: State<'_, crate::AppState>) -> Result<Vec
     Ok(bytes)
 }
 
+#[tauri::command]
+pub async fn rpa_read_screen_text(
+    region: Option<ScreenRegion>,
+    state: State<'_, crate::AppState>,
+) -> Result<OcrResult, String> {
+    let state_lock = state.rpa_state.read().await;
+    let controller = state_lock.as_ref().ok_or("RPA not initialized")?;
+
+    controller
+        .read_screen_text(region)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn rpa_verify_screen_text(
+    expected: String,
+    region: Option<ScreenRegion>,
+    timeout_ms: Option<u64>,
+    state: State<'_, crate::AppState>,
+) -> Result<TextVerification, String> {
+    let state_lock = state.rpa_state.read().await;
+    let controller = state_lock.as_ref().ok_or("RPA not initialized")?;
+
+    let timeout = std::time::Duration::from_millis(timeout_ms.unwrap_or(DEFAULT_VERIFY_TIMEOUT_MS));
+    controller
+        .verify_screen_text(&expected, region, timeout)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ==============================
+// File and Clipboard Commands
+// ==============================
+
+#[tauri::command]
+pub async fn rpa_move_file(
+    from: String,
+    to: String,
+    state: State<'_, crate::AppState>,
+) -> Result<(), String> {
+    let state_lock = state.rpa_state.read().await;
+    let controller = state_lock.as_ref().ok_or("RPA not initialized")?;
+
+    controller
+        .move_file(&from, &to)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn rpa_set_clipboard(
+    text: String,
+    state: State<'_, crate::AppState>,
+) -> Result<(), String> {
+    let state_lock = state.rpa_state.read().await;
+    let controller = state_lock.as_ref().ok_or("RPA not initialized")?;
+
+    controller
+        .set_clipboard(&text)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ==============================
+// Window Management Commands
+// ==============================
+
+#[tauri::command]
+pub async fn rpa_list_windows(
+    state: State<'_, crate::AppState>,
+) -> Result<Vec<WindowInfo>, String> {
+    let state_lock = state.rpa_state.read().await;
+    let controller = state_lock.as_ref().ok_or("RPA not initialized")?;
+
+    controller.list_windows().await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn rpa_window_action(
+    action: String,
+    title: Option<String>,
+    process: Option<String>,
+    state: State<'_, crate::AppState>,
+) -> Result<WindowInfo, String> {
+    let action: WindowAction = action.parse()?;
+    let query = WindowQuery { title, process };
+
+    let state_lock = state.rpa_state.read().await;
+    let controller = state_lock.as_ref().ok_or("RPA not initialized")?;
+
+    controller
+        .window_action(&query, action)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn rpa_move_window(
+    title: Option<String>,
+    process: Option<String>,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    state: State<'_, crate::AppState>,
+) -> Result<WindowInfo, String> {
+    let query = WindowQuery { title, process };
+
+    let state_lock = state.rpa_state.read().await;
+    let controller = state_lock.as_ref().ok_or("RPA not initialized")?;
+
+    controller
+        .move_window(&query, x, y, width, height)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 // ==============================
 // Audit Commands
 // ==============================
@@ -248,6 +485,46 @@ pub async fn rpa_get_reversible_count(state: State<'_, crate::AppState>) -> Resu
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn rpa_create_checkpoint(
+    label: String,
+    state: State<'_, crate::AppState>,
+) -> Result<Checkpoint, String> {
+    let state_lock = state.rpa_state.read().await;
+    let controller = state_lock.as_ref().ok_or("RPA not initialized")?;
+
+    controller
+        .create_checkpoint(&label)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn rpa_list_checkpoints(
+    state: State<'_, crate::AppState>,
+) -> Result<Vec<Checkpoint>, String> {
+    let state_lock = state.rpa_state.read().await;
+    let controller = state_lock.as_ref().ok_or("RPA not initialized")?;
+
+    controller
+        .rollback()
+        .checkpoints()
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn rpa_rollback_to(
+    checkpoint_id: String,
+    state: State<'_, crate::AppState>,
+) -> Result<RollbackReport, String> {
+    let state_lock = state.rpa_state.read().await;
+    let controller = state_lock.as_ref().ok_or("RPA not initialized")?;
+
+    controller
+        .rollback_to(&checkpoint_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 // ==============================
 // Confirmation Commands
 // ==============================
@@ -313,6 +590,9 @@ fn parse_permission(s: &str) -> Result<Permission, String> {
         "screen_capture" => Ok(Permission::ScreenCapture),
         "screen_capture_area" => Ok(Permission::ScreenCaptureArea),
         "screen_analyze" => Ok(Permission::ScreenAnalyze),
+        "clipboard_write" => Ok(Permission::ClipboardWrite),
+        "window_manage" => Ok(Permission::WindowManage),
+        "window_close" => Ok(Permission::WindowClose),
         "file_read" => Ok(Permission::FileRead),
         "file_write" => Ok(Permission::FileWrite),
         "file_delete" => Ok(Permission::FileDelete),
@@ -323,6 +603,43 @@ fn parse_permission(s: &str) -> Result<Permission, String> {
     }
 }
 
+fn grant_ttl(duration_secs: Option<i64>) -> chrono::Duration {
+    chrono::Duration::seconds(duration_secs.unwrap_or(DEFAULT_GRANT_SECS))
+}
+
+/// Forward grant events to the frontend and sweep expired grants until the
+/// controller owning `grants` is shut down or replaced.
+fn spawn_grant_watch(
+    app: tauri::AppHandle,
+    grants: GrantManager,
+    rpa_state: std::sync::Arc<tokio::sync::RwLock<Option<SecureRPAController>>>,
+) {
+    let mut events = grants.subscribe();
+    tokio::spawn(async move {
+        while let Ok(event) = events.recv().await {
+            events::emit(&app, &event);
+        }
+    });
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(GRANT_SWEEP_SECS));
+        loop {
+            interval.tick().await;
+            let current = rpa_state
+                .read()
+                .await
+                .as_ref()
+                .is_some_and(|controller| controller.grants().same_as(&grants));
+            if !current {
+                break;
+            }
+            if let Err(e) = grants.expire(chrono::Utc::now()) {
+                log::warn!("Failed to expire RPA grants: {e}");
+            }
+        }
+    });
+}
+
 fn parse_key(s: &str) -> Result<oxide_rpa::rpa::Key, String> {
     use oxide_rpa::rpa::Key;
     match s.to_lowercase().as_str() {
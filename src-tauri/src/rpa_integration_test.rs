@@ -10,7 +10,6 @@ mod tests {
             oxide_system: Arc::new(RwLock::new(None)),
             auth_manager: Arc::new(RwLock::new(None)),
             mcp_server: Arc::new(RwLock::new(None)),
-            folder_scan_cancels: Arc::new(RwLock::new(std::collections::HashMap::new())),
             rpa_state: Arc::new(RwLock::new(None)),
         }
     }
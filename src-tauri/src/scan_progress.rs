@@ -0,0 +1,173 @@
+//! Throughput and ETA estimates for folder scans.
+//!
+//! Rates come from a rolling window of progress samples, so the ETA follows
+//! the current pace (a run of large archives after thousands of small files)
+//! instead of the average since the scan started. Worker utilization is the
+//! share of wall time each worker spent scanning rather than waiting.
+
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// How far back rate estimates look
+pub const DEFAULT_RATE_WINDOW: Duration = Duration::from_secs(10);
+
+/// Throughput fields of a `folder_scan_progress` payload
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScanRate {
+    pub files_per_sec: f64,
+    pub bytes_per_sec: f64,
+    pub bytes_scanned: u64,
+    pub bytes_total: u64,
+    /// `None` until enough progress has been made to estimate
+    pub eta_secs: Option<u64>,
+}
+
+/// Rolling-window estimator over cumulative (files, bytes) progress.
+pub struct ScanRateEstimator {
+    window: Duration,
+    files_total: u64,
+    bytes_total: u64,
+    samples: VecDeque<(Instant, u64, u64)>,
+}
+
+impl ScanRateEstimator {
+    pub fn new(files_total: u64, bytes_total: u64, window: Duration) -> Self {
+        Self::starting_at(Instant::now(), files_total, bytes_total, window)
+    }
+
+    fn starting_at(start: Instant, files_total: u64, bytes_total: u64, window: Duration) -> Self {
+        Self {
+            window,
+            files_total,
+            bytes_total,
+            samples: VecDeque::from([(start, 0, 0)]),
+        }
+    }
+
+    /// Record cumulative progress at `now` and return the current rates.
+    /// Workers report concurrently, so counts never move backwards here.
+    pub fn observe(&mut self, now: Instant, files_done: u64, bytes_done: u64) -> ScanRate {
+        let (last_at, last_files, last_bytes) = *self.samples.back().expect("seeded sample");
+        let now = now.max(last_at);
+        let files_done = files_done.max(last_files);
+        let bytes_done = bytes_done.max(last_bytes);
+        self.samples.push_back((now, files_done, bytes_done));
+        // Keep one sample older than the window as the baseline
+        while self.samples.len() > 2 && now.duration_since(self.samples[1].0) >= self.window {
+            self.samples.pop_front();
+        }
+
+        let (since, files_then, bytes_then) = self.samples[0];
+        let span = now.duration_since(since).as_secs_f64();
+        let (files_per_sec, bytes_per_sec) = if span > 0.0 {
+            (
+                (files_done - files_then) as f64 / span,
+                (bytes_done - bytes_then) as f64 / span,
+            )
+        } else {
+            (0.0, 0.0)
+        };
+
+        let files_left = self.files_total.saturating_sub(files_done);
+        let bytes_left = self.bytes_total.saturating_sub(bytes_done);
+        // Hashing time follows bytes, so prefer them when sizes are known
+        let eta_secs = if files_left == 0 {
+            Some(0)
+        } else if bytes_left > 0 && bytes_per_sec > 0.0 {
+            Some((bytes_left as f64 / bytes_per_sec).ceil() as u64)
+        } else if files_per_sec > 0.0 {
+            Some((files_left as f64 / files_per_sec).ceil() as u64)
+        } else {
+            None
+        };
+
+        ScanRate {
+            files_per_sec,
+            bytes_per_sec,
+            bytes_scanned: bytes_done,
+            bytes_total: self.bytes_total,
+            eta_secs,
+        }
+    }
+}
+
+/// Busy time per scan worker.
+pub struct WorkerUtilization {
+    started: Instant,
+    busy_micros: Vec<AtomicU64>,
+}
+
+impl WorkerUtilization {
+    pub fn new(workers: usize) -> Self {
+        Self {
+            started: Instant::now(),
+            busy_micros: (0..workers).map(|_| AtomicU64::new(0)).collect(),
+        }
+    }
+
+    pub fn record(&self, worker: usize, busy: Duration) {
+        if let Some(slot) = self.busy_micros.get(worker) {
+            slot.fetch_add(busy.as_micros() as u64, Ordering::Relaxed);
+        }
+    }
+
+    /// Fraction of elapsed time each worker spent busy, 0.0 to 1.0.
+    pub fn snapshot(&self) -> Vec<f64> {
+        let elapsed = self.started.elapsed().as_micros().max(1) as f64;
+        self.busy_micros
+            .iter()
+            .map(|busy| (busy.load(Ordering::Relaxed) as f64 / elapsed).min(1.0))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_follows_the_recent_window() {
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        let mut est = ScanRateEstimator::starting_at(start, 100, 10_000, Duration::from_secs(10));
+
+        // Fast start: 10 files / 1000 bytes per second
+        let mut rate = est.observe(at(1), 10, 1_000);
+        for s in 2..=5 {
+            rate = est.observe(at(s), s * 10, s * 1_000);
+        }
+        assert_eq!(rate.files_per_sec, 10.0);
+        assert_eq!(rate.eta_secs, Some(5));
+
+        // Slows to 1 file / 100 bytes per second; once the fast samples
+        // leave the window the ETA reflects the new pace
+        for s in 6..=20 {
+            rate = est.observe(at(s), 50 + (s - 5), 5_000 + (s - 5) * 100);
+        }
+        assert_eq!(rate.files_per_sec, 1.0);
+        assert_eq!(rate.bytes_scanned, 6_500);
+        assert_eq!(rate.eta_secs, Some(35));
+
+        // An out-of-order report from a slower worker is ignored
+        let late = est.observe(at(19), 60, 6_400);
+        assert_eq!(late.bytes_scanned, 6_500);
+
+        assert_eq!(est.observe(at(21), 100, 10_000).eta_secs, Some(0));
+    }
+
+    #[test]
+    fn eta_falls_back_to_file_rate_without_sizes() {
+        let start = Instant::now();
+        let mut est = ScanRateEstimator::starting_at(start, 10, 0, DEFAULT_RATE_WINDOW);
+        assert_eq!(est.observe(start, 0, 0).eta_secs, None);
+        let rate = est.observe(start + Duration::from_secs(2), 4, 0);
+        assert_eq!(rate.eta_secs, Some(3));
+
+        let workers = WorkerUtilization::new(2);
+        workers.record(0, Duration::from_secs(3600));
+        workers.record(5, Duration::from_secs(1));
+        assert_eq!(workers.snapshot(), vec![1.0, 0.0]);
+    }
+}
@@ -74,6 +74,18 @@ pub struct SecurityDiagnosticReport {
     pub recommendations: Vec<String>,
 }
 
+/// Quick CPU and memory health check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemHealth {
+    /// `healthy`, `warning` or `critical`
+    pub status: String,
+    pub cpu_usage: f32,
+    pub memory_percent: f64,
+    pub memory_used_gb: f64,
+    pub memory_total_gb: f64,
+    pub timestamp: String,
+}
+
 /// State for security diagnostic operations
 pub struct SecurityDiagnosticState {
     pub monitor: Arc<RwLock<SystemMonitor>>,
@@ -366,7 +378,7 @@ pub async fn get_last_security_scan(
 #[tauri::command]
 pub async fn get_system_health(
     state: tauri::State<'_, SecurityDiagnosticState>,
-) -> Result<serde_json::Value, String> {
+) -> Result<SystemHealth, String> {
     let mut monitor = state.monitor.write().await;
     monitor.refresh_system();
 
@@ -382,14 +394,14 @@ pub async fn get_system_health(
         "healthy"
     };
 
-    Ok(serde_json::json!({
-        "status": health_status,
-        "cpu_usage": cpu_usage,
-        "memory_percent": memory_percent,
-        "memory_used_gb": memory_used as f64 / (1024.0 * 1024.0 * 1024.0),
-        "memory_total_gb": memory_total as f64 / (1024.0 * 1024.0 * 1024.0),
-        "timestamp": chrono::Utc::now().to_rfc3339(),
-    }))
+    Ok(SystemHealth {
+        status: health_status.to_string(),
+        cpu_usage,
+        memory_percent,
+        memory_used_gb: memory_used as f64 / (1024.0 * 1024.0 * 1024.0),
+        memory_total_gb: memory_total as f64 / (1024.0 * 1024.0 * 1024.0),
+        timestamp: chrono::Utc::now().to_rfc3339(),
+    })
 }
 
 #[cfg(test)]
@@ -0,0 +1,447 @@
+//! Self-diagnostics for bug reports.
+//!
+//! `run_self_diagnostics` checks the pieces that most often break on user
+//! machines (database, AI credentials, audio devices, YARA rules, disk space)
+//! and lists the compiled-in features. When given a path it also writes a zip
+//! bundle with the report, recent errors, the application log files and the
+//! configuration with secrets and home directory names removed, ready to
+//! attach to an issue.
+
+use crate::error_handler::GLOBAL_ERROR_MONITOR;
+use chrono::{DateTime, Utc};
+use log::info;
+use oxide_core::config::OxidePilotConfig;
+use oxide_core::gemini_auth::GeminiAuth;
+use oxide_core::logging;
+use oxide_core::privacy::PrivacyFilter;
+use oxide_core::qwen_auth::QwenAuth;
+use oxide_core::{network, openai_auth, openai_key};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::Path;
+use sysinfo::Disks;
+use tauri::State;
+
+/// Directory holding the database and job state; its disk is checked.
+const DATA_DIR: &str = "./data";
+
+/// Free space below which the disk check warns.
+pub const LOW_DISK_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Free space below which the disk check fails.
+pub const CRITICAL_DISK_BYTES: u64 = 200 * 1024 * 1024;
+
+/// Recent errors included in the bundle.
+const BUNDLE_ERROR_LIMIT: usize = 100;
+
+const REDACTED: &str = "<redacted>";
+
+/// Config keys whose values never leave the machine.
+const SECRET_KEY_SUFFIXES: [&str; 5] = ["api_key", "credentials", "password", "secret", "token"];
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Skipped,
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DiagnosticCheck {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl DiagnosticCheck {
+    fn new(name: &str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SelfDiagnosticsReport {
+    pub generated_at: DateTime<Utc>,
+    pub app_version: String,
+    pub platform: String,
+    /// Worst status among `checks`.
+    pub overall: CheckStatus,
+    pub checks: Vec<DiagnosticCheck>,
+    pub features: BTreeMap<String, bool>,
+    pub bundle_path: Option<String>,
+}
+
+/// Cargo features of this build.
+pub fn enabled_features() -> BTreeMap<String, bool> {
+    [
+        ("surrealdb-metrics", cfg!(feature = "surrealdb-metrics")),
+        ("yara-detection", cfg!(feature = "yara-detection")),
+        ("prometheus", cfg!(feature = "prometheus")),
+        ("cognee", cfg!(feature = "cognee")),
+    ]
+    .into_iter()
+    .map(|(name, enabled)| (name.to_string(), enabled))
+    .collect()
+}
+
+#[cfg(feature = "surrealdb-metrics")]
+async fn check_database(state: &crate::AppState) -> DiagnosticCheck {
+    let backend = match state.surreal_backend().await {
+        Ok(backend) => backend,
+        Err(e) => return DiagnosticCheck::new("database", CheckStatus::Fail, e),
+    };
+    match backend.health_check().await {
+        Ok(()) => DiagnosticCheck::new("database", CheckStatus::Pass, "SurrealDB is reachable"),
+        Err(e) => DiagnosticCheck::new("database", CheckStatus::Fail, format!("{e:#}")),
+    }
+}
+
+#[cfg(not(feature = "surrealdb-metrics"))]
+async fn check_database(_state: &crate::AppState) -> DiagnosticCheck {
+    DiagnosticCheck::new(
+        "database",
+        CheckStatus::Skipped,
+        "Built without the surrealdb-metrics feature",
+    )
+}
+
+/// Map an auth status string ("API Key", "OAuth Token Expired", ...) to a check.
+fn credential_check<E: std::fmt::Display>(
+    name: &str,
+    status: Result<String, E>,
+) -> DiagnosticCheck {
+    match status {
+        Ok(status) => {
+            let result = match status.as_str() {
+                "Not authenticated" => CheckStatus::Skipped,
+                s if s.contains("Invalid") => CheckStatus::Fail,
+                s if s.contains("Expired") => CheckStatus::Warn,
+                _ => CheckStatus::Pass,
+            };
+            DiagnosticCheck::new(name, result, status)
+        }
+        Err(e) => DiagnosticCheck::new(name, CheckStatus::Fail, e.to_string()),
+    }
+}
+
+async fn check_credentials() -> Vec<DiagnosticCheck> {
+    let gemini = if network::is_offline() {
+        // Validating the Gemini key needs a request to Google
+        DiagnosticCheck::new(
+            "gemini_credentials",
+            CheckStatus::Skipped,
+            "Offline mode is enabled",
+        )
+    } else {
+        credential_check(
+            "gemini_credentials",
+            GeminiAuth::new().get_auth_status().await,
+        )
+    };
+
+    let openai = match openai_key::get_api_key().await {
+        Ok(Some(key)) if !key.trim().is_empty() => DiagnosticCheck::new(
+            "openai_credentials",
+            CheckStatus::Pass,
+            "API key configured",
+        ),
+        _ => credential_check("openai_credentials", openai_auth::get_auth_status().await),
+    };
+
+    let qwen = credential_check("qwen_credentials", QwenAuth::new().get_auth_status().await);
+
+    let mut checks = vec![gemini, openai, qwen];
+    if checks.iter().all(|c| c.status == CheckStatus::Skipped) && !network::is_offline() {
+        checks.push(DiagnosticCheck::new(
+            "ai_credentials",
+            CheckStatus::Warn,
+            "No AI provider is configured",
+        ));
+    }
+    checks
+}
+
+fn check_audio(inputs: &[String], outputs: &[String]) -> DiagnosticCheck {
+    let detail = format!(
+        "{} input device(s), {} output device(s)",
+        inputs.len(),
+        outputs.len()
+    );
+    let status = match (inputs.is_empty(), outputs.is_empty()) {
+        (true, true) => CheckStatus::Fail,
+        (true, false) | (false, true) => CheckStatus::Warn,
+        (false, false) => CheckStatus::Pass,
+    };
+    DiagnosticCheck::new("audio_devices", status, detail)
+}
+
+fn check_yara(rules_loaded: bool) -> DiagnosticCheck {
+    if !cfg!(feature = "yara-detection") {
+        DiagnosticCheck::new(
+            "yara_rules",
+            CheckStatus::Skipped,
+            "Built without the yara-detection feature",
+        )
+    } else if rules_loaded {
+        DiagnosticCheck::new("yara_rules", CheckStatus::Pass, "Rules compiled")
+    } else {
+        DiagnosticCheck::new(
+            "yara_rules",
+            CheckStatus::Fail,
+            "Rules failed to compile; see the log for the compiler error",
+        )
+    }
+}
+
+fn disk_status(available: u64) -> CheckStatus {
+    if available < CRITICAL_DISK_BYTES {
+        CheckStatus::Fail
+    } else if available < LOW_DISK_BYTES {
+        CheckStatus::Warn
+    } else {
+        CheckStatus::Pass
+    }
+}
+
+/// Free space on the disk holding `path` (the mount point with the longest
+/// matching prefix).
+fn check_disk_space(path: &Path) -> DiagnosticCheck {
+    // Not canonicalized: Windows would return a `\\?\` path no mount matches
+    let path = std::env::current_dir()
+        .map(|dir| dir.join(path))
+        .unwrap_or_else(|_| path.to_path_buf());
+    let disks = Disks::new_with_refreshed_list();
+    let disk = disks
+        .list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len());
+
+    match disk {
+        Some(disk) => {
+            let available = disk.available_space();
+            DiagnosticCheck::new(
+                "disk_space",
+                disk_status(available),
+                format!(
+                    "{} MB free of {} MB on {}",
+                    available / (1024 * 1024),
+                    disk.total_space() / (1024 * 1024),
+                    disk.mount_point().display()
+                ),
+            )
+        }
+        None => DiagnosticCheck::new(
+            "disk_space",
+            CheckStatus::Warn,
+            format!("No disk found for {}", path.display()),
+        ),
+    }
+}
+
+/// Replace the values of secret-looking keys, at any depth.
+pub fn redact_secrets(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                let key = key.to_ascii_lowercase();
+                if !value.is_null() && SECRET_KEY_SUFFIXES.iter().any(|s| key.ends_with(s)) {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact_secrets(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_secrets),
+        _ => {}
+    }
+}
+
+fn redacted_json(value: impl Serialize) -> Result<Vec<u8>, String> {
+    let mut value = serde_json::to_value(value).map_err(|e| e.to_string())?;
+    redact_secrets(&mut value);
+    PrivacyFilter::mask_home_paths(&mut value);
+    serde_json::to_vec_pretty(&value).map_err(|e| e.to_string())
+}
+
+/// Redact each JSON log line as a value, so escaped Windows paths match too.
+fn redacted_log(contents: &str) -> Vec<u8> {
+    let mut out = Vec::with_capacity(contents.len());
+    for line in contents.lines() {
+        let mut value = serde_json::from_str(line).unwrap_or_else(|_| json!(line));
+        redact_secrets(&mut value);
+        PrivacyFilter::mask_home_paths(&mut value);
+        let _ = serde_json::to_writer(&mut out, &value);
+        out.push(b'\n');
+    }
+    out
+}
+
+fn write_bundle(
+    path: &Path,
+    report: &SelfDiagnosticsReport,
+    config: Option<&OxidePilotConfig>,
+) -> Result<(), String> {
+    let recent_errors = GLOBAL_ERROR_MONITOR
+        .get_recent_errors(BUNDLE_ERROR_LIMIT)
+        .map_err(|e| e.to_string())?;
+    let error_stats = GLOBAL_ERROR_MONITOR
+        .get_error_stats()
+        .map_err(|e| e.to_string())?;
+
+    let mut entries = vec![
+        ("report.json".to_string(), redacted_json(report)?),
+        (
+            "errors.json".to_string(),
+            redacted_json(json!({ "stats": error_stats, "recent": recent_errors }))?,
+        ),
+    ];
+    if let Some(config) = config {
+        entries.push(("config.json".to_string(), redacted_json(config)?));
+    }
+    for file in logging::logger().map(|l| l.files()).unwrap_or_default() {
+        let (Some(name), Ok(contents)) = (file.file_name(), std::fs::read_to_string(&file)) else {
+            continue;
+        };
+        entries.push((
+            format!("logs/{}", name.to_string_lossy()),
+            redacted_log(&contents),
+        ));
+    }
+
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let file = std::fs::File::create(path)
+        .map_err(|e| format!("Cannot create {}: {e}", path.display()))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options =
+        zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    for (name, bytes) in entries {
+        zip.start_file(name, options).map_err(|e| e.to_string())?;
+        zip.write_all(&bytes).map_err(|e| e.to_string())?;
+    }
+    zip.finish().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Check database, credentials, audio, YARA and disk space. With
+/// `bundle_path`, also write a redacted zip bundle for bug reports.
+#[tauri::command]
+pub async fn run_self_diagnostics(
+    state: State<'_, crate::AppState>,
+    bundle_path: Option<String>,
+    session_id: Option<String>,
+) -> Result<SelfDiagnosticsReport, String> {
+    let system = state.oxide_system.read().await.clone();
+    // Diagnostics must work before initialization, when there is no policy yet
+    if let Some(system) = &system {
+        crate::command_guard::authorize_command(
+            system,
+            session_id.as_deref(),
+            "run_self_diagnostics",
+        )
+        .await?;
+    }
+
+    let mut checks = vec![check_database(&state).await];
+    checks.extend(check_credentials().await);
+    let config = match &system {
+        Some(system) => {
+            let (inputs, outputs) = system.get_audio_devices().await;
+            checks.push(check_audio(&inputs, &outputs));
+            checks.push(check_yara(system.yara_rules_loaded()));
+            Some(system.get_config().await)
+        }
+        None => {
+            checks.push(DiagnosticCheck::new(
+                "system",
+                CheckStatus::Warn,
+                "System not initialized; audio and YARA checks skipped",
+            ));
+            None
+        }
+    };
+    let data_dir = Path::new(DATA_DIR);
+    checks.push(
+        tokio::task::spawn_blocking(move || check_disk_space(data_dir))
+            .await
+            .map_err(|e| format!("Disk check join error: {e}"))?,
+    );
+
+    let mut report = SelfDiagnosticsReport {
+        generated_at: Utc::now(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        platform: format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH),
+        overall: checks
+            .iter()
+            .map(|c| c.status)
+            .fold(CheckStatus::Pass, CheckStatus::max),
+        checks,
+        features: enabled_features(),
+        bundle_path: None,
+    };
+
+    if let Some(path) = bundle_path {
+        report.bundle_path = Some(path.clone());
+        let bundle_report = report.clone();
+        tokio::task::spawn_blocking(move || {
+            write_bundle(Path::new(&path), &bundle_report, config.as_ref())
+        })
+        .await
+        .map_err(|e| format!("Bundle task join error: {e}"))??;
+        info!("Diagnostic bundle written");
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secrets_are_redacted_at_any_depth() {
+        let mut config = json!({
+            "guardian": { "virustotal_api_key": { "nonce": "abc", "ciphertext": "def" } },
+            "mcp": { "password": "hunter2", "port": 8765 },
+            "orchestrator": { "roles": [{ "access_token": "t", "max_tokens": 2048 }] },
+            "network": { "proxy": null },
+        });
+        redact_secrets(&mut config);
+
+        assert_eq!(config["guardian"]["virustotal_api_key"], REDACTED);
+        assert_eq!(config["mcp"]["password"], REDACTED);
+        assert_eq!(config["mcp"]["port"], 8765);
+        assert_eq!(config["orchestrator"]["roles"][0]["access_token"], REDACTED);
+        assert_eq!(config["orchestrator"]["roles"][0]["max_tokens"], 2048);
+        assert!(config["network"]["proxy"].is_null());
+    }
+
+    #[test]
+    fn statuses_map_to_check_results() {
+        assert_eq!(disk_status(10 * 1024 * 1024), CheckStatus::Fail);
+        assert_eq!(disk_status(500 * 1024 * 1024), CheckStatus::Warn);
+        assert_eq!(disk_status(LOW_DISK_BYTES), CheckStatus::Pass);
+
+        let ok: Result<String, String> = Ok("API Key".to_string());
+        assert_eq!(credential_check("x", ok).status, CheckStatus::Pass);
+        let invalid: Result<String, String> = Ok("API Key Invalid".to_string());
+        assert_eq!(credential_check("x", invalid).status, CheckStatus::Fail);
+        let missing: Result<String, String> = Ok("Not authenticated".to_string());
+        assert_eq!(credential_check("x", missing).status, CheckStatus::Skipped);
+
+        assert_eq!(
+            check_audio(&[], &["Speakers".to_string()]).status,
+            CheckStatus::Warn
+        );
+    }
+}
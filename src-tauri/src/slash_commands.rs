@@ -0,0 +1,267 @@
+//! Slash commands typed into the chat box.
+//!
+//! Input starting with `/` skips the LLMs and runs a fixed code path, so
+//! `/status` or `/scan <path>` answer the same way every time and work
+//! offline. Each command is listed once in [`COMMANDS`], which `/help` is
+//! built from.
+
+use crate::command_guard;
+use crate::oxide_system::OxideSystem;
+use oxide_memory::memory::ContextQuery;
+use serde::Serialize;
+
+const RECENT_THREATS: usize = 5;
+const MEMORY_RESULTS: usize = 10;
+
+#[derive(Serialize, Debug, Clone, Copy)]
+pub struct CommandSpec {
+    pub name: &'static str,
+    pub usage: &'static str,
+    pub description: &'static str,
+}
+
+pub const COMMANDS: &[CommandSpec] = &[
+    CommandSpec {
+        name: "help",
+        usage: "/help",
+        description: "List the available commands",
+    },
+    CommandSpec {
+        name: "scan",
+        usage: "/scan <path>",
+        description: "Scan a file against local signatures",
+    },
+    CommandSpec {
+        name: "status",
+        usage: "/status",
+        description: "Show CPU, memory, process and threat counts",
+    },
+    CommandSpec {
+        name: "threats",
+        usage: "/threats",
+        description: "Show the most recent threats",
+    },
+    CommandSpec {
+        name: "optimize",
+        usage: "/optimize",
+        description: "Run the performance optimizer",
+    },
+    CommandSpec {
+        name: "memory",
+        usage: "/memory search <query>",
+        description: "Search stored memories",
+    },
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SlashCommand {
+    Help,
+    Scan { path: String },
+    Status,
+    Threats,
+    Optimize,
+    MemorySearch { query: String },
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SlashCommandOutput {
+    pub command: String,
+    /// Plain-text rendering shown in the chat
+    pub summary: String,
+    pub data: serde_json::Value,
+}
+
+impl SlashCommand {
+    /// `None` when `input` is not a slash command, otherwise the parsed
+    /// command or a usage error.
+    pub fn parse(input: &str) -> Option<Result<Self, String>> {
+        let rest = input.trim().strip_prefix('/')?;
+        let (name, args) = match rest.split_once(char::is_whitespace) {
+            Some((name, args)) => (name, args.trim()),
+            None => (rest, ""),
+        };
+        Some(Self::from_parts(&name.to_lowercase(), args))
+    }
+
+    fn from_parts(name: &str, args: &str) -> Result<Self, String> {
+        let command = match name {
+            "help" | "?" => Self::Help,
+            "status" => Self::Status,
+            "threats" => Self::Threats,
+            "optimize" => Self::Optimize,
+            "scan" if !args.is_empty() => Self::Scan {
+                path: args.trim_matches('"').to_string(),
+            },
+            "memory" => match args.split_once(char::is_whitespace) {
+                Some(("search", query)) if !query.trim().is_empty() => Self::MemorySearch {
+                    query: query.trim().to_string(),
+                },
+                _ => return Err(usage_error("memory")),
+            },
+            "scan" => return Err(usage_error("scan")),
+            _ => {
+                return Err(format!(
+                    "Unknown command '/{name}'. Type /help for the list."
+                ))
+            }
+        };
+        match (&command, args.is_empty()) {
+            (Self::Scan { .. } | Self::MemorySearch { .. }, _) | (_, true) => Ok(command),
+            _ => Err(usage_error(name)),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Help => "help",
+            Self::Scan { .. } => "scan",
+            Self::Status => "status",
+            Self::Threats => "threats",
+            Self::Optimize => "optimize",
+            Self::MemorySearch { .. } => "memory",
+        }
+    }
+}
+
+fn usage_error(name: &str) -> String {
+    let usage = COMMANDS
+        .iter()
+        .find(|spec| spec.name == name)
+        .map_or("/help", |spec| spec.usage);
+    format!("Usage: {usage}")
+}
+
+fn help_text() -> String {
+    let mut lines = vec!["Available commands:".to_string()];
+    lines.extend(
+        COMMANDS
+            .iter()
+            .map(|spec| format!("  {} - {}", spec.usage, spec.description)),
+    );
+    lines.join("\n")
+}
+
+/// Run `command`. Commands that change system state go through the same
+/// permission check as their dedicated Tauri command.
+pub async fn execute(
+    system: &OxideSystem,
+    command: SlashCommand,
+    session_id: Option<&str>,
+) -> Result<SlashCommandOutput, String> {
+    let name = command.name().to_string();
+    let (summary, data) = match command {
+        SlashCommand::Help => (help_text(), serde_json::to_value(COMMANDS)),
+        SlashCommand::Status => {
+            let status = system.get_system_status();
+            let (used, total) = status.memory_usage;
+            let summary = format!(
+                "CPU {:.1}%, memory {:.1}/{:.1} GB, {} processes, {} threats",
+                status.cpu_usage,
+                used as f64 / 1e9,
+                total as f64 / 1e9,
+                status.process_count,
+                status.threat_count
+            );
+            (summary, serde_json::to_value(status))
+        }
+        SlashCommand::Threats => {
+            let threats = system.get_threat_history();
+            let mut lines = vec![format!("{} threats recorded", threats.len())];
+            lines.extend(threats.iter().rev().take(RECENT_THREATS).map(|threat| {
+                format!(
+                    "  [{:?}] {} ({})",
+                    threat.severity,
+                    threat.description,
+                    threat.timestamp.format("%Y-%m-%d %H:%M")
+                )
+            }));
+            (lines.join("\n"), serde_json::to_value(threats))
+        }
+        SlashCommand::Scan { path } => {
+            let report = system.scan_file(path, false, false).await?;
+            let verdict = match (&report.local_match, report.malicious) {
+                (Some(signature), _) => format!("malicious ({signature})"),
+                (None, true) => "malicious".to_string(),
+                (None, false) => "no known threats".to_string(),
+            };
+            let summary = format!("{}: {verdict}", report.path);
+            (summary, serde_json::to_value(report))
+        }
+        SlashCommand::Optimize => {
+            command_guard::authorize_command(system, session_id, "optimize_performance").await?;
+            let report = system.optimize_performance().await?;
+            let summary = format!(
+                "Applied {} optimizations, {} suggested, {} failed",
+                report.applied.len(),
+                report.suggested.len(),
+                report.failed.len()
+            );
+            (summary, serde_json::to_value(report))
+        }
+        SlashCommand::MemorySearch { query } => {
+            command_guard::authorize_command(system, session_id, "list_agent_memories").await?;
+            let memory = system.memory_manager().await;
+            let entries = memory
+                .retrieve_context(&ContextQuery {
+                    query: query.clone(),
+                    context_type: None,
+                    time_range: None,
+                    max_results: MEMORY_RESULTS,
+                    min_relevance: 0.0,
+                })
+                .await?;
+            let mut lines = vec![format!("{} memories match '{query}'", entries.len())];
+            lines.extend(entries.iter().map(|entry| {
+                let preview: String = entry.content.chars().take(120).collect();
+                format!("  {} {preview}", entry.timestamp.format("%Y-%m-%d"))
+            }));
+            (lines.join("\n"), serde_json::to_value(entries))
+        }
+    };
+    Ok(SlashCommandOutput {
+        command: name,
+        summary,
+        data: data.map_err(|e| e.to_string())?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_commands_and_reports_usage() {
+        assert!(SlashCommand::parse("what is /status?").is_none());
+        assert_eq!(
+            SlashCommand::parse(" /STATUS ").unwrap(),
+            Ok(SlashCommand::Status)
+        );
+        assert_eq!(
+            SlashCommand::parse("/scan \"C:\\Temp\\a b.exe\"").unwrap(),
+            Ok(SlashCommand::Scan {
+                path: "C:\\Temp\\a b.exe".to_string()
+            })
+        );
+        assert_eq!(
+            SlashCommand::parse("/memory search  disk cleanup").unwrap(),
+            Ok(SlashCommand::MemorySearch {
+                query: "disk cleanup".to_string()
+            })
+        );
+        assert_eq!(
+            SlashCommand::parse("/scan").unwrap(),
+            Err("Usage: /scan <path>".to_string())
+        );
+        assert_eq!(
+            SlashCommand::parse("/status now").unwrap(),
+            Err("Usage: /status".to_string())
+        );
+        assert!(SlashCommand::parse("/reboot")
+            .unwrap()
+            .unwrap_err()
+            .contains("/help"));
+
+        let help = help_text();
+        assert!(COMMANDS.iter().all(|spec| help.contains(spec.usage)));
+    }
+}
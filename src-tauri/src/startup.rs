@@ -0,0 +1,176 @@
+//! Startup pipeline run from Tauri's `setup` hook.
+//!
+//! The window opens right away while slow initialization (opening the
+//! SurrealDB backend) runs as an async task. Progress is published as
+//! `startup://status` events and through `get_startup_status`, so the UI can
+//! show an initializing state. If a step still fails after its retries the
+//! app continues in degraded mode: features that need the failed component
+//! report an error, everything else keeps working.
+
+#[cfg(feature = "surrealdb-metrics")]
+use crate::error_handler::{retry_with_backoff, OxideError, RetryConfig};
+use crate::events;
+use crate::AppState;
+use chrono::{DateTime, Utc};
+#[allow(unused_imports)]
+use log::{error, info};
+use serde::Serialize;
+use tauri::{AppHandle, State};
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StartupPhase {
+    Initializing,
+    Ready,
+    /// Finished, but at least one component failed to start
+    Degraded,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ComponentFailure {
+    pub component: String,
+    pub error: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct StartupStatus {
+    pub phase: StartupPhase,
+    /// Step currently running, e.g. `database`
+    pub step: Option<String>,
+    /// Attempt number of the current step, starting at 1
+    pub attempt: u32,
+    pub failures: Vec<ComponentFailure>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl Default for StartupStatus {
+    fn default() -> Self {
+        Self {
+            phase: StartupPhase::Initializing,
+            step: None,
+            attempt: 0,
+            failures: Vec::new(),
+            started_at: Utc::now(),
+            finished_at: None,
+        }
+    }
+}
+
+impl StartupStatus {
+    #[cfg_attr(not(feature = "surrealdb-metrics"), allow(dead_code))]
+    fn begin_step(&mut self, step: &str) {
+        self.step = Some(step.to_string());
+        self.attempt = 0;
+    }
+
+    #[cfg_attr(not(feature = "surrealdb-metrics"), allow(dead_code))]
+    fn fail(&mut self, component: &str, error: impl ToString) {
+        self.failures.push(ComponentFailure {
+            component: component.to_string(),
+            error: error.to_string(),
+        });
+    }
+
+    fn finish(&mut self) {
+        self.phase = if self.failures.is_empty() {
+            StartupPhase::Ready
+        } else {
+            StartupPhase::Degraded
+        };
+        self.step = None;
+        self.finished_at = Some(Utc::now());
+    }
+}
+
+/// Backoff for opening the database: five attempts over about 8 seconds,
+/// enough for a remote server that is still starting.
+#[cfg(feature = "surrealdb-metrics")]
+fn database_retry() -> RetryConfig {
+    RetryConfig {
+        max_attempts: 5,
+        base_delay_ms: 500,
+        max_delay_ms: 8000,
+        backoff_multiplier: 2.0,
+    }
+}
+
+async fn publish(app: &AppHandle, state: &AppState, update: impl FnOnce(&mut StartupStatus)) {
+    let status = {
+        let mut status = state.startup.write().await;
+        update(&mut status);
+        status.clone()
+    };
+    events::emit(app, &status);
+}
+
+#[cfg(feature = "surrealdb-metrics")]
+async fn open_database(app: &AppHandle, state: &AppState) -> Result<(), OxideError> {
+    publish(app, state, |s| s.begin_step("database")).await;
+    let (app, state) = (app.clone(), state.clone());
+    retry_with_backoff(
+        move || {
+            let (app, state) = (app.clone(), state.clone());
+            Box::pin(async move {
+                publish(&app, &state, |s| s.attempt += 1).await;
+                state
+                    .surreal_backend()
+                    .await
+                    .map(|_| ())
+                    .map_err(|resource| OxideError::ResourceUnavailable { resource })
+            })
+        },
+        database_retry(),
+    )
+    .await
+}
+
+/// Run every startup step, then mark startup ready or degraded.
+pub async fn run(app: AppHandle, state: AppState) {
+    info!("Startup pipeline started");
+
+    #[cfg(feature = "surrealdb-metrics")]
+    if let Err(e) = open_database(&app, &state).await {
+        error!("Continuing without the database: {e}");
+        publish(&app, &state, |s| s.fail("database", &e)).await;
+    }
+
+    publish(&app, &state, StartupStatus::finish).await;
+    let status = state.startup.read().await;
+    info!(
+        "Startup finished: {:?} ({} failed component(s))",
+        status.phase,
+        status.failures.len()
+    );
+}
+
+/// Current startup progress; the UI polls this once and then follows
+/// `startup://status` events.
+#[tauri::command]
+pub async fn get_startup_status(state: State<'_, AppState>) -> Result<StartupStatus, String> {
+    Ok(state.startup.read().await.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failed_component_degrades_startup() {
+        let mut status = StartupStatus::default();
+        status.begin_step("database");
+        status.attempt += 2;
+        assert_eq!(status.phase, StartupPhase::Initializing);
+
+        status.fail("database", "lock held by another process");
+        status.finish();
+        assert_eq!(status.phase, StartupPhase::Degraded);
+        assert_eq!(status.step, None);
+        assert_eq!(status.failures[0].component, "database");
+        assert!(status.finished_at.is_some());
+
+        let mut clean = StartupStatus::default();
+        clean.finish();
+        assert_eq!(clean.phase, StartupPhase::Ready);
+    }
+}
@@ -0,0 +1,635 @@
+//! Proactive suggestions from user habits, recent threats and process
+//! memory trends.
+//!
+//! Every `interval_minutes` the engine samples per-process memory, reads the
+//! mined user patterns and the Guardian's threat history, and queues
+//! suggestions such as "chrome.exe grew from 900 MB to 3.2 GB on each of the
+//! last 3 days, peaking around 17:00 — restart it?". Responses are recorded
+//! per kind: a kind the user keeps dismissing needs a higher score to be
+//! shown again, and a dismissed subject is snoozed for a period that doubles
+//! with each dismissal. State lives in `suggestions.json` next to the
+//! active profile's memory store.
+
+use crate::oxide_system::OxideSystem;
+use chrono::{DateTime, Duration, Local, NaiveDate, Timelike, Utc};
+use log::{debug, info, warn};
+use oxide_core::config::SuggestionsConfig;
+use oxide_core::i18n;
+use oxide_guardian::guardian::{ThreatEvent, ThreatSeverity};
+use oxide_guardian::monitor::SystemMonitor;
+use oxide_memory::memory::{PatternType, UserPattern};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+use tokio::sync::{broadcast, Mutex};
+
+const STATE_FILE: &str = "suggestions.json";
+const DEFAULT_INTERVAL_MINUTES: u64 = 15;
+const DEFAULT_GROWTH_DAYS: u32 = 3;
+/// Days of memory history kept per process
+const HISTORY_DAYS: i64 = 7;
+/// Processes tracked; the smallest are dropped first
+const MAX_TRACKED_PROCESSES: usize = 100;
+/// Processes below this are not tracked
+const MIN_TRACKED_MB: f64 = 100.0;
+/// A day counts as growth when memory rose by this factor and amount
+const GROWTH_FACTOR: f64 = 1.5;
+const GROWTH_MIN_MB: f64 = 300.0;
+const MAX_PENDING: usize = 20;
+/// Score a suggestion needs after feedback weighting to be queued
+const MIN_SCORE: f32 = 0.4;
+const MIN_PATTERN_CONFIDENCE: f32 = 0.5;
+const PENDING_TTL_HOURS: i64 = 24;
+const BASE_SNOOZE_HOURS: i64 = 24;
+const MAX_SNOOZE_DOUBLINGS: u32 = 5;
+const SNOOZE_MEMORY_DAYS: i64 = 30;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SuggestionKind {
+    MemoryGrowth,
+    RecurringProblem,
+    RepeatedThreat,
+    Habit,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Suggestion {
+    pub id: String,
+    pub kind: SuggestionKind,
+    /// What the suggestion is about, e.g. `memory:chrome.exe`; at most one
+    /// suggestion per subject is pending
+    pub subject: String,
+    pub message: String,
+    /// Chat request to send when the user accepts
+    pub prompt: Option<String>,
+    pub score: f32,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SuggestionResponse {
+    Accepted,
+    Dismissed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct KindFeedback {
+    pub accepted: u32,
+    pub dismissed: u32,
+}
+
+impl KindFeedback {
+    /// Score multiplier: 1.0 without feedback, towards 2.0 for kinds the
+    /// user accepts and towards 0 for kinds they dismiss.
+    fn weight(&self) -> f32 {
+        2.0 * (self.accepted as f32 + 1.0) / ((self.accepted + self.dismissed) as f32 + 2.0)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct Snooze {
+    until: DateTime<Utc>,
+    dismissals: u32,
+}
+
+/// Memory of one process name over one local day.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct DailyMemory {
+    date: NaiveDate,
+    min_mb: f64,
+    peak_mb: f64,
+    peak_hour: u32,
+}
+
+impl DailyMemory {
+    fn grew(&self) -> bool {
+        self.peak_mb >= self.min_mb * GROWTH_FACTOR && self.peak_mb - self.min_mb >= GROWTH_MIN_MB
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct EngineState {
+    #[serde(default)]
+    pending: Vec<Suggestion>,
+    #[serde(default)]
+    feedback: HashMap<SuggestionKind, KindFeedback>,
+    #[serde(default)]
+    snoozed: HashMap<String, Snooze>,
+    #[serde(default)]
+    memory: HashMap<String, Vec<DailyMemory>>,
+}
+
+impl EngineState {
+    fn record_memory(&mut self, samples: &HashMap<String, f64>, now: DateTime<Local>) {
+        let today = now.date_naive();
+        let hour = now.hour();
+        for (name, &mb) in samples {
+            if mb < MIN_TRACKED_MB && !self.memory.contains_key(name) {
+                continue;
+            }
+            let days = self.memory.entry(name.clone()).or_default();
+            match days.last_mut() {
+                Some(day) if day.date == today => {
+                    day.min_mb = day.min_mb.min(mb);
+                    if mb > day.peak_mb {
+                        day.peak_mb = mb;
+                        day.peak_hour = hour;
+                    }
+                }
+                _ => days.push(DailyMemory {
+                    date: today,
+                    min_mb: mb,
+                    peak_mb: mb,
+                    peak_hour: hour,
+                }),
+            }
+        }
+
+        let oldest = today - Duration::days(HISTORY_DAYS - 1);
+        for days in self.memory.values_mut() {
+            days.retain(|day| day.date >= oldest);
+        }
+        self.memory.retain(|_, days| !days.is_empty());
+        if self.memory.len() > MAX_TRACKED_PROCESSES {
+            let peak = |days: &Vec<DailyMemory>| days.iter().map(|d| d.peak_mb).fold(0.0, f64::max);
+            let mut by_peak: Vec<(String, f64)> = self
+                .memory
+                .iter()
+                .map(|(name, days)| (name.clone(), peak(days)))
+                .collect();
+            by_peak.sort_by(|a, b| a.1.total_cmp(&b.1));
+            for (name, _) in by_peak
+                .into_iter()
+                .take(self.memory.len() - MAX_TRACKED_PROCESSES)
+            {
+                self.memory.remove(&name);
+            }
+        }
+    }
+
+    fn is_snoozed(&self, subject: &str, now: DateTime<Utc>) -> bool {
+        self.snoozed
+            .get(subject)
+            .is_some_and(|snooze| snooze.until > now)
+    }
+
+    /// Queue the candidates that survive feedback weighting and snoozes.
+    fn enqueue(&mut self, candidates: Vec<Suggestion>, now: DateTime<Utc>) -> Vec<Suggestion> {
+        self.pending
+            .retain(|s| now - s.created_at < Duration::hours(PENDING_TTL_HOURS));
+        let mut added = Vec::new();
+        for mut candidate in candidates {
+            let weight = self
+                .feedback
+                .get(&candidate.kind)
+                .map(KindFeedback::weight)
+                .unwrap_or(1.0);
+            candidate.score = (candidate.score * weight).min(1.0);
+            if candidate.score < MIN_SCORE
+                || self.is_snoozed(&candidate.subject, now)
+                || self.pending.iter().any(|s| s.subject == candidate.subject)
+            {
+                continue;
+            }
+            self.pending.push(candidate.clone());
+            added.push(candidate);
+        }
+        if self.pending.len() > MAX_PENDING {
+            let excess = self.pending.len() - MAX_PENDING;
+            self.pending.drain(..excess);
+        }
+        added
+    }
+
+    fn respond(
+        &mut self,
+        id: &str,
+        response: SuggestionResponse,
+        now: DateTime<Utc>,
+    ) -> Option<Suggestion> {
+        let index = self.pending.iter().position(|s| s.id == id)?;
+        let suggestion = self.pending.remove(index);
+        let feedback = self.feedback.entry(suggestion.kind).or_default();
+        let snooze = self
+            .snoozed
+            .entry(suggestion.subject.clone())
+            .or_insert(Snooze {
+                until: now,
+                dismissals: 0,
+            });
+        match response {
+            SuggestionResponse::Accepted => {
+                feedback.accepted += 1;
+                snooze.until = now + Duration::hours(BASE_SNOOZE_HOURS);
+            }
+            SuggestionResponse::Dismissed => {
+                feedback.dismissed += 1;
+                let doublings = snooze.dismissals.min(MAX_SNOOZE_DOUBLINGS);
+                snooze.dismissals += 1;
+                snooze.until = now + Duration::hours(BASE_SNOOZE_HOURS << doublings);
+            }
+        }
+        // Dismissal counts outlive the snooze so repeat dismissals keep doubling
+        self.snoozed
+            .retain(|_, snooze| snooze.until + Duration::days(SNOOZE_MEMORY_DAYS) > now);
+        Some(suggestion)
+    }
+}
+
+/// What one evaluation looks at.
+pub struct SuggestionInputs {
+    pub patterns: Vec<UserPattern>,
+    pub threats: Vec<ThreatEvent>,
+    /// Resident memory in MB, summed per process name
+    pub process_memory: HashMap<String, f64>,
+}
+
+fn suggestion(
+    kind: SuggestionKind,
+    subject: String,
+    message: String,
+    prompt: String,
+    score: f32,
+) -> Suggestion {
+    Suggestion {
+        id: uuid::Uuid::new_v4().to_string(),
+        kind,
+        subject,
+        message,
+        prompt: Some(prompt),
+        score,
+        created_at: Utc::now(),
+    }
+}
+
+/// Days in a row of memory growth, ending today or yesterday.
+fn growth_streak(days: &[DailyMemory], today: NaiveDate) -> usize {
+    let mut expected = match days.last() {
+        Some(day) if day.grew() && day.date == today => today,
+        Some(day) if day.grew() && day.date == today - Duration::days(1) => day.date,
+        _ => return 0,
+    };
+    let mut streak = 0;
+    for day in days.iter().rev() {
+        if day.date != expected || !day.grew() {
+            break;
+        }
+        streak += 1;
+        expected -= Duration::days(1);
+    }
+    streak
+}
+
+fn format_mb(mb: f64) -> String {
+    if mb >= 1024.0 {
+        format!("{:.1} GB", mb / 1024.0)
+    } else {
+        format!("{mb:.0} MB")
+    }
+}
+
+fn memory_growth(state: &EngineState, growth_days: u32, now: DateTime<Local>) -> Vec<Suggestion> {
+    let mut suggestions = Vec::new();
+    for (name, days) in &state.memory {
+        let streak = growth_streak(days, now.date_naive());
+        if streak < growth_days as usize {
+            continue;
+        }
+        let Some(latest) = days.last() else {
+            continue;
+        };
+        let message = i18n::tf(
+            "notification.memory_growth",
+            &[
+                ("name", name),
+                ("from", &format_mb(latest.min_mb)),
+                ("to", &format_mb(latest.peak_mb)),
+                ("days", &streak),
+                ("hour", &format!("{:02}", latest.peak_hour)),
+            ],
+        );
+        let score = (0.5 + 0.1 * (streak - growth_days as usize) as f32).min(0.9);
+        suggestions.push(suggestion(
+            SuggestionKind::MemoryGrowth,
+            format!("memory:{}", name.to_lowercase()),
+            message,
+            i18n::tf("notification.memory_growth.action", &[("name", name)]),
+            score,
+        ));
+    }
+    suggestions
+}
+
+fn near_hour(hour: u32, now: u32) -> bool {
+    let distance = hour.abs_diff(now);
+    distance.min(24 - distance) <= 1
+}
+
+fn from_patterns(patterns: &[UserPattern], now: DateTime<Local>) -> Vec<Suggestion> {
+    let mut suggestions = Vec::new();
+    for pattern in patterns {
+        if pattern.confidence < MIN_PATTERN_CONFIDENCE {
+            continue;
+        }
+        let Some(peak) = pattern.peak_hour().filter(|h| near_hour(*h, now.hour())) else {
+            continue;
+        };
+        match pattern.pattern_type {
+            PatternType::ErrorResolution => {
+                let category = pattern.pattern_id.trim_start_matches("problem:");
+                suggestions.push(suggestion(
+                    SuggestionKind::RecurringProblem,
+                    pattern.pattern_id.clone(),
+                    i18n::tf(
+                        "notification.recurring_problem",
+                        &[("category", &category), ("hour", &format!("{peak:02}"))],
+                    ),
+                    i18n::tf(
+                        "notification.recurring_problem.action",
+                        &[("category", &category)],
+                    ),
+                    pattern.confidence,
+                ));
+            }
+            PatternType::CommandSequence => {
+                let command = pattern.pattern_id.trim_start_matches("command:");
+                suggestions.push(suggestion(
+                    SuggestionKind::Habit,
+                    pattern.pattern_id.clone(),
+                    i18n::tf(
+                        "notification.habit",
+                        &[("command", &command), ("hour", &format!("{peak:02}"))],
+                    ),
+                    command.to_string(),
+                    pattern.confidence * 0.8,
+                ));
+            }
+            _ => {}
+        }
+    }
+    suggestions
+}
+
+fn repeated_threats(threats: &[ThreatEvent], now: DateTime<Utc>) -> Vec<Suggestion> {
+    let since = now - Duration::hours(24);
+    let mut by_process: HashMap<&str, usize> = HashMap::new();
+    for threat in threats {
+        if threat.timestamp < since
+            || !matches!(
+                threat.severity,
+                ThreatSeverity::High | ThreatSeverity::Critical
+            )
+        {
+            continue;
+        }
+        if let Some(name) = threat.process_name.as_deref() {
+            *by_process.entry(name).or_default() += 1;
+        }
+    }
+    by_process
+        .into_iter()
+        .filter(|(_, count)| *count >= 2)
+        .map(|(name, count)| {
+            suggestion(
+                SuggestionKind::RepeatedThreat,
+                format!("threat:{}", name.to_lowercase()),
+                i18n::tf(
+                    "notification.repeated_threat",
+                    &[("name", &name), ("count", &count)],
+                ),
+                i18n::tf("notification.repeated_threat.action", &[("name", &name)]),
+                (0.6 + 0.05 * count as f32).min(0.95),
+            )
+        })
+        .collect()
+}
+
+/// Queues suggestions and records the user's responses to them.
+pub struct SuggestionEngine {
+    state: Mutex<(PathBuf, EngineState)>,
+    events: broadcast::Sender<Suggestion>,
+}
+
+impl Default for SuggestionEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SuggestionEngine {
+    pub fn new() -> Self {
+        let (events, _) = broadcast::channel(32);
+        Self {
+            state: Mutex::new((PathBuf::new(), EngineState::default())),
+            events,
+        }
+    }
+
+    /// Suggestions as they are queued.
+    pub fn subscribe(&self) -> broadcast::Receiver<Suggestion> {
+        self.events.subscribe()
+    }
+
+    /// Lock the state stored in `dir`, loading it when the profile changed.
+    async fn state(&self, dir: &str) -> tokio::sync::MutexGuard<'_, (PathBuf, EngineState)> {
+        let path = Path::new(dir).join(STATE_FILE);
+        let mut guard = self.state.lock().await;
+        if guard.0 != path {
+            let state = match fs::read_to_string(&path).await {
+                Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                    warn!("Failed to parse {}: {e}", path.display());
+                    EngineState::default()
+                }),
+                Err(_) => EngineState::default(),
+            };
+            *guard = (path, state);
+        }
+        guard
+    }
+
+    async fn save(path: &Path, state: &EngineState) {
+        match serde_json::to_string_pretty(state) {
+            Ok(json) => {
+                if let Err(e) = fs::write(path, json).await {
+                    warn!("Failed to write {}: {e}", path.display());
+                }
+            }
+            Err(e) => warn!("Failed to serialize suggestions: {e}"),
+        }
+    }
+
+    /// Record `inputs` and queue the suggestions they lead to; returns the
+    /// newly queued ones.
+    pub async fn evaluate(
+        &self,
+        dir: &str,
+        inputs: SuggestionInputs,
+        cfg: &SuggestionsConfig,
+    ) -> Vec<Suggestion> {
+        let now = Utc::now();
+        let local = now.with_timezone(&Local);
+        let mut guard = self.state(dir).await;
+        let (path, state) = &mut *guard;
+        state.record_memory(&inputs.process_memory, local);
+
+        let mut candidates = memory_growth(
+            state,
+            cfg.memory_growth_days.unwrap_or(DEFAULT_GROWTH_DAYS),
+            local,
+        );
+        candidates.extend(from_patterns(&inputs.patterns, local));
+        candidates.extend(repeated_threats(&inputs.threats, now));
+        candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+        let added = state.enqueue(candidates, now);
+        Self::save(path, state).await;
+        for suggestion in &added {
+            let _ = self.events.send(suggestion.clone());
+        }
+        added
+    }
+
+    pub async fn pending(&self, dir: &str) -> Vec<Suggestion> {
+        let guard = self.state(dir).await;
+        let now = Utc::now();
+        guard
+            .1
+            .pending
+            .iter()
+            .filter(|s| now - s.created_at < Duration::hours(PENDING_TTL_HOURS))
+            .cloned()
+            .collect()
+    }
+
+    pub async fn respond(
+        &self,
+        dir: &str,
+        id: &str,
+        response: SuggestionResponse,
+    ) -> Result<Suggestion, String> {
+        let mut guard = self.state(dir).await;
+        let (path, state) = &mut *guard;
+        let suggestion = state
+            .respond(id, response, Utc::now())
+            .ok_or_else(|| format!("Suggestion not found: {id}"))?;
+        Self::save(path, state).await;
+        debug!("Suggestion {} {response:?}", suggestion.subject);
+        Ok(suggestion)
+    }
+
+    pub async fn feedback(&self, dir: &str) -> HashMap<SuggestionKind, KindFeedback> {
+        self.state(dir).await.1.feedback.clone()
+    }
+}
+
+/// Resident memory in MB summed per process name.
+pub fn sample_process_memory() -> HashMap<String, f64> {
+    let mut totals = HashMap::new();
+    for event in SystemMonitor::new().list_processes() {
+        let details = event.details;
+        let Some(name) = details.get("name").and_then(Value::as_str) else {
+            continue;
+        };
+        let bytes = details
+            .get("memory_usage")
+            .and_then(Value::as_u64)
+            .unwrap_or(0);
+        *totals.entry(name.to_string()).or_insert(0.0) += bytes as f64 / (1024.0 * 1024.0);
+    }
+    totals
+}
+
+/// Evaluate on the configured schedule while the system runs. The config
+/// is re-read before each run so changes apply without a restart.
+pub fn spawn(system: OxideSystem) {
+    tokio::spawn(async move {
+        loop {
+            let cfg = current_config(&system).await;
+            let minutes = cfg.interval_minutes.unwrap_or(DEFAULT_INTERVAL_MINUTES);
+            tokio::time::sleep(std::time::Duration::from_secs(minutes * 60)).await;
+            if !system.is_running().await {
+                break;
+            }
+            let cfg = current_config(&system).await;
+            if !cfg.enabled {
+                continue;
+            }
+            match system.evaluate_suggestions(&cfg).await {
+                Ok(added) if !added.is_empty() => info!("Queued {} suggestion(s)", added.len()),
+                Ok(_) => {}
+                Err(e) => warn!("Suggestion evaluation failed: {e}"),
+            }
+        }
+    });
+}
+
+async fn current_config(system: &OxideSystem) -> SuggestionsConfig {
+    system.get_config().await.suggestions.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn detects_memory_growing_on_consecutive_days() {
+        let mut state = EngineState::default();
+        let start = Local.with_ymd_and_hms(2024, 3, 4, 9, 0, 0).unwrap();
+        for day in 0..3 {
+            let morning = start + Duration::days(day);
+            let evening = morning + Duration::hours(8);
+            state.record_memory(&HashMap::from([("chrome.exe".to_string(), 800.0)]), morning);
+            state.record_memory(
+                &HashMap::from([("chrome.exe".to_string(), 3000.0)]),
+                evening,
+            );
+        }
+        let today = (start + Duration::days(2)).date_naive();
+        assert_eq!(growth_streak(&state.memory["chrome.exe"], today), 3);
+        assert_eq!(
+            growth_streak(&state.memory["chrome.exe"], today + Duration::days(2)),
+            0
+        );
+
+        let found = memory_growth(&state, 3, start + Duration::days(2));
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].subject, "memory:chrome.exe");
+        assert!(found[0].message.contains("around 17:00"));
+    }
+
+    #[test]
+    fn dismissals_snooze_subjects_and_lower_kind_scores() {
+        let now = Utc::now();
+        let mut state = EngineState::default();
+        let candidate = || {
+            suggestion(
+                SuggestionKind::Habit,
+                "command:/scan".to_string(),
+                "Run /scan?".to_string(),
+                "/scan".to_string(),
+                0.5,
+            )
+        };
+        let added = state.enqueue(vec![candidate(), candidate()], now);
+        assert_eq!(added.len(), 1);
+
+        state.respond(&added[0].id, SuggestionResponse::Dismissed, now);
+        assert!(state.enqueue(vec![candidate()], now).is_empty());
+
+        let later = now + Duration::hours(BASE_SNOOZE_HOURS + 1);
+        assert!(state.enqueue(vec![candidate()], later).is_empty());
+        assert_eq!(state.feedback[&SuggestionKind::Habit].dismissed, 1);
+
+        state
+            .feedback
+            .get_mut(&SuggestionKind::Habit)
+            .unwrap()
+            .accepted = 3;
+        assert_eq!(state.enqueue(vec![candidate()], later).len(), 1);
+    }
+}
@@ -0,0 +1,325 @@
+//! Helpers for the JSON system snapshot that is sent to LLM analysis.
+//!
+//! The snapshot mixes live data (process list, network interfaces) with
+//! history from SurrealDB (hourly aggregates, incidents). Before it leaves the
+//! process, the caller redacts it with `oxide_core::privacy::PrivacyFilter` and
+//! the payload is trimmed to a byte budget so large machines don't blow up
+//! prompt sizes.
+
+use log::warn;
+use oxide_guardian::monitor::SystemMonitor;
+use oxide_guardian::startup::StartupItem;
+#[cfg(feature = "surrealdb-metrics")]
+use oxide_memory::SurrealBackend;
+use serde_json::{json, Value};
+
+/// The snapshot itself. Sections are trimmed to fit the byte budget, so it
+/// stays free-form JSON rather than a fixed struct.
+pub type SystemSnapshot = Value;
+
+const MAX_COMMAND_CHARS: usize = 200;
+
+#[derive(Debug, Clone)]
+pub struct SnapshotOptions {
+    /// Number of processes kept in each top-N list.
+    pub top_n: usize,
+    /// Number of recent threats/incidents kept.
+    pub recent_limit: usize,
+    /// Look-back window for hourly metric aggregates.
+    pub hourly_window_hours: i64,
+    /// Upper bound for the serialized snapshot size.
+    pub max_bytes: usize,
+}
+
+impl Default for SnapshotOptions {
+    fn default() -> Self {
+        Self {
+            top_n: 10,
+            recent_limit: 20,
+            hourly_window_hours: 24,
+            max_bytes: 32 * 1024,
+        }
+    }
+}
+
+impl SnapshotOptions {
+    /// Defaults, overridable via `OXIDE_SNAPSHOT_TOP_N` and `OXIDE_SNAPSHOT_MAX_BYTES`.
+    pub fn from_env() -> Self {
+        let mut options = Self::default();
+        if let Some(n) = env_usize("OXIDE_SNAPSHOT_TOP_N") {
+            options.top_n = n;
+        }
+        if let Some(bytes) = env_usize("OXIDE_SNAPSHOT_MAX_BYTES") {
+            options.max_bytes = bytes;
+        }
+        options
+    }
+}
+
+fn env_usize(key: &str) -> Option<usize> {
+    std::env::var(key)
+        .ok()
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .filter(|v| *v > 0)
+}
+
+/// Top processes by CPU and by resident memory.
+pub fn top_processes(monitor: &SystemMonitor, top_n: usize) -> Value {
+    let mut processes: Vec<Value> = monitor
+        .list_processes()
+        .into_iter()
+        .map(|event| {
+            let details = event.details;
+            let memory_mb = details
+                .get("memory_usage")
+                .and_then(Value::as_u64)
+                .unwrap_or(0) as f64
+                / (1024.0 * 1024.0);
+            let command: String = details
+                .get("command")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .chars()
+                .take(MAX_COMMAND_CHARS)
+                .collect();
+            json!({
+                "pid": details.get("pid").cloned().unwrap_or(Value::Null),
+                "name": details.get("name").cloned().unwrap_or(Value::Null),
+                "cpu_usage": details.get("cpu_usage").and_then(Value::as_f64).unwrap_or(0.0),
+                "memory_mb": (memory_mb * 10.0).round() / 10.0,
+                "status": details.get("status").cloned().unwrap_or(Value::Null),
+                "command": command,
+            })
+        })
+        .collect();
+
+    let total = processes.len();
+    let metric = |p: &Value, key: &str| p.get(key).and_then(Value::as_f64).unwrap_or(0.0);
+
+    processes.sort_by(|a, b| metric(b, "cpu_usage").total_cmp(&metric(a, "cpu_usage")));
+    let by_cpu: Vec<Value> = processes.iter().take(top_n).cloned().collect();
+
+    processes.sort_by(|a, b| metric(b, "memory_mb").total_cmp(&metric(a, "memory_mb")));
+    let by_memory: Vec<Value> = processes.into_iter().take(top_n).collect();
+
+    json!({
+        "total": total,
+        "by_cpu": by_cpu,
+        "by_memory": by_memory,
+    })
+}
+
+/// Interface totals plus a TCP connection breakdown.
+pub fn network_summary(monitor: &SystemMonitor) -> Value {
+    let mut interfaces = Vec::new();
+    let (mut received, mut transmitted, mut errors) = (0u64, 0u64, 0u64);
+
+    for event in monitor.get_network_usage() {
+        let d = &event.details;
+        let field = |key: &str| d.get(key).and_then(Value::as_u64).unwrap_or(0);
+        received += field("received");
+        transmitted += field("transmitted");
+        errors += field("errors_on_received") + field("errors_on_transmitted");
+        interfaces.push(json!({
+            "interface": d.get("interface").cloned().unwrap_or(Value::Null),
+            "received": field("received"),
+            "transmitted": field("transmitted"),
+        }));
+    }
+
+    let connections = count_tcp_connections();
+
+    json!({
+        "interfaces": interfaces,
+        "total_received": received,
+        "total_transmitted": transmitted,
+        "total_errors": errors,
+        "tcp_connections": connections,
+    })
+}
+
+/// Count TCP sockets by state. Returns `null` if the platform source is unavailable.
+fn count_tcp_connections() -> Value {
+    let (mut established, mut listening, mut other) = (0u64, 0u64, 0u64);
+
+    #[cfg(target_os = "linux")]
+    {
+        let mut found = false;
+        for path in ["/proc/net/tcp", "/proc/net/tcp6"] {
+            let Ok(content) = std::fs::read_to_string(path) else {
+                continue;
+            };
+            found = true;
+            // Column 4 ("st") holds the socket state in hex: 01 = ESTABLISHED, 0A = LISTEN
+            for line in content.lines().skip(1) {
+                match line.split_whitespace().nth(3) {
+                    Some("01") => established += 1,
+                    Some("0A") => listening += 1,
+                    Some(_) => other += 1,
+                    None => {}
+                }
+            }
+        }
+        if !found {
+            return Value::Null;
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let output = match std::process::Command::new("netstat")
+            .args(["-an", "-p", "tcp"])
+            .output()
+        {
+            Ok(output) if output.status.success() => output,
+            _ => return Value::Null,
+        };
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            let upper = line.to_ascii_uppercase();
+            if !upper.trim_start().starts_with("TCP") {
+                continue;
+            }
+            if upper.contains("ESTABLISHED") {
+                established += 1;
+            } else if upper.contains("LISTEN") {
+                listening += 1;
+            } else {
+                other += 1;
+            }
+        }
+    }
+
+    json!({
+        "established": established,
+        "listening": listening,
+        "other": other,
+    })
+}
+
+/// Autostart entries that scored above "low", riskiest first. Ids are kept
+/// so findings can reference them as `startup:<id>`.
+pub fn startup_summary(items: &[StartupItem], top_n: usize) -> Value {
+    let flagged: Vec<Value> = items
+        .iter()
+        .filter(|item| item.enabled && item.risk_score > 0)
+        .take(top_n)
+        .map(|item| {
+            let command: String = item.command.chars().take(MAX_COMMAND_CHARS).collect();
+            json!({
+                "id": item.id,
+                "name": item.name,
+                "source": item.source,
+                "command": command,
+                "risk_score": item.risk_score,
+                "reasons": item.reasons,
+            })
+        })
+        .collect();
+    Value::Array(flagged)
+}
+
+/// Hourly aggregates and recent incidents from SurrealDB. Failures are
+/// logged and yield empty sections.
+#[cfg(feature = "surrealdb-metrics")]
+pub async fn backend_sections(
+    backend: &SurrealBackend,
+    options: &SnapshotOptions,
+) -> (Value, Value) {
+    let hourly = match backend
+        .query_hourly_metrics(options.hourly_window_hours)
+        .await
+    {
+        Ok(rows) => Value::Array(rows),
+        Err(e) => {
+            warn!("Snapshot: hourly metrics unavailable: {e:#}");
+            Value::Array(Vec::new())
+        }
+    };
+
+    let incidents = match backend.query_recent_incidents(options.recent_limit).await {
+        Ok(rows) => Value::Array(rows),
+        Err(e) => {
+            warn!("Snapshot: incidents unavailable: {e:#}");
+            Value::Array(Vec::new())
+        }
+    };
+
+    (hourly, incidents)
+}
+
+/// Arrays that may be shortened, in the order they are trimmed.
+const TRIMMABLE: [&str; 7] = [
+    "/threats",
+    "/incidents",
+    "/startup_items",
+    "/processes/by_memory",
+    "/processes/by_cpu",
+    "/hourly_metrics",
+    "/network/interfaces",
+];
+
+/// Halve the trimmable arrays until the serialized snapshot fits `max_bytes`.
+/// Sets `"truncated": true` when anything was removed.
+pub fn enforce_size_budget(snapshot: &mut Value, max_bytes: usize) -> bool {
+    let size = |v: &Value| serde_json::to_vec(v).map(|b| b.len()).unwrap_or(0);
+    let mut truncated = false;
+
+    while size(snapshot) > max_bytes {
+        let mut shrunk = false;
+        for pointer in TRIMMABLE {
+            if let Some(Value::Array(items)) = snapshot.pointer_mut(pointer) {
+                if items.len() > 1 {
+                    // Threats are oldest-first; keep the newest half.
+                    if pointer == "/threats" {
+                        items.drain(..items.len() / 2);
+                    } else {
+                        items.truncate(items.len() / 2);
+                    }
+                    shrunk = true;
+                    if size(snapshot) <= max_bytes {
+                        break;
+                    }
+                }
+            }
+        }
+        truncated |= shrunk;
+        if !shrunk {
+            warn!("Snapshot still exceeds {max_bytes} bytes after trimming");
+            break;
+        }
+    }
+
+    if truncated {
+        if let Value::Object(map) = snapshot {
+            map.insert("truncated".to_string(), Value::Bool(true));
+        }
+    }
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn size_budget_trims_arrays() {
+        let threats: Vec<Value> = (0..200)
+            .map(|i| json!({"id": i, "pad": "x".repeat(50)}))
+            .collect();
+        let mut snapshot = json!({"status": "ok", "threats": threats});
+
+        assert!(enforce_size_budget(&mut snapshot, 2048));
+        assert!(serde_json::to_vec(&snapshot).unwrap().len() <= 2048);
+        assert_eq!(snapshot["truncated"], true);
+        // Newest threats are kept
+        let kept = snapshot["threats"].as_array().unwrap();
+        assert_eq!(kept.last().unwrap()["id"], 199);
+    }
+
+    #[test]
+    fn size_budget_leaves_small_snapshots_alone() {
+        let mut snapshot = json!({"status": "ok", "threats": [1, 2, 3]});
+        assert!(!enforce_size_budget(&mut snapshot, 4096));
+        assert!(snapshot.get("truncated").is_none());
+    }
+}
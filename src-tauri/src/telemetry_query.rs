@@ -0,0 +1,155 @@
+//! `query_metrics` copilot tool ("ask your telemetry").
+//!
+//! The AI orchestrator translates the question into one SurrealQL `SELECT`
+//! over the metrics, process and threat tables;
+//! [`SurrealBackend::query_readonly`] refuses anything else before running
+//! it. A rejected or failing query is sent back to the model once with the
+//! error so it can correct itself.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use log::{info, warn};
+use oxide_copilot::ai::AIOrchestrator;
+use oxide_copilot::functions::ExecutableFunction;
+use oxide_memory::metrics_query::SCHEMA_SUMMARY;
+use oxide_memory::SurrealBackend;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+const MAX_ATTEMPTS: usize = 2;
+
+pub struct QueryMetricsFunction {
+    backend: Arc<SurrealBackend>,
+    ai: Arc<AIOrchestrator>,
+}
+
+impl QueryMetricsFunction {
+    pub fn new(backend: Arc<SurrealBackend>, ai: Arc<AIOrchestrator>) -> Self {
+        Self { backend, ai }
+    }
+}
+
+fn build_prompt(question: &str, now: DateTime<Utc>, previous: Option<&(String, String)>) -> String {
+    let mut prompt = format!(
+        "Translate the question into exactly one read-only SurrealQL SELECT statement.\n\
+         Only these tables exist:\n{SCHEMA_SUMMARY}\n\n\
+         Rules:\n\
+         - Use only SELECT with the math::, time::, array::, string::, duration:: and count() functions.\n\
+         - Write datetimes as d'2024-01-01T18:00:00Z' literals or time::now() - 1d; all times are UTC.\n\
+         - Aggregate with GROUP ALL or GROUP BY instead of returning raw samples where possible.\n\
+         - No record ids, graph traversal, parameters, comments or multiple statements.\n\
+         - Reply with the statement only, without explanation or code fences.\n\n\
+         Current time (UTC): {}\n\
+         Question: {question}\n",
+        now.to_rfc3339()
+    );
+    if let Some((query, error)) = previous {
+        prompt.push_str(&format!(
+            "\nYour previous answer was rejected.\nQuery: {query}\nError: {error}\nReturn a corrected statement.\n"
+        ));
+    }
+    prompt
+}
+
+/// The statement from a model reply, without Markdown fences or a language tag.
+fn extract_query(response: &str) -> String {
+    let mut text = response.trim();
+    if let Some(fenced) = text.strip_prefix("```") {
+        let body = fenced.split_once('\n').map_or(fenced, |(_, body)| body);
+        text = body.rsplit_once("```").map_or(body, |(body, _)| body);
+    }
+    text.trim().trim_end_matches(';').trim().to_string()
+}
+
+#[async_trait]
+impl ExecutableFunction for QueryMetricsFunction {
+    fn name(&self) -> &str {
+        "query_metrics"
+    }
+
+    fn description(&self) -> &str {
+        "Answers questions about stored system metrics, processes and threats, e.g. \
+         'what was my peak CPU yesterday evening?'. Read-only."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "nl_question": {
+                    "type": "string",
+                    "description": "The question in natural language"
+                }
+            },
+            "required": ["nl_question"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> Result<Value, String> {
+        let question = args["nl_question"]
+            .as_str()
+            .map(str::trim)
+            .filter(|q| !q.is_empty())
+            .ok_or("Missing 'nl_question' argument for query_metrics")?;
+
+        let mut previous: Option<(String, String)> = None;
+        for attempt in 1..=MAX_ATTEMPTS {
+            let prompt = build_prompt(question, Utc::now(), previous.as_ref());
+            let response = self
+                .ai
+                .generate_response(&prompt, &[], None)
+                .await
+                .map_err(|e| format!("Failed to generate telemetry query: {e}"))?;
+            let query = extract_query(&response);
+
+            match self.backend.query_readonly(&query).await {
+                Ok(rows) => {
+                    info!("Telemetry query returned {} row(s): {query}", rows.len());
+                    return Ok(json!({
+                        "question": question,
+                        "query": query,
+                        "row_count": rows.len(),
+                        "rows": rows,
+                    }));
+                }
+                Err(e) => {
+                    warn!("Telemetry query attempt {attempt} rejected: {e:#}");
+                    previous = Some((query, format!("{e:#}")));
+                }
+            }
+        }
+
+        let (query, error) = previous.unwrap_or_default();
+        Err(format!(
+            "Could not answer from stored metrics: {error} (query: {query})"
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_statement_from_fenced_reply() {
+        assert_eq!(
+            extract_query(
+                "```surql\nSELECT math::max(cpu_usage) FROM system_metrics GROUP ALL;\n```"
+            ),
+            "SELECT math::max(cpu_usage) FROM system_metrics GROUP ALL"
+        );
+        assert_eq!(
+            extract_query("  SELECT * FROM threat;  "),
+            "SELECT * FROM threat"
+        );
+    }
+
+    #[test]
+    fn retry_prompt_includes_rejection() {
+        let previous = ("DELETE threat".to_string(), "not allowed".to_string());
+        let prompt = build_prompt("peak cpu?", Utc::now(), Some(&previous));
+        assert!(prompt.contains("Question: peak cpu?"));
+        assert!(prompt.contains("Query: DELETE threat\nError: not allowed"));
+        assert!(!build_prompt("peak cpu?", Utc::now(), None).contains("rejected"));
+    }
+}
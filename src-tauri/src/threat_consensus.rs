@@ -1,11 +1,21 @@
 use chrono::Utc;
 use log::{error, info, warn};
+use oxide_copilot::response_cache;
+use oxide_core::config::ConsensusConfig;
 use oxide_core::gemini_auth::GeminiAuth;
+use oxide_core::i18n;
 use oxide_core::openai_client::{self, ChatMessage};
+use oxide_core::privacy::PrivacyFilter;
+use oxide_core::prompts;
 use oxide_core::qwen_auth::QwenAuth;
-use reqwest::Client;
+use oxide_core::severity::{self, Scored, Severity};
+use oxide_core::{api_governor, llm_audit, network, power};
+use oxide_guardian::attack;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Indicator {
@@ -15,57 +25,88 @@ pub struct Indicator {
     pub context: Option<String>,
 }
 
-async fn analyze_with_openai(snapshot: &Value) -> Result<ModelReport, String> {
-    // Build prompt with strict JSON requirement
-    let prompt = format!(
-        r#"
-    You are a security threat analyst. Analyze the JSON system snapshot and return STRICT JSON with keys:
-    risk_score (0-100), confidence (0-1), findings[], indicators[], recommendations[], citations[]
-    JSON only, no prose.
+/// Prefixes the remediation planner understands in `findings[].indicators`.
+const FINDING_INDICATOR_HINT: &str = "Finding indicators should use the forms pid:<pid>, path:<absolute file path> or startup:<startup_items id> where applicable.";
 
-    Snapshot:
-    {snapshot}
-    "#
-    );
+/// Asks providers to tag findings for the ATT&CK matrix summary.
+const FINDING_TECHNIQUE_HINT: &str = "Tag each finding with the MITRE ATT&CK technique IDs it matches in techniques[], e.g. T1059.001.";
+
+/// Threat analysis prompt for `provider`; `grounding` prefixes the search
+/// instruction where the template has one.
+fn threat_prompt(snapshot: &Value, provider: &str, grounding: &str) -> String {
+    let snapshot_str =
+        serde_json::to_string_pretty(snapshot).unwrap_or_else(|_| snapshot.to_string());
+    prompts::render(
+        prompts::THREAT_ANALYSIS,
+        Some(provider),
+        &[
+            ("snapshot", &snapshot_str),
+            ("indicator_hint", FINDING_INDICATOR_HINT),
+            ("technique_hint", FINDING_TECHNIQUE_HINT),
+            ("grounding", grounding),
+        ],
+    )
+}
 
-    let model_name = std::env::var("OPENAI_MODEL").unwrap_or_else(|_| "gpt-4o".to_string());
-    let messages = vec![
+async fn analyze_with_openai(snapshot: &Value, model_name: &str) -> Result<ModelReport, String> {
+    let prompt = threat_prompt(snapshot, "openai", "");
+    let mut messages = vec![
         ChatMessage {
             role: "system".into(),
-            content: "You are a concise, technical security analyst. JSON output only.".into(),
+            content: prompts::render(prompts::THREAT_ANALYSIS_SYSTEM, Some("openai"), &[]),
         },
         ChatMessage {
             role: "user".into(),
             content: prompt,
         },
     ];
+    let response_format = serde_json::json!({
+        "type": "json_schema",
+        "json_schema": {"name": "threat_report", "schema": report_json_schema()},
+    });
 
-    match openai_client::chat_completion(&model_name, messages, Some(0.1), None).await {
-        Ok(text) => match serde_json::from_str::<ModelReport>(&text) {
-            Ok(mut mr) => {
-                mr.provider = "openai".to_string();
-                Ok(mr)
-            }
-            Err(e) => {
-                warn!("OpenAI JSON parse failed, returning low-confidence fallback: {e}");
-                Ok(ModelReport {
-                    provider: "openai".to_string(),
-                    risk_score: 15.0,
-                    confidence: 0.3,
-                    findings: vec![],
-                    indicators: vec![],
-                    recommendations: vec![
-                        "Manual review recommended; model returned unstructured output".to_string(),
-                    ],
-                    citations: vec![],
-                })
-            }
-        },
-        Err(e) => {
+    let cache_key = response_cache::messages_key(
+        messages
+            .iter()
+            .map(|m| (m.role.as_str(), m.content.as_str())),
+    );
+    let text = response_cache::global()
+        .get_or_fetch("openai", model_name, Some(0.1), &cache_key, || {
+            openai_client::chat_completion_with_format(
+                model_name,
+                messages.clone(),
+                Some(0.1),
+                None,
+                Some(response_format.clone()),
+            )
+        })
+        .await
+        .map_err(|e| {
             error!("OpenAI analysis error: {e}");
-            Err(e.to_string())
-        }
-    }
+            e.to_string()
+        })?;
+
+    let report = parse_with_repair("openai", text.clone(), |repair| async move {
+        messages.push(ChatMessage {
+            role: "assistant".into(),
+            content: text,
+        });
+        messages.push(ChatMessage {
+            role: "user".into(),
+            content: repair,
+        });
+        openai_client::chat_completion_with_format(
+            model_name,
+            messages,
+            Some(0.0),
+            None,
+            Some(response_format),
+        )
+        .await
+        .map_err(|e| e.to_string())
+    })
+    .await;
+    Ok(report)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -86,6 +127,9 @@ pub struct ThreatFinding {
     pub rationale: Option<String>,
     #[serde(default)]
     pub indicators: Vec<String>,
+    /// MITRE ATT&CK technique IDs
+    #[serde(default)]
+    pub techniques: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -104,12 +148,23 @@ pub struct ThreatReport {
     pub citations: Vec<Citation>,
     #[serde(default)]
     pub disagreement_alerts: Vec<String>,
+    /// Trust weight applied to each provider's score
+    #[serde(default)]
+    pub provider_weights: HashMap<String, f32>,
     pub evidence: Value,
     pub timestamp: String,
 }
 
+impl Scored for ThreatReport {
+    fn risk_score(&self) -> f64 {
+        f64::from(self.risk_score)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
-struct ModelReport {
+pub struct ModelReport {
+    // Filled in by us; models never return it
+    #[serde(default)]
     pub provider: String,
     pub risk_score: f32,
     pub confidence: f32,
@@ -123,14 +178,60 @@ struct ModelReport {
     pub citations: Vec<Citation>,
 }
 
+/// Outcome of one provider call within a consensus run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderOutcome {
+    pub provider: String,
+    pub model: String,
+    pub weight: f32,
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub report: Option<ModelReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Aggregated report plus everything needed to audit how it was produced.
+#[derive(Debug, Clone)]
+pub struct ConsensusRun {
+    pub report: ThreatReport,
+    /// Snapshot exactly as sent to the providers (pseudonymized)
+    pub input: Value,
+    pub provider_outcomes: Vec<ProviderOutcome>,
+}
+
+#[cfg(feature = "surrealdb-metrics")]
+impl ConsensusRun {
+    pub fn to_record(&self) -> oxide_memory::ConsensusRunRecord {
+        oxide_memory::ConsensusRunRecord {
+            risk_score: self.report.risk_score as f64,
+            confidence: self.report.confidence as f64,
+            providers: self.report.providers.clone(),
+            input: self.input.clone(),
+            provider_reports: self
+                .provider_outcomes
+                .iter()
+                .filter_map(|o| serde_json::to_value(o).ok())
+                .collect(),
+            report: serde_json::to_value(&self.report).unwrap_or(Value::Null),
+        }
+    }
+}
+
 fn normalize_score(v: f32) -> f32 {
-    v.clamp(0.0, 100.0)
+    severity::clamp_score(f64::from(v)) as f32
 }
 fn normalize_conf(v: f32) -> f32 {
     v.clamp(0.0, 1.0)
 }
 
-fn aggregate(reports: Vec<ModelReport>, evidence: Value) -> ThreatReport {
+/// Combine provider reports. Each score is weighted by the provider's trust
+/// weight (default 1.0) times its self-reported confidence.
+fn aggregate(
+    reports: Vec<ModelReport>,
+    evidence: Value,
+    weights: &HashMap<String, f32>,
+) -> ThreatReport {
     if reports.is_empty() {
         return ThreatReport {
             risk_score: 0.0,
@@ -142,6 +243,7 @@ fn aggregate(reports: Vec<ModelReport>, evidence: Value) -> ThreatReport {
             recommendations: vec!["No providers available; unable to analyze".to_string()],
             citations: vec![],
             disagreement_alerts: vec!["No model reports".to_string()],
+            provider_weights: HashMap::new(),
             evidence,
             timestamp: Utc::now().to_rfc3339(),
         };
@@ -155,21 +257,30 @@ fn aggregate(reports: Vec<ModelReport>, evidence: Value) -> ThreatReport {
     }
     .to_string();
 
-    // Weighted average by confidence
+    let provider_weights: HashMap<String, f32> = providers
+        .iter()
+        .map(|p| (p.clone(), weights.get(p).copied().unwrap_or(1.0).max(0.0)))
+        .collect();
+
+    // Weighted average by trust weight x confidence
     let mut num = 0.0f32;
     let mut den = 0.0f32;
+    let mut conf_num = 0.0f32;
+    let mut trust_total = 0.0f32;
     for r in &reports {
+        let trust = provider_weights[&r.provider];
         let c = normalize_conf(r.confidence);
-        num += normalize_score(r.risk_score) * c.max(0.01);
-        den += c.max(0.01);
+        num += normalize_score(r.risk_score) * c.max(0.01) * trust;
+        den += c.max(0.01) * trust;
+        conf_num += c * trust;
+        trust_total += trust;
     }
     let risk_score = if den > 0.0 { num / den } else { 0.0 };
-    let confidence = (reports
-        .iter()
-        .map(|r| normalize_conf(r.confidence))
-        .sum::<f32>()
-        / reports.len() as f32)
-        .clamp(0.0, 1.0);
+    let confidence = if trust_total > 0.0 {
+        (conf_num / trust_total).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
 
     // Merge lists with simple concatenation + de-dup for indicators
     let mut findings = vec![];
@@ -198,6 +309,26 @@ fn aggregate(reports: Vec<ModelReport>, evidence: Value) -> ThreatReport {
         }
     }
 
+    // Keep only known technique IDs and add those the finding text implies.
+    // Severity labels off the rubric take the band of the aggregate score.
+    let report_severity = Severity::from_score(f64::from(risk_score));
+    for finding in &mut findings {
+        let severity: Severity = finding.severity.parse().unwrap_or(report_severity);
+        finding.severity = severity.to_string();
+        let mut techniques = attack::normalize_ids(&finding.techniques);
+        let text = format!(
+            "{} {}",
+            finding.summary,
+            finding.rationale.as_deref().unwrap_or_default()
+        );
+        for id in attack::map_text(&text) {
+            if !techniques.contains(&id) {
+                techniques.push(id);
+            }
+        }
+        finding.techniques = techniques;
+    }
+
     // Simple disagreement heuristic: if findings > threshold and providers >=2
     let mut disagreement_alerts = vec![];
     if providers.len() >= 2 {
@@ -219,86 +350,45 @@ fn aggregate(reports: Vec<ModelReport>, evidence: Value) -> ThreatReport {
         recommendations,
         citations,
         disagreement_alerts,
+        provider_weights,
         evidence,
         timestamp: Utc::now().to_rfc3339(),
     }
 }
 
-async fn analyze_with_gemini(snapshot: &Value, grounded: bool) -> Result<ModelReport, String> {
+async fn analyze_with_gemini(
+    snapshot: &Value,
+    grounded: bool,
+    model_name: &str,
+) -> Result<ModelReport, String> {
     let auth = GeminiAuth::new();
 
     // Enforce JSON output. If grounding no está realmente disponible, el modelo debe seguir la instrucción.
     let grounding_text = if grounded { "When possible," } else { "" };
-    let snapshot_str =
-        serde_json::to_string_pretty(snapshot).unwrap_or_else(|_| snapshot.to_string());
-
-    let prompt = format!(
-        r#"
-    You are a security threat analyst. Analyze the following JSON system snapshot and produce a STRICT JSON object with this shape:
-    {{
-      "risk_score": number (0-100),
-      "confidence": number (0-1),
-      "findings": [{{"id": string, "kind": "process|file|network|config", "severity": "low|medium|high|critical", "summary": string, "rationale": string, "indicators": string[]}}],
-      "indicators": [{{"kind": "hash|domain|path|proc", "value": string, "context": string}}],
-      "recommendations": string[],
-      "citations": [{{"title": string, "url": string, "snippet": string}}]
-    }}
-
-    Requirements:
-    - Output MUST be a single JSON object only, no prose.
-    - {grounding_text} Use Google Search to verify suspicious indicators and include citations to authoritative sources (CVE pages, vendors, security writeups). If not available, still return the JSON.
-
-    Snapshot:
-    {snapshot_str}
-    "#
-    );
+    let prompt = threat_prompt(snapshot, "gemini", grounding_text);
+    let schema = gemini_schema(&report_json_schema());
 
-    match auth.send_message(&prompt, Some("gemini-1.5-pro")).await {
-        Ok(text) => {
-            // Try to parse JSON
-            match serde_json::from_str::<ModelReport>(&text) {
-                Ok(mut mr) => {
-                    mr.provider = "gemini".to_string();
-                    Ok(mr)
-                }
-                Err(e) => {
-                    warn!("Gemini JSON parse failed, returning low-confidence fallback: {e}");
-                    Ok(ModelReport {
-                        provider: "gemini".to_string(),
-                        risk_score: 15.0,
-                        confidence: 0.3,
-                        findings: vec![],
-                        indicators: vec![],
-                        recommendations: vec![
-                            "Manual review recommended; model returned unstructured output"
-                                .to_string(),
-                        ],
-                        citations: vec![],
-                    })
-                }
-            }
-        }
-        Err(e) => {
+    let text = auth
+        .send_message_with_schema(&prompt, Some(model_name), Some(schema.clone()))
+        .await
+        .map_err(|e| {
             error!("Gemini analysis error: {e}");
-            Err(e.to_string())
-        }
-    }
+            e.to_string()
+        })?;
+
+    let report = parse_with_repair("gemini", text.clone(), |repair| async move {
+        // generateContent is single-turn here, so replay the exchange in one message
+        let retry_prompt = format!("{prompt}\n\nYour previous reply:\n{text}\n\n{repair}");
+        auth.send_message_with_schema(&retry_prompt, Some(model_name), Some(schema))
+            .await
+            .map_err(|e| e.to_string())
+    })
+    .await;
+    Ok(report)
 }
 
-async fn analyze_with_qwen(snapshot: &Value) -> Result<ModelReport, String> {
-    // Build prompt for JSON-only output
-    let snapshot_str =
-        serde_json::to_string_pretty(snapshot).unwrap_or_else(|_| snapshot.to_string());
-    let prompt = format!(
-        r#"
-    You are a security threat analyst. Analyze the JSON system snapshot and return STRICT JSON with:
-    risk_score, confidence, findings[], indicators[], recommendations[], citations[]
-    No prose, JSON only.
-
-    Snapshot:
-    {snapshot_str}
-    "#
-    );
+async fn analyze_with_qwen(snapshot: &Value, model_name: &str) -> Result<ModelReport, String> {
+    let prompt = threat_prompt(snapshot, "qwen", "");
 
     // Get auth header via QwenAuth helper
     let qauth = QwenAuth::new();
@@ -308,20 +398,60 @@ async fn analyze_with_qwen(snapshot: &Value) -> Result<ModelReport, String> {
     let path = std::env::var("QWEN_CHAT_COMPLETIONS_PATH")
         .unwrap_or_else(|_| "/v1/chat/completions".to_string());
     let url = format!("{base}{path}");
-    let model_name = std::env::var("QWEN_MODEL").unwrap_or_else(|_| "qwen-plus".to_string());
+    network::ensure_online(&url).map_err(|e| e.to_string())?;
+    let _permit = api_governor::acquire(&url)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut messages = vec![
+        serde_json::json!({"role": "system", "content": prompts::render(prompts::THREAT_ANALYSIS_SYSTEM, Some("qwen"), &[])}),
+        serde_json::json!({"role": "user", "content": prompt}),
+    ];
 
+    let cache_key = serde_json::to_string(&messages).unwrap_or_default();
+    let text = response_cache::global()
+        .get_or_fetch("qwen", model_name, Some(0.1), &cache_key, || {
+            qwen_chat(&url, &auth_header, model_name, &messages)
+        })
+        .await?;
+
+    let report = parse_with_repair("qwen", text.clone(), |repair| async move {
+        messages.push(serde_json::json!({"role": "assistant", "content": text}));
+        messages.push(serde_json::json!({"role": "user", "content": repair}));
+        qwen_chat(&url, &auth_header, model_name, &messages).await
+    })
+    .await;
+    Ok(report)
+}
+
+/// One Qwen chat completion in JSON mode; returns the message content.
+async fn qwen_chat(
+    url: &str,
+    auth_header: &str,
+    model_name: &str,
+    messages: &[Value],
+) -> Result<String, String> {
+    let sent = llm_audit::messages_text(messages);
+    let request = post_qwen_chat(url, auth_header, model_name, messages);
+    llm_audit::audited("qwen", Some(model_name), &sent, request).await
+}
+
+async fn post_qwen_chat(
+    url: &str,
+    auth_header: &str,
+    model_name: &str,
+    messages: &[Value],
+) -> Result<String, String> {
     let body = serde_json::json!({
       "model": model_name,
-      "messages": [
-        {"role": "system", "content": "You are a concise, technical security analyst. JSON output only."},
-        {"role": "user", "content": prompt}
-      ],
-      "temperature": 0.1
+      "messages": messages,
+      "temperature": 0.1,
+      "response_format": {"type": "json_object"}
     });
 
-    let client = Client::new();
+    let client = network::http_client();
     let resp = client
-        .post(&url)
+        .post(url)
         .header("Authorization", auth_header)
         .header("Content-Type", "application/json")
         .json(&body)
@@ -336,120 +466,323 @@ async fn analyze_with_qwen(snapshot: &Value) -> Result<ModelReport, String> {
     }
 
     let v: Value = resp.json().await.map_err(|e| e.to_string())?;
-    let text = v
-        .get("choices")
+    v.get("choices")
         .and_then(|c| c.as_array())
         .and_then(|a| a.first())
         .and_then(|x| x.get("message"))
         .and_then(|m| m.get("content"))
         .and_then(|t| t.as_str())
-        .ok_or_else(|| "Unexpected Qwen response format".to_string())?;
+        .map(str::to_string)
+        .ok_or_else(|| "Unexpected Qwen response format".to_string())
+}
+
+/// JSON Schema for [`ModelReport`] as requested from providers.
+fn report_json_schema() -> Value {
+    let string_list = serde_json::json!({"type": "array", "items": {"type": "string"}});
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "risk_score": {"type": "number"},
+            "confidence": {"type": "number"},
+            "findings": {"type": "array", "items": {
+                "type": "object",
+                "properties": {
+                    "id": {"type": "string"},
+                    "kind": {"type": "string", "enum": ["process", "file", "network", "config"]},
+                    "severity": {"type": "string", "enum": ["low", "medium", "high", "critical"]},
+                    "summary": {"type": "string"},
+                    "rationale": {"type": "string"},
+                    "indicators": string_list,
+                    "techniques": string_list,
+                },
+                "required": ["id", "kind", "severity", "summary"],
+                "additionalProperties": false,
+            }},
+            "indicators": {"type": "array", "items": {
+                "type": "object",
+                "properties": {
+                    "kind": {"type": "string"},
+                    "value": {"type": "string"},
+                    "context": {"type": "string"},
+                },
+                "required": ["kind", "value"],
+                "additionalProperties": false,
+            }},
+            "recommendations": string_list,
+            "citations": {"type": "array", "items": {
+                "type": "object",
+                "properties": {
+                    "title": {"type": "string"},
+                    "url": {"type": "string"},
+                    "snippet": {"type": "string"},
+                },
+                "required": ["title", "url"],
+                "additionalProperties": false,
+            }},
+        },
+        "required": ["risk_score", "confidence", "findings", "indicators", "recommendations", "citations"],
+        "additionalProperties": false,
+    })
+}
+
+/// Gemini's `responseSchema` is an OpenAPI subset: no `additionalProperties`.
+fn gemini_schema(schema: &Value) -> Value {
+    match schema {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .filter(|(k, _)| k.as_str() != "additionalProperties")
+                .map(|(k, v)| (k.clone(), gemini_schema(v)))
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(gemini_schema).collect()),
+        other => other.clone(),
+    }
+}
 
-    match serde_json::from_str::<ModelReport>(text) {
-        Ok(mut mr) => {
-            mr.provider = "qwen".to_string();
-            Ok(mr)
+/// Parse a model reply, tolerating code fences and prose around the JSON.
+fn parse_model_report(text: &str) -> Result<ModelReport, String> {
+    let trimmed = text.trim();
+    let direct_err = match serde_json::from_str::<ModelReport>(trimmed) {
+        Ok(report) => return Ok(report),
+        Err(e) => e.to_string(),
+    };
+
+    let candidates = [fenced_block(trimmed), outer_object(trimmed)];
+    for candidate in candidates.into_iter().flatten() {
+        if let Ok(report) = serde_json::from_str::<ModelReport>(candidate) {
+            return Ok(report);
         }
-        Err(e) => {
-            warn!("Qwen JSON parse failed, returning low-confidence fallback: {e}");
-            Ok(ModelReport {
-                provider: "qwen".to_string(),
-                risk_score: 15.0,
-                confidence: 0.3,
-                findings: vec![],
-                indicators: vec![],
-                recommendations: vec![
-                    "Manual review recommended; model returned unstructured output".to_string(),
-                ],
-                citations: vec![],
-            })
+    }
+    Err(direct_err)
+}
+
+/// Contents of the first ``` fenced block, minus an optional language tag.
+fn fenced_block(text: &str) -> Option<&str> {
+    let start = text.find("```")? + 3;
+    let rest = &text[start..];
+    let body_start = rest.find('\n').map(|i| i + 1).unwrap_or(0);
+    let end = rest[body_start..].find("```")?;
+    Some(rest[body_start..body_start + end].trim())
+}
+
+/// Span from the first `{` to the last `}`.
+fn outer_object(text: &str) -> Option<&str> {
+    let start = text.find('{')?;
+    let end = text.rfind('}')?;
+    (end > start).then(|| &text[start..=end])
+}
+
+fn repair_prompt(error: &str) -> String {
+    format!(
+        "Your previous reply could not be parsed as the required JSON object ({error}). \
+         Reply again with only the corrected JSON object: no prose, no code fences."
+    )
+}
+
+/// Parse `text`; on failure ask the provider once to fix its output via
+/// `retry(repair_message)`, then fall back to a low-confidence report.
+async fn parse_with_repair<F, Fut>(provider: &str, text: String, retry: F) -> ModelReport
+where
+    F: FnOnce(String) -> Fut,
+    Fut: Future<Output = Result<String, String>>,
+{
+    let error = match parse_model_report(&text) {
+        Ok(mut report) => {
+            report.provider = provider.to_string();
+            return report;
         }
+        Err(e) => e,
+    };
+
+    warn!("{provider} JSON parse failed ({error}); retrying once with repair prompt");
+    match retry(repair_prompt(&error)).await {
+        Ok(repaired) => match parse_model_report(&repaired) {
+            Ok(mut report) => {
+                report.provider = provider.to_string();
+                return report;
+            }
+            Err(e) => warn!("{provider} repair attempt still invalid JSON: {e}"),
+        },
+        Err(e) => warn!("{provider} repair attempt failed: {e}"),
     }
+
+    warn!("{provider} returning low-confidence fallback report");
+    fallback_report(provider)
 }
 
-pub async fn run_consensus(snapshot: Value, _grounded: bool) -> Result<ThreatReport, String> {
+fn fallback_report(provider: &str) -> ModelReport {
+    ModelReport {
+        provider: provider.to_string(),
+        risk_score: 15.0,
+        confidence: 0.3,
+        findings: vec![],
+        indicators: vec![],
+        recommendations: vec![
+            "Manual review recommended; model returned unstructured output".to_string(),
+        ],
+        citations: vec![],
+    }
+}
+
+/// Model used for `provider`: config override, then env, then built-in default.
+fn resolve_model(provider: &str, settings: &ConsensusConfig) -> String {
+    if let Some(model) = settings.provider(provider).model {
+        return model;
+    }
+    let (env_key, default) = match provider {
+        "gemini" => ("GEMINI_CONSENSUS_MODEL", "gemini-1.5-pro"),
+        "qwen" => ("QWEN_MODEL", "qwen-plus"),
+        _ => ("OPENAI_MODEL", "gpt-4o"),
+    };
+    std::env::var(env_key).unwrap_or_else(|_| default.to_string())
+}
+
+/// Run one provider under its timeout and record the outcome.
+async fn run_provider<F>(
+    provider: &str,
+    settings: &ConsensusConfig,
+    model: String,
+    analysis: F,
+) -> ProviderOutcome
+where
+    F: Future<Output = Result<ModelReport, String>>,
+{
+    let timeout_secs = settings.timeout_secs_for(provider);
+    let started = Instant::now();
+    let result = tokio::time::timeout(Duration::from_secs(timeout_secs), analysis)
+        .await
+        .unwrap_or_else(|_| Err(format!("timed out after {timeout_secs}s")));
+    let latency_ms = started.elapsed().as_millis() as u64;
+    if let Err(e) = &result {
+        warn!("Consensus provider {provider} failed: {e}");
+    }
+    ProviderOutcome {
+        provider: provider.to_string(),
+        model,
+        weight: settings.provider(provider).weight,
+        latency_ms,
+        report: result.as_ref().ok().cloned(),
+        error: result.err(),
+    }
+}
+
+pub async fn run_consensus(
+    snapshot: Value,
+    grounded: bool,
+    privacy: &PrivacyFilter,
+    settings: &ConsensusConfig,
+) -> Result<ConsensusRun, String> {
+    let run = consensus(snapshot, grounded, privacy, settings);
+    llm_audit::with_purpose("threat_consensus", run).await
+}
+
+async fn consensus(
+    mut snapshot: Value,
+    _grounded: bool,
+    privacy: &PrivacyFilter,
+    settings: &ConsensusConfig,
+) -> Result<ConsensusRun, String> {
+    // Every consensus provider is a cloud LLM
+    network::ensure_online("threat consensus providers").map_err(|e| e.to_string())?;
+    power::ensure_cloud_consensus()?;
     let t0 = std::time::Instant::now();
-    // Availability: Gemini, Qwen and OpenAI if authenticated
+    // Pseudonymize before anything reaches a cloud provider (idempotent if the
+    // snapshot was already redacted by get_system_snapshot).
+    privacy.redact_value(&mut snapshot);
+
+    // Availability: Gemini, Qwen and OpenAI if authenticated and enabled
     let mut providers: Vec<&str> = vec![];
+    let enabled = |provider: &str| settings.provider(provider).enabled;
 
     // Gemini availability (OAuth only)
-    let g_available = matches!(
-        oxide_core::google_auth::get_access_token().await,
-        Ok(Some(_))
-    );
+    let g_available = enabled("gemini")
+        && matches!(
+            oxide_core::google_auth::get_access_token().await,
+            Ok(Some(_))
+        );
     if g_available {
         providers.push("gemini");
     }
 
     // Qwen availability
-    let q_available =
-        QwenAuth::new().get_auth_header().await.is_ok() && std::env::var("QWEN_API_BASE").is_ok();
+    let q_available = enabled("qwen")
+        && QwenAuth::new().get_auth_header().await.is_ok()
+        && std::env::var("QWEN_API_BASE").is_ok();
     if q_available {
         providers.push("qwen");
     }
 
     // OpenAI availability (API Key)
-    let o_available = matches!(oxide_core::openai_key::get_api_key().await, Ok(Some(_)));
+    let o_available =
+        enabled("openai") && matches!(oxide_core::openai_key::get_api_key().await, Ok(Some(_)));
     if o_available {
         providers.push("openai");
     }
 
     info!("Consensus starting with providers: {providers:?}");
     if providers.is_empty() {
-        return Err("No LLM providers available (Gemini, Qwen, or OpenAI)".to_string());
+        return Err(i18n::t("error.no_llm_providers").to_string());
     }
 
     // Launch available analyses in parallel
-    let g_fut = if g_available {
-        Some(analyze_with_gemini(&snapshot, true))
-    } else {
-        None
-    };
-    let q_fut = if q_available {
-        Some(analyze_with_qwen(&snapshot))
-    } else {
-        None
-    };
-    let o_fut = if o_available {
-        Some(analyze_with_openai(&snapshot))
-    } else {
-        None
-    };
+    let g_model = resolve_model("gemini", settings);
+    let q_model = resolve_model("qwen", settings);
+    let o_model = resolve_model("openai", settings);
 
     let (g_res, q_res, o_res) = tokio::join!(
         async {
-            if let Some(f) = g_fut {
-                f.await
+            if g_available {
+                Some(
+                    run_provider(
+                        "gemini",
+                        settings,
+                        g_model.clone(),
+                        analyze_with_gemini(&snapshot, true, &g_model),
+                    )
+                    .await,
+                )
             } else {
-                Err("gemini_unavailable".into())
+                None
             }
         },
         async {
-            if let Some(f) = q_fut {
-                f.await
+            if q_available {
+                Some(
+                    run_provider(
+                        "qwen",
+                        settings,
+                        q_model.clone(),
+                        analyze_with_qwen(&snapshot, &q_model),
+                    )
+                    .await,
+                )
             } else {
-                Err("qwen_unavailable".into())
+                None
             }
         },
         async {
-            if let Some(f) = o_fut {
-                f.await
+            if o_available {
+                Some(
+                    run_provider(
+                        "openai",
+                        settings,
+                        o_model.clone(),
+                        analyze_with_openai(&snapshot, &o_model),
+                    )
+                    .await,
+                )
             } else {
-                Err("openai_unavailable".into())
+                None
             }
         },
     );
 
-    let mut reports: Vec<ModelReport> = vec![];
-    if let Ok(r) = g_res {
-        reports.push(r);
-    }
-    if let Ok(r) = q_res {
-        reports.push(r);
-    }
-    if let Ok(r) = o_res {
-        reports.push(r);
-    }
+    let provider_outcomes: Vec<ProviderOutcome> =
+        [g_res, q_res, o_res].into_iter().flatten().collect();
+    let reports: Vec<ModelReport> = provider_outcomes
+        .iter()
+        .filter_map(|o| o.report.clone())
+        .collect();
 
     // Log per-provider confidence and score for debugging
     for r in &reports {
@@ -465,12 +798,31 @@ pub async fn run_consensus(snapshot: Value, _grounded: bool) -> Result<ThreatRep
         reports.len()
     );
 
-    Ok(aggregate(reports, snapshot))
+    let weights: HashMap<String, f32> = provider_outcomes
+        .iter()
+        .map(|o| (o.provider.clone(), o.weight))
+        .collect();
+    let report = restore_report(aggregate(reports, snapshot.clone(), &weights), privacy);
+
+    Ok(ConsensusRun {
+        report,
+        input: snapshot,
+        provider_outcomes,
+    })
+}
+
+/// Map pseudonym tokens in the aggregated report back to local values.
+fn restore_report(report: ThreatReport, privacy: &PrivacyFilter) -> ThreatReport {
+    let Ok(mut value) = serde_json::to_value(&report) else {
+        return report;
+    };
+    privacy.restore_value(&mut value);
+    serde_json::from_value(value).unwrap_or(report)
 }
 
 pub fn recommendations_from_report(rep: &ThreatReport) -> Vec<String> {
     let mut out = rep.recommendations.clone();
-    if rep.risk_score >= 70.0 {
+    if rep.severity() >= Severity::High {
         out.push("High risk detected: enable containment mode and review suspicious processes immediately".to_string());
     }
     out
@@ -483,7 +835,7 @@ mod tests {
     #[test]
     fn aggregate_empty_reports() {
         let ev = serde_json::json!({"status":"ok"});
-        let rep = aggregate(vec![], ev.clone());
+        let rep = aggregate(vec![], ev.clone(), &HashMap::new());
         assert_eq!(rep.risk_score, 0.0);
         assert_eq!(rep.mode, "single");
         assert!(rep.providers.is_empty());
@@ -508,6 +860,7 @@ mod tests {
                 summary: "sus".into(),
                 rationale: None,
                 indicators: vec!["pid:1".into()],
+                techniques: vec!["t1059.001".into(), "T9999".into()],
             }],
             indicators: vec![Indicator {
                 kind: "proc".into(),
@@ -517,11 +870,13 @@ mod tests {
             recommendations: vec!["kill pid 1".into()],
             citations: vec![],
         };
-        let rep = aggregate(vec![r1], ev);
+        let rep = aggregate(vec![r1], ev, &HashMap::new());
         assert_eq!(rep.mode, "single");
         assert_eq!(rep.providers, vec!["gemini"]);
         assert!(rep.risk_score >= 79.0 && rep.risk_score <= 81.0);
         assert!(rep.findings.iter().any(|f| f.id == "p1"));
+        assert_eq!(rep.findings[0].techniques, ["T1059.001"]);
+        assert_eq!(rep.severity(), Severity::Critical);
         let recs = recommendations_from_report(&rep);
         assert!(recs.iter().any(|r| r.contains("containment")));
     }
@@ -547,10 +902,82 @@ mod tests {
             recommendations: vec!["B".into()],
             citations: vec![],
         };
-        let rep = aggregate(vec![r1, r2], ev);
+        let rep = aggregate(vec![r1, r2], ev, &HashMap::new());
         // Weighted towards gemini
         assert!(rep.risk_score > 80.0);
         assert_eq!(rep.mode, "dual");
         assert!(rep.providers.contains(&"gemini".into()) && rep.providers.contains(&"qwen".into()));
     }
+
+    #[test]
+    fn aggregate_applies_trust_weights() {
+        let report = |provider: &str, risk_score: f32| ModelReport {
+            provider: provider.into(),
+            risk_score,
+            confidence: 0.8,
+            findings: vec![],
+            indicators: vec![],
+            recommendations: vec![],
+            citations: vec![],
+        };
+        let weights = HashMap::from([("gemini".to_string(), 3.0), ("qwen".to_string(), 1.0)]);
+        let rep = aggregate(
+            vec![report("gemini", 80.0), report("qwen", 20.0)],
+            serde_json::json!({}),
+            &weights,
+        );
+        // (80*3 + 20*1) / 4
+        assert!((rep.risk_score - 65.0).abs() < 0.01);
+        assert_eq!(rep.provider_weights["gemini"], 3.0);
+
+        // A zero-weight provider contributes nothing to the score
+        let weights = HashMap::from([("qwen".to_string(), 0.0)]);
+        let rep = aggregate(
+            vec![report("gemini", 80.0), report("qwen", 20.0)],
+            serde_json::json!({}),
+            &weights,
+        );
+        assert!((rep.risk_score - 80.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn parses_fenced_and_wrapped_json() {
+        let raw = r#"{"risk_score": 40, "confidence": 0.7, "recommendations": ["patch"]}"#;
+        assert_eq!(parse_model_report(raw).unwrap().risk_score, 40.0);
+
+        let fenced = format!("Here is the report:\n```json\n{raw}\n```\nDone.");
+        assert_eq!(parse_model_report(&fenced).unwrap().confidence, 0.7);
+
+        let prose = format!("Sure! {raw} Let me know if you need more.");
+        assert_eq!(
+            parse_model_report(&prose).unwrap().recommendations,
+            vec!["patch"]
+        );
+
+        assert!(parse_model_report("no json here").is_err());
+    }
+
+    #[test]
+    fn gemini_schema_drops_additional_properties() {
+        let schema = gemini_schema(&report_json_schema());
+        assert!(!schema.to_string().contains("additionalProperties"));
+        assert_eq!(schema["properties"]["risk_score"]["type"], "number");
+    }
+
+    #[tokio::test]
+    async fn repair_pass_retries_once() {
+        let report = parse_with_repair("qwen", "not json".to_string(), |msg| async move {
+            assert!(msg.contains("corrected JSON"));
+            Ok(r#"{"risk_score": 55, "confidence": 0.9}"#.to_string())
+        })
+        .await;
+        assert_eq!(report.provider, "qwen");
+        assert_eq!(report.risk_score, 55.0);
+
+        let report = parse_with_repair("gemini", "still not json".to_string(), |_| async {
+            Ok("nope".to_string())
+        })
+        .await;
+        assert_eq!(report.confidence, 0.3);
+    }
 }
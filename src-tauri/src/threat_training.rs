@@ -0,0 +1,110 @@
+//! Periodic threat model retraining from recorded events.
+//!
+//! Every `interval_hours` resolved threats and confident consensus runs are
+//! turned into training samples, and once enough samples exist the threat
+//! model is retrained and scored on a holdout. Analyst labels set through
+//! `label_threat` are picked up by the next run.
+
+use crate::oxide_system::OxideSystem;
+use log::{info, warn};
+use oxide_core::config::ThreatTrainingConfig;
+use oxide_memory::{ModelEvaluation, SurrealBackend};
+use serde::Serialize;
+
+const DEFAULT_INTERVAL_HOURS: u64 = 24;
+const DEFAULT_CONSENSUS_MIN_CONFIDENCE: f64 = 0.7;
+const DEFAULT_MIN_SAMPLES: usize = 20;
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct TrainingRunReport {
+    /// Samples added from events not collected before
+    pub collected: usize,
+    pub total_samples: usize,
+    /// `None` when there were too few samples to retrain
+    pub evaluation: Option<ModelEvaluation>,
+    pub duration_ms: u64,
+}
+
+/// Collect new samples and retrain if there are enough of them.
+pub async fn run(
+    backend: &SurrealBackend,
+    cfg: &ThreatTrainingConfig,
+) -> Result<TrainingRunReport, String> {
+    let started = std::time::Instant::now();
+    let collected = backend
+        .collect_threat_training_samples(
+            cfg.consensus_min_confidence
+                .unwrap_or(DEFAULT_CONSENSUS_MIN_CONFIDENCE),
+        )
+        .await
+        .map_err(|e| format!("Failed to collect threat training samples: {e:#}"))?;
+    let total_samples = backend
+        .threat_training_samples()
+        .await
+        .map_err(|e| format!("Failed to load threat training samples: {e:#}"))?
+        .len();
+
+    let min_samples = cfg.min_samples.unwrap_or(DEFAULT_MIN_SAMPLES);
+    let evaluation = if total_samples >= min_samples {
+        let evaluation = backend
+            .retrain_threat_model(min_samples)
+            .await
+            .map_err(|e| format!("Threat model retraining failed: {e:#}"))?;
+        info!(
+            "Threat model evaluation: accuracy {:.2} on {} holdout samples ({})",
+            evaluation.accuracy, evaluation.evaluated, evaluation.predictor
+        );
+        for (label, metrics) in &evaluation.per_class {
+            info!(
+                "  {label}: precision {:.2}, recall {:.2}, support {}",
+                metrics.precision, metrics.recall, metrics.support
+            );
+        }
+        Some(evaluation)
+    } else {
+        info!("Threat model not retrained: {total_samples} of {min_samples} samples collected");
+        None
+    };
+
+    Ok(TrainingRunReport {
+        collected,
+        total_samples,
+        evaluation,
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+/// Run [`run`] on the configured schedule while the system runs. The config
+/// is re-read before each run so changes apply without a restart.
+pub fn spawn(system: OxideSystem) {
+    tokio::spawn(async move {
+        loop {
+            let hours = current_config(&system)
+                .await
+                .and_then(|c| c.interval_hours)
+                .unwrap_or(DEFAULT_INTERVAL_HOURS);
+            tokio::time::sleep(std::time::Duration::from_secs(hours * 3600)).await;
+            if !system.is_running().await {
+                break;
+            }
+            let cfg = current_config(&system).await.unwrap_or_default();
+            if !cfg.enabled {
+                continue;
+            }
+            let Some(backend) = system.surreal_backend() else {
+                continue;
+            };
+            if let Err(e) = run(&backend, &cfg).await {
+                warn!("Threat training run failed: {e}");
+            }
+        }
+    });
+}
+
+async fn current_config(system: &OxideSystem) -> Option<ThreatTrainingConfig> {
+    system
+        .get_config()
+        .await
+        .surreal
+        .and_then(|surreal| surreal.threat_training)
+}
@@ -0,0 +1,133 @@
+//! Update checks and installs on top of `oxide_core::updates`.
+//!
+//! While `updates.enabled` is set, a background task checks the configured
+//! channel every `check_interval_hours` and announces each new release once
+//! as an `updates://available` event. Installing re-checks the signed
+//! manifest instead of trusting what the frontend saw, downloads the
+//! verified package and runs it through `msiexec` or the NSIS installer,
+//! then exits so the installer can replace the running binary.
+//!
+//! Tauri's updater is not used: it only reads its own per-target manifest
+//! signed with a minisign key, which has no channels or delta packages.
+//! Installing is Windows-only for now; on other platforms `install_update`
+//! refuses and the release has to be installed by hand.
+
+use crate::command_guard;
+use crate::events;
+use crate::oxide_system::OxideSystem;
+use log::{info, warn};
+use oxide_core::i18n;
+use oxide_core::updates::{self, UpdateCheck, DEFAULT_CHECK_INTERVAL_HOURS, UPDATES_DIR};
+use std::path::Path;
+use std::process::Command;
+use tauri::{AppHandle, State};
+
+/// Version of the running app.
+const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Check periodically while the system runs.
+pub fn spawn(app: AppHandle, system: OxideSystem) {
+    tauri::async_runtime::spawn(async move {
+        let mut announced: Option<String> = None;
+        loop {
+            if !system.is_running().await {
+                break;
+            }
+            let config = system.get_config().await.updates.unwrap_or_default();
+            if config.enabled && config.endpoint.is_some() {
+                match updates::check(&config, CURRENT_VERSION).await {
+                    Ok(check) => {
+                        let version = check.update.as_ref().map(|u| u.version.clone());
+                        if version.is_some() && version != announced {
+                            events::emit(&app, &check);
+                            announced = version;
+                        }
+                    }
+                    Err(e) => warn!("Periodic update check failed: {e}"),
+                }
+            }
+            let hours = config
+                .check_interval_hours
+                .unwrap_or(DEFAULT_CHECK_INTERVAL_HOURS);
+            tokio::time::sleep(std::time::Duration::from_secs(u64::from(hours) * 3600)).await;
+        }
+    });
+}
+
+/// Start an unattended install of a downloaded package. Windows Installer
+/// patches (`.msp`) are the delta packages.
+fn run_installer(path: &Path) -> Result<(), String> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let mut command = match extension.as_str() {
+        "msi" | "msp" => {
+            let mut command = Command::new("msiexec");
+            command
+                .arg(if extension == "msi" { "/i" } else { "/p" })
+                .arg(path)
+                .args(["/passive", "/norestart"]);
+            command
+        }
+        // NSIS installer
+        "exe" => {
+            let mut command = Command::new(path);
+            command.arg("/S");
+            command
+        }
+        _ => return Err(format!("Cannot install update package {path:?}")),
+    };
+    command
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to start the installer {path:?}: {e}"))
+}
+
+/// Check the configured channel for a newer release, with its release
+/// notes.
+#[tauri::command]
+pub async fn check_for_updates(state: State<'_, crate::AppState>) -> Result<UpdateCheck, String> {
+    let system = state
+        .oxide_system
+        .read()
+        .await
+        .clone()
+        .ok_or(i18n::t("error.system_not_initialized"))?;
+    let config = system.get_config().await.updates.unwrap_or_default();
+    updates::check(&config, CURRENT_VERSION).await
+}
+
+/// Download, verify and install the newest release, then quit so the
+/// installer can finish. Windows only.
+#[tauri::command]
+pub async fn install_update(
+    session_id: Option<String>,
+    state: State<'_, crate::AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    let system = state
+        .oxide_system
+        .read()
+        .await
+        .clone()
+        .ok_or(i18n::t("error.system_not_initialized"))?;
+    command_guard::authorize_command(&system, session_id.as_deref(), "install_update").await?;
+    if !cfg!(windows) {
+        return Err(
+            "Installing updates is only supported on Windows; install the release by hand"
+                .to_string(),
+        );
+    }
+    let config = system.get_config().await.updates.unwrap_or_default();
+    let update = updates::check(&config, CURRENT_VERSION)
+        .await?
+        .update
+        .ok_or("Oxide Pilot is up to date")?;
+    let path = updates::download(&update, Path::new(UPDATES_DIR)).await?;
+    run_installer(&path)?;
+    info!("Installing Oxide Pilot {}; exiting", update.version);
+    app.exit(0);
+    Ok(())
+}
@@ -0,0 +1,270 @@
+//! Supervisor for the background tasks that otherwise die silently.
+//!
+//! Every [`CHECK_INTERVAL_SECS`] the system probes each [`SupervisedTask`]:
+//! a task has exited, or its heartbeat (the end of its last completed pass)
+//! is older than the task allows. Unhealthy tasks are restarted at once, then
+//! with a doubling delay capped at [`BACKOFF_CAP_SECS`] while they keep
+//! failing. Each restart is broadcast as a [`TaskRestart`] and every task's
+//! state is part of the protection status.
+
+use crate::oxide_system::OxideSystem;
+use chrono::{DateTime, Duration, Utc};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+
+pub const CHECK_INTERVAL_SECS: u64 = 15;
+/// Delay before the second restart; doubled for every further one.
+pub const BACKOFF_BASE_SECS: i64 = 5;
+pub const BACKOFF_CAP_SECS: i64 = 300;
+/// Healthy this long after a restart resets the backoff.
+const STABLE_AFTER_SECS: i64 = 600;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum SupervisedTask {
+    GuardianMonitoring,
+    MetricsCollector,
+    ThreatRemediation,
+}
+
+impl SupervisedTask {
+    pub const ALL: [SupervisedTask; 3] = [
+        SupervisedTask::GuardianMonitoring,
+        SupervisedTask::MetricsCollector,
+        SupervisedTask::ThreatRemediation,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SupervisedTask::GuardianMonitoring => "Guardian monitoring",
+            SupervisedTask::MetricsCollector => "Metrics collector",
+            SupervisedTask::ThreatRemediation => "Threat remediation",
+        }
+    }
+}
+
+/// What a probe found out about a task.
+#[derive(Debug, Clone, Copy)]
+pub enum Probe {
+    /// Turned off or not configured; nothing to supervise
+    Disabled,
+    Exited,
+    /// Running, without a heartbeat to check
+    Running,
+    /// Running; stalled once `last` is older than `stall_after`
+    Heartbeat {
+        last: Option<DateTime<Utc>>,
+        stall_after: Duration,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TaskHealth {
+    pub task: SupervisedTask,
+    pub healthy: bool,
+    pub last_heartbeat: Option<DateTime<Utc>>,
+    pub restarts: u32,
+    pub last_restart_at: Option<DateTime<Utc>>,
+    /// Why the task was last restarted
+    pub last_failure: Option<String>,
+    /// Earliest time the task may be restarted again
+    pub next_restart_at: Option<DateTime<Utc>>,
+}
+
+/// A restart the watchdog performed.
+#[derive(Serialize, Debug, Clone)]
+pub struct TaskRestart {
+    pub task: SupervisedTask,
+    pub reason: String,
+    /// Restarts of this task since the system started
+    pub restarts: u32,
+    pub at: DateTime<Utc>,
+}
+
+struct TaskState {
+    health: TaskHealth,
+    /// Restarts without a stable period in between
+    failures: u32,
+}
+
+pub struct Watchdog {
+    started_at: DateTime<Utc>,
+    tasks: Mutex<BTreeMap<SupervisedTask, TaskState>>,
+    restarts: broadcast::Sender<TaskRestart>,
+}
+
+impl Watchdog {
+    pub fn new(started_at: DateTime<Utc>) -> Self {
+        let (restarts, _) = broadcast::channel(16);
+        Self {
+            started_at,
+            tasks: Mutex::new(BTreeMap::new()),
+            restarts,
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<TaskRestart> {
+        self.restarts.subscribe()
+    }
+
+    /// Every task probed so far.
+    pub fn health(&self) -> Vec<TaskHealth> {
+        let tasks = self.tasks.lock().unwrap_or_else(|p| p.into_inner());
+        tasks.values().map(|state| state.health.clone()).collect()
+    }
+
+    /// Record a probe; returns the restart to perform now, if any.
+    pub fn observe(
+        &self,
+        task: SupervisedTask,
+        probe: Probe,
+        now: DateTime<Utc>,
+    ) -> Option<TaskRestart> {
+        let mut tasks = self.tasks.lock().unwrap_or_else(|p| p.into_inner());
+        let state = tasks.entry(task).or_insert_with(|| TaskState {
+            health: TaskHealth {
+                task,
+                healthy: true,
+                last_heartbeat: None,
+                restarts: 0,
+                last_restart_at: None,
+                last_failure: None,
+                next_restart_at: None,
+            },
+            failures: 0,
+        });
+        let health = &mut state.health;
+
+        let failure = match probe {
+            Probe::Disabled | Probe::Running => None,
+            Probe::Exited => Some("the task exited".to_string()),
+            Probe::Heartbeat { last, stall_after } => {
+                health.last_heartbeat = last;
+                // A fresh or restarted task gets a full period for its first pass
+                let since = [last, health.last_restart_at]
+                    .into_iter()
+                    .flatten()
+                    .fold(self.started_at, DateTime::max);
+                (now - since > stall_after).then(|| match last {
+                    Some(at) => format!("no heartbeat since {}", at.to_rfc3339()),
+                    None => "no heartbeat yet".to_string(),
+                })
+            }
+        };
+
+        let Some(reason) = failure else {
+            health.healthy = true;
+            if health
+                .last_restart_at
+                .is_some_and(|at| now - at >= Duration::seconds(STABLE_AFTER_SECS))
+            {
+                state.failures = 0;
+            }
+            return None;
+        };
+
+        health.healthy = false;
+        if health.next_restart_at.is_some_and(|at| now < at) {
+            return None;
+        }
+        state.failures += 1;
+        health.restarts += 1;
+        health.last_restart_at = Some(now);
+        health.last_failure = Some(reason.clone());
+        health.next_restart_at = Some(now + backoff(state.failures));
+        let restart = TaskRestart {
+            task,
+            reason,
+            restarts: health.restarts,
+            at: now,
+        };
+        let _ = self.restarts.send(restart.clone());
+        Some(restart)
+    }
+}
+
+/// Wait after the `failures`-th restart before the next one.
+fn backoff(failures: u32) -> Duration {
+    let secs = BACKOFF_BASE_SECS.saturating_mul(1 << failures.saturating_sub(1).min(16));
+    Duration::seconds(secs.min(BACKOFF_CAP_SECS))
+}
+
+/// Probe and restart the supervised tasks while the system runs.
+pub fn spawn(system: OxideSystem) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(std::time::Duration::from_secs(CHECK_INTERVAL_SECS));
+        loop {
+            ticker.tick().await;
+            if !system.is_running().await {
+                break;
+            }
+            for task in SupervisedTask::ALL {
+                let probe = system.probe_task(task).await;
+                if let Some(restart) = system.watchdog().observe(task, probe, Utc::now()) {
+                    warn!(
+                        "{} failed ({}); restart #{}",
+                        task.label(),
+                        restart.reason,
+                        restart.restarts
+                    );
+                    system.restart_task(task).await;
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restarts_back_off_and_reset_once_stable() {
+        let start = Utc::now();
+        let watchdog = Watchdog::new(start);
+        let task = SupervisedTask::GuardianMonitoring;
+        let stall_after = Duration::seconds(60);
+        let stalled = Probe::Heartbeat {
+            last: Some(start),
+            stall_after,
+        };
+
+        assert!(watchdog
+            .observe(task, stalled, start + Duration::seconds(30))
+            .is_none());
+        let first = watchdog
+            .observe(task, stalled, start + Duration::seconds(90))
+            .unwrap();
+        assert_eq!(first.restarts, 1);
+        // The restarted task gets its own grace period
+        assert!(watchdog
+            .observe(task, stalled, start + Duration::seconds(120))
+            .is_none());
+        assert!(watchdog.health()[0].healthy);
+        assert!(watchdog
+            .observe(task, stalled, start + Duration::seconds(160))
+            .is_some());
+        // Exits right after a restart wait out the backoff
+        let t = start + Duration::seconds(161);
+        assert!(watchdog.observe(task, Probe::Exited, t).is_none());
+        assert!(!watchdog.health()[0].healthy);
+        assert!(watchdog
+            .observe(task, Probe::Exited, start + Duration::seconds(170))
+            .is_some());
+        assert_eq!(watchdog.health()[0].restarts, 3);
+
+        let later = start + Duration::seconds(170 + STABLE_AFTER_SECS);
+        assert!(watchdog.observe(task, Probe::Running, later).is_none());
+        assert_eq!(watchdog.tasks.lock().unwrap()[&task].failures, 0);
+    }
+
+    #[test]
+    fn backoff_is_capped() {
+        assert_eq!(backoff(1), Duration::seconds(BACKOFF_BASE_SECS));
+        assert_eq!(backoff(2), Duration::seconds(BACKOFF_BASE_SECS * 2));
+        assert_eq!(backoff(40), Duration::seconds(BACKOFF_CAP_SECS));
+    }
+}